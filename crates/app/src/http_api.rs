@@ -0,0 +1,518 @@
+//! Minimal HTTP/JSON API over the services layer.
+//!
+//! Only compiled with the `http-api` feature. Apart from submitting reviews the API is
+//! read-only. Every request must carry the shared secret as `Authorization: Bearer <token>`.
+
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, Request, State};
+use axum::http::{StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use learn_core::model::{Card, CardId, Deck, DeckId, ReviewGrade};
+use serde_json::{Value, json};
+use services::{
+    AppServices, CardService, DeckService, NothingDue, SessionError, SessionLoopService,
+    SessionService, SessionStart,
+};
+use storage::repository::StorageError;
+use tokio::net::TcpListener;
+
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+const LIST_LIMIT: u32 = 10_000;
+
+/// Services exposed through the API, plus the token clients must present.
+#[derive(Clone)]
+pub struct ApiServices {
+    deck_service: Arc<DeckService>,
+    card_service: Arc<CardService>,
+    session_loop: Arc<SessionLoopService>,
+    token: Arc<str>,
+}
+
+impl ApiServices {
+    pub fn from_app(services: &AppServices, token: &str) -> Self {
+        Self {
+            deck_service: services.deck_service(),
+            card_service: services.card_service(),
+            session_loop: services.session_loop(),
+            token: token.into(),
+        }
+    }
+
+    /// Compare in constant time so response timing doesn't leak how much of a guess matched.
+    fn accepts(&self, header: Option<&str>) -> bool {
+        let Some(given) = header.and_then(|value| value.strip_prefix("Bearer ")) else {
+            return false;
+        };
+        let expected = self.token.as_bytes();
+        given.len() == expected.len()
+            && given
+                .bytes()
+                .zip(expected)
+                .fold(0_u8, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+}
+
+/// An error response: HTTP status and message.
+struct ApiError(StatusCode, String);
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self(StatusCode::BAD_REQUEST, message.into())
+    }
+
+    fn not_found() -> Self {
+        Self(StatusCode::NOT_FOUND, "not found".into())
+    }
+
+    fn conflict(message: impl Into<String>) -> Self {
+        Self(StatusCode::CONFLICT, message.into())
+    }
+
+    fn internal(err: impl fmt::Display) -> Self {
+        Self(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+    }
+}
+
+impl From<SessionError> for ApiError {
+    fn from(err: SessionError) -> Self {
+        match err {
+            SessionError::Storage(StorageError::NotFound) => {
+                Self(StatusCode::NOT_FOUND, "deck not found".into())
+            }
+            err => Self::internal(err),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(error_body(&self.1))).into_response()
+    }
+}
+
+type ApiResult = Result<Json<Value>, ApiError>;
+
+/// Build the API routes, all behind the token check.
+///
+/// `POST /decks/{id}/reviews` grades; every other route is `GET`.
+fn router(api: ApiServices) -> Router {
+    Router::new()
+        .route("/decks", get(list_decks))
+        .route("/decks/{id}/cards", get(deck_cards))
+        .route("/decks/{id}/stats", get(deck_stats))
+        .route("/decks/{id}/due", get(deck_due))
+        .route("/decks/{id}/reviews", post(deck_review))
+        .route("/agenda", get(agenda))
+        .fallback(|| async { ApiError::not_found() })
+        .method_not_allowed_fallback(|| async {
+            ApiError(StatusCode::METHOD_NOT_ALLOWED, "method not allowed".into())
+        })
+        .layer(DefaultBodyLimit::max(MAX_REQUEST_BYTES))
+        .layer(middleware::from_fn_with_state(api.clone(), require_token))
+        .with_state(api)
+}
+
+/// Serve the API until the listener fails.
+///
+/// # Errors
+///
+/// Returns an I/O error if the server stops on a listener failure.
+pub async fn serve(listener: TcpListener, api: ApiServices) -> io::Result<()> {
+    axum::serve(listener, router(api)).await
+}
+
+async fn require_token(State(api): State<ApiServices>, request: Request, next: Next) -> Response {
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    if !api.accepts(authorization) {
+        let error = ApiError(StatusCode::UNAUTHORIZED, "missing or invalid token".into());
+        return error.into_response();
+    }
+    next.run(request).await
+}
+
+/// Parse a deck ID path segment; anything else is an unknown path.
+fn parse_deck_id(raw: &str) -> Result<DeckId, ApiError> {
+    raw.parse().map(DeckId::new).map_err(|_| ApiError::not_found())
+}
+
+async fn list_decks(State(api): State<ApiServices>) -> ApiResult {
+    let decks = api
+        .deck_service
+        .list_decks(LIST_LIMIT, false)
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Json(Value::Array(decks.iter().map(deck_json).collect())))
+}
+
+async fn deck_cards(State(api): State<ApiServices>, Path(id): Path<String>) -> ApiResult {
+    let cards = api
+        .card_service
+        .list_cards(parse_deck_id(&id)?, LIST_LIMIT)
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Json(Value::Array(cards.iter().map(card_json).collect())))
+}
+
+async fn deck_stats(State(api): State<ApiServices>, Path(id): Path<String>) -> ApiResult {
+    let deck_id = parse_deck_id(&id)?;
+    let stats = api
+        .card_service
+        .deck_practice_stats(deck_id)
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Json(json!({
+        "deck_id": deck_id.value(),
+        "total": stats.total,
+        "due": stats.due,
+        "new": stats.new,
+    })))
+}
+
+async fn deck_due(State(api): State<ApiServices>, Path(id): Path<String>) -> ApiResult {
+    let deck_id = parse_deck_id(&id)?;
+    let (cards, next_due_at) = match deck_session(&api, deck_id).await? {
+        Ok(session) => (session.pending_cards().iter().map(card_json).collect(), None),
+        Err(nothing) => (Vec::new(), nothing.next_due_at),
+    };
+    Ok(Json(json!({
+        "deck_id": deck_id.value(),
+        "cards": cards,
+        "next_due_at": next_due_at.map(|at| at.to_rfc3339()),
+    })))
+}
+
+/// Grade the next card in the deck's session.
+///
+/// Grading holds the scheduler, which is not `Send`, so it runs on a blocking thread
+/// that drives the future to completion on the same runtime.
+async fn deck_review(
+    State(api): State<ApiServices>,
+    Path(id): Path<String>,
+    body: Bytes,
+) -> ApiResult {
+    let deck_id = parse_deck_id(&id)?;
+    let (card_id, grade) = parse_review(&body)?;
+    let runtime = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || {
+        runtime.block_on(review_next(&api, deck_id, card_id, grade))
+    })
+    .await
+    .map_err(ApiError::internal)?
+    .map(Json)
+}
+
+async fn review_next(
+    api: &ApiServices,
+    deck_id: DeckId,
+    card_id: CardId,
+    grade: ReviewGrade,
+) -> Result<Value, ApiError> {
+    let Ok(mut session) = deck_session(api, deck_id).await? else {
+        return Err(ApiError::conflict("no cards are due in this deck"));
+    };
+    if session.current_card().map(Card::id) != Some(card_id) {
+        return Err(ApiError::conflict(format!(
+            "card {card_id} is not the next card due in this deck"
+        )));
+    }
+    let answer = api.session_loop.answer_current(&mut session, grade).await?;
+    Ok(json!({
+        "card_id": card_id.value(),
+        "next_review_at": answer.review.result.applied.outcome.next_review.to_rfc3339(),
+        "remaining": session.remaining(),
+        "is_complete": answer.is_complete,
+    }))
+}
+
+async fn agenda(State(api): State<ApiServices>) -> ApiResult {
+    let decks = api
+        .deck_service
+        .list_decks(LIST_LIMIT, false)
+        .await
+        .map_err(ApiError::internal)?;
+    let deck_ids: Vec<DeckId> = decks.iter().map(Deck::id).collect();
+    let rows = api
+        .card_service
+        .list_deck_practice_stats(&deck_ids)
+        .await
+        .map_err(ApiError::internal)?;
+    let items = decks
+        .iter()
+        .map(|deck| {
+            let stats = rows
+                .iter()
+                .find(|row| row.deck_id == deck.id())
+                .map(|row| row.stats);
+            let due = stats.map_or(0, |s| s.due);
+            let new = stats.map_or(0, |s| s.new);
+            let settings = deck.settings();
+            json!({
+                "deck_id": deck.id().value(),
+                "name": deck.name(),
+                "due": due,
+                "new": new,
+                "planned_reviews": due.min(settings.review_limit_per_day()),
+                "planned_new": new.min(settings.new_cards_per_day()),
+            })
+        })
+        .collect();
+    Ok(Json(Value::Array(items)))
+}
+
+/// Resume the deck's saved session or start a due one, so consecutive requests share a queue.
+///
+/// Yields when the next card comes due instead when nothing is due now.
+async fn deck_session(
+    api: &ApiServices,
+    deck_id: DeckId,
+) -> Result<Result<SessionService, NothingDue>, SessionError> {
+    if let Some(session) = api.session_loop.resume(deck_id).await? {
+        return Ok(Ok(session));
+    }
+    Ok(match api.session_loop.start_or_nothing_due(deck_id).await? {
+        SessionStart::Started(session) => Ok(*session),
+        SessionStart::NothingDue(nothing) => Err(nothing),
+    })
+}
+
+/// Parse a `{"card_id": 12, "grade": "good"}` review submission.
+fn parse_review(body: &[u8]) -> Result<(CardId, ReviewGrade), ApiError> {
+    let body: Value = serde_json::from_slice(body)
+        .map_err(|_| ApiError::bad_request("body must be a JSON object"))?;
+    let card_id = body["card_id"]
+        .as_u64()
+        .ok_or_else(|| ApiError::bad_request("card_id must be a card ID"))?;
+    let grade = match body["grade"].as_str() {
+        Some("again") => ReviewGrade::Again,
+        Some("hard") => ReviewGrade::Hard,
+        Some("good") => ReviewGrade::Good,
+        Some("easy") => ReviewGrade::Easy,
+        _ => return Err(ApiError::bad_request("grade must be again, hard, good, or easy")),
+    };
+    Ok((CardId::new(card_id), grade))
+}
+
+fn deck_json(deck: &Deck) -> Value {
+    json!({
+        "id": deck.id().value(),
+        "name": deck.name(),
+        "description": deck.description(),
+        "created_at": deck.created_at().to_rfc3339(),
+    })
+}
+
+fn card_json(card: &Card) -> Value {
+    json!({
+        "id": card.id().value(),
+        "deck_id": card.deck_id().value(),
+        "prompt": card.prompt().text(),
+        "answer": card.answer().text(),
+        "extra": card.extra().map(|extra| extra.text()),
+        "phase": card.phase().as_str(),
+        "review_count": card.review_count(),
+        "next_review_at": card.next_review_at().to_rfc3339(),
+        "last_review_at": card.last_review_at().map(|at| at.to_rfc3339()),
+    })
+}
+
+fn error_body(message: &str) -> Value {
+    json!({ "error": message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use learn_core::model::ContentDraft;
+    use learn_core::time::fixed_now;
+    use services::Clock;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    const TOKEN: &str = "s3cret";
+
+    async fn request(
+        addr: std::net::SocketAddr,
+        method: &str,
+        path: &str,
+        token: Option<&str>,
+        body: &str,
+    ) -> (String, Value) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let auth = token.map_or_else(String::new, |token| {
+            format!("Authorization: Bearer {token}\r\n")
+        });
+        let request = format!(
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n{auth}\
+             Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut raw = String::new();
+        stream.read_to_string(&mut raw).await.unwrap();
+        let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+        let status_line = head.lines().next().unwrap().to_string();
+        (status_line, serde_json::from_str(body).unwrap())
+    }
+
+    async fn get(addr: std::net::SocketAddr, path: &str) -> (String, Value) {
+        request(addr, "GET", path, Some(TOKEN), "").await
+    }
+
+    /// Serve `services` on an ephemeral port.
+    async fn spawn_api(services: &AppServices) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, ApiServices::from_app(services, TOKEN)));
+        addr
+    }
+
+    #[tokio::test]
+    async fn decks_endpoint_returns_json_array() {
+        let services = AppServices::new_sqlite(
+            "sqlite:file:memdb_http_api?mode=memory&cache=shared",
+            Clock::fixed(fixed_now()),
+            Some(DeckId::new(1)),
+        )
+        .await
+        .unwrap();
+        let addr = spawn_api(&services).await;
+
+        let (status, body) = get(addr, "/decks").await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        let decks = body.as_array().expect("array of decks");
+        assert!(!decks.is_empty());
+        let deck = decks[0].as_object().expect("deck object");
+        assert!(deck["id"].is_u64());
+        assert!(deck["name"].is_string());
+        assert!(deck.contains_key("description"));
+        assert!(deck["created_at"].is_string());
+
+        let (status, _body) = get(addr, "/nope").await;
+        assert_eq!(status, "HTTP/1.1 404 Not Found");
+    }
+
+    #[tokio::test]
+    async fn requests_without_the_token_are_rejected() {
+        let services = AppServices::new_sqlite(
+            "sqlite:file:memdb_http_api_token?mode=memory&cache=shared",
+            Clock::fixed(fixed_now()),
+            Some(DeckId::new(1)),
+        )
+        .await
+        .unwrap();
+        let addr = spawn_api(&services).await;
+
+        let (status, body) = request(addr, "GET", "/decks", None, "").await;
+        assert_eq!(status, "HTTP/1.1 401 Unauthorized");
+        assert!(body["error"].is_string());
+
+        let (status, _body) = request(addr, "GET", "/decks", Some("guess"), "").await;
+        assert_eq!(status, "HTTP/1.1 401 Unauthorized");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn due_cards_can_be_reviewed_in_session_order() {
+        let services = AppServices::new_sqlite(
+            "sqlite:file:memdb_http_api_review?mode=memory&cache=shared",
+            Clock::fixed(fixed_now()),
+            Some(DeckId::new(1)),
+        )
+        .await
+        .unwrap();
+        let deck_id = services.deck_id();
+        for (prompt, answer) in [("Hallo", "Hello"), ("Danke", "Thank you")] {
+            services
+                .card_service()
+                .create_card(
+                    deck_id,
+                    ContentDraft::text_only(prompt),
+                    ContentDraft::text_only(answer),
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+        let addr = spawn_api(&services).await;
+
+        let due_path = format!("/decks/{deck_id}/due");
+        let reviews_path = format!("/decks/{deck_id}/reviews");
+        let (status, due) = get(addr, &due_path).await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        let cards = due["cards"].as_array().expect("due cards");
+        assert_eq!(cards.len(), 2);
+        let first = cards[0]["id"].as_u64().unwrap();
+        let second = cards[1]["id"].as_u64().unwrap();
+
+        let out_of_order = format!(r#"{{"card_id": {second}, "grade": "good"}}"#);
+        let (status, _body) =
+            request(addr, "POST", &reviews_path, Some(TOKEN), &out_of_order).await;
+        assert_eq!(status, "HTTP/1.1 409 Conflict");
+
+        let (status, _body) = request(
+            addr,
+            "POST",
+            &reviews_path,
+            Some(TOKEN),
+            r#"{"card_id": 1, "grade": "meh"}"#,
+        )
+        .await;
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+
+        let review = format!(r#"{{"card_id": {first}, "grade": "easy"}}"#);
+        let (status, body) =
+            request(addr, "POST", &reviews_path, Some(TOKEN), &review).await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(body["card_id"], first);
+        assert_eq!(body["remaining"], 1);
+        assert_eq!(body["is_complete"], false);
+        assert!(body["next_review_at"].is_string());
+
+        let (_status, due) = get(addr, &due_path).await;
+        let remaining: Vec<u64> = due["cards"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|card| card["id"].as_u64().unwrap())
+            .collect();
+        assert_eq!(remaining, [second]);
+
+        let (status, _body) = request(addr, "GET", &reviews_path, Some(TOKEN), "").await;
+        assert_eq!(status, "HTTP/1.1 405 Method Not Allowed");
+    }
+
+    #[tokio::test]
+    async fn oversized_bodies_are_rejected_as_too_large() {
+        let services = AppServices::new_sqlite(
+            "sqlite:file:memdb_http_api_oversized?mode=memory&cache=shared",
+            Clock::fixed(fixed_now()),
+            Some(DeckId::new(1)),
+        )
+        .await
+        .unwrap();
+        let addr = spawn_api(&services).await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let body = "x".repeat(MAX_REQUEST_BYTES + 1);
+        let request = format!(
+            "POST /decks/1/reviews HTTP/1.1\r\nConnection: close\r\n\
+             Authorization: Bearer {TOKEN}\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len(),
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut raw = String::new();
+        stream.read_to_string(&mut raw).await.unwrap();
+        assert!(raw.starts_with("HTTP/1.1 413 Payload Too Large"));
+    }
+}
@@ -1,31 +1,59 @@
+#[cfg(feature = "http-api")]
+mod http_api;
+
 use std::fmt;
 use std::sync::Arc;
 
 use dioxus::desktop::{Config as DesktopConfig, LogicalSize, WindowBuilder};
 use dioxus::LaunchBuilder;
-use learn_core::model::DeckId;
+use learn_core::model::{ContentDraft, DeckId, DeckSettings};
 use services::{
-    AppServices, AppSettingsService, CardService, Clock, DeckService, SessionLoopService,
+    AiUsageService, AppServices, AppSettingsService, CardService, Clock, DeckExport, DeckService,
+    DeckTransferService, ImportTarget, LogExportFormat, MediaService, SessionLoopService,
     SessionSummaryService, WritingToolsService,
 };
-use ui::{App, UiApp, UiLinkOpener, build_app_context};
-use ui::platform::DesktopLinkOpener;
+use ui::{App, UiApp, UiAudioPlayer, UiLinkOpener, build_app_context};
+use ui::platform::{DesktopAudioPlayer, DesktopLinkOpener};
 
 #[derive(Debug)]
 enum ArgsError {
     MissingValue { flag: &'static str },
+    MissingFlag { flag: &'static str },
     UnknownArg(String),
     InvalidDeckId { raw: String },
     InvalidDbUrl { raw: String },
+    InvalidCount { raw: String },
+    InvalidDelimiter { raw: String },
+    InvalidFormat { raw: String },
+    InvalidBackupKeep { raw: String },
+    #[cfg(feature = "http-api")]
+    InvalidPort { raw: String },
+    #[cfg(not(feature = "postgres"))]
+    PostgresDisabled { raw: String },
 }
 
 impl fmt::Display for ArgsError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ArgsError::MissingValue { flag } => write!(f, "{flag} requires a value"),
+            ArgsError::MissingFlag { flag } => write!(f, "{flag} is required"),
             ArgsError::UnknownArg(arg) => write!(f, "unknown argument: {arg}"),
             ArgsError::InvalidDeckId { raw } => write!(f, "invalid --deck-id value: {raw}"),
             ArgsError::InvalidDbUrl { raw } => write!(f, "invalid --db value: {raw}"),
+            ArgsError::InvalidCount { raw } => write!(f, "invalid --count value: {raw}"),
+            ArgsError::InvalidDelimiter { raw } => {
+                write!(f, "invalid --delimiter value: {raw} (use a single character or `tab`)")
+            }
+            ArgsError::InvalidFormat { raw } => write!(f, "invalid --format value: {raw}"),
+            ArgsError::InvalidBackupKeep { raw } => {
+                write!(f, "invalid --backup-keep value: {raw}")
+            }
+            #[cfg(feature = "http-api")]
+            ArgsError::InvalidPort { raw } => write!(f, "invalid --port value: {raw}"),
+            #[cfg(not(feature = "postgres"))]
+            ArgsError::PostgresDisabled { raw } => {
+                write!(f, "--db {raw} requires building with --features postgres")
+            }
         }
     }
 }
@@ -45,10 +73,13 @@ struct DesktopApp {
     session_loop: Arc<SessionLoopService>,
     card_service: Arc<CardService>,
     deck_service: Arc<DeckService>,
+    media_service: Arc<MediaService>,
     app_settings: Arc<AppSettingsService>,
     writing_tools: Arc<WritingToolsService>,
+    ai_usage: Arc<AiUsageService>,
     open_editor_on_launch: bool,
     link_opener: Arc<dyn UiLinkOpener>,
+    audio_player: Arc<dyn UiAudioPlayer>,
 }
 
 impl UiApp for DesktopApp {
@@ -72,6 +103,10 @@ impl UiApp for DesktopApp {
         Arc::clone(&self.deck_service)
     }
 
+    fn media_service(&self) -> Arc<MediaService> {
+        Arc::clone(&self.media_service)
+    }
+
     fn app_settings(&self) -> Arc<AppSettingsService> {
         Arc::clone(&self.app_settings)
     }
@@ -80,6 +115,10 @@ impl UiApp for DesktopApp {
         Arc::clone(&self.writing_tools)
     }
 
+    fn ai_usage(&self) -> Arc<AiUsageService> {
+        Arc::clone(&self.ai_usage)
+    }
+
     fn open_editor_on_launch(&self) -> bool {
         self.open_editor_on_launch
     }
@@ -87,30 +126,106 @@ impl UiApp for DesktopApp {
     fn link_opener(&self) -> Arc<dyn UiLinkOpener> {
         Arc::clone(&self.link_opener)
     }
+
+    fn audio_player(&self) -> Arc<dyn UiAudioPlayer> {
+        Arc::clone(&self.audio_player)
+    }
 }
 
 struct Args {
     db_url: String,
-    deck_id: DeckId,
+    /// Deck named by `--deck-id` or `LEARN_DECK_ID`, if any.
+    deck_id: Option<DeckId>,
+    #[cfg(feature = "http-api")]
+    port: u16,
+    /// Shared secret API clients must send, from `--token` or `LEARN_API_TOKEN`.
+    #[cfg(feature = "http-api")]
+    api_token: Option<String>,
+    seed_count: u32,
+    seed_clear: bool,
+    out_path: Option<std::path::PathBuf>,
+    in_path: Option<std::path::PathBuf>,
+    reuse_deck: bool,
+    skip_duplicates: bool,
+    delimiter: Option<char>,
+    log_format: LogExportFormat,
+    /// Where `ui` writes launch backups; `None` uses `backups/` beside the database file.
+    backup_dir: Option<std::path::PathBuf>,
+    /// Launch backups to keep; 0 turns them off.
+    backup_keep: usize,
 }
 
+#[cfg(feature = "http-api")]
+const DEFAULT_API_PORT: u16 = 7878;
+const DEFAULT_SEED_COUNT: u32 = 20;
+const DEFAULT_DECK_ID: u64 = 1;
+const DEFAULT_BACKUP_KEEP: usize = 5;
+
 fn print_usage() {
     eprintln!("Usage:");
-    eprintln!("  cargo run -p app -- ui [--db <sqlite_url>] [--deck-id <id>]");
-    eprintln!("  cargo run -p app -- seed [--db <sqlite_url>] [--deck-id <id>]");
+    eprintln!(
+        "  cargo run -p app -- ui [--db <db_url>] [--deck-id <id>] [--backup-dir <path>] \
+         [--backup-keep <n>]"
+    );
+    eprintln!(
+        "  cargo run -p app -- seed [--db <db_url>] [--deck-id <id>] [--count <n>] [--clear]"
+    );
+    eprintln!("  cargo run -p app -- export [--db <db_url>] [--deck-id <id>] --out <path>");
+    eprintln!(
+        "  cargo run -p app -- export-logs [--db <db_url>] [--deck-id <id>] --out <path> \
+         [--format csv]"
+    );
+    eprintln!(
+        "  cargo run -p app -- import [--db <db_url>] --in <path> \
+         [--reuse --deck-id <id>] [--skip-duplicates]"
+    );
+    eprintln!(
+        "  cargo run -p app -- import-csv [--db <db_url>] [--deck-id <id>] --in <path> \
+         [--delimiter <char>|tab]"
+    );
+    eprintln!("  cargo run -p app -- import-anki [--db <db_url>] --in <path.apkg>");
+    eprintln!("  cargo run -p app -- doctor [--db <db_url>]");
+    #[cfg(feature = "http-api")]
+    eprintln!(
+        "  cargo run -p app --features http-api -- serve [--db <db_url>] [--port <port>] \
+         --token <secret>"
+    );
     eprintln!();
-    eprintln!("Defaults for ui:");
+    eprintln!("Defaults:");
     eprintln!("  --db sqlite://dev.sqlite3");
-    eprintln!("  --deck-id 1");
+    #[cfg(feature = "postgres")]
+    eprintln!("       (postgres://user@host/db selects the Postgres backend)");
+    eprintln!("       (sqlite::memory: runs against a throwaway in-memory database)");
+    eprintln!("  --deck-id {DEFAULT_DECK_ID} (ui reopens the last deck used instead)");
+    eprintln!("  --count {DEFAULT_SEED_COUNT} (seed)");
+    eprintln!("  --delimiter tab for .tsv files, `,` otherwise (import-csv)");
+    eprintln!("  --format csv (export-logs)");
+    eprintln!("  a .jsonl --out or --in path streams one card per line (export, import)");
+    eprintln!("  --backup-dir backups/ beside the database file (ui)");
+    eprintln!("  --backup-keep {DEFAULT_BACKUP_KEEP} (ui; 0 turns launch backups off)");
+    #[cfg(feature = "http-api")]
+    eprintln!("  --port {DEFAULT_API_PORT} (serve, bound to 127.0.0.1)");
     eprintln!();
     eprintln!("Environment:");
-    eprintln!("  LEARN_DB_URL, LEARN_DECK_ID");
+    eprintln!("  LEARN_DB_URL, LEARN_DECK_ID, LEARN_BACKUP_DIR, LEARN_BACKUP_KEEP");
+    #[cfg(feature = "http-api")]
+    eprintln!("  LEARN_API_TOKEN (serve; clients send `Authorization: Bearer <token>`)");
+    #[cfg(feature = "tracing")]
+    eprintln!("  LEARN_LOG=services=debug (log service spans to stderr)");
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Command {
     Ui,
     Seed,
+    Export,
+    ExportLogs,
+    Import,
+    ImportCsv,
+    ImportAnki,
+    Doctor,
+    #[cfg(feature = "http-api")]
+    Serve,
 }
 
 impl Command {
@@ -118,38 +233,191 @@ impl Command {
         match arg {
             "ui" => Some(Self::Ui),
             "seed" => Some(Self::Seed),
+            "export" => Some(Self::Export),
+            "export-logs" => Some(Self::ExportLogs),
+            "import" => Some(Self::Import),
+            "import-csv" => Some(Self::ImportCsv),
+            "import-anki" => Some(Self::ImportAnki),
+            "doctor" => Some(Self::Doctor),
+            #[cfg(feature = "http-api")]
+            "serve" => Some(Self::Serve),
             _ => None,
         }
     }
 }
 
 impl Args {
+    /// Deck targeted by the command-line tools.
+    fn target_deck_id(&self) -> DeckId {
+        self.deck_id.unwrap_or(DeckId::new(DEFAULT_DECK_ID))
+    }
+
     fn parse_ui(args: &mut impl Iterator<Item = String>) -> Result<Self, ArgsError> {
+        Self::parse_flags(Command::Ui, args)
+    }
+
+    fn parse_seed(args: &mut impl Iterator<Item = String>) -> Result<Self, ArgsError> {
+        Self::parse_flags(Command::Seed, args)
+    }
+
+    fn parse_export(args: &mut impl Iterator<Item = String>) -> Result<Self, ArgsError> {
+        let parsed = Self::parse_flags(Command::Export, args)?;
+        if parsed.out_path.is_none() {
+            return Err(ArgsError::MissingFlag { flag: "--out" });
+        }
+        Ok(parsed)
+    }
+
+    fn parse_export_logs(args: &mut impl Iterator<Item = String>) -> Result<Self, ArgsError> {
+        let parsed = Self::parse_flags(Command::ExportLogs, args)?;
+        if parsed.out_path.is_none() {
+            return Err(ArgsError::MissingFlag { flag: "--out" });
+        }
+        Ok(parsed)
+    }
+
+    fn parse_import(args: &mut impl Iterator<Item = String>) -> Result<Self, ArgsError> {
+        let parsed = Self::parse_flags(Command::Import, args)?;
+        if parsed.in_path.is_none() {
+            return Err(ArgsError::MissingFlag { flag: "--in" });
+        }
+        Ok(parsed)
+    }
+
+    fn parse_import_csv(args: &mut impl Iterator<Item = String>) -> Result<Self, ArgsError> {
+        let parsed = Self::parse_flags(Command::ImportCsv, args)?;
+        if parsed.in_path.is_none() {
+            return Err(ArgsError::MissingFlag { flag: "--in" });
+        }
+        Ok(parsed)
+    }
+
+    fn parse_import_anki(args: &mut impl Iterator<Item = String>) -> Result<Self, ArgsError> {
+        let parsed = Self::parse_flags(Command::ImportAnki, args)?;
+        if parsed.in_path.is_none() {
+            return Err(ArgsError::MissingFlag { flag: "--in" });
+        }
+        Ok(parsed)
+    }
+
+    fn parse_doctor(args: &mut impl Iterator<Item = String>) -> Result<Self, ArgsError> {
+        Self::parse_flags(Command::Doctor, args)
+    }
+
+    #[cfg(feature = "http-api")]
+    fn parse_serve(args: &mut impl Iterator<Item = String>) -> Result<Self, ArgsError> {
+        let parsed = Self::parse_flags(Command::Serve, args)?;
+        if parsed.api_token.is_none() {
+            return Err(ArgsError::MissingFlag { flag: "--token" });
+        }
+        Ok(parsed)
+    }
+
+    /// Parse shared targeting flags plus the flags specific to `cmd`.
+    fn parse_flags(
+        cmd: Command,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<Self, ArgsError> {
         let mut db_url = std::env::var("LEARN_DB_URL")
             .ok()
-            .map_or_else(|| "sqlite://dev.sqlite3".into(), |value| normalize_sqlite_url(&value));
+            .map_or_else(|| "sqlite://dev.sqlite3".into(), |value| normalize_db_url(&value));
         let mut deck_id = std::env::var("LEARN_DECK_ID")
             .ok()
             .and_then(|value| value.parse::<u64>().ok())
-            .map_or_else(|| DeckId::new(1), DeckId::new);
+            .map(DeckId::new);
+        #[cfg(feature = "http-api")]
+        let mut port = DEFAULT_API_PORT;
+        #[cfg(feature = "http-api")]
+        let mut api_token = std::env::var("LEARN_API_TOKEN")
+            .ok()
+            .filter(|value| !value.trim().is_empty());
+        let mut seed_count = DEFAULT_SEED_COUNT;
+        let mut seed_clear = false;
+        let mut out_path = None;
+        let mut in_path = None;
+        let mut reuse_deck = false;
+        let mut skip_duplicates = false;
+        let mut delimiter = None;
+        let mut log_format = LogExportFormat::Csv;
+        let mut backup_dir = std::env::var("LEARN_BACKUP_DIR")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .map(std::path::PathBuf::from);
+        let mut backup_keep = match std::env::var("LEARN_BACKUP_KEEP") {
+            Ok(value) => {
+                value.trim().parse().map_err(|_| ArgsError::InvalidBackupKeep { raw: value })?
+            }
+            Err(_) => DEFAULT_BACKUP_KEEP,
+        };
 
         while let Some(arg) = args.next() {
-            match arg.as_str() {
-                "--db" => {
+            match (cmd, arg.as_str()) {
+                (_, "--db") => {
                     let value = require_value(args, "--db")?;
                     if value.trim().is_empty() {
                         return Err(ArgsError::InvalidDbUrl { raw: value });
                     }
-                    db_url = normalize_sqlite_url(&value);
+                    db_url = normalize_db_url(&value);
                 }
-                "--deck-id" => {
+                (_, "--deck-id") => {
                     let value = require_value(args, "--deck-id")?;
                     let parsed: u64 = value
                         .parse()
                         .map_err(|_| ArgsError::InvalidDeckId { raw: value.clone() })?;
-                    deck_id = DeckId::new(parsed);
+                    deck_id = Some(DeckId::new(parsed));
+                }
+                #[cfg(feature = "http-api")]
+                (Command::Serve, "--port") => {
+                    let value = require_value(args, "--port")?;
+                    port = value
+                        .parse()
+                        .map_err(|_| ArgsError::InvalidPort { raw: value.clone() })?;
+                }
+                #[cfg(feature = "http-api")]
+                (Command::Serve, "--token") => {
+                    let value = require_value(args, "--token")?;
+                    api_token = Some(value).filter(|value| !value.trim().is_empty());
+                }
+                (Command::Seed, "--count") => {
+                    let value = require_value(args, "--count")?;
+                    seed_count = value
+                        .parse()
+                        .map_err(|_| ArgsError::InvalidCount { raw: value.clone() })?;
+                }
+                (Command::Seed, "--clear") => seed_clear = true,
+                (Command::Export | Command::ExportLogs, "--out") => {
+                    let value = require_value(args, "--out")?;
+                    out_path = Some(std::path::PathBuf::from(value));
+                }
+                (Command::Import | Command::ImportCsv | Command::ImportAnki, "--in") => {
+                    let value = require_value(args, "--in")?;
+                    in_path = Some(std::path::PathBuf::from(value));
+                }
+                (Command::Import, "--reuse") => reuse_deck = true,
+                (Command::Import, "--skip-duplicates") => skip_duplicates = true,
+                (Command::ImportCsv, "--delimiter") => {
+                    let value = require_value(args, "--delimiter")?;
+                    delimiter = Some(
+                        parse_delimiter(&value)
+                            .ok_or(ArgsError::InvalidDelimiter { raw: value.clone() })?,
+                    );
+                }
+                (Command::ExportLogs, "--format") => {
+                    let value = require_value(args, "--format")?;
+                    log_format = LogExportFormat::parse(&value)
+                        .ok_or(ArgsError::InvalidFormat { raw: value.clone() })?;
                 }
-                "--help" | "-h" => {
+                (Command::Ui, "--backup-dir") => {
+                    let value = require_value(args, "--backup-dir")?;
+                    backup_dir = Some(std::path::PathBuf::from(value));
+                }
+                (Command::Ui, "--backup-keep") => {
+                    let value = require_value(args, "--backup-keep")?;
+                    backup_keep = value
+                        .parse()
+                        .map_err(|_| ArgsError::InvalidBackupKeep { raw: value.clone() })?;
+                }
+                (_, "--help" | "-h") => {
                     print_usage();
                     std::process::exit(0);
                 }
@@ -157,21 +425,53 @@ impl Args {
             }
         }
 
-        Ok(Self { db_url, deck_id })
+        Ok(Self {
+            db_url,
+            deck_id,
+            #[cfg(feature = "http-api")]
+            port,
+            #[cfg(feature = "http-api")]
+            api_token,
+            seed_count,
+            seed_clear,
+            out_path,
+            in_path,
+            reuse_deck,
+            skip_duplicates,
+            delimiter,
+            log_format,
+            backup_dir,
+            backup_keep,
+        })
     }
+}
 
-    fn parse_seed(args: &mut impl Iterator<Item = String>) -> Result<Self, ArgsError> {
-        // For now, seed uses the same db/deck targeting knobs.
-        // This keeps the command useful even before we add concrete seed operations.
-        Self::parse_ui(args)
+/// Accept a single character, or `tab`/`\t` for tab-separated files.
+fn parse_delimiter(raw: &str) -> Option<char> {
+    if raw == "tab" || raw == "\\t" {
+        return Some('\t');
     }
+    let mut chars = raw.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None,
+    }
+}
+
+fn is_postgres_url(db_url: &str) -> bool {
+    db_url.starts_with("postgres://") || db_url.starts_with("postgresql://")
 }
 
-fn normalize_sqlite_url(raw: &str) -> String {
+fn normalize_db_url(raw: &str) -> String {
     let trimmed = raw.trim();
 
+    // Postgres URLs are passed to the backend unchanged.
+    if is_postgres_url(trimmed) {
+        return trimmed.to_string();
+    }
+
     // Preserve in-memory.
-    if trimmed == "sqlite::memory:" {
+    if storage::sqlite::is_in_memory_url(trimmed) {
         return trimmed.to_string();
     }
 
@@ -218,8 +518,7 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
         })?,
     };
 
-    if matches!(cmd, Command::Ui | Command::Seed) && !argv.is_empty() && !argv[0].starts_with("--")
-    {
+    if !argv.is_empty() && !argv[0].starts_with("--") {
         argv.remove(0);
     }
 
@@ -227,6 +526,14 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
     let parsed = match cmd {
         Command::Ui => Args::parse_ui(&mut iter),
         Command::Seed => Args::parse_seed(&mut iter),
+        Command::Export => Args::parse_export(&mut iter),
+        Command::ExportLogs => Args::parse_export_logs(&mut iter),
+        Command::Import => Args::parse_import(&mut iter),
+        Command::ImportCsv => Args::parse_import_csv(&mut iter),
+        Command::ImportAnki => Args::parse_import_anki(&mut iter),
+        Command::Doctor => Args::parse_doctor(&mut iter),
+        #[cfg(feature = "http-api")]
+        Command::Serve => Args::parse_serve(&mut iter),
     }
     .map_err(|e| {
         eprintln!("{e}");
@@ -234,14 +541,17 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
         e
     })?;
 
-    // Open + migrate SQLite at startup. Keep this in the binary glue so core/services stay pure.
-    prepare_sqlite_file(&parsed.db_url)?;
+    // Open + migrate storage at startup. Keep this in the binary glue so core/services stay pure.
+    // `doctor` only reads, so it must not create a missing file.
+    if cmd != Command::Doctor && !is_postgres_url(&parsed.db_url) {
+        prepare_sqlite_file(&parsed.db_url)?;
+    }
 
     match cmd {
         Command::Ui => {
             let clock = Clock::default_clock();
-            let services =
-                AppServices::new_sqlite(&parsed.db_url, clock, parsed.deck_id).await?;
+            backup_on_launch(&parsed, &clock).await;
+            let services = open_services(&parsed.db_url, clock, parsed.deck_id).await?;
 
             let app = DesktopApp {
                 deck_id: services.deck_id(),
@@ -249,10 +559,13 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
                 session_loop: services.session_loop(),
                 card_service: services.card_service(),
                 deck_service: services.deck_service(),
+                media_service: services.media_service(),
                 app_settings: services.app_settings(),
                 writing_tools: services.writing_tools(),
+                ai_usage: services.ai_usage(),
                 open_editor_on_launch: services.open_editor_on_launch(),
                 link_opener: Arc::new(DesktopLinkOpener),
+                audio_player: Arc::new(DesktopAudioPlayer::new()),
             };
 
             let app: Arc<dyn UiApp> = Arc::new(app);
@@ -274,23 +587,344 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
             Ok(())
         }
         Command::Seed => {
-            // Placeholder: we intentionally keep seed separate from the UI launch.
-            // Next step will add concrete operations (create deck, add cards) once the
-            // storage/services API is finalized.
-            eprintln!(
-                "seed: not implemented yet (db={}, deck_id={}).",
-                parsed.db_url, parsed.deck_id
-            );
+            let clock = Clock::default_clock();
+            let services = open_services(&parsed.db_url, clock, parsed.deck_id).await?;
+            run_seed(&services, &parsed).await
+        }
+        Command::Export => {
+            let clock = Clock::default_clock();
+            let services = open_services(&parsed.db_url, clock, parsed.deck_id).await?;
+            run_export(&services, &parsed).await
+        }
+        Command::ExportLogs => {
+            let clock = Clock::default_clock();
+            let services = open_services(&parsed.db_url, clock, parsed.deck_id).await?;
+            run_export_logs(&services, &parsed).await
+        }
+        Command::Import => {
+            let clock = Clock::default_clock();
+            let services = open_services(&parsed.db_url, clock, parsed.deck_id).await?;
+            run_import(&services, &parsed).await
+        }
+        Command::ImportCsv => {
+            let clock = Clock::default_clock();
+            let services = open_services(&parsed.db_url, clock, parsed.deck_id).await?;
+            run_import_csv(&services, &parsed).await
+        }
+        Command::ImportAnki => {
+            let clock = Clock::default_clock();
+            let services = open_services(&parsed.db_url, clock, parsed.deck_id).await?;
+            run_import_anki(&services, &parsed).await
+        }
+        Command::Doctor => run_doctor(&parsed).await,
+        #[cfg(feature = "http-api")]
+        Command::Serve => {
+            let clock = Clock::default_clock();
+            let services = open_services(&parsed.db_url, clock, parsed.deck_id).await?;
+            let token = parsed
+                .api_token
+                .as_deref()
+                .ok_or(ArgsError::MissingFlag { flag: "--token" })?;
+            let addr = std::net::SocketAddr::from(([127, 0, 0, 1], parsed.port));
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            eprintln!("serving API on http://{addr}");
+            let api = http_api::ApiServices::from_app(&services, token);
+            http_api::serve(listener, api).await?;
             Ok(())
         }
     }
 }
 
-fn prepare_sqlite_file(db_url: &str) -> Result<(), Box<dyn std::error::Error>> {
-    if db_url == "sqlite::memory:" {
+const SEED_SAMPLES: [(&str, &str); 20] = [
+    ("Hallo", "Hello"),
+    ("Danke", "Thank you"),
+    ("Bitte", "Please / You are welcome"),
+    ("Tschuss", "Bye"),
+    ("Guten Morgen", "Good morning"),
+    ("Guten Abend", "Good evening"),
+    ("Gute Nacht", "Good night"),
+    ("Ja", "Yes"),
+    ("Nein", "No"),
+    ("Entschuldigung", "Excuse me / Sorry"),
+    ("Wie geht's?", "How are you?"),
+    ("Ich verstehe nicht", "I don't understand"),
+    ("das Wasser", "the water"),
+    ("das Brot", "the bread"),
+    ("der Apfel", "the apple"),
+    ("die Katze", "the cat"),
+    ("der Hund", "the dog"),
+    ("das Haus", "the house"),
+    ("die Stadt", "the city"),
+    ("der Bahnhof", "the train station"),
+];
+
+/// Deterministic sample card for seed position `index`.
+///
+/// Cycles through `SEED_SAMPLES`, suffixing later rounds so fronts stay unique.
+fn seed_sample(index: usize) -> (String, String) {
+    let (front, back) = SEED_SAMPLES[index % SEED_SAMPLES.len()];
+    let round = index / SEED_SAMPLES.len();
+    if round == 0 {
+        (front.to_string(), back.to_string())
+    } else {
+        (format!("{front} ({})", round + 1), format!("{back} ({})", round + 1))
+    }
+}
+
+async fn run_seed(services: &AppServices, parsed: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let deck_service = services.deck_service();
+    let card_service = services.card_service();
+
+    let (deck_id, decks_created) = match deck_service.get_deck(parsed.target_deck_id()).await? {
+        Some(deck) => (deck.id(), 0),
+        None => {
+            let id = deck_service
+                .create_deck("Sample Deck".to_string(), None, DeckSettings::default_for_adhd())
+                .await?;
+            (id, 1)
+        }
+    };
+
+    // Seeded cards are throwaway, so clearing them skips the trash.
+    let mut cards_deleted = 0;
+    if parsed.seed_clear {
+        for card in card_service.list_cards(deck_id, u32::MAX).await? {
+            card_service.delete_card_permanently(deck_id, card.id()).await?;
+            cards_deleted += 1;
+        }
+    }
+
+    for index in 0..usize::try_from(parsed.seed_count)? {
+        let (front, back) = seed_sample(index);
+        card_service
+            .create_card(
+                deck_id,
+                ContentDraft::text_only(front),
+                ContentDraft::text_only(back),
+                None,
+            )
+            .await?;
+    }
+
+    println!(
+        "seed: created {decks_created} deck(s) and {} card(s) in deck {deck_id} \
+         (cleared {cards_deleted})",
+        parsed.seed_count
+    );
+    Ok(())
+}
+
+async fn run_export(
+    services: &AppServices,
+    parsed: &Args,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let out_path = parsed
+        .out_path
+        .as_deref()
+        .ok_or(ArgsError::MissingFlag { flag: "--out" })?;
+    if is_jsonl(out_path) {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(out_path)?);
+        let written = services
+            .deck_service()
+            .export_jsonl(parsed.target_deck_id(), &mut writer)
+            .await?;
+        println!(
+            "export: streamed deck {} with {written} card(s) to {}",
+            parsed.target_deck_id(),
+            out_path.display()
+        );
         return Ok(());
     }
+    let transfer = DeckTransferService::new(services.deck_service(), services.card_service());
+    let export = transfer.export_deck(parsed.target_deck_id()).await?;
+
+    let json = serde_json::to_string_pretty(&export)?;
+    std::fs::write(out_path, json)?;
+    println!(
+        "export: wrote deck {} with {} card(s) to {}",
+        parsed.target_deck_id(),
+        export.cards.len(),
+        out_path.display()
+    );
+    Ok(())
+}
+
+/// JSON-lines files are told apart from single-document exports by their extension.
+fn is_jsonl(path: &std::path::Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("jsonl"))
+}
+
+async fn run_export_logs(
+    services: &AppServices,
+    parsed: &Args,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let out_path = parsed
+        .out_path
+        .as_deref()
+        .ok_or(ArgsError::MissingFlag { flag: "--out" })?;
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(out_path)?);
+    let written = services
+        .deck_service()
+        .export_review_logs(parsed.target_deck_id(), &mut writer, parsed.log_format)
+        .await?;
 
+    println!(
+        "export-logs: wrote {written} review(s) from deck {} to {}",
+        parsed.target_deck_id(),
+        out_path.display()
+    );
+    Ok(())
+}
+
+async fn run_import(
+    services: &AppServices,
+    parsed: &Args,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let in_path = parsed
+        .in_path
+        .as_deref()
+        .ok_or(ArgsError::MissingFlag { flag: "--in" })?;
+    let target = if parsed.reuse_deck {
+        ImportTarget::Existing(parsed.target_deck_id())
+    } else {
+        ImportTarget::NewDeck
+    };
+    let transfer = DeckTransferService::new(services.deck_service(), services.card_service());
+    let report = if is_jsonl(in_path) {
+        // Stream the cards in one line at a time.
+        let reader = std::io::BufReader::new(std::fs::File::open(in_path)?);
+        transfer
+            .import_jsonl(reader, target, parsed.skip_duplicates)
+            .await?
+    } else {
+        // Parse and validate the whole document before touching storage.
+        let export = DeckExport::from_json(&std::fs::read_to_string(in_path)?)?;
+        transfer
+            .import_deck(&export, target, parsed.skip_duplicates)
+            .await?
+    };
+
+    println!(
+        "import: imported {} card(s), skipped {} into deck {}",
+        report.imported, report.skipped, report.deck_id
+    );
+    Ok(())
+}
+
+async fn run_import_csv(
+    services: &AppServices,
+    parsed: &Args,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let in_path = parsed
+        .in_path
+        .as_deref()
+        .ok_or(ArgsError::MissingFlag { flag: "--in" })?;
+    let delimiter = parsed.delimiter.unwrap_or_else(|| {
+        let is_tsv = in_path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("tsv"));
+        if is_tsv { '\t' } else { ',' }
+    });
+
+    let deck_id = services
+        .deck_service()
+        .get_deck(parsed.target_deck_id())
+        .await?
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("deck {} not found", parsed.target_deck_id()),
+            )
+        })?
+        .id();
+    let file = std::fs::File::open(in_path)?;
+    let report = services
+        .card_service()
+        .import_delimited(deck_id, std::io::BufReader::new(file), delimiter)
+        .await?;
+
+    println!("import-csv: imported {} card(s) into deck {deck_id}", report.imported);
+    if !report.failed_rows.is_empty() {
+        let rows: Vec<String> = report.failed_rows.iter().map(ToString::to_string).collect();
+        println!("import-csv: skipped invalid row(s): {}", rows.join(", "));
+    }
+    Ok(())
+}
+
+async fn run_import_anki(
+    services: &AppServices,
+    parsed: &Args,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let in_path = parsed
+        .in_path
+        .as_deref()
+        .ok_or(ArgsError::MissingFlag { flag: "--in" })?;
+    let report = services.anki_import().import_apkg(&std::fs::read(in_path)?).await?;
+
+    println!(
+        "import-anki: imported {} card(s) ({} with review history) into {} deck(s)",
+        report.imported,
+        report.scheduled,
+        report.decks.len()
+    );
+    println!(
+        "import-anki: skipped {} card(s) that could not be mapped",
+        report.skipped
+    );
+    println!(
+        "import-anki: imported {} image(s), skipped {} media reference(s)",
+        report.media_imported, report.media_skipped
+    );
+    Ok(())
+}
+
+/// Check the database without migrating or writing to it, failing when it is unhealthy.
+async fn run_doctor(parsed: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    if is_postgres_url(&parsed.db_url) {
+        return Err("doctor: only SQLite databases can be checked".into());
+    }
+    let repo = storage::sqlite::SqliteRepository::connect_read_only(&parsed.db_url).await?;
+    let report = storage::sqlite::doctor::check(&repo).await?;
+
+    println!("doctor: integrity check: {}", report.integrity.join("; "));
+    println!("doctor: foreign key violations: {}", report.foreign_key_violations);
+    println!("doctor: schema version {}", report.schema_version);
+    println!(
+        "doctor: {} deck(s), {} card(s), {} review(s)",
+        report.decks, report.cards, report.review_logs
+    );
+    println!(
+        "doctor: {} card(s) without a deck, {} review(s) without a card",
+        report.orphaned_cards, report.orphaned_review_logs
+    );
+    if !report.is_healthy() {
+        return Err("doctor: integrity problems found".into());
+    }
+    println!("doctor: no problems found");
+    Ok(())
+}
+
+/// Build services for the backend selected by the `--db` URL scheme.
+async fn open_services(
+    db_url: &str,
+    clock: Clock,
+    deck_id: Option<DeckId>,
+) -> Result<AppServices, Box<dyn std::error::Error>> {
+    if is_postgres_url(db_url) {
+        #[cfg(feature = "postgres")]
+        return Ok(AppServices::new_postgres(db_url, clock, deck_id).await?);
+        #[cfg(not(feature = "postgres"))]
+        return Err(ArgsError::PostgresDisabled {
+            raw: db_url.to_string(),
+        }
+        .into());
+    }
+    Ok(AppServices::new_sqlite(db_url, clock, deck_id).await?)
+}
+
+/// File path of a file-backed `SQLite` URL, without its query string.
+fn sqlite_file_path(db_url: &str) -> Result<&std::path::Path, ArgsError> {
     let path = db_url
         .strip_prefix("sqlite://")
         .or_else(|| db_url.strip_prefix("sqlite:"))
@@ -301,11 +935,52 @@ fn prepare_sqlite_file(db_url: &str) -> Result<(), Box<dyn std::error::Error>> {
     if path.is_empty() {
         return Err(ArgsError::InvalidDbUrl {
             raw: db_url.to_string(),
-        }
-        .into());
+        });
+    }
+    Ok(std::path::Path::new(path))
+}
+
+/// Snapshot a file-backed `SQLite` database before the UI opens it, keeping the newest
+/// `--backup-keep` snapshots.
+///
+/// A failed backup is reported but does not stop the launch.
+async fn backup_on_launch(parsed: &Args, clock: &Clock) {
+    if parsed.backup_keep == 0
+        || is_postgres_url(&parsed.db_url)
+        || storage::sqlite::is_in_memory_url(&parsed.db_url)
+    {
+        return;
+    }
+    let Ok(path) = sqlite_file_path(&parsed.db_url) else {
+        return;
+    };
+    // A file `prepare_sqlite_file` just created holds nothing worth keeping.
+    if std::fs::metadata(path).map_or(true, |meta| meta.len() == 0) {
+        return;
+    }
+    let dir = parsed.backup_dir.clone().unwrap_or_else(|| {
+        path.parent()
+            .unwrap_or_else(|| std::path::Path::new("."))
+            .join("backups")
+    });
+    let result = storage::sqlite::backup::backup_with_rotation(
+        &parsed.db_url,
+        &dir,
+        parsed.backup_keep,
+        clock.now(),
+    )
+    .await;
+    if let Err(err) = result {
+        eprintln!("backup skipped: {err}");
+    }
+}
+
+fn prepare_sqlite_file(db_url: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if storage::sqlite::is_in_memory_url(db_url) {
+        return Ok(());
     }
 
-    let path = std::path::Path::new(path);
+    let path = sqlite_file_path(db_url)?;
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)?;
     }
@@ -321,11 +996,66 @@ fn prepare_sqlite_file(db_url: &str) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Log service spans to stderr when `LEARN_LOG` holds a filter such as `services=debug`.
+///
+/// Installed before launch, so it takes the place of the desktop's default logger.
+#[cfg(feature = "tracing")]
+fn init_tracing() {
+    use tracing_subscriber::fmt::format::FmtSpan;
+
+    let Ok(directives) = std::env::var("LEARN_LOG") else {
+        return;
+    };
+    let filter = match tracing_subscriber::EnvFilter::try_new(&directives) {
+        Ok(filter) => filter,
+        Err(err) => {
+            eprintln!("ignoring LEARN_LOG={directives}: {err}");
+            return;
+        }
+    };
+    // Services emit spans rather than events, so print each span as it closes.
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
 #[tokio::main]
 async fn main() {
+    #[cfg(feature = "tracing")]
+    init_tracing();
     if let Err(err) = run().await {
         // At this layer (binary glue), printing once is fine.
         eprintln!("{err}");
         std::process::exit(2);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use learn_core::time::fixed_now;
+
+    #[tokio::test]
+    async fn seeding_twice_with_clear_leaves_the_trash_empty() {
+        let services = AppServices::new_sqlite(
+            "sqlite:file:memdb_seed_clear?mode=memory&cache=shared",
+            Clock::fixed(fixed_now()),
+            None,
+        )
+        .await
+        .unwrap();
+        let parsed = Args::parse_seed(&mut ["--clear".to_string()].into_iter()).unwrap();
+
+        run_seed(&services, &parsed).await.unwrap();
+        run_seed(&services, &parsed).await.unwrap();
+
+        let deck_id = parsed.target_deck_id();
+        let card_service = services.card_service();
+        assert!(card_service.list_trash(deck_id, u32::MAX).await.unwrap().is_empty());
+        let cards = card_service.list_cards(deck_id, u32::MAX).await.unwrap();
+        assert_eq!(cards.len(), usize::try_from(DEFAULT_SEED_COUNT).unwrap());
+    }
+}
@@ -1,6 +1,9 @@
 pub mod error;
 pub mod model;
+pub mod optimizer;
 pub mod scheduler;
 pub mod time;
 
-pub use time::Clock;
+#[cfg(any(test, feature = "test-support"))]
+pub use time::AdvancingClock;
+pub use time::{Clock, StudyDay};
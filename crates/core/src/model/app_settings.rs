@@ -1,6 +1,72 @@
 use thiserror::Error;
 
+use crate::model::ids::DeckId;
+use crate::model::key_bindings::{KeyBindings, KeyConflict};
+use crate::time::StudyDay;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ThemePreference {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl ThemePreference {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ThemePreference::System => "system",
+            ThemePreference::Light => "light",
+            ThemePreference::Dark => "dark",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AccentColor {
+    #[default]
+    Blue,
+    Green,
+    Orange,
+    Purple,
+}
+
+impl AccentColor {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AccentColor::Blue => "blue",
+            AccentColor::Green => "green",
+            AccentColor::Orange => "orange",
+            AccentColor::Purple => "purple",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AppLanguage {
+    #[default]
+    English,
+    German,
+    Spanish,
+    French,
+}
+
+impl AppLanguage {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AppLanguage::English => "en",
+            AppLanguage::German => "de",
+            AppLanguage::Spanish => "es",
+            AppLanguage::French => "fr",
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct AppSettings {
     api_key: Option<String>,
     api_model: Option<String>,
@@ -8,8 +74,21 @@ pub struct AppSettings {
     ai_system_prompt: Option<String>,
     ai_daily_request_cap: u32,
     ai_cooldown_secs: u32,
+    ai_manual_price_micro_usd_per_1k: Option<u64>,
+    theme: ThemePreference,
+    accent_color: AccentColor,
+    language: AppLanguage,
+    analytics_enabled: bool,
+    autoplay_audio: bool,
+    replay_audio_after_answer: bool,
+    audio_delay_ms: u32,
+    last_deck_id: Option<DeckId>,
+    day_cutoff_hour: u32,
+    focus_mode: bool,
+    key_bindings: KeyBindings,
 }
 
+/// Unvalidated settings; `None` fields fall back to their defaults.
 #[derive(Clone, Debug, Default)]
 pub struct AppSettingsDraft {
     pub api_key: Option<String>,
@@ -18,6 +97,18 @@ pub struct AppSettingsDraft {
     pub ai_system_prompt: Option<String>,
     pub ai_daily_request_cap: Option<u32>,
     pub ai_cooldown_secs: Option<u32>,
+    pub ai_manual_price_micro_usd_per_1k: Option<u64>,
+    pub theme: Option<ThemePreference>,
+    pub accent_color: Option<AccentColor>,
+    pub language: Option<AppLanguage>,
+    pub analytics_enabled: Option<bool>,
+    pub autoplay_audio: Option<bool>,
+    pub replay_audio_after_answer: Option<bool>,
+    pub audio_delay_ms: Option<u32>,
+    pub last_deck_id: Option<DeckId>,
+    pub day_cutoff_hour: Option<u32>,
+    pub focus_mode: Option<bool>,
+    pub key_bindings: Option<KeyBindings>,
 }
 
 #[derive(Debug, Error)]
@@ -27,6 +118,17 @@ pub enum AppSettingsError {
     InvalidDailyRequestCap,
     #[error("cooldown seconds must be greater than zero")]
     InvalidCooldownSeconds,
+    #[error("audio delay must be at most {MAX_AUDIO_DELAY_MS} ms")]
+    InvalidAudioDelay,
+    #[error("day cutoff hour must be at most {}", StudyDay::MAX_CUTOFF_HOUR)]
+    InvalidDayCutoffHour,
+    #[error(
+        "{} and {} are both bound to {}",
+        .0.first.label(),
+        .0.second.label(),
+        .0.combo
+    )]
+    KeyBindingConflict(KeyConflict),
 }
 
 impl AppSettingsDraft {
@@ -39,7 +141,8 @@ impl AppSettingsDraft {
     ///
     /// # Errors
     ///
-    /// Returns `AppSettingsError` if the base URL is present but invalid.
+    /// Returns `AppSettingsError` if the request cap or cooldown is zero, the audio
+    /// delay or day cutoff hour is out of range, or two shortcuts share a key.
     pub fn validate(self) -> Result<AppSettings, AppSettingsError> {
         let api_key = normalize_optional(self.api_key);
         let api_model = normalize_optional(self.api_model);
@@ -57,6 +160,21 @@ impl AppSettingsDraft {
             return Err(AppSettingsError::InvalidCooldownSeconds);
         }
 
+        let audio_delay_ms = self.audio_delay_ms.unwrap_or(DEFAULT_AUDIO_DELAY_MS);
+        if audio_delay_ms > MAX_AUDIO_DELAY_MS {
+            return Err(AppSettingsError::InvalidAudioDelay);
+        }
+
+        let day_cutoff_hour = self.day_cutoff_hour.unwrap_or(0);
+        if day_cutoff_hour > StudyDay::MAX_CUTOFF_HOUR {
+            return Err(AppSettingsError::InvalidDayCutoffHour);
+        }
+
+        let key_bindings = self.key_bindings.unwrap_or_default();
+        if let Some(conflict) = key_bindings.conflicts().first() {
+            return Err(AppSettingsError::KeyBindingConflict(*conflict));
+        }
+
         Ok(AppSettings {
             api_key,
             api_model,
@@ -64,6 +182,18 @@ impl AppSettingsDraft {
             ai_system_prompt,
             ai_daily_request_cap,
             ai_cooldown_secs,
+            ai_manual_price_micro_usd_per_1k: self.ai_manual_price_micro_usd_per_1k,
+            theme: self.theme.unwrap_or_default(),
+            accent_color: self.accent_color.unwrap_or_default(),
+            language: self.language.unwrap_or_default(),
+            analytics_enabled: self.analytics_enabled.unwrap_or(false),
+            autoplay_audio: self.autoplay_audio.unwrap_or(true),
+            replay_audio_after_answer: self.replay_audio_after_answer.unwrap_or(false),
+            audio_delay_ms,
+            last_deck_id: self.last_deck_id,
+            day_cutoff_hour,
+            focus_mode: self.focus_mode.unwrap_or(false),
+            key_bindings,
         })
     }
 }
@@ -78,6 +208,31 @@ impl AppSettings {
         draft.validate()
     }
 
+    /// A draft holding the current values, for editing a subset of fields.
+    #[must_use]
+    pub fn to_draft(&self) -> AppSettingsDraft {
+        AppSettingsDraft {
+            api_key: self.api_key.clone(),
+            api_model: self.api_model.clone(),
+            api_fallback_model: self.api_fallback_model.clone(),
+            ai_system_prompt: self.ai_system_prompt.clone(),
+            ai_daily_request_cap: Some(self.ai_daily_request_cap),
+            ai_cooldown_secs: Some(self.ai_cooldown_secs),
+            ai_manual_price_micro_usd_per_1k: self.ai_manual_price_micro_usd_per_1k,
+            theme: Some(self.theme),
+            accent_color: Some(self.accent_color),
+            language: Some(self.language),
+            analytics_enabled: Some(self.analytics_enabled),
+            autoplay_audio: Some(self.autoplay_audio),
+            replay_audio_after_answer: Some(self.replay_audio_after_answer),
+            audio_delay_ms: Some(self.audio_delay_ms),
+            last_deck_id: self.last_deck_id,
+            day_cutoff_hour: Some(self.day_cutoff_hour),
+            focus_mode: Some(self.focus_mode),
+            key_bindings: Some(self.key_bindings),
+        }
+    }
+
     #[must_use]
     pub fn api_key(&self) -> Option<&str> {
         self.api_key.as_deref()
@@ -107,6 +262,82 @@ impl AppSettings {
     pub fn ai_cooldown_secs(&self) -> u32 {
         self.ai_cooldown_secs
     }
+
+    /// Price in micro-USD per 1,000 tokens, input and output alike, for a preferred model
+    /// the price book does not list.
+    #[must_use]
+    pub fn ai_manual_price_micro_usd_per_1k(&self) -> Option<u64> {
+        self.ai_manual_price_micro_usd_per_1k
+    }
+
+    #[must_use]
+    pub fn theme(&self) -> ThemePreference {
+        self.theme
+    }
+
+    #[must_use]
+    pub fn accent_color(&self) -> AccentColor {
+        self.accent_color
+    }
+
+    #[must_use]
+    pub fn language(&self) -> AppLanguage {
+        self.language
+    }
+
+    #[must_use]
+    pub fn analytics_enabled(&self) -> bool {
+        self.analytics_enabled
+    }
+
+    /// Play a card's prompt audio when it appears.
+    #[must_use]
+    pub fn autoplay_audio(&self) -> bool {
+        self.autoplay_audio
+    }
+
+    /// Play audio again once the answer is revealed.
+    #[must_use]
+    pub fn replay_audio_after_answer(&self) -> bool {
+        self.replay_audio_after_answer
+    }
+
+    /// Pause before audio starts playing.
+    #[must_use]
+    pub fn audio_delay_ms(&self) -> u32 {
+        self.audio_delay_ms
+    }
+
+    /// Deck the user last switched to, opened on the next launch.
+    #[must_use]
+    pub fn last_deck_id(&self) -> Option<DeckId> {
+        self.last_deck_id
+    }
+
+    /// Hour of the local day a new study day starts; earlier reviews count toward the day
+    /// before.
+    #[must_use]
+    pub fn day_cutoff_hour(&self) -> u32 {
+        self.day_cutoff_hour
+    }
+
+    /// Review sessions hide navigation and deck details, leaving the card and grade buttons.
+    #[must_use]
+    pub fn focus_mode(&self) -> bool {
+        self.focus_mode
+    }
+
+    /// Keys for the editor and session shortcuts the user can remap.
+    #[must_use]
+    pub fn key_bindings(&self) -> &KeyBindings {
+        &self.key_bindings
+    }
+
+    /// Study-day boundaries for daily limits and streaks.
+    #[must_use]
+    pub fn study_day(&self) -> StudyDay {
+        StudyDay::new(self.day_cutoff_hour)
+    }
 }
 
 impl Default for AppSettings {
@@ -118,6 +349,18 @@ impl Default for AppSettings {
             ai_system_prompt: None,
             ai_daily_request_cap: DEFAULT_AI_DAILY_REQUEST_CAP,
             ai_cooldown_secs: DEFAULT_AI_COOLDOWN_SECS,
+            ai_manual_price_micro_usd_per_1k: None,
+            theme: ThemePreference::System,
+            accent_color: AccentColor::Blue,
+            language: AppLanguage::English,
+            analytics_enabled: false,
+            autoplay_audio: true,
+            replay_audio_after_answer: false,
+            audio_delay_ms: DEFAULT_AUDIO_DELAY_MS,
+            last_deck_id: None,
+            day_cutoff_hour: 0,
+            focus_mode: false,
+            key_bindings: KeyBindings::default(),
         }
     }
 }
@@ -130,5 +373,7 @@ fn normalize_optional(value: Option<String>) -> Option<String> {
 
 const DEFAULT_AI_DAILY_REQUEST_CAP: u32 = 100;
 const DEFAULT_AI_COOLDOWN_SECS: u32 = 5;
+const DEFAULT_AUDIO_DELAY_MS: u32 = 300;
+const MAX_AUDIO_DELAY_MS: u32 = 5_000;
 const DEFAULT_AI_PREFERRED_MODEL: &str = "gpt-4.1-mini";
 const DEFAULT_AI_FALLBACK_MODEL: &str = "gpt-4o-mini";
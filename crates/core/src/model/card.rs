@@ -2,8 +2,9 @@ use chrono::{DateTime, Utc};
 use thiserror::Error;
 
 use crate::model::{
-    content::{Content, ContentValidationError},
-    ids::{CardId, DeckId},
+    cloze::{ClozeError, ClozeText},
+    content::{AudioClip, Content, ContentValidationError},
+    ids::{CardId, DeckId, NoteId},
     review::{ReviewGrade, ReviewOutcome},
 };
 use crate::scheduler::MemoryState;
@@ -32,6 +33,95 @@ pub enum CardPhase {
     Reviewing,
     Relearning,
 }
+
+/// What a card asks about.
+///
+/// A cloze note produces one `Cloze` card per index (see `expand_cloze`), so each deletion is
+/// scheduled and logged as its own card.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CardKind {
+    /// Plain prompt/answer card.
+    #[default]
+    Basic,
+    /// One position of a cloze note: the full source `text` plus the indices this card hides.
+    Cloze { text: String, indices: Vec<u32> },
+}
+
+/// One face of a card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardSide {
+    Prompt,
+    Answer,
+}
+
+/// Colored marker for triaging cards, set independently of tags.
+///
+/// A card carries at most one flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flag {
+    Red,
+    Orange,
+    Green,
+    Blue,
+}
+
+impl Flag {
+    /// Every flag, in the order `cycle` steps through them.
+    pub const ALL: [Flag; 4] = [Flag::Red, Flag::Orange, Flag::Green, Flag::Blue];
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Flag::Red => "red",
+            Flag::Orange => "orange",
+            Flag::Green => "green",
+            Flag::Blue => "blue",
+        }
+    }
+
+    /// The flag after `current`: unflagged goes to red, blue back to unflagged.
+    #[must_use]
+    pub fn cycle(current: Option<Flag>) -> Option<Flag> {
+        match current {
+            None => Some(Flag::Red),
+            Some(flag) => {
+                let index = Self::ALL.iter().position(|item| *item == flag).unwrap_or(0);
+                Self::ALL.get(index + 1).copied()
+            }
+        }
+    }
+}
+
+/// How a card entered the collection, kept so imported or AI-assisted cards can be found
+/// and cleaned up later.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CardSource {
+    /// Typed in the editor or added through the API.
+    #[default]
+    Manual,
+    /// A row of a CSV or TSV import.
+    Csv,
+    /// A note from an Anki package.
+    Anki,
+    /// Written in the editor with AI writing tools.
+    Ai,
+}
+
+impl CardSource {
+    pub const ALL: [CardSource; 4] =
+        [CardSource::Manual, CardSource::Csv, CardSource::Anki, CardSource::Ai];
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CardSource::Manual => "manual",
+            CardSource::Csv => "csv",
+            CardSource::Anki => "anki",
+            CardSource::Ai => "ai",
+        }
+    }
+}
+
 //
 // ─── ERRORS ────────────────────────────────────────────────────────────────────
 //
@@ -45,6 +135,12 @@ pub enum CardError {
     #[error("invalid answer content: {0}")]
     InvalidAnswer(#[source] ContentValidationError),
 
+    #[error("invalid extra content: {0}")]
+    InvalidExtra(#[source] ContentValidationError),
+
+    #[error("invalid cloze card: {0}")]
+    InvalidCloze(#[source] ClozeError),
+
     #[error("invalid persisted card state: {0}")]
     InvalidPersistedState(String),
 }
@@ -60,15 +156,35 @@ pub enum CardError {
 pub struct Card {
     id: CardId,
     deck_id: DeckId,
+    kind: CardKind,
+    note_id: Option<NoteId>,
     prompt: Content,
     answer: Content,
+    extra: Option<Content>,
     phase: CardPhase,
     created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
     next_review_at: DateTime<Utc>,
     last_review_at: Option<DateTime<Utc>>,
     review_count: u32,
     stability: f64,
     difficulty: f64,
+    suspended: bool,
+    buried_until: Option<DateTime<Utc>>,
+    learning_step: u32,
+    lapses: u32,
+    flag: Option<Flag>,
+    source: Option<CardSource>,
+}
+
+/// Where a review leaves a card that is working through its deck's learning or relearning
+/// steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LearningStep {
+    /// Wait `delay_secs` on the step at `index` before the next answer.
+    Step { index: u32, delay_secs: u32 },
+    /// Leave the steps and schedule the card with FSRS.
+    Graduate,
 }
 
 /// Type-state wrapper for card lifecycle phases.
@@ -85,6 +201,7 @@ impl Card {
     ///
     /// * `id` - Unique identifier for this card
     /// * `deck_id` - ID of the deck this card belongs to
+    /// * `kind` - Basic or one position of a cloze note
     /// * `prompt` - The question/prompt content (must have non-empty text)
     /// * `answer` - The answer content (must have non-empty text)
     /// * `created_at` - Timestamp when the card was created
@@ -94,11 +211,14 @@ impl Card {
     ///
     /// Returns `CardError::InvalidPrompt` if prompt text is empty.
     /// Returns `CardError::InvalidAnswer` if answer text is empty.
+    /// Returns `CardError::InvalidCloze` if a cloze kind has no `{{c1::...}}` markers or asks
+    /// for an index its text does not contain.
     ///
     /// Note: Content is already validated, so this performs additional domain-level validation.
     pub fn new(
         id: CardId,
         deck_id: DeckId,
+        kind: CardKind,
         prompt: Content,
         answer: Content,
         created_at: DateTime<Utc>,
@@ -112,18 +232,32 @@ impl Card {
             return Err(CardError::InvalidAnswer(ContentValidationError::EmptyText));
         }
 
+        if let CardKind::Cloze { text, indices } = &kind {
+            validate_cloze(text, indices).map_err(CardError::InvalidCloze)?;
+        }
+
         Ok(Self {
             id,
             deck_id,
+            kind,
+            note_id: None,
             prompt,
             answer,
+            extra: None,
             phase: CardPhase::New,
             created_at,
+            updated_at: created_at,
             next_review_at,
             last_review_at: None,
             review_count: 0,
             stability: 0.0,
             difficulty: 0.0,
+            suspended: false,
+            buried_until: None,
+            learning_step: 0,
+            lapses: 0,
+            flag: None,
+            source: None,
         })
     }
 
@@ -136,6 +270,7 @@ impl Card {
     pub fn from_persisted(
         id: CardId,
         deck_id: DeckId,
+        kind: CardKind,
         prompt: Content,
         answer: Content,
         created_at: DateTime<Utc>,
@@ -146,7 +281,7 @@ impl Card {
         stability: f64,
         difficulty: f64,
     ) -> Result<Self, CardError> {
-        let mut card = Self::new(id, deck_id, prompt, answer, created_at, next_review_at)?;
+        let mut card = Self::new(id, deck_id, kind, prompt, answer, created_at, next_review_at)?;
         card.last_review_at = last_review_at;
         card.phase = phase;
         card.review_count = review_count;
@@ -171,6 +306,182 @@ impl Card {
         self.phase
     }
 
+    #[must_use]
+    pub fn kind(&self) -> &CardKind {
+        &self.kind
+    }
+
+    /// Note shared with sibling cards (e.g. the reverse direction), if any.
+    #[must_use]
+    pub fn note_id(&self) -> Option<NoteId> {
+        self.note_id
+    }
+
+    /// Link (or unlink) the card to a note.
+    #[must_use]
+    pub fn with_note_id(mut self, note_id: Option<NoteId>) -> Self {
+        self.note_id = note_id;
+        self
+    }
+
+    /// Suspended cards stay in their deck but are left out of sessions and due counts.
+    #[must_use]
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// Suspend (or resume) the card, keeping content and scheduling state.
+    #[must_use]
+    pub fn with_suspended(mut self, suspended: bool) -> Self {
+        self.suspended = suspended;
+        self
+    }
+
+    #[must_use]
+    pub fn flag(&self) -> Option<Flag> {
+        self.flag
+    }
+
+    /// Set (or clear) the card's flag, keeping content and scheduling state.
+    #[must_use]
+    pub fn with_flag(mut self, flag: Option<Flag>) -> Self {
+        self.flag = flag;
+        self
+    }
+
+    /// How the card was created; `None` for cards that predate source tracking.
+    #[must_use]
+    pub fn source(&self) -> Option<CardSource> {
+        self.source
+    }
+
+    #[must_use]
+    pub fn with_source(mut self, source: Option<CardSource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// End of the bury window set by `with_buried_until`, if any.
+    #[must_use]
+    pub fn buried_until(&self) -> Option<DateTime<Utc>> {
+        self.buried_until
+    }
+
+    /// Buried cards sit out until `buried_until` passes; unlike suspension this clears itself.
+    #[must_use]
+    pub fn is_buried_at(&self, now: DateTime<Utc>) -> bool {
+        self.buried_until.is_some_and(|until| now < until)
+    }
+
+    /// Bury the card until `until`, or clear the marker with `None`.
+    #[must_use]
+    pub fn with_buried_until(mut self, until: Option<DateTime<Utc>>) -> Self {
+        self.buried_until = until;
+        self
+    }
+
+    /// Index of the learning step the card is waiting on; 0 outside the learning steps.
+    #[must_use]
+    pub fn learning_step(&self) -> u32 {
+        self.learning_step
+    }
+
+    /// Restore the learning step index, e.g. from storage.
+    #[must_use]
+    pub fn with_learning_step(mut self, learning_step: u32) -> Self {
+        self.learning_step = learning_step;
+        self
+    }
+
+    /// How many times the card was forgotten after graduating.
+    #[must_use]
+    pub fn lapses(&self) -> u32 {
+        self.lapses
+    }
+
+    /// Restore the lapse count, e.g. from storage.
+    #[must_use]
+    pub fn with_lapses(mut self, lapses: u32) -> Self {
+        self.lapses = lapses;
+        self
+    }
+
+    /// Where answering with `grade` moves the card within `steps`.
+    ///
+    /// Returns `None` when `steps` is empty or the card is past them (reviewing or
+    /// relearning). Again restarts at the first step, Hard repeats the current one, Good
+    /// moves to the next and graduates after the last, and Easy graduates at once.
+    #[must_use]
+    pub fn next_learning_step(&self, grade: ReviewGrade, steps: &[u32]) -> Option<LearningStep> {
+        if steps.is_empty() || !matches!(self.phase, CardPhase::New | CardPhase::Learning) {
+            return None;
+        }
+        let current = if self.phase == CardPhase::New {
+            0
+        } else {
+            usize::try_from(self.learning_step)
+                .unwrap_or(usize::MAX)
+                .min(steps.len() - 1)
+        };
+        let index = match grade {
+            ReviewGrade::Again => 0,
+            ReviewGrade::Hard => current,
+            ReviewGrade::Good => current + 1,
+            ReviewGrade::Easy => return Some(LearningStep::Graduate),
+        };
+        Some(steps.get(index).map_or(LearningStep::Graduate, |&delay_secs| {
+            LearningStep::Step {
+                index: u32::try_from(index).unwrap_or(u32::MAX),
+                delay_secs,
+            }
+        }))
+    }
+
+    /// Where answering with `grade` moves the card within relearning `steps`.
+    ///
+    /// Returns `None` when `steps` is empty, for new and learning cards, and for reviewing
+    /// cards answered with anything but Again. Again on a reviewing card lapses it onto the
+    /// first step; a relearning card then moves through the steps like a learning card.
+    #[must_use]
+    pub fn next_relearning_step(&self, grade: ReviewGrade, steps: &[u32]) -> Option<LearningStep> {
+        if steps.is_empty() {
+            return None;
+        }
+        let current = match (self.phase, grade) {
+            (CardPhase::Reviewing, ReviewGrade::Again) => 0,
+            (CardPhase::Relearning, _) => usize::try_from(self.learning_step)
+                .unwrap_or(usize::MAX)
+                .min(steps.len() - 1),
+            _ => return None,
+        };
+        let index = match grade {
+            ReviewGrade::Again => 0,
+            ReviewGrade::Hard => current,
+            ReviewGrade::Good => current + 1,
+            ReviewGrade::Easy => return Some(LearningStep::Graduate),
+        };
+        Some(steps.get(index).map_or(LearningStep::Graduate, |&delay_secs| {
+            LearningStep::Step {
+                index: u32::try_from(index).unwrap_or(u32::MAX),
+                delay_secs,
+            }
+        }))
+    }
+
+    /// Reassign the card to another deck, keeping content and scheduling state.
+    pub fn move_to_deck(&mut self, deck_id: DeckId) {
+        self.deck_id = deck_id;
+    }
+
+    /// Attach (or clear) audio on one side, keeping its text and scheduling state.
+    pub fn set_audio(&mut self, side: CardSide, audio: Option<AudioClip>) {
+        let content = match side {
+            CardSide::Prompt => &mut self.prompt,
+            CardSide::Answer => &mut self.answer,
+        };
+        *content = content.clone().with_audio(audio);
+    }
+
     #[must_use]
     pub fn prompt(&self) -> &Content {
         &self.prompt
@@ -181,11 +492,37 @@ impl Card {
         &self.answer
     }
 
+    /// Optional extra info (usage notes, IPA) shown beneath the answer once it is revealed.
+    #[must_use]
+    pub fn extra(&self) -> Option<&Content> {
+        self.extra.as_ref()
+    }
+
+    /// Set (or clear) the extra info, keeping prompt, answer, and scheduling state.
+    #[must_use]
+    pub fn with_extra(mut self, extra: Option<Content>) -> Self {
+        self.extra = extra;
+        self
+    }
+
     #[must_use]
     pub fn created_at(&self) -> DateTime<Utc> {
         self.created_at
     }
 
+    /// When the card was last written; starts out equal to `created_at`.
+    #[must_use]
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    /// Stamp the time of the latest write, keeping everything else.
+    #[must_use]
+    pub fn with_updated_at(mut self, updated_at: DateTime<Utc>) -> Self {
+        self.updated_at = updated_at;
+        self
+    }
+
     #[must_use]
     pub fn next_review_at(&self) -> DateTime<Utc> {
         self.next_review_at
@@ -236,7 +573,7 @@ impl Card {
     /// Phase transition rules (can evolve with product needs):
     /// - `New` -> `Learning` on any grade.
     /// - `Learning` -> `Reviewing` on Hard/Good/Easy; stay `Learning` on Again.
-    /// - `Reviewing` -> `Relearning` on Again; stay `Reviewing` otherwise.
+    /// - `Reviewing` -> `Relearning` on Again, counting a lapse; stay `Reviewing` otherwise.
     /// - `Relearning` -> `Reviewing` on Hard/Good/Easy; stay `Relearning` on Again.
     pub fn apply_review_with_phase(
         &mut self,
@@ -246,6 +583,9 @@ impl Card {
     ) {
         self.apply_review(outcome, reviewed_at);
 
+        if (self.phase, grade) == (CardPhase::Reviewing, ReviewGrade::Again) {
+            self.lapses = self.lapses.saturating_add(1);
+        }
         self.phase = match (self.phase, grade) {
             (CardPhase::New, _) | (CardPhase::Learning, ReviewGrade::Again) => CardPhase::Learning,
             (
@@ -256,6 +596,42 @@ impl Card {
                 CardPhase::Relearning
             }
         };
+        self.learning_step = 0;
+    }
+
+    /// Apply a review outcome for a card in its learning or relearning steps.
+    ///
+    /// A `Step` keeps the card on that step, `Learning` for new and learning cards and
+    /// `Relearning` once it has lapsed; a reviewing card stepping back counts a lapse.
+    /// `Graduate` moves it to `Reviewing`.
+    pub fn apply_learning_review(
+        &mut self,
+        step: LearningStep,
+        outcome: &ReviewOutcome,
+        reviewed_at: DateTime<Utc>,
+    ) {
+        self.apply_review(outcome, reviewed_at);
+
+        let relearning = matches!(self.phase, CardPhase::Reviewing | CardPhase::Relearning);
+        if self.phase == CardPhase::Reviewing && matches!(step, LearningStep::Step { .. }) {
+            self.lapses = self.lapses.saturating_add(1);
+        }
+        (self.phase, self.learning_step) = match step {
+            LearningStep::Step { index, .. } if relearning => (CardPhase::Relearning, index),
+            LearningStep::Step { index, .. } => (CardPhase::Learning, index),
+            LearningStep::Graduate => (CardPhase::Reviewing, 0),
+        };
+    }
+}
+
+fn validate_cloze(text: &str, indices: &[u32]) -> Result<(), ClozeError> {
+    if indices.is_empty() {
+        return Err(ClozeError::NoIndices);
+    }
+    let available = ClozeText::parse(text)?.indices();
+    match indices.iter().find(|index| !available.contains(index)) {
+        Some(missing) => Err(ClozeError::MissingIndex(*missing)),
+        None => Ok(()),
     }
 }
 
@@ -376,6 +752,7 @@ impl CardState<Reviewing> {
     pub fn lapse(self) -> CardState<Relearning> {
         let mut card = self.card;
         card.phase = CardPhase::Relearning;
+        card.lapses = card.lapses.saturating_add(1);
         CardState {
             card,
             state: std::marker::PhantomData,
@@ -470,12 +847,28 @@ mod tests {
             .unwrap();
 
         let now = fixed_now();
-        let card = Card::new(CardId::new(10), DeckId::new(5), prompt, answer, now, now).unwrap();
+        let card = Card::new(
+            CardId::new(10),
+            DeckId::new(5),
+            CardKind::Basic,
+            prompt,
+            answer,
+            now,
+            now,
+        )
+        .unwrap();
 
         assert_eq!(card.id(), CardId::new(10));
         assert_eq!(card.deck_id(), DeckId::new(5));
         assert_eq!(card.prompt().text(), "What is 2+2?");
         assert_eq!(card.answer().text(), "4");
+        assert!(!card.is_suspended());
+        assert!(card.clone().with_suspended(true).is_suspended());
+
+        let buried = card.with_buried_until(Some(now + chrono::Duration::hours(6)));
+        assert!(!buried.is_suspended());
+        assert!(buried.is_buried_at(now));
+        assert!(!buried.is_buried_at(now + chrono::Duration::hours(6)));
     }
 
     #[test]
@@ -495,7 +888,25 @@ mod tests {
             .validate(now, None, None)
             .unwrap();
 
-        let card = Card::new(CardId::new(1), DeckId::new(1), prompt, answer, now, now).unwrap();
+        let card = Card::new(
+
+            CardId::new(1),
+
+            DeckId::new(1),
+
+            CardKind::Basic,
+
+            prompt,
+
+            answer,
+
+            now,
+
+            now,
+
+        )
+
+        .unwrap();
 
         assert!(card.prompt().has_media());
         assert!(!card.answer().has_media());
@@ -520,7 +931,16 @@ mod tests {
             .validate(fixed_now(), None, None)
             .unwrap();
         let now = fixed_now();
-        let card = Card::new(CardId::new(1), DeckId::new(1), prompt, answer, now, now).unwrap();
+        let card = Card::new(
+            CardId::new(1),
+            DeckId::new(1),
+            CardKind::Basic,
+            prompt,
+            answer,
+            now,
+            now,
+        )
+        .unwrap();
 
         let new_state = CardState::<New>::new(card);
         assert_eq!(new_state.phase(), CardPhase::New);
@@ -533,11 +953,55 @@ mod tests {
 
         let relearning = reviewing.lapse();
         assert_eq!(relearning.phase(), CardPhase::Relearning);
+        assert_eq!(relearning.card().lapses(), 1);
 
         let back = relearning.regraduate();
         assert_eq!(back.phase(), CardPhase::Reviewing);
     }
 
+    #[test]
+    fn flags_cycle_through_every_color_then_clear() {
+        let mut flag = None;
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            flag = Flag::cycle(flag);
+            seen.push(flag);
+        }
+        assert_eq!(
+            seen,
+            [Some(Flag::Red), Some(Flag::Orange), Some(Flag::Green), Some(Flag::Blue), None]
+        );
+    }
+
+    #[test]
+    fn lapses_count_only_forgotten_review_cards() {
+        let now = fixed_now();
+        let content = |text| ContentDraft::text_only(text).validate(now, None, None).unwrap();
+        let outcome = ReviewOutcome::new(now + chrono::Duration::days(1), 1.0, 2.0, 0.0, 1.0);
+        let mut card = Card::new(
+            CardId::new(1),
+            DeckId::new(1),
+            CardKind::Basic,
+            content("Q"),
+            content("A"),
+            now,
+            now,
+        )
+        .unwrap();
+
+        for grade in [ReviewGrade::Again, ReviewGrade::Again, ReviewGrade::Good] {
+            card.apply_review_with_phase(grade, &outcome, now);
+        }
+        assert_eq!((card.phase(), card.lapses()), (CardPhase::Reviewing, 0));
+
+        card.apply_review_with_phase(ReviewGrade::Again, &outcome, now);
+        card.apply_review_with_phase(ReviewGrade::Again, &outcome, now);
+        assert_eq!((card.phase(), card.lapses()), (CardPhase::Relearning, 1));
+        card.apply_review_with_phase(ReviewGrade::Good, &outcome, now);
+        card.apply_review_with_phase(ReviewGrade::Again, &outcome, now);
+        assert_eq!(card.lapses(), 2);
+    }
+
     #[test]
     fn applying_outcome_updates_card_across_states() {
         let prompt = ContentDraft::text_only("Q")
@@ -549,7 +1013,25 @@ mod tests {
         let now = fixed_now();
         let outcome = ReviewOutcome::new(now + chrono::Duration::days(1), 1.0, 2.0, 0.0, 1.0);
 
-        let mut card = Card::new(CardId::new(1), DeckId::new(1), prompt, answer, now, now).unwrap();
+        let mut card = Card::new(
+
+            CardId::new(1),
+
+            DeckId::new(1),
+
+            CardKind::Basic,
+
+            prompt,
+
+            answer,
+
+            now,
+
+            now,
+
+        )
+
+        .unwrap();
 
         card.apply_review_with_phase(ReviewGrade::Good, &outcome, now);
         assert_eq!(card.review_count(), 1);
@@ -569,6 +1051,61 @@ mod tests {
         assert_eq!(card.phase(), CardPhase::Reviewing);
     }
 
+    #[test]
+    fn cloze_kind_requires_markers_and_known_indices() {
+        use crate::model::cloze::expand_cloze;
+
+        let now = fixed_now();
+        let source = ContentDraft::text_only("{{c1::Paris}} is in {{c2::France}}")
+            .validate(now, None, None)
+            .unwrap();
+        let pairs = expand_cloze(&source).unwrap();
+        let cloze = |indices: Vec<u32>| CardKind::Cloze {
+            text: source.text().to_string(),
+            indices,
+        };
+
+        let card = Card::new(
+            CardId::new(1),
+            DeckId::new(1),
+            cloze(vec![2]),
+            pairs[1].prompt.clone(),
+            pairs[1].answer.clone(),
+            now,
+            now,
+        )
+        .unwrap();
+        assert_eq!(card.prompt().text(), "Paris is in [...]");
+        assert!(matches!(card.kind(), CardKind::Cloze { indices, .. } if indices == &[2]));
+
+        let err = Card::new(
+            CardId::new(2),
+            DeckId::new(1),
+            cloze(vec![3]),
+            pairs[0].prompt.clone(),
+            pairs[0].answer.clone(),
+            now,
+            now,
+        )
+        .unwrap_err();
+        assert_eq!(err, CardError::InvalidCloze(ClozeError::MissingIndex(3)));
+
+        let err = Card::new(
+            CardId::new(3),
+            DeckId::new(1),
+            CardKind::Cloze {
+                text: "no markers".to_string(),
+                indices: vec![1],
+            },
+            pairs[0].prompt.clone(),
+            pairs[0].answer.clone(),
+            now,
+            now,
+        )
+        .unwrap_err();
+        assert_eq!(err, CardError::InvalidCloze(ClozeError::NoDeletions));
+    }
+
     #[test]
     fn card_phase_as_str() {
         assert_eq!(CardPhase::New.as_str(), "new");
@@ -587,7 +1124,16 @@ mod tests {
             .unwrap();
         let now = fixed_now();
         let outcome = ReviewOutcome::new(now + chrono::Duration::days(1), 1.0, 2.0, 0.0, 1.0);
-        let mut card = Card::new(CardId::new(1), DeckId::new(1), prompt, answer, now, now).unwrap();
+        let mut card = Card::new(
+            CardId::new(1),
+            DeckId::new(1),
+            CardKind::Basic,
+            prompt,
+            answer,
+            now,
+            now,
+        )
+        .unwrap();
 
         assert_eq!(card.phase(), CardPhase::New);
         card.apply_review_with_phase(ReviewGrade::Good, &outcome, now);
@@ -602,4 +1148,113 @@ mod tests {
         card.apply_review_with_phase(ReviewGrade::Hard, &outcome, now);
         assert_eq!(card.phase(), CardPhase::Reviewing);
     }
+
+    #[test]
+    fn learning_steps_advance_reset_and_graduate() {
+        let prompt = ContentDraft::text_only("Q")
+            .validate(fixed_now(), None, None)
+            .unwrap();
+        let answer = ContentDraft::text_only("A")
+            .validate(fixed_now(), None, None)
+            .unwrap();
+        let now = fixed_now();
+        let outcome = ReviewOutcome::new(now + chrono::Duration::days(1), 1.0, 2.0, 0.0, 1.0);
+        let mut card =
+            Card::new(CardId::new(1), DeckId::new(1), CardKind::Basic, prompt, answer, now, now)
+                .unwrap();
+        let steps = [60, 600];
+        let answer_with = |card: &mut Card, grade| {
+            let step = card.next_learning_step(grade, &steps).unwrap();
+            card.apply_learning_review(step, &outcome, now);
+            step
+        };
+
+        let step = answer_with(&mut card, ReviewGrade::Good);
+        assert_eq!(step, LearningStep::Step { index: 1, delay_secs: 600 });
+        assert_eq!((card.phase(), card.learning_step()), (CardPhase::Learning, 1));
+
+        let step = answer_with(&mut card, ReviewGrade::Hard);
+        assert_eq!(step, LearningStep::Step { index: 1, delay_secs: 600 });
+
+        let step = answer_with(&mut card, ReviewGrade::Again);
+        assert_eq!(step, LearningStep::Step { index: 0, delay_secs: 60 });
+        assert_eq!((card.phase(), card.learning_step()), (CardPhase::Learning, 0));
+
+        answer_with(&mut card, ReviewGrade::Good);
+        assert_eq!(answer_with(&mut card, ReviewGrade::Good), LearningStep::Graduate);
+        assert_eq!((card.phase(), card.learning_step()), (CardPhase::Reviewing, 0));
+        assert_eq!(card.next_learning_step(ReviewGrade::Again, &steps), None);
+    }
+
+    #[test]
+    fn learning_steps_skip_when_empty_or_easy() {
+        let prompt = ContentDraft::text_only("Q")
+            .validate(fixed_now(), None, None)
+            .unwrap();
+        let answer = ContentDraft::text_only("A")
+            .validate(fixed_now(), None, None)
+            .unwrap();
+        let now = fixed_now();
+        let card =
+            Card::new(CardId::new(1), DeckId::new(1), CardKind::Basic, prompt, answer, now, now)
+                .unwrap();
+
+        assert_eq!(card.next_learning_step(ReviewGrade::Good, &[]), None);
+        assert_eq!(
+            card.next_learning_step(ReviewGrade::Easy, &[60, 600]),
+            Some(LearningStep::Graduate)
+        );
+        assert_eq!(
+            card.next_learning_step(ReviewGrade::Good, &[60]),
+            Some(LearningStep::Graduate)
+        );
+    }
+
+    #[test]
+    fn relearning_steps_lapse_review_cards_and_graduate() {
+        let prompt = ContentDraft::text_only("Q")
+            .validate(fixed_now(), None, None)
+            .unwrap();
+        let answer = ContentDraft::text_only("A")
+            .validate(fixed_now(), None, None)
+            .unwrap();
+        let now = fixed_now();
+        let outcome = ReviewOutcome::new(now + chrono::Duration::days(1), 1.0, 2.0, 0.0, 1.0);
+        let mut card = Card::from_persisted(
+            CardId::new(1),
+            DeckId::new(1),
+            CardKind::Basic,
+            prompt,
+            answer,
+            now,
+            now,
+            Some(now),
+            CardPhase::Reviewing,
+            3,
+            5.0,
+            5.0,
+        )
+        .unwrap();
+        let steps = [300, 1_800];
+        let state = |card: &Card| (card.phase(), card.learning_step(), card.lapses());
+
+        assert_eq!(card.next_relearning_step(ReviewGrade::Good, &steps), None);
+        assert_eq!(card.next_relearning_step(ReviewGrade::Again, &[]), None);
+
+        let step = card.next_relearning_step(ReviewGrade::Again, &steps).unwrap();
+        assert_eq!(step, LearningStep::Step { index: 0, delay_secs: 300 });
+        card.apply_learning_review(step, &outcome, now);
+        assert_eq!(state(&card), (CardPhase::Relearning, 0, 1));
+        assert_eq!(card.next_learning_step(ReviewGrade::Good, &[60, 600]), None);
+
+        let step = card.next_relearning_step(ReviewGrade::Good, &steps).unwrap();
+        assert_eq!(step, LearningStep::Step { index: 1, delay_secs: 1_800 });
+        card.apply_learning_review(step, &outcome, now);
+        assert_eq!(state(&card), (CardPhase::Relearning, 1, 1));
+
+        let step = card.next_relearning_step(ReviewGrade::Good, &steps).unwrap();
+        assert_eq!(step, LearningStep::Graduate);
+        card.apply_learning_review(step, &outcome, now);
+        assert_eq!(state(&card), (CardPhase::Reviewing, 0, 1));
+    }
 }
@@ -0,0 +1,352 @@
+use std::collections::BTreeSet;
+
+use thiserror::Error;
+
+use crate::model::content::{Content, ContentValidationError};
+
+//
+// ─── ERRORS ────────────────────────────────────────────────────────────────────
+//
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ClozeError {
+    #[error("cloze text has no {{{{c1::...}}}} markers")]
+    NoDeletions,
+
+    #[error("cloze index must be at least 1")]
+    InvalidIndex,
+
+    #[error("cloze c{0} has no content")]
+    EmptyDeletion(u32),
+
+    #[error("cloze marker opened at byte {0} is never closed")]
+    Unclosed(usize),
+
+    #[error("cloze card asks for c{0}, which the text does not contain")]
+    MissingIndex(u32),
+
+    #[error("cloze card must ask for at least one index")]
+    NoIndices,
+
+    #[error(transparent)]
+    Content(#[from] ContentValidationError),
+}
+
+//
+// ─── PARSED CLOZE TEXT ─────────────────────────────────────────────────────────
+//
+
+/// Cloze source text parsed into literal text and `{{cN::answer::hint}}` deletions.
+///
+/// Deletions may nest; hiding an outer deletion hides everything inside it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClozeText {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Deletion {
+        index: u32,
+        body: Vec<Segment>,
+        hint: Option<String>,
+    },
+}
+
+/// One review item produced by a cloze note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClozeExpansion {
+    pub index: u32,
+    pub prompt: Content,
+    pub answer: Content,
+}
+
+const OPEN: &str = "{{c";
+const CLOSE: &str = "}}";
+const SEP: &str = "::";
+
+impl ClozeText {
+    /// Parse cloze markup.
+    ///
+    /// `{{c` not followed by digits and `::` is kept as literal text, as is a stray `}}`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ClozeError::NoDeletions` if the text contains no markers,
+    /// `ClozeError::InvalidIndex` for `c0`, `ClozeError::EmptyDeletion` for blank answers,
+    /// and `ClozeError::Unclosed` for a marker without matching `}}`.
+    pub fn parse(text: &str) -> Result<Self, ClozeError> {
+        let mut parser = Parser { src: text, pos: 0 };
+        let segments = parser.parse_segments(None)?;
+        let parsed = Self { segments };
+        if parsed.indices().is_empty() {
+            return Err(ClozeError::NoDeletions);
+        }
+        Ok(parsed)
+    }
+
+    /// Distinct cloze indices, ascending.
+    #[must_use]
+    pub fn indices(&self) -> BTreeSet<u32> {
+        let mut out = BTreeSet::new();
+        collect_indices(&self.segments, &mut out);
+        out
+    }
+
+    /// Render the prompt side with every deletion in `hidden` replaced by `[...]` (or its hint).
+    #[must_use]
+    pub fn render_prompt(&self, hidden: &[u32]) -> String {
+        let mut out = String::new();
+        render(&self.segments, hidden, Side::Prompt, &mut out);
+        out
+    }
+
+    /// Render the answer side with every deletion revealed; `hidden` ones are marked `[...]`.
+    #[must_use]
+    pub fn render_answer(&self, hidden: &[u32]) -> String {
+        let mut out = String::new();
+        render(&self.segments, hidden, Side::Answer, &mut out);
+        out
+    }
+}
+
+/// Expand cloze content into one prompt/answer pair per cloze index.
+///
+/// Media on the source content is kept on both sides.
+///
+/// # Errors
+///
+/// Returns `ClozeError` if the text is not valid cloze markup.
+pub fn expand_cloze(content: &Content) -> Result<Vec<ClozeExpansion>, ClozeError> {
+    let parsed = ClozeText::parse(content.text())?;
+    parsed
+        .indices()
+        .into_iter()
+        .map(|index| {
+            let prompt = Content::from_persisted(parsed.render_prompt(&[index]), content.media_id())?;
+            let answer = Content::from_persisted(parsed.render_answer(&[index]), content.media_id())?;
+            Ok(ClozeExpansion {
+                index,
+                prompt,
+                answer,
+            })
+        })
+        .collect()
+}
+
+//
+// ─── PARSER ────────────────────────────────────────────────────────────────────
+//
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /// Parse until end of input or, inside a deletion, its closing `}}` or `::` hint separator.
+    fn parse_segments(&mut self, open_at: Option<usize>) -> Result<Vec<Segment>, ClozeError> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+
+        while self.pos < self.src.len() {
+            let rest = &self.src[self.pos..];
+            if open_at.is_some() && (rest.starts_with(CLOSE) || rest.starts_with(SEP)) {
+                break;
+            }
+            if let Some((index, header_len)) = deletion_header(rest) {
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                let start = self.pos;
+                self.pos += header_len;
+                segments.push(self.parse_deletion(index, start)?);
+                continue;
+            }
+            let ch = rest.chars().next().unwrap_or_default();
+            text.push(ch);
+            self.pos += ch.len_utf8();
+        }
+
+        if let Some(start) = open_at
+            && self.pos >= self.src.len()
+        {
+            return Err(ClozeError::Unclosed(start));
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Ok(segments)
+    }
+
+    fn parse_deletion(&mut self, index: u32, start: usize) -> Result<Segment, ClozeError> {
+        if index == 0 {
+            return Err(ClozeError::InvalidIndex);
+        }
+        let body = self.parse_segments(Some(start))?;
+
+        let mut hint = None;
+        if self.src[self.pos..].starts_with(SEP) {
+            self.pos += SEP.len();
+            let rest = &self.src[self.pos..];
+            let end = rest.find(CLOSE).ok_or(ClozeError::Unclosed(start))?;
+            let raw = rest[..end].trim();
+            if !raw.is_empty() {
+                hint = Some(raw.to_owned());
+            }
+            self.pos += end;
+        }
+        self.pos += CLOSE.len();
+
+        if plain_text(&body).trim().is_empty() {
+            return Err(ClozeError::EmptyDeletion(index));
+        }
+        Ok(Segment::Deletion { index, body, hint })
+    }
+}
+
+/// Match `{{c<digits>::` and return the index and header length.
+fn deletion_header(rest: &str) -> Option<(u32, usize)> {
+    let after_open = rest.strip_prefix(OPEN)?;
+    let digits = after_open
+        .bytes()
+        .take_while(u8::is_ascii_digit)
+        .count();
+    if digits == 0 || !after_open[digits..].starts_with(SEP) {
+        return None;
+    }
+    let index = after_open[..digits].parse().ok()?;
+    Some((index, OPEN.len() + digits + SEP.len()))
+}
+
+//
+// ─── RENDERING ─────────────────────────────────────────────────────────────────
+//
+
+#[derive(Clone, Copy)]
+enum Side {
+    Prompt,
+    Answer,
+}
+
+fn collect_indices(segments: &[Segment], out: &mut BTreeSet<u32>) {
+    for segment in segments {
+        if let Segment::Deletion { index, body, .. } = segment {
+            out.insert(*index);
+            collect_indices(body, out);
+        }
+    }
+}
+
+fn plain_text(segments: &[Segment]) -> String {
+    let mut out = String::new();
+    render(segments, &[], Side::Answer, &mut out);
+    out
+}
+
+fn render(segments: &[Segment], hidden: &[u32], side: Side, out: &mut String) {
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => out.push_str(text),
+            Segment::Deletion { index, body, hint } => {
+                let is_hidden = hidden.contains(index);
+                match side {
+                    Side::Prompt if is_hidden => {
+                        out.push('[');
+                        out.push_str(hint.as_deref().unwrap_or("..."));
+                        out.push(']');
+                    }
+                    Side::Answer if is_hidden => {
+                        out.push('[');
+                        render(body, hidden, side, out);
+                        out.push(']');
+                    }
+                    _ => render(body, hidden, side, out),
+                }
+            }
+        }
+    }
+}
+
+//
+// ─── TESTS ─────────────────────────────────────────────────────────────────────
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content(text: &str) -> Content {
+        Content::from_persisted(text.to_string(), None).unwrap()
+    }
+
+    #[test]
+    fn expands_one_pair_per_index() {
+        let pairs =
+            expand_cloze(&content("{{c1::Paris}} is the capital of {{c2::France::country}}."))
+                .unwrap();
+
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].index, 1);
+        assert_eq!(pairs[0].prompt.text(), "[...] is the capital of France.");
+        assert_eq!(pairs[0].answer.text(), "[Paris] is the capital of France.");
+        assert_eq!(pairs[1].prompt.text(), "Paris is the capital of [country].");
+        assert_eq!(pairs[1].answer.text(), "Paris is the capital of [France].");
+    }
+
+    #[test]
+    fn repeated_index_hides_every_occurrence() {
+        let parsed = ClozeText::parse("{{c1::H}}2{{c1::O}} and {{c3::salt}}").unwrap();
+
+        assert_eq!(parsed.indices().into_iter().collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(parsed.render_prompt(&[1]), "[...]2[...] and salt");
+    }
+
+    #[test]
+    fn nested_deletions_hide_inner_content_with_outer() {
+        let parsed =
+            ClozeText::parse("{{c1::Canberra, capital of {{c2::Australia}}}} is inland").unwrap();
+
+        assert_eq!(parsed.indices().into_iter().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(parsed.render_prompt(&[1]), "[...] is inland");
+        assert_eq!(
+            parsed.render_prompt(&[2]),
+            "Canberra, capital of [...] is inland"
+        );
+        assert_eq!(
+            parsed.render_answer(&[2]),
+            "Canberra, capital of [Australia] is inland"
+        );
+    }
+
+    #[test]
+    fn literal_braces_are_kept() {
+        let parsed = ClozeText::parse("{{notcloze}} {{c::x}} }} {{c1::ok}}").unwrap();
+
+        assert_eq!(parsed.render_prompt(&[1]), "{{notcloze}} {{c::x}} }} [...]");
+    }
+
+    #[test]
+    fn malformed_markup_is_rejected() {
+        assert_eq!(
+            ClozeText::parse("no markers here"),
+            Err(ClozeError::NoDeletions)
+        );
+        assert_eq!(ClozeText::parse("{{c1::open"), Err(ClozeError::Unclosed(0)));
+        assert_eq!(
+            ClozeText::parse("a {{c1::outer {{c2::inner}}"),
+            Err(ClozeError::Unclosed(2))
+        );
+        assert_eq!(
+            ClozeText::parse("{{c1::x::hint"),
+            Err(ClozeError::Unclosed(0))
+        );
+        assert_eq!(ClozeText::parse("{{c0::zero}}"), Err(ClozeError::InvalidIndex));
+        assert_eq!(
+            ClozeText::parse("{{c2::  }}"),
+            Err(ClozeError::EmptyDeletion(2))
+        );
+    }
+}
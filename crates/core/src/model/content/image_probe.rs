@@ -0,0 +1,160 @@
+//! Dimension readers for the image headers `ImageMeta::probe` accepts.
+//!
+//! Only the header is inspected; pixel data is never decoded.
+
+use super::media::ImageFormat;
+
+/// Width and height from the header of an image already identified as `format`.
+///
+/// Returns `None` if the header is truncated or malformed.
+pub(super) fn dimensions(format: ImageFormat, bytes: &[u8]) -> Option<(u32, u32)> {
+    match format {
+        ImageFormat::Png => png(bytes),
+        ImageFormat::Jpeg => jpeg(bytes),
+        ImageFormat::Gif => gif(bytes),
+        ImageFormat::Webp => webp(bytes),
+    }
+}
+
+fn u16_be(bytes: &[u8], at: usize) -> Option<u32> {
+    let raw = bytes.get(at..at + 2)?;
+    Some(u32::from(u16::from_be_bytes([raw[0], raw[1]])))
+}
+
+fn u16_le(bytes: &[u8], at: usize) -> Option<u32> {
+    let raw = bytes.get(at..at + 2)?;
+    Some(u32::from(u16::from_le_bytes([raw[0], raw[1]])))
+}
+
+fn u24_le(bytes: &[u8], at: usize) -> Option<u32> {
+    let raw = bytes.get(at..at + 3)?;
+    Some(u32::from_le_bytes([raw[0], raw[1], raw[2], 0]))
+}
+
+fn u32_be(bytes: &[u8], at: usize) -> Option<u32> {
+    let raw = bytes.get(at..at + 4)?;
+    Some(u32::from_be_bytes([raw[0], raw[1], raw[2], raw[3]]))
+}
+
+/// The first chunk after the signature must be `IHDR`.
+fn png(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    Some((u32_be(bytes, 16)?, u32_be(bytes, 20)?))
+}
+
+fn gif(bytes: &[u8]) -> Option<(u32, u32)> {
+    Some((u16_le(bytes, 6)?, u16_le(bytes, 8)?))
+}
+
+/// Walk the marker segments until a start-of-frame marker.
+fn jpeg(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut pos = 2;
+    loop {
+        if *bytes.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(pos + 1)?;
+        match marker {
+            // Fill byte before a marker.
+            0xFF => pos += 1,
+            // Standalone markers carry no length.
+            0x01 | 0xD0..=0xD7 => pos += 2,
+            // SOF0..SOF15, except DHT (C4), JPG (C8) and DAC (CC).
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                let height = u16_be(bytes, pos + 5)?;
+                let width = u16_be(bytes, pos + 7)?;
+                return Some((width, height));
+            }
+            0xD9 | 0xDA => return None,
+            _ => {
+                let len = usize::try_from(u16_be(bytes, pos + 2)?).ok()?;
+                pos += 2 + len;
+            }
+        }
+    }
+}
+
+/// Lossy (`VP8 `), lossless (`VP8L`) and extended (`VP8X`) WebP headers.
+fn webp(bytes: &[u8]) -> Option<(u32, u32)> {
+    match bytes.get(12..16)? {
+        b"VP8 " => Some((u16_le(bytes, 26)? & 0x3FFF, u16_le(bytes, 28)? & 0x3FFF)),
+        b"VP8L" => {
+            let bits = u32::from_le_bytes(bytes.get(21..25)?.try_into().ok()?);
+            Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+        }
+        b"VP8X" => Some((u24_le(bytes, 24)? + 1, u24_le(bytes, 27)? + 1)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::content::media::{ImageMeta, MAX_IMAGE_SIZE_BYTES, MediaValidationError};
+
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+        bytes
+    }
+
+    #[test]
+    fn reads_png_and_gif_dimensions() {
+        assert_eq!(
+            ImageMeta::probe(&png_header(640, 480)),
+            Ok((ImageFormat::Png, ImageMeta::new(640, 480).unwrap()))
+        );
+
+        let gif = b"GIF89a\x20\x03\x58\x02\0\0\0";
+        assert_eq!(dimensions(ImageFormat::Gif, gif), Some((800, 600)));
+    }
+
+    #[test]
+    fn reads_jpeg_frame_after_other_segments() {
+        let jpeg = [
+            0xFF, 0xD8, // SOI
+            0xFF, 0xE0, 0x00, 0x04, 0x4A, 0x46, // APP0, length 4
+            0xFF, 0xC0, 0x00, 0x11, 0x08, // SOF0, length 17, precision 8
+            0x01, 0xE0, // height 480
+            0x02, 0x80, // width 640
+        ];
+        assert_eq!(ImageMeta::probe(&jpeg).unwrap().1, ImageMeta::new(640, 480).unwrap());
+    }
+
+    #[test]
+    fn reads_webp_variants() {
+        let mut vp8x = b"RIFF\0\0\0\0WEBPVP8X\x0a\0\0\0\0\0\0\0".to_vec();
+        vp8x.extend_from_slice(&[0x7F, 0x02, 0x00, 0xDF, 0x01, 0x00]);
+        assert_eq!(dimensions(ImageFormat::Webp, &vp8x), Some((640, 480)));
+
+        // 640x480 lossless: (639) | (479 << 14).
+        let bits: u32 = 639 | (479 << 14);
+        let mut vp8l = b"RIFF\0\0\0\0WEBPVP8L\0\0\0\0\x2f".to_vec();
+        vp8l.extend_from_slice(&bits.to_le_bytes());
+        assert_eq!(dimensions(ImageFormat::Webp, &vp8l), Some((640, 480)));
+    }
+
+    #[test]
+    fn rejects_unknown_truncated_zero_and_oversized_images() {
+        assert_eq!(
+            ImageMeta::probe(b"BM\0\0\0\0"),
+            Err(MediaValidationError::UnsupportedImageFormat)
+        );
+        assert_eq!(
+            ImageMeta::probe(&png_header(1, 1)[..18]),
+            Err(MediaValidationError::InvalidImageDimensions)
+        );
+        assert_eq!(
+            ImageMeta::probe(&png_header(0, 10)),
+            Err(MediaValidationError::InvalidImageDimensions)
+        );
+
+        let mut huge = png_header(10, 10);
+        huge.resize(usize::try_from(MAX_IMAGE_SIZE_BYTES).unwrap() + 1, 0);
+        assert_eq!(ImageMeta::probe(&huge), Err(MediaValidationError::ImageTooLarge));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::model::content::image_probe;
 use crate::model::ids::MediaId;
 use chrono::{DateTime, Utc};
 use std::path::{Path, PathBuf};
@@ -18,10 +19,37 @@ pub enum MediaValidationError {
     #[error("Image dimensions cannot be zero.")]
     InvalidImageDimensions,
 
+    #[error("Images must be PNG, JPEG, GIF, or WebP files.")]
+    UnsupportedImageFormat,
+
+    #[error("Images can be at most {} MiB.", MAX_IMAGE_SIZE_BYTES / (1024 * 1024))]
+    ImageTooLarge,
+
     #[error("Alt text cannot be empty.")]
     EmptyAltText,
+
+    #[error("Audio must be an mp3, ogg, or wav file.")]
+    UnsupportedAudioFormat,
+
+    #[error("Audio clips cannot be empty.")]
+    EmptyAudio,
+
+    #[error("Audio clips can be at most {} minutes long.", MAX_AUDIO_DURATION_MS / 60_000)]
+    AudioTooLong,
+
+    #[error("Audio files can be at most {} MiB.", MAX_AUDIO_SIZE_BYTES / (1024 * 1024))]
+    AudioTooLarge,
 }
 
+/// Largest image file a card may embed.
+pub const MAX_IMAGE_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Longest audio clip a card may carry.
+pub const MAX_AUDIO_DURATION_MS: u64 = 5 * 60 * 1000;
+
+/// Largest audio file a card may carry.
+pub const MAX_AUDIO_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+
 //
 // ─── MEDIA CORE TYPES ──────────────────────────────────────────────────────────
 //
@@ -133,6 +161,212 @@ impl ImageMeta {
         }
         Ok(Self { width, height })
     }
+
+    /// Reads format and dimensions from encoded image bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MediaValidationError::ImageTooLarge` above `MAX_IMAGE_SIZE_BYTES`,
+    /// `MediaValidationError::UnsupportedImageFormat` if the bytes are not PNG, JPEG, GIF, or
+    /// WebP, and `MediaValidationError::InvalidImageDimensions` if the header is truncated or
+    /// reports a zero dimension.
+    pub fn probe(bytes: &[u8]) -> Result<(ImageFormat, Self), MediaValidationError> {
+        if u64::try_from(bytes.len()).unwrap_or(u64::MAX) > MAX_IMAGE_SIZE_BYTES {
+            return Err(MediaValidationError::ImageTooLarge);
+        }
+        let format = ImageFormat::sniff(bytes)?;
+        let (width, height) = image_probe::dimensions(format, bytes)
+            .ok_or(MediaValidationError::InvalidImageDimensions)?;
+        Ok((format, Self::new(width, height)?))
+    }
+}
+
+/// Supported image encodings, detected from the file signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+}
+
+impl ImageFormat {
+    /// Detects the format from the leading signature bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MediaValidationError::UnsupportedImageFormat` for any other signature.
+    pub fn sniff(bytes: &[u8]) -> Result<Self, MediaValidationError> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Ok(Self::Png)
+        } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Ok(Self::Jpeg)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Ok(Self::Gif)
+        } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            Ok(Self::Webp)
+        } else {
+            Err(MediaValidationError::UnsupportedImageFormat)
+        }
+    }
+
+    #[must_use]
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Gif => "image/gif",
+            Self::Webp => "image/webp",
+        }
+    }
+
+    #[must_use]
+    pub fn from_mime_type(raw: &str) -> Option<Self> {
+        match raw {
+            "image/png" => Some(Self::Png),
+            "image/jpeg" => Some(Self::Jpeg),
+            "image/gif" => Some(Self::Gif),
+            "image/webp" => Some(Self::Webp),
+            _ => None,
+        }
+    }
+}
+
+/// Supported audio containers, detected from the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Mp3,
+    Ogg,
+    Wav,
+}
+
+impl AudioFormat {
+    /// Detects the format from the extension of a file path or URL path.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MediaValidationError::UnsupportedAudioFormat` for any other extension.
+    pub fn from_uri(uri: &MediaUri) -> Result<Self, MediaValidationError> {
+        let path = match uri {
+            MediaUri::FilePath(p) => p.as_path(),
+            MediaUri::Url(u) => Path::new(u.path()),
+        };
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match ext.as_deref() {
+            Some("mp3") => Ok(Self::Mp3),
+            Some("ogg") => Ok(Self::Ogg),
+            Some("wav") => Ok(Self::Wav),
+            _ => Err(MediaValidationError::UnsupportedAudioFormat),
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Mp3 => "mp3",
+            Self::Ogg => "ogg",
+            Self::Wav => "wav",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioMeta {
+    pub duration_ms: u64,
+    pub size_bytes: u64,
+}
+
+impl AudioMeta {
+    /// Creates audio metadata, enforcing the per-card limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MediaValidationError::EmptyAudio` if duration or size is zero,
+    /// `MediaValidationError::AudioTooLong` above `MAX_AUDIO_DURATION_MS`, and
+    /// `MediaValidationError::AudioTooLarge` above `MAX_AUDIO_SIZE_BYTES`.
+    pub fn new(duration_ms: u64, size_bytes: u64) -> Result<Self, MediaValidationError> {
+        let meta = Self {
+            duration_ms,
+            size_bytes,
+        };
+        meta.validate()?;
+        Ok(meta)
+    }
+
+    fn validate(&self) -> Result<(), MediaValidationError> {
+        if self.duration_ms == 0 || self.size_bytes == 0 {
+            return Err(MediaValidationError::EmptyAudio);
+        }
+        if self.duration_ms > MAX_AUDIO_DURATION_MS {
+            return Err(MediaValidationError::AudioTooLong);
+        }
+        if self.size_bytes > MAX_AUDIO_SIZE_BYTES {
+            return Err(MediaValidationError::AudioTooLarge);
+        }
+        Ok(())
+    }
+}
+
+/// Audio attached to one side of a card, identified by its content hash.
+///
+/// Limits are checked through `AudioMeta` when the clip is first attached; persisted clips
+/// only keep what playback needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioClip {
+    uri: MediaUri,
+    format: AudioFormat,
+    checksum: MediaHash,
+}
+
+impl AudioClip {
+    /// Creates a clip for a new attachment, checking format and size limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `AudioMeta::new` errors if `meta` exceeds the limits, and the
+    /// `AudioClip::from_persisted` errors for the URI.
+    pub fn new(
+        uri: MediaUri,
+        meta: &AudioMeta,
+        checksum: MediaHash,
+    ) -> Result<Self, MediaValidationError> {
+        meta.validate()?;
+        Self::from_persisted(uri, checksum)
+    }
+
+    /// Rehydrates a clip from storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MediaValidationError::EmptyMediaUri` if the URI is empty and
+    /// `MediaValidationError::UnsupportedAudioFormat` if it is not mp3, ogg, or wav.
+    pub fn from_persisted(uri: MediaUri, checksum: MediaHash) -> Result<Self, MediaValidationError> {
+        uri.validate_non_empty()?;
+        let format = AudioFormat::from_uri(&uri)?;
+        Ok(Self {
+            uri,
+            format,
+            checksum,
+        })
+    }
+
+    #[must_use]
+    pub fn uri(&self) -> &MediaUri {
+        &self.uri
+    }
+
+    #[must_use]
+    pub fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    #[must_use]
+    pub fn checksum(&self) -> &MediaHash {
+        &self.checksum
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -398,6 +632,63 @@ mod tests {
         assert_eq!(result, Err(MediaValidationError::InvalidImageDimensions));
     }
 
+    // ─── Audio Tests ────────────────────────────────────────────────────
+
+    #[test]
+    fn test_audio_format_from_extension() {
+        let mp3 = MediaUri::from_file("/audio/word.MP3").unwrap();
+        let ogg = MediaUri::from_url("https://example.com/a/b.ogg?x=1").unwrap();
+        let wav = MediaUri::from_file("clip.wav").unwrap();
+        assert_eq!(AudioFormat::from_uri(&mp3), Ok(AudioFormat::Mp3));
+        assert_eq!(AudioFormat::from_uri(&ogg), Ok(AudioFormat::Ogg));
+        assert_eq!(AudioFormat::from_uri(&wav), Ok(AudioFormat::Wav));
+    }
+
+    #[test]
+    fn test_audio_format_rejects_unsupported() {
+        for raw in ["clip.flac", "clip", "image.png"] {
+            let uri = MediaUri::from_file(raw).unwrap();
+            assert_eq!(
+                AudioFormat::from_uri(&uri),
+                Err(MediaValidationError::UnsupportedAudioFormat)
+            );
+        }
+    }
+
+    #[test]
+    fn test_audio_meta_limits() {
+        assert!(AudioMeta::new(MAX_AUDIO_DURATION_MS, MAX_AUDIO_SIZE_BYTES).is_ok());
+        assert_eq!(AudioMeta::new(0, 10), Err(MediaValidationError::EmptyAudio));
+        assert_eq!(
+            AudioMeta::new(MAX_AUDIO_DURATION_MS + 1, 10),
+            Err(MediaValidationError::AudioTooLong)
+        );
+        assert_eq!(
+            AudioMeta::new(1_000, MAX_AUDIO_SIZE_BYTES + 1),
+            Err(MediaValidationError::AudioTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_audio_clip_keeps_uri_and_checksum() {
+        let uri = MediaUri::from_file("/audio/hola.ogg").unwrap();
+        let meta = AudioMeta::new(2_500, 40_000).unwrap();
+        let clip = AudioClip::new(uri.clone(), &meta, MediaHash::new("sha256:01")).unwrap();
+
+        assert_eq!(clip.uri(), &uri);
+        assert_eq!(clip.format(), AudioFormat::Ogg);
+        assert_eq!(clip.checksum().as_str(), "sha256:01");
+
+        let huge = AudioMeta {
+            duration_ms: 1_000,
+            size_bytes: MAX_AUDIO_SIZE_BYTES * 2,
+        };
+        assert_eq!(
+            AudioClip::new(uri, &huge, MediaHash::new("sha256:02")),
+            Err(MediaValidationError::AudioTooLarge)
+        );
+    }
+
     // ─── MediaAltText Tests ─────────────────────────────────────────────
 
     #[test]
@@ -1,8 +1,13 @@
+mod image_probe;
 pub mod media;
 pub mod text;
 mod types;
 
-pub use media::{ImageMeta, MediaDraft, MediaHash, MediaUri, MediaValidationError};
+pub use media::{
+    AudioClip, AudioFormat, AudioMeta, ImageFormat, ImageMeta, MAX_AUDIO_DURATION_MS,
+    MAX_AUDIO_SIZE_BYTES, MAX_IMAGE_SIZE_BYTES, MediaDraft, MediaHash, MediaUri,
+    MediaValidationError,
+};
 
 pub use types::{Content, ContentDraft, ContentValidationError};
-pub use text::TextError;
+pub use text::{MAX_TEXT_CHARS, TextError};
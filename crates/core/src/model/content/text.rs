@@ -1,12 +1,30 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// Characters allowed in one piece of card text unless a caller sets its own limit.
+pub const MAX_TEXT_CHARS: usize = 8_000;
+
 /// Errors that can occur when validating text content.
-#[derive(Debug, Error, PartialEq, Eq)]
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum TextError {
     #[error("Text cannot be empty.")]
     Empty,
+
+    #[error("Text cannot be longer than {max} characters.")]
+    TooLong { max: usize },
+}
+
+/// Check that `text` has at most `max_chars` characters.
+///
+/// # Errors
+///
+/// Returns `TextError::TooLong` if `text` has more than `max_chars` characters.
+pub fn check_length(text: &str, max_chars: usize) -> Result<(), TextError> {
+    if text.chars().count() > max_chars {
+        return Err(TextError::TooLong { max: max_chars });
+    }
+    Ok(())
 }
 
 /// Type-safe wrapper for text with phantom type marker.
@@ -47,7 +65,8 @@ impl<T> Text<T> {
     ///
     /// # Errors
     ///
-    /// Returns `TextError::Empty` if the input is empty or whitespace-only.
+    /// Returns `TextError::Empty` if the input is empty or whitespace-only, or
+    /// `TextError::TooLong` if it has more than `MAX_TEXT_CHARS` characters.
     ///
     /// # Examples
     ///
@@ -61,10 +80,21 @@ impl<T> Text<T> {
     /// assert!(empty.is_err());
     /// ```
     pub fn parse(s: impl Into<String>) -> Result<Self, TextError> {
+        Self::parse_with_max_chars(s, MAX_TEXT_CHARS)
+    }
+
+    /// Parses text like `parse`, allowing at most `max_chars` characters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TextError::Empty` if the input is empty or whitespace-only, or
+    /// `TextError::TooLong` if it has more than `max_chars` characters.
+    pub fn parse_with_max_chars(s: impl Into<String>, max_chars: usize) -> Result<Self, TextError> {
         let s = s.into();
         if s.trim().is_empty() {
             return Err(TextError::Empty);
         }
+        check_length(&s, max_chars)?;
         Ok(Self(s, std::marker::PhantomData))
     }
 
@@ -100,4 +130,25 @@ mod tests {
         let txt = FrontText::parse("  ");
         assert_eq!(txt, Err(TextError::Empty));
     }
+
+    #[test]
+    fn test_text_length_limit_is_inclusive() {
+        let at_limit = "é".repeat(MAX_TEXT_CHARS);
+        assert!(FrontText::parse(at_limit.clone()).is_ok());
+
+        let over = format!("{at_limit}x");
+        assert_eq!(
+            FrontText::parse(over),
+            Err(TextError::TooLong { max: MAX_TEXT_CHARS })
+        );
+    }
+
+    #[test]
+    fn test_text_custom_length_limit() {
+        assert!(BackText::parse_with_max_chars("four", 4).is_ok());
+        assert_eq!(
+            BackText::parse_with_max_chars("five!", 4),
+            Err(TextError::TooLong { max: 4 })
+        );
+    }
 }
@@ -1,20 +1,35 @@
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::model::MediaId;
-use crate::model::content::{ImageMeta, MediaDraft, MediaHash, MediaValidationError};
+use crate::model::content::text::{self, MAX_TEXT_CHARS, TextError};
+use crate::model::content::{AudioClip, ImageMeta, MediaDraft, MediaHash, MediaValidationError};
 
 //
 // ─── CONTENT TYPES ─────────────────────────────────────────────────────────────
 //
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Unvalidated content input.
+///
+/// Serializes text only; media drafts point at local files and stored media IDs are local to
+/// one database, so neither is portable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContentDraft {
     text: String,
+    #[serde(skip)]
     media: Option<MediaDraft>,
+    #[serde(skip)]
+    media_id: Option<MediaId>,
+    #[serde(skip, default = "default_max_chars")]
+    max_chars: usize,
+}
+
+fn default_max_chars() -> usize {
+    MAX_TEXT_CHARS
 }
 
-/// Validated content with text and optional media reference.
+/// Validated content with text, an optional image reference, and optional audio.
 ///
 /// Content is guaranteed to have non-empty text and valid media references.
 /// Use `ContentDraft` to build and validate new content.
@@ -22,6 +37,7 @@ pub struct ContentDraft {
 pub struct Content {
     text: String,
     media: Option<MediaId>,
+    audio: Option<AudioClip>,
 }
 
 //
@@ -37,6 +53,9 @@ pub enum ContentValidationError {
     #[error("Image metadata must be provided when media is present.")]
     MissingImageMeta,
 
+    #[error(transparent)]
+    Text(#[from] TextError),
+
     #[error(transparent)]
     Media(#[from] MediaValidationError),
 }
@@ -50,6 +69,8 @@ impl ContentDraft {
         Self {
             text: text.into(),
             media,
+            media_id: None,
+            max_chars: MAX_TEXT_CHARS,
         }
     }
 
@@ -57,6 +78,8 @@ impl ContentDraft {
         Self {
             text: text.into(),
             media: None,
+            media_id: None,
+            max_chars: MAX_TEXT_CHARS,
         }
     }
 
@@ -64,14 +87,44 @@ impl ContentDraft {
         Self {
             text: text.into(),
             media: Some(media),
+            media_id: None,
+            max_chars: MAX_TEXT_CHARS,
         }
     }
 
+    /// Reference an image that is already stored, e.g. via `MediaRepository`.
+    ///
+    /// Ignored when the draft also carries a `MediaDraft`.
+    #[must_use]
+    pub fn with_media_id(mut self, media_id: Option<MediaId>) -> Self {
+        self.media_id = media_id;
+        self
+    }
+
+    #[must_use]
+    pub fn media_id(&self) -> Option<MediaId> {
+        self.media_id
+    }
+
+    /// Allow at most `max_chars` characters of text instead of `MAX_TEXT_CHARS`.
+    #[must_use]
+    pub fn with_max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = max_chars;
+        self
+    }
+
+    /// Raw, unvalidated text of the draft.
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
     /// Validates the content draft and creates a Content entity.
     ///
     /// # Errors
     ///
     /// Returns `ContentValidationError::EmptyText` if the text is empty or whitespace-only.
+    /// Returns `ContentValidationError::Text` if the text is longer than the draft's limit.
     /// Returns `ContentValidationError::MissingImageMeta` if media is present but meta is None.
     /// Returns `ContentValidationError::Media` if media validation fails.
     pub fn validate(
@@ -83,9 +136,10 @@ impl ContentDraft {
         if self.text.trim().is_empty() {
             return Err(ContentValidationError::EmptyText);
         }
+        text::check_length(&self.text, self.max_chars)?;
 
         let media_id = match self.media {
-            None => None,
+            None => self.media_id,
             Some(draft) => {
                 let meta = meta.ok_or(ContentValidationError::MissingImageMeta)?;
                 let item = draft.validate(now, meta, checksum)?;
@@ -96,6 +150,7 @@ impl ContentDraft {
         Ok(Content {
             text: self.text,
             media: media_id,
+            audio: None,
         })
     }
 }
@@ -117,7 +172,18 @@ impl Content {
         if text.trim().is_empty() {
             return Err(ContentValidationError::EmptyText);
         }
-        Ok(Self { text, media })
+        Ok(Self {
+            text,
+            media,
+            audio: None,
+        })
+    }
+
+    /// Replace the audio clip, keeping text and image.
+    #[must_use]
+    pub fn with_audio(mut self, audio: Option<AudioClip>) -> Self {
+        self.audio = audio;
+        self
     }
 
     #[must_use]
@@ -134,6 +200,11 @@ impl Content {
     pub fn has_media(&self) -> bool {
         self.media.is_some()
     }
+
+    #[must_use]
+    pub fn audio(&self) -> Option<&AudioClip> {
+        self.audio.as_ref()
+    }
 }
 
 //
@@ -144,7 +215,7 @@ impl Content {
 mod tests {
     use super::*;
     use crate::time::fixed_now;
-    use crate::model::content::media::MediaUri;
+    use crate::model::content::media::{AudioMeta, MediaUri};
     #[test]
     fn empty_text_fails() {
         let d = ContentDraft::text_only("   ");
@@ -160,6 +231,27 @@ mod tests {
         assert!(!c.has_media());
     }
 
+    #[test]
+    fn text_longer_than_limit_fails() {
+        let at_limit = "a".repeat(MAX_TEXT_CHARS);
+        let draft = ContentDraft::text_only(at_limit.clone());
+        assert!(draft.validate(fixed_now(), None, None).is_ok());
+
+        let err = ContentDraft::text_only(format!("{at_limit}a"))
+            .validate(fixed_now(), None, None)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContentValidationError::Text(TextError::TooLong { max: MAX_TEXT_CHARS })
+        );
+
+        let err = ContentDraft::text_only("hello")
+            .with_max_chars(4)
+            .validate(fixed_now(), None, None)
+            .unwrap_err();
+        assert_eq!(err, ContentValidationError::Text(TextError::TooLong { max: 4 }));
+    }
+
     #[test]
     fn media_requires_meta() {
         let md = MediaDraft::new_image(MediaUri::from_file("img.png").unwrap(), None);
@@ -180,4 +272,27 @@ mod tests {
         assert!(c.has_media());
         assert_eq!(c.media_id(), Some(MediaId::new(0)));
     }
+
+    #[test]
+    fn stored_media_id_is_kept() {
+        let d = ContentDraft::text_only("hello").with_media_id(Some(MediaId::new(7)));
+        let c = d.validate(fixed_now(), None, None).unwrap();
+
+        assert_eq!(c.media_id(), Some(MediaId::new(7)));
+    }
+
+    #[test]
+    fn audio_is_kept_alongside_text() {
+        let uri = MediaUri::from_file("say.mp3").unwrap();
+        let meta = AudioMeta::new(1_200, 9_000).unwrap();
+        let clip = AudioClip::new(uri, &meta, MediaHash::new("h")).unwrap();
+
+        let c = Content::from_persisted("hola".to_string(), None)
+            .unwrap()
+            .with_audio(Some(clip.clone()));
+
+        assert_eq!(c.text(), "hola");
+        assert_eq!(c.audio(), Some(&clip));
+        assert!(c.clone().with_audio(None).audio().is_none());
+    }
 }
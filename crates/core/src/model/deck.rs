@@ -1,8 +1,15 @@
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
 use chrono::Weekday;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::model::ids::DeckId;
+use crate::model::card::CardSide;
+use crate::model::review::ReviewGrade;
+use crate::model::template::{CardTemplate, TemplateError};
 
 //
 // ─── ERRORS ────────────────────────────────────────────────────────────────────
@@ -26,7 +33,7 @@ pub enum DeckError {
     #[error("lapse minimum interval must be > 0")]
     InvalidLapseMinInterval,
 
-    #[error("FSRS target retention must be in (0, 1]")]
+    #[error("FSRS target retention must be between 0.70 and 0.99")]
     InvalidFsrsTargetRetention,
 
     #[error("FSRS optimize-after must be > 0")]
@@ -52,16 +59,309 @@ pub enum DeckError {
 
     #[error("easy days must include at least one day when enabled")]
     InvalidEasyDaysMask,
+
+    #[error("learning steps must be at most 10 delays between 1 second and 1 day")]
+    InvalidLearningSteps,
+
+    #[error("relearning steps must be at most 10 delays between 1 second and 1 day")]
+    InvalidRelearningSteps,
+
+    #[error("FSRS parameters must be empty or 21 finite weights")]
+    InvalidFsrsParameters,
+
+    #[error("unknown deck color: {0}")]
+    UnknownDeckColor(String),
+
+    #[error("unknown deck icon: {0}")]
+    UnknownDeckIcon(String),
+
+    #[error("invalid card template: {0}")]
+    InvalidTemplate(#[source] TemplateError),
+
+    #[error("not a deck settings preset: {0}")]
+    InvalidPreset(String),
 }
 
 //
 // ─── SETTINGS ──────────────────────────────────────────────────────────────────
 //
 
+/// How new cards are ordered among due reviews within a session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NewReviewMix {
+    /// New cards first, then due reviews.
+    FrontLoad,
+    /// New cards spaced evenly through the due reviews.
+    Interleaved,
+    /// New cards only once every due review has been shown.
+    #[default]
+    AfterReviews,
+}
+
+impl NewReviewMix {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NewReviewMix::FrontLoad => "front_load",
+            NewReviewMix::Interleaved => "interleaved",
+            NewReviewMix::AfterReviews => "after_reviews",
+        }
+    }
+}
+
+/// Order in which a session draws the day's new cards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NewCardOrder {
+    /// Oldest first, the order the cards were added in.
+    #[default]
+    Sequential,
+    /// Shuffled once per study day, so the order holds within a day and changes across days.
+    Random,
+}
+
+impl NewCardOrder {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NewCardOrder::Sequential => "sequential",
+            NewCardOrder::Random => "random",
+        }
+    }
+}
+
+/// Starter text the editor puts in a new card before anything is typed.
+///
+/// Both sides are plain editor HTML and stay fully editable; an empty template leaves new
+/// cards blank.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NewCardTemplate {
+    prompt: String,
+    answer: String,
+}
+
+impl NewCardTemplate {
+    /// Blank sides (only whitespace) count as empty and are stored as such.
+    #[must_use]
+    pub fn new(prompt: &str, answer: &str) -> Self {
+        let keep = |text: &str| {
+            if text.trim().is_empty() {
+                String::new()
+            } else {
+                text.to_string()
+            }
+        };
+        Self {
+            prompt: keep(prompt),
+            answer: keep(answer),
+        }
+    }
+
+    #[must_use]
+    pub fn prompt(&self) -> &str {
+        &self.prompt
+    }
+
+    #[must_use]
+    pub fn answer(&self) -> &str {
+        &self.answer
+    }
+
+    /// Whether new cards start blank.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.prompt.is_empty() && self.answer.is_empty()
+    }
+}
+
+/// Which grade buttons a session offers after the answer is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerButtons {
+    /// Again and Good only; each schedules exactly like its four-button counterpart.
+    Two,
+    /// Again, Hard, Good, and Easy.
+    #[default]
+    Four,
+}
+
+impl AnswerButtons {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AnswerButtons::Two => "two",
+            AnswerButtons::Four => "four",
+        }
+    }
+
+    /// Grades behind the buttons, in the order they are shown.
+    #[must_use]
+    pub fn grades(self) -> &'static [ReviewGrade] {
+        match self {
+            AnswerButtons::Two => &[ReviewGrade::Again, ReviewGrade::Good],
+            AnswerButtons::Four => &[
+                ReviewGrade::Again,
+                ReviewGrade::Hard,
+                ReviewGrade::Good,
+                ReviewGrade::Easy,
+            ],
+        }
+    }
+
+    /// Grade behind the button at zero-based `index`, if there is such a button.
+    #[must_use]
+    pub fn grade_at(self, index: usize) -> Option<ReviewGrade> {
+        self.grades().get(index).copied()
+    }
+
+    /// Whether one of the buttons answers with `grade`.
+    #[must_use]
+    pub fn offers(self, grade: ReviewGrade) -> bool {
+        self.grades().contains(&grade)
+    }
+}
+
+/// What happens to a card once its lapses reach the deck's leech threshold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeechAction {
+    /// Tag the card as a leech and keep scheduling it.
+    #[default]
+    Tag,
+    /// Tag the card as a leech and suspend it until it is rewritten.
+    Suspend,
+}
+
+impl LeechAction {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LeechAction::Tag => "tag",
+            LeechAction::Suspend => "suspend",
+        }
+    }
+}
+
+/// Preset colors a deck can be marked with in deck lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeckColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Teal,
+    Blue,
+    Purple,
+    Pink,
+    Gray,
+}
+
+impl DeckColor {
+    pub const ALL: [DeckColor; 9] = [
+        DeckColor::Red,
+        DeckColor::Orange,
+        DeckColor::Yellow,
+        DeckColor::Green,
+        DeckColor::Teal,
+        DeckColor::Blue,
+        DeckColor::Purple,
+        DeckColor::Pink,
+        DeckColor::Gray,
+    ];
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DeckColor::Red => "red",
+            DeckColor::Orange => "orange",
+            DeckColor::Yellow => "yellow",
+            DeckColor::Green => "green",
+            DeckColor::Teal => "teal",
+            DeckColor::Blue => "blue",
+            DeckColor::Purple => "purple",
+            DeckColor::Pink => "pink",
+            DeckColor::Gray => "gray",
+        }
+    }
+}
+
+impl FromStr for DeckColor {
+    type Err = DeckError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|color| color.as_str() == s)
+            .ok_or_else(|| DeckError::UnknownDeckColor(s.to_string()))
+    }
+}
+
+/// Preset icons a deck can be marked with in deck lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeckIcon {
+    Book,
+    Language,
+    Science,
+    Math,
+    Music,
+    Code,
+    Globe,
+    Star,
+    Heart,
+}
+
+impl DeckIcon {
+    pub const ALL: [DeckIcon; 9] = [
+        DeckIcon::Book,
+        DeckIcon::Language,
+        DeckIcon::Science,
+        DeckIcon::Math,
+        DeckIcon::Music,
+        DeckIcon::Code,
+        DeckIcon::Globe,
+        DeckIcon::Star,
+        DeckIcon::Heart,
+    ];
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DeckIcon::Book => "book",
+            DeckIcon::Language => "language",
+            DeckIcon::Science => "science",
+            DeckIcon::Math => "math",
+            DeckIcon::Music => "music",
+            DeckIcon::Code => "code",
+            DeckIcon::Globe => "globe",
+            DeckIcon::Star => "star",
+            DeckIcon::Heart => "heart",
+        }
+    }
+}
+
+impl FromStr for DeckIcon {
+    type Err = DeckError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|icon| icon.as_str() == s)
+            .ok_or_else(|| DeckError::UnknownDeckIcon(s.to_string()))
+    }
+}
+
 /// Configuration settings for a deck.
 ///
 /// Controls daily limits and session sizes for spaced repetition learning.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Deserialization runs the same validation as `DeckSettings::new`; missing fields
+/// fall back to `DeckSettings::default_for_adhd()`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "DeckSettingsWire")]
 #[allow(clippy::struct_excessive_bools)]
 pub struct DeckSettings {
     new_cards_per_day: u32,
@@ -83,9 +383,54 @@ pub struct DeckSettings {
     fsrs_target_retention: f32,
     fsrs_optimize_enabled: bool,
     fsrs_optimize_after: u32,
+    new_review_mix: NewReviewMix,
+    load_balance: bool,
+    learning_steps_secs: Vec<u32>,
+    relearning_steps_secs: Vec<u32>,
+    fsrs_parameters: Vec<f32>,
+    answer_buttons: AnswerButtons,
+    leech_threshold: u32,
+    leech_action: LeechAction,
+    color: Option<DeckColor>,
+    icon: Option<DeckIcon>,
+    hold_new_until_reviews_done: bool,
+    front_template: Option<CardTemplate>,
+    back_template: Option<CardTemplate>,
+    interval_fuzz: bool,
+    bury_siblings: bool,
+    same_session_fail_limit: u32,
+    progressive_reveal: bool,
+    typed_answer: bool,
+    new_card_order: NewCardOrder,
+    new_card_template: NewCardTemplate,
+    new_per_session: u32,
 }
 
 impl DeckSettings {
+    /// Accepted FSRS target retention; outside it intervals become uselessly short or long.
+    pub const TARGET_RETENTION_RANGE: RangeInclusive<f32> = 0.7..=0.99;
+
+    /// Accepted soft reminder and auto-reveal delays, in seconds.
+    pub const TIMER_SECS_RANGE: RangeInclusive<u32> = 5..=600;
+
+    /// Accepted delay for a single learning step, in seconds.
+    pub const LEARNING_STEP_SECS_RANGE: RangeInclusive<u32> = 1..=86_400;
+
+    /// Most learning steps a deck may configure.
+    pub const MAX_LEARNING_STEPS: usize = 10;
+
+    /// Relearning steps a lapsed card walks through unless a deck sets its own.
+    pub const DEFAULT_RELEARNING_STEPS_SECS: [u32; 1] = [600];
+
+    /// Number of weights in a learned FSRS parameter set.
+    pub const FSRS_PARAMETER_COUNT: usize = 21;
+
+    /// Lapses that make a card a leech unless a deck sets its own threshold.
+    pub const DEFAULT_LEECH_THRESHOLD: u32 = 8;
+
+    /// Agains in a row that pull a card out of a session unless a deck sets its own limit.
+    pub const DEFAULT_SAME_SESSION_FAIL_LIMIT: u32 = 4;
+
     /// Creates ADHD-friendly default settings.
     ///
     /// Returns settings optimized for users with ADHD:
@@ -93,6 +438,8 @@ impl DeckSettings {
     /// - 30 reviews per day limit (prevents overwhelm)
     /// - 5 cards per micro-session (quick wins)
     /// - protect overload enabled (keeps daily load calm)
+    /// - 1 and 10 minute learning steps (new cards come back within the session)
+    /// - a 10 minute relearning step (a lapse is retried soon instead of a day later)
     #[must_use]
     pub fn default_for_adhd() -> Self {
         Self {
@@ -115,14 +462,40 @@ impl DeckSettings {
             fsrs_target_retention: 0.85,
             fsrs_optimize_enabled: true,
             fsrs_optimize_after: 100,
+            new_review_mix: NewReviewMix::AfterReviews,
+            load_balance: false,
+            learning_steps_secs: vec![60, 600],
+            relearning_steps_secs: Self::DEFAULT_RELEARNING_STEPS_SECS.to_vec(),
+            fsrs_parameters: Vec::new(),
+            answer_buttons: AnswerButtons::Four,
+            leech_threshold: Self::DEFAULT_LEECH_THRESHOLD,
+            leech_action: LeechAction::Tag,
+            color: None,
+            icon: None,
+            hold_new_until_reviews_done: false,
+            front_template: None,
+            back_template: None,
+            interval_fuzz: true,
+            bury_siblings: true,
+            same_session_fail_limit: Self::DEFAULT_SAME_SESSION_FAIL_LIMIT,
+            progressive_reveal: false,
+            typed_answer: false,
+            new_card_order: NewCardOrder::Sequential,
+            new_card_template: NewCardTemplate::default(),
+            new_per_session: 0,
         }
     }
 
     /// Creates custom deck settings.
     ///
+    /// This is the single source of truth for settings invariants; forms, imports, and
+    /// storage all go through it.
+    ///
     /// # Errors
     ///
-    /// Returns error if any parameter is zero.
+    /// Returns the `DeckError` for the first violated invariant: zero limits or sizes,
+    /// retention outside `TARGET_RETENTION_RANGE`, timers outside `TIMER_SECS_RANGE`,
+    /// a minimum interval above the maximum, or an invalid easy-day setup.
     #[allow(clippy::too_many_arguments, clippy::fn_params_excessive_bools)]
     pub fn new(
         new_cards_per_day: u32,
@@ -157,19 +530,16 @@ impl DeckSettings {
         if lapse_min_interval_secs == 0 {
             return Err(DeckError::InvalidLapseMinInterval);
         }
-        if !fsrs_target_retention.is_finite()
-            || fsrs_target_retention <= 0.0
-            || fsrs_target_retention > 1.0
-        {
+        if !Self::TARGET_RETENTION_RANGE.contains(&fsrs_target_retention) {
             return Err(DeckError::InvalidFsrsTargetRetention);
         }
         if fsrs_optimize_after == 0 {
             return Err(DeckError::InvalidFsrsOptimizeAfter);
         }
-        if !(5..=600).contains(&soft_time_reminder_secs) {
+        if !Self::TIMER_SECS_RANGE.contains(&soft_time_reminder_secs) {
             return Err(DeckError::InvalidSoftReminderSeconds);
         }
-        if !(5..=600).contains(&auto_reveal_secs) {
+        if !Self::TIMER_SECS_RANGE.contains(&auto_reveal_secs) {
             return Err(DeckError::InvalidAutoRevealSeconds);
         }
         if min_interval_secs == 0 {
@@ -214,9 +584,256 @@ impl DeckSettings {
             fsrs_target_retention,
             fsrs_optimize_enabled,
             fsrs_optimize_after,
+            new_review_mix: NewReviewMix::AfterReviews,
+            load_balance: false,
+            learning_steps_secs: Vec::new(),
+            relearning_steps_secs: Self::DEFAULT_RELEARNING_STEPS_SECS.to_vec(),
+            fsrs_parameters: Vec::new(),
+            answer_buttons: AnswerButtons::Four,
+            leech_threshold: Self::DEFAULT_LEECH_THRESHOLD,
+            leech_action: LeechAction::Tag,
+            color: None,
+            icon: None,
+            hold_new_until_reviews_done: false,
+            front_template: None,
+            back_template: None,
+            interval_fuzz: true,
+            bury_siblings: true,
+            same_session_fail_limit: Self::DEFAULT_SAME_SESSION_FAIL_LIMIT,
+            progressive_reveal: false,
+            typed_answer: false,
+            new_card_order: NewCardOrder::Sequential,
+            new_card_template: NewCardTemplate::default(),
+            new_per_session: 0,
         })
     }
 
+    /// Replace the learned FSRS weights; an empty set schedules with the FSRS defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckError::InvalidFsrsParameters` unless `parameters` is empty or holds
+    /// `FSRS_PARAMETER_COUNT` finite weights.
+    pub fn with_fsrs_parameters(mut self, parameters: Vec<f32>) -> Result<Self, DeckError> {
+        validate_fsrs_parameters(&parameters)?;
+        self.fsrs_parameters = parameters;
+        Ok(self)
+    }
+
+    /// Choose between the two- and four-button grade layouts.
+    #[must_use]
+    pub fn with_answer_buttons(mut self, answer_buttons: AnswerButtons) -> Self {
+        self.answer_buttons = answer_buttons;
+        self
+    }
+
+    /// Set how many lapses make a card a leech, and what then happens to it.
+    ///
+    /// A threshold of zero turns leech detection off.
+    #[must_use]
+    pub fn with_leech(mut self, threshold: u32, action: LeechAction) -> Self {
+        self.leech_threshold = threshold;
+        self.leech_action = action;
+        self
+    }
+
+    /// Mark the deck with a preset color and icon; `None` keeps the plain look.
+    #[must_use]
+    pub fn with_appearance(mut self, color: Option<DeckColor>, icon: Option<DeckIcon>) -> Self {
+        self.color = color;
+        self.icon = icon;
+        self
+    }
+
+    /// Keep new cards out of sessions while any review is still due today.
+    #[must_use]
+    pub fn with_hold_new_until_reviews_done(mut self, hold: bool) -> Self {
+        self.hold_new_until_reviews_done = hold;
+        self
+    }
+
+    /// Spread the first few review intervals of new cards by a small random amount.
+    #[must_use]
+    pub fn with_interval_fuzz(mut self, fuzz: bool) -> Self {
+        self.interval_fuzz = fuzz;
+        self
+    }
+
+    /// Hold back a card's note siblings for the rest of the study day once it is queued.
+    #[must_use]
+    pub fn with_bury_siblings(mut self, bury: bool) -> Self {
+        self.bury_siblings = bury;
+        self
+    }
+
+    /// Set how many Agains in a row take a card out of the session it is failing in.
+    ///
+    /// A limit of zero keeps failed cards coming back for as long as the session runs.
+    #[must_use]
+    pub fn with_same_session_fail_limit(mut self, limit: u32) -> Self {
+        self.same_session_fail_limit = limit;
+        self
+    }
+
+    /// Reveal a long answer one paragraph or line at a time before grading unlocks.
+    #[must_use]
+    pub fn with_progressive_reveal(mut self, enabled: bool) -> Self {
+        self.progressive_reveal = enabled;
+        self
+    }
+
+    /// Ask for the answer to be typed and checked against the card before self-grading.
+    #[must_use]
+    pub fn with_typed_answer(mut self, enabled: bool) -> Self {
+        self.typed_answer = enabled;
+        self
+    }
+
+    /// Choose whether new cards come out oldest first or in a per-day shuffle.
+    #[must_use]
+    pub fn with_new_card_order(mut self, order: NewCardOrder) -> Self {
+        self.new_card_order = order;
+        self
+    }
+
+    /// Pre-fill new cards in this deck with starter text.
+    #[must_use]
+    pub fn with_new_card_template(mut self, template: NewCardTemplate) -> Self {
+        self.new_card_template = template;
+        self
+    }
+
+    /// Set how many new cards one session may introduce, on top of the daily limit.
+    ///
+    /// Zero leaves sessions bounded by the daily limit and session size alone.
+    #[must_use]
+    pub fn with_new_per_session(mut self, limit: u32) -> Self {
+        self.new_per_session = limit;
+        self
+    }
+
+    /// Choose how new cards are ordered among the due reviews of a session.
+    #[must_use]
+    pub fn with_new_review_mix(mut self, mix: NewReviewMix) -> Self {
+        self.new_review_mix = mix;
+        self
+    }
+
+    /// Let review due dates shift a day or two toward quieter days.
+    #[must_use]
+    pub fn with_load_balance(mut self, enabled: bool) -> Self {
+        self.load_balance = enabled;
+        self
+    }
+
+    /// Set the delays a new card waits on before graduating; empty graduates it on the
+    /// first answer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckError::InvalidLearningSteps` for delays outside
+    /// `LEARNING_STEP_SECS_RANGE` or more than `MAX_LEARNING_STEPS` of them.
+    pub fn with_learning_steps(mut self, steps: Vec<u32>) -> Result<Self, DeckError> {
+        if !valid_steps(&steps) {
+            return Err(DeckError::InvalidLearningSteps);
+        }
+        self.learning_steps_secs = steps;
+        Ok(self)
+    }
+
+    /// Set the delays a lapsed review card waits on before it returns to review; empty
+    /// sends it straight back to FSRS scheduling.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckError::InvalidRelearningSteps` for delays outside
+    /// `LEARNING_STEP_SECS_RANGE` or more than `MAX_LEARNING_STEPS` of them.
+    pub fn with_relearning_steps(mut self, steps: Vec<u32>) -> Result<Self, DeckError> {
+        if !valid_steps(&steps) {
+            return Err(DeckError::InvalidRelearningSteps);
+        }
+        self.relearning_steps_secs = steps;
+        Ok(self)
+    }
+
+    /// Lay out the prompt and answer with custom templates; blank text keeps the default.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckError::InvalidTemplate` if either template fails `CardTemplate::parse`.
+    pub fn with_templates(
+        mut self,
+        front: Option<&str>,
+        back: Option<&str>,
+    ) -> Result<Self, DeckError> {
+        let parse = |side, source: Option<&str>| {
+            source
+                .filter(|source| !source.trim().is_empty())
+                .map(|source| CardTemplate::parse(side, source))
+                .transpose()
+                .map_err(DeckError::InvalidTemplate)
+        };
+        self.front_template = parse(CardSide::Prompt, front)?;
+        self.back_template = parse(CardSide::Answer, back)?;
+        Ok(self)
+    }
+
+    /// Parse FSRS weights written as numbers separated by commas and/or whitespace.
+    ///
+    /// Blank text parses to no weights, which schedules with the FSRS defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckError::InvalidFsrsParameters` if a weight is not a finite number or the
+    /// text holds anything other than `FSRS_PARAMETER_COUNT` weights.
+    pub fn parse_fsrs_parameters(text: &str) -> Result<Vec<f32>, DeckError> {
+        let parameters = text
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|weight| !weight.is_empty())
+            .map(|weight| weight.parse::<f32>().map_err(|_| DeckError::InvalidFsrsParameters))
+            .collect::<Result<Vec<_>, _>>()?;
+        validate_fsrs_parameters(&parameters)?;
+        Ok(parameters)
+    }
+
+    /// Write FSRS weights as comma-separated numbers that `parse_fsrs_parameters` reads back
+    /// exactly; no weights write as an empty string.
+    #[must_use]
+    pub fn format_fsrs_parameters(parameters: &[f32]) -> String {
+        parameters
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Write these settings as a JSON preset to reuse on other decks or share.
+    ///
+    /// Color and icon are left out since they identify a deck rather than how it schedules.
+    #[must_use]
+    pub fn to_preset_json(&self) -> String {
+        let preset = self.clone().with_appearance(None, None);
+        serde_json::to_string_pretty(&preset).expect("deck settings serialize to JSON")
+    }
+
+    /// Read a preset written by `to_preset_json`, with the same checks as `DeckSettings::new`.
+    ///
+    /// Fields the preset leaves out take the `default_for_adhd` value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckError::InvalidPreset` if `json` is not a JSON object of settings fields,
+    /// or the `DeckError` for the first setting that is out of range.
+    pub fn from_preset_json(json: &str) -> Result<Self, DeckError> {
+        let invalid = |err: serde_json::Error| DeckError::InvalidPreset(err.to_string());
+        let value: serde_json::Value = serde_json::from_str(json).map_err(invalid)?;
+        // Serde would read an array positionally into the fields.
+        if !value.is_object() {
+            return Err(DeckError::InvalidPreset("expected a JSON object".into()));
+        }
+        Self::try_from(serde_json::from_value::<DeckSettingsWire>(value).map_err(invalid)?)
+    }
+
     // Accessors
     #[must_use]
     pub fn new_cards_per_day(&self) -> u32 {
@@ -322,12 +939,302 @@ impl DeckSettings {
         self.fsrs_optimize_after
     }
 
+    #[must_use]
+    pub fn new_review_mix(&self) -> NewReviewMix {
+        self.new_review_mix
+    }
+
+    /// Whether review due dates may shift a day or two toward quieter days.
+    #[must_use]
+    pub fn load_balance(&self) -> bool {
+        self.load_balance
+    }
+
+    /// Whether a young card's interval is nudged randomly so cards learned together don't
+    /// all come due on the same day.
+    #[must_use]
+    pub fn interval_fuzz(&self) -> bool {
+        self.interval_fuzz
+    }
+
+    /// Whether queueing a card buries the other cards of its note, such as its reverse or
+    /// other clozes, until the next study day.
+    #[must_use]
+    pub fn bury_siblings(&self) -> bool {
+        self.bury_siblings
+    }
+
+    /// Delays a new card waits on before graduating; empty graduates on the first answer.
+    #[must_use]
+    pub fn learning_steps_secs(&self) -> &[u32] {
+        &self.learning_steps_secs
+    }
+
+    /// Delays a lapsed card waits on before returning to review; empty skips relearning.
+    #[must_use]
+    pub fn relearning_steps_secs(&self) -> &[u32] {
+        &self.relearning_steps_secs
+    }
+
+    /// FSRS weights learned from this deck's reviews; empty until the optimizer has run.
+    #[must_use]
+    pub fn fsrs_parameters(&self) -> &[f32] {
+        &self.fsrs_parameters
+    }
+
+    /// Grade buttons offered in this deck's sessions.
+    #[must_use]
+    pub fn answer_buttons(&self) -> AnswerButtons {
+        self.answer_buttons
+    }
+
+    /// Lapses that make a card a leech; zero when leech detection is off.
+    #[must_use]
+    pub fn leech_threshold(&self) -> u32 {
+        self.leech_threshold
+    }
+
+    #[must_use]
+    pub fn leech_action(&self) -> LeechAction {
+        self.leech_action
+    }
+
+    #[must_use]
+    pub fn color(&self) -> Option<DeckColor> {
+        self.color
+    }
+
+    #[must_use]
+    pub fn icon(&self) -> Option<DeckIcon> {
+        self.icon
+    }
+
+    /// Whether new cards wait until the day's due reviews are done.
+    #[must_use]
+    pub fn hold_new_until_reviews_done(&self) -> bool {
+        self.hold_new_until_reviews_done
+    }
+
+    /// Custom layout for the prompt side; `None` uses `CardTemplate::DEFAULT_FRONT`.
+    #[must_use]
+    pub fn front_template(&self) -> Option<&CardTemplate> {
+        self.front_template.as_ref()
+    }
+
+    /// Custom layout for the revealed answer; `None` uses `CardTemplate::DEFAULT_BACK`.
+    #[must_use]
+    pub fn back_template(&self) -> Option<&CardTemplate> {
+        self.back_template.as_ref()
+    }
+
+    /// Agains in a row that take a card out of a session; zero when the limit is off.
+    #[must_use]
+    pub fn same_session_fail_limit(&self) -> u32 {
+        self.same_session_fail_limit
+    }
+
+    /// Whether `consecutive_agains` in one session are enough to pull a card out of it.
+    #[must_use]
+    pub fn fails_out_of_session(&self, consecutive_agains: u32) -> bool {
+        self.same_session_fail_limit > 0 && consecutive_agains >= self.same_session_fail_limit
+    }
+
+    /// Whether answers reveal step by step, one paragraph or line per keypress.
+    #[must_use]
+    pub fn progressive_reveal(&self) -> bool {
+        self.progressive_reveal
+    }
+
+    /// Whether sessions take a typed answer and show how it differs from the card's.
+    #[must_use]
+    pub fn typed_answer(&self) -> bool {
+        self.typed_answer
+    }
+
+    /// Order the day's new cards are drawn in.
+    #[must_use]
+    pub fn new_card_order(&self) -> NewCardOrder {
+        self.new_card_order
+    }
+
+    /// Starter text for new cards; empty when they start blank.
+    #[must_use]
+    pub fn new_card_template(&self) -> &NewCardTemplate {
+        &self.new_card_template
+    }
+
+    /// New cards one session may introduce; zero when only the daily limit applies.
+    #[must_use]
+    pub fn new_per_session(&self) -> u32 {
+        self.new_per_session
+    }
+
+    /// New cards a session may introduce when `daily_remaining` are left for the day.
+    #[must_use]
+    pub fn session_new_limit(&self, daily_remaining: u32) -> u32 {
+        if self.new_per_session == 0 {
+            daily_remaining
+        } else {
+            daily_remaining.min(self.new_per_session)
+        }
+    }
+
+    /// Whether a card with `lapses` lapses counts as a leech in this deck.
+    #[must_use]
+    pub fn is_leech(&self, lapses: u32) -> bool {
+        self.leech_threshold > 0 && lapses >= self.leech_threshold
+    }
+
     #[must_use]
     pub fn lapse_min_interval(&self) -> chrono::Duration {
         chrono::Duration::seconds(i64::from(self.lapse_min_interval_secs))
     }
 }
 
+/// Unvalidated serde shape of `DeckSettings`.
+#[derive(Deserialize)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
+struct DeckSettingsWire {
+    new_cards_per_day: u32,
+    review_limit_per_day: u32,
+    micro_session_size: u32,
+    protect_overload: bool,
+    preserve_stability_on_lapse: bool,
+    lapse_min_interval_secs: u32,
+    show_timer: bool,
+    soft_time_reminder: bool,
+    auto_advance_cards: bool,
+    soft_time_reminder_secs: u32,
+    auto_reveal_secs: u32,
+    min_interval_secs: u32,
+    max_interval_days: u32,
+    easy_days_enabled: bool,
+    easy_day_load_factor: f32,
+    easy_days_mask: u8,
+    fsrs_target_retention: f32,
+    fsrs_optimize_enabled: bool,
+    fsrs_optimize_after: u32,
+    new_review_mix: NewReviewMix,
+    load_balance: bool,
+    learning_steps_secs: Vec<u32>,
+    relearning_steps_secs: Vec<u32>,
+    fsrs_parameters: Vec<f32>,
+    answer_buttons: AnswerButtons,
+    leech_threshold: u32,
+    leech_action: LeechAction,
+    color: Option<DeckColor>,
+    icon: Option<DeckIcon>,
+    hold_new_until_reviews_done: bool,
+    front_template: Option<String>,
+    back_template: Option<String>,
+    interval_fuzz: bool,
+    bury_siblings: bool,
+    same_session_fail_limit: u32,
+    progressive_reveal: bool,
+    typed_answer: bool,
+    new_card_order: NewCardOrder,
+    new_card_template: NewCardTemplate,
+    new_per_session: u32,
+}
+
+impl Default for DeckSettingsWire {
+    fn default() -> Self {
+        let d = DeckSettings::default_for_adhd();
+        Self {
+            new_cards_per_day: d.new_cards_per_day,
+            review_limit_per_day: d.review_limit_per_day,
+            micro_session_size: d.micro_session_size,
+            protect_overload: d.protect_overload,
+            preserve_stability_on_lapse: d.preserve_stability_on_lapse,
+            lapse_min_interval_secs: d.lapse_min_interval_secs,
+            show_timer: d.show_timer,
+            soft_time_reminder: d.soft_time_reminder,
+            auto_advance_cards: d.auto_advance_cards,
+            soft_time_reminder_secs: d.soft_time_reminder_secs,
+            auto_reveal_secs: d.auto_reveal_secs,
+            min_interval_secs: d.min_interval_secs,
+            max_interval_days: d.max_interval_days,
+            easy_days_enabled: d.easy_days_enabled,
+            easy_day_load_factor: d.easy_day_load_factor,
+            easy_days_mask: d.easy_days_mask,
+            fsrs_target_retention: d.fsrs_target_retention,
+            fsrs_optimize_enabled: d.fsrs_optimize_enabled,
+            fsrs_optimize_after: d.fsrs_optimize_after,
+            new_review_mix: d.new_review_mix,
+            load_balance: d.load_balance,
+            learning_steps_secs: d.learning_steps_secs,
+            relearning_steps_secs: d.relearning_steps_secs,
+            fsrs_parameters: d.fsrs_parameters,
+            answer_buttons: d.answer_buttons,
+            leech_threshold: d.leech_threshold,
+            leech_action: d.leech_action,
+            color: d.color,
+            icon: d.icon,
+            hold_new_until_reviews_done: d.hold_new_until_reviews_done,
+            front_template: None,
+            back_template: None,
+            interval_fuzz: d.interval_fuzz,
+            bury_siblings: d.bury_siblings,
+            same_session_fail_limit: d.same_session_fail_limit,
+            progressive_reveal: d.progressive_reveal,
+            typed_answer: d.typed_answer,
+            new_card_order: d.new_card_order,
+            new_card_template: d.new_card_template,
+            new_per_session: d.new_per_session,
+        }
+    }
+}
+
+impl TryFrom<DeckSettingsWire> for DeckSettings {
+    type Error = DeckError;
+
+    fn try_from(w: DeckSettingsWire) -> Result<Self, Self::Error> {
+        Self::new(
+            w.new_cards_per_day,
+            w.review_limit_per_day,
+            w.micro_session_size,
+            w.protect_overload,
+            w.preserve_stability_on_lapse,
+            w.lapse_min_interval_secs,
+            w.show_timer,
+            w.soft_time_reminder,
+            w.auto_advance_cards,
+            w.soft_time_reminder_secs,
+            w.auto_reveal_secs,
+            w.min_interval_secs,
+            w.max_interval_days,
+            w.easy_days_enabled,
+            w.easy_day_load_factor,
+            w.easy_days_mask,
+            w.fsrs_target_retention,
+            w.fsrs_optimize_enabled,
+            w.fsrs_optimize_after,
+        )?
+        .with_fsrs_parameters(w.fsrs_parameters)?
+        .with_learning_steps(w.learning_steps_secs)?
+        .with_relearning_steps(w.relearning_steps_secs)
+        .map(|settings| {
+            settings
+                .with_new_review_mix(w.new_review_mix)
+                .with_load_balance(w.load_balance)
+                .with_answer_buttons(w.answer_buttons)
+                .with_leech(w.leech_threshold, w.leech_action)
+                .with_appearance(w.color, w.icon)
+                .with_hold_new_until_reviews_done(w.hold_new_until_reviews_done)
+                .with_interval_fuzz(w.interval_fuzz)
+                .with_bury_siblings(w.bury_siblings)
+                .with_same_session_fail_limit(w.same_session_fail_limit)
+                .with_progressive_reveal(w.progressive_reveal)
+                .with_typed_answer(w.typed_answer)
+                .with_new_card_order(w.new_card_order)
+                .with_new_card_template(w.new_card_template)
+                .with_new_per_session(w.new_per_session)
+        })?
+        .with_templates(w.front_template.as_deref(), w.back_template.as_deref())
+    }
+}
+
 fn weekday_bit(weekday: Weekday) -> u8 {
     match weekday {
         Weekday::Mon => 1 << 0,
@@ -344,6 +1251,26 @@ fn easy_days_mask(days: &[Weekday]) -> u8 {
     days.iter().fold(0, |mask, day| mask | weekday_bit(*day))
 }
 
+/// Learning and relearning steps share the same count and delay limits.
+fn valid_steps(steps: &[u32]) -> bool {
+    steps.len() <= DeckSettings::MAX_LEARNING_STEPS
+        && steps
+            .iter()
+            .all(|step| DeckSettings::LEARNING_STEP_SECS_RANGE.contains(step))
+}
+
+/// FSRS weights are either absent or a full set of finite numbers.
+fn validate_fsrs_parameters(parameters: &[f32]) -> Result<(), DeckError> {
+    if parameters.is_empty()
+        || (parameters.len() == DeckSettings::FSRS_PARAMETER_COUNT
+            && parameters.iter().all(|weight| weight.is_finite()))
+    {
+        Ok(())
+    } else {
+        Err(DeckError::InvalidFsrsParameters)
+    }
+}
+
 //
 // ─── DECK ──────────────────────────────────────────────────────────────────────
 //
@@ -358,6 +1285,10 @@ pub struct Deck {
     description: Option<String>,
     settings: DeckSettings,
     created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    archived: bool,
+    favorite: bool,
+    version: u64,
 }
 
 impl Deck {
@@ -388,9 +1319,48 @@ impl Deck {
             description,
             settings,
             created_at,
+            updated_at: created_at,
+            archived: false,
+            favorite: false,
+            version: 0,
         })
     }
 
+    /// Mark the deck as archived; archived decks keep their cards and history.
+    #[must_use]
+    pub fn with_archived(mut self, archived: bool) -> Self {
+        self.archived = archived;
+        self
+    }
+
+    /// Mark the deck as a favorite; favorites are listed ahead of other decks.
+    #[must_use]
+    pub fn with_favorite(mut self, favorite: bool) -> Self {
+        self.favorite = favorite;
+        self
+    }
+
+    /// Replace the deck's settings, keeping its identity and stored revision.
+    #[must_use]
+    pub fn with_settings(mut self, settings: DeckSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Set the stored revision this deck was loaded at.
+    #[must_use]
+    pub fn with_version(mut self, version: u64) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Stamp the time of the latest write, keeping everything else.
+    #[must_use]
+    pub fn with_updated_at(mut self, updated_at: DateTime<Utc>) -> Self {
+        self.updated_at = updated_at;
+        self
+    }
+
     // Accessors
     #[must_use]
     pub fn id(&self) -> DeckId {
@@ -416,6 +1386,30 @@ impl Deck {
     pub fn created_at(&self) -> DateTime<Utc> {
         self.created_at
     }
+
+    /// When the deck was last written; starts out equal to `created_at`.
+    #[must_use]
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    /// Archived decks are hidden from deck lists, session planning, and due counts.
+    #[must_use]
+    pub fn is_archived(&self) -> bool {
+        self.archived
+    }
+
+    /// Favorite decks are pinned to the top of deck lists.
+    #[must_use]
+    pub fn is_favorite(&self) -> bool {
+        self.favorite
+    }
+
+    /// Stored revision, bumped on every write; used to detect concurrent edits.
+    #[must_use]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
 }
 
 //
@@ -468,6 +1462,11 @@ mod tests {
         assert!((settings.fsrs_target_retention() - 0.85).abs() < f32::EPSILON);
         assert!(settings.fsrs_optimize_enabled());
         assert_eq!(settings.fsrs_optimize_after(), 100);
+        assert!(!settings.load_balance());
+        assert!(settings.interval_fuzz());
+        assert!(settings.bury_siblings());
+        assert_eq!(settings.learning_steps_secs(), &[60, 600]);
+        assert_eq!(settings.relearning_steps_secs(), &[600]);
     }
 
     #[test]
@@ -485,6 +1484,53 @@ mod tests {
         )
         .unwrap_err();
         assert_eq!(err, DeckError::InvalidFsrsTargetRetention);
+
+        for retention in [0.69, 0.995, 1.0, f32::NAN] {
+            let err = DeckSettings::new(
+                5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false,
+                0.5, 0, retention, true, 100,
+            )
+            .unwrap_err();
+            assert_eq!(err, DeckError::InvalidFsrsTargetRetention, "retention {retention}");
+        }
+        for retention in [0.7, 0.99] {
+            DeckSettings::new(
+                5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false,
+                0.5, 0, retention, true, 100,
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn settings_rejects_zero_daily_limits_and_counts() {
+        let err = DeckSettings::new(
+            0, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+            0.85, true, 100,
+        )
+        .unwrap_err();
+        assert_eq!(err, DeckError::InvalidNewCardsPerDay);
+
+        let err = DeckSettings::new(
+            5, 0, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+            0.85, true, 100,
+        )
+        .unwrap_err();
+        assert_eq!(err, DeckError::InvalidReviewLimitPerDay);
+
+        let err = DeckSettings::new(
+            5, 30, 5, true, true, 0, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0, 0.85,
+            true, 100,
+        )
+        .unwrap_err();
+        assert_eq!(err, DeckError::InvalidLapseMinInterval);
+
+        let err = DeckSettings::new(
+            5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+            0.85, true, 0,
+        )
+        .unwrap_err();
+        assert_eq!(err, DeckError::InvalidFsrsOptimizeAfter);
     }
 
     #[test]
@@ -502,6 +1548,12 @@ mod tests {
         )
         .unwrap_err();
         assert_eq!(err, DeckError::InvalidAutoRevealSeconds);
+
+        DeckSettings::new(
+            5, 30, 5, true, true, 86_400, false, false, false, 5, 600, 86_400, 365, false, 0.5, 0,
+            0.85, true, 100,
+        )
+        .unwrap();
     }
 
     #[test]
@@ -545,6 +1597,213 @@ mod tests {
         assert_eq!(err, DeckError::InvalidEasyDaysMask);
     }
 
+    #[test]
+    fn settings_rejects_invalid_learning_steps() {
+        let with_steps = |steps: Vec<u32>| {
+            DeckSettings::new(
+                5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false,
+                0.5, 0, 0.85, true, 100,
+            )
+            .and_then(|settings| settings.with_learning_steps(steps))
+        };
+
+        assert_eq!(with_steps(vec![60, 0]).unwrap_err(), DeckError::InvalidLearningSteps);
+        assert_eq!(with_steps(vec![86_401]).unwrap_err(), DeckError::InvalidLearningSteps);
+        assert_eq!(with_steps(vec![60; 11]).unwrap_err(), DeckError::InvalidLearningSteps);
+        assert_eq!(with_steps(vec![60; 10]).unwrap().learning_steps_secs().len(), 10);
+        assert!(with_steps(Vec::new()).unwrap().learning_steps_secs().is_empty());
+    }
+
+    #[test]
+    fn settings_rejects_invalid_relearning_steps() {
+        let with_steps = |steps| DeckSettings::default_for_adhd().with_relearning_steps(steps);
+
+        assert_eq!(with_steps(vec![0]).unwrap_err(), DeckError::InvalidRelearningSteps);
+        assert_eq!(with_steps(vec![60; 11]).unwrap_err(), DeckError::InvalidRelearningSteps);
+        assert_eq!(with_steps(vec![60, 3_600]).unwrap().relearning_steps_secs(), &[60, 3_600]);
+        assert!(with_steps(Vec::new()).unwrap().relearning_steps_secs().is_empty());
+    }
+
+    #[test]
+    fn settings_rejects_invalid_fsrs_parameters() {
+        let settings = DeckSettings::default_for_adhd();
+
+        assert_eq!(
+            settings.clone().with_fsrs_parameters(vec![1.0; 20]).unwrap_err(),
+            DeckError::InvalidFsrsParameters
+        );
+        let mut weights = vec![1.0; DeckSettings::FSRS_PARAMETER_COUNT];
+        weights[3] = f32::NAN;
+        assert_eq!(
+            settings.clone().with_fsrs_parameters(weights).unwrap_err(),
+            DeckError::InvalidFsrsParameters
+        );
+        let learned = settings
+            .with_fsrs_parameters(vec![1.0; DeckSettings::FSRS_PARAMETER_COUNT])
+            .unwrap();
+        assert_eq!(learned.fsrs_parameters().len(), DeckSettings::FSRS_PARAMETER_COUNT);
+        assert!(learned.with_fsrs_parameters(Vec::new()).unwrap().fsrs_parameters().is_empty());
+    }
+
+    #[test]
+    fn fsrs_parameters_round_trip_through_text() {
+        let weights: Vec<f32> = (1..=21u8).map(|i| f32::from(i) * 0.1234).collect();
+
+        let text = DeckSettings::format_fsrs_parameters(&weights);
+
+        assert_eq!(DeckSettings::parse_fsrs_parameters(&text).unwrap(), weights);
+        let spaced = text.replace(", ", "\n");
+        assert_eq!(DeckSettings::parse_fsrs_parameters(&spaced).unwrap(), weights);
+        assert!(DeckSettings::parse_fsrs_parameters("  ").unwrap().is_empty());
+        assert_eq!(DeckSettings::format_fsrs_parameters(&[]), "");
+        assert_eq!(
+            DeckSettings::parse_fsrs_parameters(&text.replacen("0.1234", "inf", 1)).unwrap_err(),
+            DeckError::InvalidFsrsParameters
+        );
+        assert_eq!(
+            DeckSettings::parse_fsrs_parameters(&format!("{text}, inf")).unwrap_err(),
+            DeckError::InvalidFsrsParameters
+        );
+    }
+
+    #[test]
+    fn answer_buttons_map_positions_to_grades() {
+        assert_eq!(DeckSettings::default_for_adhd().answer_buttons(), AnswerButtons::Four);
+        assert_eq!(AnswerButtons::Four.grade_at(1), Some(ReviewGrade::Hard));
+        assert_eq!(AnswerButtons::Two.grade_at(1), Some(ReviewGrade::Good));
+        assert_eq!(AnswerButtons::Two.grade_at(2), None);
+        assert!(!AnswerButtons::Two.offers(ReviewGrade::Easy));
+
+        let settings = DeckSettings::default_for_adhd().with_answer_buttons(AnswerButtons::Two);
+        assert_eq!(settings.answer_buttons(), AnswerButtons::Two);
+    }
+
+    #[test]
+    fn leech_threshold_is_reached_not_exceeded_and_zero_disables_it() {
+        let settings = DeckSettings::default_for_adhd();
+        assert_eq!(settings.leech_threshold(), DeckSettings::DEFAULT_LEECH_THRESHOLD);
+        assert_eq!(settings.leech_action(), LeechAction::Tag);
+        assert!(!settings.is_leech(7));
+        assert!(settings.is_leech(8));
+
+        let off = settings.with_leech(0, LeechAction::Suspend);
+        assert!(!off.is_leech(100));
+        assert_eq!(off.leech_action(), LeechAction::Suspend);
+    }
+
+    #[test]
+    fn same_session_fail_limit_is_reached_not_exceeded_and_zero_disables_it() {
+        let settings = DeckSettings::default_for_adhd();
+        assert_eq!(
+            settings.same_session_fail_limit(),
+            DeckSettings::DEFAULT_SAME_SESSION_FAIL_LIMIT
+        );
+        assert!(!settings.fails_out_of_session(3));
+        assert!(settings.fails_out_of_session(4));
+
+        let off = settings.with_same_session_fail_limit(0);
+        assert!(!off.fails_out_of_session(100));
+    }
+
+    #[test]
+    fn deck_colors_and_icons_parse_by_name_and_reject_unknown_ones() {
+        for color in DeckColor::ALL {
+            assert_eq!(color.as_str().parse::<DeckColor>(), Ok(color));
+        }
+        for icon in DeckIcon::ALL {
+            assert_eq!(icon.as_str().parse::<DeckIcon>(), Ok(icon));
+        }
+        assert_eq!(
+            "magenta".parse::<DeckColor>(),
+            Err(DeckError::UnknownDeckColor("magenta".into()))
+        );
+        assert_eq!("Blue".parse::<DeckColor>(), Err(DeckError::UnknownDeckColor("Blue".into())));
+        assert_eq!("rocket".parse::<DeckIcon>(), Err(DeckError::UnknownDeckIcon("rocket".into())));
+
+        let settings = DeckSettings::default_for_adhd();
+        assert_eq!((settings.color(), settings.icon()), (None, None));
+        let marked = settings.with_appearance(Some(DeckColor::Teal), Some(DeckIcon::Music));
+        assert_eq!(marked.color(), Some(DeckColor::Teal));
+        assert_eq!(marked.icon(), Some(DeckIcon::Music));
+    }
+
+    #[test]
+    fn hold_new_until_reviews_done_is_off_by_default() {
+        let settings = DeckSettings::default_for_adhd();
+        assert!(!settings.hold_new_until_reviews_done());
+        assert!(settings.with_hold_new_until_reviews_done(true).hold_new_until_reviews_done());
+    }
+
+    #[test]
+    fn templates_default_to_none_and_reject_unknown_fields() {
+        let settings = DeckSettings::default_for_adhd();
+        assert_eq!((settings.front_template(), settings.back_template()), (None, None));
+
+        let custom = settings
+            .clone()
+            .with_templates(Some("<h2>{{front}}</h2>"), Some("  "))
+            .unwrap();
+        assert_eq!(custom.front_template().map(CardTemplate::as_str), Some("<h2>{{front}}</h2>"));
+        assert_eq!(custom.back_template(), None);
+
+        let err = settings.with_templates(None, Some("{{hint}}")).unwrap_err();
+        assert_eq!(err, DeckError::InvalidTemplate(TemplateError::UnknownField("hint".into())));
+    }
+
+    #[test]
+    fn new_per_session_caps_the_daily_allowance_and_zero_disables_it() {
+        let settings = DeckSettings::default_for_adhd();
+        assert_eq!(settings.new_per_session(), 0);
+        assert_eq!(settings.session_new_limit(20), 20);
+
+        let capped = settings.with_new_per_session(5);
+        assert_eq!(capped.session_new_limit(20), 5);
+        assert_eq!(capped.session_new_limit(3), 3);
+    }
+
+    #[test]
+    fn new_card_template_treats_blank_sides_as_empty() {
+        let settings = DeckSettings::default_for_adhd();
+        assert!(settings.new_card_template().is_empty());
+
+        let template = NewCardTemplate::new("Word: ", "  \n");
+        assert_eq!((template.prompt(), template.answer()), ("Word: ", ""));
+        let custom = settings.with_new_card_template(template.clone());
+        assert_eq!(custom.new_card_template(), &template);
+        assert!(NewCardTemplate::new(" ", "").is_empty());
+    }
+
+    #[test]
+    fn preset_json_round_trips_settings_without_appearance() {
+        let settings = DeckSettings::default_for_adhd()
+            .with_leech(4, LeechAction::Tag)
+            .with_interval_fuzz(false)
+            .with_bury_siblings(false)
+            .with_relearning_steps(vec![300, 1_800])
+            .unwrap()
+            .with_templates(Some("<h2>{{front}}</h2>"), None)
+            .unwrap();
+        let marked = settings
+            .clone()
+            .with_appearance(Some(DeckColor::Teal), Some(DeckIcon::Music));
+
+        let restored = DeckSettings::from_preset_json(&marked.to_preset_json()).unwrap();
+
+        assert_eq!(restored, settings);
+    }
+
+    #[test]
+    fn preset_json_rejects_out_of_range_and_malformed_presets() {
+        let err = DeckSettings::from_preset_json(r#"{"fsrs_target_retention": 1.5}"#).unwrap_err();
+        assert_eq!(err, DeckError::InvalidFsrsTargetRetention);
+
+        let err = DeckSettings::from_preset_json(r#"{"micro_session_size": 0}"#).unwrap_err();
+        assert_eq!(err, DeckError::InvalidMicroSessionSize);
+
+        let err = DeckSettings::from_preset_json("[1, 2]").unwrap_err();
+        assert!(matches!(err, DeckError::InvalidPreset(_)));
+    }
+
     #[test]
     fn deck_new_happy_path() {
         let settings = DeckSettings::default_for_adhd();
@@ -561,6 +1820,10 @@ mod tests {
         assert_eq!(deck.name(), "German B1");
         assert_eq!(deck.description(), Some("verbs + phrases"));
         assert_eq!(deck.settings().micro_session_size(), 5);
+        assert!(!deck.is_archived());
+        assert!(!deck.is_favorite());
+        assert!(deck.clone().with_favorite(true).is_favorite());
+        assert!(deck.with_archived(true).is_archived());
     }
 
     #[test]
@@ -74,6 +74,24 @@ impl TagId {
     }
 }
 
+/// Shared identifier for cards generated from one note (e.g. a card and its reverse)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct NoteId(u64);
+
+impl NoteId {
+    /// Creates a new `NoteId`
+    #[must_use]
+    pub fn new(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// Returns the underlying u64 value
+    #[must_use]
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
 impl fmt::Debug for CardId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "CardId({})", self.0)
@@ -98,6 +116,12 @@ impl fmt::Debug for TagId {
     }
 }
 
+impl fmt::Debug for NoteId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NoteId({})", self.0)
+    }
+}
+
 // ─── Display Implementations ───────────────────────────────────────────────────
 
 impl fmt::Display for CardId {
@@ -124,6 +148,12 @@ impl fmt::Display for TagId {
     }
 }
 
+impl fmt::Display for NoteId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 // ─── FromStr Implementations ───────────────────────────────────────────────────
 
 /// Error type for parsing ID from string
@@ -0,0 +1,418 @@
+use std::fmt;
+
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeyBindingError {
+    #[error("unknown shortcut action: {0}")]
+    UnknownAction(String),
+
+    #[error("invalid key combination: {0}")]
+    InvalidCombo(String),
+
+    #[error("line {0} of the key bindings has no `action=keys` pair")]
+    MalformedLine(usize),
+}
+
+/// Where an action's shortcut is listened for; actions only clash within one scope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyScope {
+    Editor,
+    Session,
+}
+
+/// A shortcut the user may bind to a different key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KeyAction {
+    Save,
+    NewCard,
+    Delete,
+    Reveal,
+    /// Grade with the leftmost answer button.
+    Grade1,
+    Grade2,
+    Grade3,
+    Grade4,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 8] = [
+        KeyAction::Save,
+        KeyAction::NewCard,
+        KeyAction::Delete,
+        KeyAction::Reveal,
+        KeyAction::Grade1,
+        KeyAction::Grade2,
+        KeyAction::Grade3,
+        KeyAction::Grade4,
+    ];
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KeyAction::Save => "save",
+            KeyAction::NewCard => "new-card",
+            KeyAction::Delete => "delete",
+            KeyAction::Reveal => "reveal",
+            KeyAction::Grade1 => "grade-1",
+            KeyAction::Grade2 => "grade-2",
+            KeyAction::Grade3 => "grade-3",
+            KeyAction::Grade4 => "grade-4",
+        }
+    }
+
+    /// Parse the name written by [`KeyAction::as_str`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `KeyBindingError::UnknownAction` for any other name.
+    pub fn parse(name: &str) -> Result<Self, KeyBindingError> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.as_str() == name)
+            .ok_or_else(|| KeyBindingError::UnknownAction(name.to_string()))
+    }
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            KeyAction::Save => "Save card",
+            KeyAction::NewCard => "New card",
+            KeyAction::Delete => "Delete selected card",
+            KeyAction::Reveal => "Reveal answer",
+            KeyAction::Grade1 => "Grade with 1st button",
+            KeyAction::Grade2 => "Grade with 2nd button",
+            KeyAction::Grade3 => "Grade with 3rd button",
+            KeyAction::Grade4 => "Grade with 4th button",
+        }
+    }
+
+    #[must_use]
+    pub fn scope(self) -> KeyScope {
+        match self {
+            KeyAction::Save | KeyAction::NewCard | KeyAction::Delete => KeyScope::Editor,
+            KeyAction::Reveal
+            | KeyAction::Grade1
+            | KeyAction::Grade2
+            | KeyAction::Grade3
+            | KeyAction::Grade4 => KeyScope::Session,
+        }
+    }
+
+    /// The grade action for the answer button at zero-based `position`.
+    #[must_use]
+    pub fn grade(position: usize) -> Option<Self> {
+        match position {
+            0 => Some(KeyAction::Grade1),
+            1 => Some(KeyAction::Grade2),
+            2 => Some(KeyAction::Grade3),
+            3 => Some(KeyAction::Grade4),
+            _ => None,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// The key in a combination, apart from its modifiers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundKey {
+    /// A printed character, stored lowercase.
+    Char(char),
+    Enter,
+    Escape,
+    Backspace,
+    Space,
+    Tab,
+    ArrowUp,
+    ArrowDown,
+}
+
+/// A key and the modifiers held with it, written like `Shift+Cmd+N`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyCombo {
+    pub key: BoundKey,
+    /// Whether the Command key is held.
+    pub command: bool,
+    pub shift: bool,
+}
+
+impl KeyCombo {
+    #[must_use]
+    pub const fn plain(key: BoundKey) -> Self {
+        Self {
+            key,
+            command: false,
+            shift: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn command(key: BoundKey) -> Self {
+        Self {
+            key,
+            command: true,
+            shift: false,
+        }
+    }
+
+    /// Parse a combination such as `Cmd+Enter`, `Space`, or `Shift+Cmd+7`.
+    ///
+    /// Modifier and key names ignore case; a single character names that key.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KeyBindingError::InvalidCombo` for an unknown modifier or key name.
+    pub fn parse(text: &str) -> Result<Self, KeyBindingError> {
+        let invalid = || KeyBindingError::InvalidCombo(text.to_string());
+        let trimmed = text.trim();
+        // A trailing "++" binds the plus key itself.
+        let (modifiers, key) = match trimmed.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None if trimmed == "+" => ("", "+"),
+            None => trimmed.rsplit_once('+').unwrap_or(("", trimmed)),
+        };
+
+        let mut combo = Self::plain(parse_key(key.trim()).ok_or_else(invalid)?);
+        for modifier in modifiers.split('+').map(str::trim).filter(|part| !part.is_empty()) {
+            match modifier.to_lowercase().as_str() {
+                "cmd" | "command" | "meta" => combo.command = true,
+                "shift" => combo.shift = true,
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(combo)
+    }
+}
+
+fn parse_key(name: &str) -> Option<BoundKey> {
+    let mut chars = name.chars();
+    if let (Some(value), None) = (chars.next(), chars.next()) {
+        return (!value.is_whitespace()).then(|| BoundKey::Char(lowercase(value)));
+    }
+    match name.to_lowercase().as_str() {
+        "enter" | "return" => Some(BoundKey::Enter),
+        "esc" | "escape" => Some(BoundKey::Escape),
+        "backspace" => Some(BoundKey::Backspace),
+        "space" => Some(BoundKey::Space),
+        "tab" => Some(BoundKey::Tab),
+        "up" | "arrowup" => Some(BoundKey::ArrowUp),
+        "down" | "arrowdown" => Some(BoundKey::ArrowDown),
+        _ => None,
+    }
+}
+
+fn lowercase(value: char) -> char {
+    value.to_lowercase().next().unwrap_or(value)
+}
+
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.shift {
+            f.write_str("Shift+")?;
+        }
+        if self.command {
+            f.write_str("Cmd+")?;
+        }
+        match self.key {
+            BoundKey::Char(value) => write!(f, "{}", value.to_uppercase()),
+            BoundKey::Enter => f.write_str("Enter"),
+            BoundKey::Escape => f.write_str("Esc"),
+            BoundKey::Backspace => f.write_str("Backspace"),
+            BoundKey::Space => f.write_str("Space"),
+            BoundKey::Tab => f.write_str("Tab"),
+            BoundKey::ArrowUp => f.write_str("Up"),
+            BoundKey::ArrowDown => f.write_str("Down"),
+        }
+    }
+}
+
+/// Two actions in the same scope bound to the same keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyConflict {
+    pub first: KeyAction,
+    pub second: KeyAction,
+    pub combo: KeyCombo,
+}
+
+/// The key combination for every [`KeyAction`].
+///
+/// Persisted as one `action=keys` line per action, e.g. `save=Cmd+Enter`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyBindings {
+    combos: [KeyCombo; KeyAction::ALL.len()],
+}
+
+impl KeyBindings {
+    /// Parse persisted bindings; actions without a line keep their default keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns `KeyBindingError` for a malformed line, unknown action, or invalid combo.
+    pub fn parse(text: &str) -> Result<Self, KeyBindingError> {
+        let mut bindings = Self::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (action, combo) = line
+                .split_once('=')
+                .ok_or(KeyBindingError::MalformedLine(index + 1))?;
+            bindings = bindings.with_binding(
+                KeyAction::parse(action.trim())?,
+                KeyCombo::parse(combo)?,
+            );
+        }
+        Ok(bindings)
+    }
+
+    #[must_use]
+    pub fn get(&self, action: KeyAction) -> KeyCombo {
+        self.combos[action.index()]
+    }
+
+    #[must_use]
+    pub fn with_binding(mut self, action: KeyAction, combo: KeyCombo) -> Self {
+        self.combos[action.index()] = combo;
+        self
+    }
+
+    /// The action in `scope` bound to `combo`, if any.
+    #[must_use]
+    pub fn action_for(&self, scope: KeyScope, combo: KeyCombo) -> Option<KeyAction> {
+        KeyAction::ALL
+            .into_iter()
+            .find(|action| action.scope() == scope && self.get(*action) == combo)
+    }
+
+    /// Every pair of actions sharing a scope and a key combination.
+    #[must_use]
+    pub fn conflicts(&self) -> Vec<KeyConflict> {
+        let mut conflicts = Vec::new();
+        for (index, first) in KeyAction::ALL.iter().enumerate() {
+            let combo = self.get(*first);
+            for second in &KeyAction::ALL[index + 1..] {
+                if first.scope() == second.scope() && combo == self.get(*second) {
+                    conflicts.push(KeyConflict {
+                        first: *first,
+                        second: *second,
+                        combo,
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            combos: [
+                KeyCombo::command(BoundKey::Enter),
+                KeyCombo::command(BoundKey::Char('n')),
+                KeyCombo::command(BoundKey::Backspace),
+                KeyCombo::plain(BoundKey::Space),
+                KeyCombo::plain(BoundKey::Char('1')),
+                KeyCombo::plain(BoundKey::Char('2')),
+                KeyCombo::plain(BoundKey::Char('3')),
+                KeyCombo::plain(BoundKey::Char('4')),
+            ],
+        }
+    }
+}
+
+impl fmt::Display for KeyBindings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, action) in KeyAction::ALL.iter().enumerate() {
+            if index > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{}={}", action.as_str(), self.get(*action))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_the_built_in_shortcuts() {
+        let bindings = KeyBindings::default();
+        assert_eq!(bindings.get(KeyAction::Save).to_string(), "Cmd+Enter");
+        assert_eq!(bindings.get(KeyAction::NewCard).to_string(), "Cmd+N");
+        assert_eq!(bindings.get(KeyAction::Delete).to_string(), "Cmd+Backspace");
+        assert_eq!(bindings.get(KeyAction::Reveal).to_string(), "Space");
+        assert_eq!(bindings.get(KeyAction::Grade3).to_string(), "3");
+        assert!(bindings.conflicts().is_empty());
+    }
+
+    #[test]
+    fn combos_parse_names_and_modifiers_in_any_case() {
+        assert_eq!(
+            KeyCombo::parse("shift+CMD+s"),
+            Ok(KeyCombo {
+                key: BoundKey::Char('s'),
+                command: true,
+                shift: true,
+            })
+        );
+        assert_eq!(KeyCombo::parse(" return "), Ok(KeyCombo::plain(BoundKey::Enter)));
+        assert_eq!(KeyCombo::parse("Cmd++"), Ok(KeyCombo::command(BoundKey::Char('+'))));
+        assert!(KeyCombo::parse("Alt+S").is_err());
+        assert!(KeyCombo::parse("Cmd+").is_err());
+    }
+
+    #[test]
+    fn lookup_finds_the_action_in_its_scope() {
+        let bindings = KeyBindings::default()
+            .with_binding(KeyAction::Save, KeyCombo::command(BoundKey::Char('s')));
+        let save = KeyCombo::command(BoundKey::Char('s'));
+        assert_eq!(bindings.action_for(KeyScope::Editor, save), Some(KeyAction::Save));
+        assert_eq!(bindings.action_for(KeyScope::Session, save), None);
+        assert_eq!(
+            bindings.action_for(KeyScope::Session, KeyCombo::plain(BoundKey::Char('2'))),
+            Some(KeyAction::Grade2)
+        );
+    }
+
+    #[test]
+    fn conflicts_only_count_within_one_scope() {
+        let space = KeyCombo::plain(BoundKey::Space);
+        let bindings = KeyBindings::default()
+            .with_binding(KeyAction::Grade4, space)
+            .with_binding(KeyAction::Save, space);
+        assert_eq!(
+            bindings.conflicts(),
+            [KeyConflict {
+                first: KeyAction::Reveal,
+                second: KeyAction::Grade4,
+                combo: space,
+            }]
+        );
+    }
+
+    #[test]
+    fn bindings_round_trip_through_text() {
+        let bindings = KeyBindings::default()
+            .with_binding(KeyAction::NewCard, KeyCombo::command(BoundKey::Char('t')))
+            .with_binding(KeyAction::Grade1, KeyCombo::plain(BoundKey::Char('=')));
+        assert_eq!(KeyBindings::parse(&bindings.to_string()), Ok(bindings));
+
+        let partial = KeyBindings::parse("reveal=Enter\n").expect("parse");
+        assert_eq!(partial.get(KeyAction::Reveal), KeyCombo::plain(BoundKey::Enter));
+        assert_eq!(partial.get(KeyAction::Save), KeyBindings::default().get(KeyAction::Save));
+
+        assert_eq!(
+            KeyBindings::parse("undo=Cmd+Z"),
+            Err(KeyBindingError::UnknownAction("undo".to_string()))
+        );
+        assert_eq!(KeyBindings::parse("save"), Err(KeyBindingError::MalformedLine(1)));
+    }
+}
@@ -1,21 +1,34 @@
 mod card;
+mod cloze;
 pub mod content;
 mod app_settings;
 mod deck;
 mod ids;
+mod key_bindings;
 mod review;
 mod session;
 mod tag;
+mod template;
 
 pub use content::{
-    Content, ContentDraft, ContentValidationError, ImageMeta, MediaHash, MediaUri,
-    MediaValidationError, TextError,
+    AudioClip, AudioFormat, AudioMeta, Content, ContentDraft, ContentValidationError,
+    ImageFormat, ImageMeta, MAX_TEXT_CHARS, MediaHash, MediaUri, MediaValidationError, TextError,
+};
+pub use ids::{CardId, DeckId, MediaId, NoteId, TagId};
+pub use key_bindings::{
+    BoundKey, KeyAction, KeyBindingError, KeyBindings, KeyCombo, KeyConflict, KeyScope,
 };
-pub use ids::{CardId, DeckId, MediaId, TagId};
 
-pub use card::{Card, CardError, CardPhase};
-pub use app_settings::{AppSettings, AppSettingsDraft, AppSettingsError};
-pub use deck::{Deck, DeckError, DeckSettings};
+pub use card::{Card, CardError, CardKind, CardPhase, CardSide, CardSource, Flag, LearningStep};
+pub use cloze::{ClozeError, ClozeExpansion, ClozeText, expand_cloze};
+pub use app_settings::{
+    AccentColor, AppLanguage, AppSettings, AppSettingsDraft, AppSettingsError, ThemePreference,
+};
+pub use deck::{
+    AnswerButtons, Deck, DeckColor, DeckError, DeckIcon, DeckSettings, LeechAction, NewCardOrder,
+    NewCardTemplate, NewReviewMix,
+};
 pub use review::{ReviewError, ReviewGrade, ReviewLog, ReviewOutcome};
 pub use session::{SessionSummary, SessionSummaryError};
 pub use tag::{Tag, TagError, TagName};
+pub use template::{CardTemplate, TemplateError, TemplateField, TemplatePart};
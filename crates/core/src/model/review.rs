@@ -72,13 +72,17 @@ impl ReviewGrade {
 
 /// Record of a single card review event.
 ///
-/// Stores which card was reviewed, when, and what grade was given.
-/// Used for tracking study history and analytics.
+/// Stores which card was reviewed, when, and what grade was given, plus how long the
+/// answer took when it was timed. Used for tracking study history and analytics.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ReviewLog {
     pub card_id: CardId,
     pub reviewed_at: DateTime<Utc>,
     pub grade: ReviewGrade,
+    /// Milliseconds from showing the card to grading it; `None` when it was not timed.
+    pub answer_ms: Option<u32>,
+    /// The card had never been reviewed before, so this review introduced it.
+    pub first_review: bool,
 }
 
 impl ReviewLog {
@@ -88,8 +92,24 @@ impl ReviewLog {
             card_id,
             reviewed_at,
             grade,
+            answer_ms: None,
+            first_review: false,
         }
     }
+
+    /// Attach how long the answer took, in milliseconds.
+    #[must_use]
+    pub fn with_answer_ms(mut self, answer_ms: Option<u32>) -> Self {
+        self.answer_ms = answer_ms;
+        self
+    }
+
+    /// Mark whether this review was the card's first.
+    #[must_use]
+    pub fn with_first_review(mut self, first_review: bool) -> Self {
+        self.first_review = first_review;
+        self
+    }
 }
 
 //
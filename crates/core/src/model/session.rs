@@ -27,6 +27,8 @@ pub struct SessionSummary {
     hard: u32,
     good: u32,
     easy: u32,
+    cram: bool,
+    ahead: bool,
 }
 
 impl SessionSummary {
@@ -66,9 +68,25 @@ impl SessionSummary {
             hard,
             good,
             easy,
+            cram: false,
+            ahead: false,
         })
     }
 
+    /// Mark the summary as coming from a cram session, whose grades did not change scheduling.
+    #[must_use]
+    pub fn with_cram(mut self, cram: bool) -> Self {
+        self.cram = cram;
+        self
+    }
+
+    /// Mark the summary as coming from a study-ahead session over cards not yet due.
+    #[must_use]
+    pub fn with_ahead(mut self, ahead: bool) -> Self {
+        self.ahead = ahead;
+        self
+    }
+
     /// Build a summary from a list of review logs.
     ///
     /// # Errors
@@ -152,6 +170,18 @@ impl SessionSummary {
     pub fn easy(&self) -> u32 {
         self.easy
     }
+
+    /// Whether the session was a cram session that left scheduling untouched.
+    #[must_use]
+    pub fn is_cram(&self) -> bool {
+        self.cram
+    }
+
+    /// Whether the session reviewed cards ahead of their due date.
+    #[must_use]
+    pub fn is_ahead(&self) -> bool {
+        self.ahead
+    }
 }
 
 #[cfg(test)]
@@ -177,5 +207,9 @@ mod tests {
         assert_eq!(summary.hard(), 1);
         assert_eq!(summary.good(), 2);
         assert_eq!(summary.easy(), 1);
+        assert!(!summary.is_cram());
+        assert!(summary.clone().with_cram(true).is_cram());
+        assert!(!summary.is_ahead());
+        assert!(summary.with_ahead(true).is_ahead());
     }
 }
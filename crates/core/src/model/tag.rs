@@ -22,6 +22,15 @@ impl TagName {
         Ok(Self(trimmed.to_string()))
     }
 
+    /// Name of the tag given to cards that keep lapsing.
+    pub const LEECH: &'static str = "leech";
+
+    /// The tag sessions attach to leech cards.
+    #[must_use]
+    pub fn leech() -> Self {
+        Self(Self::LEECH.to_string())
+    }
+
     #[must_use]
     pub fn as_str(&self) -> &str {
         &self.0
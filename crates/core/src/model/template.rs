@@ -0,0 +1,239 @@
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+
+use crate::model::card::CardSide;
+
+//
+// ─── ERRORS ────────────────────────────────────────────────────────────────────
+//
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TemplateError {
+    #[error("unknown template field {{{{{0}}}}}; use front, back, or extra")]
+    UnknownField(String),
+
+    #[error("template placeholder opened at byte {0} is never closed")]
+    Unclosed(usize),
+
+    #[error("template field {{{{{0}}}}} cannot sit inside an HTML tag")]
+    FieldInTag(&'static str),
+
+    #[error("the front template cannot show {{{{back}}}} before the answer is revealed")]
+    AnswerOnFront,
+}
+
+//
+// ─── TEMPLATE ──────────────────────────────────────────────────────────────────
+//
+
+/// Card content a template can place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateField {
+    Front,
+    Back,
+    Extra,
+}
+
+impl TemplateField {
+    pub const ALL: [TemplateField; 3] =
+        [TemplateField::Front, TemplateField::Back, TemplateField::Extra];
+
+    /// Name used between the braces, e.g. `front` in `{{front}}`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TemplateField::Front => "front",
+            TemplateField::Back => "back",
+            TemplateField::Extra => "extra",
+        }
+    }
+}
+
+/// A piece of a parsed template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplatePart {
+    /// Literal text or markup, copied as written.
+    Text(String),
+    /// A `{{field}}` placeholder.
+    Field(TemplateField),
+}
+
+/// Layout for one side of a deck's cards, with `{{front}}`, `{{back}}`, and `{{extra}}`
+/// placeholders.
+///
+/// Placeholders may only appear in text, never inside a tag or attribute, so card content
+/// always lands between elements. The front template cannot use `{{back}}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardTemplate {
+    side: CardSide,
+    source: String,
+    parts: Vec<TemplatePart>,
+}
+
+const OPEN: &str = "{{";
+const CLOSE: &str = "}}";
+
+impl CardTemplate {
+    /// Layout of the prompt side when a deck sets none.
+    pub const DEFAULT_FRONT: &'static str = "{{front}}";
+
+    /// Layout of the revealed answer when a deck sets none.
+    pub const DEFAULT_BACK: &'static str = "{{back}}{{extra}}";
+
+    /// Parse a template for `side`.
+    ///
+    /// Whitespace inside the braces is ignored, so `{{ front }}` works too.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TemplateError::UnknownField` for a placeholder other than front, back,
+    /// or extra, `TemplateError::Unclosed` for `{{` without a matching `}}`,
+    /// `TemplateError::FieldInTag` for a placeholder inside a tag, and
+    /// `TemplateError::AnswerOnFront` for `{{back}}` in a prompt template.
+    pub fn parse(side: CardSide, source: &str) -> Result<Self, TemplateError> {
+        let mut parts = Vec::new();
+        let mut markup = MarkupScan::default();
+        let mut rest = source;
+        while let Some(start) = rest.find(OPEN) {
+            let (text, tail) = rest.split_at(start);
+            markup.scan(text);
+            let body = &tail[OPEN.len()..];
+            let Some(end) = body.find(CLOSE) else {
+                return Err(TemplateError::Unclosed(source.len() - tail.len()));
+            };
+            let name = body[..end].trim();
+            let field = TemplateField::ALL
+                .into_iter()
+                .find(|field| field.as_str() == name)
+                .ok_or_else(|| TemplateError::UnknownField(name.to_string()))?;
+            if side == CardSide::Prompt && field == TemplateField::Back {
+                return Err(TemplateError::AnswerOnFront);
+            }
+            if markup.in_tag {
+                return Err(TemplateError::FieldInTag(field.as_str()));
+            }
+            if !text.is_empty() {
+                parts.push(TemplatePart::Text(text.to_string()));
+            }
+            parts.push(TemplatePart::Field(field));
+            rest = &body[end + CLOSE.len()..];
+        }
+        if !rest.is_empty() {
+            parts.push(TemplatePart::Text(rest.to_string()));
+        }
+        Ok(Self {
+            side,
+            source: source.to_string(),
+            parts,
+        })
+    }
+
+    /// The built-in layout for `side`.
+    #[must_use]
+    pub fn default_for(side: CardSide) -> Self {
+        let source = match side {
+            CardSide::Prompt => Self::DEFAULT_FRONT,
+            CardSide::Answer => Self::DEFAULT_BACK,
+        };
+        Self::parse(side, source).expect("default templates are valid")
+    }
+
+    #[must_use]
+    pub fn side(&self) -> CardSide {
+        self.side
+    }
+
+    /// The template as written.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    #[must_use]
+    pub fn parts(&self) -> &[TemplatePart] {
+        &self.parts
+    }
+}
+
+impl Serialize for CardTemplate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+/// Tracks whether scanned text ends inside an HTML tag, skipping quoted attribute values.
+///
+/// A `<` only opens a tag when a letter, `/`, or `!` follows, so `a < b` stays text.
+#[derive(Default)]
+struct MarkupScan {
+    in_tag: bool,
+    quote: Option<char>,
+}
+
+impl MarkupScan {
+    fn scan(&mut self, text: &str) {
+        let mut chars = text.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if !self.in_tag {
+                self.in_tag = ch == '<'
+                    && chars
+                        .peek()
+                        .is_some_and(|next| next.is_ascii_alphabetic() || matches!(next, '/' | '!'));
+            } else if let Some(quote) = self.quote {
+                if ch == quote {
+                    self.quote = None;
+                }
+            } else if matches!(ch, '"' | '\'') {
+                self.quote = Some(ch);
+            } else if ch == '>' {
+                self.in_tag = false;
+            }
+        }
+    }
+}
+
+//
+// ─── TESTS ─────────────────────────────────────────────────────────────────────
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fields_between_literal_markup() {
+        let template =
+            CardTemplate::parse(CardSide::Answer, "<p>{{ back }}</p>{{front}} a < b").unwrap();
+
+        assert_eq!(
+            template.parts(),
+            [
+                TemplatePart::Text("<p>".into()),
+                TemplatePart::Field(TemplateField::Back),
+                TemplatePart::Text("</p>".into()),
+                TemplatePart::Field(TemplateField::Front),
+                TemplatePart::Text(" a < b".into()),
+            ]
+        );
+        assert_eq!(template.as_str(), "<p>{{ back }}</p>{{front}} a < b");
+        assert_eq!(CardTemplate::default_for(CardSide::Answer).side(), CardSide::Answer);
+    }
+
+    #[test]
+    fn rejects_unknown_unclosed_and_misplaced_fields() {
+        let parse = |side, source| CardTemplate::parse(side, source).unwrap_err();
+
+        assert_eq!(
+            parse(CardSide::Answer, "{{hint}}"),
+            TemplateError::UnknownField("hint".into())
+        );
+        assert_eq!(parse(CardSide::Answer, "ok {{front"), TemplateError::Unclosed(3));
+        assert_eq!(
+            parse(CardSide::Answer, r#"<a href="{{front}}">x</a>"#),
+            TemplateError::FieldInTag("front")
+        );
+        assert_eq!(parse(CardSide::Prompt, "{{front}} {{back}}"), TemplateError::AnswerOnFront);
+        assert!(CardTemplate::parse(CardSide::Prompt, r#"<p title="a>b">{{extra}}</p>"#).is_ok());
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::model::{CardId, ReviewLog};
+use crate::scheduler::{Scheduler, SchedulerError};
+
+//
+// ─── FITTED PARAMETERS ─────────────────────────────────────────────────────────
+//
+
+/// FSRS weights fitted to a review history, with how well they predict it.
+///
+/// # Fields
+///
+/// * `parameters` - The fitted weights, ready for `Scheduler::try_with_parameters`
+/// * `log_loss` - Mean log loss of predicted recall against the actual answers (lower is better)
+/// * `rmse_bins` - Binned RMSE between predicted and actual recall rates (lower is better)
+/// * `review_count` - Reviews in the history the weights were fitted to
+#[derive(Debug, Clone, PartialEq)]
+pub struct FittedParameters {
+    pub parameters: Vec<f32>,
+    pub log_loss: f32,
+    pub rmse_bins: f32,
+    pub review_count: usize,
+}
+
+//
+// ─── OPTIMIZER ─────────────────────────────────────────────────────────────────
+//
+
+/// Fit FSRS weights to a review history and score them against it.
+///
+/// Logs may cover many cards in any order; each card's reviews are replayed by review time,
+/// with elapsed time counted in whole UTC days. Small histories get FSRS's initial-stability
+/// fit only, since full training needs a few dozen spaced reviews to beat the defaults.
+///
+/// # Errors
+///
+/// Returns `SchedulerError::NotEnoughHistory` if no card was reviewed on two different days.
+/// Returns `SchedulerError::FsrsError` if fitting or evaluation fails.
+pub fn optimize_parameters(logs: &[ReviewLog]) -> Result<FittedParameters, SchedulerError> {
+    let items = training_items(logs);
+    if items.is_empty() {
+        return Err(SchedulerError::NotEnoughHistory);
+    }
+
+    let fsrs_error = |e: fsrs::FSRSError| SchedulerError::FsrsError(e.to_string());
+    let parameters = fsrs::FSRS::new(None)
+        .map_err(fsrs_error)?
+        .compute_parameters(fsrs::ComputeParametersInput {
+            train_set: items.clone(),
+            ..fsrs::ComputeParametersInput::default()
+        })
+        .map_err(fsrs_error)?;
+    let evaluation = fsrs::FSRS::new(Some(&parameters))
+        .map_err(fsrs_error)?
+        .evaluate(items, |_| true)
+        .map_err(fsrs_error)?;
+
+    Ok(FittedParameters {
+        parameters,
+        log_loss: evaluation.log_loss,
+        rmse_bins: evaluation.rmse_bins,
+        review_count: logs.len(),
+    })
+}
+
+/// One training item per review that came at least a day after the card's previous one,
+/// carrying the card's history up to and including it.
+fn training_items(logs: &[ReviewLog]) -> Vec<fsrs::FSRSItem> {
+    let mut by_card: BTreeMap<CardId, Vec<&ReviewLog>> = BTreeMap::new();
+    for log in logs {
+        by_card.entry(log.card_id).or_default().push(log);
+    }
+
+    let mut items = Vec::new();
+    for history in by_card.values_mut() {
+        history.sort_by_key(|log| log.reviewed_at);
+        let mut reviews = Vec::with_capacity(history.len());
+        let mut previous: Option<DateTime<Utc>> = None;
+        for log in history.iter() {
+            let delta_t = previous.map_or(0, |previous| {
+                let days = (log.reviewed_at.date_naive() - previous.date_naive()).num_days();
+                u32::try_from(days).unwrap_or(0)
+            });
+            reviews.push(fsrs::FSRSReview {
+                rating: u32::from(Scheduler::grade_to_rating(log.grade)),
+                delta_t,
+            });
+            previous = Some(log.reviewed_at);
+            if reviews.len() > 1 && delta_t > 0 {
+                items.push(fsrs::FSRSItem {
+                    reviews: reviews.clone(),
+                });
+            }
+        }
+    }
+    items
+}
+
+//
+// ─── TESTS ─────────────────────────────────────────────────────────────────────
+//
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::model::ReviewGrade;
+    use crate::time::fixed_now;
+
+    /// Cards answered on a doubling schedule, forgetting every `lapse_every`th review.
+    fn synthetic_history(cards: u64, reviews_per_card: u32, lapse_every: u32) -> Vec<ReviewLog> {
+        let start = fixed_now();
+        let mut logs = Vec::new();
+        for card in 1..=cards {
+            let mut at = start;
+            let mut gap = 1;
+            for review in 0..reviews_per_card {
+                let lapsed = review > 0 && (u64::from(review) + card) % u64::from(lapse_every) == 0;
+                let grade = if lapsed {
+                    ReviewGrade::Again
+                } else {
+                    ReviewGrade::Good
+                };
+                logs.push(ReviewLog::new(CardId::new(card), grade, at));
+                gap = if lapsed { 1 } else { gap * 2 };
+                at += Duration::days(gap);
+            }
+        }
+        logs
+    }
+
+    #[test]
+    fn training_items_replay_each_card_by_review_time() {
+        let now = fixed_now();
+        let logs = vec![
+            ReviewLog::new(CardId::new(1), ReviewGrade::Good, now + Duration::days(3)),
+            ReviewLog::new(CardId::new(2), ReviewGrade::Good, now),
+            ReviewLog::new(CardId::new(1), ReviewGrade::Again, now),
+            ReviewLog::new(CardId::new(1), ReviewGrade::Good, now + Duration::minutes(10)),
+        ];
+
+        let items = training_items(&logs);
+
+        assert_eq!(items.len(), 1);
+        let replayed: Vec<(u32, u32)> = items[0]
+            .reviews
+            .iter()
+            .map(|review| (review.rating, review.delta_t))
+            .collect();
+        assert_eq!(replayed, vec![(1, 0), (3, 0), (3, 3)]);
+    }
+
+    #[test]
+    fn optimize_parameters_rejects_history_without_spaced_reviews() {
+        let now = fixed_now();
+        let logs = vec![
+            ReviewLog::new(CardId::new(1), ReviewGrade::Good, now),
+            ReviewLog::new(CardId::new(1), ReviewGrade::Good, now + Duration::minutes(10)),
+        ];
+
+        assert_eq!(
+            optimize_parameters(&logs).unwrap_err(),
+            SchedulerError::NotEnoughHistory
+        );
+    }
+
+    #[test]
+    fn optimize_parameters_fits_synthetic_history() {
+        let logs = synthetic_history(40, 6, 4);
+
+        let fitted = optimize_parameters(&logs).unwrap();
+
+        assert_eq!(fitted.review_count, logs.len());
+        assert_eq!(fitted.parameters.len(), 21);
+        assert!(fitted.parameters.iter().all(|weight| weight.is_finite()));
+        assert_ne!(fitted.parameters, fsrs::DEFAULT_PARAMETERS.to_vec());
+        assert!(fitted.log_loss.is_finite() && fitted.log_loss > 0.0);
+        assert!(fitted.rmse_bins.is_finite() && fitted.rmse_bins >= 0.0);
+        assert!(Scheduler::try_with_parameters(0.9, &fitted.parameters).is_ok());
+    }
+}
@@ -17,6 +17,8 @@ pub enum SchedulerError {
     InvalidRetention { provided: f32 },
     #[error("elapsed days must be non-negative and finite, got {provided}")]
     InvalidElapsedDays { provided: f64 },
+    #[error("review history has no card reviewed on two different days")]
+    NotEnoughHistory,
 }
 
 //
@@ -151,6 +153,7 @@ impl ScheduledStates {
 pub struct Scheduler {
     fsrs: fsrs::FSRS,
     optimal_retention: f32,
+    parameters: Vec<f32>,
 }
 
 /// Outcome of applying a review: log entry, chosen schedule, and updated memory state.
@@ -161,6 +164,86 @@ pub struct AppliedReview {
     pub memory: MemoryState,
 }
 
+/// A step after FSRS that moved a card's interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalAdjustmentKind {
+    /// The card is still in its (re)learning steps, so the step delay replaced FSRS.
+    LearningStep,
+    /// The deck's minimum interval after a lapse.
+    LapseMinimum,
+    /// The deck's minimum or maximum interval.
+    Clamp,
+    /// Random spread so cards graded together do not come due together.
+    Fuzz,
+    /// A nudge toward a quieter day.
+    LoadBalance,
+}
+
+/// One adjustment to the interval, in days before and after it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntervalAdjustment {
+    pub kind: IntervalAdjustmentKind,
+    pub from_days: f64,
+    pub to_days: f64,
+}
+
+/// Why a review was scheduled the way it was.
+///
+/// Starts from the FSRS inputs and proposal with `new`; each later adjustment is added with
+/// `record`, so `scheduled_days` always matches the outcome it describes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewExplanation {
+    pub grade: ReviewGrade,
+    /// Memory state FSRS started from; `None` for a first review or a reset lapse.
+    pub previous: Option<MemoryState>,
+    pub elapsed_days: f64,
+    /// Predicted recall at the time of review; `None` without a previous state.
+    pub retrievability: Option<f64>,
+    /// Interval FSRS proposed before any adjustment.
+    pub fsrs_days: f64,
+    /// Adjustments that changed the interval, in the order they were applied.
+    pub adjustments: Vec<IntervalAdjustment>,
+    /// Interval the card was actually given.
+    pub scheduled_days: f64,
+}
+
+impl ReviewExplanation {
+    /// Explain `applied` as `scheduler.apply_review` returned it for these inputs.
+    #[must_use]
+    pub fn new(
+        scheduler: &Scheduler,
+        previous: Option<&MemoryState>,
+        grade: ReviewGrade,
+        elapsed_days: f64,
+        applied: &AppliedReview,
+    ) -> Self {
+        let scheduled_days = applied.outcome.scheduled_days;
+        Self {
+            grade,
+            previous: previous.cloned(),
+            elapsed_days,
+            retrievability: previous.map(|state| scheduler.retrievability(state, elapsed_days)),
+            fsrs_days: scheduled_days,
+            adjustments: Vec::new(),
+            scheduled_days,
+        }
+    }
+
+    /// Note that `kind` ran on `applied`; does nothing if it left the interval unchanged.
+    pub fn record(&mut self, kind: IntervalAdjustmentKind, applied: &AppliedReview) {
+        let to_days = applied.outcome.scheduled_days;
+        if (to_days - self.scheduled_days).abs() < f64::EPSILON {
+            return;
+        }
+        self.adjustments.push(IntervalAdjustment {
+            kind,
+            from_days: self.scheduled_days,
+            to_days,
+        });
+        self.scheduled_days = to_days;
+    }
+}
+
 impl Scheduler {
     /// Create scheduler with default parameters and 0.9 retention.
     ///
@@ -178,18 +261,32 @@ impl Scheduler {
     /// - `InvalidRetention` if `optimal_retention` is not in `(0, 1]`
     /// - `FsrsError` if FSRS initialization fails
     pub fn try_with_retention(optimal_retention: f32) -> Result<Self, SchedulerError> {
+        Self::try_with_parameters(optimal_retention, &[])
+    }
+
+    /// Create scheduler with custom retention and FSRS weights; empty weights use the defaults.
+    ///
+    /// # Errors
+    ///
+    /// - `InvalidRetention` if `optimal_retention` is not in `(0, 1]`
+    /// - `FsrsError` if FSRS rejects the weights
+    pub fn try_with_parameters(
+        optimal_retention: f32,
+        parameters: &[f32],
+    ) -> Result<Self, SchedulerError> {
         if !(0.0..=1.0).contains(&optimal_retention) || optimal_retention == 0.0 {
             return Err(SchedulerError::InvalidRetention {
                 provided: optimal_retention,
             });
         }
 
-        let fsrs = fsrs::FSRS::new(Some(&[]))
+        let fsrs = fsrs::FSRS::new(Some(parameters))
             .map_err(|e| SchedulerError::FsrsError(e.to_string()))?;
 
         Ok(Self {
             fsrs,
             optimal_retention,
+            parameters: parameters.to_vec(),
         })
     }
 
@@ -208,6 +305,12 @@ impl Scheduler {
         self.optimal_retention
     }
 
+    /// FSRS weights this scheduler was built with; empty means the FSRS defaults.
+    #[must_use]
+    pub fn parameters(&self) -> &[f32] {
+        &self.parameters
+    }
+
     /// Schedule a brand-new card (no previous state).
     ///
     /// Returns all four possible next states (again/hard/good/easy).
@@ -355,6 +458,40 @@ impl Scheduler {
         self.optimal_retention
     }
 
+    /// Predicted recall probability `elapsed_days` after the review that produced `state`.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn retrievability(&self, state: &MemoryState, elapsed_days: f64) -> f64 {
+        let fsrs_state = fsrs::MemoryState {
+            stability: state.stability as f32,
+            difficulty: state.difficulty as f32,
+        };
+        f64::from(fsrs::current_retrievability(
+            fsrs_state,
+            elapsed_days.max(0.0) as f32,
+            self.decay(),
+        ))
+    }
+
+    /// Forgetting-curve decay: the last FSRS-6 weight, or what FSRS assumes for shorter sets.
+    fn decay(&self) -> f32 {
+        match self.parameters.len() {
+            0 => fsrs::FSRS6_DEFAULT_DECAY,
+            21 => self.parameters[20],
+            _ => fsrs::FSRS5_DEFAULT_DECAY,
+        }
+    }
+
+    /// Days after a review until recall for `state` is predicted to fall to the target retention.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn interval_days(&self, state: &MemoryState) -> f64 {
+        f64::from(
+            self.fsrs
+                .next_interval(Some(state.stability as f32), self.optimal_retention, 3),
+        )
+    }
+
     /// Apply a user's review and return the selected schedule, memory update, and log entry.
     ///
     /// - For brand-new cards, pass `None` for `previous_state` (elapsed days ignored).
@@ -398,6 +535,16 @@ mod tests {
     use super::*;
     use crate::time::fixed_now;
 
+    #[test]
+    fn interval_days_matches_stability_at_ninety_percent_and_grows_below() {
+        let state = MemoryState::new(10.0, 5.0);
+        let at_ninety = Scheduler::new().unwrap().interval_days(&state);
+        assert!((at_ninety - 10.0).abs() < 0.01);
+
+        let at_eighty = Scheduler::try_with_retention(0.8).unwrap().interval_days(&state);
+        assert!(at_eighty > at_ninety);
+    }
+
     #[test]
     fn scheduler_default_retention() {
         let s = Scheduler::new().unwrap();
@@ -599,6 +746,94 @@ mod tests {
         }
     }
 
+    #[test]
+    fn retrievability_decays_to_target_at_scheduled_interval() {
+        let scheduler = Scheduler::with_retention(0.85).unwrap();
+        let now = fixed_now();
+        let states = scheduler.schedule_new_card(CardId::new(1), now).unwrap();
+        let memory = MemoryState::from_outcome(states.select(ReviewGrade::Good));
+        let states = scheduler.schedule_review(CardId::new(1), &memory, 3.0, now).unwrap();
+        let outcome = states.select(ReviewGrade::Good);
+        let memory = MemoryState::from_outcome(outcome);
+
+        assert!((scheduler.retrievability(&memory, 0.0) - 1.0).abs() < 1e-6);
+        let at_due = scheduler.retrievability(&memory, outcome.scheduled_days);
+        assert!((at_due - 0.85).abs() < 0.02, "retrievability at due {at_due}");
+        assert!(scheduler.retrievability(&memory, outcome.scheduled_days + 2.0) < at_due);
+    }
+
+    #[test]
+    fn retrievability_uses_the_decay_from_custom_weights() {
+        let mut weights = fsrs::DEFAULT_PARAMETERS.to_vec();
+        weights[20] = 0.5;
+        let custom = Scheduler::try_with_parameters(0.9, &weights).unwrap();
+        let default = Scheduler::new().unwrap();
+        let memory = MemoryState::new(10.0, 5.0);
+
+        // Every decay predicts 90% recall once `stability` days have passed.
+        assert!((custom.retrievability(&memory, 10.0) - 0.9).abs() < 1e-4);
+        let expected = fsrs::current_retrievability(
+            fsrs::MemoryState {
+                stability: 10.0,
+                difficulty: 5.0,
+            },
+            40.0,
+            0.5,
+        );
+        assert!((custom.retrievability(&memory, 40.0) - f64::from(expected)).abs() < 1e-6);
+        // A steeper decay forgets faster past the stability point than the FSRS-6 default.
+        assert!(custom.retrievability(&memory, 40.0) < default.retrievability(&memory, 40.0));
+    }
+
+    #[test]
+    fn explanation_of_good_on_a_young_card_matches_the_scheduling_math() {
+        let scheduler = Scheduler::new().unwrap();
+        let now = fixed_now();
+        let card_id = CardId::new(1);
+        let young = MemoryState::new(3.0, 5.0);
+        let elapsed = 3.0;
+
+        let applied = scheduler
+            .apply_review(card_id, Some(&young), ReviewGrade::Good, now, elapsed)
+            .unwrap();
+        let mut explanation = ReviewExplanation::new(
+            &scheduler,
+            Some(&young),
+            ReviewGrade::Good,
+            elapsed,
+            &applied,
+        );
+
+        assert_eq!(explanation.previous, Some(young.clone()));
+        assert_eq!(explanation.elapsed_days, elapsed);
+        let expected_recall = scheduler.retrievability(&young, elapsed);
+        assert_eq!(explanation.retrievability, Some(expected_recall));
+        assert!(expected_recall < 1.0 && expected_recall > 0.8, "{expected_recall}");
+        let good = scheduler
+            .schedule_review(card_id, &young, elapsed, now)
+            .unwrap()
+            .good;
+        assert_eq!(explanation.fsrs_days, good.scheduled_days);
+        assert_eq!(explanation.scheduled_days, good.scheduled_days);
+        assert!(explanation.adjustments.is_empty());
+
+        // Only adjustments that move the interval are kept.
+        explanation.record(IntervalAdjustmentKind::Fuzz, &applied);
+        assert!(explanation.adjustments.is_empty());
+        let mut clamped = applied.clone();
+        clamped.outcome.scheduled_days = 2.0;
+        explanation.record(IntervalAdjustmentKind::Clamp, &clamped);
+        assert_eq!(
+            explanation.adjustments,
+            [IntervalAdjustment {
+                kind: IntervalAdjustmentKind::Clamp,
+                from_days: good.scheduled_days,
+                to_days: 2.0,
+            }]
+        );
+        assert_eq!(explanation.scheduled_days, 2.0);
+    }
+
     #[test]
     fn select_picks_correct_outcome() {
         let s = Scheduler::new().unwrap();
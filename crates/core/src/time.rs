@@ -1,4 +1,4 @@
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc};
 
 /// A simple clock abstraction for deterministic time in services and tests.
 #[derive(Debug, Clone, Copy, Default)]
@@ -21,6 +21,19 @@ impl Clock {
         Self::Fixed(at)
     }
 
+    /// Returns fixed clocks starting at `start`, each one `step` later than the last.
+    ///
+    /// Handy in tests that need time to move between service calls without reading
+    /// the system time.
+    #[cfg(any(test, feature = "test-support"))]
+    #[must_use]
+    pub fn advancing(start: DateTime<Utc>, step: Duration) -> AdvancingClock {
+        AdvancingClock {
+            next: Some(start),
+            step,
+        }
+    }
+
     /// Returns the current time according to the clock.
     #[must_use]
     pub fn now(&self) -> DateTime<Utc> {
@@ -50,6 +63,109 @@ impl Clock {
     pub fn is_fixed(&self) -> bool {
         matches!(self, Clock::Fixed(_))
     }
+
+    /// The study day the clock's current time falls in.
+    #[must_use]
+    pub fn study_date(&self, day: StudyDay) -> NaiveDate {
+        day.date_of(self.now())
+    }
+}
+
+/// Fixed clocks that move forward by a constant step; see [`Clock::advancing`].
+#[cfg(any(test, feature = "test-support"))]
+#[derive(Debug, Clone, Copy)]
+pub struct AdvancingClock {
+    next: Option<DateTime<Utc>>,
+    step: Duration,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl Iterator for AdvancingClock {
+    type Item = Clock;
+
+    fn next(&mut self) -> Option<Clock> {
+        let at = self.next?;
+        self.next = at.checked_add_signed(self.step);
+        Some(Clock::Fixed(at))
+    }
+}
+
+/// Where one study day ends and the next begins.
+///
+/// A study day is a local calendar day that starts at `cutoff_hour` o'clock instead of
+/// midnight, so with a 4am cutoff a review at 2am still counts toward the day before.
+/// Local time is UTC until a UTC offset is set; services resolve the system offset and
+/// pass it in, so the calendar never depends on reading the time zone here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StudyDay {
+    utc_offset: FixedOffset,
+    cutoff_hour: u32,
+}
+
+impl Default for StudyDay {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl StudyDay {
+    /// Latest hour a study day may start at.
+    pub const MAX_CUTOFF_HOUR: u32 = 23;
+
+    /// Study days starting at `cutoff_hour` in UTC.
+    ///
+    /// Hours past [`Self::MAX_CUTOFF_HOUR`] are clamped to it.
+    #[must_use]
+    pub fn new(cutoff_hour: u32) -> Self {
+        Self {
+            utc_offset: Utc.fix(),
+            cutoff_hour: cutoff_hour.min(Self::MAX_CUTOFF_HOUR),
+        }
+    }
+
+    /// Use `offset` for local time.
+    #[must_use]
+    pub fn with_utc_offset(mut self, offset: FixedOffset) -> Self {
+        self.utc_offset = offset;
+        self
+    }
+
+    #[must_use]
+    pub fn cutoff_hour(&self) -> u32 {
+        self.cutoff_hour
+    }
+
+    #[must_use]
+    pub fn utc_offset(&self) -> FixedOffset {
+        self.utc_offset
+    }
+
+    /// The study day `at` falls in.
+    #[must_use]
+    pub fn date_of(&self, at: DateTime<Utc>) -> NaiveDate {
+        let local = at.with_timezone(&self.utc_offset).naive_local();
+        (local - Duration::hours(i64::from(self.cutoff_hour))).date()
+    }
+
+    /// When the study day `date` starts.
+    #[must_use]
+    pub fn start_of(&self, date: NaiveDate) -> DateTime<Utc> {
+        let start = date
+            .and_hms_opt(self.cutoff_hour, 0, 0)
+            .unwrap_or_else(|| NaiveDateTime::from(date));
+        self.utc_offset
+            .from_local_datetime(&start)
+            .single()
+            .map_or_else(|| start.and_utc(), |at| at.to_utc())
+    }
+
+    /// When the study day after the one containing `at` starts.
+    #[must_use]
+    pub fn next_start(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        self.date_of(at)
+            .succ_opt()
+            .map_or(at + Duration::days(1), |next| self.start_of(next))
+    }
 }
 
 /// Deterministic timestamp for tests and examples (2023-11-14T22:13:20Z).
@@ -71,3 +187,65 @@ pub fn fixed_now() -> DateTime<Utc> {
 pub fn fixed_clock() -> Clock {
     Clock::fixed(fixed_now())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        text.parse().unwrap()
+    }
+
+    fn date(text: &str) -> NaiveDate {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn review_before_cutoff_counts_toward_previous_study_day() {
+        let day = StudyDay::new(4).with_utc_offset(FixedOffset::east_opt(0).unwrap());
+
+        assert_eq!(day.date_of(utc("2024-03-06T02:00:00Z")), date("2024-03-05"));
+        assert_eq!(day.date_of(utc("2024-03-06T03:59:59Z")), date("2024-03-05"));
+        assert_eq!(day.date_of(utc("2024-03-06T04:00:00Z")), date("2024-03-06"));
+        assert_eq!(day.next_start(utc("2024-03-06T02:00:00Z")), utc("2024-03-06T04:00:00Z"));
+        assert_eq!(Clock::fixed(utc("2024-03-06T02:00:00Z")).study_date(day), date("2024-03-05"));
+    }
+
+    #[test]
+    fn study_day_follows_the_local_offset() {
+        // 23:30 UTC is 01:30 the next morning at UTC+2, still before a 4am cutoff.
+        let day = StudyDay::new(4).with_utc_offset(FixedOffset::east_opt(2 * 3600).unwrap());
+
+        assert_eq!(day.date_of(utc("2024-03-05T23:30:00Z")), date("2024-03-05"));
+        assert_eq!(day.date_of(utc("2024-03-06T02:00:00Z")), date("2024-03-06"));
+        assert_eq!(day.start_of(date("2024-03-06")), utc("2024-03-06T02:00:00Z"));
+    }
+
+    #[test]
+    fn advancing_clock_steps_forward_from_its_start() {
+        let start = utc("2024-03-06T02:00:00Z");
+        let mut clocks = Clock::advancing(start, Duration::minutes(10));
+
+        assert_eq!(clocks.next().map(|clock| clock.now()), Some(start));
+        assert_eq!(clocks.next().map(|clock| clock.now()), Some(utc("2024-03-06T02:10:00Z")));
+        let mut third = clocks.next().unwrap();
+        assert!(third.is_fixed());
+        third.advance(Duration::minutes(5));
+        assert_eq!(third.now(), utc("2024-03-06T02:25:00Z"));
+    }
+
+    #[test]
+    fn cutoff_hour_is_clamped_to_the_last_hour_of_the_day() {
+        assert_eq!(StudyDay::new(30).cutoff_hour(), StudyDay::MAX_CUTOFF_HOUR);
+        assert_eq!(StudyDay::default().cutoff_hour(), 0);
+    }
+
+    #[test]
+    fn study_days_are_utc_until_an_offset_is_set() {
+        let day = StudyDay::new(4);
+
+        assert_eq!(day.utc_offset(), Utc.fix());
+        assert_eq!(day.date_of(utc("2024-03-06T03:59:59Z")), date("2024-03-05"));
+        assert_eq!(day.start_of(date("2024-03-06")), utc("2024-03-06T04:00:00Z"));
+    }
+}
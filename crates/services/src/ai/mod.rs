@@ -1,5 +1,9 @@
 pub mod pricing;
 pub mod usage;
+pub mod writing;
 
-pub use pricing::PriceBook;
-pub use usage::{AiUsageHandle, AiUsageService};
+pub use pricing::{ModelRate, PriceBook, PriceRates};
+pub use usage::{
+    AiCostEstimate, AiUsageHandle, AiUsagePermit, AiUsageRange, AiUsageService, UnpricedUsage,
+};
+pub use writing::AiWritingService;
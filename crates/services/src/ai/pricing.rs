@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use storage::repository::{AiPriceBookEntry, AiPriceBookRepository, StorageError};
@@ -35,6 +36,38 @@ impl PriceBook {
         self.repo.list_entries().await
     }
 
+    /// Whether any provider's entry prices `model`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on persistence failures.
+    pub async fn is_known(&self, model: &str) -> Result<bool, StorageError> {
+        let entries = self.repo.list_entries().await?;
+        Ok(entries.iter().any(|entry| entry.model == model))
+    }
+
+    /// Load every entry into a lookup table for bulk estimates.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on persistence failures.
+    pub async fn load_rates(&self) -> Result<PriceRates, StorageError> {
+        let rates = self
+            .repo
+            .list_entries()
+            .await?
+            .into_iter()
+            .map(|entry| {
+                let rate = ModelRate {
+                    input_micro_usd_per_million: entry.input_micro_usd_per_million,
+                    output_micro_usd_per_million: entry.output_micro_usd_per_million,
+                };
+                ((entry.provider, entry.model), rate)
+            })
+            .collect();
+        Ok(PriceRates { rates })
+    }
+
     /// Estimate the cost in micro-USD for the given token usage.
     ///
     /// # Errors
@@ -60,6 +93,50 @@ impl PriceBook {
     }
 }
 
+/// Input and output token prices for one model.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModelRate {
+    pub input_micro_usd_per_million: u64,
+    pub output_micro_usd_per_million: u64,
+}
+
+impl ModelRate {
+    /// One price for input and output tokens alike, given per 1,000 tokens.
+    #[must_use]
+    pub fn flat_per_1k(micro_usd_per_1k: u64) -> Self {
+        let per_million = micro_usd_per_1k.saturating_mul(1_000);
+        Self {
+            input_micro_usd_per_million: per_million,
+            output_micro_usd_per_million: per_million,
+        }
+    }
+
+    #[must_use]
+    pub fn cost_micro_usd(self, prompt_tokens: u32, completion_tokens: u32) -> u64 {
+        estimate_cost_micro_usd(
+            self.input_micro_usd_per_million,
+            self.output_micro_usd_per_million,
+            prompt_tokens,
+            completion_tokens,
+        )
+    }
+}
+
+/// Snapshot of the price book keyed by provider and model.
+#[derive(Clone, Debug, Default)]
+pub struct PriceRates {
+    rates: HashMap<(String, String), ModelRate>,
+}
+
+impl PriceRates {
+    #[must_use]
+    pub fn get(&self, provider: &str, model: &str) -> Option<ModelRate> {
+        self.rates
+            .get(&(provider.to_string(), model.to_string()))
+            .copied()
+    }
+}
+
 fn estimate_cost_micro_usd(
     input_micro_usd_per_million: u64,
     output_micro_usd_per_million: u64,
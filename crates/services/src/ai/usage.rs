@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
-use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, Months, NaiveTime, Utc};
 
-use crate::ai::pricing::PriceBook;
+use crate::ai::pricing::{ModelRate, PriceBook};
 use crate::error::AiUsageError;
 use crate::Clock;
 use storage::repository::{
@@ -18,9 +18,51 @@ pub struct AiUsageHandle {
     pub started_at: DateTime<Utc>,
 }
 
+/// Proof that another request fits within the daily cap and cooldown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AiUsagePermit {
+    /// Requests left today, including the one this permit allows.
+    pub remaining_today: u32,
+}
+
+/// Period covered by a cost estimate, in the local time zone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AiUsageRange {
+    Today,
+    ThisMonth,
+}
+
+/// Token usage for a model that has no price book entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnpricedUsage {
+    pub provider: String,
+    pub model: String,
+    pub requests: u32,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+/// Estimated spend for completed requests in a range.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AiCostEstimate {
+    pub total_micro_usd: u64,
+    pub priced_requests: u32,
+    /// Usage that could not be priced; it is not included in the total.
+    pub unpriced: Vec<UnpricedUsage>,
+}
+
+impl AiCostEstimate {
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn total_usd(&self) -> f64 {
+        self.total_micro_usd as f64 / 1_000_000.0
+    }
+}
+
 #[derive(Clone)]
 pub struct AiUsageService {
     clock: Clock,
+    utc_offset: Option<FixedOffset>,
     settings_repo: Arc<dyn AppSettingsRepository>,
     usage_repo: Arc<dyn AiUsageRepository>,
     price_book: PriceBook,
@@ -36,12 +78,107 @@ impl AiUsageService {
     ) -> Self {
         Self {
             clock,
+            utc_offset: None,
             settings_repo,
             usage_repo,
             price_book: PriceBook::new(price_repo),
         }
     }
 
+    #[must_use]
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Use a fixed offset for the daily reset instead of the system time zone.
+    #[must_use]
+    pub fn with_utc_offset(mut self, offset: FixedOffset) -> Self {
+        self.utc_offset = Some(offset);
+        self
+    }
+
+    /// Check whether another request is allowed right now.
+    ///
+    /// The daily count resets at local midnight. Counts come from the persisted
+    /// usage records, so restarting the app does not reset the cap.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AiUsageError::RateLimited` with the wait until the next allowed
+    /// request, or `AiUsageError::Storage` on persistence failures.
+    pub async fn try_acquire(&self) -> Result<AiUsagePermit, AiUsageError> {
+        self.check_limits(self.clock.now(), true).await
+    }
+
+    /// Estimate spend for `range` from recorded token usage and the current price book.
+    ///
+    /// Usage of the preferred model falls back to the manual price in settings when the
+    /// price book has no entry for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AiUsageError::Storage` on persistence failures.
+    pub async fn estimated_cost(&self, range: AiUsageRange) -> Result<AiCostEstimate, AiUsageError> {
+        let now = self.clock.now();
+        let offset = self.local_offset(now);
+        let day_start = start_of_local_day(now, offset);
+        let start = match range {
+            AiUsageRange::Today => day_start,
+            AiUsageRange::ThisMonth => start_of_local_month(now, offset),
+        };
+        let end = match range {
+            AiUsageRange::Today => day_start + Duration::days(1),
+            AiUsageRange::ThisMonth => start_of_next_local_month(now, offset),
+        };
+
+        let settings = self
+            .settings_repo
+            .get_settings()
+            .await?
+            .unwrap_or_default();
+        let manual_rate = settings
+            .ai_manual_price_micro_usd_per_1k()
+            .map(ModelRate::flat_per_1k);
+        let rates = self.price_book.load_rates().await?;
+        let records = self.usage_repo.list_between(start, end).await?;
+        let mut estimate = AiCostEstimate::default();
+        for record in records {
+            let (Some(prompt), Some(completion)) = (record.prompt_tokens, record.completion_tokens)
+            else {
+                continue;
+            };
+            let rate = rates.get(&record.provider, &record.model).or_else(|| {
+                manual_rate.filter(|_| settings.api_model() == Some(record.model.as_str()))
+            });
+            if let Some(rate) = rate {
+                estimate.total_micro_usd = estimate
+                    .total_micro_usd
+                    .saturating_add(rate.cost_micro_usd(prompt, completion));
+                estimate.priced_requests += 1;
+                continue;
+            }
+            if let Some(item) = estimate
+                .unpriced
+                .iter_mut()
+                .find(|item| item.provider == record.provider && item.model == record.model)
+            {
+                item.requests += 1;
+                item.prompt_tokens += u64::from(prompt);
+                item.completion_tokens += u64::from(completion);
+            } else {
+                estimate.unpriced.push(UnpricedUsage {
+                    provider: record.provider,
+                    model: record.model,
+                    requests: 1,
+                    prompt_tokens: u64::from(prompt),
+                    completion_tokens: u64::from(completion),
+                });
+            }
+        }
+        Ok(estimate)
+    }
+
     /// Start a request after enforcing cooldown, daily cap, and monthly budget.
     ///
     /// # Errors
@@ -52,29 +189,31 @@ impl AiUsageService {
         provider: &str,
         model: &str,
     ) -> Result<AiUsageHandle, AiUsageError> {
-        let settings = self
-            .settings_repo
-            .get_settings()
-            .await?
-            .unwrap_or_default();
-        let now = self.clock.now();
+        self.start(provider, model, true).await
+    }
 
-        let daily_start = start_of_day(now);
-        let requests_today = self.usage_repo.count_since(daily_start).await?;
-        if requests_today >= settings.ai_daily_request_cap() {
-            return Err(AiUsageError::DailyCapReached {
-                cap: settings.ai_daily_request_cap(),
-            });
-        }
+    /// Start a retry of a request that already passed the limits, e.g. on the
+    /// fallback model. Only the daily cap applies; the cooldown does not.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AiUsageError` if the daily cap is reached or persistence fails.
+    pub async fn start_fallback_request(
+        &self,
+        provider: &str,
+        model: &str,
+    ) -> Result<AiUsageHandle, AiUsageError> {
+        self.start(provider, model, false).await
+    }
 
-        if let Some(last_request_at) = self.usage_repo.last_request_at().await? {
-            let cooldown = Duration::seconds(i64::from(settings.ai_cooldown_secs()));
-            if last_request_at + cooldown > now {
-                let remaining_secs = (last_request_at + cooldown - now).num_seconds().max(0);
-                let remaining = u32::try_from(remaining_secs).unwrap_or(u32::MAX);
-                return Err(AiUsageError::CooldownActive { remaining_secs: remaining });
-            }
-        }
+    async fn start(
+        &self,
+        provider: &str,
+        model: &str,
+        enforce_cooldown: bool,
+    ) -> Result<AiUsageHandle, AiUsageError> {
+        let now = self.clock.now();
+        self.check_limits(now, enforce_cooldown).await?;
 
         let id = self
             .usage_repo
@@ -93,6 +232,44 @@ impl AiUsageService {
         })
     }
 
+    fn local_offset(&self, now: DateTime<Utc>) -> FixedOffset {
+        self.utc_offset
+            .unwrap_or_else(|| *now.with_timezone(&Local).offset())
+    }
+
+    async fn check_limits(
+        &self,
+        now: DateTime<Utc>,
+        enforce_cooldown: bool,
+    ) -> Result<AiUsagePermit, AiUsageError> {
+        let settings = self
+            .settings_repo
+            .get_settings()
+            .await?
+            .unwrap_or_default();
+
+        let offset = self.local_offset(now);
+        let daily_start = start_of_local_day(now, offset);
+        let requests_today = self.usage_repo.count_since(daily_start).await?;
+        let cap = settings.ai_daily_request_cap();
+        if requests_today >= cap {
+            return Err(rate_limited(daily_start + Duration::days(1) - now));
+        }
+
+        if enforce_cooldown
+            && let Some(last_request_at) = self.usage_repo.last_request_at().await?
+        {
+            let cooldown = Duration::seconds(i64::from(settings.ai_cooldown_secs()));
+            if last_request_at + cooldown > now {
+                return Err(rate_limited(last_request_at + cooldown - now));
+            }
+        }
+
+        Ok(AiUsagePermit {
+            remaining_today: cap - requests_today,
+        })
+    }
+
     /// Record a successful request and compute the cost.
     ///
     /// # Errors
@@ -115,14 +292,15 @@ impl AiUsageService {
             )
             .await?
         else {
+            // Keep the token counts so the spend estimate can report the model as unpriced.
             self.usage_repo
                 .update_completion(
                     handle.id,
                     AiUsageCompletion {
-                        status: AiUsageStatus::Failed,
-                        prompt_tokens: None,
-                        completion_tokens: None,
-                        total_tokens: None,
+                        status: AiUsageStatus::Succeeded,
+                        prompt_tokens: Some(prompt_tokens),
+                        completion_tokens: Some(completion_tokens),
+                        total_tokens: Some(total_tokens),
                         cost_micro_usd: None,
                     },
                 )
@@ -170,6 +348,16 @@ impl AiUsageService {
         Ok(())
     }
 
+    /// Whether the price book lists `model`, so its usage can be costed without a manual
+    /// price.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AiUsageError` on persistence failures.
+    pub async fn is_model_priced(&self, model: &str) -> Result<bool, AiUsageError> {
+        Ok(self.price_book.is_known(model).await?)
+    }
+
     /// List the current price book entries.
     ///
     /// # Errors
@@ -180,9 +368,196 @@ impl AiUsageService {
     }
 }
 
-fn start_of_day(now: DateTime<Utc>) -> DateTime<Utc> {
-    let date = now.date_naive();
-    Utc.with_ymd_and_hms(date.year(), date.month(), date.day(), 0, 0, 0)
+fn start_of_local_day(now: DateTime<Utc>, offset: FixedOffset) -> DateTime<Utc> {
+    local_midnight(now, offset, now.with_timezone(&offset).date_naive())
+}
+
+fn start_of_local_month(now: DateTime<Utc>, offset: FixedOffset) -> DateTime<Utc> {
+    let date = now.with_timezone(&offset).date_naive();
+    local_midnight(now, offset, date.with_day(1).unwrap_or(date))
+}
+
+fn start_of_next_local_month(now: DateTime<Utc>, offset: FixedOffset) -> DateTime<Utc> {
+    let date = now.with_timezone(&offset).date_naive();
+    let next = date
+        .with_day(1)
+        .and_then(|first| first.checked_add_months(Months::new(1)))
+        .unwrap_or(date);
+    local_midnight(now, offset, next)
+}
+
+fn local_midnight(
+    now: DateTime<Utc>,
+    offset: FixedOffset,
+    date: chrono::NaiveDate,
+) -> DateTime<Utc> {
+    date.and_time(NaiveTime::MIN)
+        .and_local_timezone(offset)
         .single()
-        .unwrap_or(now)
+        .map_or(now, |start| start.with_timezone(&Utc))
+}
+
+fn rate_limited(wait: Duration) -> AiUsageError {
+    let secs = wait.num_seconds().max(0);
+    AiUsageError::RateLimited {
+        retry_after_secs: u32::try_from(secs).unwrap_or(u32::MAX),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use learn_core::model::AppSettingsDraft;
+    use storage::repository::Storage;
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    async fn build_service(storage: &Storage, cap: u32, cooldown_secs: u32) -> AiUsageService {
+        let settings = AppSettingsDraft {
+            ai_daily_request_cap: Some(cap),
+            ai_cooldown_secs: Some(cooldown_secs),
+            ..AppSettingsDraft::default()
+        }
+        .validate()
+        .unwrap();
+        storage.app_settings.save_settings(&settings).await.unwrap();
+        AiUsageService::new(
+            Clock::default(),
+            Arc::clone(&storage.app_settings),
+            Arc::clone(&storage.ai_usage),
+            Arc::clone(&storage.ai_price_book),
+        )
+        .with_utc_offset(FixedOffset::east_opt(2 * 3600).unwrap())
+    }
+
+    async fn record(service: &AiUsageService, model: &str, prompt: u32, completion: u32) {
+        let handle = service.start_fallback_request("openai", model).await.unwrap();
+        let _ = service
+            .finish_success(&handle, prompt, completion, prompt + completion)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn estimated_cost_splits_priced_and_unpriced_models() {
+        let storage = Storage::in_memory();
+        let yesterday = Clock::fixed(at("2024-05-14T12:00:00Z"));
+        let today = Clock::fixed(at("2024-05-15T12:00:00Z"));
+        let service = build_service(&storage, 100, 1).await;
+
+        // gpt-4.1-mini: 1M input tokens at $3 per million.
+        record(&service.clone().with_clock(yesterday), "gpt-4.1-mini", 1_000_000, 0).await;
+        // gpt-4.1: 100k input at $10/M plus 10k output at $30/M.
+        record(&service.clone().with_clock(today), "gpt-4.1", 100_000, 10_000).await;
+        record(&service.clone().with_clock(today), "local-llama", 500, 50).await;
+        record(&service.clone().with_clock(today), "local-llama", 300, 30).await;
+
+        let service = service.with_clock(today);
+        let daily = service.estimated_cost(AiUsageRange::Today).await.unwrap();
+        assert_eq!(daily.total_micro_usd, 1_300_000);
+        assert_eq!(daily.priced_requests, 1);
+        assert_eq!(
+            daily.unpriced,
+            [UnpricedUsage {
+                provider: "openai".into(),
+                model: "local-llama".into(),
+                requests: 2,
+                prompt_tokens: 800,
+                completion_tokens: 80,
+            }]
+        );
+
+        let monthly = service.estimated_cost(AiUsageRange::ThisMonth).await.unwrap();
+        assert_eq!(monthly.total_micro_usd, 4_300_000);
+        assert_eq!(monthly.priced_requests, 2);
+        assert!((monthly.total_usd() - 4.3).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn price_book_knows_listed_models_only() {
+        let storage = Storage::in_memory();
+        let service = build_service(&storage, 100, 1).await;
+
+        assert!(service.is_model_priced("gpt-4.1-mini").await.unwrap());
+        assert!(!service.is_model_priced("local-llama").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn manual_price_costs_an_unknown_preferred_model() {
+        let storage = Storage::in_memory();
+        let today = Clock::fixed(at("2024-05-15T12:00:00Z"));
+        let service = build_service(&storage, 100, 1).await.with_clock(today);
+        record(&service, "local-llama", 500, 50).await;
+
+        let mut draft = storage.app_settings.get_settings().await.unwrap().unwrap().to_draft();
+        draft.api_model = Some("local-llama".into());
+        storage.app_settings.save_settings(&draft.clone().validate().unwrap()).await.unwrap();
+        let unpriced = service.estimated_cost(AiUsageRange::Today).await.unwrap();
+        assert_eq!(unpriced.total_micro_usd, 0);
+        assert_eq!(unpriced.unpriced.len(), 1);
+
+        // $2 per 1k tokens: 550 tokens cost $1.10.
+        draft.ai_manual_price_micro_usd_per_1k = Some(2_000_000);
+        storage.app_settings.save_settings(&draft.validate().unwrap()).await.unwrap();
+        let priced = service.estimated_cost(AiUsageRange::Today).await.unwrap();
+        assert_eq!(priced.total_micro_usd, 1_100_000);
+        assert_eq!(priced.priced_requests, 1);
+        assert!(priced.unpriced.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cooldown_blocks_until_it_elapses() {
+        let storage = Storage::in_memory();
+        let mut clock = Clock::fixed(at("2024-05-01T08:00:00Z"));
+        let service = build_service(&storage, 10, 30).await.with_clock(clock);
+        service.start_request("openai", "gpt-4.1-mini").await.unwrap();
+
+        clock.advance(Duration::seconds(10));
+        let service = service.with_clock(clock);
+        let err = service.try_acquire().await.unwrap_err();
+        assert!(matches!(
+            err,
+            AiUsageError::RateLimited {
+                retry_after_secs: 20
+            }
+        ));
+
+        clock.advance(Duration::seconds(20));
+        let permit = service.with_clock(clock).try_acquire().await.unwrap();
+        assert_eq!(permit.remaining_today, 9);
+    }
+
+    #[tokio::test]
+    async fn daily_cap_resets_at_local_midnight_and_survives_restart() {
+        let storage = Storage::in_memory();
+        // 23:00 local time at UTC+2.
+        let mut clock = Clock::fixed(at("2024-05-01T21:00:00Z"));
+        let service = build_service(&storage, 2, 1).await.with_clock(clock);
+        service.start_request("openai", "gpt-4.1-mini").await.unwrap();
+        clock.advance(Duration::minutes(5));
+        service
+            .with_clock(clock)
+            .start_request("openai", "gpt-4.1-mini")
+            .await
+            .unwrap();
+
+        // A fresh service over the same storage still sees today's requests.
+        clock.advance(Duration::minutes(25));
+        let restarted = build_service(&storage, 2, 1).await.with_clock(clock);
+        let err = restarted.try_acquire().await.unwrap_err();
+        assert!(matches!(
+            err,
+            AiUsageError::RateLimited {
+                retry_after_secs: 1800
+            }
+        ));
+
+        clock.advance(Duration::minutes(30));
+        let permit = restarted.with_clock(clock).try_acquire().await.unwrap();
+        assert_eq!(permit.remaining_today, 2);
+    }
 }
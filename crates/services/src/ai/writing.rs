@@ -0,0 +1,267 @@
+use std::sync::Arc;
+
+use crate::error::{AiUsageError, AiWritingError, WritingToolsError};
+use crate::writing_tools_service::{WritingToolsOutput, WritingToolsService};
+
+/// Card-content suggestions on top of the configured writing-tool provider.
+///
+/// Usage is recorded through `AiUsageService`; the daily cap and cooldown
+/// surface as `AiWritingError::RateLimited`.
+#[derive(Clone)]
+pub struct AiWritingService {
+    tools: Arc<WritingToolsService>,
+}
+
+impl AiWritingService {
+    #[must_use]
+    pub fn new(tools: Arc<WritingToolsService>) -> Self {
+        Self { tools }
+    }
+
+    /// Rewrite `text` following `instruction`, e.g. "make it shorter".
+    ///
+    /// # Errors
+    ///
+    /// Returns `AiWritingError::RateLimited` when a usage limit applies and
+    /// `AiWritingError::Tools` for configuration, transport, or response failures.
+    pub async fn improve_text(
+        &self,
+        instruction: &str,
+        text: &str,
+    ) -> Result<WritingToolsOutput, AiWritingError> {
+        let prompt = build_prompt(
+            "Improve the text for a flashcard.\nKeep the meaning and facts unchanged.\n\n\
+             Output JSON:\n- title: \"Improved\"\n- result: the improved text\n- notes: \"\"",
+            "Improve",
+            instruction,
+            text,
+        );
+        self.run(&prompt).await
+    }
+
+    /// Suggest an example sentence that uses `word`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AiWritingError::RateLimited` when a usage limit applies and
+    /// `AiWritingError::Tools` for configuration, transport, or response failures.
+    pub async fn generate_example(&self, word: &str) -> Result<WritingToolsOutput, AiWritingError> {
+        let prompt = build_prompt(
+            "Write one short, natural example sentence that uses the word.\n\
+             Keep it under 20 words.\n\n\
+             Output JSON:\n- title: \"Example\"\n- result: the example sentence\n- notes: \"\"",
+            "Example",
+            "",
+            word,
+        );
+        self.run(&prompt).await
+    }
+
+    async fn run(&self, prompt: &str) -> Result<WritingToolsOutput, AiWritingError> {
+        self.tools.generate(prompt).await.map_err(|err| match err {
+            WritingToolsError::Usage(AiUsageError::RateLimited { retry_after_secs }) => {
+                AiWritingError::RateLimited { retry_after_secs }
+            }
+            err => AiWritingError::Tools(err),
+        })
+    }
+}
+
+fn build_prompt(action_prompt: &str, action_label: &str, constraints: &str, text: &str) -> String {
+    let mut prompt = String::new();
+    prompt.push_str(action_prompt);
+    prompt.push_str(
+        "\n\nReturn only a JSON object with keys: result, title, notes. Do not use code fences.",
+    );
+    prompt.push_str("\nACTION: ");
+    prompt.push_str(action_label);
+    prompt.push_str("\nCONSTRAINTS: ");
+    prompt.push_str(constraints.trim());
+    prompt.push_str("\nTEXT:\n<<<\n");
+    prompt.push_str(text.trim());
+    prompt.push_str("\n>>>");
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use learn_core::model::AppSettingsDraft;
+    use learn_core::time::fixed_now;
+    use reqwest::StatusCode;
+    use storage::repository::Storage;
+
+    use crate::Clock;
+    use crate::ai::AiUsageService;
+    use crate::error::WritingToolsErrorKind;
+    use crate::writing_tools_service::{
+        ChatCompletion, ChatCompletionClient, ChatMessage, ChatUsage, RetryPolicy,
+        WritingToolsConfig,
+    };
+
+    #[derive(Default)]
+    struct FakeClient {
+        failing_model: Option<&'static str>,
+        /// Requests that fail with a transient error before any succeeds.
+        transient_failures: Mutex<Vec<WritingToolsError>>,
+        models: Mutex<Vec<String>>,
+    }
+
+    impl FakeClient {
+        fn failing_first(failures: Vec<WritingToolsError>) -> Self {
+            Self {
+                transient_failures: Mutex::new(failures),
+                ..Self::default()
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ChatCompletionClient for FakeClient {
+        async fn complete(
+            &self,
+            _config: &WritingToolsConfig,
+            model: &str,
+            _messages: Vec<ChatMessage>,
+        ) -> Result<ChatCompletion, WritingToolsError> {
+            self.models.lock().unwrap().push(model.to_string());
+            if let Some(err) = self.transient_failures.lock().unwrap().pop() {
+                return Err(err);
+            }
+            if self.failing_model == Some(model) {
+                return Err(WritingToolsError::HttpStatus(StatusCode::NOT_FOUND));
+            }
+            Ok(ChatCompletion {
+                content: Some(r#"{"result":"Der Hund schläft.","title":"Example"}"#.into()),
+                usage: Some(ChatUsage {
+                    prompt: 10,
+                    completion: 5,
+                    total: 15,
+                }),
+            })
+        }
+    }
+
+    async fn build_service(
+        storage: &Storage,
+        client: Arc<FakeClient>,
+        cooldown_secs: u32,
+    ) -> AiWritingService {
+        let settings = AppSettingsDraft {
+            api_key: Some("test-key".into()),
+            api_model: Some("gpt-4.1".into()),
+            api_fallback_model: Some("gpt-4.1-mini".into()),
+            ai_cooldown_secs: Some(cooldown_secs),
+            ..AppSettingsDraft::default()
+        }
+        .validate()
+        .unwrap();
+        storage.app_settings.save_settings(&settings).await.unwrap();
+
+        let usage = Arc::new(AiUsageService::new(
+            Clock::Fixed(fixed_now()),
+            Arc::clone(&storage.app_settings),
+            Arc::clone(&storage.ai_usage),
+            Arc::clone(&storage.ai_price_book),
+        ));
+        let tools = WritingToolsService::new(Arc::clone(&storage.app_settings), None, usage)
+            .with_client(client)
+            .with_retry_policy(RetryPolicy {
+                max_retries: 2,
+                initial_backoff: std::time::Duration::ZERO,
+                max_backoff: std::time::Duration::ZERO,
+            });
+        AiWritingService::new(Arc::new(tools))
+    }
+
+    #[tokio::test]
+    async fn falls_back_on_model_error_and_records_usage() {
+        let storage = Storage::in_memory();
+        let client = Arc::new(FakeClient {
+            failing_model: Some("gpt-4.1"),
+            ..FakeClient::default()
+        });
+        let service = build_service(&storage, Arc::clone(&client), 60).await;
+
+        let output = service.generate_example("Hund").await.unwrap();
+
+        assert_eq!(output.result, "Der Hund schläft.");
+        assert_eq!(*client.models.lock().unwrap(), ["gpt-4.1", "gpt-4.1-mini"]);
+        let since = fixed_now() - chrono::Duration::days(1);
+        assert_eq!(storage.ai_usage.count_since(since).await.unwrap(), 2);
+        assert!(storage.ai_usage.sum_cost_since(since).await.unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn cooldown_returns_rate_limited() {
+        let storage = Storage::in_memory();
+        let service = build_service(&storage, Arc::new(FakeClient::default()), 30).await;
+
+        service.improve_text("shorter", "The dog is sleeping.").await.unwrap();
+        let err = service
+            .improve_text("shorter", "The dog is sleeping.")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            AiWritingError::RateLimited {
+                retry_after_secs: 30
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failures_within_one_request() {
+        let storage = Storage::in_memory();
+        let client = Arc::new(FakeClient::failing_first(vec![
+            WritingToolsError::HttpStatus(StatusCode::BAD_GATEWAY),
+            WritingToolsError::Timeout,
+        ]));
+        let service = build_service(&storage, Arc::clone(&client), 60).await;
+
+        let output = service.generate_example("Hund").await.unwrap();
+
+        assert_eq!(output.result, "Der Hund schläft.");
+        assert_eq!(*client.models.lock().unwrap(), ["gpt-4.1", "gpt-4.1", "gpt-4.1"]);
+        // Retries share the request's usage record, so the cooldown sees one request.
+        let since = fixed_now() - chrono::Duration::days(1);
+        assert_eq!(storage.ai_usage.count_since(since).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries_and_reports_offline() {
+        let storage = Storage::in_memory();
+        let client = Arc::new(FakeClient::failing_first(
+            (0..3).map(|_| WritingToolsError::Offline).collect(),
+        ));
+        let service = build_service(&storage, Arc::clone(&client), 60).await;
+
+        let err = service.generate_example("Hund").await.unwrap_err();
+
+        assert_eq!(err.kind(), WritingToolsErrorKind::Offline);
+        assert_eq!(client.models.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn classifies_errors_and_doubles_backoff_up_to_the_cap() {
+        let kind = |status| WritingToolsError::HttpStatus(status).kind();
+        assert_eq!(kind(StatusCode::UNAUTHORIZED), WritingToolsErrorKind::Auth);
+        assert_eq!(kind(StatusCode::TOO_MANY_REQUESTS), WritingToolsErrorKind::RateLimited);
+        assert_eq!(kind(StatusCode::SERVICE_UNAVAILABLE), WritingToolsErrorKind::Retryable);
+        assert_eq!(kind(StatusCode::BAD_REQUEST), WritingToolsErrorKind::Other);
+        assert!(!WritingToolsError::HttpStatus(StatusCode::TOO_MANY_REQUESTS).is_transient());
+
+        let policy = RetryPolicy {
+            max_retries: 5,
+            initial_backoff: std::time::Duration::from_millis(100),
+            max_backoff: std::time::Duration::from_millis(350),
+        };
+        let delays: Vec<_> = (0..4).map(|retry| policy.backoff(retry).as_millis()).collect();
+        assert_eq!(delays, [100, 200, 350, 350]);
+    }
+}
@@ -0,0 +1,531 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Cursor, Read};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use learn_core::Clock;
+use learn_core::model::{
+    CardKind, CardPhase, CardSource, ContentDraft, DeckId, DeckSettings, MediaId, expand_cloze,
+};
+use storage::repository::{CardRepository, NewCardRecord};
+use storage::sqlite::anki::{AnkiCardRow, AnkiCollection, AnkiNoteRow, read_anki_collection};
+use zip::ZipArchive;
+
+use crate::deck_service::DeckService;
+use crate::error::{AnkiImportError, MediaServiceError};
+use crate::media_service::MediaService;
+
+/// Collection entries in the order they are tried; `.anki21` is newer than `.anki2`.
+const COLLECTION_ENTRIES: [&str; 2] = ["collection.anki21", "collection.anki2"];
+/// Entry written by Anki 2.1.50+ when "support older Anki versions" is off.
+const COMPRESSED_COLLECTION_ENTRY: &str = "collection.anki21b";
+/// JSON map from numbered archive entries to the media file names notes refer to.
+const MEDIA_MANIFEST_ENTRY: &str = "media";
+/// Anki separates a note's field values with the unit separator.
+const FIELD_SEPARATOR: char = '\x1f';
+/// Anki card type for cards in the review queue.
+const ANKI_REVIEW: i64 = 2;
+/// Anki note type kind for cloze notes.
+const ANKI_CLOZE_MODEL: u8 = 1;
+
+/// Keeps concurrent imports in one process from sharing a temporary file.
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Counts reported after an Anki import.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AnkiImportReport {
+    /// Decks created, one per Anki deck that had cards to import.
+    pub decks: Vec<DeckId>,
+    pub imported: u32,
+    /// Imported cards that kept their Anki review schedule; the rest start as new.
+    pub scheduled: u32,
+    /// Cards whose note type or content could not be mapped.
+    pub skipped: u32,
+    /// Distinct images stored.
+    pub media_imported: u32,
+    /// Sound and image references left out: all audio, images beyond the first on a side,
+    /// and images that are missing or in an unsupported format.
+    pub media_skipped: u32,
+}
+
+/// Imports Anki `.apkg` packages as new decks.
+///
+/// Basic, reversed, and other standard note types become basic cards and cloze notes
+/// become cloze cards. Review cards keep their due date and approximate memory state;
+/// learning cards restart as new. Tags and suspension are not carried over.
+#[derive(Clone)]
+pub struct AnkiImportService {
+    clock: Clock,
+    decks: Arc<DeckService>,
+    cards: Arc<dyn CardRepository>,
+    media: Arc<MediaService>,
+}
+
+#[derive(Deserialize)]
+struct AnkiModel {
+    #[serde(rename = "type", default)]
+    kind: u8,
+    flds: Vec<AnkiField>,
+    tmpls: Vec<AnkiTemplate>,
+}
+
+#[derive(Deserialize)]
+struct AnkiField {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct AnkiTemplate {
+    ord: i64,
+    qfmt: String,
+    afmt: String,
+}
+
+#[derive(Deserialize)]
+struct AnkiDeck {
+    name: String,
+}
+
+/// One side of a card after its fields are flattened to plain text.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct FieldText {
+    text: String,
+    images: Vec<String>,
+    sounds: u32,
+}
+
+/// Deletes the extracted collection once the import is done with it.
+struct TempCollection(PathBuf);
+
+impl Drop for TempCollection {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+impl AnkiImportService {
+    #[must_use]
+    pub fn new(
+        clock: Clock,
+        decks: Arc<DeckService>,
+        cards: Arc<dyn CardRepository>,
+        media: Arc<MediaService>,
+    ) -> Self {
+        Self {
+            clock,
+            decks,
+            cards,
+            media,
+        }
+    }
+
+    /// Import every card of an `.apkg` package, creating one deck per Anki deck.
+    ///
+    /// Deck names are checked before anything is written. Cards are inserted one deck at a
+    /// time, so a storage failure part way through can leave earlier decks imported.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AnkiImportError::Archive` or `MissingCollection` if `apkg` is not a
+    /// readable package, `AnkiImportError::CompressedCollection` for packages exported
+    /// without "support older Anki versions", and `AnkiImportError::InvalidCollection`
+    /// for collections whose note types or decks cannot be read.
+    /// Returns `AnkiImportError::DeckNameTaken` if an Anki deck's name is already used.
+    /// Returns `AnkiImportError::Deck`, `Media`, or `Storage` if persistence fails.
+    pub async fn import_apkg(&self, apkg: &[u8]) -> Result<AnkiImportReport, AnkiImportError> {
+        let mut archive = ZipArchive::new(Cursor::new(apkg))?;
+        let collection = read_collection(&mut archive).await?;
+        let models: HashMap<String, AnkiModel> = serde_json::from_str(&collection.models)?;
+        let anki_decks: HashMap<String, AnkiDeck> = serde_json::from_str(&collection.decks)?;
+        let media_names: HashMap<String, String> = match archive.by_name(MEDIA_MANIFEST_ENTRY) {
+            Ok(mut entry) => {
+                let mut json = String::new();
+                entry.read_to_string(&mut json)?;
+                serde_json::from_str::<HashMap<String, String>>(&json)?
+                    .into_iter()
+                    .map(|(entry, name)| (name, entry))
+                    .collect()
+            }
+            Err(zip::result::ZipError::FileNotFound) => HashMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        let notes: HashMap<i64, &AnkiNoteRow> =
+            collection.notes.iter().map(|note| (note.id, note)).collect();
+        let mut by_deck: BTreeMap<i64, Vec<&AnkiCardRow>> = BTreeMap::new();
+        for card in &collection.cards {
+            by_deck.entry(card.deck_id).or_default().push(card);
+        }
+        let deck_name = |anki_id: i64| {
+            anki_decks
+                .get(&anki_id.to_string())
+                .map_or_else(|| format!("Anki deck {anki_id}"), |deck| deck.name.clone())
+        };
+        let now = self.clock.now();
+        let taken: Vec<String> = self
+            .decks
+            .list_decks(u32::MAX, false)
+            .await?
+            .iter()
+            .map(|deck| deck.name().trim().to_lowercase())
+            .collect();
+        if let Some(name) = by_deck
+            .keys()
+            .map(|&anki_id| deck_name(anki_id))
+            .find(|name| taken.contains(&name.trim().to_lowercase()))
+        {
+            return Err(AnkiImportError::DeckNameTaken(name));
+        }
+
+        let mut report = AnkiImportReport::default();
+        let mut stored_media: HashMap<String, Option<MediaId>> = HashMap::new();
+        for (anki_id, cards) in by_deck {
+            let mut mapped = Vec::new();
+            for card in cards {
+                let Some(sides) = notes
+                    .get(&card.note_id)
+                    .and_then(|note| Some((note, models.get(&note.model_id.to_string())?)))
+                    .and_then(|(note, model)| card_sides(model, note, card, now))
+                else {
+                    report.skipped += 1;
+                    continue;
+                };
+                mapped.push((card, sides));
+            }
+            if mapped.is_empty() {
+                continue;
+            }
+
+            let deck_id = self
+                .decks
+                .create_deck(deck_name(anki_id), None, DeckSettings::default_for_adhd())
+                .await?;
+            report.decks.push(deck_id);
+            let mut records = Vec::with_capacity(mapped.len());
+            for (card, (kind, prompt, answer, extra)) in mapped {
+                let mut stored = [None, None];
+                for (slot, side) in stored.iter_mut().zip([&prompt, &answer]) {
+                    let extra_images = side.images.len().saturating_sub(1);
+                    report.media_skipped +=
+                        side.sounds + u32::try_from(extra_images).unwrap_or(u32::MAX);
+                    let Some(name) = side.images.first() else { continue };
+                    if !stored_media.contains_key(name) {
+                        let id = self.store_image(&mut archive, &media_names, name).await?;
+                        report.media_imported += u32::from(id.is_some());
+                        stored_media.insert(name.clone(), id);
+                    }
+                    *slot = stored_media[name];
+                    report.media_skipped += u32::from(slot.is_none());
+                }
+
+                let schedule = Schedule::from_anki(card, collection.created_secs, now);
+                report.scheduled += u32::from(schedule.phase == CardPhase::Reviewing);
+                records.push(NewCardRecord {
+                    deck_id,
+                    kind,
+                    note_id: None,
+                    prompt_text: prompt.text,
+                    prompt_media_id: stored[0].map(|id| id.value()),
+                    answer_text: answer.text,
+                    answer_media_id: stored[1].map(|id| id.value()),
+                    phase: schedule.phase,
+                    created_at: now,
+                    next_review_at: schedule.next_review_at,
+                    last_review_at: schedule.last_review_at,
+                    review_count: schedule.review_count,
+                    stability: schedule.stability,
+                    difficulty: schedule.difficulty,
+                    extra_text: extra,
+                    source: Some(CardSource::Anki),
+                });
+            }
+            let ids = self.cards.insert_new_cards(records).await?;
+            report.imported += u32::try_from(ids.len()).unwrap_or(u32::MAX);
+        }
+        Ok(report)
+    }
+
+    /// Store the image Anki calls `name`, or `None` when it is missing or unsupported.
+    async fn store_image(
+        &self,
+        archive: &mut ZipArchive<Cursor<&[u8]>>,
+        media_names: &HashMap<String, String>,
+        name: &str,
+    ) -> Result<Option<MediaId>, AnkiImportError> {
+        let Some(entry) = media_names.get(name) else {
+            return Ok(None);
+        };
+        let mut bytes = Vec::new();
+        match archive.by_name(entry) {
+            Ok(mut file) => file.read_to_end(&mut bytes)?,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        match self.media.store_image(bytes).await {
+            Ok(id) => Ok(Some(id)),
+            Err(MediaServiceError::Invalid(_)) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Extract the collection database to a temporary file and read it.
+async fn read_collection(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+) -> Result<AnkiCollection, AnkiImportError> {
+    let Some(entry) = COLLECTION_ENTRIES
+        .into_iter()
+        .find(|name| archive.index_for_name(name).is_some())
+    else {
+        if archive.index_for_name(COMPRESSED_COLLECTION_ENTRY).is_some() {
+            return Err(AnkiImportError::CompressedCollection);
+        }
+        return Err(AnkiImportError::MissingCollection);
+    };
+    let mut bytes = Vec::new();
+    archive.by_name(entry)?.read_to_end(&mut bytes)?;
+
+    let count = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir()
+        .join(format!("learn-anki-{}-{count}.anki2", std::process::id()));
+    std::fs::write(&path, bytes)?;
+    let temp = TempCollection(path);
+    Ok(read_anki_collection(&temp.0).await?)
+}
+
+/// Card kind, prompt, answer, and extra text for one Anki card, or `None` if it can't map.
+fn card_sides(
+    model: &AnkiModel,
+    note: &AnkiNoteRow,
+    card: &AnkiCardRow,
+    now: DateTime<Utc>,
+) -> Option<(CardKind, FieldText, FieldText, Option<String>)> {
+    let values: HashMap<&str, &str> = model
+        .flds
+        .iter()
+        .map(|field| field.name.as_str())
+        .zip(note.fields.split(FIELD_SEPARATOR))
+        .collect();
+    let side = |template: &str, except: &[String]| {
+        let html = template_fields(template)
+            .into_iter()
+            .filter(|name| !except.contains(name))
+            .filter_map(|name| values.get(name.as_str()).copied())
+            .filter(|value| !value.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("<br>");
+        field_text(&html)
+    };
+
+    if model.kind == ANKI_CLOZE_MODEL {
+        let template = model.tmpls.first()?;
+        let text_fields = template_fields(&template.qfmt);
+        let text = side(&template.qfmt, &[]);
+        let extra = side(&template.afmt, &text_fields);
+        let source = ContentDraft::text_only(text.text.clone())
+            .validate(now, None, None)
+            .ok()?;
+        let index = u32::try_from(card.ord + 1).ok()?;
+        let expansion = expand_cloze(&source)
+            .ok()?
+            .into_iter()
+            .find(|expansion| expansion.index == index)?;
+        let prompt = FieldText {
+            text: expansion.prompt.text().to_owned(),
+            ..text
+        };
+        let answer = FieldText {
+            text: expansion.answer.text().to_owned(),
+            ..FieldText::default()
+        };
+        let kind = CardKind::Cloze {
+            text: source.text().to_owned(),
+            indices: vec![index],
+        };
+        let extra = (!extra.text.is_empty()).then_some(extra.text);
+        return Some((kind, prompt, answer, extra));
+    }
+
+    let template = model.tmpls.iter().find(|template| template.ord == card.ord)?;
+    let front_fields = template_fields(&template.qfmt);
+    let prompt = side(&template.qfmt, &[]);
+    let answer = side(&template.afmt, &front_fields);
+    if prompt.text.is_empty() || answer.text.is_empty() {
+        return None;
+    }
+    Some((CardKind::Basic, prompt, answer, None))
+}
+
+/// Field names a template shows, in order, e.g. `Text` for `{{cloze:Text}}`.
+///
+/// Conditionals, comments, and `{{FrontSide}}` are left out.
+fn template_fields(template: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let body = &rest[start + 2..];
+        let Some(end) = body.find("}}") else { break };
+        let tag = body[..end].trim();
+        rest = &body[end + 2..];
+        if tag.starts_with(['#', '/', '^', '!']) {
+            continue;
+        }
+        let name = tag.rsplit(':').next().unwrap_or(tag).trim();
+        if name != "FrontSide" && !fields.iter().any(|field| field == name) {
+            fields.push(name.to_owned());
+        }
+    }
+    fields
+}
+
+/// Flatten Anki field HTML to plain text, collecting image sources and counting sounds.
+///
+/// Line breaks, `div`s, and paragraphs become newlines; other tags are dropped.
+fn field_text(html: &str) -> FieldText {
+    let mut out = FieldText::default();
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(ch) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("[sound:")
+            && let Some(end) = after.find(']')
+        {
+            out.sounds += 1;
+            rest = &after[end + 1..];
+        } else if ch == '<'
+            && let Some(end) = rest.find('>')
+        {
+            let tag = &rest[1..end];
+            let name = tag
+                .trim_start_matches('/')
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            match name.as_str() {
+                "br" | "div" | "p" => text.push('\n'),
+                "img" => out.images.extend(attribute(tag, "src")),
+                _ => {}
+            }
+            rest = &rest[end + 1..];
+        } else if ch == '&'
+            && let Some((entity, decoded)) = [
+                ("&nbsp;", ' '),
+                ("&amp;", '&'),
+                ("&lt;", '<'),
+                ("&gt;", '>'),
+                ("&quot;", '"'),
+                ("&#39;", '\''),
+            ]
+            .into_iter()
+            .find(|(entity, _)| rest.starts_with(entity))
+        {
+            text.push(decoded);
+            rest = &rest[entity.len()..];
+        } else {
+            text.push(ch);
+            rest = &rest[ch.len_utf8()..];
+        }
+    }
+    out.text = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    out
+}
+
+/// Value of a quoted or bare `name=` attribute within a tag's source.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let start = lower.find(&format!("{name}="))? + name.len() + 1;
+    let value = &tag[start..];
+    let value = match value.chars().next()? {
+        quote @ ('"' | '\'') => value[1..].split(quote).next()?,
+        _ => value.split(|c: char| c.is_whitespace() || c == '/').next()?,
+    };
+    (!value.is_empty()).then(|| value.to_owned())
+}
+
+/// Scheduling state for an imported card.
+struct Schedule {
+    phase: CardPhase,
+    next_review_at: DateTime<Utc>,
+    last_review_at: Option<DateTime<Utc>>,
+    review_count: u32,
+    stability: Option<f64>,
+    difficulty: Option<f64>,
+}
+
+impl Schedule {
+    /// Review cards keep their due date, with the interval standing in for stability and
+    /// ease mapped onto FSRS difficulty. Every other card starts over as new.
+    #[allow(clippy::cast_precision_loss)]
+    fn from_anki(card: &AnkiCardRow, created_secs: i64, now: DateTime<Utc>) -> Self {
+        let due = DateTime::from_timestamp(created_secs, 0)
+            .and_then(|created| created.checked_add_signed(chrono::Duration::days(card.due)));
+        match due {
+            Some(due) if card.card_type == ANKI_REVIEW && card.interval > 0 => Self {
+                phase: CardPhase::Reviewing,
+                next_review_at: due,
+                last_review_at: Some(due - chrono::Duration::days(card.interval)),
+                review_count: u32::try_from(card.reps.max(1)).unwrap_or(u32::MAX),
+                stability: Some(card.interval as f64),
+                difficulty: Some(ease_to_difficulty(card.factor)),
+            },
+            _ => Self {
+                phase: CardPhase::New,
+                next_review_at: now,
+                last_review_at: None,
+                review_count: 0,
+                stability: None,
+                difficulty: None,
+            },
+        }
+    }
+}
+
+/// Map Anki's ease (permille, 1300 at its floor) onto FSRS difficulty from 1 to 10.
+///
+/// The lowest ease is the hardest card; 350% and up counts as the easiest.
+#[allow(clippy::cast_precision_loss)]
+fn ease_to_difficulty(factor: i64) -> f64 {
+    let ease = factor as f64 / 1000.0;
+    (10.0 - (ease - 1.3) / (3.5 - 1.3) * 9.0).clamp(1.0, 10.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_text_flattens_html_and_collects_media() {
+        let field = field_text(
+            "to eat&nbsp;<b>now</b><br><img src=\"eat.png\"><div>[sound:eat.mp3]a &amp; b</div>",
+        );
+
+        assert_eq!(field.text, "to eat now\na & b");
+        assert_eq!(field.images, ["eat.png"]);
+        assert_eq!(field.sounds, 1);
+    }
+
+    #[test]
+    fn template_fields_skip_front_side_and_conditionals() {
+        assert_eq!(
+            template_fields("{{FrontSide}}<hr id=answer>{{#Back}}{{Back}}{{/Back}}{{hint:Extra}}"),
+            ["Back", "Extra"]
+        );
+        assert_eq!(template_fields("{{cloze:Text}}"), ["Text"]);
+    }
+
+    #[test]
+    fn ease_maps_onto_fsrs_difficulty_range() {
+        assert!((ease_to_difficulty(1300) - 10.0).abs() < 1e-9);
+        assert!((ease_to_difficulty(2500) - 5.09).abs() < 0.01);
+        assert!((ease_to_difficulty(9000) - 1.0).abs() < 1e-9);
+    }
+}
@@ -1,12 +1,15 @@
 use std::sync::Arc;
 
+use chrono::Local;
 use learn_core::model::{Deck, DeckId, DeckSettings};
-use storage::repository::{DeckRepository, NewDeckRecord, Storage};
+use storage::repository::{AppSettingsRepository, DeckRepository, NewDeckRecord, Storage};
 
-use crate::ai::AiUsageService;
+use crate::ai::{AiUsageService, AiWritingService};
+use crate::anki_import_service::AnkiImportService;
 use crate::card_service::CardService;
 use crate::deck_service::DeckService;
 use crate::error::AppServicesError;
+use crate::media_service::MediaService;
 use crate::app_settings_service::AppSettingsService;
 use crate::sessions::{SessionLoopService, SessionSummaryService};
 use crate::writing_tools_service::WritingToolsService;
@@ -21,36 +24,84 @@ pub struct AppServices {
     session_loop: Arc<SessionLoopService>,
     card_service: Arc<CardService>,
     deck_service: Arc<DeckService>,
+    media: Arc<MediaService>,
+    anki_import: Arc<AnkiImportService>,
     app_settings: Arc<AppSettingsService>,
     writing_tools: Arc<WritingToolsService>,
+    ai_usage: Arc<AiUsageService>,
+    ai_writing: Arc<AiWritingService>,
 }
 
 impl AppServices {
     /// Build services backed by `SQLite` storage.
     ///
+    /// Opens `preferred_deck_id` when given, else the last deck the user switched to, else the
+    /// first deck.
+    ///
     /// # Errors
     ///
     /// Returns `AppServicesError` if storage initialization or default deck setup fails.
     pub async fn new_sqlite(
         db_url: &str,
         clock: Clock,
-        preferred_deck_id: DeckId,
+        preferred_deck_id: Option<DeckId>,
     ) -> Result<Self, AppServicesError> {
         let storage = Storage::sqlite(db_url).await?;
+        Self::from_storage(storage, clock, preferred_deck_id).await
+    }
+
+    /// Build services backed by Postgres storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppServicesError` if storage initialization or default deck setup fails.
+    #[cfg(feature = "postgres")]
+    pub async fn new_postgres(
+        db_url: &str,
+        clock: Clock,
+        preferred_deck_id: Option<DeckId>,
+    ) -> Result<Self, AppServicesError> {
+        let storage = Storage::postgres(db_url).await?;
+        Self::from_storage(storage, clock, preferred_deck_id).await
+    }
+
+    async fn from_storage(
+        storage: Storage,
+        clock: Clock,
+        preferred_deck_id: Option<DeckId>,
+    ) -> Result<Self, AppServicesError> {
         let (deck_id, open_editor_on_launch) =
-            ensure_default_deck(storage.decks.as_ref(), clock, preferred_deck_id).await?;
+            ensure_default_deck(
+                storage.decks.as_ref(),
+                storage.app_settings.as_ref(),
+                clock,
+                preferred_deck_id,
+            )
+            .await?;
+        // The system time zone is read once here; every service counts days in this offset.
+        let local_offset = *clock.now().with_timezone(&Local).offset();
+        let study_day = storage
+            .app_settings
+            .get_settings()
+            .await?
+            .unwrap_or_default()
+            .study_day()
+            .with_utc_offset(local_offset);
 
-        let session_summaries = Arc::new(SessionSummaryService::new(
-            clock,
-            Arc::clone(&storage.session_summaries),
-        ));
+        let session_summaries = Arc::new(
+            SessionSummaryService::new(clock, Arc::clone(&storage.session_summaries))
+                .with_study_day(study_day),
+        );
         let session_loop = Arc::new(SessionLoopService::new(
             clock,
             Arc::clone(&storage.decks),
             Arc::clone(&storage.cards),
             Arc::clone(&storage.reviews),
             Arc::clone(&storage.session_summaries),
-        ));
+        )
+        .with_progress(Arc::clone(&storage.session_progress))
+        .with_review_logs(Arc::clone(&storage.review_logs))
+        .with_study_day(study_day));
         let app_settings = Arc::new(AppSettingsService::new(Arc::clone(&storage.app_settings)));
         let ai_usage = Arc::new(AiUsageService::new(
             clock,
@@ -58,12 +109,31 @@ impl AppServices {
             Arc::clone(&storage.ai_usage),
             Arc::clone(&storage.ai_price_book),
         ));
-        let card_service = Arc::new(CardService::new(clock, Arc::clone(&storage.cards)));
-        let deck_service = Arc::new(DeckService::new(clock, Arc::clone(&storage.decks)));
+        let card_service = Arc::new(
+            CardService::new(clock, Arc::clone(&storage.cards))
+                .with_media(Arc::clone(&storage.media)),
+        );
+        let deck_service = Arc::new(
+            DeckService::new(
+                clock,
+                Arc::clone(&storage.decks),
+                Arc::clone(&storage.cards),
+                Arc::clone(&storage.review_logs),
+            )
+            .with_study_day(study_day),
+        );
+        let media = Arc::new(MediaService::new(clock, Arc::clone(&storage.media)));
+        let anki_import = Arc::new(AnkiImportService::new(
+            clock,
+            Arc::clone(&deck_service),
+            Arc::clone(&storage.cards),
+            Arc::clone(&media),
+        ));
         let writing_tools = Arc::new(WritingToolsService::from_env(
             Arc::clone(&storage.app_settings),
             Arc::clone(&ai_usage),
         ));
+        let ai_writing = Arc::new(AiWritingService::new(Arc::clone(&writing_tools)));
 
         Ok(Self {
             deck_id,
@@ -72,8 +142,12 @@ impl AppServices {
             session_loop,
             card_service,
             deck_service,
+            media,
+            anki_import,
             app_settings,
             writing_tools,
+            ai_usage,
+            ai_writing,
         })
     }
 
@@ -107,6 +181,16 @@ impl AppServices {
         Arc::clone(&self.deck_service)
     }
 
+    #[must_use]
+    pub fn media_service(&self) -> Arc<MediaService> {
+        Arc::clone(&self.media)
+    }
+
+    #[must_use]
+    pub fn anki_import(&self) -> Arc<AnkiImportService> {
+        Arc::clone(&self.anki_import)
+    }
+
     #[must_use]
     pub fn app_settings(&self) -> Arc<AppSettingsService> {
         Arc::clone(&self.app_settings)
@@ -116,18 +200,39 @@ impl AppServices {
     pub fn writing_tools(&self) -> Arc<WritingToolsService> {
         Arc::clone(&self.writing_tools)
     }
+
+    #[must_use]
+    pub fn ai_usage(&self) -> Arc<AiUsageService> {
+        Arc::clone(&self.ai_usage)
+    }
+
+    #[must_use]
+    pub fn ai_writing(&self) -> Arc<AiWritingService> {
+        Arc::clone(&self.ai_writing)
+    }
 }
 
 async fn ensure_default_deck(
     decks: &dyn DeckRepository,
+    app_settings: &dyn AppSettingsRepository,
     clock: Clock,
-    preferred_id: DeckId,
+    preferred_id: Option<DeckId>,
 ) -> Result<(DeckId, bool), AppServicesError> {
-    if decks.get_deck(preferred_id).await?.is_some() {
-        return Ok((preferred_id, false));
+    let last_deck_id = app_settings
+        .get_settings()
+        .await?
+        .and_then(|settings| settings.last_deck_id());
+    for candidate in [preferred_id, last_deck_id].into_iter().flatten() {
+        if decks
+            .get_deck(candidate)
+            .await?
+            .is_some_and(|deck| !deck.is_archived())
+        {
+            return Ok((candidate, false));
+        }
     }
 
-    let existing = decks.list_decks(128).await?;
+    let existing = decks.list_decks(128, false).await?;
     if let Some(first) = existing.first() {
         return Ok((first.id(), false));
     }
@@ -146,3 +251,45 @@ async fn ensure_default_deck(
 
     Ok((deck_id, false))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use learn_core::model::AppSettings;
+    use learn_core::time::fixed_now;
+    use storage::repository::InMemoryRepository;
+
+    #[tokio::test]
+    async fn ensure_default_deck_prefers_the_last_used_deck_while_it_exists() {
+        let repo = InMemoryRepository::new();
+        let clock = Clock::Fixed(fixed_now());
+        let mut ids = Vec::new();
+        for name in ["First", "Second"] {
+            let deck = Deck::new(
+                DeckId::new(1),
+                name,
+                None,
+                DeckSettings::default_for_adhd(),
+                fixed_now(),
+            )
+            .unwrap();
+            ids.push(repo.insert_new_deck(NewDeckRecord::from_deck(&deck)).await.unwrap());
+        }
+        let remember = |deck_id: u64| {
+            let mut draft = AppSettings::default().to_draft();
+            draft.last_deck_id = Some(DeckId::new(deck_id));
+            draft.validate().unwrap()
+        };
+
+        repo.save_settings(&remember(ids[1].value())).await.unwrap();
+        let (opened, _) = ensure_default_deck(&repo, &repo, clock, None).await.unwrap();
+        assert_eq!(opened, ids[1]);
+        let (opened, _) = ensure_default_deck(&repo, &repo, clock, Some(ids[0])).await.unwrap();
+        assert_eq!(opened, ids[0]);
+
+        repo.save_settings(&remember(99)).await.unwrap();
+        let first = repo.list_decks(128, false).await.unwrap()[0].id();
+        let (opened, _) = ensure_default_deck(&repo, &repo, clock, None).await.unwrap();
+        assert_eq!(opened, first);
+    }
+}
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use learn_core::model::{AppSettings, AppSettingsDraft};
+use learn_core::model::{AppSettings, AppSettingsDraft, DeckId};
 use storage::repository::AppSettingsRepository;
 
 use crate::error::AppSettingsServiceError;
@@ -39,4 +39,40 @@ impl AppSettingsService {
         self.repo.save_settings(&settings).await?;
         Ok(settings)
     }
+
+    /// Remember `deck_id` as the deck to open on the next launch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppSettingsServiceError` on storage failures.
+    pub async fn remember_last_deck(
+        &self,
+        deck_id: DeckId,
+    ) -> Result<AppSettings, AppSettingsServiceError> {
+        let settings = self.load().await?;
+        if settings.last_deck_id() == Some(deck_id) {
+            return Ok(settings);
+        }
+        let mut draft = settings.to_draft();
+        draft.last_deck_id = Some(deck_id);
+        self.save(draft).await
+    }
+
+    /// Turn the review session's focus mode on or off.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppSettingsServiceError` on storage failures.
+    pub async fn set_focus_mode(
+        &self,
+        enabled: bool,
+    ) -> Result<AppSettings, AppSettingsServiceError> {
+        let settings = self.load().await?;
+        if settings.focus_mode() == enabled {
+            return Ok(settings);
+        }
+        let mut draft = settings.to_draft();
+        draft.focus_mode = Some(enabled);
+        self.save(draft).await
+    }
 }
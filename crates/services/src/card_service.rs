@@ -1,10 +1,18 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use chrono::{DateTime, Duration, Utc};
-use learn_core::model::{Card, CardError, CardId, CardPhase, ContentDraft, DeckId, Tag, TagName};
-use storage::repository::{CardRepository, NewCardRecord};
-
+use learn_core::model::{
+    AudioClip, AudioMeta, Card, CardError, CardId, CardKind, CardPhase, CardSide, CardSource,
+    Content, ContentDraft, ContentValidationError, DeckId, Flag, MediaHash, MediaId, MediaUri,
+    NoteId, Tag, TagName, expand_cloze,
+};
+use storage::repository::{
+    CardOrder, CardPhaseCounts, CardRepository, MediaRepository, NewCardRecord, StorageError,
+    TrashedCard, sort_cards_by_order,
+};
+
+use crate::delimited::parse_delimited;
 use crate::error::CardServiceError;
 use crate::Clock;
 
@@ -13,6 +21,8 @@ use crate::Clock;
 pub struct CardService {
     clock: Clock,
     cards: Arc<dyn CardRepository>,
+    media: Option<Arc<dyn MediaRepository>>,
+    front_normalization: FrontNormalization,
 }
 
 /// Aggregate counts for a deck in the practice view.
@@ -30,6 +40,30 @@ pub struct DeckPracticeStatsRow {
     pub stats: DeckPracticeStats,
 }
 
+/// Cards from one deck found by `CardService::search_all_decks`, best match first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeckSearchResults {
+    pub deck_id: DeckId,
+    pub deck_name: String,
+    pub cards: Vec<Card>,
+}
+
+/// One window of a deck's card list, newest first, with the deck's total card count.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CardPage {
+    pub cards: Vec<Card>,
+    pub total: u32,
+}
+
+impl CardPage {
+    /// Whether cards remain after this page, given the offset it was loaded from.
+    #[must_use]
+    pub fn has_more(&self, offset: u32) -> bool {
+        let loaded = u32::try_from(self.cards.len()).unwrap_or(u32::MAX);
+        offset.saturating_add(loaded) < self.total
+    }
+}
+
 /// Aggregate counts for a tag scoped to a deck.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TagPracticeStats {
@@ -39,6 +73,111 @@ pub struct TagPracticeStats {
     pub new: u32,
 }
 
+/// Validate an optional extra draft; blank drafts mean no extra info.
+fn validate_extra(
+    extra: Option<ContentDraft>,
+    now: DateTime<Utc>,
+) -> Result<Option<Content>, CardError> {
+    extra
+        .filter(|draft| !draft.text().trim().is_empty())
+        .map(|draft| draft.validate(now, None, None))
+        .transpose()
+        .map_err(CardError::InvalidExtra)
+}
+
+/// Rebuild `card` with new prompt and answer content, keeping everything else.
+fn with_content(card: &Card, prompt: Content, answer: Content) -> Result<Card, CardError> {
+    let (stability, difficulty) = if card.review_count() == 0 {
+        (0.0, 0.0)
+    } else {
+        let state = card
+            .memory_state()
+            .ok_or_else(|| CardError::InvalidPersistedState("missing memory state".into()))?;
+        (state.stability, state.difficulty)
+    };
+
+    Ok(Card::from_persisted(
+        card.id(),
+        card.deck_id(),
+        card.kind().clone(),
+        prompt,
+        answer,
+        card.created_at(),
+        card.next_review_at(),
+        card.last_review_at(),
+        card.phase(),
+        card.review_count(),
+        stability,
+        difficulty,
+    )?
+    .with_note_id(card.note_id())
+    .with_suspended(card.is_suspended())
+    .with_buried_until(card.buried_until())
+    .with_learning_step(card.learning_step())
+    .with_extra(card.extra().cloned())
+    .with_flag(card.flag())
+    .with_source(card.source()))
+}
+
+/// Replace every match of `find` in `text`, scanning left to right.
+///
+/// Matches never overlap and inserted text is not searched again. Returns the new text and
+/// the number of matches.
+fn replace_matches(
+    text: &str,
+    find: &str,
+    replace: &str,
+    options: ReplaceTextOptions,
+) -> (String, u32) {
+    let mut replaced = String::with_capacity(text.len());
+    let mut matches = 0;
+    let mut copied_to = 0;
+    let mut at = 0;
+    while let Some(ch) = text[at..].chars().next() {
+        if let Some(end) = match_end(text, at, find, options) {
+            replaced.push_str(&text[copied_to..at]);
+            replaced.push_str(replace);
+            matches += 1;
+            copied_to = end;
+            at = end;
+        } else {
+            at += ch.len_utf8();
+        }
+    }
+    replaced.push_str(&text[copied_to..]);
+    (replaced, matches)
+}
+
+/// Byte offset just past a match of `find` starting at `start`, if there is one.
+fn match_end(text: &str, start: usize, find: &str, options: ReplaceTextOptions) -> Option<usize> {
+    let mut haystack = text[start..].char_indices();
+    let mut end = start;
+    for wanted in find.chars() {
+        let (offset, found) = haystack.next()?;
+        let same = if options.case_sensitive {
+            found == wanted
+        } else {
+            found.to_lowercase().eq(wanted.to_lowercase())
+        };
+        if !same {
+            return None;
+        }
+        end = start + offset + found.len_utf8();
+    }
+    if options.whole_word {
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        if before.is_some_and(is_word_char) || after.is_some_and(is_word_char) {
+            return None;
+        }
+    }
+    Some(end)
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
 fn dedup_tags(tags: &[TagName]) -> Vec<TagName> {
     let mut seen = HashSet::new();
     let mut out = Vec::new();
@@ -59,27 +198,211 @@ pub enum CardListSort {
     Created,
     /// Alphabetical by prompt text.
     Alpha,
+    /// Next review soonest first; new cards last.
+    DueDate,
+    /// Highest FSRS difficulty first; new cards last.
+    Difficulty,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+impl CardListSort {
+    fn card_order(self) -> Option<CardOrder> {
+        match self {
+            Self::DueDate => Some(CardOrder::DueDate),
+            Self::Difficulty => Some(CardOrder::Difficulty),
+            Self::Recent | Self::Created | Self::Alpha => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum CardListFilter {
     /// No filtering.
     All,
     /// Cards due within the next 24 hours (reviewed cards only).
     DueSoon,
+    /// Cards carrying every listed tag; an empty list matches all cards.
+    WithAllTags(Vec<TagName>),
+    /// Cards with no tags at all.
+    Untagged,
+    /// Cards carrying any flag.
+    Flagged,
+    /// Cards created a given way; cards from before sources were recorded never match.
+    Source(CardSource),
+}
+
+/// How `create_card` treats a front that already exists in the deck.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DuplicatePolicy {
+    /// Create the card without checking for duplicates.
+    #[default]
+    Allow,
+    /// Create the card and report the existing duplicate.
+    Warn,
+    /// Refuse to create the card with `CardServiceError::DuplicateFront`.
+    Reject,
+}
+
+/// Normalization applied to fronts before comparing them for duplicates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrontNormalization {
+    /// Compare case-insensitively.
+    pub ignore_case: bool,
+    /// Trim and collapse runs of whitespace before comparing.
+    pub collapse_whitespace: bool,
+}
+
+impl Default for FrontNormalization {
+    fn default() -> Self {
+        Self {
+            ignore_case: true,
+            collapse_whitespace: true,
+        }
+    }
+}
+
+impl FrontNormalization {
+    /// Exact comparison with no normalization.
+    #[must_use]
+    pub fn exact() -> Self {
+        Self {
+            ignore_case: false,
+            collapse_whitespace: false,
+        }
+    }
+
+    #[must_use]
+    pub fn normalize(&self, text: &str) -> String {
+        let text = if self.collapse_whitespace {
+            text.split_whitespace().collect::<Vec<_>>().join(" ")
+        } else {
+            text.to_owned()
+        };
+        if self.ignore_case {
+            text.to_lowercase()
+        } else {
+            text
+        }
+    }
+}
+
+/// Result of creating a card under a `DuplicatePolicy`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CreatedCard {
+    pub id: CardId,
+    /// Existing card with the same front, reported under `DuplicatePolicy::Warn`.
+    pub duplicate_of: Option<CardId>,
+}
+
+/// Extra cards to create alongside a new card.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CreateCardOptions {
+    /// Also create a reverse card (answer → prompt) linked to the same note.
+    pub generate_reverse: bool,
+    /// How the card (and its reverse) were created.
+    pub source: CardSource,
+}
+
+/// How much of a card's past `CardService::reset_card_learning` forgets.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResetCardOptions {
+    /// Also delete the card's review logs, not just its scheduling state.
+    pub clear_history: bool,
+}
+
+/// How `CardService::replace_text` matches the search text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReplaceTextOptions {
+    /// Match letter case exactly instead of ignoring it.
+    pub case_sensitive: bool,
+    /// Skip matches that sit inside a longer word.
+    pub whole_word: bool,
+}
+
+/// Result of `CardService::replace_text` and `CardService::preview_replace_text`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReplaceTextReport {
+    /// Matches found across all prompts and answers.
+    pub matches: u32,
+    /// Cards whose prompt or answer text differs after replacing.
+    pub cards_changed: u32,
+}
+
+/// Result of `CardService::create_card_with_options`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CreatedNote {
+    pub id: CardId,
+    pub reverse_id: Option<CardId>,
+    /// Shared note of the forward and reverse cards; `None` without a reverse.
+    pub note_id: Option<NoteId>,
+}
+
+/// Result of `CardService::import_delimited`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DelimitedImportReport {
+    pub imported: u32,
+    /// 1-based row numbers that were not imported, in input order.
+    pub failed_rows: Vec<usize>,
+}
+
+/// Media a card side refers to that is no longer stored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MissingMedia {
+    Image(MediaId),
+    Audio(MediaHash),
+}
+
+/// One dangling media reference found by `CardService::find_broken_media`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BrokenMedia {
+    pub card_id: CardId,
+    pub side: CardSide,
+    pub missing: MissingMedia,
 }
 
 impl CardService {
+    /// How long deleted cards stay in the trash before `purge_trash` may remove them.
+    pub const TRASH_RETENTION: Duration = Duration::days(30);
+
     #[must_use]
     pub fn new(clock: Clock, cards: Arc<dyn CardRepository>) -> Self {
-        Self { clock, cards }
+        Self {
+            clock,
+            cards,
+            media: None,
+            front_normalization: FrontNormalization::default(),
+        }
+    }
+
+    /// Check card media against `media` in `find_broken_media`.
+    #[must_use]
+    pub fn with_media(mut self, media: Arc<dyn MediaRepository>) -> Self {
+        self.media = Some(media);
+        self
+    }
+
+    /// Compare fronts after `normalization` when `create_card` checks for duplicates.
+    #[must_use]
+    pub fn with_front_normalization(mut self, normalization: FrontNormalization) -> Self {
+        self.front_normalization = normalization;
+        self
+    }
+
+    #[must_use]
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
     }
 
     /// Create a new card from validated drafts and persist it.
     ///
+    /// `on_duplicate` says what to do when the deck already has a card with the same
+    /// front; `None` means `DuplicatePolicy::Allow`.
+    ///
     /// # Errors
     ///
+    /// Returns `CardServiceError::DuplicateFront` when `on_duplicate` is `Reject`
+    /// and a matching front exists.
     /// Returns `CardServiceError::Card` for validation failures.
     /// Returns `CardServiceError::Storage` if persistence fails.
     pub async fn create_card(
@@ -87,8 +410,24 @@ impl CardService {
         deck_id: DeckId,
         prompt: ContentDraft,
         answer: ContentDraft,
-    ) -> Result<CardId, CardServiceError> {
-        self.create_card_with_tags(deck_id, prompt, answer, &[]).await
+        on_duplicate: Option<DuplicatePolicy>,
+    ) -> Result<CreatedCard, CardServiceError> {
+        let on_duplicate = on_duplicate.unwrap_or_default();
+        let duplicate_of = match on_duplicate {
+            DuplicatePolicy::Allow => None,
+            DuplicatePolicy::Warn | DuplicatePolicy::Reject => {
+                self.find_duplicate_front(deck_id, prompt.text(), self.front_normalization, None)
+                    .await?
+            }
+        };
+        if let (DuplicatePolicy::Reject, Some(existing)) = (on_duplicate, duplicate_of) {
+            return Err(CardServiceError::DuplicateFront(existing));
+        }
+
+        let id = self
+            .create_card_with_tags(deck_id, prompt, answer, &[])
+            .await?;
+        Ok(CreatedCard { id, duplicate_of })
     }
 
     /// Create a new card with tags and persist it.
@@ -103,6 +442,48 @@ impl CardService {
         prompt: ContentDraft,
         answer: ContentDraft,
         tag_names: &[TagName],
+    ) -> Result<CardId, CardServiceError> {
+        self.create_card_with_extra(deck_id, prompt, answer, None, tag_names)
+            .await
+    }
+
+    /// Create a new card with tags and optional extra info shown beneath the answer.
+    ///
+    /// A blank `extra` draft is treated as no extra info.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Card` for validation failures.
+    /// Returns `CardServiceError::Storage` if persistence fails.
+    pub async fn create_card_with_extra(
+        &self,
+        deck_id: DeckId,
+        prompt: ContentDraft,
+        answer: ContentDraft,
+        extra: Option<ContentDraft>,
+        tag_names: &[TagName],
+    ) -> Result<CardId, CardServiceError> {
+        self.create_card_from(deck_id, prompt, answer, extra, tag_names, CardSource::Manual)
+            .await
+    }
+
+    /// Validate and persist one basic card, stamped with how it was created.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "create_card",
+            skip_all,
+            fields(deck_id = deck_id.value(), card_id = tracing::field::Empty),
+        )
+    )]
+    async fn create_card_from(
+        &self,
+        deck_id: DeckId,
+        prompt: ContentDraft,
+        answer: ContentDraft,
+        extra: Option<ContentDraft>,
+        tag_names: &[TagName],
+        source: CardSource,
     ) -> Result<CardId, CardServiceError> {
         let now = self.clock.now();
         let prompt = prompt
@@ -111,9 +492,12 @@ impl CardService {
         let answer = answer
             .validate(now, None, None)
             .map_err(CardError::InvalidAnswer)?;
+        let extra = validate_extra(extra, now)?;
 
         let record = NewCardRecord {
             deck_id,
+            kind: CardKind::Basic,
+            note_id: None,
             prompt_text: prompt.text().to_owned(),
             prompt_media_id: prompt.media_id().map(|m| m.value()),
             answer_text: answer.text().to_owned(),
@@ -125,9 +509,13 @@ impl CardService {
             review_count: 0,
             stability: None,
             difficulty: None,
+            extra_text: extra.map(|extra| extra.text().to_owned()),
+            source: Some(source),
         };
 
         let card_id = self.cards.insert_new_card(record).await?;
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("card_id", card_id.value());
         if !tag_names.is_empty() {
             let tags = dedup_tags(tag_names);
             self.cards.set_tags_for_card(deck_id, card_id, &tags).await?;
@@ -135,6 +523,237 @@ impl CardService {
         Ok(card_id)
     }
 
+    /// Create a new card with tags and optional extra info and return it as stored, with its
+    /// ID, phase and timestamps.
+    ///
+    /// Lets callers add the card to an in-memory list without reloading the whole list.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Card` for validation failures.
+    /// Returns `CardServiceError::Storage` if persistence fails.
+    pub async fn create_card_returning(
+        &self,
+        deck_id: DeckId,
+        prompt: ContentDraft,
+        answer: ContentDraft,
+        extra: Option<ContentDraft>,
+        tag_names: &[TagName],
+        source: CardSource,
+    ) -> Result<Card, CardServiceError> {
+        let id = self
+            .create_card_from(deck_id, prompt, answer, extra, tag_names, source)
+            .await?;
+        let mut created = self.cards.get_cards(deck_id, &[id]).await?;
+        created.pop().ok_or_else(|| StorageError::NotFound.into())
+    }
+
+    /// Create a new card with tags and, if requested, its reverse.
+    ///
+    /// The reverse card swaps prompt and answer, gets the same tags, and is linked to the
+    /// forward card by a note ID equal to the forward card's ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Card` for validation failures.
+    /// Returns `CardServiceError::Storage` if persistence fails.
+    pub async fn create_card_with_options(
+        &self,
+        deck_id: DeckId,
+        prompt: ContentDraft,
+        answer: ContentDraft,
+        tag_names: &[TagName],
+        options: CreateCardOptions,
+    ) -> Result<CreatedNote, CardServiceError> {
+        if !options.generate_reverse {
+            let id = self
+                .create_card_from(deck_id, prompt, answer, None, tag_names, options.source)
+                .await?;
+            return Ok(CreatedNote {
+                id,
+                reverse_id: None,
+                note_id: None,
+            });
+        }
+
+        let now = self.clock.now();
+        let reverse_prompt = answer
+            .clone()
+            .validate(now, None, None)
+            .map_err(CardError::InvalidPrompt)?;
+        let reverse_answer = prompt
+            .clone()
+            .validate(now, None, None)
+            .map_err(CardError::InvalidAnswer)?;
+
+        let id = self
+            .create_card_from(deck_id, prompt, answer, None, tag_names, options.source)
+            .await?;
+        let note_id = NoteId::new(id.value());
+
+        let record = NewCardRecord {
+            deck_id,
+            kind: CardKind::Basic,
+            note_id: Some(note_id),
+            prompt_text: reverse_prompt.text().to_owned(),
+            prompt_media_id: reverse_prompt.media_id().map(|m| m.value()),
+            answer_text: reverse_answer.text().to_owned(),
+            answer_media_id: reverse_answer.media_id().map(|m| m.value()),
+            phase: CardPhase::New,
+            created_at: now,
+            next_review_at: now,
+            last_review_at: None,
+            review_count: 0,
+            stability: None,
+            difficulty: None,
+            extra_text: None,
+            source: Some(options.source),
+        };
+        let reverse_id = self.cards.insert_new_card(record).await?;
+        if !tag_names.is_empty() {
+            let tags = dedup_tags(tag_names);
+            self.cards.set_tags_for_card(deck_id, reverse_id, &tags).await?;
+        }
+
+        let mut forward = self.cards.get_cards(deck_id, &[id]).await?;
+        let Some(forward) = forward.pop() else {
+            return Err(StorageError::NotFound.into());
+        };
+        self.cards
+            .upsert_card(&forward.with_note_id(Some(note_id)).with_updated_at(now))
+            .await?;
+
+        Ok(CreatedNote {
+            id,
+            reverse_id: Some(reverse_id),
+            note_id: Some(note_id),
+        })
+    }
+
+    /// Create one card per cloze index in `text`, returning their IDs in index order.
+    ///
+    /// Every card keeps the full cloze source, so each deletion is scheduled and logged as a
+    /// card of its own.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Card` if the text is empty or not valid cloze markup.
+    /// Returns `CardServiceError::Storage` if persistence fails.
+    pub async fn create_cloze_cards(
+        &self,
+        deck_id: DeckId,
+        text: ContentDraft,
+        tag_names: &[TagName],
+    ) -> Result<Vec<CardId>, CardServiceError> {
+        let now = self.clock.now();
+        let source = text
+            .validate(now, None, None)
+            .map_err(CardError::InvalidPrompt)?;
+        let expansions = expand_cloze(&source).map_err(CardError::InvalidCloze)?;
+        let tags = dedup_tags(tag_names);
+
+        let mut ids = Vec::with_capacity(expansions.len());
+        for expansion in expansions {
+            let record = NewCardRecord {
+                deck_id,
+                kind: CardKind::Cloze {
+                    text: source.text().to_owned(),
+                    indices: vec![expansion.index],
+                },
+                note_id: None,
+                prompt_text: expansion.prompt.text().to_owned(),
+                prompt_media_id: expansion.prompt.media_id().map(|m| m.value()),
+                answer_text: expansion.answer.text().to_owned(),
+                answer_media_id: expansion.answer.media_id().map(|m| m.value()),
+                phase: CardPhase::New,
+                created_at: now,
+                next_review_at: now,
+                last_review_at: None,
+                review_count: 0,
+                stability: None,
+                difficulty: None,
+                extra_text: None,
+                source: Some(CardSource::Manual),
+            };
+            let card_id = self.cards.insert_new_card(record).await?;
+            if !tags.is_empty() {
+                self.cards.set_tags_for_card(deck_id, card_id, &tags).await?;
+            }
+            ids.push(card_id);
+        }
+        Ok(ids)
+    }
+
+    /// Import two-column prompt/answer rows from CSV or TSV text.
+    ///
+    /// Valid rows are inserted in a single transaction. Rows with a blank column, more than
+    /// two non-empty columns, or content that fails validation are reported in
+    /// `failed_rows` instead of being imported.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Read` if the reader fails or the data is not UTF-8.
+    /// Returns `CardServiceError::InvalidDelimiter` or `UnterminatedQuote` for unparseable
+    /// input; nothing is written in that case.
+    /// Returns `CardServiceError::Storage` if persistence fails.
+    pub async fn import_delimited(
+        &self,
+        deck_id: DeckId,
+        mut reader: impl std::io::Read,
+        delimiter: char,
+    ) -> Result<DelimitedImportReport, CardServiceError> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let rows = parse_delimited(&text, delimiter)?;
+
+        let now = self.clock.now();
+        let mut records = Vec::with_capacity(rows.len());
+        let mut failed_rows = Vec::new();
+        for row in rows {
+            let (prompt, answer) = match row.fields.as_slice() {
+                [prompt, answer, rest @ ..] if rest.iter().all(|f| f.trim().is_empty()) => {
+                    (prompt.trim(), answer.trim())
+                }
+                _ => {
+                    failed_rows.push(row.row);
+                    continue;
+                }
+            };
+            let validated = (
+                ContentDraft::text_only(prompt).validate(now, None, None),
+                ContentDraft::text_only(answer).validate(now, None, None),
+            );
+            let (Ok(prompt), Ok(answer)) = validated else {
+                failed_rows.push(row.row);
+                continue;
+            };
+            records.push(NewCardRecord {
+                deck_id,
+                kind: CardKind::Basic,
+                note_id: None,
+                prompt_text: prompt.text().to_owned(),
+                prompt_media_id: None,
+                answer_text: answer.text().to_owned(),
+                answer_media_id: None,
+                phase: CardPhase::New,
+                created_at: now,
+                next_review_at: now,
+                last_review_at: None,
+                review_count: 0,
+                stability: None,
+                difficulty: None,
+                extra_text: None,
+                source: Some(CardSource::Csv),
+            });
+        }
+
+        let ids = self.cards.insert_new_cards(records).await?;
+        Ok(DelimitedImportReport {
+            imported: u32::try_from(ids.len()).unwrap_or(u32::MAX),
+            failed_rows,
+        })
+    }
+
     /// Count cards created today for a deck (UTC day).
     ///
     /// # Errors
@@ -159,7 +778,8 @@ impl CardService {
     ///
     /// Returns `CardServiceError::Storage` if persistence fails.
     pub async fn save_card(&self, card: &Card) -> Result<(), CardServiceError> {
-        self.cards.upsert_card(card).await?;
+        let card = card.clone().with_updated_at(self.clock.now());
+        self.cards.upsert_card(&card).await?;
         Ok(())
     }
 
@@ -177,6 +797,26 @@ impl CardService {
         Ok(cards)
     }
 
+    /// List up to `limit` cards for a deck after the first `offset`, ordered like `list_cards`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if repository access fails.
+    pub async fn list_cards_page(
+        &self,
+        deck_id: DeckId,
+        offset: u32,
+        limit: u32,
+    ) -> Result<CardPage, CardServiceError> {
+        let cards = self.cards.list_cards_page(deck_id, offset, limit).await?;
+        let total = self
+            .cards
+            .deck_practice_counts(deck_id, self.clock.now())
+            .await?
+            .total;
+        Ok(CardPage { cards, total })
+    }
+
     /// Count cards currently in a mistake/relearning state.
     ///
     /// # Errors
@@ -187,6 +827,17 @@ impl CardService {
         Ok(count)
     }
 
+    /// Count a deck's cards by phase without loading them; suspended cards are counted
+    /// apart from their phase.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` on persistence failures.
+    pub async fn count_cards(&self, deck_id: DeckId) -> Result<CardPhaseCounts, CardServiceError> {
+        let counts = self.cards.count_cards_by_phase(deck_id).await?;
+        Ok(counts)
+    }
+
     /// Reset learning state for all cards in a deck.
     ///
     /// # Errors
@@ -198,6 +849,26 @@ impl CardService {
         Ok(updated)
     }
 
+    /// Return one card to the `New` phase, clearing its FSRS state, and return it as stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` with `StorageError::NotFound` if the card is not in
+    /// the deck, or on other persistence failures.
+    pub async fn reset_card_learning(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        options: ResetCardOptions,
+    ) -> Result<Card, CardServiceError> {
+        let now = self.clock.now();
+        self.cards
+            .reset_card_learning(deck_id, card_id, now, options.clear_history)
+            .await?;
+        let mut reset = self.cards.get_cards(deck_id, &[card_id]).await?;
+        reset.pop().ok_or_else(|| StorageError::NotFound.into())
+    }
+
     /// Compute practice-ready card counts for a deck.
     ///
     /// # Errors
@@ -269,6 +940,8 @@ impl CardService {
     /// List cards for a deck with sorting and filtering.
     ///
     /// If `tag_names` is non-empty, only cards with at least one of the tags are returned.
+    /// Tag filters (`WithAllTags`, `Untagged`) are applied by the repository query and
+    /// combine with `tag_names`; `DueSoon`, `Flagged` and `Source` narrow the listed cards.
     ///
     /// # Errors
     ///
@@ -281,10 +954,41 @@ impl CardService {
         filter: CardListFilter,
         tag_names: &[TagName],
     ) -> Result<Vec<Card>, CardServiceError> {
-        let mut cards = if tag_names.is_empty() {
-            self.cards.list_cards(deck_id, limit).await?
-        } else {
-            self.cards.list_cards_by_tags(deck_id, tag_names).await?
+        let mut cards = match &filter {
+            CardListFilter::WithAllTags(required) => {
+                let mut cards = self
+                    .cards
+                    .list_cards_with_all_tags(deck_id, required, limit)
+                    .await?;
+                if !tag_names.is_empty() {
+                    let card_ids: Vec<CardId> = cards.iter().map(Card::id).collect();
+                    let tags = self.cards.list_tags_for_cards(deck_id, &card_ids).await?;
+                    cards.retain(|card| {
+                        tags.iter().any(|(card_id, tag)| {
+                            *card_id == card.id() && tag_names.contains(tag.name())
+                        })
+                    });
+                }
+                cards
+            }
+            CardListFilter::Untagged if !tag_names.is_empty() => Vec::new(),
+            CardListFilter::Untagged => self.cards.list_untagged_cards(deck_id, limit).await?,
+            CardListFilter::All
+            | CardListFilter::DueSoon
+            | CardListFilter::Flagged
+            | CardListFilter::Source(_) => {
+                // Flags and sources are matched below, so those lists look past `limit` first.
+                let matched_below =
+                    matches!(filter, CardListFilter::Flagged | CardListFilter::Source(_));
+                let fetch_limit = if matched_below { u32::MAX } else { limit };
+                if !tag_names.is_empty() {
+                    self.cards.list_cards_by_tags(deck_id, tag_names).await?
+                } else if let Some(order) = sort.card_order() {
+                    self.cards.list_cards_ordered(deck_id, order, fetch_limit).await?
+                } else {
+                    self.cards.list_cards(deck_id, fetch_limit).await?
+                }
+            }
         };
 
         if matches!(filter, CardListFilter::DueSoon) {
@@ -292,6 +996,12 @@ impl CardService {
             let cutoff = now + Duration::hours(24);
             cards.retain(|card| card.review_count() > 0 && card.next_review_at() <= cutoff);
         }
+        if matches!(filter, CardListFilter::Flagged) {
+            cards.retain(|card| card.flag().is_some());
+        }
+        if let CardListFilter::Source(source) = &filter {
+            cards.retain(|card| card.source() == Some(*source));
+        }
 
         match sort {
             CardListSort::Recent => {
@@ -317,23 +1027,77 @@ impl CardService {
                         .then_with(|| left.id().value().cmp(&right.id().value()))
                 });
             }
+            // Already ordered by the repository unless the cards came from a tag query.
+            CardListSort::DueDate => sort_cards_by_order(&mut cards, CardOrder::DueDate),
+            CardListSort::Difficulty => sort_cards_by_order(&mut cards, CardOrder::Difficulty),
         }
 
         cards.truncate(limit as usize);
         Ok(cards)
     }
 
-    /// List tags for a deck.
+    /// Search card prompt and answer text, best matches first.
+    ///
+    /// Every word in `query` must match the start of a word on the card, case-insensitively.
+    /// A blank query lists the most recent cards instead.
     ///
     /// # Errors
     ///
     /// Returns `CardServiceError::Storage` if repository access fails.
-    pub async fn list_tags_for_deck(&self, deck_id: DeckId) -> Result<Vec<Tag>, CardServiceError> {
-        let tags = self.cards.list_tags_for_deck(deck_id).await?;
-        Ok(tags)
+    pub async fn search_cards(
+        &self,
+        deck_id: DeckId,
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<Card>, CardServiceError> {
+        let cards = if query.trim().is_empty() {
+            self.cards.list_cards(deck_id, limit).await?
+        } else {
+            self.cards.search_cards(deck_id, query, limit).await?
+        };
+        Ok(cards)
     }
 
-    /// List tags for a card.
+    /// Search card prompt and answer text in every deck, grouped by deck.
+    ///
+    /// Words match as in `search_cards`. Up to `limit` cards are returned in total; decks
+    /// come in the order of their best match. A blank query finds nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if repository access fails.
+    pub async fn search_all_decks(
+        &self,
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<DeckSearchResults>, CardServiceError> {
+        let matches = self.cards.search_all_decks(query, limit).await?;
+        let mut groups: Vec<DeckSearchResults> = Vec::new();
+        for hit in matches {
+            let deck_id = hit.card.deck_id();
+            match groups.iter_mut().find(|group| group.deck_id == deck_id) {
+                Some(group) => group.cards.push(hit.card),
+                None => groups.push(DeckSearchResults {
+                    deck_id,
+                    deck_name: hit.deck_name,
+                    cards: vec![hit.card],
+                }),
+            }
+        }
+        Ok(groups)
+    }
+
+    /// List tags for a deck.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if repository access fails.
+    pub async fn list_tags_for_deck(&self, deck_id: DeckId) -> Result<Vec<Tag>, CardServiceError> {
+        let tags = self.cards.list_tags_for_deck(deck_id).await?;
+        Ok(tags)
+    }
+
+    /// List tags for a card.
     ///
     /// # Errors
     ///
@@ -347,6 +1111,38 @@ impl CardService {
         Ok(tags)
     }
 
+    /// List tag names for each of the given cards, keyed by card ID.
+    ///
+    /// Untagged cards are absent from the map. Names within a card are sorted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if repository access fails.
+    pub async fn list_tags_for_cards(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+    ) -> Result<HashMap<CardId, Vec<TagName>>, CardServiceError> {
+        let rows = self.cards.list_tags_for_cards(deck_id, card_ids).await?;
+        let mut out: HashMap<CardId, Vec<TagName>> = HashMap::new();
+        for (card_id, tag) in rows {
+            out.entry(card_id).or_default().push(tag.name().clone());
+        }
+        Ok(out)
+    }
+
+    /// Create a tag in a deck without attaching it to any card.
+    ///
+    /// Returns the existing tag when the name is already in use.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if repository access fails.
+    pub async fn upsert_tag(&self, deck_id: DeckId, name: &TagName) -> Result<Tag, CardServiceError> {
+        let tag = self.cards.upsert_tag(deck_id, name).await?;
+        Ok(tag)
+    }
+
     /// Replace tags for a card.
     ///
     /// # Errors
@@ -387,7 +1183,37 @@ impl CardService {
         Ok(exists)
     }
 
-    /// Update a card's prompt/answer content while preserving scheduling state.
+    /// Find an existing card in the deck whose front matches `prompt_text`.
+    ///
+    /// Returns the lowest matching card ID so the editor can link to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if repository access fails.
+    pub async fn find_duplicate_front(
+        &self,
+        deck_id: DeckId,
+        prompt_text: &str,
+        normalization: FrontNormalization,
+        exclude: Option<CardId>,
+    ) -> Result<Option<CardId>, CardServiceError> {
+        let needle = normalization.normalize(prompt_text);
+        if needle.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let cards = self.cards.list_cards(deck_id, u32::MAX).await?;
+        let existing = cards
+            .iter()
+            .filter(|card| Some(card.id()) != exclude)
+            .filter(|card| normalization.normalize(card.prompt().text()) == needle)
+            .map(Card::id)
+            .min_by_key(|id| id.value());
+        Ok(existing)
+    }
+
+    /// Update a card's prompt/answer content while preserving scheduling state, audio, and
+    /// extra info.
     ///
     /// # Errors
     ///
@@ -410,36 +1236,151 @@ impl CardService {
 
         let mut cards = self.cards.get_cards(deck_id, &[card_id]).await?;
         let Some(card) = cards.pop() else {
-            return Err(storage::repository::StorageError::NotFound.into());
+            return Err(StorageError::NotFound.into());
         };
 
-        let (stability, difficulty) = if card.review_count() == 0 {
-            (0.0, 0.0)
-        } else {
-            let state = card
-                .memory_state()
-                .ok_or_else(|| CardError::InvalidPersistedState("missing memory state".into()))?;
-            (state.stability, state.difficulty)
-        };
-
-        let updated = Card::from_persisted(
-            card.id(),
-            card.deck_id(),
-            prompt,
-            answer,
-            card.created_at(),
-            card.next_review_at(),
-            card.last_review_at(),
-            card.phase(),
-            card.review_count(),
-            stability,
-            difficulty,
-        )?;
+        let updated = with_content(
+            &card,
+            prompt.with_audio(card.prompt().audio().cloned()),
+            answer.with_audio(card.answer().audio().cloned()),
+        )?
+        .with_updated_at(now);
 
         self.cards.upsert_card(&updated).await?;
         Ok(())
     }
 
+    /// Replace `find` with `replace` in the prompt and answer text of every card in a deck.
+    ///
+    /// Works on the stored text, so markup is matched as written rather than as rendered.
+    /// Cloze cards are skipped because their sides are generated from the cloze source. All
+    /// changed cards are written in one transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::EmptySearch` if `find` is empty.
+    /// Returns `CardServiceError::Card` if a replacement would leave a side empty; nothing is
+    /// written in that case.
+    /// Returns `CardServiceError::Storage` if persistence fails.
+    pub async fn replace_text(
+        &self,
+        deck_id: DeckId,
+        find: &str,
+        replace: &str,
+        options: ReplaceTextOptions,
+    ) -> Result<ReplaceTextReport, CardServiceError> {
+        let (report, changed) = self.plan_replace_text(deck_id, find, replace, options).await?;
+        let now = self.clock.now();
+        let changed: Vec<Card> =
+            changed.into_iter().map(|card| card.with_updated_at(now)).collect();
+        if !changed.is_empty() {
+            self.cards.upsert_cards(&changed).await?;
+        }
+        Ok(report)
+    }
+
+    /// Count what `replace_text` would change without writing anything.
+    ///
+    /// # Errors
+    ///
+    /// Same as `replace_text`.
+    pub async fn preview_replace_text(
+        &self,
+        deck_id: DeckId,
+        find: &str,
+        replace: &str,
+        options: ReplaceTextOptions,
+    ) -> Result<ReplaceTextReport, CardServiceError> {
+        let (report, _) = self.plan_replace_text(deck_id, find, replace, options).await?;
+        Ok(report)
+    }
+
+    async fn plan_replace_text(
+        &self,
+        deck_id: DeckId,
+        find: &str,
+        replace: &str,
+        options: ReplaceTextOptions,
+    ) -> Result<(ReplaceTextReport, Vec<Card>), CardServiceError> {
+        if find.is_empty() {
+            return Err(CardServiceError::EmptySearch);
+        }
+        let mut report = ReplaceTextReport::default();
+        let mut changed = Vec::new();
+        for card in self.cards.list_cards(deck_id, u32::MAX).await? {
+            if !matches!(card.kind(), CardKind::Basic) {
+                continue;
+            }
+            let (prompt, prompt_matches) =
+                replace_matches(card.prompt().text(), find, replace, options);
+            let (answer, answer_matches) =
+                replace_matches(card.answer().text(), find, replace, options);
+            report.matches += prompt_matches + answer_matches;
+            if prompt == card.prompt().text() && answer == card.answer().text() {
+                continue;
+            }
+            let prompt = Content::from_persisted(prompt, card.prompt().media_id())
+                .map_err(CardError::InvalidPrompt)?
+                .with_audio(card.prompt().audio().cloned());
+            let answer = Content::from_persisted(answer, card.answer().media_id())
+                .map_err(CardError::InvalidAnswer)?
+                .with_audio(card.answer().audio().cloned());
+            changed.push(with_content(&card, prompt, answer)?);
+            report.cards_changed += 1;
+        }
+        Ok((report, changed))
+    }
+
+    /// Replace (or clear, with `None` or a blank draft) a card's extra info, keeping its
+    /// content and scheduling state.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Card` if the extra content fails validation.
+    /// Returns `CardServiceError::Storage` if the card is missing or persistence fails.
+    pub async fn update_card_extra(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        extra: Option<ContentDraft>,
+    ) -> Result<(), CardServiceError> {
+        let now = self.clock.now();
+        let extra = validate_extra(extra, now)?;
+        let mut cards = self.cards.get_cards(deck_id, &[card_id]).await?;
+        let Some(card) = cards.pop() else {
+            return Err(StorageError::NotFound.into());
+        };
+        self.cards
+            .upsert_card(&card.with_extra(extra).with_updated_at(now))
+            .await?;
+        Ok(())
+    }
+
+    /// Update a card's content and write the swapped content to the other cards of its note.
+    ///
+    /// Cards without a note are updated like `update_card_content`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Card` for validation failures.
+    /// Returns `CardServiceError::Storage` if persistence fails.
+    pub async fn update_card_and_reverse(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        prompt: ContentDraft,
+        answer: ContentDraft,
+    ) -> Result<(), CardServiceError> {
+        let siblings = self.note_siblings(deck_id, card_id).await?;
+        self.update_card_content(deck_id, card_id, prompt.clone(), answer.clone())
+            .await?;
+        for sibling in siblings {
+            self.update_card_content(deck_id, sibling.id(), answer.clone(), prompt.clone())
+                .await?;
+        }
+        Ok(())
+    }
+
     /// Update a card's prompt/answer content and tags while preserving scheduling state.
     ///
     /// # Errors
@@ -460,7 +1401,111 @@ impl CardService {
         Ok(())
     }
 
-    /// Delete a card and any associated persisted history.
+    /// Attach an audio clip to one side of a card, replacing any clip already there.
+    ///
+    /// The clip is identified by `checksum`; format and size limits are checked before
+    /// anything is written.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Card` if the clip fails media validation.
+    /// Returns `CardServiceError::Storage` if the card is missing or persistence fails.
+    pub async fn attach_audio(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        side: CardSide,
+        uri: MediaUri,
+        meta: AudioMeta,
+        checksum: MediaHash,
+    ) -> Result<Card, CardServiceError> {
+        let clip = AudioClip::new(uri, &meta, checksum).map_err(|err| {
+            let err = ContentValidationError::Media(err);
+            match side {
+                CardSide::Prompt => CardError::InvalidPrompt(err),
+                CardSide::Answer => CardError::InvalidAnswer(err),
+            }
+        })?;
+
+        let mut cards = self.cards.get_cards(deck_id, &[card_id]).await?;
+        let Some(mut card) = cards.pop() else {
+            return Err(StorageError::NotFound.into());
+        };
+        card.set_audio(side, Some(clip));
+        let card = card.with_updated_at(self.clock.now());
+        self.cards.upsert_card(&card).await?;
+        Ok(card)
+    }
+
+    /// List the images and audio clips in a deck that cards refer to but that are no longer
+    /// stored, e.g. after restoring a database without its media.
+    ///
+    /// Results follow the deck's card order, front before back. Without `with_media` there
+    /// is nothing to check against and the list is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if repository access fails.
+    pub async fn find_broken_media(
+        &self,
+        deck_id: DeckId,
+    ) -> Result<Vec<BrokenMedia>, CardServiceError> {
+        let Some(media) = &self.media else {
+            return Ok(Vec::new());
+        };
+        let cards = self.cards.list_cards(deck_id, u32::MAX).await?;
+        // Cards often share an image, so each reference is looked up once.
+        let mut images: HashMap<MediaId, bool> = HashMap::new();
+        let mut clips: HashMap<String, bool> = HashMap::new();
+        let mut broken = Vec::new();
+        for card in &cards {
+            let sides = [(CardSide::Prompt, card.prompt()), (CardSide::Answer, card.answer())];
+            for (side, content) in sides {
+                if let Some(id) = content.media_id() {
+                    let stored = match images.get(&id) {
+                        Some(stored) => *stored,
+                        None => {
+                            let stored = media.get_media(id).await?.is_some();
+                            images.insert(id, stored);
+                            stored
+                        }
+                    };
+                    if !stored {
+                        broken.push(BrokenMedia {
+                            card_id: card.id(),
+                            side,
+                            missing: MissingMedia::Image(id),
+                        });
+                    }
+                }
+                if let Some(clip) = content.audio() {
+                    let hash = clip.checksum();
+                    let stored = match clips.get(hash.as_str()) {
+                        Some(stored) => *stored,
+                        None => {
+                            let stored = media.exists(hash).await?;
+                            clips.insert(hash.as_str().to_owned(), stored);
+                            stored
+                        }
+                    };
+                    if !stored {
+                        broken.push(BrokenMedia {
+                            card_id: card.id(),
+                            side,
+                            missing: MissingMedia::Audio(hash.clone()),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(broken)
+    }
+
+    /// Move a card to the trash.
+    ///
+    /// The card drops out of lists, counts and sessions but keeps its review history, so
+    /// `restore_card` brings it back as it was. `purge_trash` deletes it for good.
+    /// A card left alone in its note is unlinked, so it behaves like a plain card.
     ///
     /// # Errors
     ///
@@ -470,60 +1515,419 @@ impl CardService {
         deck_id: DeckId,
         card_id: CardId,
     ) -> Result<(), CardServiceError> {
-        self.cards.delete_card(deck_id, card_id).await?;
+        let siblings = self.note_siblings(deck_id, card_id).await?;
+        let now = self.clock.now();
+        self.cards.trash_card(deck_id, card_id, now).await?;
+        if let [last] = siblings.as_slice() {
+            self.cards
+                .upsert_card(&last.clone().with_note_id(None).with_updated_at(now))
+                .await?;
+        }
         Ok(())
     }
-}
 
-fn day_bounds(now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
-    let date = now.date_naive();
-    let start = DateTime::<Utc>::from_naive_utc_and_offset(
-        date.and_hms_opt(0, 0, 0).unwrap(),
-        Utc,
-    );
-    let end = start + Duration::days(1);
-    (start, end)
-}
+    /// Delete a card for good, along with its review history, skipping the trash.
+    ///
+    /// A card left alone in its note is unlinked, as with `delete_card`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if the card is missing or persistence fails.
+    pub async fn delete_card_permanently(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+    ) -> Result<(), CardServiceError> {
+        let siblings = self.note_siblings(deck_id, card_id).await?;
+        self.cards.delete_card(deck_id, card_id).await?;
+        if let [last] = siblings.as_slice() {
+            self.cards
+                .upsert_card(&last.clone().with_note_id(None).with_updated_at(self.clock.now()))
+                .await?;
+        }
+        Ok(())
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Move several cards of one deck to the trash in a single transaction, returning how
+    /// many were removed.
+    ///
+    /// Repeated ids count once. Cards left alone in their notes are unlinked, as with
+    /// `delete_card`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::CardNotInDeck`, changing nothing, if any id is not an active
+    /// card of `deck_id`.
+    /// Returns `CardServiceError::Storage` if persistence fails; the batch is rolled back.
+    pub async fn delete_cards(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+    ) -> Result<u32, CardServiceError> {
+        let cards = self.batch_cards(deck_id, card_ids).await?;
+        let ids: Vec<CardId> = cards.iter().map(Card::id).collect();
+        let now = self.clock.now();
+        self.cards.trash_cards(deck_id, &ids, now).await?;
+
+        let notes: HashSet<NoteId> = cards.iter().filter_map(Card::note_id).collect();
+        for note_id in notes {
+            if let [last] = self.cards.list_note_cards(deck_id, note_id).await?.as_slice() {
+                self.cards
+                    .upsert_card(&last.clone().with_note_id(None).with_updated_at(now))
+                    .await?;
+            }
+        }
+        Ok(u32::try_from(ids.len()).unwrap_or(u32::MAX))
+    }
 
-    use chrono::Duration;
-    use learn_core::model::{Card, CardId, ContentDraft, DeckId, ReviewOutcome, TagName};
-    use learn_core::time::fixed_now;
-    use storage::repository::{CardRepository, InMemoryRepository};
+    /// Suspend (or resume) several cards of one deck in a single transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::CardNotInDeck`, changing nothing, if any id is not an active
+    /// card of `deck_id`.
+    /// Returns `CardServiceError::Storage` if persistence fails; the batch is rolled back.
+    pub async fn set_suspended_many(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+        suspended: bool,
+    ) -> Result<Vec<Card>, CardServiceError> {
+        let now = self.clock.now();
+        let cards: Vec<Card> = self
+            .batch_cards(deck_id, card_ids)
+            .await?
+            .into_iter()
+            .map(|card| card.with_suspended(suspended).with_updated_at(now))
+            .collect();
+        self.cards.upsert_cards(&cards).await?;
+        Ok(cards)
+    }
 
-    fn build_content(text: &str, now: chrono::DateTime<chrono::Utc>) -> learn_core::model::Content {
-        ContentDraft::text_only(text)
-            .validate(now, None, None)
-            .expect("valid content")
+    /// Attach tags to several cards of one deck in a single transaction, creating tags the
+    /// deck does not have yet.
+    ///
+    /// Returns how many cards gained a tag; cards that already carry every tag are not
+    /// counted and keep a single copy of each.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::CardNotInDeck`, changing nothing, if any id is not an active
+    /// card of `deck_id`.
+    /// Returns `CardServiceError::Storage` if persistence fails; the batch is rolled back.
+    pub async fn add_tags_many(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+        tag_names: &[TagName],
+    ) -> Result<u32, CardServiceError> {
+        let cards = self.batch_cards(deck_id, card_ids).await?;
+        let ids: Vec<CardId> = cards.iter().map(Card::id).collect();
+        let tags = dedup_tags(tag_names);
+        if tags.is_empty() {
+            return Ok(0);
+        }
+        let affected = self.cards.add_tags_to_cards(deck_id, &ids, &tags).await?;
+        Ok(u32::try_from(affected).unwrap_or(u32::MAX))
     }
 
-    fn build_card(id: u64, deck_id: DeckId, now: chrono::DateTime<chrono::Utc>) -> Card {
-        let prompt = build_content("Q", now);
-        let answer = build_content("A", now);
-        Card::new(CardId::new(id), deck_id, prompt, answer, now, now).expect("card")
+    /// Detach tags from several cards of one deck in a single transaction.
+    ///
+    /// Returns how many cards lost a tag. Removing a tag a card does not carry, or one the
+    /// deck has never used, changes nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::CardNotInDeck`, changing nothing, if any id is not an active
+    /// card of `deck_id`.
+    /// Returns `CardServiceError::Storage` if persistence fails; the batch is rolled back.
+    pub async fn remove_tags_many(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+        tag_names: &[TagName],
+    ) -> Result<u32, CardServiceError> {
+        let cards = self.batch_cards(deck_id, card_ids).await?;
+        let ids: Vec<CardId> = cards.iter().map(Card::id).collect();
+        let tags = dedup_tags(tag_names);
+        if tags.is_empty() {
+            return Ok(0);
+        }
+        let affected = self.cards.remove_tags_from_cards(deck_id, &ids, &tags).await?;
+        Ok(u32::try_from(affected).unwrap_or(u32::MAX))
     }
 
-    #[tokio::test]
-    async fn deck_practice_stats_counts_due_new_total() {
-        let repo = InMemoryRepository::new();
-        let deck_id = DeckId::new(1);
-        let now = fixed_now();
+    /// The active cards for `card_ids` in `deck_id`, without repeats, in the given order.
+    async fn batch_cards(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+    ) -> Result<Vec<Card>, CardServiceError> {
+        let mut seen = HashSet::new();
+        let ids: Vec<CardId> = card_ids.iter().copied().filter(|id| seen.insert(*id)).collect();
+        match self.cards.get_cards(deck_id, &ids).await {
+            Ok(cards) => Ok(cards),
+            Err(StorageError::NotFound) => {
+                // Name the first missing card so callers can tell what was rejected.
+                for card_id in ids {
+                    match self.cards.get_cards(deck_id, &[card_id]).await {
+                        Ok(_) => {}
+                        Err(StorageError::NotFound) => {
+                            return Err(CardServiceError::CardNotInDeck { card_id, deck_id });
+                        }
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+                Err(StorageError::NotFound.into())
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
 
-        let mut due_card = build_card(1, deck_id, now);
-        due_card.apply_review(
-            &ReviewOutcome::new(
-                now - Duration::hours(2),
-                1.0,
-                1.0,
-                1.0,
-                1.0,
-            ),
-            now - Duration::days(1),
-        );
-        repo.upsert_card(&due_card).await.expect("due card");
+    /// Take a card out of the trash, with its schedule and review history intact.
+    ///
+    /// A restored card whose note has no other cards left is unlinked from it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if the card is not in the deck's trash or
+    /// persistence fails.
+    pub async fn restore_card(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+    ) -> Result<Card, CardServiceError> {
+        self.cards.restore_card(deck_id, card_id).await?;
+        let mut cards = self.cards.get_cards(deck_id, &[card_id]).await?;
+        let Some(card) = cards.pop() else {
+            return Err(StorageError::NotFound.into());
+        };
+        if card.note_id().is_some() && self.note_siblings(deck_id, card_id).await?.is_empty() {
+            let card = card.with_note_id(None).with_updated_at(self.clock.now());
+            self.cards.upsert_card(&card).await?;
+            return Ok(card);
+        }
+        Ok(card)
+    }
+
+    /// A deck's trashed cards, most recently deleted first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if persistence fails.
+    pub async fn list_trash(
+        &self,
+        deck_id: DeckId,
+        limit: u32,
+    ) -> Result<Vec<TrashedCard>, CardServiceError> {
+        Ok(self.cards.list_trashed_cards(deck_id, limit).await?)
+    }
+
+    /// Permanently delete cards that have been in the trash for longer than `older_than`,
+    /// along with their review history. Returns how many cards were deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if persistence fails.
+    pub async fn purge_trash(&self, older_than: Duration) -> Result<u64, CardServiceError> {
+        let cutoff = self.clock.now() - older_than;
+        Ok(self.cards.purge_trashed_cards(cutoff).await?)
+    }
+
+    /// Suspend (or resume) a card without touching its content or schedule.
+    ///
+    /// Suspended cards stay in the deck and the editor list but are left out of
+    /// session planning and due counts.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if the card is missing or persistence fails.
+    pub async fn set_suspended(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        suspended: bool,
+    ) -> Result<Card, CardServiceError> {
+        let mut cards = self.cards.get_cards(deck_id, &[card_id]).await?;
+        let Some(card) = cards.pop() else {
+            return Err(StorageError::NotFound.into());
+        };
+        let card = card.with_suspended(suspended).with_updated_at(self.clock.now());
+        self.cards.upsert_card(&card).await?;
+        Ok(card)
+    }
+
+    /// Set or clear a card's flag without touching its content, schedule, or tags.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if the card is missing or persistence fails.
+    pub async fn set_flag(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        flag: Option<Flag>,
+    ) -> Result<Card, CardServiceError> {
+        let mut cards = self.cards.get_cards(deck_id, &[card_id]).await?;
+        let Some(card) = cards.pop() else {
+            return Err(StorageError::NotFound.into());
+        };
+        let card = card.with_flag(flag).with_updated_at(self.clock.now());
+        self.cards.upsert_card(&card).await?;
+        Ok(card)
+    }
+
+    /// Move a card to another deck, keeping its scheduling state, review history and tags.
+    ///
+    /// The card's FSRS memory state is unchanged, but from now on it counts against the
+    /// destination deck's daily limits. A card moved out of a note is unlinked from it, and
+    /// a sibling left alone in the note is unlinked too.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::CardNotInDeck` if the card is not in `from_deck`.
+    /// Returns `CardServiceError::DeckNotFound` if `to_deck` does not exist.
+    /// Returns `CardServiceError::Storage` if persistence fails.
+    pub async fn move_card(
+        &self,
+        card_id: CardId,
+        from_deck: DeckId,
+        to_deck: DeckId,
+    ) -> Result<(), CardServiceError> {
+        let card = match self.cards.get_cards(from_deck, &[card_id]).await {
+            Ok(mut cards) => cards.pop(),
+            Err(StorageError::NotFound) => None,
+            Err(err) => return Err(err.into()),
+        };
+        let Some(card) = card else {
+            return Err(CardServiceError::CardNotInDeck {
+                card_id,
+                deck_id: from_deck,
+            });
+        };
+        let mut siblings = match card.note_id() {
+            Some(note_id) => self.cards.list_note_cards(from_deck, note_id).await?,
+            None => Vec::new(),
+        };
+        siblings.retain(|sibling| sibling.id() != card_id);
+
+        match self.cards.move_card(card_id, from_deck, to_deck).await {
+            Ok(()) => {}
+            Err(StorageError::NotFound) => return Err(CardServiceError::DeckNotFound(to_deck)),
+            Err(err) => return Err(err.into()),
+        }
+        if from_deck != to_deck
+            && let [last] = siblings.as_slice()
+        {
+            let last = last.clone().with_note_id(None);
+            self.cards
+                .upsert_card(&last.with_updated_at(self.clock.now()))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Move every card of a note to the trash, returning how many were removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if persistence fails.
+    pub async fn delete_note(
+        &self,
+        deck_id: DeckId,
+        note_id: NoteId,
+    ) -> Result<u32, CardServiceError> {
+        let cards = self.cards.list_note_cards(deck_id, note_id).await?;
+        let now = self.clock.now();
+        for card in &cards {
+            self.cards.trash_card(deck_id, card.id(), now).await?;
+        }
+        Ok(u32::try_from(cards.len()).unwrap_or(u32::MAX))
+    }
+
+    /// Other cards sharing a note with `card_id`; empty if it has none.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CardServiceError::Storage` if persistence fails.
+    pub async fn note_siblings(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+    ) -> Result<Vec<Card>, CardServiceError> {
+        let mut cards = self.cards.get_cards(deck_id, &[card_id]).await?;
+        let Some(note_id) = cards.pop().and_then(|card| card.note_id()) else {
+            return Ok(Vec::new());
+        };
+        let mut siblings = self.cards.list_note_cards(deck_id, note_id).await?;
+        siblings.retain(|card| card.id() != card_id);
+        Ok(siblings)
+    }
+}
+
+fn day_bounds(now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let date = now.date_naive();
+    let start = DateTime::<Utc>::from_naive_utc_and_offset(
+        date.and_hms_opt(0, 0, 0).unwrap(),
+        Utc,
+    );
+    let end = start + Duration::days(1);
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Duration;
+    use learn_core::model::{
+        Card, CardId, ContentDraft, DeckId, DeckSettings, MediaValidationError, ReviewGrade,
+        ReviewOutcome, TagName,
+    };
+    use learn_core::time::fixed_now;
+    use storage::repository::{CardRepository, InMemoryRepository};
+
+    use crate::test_support::ServicesFixture;
+
+    fn build_content(text: &str, now: chrono::DateTime<chrono::Utc>) -> learn_core::model::Content {
+        ContentDraft::text_only(text)
+            .validate(now, None, None)
+            .expect("valid content")
+    }
+
+    fn build_card(id: u64, deck_id: DeckId, now: chrono::DateTime<chrono::Utc>) -> Card {
+        let prompt = build_content("Q", now);
+        let answer = build_content("A", now);
+        Card::new(
+            CardId::new(id),
+            deck_id,
+            CardKind::Basic,
+            prompt,
+            answer,
+            now,
+            now,
+        )
+        .expect("card")
+    }
+
+    #[tokio::test]
+    async fn deck_practice_stats_counts_due_new_total() {
+        let repo = InMemoryRepository::new();
+        let deck_id = DeckId::new(1);
+        let now = fixed_now();
+
+        let mut due_card = build_card(1, deck_id, now);
+        due_card.apply_review(
+            &ReviewOutcome::new(
+                now - Duration::hours(2),
+                1.0,
+                1.0,
+                1.0,
+                1.0,
+            ),
+            now - Duration::days(1),
+        );
+        repo.upsert_card(&due_card).await.expect("due card");
 
         let mut future_card = build_card(2, deck_id, now);
         future_card.apply_review(
@@ -697,4 +2101,1360 @@ mod tests {
 
         assert_eq!(count, 1);
     }
+
+    #[tokio::test]
+    async fn writes_stamp_updated_at_and_keep_created_at() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let deck_id = fixture.deck_id();
+        let created = fixture.clock().now();
+        let card_id = fixture
+            .card_service()
+            .create_card(
+                deck_id,
+                ContentDraft::text_only("Hund"),
+                ContentDraft::text_only("Dog"),
+                None,
+            )
+            .await
+            .unwrap()
+            .id;
+        let cards = &fixture.storage().cards;
+        let stored = cards.get_cards(deck_id, &[card_id]).await.unwrap();
+        assert_eq!(stored[0].created_at(), created);
+        assert_eq!(stored[0].updated_at(), created);
+
+        let later = created + Duration::hours(2);
+        CardService::new(Clock::Fixed(later), Arc::clone(cards))
+            .update_card_content(
+                deck_id,
+                card_id,
+                ContentDraft::text_only("Hund"),
+                ContentDraft::text_only("The dog"),
+            )
+            .await
+            .unwrap();
+        let stored = cards.get_cards(deck_id, &[card_id]).await.unwrap();
+        assert_eq!(stored[0].created_at(), created);
+        assert_eq!(stored[0].updated_at(), later);
+    }
+
+    #[tokio::test]
+    async fn list_tags_for_cards_groups_names_by_card() {
+        let fixture = ServicesFixture::builder().with_cards(3).build().await.unwrap();
+        let service = fixture.card_service();
+        let ids = fixture.card_ids();
+        let verbs = TagName::new("Verbs").unwrap();
+        let nouns = TagName::new("Nouns").unwrap();
+        service
+            .set_tags_for_card(fixture.deck_id(), ids[0], &[verbs.clone(), nouns.clone()])
+            .await
+            .unwrap();
+        service
+            .set_tags_for_card(fixture.deck_id(), ids[1], std::slice::from_ref(&verbs))
+            .await
+            .unwrap();
+        let extra = service
+            .upsert_tag(fixture.deck_id(), &TagName::new("Spare").unwrap())
+            .await
+            .unwrap();
+
+        let by_card = service
+            .list_tags_for_cards(fixture.deck_id(), &ids)
+            .await
+            .unwrap();
+
+        assert_eq!(by_card.get(&ids[0]), Some(&vec![nouns, verbs.clone()]));
+        assert_eq!(by_card.get(&ids[1]), Some(&vec![verbs]));
+        assert!(!by_card.contains_key(&ids[2]));
+        let deck_tags = service.list_tags_for_deck(fixture.deck_id()).await.unwrap();
+        assert!(deck_tags.iter().any(|tag| tag.id() == extra.id()));
+    }
+
+    #[tokio::test]
+    async fn list_cards_page_windows_the_recent_list() {
+        let repo = InMemoryRepository::new();
+        let deck_id = DeckId::new(1);
+        let now = fixed_now();
+        for id in 1..=5 {
+            let created = now + Duration::minutes(i64::try_from(id).unwrap());
+            repo.upsert_card(&build_card(id, deck_id, created)).await.unwrap();
+        }
+        repo.upsert_card(&build_card(6, DeckId::new(2), now)).await.unwrap();
+        let service = CardService::new(Clock::Fixed(now), Arc::new(repo));
+
+        let ids = |page: &CardPage| {
+            page.cards.iter().map(|card| card.id().value()).collect::<Vec<_>>()
+        };
+        let first = service.list_cards_page(deck_id, 0, 2).await.unwrap();
+        let second = service.list_cards_page(deck_id, 2, 2).await.unwrap();
+        let last = service.list_cards_page(deck_id, 4, 2).await.unwrap();
+        let past_end = service.list_cards_page(deck_id, 9, 2).await.unwrap();
+
+        assert_eq!(ids(&first), vec![5, 4]);
+        assert_eq!(ids(&second), vec![3, 2]);
+        assert_eq!(ids(&last), vec![1]);
+        assert!(past_end.cards.is_empty());
+        assert_eq!(first.total, 5);
+        assert!(first.has_more(0) && second.has_more(2));
+        assert!(!last.has_more(4) && !past_end.has_more(9));
+    }
+
+    #[tokio::test]
+    async fn list_cards_sorts_by_due_date_and_difficulty_with_new_cards_last() {
+        let repo = InMemoryRepository::new();
+        let deck_id = DeckId::new(1);
+        let now = fixed_now();
+
+        repo.upsert_card(&build_card(1, deck_id, now)).await.unwrap();
+        for (id, due_hours, difficulty) in [(2, 30, 3.0), (3, -2, 6.5), (4, 10, 8.0)] {
+            let mut card = build_card(id, deck_id, now);
+            card.apply_review(
+                &ReviewOutcome::new(now + Duration::hours(due_hours), 1.0, difficulty, 1.0, 1.0),
+                now - Duration::days(1),
+            );
+            repo.upsert_card(&card).await.unwrap();
+        }
+        repo.upsert_card(&build_card(5, deck_id, now)).await.unwrap();
+        let service = CardService::new(Clock::Fixed(now), Arc::new(repo));
+        let tag = TagName::new("Verbs").unwrap();
+        for id in [1, 3, 4] {
+            service
+                .set_tags_for_card(deck_id, CardId::new(id), std::slice::from_ref(&tag))
+                .await
+                .unwrap();
+        }
+
+        let list = |sort: CardListSort, tags: Vec<TagName>| {
+            let service = service.clone();
+            async move {
+                let cards = service
+                    .list_cards_filtered(deck_id, 10, sort, CardListFilter::All, &tags)
+                    .await
+                    .unwrap();
+                cards.iter().map(|card| card.id().value()).collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(list(CardListSort::DueDate, Vec::new()).await, vec![3, 4, 2, 1, 5]);
+        assert_eq!(list(CardListSort::Difficulty, Vec::new()).await, vec![4, 3, 2, 1, 5]);
+        assert_eq!(list(CardListSort::DueDate, vec![tag.clone()]).await, vec![3, 4, 1]);
+        assert_eq!(list(CardListSort::Difficulty, vec![tag]).await, vec![4, 3, 1]);
+    }
+
+    #[tokio::test]
+    async fn list_cards_filtered_by_tag_sets() {
+        let fixture = ServicesFixture::builder().with_cards(3).build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let ids = fixture.card_ids();
+        let verbs = TagName::new("Verbs").unwrap();
+        let nouns = TagName::new("Nouns").unwrap();
+        service
+            .set_tags_for_card(deck_id, ids[0], &[verbs.clone(), nouns.clone()])
+            .await
+            .unwrap();
+        service
+            .set_tags_for_card(deck_id, ids[1], std::slice::from_ref(&verbs))
+            .await
+            .unwrap();
+
+        let list = |filter: CardListFilter| {
+            let service = service.clone();
+            async move {
+                let cards = service
+                    .list_cards_filtered(deck_id, 10, CardListSort::Created, filter, &[])
+                    .await
+                    .unwrap();
+                cards.iter().map(Card::id).collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(list(CardListFilter::WithAllTags(Vec::new())).await, ids);
+        assert_eq!(
+            list(CardListFilter::WithAllTags(vec![verbs.clone(), nouns.clone()])).await,
+            vec![ids[0]]
+        );
+        assert_eq!(
+            list(CardListFilter::WithAllTags(vec![verbs.clone()])).await,
+            vec![ids[0], ids[1]]
+        );
+        assert!(
+            list(CardListFilter::WithAllTags(vec![
+                verbs.clone(),
+                TagName::new("Missing").unwrap(),
+            ]))
+            .await
+            .is_empty()
+        );
+        assert_eq!(list(CardListFilter::Untagged).await, vec![ids[2]]);
+
+        let any_nouns = service
+            .list_cards_filtered(
+                deck_id,
+                10,
+                CardListSort::Created,
+                CardListFilter::WithAllTags(vec![verbs]),
+                std::slice::from_ref(&nouns),
+            )
+            .await
+            .unwrap();
+        assert_eq!(any_nouns.iter().map(Card::id).collect::<Vec<_>>(), vec![ids[0]]);
+    }
+
+    #[tokio::test]
+    async fn flags_are_single_valued_and_filter_the_list() {
+        let fixture = ServicesFixture::builder().with_cards(3).build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let ids = fixture.card_ids();
+        service.set_flag(deck_id, ids[0], Some(Flag::Red)).await.unwrap();
+        service.set_flag(deck_id, ids[2], Some(Flag::Green)).await.unwrap();
+        let card = service.set_flag(deck_id, ids[2], Some(Flag::Blue)).await.unwrap();
+        assert_eq!(card.flag(), Some(Flag::Blue));
+
+        let flagged = service
+            .list_cards_filtered(deck_id, 10, CardListSort::Created, CardListFilter::Flagged, &[])
+            .await
+            .unwrap();
+        let flags: Vec<_> = flagged.iter().map(|card| (card.id(), card.flag())).collect();
+        assert_eq!(flags, vec![(ids[0], Some(Flag::Red)), (ids[2], Some(Flag::Blue))]);
+
+        service.set_flag(deck_id, ids[0], None).await.unwrap();
+        let flagged = service
+            .list_cards_filtered(deck_id, 1, CardListSort::Created, CardListFilter::Flagged, &[])
+            .await
+            .unwrap();
+        assert_eq!(flagged.iter().map(Card::id).collect::<Vec<_>>(), vec![ids[2]]);
+
+        let err = service.set_flag(deck_id, CardId::new(999), Some(Flag::Red)).await.unwrap_err();
+        assert!(matches!(err, CardServiceError::Storage(StorageError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn each_creation_path_stamps_its_source_and_the_list_filters_by_it() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+
+        let typed = service
+            .create_card(
+                deck_id,
+                ContentDraft::text_only("Hund"),
+                ContentDraft::text_only("Dog"),
+                None,
+            )
+            .await
+            .unwrap()
+            .id;
+        let cloze = service
+            .create_cloze_cards(deck_id, ContentDraft::text_only("{{c1::Berlin}} lies east"), &[])
+            .await
+            .unwrap();
+        service
+            .import_delimited(deck_id, "Katze,Cat\n".as_bytes(), ',')
+            .await
+            .unwrap();
+        let assisted = service
+            .create_card_returning(
+                deck_id,
+                ContentDraft::text_only("Maus"),
+                ContentDraft::text_only("Mouse"),
+                None,
+                &[],
+                CardSource::Ai,
+            )
+            .await
+            .unwrap();
+        let note = service
+            .create_card_with_options(
+                deck_id,
+                ContentDraft::text_only("Vogel"),
+                ContentDraft::text_only("Bird"),
+                &[],
+                CreateCardOptions {
+                    generate_reverse: true,
+                    source: CardSource::Ai,
+                },
+            )
+            .await
+            .unwrap();
+
+        let sources: HashMap<_, _> = service
+            .list_cards(deck_id, 10)
+            .await
+            .unwrap()
+            .iter()
+            .map(|card| (card.prompt().text().to_owned(), card.source()))
+            .collect();
+        assert_eq!(sources["Hund"], Some(CardSource::Manual));
+        assert_eq!(sources["Katze"], Some(CardSource::Csv));
+        assert_eq!(sources["Maus"], Some(CardSource::Ai));
+        assert_eq!(sources["Vogel"], Some(CardSource::Ai));
+        assert_eq!(sources["Bird"], Some(CardSource::Ai));
+        assert_eq!(assisted.source(), Some(CardSource::Ai));
+        let cloze = fixture.storage().cards.get_cards(deck_id, &cloze).await.unwrap();
+        assert_eq!(cloze[0].source(), Some(CardSource::Manual));
+
+        let list = |source| {
+            service.list_cards_filtered(
+                deck_id,
+                10,
+                CardListSort::Created,
+                CardListFilter::Source(source),
+                &[],
+            )
+        };
+        let ai: Vec<_> = list(CardSource::Ai).await.unwrap().iter().map(Card::id).collect();
+        assert_eq!(ai, vec![assisted.id(), note.id, note.reverse_id.unwrap()]);
+        let csv = list(CardSource::Csv).await.unwrap();
+        assert_eq!(csv.len(), 1);
+        assert_eq!(csv[0].prompt().text(), "Katze");
+        let manual: Vec<_> = list(CardSource::Manual).await.unwrap().iter().map(Card::id).collect();
+        assert_eq!(manual, vec![typed, cloze[0].id()]);
+        assert!(list(CardSource::Anki).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_cards_matches_word_prefixes_across_prompt_and_answer() {
+        let (service, deck_id, first) = service_with_front("Photosynthesis").await;
+        let second = service
+            .create_card(
+                deck_id,
+                ContentDraft::text_only("Mitochondria"),
+                ContentDraft::text_only("Powerhouse of the photo cell"),
+                None,
+            )
+            .await
+            .unwrap()
+            .id;
+
+        let found = service.search_cards(deck_id, "PHOTO", 10).await.unwrap();
+        assert_eq!(found.len(), 2);
+        let found = service.search_cards(deck_id, "power mito", 10).await.unwrap();
+        assert_eq!(found.iter().map(Card::id).collect::<Vec<_>>(), vec![second]);
+        assert!(service.search_cards(deck_id, "synthesis", 10).await.unwrap().is_empty());
+
+        let all = service.search_cards(deck_id, "   ", 10).await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert!(all.iter().any(|card| card.id() == first));
+    }
+
+    #[tokio::test]
+    async fn search_all_decks_groups_matches_by_deck() {
+        let fixture = ServicesFixture::builder()
+            .with_deck("Biology", DeckSettings::default_for_adhd())
+            .build()
+            .await
+            .unwrap();
+        let service = fixture.card_service();
+        let biology = fixture.deck_id();
+        let physics = fixture
+            .deck_service()
+            .create_deck("Physics".to_string(), None, DeckSettings::default_for_adhd())
+            .await
+            .unwrap();
+        let create = async |deck_id, front: &str, back: &str| {
+            service
+                .create_card(
+                    deck_id,
+                    ContentDraft::text_only(front),
+                    ContentDraft::text_only(back),
+                    None,
+                )
+                .await
+                .unwrap()
+                .id
+        };
+        let photosynthesis = create(biology, "Photosynthesis", "Light into sugar").await;
+        create(biology, "Mitochondria", "Powerhouse of the cell").await;
+        let photon = create(physics, "Photon", "A quantum of light").await;
+
+        let groups = service.search_all_decks("photo", 10).await.unwrap();
+        let mut labeled: Vec<_> = groups
+            .iter()
+            .map(|group| {
+                let ids: Vec<CardId> = group.cards.iter().map(Card::id).collect();
+                (group.deck_id, group.deck_name.as_str(), ids)
+            })
+            .collect();
+        labeled.sort_by_key(|(deck_id, ..)| deck_id.value());
+        assert_eq!(
+            labeled,
+            vec![(biology, "Biology", vec![photosynthesis]), (physics, "Physics", vec![photon])]
+        );
+
+        let light = service.search_all_decks("light", 1).await.unwrap();
+        assert_eq!(light.iter().map(|group| group.cards.len()).sum::<usize>(), 1);
+        assert!(service.search_all_decks("synthesis", 10).await.unwrap().is_empty());
+        assert!(service.search_all_decks("  ", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn create_cloze_cards_makes_one_schedulable_card_per_index() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+
+        let ids = service
+            .create_cloze_cards(
+                deck_id,
+                ContentDraft::text_only("{{c1::Paris}} is the capital of {{c2::France}}"),
+                &[],
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids.len(), 2);
+
+        let cards = fixture.storage().cards.get_cards(deck_id, &ids).await.unwrap();
+        assert_eq!(cards[0].prompt().text(), "[...] is the capital of France");
+        assert_eq!(cards[1].prompt().text(), "Paris is the capital of [...]");
+        assert!(matches!(cards[1].kind(), CardKind::Cloze { indices, .. } if indices == &[2]));
+        assert!(cards.iter().all(Card::is_new));
+
+        let err = service
+            .create_cloze_cards(deck_id, ContentDraft::text_only("{{c1::open"), &[])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CardServiceError::Card(CardError::InvalidCloze(_))));
+    }
+
+    #[tokio::test]
+    async fn create_card_returning_matches_a_later_fetch() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let tag = TagName::new("Vocab").unwrap();
+
+        let created = service
+            .create_card_returning(
+                deck_id,
+                ContentDraft::text_only("Hund"),
+                ContentDraft::text_only("Dog"),
+                None,
+                std::slice::from_ref(&tag),
+                CardSource::Manual,
+            )
+            .await
+            .unwrap();
+
+        let fetched = fixture
+            .storage()
+            .cards
+            .get_cards(deck_id, &[created.id()])
+            .await
+            .unwrap();
+        assert_eq!(fetched, vec![created.clone()]);
+        assert!(created.is_new());
+        assert_eq!(created.prompt().text(), "Hund");
+        let tags = service.list_tags_for_card(deck_id, created.id()).await.unwrap();
+        assert_eq!(tags.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn extra_info_survives_content_edits_until_cleared() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+
+        let created = service
+            .create_card_returning(
+                deck_id,
+                ContentDraft::text_only("Hund"),
+                ContentDraft::text_only("Dog"),
+                Some(ContentDraft::text_only("/hʊnt/, der Hund")),
+                &[],
+                CardSource::Manual,
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.extra().map(Content::text), Some("/hʊnt/, der Hund"));
+
+        service
+            .update_card_content(
+                deck_id,
+                created.id(),
+                ContentDraft::text_only("der Hund"),
+                ContentDraft::text_only("the dog"),
+            )
+            .await
+            .unwrap();
+        let edited = fixture.storage().cards.get_cards(deck_id, &[created.id()]).await.unwrap();
+        assert_eq!(edited[0].extra(), created.extra());
+
+        service
+            .update_card_extra(deck_id, created.id(), Some(ContentDraft::text_only("  ")))
+            .await
+            .unwrap();
+        let cleared = fixture.storage().cards.get_cards(deck_id, &[created.id()]).await.unwrap();
+        assert!(cleared[0].extra().is_none());
+    }
+
+    #[test]
+    fn replace_matches_scans_left_to_right_without_overlap() {
+        let exact = ReplaceTextOptions {
+            case_sensitive: true,
+            whole_word: false,
+        };
+        assert_eq!(replace_matches("aaaa", "aa", "b", exact), ("bb".to_owned(), 2));
+        assert_eq!(replace_matches("aaa", "aa", "b", exact), ("ba".to_owned(), 1));
+        // Inserted text is never searched again.
+        assert_eq!(replace_matches("a-a", "a", "aa", exact), ("aa-aa".to_owned(), 2));
+        assert_eq!(replace_matches("Hund", "hund", "x", exact), ("Hund".to_owned(), 0));
+
+        let loose = ReplaceTextOptions::default();
+        assert_eq!(
+            replace_matches("Über über", "ÜBER", "over", loose),
+            ("over over".to_owned(), 2)
+        );
+
+        let words = ReplaceTextOptions {
+            case_sensitive: false,
+            whole_word: true,
+        };
+        assert_eq!(
+            replace_matches("Hund, Hunde, hund_x (hund)", "hund", "dog", words),
+            ("dog, Hunde, hund_x (dog)".to_owned(), 2)
+        );
+    }
+
+    #[tokio::test]
+    async fn replace_text_rewrites_matching_cards_in_one_pass() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+
+        let first = service
+            .create_card_returning(
+                deck_id,
+                ContentDraft::text_only("der Hund"),
+                ContentDraft::text_only("the dog"),
+                Some(ContentDraft::text_only("masculine")),
+                &[],
+                CardSource::Manual,
+            )
+            .await
+            .unwrap();
+        let second = service
+            .create_card(
+                deck_id,
+                ContentDraft::text_only("die Hunde"),
+                ContentDraft::text_only("the dogs"),
+                None,
+            )
+            .await
+            .unwrap()
+            .id;
+        let options = ReplaceTextOptions {
+            case_sensitive: false,
+            whole_word: true,
+        };
+
+        let preview = service
+            .preview_replace_text(deck_id, "THE", "a", options)
+            .await
+            .unwrap();
+        assert_eq!(
+            preview,
+            ReplaceTextReport {
+                matches: 2,
+                cards_changed: 2,
+            }
+        );
+        let untouched = fixture.storage().cards.get_cards(deck_id, &[first.id()]).await.unwrap();
+        assert_eq!(untouched[0].answer().text(), "the dog");
+
+        let report = service
+            .replace_text(deck_id, "hund", "Katze", options)
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            ReplaceTextReport {
+                matches: 1,
+                cards_changed: 1,
+            }
+        );
+        let cards = fixture
+            .storage()
+            .cards
+            .get_cards(deck_id, &[first.id(), second])
+            .await
+            .unwrap();
+        let by_id = |id: CardId| cards.iter().find(|card| card.id() == id).unwrap();
+        assert_eq!(by_id(first.id()).prompt().text(), "der Katze");
+        assert_eq!(by_id(first.id()).extra(), first.extra());
+        assert_eq!(by_id(second).prompt().text(), "die Hunde");
+    }
+
+    #[tokio::test]
+    async fn replace_text_skips_no_op_replacements_and_rejects_empty_sides() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let card_id = service
+            .create_card(
+                deck_id,
+                ContentDraft::text_only("Hund"),
+                ContentDraft::text_only("dog"),
+                None,
+            )
+            .await
+            .unwrap()
+            .id;
+
+        let same = service
+            .replace_text(deck_id, "Hund", "Hund", ReplaceTextOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            same,
+            ReplaceTextReport {
+                matches: 1,
+                cards_changed: 0,
+            }
+        );
+
+        let err = service
+            .replace_text(deck_id, "hund", " ", ReplaceTextOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CardServiceError::Card(CardError::InvalidPrompt(_))));
+        let stored = fixture.storage().cards.get_cards(deck_id, &[card_id]).await.unwrap();
+        assert_eq!(stored[0].prompt().text(), "Hund");
+
+        let err = service
+            .replace_text(deck_id, "", "x", ReplaceTextOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CardServiceError::EmptySearch));
+    }
+
+    #[tokio::test]
+    async fn create_card_with_reverse_links_both_directions() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let tag = TagName::new("Vocab").unwrap();
+
+        let created = service
+            .create_card_with_options(
+                deck_id,
+                ContentDraft::text_only("Hund"),
+                ContentDraft::text_only("Dog"),
+                std::slice::from_ref(&tag),
+                CreateCardOptions {
+                    generate_reverse: true,
+                    ..CreateCardOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+        let reverse_id = created.reverse_id.expect("reverse card");
+        assert_eq!(created.note_id, Some(NoteId::new(created.id.value())));
+
+        let cards = fixture
+            .storage()
+            .cards
+            .get_cards(deck_id, &[created.id, reverse_id])
+            .await
+            .unwrap();
+        assert!(cards.iter().all(|card| card.note_id() == created.note_id));
+        assert_eq!(cards[1].prompt().text(), "Dog");
+        assert_eq!(cards[1].answer().text(), "Hund");
+        let tags = service.list_tags_for_card(deck_id, reverse_id).await.unwrap();
+        assert_eq!(tags.len(), 1);
+
+        service
+            .update_card_and_reverse(
+                deck_id,
+                reverse_id,
+                ContentDraft::text_only("Big dog"),
+                ContentDraft::text_only("Großer Hund"),
+            )
+            .await
+            .unwrap();
+        let siblings = service.note_siblings(deck_id, reverse_id).await.unwrap();
+        assert_eq!(siblings.len(), 1);
+        assert_eq!(siblings[0].prompt().text(), "Großer Hund");
+        assert_eq!(siblings[0].answer().text(), "Big dog");
+
+        let single = service
+            .create_card_with_options(
+                deck_id,
+                ContentDraft::text_only("Katze"),
+                ContentDraft::text_only("Cat"),
+                &[],
+                CreateCardOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(single.reverse_id, None);
+        assert_eq!(single.note_id, None);
+    }
+
+    #[tokio::test]
+    async fn deleting_one_direction_keeps_and_unlinks_the_other() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let options = CreateCardOptions {
+            generate_reverse: true,
+            ..CreateCardOptions::default()
+        };
+
+        let first = service
+            .create_card_with_options(
+                deck_id,
+                ContentDraft::text_only("Haus"),
+                ContentDraft::text_only("House"),
+                &[],
+                options,
+            )
+            .await
+            .unwrap();
+        service.delete_card(deck_id, first.id).await.unwrap();
+        let left = fixture
+            .storage()
+            .cards
+            .get_cards(deck_id, &[first.reverse_id.unwrap()])
+            .await
+            .unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].note_id(), None);
+
+        let second = service
+            .create_card_with_options(
+                deck_id,
+                ContentDraft::text_only("Baum"),
+                ContentDraft::text_only("Tree"),
+                &[],
+                options,
+            )
+            .await
+            .unwrap();
+        let removed = service
+            .delete_note(deck_id, second.note_id.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(removed, 2);
+        assert_eq!(service.list_cards(deck_id, 10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn deleted_cards_wait_in_the_trash_with_their_history_until_restored() {
+        let fixture = ServicesFixture::builder()
+            .with_cards(2)
+            .with_reviews([ReviewGrade::Good])
+            .build()
+            .await
+            .unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let card_id = fixture.card_ids()[0];
+        let logs = fixture.storage().review_logs.logs_for_card(deck_id, card_id).await.unwrap();
+        assert_eq!(logs.len(), 1);
+
+        service.delete_card(deck_id, card_id).await.unwrap();
+        let listed = service.list_cards(deck_id, 10).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_ne!(listed[0].id(), card_id);
+        assert_eq!(service.deck_practice_stats(deck_id).await.unwrap().total, 1);
+        let trash = service.list_trash(deck_id, 10).await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].card.id(), card_id);
+        assert_eq!(trash[0].deleted_at, fixture.clock().now());
+        assert!(matches!(
+            service.delete_card(deck_id, card_id).await,
+            Err(CardServiceError::Storage(StorageError::NotFound))
+        ));
+
+        let restored = service.restore_card(deck_id, card_id).await.unwrap();
+        assert_eq!(restored.review_count(), 1);
+        assert_eq!(service.list_cards(deck_id, 10).await.unwrap().len(), 2);
+        assert!(service.list_trash(deck_id, 10).await.unwrap().is_empty());
+        let kept = fixture.storage().review_logs.logs_for_card(deck_id, card_id).await.unwrap();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].id, logs[0].id);
+        assert!(service.restore_card(deck_id, card_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn permanent_deletes_skip_the_trash_and_drop_history() {
+        let fixture = ServicesFixture::builder()
+            .with_cards(2)
+            .with_reviews([ReviewGrade::Good])
+            .build()
+            .await
+            .unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let card_id = fixture.card_ids()[0];
+
+        service.delete_card_permanently(deck_id, card_id).await.unwrap();
+
+        assert_eq!(service.list_cards(deck_id, 10).await.unwrap().len(), 1);
+        assert!(service.list_trash(deck_id, 10).await.unwrap().is_empty());
+        let logs = fixture.storage().review_logs.logs_for_card(deck_id, card_id).await.unwrap();
+        assert!(logs.is_empty());
+        assert!(service.delete_card_permanently(deck_id, card_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn purging_the_trash_only_removes_cards_past_the_retention_window() {
+        let fixture = ServicesFixture::builder()
+            .with_cards(2)
+            .with_reviews([ReviewGrade::Good])
+            .build()
+            .await
+            .unwrap();
+        let deck_id = fixture.deck_id();
+        let [old, recent] = fixture.card_ids()[..] else {
+            panic!("expected two cards");
+        };
+        let start = fixture.clock().now();
+        fixture.card_service().delete_card(deck_id, old).await.unwrap();
+        let later = CardService::new(
+            Clock::fixed(start + Duration::days(10)),
+            Arc::clone(&fixture.storage().cards),
+        );
+        later.delete_card(deck_id, recent).await.unwrap();
+
+        let within = CardService::new(
+            Clock::fixed(start + CardService::TRASH_RETENTION),
+            Arc::clone(&fixture.storage().cards),
+        );
+        assert_eq!(within.purge_trash(CardService::TRASH_RETENTION).await.unwrap(), 0);
+
+        let after = CardService::new(
+            Clock::fixed(start + CardService::TRASH_RETENTION + Duration::days(1)),
+            Arc::clone(&fixture.storage().cards),
+        );
+        assert_eq!(after.purge_trash(CardService::TRASH_RETENTION).await.unwrap(), 1);
+        let trash = after.list_trash(deck_id, 10).await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].card.id(), recent);
+        assert!(after.restore_card(deck_id, old).await.is_err());
+        let logs = fixture.storage().review_logs.logs_for_card(deck_id, old).await.unwrap();
+        assert!(logs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn batch_delete_and_suspend_apply_to_every_card_of_one_deck() {
+        let fixture = ServicesFixture::builder().with_cards(3).build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let ids = fixture.card_ids();
+
+        let suspended = service
+            .set_suspended_many(deck_id, &[ids[0], ids[1], ids[0]], true)
+            .await
+            .unwrap();
+        assert_eq!(suspended.len(), 2);
+        let stored = fixture.storage().cards.get_cards(deck_id, &ids).await.unwrap();
+        let flags: Vec<bool> = stored.iter().map(Card::is_suspended).collect();
+        assert_eq!(flags, vec![true, true, false]);
+        service.set_suspended_many(deck_id, &ids[..2], false).await.unwrap();
+        let stored = fixture.storage().cards.get_cards(deck_id, &ids).await.unwrap();
+        assert!(stored.iter().all(|card| !card.is_suspended()));
+
+        assert_eq!(service.delete_cards(deck_id, &ids[1..]).await.unwrap(), 2);
+        let left = service.list_cards(deck_id, 10).await.unwrap();
+        assert_eq!(left.iter().map(Card::id).collect::<Vec<_>>(), vec![ids[0]]);
+        assert_eq!(service.list_trash(deck_id, 10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn add_tags_many_counts_only_cards_that_gain_a_tag() {
+        let fixture = ServicesFixture::builder().with_cards(3).build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let ids = fixture.card_ids();
+        let verb = TagName::new("verb").unwrap();
+        let irregular = TagName::new("irregular").unwrap();
+        service.set_tags_for_card(deck_id, ids[0], std::slice::from_ref(&verb)).await.unwrap();
+
+        let added = service
+            .add_tags_many(deck_id, &ids[..2], &[verb.clone(), verb.clone()])
+            .await
+            .unwrap();
+        assert_eq!(added, 1);
+        let again = service.add_tags_many(deck_id, &ids[..2], std::slice::from_ref(&verb)).await;
+        assert_eq!(again.unwrap(), 0);
+        let tags = service.list_tags_for_cards(deck_id, &ids).await.unwrap();
+        assert_eq!(tags.get(&ids[0]), Some(&vec![verb.clone()]));
+        assert_eq!(tags.get(&ids[1]), Some(&vec![verb.clone()]));
+        assert_eq!(tags.get(&ids[2]), None);
+
+        let added = service
+            .add_tags_many(deck_id, &ids, std::slice::from_ref(&irregular))
+            .await
+            .unwrap();
+        assert_eq!(added, 3);
+        let deck_tags = service.list_tags_for_deck(deck_id).await.unwrap();
+        assert_eq!(deck_tags.iter().filter(|tag| tag.name() == &irregular).count(), 1);
+    }
+
+    #[tokio::test]
+    async fn remove_tags_many_ignores_tags_cards_do_not_carry() {
+        let fixture = ServicesFixture::builder().with_cards(2).build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let ids = fixture.card_ids();
+        let verb = TagName::new("verb").unwrap();
+        let noun = TagName::new("noun").unwrap();
+        service.set_tags_for_card(deck_id, ids[0], std::slice::from_ref(&verb)).await.unwrap();
+
+        let removed = service
+            .remove_tags_many(deck_id, &ids, std::slice::from_ref(&noun))
+            .await
+            .unwrap();
+        assert_eq!(removed, 0);
+        let tags = service.list_tags_for_cards(deck_id, &ids).await.unwrap();
+        assert_eq!(tags.get(&ids[0]), Some(&vec![verb.clone()]));
+
+        let removed = service
+            .remove_tags_many(deck_id, &ids, &[verb.clone(), noun])
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert!(service.list_tags_for_cards(deck_id, &ids).await.unwrap().is_empty());
+        // The tag stays available in the deck for later use.
+        let deck_tags = service.list_tags_for_deck(deck_id).await.unwrap();
+        assert!(deck_tags.iter().any(|tag| tag.name() == &verb));
+    }
+
+    #[tokio::test]
+    async fn batch_actions_reject_ids_from_other_decks_without_changing_anything() {
+        let fixture = ServicesFixture::builder().with_cards(2).build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let ids = fixture.card_ids();
+        let other = fixture
+            .deck_service()
+            .create_deck("Other".to_string(), None, DeckSettings::default_for_adhd())
+            .await
+            .unwrap();
+        let stray = service
+            .create_card(other, ContentDraft::text_only("Q"), ContentDraft::text_only("A"), None)
+            .await
+            .unwrap()
+            .id;
+        let mixed = [ids[0], stray, ids[1]];
+
+        let err = service.delete_cards(deck_id, &mixed).await.unwrap_err();
+        assert!(matches!(
+            err,
+            CardServiceError::CardNotInDeck { card_id, deck_id: rejected }
+                if card_id == stray && rejected == deck_id
+        ));
+        let err = service.set_suspended_many(deck_id, &mixed, true).await.unwrap_err();
+        assert!(matches!(err, CardServiceError::CardNotInDeck { card_id, .. } if card_id == stray));
+        let tag = TagName::new("stray").unwrap();
+        let err = service.add_tags_many(deck_id, &mixed, std::slice::from_ref(&tag)).await;
+        assert!(matches!(
+            err,
+            Err(CardServiceError::CardNotInDeck { card_id, .. }) if card_id == stray
+        ));
+        assert!(service.list_tags_for_deck(deck_id).await.unwrap().is_empty());
+
+        assert_eq!(service.list_cards(deck_id, 10).await.unwrap().len(), 2);
+        assert_eq!(service.list_cards(other, 10).await.unwrap().len(), 1);
+        let stored = fixture.storage().cards.get_cards(deck_id, &ids).await.unwrap();
+        assert!(stored.iter().all(|card| !card.is_suspended()));
+        assert!(service.list_trash(deck_id, 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn import_delimited_inserts_valid_rows_and_reports_failures() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let csv = "Hund,Dog\n\"Hallo, Welt\",\"Hello,\nworld\"\n  ,Empty\nOnly one\nKatze,Cat,\n";
+
+        let report = service
+            .import_delimited(deck_id, csv.as_bytes(), ',')
+            .await
+            .unwrap();
+
+        assert_eq!(report.imported, 3);
+        assert_eq!(report.failed_rows, vec![3, 4]);
+        let cards = service.list_cards(deck_id, 10).await.unwrap();
+        assert!(cards.iter().any(|card| card.prompt().text() == "Hallo, Welt"
+            && card.answer().text() == "Hello,\nworld"));
+
+        let err = service
+            .import_delimited(deck_id, "a\t\"b".as_bytes(), '\t')
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CardServiceError::UnterminatedQuote { row: 1 }));
+        assert_eq!(service.list_cards(deck_id, 10).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn count_cards_splits_a_deck_by_phase() {
+        let fixture = ServicesFixture::builder().with_cards(6).build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let ids = fixture.card_ids();
+        let now = fixture.clock().now();
+        let store = &fixture.storage().cards;
+        for (card, phase) in fixture.cards()[1..5].iter().zip([
+            CardPhase::Learning,
+            CardPhase::Relearning,
+            CardPhase::Reviewing,
+            CardPhase::Reviewing,
+        ]) {
+            let moved = Card::from_persisted(
+                card.id(),
+                deck_id,
+                card.kind().clone(),
+                card.prompt().clone(),
+                card.answer().clone(),
+                card.created_at(),
+                now + Duration::days(1),
+                Some(now - Duration::days(1)),
+                phase,
+                1,
+                5.0,
+                5.0,
+            )
+            .unwrap();
+            store.upsert_card(&moved).await.unwrap();
+        }
+        service.set_suspended(deck_id, ids[4], true).await.unwrap();
+        service.delete_card(deck_id, ids[5]).await.unwrap();
+
+        let counts = service.count_cards(deck_id).await.unwrap();
+
+        assert_eq!(
+            counts,
+            CardPhaseCounts {
+                total: 5,
+                new: 1,
+                learning: 2,
+                review: 1,
+                suspended: 1,
+            }
+        );
+        let other = service.count_cards(DeckId::new(999)).await.unwrap();
+        assert_eq!(other, CardPhaseCounts::default());
+    }
+
+    #[tokio::test]
+    async fn reset_card_learning_returns_a_reviewed_card_to_new() {
+        let fixture = ServicesFixture::builder()
+            .with_cards(1)
+            .with_reviews([ReviewGrade::Good])
+            .build()
+            .await
+            .unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let card_id = fixture.card_ids()[0];
+        let logs = fixture.storage().review_logs.clone();
+        assert!(!fixture.cards()[0].is_new());
+
+        let kept = service
+            .reset_card_learning(deck_id, card_id, ResetCardOptions::default())
+            .await
+            .unwrap();
+        assert!(kept.is_new());
+        assert_eq!(kept.review_count(), 0);
+        assert_eq!(kept.memory_state(), None);
+        assert_eq!(logs.logs_for_card(deck_id, card_id).await.unwrap().len(), 1);
+
+        let cleared = service
+            .reset_card_learning(deck_id, card_id, ResetCardOptions { clear_history: true })
+            .await
+            .unwrap();
+        assert_eq!(cleared.phase(), CardPhase::New);
+        assert_eq!(cleared.last_review_at(), None);
+        assert!(logs.logs_for_card(deck_id, card_id).await.unwrap().is_empty());
+
+        let err = service
+            .reset_card_learning(DeckId::new(999), card_id, ResetCardOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CardServiceError::Storage(StorageError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn move_card_carries_review_history_and_tags_to_new_deck() {
+        let fixture = ServicesFixture::builder()
+            .with_cards(2)
+            .with_reviews([ReviewGrade::Good])
+            .build()
+            .await
+            .unwrap();
+        let service = fixture.card_service();
+        let from = fixture.deck_id();
+        let card = fixture.cards()[0].clone();
+        let tag = TagName::new("Verb").unwrap();
+        service
+            .set_tags_for_card(from, card.id(), std::slice::from_ref(&tag))
+            .await
+            .unwrap();
+        let to = fixture
+            .deck_service()
+            .create_deck("Other".to_string(), None, fixture.deck().settings().clone())
+            .await
+            .unwrap();
+
+        service.move_card(card.id(), from, to).await.unwrap();
+
+        let moved = fixture.storage().cards.get_cards(to, &[card.id()]).await.unwrap();
+        assert_eq!(moved[0].deck_id(), to);
+        assert_eq!(moved[0].memory_state(), card.memory_state());
+        assert_eq!(moved[0].next_review_at(), card.next_review_at());
+        let logs = fixture
+            .storage()
+            .review_logs
+            .logs_for_card(to, card.id())
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert!(fixture
+            .storage()
+            .review_logs
+            .logs_for_card(from, card.id())
+            .await
+            .unwrap()
+            .is_empty());
+        let tags = service.list_tags_for_card(to, card.id()).await.unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].deck_id(), to);
+        assert_eq!(service.deck_practice_stats(from).await.unwrap().total, 1);
+        assert_eq!(service.deck_practice_stats(to).await.unwrap().total, 1);
+    }
+
+    #[tokio::test]
+    async fn move_card_rejects_wrong_source_or_missing_destination() {
+        let fixture = ServicesFixture::builder().with_cards(1).build().await.unwrap();
+        let service = fixture.card_service();
+        let card_id = fixture.card_ids()[0];
+
+        let err = service
+            .move_card(card_id, fixture.deck_id(), DeckId::new(99))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CardServiceError::DeckNotFound(id) if id == DeckId::new(99)));
+
+        let err = service
+            .move_card(card_id, DeckId::new(99), fixture.deck_id())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CardServiceError::CardNotInDeck { .. }));
+        assert_eq!(service.list_cards(fixture.deck_id(), 10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn attach_audio_persists_clip_and_survives_content_edits() {
+        let fixture = ServicesFixture::builder().with_cards(1).build().await.unwrap();
+        let service = fixture.card_service();
+        let card_id = fixture.card_ids()[0];
+        let meta = AudioMeta::new(3_000, 48_000).unwrap();
+
+        let card = service
+            .attach_audio(
+                fixture.deck_id(),
+                card_id,
+                CardSide::Answer,
+                MediaUri::from_file("/audio/answer.mp3").unwrap(),
+                meta,
+                MediaHash::new("sha256:abc"),
+            )
+            .await
+            .unwrap();
+        assert!(card.prompt().audio().is_none());
+        assert_eq!(card.answer().audio().unwrap().checksum().as_str(), "sha256:abc");
+
+        service
+            .update_card_content(
+                fixture.deck_id(),
+                card_id,
+                ContentDraft::text_only("Q edited"),
+                ContentDraft::text_only("A edited"),
+            )
+            .await
+            .unwrap();
+        let stored = fixture
+            .storage()
+            .cards
+            .get_cards(fixture.deck_id(), &[card_id])
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(stored.answer().text(), "A edited");
+        assert_eq!(stored.answer().audio(), card.answer().audio());
+    }
+
+    #[tokio::test]
+    async fn attach_audio_rejects_unsupported_format_before_writing() {
+        let fixture = ServicesFixture::builder().with_cards(1).build().await.unwrap();
+        let service = fixture.card_service();
+        let card_id = fixture.card_ids()[0];
+
+        let err = service
+            .attach_audio(
+                fixture.deck_id(),
+                card_id,
+                CardSide::Prompt,
+                MediaUri::from_file("/audio/prompt.flac").unwrap(),
+                AudioMeta::new(3_000, 48_000).unwrap(),
+                MediaHash::new("sha256:abc"),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CardServiceError::Card(CardError::InvalidPrompt(ContentValidationError::Media(
+                MediaValidationError::UnsupportedAudioFormat
+            )))
+        ));
+
+        let stored = service.list_cards(fixture.deck_id(), 10).await.unwrap();
+        assert!(stored[0].prompt().audio().is_none());
+    }
+
+    #[tokio::test]
+    async fn find_broken_media_lists_references_to_missing_images_and_audio() {
+        let fixture = ServicesFixture::builder().with_cards(2).build().await.unwrap();
+        let service = fixture.card_service();
+        let deck_id = fixture.deck_id();
+        let [kept, lost] = fixture.card_ids()[..] else {
+            panic!("two cards");
+        };
+        let meta = AudioMeta::new(3_000, 48_000).unwrap();
+        let stored = MediaHash::new("sha256:kept");
+        fixture
+            .storage()
+            .media
+            .put_blob(&stored, vec![1], fixed_now())
+            .await
+            .unwrap();
+        for (card_id, side, hash) in [
+            (kept, CardSide::Prompt, stored),
+            (lost, CardSide::Answer, MediaHash::new("sha256:gone")),
+        ] {
+            let uri = MediaUri::from_file("/audio/clip.mp3").unwrap();
+            service
+                .attach_audio(deck_id, card_id, side, uri, meta, hash)
+                .await
+                .unwrap();
+        }
+        let missing_image = MediaId::new(42);
+        let with_image = service
+            .create_card(
+                deck_id,
+                ContentDraft::text_only("Cat").with_media_id(Some(missing_image)),
+                ContentDraft::text_only("Chat"),
+                None,
+            )
+            .await
+            .unwrap()
+            .id;
+
+        let broken = service.find_broken_media(deck_id).await.unwrap();
+        assert_eq!(broken.len(), 2, "{broken:?}");
+        assert!(broken.contains(&BrokenMedia {
+            card_id: lost,
+            side: CardSide::Answer,
+            missing: MissingMedia::Audio(MediaHash::new("sha256:gone")),
+        }));
+        assert!(broken.contains(&BrokenMedia {
+            card_id: with_image,
+            side: CardSide::Prompt,
+            missing: MissingMedia::Image(missing_image),
+        }));
+
+        let unchecked = CardService::new(fixture.clock(), Arc::clone(&fixture.storage().cards));
+        assert!(unchecked.find_broken_media(deck_id).await.unwrap().is_empty());
+    }
+
+    async fn service_with_front(front: &str) -> (CardService, DeckId, CardId) {
+        let repo = InMemoryRepository::new();
+        let service = CardService::new(Clock::Fixed(fixed_now()), Arc::new(repo));
+        let deck_id = DeckId::new(1);
+        let id = service
+            .create_card(
+                deck_id,
+                ContentDraft::text_only(front),
+                ContentDraft::text_only("A"),
+                None,
+            )
+            .await
+            .expect("seed card")
+            .id;
+        (service, deck_id, id)
+    }
+
+    #[tokio::test]
+    async fn duplicate_policy_defaults_to_allow_without_check() {
+        let (service, deck_id, _existing) = service_with_front("Hallo").await;
+        let created = service
+            .create_card(
+                deck_id,
+                ContentDraft::text_only("Hallo"),
+                ContentDraft::text_only("Hello"),
+                None,
+            )
+            .await
+            .expect("create");
+
+        assert_eq!(created.duplicate_of, None);
+        assert_eq!(service.list_cards(deck_id, 10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn duplicate_policy_warn_creates_and_reports_existing() {
+        let (service, deck_id, existing) = service_with_front("Hallo").await;
+        let created = service
+            .create_card(
+                deck_id,
+                ContentDraft::text_only("  hallo "),
+                ContentDraft::text_only("Hello"),
+                Some(DuplicatePolicy::Warn),
+            )
+            .await
+            .expect("create");
+
+        assert_eq!(created.duplicate_of, Some(existing));
+        assert_ne!(created.id, existing);
+        assert_eq!(service.list_cards(deck_id, 10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn duplicate_policy_reject_returns_existing_id() {
+        let (service, deck_id, existing) = service_with_front("Guten  Morgen").await;
+        let err = service
+            .create_card(
+                deck_id,
+                ContentDraft::text_only("guten morgen"),
+                ContentDraft::text_only("Good morning"),
+                Some(DuplicatePolicy::Reject),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, CardServiceError::DuplicateFront(id) if id == existing));
+        assert_eq!(service.list_cards(deck_id, 10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn duplicate_policy_respects_exact_normalization() {
+        let (service, deck_id, _existing) = service_with_front("Hallo").await;
+        let created = service
+            .with_front_normalization(FrontNormalization::exact())
+            .create_card(
+                deck_id,
+                ContentDraft::text_only("hallo"),
+                ContentDraft::text_only("Hello"),
+                Some(DuplicatePolicy::Reject),
+            )
+            .await
+            .expect("case differs");
+
+        assert_eq!(created.duplicate_of, None);
+    }
 }
@@ -1,22 +1,90 @@
+use std::collections::HashMap;
+use std::io::Write;
 use std::sync::Arc;
 
-use learn_core::model::{Deck, DeckId, DeckSettings};
-use storage::repository::{DeckRepository, NewDeckRecord};
+use chrono::{DateTime, Utc};
+use learn_core::model::{CardId, Deck, DeckId, DeckSettings};
+use learn_core::optimizer::FittedParameters;
+use learn_core::scheduler::Scheduler;
+use learn_core::time::StudyDay;
+use storage::repository::{
+    CardMaturityCounts, CardRepository, DeckMergeCounts, DeckRepository, MergeConflict,
+    NewDeckRecord, ReviewLogPrune, ReviewLogRepository,
+};
 
+use crate::deck_transfer_service::{
+    CardExport, DECK_EXPORT_VERSION, DeckExportHeader, DeckExportMeta,
+};
 use crate::error::DeckServiceError;
+use crate::review_service::{CardHistoryEntry, LogExportFormat, ReviewService};
+use crate::sessions::effective_daily_limits;
 use crate::Clock;
 
+/// Interval, in days, at which a studied card counts as mature.
+///
+/// Three weeks is the usual line between cards still being learned and cards that have
+/// settled into long-term memory; the deck health metric is the share of cards past it.
+pub const MATURE_INTERVAL_DAYS: u32 = 21;
+
+/// Cards read per page while streaming a JSON-lines export.
+const JSONL_EXPORT_PAGE_SIZE: u32 = 500;
+
+/// Cards a deck would put into a session started now, after daily limits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DeckDueCounts {
+    /// Due reviews outside (re)learning.
+    pub due: u32,
+    /// Due cards still in (re)learning.
+    pub learning: u32,
+    /// New cards that fit under today's new-card limit.
+    pub new: u32,
+}
+
+impl DeckDueCounts {
+    /// Cards waiting for review, whether or not they are still in (re)learning.
+    #[must_use]
+    pub fn reviews(&self) -> u32 {
+        self.due.saturating_add(self.learning)
+    }
+}
+
 /// Orchestrates deck creation and persistence.
 #[derive(Clone)]
 pub struct DeckService {
     clock: Clock,
     decks: Arc<dyn DeckRepository>,
+    cards: Arc<dyn CardRepository>,
+    review_logs: Arc<dyn ReviewLogRepository>,
+    study_day: StudyDay,
 }
 
 impl DeckService {
     #[must_use]
-    pub fn new(clock: Clock, decks: Arc<dyn DeckRepository>) -> Self {
-        Self { clock, decks }
+    pub fn new(
+        clock: Clock,
+        decks: Arc<dyn DeckRepository>,
+        cards: Arc<dyn CardRepository>,
+        review_logs: Arc<dyn ReviewLogRepository>,
+    ) -> Self {
+        Self {
+            clock,
+            decks,
+            cards,
+            review_logs,
+            study_day: StudyDay::default(),
+        }
+    }
+
+    /// Apply daily limits for the study day `study_day` places now in.
+    #[must_use]
+    pub fn with_study_day(mut self, study_day: StudyDay) -> Self {
+        self.study_day = study_day;
+        self
+    }
+
+    #[must_use]
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
     }
 
     /// Create a new deck with the given settings and persist it.
@@ -24,6 +92,7 @@ impl DeckService {
     /// # Errors
     ///
     /// Returns `DeckServiceError::Deck` for validation failures.
+    /// Returns `DeckServiceError::DuplicateName` if an active deck already has the name.
     /// Returns `DeckServiceError::Storage` if persistence fails.
     pub async fn create_deck(
         &self,
@@ -33,6 +102,7 @@ impl DeckService {
     ) -> Result<DeckId, DeckServiceError> {
         let now = self.clock.now();
         let deck = Deck::new(DeckId::new(1), name, description, settings, now)?;
+        self.ensure_unique_name(deck.name(), None).await?;
         let deck_id = self
             .decks
             .insert_new_deck(NewDeckRecord::from_deck(&deck))
@@ -40,16 +110,103 @@ impl DeckService {
         Ok(deck_id)
     }
 
-    /// List decks ordered by ID, up to the given limit.
+    /// List decks with favorites first and then by ID, up to the given limit.
+    ///
+    /// Archived decks are left out unless `include_archived` is set.
     ///
     /// # Errors
     ///
     /// Returns `DeckServiceError::Storage` if repository access fails.
-    pub async fn list_decks(&self, limit: u32) -> Result<Vec<Deck>, DeckServiceError> {
-        let decks = self.decks.list_decks(limit).await?;
+    pub async fn list_decks(
+        &self,
+        limit: u32,
+        include_archived: bool,
+    ) -> Result<Vec<Deck>, DeckServiceError> {
+        let decks = self.decks.list_decks(limit, include_archived).await?;
         Ok(decks)
     }
 
+    /// Count due, learning, and new cards for each deck, capped by its daily limits.
+    ///
+    /// All decks are counted in one repository query. Archived decks report zero counts
+    /// because they never start sessions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Storage` if repository access fails.
+    pub async fn deck_counts(
+        &self,
+        decks: &[Deck],
+    ) -> Result<HashMap<DeckId, DeckDueCounts>, DeckServiceError> {
+        let now = self.clock.now();
+        let active: Vec<DeckId> = decks
+            .iter()
+            .filter(|deck| !deck.is_archived())
+            .map(Deck::id)
+            .collect();
+        let rows = if active.is_empty() {
+            Vec::new()
+        } else {
+            self.cards.list_deck_practice_counts(&active, now).await?
+        };
+        let raw: HashMap<DeckId, _> =
+            rows.into_iter().map(|row| (row.deck_id, row.counts)).collect();
+        let today = self.study_day.date_of(now);
+
+        Ok(decks
+            .iter()
+            .map(|deck| {
+                let counts = if deck.is_archived() {
+                    DeckDueCounts::default()
+                } else {
+                    let practice = raw.get(&deck.id()).copied().unwrap_or_default();
+                    let (review_limit, new_limit) = effective_daily_limits(deck.settings(), today);
+                    let reviews = practice.due.min(review_limit);
+                    let learning = practice.learning.min(reviews);
+                    DeckDueCounts {
+                        due: reviews - learning,
+                        learning,
+                        new: practice.new.min(new_limit),
+                    }
+                };
+                (deck.id(), counts)
+            })
+            .collect())
+    }
+
+    /// Sum `deck_counts` over every active deck, for an across-the-collection total.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Storage` if repository access fails.
+    pub async fn due_now_counts(&self) -> Result<DeckDueCounts, DeckServiceError> {
+        let decks = self.decks.list_decks(u32::MAX, false).await?;
+        Ok(self
+            .deck_counts(&decks)
+            .await?
+            .into_values()
+            .fold(DeckDueCounts::default(), |total, counts| DeckDueCounts {
+                due: total.due.saturating_add(counts.due),
+                learning: total.learning.saturating_add(counts.learning),
+                new: total.new.saturating_add(counts.new),
+            }))
+    }
+
+    /// Split a deck's cards into new, young and mature, using `MATURE_INTERVAL_DAYS`.
+    ///
+    /// `CardMaturityCounts::mature_percent` gives the deck's glanceable health number.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Storage` if repository access fails.
+    pub async fn maturity(&self, deck_id: DeckId) -> Result<CardMaturityCounts, DeckServiceError> {
+        let counts = self
+            .cards
+            .count_cards_by_maturity(deck_id, MATURE_INTERVAL_DAYS)
+            .await?;
+        Ok(counts)
+    }
+
     /// Fetch a deck by ID.
     ///
     /// Returns `Ok(None)` when the deck does not exist.
@@ -62,11 +219,28 @@ impl DeckService {
         Ok(deck)
     }
 
+    /// The scheduler for a deck's retention and learned weights, for predicting recall.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Review` if FSRS rejects the deck's weights.
+    /// Returns `DeckServiceError::Storage` if the deck is missing or repository access fails.
+    pub async fn scheduler(&self, deck_id: DeckId) -> Result<Scheduler, DeckServiceError> {
+        let deck = self
+            .decks
+            .get_deck(deck_id)
+            .await?
+            .ok_or(storage::repository::StorageError::NotFound)?;
+        Ok(ReviewService::scheduler_for(deck.settings())?)
+    }
+
     /// Rename a deck while preserving existing settings and metadata.
     ///
     /// # Errors
     ///
     /// Returns `DeckServiceError::Deck` if validation fails.
+    /// Returns `DeckServiceError::DuplicateName` if another active deck has the name.
+    /// Returns `DeckServiceError::Conflict` if the deck changes while it is being renamed.
     /// Returns `DeckServiceError::Storage` if repository access fails.
     pub async fn rename_deck(
         &self,
@@ -81,25 +255,186 @@ impl DeckService {
 
         self.update_deck(
             deck_id,
+            deck.version(),
             name,
             deck.description().map(str::to_owned),
             deck.settings().clone(),
         )
-        .await
+        .await?;
+        Ok(())
     }
 
-    /// Update deck name, description, and settings.
+    /// Update deck name, description, and settings, returning the new stored version.
+    ///
+    /// `expected_version` is the `Deck::version` the caller loaded; the write is refused
+    /// when the stored deck moved past it, so a stale editor cannot clobber newer changes.
     ///
     /// # Errors
     ///
     /// Returns `DeckServiceError::Deck` if validation fails.
+    /// Returns `DeckServiceError::DuplicateName` if another active deck has the name.
+    /// Returns `DeckServiceError::Conflict` if the deck was saved since `expected_version`.
     /// Returns `DeckServiceError::Storage` if repository access fails.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(deck_id = deck_id.value(), expected_version))
+    )]
     pub async fn update_deck(
         &self,
         deck_id: DeckId,
+        expected_version: u64,
         name: String,
         description: Option<String>,
         settings: DeckSettings,
+    ) -> Result<u64, DeckServiceError> {
+        let deck = self
+            .decks
+            .get_deck(deck_id)
+            .await?
+            .ok_or(storage::repository::StorageError::NotFound)?;
+
+        let updated = Deck::new(deck.id(), name, description, settings, deck.created_at())?
+            .with_archived(deck.is_archived())
+            .with_favorite(deck.is_favorite())
+            .with_version(expected_version)
+            .with_updated_at(self.clock.now());
+        if !updated.is_archived() {
+            self.ensure_unique_name(updated.name(), Some(deck_id)).await?;
+        }
+        match self.decks.update_deck_if_version(&updated).await {
+            Ok(version) => Ok(version),
+            Err(storage::repository::StorageError::Conflict) => Err(DeckServiceError::Conflict),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Replace a deck's settings with a preset from `DeckSettings::to_preset_json`,
+    /// returning the new stored version.
+    ///
+    /// The deck keeps its own color and icon; cards and their schedules are not touched.
+    /// Nothing is written unless the whole preset is valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Deck` if the preset is malformed or a value is out of range.
+    /// Returns `DeckServiceError::Conflict` if the deck changes while the preset is applied.
+    /// Returns `DeckServiceError::Storage` if the deck is missing or persistence fails.
+    pub async fn apply_preset(&self, deck_id: DeckId, json: &str) -> Result<u64, DeckServiceError> {
+        let preset = DeckSettings::from_preset_json(json)?;
+        let deck = self
+            .decks
+            .get_deck(deck_id)
+            .await?
+            .ok_or(storage::repository::StorageError::NotFound)?;
+
+        let settings = preset.with_appearance(deck.settings().color(), deck.settings().icon());
+        self.update_deck(
+            deck_id,
+            deck.version(),
+            deck.name().to_owned(),
+            deck.description().map(str::to_owned),
+            settings,
+        )
+        .await
+    }
+
+    /// Create a new deck with the source deck's description and settings and a fresh copy
+    /// of every card, returning the new deck's ID.
+    ///
+    /// Copies keep their content, media and audio references, and tags, but start as `New`
+    /// cards with no review history. The whole copy runs in one transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Deck` if `new_name` is blank.
+    /// Returns `DeckServiceError::DuplicateName` if an active deck already has `new_name`.
+    /// Returns `DeckServiceError::Storage` if the source deck is missing or persistence fails.
+    pub async fn duplicate_deck(
+        &self,
+        source_id: DeckId,
+        new_name: String,
+    ) -> Result<DeckId, DeckServiceError> {
+        let source = self
+            .decks
+            .get_deck(source_id)
+            .await?
+            .ok_or(storage::repository::StorageError::NotFound)?;
+
+        let deck = Deck::new(
+            DeckId::new(1),
+            new_name,
+            source.description().map(str::to_owned),
+            source.settings().clone(),
+            self.clock.now(),
+        )?;
+        self.ensure_unique_name(deck.name(), None).await?;
+        let deck_id = self
+            .decks
+            .duplicate_deck(source_id, NewDeckRecord::from_deck(&deck))
+            .await?;
+        Ok(deck_id)
+    }
+
+    /// Move every card of `source_id` into `dest_id`, then archive the emptied source deck.
+    ///
+    /// Cards keep their scheduling, review history, tags, and media, and the whole merge
+    /// runs in one transaction. A card whose prompt the destination already has is left in
+    /// the source under `MergeConflict::Skip` and moved anyway under `KeepBoth`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::MergeIntoSelf` if both IDs name the same deck.
+    /// Returns `DeckServiceError::Storage` if either deck is missing or persistence fails.
+    pub async fn merge_decks(
+        &self,
+        source_id: DeckId,
+        dest_id: DeckId,
+        on_conflict: MergeConflict,
+    ) -> Result<DeckMergeCounts, DeckServiceError> {
+        if source_id == dest_id {
+            return Err(DeckServiceError::MergeIntoSelf);
+        }
+        let counts = self
+            .decks
+            .merge_decks(source_id, dest_id, on_conflict)
+            .await?;
+        Ok(counts)
+    }
+
+    /// Archive or restore a deck; its cards and review history are kept either way.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::DuplicateName` if a restored deck's name is taken by now.
+    /// Returns `DeckServiceError::Storage` if the deck is missing or persistence fails.
+    pub async fn set_archived(
+        &self,
+        deck_id: DeckId,
+        archived: bool,
+    ) -> Result<(), DeckServiceError> {
+        let deck = self
+            .decks
+            .get_deck(deck_id)
+            .await?
+            .ok_or(storage::repository::StorageError::NotFound)?;
+
+        if deck.is_archived() && !archived {
+            self.ensure_unique_name(deck.name(), Some(deck_id)).await?;
+        }
+        let deck = deck.with_archived(archived).with_updated_at(self.clock.now());
+        self.decks.upsert_deck(&deck).await?;
+        Ok(())
+    }
+
+    /// Star or unstar a deck; starred decks are listed ahead of the rest.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Storage` if the deck is missing or persistence fails.
+    pub async fn set_favorite(
+        &self,
+        deck_id: DeckId,
+        favorite: bool,
     ) -> Result<(), DeckServiceError> {
         let deck = self
             .decks
@@ -107,19 +442,205 @@ impl DeckService {
             .await?
             .ok_or(storage::repository::StorageError::NotFound)?;
 
-        let updated = Deck::new(deck.id(), name, description, settings, deck.created_at())?;
-        self.decks.upsert_deck(&updated).await?;
+        let deck = deck.with_favorite(favorite).with_updated_at(self.clock.now());
+        self.decks.upsert_deck(&deck).await?;
+        Ok(())
+    }
+
+    /// Move review cards' due dates to match the deck's current target retention.
+    ///
+    /// See `ReviewService::reschedule_all`. Returns the number of cards moved.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Review` if the deck is missing or rescheduling fails.
+    pub async fn reschedule_cards(&self, deck_id: DeckId) -> Result<u64, DeckServiceError> {
+        let reviews = ReviewService::new()?.with_clock(self.clock);
+        Ok(reviews
+            .reschedule_all(deck_id, self.decks.as_ref(), self.cards.as_ref())
+            .await?)
+    }
+
+    /// Pull in cards due past the deck's maximum interval.
+    ///
+    /// See `ReviewService::clamp_intervals`. Returns the number of cards clamped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Review` if the deck is missing or persistence fails.
+    pub async fn clamp_intervals(&self, deck_id: DeckId) -> Result<u64, DeckServiceError> {
+        let reviews = ReviewService::new()?.with_clock(self.clock);
+        Ok(reviews
+            .clamp_intervals(deck_id, self.decks.as_ref(), self.cards.as_ref())
+            .await?)
+    }
+
+    /// Fit the deck's FSRS parameters to its review history and store them.
+    ///
+    /// See `ReviewService::optimize_parameters`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Review` if the deck is missing, not ready, or fitting fails.
+    pub async fn optimize_parameters(
+        &self,
+        deck_id: DeckId,
+    ) -> Result<FittedParameters, DeckServiceError> {
+        let reviews = ReviewService::new()?.with_clock(self.clock);
+        Ok(reviews
+            .optimize_parameters(deck_id, self.decks.as_ref(), self.review_logs.as_ref())
+            .await?)
+    }
+
+    /// Delete the deck's review history matched by `prune`, returning how many reviews went.
+    ///
+    /// See `ReviewService::prune_history`, including what it costs the optimizer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Review` if the logs cannot be deleted.
+    pub async fn prune_history(
+        &self,
+        deck_id: DeckId,
+        prune: ReviewLogPrune,
+    ) -> Result<u64, DeckServiceError> {
+        let reviews = ReviewService::new()?.with_clock(self.clock);
+        Ok(reviews
+            .prune_history(deck_id, prune, self.review_logs.as_ref())
+            .await?)
+    }
+
+    /// Up to `limit` of a card's reviews, newest first.
+    ///
+    /// See `ReviewService::card_history`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Review` if the logs cannot be read.
+    pub async fn card_history(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        limit: u32,
+    ) -> Result<Vec<CardHistoryEntry>, DeckServiceError> {
+        let reviews = ReviewService::new()?.with_clock(self.clock);
+        Ok(reviews
+            .card_history(deck_id, card_id, limit, self.review_logs.as_ref())
+            .await?)
+    }
+
+    /// Write the deck's review logs to `writer` and return how many rows were written.
+    ///
+    /// See `ReviewService::export_logs`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Review` if the logs cannot be read or written.
+    pub async fn export_review_logs(
+        &self,
+        deck_id: DeckId,
+        writer: &mut (impl std::io::Write + Send),
+        format: LogExportFormat,
+    ) -> Result<usize, DeckServiceError> {
+        let reviews = ReviewService::new()?.with_clock(self.clock);
+        Ok(reviews
+            .export_logs(deck_id, writer, format, self.review_logs.as_ref())
+            .await?)
+    }
+
+    /// Stream the deck to `writer` as JSON lines and return how many cards were written.
+    ///
+    /// The first line is a `DeckExportHeader` carrying the deck's name, description, and
+    /// settings; each later line is one `CardExport`, in ID order. Cards are read a page at
+    /// a time, each page starting after the last ID written, so large decks export without
+    /// being held in memory. See `DeckTransferService::import_jsonl` for reading the stream
+    /// back.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckServiceError::Storage` if the deck is missing or cannot be read.
+    /// Returns `DeckServiceError::Write` if writing to `writer` fails.
+    pub async fn export_jsonl(
+        &self,
+        deck_id: DeckId,
+        writer: &mut (impl Write + Send),
+    ) -> Result<usize, DeckServiceError> {
+        let deck = self
+            .decks
+            .get_deck(deck_id)
+            .await?
+            .ok_or(storage::repository::StorageError::NotFound)?;
+        let header = DeckExportHeader {
+            version: DECK_EXPORT_VERSION,
+            deck: DeckExportMeta::from_deck(&deck),
+        };
+        write_json_line(writer, &header)?;
+
+        let mut written = 0;
+        let mut after = None;
+        loop {
+            let page = self
+                .cards
+                .list_cards_after(deck_id, after, JSONL_EXPORT_PAGE_SIZE)
+                .await?;
+            for card in &page {
+                write_json_line(writer, &CardExport::from_card(card))?;
+            }
+            written += page.len();
+            match page.last() {
+                Some(last) if page.len() == JSONL_EXPORT_PAGE_SIZE as usize => {
+                    after = Some(last.id());
+                }
+                _ => break,
+            }
+        }
+        writer.flush()?;
+        Ok(written)
+    }
+
+    /// Refuse `name` when an active deck other than `except` already uses it, ignoring
+    /// case and surrounding whitespace. Archived decks do not count.
+    async fn ensure_unique_name(
+        &self,
+        name: &str,
+        except: Option<DeckId>,
+    ) -> Result<(), DeckServiceError> {
+        let key = name.trim().to_lowercase();
+        let taken = self
+            .decks
+            .list_decks(u32::MAX, false)
+            .await?
+            .iter()
+            .any(|deck| Some(deck.id()) != except && deck.name().trim().to_lowercase() == key);
+        if taken {
+            return Err(DeckServiceError::DuplicateName);
+        }
         Ok(())
     }
 }
 
+fn write_json_line(writer: &mut impl Write, value: &impl serde::Serialize) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *writer, value)?;
+    writer.write_all(b"\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use learn_core::model::{
+        Card, CardPhase, ContentDraft, DeckColor, DeckError, LeechAction, ReviewGrade, TagName,
+    };
     use learn_core::time::fixed_now;
     use storage::repository::InMemoryRepository;
 
+    use crate::test_support::ServicesFixture;
+
+    fn service_for(clock: Clock, repo: InMemoryRepository) -> DeckService {
+        let repo = Arc::new(repo);
+        DeckService::new(clock, repo.clone(), repo.clone(), repo)
+    }
+
     #[tokio::test]
     async fn get_deck_returns_persisted_deck() {
         let repo = InMemoryRepository::new();
@@ -133,17 +654,56 @@ mod tests {
         .unwrap();
         repo.upsert_deck(&deck).await.unwrap();
 
-        let service = DeckService::new(Clock::Fixed(fixed_now()), std::sync::Arc::new(repo));
+        let service = service_for(Clock::Fixed(fixed_now()), repo);
         let fetched = service.get_deck(deck.id()).await.unwrap();
         assert!(fetched.is_some());
         assert_eq!(fetched.unwrap().name(), "Test");
     }
 
+    #[tokio::test]
+    async fn deck_names_must_be_unique_among_active_decks() {
+        let service = service_for(Clock::Fixed(fixed_now()), InMemoryRepository::new());
+        let settings = DeckSettings::default_for_adhd;
+        let spanish = service
+            .create_deck("Spanish".to_string(), None, settings())
+            .await
+            .unwrap();
+        let french = service
+            .create_deck("French".to_string(), None, settings())
+            .await
+            .unwrap();
+
+        let err = service
+            .create_deck("  spanish ".to_string(), None, settings())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DeckServiceError::DuplicateName));
+        let err = service.rename_deck(french, "SPANISH".to_string()).await.unwrap_err();
+        assert!(matches!(err, DeckServiceError::DuplicateName));
+        let err = service.duplicate_deck(french, "Spanish".to_string()).await.unwrap_err();
+        assert!(matches!(err, DeckServiceError::DuplicateName));
+
+        service.rename_deck(spanish, "Spanish".to_string()).await.unwrap();
+        service.rename_deck(spanish, "spanish".to_string()).await.unwrap();
+        let renamed = service.get_deck(spanish).await.unwrap().unwrap();
+        assert_eq!(renamed.name(), "spanish");
+
+        service.set_archived(spanish, true).await.unwrap();
+        let reused = service
+            .create_deck("Spanish".to_string(), None, settings())
+            .await
+            .unwrap();
+        let err = service.set_archived(spanish, false).await.unwrap_err();
+        assert!(matches!(err, DeckServiceError::DuplicateName));
+        service.rename_deck(reused, "Spanish II".to_string()).await.unwrap();
+        service.set_archived(spanish, false).await.unwrap();
+    }
+
     #[tokio::test]
     async fn update_deck_persists_daily_limits() {
         let repo = InMemoryRepository::new();
         let clock = Clock::Fixed(fixed_now());
-        let service = DeckService::new(clock, std::sync::Arc::new(repo));
+        let service = service_for(clock, repo);
 
         let deck_id = service
             .create_deck(
@@ -158,9 +718,11 @@ mod tests {
             12, 55, 5, false, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5,
             0, 0.85, true, 100,
         )
+        .unwrap()
+        .with_fsrs_parameters(vec![0.5; DeckSettings::FSRS_PARAMETER_COUNT])
         .unwrap();
         service
-            .update_deck(deck_id, "Daily Limits".to_string(), None, updated_settings)
+            .update_deck(deck_id, 0, "Daily Limits".to_string(), None, updated_settings)
             .await
             .unwrap();
 
@@ -169,5 +731,508 @@ mod tests {
         assert_eq!(refreshed.settings().review_limit_per_day(), 55);
         assert_eq!(refreshed.settings().micro_session_size(), 5);
         assert!(!refreshed.settings().protect_overload());
+        assert_eq!(
+            refreshed.settings().fsrs_parameters(),
+            [0.5; DeckSettings::FSRS_PARAMETER_COUNT].as_slice()
+        );
+    }
+
+    #[tokio::test]
+    async fn update_deck_rejects_a_stale_window_after_a_concurrent_save() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let service = fixture.deck_service();
+        let deck_id = fixture.deck_id();
+        let first_window = service.get_deck(deck_id).await.unwrap().unwrap();
+        let second_window = first_window.clone();
+
+        let saved = service
+            .update_deck(
+                deck_id,
+                first_window.version(),
+                "First".to_string(),
+                None,
+                limited_settings(8, 40),
+            )
+            .await
+            .unwrap();
+        assert_eq!(saved, first_window.version() + 1);
+
+        let err = service
+            .update_deck(
+                deck_id,
+                second_window.version(),
+                "Second".to_string(),
+                None,
+                limited_settings(3, 10),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DeckServiceError::Conflict));
+
+        let reloaded = service.get_deck(deck_id).await.unwrap().unwrap();
+        assert_eq!(reloaded.name(), "First");
+        assert_eq!(reloaded.settings().new_cards_per_day(), 8);
+        assert_eq!(reloaded.version(), saved);
+        service
+            .update_deck(
+                deck_id,
+                reloaded.version(),
+                "Second".to_string(),
+                None,
+                limited_settings(3, 10),
+            )
+            .await
+            .unwrap();
+        let stored = service.get_deck(deck_id).await.unwrap().unwrap();
+        assert_eq!(stored.name(), "Second");
+    }
+
+    #[tokio::test]
+    async fn archived_decks_are_hidden_from_default_listing() {
+        let repo = InMemoryRepository::new();
+        let service = service_for(Clock::Fixed(fixed_now()), repo);
+        let kept = service
+            .create_deck("Kept".to_string(), None, DeckSettings::default_for_adhd())
+            .await
+            .unwrap();
+        let archived = service
+            .create_deck("Old".to_string(), None, DeckSettings::default_for_adhd())
+            .await
+            .unwrap();
+
+        service.set_archived(archived, true).await.unwrap();
+        service
+            .rename_deck(archived, "Older".to_string())
+            .await
+            .unwrap();
+
+        let active: Vec<DeckId> = service
+            .list_decks(10, false)
+            .await
+            .unwrap()
+            .iter()
+            .map(Deck::id)
+            .collect();
+        assert_eq!(active, vec![kept]);
+        let all = service.list_decks(10, true).await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert!(all[1].is_archived());
+
+        service.set_archived(archived, false).await.unwrap();
+        assert_eq!(service.list_decks(10, false).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn favorite_decks_sort_ahead_of_the_rest_in_name_order() {
+        let repo = InMemoryRepository::new();
+        let service = service_for(Clock::Fixed(fixed_now()), repo);
+        let mut ids = Vec::new();
+        for name in ["Arabic", "Biology", "Chemistry", "Dutch"] {
+            let id = service
+                .create_deck(name.to_string(), None, DeckSettings::default_for_adhd())
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+
+        service.set_favorite(ids[3], true).await.unwrap();
+        service.set_favorite(ids[1], true).await.unwrap();
+        service
+            .rename_deck(ids[1], "Botany".to_string())
+            .await
+            .unwrap();
+
+        let decks = service.list_decks(10, false).await.unwrap();
+        let names: Vec<&str> = decks.iter().map(Deck::name).collect();
+        assert_eq!(names, vec!["Botany", "Dutch", "Arabic", "Chemistry"]);
+        assert!(decks[0].is_favorite() && decks[1].is_favorite());
+
+        service.set_favorite(ids[3], false).await.unwrap();
+        let decks = service.list_decks(10, false).await.unwrap();
+        let names: Vec<&str> = decks.iter().map(Deck::name).collect();
+        assert_eq!(names, vec!["Botany", "Arabic", "Chemistry", "Dutch"]);
+    }
+
+    #[tokio::test]
+    async fn apply_preset_copies_settings_but_keeps_appearance_and_cards() {
+        let fixture = ServicesFixture::builder()
+            .with_cards(2)
+            .with_reviews([ReviewGrade::Good])
+            .build()
+            .await
+            .unwrap();
+        let service = fixture.deck_service();
+        let tuned = DeckSettings::default_for_adhd()
+            .with_leech(3, LeechAction::Suspend)
+            .with_appearance(Some(DeckColor::Teal), None);
+        let source = service
+            .create_deck("Tuned".to_string(), None, tuned)
+            .await
+            .unwrap();
+        let target = fixture.deck_id();
+        let cards_before = fixture.card_service().list_cards(target, 10).await.unwrap();
+        let preset = service
+            .get_deck(source)
+            .await
+            .unwrap()
+            .unwrap()
+            .settings()
+            .to_preset_json();
+
+        service.apply_preset(target, &preset).await.unwrap();
+
+        let applied = service.get_deck(target).await.unwrap().unwrap();
+        assert_eq!(applied.settings().leech_threshold(), 3);
+        assert_eq!(applied.settings().leech_action(), LeechAction::Suspend);
+        assert_eq!(applied.settings().color(), fixture.deck().settings().color());
+        let cards_after = fixture.card_service().list_cards(target, 10).await.unwrap();
+        assert_eq!(cards_after, cards_before);
+    }
+
+    #[tokio::test]
+    async fn apply_preset_rejects_invalid_presets_without_writing() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let service = fixture.deck_service();
+        let deck_id = fixture.deck_id();
+
+        let err = service
+            .apply_preset(deck_id, r#"{"new_cards_per_day": 0}"#)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, DeckServiceError::Deck(DeckError::InvalidNewCardsPerDay)));
+        let deck = service.get_deck(deck_id).await.unwrap().unwrap();
+        assert_eq!(deck.version(), fixture.deck().version());
+        assert_eq!(deck.settings(), fixture.deck().settings());
+    }
+
+    #[tokio::test]
+    async fn duplicate_deck_copies_cards_as_new_with_tags() {
+        let fixture = ServicesFixture::builder()
+            .with_cards(3)
+            .with_reviews([ReviewGrade::Good, ReviewGrade::Again])
+            .build()
+            .await
+            .unwrap();
+        let card_service = fixture.card_service();
+        let source_id = fixture.deck_id();
+        let tag = TagName::new("verbs").unwrap();
+        card_service
+            .set_tags_for_card(source_id, fixture.card_ids()[0], std::slice::from_ref(&tag))
+            .await
+            .unwrap();
+
+        let clone_id = fixture
+            .deck_service()
+            .duplicate_deck(source_id, "Variant".to_string())
+            .await
+            .unwrap();
+
+        let clone = fixture.deck_service().get_deck(clone_id).await.unwrap().unwrap();
+        assert_eq!(clone.name(), "Variant");
+        assert_eq!(clone.settings(), fixture.deck().settings());
+
+        let mut copies = card_service.list_cards(clone_id, 10).await.unwrap();
+        copies.sort_by_key(|card| card.id().value());
+        assert_eq!(copies.len(), 3);
+        assert!(copies.iter().all(|card| card.phase() == CardPhase::New));
+        assert!(copies.iter().all(|card| card.review_count() == 0));
+        assert_eq!(copies[0].prompt().text(), "Q1");
+        let tags = card_service
+            .list_tags_for_card(clone_id, copies[0].id())
+            .await
+            .unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name(), &tag);
+        assert_eq!(tags[0].deck_id(), clone_id);
+
+        let source_cards = card_service.list_cards(source_id, 10).await.unwrap();
+        assert!(source_cards.iter().any(|card| card.review_count() > 0));
+    }
+
+    #[tokio::test]
+    async fn duplicate_missing_deck_returns_not_found() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+
+        let err = fixture
+            .deck_service()
+            .duplicate_deck(DeckId::new(99), "Copy".to_string())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            DeckServiceError::Storage(storage::repository::StorageError::NotFound)
+        ));
+    }
+
+    /// A second deck holding one card per prompt, for merges into it.
+    async fn deck_with_prompts(fixture: &ServicesFixture, prompts: &[&str]) -> DeckId {
+        let deck_id = fixture
+            .deck_service()
+            .create_deck("Target".to_string(), None, DeckSettings::default_for_adhd())
+            .await
+            .unwrap();
+        for prompt in prompts {
+            fixture
+                .card_service()
+                .create_card(
+                    deck_id,
+                    ContentDraft::text_only(*prompt),
+                    ContentDraft::text_only("existing"),
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+        deck_id
+    }
+
+    #[tokio::test]
+    async fn merge_moves_cards_with_history_and_tags_then_archives_source() {
+        let fixture = ServicesFixture::builder()
+            .with_cards(3)
+            .with_reviews([ReviewGrade::Good, ReviewGrade::Again])
+            .build()
+            .await
+            .unwrap();
+        let card_service = fixture.card_service();
+        let deck_service = fixture.deck_service();
+        let source_id = fixture.deck_id();
+        let reviewed = fixture.card_ids()[0];
+        let tag = TagName::new("verbs").unwrap();
+        card_service
+            .set_tags_for_card(source_id, reviewed, std::slice::from_ref(&tag))
+            .await
+            .unwrap();
+        let dest_id = deck_with_prompts(&fixture, &["Other"]).await;
+
+        let counts = deck_service
+            .merge_decks(source_id, dest_id, MergeConflict::Skip)
+            .await
+            .unwrap();
+
+        assert_eq!(counts, DeckMergeCounts { moved: 3, skipped: 0 });
+        assert!(card_service.list_cards(source_id, 10).await.unwrap().is_empty());
+        let merged = card_service.list_cards(dest_id, 10).await.unwrap();
+        assert_eq!(merged.len(), 4);
+        let moved = merged.iter().find(|card| card.id() == reviewed).unwrap();
+        assert_eq!(moved.review_count(), 1);
+        assert_eq!(moved.next_review_at(), fixture.cards()[0].next_review_at());
+        let history = deck_service.card_history(dest_id, reviewed, 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        let tags = card_service.list_tags_for_card(dest_id, reviewed).await.unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name(), &tag);
+        assert_eq!(tags[0].deck_id(), dest_id);
+
+        let source = deck_service.get_deck(source_id).await.unwrap().unwrap();
+        assert!(source.is_archived());
+    }
+
+    #[tokio::test]
+    async fn merge_skips_duplicate_prompts_under_skip() {
+        let fixture = ServicesFixture::builder().with_cards(3).build().await.unwrap();
+        let card_service = fixture.card_service();
+        let source_id = fixture.deck_id();
+        let dest_id = deck_with_prompts(&fixture, &["  q1 "]).await;
+
+        let counts = fixture
+            .deck_service()
+            .merge_decks(source_id, dest_id, MergeConflict::Skip)
+            .await
+            .unwrap();
+
+        assert_eq!(counts, DeckMergeCounts { moved: 2, skipped: 1 });
+        let left = card_service.list_cards(source_id, 10).await.unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].prompt().text(), "Q1");
+        assert_eq!(card_service.list_cards(dest_id, 10).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn merge_moves_duplicate_prompts_under_keep_both() {
+        let fixture = ServicesFixture::builder().with_cards(3).build().await.unwrap();
+        let card_service = fixture.card_service();
+        let source_id = fixture.deck_id();
+        let dest_id = deck_with_prompts(&fixture, &["  q1 "]).await;
+
+        let counts = fixture
+            .deck_service()
+            .merge_decks(source_id, dest_id, MergeConflict::KeepBoth)
+            .await
+            .unwrap();
+
+        assert_eq!(counts, DeckMergeCounts { moved: 3, skipped: 0 });
+        assert!(card_service.list_cards(source_id, 10).await.unwrap().is_empty());
+        let merged = card_service.list_cards(dest_id, 10).await.unwrap();
+        assert_eq!(merged.len(), 4);
+        let duplicates = merged
+            .iter()
+            .filter(|card| card.prompt().text().trim().eq_ignore_ascii_case("q1"))
+            .count();
+        assert_eq!(duplicates, 2);
+    }
+
+    #[tokio::test]
+    async fn merge_into_the_same_deck_is_refused() {
+        let fixture = ServicesFixture::builder().with_cards(1).build().await.unwrap();
+        let deck_id = fixture.deck_id();
+
+        let err = fixture
+            .deck_service()
+            .merge_decks(deck_id, deck_id, MergeConflict::KeepBoth)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, DeckServiceError::MergeIntoSelf));
+    }
+
+    fn limited_settings(new_per_day: u32, reviews_per_day: u32) -> DeckSettings {
+        DeckSettings::new(
+            new_per_day, reviews_per_day, 5, false, true, 86_400, false, false, false, 25, 20,
+            86_400, 365, false, 0.5, 0, 0.85, true, 100,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn deck_counts_cap_new_cards_at_the_daily_limit() {
+        let fixture = ServicesFixture::builder()
+            .with_deck("Capped", limited_settings(2, 50))
+            .with_cards(5)
+            .build()
+            .await
+            .unwrap();
+
+        let counts = fixture
+            .deck_service()
+            .deck_counts(std::slice::from_ref(fixture.deck()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            counts.get(&fixture.deck_id()),
+            Some(&DeckDueCounts {
+                due: 0,
+                learning: 0,
+                new: 2,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn deck_counts_cap_due_cards_at_the_review_limit() {
+        let fixture = ServicesFixture::builder()
+            .with_deck("Busy", limited_settings(10, 2))
+            .with_cards(4)
+            .with_reviews([ReviewGrade::Good, ReviewGrade::Good, ReviewGrade::Again])
+            .build()
+            .await
+            .unwrap();
+        let mut clock = fixture.clock();
+        clock.advance(chrono::Duration::days(60));
+        let storage = fixture.storage();
+        let service = DeckService::new(
+            clock,
+            Arc::clone(&storage.decks),
+            Arc::clone(&storage.cards),
+            Arc::clone(&storage.review_logs),
+        );
+
+        let counts = service
+            .deck_counts(std::slice::from_ref(fixture.deck()))
+            .await
+            .unwrap()[&fixture.deck_id()];
+
+        assert_eq!(counts.due + counts.learning, 2);
+        assert_eq!(counts.new, 1);
+    }
+
+    #[tokio::test]
+    async fn maturity_splits_cards_at_the_three_week_interval() {
+        let fixture = ServicesFixture::builder().with_cards(5).build().await.unwrap();
+        let deck_id = fixture.deck_id();
+        let now = fixture.clock().now();
+        let store = &fixture.storage().cards;
+        for (card, interval) in fixture.cards()[1..].iter().zip([1, 20, 21, 90]) {
+            let reviewed = Card::from_persisted(
+                card.id(),
+                deck_id,
+                card.kind().clone(),
+                card.prompt().clone(),
+                card.answer().clone(),
+                card.created_at(),
+                now + chrono::Duration::days(interval),
+                Some(now),
+                CardPhase::Reviewing,
+                1,
+                5.0,
+                5.0,
+            )
+            .unwrap();
+            store.upsert_card(&reviewed).await.unwrap();
+        }
+
+        let counts = fixture.deck_service().maturity(deck_id).await.unwrap();
+
+        assert_eq!(
+            counts,
+            CardMaturityCounts {
+                new: 1,
+                young: 2,
+                mature: 2,
+            }
+        );
+        assert_eq!(counts.mature_percent(), 40);
+        let empty = fixture.deck_service().maturity(DeckId::new(999)).await.unwrap();
+        assert_eq!(empty.mature_percent(), 0);
+    }
+
+    #[tokio::test]
+    async fn deck_counts_report_zero_for_archived_decks() {
+        let fixture = ServicesFixture::builder().with_cards(3).build().await.unwrap();
+        let archived = fixture.deck().clone().with_archived(true);
+
+        let counts = fixture
+            .deck_service()
+            .deck_counts(std::slice::from_ref(&archived))
+            .await
+            .unwrap();
+
+        assert_eq!(counts.get(&archived.id()), Some(&DeckDueCounts::default()));
+    }
+
+    #[tokio::test]
+    async fn due_now_counts_sum_active_decks_and_skip_archived_ones() {
+        let fixture = ServicesFixture::builder()
+            .with_deck("Capped", limited_settings(2, 50))
+            .with_cards(5)
+            .build()
+            .await
+            .unwrap();
+        let service = fixture.deck_service();
+        let other = service
+            .create_deck("Other".into(), None, limited_settings(3, 50))
+            .await
+            .unwrap();
+        let card_service = fixture.card_service();
+        for prompt in ["a", "b"] {
+            card_service
+                .create_card(
+                    other,
+                    ContentDraft::text_only(prompt),
+                    ContentDraft::text_only("answer"),
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+
+        let total = service.due_now_counts().await.unwrap();
+        assert_eq!((total.reviews(), total.new), (0, 4));
+
+        service.set_archived(other, true).await.unwrap();
+        assert_eq!(service.due_now_counts().await.unwrap().new, 2);
     }
 }
@@ -0,0 +1,625 @@
+use std::collections::HashSet;
+use std::io::BufRead;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use learn_core::model::{Card, ContentDraft, Deck, DeckId, DeckSettings};
+
+use crate::card_service::{CardService, FrontNormalization};
+use crate::deck_service::DeckService;
+use crate::error::DeckTransferError;
+
+/// Current version of the deck export document.
+pub const DECK_EXPORT_VERSION: u32 = 1;
+
+/// Portable JSON document describing a deck and its card content.
+///
+/// Scheduling state and media are intentionally not included.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeckExport {
+    pub version: u32,
+    pub deck: DeckExportMeta,
+    pub cards: Vec<CardExport>,
+}
+
+/// First line of a JSON-lines export; every later line is one `CardExport`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeckExportHeader {
+    pub version: u32,
+    pub deck: DeckExportMeta,
+}
+
+impl DeckExportHeader {
+    /// Check the version and deck name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckTransferError::UnsupportedVersion` for unknown versions and
+    /// `DeckTransferError::EmptyDeckName` for a blank deck name.
+    pub fn validate(&self) -> Result<(), DeckTransferError> {
+        if self.version != DECK_EXPORT_VERSION {
+            return Err(DeckTransferError::UnsupportedVersion(self.version));
+        }
+        if self.deck.name.trim().is_empty() {
+            return Err(DeckTransferError::EmptyDeckName);
+        }
+        Ok(())
+    }
+}
+
+/// Deck metadata and settings within a `DeckExport`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeckExportMeta {
+    pub name: String,
+    pub description: Option<String>,
+    pub settings: DeckSettings,
+}
+
+impl DeckExportMeta {
+    #[must_use]
+    pub fn from_deck(deck: &Deck) -> Self {
+        Self {
+            name: deck.name().to_owned(),
+            description: deck.description().map(str::to_owned),
+            settings: deck.settings().clone(),
+        }
+    }
+}
+
+/// Card content within a `DeckExport`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardExport {
+    pub prompt: ContentDraft,
+    pub answer: ContentDraft,
+    /// Extra info shown beneath the answer; absent in older exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra: Option<ContentDraft>,
+}
+
+impl CardExport {
+    /// The text of a stored card's prompt, answer, and extra info.
+    #[must_use]
+    pub fn from_card(card: &Card) -> Self {
+        Self {
+            prompt: ContentDraft::text_only(card.prompt().text()),
+            answer: ContentDraft::text_only(card.answer().text()),
+            extra: card.extra().map(|extra| ContentDraft::text_only(extra.text())),
+        }
+    }
+
+    /// Reject blank content, reporting the card's zero-based `index` in its export.
+    fn validate(&self, index: usize) -> Result<(), DeckTransferError> {
+        if self.prompt.text().trim().is_empty() || self.answer.text().trim().is_empty() {
+            return Err(DeckTransferError::InvalidCard { index });
+        }
+        Ok(())
+    }
+}
+
+impl DeckExport {
+    /// Parse and validate an export document.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckTransferError::InvalidJson` if the JSON does not match the shape.
+    /// Returns `DeckTransferError::UnsupportedVersion`, `EmptyDeckName`, or
+    /// `InvalidCard` if the document fails validation.
+    pub fn from_json(json: &str) -> Result<Self, DeckTransferError> {
+        let export: Self = serde_json::from_str(json)?;
+        export.validate()?;
+        Ok(export)
+    }
+
+    /// Check the document can be imported in full before anything is written.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckTransferError::UnsupportedVersion` for unknown versions,
+    /// `DeckTransferError::EmptyDeckName` for a blank deck name, and
+    /// `DeckTransferError::InvalidCard` for the first card with blank content.
+    pub fn validate(&self) -> Result<(), DeckTransferError> {
+        if self.version != DECK_EXPORT_VERSION {
+            return Err(DeckTransferError::UnsupportedVersion(self.version));
+        }
+        if self.deck.name.trim().is_empty() {
+            return Err(DeckTransferError::EmptyDeckName);
+        }
+        for (index, card) in self.cards.iter().enumerate() {
+            card.validate(index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Where `DeckTransferService::import_deck` writes cards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ImportTarget {
+    /// Create a new deck from the document metadata and settings.
+    NewDeck,
+    /// Append cards to an existing deck, keeping its settings.
+    Existing(DeckId),
+}
+
+/// Counts reported after an import.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImportReport {
+    pub deck_id: DeckId,
+    pub imported: u32,
+    pub skipped: u32,
+}
+
+/// Exports and imports decks as portable documents via `DeckService` and `CardService`.
+#[derive(Clone)]
+pub struct DeckTransferService {
+    decks: Arc<DeckService>,
+    cards: Arc<CardService>,
+}
+
+impl DeckTransferService {
+    #[must_use]
+    pub fn new(decks: Arc<DeckService>, cards: Arc<CardService>) -> Self {
+        Self { decks, cards }
+    }
+
+    /// Build an export document for a deck, with cards ordered by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckTransferError::DeckNotFound` if the deck does not exist.
+    /// Returns `DeckTransferError::Deck`/`Card` if repository access fails.
+    pub async fn export_deck(&self, deck_id: DeckId) -> Result<DeckExport, DeckTransferError> {
+        let deck = self
+            .decks
+            .get_deck(deck_id)
+            .await?
+            .ok_or(DeckTransferError::DeckNotFound(deck_id))?;
+
+        let mut cards = self.cards.list_cards(deck_id, u32::MAX).await?;
+        cards.sort_by_key(|card| card.id().value());
+
+        Ok(DeckExport {
+            version: DECK_EXPORT_VERSION,
+            deck: DeckExportMeta::from_deck(&deck),
+            cards: cards.iter().map(CardExport::from_card).collect(),
+        })
+    }
+
+    /// Import a validated export document.
+    ///
+    /// When `skip_duplicates` is true, cards whose prompt already exists in the target
+    /// deck (trimmed, case-insensitive) are skipped instead of created.
+    ///
+    /// # Errors
+    ///
+    /// Returns validation errors from `DeckExport::validate` before writing anything.
+    /// Returns `DeckTransferError::DeckNotFound` if an existing target deck is missing.
+    /// Returns `DeckTransferError::Deck`/`Card` if persistence fails.
+    pub async fn import_deck(
+        &self,
+        export: &DeckExport,
+        target: ImportTarget,
+        skip_duplicates: bool,
+    ) -> Result<ImportReport, DeckTransferError> {
+        export.validate()?;
+
+        let deck_id = self.target_deck(&export.deck, target).await?;
+        let mut fronts = self.existing_fronts(deck_id, skip_duplicates).await?;
+        let mut report = ImportReport {
+            deck_id,
+            imported: 0,
+            skipped: 0,
+        };
+        for card in &export.cards {
+            self.import_card(deck_id, card, fronts.as_mut(), &mut report)
+                .await?;
+        }
+        Ok(report)
+    }
+
+    /// Import a JSON-lines export, as written by `DeckService::export_jsonl`, reading one
+    /// line at a time: a `DeckExportHeader` line followed by one `CardExport` per line.
+    /// Blank lines are skipped.
+    ///
+    /// Each card is checked and imported as its line is read, so a bad line stops the
+    /// import with the cards before it already written.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DeckTransferError::InvalidJson` if the header is missing or a line does
+    /// not match its shape, and `DeckTransferError::Io` if reading fails.
+    /// Returns the validation errors of `DeckExportHeader::validate` before writing
+    /// anything, and `DeckTransferError::InvalidCard` for the first blank card.
+    /// Returns `DeckTransferError::DeckNotFound` if an existing target deck is missing.
+    /// Returns `DeckTransferError::Deck`/`Card` if persistence fails.
+    pub async fn import_jsonl(
+        &self,
+        reader: impl BufRead + Send,
+        target: ImportTarget,
+        skip_duplicates: bool,
+    ) -> Result<ImportReport, DeckTransferError> {
+        let mut lines = reader.lines();
+        let header: DeckExportHeader =
+            serde_json::from_str(&next_line(&mut lines)?.unwrap_or_default())?;
+        header.validate()?;
+
+        let deck_id = self.target_deck(&header.deck, target).await?;
+        let mut fronts = self.existing_fronts(deck_id, skip_duplicates).await?;
+        let mut report = ImportReport {
+            deck_id,
+            imported: 0,
+            skipped: 0,
+        };
+        let mut index = 0;
+        while let Some(line) = next_line(&mut lines)? {
+            let card: CardExport = serde_json::from_str(&line)?;
+            card.validate(index)?;
+            self.import_card(deck_id, &card, fronts.as_mut(), &mut report)
+                .await?;
+            index += 1;
+        }
+        Ok(report)
+    }
+
+    /// Create the deck described by `meta`, or check the existing `target` deck.
+    async fn target_deck(
+        &self,
+        meta: &DeckExportMeta,
+        target: ImportTarget,
+    ) -> Result<DeckId, DeckTransferError> {
+        Ok(match target {
+            ImportTarget::NewDeck => {
+                self.decks
+                    .create_deck(
+                        meta.name.clone(),
+                        meta.description.clone(),
+                        meta.settings.clone(),
+                    )
+                    .await?
+            }
+            ImportTarget::Existing(deck_id) => {
+                self.decks
+                    .get_deck(deck_id)
+                    .await?
+                    .ok_or(DeckTransferError::DeckNotFound(deck_id))?
+                    .id()
+            }
+        })
+    }
+
+    /// Fronts already in the deck when skipping duplicates, normalized once so each
+    /// imported card is a set lookup.
+    async fn existing_fronts(
+        &self,
+        deck_id: DeckId,
+        skip_duplicates: bool,
+    ) -> Result<Option<HashSet<String>>, DeckTransferError> {
+        if !skip_duplicates {
+            return Ok(None);
+        }
+        let normalization = FrontNormalization::default();
+        let cards = self.cards.list_cards(deck_id, u32::MAX).await?;
+        Ok(Some(
+            cards
+                .iter()
+                .map(|card| normalization.normalize(card.prompt().text()))
+                .collect(),
+        ))
+    }
+
+    /// Create one card, or count it as skipped when its front is already in `fronts`.
+    async fn import_card(
+        &self,
+        deck_id: DeckId,
+        card: &CardExport,
+        fronts: Option<&mut HashSet<String>>,
+        report: &mut ImportReport,
+    ) -> Result<(), DeckTransferError> {
+        if let Some(fronts) = fronts {
+            let front = FrontNormalization::default().normalize(card.prompt.text());
+            if !front.trim().is_empty() && !fronts.insert(front) {
+                report.skipped += 1;
+                return Ok(());
+            }
+        }
+        let created = self
+            .cards
+            .create_card(deck_id, card.prompt.clone(), card.answer.clone(), None)
+            .await?
+            .id;
+        if card.extra.is_some() {
+            self.cards
+                .update_card_extra(deck_id, created, card.extra.clone())
+                .await?;
+        }
+        report.imported += 1;
+        Ok(())
+    }
+}
+
+/// The next non-blank line, or `None` at the end of the stream.
+fn next_line(
+    lines: &mut impl Iterator<Item = std::io::Result<String>>,
+) -> Result<Option<String>, DeckTransferError> {
+    for line in lines {
+        let line = line?;
+        if !line.trim().is_empty() {
+            return Ok(Some(line));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use learn_core::model::{DeckColor, DeckIcon, ReviewGrade};
+
+    use crate::error::DeckServiceError;
+    use crate::test_support::ServicesFixture;
+
+    fn transfer_service(fixture: &ServicesFixture) -> DeckTransferService {
+        DeckTransferService::new(
+            Arc::new(fixture.deck_service()),
+            Arc::new(fixture.card_service()),
+        )
+    }
+
+    #[tokio::test]
+    async fn export_deck_includes_settings_and_card_content() {
+        let fixture = ServicesFixture::builder()
+            .with_cards(2)
+            .with_reviews([ReviewGrade::Good])
+            .build()
+            .await
+            .unwrap();
+
+        let export = transfer_service(&fixture)
+            .export_deck(fixture.deck_id())
+            .await
+            .unwrap();
+
+        assert_eq!(export.version, DECK_EXPORT_VERSION);
+        assert_eq!(export.deck.name, "Fixture Deck");
+        assert_eq!(&export.deck.settings, fixture.deck().settings());
+        assert_eq!(export.cards.len(), 2);
+        assert_eq!(export.cards[0].prompt.text(), "Q1");
+        assert_eq!(export.cards[1].answer.text(), "A2");
+
+        let json = serde_json::to_string(&export).unwrap();
+        let parsed: DeckExport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, export);
+    }
+
+    #[tokio::test]
+    async fn export_missing_deck_returns_not_found() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+
+        let err = transfer_service(&fixture)
+            .export_deck(DeckId::new(99))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, DeckTransferError::DeckNotFound(id) if id == DeckId::new(99)));
+    }
+
+    #[test]
+    fn deck_settings_deserialize_validates_and_defaults() {
+        let settings: DeckSettings = serde_json::from_str(r#"{"micro_session_size": 8}"#).unwrap();
+        assert_eq!(settings.micro_session_size(), 8);
+        assert_eq!(
+            settings.new_cards_per_day(),
+            DeckSettings::default_for_adhd().new_cards_per_day()
+        );
+
+        let invalid = serde_json::from_str::<DeckSettings>(r#"{"micro_session_size": 0}"#);
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn deck_settings_serialize_color_and_icon_by_name() {
+        let settings = DeckSettings::default_for_adhd()
+            .with_appearance(Some(DeckColor::Purple), Some(DeckIcon::Language));
+        let json = serde_json::to_string(&settings).unwrap();
+        assert!(json.contains(r#""color":"purple""#));
+        assert!(json.contains(r#""icon":"language""#));
+        assert_eq!(serde_json::from_str::<DeckSettings>(&json).unwrap(), settings);
+
+        let plain: DeckSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!((plain.color(), plain.icon()), (None, None));
+
+        let unknown = serde_json::from_str::<DeckSettings>(r#"{"color": "magenta"}"#);
+        assert!(unknown.is_err());
+    }
+
+    #[tokio::test]
+    async fn import_creates_new_deck_from_export() {
+        let source = ServicesFixture::builder().with_cards(3).build().await.unwrap();
+        let export = transfer_service(&source)
+            .export_deck(source.deck_id())
+            .await
+            .unwrap();
+        let json = serde_json::to_string(&export).unwrap();
+
+        let target = ServicesFixture::builder()
+            .with_deck("Target Deck", DeckSettings::default_for_adhd())
+            .build()
+            .await
+            .unwrap();
+        let parsed = DeckExport::from_json(&json).unwrap();
+        let report = transfer_service(&target)
+            .import_deck(&parsed, ImportTarget::NewDeck, false)
+            .await
+            .unwrap();
+        let err = transfer_service(&target)
+            .import_deck(&parsed, ImportTarget::NewDeck, false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DeckTransferError::Deck(DeckServiceError::DuplicateName)));
+
+        assert_ne!(report.deck_id, target.deck_id());
+        assert_eq!(report.imported, 3);
+        assert_eq!(report.skipped, 0);
+        let reexported = transfer_service(&target)
+            .export_deck(report.deck_id)
+            .await
+            .unwrap();
+        assert_eq!(reexported, export);
+    }
+
+    #[tokio::test]
+    async fn import_skips_duplicates_in_existing_deck() {
+        let fixture = ServicesFixture::builder().with_cards(2).build().await.unwrap();
+        let mut export = transfer_service(&fixture)
+            .export_deck(fixture.deck_id())
+            .await
+            .unwrap();
+        export.cards.push(CardExport {
+            prompt: ContentDraft::text_only("New front"),
+            answer: ContentDraft::text_only("New back"),
+            extra: Some(ContentDraft::text_only("New note")),
+        });
+        // A repeat within the import is skipped too, once its first copy is in.
+        export.cards.push(CardExport {
+            prompt: ContentDraft::text_only("  new   FRONT "),
+            answer: ContentDraft::text_only("Repeat"),
+            extra: None,
+        });
+
+        let report = transfer_service(&fixture)
+            .import_deck(&export, ImportTarget::Existing(fixture.deck_id()), true)
+            .await
+            .unwrap();
+
+        assert_eq!(report.deck_id, fixture.deck_id());
+        assert_eq!(report.imported, 1);
+        assert_eq!(report.skipped, 3);
+        let reexported = transfer_service(&fixture)
+            .export_deck(fixture.deck_id())
+            .await
+            .unwrap();
+        assert_eq!(reexported.cards.last(), export.cards.get(2));
+    }
+
+    #[tokio::test]
+    async fn import_rejects_invalid_document_before_writing() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let export = DeckExport {
+            version: DECK_EXPORT_VERSION,
+            deck: DeckExportMeta {
+                name: "Imported".to_string(),
+                description: None,
+                settings: DeckSettings::default_for_adhd(),
+            },
+            cards: vec![
+                CardExport {
+                    prompt: ContentDraft::text_only("Q"),
+                    answer: ContentDraft::text_only("A"),
+                    extra: None,
+                },
+                CardExport {
+                    prompt: ContentDraft::text_only("  "),
+                    answer: ContentDraft::text_only("A"),
+                    extra: None,
+                },
+            ],
+        };
+
+        let err = transfer_service(&fixture)
+            .import_deck(&export, ImportTarget::NewDeck, false)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, DeckTransferError::InvalidCard { index: 1 }));
+        let decks = fixture.deck_service().list_decks(10, false).await.unwrap();
+        assert_eq!(decks.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn jsonl_export_streams_a_header_and_one_card_per_line_and_reimports() {
+        let source = ServicesFixture::builder().with_cards(3).build().await.unwrap();
+        let mut jsonl = Vec::new();
+        let written = source
+            .deck_service()
+            .export_jsonl(source.deck_id(), &mut jsonl)
+            .await
+            .unwrap();
+        let jsonl = String::from_utf8(jsonl).unwrap();
+
+        assert_eq!(written, 3);
+        assert_eq!(jsonl.lines().count(), 4);
+        let header: DeckExportHeader =
+            serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+        assert_eq!(header.version, DECK_EXPORT_VERSION);
+        assert_eq!(&header.deck.settings, source.deck().settings());
+
+        let target = ServicesFixture::builder()
+            .with_deck("Target Deck", DeckSettings::default_for_adhd())
+            .build()
+            .await
+            .unwrap();
+        let report = transfer_service(&target)
+            .import_jsonl(jsonl.as_bytes(), ImportTarget::NewDeck, false)
+            .await
+            .unwrap();
+
+        assert_eq!(report.imported, 3);
+        let original = transfer_service(&source)
+            .export_deck(source.deck_id())
+            .await
+            .unwrap();
+        let mut reimported = transfer_service(&target)
+            .export_deck(report.deck_id)
+            .await
+            .unwrap();
+        reimported.cards.sort_by(|a, b| a.prompt.text().cmp(b.prompt.text()));
+        assert_eq!(reimported, original);
+    }
+
+    #[tokio::test]
+    async fn import_jsonl_rejects_a_missing_header_and_stops_at_bad_lines() {
+        let fixture = ServicesFixture::builder().build().await.unwrap();
+        let transfer = transfer_service(&fixture);
+        let existing = ImportTarget::Existing(fixture.deck_id());
+        let err = transfer
+            .import_jsonl("".as_bytes(), ImportTarget::NewDeck, false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DeckTransferError::InvalidJson(_)));
+        assert_eq!(fixture.deck_service().list_decks(10, false).await.unwrap().len(), 1);
+
+        let header =
+            r#"{"version": 1, "deck": {"name": "D", "description": null, "settings": {}}}"#;
+        let jsonl = format!("{header}\n{{\"prompt\": 3}}");
+        let err = transfer
+            .import_jsonl(jsonl.as_bytes(), existing, false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DeckTransferError::InvalidJson(_)));
+
+        let card = r#"{"prompt": {"text": "Q"}, "answer": {"text": "A"}}"#;
+        let blank = r#"{"prompt": {"text": " "}, "answer": {"text": "A"}}"#;
+        let jsonl = format!("{header}\n\n{card}\n{blank}\n{card}\n");
+        let err = transfer
+            .import_jsonl(jsonl.as_bytes(), existing, false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DeckTransferError::InvalidCard { index: 1 }));
+        let cards = fixture.card_service().list_cards(fixture.deck_id(), 10).await.unwrap();
+        assert_eq!(cards.len(), 1);
+    }
+
+    #[test]
+    fn from_json_rejects_malformed_shape() {
+        let err = DeckExport::from_json(r#"{"version": 1, "cards": []}"#).unwrap_err();
+        assert!(matches!(err, DeckTransferError::InvalidJson(_)));
+
+        let err = DeckExport::from_json(
+            r#"{"version": 7, "deck": {"name": "D", "description": null, "settings": {}}, "cards": []}"#,
+        )
+        .unwrap_err();
+        assert!(matches!(err, DeckTransferError::UnsupportedVersion(7)));
+    }
+}
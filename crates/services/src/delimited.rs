@@ -0,0 +1,129 @@
+//! Minimal CSV/TSV reader for bulk card imports.
+//!
+//! Follows RFC 4180 quoting: a field starting with `"` may contain the delimiter and line
+//! breaks, and `""` inside it is a literal quote. Quotes elsewhere are kept as text.
+
+use crate::error::CardServiceError;
+
+/// One parsed row with its 1-based position in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DelimitedRow {
+    pub row: usize,
+    pub fields: Vec<String>,
+}
+
+/// Split `text` into rows of fields.
+///
+/// Empty lines are skipped but still count towards row numbers, so numbers match what a
+/// spreadsheet shows. A leading byte-order mark is ignored.
+///
+/// # Errors
+///
+/// Returns `CardServiceError::InvalidDelimiter` for `"`, `\r` or `\n`, and
+/// `CardServiceError::UnterminatedQuote` if the input ends inside a quoted field.
+pub(crate) fn parse_delimited(
+    text: &str,
+    delimiter: char,
+) -> Result<Vec<DelimitedRow>, CardServiceError> {
+    if matches!(delimiter, '"' | '\r' | '\n') {
+        return Err(CardServiceError::InvalidDelimiter(delimiter));
+    }
+
+    let mut rows = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut quoted = false;
+    let mut row = 1;
+
+    let mut chars = text.strip_prefix('\u{feff}').unwrap_or(text).chars().peekable();
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(ch),
+            }
+            continue;
+        }
+
+        match ch {
+            '"' if field.is_empty() && !quoted => {
+                in_quotes = true;
+                quoted = true;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => {
+                if !fields.is_empty() || !field.is_empty() || quoted {
+                    fields.push(std::mem::take(&mut field));
+                    rows.push(DelimitedRow {
+                        row,
+                        fields: std::mem::take(&mut fields),
+                    });
+                }
+                quoted = false;
+                row += 1;
+            }
+            _ if ch == delimiter => {
+                fields.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            _ => field.push(ch),
+        }
+    }
+
+    if in_quotes {
+        return Err(CardServiceError::UnterminatedQuote { row });
+    }
+    if !fields.is_empty() || !field.is_empty() || quoted {
+        fields.push(field);
+        rows.push(DelimitedRow { row, fields });
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(rows: &[DelimitedRow]) -> Vec<Vec<&str>> {
+        rows.iter()
+            .map(|row| row.fields.iter().map(String::as_str).collect())
+            .collect()
+    }
+
+    #[test]
+    fn splits_rows_and_skips_blank_lines() {
+        let rows = parse_delimited("a,b\r\n\nc,d", ',').unwrap();
+
+        assert_eq!(fields(&rows), vec![vec!["a", "b"], vec!["c", "d"]]);
+        assert_eq!(rows[1].row, 3);
+    }
+
+    #[test]
+    fn quoted_fields_keep_delimiters_newlines_and_quotes() {
+        let rows = parse_delimited("\"x\ty\"\t\"line 1\nline 2\"\n\"say \"\"hi\"\"\"\t\"\"\n", '\t')
+            .unwrap();
+
+        assert_eq!(
+            fields(&rows),
+            vec![vec!["x\ty", "line 1\nline 2"], vec!["say \"hi\"", ""]]
+        );
+        assert_eq!(rows[1].row, 2);
+    }
+
+    #[test]
+    fn rejects_unterminated_quote_and_bad_delimiter() {
+        assert!(matches!(
+            parse_delimited("a,b\n\"open,c", ','),
+            Err(CardServiceError::UnterminatedQuote { row: 2 })
+        ));
+        assert!(matches!(
+            parse_delimited("a", '"'),
+            Err(CardServiceError::InvalidDelimiter('"'))
+        ));
+    }
+}
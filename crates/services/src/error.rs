@@ -2,7 +2,10 @@
 
 use thiserror::Error;
 
-use learn_core::model::{AppSettingsError, CardError, DeckError, SessionSummaryError};
+use learn_core::model::{
+    AppSettingsError, CardError, CardId, DeckError, DeckId, MediaValidationError,
+    SessionSummaryError,
+};
 use learn_core::scheduler::SchedulerError;
 use storage::repository::StorageError;
 use storage::sqlite::SqliteInitError;
@@ -11,10 +14,8 @@ use storage::sqlite::SqliteInitError;
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum AiUsageError {
-    #[error("daily request cap reached ({cap})")]
-    DailyCapReached { cap: u32 },
-    #[error("cooldown active ({remaining_secs}s remaining)")]
-    CooldownActive { remaining_secs: u32 },
+    #[error("AI request limit reached (retry in {retry_after_secs}s)")]
+    RateLimited { retry_after_secs: u32 },
     #[error("pricing missing for provider {provider} model {model}")]
     MissingPriceEntry { provider: String, model: String },
     #[error(transparent)]
@@ -35,6 +36,10 @@ pub enum WritingToolsError {
     InvalidResponse,
     #[error("writing tools request failed with status {0}")]
     HttpStatus(reqwest::StatusCode),
+    #[error("writing tools request timed out")]
+    Timeout,
+    #[error("writing tools could not reach the provider")]
+    Offline,
     #[error(transparent)]
     Usage(#[from] AiUsageError),
     #[error(transparent)]
@@ -43,32 +48,189 @@ pub enum WritingToolsError {
     Http(#[from] reqwest::Error),
 }
 
+/// What kind of failure a writing-tools error is, so callers can pick a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WritingToolsErrorKind {
+    /// A timeout or server-side failure; the same request may succeed later.
+    Retryable,
+    /// The provider or the local usage limits turned the request away for now.
+    RateLimited,
+    /// The API key is missing or was rejected.
+    Auth,
+    /// The provider could not be reached.
+    Offline,
+    /// A failure retrying will not fix, such as a malformed response.
+    Other,
+}
+
+impl WritingToolsError {
+    #[must_use]
+    pub fn kind(&self) -> WritingToolsErrorKind {
+        match self {
+            Self::Disabled => WritingToolsErrorKind::Auth,
+            Self::HttpStatus(status) => match *status {
+                reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                    WritingToolsErrorKind::Auth
+                }
+                reqwest::StatusCode::TOO_MANY_REQUESTS => WritingToolsErrorKind::RateLimited,
+                reqwest::StatusCode::REQUEST_TIMEOUT => WritingToolsErrorKind::Retryable,
+                status if status.is_server_error() => WritingToolsErrorKind::Retryable,
+                _ => WritingToolsErrorKind::Other,
+            },
+            Self::Timeout => WritingToolsErrorKind::Retryable,
+            Self::Offline => WritingToolsErrorKind::Offline,
+            Self::Usage(AiUsageError::RateLimited { .. }) => WritingToolsErrorKind::RateLimited,
+            Self::Http(err) if err.is_timeout() => WritingToolsErrorKind::Retryable,
+            Self::Http(err) if err.is_connect() => WritingToolsErrorKind::Offline,
+            _ => WritingToolsErrorKind::Other,
+        }
+    }
+
+    /// Whether sending the same request again may succeed.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self.kind(),
+            WritingToolsErrorKind::Retryable | WritingToolsErrorKind::Offline
+        )
+    }
+}
+
+/// Errors emitted by `AiWritingService`.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum AiWritingError {
+    #[error("AI request limit reached (retry in {retry_after_secs}s)")]
+    RateLimited { retry_after_secs: u32 },
+    #[error(transparent)]
+    Tools(#[from] WritingToolsError),
+}
+
+impl AiWritingError {
+    #[must_use]
+    pub fn kind(&self) -> WritingToolsErrorKind {
+        match self {
+            Self::RateLimited { .. } => WritingToolsErrorKind::RateLimited,
+            Self::Tools(err) => err.kind(),
+        }
+    }
+}
+
 /// Errors emitted by `ReviewService`.
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum ReviewServiceError {
+    #[error("FSRS optimization is turned off for this deck")]
+    OptimizerDisabled,
+    #[error("FSRS optimization needs more than {required} reviews, found {found}")]
+    NotEnoughReviews { required: u32, found: usize },
+    #[error(transparent)]
+    Deck(#[from] DeckError),
     #[error(transparent)]
     Scheduler(#[from] SchedulerError),
     #[error(transparent)]
     Storage(#[from] StorageError),
+    #[error("failed to write log export: {0}")]
+    Write(#[from] std::io::Error),
 }
 
 /// Errors emitted by `CardService`.
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum CardServiceError {
+    #[error("a card with this front already exists ({0})")]
+    DuplicateFront(CardId),
+    #[error("card {card_id} is not in deck {deck_id}")]
+    CardNotInDeck { card_id: CardId, deck_id: DeckId },
+    #[error("deck {0} not found")]
+    DeckNotFound(DeckId),
+    #[error("import delimiter {0:?} cannot be a quote or line break")]
+    InvalidDelimiter(char),
+    #[error("import row {row} has an unterminated quoted field")]
+    UnterminatedQuote { row: usize },
+    #[error("search text is empty")]
+    EmptySearch,
+    #[error("failed to read import data: {0}")]
+    Read(#[from] std::io::Error),
     #[error(transparent)]
     Card(#[from] CardError),
     #[error(transparent)]
     Storage(#[from] StorageError),
 }
 
+/// Errors emitted by `MediaService`.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum MediaServiceError {
+    #[error(transparent)]
+    Invalid(#[from] MediaValidationError),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
 /// Errors emitted by `DeckService`.
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum DeckServiceError {
     #[error(transparent)]
     Deck(#[from] DeckError),
+    #[error("deck was changed since it was loaded")]
+    Conflict,
+    #[error("another deck already has this name")]
+    DuplicateName,
+    #[error("a deck cannot be merged into itself")]
+    MergeIntoSelf,
+    #[error("failed to write export: {0}")]
+    Write(#[from] std::io::Error),
+    #[error(transparent)]
+    Review(#[from] ReviewServiceError),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+/// Errors emitted by `DeckTransferService`.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum DeckTransferError {
+    #[error("deck {0} not found")]
+    DeckNotFound(DeckId),
+    #[error("unsupported export version {0}")]
+    UnsupportedVersion(u32),
+    #[error("export deck name cannot be empty")]
+    EmptyDeckName,
+    #[error("export card {index} has empty content")]
+    InvalidCard { index: usize },
+    #[error(transparent)]
+    InvalidJson(#[from] serde_json::Error),
+    #[error("failed to read the export: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Deck(#[from] DeckServiceError),
+    #[error(transparent)]
+    Card(#[from] CardServiceError),
+}
+
+/// Errors emitted by `AnkiImportService`.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum AnkiImportError {
+    #[error("not a valid .apkg archive: {0}")]
+    Archive(#[from] zip::result::ZipError),
+    #[error("the archive has no Anki collection")]
+    MissingCollection,
+    #[error("this collection needs \"support older Anki versions\" turned on when exporting")]
+    CompressedCollection,
+    #[error("collection note types or decks are not valid JSON: {0}")]
+    InvalidCollection(#[from] serde_json::Error),
+    #[error("a deck named {0:?} already exists")]
+    DeckNameTaken(String),
+    #[error("failed to read the archive: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Deck(#[from] DeckServiceError),
+    #[error(transparent)]
+    Media(#[from] MediaServiceError),
     #[error(transparent)]
     Storage(#[from] StorageError),
 }
@@ -83,6 +245,18 @@ pub enum SessionError {
     Completed,
     #[error("not enough grades to complete session")]
     InsufficientGrades,
+    #[error("no answer to undo")]
+    NothingToUndo,
+    #[error("the last answer was not a lapse")]
+    NothingToRestudy,
+    #[error("card is not waiting in this session")]
+    CardNotQueued,
+    #[error("card was skipped too often and has to be graded")]
+    SkipLimitReached,
+    #[error("the deck offers no answer button at position {0}")]
+    UnknownAnswerButton(usize),
+    #[error("chosen cards are not all in an active deck")]
+    CardsUnavailable,
     #[error(transparent)]
     Summary(#[from] SessionSummaryError),
     #[error(transparent)]
@@ -97,6 +271,9 @@ pub enum SessionError {
 pub enum AppServicesError {
     #[error(transparent)]
     Sqlite(#[from] SqliteInitError),
+    #[cfg(feature = "postgres")]
+    #[error(transparent)]
+    Postgres(#[from] storage::postgres::PostgresInitError),
     #[error(transparent)]
     Storage(#[from] StorageError),
     #[error(transparent)]
@@ -2,35 +2,63 @@
 
 pub mod error;
 pub mod ai;
+pub mod anki_import_service;
 pub mod app_services;
 pub mod app_settings_service;
 pub mod card_service;
 pub mod deck_service;
+pub mod deck_transfer_service;
+mod delimited;
+pub mod media_service;
 pub mod review_service;
 pub mod sessions;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 pub mod writing_tools_service;
 
-pub use learn_core::Clock;
+pub use learn_core::{Clock, StudyDay};
+pub use storage::repository::{
+    CardMaturityCounts, CardPhaseCounts, DeckMergeCounts, MergeConflict, ReviewLogPrune,
+};
 pub use sessions as session;
 
 pub use error::{
-    AiUsageError, AppSettingsServiceError, CardServiceError, DeckServiceError, ReviewServiceError,
-    SessionError, WritingToolsError,
+    AiUsageError, AiWritingError, AnkiImportError, AppSettingsServiceError, CardServiceError,
+    DeckServiceError, DeckTransferError, MediaServiceError, ReviewServiceError, SessionError,
+    WritingToolsError, WritingToolsErrorKind,
 };
 pub use error::AppServicesError;
+pub use anki_import_service::{AnkiImportReport, AnkiImportService};
 pub use app_settings_service::AppSettingsService;
 pub use app_services::AppServices;
 pub use card_service::{
-    CardListFilter, CardListSort, CardService, DeckPracticeStats, DeckPracticeStatsRow,
-    TagPracticeStats,
+    BrokenMedia, CardListFilter, CardListSort, CardPage, CardService, CreateCardOptions,
+    CreatedCard, CreatedNote, DeckPracticeStats, DeckPracticeStatsRow, DeckSearchResults,
+    DelimitedImportReport, DuplicatePolicy, FrontNormalization, MissingMedia,
+    ReplaceTextOptions, ReplaceTextReport, ResetCardOptions, TagPracticeStats,
+};
+pub use deck_service::{DeckDueCounts, DeckService, MATURE_INTERVAL_DAYS};
+pub use media_service::MediaService;
+pub use deck_transfer_service::{
+    CardExport, DECK_EXPORT_VERSION, DeckExport, DeckExportHeader, DeckExportMeta,
+    DeckTransferService, ImportReport, ImportTarget,
+};
+pub use review_service::{
+    CardHistoryEntry, LogExportFormat, PersistedReview, ReviewGradePreview, ReviewResult,
+    ReviewService,
+};
+pub use writing_tools_service::{
+    ChatCompletion, ChatCompletionClient, ChatMessage, ChatUsage, OpenAiChatClient, RetryPolicy,
+    WritingToolsConfig, WritingToolsOutput, WritingToolsService,
+};
+pub use ai::{
+    AiCostEstimate, AiUsageHandle, AiUsagePermit, AiUsageRange, AiUsageService, AiWritingService,
+    UnpricedUsage,
 };
-pub use deck_service::DeckService;
-pub use review_service::{PersistedReview, ReviewResult, ReviewService};
-pub use writing_tools_service::{WritingToolsConfig, WritingToolsOutput, WritingToolsService};
-pub use ai::{AiUsageHandle, AiUsageService};
 
 pub use sessions::{
-    ReviewGradePreview, SessionAnswerResult, SessionLoopService, SessionReview,
-    SessionReviewPreview, SessionService, SessionSummaryDeckItem, SessionSummaryId,
-    SessionSummaryListItem, SessionSummaryService,
+    CramFilter, DeckStats, NothingDue, SessionAnswerResult, SessionLoopService,
+    SessionPlanPreview, SessionProgress, SessionReview, SessionReviewPreview, SessionService,
+    SessionStart, SessionSummaryDeckItem, SessionSummaryId, SessionSummaryListItem,
+    SessionSummaryService, StatsRange,
 };
@@ -0,0 +1,157 @@
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use learn_core::model::{ImageMeta, MediaHash, MediaId};
+use sha2::{Digest, Sha256};
+use storage::repository::{MediaRepository, NewMediaRecord};
+
+use crate::error::MediaServiceError;
+use crate::Clock;
+
+/// Validates and stores images attached to card content.
+#[derive(Clone)]
+pub struct MediaService {
+    clock: Clock,
+    media: Arc<dyn MediaRepository>,
+    /// Images already found missing, so each is logged once rather than on every render.
+    reported_missing: Arc<Mutex<HashSet<MediaId>>>,
+}
+
+impl MediaService {
+    #[must_use]
+    pub fn new(clock: Clock, media: Arc<dyn MediaRepository>) -> Self {
+        Self {
+            clock,
+            media,
+            reported_missing: Arc::default(),
+        }
+    }
+
+    /// Validate raw image bytes and store them.
+    ///
+    /// Identical bytes resolve to the same `MediaId`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MediaServiceError::Invalid` if the bytes are not a supported image.
+    /// Returns `MediaServiceError::Storage` if persistence fails.
+    pub async fn store_image(&self, bytes: Vec<u8>) -> Result<MediaId, MediaServiceError> {
+        let (format, meta) = ImageMeta::probe(&bytes)?;
+        let record = NewMediaRecord {
+            format,
+            meta,
+            checksum: checksum(&bytes),
+            bytes,
+            created_at: self.clock.now(),
+        };
+        Ok(self.media.insert_media(record).await?)
+    }
+
+    /// Render a stored image as a `data:` URL for display.
+    ///
+    /// Returns `Ok(None)` when the image does not exist, e.g. after a database was restored
+    /// without its media; callers show a placeholder instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MediaServiceError::Storage` if repository access fails.
+    pub async fn image_data_url(&self, id: MediaId) -> Result<Option<String>, MediaServiceError> {
+        let Some(record) = self.media.get_media(id).await? else {
+            self.report_missing(id);
+            return Ok(None);
+        };
+        Ok(Some(format!(
+            "data:{};base64,{}",
+            record.format.mime_type(),
+            STANDARD.encode(&record.bytes)
+        )))
+    }
+
+    /// Note that a card refers to an image that is not stored.
+    ///
+    /// Returns whether this is the first time `id` was found missing; only then is it logged.
+    fn report_missing(&self, id: MediaId) -> bool {
+        let first = self
+            .reported_missing
+            .lock()
+            .is_ok_and(|mut reported| reported.insert(id));
+        #[cfg(feature = "tracing")]
+        if first {
+            tracing::warn!(media_id = id.value(), "card image is missing from storage");
+        }
+        first
+    }
+}
+
+fn checksum(bytes: &[u8]) -> MediaHash {
+    let digest = Sha256::digest(bytes);
+    let mut hex = String::with_capacity(7 + digest.len() * 2);
+    hex.push_str("sha256:");
+    for byte in digest {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    MediaHash::new(hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use learn_core::model::MediaValidationError;
+    use learn_core::time::fixed_now;
+    use storage::repository::InMemoryRepository;
+
+    fn service() -> MediaService {
+        MediaService::new(Clock::fixed(fixed_now()), Arc::new(InMemoryRepository::new()))
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+        bytes
+    }
+
+    #[tokio::test]
+    async fn stores_images_once_per_checksum() {
+        let service = service();
+
+        let first = service.store_image(png(4, 3)).await.unwrap();
+        let again = service.store_image(png(4, 3)).await.unwrap();
+        let other = service.store_image(png(8, 6)).await.unwrap();
+
+        assert_eq!(first, again);
+        assert_ne!(first, other);
+        let url = service.image_data_url(first).await.unwrap().unwrap();
+        assert!(url.starts_with("data:image/png;base64,iVBORw0KGgo"));
+        assert_eq!(service.image_data_url(MediaId::new(99)).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn reports_each_missing_image_once() {
+        let service = service();
+        let missing = MediaId::new(99);
+
+        assert_eq!(service.image_data_url(missing).await.unwrap(), None);
+        assert_eq!(service.image_data_url(missing).await.unwrap(), None);
+
+        assert!(!service.report_missing(missing));
+        assert!(service.report_missing(MediaId::new(100)));
+        // Clones share what has been reported.
+        assert!(!service.clone().report_missing(missing));
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_bytes() {
+        let err = service().store_image(b"not an image".to_vec()).await.unwrap_err();
+
+        assert!(matches!(
+            err,
+            MediaServiceError::Invalid(MediaValidationError::UnsupportedImageFormat)
+        ));
+    }
+}
@@ -1,17 +1,41 @@
-use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
 use learn_core::{
-    model::{Card, CardId, DeckId, DeckSettings, ReviewGrade},
-    scheduler::{AppliedReview, MemoryState, Scheduler},
-    time::Clock,
+    model::{Card, CardId, CardPhase, DeckId, DeckSettings, LearningStep, ReviewGrade, ReviewLog},
+    optimizer::{self, FittedParameters},
+    scheduler::{
+        AppliedReview, IntervalAdjustmentKind, MemoryState, ReviewExplanation, Scheduler,
+    },
+    time::{Clock, StudyDay},
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use storage::repository::{
+    CardRepository, DeckRepository, ReviewLogPrune, ReviewLogRecord, ReviewLogRepository,
+    ReviewPersistence, StorageError,
 };
-use storage::repository::{CardRepository, ReviewLogRecord, ReviewPersistence, StorageError};
 
-const SECONDS_PER_DAY: f64 = 86_400.0;
 use crate::error::ReviewServiceError;
 
+const SECONDS_PER_DAY: f64 = 86_400.0;
+/// Review logs read from storage per round trip while exporting.
+const EXPORT_PAGE_SIZE: u32 = 500;
+/// How far below target retention a load-balanced card may fall on its new due date.
+const LOAD_BALANCE_RETENTION_SLACK: f64 = 0.02;
+/// Graded reviews a card may have behind it and still get a fuzzed interval.
+const INTERVAL_FUZZ_REVIEWS: u32 = 3;
+/// Largest share of an interval that fuzzing adds or removes.
+const INTERVAL_FUZZ_RATIO: f64 = 0.1;
+/// Shortest interval, in days, that fuzzing touches; a few hours matter to a 1-day card.
+const INTERVAL_FUZZ_MIN_DAYS: f64 = 2.0;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ReviewResult {
     pub applied: AppliedReview,
+    /// How the interval was reached; `None` for a review rebuilt from a saved session.
+    pub explanation: Option<ReviewExplanation>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -21,6 +45,77 @@ pub struct PersistedReview {
     pub result: ReviewResult,
 }
 
+/// One past review of a card, as shown in its history.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CardHistoryEntry {
+    pub grade: ReviewGrade,
+    pub reviewed_at: DateTime<Utc>,
+    /// Days from this review until the next one was scheduled.
+    pub interval_days: f64,
+    /// FSRS stability right after this review, in days.
+    pub stability: f64,
+}
+
+impl CardHistoryEntry {
+    #[must_use]
+    pub fn from_record(record: &ReviewLogRecord) -> Self {
+        Self {
+            grade: record.grade,
+            reviewed_at: record.reviewed_at,
+            interval_days: record.scheduled_days,
+            stability: record.stability,
+        }
+    }
+}
+
+/// Where one grade would schedule a card next.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewGradePreview {
+    pub grade: ReviewGrade,
+    pub next_review: DateTime<Utc>,
+    pub scheduled_days: f64,
+}
+
+/// File formats `ReviewService::export_logs` can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogExportFormat {
+    /// Comma-separated values with a header row.
+    Csv,
+}
+
+impl LogExportFormat {
+    /// Parse a format name as given on the command line, e.g. `csv`.
+    #[must_use]
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+fn grade_name(grade: ReviewGrade) -> &'static str {
+    match grade {
+        ReviewGrade::Again => "again",
+        ReviewGrade::Hard => "hard",
+        ReviewGrade::Good => "good",
+        ReviewGrade::Easy => "easy",
+    }
+}
+
+fn write_csv_log(writer: &mut impl Write, record: &ReviewLogRecord) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "{},{},{},{},{},{}",
+        record.card_id.value(),
+        record.reviewed_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        grade_name(record.grade),
+        record.scheduled_days,
+        record.stability,
+        record.difficulty,
+    )
+}
+
 #[must_use]
 pub fn compute_elapsed_days(
     last_review_at: Option<DateTime<Utc>>,
@@ -53,6 +148,12 @@ fn apply_lapse_min_interval(
     }
 }
 
+/// Schedule the card for its next learning step instead of the FSRS interval.
+fn apply_learning_step_delay(applied: &mut AppliedReview, reviewed_at: DateTime<Utc>, secs: u32) {
+    applied.outcome.scheduled_days = f64::from(secs) / SECONDS_PER_DAY;
+    applied.outcome.next_review = reviewed_at + chrono::Duration::seconds(i64::from(secs));
+}
+
 fn apply_interval_bounds(
     applied: &mut AppliedReview,
     reviewed_at: DateTime<Utc>,
@@ -75,9 +176,118 @@ fn apply_interval_bounds(
     }
 }
 
+/// Stretch or shrink the interval by up to `INTERVAL_FUZZ_RATIO`, staying within the deck's
+/// interval bounds. Intervals under `INTERVAL_FUZZ_MIN_DAYS` are left alone.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn apply_interval_fuzz(
+    applied: &mut AppliedReview,
+    reviewed_at: DateTime<Utc>,
+    settings: &DeckSettings,
+    rng: &mut impl Rng,
+) {
+    if applied.outcome.scheduled_days < INTERVAL_FUZZ_MIN_DAYS {
+        return;
+    }
+    let factor = rng.random_range(-INTERVAL_FUZZ_RATIO..=INTERVAL_FUZZ_RATIO);
+    let mut days = (applied.outcome.scheduled_days * (1.0 + factor))
+        .max(f64::from(settings.min_interval_secs()) / SECONDS_PER_DAY);
+    if settings.max_interval_days() > 0 {
+        days = days.min(f64::from(settings.max_interval_days()));
+    }
+    let secs = (days * SECONDS_PER_DAY).round() as i64;
+    applied.outcome.scheduled_days = secs as f64 / SECONDS_PER_DAY;
+    applied.outcome.next_review = reviewed_at + chrono::Duration::seconds(secs);
+}
+
+/// Due date for a review card at `scheduler`'s retention, counted from its last review.
+#[allow(clippy::cast_possible_truncation)]
+fn rescheduled_due_at(
+    scheduler: &Scheduler,
+    card: &Card,
+    settings: &DeckSettings,
+) -> Option<DateTime<Utc>> {
+    if card.phase() != CardPhase::Reviewing {
+        return None;
+    }
+    let state = card.memory_state()?;
+    let last_review_at = card.last_review_at()?;
+    let mut secs = (scheduler.interval_days(&state) * SECONDS_PER_DAY).round().max(1.0) as i64;
+    if settings.min_interval_secs() > 0 {
+        secs = secs.max(i64::from(settings.min_interval_secs()));
+    }
+    if settings.max_interval_days() > 0 {
+        secs = secs.min(i64::from(settings.max_interval_days()) * 86_400);
+    }
+    Some(last_review_at + chrono::Duration::seconds(secs))
+}
+
+/// Days a due date may move either way when load balancing an interval of `scheduled_days`.
+///
+/// Short intervals stay put: a day matters much more to a 2-day card than to a 30-day one.
+fn load_balance_window(scheduled_days: f64) -> i64 {
+    if scheduled_days < 3.0 {
+        0
+    } else if scheduled_days < 7.0 {
+        1
+    } else {
+        2
+    }
+}
+
+/// Nudge the due date toward the least-loaded day within its fuzz window.
+///
+/// Candidates outside the deck's interval bounds, or whose retrievability on the new due
+/// date drops more than `LOAD_BALANCE_RETENTION_SLACK` below target, are skipped. Ties keep
+/// the smallest move, so a card only moves when another day is strictly quieter.
+#[allow(clippy::cast_precision_loss)]
+fn apply_load_balance(
+    applied: &mut AppliedReview,
+    scheduler: &Scheduler,
+    settings: &DeckSettings,
+    due_load: &BTreeMap<NaiveDate, u32>,
+    study_day: StudyDay,
+) {
+    let window = load_balance_window(applied.outcome.scheduled_days);
+    if window == 0 {
+        return;
+    }
+    let min_days = f64::from(settings.min_interval_secs()) / SECONDS_PER_DAY;
+    let max_days = f64::from(settings.max_interval_days());
+    let floor = f64::from(settings.fsrs_target_retention()) - LOAD_BALANCE_RETENTION_SLACK;
+    let original = applied.outcome.next_review;
+
+    let mut best: Option<(u32, i64, i64)> = None;
+    for shift in -window..=window {
+        let days = applied.outcome.scheduled_days + shift as f64;
+        if days < min_days || days > max_days {
+            continue;
+        }
+        if shift > 0 && scheduler.retrievability(&applied.memory, days) < floor {
+            continue;
+        }
+        let date = study_day.date_of(original + chrono::Duration::days(shift));
+        let load = due_load.get(&date).copied().unwrap_or(0);
+        let candidate = (load, shift.abs(), shift);
+        if best.is_none_or(|current| candidate < current) {
+            best = Some(candidate);
+        }
+    }
+
+    if let Some((_, _, shift)) = best
+        && shift != 0
+    {
+        applied.outcome.scheduled_days += shift as f64;
+        applied.outcome.next_review = original + chrono::Duration::days(shift);
+    }
+}
+
 pub struct ReviewService {
     clock: Clock,
     scheduler: Scheduler,
+    due_load: Option<BTreeMap<NaiveDate, u32>>,
+    study_day: StudyDay,
+    answer_ms: Option<u32>,
+    fuzz_seed: u64,
 }
 
 impl ReviewService {
@@ -91,6 +301,10 @@ impl ReviewService {
         Ok(Self {
             clock: Clock::default(),
             scheduler: Scheduler::try_with_retention(retention)?,
+            due_load: None,
+            study_day: StudyDay::default(),
+            answer_ms: None,
+            fuzz_seed: 0,
         })
     }
 
@@ -99,6 +313,10 @@ impl ReviewService {
         Self {
             clock: Clock::default(),
             scheduler,
+            due_load: None,
+            study_day: StudyDay::default(),
+            answer_ms: None,
+            fuzz_seed: 0,
         }
     }
 
@@ -108,11 +326,71 @@ impl ReviewService {
         self
     }
 
+    /// Upcoming reviews per day, as returned by `forecast`, for decks with load balancing on.
+    ///
+    /// Without it, `DeckSettings::load_balance` has no effect.
+    #[must_use]
+    pub fn with_due_load(mut self, due_load: impl IntoIterator<Item = (NaiveDate, u32)>) -> Self {
+        self.due_load = Some(due_load.into_iter().collect());
+        self
+    }
+
+    /// Count `forecast` and load balancing in the study days `study_day` describes.
+    #[must_use]
+    pub fn with_study_day(mut self, study_day: StudyDay) -> Self {
+        self.study_day = study_day;
+        self
+    }
+
+    /// Seed for interval fuzzing.
+    ///
+    /// Each review's fuzz is drawn from this seed, the card, and its review count, so a
+    /// preview and the grade it previews land on the same due date.
+    #[must_use]
+    pub fn with_fuzz_seed(mut self, seed: u64) -> Self {
+        self.fuzz_seed = seed;
+        self
+    }
+
+    /// How long the learner took to answer, recorded on the review log of the next review.
+    #[must_use]
+    pub fn with_answer_ms(mut self, answer_ms: Option<u32>) -> Self {
+        self.answer_ms = answer_ms;
+        self
+    }
+
     #[must_use]
     pub fn now(&self) -> DateTime<Utc> {
         self.clock.now()
     }
 
+    /// A scheduler for the deck's retention and learned weights.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReviewServiceError::Scheduler` if FSRS rejects the deck's weights.
+    pub fn scheduler_for(settings: &DeckSettings) -> Result<Scheduler, ReviewServiceError> {
+        Ok(Scheduler::try_with_parameters(
+            settings.fsrs_target_retention(),
+            settings.fsrs_parameters(),
+        )?)
+    }
+
+    /// A scheduler for the deck's retention and learned weights, or `None` when the
+    /// service's own scheduler already matches them.
+    fn deck_scheduler(
+        &self,
+        settings: &DeckSettings,
+    ) -> Result<Option<Scheduler>, ReviewServiceError> {
+        let retention = settings.fsrs_target_retention();
+        if (self.scheduler.retention() - retention).abs() < f32::EPSILON
+            && self.scheduler.parameters() == settings.fsrs_parameters()
+        {
+            return Ok(None);
+        }
+        Ok(Some(Self::scheduler_for(settings)?))
+    }
+
     /// Apply a grade to a card and return the scheduler output.
     ///
     /// # Errors
@@ -141,52 +419,127 @@ impl ReviewService {
         settings: &DeckSettings,
     ) -> Result<ReviewResult, ReviewServiceError> {
         let mut previous_state: Option<MemoryState> = card.memory_state();
-        let is_lapse = matches!(
-            card.phase(),
-            learn_core::model::CardPhase::Reviewing | learn_core::model::CardPhase::Relearning
-        ) && grade == ReviewGrade::Again;
+        let is_lapse = matches!(card.phase(), CardPhase::Reviewing | CardPhase::Relearning)
+            && grade == ReviewGrade::Again;
         if is_lapse && !settings.preserve_stability_on_lapse() {
             previous_state = None;
         }
+        // The first interval back in review after a lapse, whether straight from the lapse
+        // or on leaving the relearning steps.
+        let after_lapse = is_lapse || card.phase() == CardPhase::Relearning;
 
+        let learning_step = card
+            .next_learning_step(grade, settings.learning_steps_secs())
+            .or_else(|| card.next_relearning_step(grade, settings.relearning_steps_secs()));
         let elapsed_days = compute_elapsed_days(card.last_review_at(), reviewed_at);
-        let retention = settings.fsrs_target_retention();
-        let mut applied = if (self.scheduler.retention() - retention).abs() < f32::EPSILON {
-            self.scheduler.apply_review(
-                card.id(),
-                previous_state.as_ref(),
-                grade,
-                reviewed_at,
-                elapsed_days,
-            )?
-        } else {
-            let scheduler = Scheduler::try_with_retention(retention)?;
-            scheduler.apply_review(
-                card.id(),
-                previous_state.as_ref(),
-                grade,
-                reviewed_at,
-                elapsed_days,
-            )?
-        };
+        let deck_scheduler = self.deck_scheduler(settings)?;
+        let scheduler = deck_scheduler.as_ref().unwrap_or(&self.scheduler);
+        let mut applied = scheduler.apply_review(
+            card.id(),
+            previous_state.as_ref(),
+            grade,
+            reviewed_at,
+            elapsed_days,
+        )?;
+        let mut explanation = ReviewExplanation::new(
+            scheduler,
+            previous_state.as_ref(),
+            grade,
+            elapsed_days,
+            &applied,
+        );
 
-        if is_lapse {
-            apply_lapse_min_interval(
+        if let Some(LearningStep::Step { delay_secs, .. }) = learning_step {
+            apply_learning_step_delay(&mut applied, reviewed_at, delay_secs);
+            explanation.record(IntervalAdjustmentKind::LearningStep, &applied);
+        } else {
+            if after_lapse {
+                apply_lapse_min_interval(
+                    &mut applied,
+                    reviewed_at,
+                    settings.lapse_min_interval_secs(),
+                );
+                explanation.record(IntervalAdjustmentKind::LapseMinimum, &applied);
+            }
+            apply_interval_bounds(
                 &mut applied,
                 reviewed_at,
-                settings.lapse_min_interval_secs(),
+                settings.min_interval_secs(),
+                settings.max_interval_days(),
             );
+            explanation.record(IntervalAdjustmentKind::Clamp, &applied);
+            if settings.interval_fuzz() && card.review_count() < INTERVAL_FUZZ_REVIEWS {
+                let seed = self.fuzz_seed
+                    ^ card.id().value().rotate_left(32)
+                    ^ u64::from(card.review_count());
+                apply_interval_fuzz(
+                    &mut applied,
+                    reviewed_at,
+                    settings,
+                    &mut StdRng::seed_from_u64(seed),
+                );
+                explanation.record(IntervalAdjustmentKind::Fuzz, &applied);
+            }
+            if settings.load_balance()
+                && let Some(due_load) = &self.due_load
+            {
+                apply_load_balance(&mut applied, scheduler, settings, due_load, self.study_day);
+                explanation.record(IntervalAdjustmentKind::LoadBalance, &applied);
+            }
         }
-        apply_interval_bounds(
-            &mut applied,
-            reviewed_at,
-            settings.min_interval_secs(),
-            settings.max_interval_days(),
-        );
 
-        card.apply_review_with_phase(grade, &applied.outcome, reviewed_at);
+        applied.log.answer_ms = self.answer_ms;
+        applied.log.first_review = card.is_new();
 
-        Ok(ReviewResult { applied })
+        match learning_step {
+            Some(step) => card.apply_learning_review(step, &applied.outcome, reviewed_at),
+            None => card.apply_review_with_phase(grade, &applied.outcome, reviewed_at),
+        }
+
+        Ok(ReviewResult {
+            applied,
+            explanation: Some(explanation),
+        })
+    }
+
+    /// Project the next interval for every grade without touching `card`.
+    ///
+    /// Each preview runs the same scheduling as `review_card_with_settings` on a copy, so
+    /// learning-step cards show their step delay rather than an FSRS interval.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReviewServiceError::Scheduler` on scheduler failures.
+    pub fn preview_intervals(
+        &self,
+        card: &Card,
+        reviewed_at: DateTime<Utc>,
+        settings: &DeckSettings,
+    ) -> Result<Vec<ReviewGradePreview>, ReviewServiceError> {
+        [ReviewGrade::Again, ReviewGrade::Hard, ReviewGrade::Good, ReviewGrade::Easy]
+            .into_iter()
+            .map(|grade| {
+                let mut copy = card.clone();
+                let result = self.review_card_with_settings(&mut copy, grade, reviewed_at, settings)?;
+                Ok(ReviewGradePreview {
+                    grade,
+                    next_review: result.applied.outcome.next_review,
+                    scheduled_days: result.applied.outcome.scheduled_days,
+                })
+            })
+            .collect()
+    }
+
+    /// Predicted probability, from 0 to 1, that the card would be recalled at `now`.
+    ///
+    /// Uses the forgetting curve of `scheduler`, normally the one `scheduler_for` builds for
+    /// the card's deck, over the card's stability and the time since its last review.
+    /// Returns `None` for cards that were never reviewed.
+    #[must_use]
+    pub fn retrievability(scheduler: &Scheduler, card: &Card, now: DateTime<Utc>) -> Option<f64> {
+        let memory = card.memory_state()?;
+        let elapsed_days = compute_elapsed_days(Some(card.last_review_at()?), now);
+        Some(scheduler.retrievability(&memory, elapsed_days).clamp(0.0, 1.0))
     }
 
     /// Apply a grade, persist the updated card and review log atomically.
@@ -272,6 +625,251 @@ impl ReviewService {
         })
     }
 
+    /// Project how many reviews fall on each of the next `days` study days, starting today.
+    ///
+    /// Overdue cards count towards day 0. New cards are not scheduled yet and are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReviewServiceError::Storage` if the card query fails.
+    pub async fn forecast(
+        &self,
+        deck_id: DeckId,
+        days: u32,
+        cards: &dyn CardRepository,
+    ) -> Result<Vec<(NaiveDate, u32)>, ReviewServiceError> {
+        let today = self.study_day.date_of(self.now());
+        let mut forecast: Vec<(NaiveDate, u32)> = today
+            .iter_days()
+            .take(usize::try_from(days).unwrap_or(usize::MAX))
+            .map(|date| (date, 0))
+            .collect();
+        let Some(&(last, _)) = forecast.last() else {
+            return Ok(forecast);
+        };
+
+        let until = last
+            .succ_opt()
+            .map_or(DateTime::<Utc>::MAX_UTC, |end| self.study_day.start_of(end));
+        for due_at in cards.due_times(deck_id, until).await? {
+            let offset = (self.study_day.date_of(due_at) - today).num_days().max(0);
+            if let Some((_, count)) = usize::try_from(offset)
+                .ok()
+                .and_then(|index| forecast.get_mut(index))
+            {
+                *count = count.saturating_add(1);
+            }
+        }
+        Ok(forecast)
+    }
+
+    /// Recompute review cards' due dates from their stability and the deck's target retention.
+    ///
+    /// Use after changing `fsrs_target_retention`, so cards don't wait for their next review
+    /// to pick up the new target. Interval bounds apply as on review; cards in (re)learning
+    /// keep their short delays, and no review logs are written. Returns the number of cards
+    /// whose due date moved.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReviewServiceError::Storage` if the deck is missing or persistence fails.
+    /// Returns `ReviewServiceError::Scheduler` if the deck's retention is rejected.
+    pub async fn reschedule_all(
+        &self,
+        deck_id: DeckId,
+        decks: &dyn DeckRepository,
+        cards: &dyn CardRepository,
+    ) -> Result<u64, ReviewServiceError> {
+        let deck = decks.get_deck(deck_id).await?.ok_or(StorageError::NotFound)?;
+        let settings = deck.settings();
+        let deck_scheduler = self.deck_scheduler(settings)?;
+        let scheduler = deck_scheduler.as_ref().unwrap_or(&self.scheduler);
+
+        let due_dates: Vec<(CardId, DateTime<Utc>)> = cards
+            .list_cards(deck_id, u32::MAX)
+            .await?
+            .iter()
+            .filter_map(|card| {
+                let due_at = rescheduled_due_at(scheduler, card, settings)?;
+                (due_at != card.next_review_at()).then_some((card.id(), due_at))
+            })
+            .collect();
+        if due_dates.is_empty() {
+            return Ok(0);
+        }
+        Ok(cards.reschedule_cards(deck_id, &due_dates).await?)
+    }
+
+    /// Pull in cards due further out than the deck's `max_interval_days`, making them due
+    /// that many days from now.
+    ///
+    /// Use after lowering the maximum interval, since it otherwise only applies to the next
+    /// review. All cards move in one transaction, and no review logs are written. Returns
+    /// the number of cards clamped; zero when the deck has no maximum.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReviewServiceError::Storage` if the deck is missing or persistence fails.
+    pub async fn clamp_intervals(
+        &self,
+        deck_id: DeckId,
+        decks: &dyn DeckRepository,
+        cards: &dyn CardRepository,
+    ) -> Result<u64, ReviewServiceError> {
+        let deck = decks.get_deck(deck_id).await?.ok_or(StorageError::NotFound)?;
+        let max_days = deck.settings().max_interval_days();
+        if max_days == 0 {
+            return Ok(0);
+        }
+        let latest = self.now() + chrono::Duration::days(i64::from(max_days));
+
+        let due_dates: Vec<(CardId, DateTime<Utc>)> = cards
+            .list_cards(deck_id, u32::MAX)
+            .await?
+            .iter()
+            .filter(|card| !card.is_new() && card.next_review_at() > latest)
+            .map(|card| (card.id(), latest))
+            .collect();
+        if due_dates.is_empty() {
+            return Ok(0);
+        }
+        Ok(cards.reschedule_cards(deck_id, &due_dates).await?)
+    }
+
+    /// Fit FSRS weights to the deck's review history and store them in its settings.
+    ///
+    /// Later reviews in the deck schedule with the stored weights. Runs only for decks with
+    /// `fsrs_optimize_enabled` once their history holds more than `fsrs_optimize_after`
+    /// reviews. Returns the weights with their fit against the history.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReviewServiceError::OptimizerDisabled` if the deck turned optimization off.
+    /// Returns `ReviewServiceError::NotEnoughReviews` below the deck's review threshold.
+    /// Returns `ReviewServiceError::Scheduler` if fitting fails.
+    /// Returns `ReviewServiceError::Storage` if the deck is missing, was saved concurrently
+    /// (`StorageError::Conflict`), or persistence fails.
+    pub async fn optimize_parameters(
+        &self,
+        deck_id: DeckId,
+        decks: &dyn DeckRepository,
+        logs: &dyn ReviewLogRepository,
+    ) -> Result<FittedParameters, ReviewServiceError> {
+        let deck = decks.get_deck(deck_id).await?.ok_or(StorageError::NotFound)?;
+        let settings = deck.settings();
+        if !settings.fsrs_optimize_enabled() {
+            return Err(ReviewServiceError::OptimizerDisabled);
+        }
+
+        let history: Vec<ReviewLog> = logs
+            .logs_for_deck(deck_id)
+            .await?
+            .iter()
+            .map(|log| ReviewLog::new(log.card_id, log.grade, log.reviewed_at))
+            .collect();
+        let required = settings.fsrs_optimize_after();
+        if history.len() <= usize::try_from(required).unwrap_or(usize::MAX) {
+            return Err(ReviewServiceError::NotEnoughReviews {
+                required,
+                found: history.len(),
+            });
+        }
+
+        let fitted = optimizer::optimize_parameters(&history)?;
+        let settings = settings
+            .clone()
+            .with_fsrs_parameters(fitted.parameters.clone())?;
+        let deck = deck.with_settings(settings).with_updated_at(self.clock.now());
+        decks.update_deck_if_version(&deck).await?;
+        Ok(fitted)
+    }
+
+    /// Delete review history in a deck to cap its size, returning how many reviews went.
+    ///
+    /// Scheduling is unaffected, since each card carries its own memory state, but
+    /// `optimize_parameters` fits only the history that is left. Pruning the deck below
+    /// `fsrs_optimize_after` reviews, or to fewer than two reviews per card, leaves later
+    /// optimization with too little spaced history and degrades or prevents it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReviewServiceError::Storage` if the logs cannot be deleted.
+    pub async fn prune_history(
+        &self,
+        deck_id: DeckId,
+        prune: ReviewLogPrune,
+        logs: &dyn ReviewLogRepository,
+    ) -> Result<u64, ReviewServiceError> {
+        Ok(logs.prune(deck_id, prune).await?)
+    }
+
+    /// Up to `limit` of a card's reviews, newest first.
+    ///
+    /// Cards that were never reviewed return an empty list.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReviewServiceError::Storage` if the logs cannot be read.
+    pub async fn card_history(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        limit: u32,
+        logs: &dyn ReviewLogRepository,
+    ) -> Result<Vec<CardHistoryEntry>, ReviewServiceError> {
+        let records = logs.logs_for_card(deck_id, card_id).await?;
+        Ok(records
+            .iter()
+            .rev()
+            .take(usize::try_from(limit).unwrap_or(usize::MAX))
+            .map(CardHistoryEntry::from_record)
+            .collect())
+    }
+
+    /// Write every review log in a deck to `writer`, oldest first, and return the row count.
+    ///
+    /// CSV rows hold the card ID, the UTC review time in ISO-8601, the grade, the scheduled
+    /// interval in days, and the FSRS stability and difficulty after the review. Logs are
+    /// read and written a page at a time, so long histories are never held in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReviewServiceError::Storage` if the logs cannot be read, or
+    /// `ReviewServiceError::Write` if `writer` fails.
+    pub async fn export_logs(
+        &self,
+        deck_id: DeckId,
+        writer: &mut (impl Write + Send),
+        format: LogExportFormat,
+        logs: &dyn ReviewLogRepository,
+    ) -> Result<usize, ReviewServiceError> {
+        match format {
+            LogExportFormat::Csv => {
+                writeln!(writer, "card_id,reviewed_at,grade,interval_days,stability,difficulty")?;
+            }
+        }
+        let mut written = 0;
+        let mut after_id = None;
+        loop {
+            let page = logs.logs_for_deck_page(deck_id, after_id, EXPORT_PAGE_SIZE).await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            after_id = last.id;
+            for record in &page {
+                match format {
+                    LogExportFormat::Csv => write_csv_log(writer, record)?,
+                }
+            }
+            written += page.len();
+            if page.len() < EXPORT_PAGE_SIZE as usize || after_id.is_none() {
+                break;
+            }
+        }
+        writer.flush()?;
+        Ok(written)
+    }
+
     /// Persist a batch of already-applied reviews.
     ///
     /// # Errors
@@ -299,8 +897,11 @@ impl ReviewService {
 mod tests {
     use super::*;
 
-    use learn_core::model::{Card, CardId, ContentDraft, DeckId, ReviewLog, ReviewOutcome};
+    use learn_core::model::{
+        Card, CardId, CardKind, ContentDraft, Deck, DeckId, ReviewLog, ReviewOutcome,
+    };
     use learn_core::time::fixed_now;
+    use storage::repository::ReviewLogRepository;
 
     fn build_card(now: DateTime<Utc>) -> Card {
         let prompt = ContentDraft::text_only("Q")
@@ -309,7 +910,16 @@ mod tests {
         let answer = ContentDraft::text_only("A")
             .validate(now, None, None)
             .unwrap();
-        Card::new(CardId::new(1), DeckId::new(1), prompt, answer, now, now).unwrap()
+        Card::new(
+            CardId::new(1),
+            DeckId::new(1),
+            CardKind::Basic,
+            prompt,
+            answer,
+            now,
+            now,
+        )
+        .unwrap()
     }
 
     #[test]
@@ -321,7 +931,8 @@ mod tests {
             5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
             retention, true, 100,
         )
-        .unwrap();
+        .unwrap()
+        .with_interval_fuzz(false);
         let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
 
         let scheduler = Scheduler::try_with_retention(retention).unwrap();
@@ -339,6 +950,34 @@ mod tests {
         assert_eq!(result.applied.outcome.next_review, expected.outcome.next_review);
     }
 
+    #[test]
+    fn retrievability_is_target_when_due_and_low_well_past_it() {
+        let now = fixed_now();
+        let mut card = build_card(now);
+        let settings = DeckSettings::new(
+            5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+            0.9, true, 100,
+        )
+        .unwrap();
+        let scheduler = ReviewService::scheduler_for(&settings).unwrap();
+        assert_eq!(ReviewService::retrievability(&scheduler, &card, now), None);
+
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+        service
+            .review_card_with_settings(&mut card, ReviewGrade::Good, now, &settings)
+            .unwrap();
+
+        let fresh = ReviewService::retrievability(&scheduler, &card, now).unwrap();
+        assert!((fresh - 1.0).abs() < 1e-6, "right after review {fresh}");
+        let due = card.next_review_at();
+        let at_due = ReviewService::retrievability(&scheduler, &card, due).unwrap();
+        assert!((at_due - 0.9).abs() < 0.02, "zero days overdue {at_due}");
+        let interval = due - now;
+        let overdue =
+            ReviewService::retrievability(&scheduler, &card, due + interval * 100).unwrap();
+        assert!(overdue < 0.5, "well past the interval {overdue}");
+    }
+
     #[test]
     fn lapse_min_interval_clamps_again_outcome() {
         let now = fixed_now();
@@ -358,6 +997,8 @@ mod tests {
             5, 30, 5, true, true, 3 * 86_400, false, false, false, 25, 20, 86_400, 365, false,
             0.5, 0, 0.85, true, 100,
         )
+        .unwrap()
+        .with_relearning_steps(Vec::new())
         .unwrap();
         let lapse_review = now + chrono::Duration::days(2);
         let result = service
@@ -368,6 +1009,154 @@ mod tests {
         assert!(scheduled >= chrono::Duration::days(3));
     }
 
+    #[tokio::test]
+    async fn reviews_by_id_are_timed_by_the_injected_clock() {
+        let repo = storage::repository::InMemoryRepository::new();
+        let now = fixed_now();
+        let card = build_card(now);
+        repo.upsert_card(&card).await.unwrap();
+        let (deck_id, card_id) = (card.deck_id(), card.id());
+        let mut clocks = Clock::advancing(now, chrono::Duration::minutes(1));
+
+        let service = ReviewService::new().unwrap().with_clock(clocks.next().unwrap());
+        let first = service
+            .review_card_persisted_by_id(deck_id, card_id, &repo, &repo, ReviewGrade::Again)
+            .await
+            .unwrap();
+        assert_eq!(first.card.last_review_at(), Some(now));
+        assert_eq!(first.card.next_review_at(), now + chrono::Duration::seconds(60));
+
+        let later = now + chrono::Duration::minutes(1);
+        let service = ReviewService::new().unwrap().with_clock(clocks.next().unwrap());
+        let second = service
+            .review_card_persisted_by_id(deck_id, card_id, &repo, &repo, ReviewGrade::Good)
+            .await
+            .unwrap();
+        assert_eq!(second.card.last_review_at(), Some(later));
+        assert_eq!(second.card.next_review_at(), later + chrono::Duration::seconds(600));
+        let stored = repo.get_cards(deck_id, &[card_id]).await.unwrap();
+        assert_eq!(stored[0].updated_at(), later);
+    }
+
+    /// A card reviewed twice with the default settings, so it sits in `Reviewing`.
+    fn build_reviewing_card(service: &ReviewService, now: DateTime<Utc>) -> Card {
+        let mut card = build_card(now);
+        let defaults = DeckSettings::default_for_adhd();
+        for reviewed_at in [now, now + chrono::Duration::days(1)] {
+            service
+                .review_card_with_settings(&mut card, ReviewGrade::Good, reviewed_at, &defaults)
+                .unwrap();
+        }
+        assert_eq!(card.phase(), CardPhase::Reviewing);
+        card
+    }
+
+    #[test]
+    fn lapse_walks_relearning_steps_before_returning_to_review() {
+        let now = fixed_now();
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+        let mut card = build_reviewing_card(&service, now);
+        let settings = DeckSettings::new(
+            5, 30, 5, true, true, 3 * 86_400, false, false, false, 25, 20, 86_400, 365, false,
+            0.5, 0, 0.85, true, 100,
+        )
+        .unwrap()
+        .with_learning_steps(vec![60, 600])
+        .unwrap()
+        .with_relearning_steps(vec![300, 1_800])
+        .unwrap()
+        .with_interval_fuzz(false);
+        let mut reviewed_at = now + chrono::Duration::days(5);
+        let mut answer = |card: &mut Card, grade| {
+            let result = service
+                .review_card_with_settings(card, grade, reviewed_at, &settings)
+                .unwrap();
+            let delay = result.applied.outcome.next_review - reviewed_at;
+            reviewed_at = result.applied.outcome.next_review;
+            delay
+        };
+
+        assert_eq!(answer(&mut card, ReviewGrade::Again), chrono::Duration::minutes(5));
+        assert_eq!((card.phase(), card.learning_step()), (CardPhase::Relearning, 0));
+        assert_eq!(card.lapses(), 1);
+
+        assert_eq!(answer(&mut card, ReviewGrade::Good), chrono::Duration::minutes(30));
+        assert_eq!((card.phase(), card.learning_step()), (CardPhase::Relearning, 1));
+
+        assert_eq!(answer(&mut card, ReviewGrade::Again), chrono::Duration::minutes(5));
+        assert_eq!((card.phase(), card.learning_step()), (CardPhase::Relearning, 0));
+        assert_eq!(card.lapses(), 1);
+
+        answer(&mut card, ReviewGrade::Good);
+        let back_in_review = answer(&mut card, ReviewGrade::Good);
+        assert_eq!(card.phase(), CardPhase::Reviewing);
+        assert!(back_in_review >= chrono::Duration::days(3), "post-lapse {back_in_review}");
+    }
+
+    #[test]
+    fn lapse_min_interval_applies_when_relearning_is_skipped_with_easy() {
+        let now = fixed_now();
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+        let mut card = build_reviewing_card(&service, now);
+        let settings = DeckSettings::new(
+            5, 30, 5, true, true, 30 * 86_400, false, false, false, 25, 20, 3_600, 365, false,
+            0.5, 0, 0.85, true, 100,
+        )
+        .unwrap()
+        .with_interval_fuzz(false);
+        let lapse_review = now + chrono::Duration::days(5);
+        service
+            .review_card_with_settings(&mut card, ReviewGrade::Again, lapse_review, &settings)
+            .unwrap();
+        assert_eq!(card.phase(), CardPhase::Relearning);
+
+        let relearn_review = card.next_review_at();
+        let result = service
+            .review_card_with_settings(&mut card, ReviewGrade::Easy, relearn_review, &settings)
+            .unwrap();
+
+        assert_eq!(card.phase(), CardPhase::Reviewing);
+        assert!(result.applied.outcome.scheduled_days >= 30.0);
+    }
+
+    #[test]
+    fn lapse_stability_depends_on_preserve_stability_setting() {
+        let now = fixed_now();
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+        let reviewing = build_reviewing_card(&service, now);
+        let lapse_review = now + chrono::Duration::days(5);
+        let lapse = |preserve: bool| {
+            let settings = DeckSettings::new(
+                5, 30, 5, true, preserve, 86_400, false, false, false, 25, 20, 86_400, 365, false,
+                0.5, 0, 0.85, true, 100,
+            )
+            .unwrap();
+            let mut card = reviewing.clone();
+            let result = service
+                .review_card_with_settings(&mut card, ReviewGrade::Again, lapse_review, &settings)
+                .unwrap();
+            assert_eq!((card.phase(), card.lapses()), (CardPhase::Relearning, 1));
+            result.applied.outcome
+        };
+        let fresh = Scheduler::try_with_retention(0.85)
+            .unwrap()
+            .apply_review(reviewing.id(), None, ReviewGrade::Again, lapse_review, 0.0)
+            .unwrap();
+
+        let preserved = lapse(true);
+        let reset = lapse(false);
+
+        assert!((reset.stability - fresh.outcome.stability).abs() < 1e-9);
+        assert!(
+            preserved.stability > reset.stability,
+            "preserved {} vs reset {}",
+            preserved.stability,
+            reset.stability
+        );
+        assert!(preserved.elapsed_days > 0.0);
+        assert_eq!(reset.elapsed_days, 0.0);
+    }
+
     #[test]
     fn lapse_without_preserve_stability_uses_new_card_schedule() {
         let now = fixed_now();
@@ -416,4 +1205,745 @@ mod tests {
         assert_eq!(applied.outcome.scheduled_days, 7.0);
         assert_eq!(applied.outcome.next_review, now + chrono::Duration::days(7));
     }
+
+    #[test]
+    fn review_explains_the_fsrs_interval_and_the_clamp_that_moved_it() {
+        let now = fixed_now();
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+        let mut card = build_reviewing_card(&service, now);
+        let settings = DeckSettings::new(
+            5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 2, false, 0.5, 0,
+            0.9, true, 100,
+        )
+        .unwrap()
+        .with_interval_fuzz(false);
+        let reviewed_at = card.next_review_at();
+        let previous = card.memory_state().unwrap();
+        let elapsed = compute_elapsed_days(card.last_review_at(), reviewed_at);
+        let scheduler = ReviewService::scheduler_for(&settings).unwrap();
+        let expected = scheduler
+            .apply_review(card.id(), Some(&previous), ReviewGrade::Good, reviewed_at, elapsed)
+            .unwrap();
+
+        let result = service
+            .review_card_with_settings(&mut card, ReviewGrade::Good, reviewed_at, &settings)
+            .unwrap();
+
+        let explanation = result.explanation.unwrap();
+        assert_eq!(explanation.previous, Some(previous.clone()));
+        assert_eq!(explanation.elapsed_days, elapsed);
+        assert_eq!(
+            explanation.retrievability,
+            Some(scheduler.retrievability(&previous, elapsed))
+        );
+        assert_eq!(explanation.fsrs_days, expected.outcome.scheduled_days);
+        assert!(explanation.fsrs_days > 2.0, "{}", explanation.fsrs_days);
+        assert_eq!(explanation.adjustments.len(), 1);
+        assert_eq!(explanation.adjustments[0].kind, IntervalAdjustmentKind::Clamp);
+        assert_eq!(explanation.scheduled_days, 2.0);
+        assert_eq!(explanation.scheduled_days, result.applied.outcome.scheduled_days);
+    }
+
+    #[test]
+    fn learning_steps_walk_new_card_to_review() {
+        let now = fixed_now();
+        let mut card = build_card(now);
+        let settings = DeckSettings::new(
+            5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+            0.85, true, 100,
+        )
+        .unwrap()
+        .with_learning_steps(vec![60, 600])
+        .unwrap();
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+        let mut reviewed_at = now;
+        let mut answer = |card: &mut Card, grade| {
+            let result = service
+                .review_card_with_settings(card, grade, reviewed_at, &settings)
+                .unwrap();
+            let delay = result.applied.outcome.next_review - reviewed_at;
+            reviewed_at = result.applied.outcome.next_review;
+            delay
+        };
+
+        assert_eq!(answer(&mut card, ReviewGrade::Good), chrono::Duration::minutes(10));
+        assert_eq!((card.phase(), card.learning_step()), (CardPhase::Learning, 1));
+
+        assert_eq!(answer(&mut card, ReviewGrade::Again), chrono::Duration::minutes(1));
+        assert_eq!((card.phase(), card.learning_step()), (CardPhase::Learning, 0));
+
+        assert_eq!(answer(&mut card, ReviewGrade::Good), chrono::Duration::minutes(10));
+        let graduated = answer(&mut card, ReviewGrade::Good);
+        assert_eq!(card.phase(), CardPhase::Reviewing);
+        assert!(graduated >= chrono::Duration::days(1));
+        assert_eq!(card.review_count(), 4);
+    }
+
+    #[test]
+    fn learning_steps_leave_new_cards_alone_when_empty() {
+        let now = fixed_now();
+        let mut card = build_card(now);
+        let settings = load_balance_settings(365, false);
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+
+        let result = service
+            .review_card_with_settings(&mut card, ReviewGrade::Again, now, &settings)
+            .unwrap();
+
+        assert_eq!(result.applied.outcome.next_review, now + chrono::Duration::days(1));
+        assert_eq!(card.learning_step(), 0);
+    }
+
+    #[test]
+    fn preview_intervals_match_grading_without_changing_the_card() {
+        let now = fixed_now();
+        let settings = DeckSettings::new(
+            5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+            0.85, true, 100,
+        )
+        .unwrap()
+        .with_learning_steps(vec![60, 600])
+        .unwrap();
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+        let learning = build_card(now);
+        let mut reviewing = build_card(now);
+        for _ in 0..3 {
+            service
+                .review_card_with_settings(&mut reviewing, ReviewGrade::Easy, now, &settings)
+                .unwrap();
+        }
+        assert_eq!(reviewing.phase(), CardPhase::Reviewing);
+        let later = now + chrono::Duration::days(5);
+
+        for (card, reviewed_at) in [(learning, now), (reviewing, later)] {
+            let before = card.clone();
+            let previews = service.preview_intervals(&card, reviewed_at, &settings).unwrap();
+            assert_eq!(card, before);
+            assert_eq!(previews.len(), 4);
+            for preview in previews {
+                let mut graded = card.clone();
+                let result = service
+                    .review_card_with_settings(&mut graded, preview.grade, reviewed_at, &settings)
+                    .unwrap();
+                assert_eq!(preview.next_review, result.applied.outcome.next_review);
+                let days = result.applied.outcome.scheduled_days;
+                assert!((preview.scheduled_days - days).abs() < 1e-9);
+            }
+        }
+
+        let learning_previews =
+            service.preview_intervals(&build_card(now), now, &settings).unwrap();
+        let again = &learning_previews[0];
+        assert_eq!(again.grade, ReviewGrade::Again);
+        assert_eq!(again.next_review - now, chrono::Duration::minutes(1));
+    }
+
+    fn load_balance_settings(max_interval_days: u32, load_balance: bool) -> DeckSettings {
+        DeckSettings::new(
+            5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, max_interval_days,
+            false, 0.5, 0, 0.9, true, 100,
+        )
+        .unwrap()
+        .with_load_balance(load_balance)
+    }
+
+    fn applied_with_interval(now: DateTime<Utc>, days: i64, stability: f64) -> AppliedReview {
+        let log = ReviewLog::new(CardId::new(1), ReviewGrade::Good, now);
+        #[allow(clippy::cast_precision_loss)]
+        let outcome = ReviewOutcome::new(
+            now + chrono::Duration::days(days),
+            stability,
+            5.0,
+            1.0,
+            days as f64,
+        );
+        let memory = MemoryState::new(stability, 5.0);
+        AppliedReview { log, outcome, memory }
+    }
+
+    fn load_around(due: DateTime<Utc>, counts: [u32; 5]) -> BTreeMap<NaiveDate, u32> {
+        (-2..=2)
+            .zip(counts)
+            .map(|(shift, count)| ((due + chrono::Duration::days(shift)).date_naive(), count))
+            .collect()
+    }
+
+    #[test]
+    fn load_balance_moves_due_date_to_quietest_day_in_window() {
+        let now = fixed_now();
+        let scheduler = Scheduler::new().unwrap();
+        let mut applied = applied_with_interval(now, 10, 10.0);
+        let original = applied.outcome.next_review;
+        let load = load_around(original, [15, 10, 20, 3, 1]);
+
+        apply_load_balance(
+            &mut applied,
+            &scheduler,
+            &load_balance_settings(365, true),
+            &load,
+            StudyDay::default(),
+        );
+
+        assert_eq!(applied.outcome.next_review, original + chrono::Duration::days(2));
+        assert_eq!(applied.outcome.scheduled_days, 12.0);
+    }
+
+    #[test]
+    fn load_balance_keeps_due_date_when_no_day_is_quieter() {
+        let now = fixed_now();
+        let scheduler = Scheduler::new().unwrap();
+        let mut applied = applied_with_interval(now, 10, 10.0);
+        let original = applied.outcome.next_review;
+        let load = load_around(original, [4, 4, 4, 4, 4]);
+
+        apply_load_balance(
+            &mut applied,
+            &scheduler,
+            &load_balance_settings(365, true),
+            &load,
+            StudyDay::default(),
+        );
+
+        assert_eq!(applied.outcome.next_review, original);
+    }
+
+    #[test]
+    fn load_balance_never_moves_past_retrievability_floor() {
+        let now = fixed_now();
+        let scheduler = Scheduler::new().unwrap();
+        // Retrievability is about 0.885 one day late and 0.876 two days late; the floor
+        // for a 0.9 target is 0.88.
+        let mut applied = applied_with_interval(now, 7, 6.5);
+        let original = applied.outcome.next_review;
+        let load = load_around(original, [9, 8, 10, 5, 0]);
+
+        apply_load_balance(
+            &mut applied,
+            &scheduler,
+            &load_balance_settings(365, true),
+            &load,
+            StudyDay::default(),
+        );
+
+        assert_eq!(applied.outcome.next_review, original + chrono::Duration::days(1));
+    }
+
+    #[test]
+    fn load_balance_respects_interval_bounds_and_short_intervals() {
+        let now = fixed_now();
+        let scheduler = Scheduler::new().unwrap();
+        let mut capped = applied_with_interval(now, 10, 30.0);
+        let original = capped.outcome.next_review;
+        let load = load_around(original, [6, 2, 9, 0, 0]);
+        apply_load_balance(
+            &mut capped,
+            &scheduler,
+            &load_balance_settings(10, true),
+            &load,
+            StudyDay::default(),
+        );
+        assert_eq!(capped.outcome.next_review, original - chrono::Duration::days(1));
+        assert_eq!(capped.outcome.scheduled_days, 9.0);
+
+        let mut short = applied_with_interval(now, 2, 2.0);
+        let original = short.outcome.next_review;
+        let load = load_around(original, [0, 0, 50, 0, 0]);
+        apply_load_balance(
+            &mut short,
+            &scheduler,
+            &load_balance_settings(365, true),
+            &load,
+            StudyDay::default(),
+        );
+        assert_eq!(short.outcome.next_review, original);
+    }
+
+    #[test]
+    fn load_balance_shifts_reviews_only_when_enabled() {
+        let now = fixed_now();
+        let mut seed = build_card(now);
+        seed.apply_review(
+            &ReviewOutcome::new(now + chrono::Duration::days(20), 20.0, 5.0, 0.0, 20.0),
+            now,
+        );
+        let reviewed_at = now + chrono::Duration::days(20);
+        let plain = ReviewService::new().unwrap().with_clock(Clock::Fixed(reviewed_at));
+        let mut unbalanced_card = seed.clone();
+        let natural = plain
+            .review_card_with_settings(
+                &mut unbalanced_card,
+                ReviewGrade::Good,
+                reviewed_at,
+                &load_balance_settings(365, true),
+            )
+            .unwrap()
+            .applied
+            .outcome;
+        let load = load_around(natural.next_review, [0, 30, 40, 30, 30]);
+
+        let balancing = ReviewService::new()
+            .unwrap()
+            .with_clock(Clock::Fixed(reviewed_at))
+            .with_due_load(load);
+        let mut card = seed.clone();
+        let balanced = balancing
+            .review_card_with_settings(
+                &mut card,
+                ReviewGrade::Good,
+                reviewed_at,
+                &load_balance_settings(365, true),
+            )
+            .unwrap()
+            .applied
+            .outcome;
+        assert_eq!(balanced.next_review, natural.next_review - chrono::Duration::days(2));
+        assert_eq!(card.next_review_at(), balanced.next_review);
+
+        let mut disabled_card = seed;
+        let disabled = balancing
+            .review_card_with_settings(
+                &mut disabled_card,
+                ReviewGrade::Good,
+                reviewed_at,
+                &load_balance_settings(365, false),
+            )
+            .unwrap()
+            .applied
+            .outcome;
+        assert_eq!(disabled.next_review, natural.next_review);
+    }
+
+    #[test]
+    fn interval_fuzz_spreads_cards_graded_together() {
+        let now = fixed_now();
+        let template = build_card(now);
+        let grade_all = |service: &ReviewService, settings: &DeckSettings| {
+            (1..=20)
+                .map(|id| {
+                    let mut card = Card::new(
+                        CardId::new(id),
+                        template.deck_id(),
+                        CardKind::Basic,
+                        template.prompt().clone(),
+                        template.answer().clone(),
+                        now,
+                        now,
+                    )
+                    .unwrap();
+                    service
+                        .review_card_with_settings(&mut card, ReviewGrade::Easy, now, settings)
+                        .unwrap()
+                        .applied
+                        .outcome
+                })
+                .collect::<Vec<_>>()
+        };
+        let service = ReviewService::new().unwrap().with_fuzz_seed(7);
+        let settings = load_balance_settings(365, false);
+
+        let fuzzed = grade_all(&service, &settings);
+        let plain = grade_all(&service, &settings.clone().with_interval_fuzz(false));
+
+        let natural = plain[0].scheduled_days;
+        assert!(plain.iter().all(|outcome| outcome.next_review == plain[0].next_review));
+        let due_days = fuzzed
+            .iter()
+            .map(|outcome| outcome.next_review.date_naive())
+            .collect::<std::collections::BTreeSet<_>>();
+        assert!(due_days.len() > 1, "all fuzzed cards landed on {due_days:?}");
+        assert!(fuzzed.iter().all(|outcome| {
+            (outcome.scheduled_days - natural).abs() <= natural * INTERVAL_FUZZ_RATIO + 1e-6
+        }));
+        assert_eq!(grade_all(&service, &settings), fuzzed);
+    }
+
+    async fn insert_due_card(
+        repo: &storage::repository::InMemoryRepository,
+        id: u64,
+        deck_id: DeckId,
+        now: DateTime<Utc>,
+        due_at: DateTime<Utc>,
+    ) {
+        let base = build_card(now);
+        let mut card = Card::new(
+            CardId::new(id),
+            deck_id,
+            CardKind::Basic,
+            base.prompt().clone(),
+            base.answer().clone(),
+            now,
+            now,
+        )
+        .unwrap();
+        card.apply_review(&ReviewOutcome::new(due_at, 1.0, 5.0, 0.0, 1.0), now);
+        repo.upsert_card(&card).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn forecast_counts_overdue_into_day_zero() {
+        let repo = storage::repository::InMemoryRepository::new();
+        let now = fixed_now();
+        let deck_id = DeckId::new(1);
+        let day = chrono::Duration::days(1);
+        for (id, due_at) in [
+            (1, now - day * 3),
+            (2, now - day),
+            (3, now),
+            (4, now + day * 2),
+            (5, now + day * 2),
+            (6, now + day * 10),
+        ] {
+            insert_due_card(&repo, id, deck_id, now, due_at).await;
+        }
+        insert_due_card(&repo, 7, DeckId::new(2), now, now).await;
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+
+        let forecast = service.forecast(deck_id, 4, &repo).await.unwrap();
+
+        let today = now.date_naive();
+        let counts: Vec<u32> = forecast.iter().map(|(_, count)| *count).collect();
+        assert_eq!(counts, vec![3, 0, 2, 0]);
+        assert_eq!(forecast[0].0, today);
+        assert_eq!(forecast[3].0, today + chrono::Days::new(3));
+    }
+
+    #[tokio::test]
+    async fn forecast_buckets_reviews_by_study_day() {
+        let repo = storage::repository::InMemoryRepository::new();
+        // 22:13 UTC is already 01:13 the next day three hours east.
+        let now = fixed_now();
+        let deck_id = DeckId::new(1);
+        let hours = chrono::Duration::hours;
+        for (id, due_at) in [(1, now + hours(1)), (2, now + hours(24)), (3, now + hours(26))] {
+            insert_due_card(&repo, id, deck_id, now, due_at).await;
+        }
+        let east = chrono::FixedOffset::east_opt(3 * 3600).unwrap();
+        let service = ReviewService::new()
+            .unwrap()
+            .with_clock(Clock::Fixed(now))
+            .with_study_day(StudyDay::new(4).with_utc_offset(east));
+
+        let forecast = service.forecast(deck_id, 3, &repo).await.unwrap();
+
+        // Before the 4am cutoff, local 01:13 still belongs to the study day that began the
+        // day before; the cards due at 01:13 and 03:13 a day later share tomorrow.
+        let today = now.date_naive();
+        let counts: Vec<u32> = forecast.iter().map(|(_, count)| *count).collect();
+        assert_eq!(counts, vec![1, 2, 0]);
+        assert_eq!(forecast[0].0, today);
+    }
+
+    fn retention_settings(retention: f32) -> DeckSettings {
+        DeckSettings::new(
+            5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+            retention, true, 100,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn reschedule_all_moves_review_cards_to_new_retention() {
+        let repo = storage::repository::InMemoryRepository::new();
+        let now = fixed_now();
+        let deck_id = DeckId::new(1);
+        let deck = Deck::new(deck_id, "Deck", None, retention_settings(0.9), now).unwrap();
+        repo.upsert_deck(&deck).await.unwrap();
+        let base = build_card(now);
+        let reviewed = Card::from_persisted(
+            CardId::new(1),
+            deck_id,
+            CardKind::Basic,
+            base.prompt().clone(),
+            base.answer().clone(),
+            now,
+            now + chrono::Duration::days(10),
+            Some(now),
+            CardPhase::Reviewing,
+            3,
+            10.0,
+            5.0,
+        )
+        .unwrap();
+        repo.upsert_card(&reviewed).await.unwrap();
+        insert_due_card(&repo, 2, deck_id, now, now + chrono::Duration::hours(1)).await;
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+
+        let before = repo.get_cards(deck_id, &[CardId::new(1), CardId::new(2)]).await.unwrap();
+        assert_eq!(service.reschedule_all(deck_id, &repo, &repo).await.unwrap(), 0);
+
+        let lowered = Deck::new(deck_id, "Deck", None, retention_settings(0.8), now).unwrap();
+        repo.upsert_deck(&lowered).await.unwrap();
+        assert_eq!(service.reschedule_all(deck_id, &repo, &repo).await.unwrap(), 1);
+
+        let after = repo.get_cards(deck_id, &[CardId::new(1), CardId::new(2)]).await.unwrap();
+        assert!(after[0].next_review_at() > before[0].next_review_at());
+        assert_eq!(after[0].memory_state(), before[0].memory_state());
+        assert_eq!(after[1].next_review_at(), before[1].next_review_at());
+        assert!(repo.logs_for_card(deck_id, CardId::new(1)).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn clamp_intervals_pulls_in_cards_due_past_the_max() {
+        let repo = storage::repository::InMemoryRepository::new();
+        let now = fixed_now();
+        let deck_id = DeckId::new(1);
+        let settings = DeckSettings::new(
+            5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 180, false, 0.5, 0,
+            0.9, true, 100,
+        )
+        .unwrap();
+        let deck = Deck::new(deck_id, "Deck", None, settings, now).unwrap();
+        repo.upsert_deck(&deck).await.unwrap();
+        insert_due_card(&repo, 1, deck_id, now, now + chrono::Duration::days(400)).await;
+        insert_due_card(&repo, 2, deck_id, now, now + chrono::Duration::days(30)).await;
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+
+        assert_eq!(service.clamp_intervals(deck_id, &repo, &repo).await.unwrap(), 1);
+
+        let after = repo.get_cards(deck_id, &[CardId::new(1), CardId::new(2)]).await.unwrap();
+        assert_eq!(after[0].next_review_at(), now + chrono::Duration::days(180));
+        assert_eq!(after[1].next_review_at(), now + chrono::Duration::days(30));
+        assert_eq!(service.clamp_intervals(deck_id, &repo, &repo).await.unwrap(), 0);
+    }
+
+    fn optimizer_settings(enabled: bool, optimize_after: u32) -> DeckSettings {
+        DeckSettings::new(
+            5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+            0.9, enabled, optimize_after,
+        )
+        .unwrap()
+    }
+
+    /// Append `reviews` spaced reviews per card, doubling the gap after each `Good`.
+    async fn append_history(
+        repo: &storage::repository::InMemoryRepository,
+        deck_id: DeckId,
+        cards: u64,
+        reviews: u32,
+    ) {
+        let now = fixed_now();
+        for card in 1..=cards {
+            let mut at = now;
+            let mut gap = 1;
+            for review in 0..reviews {
+                let grade = if (u64::from(review) + card) % 4 == 0 {
+                    ReviewGrade::Again
+                } else {
+                    ReviewGrade::Good
+                };
+                repo.append_log(ReviewLogRecord {
+                    id: None,
+                    deck_id,
+                    card_id: CardId::new(card),
+                    grade,
+                    reviewed_at: at,
+                    elapsed_days: 0.0,
+                    scheduled_days: 1.0,
+                    stability: 1.0,
+                    difficulty: 5.0,
+                    next_review_at: at,
+                    answer_ms: None,
+                    first_review: false,
+                })
+                .await
+                .unwrap();
+                gap = if grade == ReviewGrade::Again { 1 } else { gap * 2 };
+                at += chrono::Duration::days(gap);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn optimize_parameters_stores_fitted_weights_past_the_threshold() {
+        let repo = storage::repository::InMemoryRepository::new();
+        let now = fixed_now();
+        let deck_id = DeckId::new(1);
+        let deck = Deck::new(deck_id, "Deck", None, optimizer_settings(true, 100), now).unwrap();
+        repo.upsert_deck(&deck).await.unwrap();
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+
+        append_history(&repo, deck_id, 20, 5).await;
+        let err = service.optimize_parameters(deck_id, &repo, &repo).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ReviewServiceError::NotEnoughReviews {
+                required: 100,
+                found: 100
+            }
+        ));
+        let untouched = repo.get_deck(deck_id).await.unwrap().unwrap();
+        assert!(untouched.settings().fsrs_parameters().is_empty());
+
+        append_history(&repo, deck_id, 20, 1).await;
+        let fitted = service.optimize_parameters(deck_id, &repo, &repo).await.unwrap();
+        assert_eq!(fitted.review_count, 120);
+        assert!(fitted.log_loss.is_finite());
+
+        let stored = repo.get_deck(deck_id).await.unwrap().unwrap();
+        assert_eq!(stored.settings().fsrs_parameters(), fitted.parameters.as_slice());
+        assert_eq!(stored.version(), deck.version() + 1);
+        let deck_scheduler = service.deck_scheduler(stored.settings()).unwrap().unwrap();
+        assert_eq!(deck_scheduler.parameters(), fitted.parameters.as_slice());
+    }
+
+    #[tokio::test]
+    async fn optimize_parameters_respects_disabled_optimizer() {
+        let repo = storage::repository::InMemoryRepository::new();
+        let now = fixed_now();
+        let deck_id = DeckId::new(1);
+        let deck = Deck::new(deck_id, "Deck", None, optimizer_settings(false, 1), now).unwrap();
+        repo.upsert_deck(&deck).await.unwrap();
+        append_history(&repo, deck_id, 10, 4).await;
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+
+        let err = service.optimize_parameters(deck_id, &repo, &repo).await.unwrap_err();
+        assert!(matches!(err, ReviewServiceError::OptimizerDisabled));
+    }
+
+    #[tokio::test]
+    async fn prune_history_keeps_each_cards_newest_reviews() {
+        let repo = storage::repository::InMemoryRepository::new();
+        let deck_id = DeckId::new(1);
+        let other_deck = DeckId::new(2);
+        append_history(&repo, deck_id, 3, 5).await;
+        append_history(&repo, other_deck, 1, 4).await;
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(fixed_now()));
+        let reviewed_at = |logs: Vec<ReviewLogRecord>| {
+            logs.iter().map(|log| log.reviewed_at).collect::<Vec<_>>()
+        };
+        let mut expected = Vec::new();
+        for card in 1..=3 {
+            let history = repo.logs_for_card(deck_id, CardId::new(card)).await.unwrap();
+            expected.push(reviewed_at(history)[3..].to_vec());
+        }
+
+        let pruned = service
+            .prune_history(deck_id, ReviewLogPrune::KeepLastPerCard(2), &repo)
+            .await
+            .unwrap();
+
+        assert_eq!(pruned, 9);
+        for (card, newest) in (1..=3).zip(expected) {
+            let remaining = repo.logs_for_card(deck_id, CardId::new(card)).await.unwrap();
+            assert_eq!(reviewed_at(remaining), newest);
+        }
+        assert_eq!(repo.logs_for_deck(other_deck).await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn forecast_for_empty_deck_is_all_zeros() {
+        let repo = storage::repository::InMemoryRepository::new();
+        let now = fixed_now();
+        let service = ReviewService::new().unwrap().with_clock(Clock::Fixed(now));
+
+        let forecast = service.forecast(DeckId::new(1), 3, &repo).await.unwrap();
+        assert_eq!(forecast.len(), 3);
+        assert!(forecast.iter().all(|(_, count)| *count == 0));
+
+        let none = service.forecast(DeckId::new(1), 0, &repo).await.unwrap();
+        assert!(none.is_empty());
+    }
+
+    #[tokio::test]
+    async fn card_history_lists_newest_reviews_first() {
+        let repo = storage::repository::InMemoryRepository::new();
+        let now = fixed_now();
+        let deck_id = DeckId::new(1);
+        let service = ReviewService::new().unwrap();
+        let reviews = [
+            (CardId::new(1), ReviewGrade::Again, 0, 0.01, 0.4),
+            (CardId::new(1), ReviewGrade::Good, 1, 3.0, 2.5),
+            (CardId::new(2), ReviewGrade::Easy, 2, 9.0, 9.0),
+            (CardId::new(1), ReviewGrade::Hard, 4, 5.5, 4.1),
+        ];
+        for (card_id, grade, day, scheduled_days, stability) in reviews {
+            let reviewed_at = now + chrono::Duration::days(day);
+            repo.append_log(ReviewLogRecord {
+                id: None,
+                deck_id,
+                card_id,
+                grade,
+                reviewed_at,
+                elapsed_days: 0.0,
+                scheduled_days,
+                stability,
+                difficulty: 5.0,
+                next_review_at: reviewed_at,
+                answer_ms: None,
+                first_review: false,
+            })
+            .await
+            .unwrap();
+        }
+
+        let history = service
+            .card_history(deck_id, CardId::new(1), 2, &repo)
+            .await
+            .unwrap();
+        assert_eq!(
+            history,
+            vec![
+                CardHistoryEntry {
+                    grade: ReviewGrade::Hard,
+                    reviewed_at: now + chrono::Duration::days(4),
+                    interval_days: 5.5,
+                    stability: 4.1,
+                },
+                CardHistoryEntry {
+                    grade: ReviewGrade::Good,
+                    reviewed_at: now + chrono::Duration::days(1),
+                    interval_days: 3.0,
+                    stability: 2.5,
+                },
+            ]
+        );
+
+        let unreviewed = service
+            .card_history(deck_id, CardId::new(3), 10, &repo)
+            .await
+            .unwrap();
+        assert!(unreviewed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn export_logs_writes_only_a_header_for_an_unreviewed_deck() {
+        let repo = storage::repository::InMemoryRepository::new();
+        let service = ReviewService::new().unwrap();
+        let mut out = Vec::new();
+
+        let written = service
+            .export_logs(DeckId::new(1), &mut out, LogExportFormat::Csv, &repo)
+            .await
+            .unwrap();
+
+        assert_eq!(written, 0);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "card_id,reviewed_at,grade,interval_days,stability,difficulty\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn export_logs_pages_through_thousands_of_reviews() {
+        let repo = storage::repository::InMemoryRepository::new();
+        let deck_id = DeckId::new(1);
+        append_history(&repo, deck_id, 30, 101).await;
+        append_history(&repo, DeckId::new(2), 2, 3).await;
+        let service = ReviewService::new().unwrap();
+        let mut out = Vec::new();
+
+        let written = service
+            .export_logs(deck_id, &mut out, LogExportFormat::Csv, &repo)
+            .await
+            .unwrap();
+
+        assert_eq!(written, 3030);
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3031);
+        assert_eq!(lines[1], "1,2023-11-14T22:13:20Z,good,1,1,5");
+        assert!(lines[1..].iter().all(|line| line.split(',').count() == 6));
+        let last_card = lines.last().unwrap().split(',').next().unwrap();
+        assert_eq!(last_card, "30");
+    }
 }
@@ -6,9 +6,16 @@ mod view;
 mod workflow;
 
 // Public API of the session subsystem.
+pub(crate) use plan::effective_daily_limits;
 pub use crate::error::SessionError;
+pub use plan::CramFilter;
+pub use progress::SessionProgress;
 pub use service::{SessionReview, SessionService};
-pub use view::{SessionSummaryDeckItem, SessionSummaryId, SessionSummaryListItem, SessionSummaryService};
+pub use view::{
+    DeckStats, SessionSummaryDeckItem, SessionSummaryId, SessionSummaryListItem,
+    SessionSummaryService, StatsRange,
+};
 pub use workflow::{
-    ReviewGradePreview, SessionAnswerResult, SessionLoopService, SessionReviewPreview,
+    NothingDue, SessionAnswerResult, SessionLoopService, SessionPlanPreview,
+    SessionReviewPreview, SessionStart,
 };
@@ -1,8 +1,63 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use rand::rng;
 use rand::seq::SliceRandom;
 use std::collections::HashSet;
 
-use learn_core::model::{Card, Deck};
+use learn_core::model::{
+    Card, CardId, Deck, DeckSettings, NewCardOrder, NewReviewMix, NoteId, TagName,
+};
+use learn_core::time::StudyDay;
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn apply_easy_day_limit(limit: u32, factor: f32) -> u32 {
+    let scaled = f64::from(limit) * f64::from(factor);
+    if scaled <= 0.0 {
+        return 0;
+    }
+    if scaled >= f64::from(u32::MAX) {
+        return u32::MAX;
+    }
+    scaled.floor() as u32
+}
+
+/// Daily review and new-card limits for a deck on `study_date`, scaled down on easy days.
+///
+/// Due cards cut by the smaller cap stay due and carry over to the next day.
+pub(crate) fn effective_daily_limits(
+    settings: &DeckSettings,
+    study_date: NaiveDate,
+) -> (u32, u32) {
+    if !settings.is_easy_day(study_date.weekday()) {
+        return (settings.review_limit_per_day(), settings.new_cards_per_day());
+    }
+    let factor = settings.easy_day_load_factor();
+    (
+        apply_easy_day_limit(settings.review_limit_per_day(), factor),
+        apply_easy_day_limit(settings.new_cards_per_day(), factor),
+    )
+}
+
+/// Put new cards in the order `order` draws them on `study_date`.
+///
+/// Random order sorts by a hash of the day and card id, so it holds all day whichever
+/// cards are still new, and changes when the day does.
+pub(crate) fn order_new_cards(cards: &mut [Card], order: NewCardOrder, study_date: NaiveDate) {
+    match order {
+        NewCardOrder::Sequential => cards.sort_by_key(|c| (c.created_at(), c.id().value())),
+        NewCardOrder::Random => {
+            let day = u64::from(study_date.num_days_from_ce().unsigned_abs());
+            cards.sort_by_key(|c| (daily_shuffle_key(day, c.id()), c.id().value()));
+        }
+    }
+}
+
+/// SplitMix64 over the day and card id; the same on every run and platform.
+fn daily_shuffle_key(day: u64, id: CardId) -> u64 {
+    let mut z = (day.rotate_left(32) ^ id.value()).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
 
 /// Selection result for a session build.
 #[derive(Debug, Clone, PartialEq)]
@@ -11,6 +66,8 @@ pub struct SessionPlan {
     pub due_selected: usize,
     pub new_selected: usize,
     pub future_selected: usize,
+    /// Candidates held back because a card of the same note is queued, to bury for the day.
+    pub siblings_to_bury: Vec<CardId>,
 }
 
 // Some plan helpers are currently used only in tests or planned UI flows.
@@ -29,10 +86,24 @@ impl SessionPlan {
     }
 }
 
+/// Which cards a cram session draws from a deck.
+///
+/// Filters combine: with both set, a card must carry one of the tags and be due in time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CramFilter {
+    /// Only cards carrying at least one of these tags; empty means any card.
+    pub tags: Vec<TagName>,
+    /// Only reviewed cards due within this many days from now, overdue ones included.
+    pub due_within_days: Option<u32>,
+}
+
 /// Builds a micro-session by picking due and new cards according to deck settings.
 pub struct SessionBuilder<'a> {
     deck: &'a Deck,
     shuffle_new: bool,
+    now: Option<DateTime<Utc>>,
+    study_day: StudyDay,
+    new_introduced_today: u32,
 }
 
 impl<'a> SessionBuilder<'a> {
@@ -41,6 +112,9 @@ impl<'a> SessionBuilder<'a> {
         Self {
             deck,
             shuffle_new: false,
+            now: None,
+            study_day: StudyDay::default(),
+            new_introduced_today: 0,
         }
     }
 
@@ -51,84 +125,202 @@ impl<'a> SessionBuilder<'a> {
         self
     }
 
+    /// Plan for the day containing `now`, so easy days scale the daily caps.
+    #[must_use]
+    pub fn with_now(mut self, now: DateTime<Utc>) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// Where study days begin when deciding which day `with_now` falls in.
+    #[must_use]
+    pub fn with_study_day(mut self, study_day: StudyDay) -> Self {
+        self.study_day = study_day;
+        self
+    }
+
+    /// Count `count` new cards as already introduced today, so they come out of the daily
+    /// new-card allowance before the per-session cap applies.
+    #[must_use]
+    pub fn with_new_introduced_today(mut self, count: u32) -> Self {
+        self.new_introduced_today = count;
+        self
+    }
+
     /// Build a session plan from storage-provided lists of due and new cards.
     ///
     /// - `due_cards` are assumed to already be due; they are sorted by `next_review_at`.
-    /// - `new_cards` are unreviewed; they are optionally shuffled.
+    /// - `new_cards` are unreviewed; they follow the deck's `new_card_order` for the day of
+    ///   `with_now` (oldest first without it), unless `with_shuffle_new` shuffles them.
     /// - Selection respects deck `review_limit_per_day`, `new_cards_per_day`, and `micro_session_size`.
+    /// - With `with_now` on an easy day, both daily caps are scaled by `easy_day_load_factor`.
+    /// - New cards already introduced today (`with_new_introduced_today`) come out of the
+    ///   daily allowance; a deck `new_per_session` limit then caps what is left.
+    /// - Selected new cards are placed among the reviews according to `new_review_mix`.
+    /// - With `hold_new_until_reviews_done`, no new cards are picked while any review within
+    ///   the day's review cap is still due.
+    /// - With `bury_siblings`, at most one card per note is picked; the candidates sharing a
+    ///   note with a picked card are listed in `siblings_to_bury`.
     pub fn build(
         self,
         due_cards: impl IntoIterator<Item = Card>,
         new_cards: impl IntoIterator<Item = Card>,
     ) -> SessionPlan {
         let settings = self.deck.settings();
+        let (review_limit, new_limit) = match self.now {
+            Some(now) => effective_daily_limits(settings, self.study_day.date_of(now)),
+            None => (settings.review_limit_per_day(), settings.new_cards_per_day()),
+        };
         let micro_cap = usize::try_from(settings.micro_session_size()).unwrap_or(usize::MAX);
         let due_cap = if settings.protect_overload() {
-            usize::try_from(settings.review_limit_per_day()).unwrap_or(usize::MAX)
+            usize::try_from(review_limit).unwrap_or(usize::MAX)
         } else {
             usize::MAX
         };
-        let new_cap = usize::try_from(settings.new_cards_per_day()).unwrap_or(usize::MAX);
+        let new_left = new_limit.saturating_sub(self.new_introduced_today);
+        let new_cap = usize::try_from(settings.session_new_limit(new_left)).unwrap_or(usize::MAX);
 
+        let bury_siblings = settings.bury_siblings();
+        let mut held_back = Vec::new();
         let mut due: Vec<Card> = due_cards.into_iter().collect();
         due.sort_by_key(|c| (c.next_review_at(), c.id().value()));
+        if bury_siblings {
+            due = one_per_note(due, &mut HashSet::new(), &mut held_back);
+        }
 
-        let mut selected = Vec::new();
+        let reviews_pending = due.len().min(due_cap) > 0;
+        let new_cap = if settings.hold_new_until_reviews_done() && reviews_pending {
+            0
+        } else {
+            new_cap
+        };
 
         let due_take = due_cap.min(micro_cap);
-        let due_selected = due.into_iter().take(due_take).collect::<Vec<_>>();
+        let mut due_selected = due;
+        held_back.extend(due_selected.split_off(due_take.min(due_selected.len())));
         let due_count = due_selected.len();
-        selected.extend(due_selected);
 
-        let mut selected_ids: HashSet<_> = selected.iter().map(Card::id).collect();
+        let selected_ids: HashSet<_> = due_selected.iter().map(Card::id).collect();
 
-        let remaining = micro_cap.saturating_sub(selected.len());
-        let mut new_count = 0;
-        if remaining > 0 && new_cap > 0 {
-            let take = new_cap.min(remaining);
-            let mut new_candidates: Vec<Card> = new_cards
-                .into_iter()
-                .filter(|c| !selected_ids.contains(&c.id()))
-                .collect();
+        let remaining = micro_cap.saturating_sub(due_count);
+        let mut new_candidates: Vec<Card> = new_cards
+            .into_iter()
+            .filter(|c| !selected_ids.contains(&c.id()))
+            .collect();
+        if self.shuffle_new {
+            let mut rng = rng();
+            new_candidates.as_mut_slice().shuffle(&mut rng);
+        } else if let Some(now) = self.now {
+            let study_date = self.study_day.date_of(now);
+            order_new_cards(&mut new_candidates, settings.new_card_order(), study_date);
+        } else {
+            new_candidates.sort_by_key(|c| (c.created_at(), c.id().value()));
+        }
+        if bury_siblings {
+            let mut queued_notes = due_selected.iter().filter_map(Card::note_id).collect();
+            new_candidates = one_per_note(new_candidates, &mut queued_notes, &mut held_back);
+        }
+        let take = if remaining > 0 { new_cap.min(remaining) } else { 0 };
+        let mut new_selected = new_candidates;
+        held_back.extend(new_selected.split_off(take.min(new_selected.len())));
+        let new_count = new_selected.len();
 
-            if self.shuffle_new {
-                let mut rng = rng();
-                new_candidates.as_mut_slice().shuffle(&mut rng);
-            } else {
-                new_candidates.sort_by_key(|c| (c.created_at(), c.id().value()));
-            }
+        let siblings_to_bury = if bury_siblings {
+            let queued_notes: HashSet<NoteId> = due_selected
+                .iter()
+                .chain(&new_selected)
+                .filter_map(Card::note_id)
+                .collect();
+            held_back
+                .iter()
+                .filter(|c| c.note_id().is_some_and(|note| queued_notes.contains(&note)))
+                .map(Card::id)
+                .collect()
+        } else {
+            Vec::new()
+        };
 
-            let new_cards: Vec<Card> = new_candidates.into_iter().take(take).collect();
-            new_count = new_cards.len();
-            selected_ids.extend(new_cards.iter().map(Card::id));
-            selected.extend(new_cards);
-        }
+        let cards = match settings.new_review_mix() {
+            NewReviewMix::FrontLoad => new_selected.into_iter().chain(due_selected).collect(),
+            NewReviewMix::Interleaved => interleave(due_selected, new_selected),
+            NewReviewMix::AfterReviews => due_selected.into_iter().chain(new_selected).collect(),
+        };
 
         SessionPlan {
-            cards: selected,
+            cards,
             due_selected: due_count,
             new_selected: new_count,
             future_selected: 0,
+            siblings_to_bury,
+        }
+    }
+}
+
+/// Keep the first of `cards` for each note, moving later siblings to `held_back`.
+///
+/// Notes already in `seen` keep none; cards without a note are always kept.
+fn one_per_note(
+    cards: Vec<Card>,
+    seen: &mut HashSet<NoteId>,
+    held_back: &mut Vec<Card>,
+) -> Vec<Card> {
+    let mut kept = Vec::with_capacity(cards.len());
+    for card in cards {
+        match card.note_id() {
+            Some(note) if !seen.insert(note) => held_back.push(card),
+            _ => kept.push(card),
         }
     }
+    kept
+}
+
+/// Spread `new` through `due`, placing each new card after the nearest evenly spaced review.
+fn interleave(due: Vec<Card>, new: Vec<Card>) -> Vec<Card> {
+    let (due_len, new_len) = (due.len(), new.len());
+    let mut out = Vec::with_capacity(due_len + new_len);
+    let mut due = due.into_iter();
+    for (index, card) in new.into_iter().enumerate() {
+        // Reviews before this new card: round((index + 1) * due_len / (new_len + 1)).
+        let reviews_before = ((index + 1) * due_len * 2 + new_len + 1) / (2 * (new_len + 1));
+        let shown = out.len() - index;
+        out.extend(due.by_ref().take(reviews_before.saturating_sub(shown)));
+        out.push(card);
+    }
+    out.extend(due);
+    out
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use learn_core::model::{CardId, DeckId, ReviewGrade, content::ContentDraft};
+    use chrono::FixedOffset;
+    use learn_core::model::{
+        CardId, CardKind, DeckId, DeckSettings, ReviewGrade, content::ContentDraft,
+    };
     use learn_core::scheduler::Scheduler;
     use learn_core::time::fixed_now;
 
     fn build_card(id: u64) -> Card {
+        build_card_created_at(id, fixed_now())
+    }
+
+    fn build_card_created_at(id: u64, created_at: DateTime<Utc>) -> Card {
         let prompt = ContentDraft::text_only("Q")
             .validate(fixed_now(), None, None)
             .unwrap();
         let answer = ContentDraft::text_only("A")
             .validate(fixed_now(), None, None)
             .unwrap();
-        let now = fixed_now();
-        Card::new(CardId::new(id), DeckId::new(1), prompt, answer, now, now).unwrap()
+        Card::new(
+            CardId::new(id),
+            DeckId::new(1),
+            CardKind::Basic,
+            prompt,
+            answer,
+            created_at,
+            created_at,
+        )
+        .unwrap()
     }
 
     fn build_deck() -> Deck {
@@ -205,4 +397,268 @@ mod tests {
         assert!(plan.due_selected > 1);
         assert_eq!(plan.due_selected, plan.cards.len());
     }
+
+    #[test]
+    fn builder_caps_new_cards_per_session_under_the_daily_limit() {
+        let settings = DeckSettings::new(
+            20, 50, 30, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5,
+            0, 0.85, true, 100,
+        )
+        .unwrap();
+        let new_cards: Vec<Card> = (1..=25).map(build_card).collect();
+
+        let daily_deck = build_deck_with_settings(settings.clone());
+        let capped_deck = build_deck_with_settings(settings.with_new_per_session(5));
+        let daily = SessionBuilder::new(&daily_deck).build(Vec::new(), new_cards.clone());
+        let capped = SessionBuilder::new(&capped_deck).build(Vec::new(), new_cards);
+
+        assert_eq!(daily.new_selected, 20);
+        assert_eq!(capped.new_selected, 5);
+        assert_eq!(capped.cards.len(), 5);
+    }
+
+    #[test]
+    fn builder_takes_new_cards_introduced_today_from_the_daily_limit() {
+        let settings = DeckSettings::new(
+            20, 50, 30, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5,
+            0, 0.85, true, 100,
+        )
+        .unwrap()
+        .with_new_per_session(5);
+        let deck = build_deck_with_settings(settings);
+        let new_cards: Vec<Card> = (1..=25).map(build_card).collect();
+
+        let plan = SessionBuilder::new(&deck)
+            .with_new_introduced_today(18)
+            .build(Vec::new(), new_cards.clone());
+        let spent = SessionBuilder::new(&deck)
+            .with_new_introduced_today(20)
+            .build(Vec::new(), new_cards);
+
+        assert_eq!(plan.new_selected, 2);
+        assert_eq!(spent.new_selected, 0);
+    }
+
+    fn easy_weekend_settings() -> DeckSettings {
+        let weekend = DeckSettings::default_for_adhd().easy_days_mask();
+        DeckSettings::new(
+            4, 10, 20, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, true, 0.5,
+            weekend, 0.85, true, 100,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn builder_scales_daily_caps_on_easy_days() {
+        let deck = build_deck_with_settings(easy_weekend_settings());
+        let due_cards: Vec<Card> = (1..=12).map(|id| build_due_card(id, 3)).collect();
+        let new_cards: Vec<Card> = (13..=18).map(build_card).collect();
+        // `fixed_now` is a Tuesday; four days later is a Saturday.
+        let saturday = fixed_now() + chrono::Duration::days(4);
+
+        let weekday = SessionBuilder::new(&deck)
+            .with_now(fixed_now())
+            .build(due_cards.clone(), new_cards.clone());
+        let easy = SessionBuilder::new(&deck)
+            .with_now(saturday)
+            .build(due_cards, new_cards);
+
+        assert_eq!((weekday.due_selected, weekday.new_selected), (10, 4));
+        assert_eq!((easy.due_selected, easy.new_selected), (5, 2));
+        assert_eq!(easy.total(), 7);
+    }
+
+    #[test]
+    fn builder_counts_hours_before_the_cutoff_toward_the_previous_day() {
+        let deck = build_deck_with_settings(easy_weekend_settings());
+        let utc = FixedOffset::east_opt(0).unwrap();
+        // 2am on a Saturday, which is still Friday's study day with a 4am cutoff.
+        let saturday_2am = NaiveDate::from_ymd_opt(2023, 11, 18)
+            .unwrap()
+            .and_hms_opt(2, 0, 0)
+            .unwrap()
+            .and_utc();
+        let plan = |study_day: StudyDay| {
+            SessionBuilder::new(&deck)
+                .with_now(saturday_2am)
+                .with_study_day(study_day.with_utc_offset(utc))
+                .build((1..=12).map(|id| build_due_card(id, 3)), (13..=18).map(build_card))
+        };
+
+        let midnight = plan(StudyDay::new(0));
+        let cutoff = plan(StudyDay::new(4));
+
+        assert_eq!((midnight.due_selected, midnight.new_selected), (5, 2));
+        assert_eq!((cutoff.due_selected, cutoff.new_selected), (10, 4));
+    }
+
+    #[test]
+    fn builder_ignores_easy_days_when_disabled() {
+        let weekend = DeckSettings::default_for_adhd().easy_days_mask();
+        let settings = DeckSettings::new(
+            4, 10, 20, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5,
+            weekend, 0.85, true, 100,
+        )
+        .unwrap();
+        let deck = build_deck_with_settings(settings);
+        let saturday = fixed_now() + chrono::Duration::days(4);
+
+        let plan = SessionBuilder::new(&deck)
+            .with_now(saturday)
+            .build((1..=12).map(|id| build_due_card(id, 3)), (13..=18).map(build_card));
+
+        assert_eq!((plan.due_selected, plan.new_selected), (10, 4));
+    }
+
+    fn hold_new_settings() -> DeckSettings {
+        DeckSettings::new(
+            5, 30, 10, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+            0.85, true, 100,
+        )
+        .unwrap()
+        .with_hold_new_until_reviews_done(true)
+    }
+
+    #[test]
+    fn hold_new_withholds_new_cards_while_reviews_are_pending() {
+        let deck = build_deck_with_settings(hold_new_settings());
+        // More reviews than fit one micro-session: the leftovers are still pending.
+        let due_cards: Vec<Card> = (1..=12).map(|id| build_due_card(id, 3)).collect();
+
+        let plan = SessionBuilder::new(&deck).build(due_cards, (13..=15).map(build_card));
+
+        assert_eq!((plan.due_selected, plan.new_selected), (10, 0));
+        assert!(plan.cards.iter().all(|card| !card.is_new()));
+    }
+
+    #[test]
+    fn hold_new_lets_new_cards_in_once_reviews_are_cleared() {
+        let deck = build_deck_with_settings(hold_new_settings());
+
+        let plan = SessionBuilder::new(&deck).build(Vec::new(), (1..=8).map(build_card));
+
+        assert_eq!((plan.due_selected, plan.new_selected), (0, 5));
+    }
+
+    #[test]
+    fn hold_new_off_mixes_new_cards_in_with_pending_reviews() {
+        let settings = hold_new_settings().with_hold_new_until_reviews_done(false);
+        let deck = build_deck_with_settings(settings);
+
+        let plan = SessionBuilder::new(&deck)
+            .build((1..=3).map(|id| build_due_card(id, 3)), (4..=6).map(build_card));
+
+        assert_eq!((plan.due_selected, plan.new_selected), (3, 3));
+    }
+
+    fn mixed_plan_ids(mix: NewReviewMix, due: u64, new: u64) -> Vec<u64> {
+        let settings = DeckSettings::new(
+            5, 30, 10, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+            0.85, true, 100,
+        )
+        .unwrap()
+        .with_new_review_mix(mix);
+        let deck = build_deck_with_settings(settings);
+        let due_cards = (1..=due).map(|id| build_due_card(id, 10 - i64::try_from(id).unwrap()));
+        let new_cards = (due + 1..=due + new).map(build_card);
+
+        let plan = SessionBuilder::new(&deck).build(due_cards, new_cards);
+
+        assert_eq!(plan.due_selected, usize::try_from(due).unwrap());
+        assert_eq!(plan.new_selected, usize::try_from(new).unwrap());
+        plan.cards.iter().map(|card| card.id().value()).collect()
+    }
+
+    #[test]
+    fn after_reviews_places_new_cards_last() {
+        assert_eq!(mixed_plan_ids(NewReviewMix::AfterReviews, 3, 2), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn front_load_places_new_cards_first() {
+        assert_eq!(mixed_plan_ids(NewReviewMix::FrontLoad, 3, 2), vec![4, 5, 1, 2, 3]);
+    }
+
+    #[test]
+    fn interleaved_spaces_new_cards_evenly() {
+        assert_eq!(mixed_plan_ids(NewReviewMix::Interleaved, 3, 2), vec![1, 4, 2, 5, 3]);
+        assert_eq!(mixed_plan_ids(NewReviewMix::Interleaved, 4, 2), vec![1, 5, 2, 3, 6, 4]);
+        assert_eq!(mixed_plan_ids(NewReviewMix::Interleaved, 0, 2), vec![1, 2]);
+        assert_eq!(mixed_plan_ids(NewReviewMix::Interleaved, 2, 0), vec![1, 2]);
+    }
+
+    fn new_card_order_ids(order: NewCardOrder, now: DateTime<Utc>) -> Vec<u64> {
+        let settings = hold_new_settings()
+            .with_hold_new_until_reviews_done(false)
+            .with_new_card_order(order);
+        let deck = build_deck_with_settings(settings);
+        // Higher ids were added earlier, so id order and creation order disagree.
+        let new_cards = (1..=5).map(|id| {
+            let minutes = i64::try_from(id).unwrap();
+            build_card_created_at(id, fixed_now() - chrono::Duration::minutes(minutes))
+        });
+        let utc = FixedOffset::east_opt(0).unwrap();
+
+        let plan = SessionBuilder::new(&deck)
+            .with_now(now)
+            .with_study_day(StudyDay::new(0).with_utc_offset(utc))
+            .build(Vec::new(), new_cards);
+
+        plan.cards.iter().map(|card| card.id().value()).collect()
+    }
+
+    #[test]
+    fn sequential_new_card_order_follows_creation_order() {
+        let ids = new_card_order_ids(NewCardOrder::Sequential, fixed_now());
+
+        assert_eq!(ids, vec![5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn random_new_card_order_holds_within_a_day_and_changes_across_days() {
+        let today = new_card_order_ids(NewCardOrder::Random, fixed_now());
+        // `fixed_now` is 22:13 UTC, so an hour earlier is the same study day.
+        let earlier_today =
+            new_card_order_ids(NewCardOrder::Random, fixed_now() - chrono::Duration::hours(1));
+        let later_days: Vec<Vec<u64>> = (1..=7)
+            .map(|days| {
+                new_card_order_ids(NewCardOrder::Random, fixed_now() + chrono::Duration::days(days))
+            })
+            .collect();
+
+        assert_eq!(today, earlier_today);
+        let mut sorted = today.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, vec![1, 2, 3, 4, 5]);
+        assert!(later_days.iter().any(|ids| *ids != today));
+    }
+
+    fn sibling_cards() -> (Card, Card) {
+        let note = Some(NoteId::new(7));
+        (build_card(1).with_note_id(note), build_card(2).with_note_id(note))
+    }
+
+    #[test]
+    fn bury_siblings_queues_one_card_per_note() {
+        let (front, reverse) = sibling_cards();
+
+        let plan = SessionBuilder::new(&build_deck()).build(Vec::new(), vec![front, reverse]);
+
+        let ids: Vec<u64> = plan.cards.iter().map(|card| card.id().value()).collect();
+        assert_eq!(ids, vec![1]);
+        assert_eq!(plan.new_selected, 1);
+        assert_eq!(plan.siblings_to_bury, vec![CardId::new(2)]);
+    }
+
+    #[test]
+    fn bury_siblings_off_queues_both_siblings() {
+        let settings = DeckSettings::default_for_adhd().with_bury_siblings(false);
+        let (front, reverse) = sibling_cards();
+
+        let plan = SessionBuilder::new(&build_deck_with_settings(settings))
+            .build(Vec::new(), vec![front, reverse]);
+
+        assert_eq!(plan.cards.len(), 2);
+        assert!(plan.siblings_to_bury.is_empty());
+    }
 }
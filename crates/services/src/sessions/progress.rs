@@ -3,6 +3,21 @@
 pub struct SessionProgress {
     pub total: usize,
     pub answered: usize,
+    /// Cards still queued, including learning cards queued again for a later step.
     pub remaining: usize,
+    /// Reviews plus new cards the deck's daily limits allow today.
+    pub daily_target: usize,
     pub is_complete: bool,
 }
+
+impl SessionProgress {
+    /// Whole-number share of the session answered so far, from 0 to 100.
+    #[must_use]
+    pub fn percent_answered(&self) -> usize {
+        let planned = self.answered + self.remaining;
+        if planned == 0 {
+            return if self.is_complete { 100 } else { 0 };
+        }
+        self.answered * 100 / planned
+    }
+}
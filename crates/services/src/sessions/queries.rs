@@ -1,49 +1,27 @@
-use chrono::{DateTime, Datelike, Utc};
+use chrono::{DateTime, Utc};
 use rand::rng;
 use rand::seq::SliceRandom;
 
-use learn_core::model::{CardPhase, Deck, DeckId, SessionSummary, TagName};
+use learn_core::model::{CardId, CardPhase, Deck, DeckId, NewCardOrder, SessionSummary, TagName};
+use learn_core::time::StudyDay;
 use storage::repository::{
-    CardRepository, DeckRepository, SessionSummaryRepository, SessionSummaryRow,
+    CardRepository, DeckRepository, SessionSummaryRepository, SessionSummaryRow, StorageError,
 };
 
 use crate::error::SessionError;
-use super::plan::{SessionBuilder, SessionPlan};
+use super::plan::{
+    CramFilter, SessionBuilder, SessionPlan, effective_daily_limits, order_new_cards,
+};
 use super::service::SessionService;
 
 /// Storage-backed session queries and builders.
 pub(crate) struct SessionQueries;
 
-#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-fn apply_easy_day_limit(limit: u32, factor: f32) -> u32 {
-    let scaled = f64::from(limit) * f64::from(factor);
-    if scaled <= 0.0 {
-        return 0;
-    }
-    if scaled >= f64::from(u32::MAX) {
-        return u32::MAX;
-    }
-    scaled.floor() as u32
-}
-
-fn effective_daily_limits(
-    settings: &learn_core::model::DeckSettings,
-    now: DateTime<Utc>,
-) -> (u32, u32) {
-    if !settings.is_easy_day(now.weekday()) {
-        return (settings.review_limit_per_day(), settings.new_cards_per_day());
-    }
-    let factor = settings.easy_day_load_factor();
-    (
-        apply_easy_day_limit(settings.review_limit_per_day(), factor),
-        apply_easy_day_limit(settings.new_cards_per_day(), factor),
-    )
-}
-
 // Some query helpers are used only in tests or planned UI flows.
 #[allow(dead_code)]
 impl SessionQueries {
-    /// Build a session plan using repository data.
+    /// Build a session plan using repository data, with the daily caps of the study day
+    /// containing `now`, less the `new_introduced_today` new cards already started in it.
     ///
     /// # Errors
     ///
@@ -53,23 +31,38 @@ impl SessionQueries {
         decks: &dyn DeckRepository,
         cards: &dyn CardRepository,
         now: DateTime<Utc>,
+        study_day: StudyDay,
         shuffle_new: bool,
+        new_introduced_today: u32,
     ) -> Result<(Deck, SessionPlan), SessionError> {
         let deck = decks
             .get_deck(deck_id)
             .await?
             .ok_or(storage::repository::StorageError::NotFound)?;
+        if deck.is_archived() {
+            let plan = SessionBuilder::new(&deck).build(Vec::new(), Vec::new());
+            return Ok((deck, plan));
+        }
         let settings = deck.settings();
-        let (review_limit, new_limit) = effective_daily_limits(settings, now);
+        let (review_limit, new_limit) = effective_daily_limits(settings, study_day.date_of(now));
+        let new_limit = new_limit.saturating_sub(new_introduced_today);
         let due = cards
             .due_cards(deck_id, now, review_limit)
             .await?;
+        // A random pick draws from every new card, not just the oldest few.
+        let new_fetch = match settings.new_card_order() {
+            NewCardOrder::Sequential => settings.session_new_limit(new_limit),
+            NewCardOrder::Random => u32::MAX,
+        };
         let new_cards = cards
-            .new_cards(deck_id, new_limit)
+            .new_cards(deck_id, now, new_fetch)
             .await?;
 
         let plan = SessionBuilder::new(&deck)
             .with_shuffle_new(shuffle_new)
+            .with_now(now)
+            .with_study_day(study_day)
+            .with_new_introduced_today(new_introduced_today)
             .build(due, new_cards);
 
         Ok((deck, plan))
@@ -86,10 +79,21 @@ impl SessionQueries {
         decks: &dyn DeckRepository,
         cards: &dyn CardRepository,
         now: DateTime<Utc>,
+        study_day: StudyDay,
         shuffle_new: bool,
+        new_introduced_today: u32,
     ) -> Result<(Deck, SessionService), SessionError> {
         let (deck, plan) =
-            Self::build_plan_from_storage(deck_id, decks, cards, now, shuffle_new).await?;
+            Self::build_plan_from_storage(
+                deck_id,
+                decks,
+                cards,
+                now,
+                study_day,
+                shuffle_new,
+                new_introduced_today,
+            )
+            .await?;
         let session = SessionService::new(&deck, plan.cards, now)?;
         Ok((deck, session))
     }
@@ -110,7 +114,8 @@ impl SessionQueries {
             .get_deck(deck_id)
             .await?
             .ok_or(storage::repository::StorageError::NotFound)?;
-        let cards = cards.list_cards(deck_id, u32::MAX).await?;
+        let mut cards = cards.list_cards(deck_id, u32::MAX).await?;
+        cards.retain(|card| !card.is_suspended());
         let session = SessionService::new_all(&deck, cards, now)?;
         Ok((deck, session))
     }
@@ -135,7 +140,7 @@ impl SessionQueries {
             .list_cards(deck_id, u32::MAX)
             .await?
             .into_iter()
-            .filter(|card| card.phase() == CardPhase::Relearning)
+            .filter(|card| card.phase() == CardPhase::Relearning && !card.is_suspended())
             .collect();
         mistakes.sort_by_key(|card| (card.next_review_at(), card.id().value()));
         let session = SessionService::new(&deck, mistakes, now)?;
@@ -148,30 +153,49 @@ impl SessionQueries {
     ///
     /// Returns `SessionError::Empty` if no cards are available, or
     /// `SessionError::Storage` on repository failures.
+    #[allow(clippy::too_many_arguments)]
     pub async fn start_from_storage_with_tags(
         deck_id: DeckId,
         decks: &dyn DeckRepository,
         cards: &dyn CardRepository,
         now: DateTime<Utc>,
+        study_day: StudyDay,
         shuffle_new: bool,
+        new_introduced_today: u32,
         tag_names: &[TagName],
     ) -> Result<(Deck, SessionService), SessionError> {
         if tag_names.is_empty() {
-            return Self::start_from_storage(deck_id, decks, cards, now, shuffle_new).await;
+            return Self::start_from_storage(
+                deck_id,
+                decks,
+                cards,
+                now,
+                study_day,
+                shuffle_new,
+                new_introduced_today,
+            )
+            .await;
         }
 
         let deck = decks
             .get_deck(deck_id)
             .await?
             .ok_or(storage::repository::StorageError::NotFound)?;
+        if deck.is_archived() {
+            return Err(SessionError::Empty);
+        }
         let settings = deck.settings();
-        let (review_limit, new_limit) = effective_daily_limits(settings, now);
+        let (review_limit, new_limit) = effective_daily_limits(settings, study_day.date_of(now));
+        let new_limit = new_limit.saturating_sub(new_introduced_today);
 
         let tagged_cards = cards.list_cards_by_tags(deck_id, tag_names).await?;
         let mut due = Vec::new();
         let mut new_cards = Vec::new();
 
         for card in tagged_cards {
+            if card.is_suspended() || card.is_buried_at(now) {
+                continue;
+            }
             if card.is_new() {
                 new_cards.push(card);
             } else if card.is_due(now) {
@@ -192,19 +216,128 @@ impl SessionQueries {
                 let mut rng = rng();
                 new_cards.as_mut_slice().shuffle(&mut rng);
             } else {
-                new_cards.sort_by_key(|card| (card.created_at(), card.id().value()));
+                let study_date = study_day.date_of(now);
+                order_new_cards(&mut new_cards, settings.new_card_order(), study_date);
             }
             new_cards.truncate(new_limit as usize);
         }
 
         let plan = SessionBuilder::new(&deck)
             .with_shuffle_new(shuffle_new)
+            .with_now(now)
+            .with_study_day(study_day)
+            .with_new_introduced_today(new_introduced_today)
             .build(due, new_cards);
 
         let session = SessionService::new(&deck, plan.cards, now)?;
         Ok((deck, session))
     }
 
+    /// Create a cram session from every card matching `filter`, ordered by due time.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Empty` if no cards match, or
+    /// `SessionError::Storage` on repository failures.
+    pub async fn start_from_storage_cram(
+        deck_id: DeckId,
+        decks: &dyn DeckRepository,
+        cards: &dyn CardRepository,
+        now: DateTime<Utc>,
+        filter: &CramFilter,
+    ) -> Result<(Deck, SessionService), SessionError> {
+        let deck = decks
+            .get_deck(deck_id)
+            .await?
+            .ok_or(storage::repository::StorageError::NotFound)?;
+        let mut selected = if filter.tags.is_empty() {
+            cards.list_cards(deck_id, u32::MAX).await?
+        } else {
+            cards.list_cards_by_tags(deck_id, &filter.tags).await?
+        };
+        selected.retain(|card| !card.is_suspended());
+        if let Some(days) = filter.due_within_days {
+            let until = now + chrono::Duration::days(i64::from(days));
+            selected.retain(|card| !card.is_new() && card.is_due(until));
+        }
+        selected.sort_by_key(|card| (card.next_review_at(), card.id().value()));
+        let session = SessionService::new_cram(&deck, selected, now)?;
+        Ok((deck, session))
+    }
+
+    /// Create a session from exactly the cards in `card_ids`, in the order given.
+    ///
+    /// Due dates and suspension are ignored, since the cards were chosen by hand; repeated
+    /// ids are queued once. With `cram`, grades leave scheduling unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::CardsUnavailable` if the deck is missing or archived, or any
+    /// id is not a card in it, `SessionError::Empty` if `card_ids` is empty, or
+    /// `SessionError::Storage` on other repository failures.
+    pub async fn start_from_storage_custom(
+        deck_id: DeckId,
+        decks: &dyn DeckRepository,
+        cards: &dyn CardRepository,
+        now: DateTime<Utc>,
+        card_ids: &[CardId],
+        cram: bool,
+    ) -> Result<(Deck, SessionService), SessionError> {
+        let deck = decks
+            .get_deck(deck_id)
+            .await?
+            .filter(|deck| !deck.is_archived())
+            .ok_or(SessionError::CardsUnavailable)?;
+        let mut unique = Vec::with_capacity(card_ids.len());
+        for id in card_ids {
+            if !unique.contains(id) {
+                unique.push(*id);
+            }
+        }
+        let selected = match cards.get_cards(deck_id, &unique).await {
+            Ok(selected) => selected,
+            Err(StorageError::NotFound) => return Err(SessionError::CardsUnavailable),
+            Err(err) => return Err(err.into()),
+        };
+        let session = if cram {
+            SessionService::new_cram(&deck, selected, now)?
+        } else {
+            SessionService::new_all(&deck, selected, now)?
+        };
+        Ok((deck, session))
+    }
+
+    /// Create a study-ahead session from reviewed cards due within `within_days`, soonest
+    /// first and capped at the micro-session size.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Empty` if no cards are due in the window, or
+    /// `SessionError::Storage` on repository failures.
+    pub async fn start_from_storage_ahead(
+        deck_id: DeckId,
+        decks: &dyn DeckRepository,
+        cards: &dyn CardRepository,
+        now: DateTime<Utc>,
+        within_days: u32,
+    ) -> Result<(Deck, SessionService), SessionError> {
+        let deck = decks
+            .get_deck(deck_id)
+            .await?
+            .ok_or(storage::repository::StorageError::NotFound)?;
+        if deck.is_archived() {
+            return Err(SessionError::Empty);
+        }
+        let until = now + chrono::Duration::days(i64::from(within_days));
+        let mut selected = cards.list_cards(deck_id, u32::MAX).await?;
+        selected.retain(|card| {
+            !card.is_new() && !card.is_suspended() && !card.is_buried_at(now) && card.is_due(until)
+        });
+        selected.sort_by_key(|card| (card.next_review_at(), card.id().value()));
+        let session = SessionService::new_ahead(&deck, selected, now)?;
+        Ok((deck, session))
+    }
+
     /// Create a session directly from storage and return the plan for UI summary.
     ///
     /// # Errors
@@ -216,10 +349,21 @@ impl SessionQueries {
         decks: &dyn DeckRepository,
         cards: &dyn CardRepository,
         now: DateTime<Utc>,
+        study_day: StudyDay,
         shuffle_new: bool,
+        new_introduced_today: u32,
     ) -> Result<(Deck, SessionPlan, SessionService), SessionError> {
         let (deck, plan) =
-            Self::build_plan_from_storage(deck_id, decks, cards, now, shuffle_new).await?;
+            Self::build_plan_from_storage(
+                deck_id,
+                decks,
+                cards,
+                now,
+                study_day,
+                shuffle_new,
+                new_introduced_today,
+            )
+            .await?;
         let session = SessionService::new(&deck, plan.cards.clone(), now)?;
         Ok((deck, plan, session))
     }
@@ -332,6 +476,22 @@ impl SessionQueries {
         let rows = summaries.list_latest_summary_rows(deck_ids).await?;
         Ok(rows)
     }
+
+    /// List summaries across every deck completed in `[completed_from, completed_until)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Storage` on repository failures.
+    pub async fn list_summaries_between(
+        summaries: &dyn SessionSummaryRepository,
+        completed_from: DateTime<Utc>,
+        completed_until: DateTime<Utc>,
+    ) -> Result<Vec<SessionSummary>, SessionError> {
+        let items = summaries
+            .list_summaries_between(completed_from, completed_until)
+            .await?;
+        Ok(items)
+    }
 }
 
 #[cfg(test)]
@@ -361,7 +521,15 @@ mod tests {
             .validate(fixed_now(), None, None)
             .unwrap();
         let now = fixed_now();
-        learn_core::model::Card::new(CardId::new(id), DeckId::new(1), prompt, answer, now, now)
+        learn_core::model::Card::new(
+            CardId::new(id),
+            DeckId::new(1),
+            learn_core::model::CardKind::Basic,
+            prompt,
+            answer,
+            now,
+            now,
+        )
             .unwrap()
     }
 
@@ -375,15 +543,56 @@ mod tests {
         repo.upsert_card(&card1).await.unwrap();
 
         let now = fixed_now();
-        let (loaded, session) =
-            SessionQueries::start_from_storage(deck.id(), &repo, &repo, now, false)
-                .await
-                .unwrap();
+        let (loaded, session) = SessionQueries::start_from_storage(
+            deck.id(),
+            &repo,
+            &repo,
+            now,
+            StudyDay::default(),
+            false,
+            0,
+        )
+        .await
+        .unwrap();
 
         assert_eq!(loaded.id(), deck.id());
         assert_eq!(session.total_cards(), 1);
     }
 
+    #[tokio::test]
+    async fn archived_deck_plans_no_cards() {
+        let repo = InMemoryRepository::new();
+        let deck = build_deck().with_archived(true);
+        repo.upsert_deck(&deck).await.unwrap();
+        repo.upsert_card(&build_card(1)).await.unwrap();
+
+        let (_, plan) = SessionQueries::build_plan_from_storage(
+            deck.id(),
+            &repo,
+            &repo,
+            fixed_now(),
+            StudyDay::default(),
+            false,
+            0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(plan.total(), 0);
+
+        let err = SessionQueries::start_from_storage(
+            deck.id(),
+            &repo,
+            &repo,
+            fixed_now(),
+            StudyDay::default(),
+            false,
+            0,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, SessionError::Empty));
+    }
+
     #[tokio::test]
     async fn start_from_storage_with_plan_returns_summary() {
         let repo = InMemoryRepository::new();
@@ -396,10 +605,17 @@ mod tests {
         repo.upsert_card(&card2).await.unwrap();
 
         let now = fixed_now();
-        let (loaded, plan, session) =
-            SessionQueries::start_from_storage_with_plan(deck.id(), &repo, &repo, now, false)
-                .await
-                .unwrap();
+        let (loaded, plan, session) = SessionQueries::start_from_storage_with_plan(
+            deck.id(),
+            &repo,
+            &repo,
+            now,
+            StudyDay::default(),
+            false,
+            0,
+        )
+        .await
+        .unwrap();
 
         assert_eq!(loaded.id(), deck.id());
         assert_eq!(plan.total(), session.total_cards());
@@ -425,7 +641,9 @@ mod tests {
             &repo,
             &repo,
             now,
+            StudyDay::default(),
             false,
+            0,
             &[tag],
         )
         .await
@@ -439,7 +657,9 @@ mod tests {
             &repo,
             &repo,
             now,
+            StudyDay::default(),
             false,
+            0,
             &[other_tag],
         )
         .await
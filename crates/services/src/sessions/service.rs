@@ -1,9 +1,14 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
 use std::fmt;
-use learn_core::model::{Card, CardId, Deck, DeckId, DeckSettings, ReviewGrade, SessionSummary};
+use learn_core::model::{
+    Card, CardId, CardPhase, Deck, DeckId, DeckSettings, ReviewGrade, SessionSummary,
+};
+use learn_core::time::StudyDay;
 
 use crate::error::SessionError;
 use crate::review_service::{ReviewResult, ReviewService};
+use super::plan::effective_daily_limits;
 use super::progress::SessionProgress;
 
 //
@@ -24,7 +29,9 @@ pub struct SessionReview {
 /// In-memory micro-session for a deck.
 ///
 /// Selects up to `micro_session_size` cards from the provided list and steps through
-/// them sequentially, applying grades via `ReviewService`.
+/// them sequentially, applying grades via `ReviewService`. In a cram session grades are
+/// only recorded for the summary; the cards' scheduling state is left as it was. A
+/// study-ahead session grades cards that are not due yet as usual.
 pub struct SessionService {
     deck_id: DeckId,
     deck_settings: DeckSettings,
@@ -32,11 +39,50 @@ pub struct SessionService {
     current: usize,
     results: Vec<SessionReview>,
     started_at: DateTime<Utc>,
+    /// When the current card was put in front of the learner.
+    shown_at: DateTime<Utc>,
     completed_at: Option<DateTime<Utc>>,
     summary_id: Option<i64>,
+    last_answer: Option<AnswerSnapshot>,
+    /// Whether the last answer queued the card again for a later learning step.
+    requeued_last: bool,
+    /// Agains in a row each card has had in this session, for the deck's fail limit.
+    fail_streaks: HashMap<CardId, u32>,
+    /// The answered card's streak before the last answer, so undo can put it back.
+    streak_before_last: u32,
+    /// Whether the last answer took its card out of the session for failing too often.
+    failed_out_last: bool,
+    /// Times each card has been skipped in this session, for `MAX_SKIPS`.
+    skips: HashMap<CardId, u32>,
+    cram: bool,
+    ahead: bool,
+    /// Where study days begin, for the daily target in `progress`.
+    study_day: StudyDay,
+}
+
+/// Card state captured before the most recent answer, so it can be rolled back.
+#[derive(Debug, Clone)]
+pub(crate) struct AnswerSnapshot {
+    pub card: Card,
+    /// Persisted review log for the answer; `None` when it was not persisted.
+    pub log_id: Option<i64>,
+    /// What the answer did to the card for reaching the deck's leech threshold.
+    pub leech: LeechMark,
+}
+
+/// Changes made to a card when an answer turned it into a leech.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct LeechMark {
+    /// The "leech" tag was added by this answer.
+    pub tagged: bool,
+    /// The card was suspended by this answer.
+    pub suspended: bool,
 }
 
 impl SessionService {
+    /// How many times one card may be skipped in a session before it has to be graded.
+    pub const MAX_SKIPS: u32 = 2;
+
     /// Create a new session for the given deck, selecting up to `micro_session_size` cards.
     ///
     /// `started_at` should come from the services layer clock to keep time deterministic.
@@ -56,6 +102,26 @@ impl SessionService {
         Self::new_with_limit(deck, cards, started_at, None)
     }
 
+    pub(crate) fn new_cram(
+        deck: &Deck,
+        cards: Vec<Card>,
+        started_at: DateTime<Utc>,
+    ) -> Result<Self, SessionError> {
+        let mut session = Self::new_with_limit(deck, cards, started_at, None)?;
+        session.cram = true;
+        Ok(session)
+    }
+
+    pub(crate) fn new_ahead(
+        deck: &Deck,
+        cards: Vec<Card>,
+        started_at: DateTime<Utc>,
+    ) -> Result<Self, SessionError> {
+        let mut session = Self::new(deck, cards, started_at)?;
+        session.ahead = true;
+        Ok(session)
+    }
+
     fn new_with_limit(
         deck: &Deck,
         mut cards: Vec<Card>,
@@ -78,8 +144,66 @@ impl SessionService {
             current: 0,
             results: Vec::new(),
             started_at,
+            shown_at: started_at,
             completed_at: None,
             summary_id: None,
+            last_answer: None,
+            requeued_last: false,
+            fail_streaks: HashMap::new(),
+            streak_before_last: 0,
+            failed_out_last: false,
+            skips: HashMap::new(),
+            cram: false,
+            ahead: false,
+            study_day: StudyDay::default(),
+        })
+    }
+
+    /// Rebuild a session saved before a restart.
+    ///
+    /// `answered` pairs each given answer with its card, oldest first; `pending` holds the
+    /// cards still to answer, in queue order. The restored session has nothing to undo.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Empty` if no cards are pending.
+    pub(crate) fn restore(
+        deck: &Deck,
+        answered: Vec<(Card, SessionReview)>,
+        pending: Vec<Card>,
+        started_at: DateTime<Utc>,
+        cram: bool,
+        ahead: bool,
+    ) -> Result<Self, SessionError> {
+        if pending.is_empty() {
+            return Err(SessionError::Empty);
+        }
+        let (mut cards, results): (Vec<_>, Vec<_>) = answered.into_iter().unzip();
+        let current = cards.len();
+        cards.extend(pending);
+        let mut fail_streaks = HashMap::new();
+        for review in &results {
+            track_fail_streak(&mut fail_streaks, review.card_id, review.result.applied.log.grade);
+        }
+        Ok(Self {
+            deck_id: deck.id(),
+            deck_settings: deck.settings().clone(),
+            cards,
+            current,
+            results,
+            started_at,
+            shown_at: started_at,
+            completed_at: None,
+            summary_id: None,
+            last_answer: None,
+            requeued_last: false,
+            fail_streaks,
+            streak_before_last: 0,
+            failed_out_last: false,
+            skips: HashMap::new(),
+            cram,
+            ahead,
+            study_day: StudyDay::default(),
         })
     }
 
@@ -108,6 +232,18 @@ impl SessionService {
         self.summary_id
     }
 
+    /// Whether grades in this session leave scheduling untouched.
+    #[must_use]
+    pub fn is_cram(&self) -> bool {
+        self.cram
+    }
+
+    /// Whether this session reviews cards before their due date.
+    #[must_use]
+    pub fn is_ahead(&self) -> bool {
+        self.ahead
+    }
+
     #[must_use]
     pub fn results(&self) -> &[SessionReview] {
         &self.results
@@ -132,12 +268,23 @@ impl SessionService {
     }
 
     /// Returns a summary of the current session progress.
+    ///
+    /// The daily target uses the limits for the day the session started.
     #[must_use]
     pub fn progress(&self) -> SessionProgress {
+        self.progress_at(self.started_at)
+    }
+
+    /// Like `progress`, with the daily target for the study day containing `now`.
+    #[must_use]
+    pub(crate) fn progress_at(&self, now: DateTime<Utc>) -> SessionProgress {
+        let (review_limit, new_limit) =
+            effective_daily_limits(&self.deck_settings, self.study_day.date_of(now));
         SessionProgress {
             total: self.total_cards(),
             answered: self.answered_count(),
             remaining: self.remaining(),
+            daily_target: review_limit.saturating_add(new_limit) as usize,
             is_complete: self.is_complete(),
         }
     }
@@ -179,20 +326,187 @@ impl SessionService {
         reviewed_at: DateTime<Utc>,
     ) -> Result<&SessionReview, SessionError> {
         let deck_settings = self.deck_settings.clone();
-        let (card_id, result) = {
+        let cram = self.cram;
+        let (before, result) = {
             let Some(card) = self.current_card_mut() else {
                 return Err(SessionError::Completed);
             };
+            let before = card.clone();
+            let mut graded = before.clone();
             let result = review_service.review_card_with_settings(
-                card,
+                if cram { &mut graded } else { card },
                 grade,
                 reviewed_at,
                 &deck_settings,
             )?;
-            (card.id(), result)
+            (before, result)
         };
 
-        self.record_review_result(card_id, result, reviewed_at)
+        let card_id = before.id();
+        self.record_review_result(card_id, result, reviewed_at)?;
+        self.last_answer = Some(AnswerSnapshot {
+            card: before,
+            log_id: None,
+            leech: LeechMark::default(),
+        });
+        self.results.last().ok_or(SessionError::Completed)
+    }
+
+    /// Whether the most recent answer took its card out of the session because it reached
+    /// the deck's `same_session_fail_limit` of Agains in a row.
+    #[must_use]
+    pub fn failed_out_last(&self) -> bool {
+        self.failed_out_last
+    }
+
+    /// Whether the most recent answer can still be undone.
+    #[must_use]
+    pub fn can_undo(&self) -> bool {
+        self.last_answer.is_some()
+    }
+
+    /// The card just answered with Again, while that answer is still the latest one.
+    ///
+    /// Gone after an undo and in sessions restored from a save.
+    #[must_use]
+    pub fn last_lapse(&self) -> Option<&Card> {
+        self.last_answer.as_ref()?;
+        let review = self.results.last()?;
+        if review.result.applied.log.grade != ReviewGrade::Again {
+            return None;
+        }
+        self.cards
+            .get(..self.current)?
+            .iter()
+            .rev()
+            .find(|card| card.id() == review.card_id)
+    }
+
+    pub(crate) fn last_answer(&self) -> Option<&AnswerSnapshot> {
+        self.last_answer.as_ref()
+    }
+
+    pub(crate) fn set_last_answer(&mut self, snapshot: AnswerSnapshot) {
+        self.last_answer = Some(snapshot);
+    }
+
+    /// Restore the card answered last and make it current again.
+    ///
+    /// Clears completion state and the summary ID, since the session is open again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::NothingToUndo` if there is no answer to undo, including
+    /// after an undo.
+    pub(crate) fn rollback_last_answer(&mut self) -> Result<SessionReview, SessionError> {
+        let snapshot = self.last_answer.take().ok_or(SessionError::NothingToUndo)?;
+        let review = self.results.pop().ok_or(SessionError::NothingToUndo)?;
+        // A skip since the answer may have put another card behind the requeued copy.
+        if std::mem::take(&mut self.requeued_last)
+            && let Some(index) = self.cards.iter().rposition(|card| card.id() == review.card_id)
+            && index >= self.current
+        {
+            self.cards.remove(index);
+        }
+        match std::mem::take(&mut self.streak_before_last) {
+            0 => self.fail_streaks.remove(&review.card_id),
+            streak => self.fail_streaks.insert(review.card_id, streak),
+        };
+        self.failed_out_last = false;
+        self.current = self.current.saturating_sub(1);
+        self.cards[self.current] = snapshot.card;
+        self.completed_at = None;
+        self.summary_id = None;
+        Ok(review)
+    }
+
+    /// Count the daily target against study days starting where `study_day` says.
+    pub(crate) fn set_study_day(&mut self, study_day: StudyDay) {
+        self.study_day = study_day;
+    }
+
+    /// Mark the current card as shown at `now`, restarting its answer timer.
+    pub(crate) fn set_shown_at(&mut self, now: DateTime<Utc>) {
+        self.shown_at = now;
+    }
+
+    /// Milliseconds from showing the current card to `reviewed_at`, capped at `max`.
+    pub(crate) fn answer_ms_at(&self, reviewed_at: DateTime<Utc>, max: Duration) -> u32 {
+        let elapsed = (reviewed_at - self.shown_at).clamp(Duration::zero(), max);
+        u32::try_from(elapsed.num_milliseconds()).unwrap_or(u32::MAX)
+    }
+
+    /// Cards still waiting to be answered, in queue order, starting with the current one.
+    #[must_use]
+    pub fn pending_cards(&self) -> &[Card] {
+        self.cards.get(self.current..).unwrap_or_default()
+    }
+
+    /// Whether `card_id` is still waiting to be answered in this session.
+    #[must_use]
+    pub fn is_pending(&self, card_id: CardId) -> bool {
+        self.pending_cards().iter().any(|card| card.id() == card_id)
+    }
+
+    /// Whether the current card may still be skipped.
+    #[must_use]
+    pub fn can_skip(&self) -> bool {
+        self.current_card().is_some_and(|card| {
+            self.skips.get(&card.id()).copied().unwrap_or(0) < Self::MAX_SKIPS
+        })
+    }
+
+    /// Move the current card to the back of the queue without grading it.
+    ///
+    /// Skipping the only card left shows it again, restarting its answer timer at `now`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Completed` if there is no current card, or
+    /// `SessionError::SkipLimitReached` once the card was skipped `MAX_SKIPS` times.
+    pub(crate) fn skip_current(&mut self, now: DateTime<Utc>) -> Result<(), SessionError> {
+        let card_id = self.current_card().ok_or(SessionError::Completed)?.id();
+        let skips = self.skips.entry(card_id).or_insert(0);
+        if *skips >= Self::MAX_SKIPS {
+            return Err(SessionError::SkipLimitReached);
+        }
+        *skips += 1;
+        let card = self.cards.remove(self.current);
+        self.cards.push(card);
+        self.failed_out_last = false;
+        self.shown_at = now;
+        Ok(())
+    }
+
+    /// Drop a card that has not been answered yet from the queue.
+    ///
+    /// Completes the session at `now` when it was the last card left.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Completed` if the session is already finished, or
+    /// `SessionError::CardNotQueued` if the card is not waiting in it.
+    pub(crate) fn remove_pending(
+        &mut self,
+        card_id: CardId,
+        now: DateTime<Utc>,
+    ) -> Result<Card, SessionError> {
+        if self.is_complete() {
+            return Err(SessionError::Completed);
+        }
+        let index = self.cards[self.current..]
+            .iter()
+            .position(|card| card.id() == card_id)
+            .ok_or(SessionError::CardNotQueued)?;
+        let card = self.cards.remove(self.current + index);
+        self.failed_out_last = false;
+        if index == 0 {
+            self.shown_at = now;
+        }
+        if self.current >= self.cards.len() {
+            self.completed_at = Some(now);
+        }
+        Ok(card)
     }
 
     pub(crate) fn record_review_result(
@@ -205,9 +519,28 @@ impl SessionService {
             return Err(SessionError::Completed);
         }
 
+        let grade = result.applied.log.grade;
         self.results.push(SessionReview { card_id, result });
 
+        self.streak_before_last = self.fail_streaks.get(&card_id).copied().unwrap_or(0);
+        let streak = track_fail_streak(&mut self.fail_streaks, card_id, grade);
+        self.requeued_last = false;
+        self.failed_out_last = false;
+        // The fail limit counts whether or not the card was coming back, so a lapse
+        // without relearning steps still fails out.
+        if !self.cram && self.deck_settings.fails_out_of_session(streak) {
+            self.failed_out_last = true;
+        } else if let Some(card) = self
+            .cards
+            .get(self.current)
+            .filter(|card| self.requeues(card, reviewed_at))
+        {
+            let again = card.clone();
+            self.cards.push(again);
+            self.requeued_last = true;
+        }
         self.current += 1;
+        self.shown_at = reviewed_at;
         if self.current >= self.cards.len() {
             self.completed_at = Some(reviewed_at);
         }
@@ -215,6 +548,19 @@ impl SessionService {
         self.results.last().ok_or(SessionError::Completed)
     }
 
+    /// Whether an answered card is due again for a learning or relearning step later today.
+    ///
+    /// Such cards go to the back of the queue, so they come back once the cards
+    /// ahead of them are done.
+    fn requeues(&self, card: &Card, reviewed_at: DateTime<Utc>) -> bool {
+        let steps = match card.phase() {
+            CardPhase::Learning => self.deck_settings.learning_steps_secs(),
+            CardPhase::Relearning => self.deck_settings.relearning_steps_secs(),
+            CardPhase::New | CardPhase::Reviewing => return false,
+        };
+        !self.cram && !steps.is_empty() && card.next_review_at() - reviewed_at < Duration::days(1)
+    }
+
     pub(crate) fn build_summary(
         &self,
         completed_at: DateTime<Utc>,
@@ -229,7 +575,9 @@ impl SessionService {
             self.started_at,
             completed_at,
             &logs,
-        )?)
+        )?
+        .with_cram(self.cram)
+        .with_ahead(self.ahead))
     }
 
     pub(crate) fn set_summary_id(&mut self, id: i64) {
@@ -247,10 +595,29 @@ impl fmt::Debug for SessionService {
             .field("started_at", &self.started_at)
             .field("completed_at", &self.completed_at)
             .field("summary_id", &self.summary_id)
+            .field("can_undo", &self.can_undo())
+            .field("cram", &self.cram)
+            .field("ahead", &self.ahead)
             .finish_non_exhaustive()
     }
 }
 
+/// Count `grade` toward `card_id`'s run of Agains, returning the run's new length.
+fn track_fail_streak(
+    streaks: &mut HashMap<CardId, u32>,
+    card_id: CardId,
+    grade: ReviewGrade,
+) -> u32 {
+    if grade == ReviewGrade::Again {
+        let streak = streaks.entry(card_id).or_insert(0);
+        *streak += 1;
+        *streak
+    } else {
+        streaks.remove(&card_id);
+        0
+    }
+}
+
 //
 // ─── TESTS ─────────────────────────────────────────────────────────────────────
 //
@@ -259,7 +626,7 @@ impl fmt::Debug for SessionService {
 mod tests {
     use super::*;
     use learn_core::Clock;
-    use learn_core::model::{CardPhase, DeckId, content::ContentDraft};
+    use learn_core::model::{CardKind, CardPhase, DeckId, content::ContentDraft};
     use learn_core::time::fixed_now;
 
     fn build_card(id: u64) -> Card {
@@ -270,18 +637,51 @@ mod tests {
             .validate(fixed_now(), None, None)
             .unwrap();
         let now = fixed_now();
-        Card::new(CardId::new(id), DeckId::new(1), prompt, answer, now, now).unwrap()
+        Card::new(
+            CardId::new(id),
+            DeckId::new(1),
+            CardKind::Basic,
+            prompt,
+            answer,
+            now,
+            now,
+        )
+        .unwrap()
+    }
+
+    fn build_reviewing_card(id: u64) -> Card {
+        let card = build_card(id);
+        let now = fixed_now();
+        Card::from_persisted(
+            card.id(),
+            card.deck_id(),
+            CardKind::Basic,
+            card.prompt().clone(),
+            card.answer().clone(),
+            now,
+            now,
+            Some(now),
+            CardPhase::Reviewing,
+            3,
+            5.0,
+            5.0,
+        )
+        .unwrap()
     }
 
     fn build_deck() -> Deck {
-        Deck::new(
-            DeckId::new(1),
-            "Test",
-            None,
-            learn_core::model::DeckSettings::default_for_adhd(),
-            fixed_now(),
+        build_deck_with_steps(Vec::new())
+    }
+
+    fn build_deck_with_steps(learning_steps_secs: Vec<u32>) -> Deck {
+        let settings = DeckSettings::new(
+            5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5,
+            0, 0.85, true, 100,
         )
         .unwrap()
+        .with_learning_steps(learning_steps_secs)
+        .unwrap();
+        Deck::new(DeckId::new(1), "Test", None, settings, fixed_now()).unwrap()
     }
 
     #[test]
@@ -328,6 +728,37 @@ mod tests {
         assert_eq!(session.completed_at(), Some(fixed_now()));
     }
 
+    #[test]
+    fn rollback_restores_last_card_once() {
+        let deck = build_deck();
+        let mut session =
+            SessionService::new(&deck, vec![build_card(1), build_card(2)], fixed_now()).unwrap();
+        let review_service = ReviewService::new()
+            .unwrap()
+            .with_clock(Clock::fixed(fixed_now()));
+
+        session
+            .answer_current(&review_service, ReviewGrade::Good, fixed_now())
+            .unwrap();
+        session
+            .answer_current(&review_service, ReviewGrade::Again, fixed_now())
+            .unwrap();
+        assert!(session.is_complete());
+
+        let undone = session.rollback_last_answer().unwrap();
+        assert_eq!(undone.card_id, CardId::new(2));
+        assert!(!session.is_complete());
+        assert_eq!(session.answered_count(), 1);
+        let current = session.current_card().unwrap();
+        assert_eq!(current.id(), CardId::new(2));
+        assert_eq!(current.review_count(), 0);
+        assert!(!session.can_undo());
+        assert!(matches!(
+            session.rollback_last_answer(),
+            Err(SessionError::NothingToUndo)
+        ));
+    }
+
     #[test]
     fn integration_session_runs_with_review_logs_and_phase_updates() {
         let deck = build_deck();
@@ -358,4 +789,169 @@ mod tests {
         assert!(session.is_complete());
         assert_eq!(session.results.len(), 2);
     }
+
+    #[test]
+    fn learning_cards_come_back_until_they_graduate() {
+        let deck = build_deck_with_steps(vec![60, 600]);
+        let mut session =
+            SessionService::new(&deck, vec![build_card(1), build_card(2)], fixed_now()).unwrap();
+        let review_service = ReviewService::new()
+            .unwrap()
+            .with_clock(Clock::fixed(fixed_now()));
+
+        let grades = [
+            (1, ReviewGrade::Good),
+            (2, ReviewGrade::Easy),
+            (1, ReviewGrade::Again),
+            (1, ReviewGrade::Good),
+            (1, ReviewGrade::Good),
+        ];
+        for (id, grade) in grades {
+            assert_eq!(session.current_card().unwrap().id(), CardId::new(id));
+            session
+                .answer_current(&review_service, grade, fixed_now())
+                .unwrap();
+        }
+
+        assert!(session.is_complete());
+        assert_eq!(session.answered_count(), 5);
+        let last = session.cards.last().unwrap();
+        assert_eq!(last.phase(), CardPhase::Reviewing);
+        assert!(last.next_review_at() >= fixed_now() + chrono::Duration::days(1));
+    }
+
+    #[test]
+    fn lapsed_review_cards_come_back_for_their_relearning_step() {
+        let deck = build_deck();
+        assert_eq!(deck.settings().relearning_steps_secs(), &[600]);
+        let mut session = SessionService::new(
+            &deck,
+            vec![build_reviewing_card(1), build_card(2)],
+            fixed_now(),
+        )
+        .unwrap();
+        let review_service = ReviewService::new()
+            .unwrap()
+            .with_clock(Clock::fixed(fixed_now()));
+
+        let grades = [
+            (1, ReviewGrade::Again),
+            (2, ReviewGrade::Good),
+            (1, ReviewGrade::Good),
+        ];
+        for (id, grade) in grades {
+            assert_eq!(session.current_card().unwrap().id(), CardId::new(id));
+            session
+                .answer_current(&review_service, grade, fixed_now())
+                .unwrap();
+        }
+
+        assert!(session.is_complete());
+        let last = session.cards.last().unwrap();
+        assert_eq!(last.phase(), CardPhase::Reviewing);
+        assert_eq!(last.lapses(), 1);
+    }
+
+    #[test]
+    fn lapsed_review_cards_stay_out_without_relearning_steps() {
+        let deck = build_deck();
+        let settings = deck.settings().clone().with_relearning_steps(Vec::new()).unwrap();
+        let deck = deck.with_settings(settings);
+        let mut session =
+            SessionService::new(&deck, vec![build_reviewing_card(1)], fixed_now()).unwrap();
+        let review_service = ReviewService::new()
+            .unwrap()
+            .with_clock(Clock::fixed(fixed_now()));
+
+        session
+            .answer_current(&review_service, ReviewGrade::Again, fixed_now())
+            .unwrap();
+        assert!(session.is_complete());
+    }
+
+    #[test]
+    fn lapsed_review_cards_fail_out_at_the_limit_without_requeueing() {
+        let deck = build_deck();
+        let settings = deck
+            .settings()
+            .clone()
+            .with_relearning_steps(Vec::new())
+            .unwrap()
+            .with_same_session_fail_limit(1);
+        let deck = deck.with_settings(settings);
+        let mut session =
+            SessionService::new(&deck, vec![build_reviewing_card(1)], fixed_now()).unwrap();
+        let review_service = ReviewService::new()
+            .unwrap()
+            .with_clock(Clock::fixed(fixed_now()));
+
+        session
+            .answer_current(&review_service, ReviewGrade::Again, fixed_now())
+            .unwrap();
+        assert!(session.failed_out_last());
+        assert_eq!(session.total_cards(), 1);
+        assert!(session.is_complete());
+    }
+
+    #[test]
+    fn rollback_drops_requeued_learning_card() {
+        let deck = build_deck_with_steps(vec![60, 600]);
+        let mut session = SessionService::new(&deck, vec![build_card(1)], fixed_now()).unwrap();
+        let review_service = ReviewService::new()
+            .unwrap()
+            .with_clock(Clock::fixed(fixed_now()));
+
+        session
+            .answer_current(&review_service, ReviewGrade::Again, fixed_now())
+            .unwrap();
+        assert_eq!(session.total_cards(), 2);
+        assert!(!session.is_complete());
+
+        session.rollback_last_answer().unwrap();
+        assert_eq!(session.total_cards(), 1);
+        assert_eq!(session.current_card().unwrap().phase(), CardPhase::New);
+    }
+
+    #[test]
+    fn cram_sessions_do_not_requeue_learning_cards() {
+        let deck = build_deck_with_steps(vec![60, 600]);
+        let mut session = SessionService::new_cram(&deck, vec![build_card(1)], fixed_now()).unwrap();
+        let review_service = ReviewService::new()
+            .unwrap()
+            .with_clock(Clock::fixed(fixed_now()));
+
+        session
+            .answer_current(&review_service, ReviewGrade::Again, fixed_now())
+            .unwrap();
+        assert!(session.is_complete());
+    }
+
+    #[test]
+    fn rollback_restores_the_fail_streak_of_a_failed_out_card() {
+        let deck = build_deck_with_steps(vec![60, 600]);
+        let settings = deck.settings().clone().with_same_session_fail_limit(2);
+        let deck = deck.with_settings(settings);
+        let mut session = SessionService::new(&deck, vec![build_card(1)], fixed_now()).unwrap();
+        let review_service = ReviewService::new()
+            .unwrap()
+            .with_clock(Clock::fixed(fixed_now()));
+
+        session
+            .answer_current(&review_service, ReviewGrade::Again, fixed_now())
+            .unwrap();
+        assert!(!session.failed_out_last());
+        session
+            .answer_current(&review_service, ReviewGrade::Again, fixed_now())
+            .unwrap();
+        assert!(session.failed_out_last());
+        assert!(session.is_complete());
+
+        session.rollback_last_answer().unwrap();
+        assert!(!session.failed_out_last());
+        session
+            .answer_current(&review_service, ReviewGrade::Again, fixed_now())
+            .unwrap();
+        assert!(session.failed_out_last());
+        assert_eq!(session.total_cards(), 2);
+    }
 }
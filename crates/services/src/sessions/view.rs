@@ -1,7 +1,9 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, Utc};
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 
 use learn_core::model::{DeckId, SessionSummary};
+use learn_core::time::StudyDay;
 use storage::repository::SessionSummaryRepository;
 
 use crate::Clock;
@@ -22,6 +24,7 @@ pub type SessionSummaryId = i64;
 /// The UI may format timestamps (e.g., relative time, locale) as needed.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SessionSummaryListItem {
+    /// Never changes for a stored summary, so lists can key rows by it.
     pub id: SessionSummaryId,
     pub completed_at: DateTime<Utc>,
 
@@ -30,6 +33,10 @@ pub struct SessionSummaryListItem {
     pub hard: u32,
     pub good: u32,
     pub easy: u32,
+    /// The session was a cram session and did not change scheduling.
+    pub is_cram: bool,
+    /// The session reviewed cards ahead of their due date.
+    pub is_ahead: bool,
 }
 
 /// Latest summary per deck, preserving deck identifiers.
@@ -44,6 +51,10 @@ pub struct SessionSummaryDeckItem {
     pub hard: u32,
     pub good: u32,
     pub easy: u32,
+    /// The session was a cram session and did not change scheduling.
+    pub is_cram: bool,
+    /// The session reviewed cards ahead of their due date.
+    pub is_ahead: bool,
 }
 
 impl SessionSummaryDeckItem {
@@ -59,6 +70,8 @@ impl SessionSummaryDeckItem {
             hard: summary.hard(),
             good: summary.good(),
             easy: summary.easy(),
+            is_cram: summary.is_cram(),
+            is_ahead: summary.is_ahead(),
         }
     }
 }
@@ -74,10 +87,121 @@ impl SessionSummaryListItem {
             hard: summary.hard(),
             good: summary.good(),
             easy: summary.easy(),
+            is_cram: summary.is_cram(),
+            is_ahead: summary.is_ahead(),
         }
     }
 }
 
+/// Time window for `SessionSummaryService::deck_stats` and `activity_by_day`, ending now.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StatsRange {
+    Last7Days,
+    Last30Days,
+    #[default]
+    AllTime,
+}
+
+impl StatsRange {
+    fn day_count(self) -> Option<i64> {
+        match self {
+            Self::Last7Days => Some(7),
+            Self::Last30Days => Some(30),
+            Self::AllTime => None,
+        }
+    }
+
+    fn completed_from(self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.day_count().map(|days| now - chrono::Duration::days(days))
+    }
+}
+
+/// Aggregate study statistics for one deck over a `StatsRange`.
+///
+/// Study days are the `StudyDay`s sessions completed in; cram sessions count as study.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeckStats {
+    pub total_reviews: u32,
+    /// Share of reviews graded Hard, Good, or Easy; `None` without reviews.
+    pub retention_rate: Option<f64>,
+    /// Session time divided by reviews; `None` without reviews.
+    pub average_answer_time: Option<chrono::Duration>,
+    /// Consecutive study days ending today, or yesterday if today has no session yet.
+    pub current_streak_days: u32,
+    pub longest_streak_days: u32,
+}
+
+impl DeckStats {
+    fn from_summaries<'a>(
+        summaries: impl IntoIterator<Item = &'a SessionSummary>,
+        study_day: StudyDay,
+        today: NaiveDate,
+    ) -> Self {
+        let mut total_reviews = 0_u32;
+        let mut correct = 0_u32;
+        let mut study_time = chrono::Duration::zero();
+        let mut days = BTreeSet::new();
+
+        for summary in summaries {
+            total_reviews = total_reviews.saturating_add(summary.total_reviews());
+            correct = correct
+                .saturating_add(summary.hard())
+                .saturating_add(summary.good())
+                .saturating_add(summary.easy());
+            study_time += summary.completed_at() - summary.started_at();
+            days.insert(study_day.date_of(summary.completed_at()));
+        }
+
+        let (retention_rate, average_answer_time) = if total_reviews == 0 {
+            (None, None)
+        } else {
+            (
+                Some(f64::from(correct) / f64::from(total_reviews)),
+                Some(study_time / i32::try_from(total_reviews).unwrap_or(i32::MAX)),
+            )
+        };
+        let (current_streak_days, longest_streak_days) = streaks(&days, today);
+
+        Self {
+            total_reviews,
+            retention_rate,
+            average_answer_time,
+            current_streak_days,
+            longest_streak_days,
+        }
+    }
+}
+
+/// Current and longest runs of consecutive days in `days`.
+fn streaks(days: &BTreeSet<NaiveDate>, today: NaiveDate) -> (u32, u32) {
+    let mut longest = 0_u32;
+    let mut run = 0_u32;
+    let mut previous: Option<NaiveDate> = None;
+    for &day in days {
+        run = match previous {
+            Some(prev) if prev.succ_opt() == Some(day) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+
+    let mut cursor = if days.contains(&today) {
+        Some(today)
+    } else {
+        today.pred_opt()
+    };
+    let mut current = 0_u32;
+    while let Some(day) = cursor
+        && days.contains(&day)
+    {
+        current += 1;
+        cursor = day.pred_opt();
+    }
+    (current, longest)
+}
+
 /// Presentation-facing session summary facade that hides repositories and time from the UI.
 ///
 /// This service owns:
@@ -88,13 +212,18 @@ impl SessionSummaryListItem {
 #[derive(Clone)]
 pub struct SessionSummaryService {
     clock: Clock,
+    study_day: StudyDay,
     summaries: Arc<dyn SessionSummaryRepository>,
 }
 
 impl SessionSummaryService {
     #[must_use]
     pub fn new(clock: Clock, summaries: Arc<dyn SessionSummaryRepository>) -> Self {
-        Self { clock, summaries }
+        Self {
+            clock,
+            study_day: StudyDay::default(),
+            summaries,
+        }
     }
 
     #[must_use]
@@ -105,12 +234,31 @@ impl SessionSummaryService {
         )
     }
 
+    /// Use a fixed offset for local calendar days instead of UTC.
+    #[must_use]
+    pub fn with_utc_offset(mut self, offset: FixedOffset) -> Self {
+        self.study_day = self.study_day.with_utc_offset(offset);
+        self
+    }
+
+    /// Count streaks and activity in study days, which start at `study_day`'s cutoff.
+    ///
+    /// This replaces any offset set with `with_utc_offset`.
+    #[must_use]
+    pub fn with_study_day(mut self, study_day: StudyDay) -> Self {
+        self.study_day = study_day;
+        self
+    }
+
     #[must_use]
     pub fn now(&self) -> DateTime<Utc> {
         self.clock.now()
     }
 
-    /// Load recent summaries for a deck.
+    /// Load recent summaries for a deck, newest first.
+    ///
+    /// Summaries completed at the same instant are ordered by descending `id`, so
+    /// repeated loads list them identically.
     ///
     /// # Errors
     ///
@@ -131,10 +279,12 @@ impl SessionSummaryService {
         )
         .await?;
 
-        Ok(rows
+        let mut items: Vec<SessionSummaryListItem> = rows
             .iter()
             .map(|row| SessionSummaryListItem::from_summary(row.id, &row.summary))
-            .collect())
+            .collect();
+        items.sort_by_key(|item| std::cmp::Reverse((item.completed_at, item.id)));
+        Ok(items)
     }
 
     /// Load the latest summary per deck.
@@ -151,6 +301,80 @@ impl SessionSummaryService {
         Ok(rows.iter().map(SessionSummaryDeckItem::from_row).collect())
     }
 
+    /// Compute review totals, retention, pace, and study streaks for a deck.
+    ///
+    /// Streaks only see sessions inside `range`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Storage` on repository failures.
+    pub async fn deck_stats(
+        &self,
+        deck_id: DeckId,
+        range: StatsRange,
+    ) -> Result<DeckStats, SessionError> {
+        let now = self.clock.now();
+        let rows = SessionQueries::list_summary_rows(
+            deck_id,
+            self.summaries.as_ref(),
+            range.completed_from(now),
+            Some(now),
+            u32::MAX,
+        )
+        .await?;
+        Ok(DeckStats::from_summaries(
+            rows.iter().map(|row| &row.summary),
+            self.study_day,
+            self.local_date(now),
+        ))
+    }
+
+    /// Count reviews per study day across every deck, for an activity heatmap.
+    ///
+    /// Days run through today; `Last7Days` and `Last30Days` cover that many days including
+    /// today, and `AllTime` starts at the first studied day. Every day in the range has an
+    /// entry, zero when nothing was studied, and a session counts on the day it completed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Storage` on repository failures.
+    pub async fn activity_by_day(
+        &self,
+        range: StatsRange,
+    ) -> Result<BTreeMap<NaiveDate, u32>, SessionError> {
+        let today = self.local_date(self.clock.now());
+        let first_day = range
+            .day_count()
+            .map(|days| today - chrono::Duration::days(days - 1));
+        // Study days sit within a day of the matching UTC day, so pad the query by one.
+        let utc_midnight = |day: NaiveDate| day.and_time(NaiveTime::MIN).and_utc();
+        let completed_from = first_day.map_or(DateTime::UNIX_EPOCH, |day| {
+            utc_midnight(day) - chrono::Duration::days(1)
+        });
+        let completed_until = utc_midnight(today) + chrono::Duration::days(2);
+        let summaries = SessionQueries::list_summaries_between(
+            self.summaries.as_ref(),
+            completed_from,
+            completed_until,
+        )
+        .await?;
+
+        let mut activity = BTreeMap::new();
+        for summary in &summaries {
+            let day = self.local_date(summary.completed_at());
+            if first_day.is_none_or(|first| day >= first) && day <= today {
+                let reviews = activity.entry(day).or_insert(0_u32);
+                *reviews = reviews.saturating_add(summary.total_reviews());
+            }
+        }
+        if let Some(first) = first_day.or_else(|| activity.keys().next().copied()) {
+            for day in first.iter_days().take_while(|day| *day <= today) {
+                activity.entry(day).or_insert(0);
+            }
+        }
+        Ok(activity)
+    }
+
     /// Fetch a session summary by ID.
     ///
     /// # Errors
@@ -159,6 +383,10 @@ impl SessionSummaryService {
     pub async fn get_summary(&self, id: SessionSummaryId) -> Result<SessionSummary, SessionError> {
         SessionQueries::get_summary(id, self.summaries.as_ref()).await
     }
+
+    fn local_date(&self, at: DateTime<Utc>) -> NaiveDate {
+        self.study_day.date_of(at)
+    }
 }
 
 #[cfg(test)]
@@ -216,6 +444,33 @@ mod tests {
         assert_eq!(items[0].total, 1);
     }
 
+    #[tokio::test]
+    async fn list_recent_summaries_orders_same_second_sessions_by_id() {
+        let repo = InMemoryRepository::new();
+        let deck_id = DeckId::new(1);
+        let now = fixed_now();
+        let logs = vec![ReviewLog::new(CardId::new(1), ReviewGrade::Good, now)];
+        let tied_at = now - chrono::Duration::hours(1);
+        let tied = SessionSummary::from_logs(deck_id, tied_at, tied_at, &logs).unwrap();
+        let newest = SessionSummary::from_logs(deck_id, now, now, &logs).unwrap();
+
+        let first_tied = repo.append_summary(&tied).await.unwrap();
+        let newest_id = repo.append_summary(&newest).await.unwrap();
+        let second_tied = repo.append_summary(&tied).await.unwrap();
+
+        let svc = SessionSummaryService::new(Clock::Fixed(now), Arc::new(repo));
+        for _ in 0..3 {
+            let ids: Vec<SessionSummaryId> = svc
+                .list_recent_summaries(deck_id, 7, 10)
+                .await
+                .unwrap()
+                .iter()
+                .map(|item| item.id)
+                .collect();
+            assert_eq!(ids, vec![newest_id, second_tied, first_tied]);
+        }
+    }
+
     #[tokio::test]
     async fn list_latest_summaries_by_deck_returns_latest_for_each_deck() {
         let repo = InMemoryRepository::new();
@@ -250,4 +505,152 @@ mod tests {
         assert_eq!(by_deck.get(&deck_b), Some(&id_b));
         assert_ne!(by_deck.get(&deck_a), Some(&id_a1));
     }
+
+    fn summary_days_ago(
+        deck_id: DeckId,
+        now: DateTime<Utc>,
+        days: i64,
+        grades: &[ReviewGrade],
+    ) -> SessionSummary {
+        let completed = now - chrono::Duration::days(days);
+        let started = completed - chrono::Duration::seconds(30 * grades.len() as i64);
+        let logs: Vec<_> = grades
+            .iter()
+            .map(|grade| ReviewLog::new(CardId::new(1), *grade, completed))
+            .collect();
+        SessionSummary::from_logs(deck_id, started, completed, &logs).unwrap()
+    }
+
+    #[tokio::test]
+    async fn deck_stats_aggregates_and_detects_streak_breaks() {
+        let repo = InMemoryRepository::new();
+        let deck_id = DeckId::new(1);
+        let now = fixed_now();
+        // Study days: today..2 ago, 5..8 ago (with two sessions 6 ago), and 12 ago.
+        for days in [0, 1, 2, 5, 6, 6, 7, 8, 12] {
+            let summary =
+                summary_days_ago(deck_id, now, days, &[ReviewGrade::Good, ReviewGrade::Again]);
+            repo.append_summary(&summary).await.unwrap();
+        }
+        let other = summary_days_ago(DeckId::new(2), now, 3, &[ReviewGrade::Good]);
+        repo.append_summary(&other).await.unwrap();
+
+        let svc = SessionSummaryService::new(Clock::Fixed(now), Arc::new(repo));
+        let stats = svc.deck_stats(deck_id, StatsRange::AllTime).await.unwrap();
+
+        assert_eq!(stats.total_reviews, 18);
+        assert_eq!(stats.retention_rate, Some(0.5));
+        assert_eq!(stats.average_answer_time, Some(chrono::Duration::seconds(30)));
+        assert_eq!(stats.current_streak_days, 3);
+        assert_eq!(stats.longest_streak_days, 4);
+
+        let week = svc.deck_stats(deck_id, StatsRange::Last7Days).await.unwrap();
+        assert_eq!(week.total_reviews, 14);
+        assert_eq!(week.current_streak_days, 3);
+        assert_eq!(week.longest_streak_days, 3);
+    }
+
+    #[tokio::test]
+    async fn deck_stats_current_streak_survives_until_a_day_is_missed() {
+        let repo = InMemoryRepository::new();
+        let deck_id = DeckId::new(1);
+        let now = fixed_now();
+        for days in [1, 2] {
+            let summary = summary_days_ago(deck_id, now, days, &[ReviewGrade::Easy]);
+            repo.append_summary(&summary).await.unwrap();
+        }
+        let repo = Arc::new(repo);
+
+        let svc = SessionSummaryService::new(Clock::Fixed(now), repo.clone());
+        let stats = svc.deck_stats(deck_id, StatsRange::Last30Days).await.unwrap();
+        assert_eq!(stats.current_streak_days, 2);
+        assert_eq!(stats.retention_rate, Some(1.0));
+
+        let later = now + chrono::Duration::days(1);
+        let svc = SessionSummaryService::new(Clock::Fixed(later), repo);
+        let stats = svc.deck_stats(deck_id, StatsRange::Last30Days).await.unwrap();
+        assert_eq!(stats.current_streak_days, 0);
+        assert_eq!(stats.longest_streak_days, 2);
+
+        let empty = svc.deck_stats(DeckId::new(9), StatsRange::AllTime).await.unwrap();
+        assert_eq!(empty.total_reviews, 0);
+        assert_eq!(empty.retention_rate, None);
+        assert_eq!(empty.average_answer_time, None);
+        assert_eq!(empty.longest_streak_days, 0);
+    }
+
+    #[tokio::test]
+    async fn deck_stats_counts_a_review_before_the_cutoff_toward_the_previous_day() {
+        let repo = InMemoryRepository::new();
+        let deck_id = DeckId::new(1);
+        let at = |rfc3339: &str| DateTime::parse_from_rfc3339(rfc3339).unwrap().to_utc();
+        for completed in ["2024-03-05T20:00:00Z", "2024-03-07T02:00:00Z"] {
+            let completed = at(completed);
+            let logs = [ReviewLog::new(CardId::new(1), ReviewGrade::Good, completed)];
+            let summary = SessionSummary::from_logs(deck_id, completed, completed, &logs).unwrap();
+            repo.append_summary(&summary).await.unwrap();
+        }
+        let repo = Arc::new(repo);
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let stats = |cutoff_hour| {
+            SessionSummaryService::new(Clock::Fixed(at("2024-03-07T10:00:00Z")), repo.clone())
+                .with_study_day(StudyDay::new(cutoff_hour).with_utc_offset(utc))
+        };
+
+        // At midnight the 2am review starts a new streak on the 7th.
+        let midnight = stats(0).deck_stats(deck_id, StatsRange::AllTime).await.unwrap();
+        assert_eq!((midnight.current_streak_days, midnight.longest_streak_days), (1, 1));
+
+        // With a 4am cutoff it belongs to the 6th, joining the 5th into a two-day streak.
+        let cutoff = stats(4).deck_stats(deck_id, StatsRange::AllTime).await.unwrap();
+        assert_eq!((cutoff.current_streak_days, cutoff.longest_streak_days), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn activity_by_day_sums_local_days_across_a_month_boundary() {
+        let repo = InMemoryRepository::new();
+        let at = |rfc3339: &str| DateTime::parse_from_rfc3339(rfc3339).unwrap().to_utc();
+        let session = |deck: u64, completed: &str, reviews: usize| {
+            let completed = at(completed);
+            let logs = vec![ReviewLog::new(CardId::new(1), ReviewGrade::Good, completed); reviews];
+            SessionSummary::from_logs(DeckId::new(deck), completed, completed, &logs).unwrap()
+        };
+        for summary in [
+            session(1, "2024-02-20T12:00:00Z", 4),
+            session(1, "2024-02-28T08:00:00Z", 2),
+            session(1, "2024-02-28T20:00:00Z", 3),
+            // 01:30 on March 1st at UTC+2.
+            session(1, "2024-02-29T23:30:00Z", 1),
+            session(2, "2024-03-01T09:00:00Z", 2),
+            session(1, "2024-03-02T23:00:00Z", 5),
+        ] {
+            repo.append_summary(&summary).await.unwrap();
+        }
+
+        let svc = SessionSummaryService::new(
+            Clock::Fixed(at("2024-03-02T10:00:00Z")),
+            Arc::new(repo),
+        )
+        .with_utc_offset(FixedOffset::east_opt(2 * 3600).unwrap());
+        let week = svc.activity_by_day(StatsRange::Last7Days).await.unwrap();
+
+        let day = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        let expected: BTreeMap<_, _> = [
+            (day(2, 25), 0),
+            (day(2, 26), 0),
+            (day(2, 27), 0),
+            (day(2, 28), 5),
+            (day(2, 29), 0),
+            (day(3, 1), 3),
+            (day(3, 2), 0),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(week, expected);
+
+        let all = svc.activity_by_day(StatsRange::AllTime).await.unwrap();
+        assert_eq!(all.len(), 12);
+        assert_eq!(all.first_key_value(), Some((&day(2, 20), &4)));
+        assert_eq!(all.values().sum::<u32>(), 12);
+    }
 }
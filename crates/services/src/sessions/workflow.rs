@@ -1,16 +1,26 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use chrono::{DateTime, Utc};
-use learn_core::model::{DeckId, ReviewGrade, TagName};
+use chrono::{DateTime, Duration, Utc};
+use learn_core::model::{
+    Card, CardId, CardPhase, DeckId, DeckSettings, Flag, LeechAction, ReviewGrade, ReviewLog,
+    ReviewOutcome, TagName,
+};
+use learn_core::scheduler::{AppliedReview, MemoryState};
+use learn_core::time::StudyDay;
 use storage::repository::{
-    CardRepository, DeckRepository, ReviewPersistence, SessionSummaryRepository,
+    CardRepository, DeckRepository, PendingSessionCard, ReviewLogRecord, ReviewLogRepository,
+    ReviewPersistence, SessionProgressRecord, SessionProgressRepository, SessionSummaryRepository,
+    StorageError,
 };
 
-use crate::review_service::ReviewService;
+use crate::review_service::{ReviewGradePreview, ReviewResult, ReviewService};
 use crate::Clock;
+use super::plan::CramFilter;
 use super::queries::SessionQueries;
 use crate::error::SessionError;
-use super::service::{SessionReview, SessionService};
+use super::progress::SessionProgress;
+use super::service::{AnswerSnapshot, LeechMark, SessionReview, SessionService};
 
 /// Result of answering a single card in a session.
 #[derive(Debug, Clone, PartialEq)]
@@ -18,6 +28,9 @@ pub struct SessionAnswerResult {
     pub review: SessionReview,
     pub is_complete: bool,
     pub summary_id: Option<i64>,
+    /// The card reached the deck's `same_session_fail_limit` and was taken out of the
+    /// session instead of coming back; it is tagged as a leech.
+    pub failed_out: bool,
 }
 
 /// Preview of next-review scheduling for each grade.
@@ -27,11 +40,62 @@ pub struct SessionReviewPreview {
     pub grades: Vec<ReviewGradePreview>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct ReviewGradePreview {
-    pub grade: ReviewGrade,
-    pub next_review: DateTime<Utc>,
-    pub scheduled_days: f64,
+/// What `SessionLoopService::start_or_nothing_due` found.
+#[derive(Debug)]
+pub enum SessionStart {
+    Started(Box<SessionService>),
+    /// No card is due for a session right now.
+    NothingDue(NothingDue),
+}
+
+/// An empty due session, with when the next card comes due.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NothingDue {
+    pub checked_at: DateTime<Utc>,
+    /// `None` when the deck has no card left to schedule, e.g. when it is empty or archived.
+    pub next_due_at: Option<DateTime<Utc>>,
+}
+
+impl NothingDue {
+    /// Time from the check until the next card is due.
+    #[must_use]
+    pub fn time_until_next(&self) -> Option<Duration> {
+        self.next_due_at
+            .map(|at| at.signed_duration_since(self.checked_at).max(Duration::zero()))
+    }
+}
+
+/// Cards `start_session` would serve right now, in order, without starting it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionPlanPreview {
+    pub card_ids: Vec<CardId>,
+    /// Due cards outside (re)learning.
+    pub reviews: usize,
+    pub new: usize,
+    /// Due cards still in (re)learning.
+    pub learning: usize,
+}
+
+impl SessionPlanPreview {
+    fn from_cards(cards: &[Card]) -> Self {
+        let mut preview = Self {
+            card_ids: cards.iter().map(Card::id).collect(),
+            ..Self::default()
+        };
+        for card in cards {
+            match card.phase() {
+                CardPhase::New => preview.new += 1,
+                CardPhase::Learning | CardPhase::Relearning => preview.learning += 1,
+                CardPhase::Reviewing => preview.reviews += 1,
+            }
+        }
+        preview
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.card_ids.is_empty()
+    }
 }
 
 /// Orchestrates session start and persisted answering.
@@ -42,10 +106,20 @@ pub struct SessionLoopService {
     cards: Arc<dyn CardRepository>,
     reviews: Arc<dyn ReviewPersistence>,
     summaries: Arc<dyn SessionSummaryRepository>,
+    progress: Option<Arc<dyn SessionProgressRepository>>,
+    review_logs: Option<Arc<dyn ReviewLogRepository>>,
     shuffle_new: bool,
+    max_answer_time: Duration,
+    study_day: StudyDay,
 }
 
 impl SessionLoopService {
+    /// Longest answer time recorded on a review log; longer waits are stored as this.
+    pub const DEFAULT_MAX_ANSWER_TIME: Duration = Duration::seconds(60);
+
+    /// Furthest ahead a study-ahead session looks, so it cannot pull in the whole deck.
+    pub const MAX_AHEAD_DAYS: u32 = 7;
+
     #[must_use]
     pub fn new(
         clock: Clock,
@@ -60,7 +134,11 @@ impl SessionLoopService {
             cards,
             reviews,
             summaries,
+            progress: None,
+            review_logs: None,
             shuffle_new: false,
+            max_answer_time: Self::DEFAULT_MAX_ANSWER_TIME,
+            study_day: StudyDay::default(),
         }
     }
 
@@ -70,24 +148,141 @@ impl SessionLoopService {
         self
     }
 
+    /// Cap answer times at `max_answer_time`, so a card left on screen while the learner
+    /// walked away does not skew their timing stats.
+    #[must_use]
+    pub fn with_max_answer_time(mut self, max_answer_time: Duration) -> Self {
+        self.max_answer_time = max_answer_time;
+        self
+    }
+
+    /// Start study days at `study_day`'s cutoff, for daily limits, burying until tomorrow,
+    /// and load balancing.
+    #[must_use]
+    pub fn with_study_day(mut self, study_day: StudyDay) -> Self {
+        self.study_day = study_day;
+        self
+    }
+
+    /// Save unfinished sessions to `progress` so `resume` can pick them up after a restart.
+    #[must_use]
+    pub fn with_progress(mut self, progress: Arc<dyn SessionProgressRepository>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Read review history from `review_logs`, so new cards introduced earlier in the study
+    /// day count against its new-card limit.
+    #[must_use]
+    pub fn with_review_logs(mut self, review_logs: Arc<dyn ReviewLogRepository>) -> Self {
+        self.review_logs = Some(review_logs);
+        self
+    }
+
+    /// Preview the session `start_session` would start for `deck_id` now.
+    ///
+    /// Uses the same daily limits, easy days, and micro-session size as the real planner,
+    /// but starts nothing and writes nothing. An empty or archived deck previews no cards.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Storage` on repository failures.
+    pub async fn peek_plan(&self, deck_id: DeckId) -> Result<SessionPlanPreview, SessionError> {
+        let now = self.clock.now();
+        let (deck, plan) = SessionQueries::build_plan_from_storage(
+            deck_id,
+            self.decks.as_ref(),
+            self.cards.as_ref(),
+            now,
+            self.study_day,
+            self.shuffle_new,
+            self.new_introduced_today(deck_id, now).await?,
+        )
+        .await?;
+        let micro_cap = usize::try_from(deck.settings().micro_session_size()).unwrap_or(usize::MAX);
+        let served = &plan.cards[..plan.cards.len().min(micro_cap)];
+        Ok(SessionPlanPreview::from_cards(served))
+    }
+
     /// Start a new session for the given deck.
     ///
+    /// With the deck's `bury_siblings` on, the cards held back because a card of the same
+    /// note was queued are buried until the next study day, the same way `bury_card` does.
+    ///
     /// # Errors
     ///
     /// Returns `SessionError` for storage or session start failures.
     pub async fn start_session(&self, deck_id: DeckId) -> Result<SessionService, SessionError> {
         let now = self.clock.now();
-        let (_deck, session) = SessionQueries::start_from_storage(
+        let (_deck, plan, mut session) = SessionQueries::start_from_storage_with_plan(
             deck_id,
             self.decks.as_ref(),
             self.cards.as_ref(),
             now,
+            self.study_day,
             self.shuffle_new,
+            self.new_introduced_today(deck_id, now).await?,
         )
         .await?;
+        if !plan.siblings_to_bury.is_empty() {
+            let until = self.study_day.next_start(now);
+            for sibling in self.cards.get_cards(deck_id, &plan.siblings_to_bury).await? {
+                self.cards
+                    .upsert_card(&sibling.with_buried_until(Some(until)).with_updated_at(now))
+                    .await?;
+            }
+        }
+        session.set_study_day(self.study_day);
+        self.save_progress(&session).await?;
         Ok(session)
     }
 
+    /// Start a due session like `start_session`, reporting an empty plan as `NothingDue`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError` for storage or session start failures other than an empty plan.
+    pub async fn start_or_nothing_due(
+        &self,
+        deck_id: DeckId,
+    ) -> Result<SessionStart, SessionError> {
+        match self.start_session(deck_id).await {
+            Ok(session) => Ok(SessionStart::Started(Box::new(session))),
+            Err(SessionError::Empty) => Ok(SessionStart::NothingDue(NothingDue {
+                checked_at: self.clock.now(),
+                next_due_at: self.next_due_at(deck_id).await?,
+            })),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// When the soonest card in `deck_id` comes due, skipping suspended cards.
+    ///
+    /// Buried cards count from when they are unburied. New cards, and cards already due but
+    /// held back by today's limits, count from the start of the next study day.
+    async fn next_due_at(&self, deck_id: DeckId) -> Result<Option<DateTime<Utc>>, SessionError> {
+        let Some(deck) = self.decks.get_deck(deck_id).await? else {
+            return Ok(None);
+        };
+        if deck.is_archived() {
+            return Ok(None);
+        }
+        let now = self.clock.now();
+        let next_day = self.study_day.next_start(now);
+        Ok(self
+            .cards
+            .list_cards(deck_id, u32::MAX)
+            .await?
+            .iter()
+            .filter(|card| !card.is_suspended())
+            .map(|card| {
+                let due = if card.is_new() { now } else { card.next_review_at() };
+                let due = card.buried_until().map_or(due, |until| due.max(until));
+                if due <= now { next_day } else { due }
+            })
+            .min())
+    }
+
     /// Start a new session including all cards in the deck.
     ///
     /// # Errors
@@ -98,13 +293,15 @@ impl SessionLoopService {
         deck_id: DeckId,
     ) -> Result<SessionService, SessionError> {
         let now = self.clock.now();
-        let (_deck, session) = SessionQueries::start_from_storage_all_cards(
+        let (_deck, mut session) = SessionQueries::start_from_storage_all_cards(
             deck_id,
             self.decks.as_ref(),
             self.cards.as_ref(),
             now,
         )
         .await?;
+        session.set_study_day(self.study_day);
+        self.save_progress(&session).await?;
         Ok(session)
     }
 
@@ -118,13 +315,15 @@ impl SessionLoopService {
         deck_id: DeckId,
     ) -> Result<SessionService, SessionError> {
         let now = self.clock.now();
-        let (_deck, session) = SessionQueries::start_from_storage_mistakes(
+        let (_deck, mut session) = SessionQueries::start_from_storage_mistakes(
             deck_id,
             self.decks.as_ref(),
             self.cards.as_ref(),
             now,
         )
         .await?;
+        session.set_study_day(self.study_day);
+        self.save_progress(&session).await?;
         Ok(session)
     }
 
@@ -139,48 +338,198 @@ impl SessionLoopService {
         tag_names: &[TagName],
     ) -> Result<SessionService, SessionError> {
         let now = self.clock.now();
-        let (_deck, session) = SessionQueries::start_from_storage_with_tags(
+        let (_deck, mut session) = SessionQueries::start_from_storage_with_tags(
             deck_id,
             self.decks.as_ref(),
             self.cards.as_ref(),
             now,
+            self.study_day,
             self.shuffle_new,
+            self.new_introduced_today(deck_id, now).await?,
             tag_names,
         )
         .await?;
+        session.set_study_day(self.study_day);
+        self.save_progress(&session).await?;
+        Ok(session)
+    }
+
+    /// Start a cram session over every card in the deck matching `filter`.
+    ///
+    /// Cards are graded for self-check only: answers write no review logs and leave
+    /// scheduling unchanged. The summary is still recorded, flagged as cram.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Empty` if no cards match, or
+    /// `SessionError::Storage` on repository failures.
+    pub async fn start_cram_session(
+        &self,
+        deck_id: DeckId,
+        filter: &CramFilter,
+    ) -> Result<SessionService, SessionError> {
+        let now = self.clock.now();
+        let (_deck, mut session) = SessionQueries::start_from_storage_cram(
+            deck_id,
+            self.decks.as_ref(),
+            self.cards.as_ref(),
+            now,
+            filter,
+        )
+        .await?;
+        session.set_study_day(self.study_day);
+        self.save_progress(&session).await?;
+        Ok(session)
+    }
+
+    /// Start a session over exactly the cards in `card_ids`, such as a selection in the
+    /// editor, whether or not they are due.
+    ///
+    /// With `cram` the session behaves like `start_cram_session`: answers write no review
+    /// logs and leave scheduling unchanged. Otherwise cards are graded and logged as usual.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::CardsUnavailable` if any id is not a card in `deck_id`, or the
+    /// deck is archived, `SessionError::Empty` if `card_ids` is empty, or
+    /// `SessionError::Storage` on repository failures.
+    pub async fn start_custom_session(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+        cram: bool,
+    ) -> Result<SessionService, SessionError> {
+        let now = self.clock.now();
+        let (_deck, mut session) = SessionQueries::start_from_storage_custom(
+            deck_id,
+            self.decks.as_ref(),
+            self.cards.as_ref(),
+            now,
+            card_ids,
+            cram,
+        )
+        .await?;
+        session.set_study_day(self.study_day);
+        self.save_progress(&session).await?;
+        Ok(session)
+    }
+
+    /// Start a study-ahead session over reviewed cards due within `within_days` from now.
+    ///
+    /// `within_days` is capped at [`Self::MAX_AHEAD_DAYS`]. Cards are graded and logged as
+    /// usual; each is scheduled from the time it was actually reviewed, with the elapsed
+    /// time since its last review, so an early answer does not earn the full interval.
+    /// The summary is flagged as ahead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Empty` if no cards are due in the window, or
+    /// `SessionError::Storage` on repository failures.
+    pub async fn start_ahead_session(
+        &self,
+        deck_id: DeckId,
+        within_days: u32,
+    ) -> Result<SessionService, SessionError> {
+        let now = self.clock.now();
+        let (_deck, mut session) = SessionQueries::start_from_storage_ahead(
+            deck_id,
+            self.decks.as_ref(),
+            self.cards.as_ref(),
+            now,
+            within_days.min(Self::MAX_AHEAD_DAYS),
+        )
+        .await?;
+        session.set_study_day(self.study_day);
+        self.save_progress(&session).await?;
         Ok(session)
     }
 
     /// Answer the current card and persist review + summary when completed.
     ///
+    /// The time since the card was shown, capped at the configured maximum, is stored on
+    /// its review log. In a cram session only the summary is persisted; the card is graded
+    /// on a copy.
+    /// With load balancing on, the deck's upcoming due counts are loaded first so the
+    /// new due date can move toward a quieter day.
+    /// A card answered Again as many times in a row as the deck's `same_session_fail_limit`
+    /// is not queued again this session, and gets the "leech" tag so it can be reworked.
+    ///
     /// # Errors
     ///
     /// Returns `SessionError` for review or persistence failures.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "answer_card",
+            skip_all,
+            fields(
+                deck_id = session.deck_id().value(),
+                card_id = session.current_card().map(|card| card.id().value()),
+                ?grade,
+                cram = session.is_cram(),
+            ),
+        )
+    )]
     pub async fn answer_current(
         &self,
         session: &mut SessionService,
         grade: ReviewGrade,
     ) -> Result<SessionAnswerResult, SessionError> {
-        let review_service = ReviewService::new()?.with_clock(self.clock);
         let reviewed_at = self.clock.now();
+        let answer_ms = session.answer_ms_at(reviewed_at, self.max_answer_time);
+        let mut review_service = ReviewService::new()?
+            .with_clock(self.clock)
+            .with_study_day(self.study_day)
+            .with_answer_ms(Some(answer_ms));
         let deck_settings = session.deck_settings().clone();
+        let cram = session.is_cram();
+        if deck_settings.load_balance() && !cram && !session.is_complete() {
+            let days = deck_settings.max_interval_days().saturating_add(1);
+            let due_load = review_service
+                .forecast(session.deck_id(), days, self.cards.as_ref())
+                .await?;
+            review_service = review_service.with_due_load(due_load);
+        }
         let Some(card) = session.current_card_mut() else {
             return Err(SessionError::Completed);
         };
 
-        let card_id = card.id();
-        let (result, _log_id) = review_service
-            .review_card_persisted_with_settings(
-                card,
+        let before = card.clone();
+        let mut leech = LeechMark::default();
+        let (result, log_id) = if cram {
+            let mut graded = before.clone();
+            let result = review_service.review_card_with_settings(
+                &mut graded,
                 grade,
                 reviewed_at,
                 &deck_settings,
-                self.reviews.as_ref(),
-            )
-            .await?;
+            )?;
+            (result, None)
+        } else {
+            let (result, log_id) = review_service
+                .review_card_persisted_with_settings(
+                    card,
+                    grade,
+                    reviewed_at,
+                    &deck_settings,
+                    self.reviews.as_ref(),
+                )
+                .await?;
+            leech = self.mark_leech(&before, card, &deck_settings).await?;
+            (result, Some(log_id))
+        };
         let review = session
-            .record_review_result(card_id, result, reviewed_at)?
+            .record_review_result(before.id(), result, reviewed_at)?
             .clone();
+        let failed_out = session.failed_out_last();
+        if failed_out && !leech.tagged {
+            leech.tagged = self.tag_leech(&before).await?;
+        }
+        session.set_last_answer(AnswerSnapshot {
+            card: before,
+            log_id,
+            leech,
+        });
 
         if session.is_complete() && session.summary_id().is_none() {
             let completed_at = session.completed_at().ok_or(SessionError::Completed)?;
@@ -188,15 +537,388 @@ impl SessionLoopService {
             let summary_id = self.summaries.append_summary(&summary).await?;
             session.set_summary_id(summary_id);
         }
+        self.save_progress(session).await?;
 
         Ok(SessionAnswerResult {
             review,
             is_complete: session.is_complete(),
             summary_id: session.summary_id(),
+            failed_out,
         })
     }
 
-    /// Preview next-review times for all grades without mutating session state.
+    /// Answer the current card with the grade behind the zero-based answer `button`.
+    ///
+    /// Buttons follow the deck's `AnswerButtons` layout, so in the two-button layout
+    /// button 1 is Good and schedules exactly as Good does with four buttons.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::UnknownAnswerButton` if the layout has no such button;
+    /// otherwise the same errors as `answer_current`.
+    pub async fn answer_button(
+        &self,
+        session: &mut SessionService,
+        button: usize,
+    ) -> Result<SessionAnswerResult, SessionError> {
+        let grade = session
+            .deck_settings()
+            .answer_buttons()
+            .grade_at(button)
+            .ok_or(SessionError::UnknownAnswerButton(button))?;
+        self.answer_current(session, grade).await
+    }
+
+    /// Undo the most recent answer in `session`.
+    ///
+    /// Deletes its review log, restores the card's prior scheduling state, and makes the
+    /// card current again. A summary persisted because that answer completed the session
+    /// is deleted as well. Only the single most recent answer can be undone.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::NothingToUndo` if there is no answer to undo, e.g. on a
+    /// second undo in a row. The session is unchanged on any error.
+    /// Returns `SessionError::Storage` if persistence fails.
+    pub async fn undo_last_answer(
+        &self,
+        session: &mut SessionService,
+    ) -> Result<SessionReview, SessionError> {
+        let Some(snapshot) = session.last_answer() else {
+            return Err(SessionError::NothingToUndo);
+        };
+        if let Some(log_id) = snapshot.log_id {
+            self.reviews.revert_review(&snapshot.card, log_id).await?;
+        }
+        self.unmark_leech(&snapshot.card, snapshot.leech).await?;
+        if let Some(summary_id) = session.summary_id() {
+            self.summaries.delete_summary(summary_id).await?;
+        }
+        let review = session.rollback_last_answer()?;
+        session.set_shown_at(self.clock.now());
+        self.save_progress(session).await?;
+        Ok(review)
+    }
+
+    /// The card just failed in `session`, to show its answer again for restudy.
+    ///
+    /// Only the session's view of the card is handed back: nothing is graded or stored,
+    /// so the lapse is counted once and no extra review log is written. Call
+    /// `finish_restudy` before showing the next card.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::NothingToRestudy` unless the last answer was an Again that
+    /// has not been undone.
+    pub fn restudy_last_lapse(&self, session: &SessionService) -> Result<Card, SessionError> {
+        session
+            .last_lapse()
+            .cloned()
+            .ok_or(SessionError::NothingToRestudy)
+    }
+
+    /// Leave restudy, restarting the answer timer so the next card is not charged for it.
+    pub fn finish_restudy(&self, session: &mut SessionService) {
+        session.set_shown_at(self.clock.now());
+    }
+
+    /// Bury a card for the rest of the study day and drop it from `session`.
+    ///
+    /// The card keeps its schedule; planning skips it until the next study day starts,
+    /// after which it returns without an explicit unbury. When the card was the last
+    /// one left, the session completes and its summary is persisted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::CardNotQueued` if the card is not waiting in `session`.
+    /// Returns `SessionError::Storage` if persistence fails.
+    pub async fn bury_card(
+        &self,
+        session: &mut SessionService,
+        card_id: CardId,
+    ) -> Result<(), SessionError> {
+        if session.is_complete() {
+            return Err(SessionError::Completed);
+        }
+        if !session.is_pending(card_id) {
+            return Err(SessionError::CardNotQueued);
+        }
+        let now = self.clock.now();
+        let mut stored = self.cards.get_cards(session.deck_id(), &[card_id]).await?;
+        let Some(card) = stored.pop() else {
+            return Err(SessionError::CardNotQueued);
+        };
+        let until = self.study_day.next_start(now);
+        self.cards
+            .upsert_card(&card.with_buried_until(Some(until)).with_updated_at(now))
+            .await?;
+        session.remove_pending(card_id, now)?;
+
+        if session.is_complete() && session.answered_count() > 0 {
+            self.finalize_summary(session).await?;
+        }
+        self.save_progress(session).await
+    }
+
+    /// Put the card `session` is showing at the back of its queue without grading it.
+    ///
+    /// Nothing is written to the card or its review history. Each card may be skipped
+    /// `SessionService::MAX_SKIPS` times per session; a resumed session counts afresh.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Completed` if the session has no current card.
+    /// Returns `SessionError::SkipLimitReached` once the card has to be graded.
+    /// Returns `SessionError::Storage` if saving the session's progress fails.
+    pub async fn skip_card(&self, session: &mut SessionService) -> Result<(), SessionError> {
+        session.skip_current(self.clock.now())?;
+        self.save_progress(session).await
+    }
+
+    /// Set or clear the flag on the card `session` is showing.
+    ///
+    /// The flag is stored right away and kept on the session's copy of the card, so
+    /// answering it afterwards does not drop the flag.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Completed` if the session has no current card.
+    /// Returns `SessionError::Storage` if persistence fails.
+    pub async fn flag_current(
+        &self,
+        session: &mut SessionService,
+        flag: Option<Flag>,
+    ) -> Result<(), SessionError> {
+        let Some(card) = session.current_card_mut() else {
+            return Err(SessionError::Completed);
+        };
+        let mut stored = self.cards.get_cards(card.deck_id(), &[card.id()]).await?;
+        let stored = stored.pop().ok_or(StorageError::NotFound)?;
+        let stored = stored.with_flag(flag).with_updated_at(self.clock.now());
+        self.cards.upsert_card(&stored).await?;
+        *card = card.clone().with_flag(flag);
+        Ok(())
+    }
+
+    /// Pick up the session saved for `deck_id` before the app was closed.
+    ///
+    /// Cards deleted, suspended, or buried since are skipped, and so are cards whose due
+    /// time changed while away, e.g. because they were reviewed in another session. Answers
+    /// already given still count toward the summary, but cannot be undone. Returns `None`
+    /// when nothing was saved or no saved card is left to answer; the saved state is then
+    /// cleared.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Storage` on repository failures, including a deck that no
+    /// longer exists.
+    pub async fn resume(&self, deck_id: DeckId) -> Result<Option<SessionService>, SessionError> {
+        let Some(progress) = &self.progress else {
+            return Ok(None);
+        };
+        let Some(saved) = progress.get_progress(deck_id).await? else {
+            return Ok(None);
+        };
+        let deck = self
+            .decks
+            .get_deck(deck_id)
+            .await?
+            .ok_or(StorageError::NotFound)?;
+
+        let mut ids: Vec<CardId> = saved
+            .answered
+            .iter()
+            .map(|answer| answer.card_id)
+            .chain(saved.pending.iter().map(|pending| pending.card_id))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        let stored: HashMap<CardId, Card> = self
+            .cards
+            .get_cards(deck_id, &ids)
+            .await?
+            .into_iter()
+            .map(|card| (card.id(), card))
+            .collect();
+
+        let now = self.clock.now();
+        let pending: Vec<Card> = saved
+            .pending
+            .iter()
+            .filter_map(|pending| {
+                stored.get(&pending.card_id).filter(|card| {
+                    !card.is_suspended()
+                        && !card.is_buried_at(now)
+                        && card.next_review_at() == pending.next_review_at
+                })
+            })
+            .cloned()
+            .collect();
+        if pending.is_empty() {
+            progress.clear_progress(deck_id).await?;
+            return Ok(None);
+        }
+        let answered = saved
+            .answered
+            .iter()
+            .filter_map(|answer| {
+                let card = stored.get(&answer.card_id)?.clone();
+                Some((card, saved_review(answer)))
+            })
+            .collect();
+
+        let mut session = SessionService::restore(
+            &deck,
+            answered,
+            pending,
+            saved.started_at,
+            saved.is_cram,
+            saved.is_ahead,
+        )?;
+        session.set_study_day(self.study_day);
+        session.set_shown_at(self.clock.now());
+        self.save_progress(&session).await?;
+        Ok(Some(session))
+    }
+
+    /// Whether an unfinished session is saved for `deck_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SessionError::Storage` on repository failures.
+    pub async fn has_saved_session(&self, deck_id: DeckId) -> Result<bool, SessionError> {
+        let Some(progress) = &self.progress else {
+            return Ok(false);
+        };
+        Ok(progress.get_progress(deck_id).await?.is_some())
+    }
+
+    /// New cards in `deck_id` first reviewed during the study day containing `now`; zero
+    /// without `with_review_logs`.
+    async fn new_introduced_today(
+        &self,
+        deck_id: DeckId,
+        now: DateTime<Utc>,
+    ) -> Result<u32, SessionError> {
+        let Some(review_logs) = &self.review_logs else {
+            return Ok(0);
+        };
+        let day_start = self.study_day.start_of(self.study_day.date_of(now));
+        Ok(review_logs.count_introduced_since(deck_id, day_start).await?)
+    }
+
+    /// Save `session` for `resume`, or forget it once it is complete.
+    async fn save_progress(&self, session: &SessionService) -> Result<(), SessionError> {
+        let Some(progress) = &self.progress else {
+            return Ok(());
+        };
+        if session.is_complete() {
+            progress.clear_progress(session.deck_id()).await?;
+            return Ok(());
+        }
+        let record = SessionProgressRecord {
+            deck_id: session.deck_id(),
+            started_at: session.started_at(),
+            is_cram: session.is_cram(),
+            is_ahead: session.is_ahead(),
+            answered: session
+                .results()
+                .iter()
+                .map(|review| {
+                    ReviewLogRecord::from_applied(
+                        session.deck_id(),
+                        &review.result.applied.log,
+                        &review.result.applied.outcome,
+                    )
+                })
+                .collect(),
+            pending: session
+                .pending_cards()
+                .iter()
+                .map(|card| PendingSessionCard {
+                    card_id: card.id(),
+                    next_review_at: card.next_review_at(),
+                })
+                .collect(),
+        };
+        progress.save_progress(&record).await?;
+        Ok(())
+    }
+
+    /// Tag `card` as a leech when the answer just given lapsed it to the deck's leech
+    /// threshold, and suspend it as well when the deck's leech action says so.
+    ///
+    /// Every later lapse past the threshold flags the card again, so a leech that was
+    /// resumed without being rewritten goes straight back out of the rotation.
+    async fn mark_leech(
+        &self,
+        before: &Card,
+        card: &mut Card,
+        settings: &DeckSettings,
+    ) -> Result<LeechMark, SessionError> {
+        let mut mark = LeechMark::default();
+        if card.lapses() == before.lapses() || !settings.is_leech(card.lapses()) {
+            return Ok(mark);
+        }
+        mark.tagged = self.tag_leech(card).await?;
+        if settings.leech_action() == LeechAction::Suspend && !card.is_suspended() {
+            *card = card.clone().with_suspended(true).with_updated_at(self.clock.now());
+            self.cards.upsert_card(card).await?;
+            mark.suspended = true;
+        }
+        Ok(mark)
+    }
+
+    /// Add the "leech" tag to `card`, returning whether it was not tagged yet.
+    async fn tag_leech(&self, card: &Card) -> Result<bool, SessionError> {
+        let leech = TagName::leech();
+        let mut names: Vec<TagName> = self
+            .cards
+            .list_tags_for_card(card.deck_id(), card.id())
+            .await?
+            .into_iter()
+            .map(|tag| tag.name().clone())
+            .collect();
+        if names.contains(&leech) {
+            return Ok(false);
+        }
+        names.push(leech);
+        self.cards
+            .set_tags_for_card(card.deck_id(), card.id(), &names)
+            .await?;
+        Ok(true)
+    }
+
+    /// Take back what `mark_leech` did for an answer that is being undone.
+    async fn unmark_leech(&self, card: &Card, mark: LeechMark) -> Result<(), SessionError> {
+        if mark.tagged {
+            let names: Vec<TagName> = self
+                .cards
+                .list_tags_for_card(card.deck_id(), card.id())
+                .await?
+                .into_iter()
+                .map(|tag| tag.name().clone())
+                .filter(|name| name.as_str() != TagName::LEECH)
+                .collect();
+            self.cards
+                .set_tags_for_card(card.deck_id(), card.id(), &names)
+                .await?;
+        }
+        if mark.suspended {
+            let mut stored = self.cards.get_cards(card.deck_id(), &[card.id()]).await?;
+            if let Some(stored) = stored.pop() {
+                let stored = stored.with_suspended(false).with_updated_at(self.clock.now());
+                self.cards.upsert_card(&stored).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Preview next-review times for each grade the deck's answer buttons offer, in button
+    /// order, without mutating session state.
+    ///
+    /// Load balancing is not applied here, so an answered card may land a day or two
+    /// away from its preview.
     ///
     /// # Errors
     ///
@@ -212,29 +934,24 @@ impl SessionLoopService {
         let Some(card) = session.current_card() else {
             return Err(SessionError::Completed);
         };
-        let mut grades = Vec::with_capacity(4);
-        for grade in [
-            ReviewGrade::Again,
-            ReviewGrade::Hard,
-            ReviewGrade::Good,
-            ReviewGrade::Easy,
-        ] {
-            let mut preview_card = card.clone();
-            let result = review_service.review_card_with_settings(
-                &mut preview_card,
-                grade,
-                reviewed_at,
-                &deck_settings,
-            )?;
-            grades.push(ReviewGradePreview {
-                grade,
-                next_review: result.applied.outcome.next_review,
-                scheduled_days: result.applied.outcome.scheduled_days,
-            });
-        }
+        let buttons = deck_settings.answer_buttons();
+        let grades = review_service
+            .preview_intervals(card, reviewed_at, &deck_settings)?
+            .into_iter()
+            .filter(|preview| buttons.offers(preview.grade))
+            .collect();
         Ok(SessionReviewPreview { reviewed_at, grades })
     }
 
+    /// How many cards `session` has answered and has left, against today's daily limits.
+    ///
+    /// Buried cards leave the queue and stop counting; learning cards queued again for a
+    /// later step count once more until they are answered.
+    #[must_use]
+    pub fn progress(&self, session: &SessionService) -> SessionProgress {
+        session.progress_at(self.clock.now())
+    }
+
     /// Retry summary persistence after a completed session.
     ///
     /// This is useful when the final summary append failed (e.g. transient storage error).
@@ -259,3 +976,27 @@ impl SessionLoopService {
         Ok(id)
     }
 }
+
+/// Rebuild the review result of an answer saved with an unfinished session.
+fn saved_review(answer: &ReviewLogRecord) -> SessionReview {
+    let outcome = ReviewOutcome::new(
+        answer.next_review_at,
+        answer.stability,
+        answer.difficulty,
+        answer.elapsed_days,
+        answer.scheduled_days,
+    );
+    SessionReview {
+        card_id: answer.card_id,
+        result: ReviewResult {
+            applied: AppliedReview {
+                log: ReviewLog::new(answer.card_id, answer.grade, answer.reviewed_at)
+                    .with_answer_ms(answer.answer_ms)
+                    .with_first_review(answer.first_review),
+                outcome,
+                memory: MemoryState::new(answer.stability, answer.difficulty),
+            },
+            explanation: None,
+        },
+    }
+}
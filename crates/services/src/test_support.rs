@@ -0,0 +1,291 @@
+//! Deterministic fixtures for service-level tests.
+//!
+//! Compiled for this crate's unit tests and, behind the `test-support` feature,
+//! for integration tests and downstream crates.
+
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use learn_core::model::{Card, CardId, Deck, DeckId, DeckSettings, ReviewGrade};
+use learn_core::model::content::ContentDraft;
+use learn_core::time::fixed_now;
+use storage::repository::{Storage, StorageError};
+
+use crate::card_service::CardService;
+use crate::deck_service::DeckService;
+use crate::error::{CardServiceError, DeckServiceError, ReviewServiceError};
+use crate::review_service::ReviewService;
+use crate::sessions::{SessionLoopService, SessionSummaryService};
+use crate::Clock;
+
+/// Errors emitted while building a `ServicesFixture`.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ServicesFixtureError {
+    #[error(transparent)]
+    Deck(#[from] DeckServiceError),
+    #[error(transparent)]
+    Card(#[from] CardServiceError),
+    #[error(transparent)]
+    Review(#[from] ReviewServiceError),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+/// Default deck settings without learning steps, so each card is answered once.
+///
+/// # Panics
+///
+/// Never: the ADHD defaults accept an empty step list.
+#[must_use]
+pub fn single_pass_settings() -> DeckSettings {
+    DeckSettings::default_for_adhd()
+        .with_learning_steps(Vec::new())
+        .expect("no learning steps is always valid")
+}
+
+//
+// ─── BUILDER ───────────────────────────────────────────────────────────────────
+//
+
+/// Configures an in-memory services fixture.
+///
+/// Defaults to `Clock::Fixed(fixed_now())`, a single deck with
+/// `DeckSettings::default_for_adhd()`, and no cards.
+#[derive(Debug, Clone)]
+pub struct ServicesFixtureBuilder {
+    clock: Clock,
+    deck_name: String,
+    deck_settings: DeckSettings,
+    card_count: u32,
+    reviews: Vec<ReviewGrade>,
+}
+
+impl Default for ServicesFixtureBuilder {
+    fn default() -> Self {
+        Self {
+            clock: Clock::fixed(fixed_now()),
+            deck_name: "Fixture Deck".to_string(),
+            deck_settings: DeckSettings::default_for_adhd(),
+            card_count: 0,
+            reviews: Vec::new(),
+        }
+    }
+}
+
+impl ServicesFixtureBuilder {
+    /// Override the fixed clock shared by all services.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Configure the fixture deck name and settings.
+    #[must_use]
+    pub fn with_deck(mut self, name: impl Into<String>, settings: DeckSettings) -> Self {
+        self.deck_name = name.into();
+        self.deck_settings = settings;
+        self
+    }
+
+    /// Create `count` cards with prompts `Q1..Qn` and answers `A1..An`.
+    #[must_use]
+    pub fn with_cards(mut self, count: u32) -> Self {
+        self.card_count = count;
+        self
+    }
+
+    /// Review cards in creation order, one grade per card, at the fixture clock time.
+    ///
+    /// Grades beyond the number of cards are ignored.
+    #[must_use]
+    pub fn with_reviews(mut self, grades: impl IntoIterator<Item = ReviewGrade>) -> Self {
+        self.reviews = grades.into_iter().collect();
+        self
+    }
+
+    /// Create the storage, deck, cards, and reviews.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ServicesFixtureError` if any seeding step fails.
+    pub async fn build(self) -> Result<ServicesFixture, ServicesFixtureError> {
+        let storage = Storage::in_memory();
+        let deck_service = DeckService::new(
+            self.clock,
+            Arc::clone(&storage.decks),
+            Arc::clone(&storage.cards),
+            Arc::clone(&storage.review_logs),
+        );
+        let card_service = CardService::new(self.clock, Arc::clone(&storage.cards));
+
+        let deck_id = deck_service
+            .create_deck(self.deck_name, None, self.deck_settings)
+            .await?;
+        let deck = deck_service
+            .get_deck(deck_id)
+            .await?
+            .ok_or(StorageError::NotFound)?;
+
+        let mut card_ids = Vec::new();
+        for n in 1..=self.card_count {
+            let id = card_service
+                .create_card(
+                    deck_id,
+                    ContentDraft::text_only(format!("Q{n}")),
+                    ContentDraft::text_only(format!("A{n}")),
+                    None,
+                )
+                .await?
+                .id;
+            card_ids.push(id);
+        }
+
+        let review_service = ReviewService::new()?.with_clock(self.clock);
+        for (card_id, grade) in card_ids.iter().zip(self.reviews) {
+            review_service
+                .review_card_persisted_by_id(
+                    deck_id,
+                    *card_id,
+                    storage.cards.as_ref(),
+                    storage.reviews.as_ref(),
+                    grade,
+                )
+                .await?;
+        }
+
+        let cards = storage.cards.get_cards(deck_id, &card_ids).await?;
+
+        Ok(ServicesFixture {
+            clock: self.clock,
+            storage,
+            deck,
+            cards,
+        })
+    }
+}
+
+//
+// ─── FIXTURE ───────────────────────────────────────────────────────────────────
+//
+
+/// Seeded in-memory storage plus ready-to-use services sharing one clock.
+pub struct ServicesFixture {
+    clock: Clock,
+    storage: Storage,
+    deck: Deck,
+    cards: Vec<Card>,
+}
+
+impl ServicesFixture {
+    #[must_use]
+    pub fn builder() -> ServicesFixtureBuilder {
+        ServicesFixtureBuilder::default()
+    }
+
+    #[must_use]
+    pub fn clock(&self) -> Clock {
+        self.clock
+    }
+
+    #[must_use]
+    pub fn storage(&self) -> &Storage {
+        &self.storage
+    }
+
+    #[must_use]
+    pub fn deck(&self) -> &Deck {
+        &self.deck
+    }
+
+    #[must_use]
+    pub fn deck_id(&self) -> DeckId {
+        self.deck.id()
+    }
+
+    /// Cards as seeded, after any configured reviews.
+    #[must_use]
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    #[must_use]
+    pub fn card_ids(&self) -> Vec<CardId> {
+        self.cards.iter().map(Card::id).collect()
+    }
+
+    #[must_use]
+    pub fn card_service(&self) -> CardService {
+        CardService::new(self.clock, Arc::clone(&self.storage.cards))
+            .with_media(Arc::clone(&self.storage.media))
+    }
+
+    #[must_use]
+    pub fn deck_service(&self) -> DeckService {
+        DeckService::new(
+            self.clock,
+            Arc::clone(&self.storage.decks),
+            Arc::clone(&self.storage.cards),
+            Arc::clone(&self.storage.review_logs),
+        )
+    }
+
+    /// Build a review service bound to the fixture clock.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ReviewServiceError::Scheduler` if the scheduler cannot be created.
+    pub fn review_service(&self) -> Result<ReviewService, ReviewServiceError> {
+        Ok(ReviewService::new()?.with_clock(self.clock))
+    }
+
+    #[must_use]
+    pub fn session_loop(&self) -> SessionLoopService {
+        SessionLoopService::new(
+            self.clock,
+            Arc::clone(&self.storage.decks),
+            Arc::clone(&self.storage.cards),
+            Arc::clone(&self.storage.reviews),
+            Arc::clone(&self.storage.session_summaries),
+        )
+        .with_progress(Arc::clone(&self.storage.session_progress))
+        .with_review_logs(Arc::clone(&self.storage.review_logs))
+    }
+
+    #[must_use]
+    pub fn session_summaries(&self) -> SessionSummaryService {
+        SessionSummaryService::new(self.clock, Arc::clone(&self.storage.session_summaries))
+    }
+}
+
+//
+// ─── TESTS ─────────────────────────────────────────────────────────────────────
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use learn_core::model::CardPhase;
+
+    #[tokio::test]
+    async fn builder_seeds_deck_cards_and_reviews() {
+        let fixture = ServicesFixture::builder()
+            .with_cards(3)
+            .with_reviews([ReviewGrade::Good])
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(fixture.deck().name(), "Fixture Deck");
+        assert_eq!(fixture.cards().len(), 3);
+        let reviewed: Vec<_> = fixture
+            .cards()
+            .iter()
+            .filter(|card| card.phase() != CardPhase::New)
+            .collect();
+        assert_eq!(reviewed.len(), 1);
+        assert_eq!(reviewed[0].last_review_at(), Some(fixed_now()));
+    }
+}
@@ -1,6 +1,8 @@
 use std::env;
 use std::sync::Arc;
+use std::time::Duration;
 
+use async_trait::async_trait;
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -93,12 +95,138 @@ impl WritingToolsConfig {
     }
 }
 
+/// How `WritingToolsService` retries timeouts, server errors, and lost connections.
+///
+/// Retries reuse the request's usage record, so they count once against the daily cap and
+/// cooldown. Rate-limit and auth failures are never retried.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts after the first one; zero turns retrying off.
+    pub max_retries: u32,
+    /// Wait before the first retry; each later retry waits twice as long.
+    pub initial_backoff: Duration,
+    /// Longest wait between two attempts.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Wait before retry number `retry`, counting from zero.
+    #[must_use]
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(retry);
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |delay| delay.min(self.max_backoff))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+/// One message in a chat-completion request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ChatMessage {
+    pub role: &'static str,
+    pub content: String,
+}
+
+/// Token counts reported by the provider for one completion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub struct ChatUsage {
+    #[serde(rename = "prompt_tokens")]
+    pub prompt: u32,
+    #[serde(rename = "completion_tokens")]
+    pub completion: u32,
+    #[serde(rename = "total_tokens")]
+    pub total: u32,
+}
+
+/// The parts of a chat-completion response the writing tools use.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChatCompletion {
+    pub content: Option<String>,
+    pub usage: Option<ChatUsage>,
+}
+
+/// Transport for chat completions, so tests can stand in for the provider.
+#[async_trait]
+pub trait ChatCompletionClient: Send + Sync {
+    /// Send one completion request for `model`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WritingToolsError::HttpStatus` for non-success responses,
+    /// `WritingToolsError::Timeout` or `WritingToolsError::Offline` when the provider does not
+    /// answer, and `WritingToolsError::Http` if the request or response decoding fails.
+    async fn complete(
+        &self,
+        config: &WritingToolsConfig,
+        model: &str,
+        messages: Vec<ChatMessage>,
+    ) -> Result<ChatCompletion, WritingToolsError>;
+}
+
+/// `ChatCompletionClient` for OpenAI-compatible `/chat/completions` endpoints.
+#[derive(Clone, Default)]
+pub struct OpenAiChatClient {
+    client: Client,
+}
+
+#[async_trait]
+impl ChatCompletionClient for OpenAiChatClient {
+    async fn complete(
+        &self,
+        config: &WritingToolsConfig,
+        model: &str,
+        messages: Vec<ChatMessage>,
+    ) -> Result<ChatCompletion, WritingToolsError> {
+        let url = format!(
+            "{}/chat/completions",
+            config.base_url.trim_end_matches('/')
+        );
+        let payload = ChatRequest {
+            model: model.to_string(),
+            messages,
+            temperature: 0.2,
+        };
+        let response = self
+            .client
+            .post(url)
+            .bearer_auth(&config.api_key)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(transport_error)?;
+        if !response.status().is_success() {
+            return Err(WritingToolsError::HttpStatus(response.status()));
+        }
+        let body: ChatResponse = response.json().await.map_err(transport_error)?;
+        Ok(ChatCompletion {
+            content: body
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|choice| choice.message.content),
+            usage: body.usage,
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct WritingToolsService {
-    client: Client,
+    client: Arc<dyn ChatCompletionClient>,
     settings_repo: Arc<dyn AppSettingsRepository>,
     env_config: Option<WritingToolsConfig>,
     usage: Arc<AiUsageService>,
+    retry: RetryPolicy,
 }
 
 impl WritingToolsService {
@@ -117,13 +245,28 @@ impl WritingToolsService {
         usage: Arc<AiUsageService>,
     ) -> Self {
         Self {
-            client: Client::new(),
+            client: Arc::new(OpenAiChatClient::default()),
             settings_repo,
             env_config,
             usage,
+            retry: RetryPolicy::default(),
         }
     }
 
+    /// Replace the chat transport, e.g. with a fake in tests.
+    #[must_use]
+    pub fn with_client(mut self, client: Arc<dyn ChatCompletionClient>) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Replace the retry policy, e.g. with zero backoff in tests.
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     /// Generate text from a prompt.
     ///
     /// # Errors
@@ -135,15 +278,13 @@ impl WritingToolsService {
         let preferred = config.preferred_model.clone();
         let fallback = config.fallback_model.clone();
 
-        match self.generate_with_model(prompt, &config, &preferred).await {
+        match self.generate_with_model(prompt, &config, &preferred, false).await {
             Ok(result) => Ok(result),
-            Err(WritingToolsError::HttpStatus(status))
-                if status == StatusCode::TOO_MANY_REQUESTS =>
-            {
+            Err(WritingToolsError::HttpStatus(status)) if should_fall_back(status) => {
                 let Some(fallback_model) = fallback.filter(|model| model != &preferred) else {
                     return Err(WritingToolsError::HttpStatus(status));
                 };
-                self.generate_with_model(prompt, &config, &fallback_model)
+                self.generate_with_model(prompt, &config, &fallback_model, true)
                     .await
             }
             Err(err) => Err(err),
@@ -162,15 +303,13 @@ impl WritingToolsService {
         prompt: &str,
         config: &WritingToolsConfig,
         model: &str,
+        is_fallback: bool,
     ) -> Result<WritingToolsOutput, WritingToolsError> {
-        let usage_handle = self
-            .usage
-            .start_request(&config.provider, model)
-            .await?;
-        let url = format!(
-            "{}/chat/completions",
-            config.base_url.trim_end_matches('/')
-        );
+        let usage_handle = if is_fallback {
+            self.usage.start_fallback_request(&config.provider, model).await?
+        } else {
+            self.usage.start_request(&config.provider, model).await?
+        };
         let mut messages = Vec::new();
         if let Some(system_prompt) = config.system_prompt.as_ref() {
             messages.push(ChatMessage {
@@ -182,49 +321,19 @@ impl WritingToolsService {
             role: "user",
             content: prompt.to_string(),
         });
-        let payload = ChatRequest {
-            model: model.to_string(),
-            messages,
-            temperature: 0.2,
-        };
-
-        let response = match self
-            .client
-            .post(url)
-            .bearer_auth(&config.api_key)
-            .json(&payload)
-            .send()
-            .await
-        {
-            Ok(response) => response,
-            Err(err) => {
-                self.usage.finish_failure(&usage_handle).await?;
-                return Err(WritingToolsError::Http(err));
-            }
-        };
-
-        if !response.status().is_success() {
-            self.usage.finish_failure(&usage_handle).await?;
-            return Err(WritingToolsError::HttpStatus(response.status()));
-        }
 
-        let body: ChatResponse = match response.json().await {
-            Ok(body) => body,
+        let completion = match self.complete_with_retry(config, model, messages).await {
+            Ok(completion) => completion,
             Err(err) => {
                 self.usage.finish_failure(&usage_handle).await?;
-                return Err(WritingToolsError::Http(err));
+                return Err(err);
             }
         };
-        let Some(content) = body
-            .choices
-            .into_iter()
-            .next()
-            .and_then(|choice| choice.message.content)
-        else {
+        let Some(content) = completion.content else {
             self.usage.finish_failure(&usage_handle).await?;
             return Err(WritingToolsError::EmptyResponse);
         };
-        let Some(usage) = body.usage else {
+        let Some(usage) = completion.usage else {
             self.usage.finish_failure(&usage_handle).await?;
             return Err(WritingToolsError::MissingUsage);
         };
@@ -240,6 +349,42 @@ impl WritingToolsService {
 
         parse_writing_tools_output(&content, prompt)
     }
+
+    /// Send the request, retrying transient failures with exponential backoff.
+    async fn complete_with_retry(
+        &self,
+        config: &WritingToolsConfig,
+        model: &str,
+        messages: Vec<ChatMessage>,
+    ) -> Result<ChatCompletion, WritingToolsError> {
+        let mut retry = 0;
+        loop {
+            match self.client.complete(config, model, messages.clone()).await {
+                Err(err) if err.is_transient() && retry < self.retry.max_retries => {
+                    let delay = self.retry.backoff(retry);
+                    if !delay.is_zero() {
+                        tokio::time::sleep(delay).await;
+                    }
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// How long one completion request may take before it counts as timed out.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Split transport failures into timeouts, unreachable providers, and everything else.
+fn transport_error(err: reqwest::Error) -> WritingToolsError {
+    if err.is_timeout() {
+        WritingToolsError::Timeout
+    } else if err.is_connect() {
+        WritingToolsError::Offline
+    } else {
+        WritingToolsError::Http(err)
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -249,12 +394,6 @@ struct ChatRequest {
     temperature: f32,
 }
 
-#[derive(Debug, Serialize)]
-struct ChatMessage {
-    role: &'static str,
-    content: String,
-}
-
 #[derive(Debug, Deserialize)]
 struct ChatResponse {
     choices: Vec<ChatChoice>,
@@ -388,14 +527,12 @@ fn strip_json_fence(content: &str) -> String {
     body.join("\n").trim().to_string()
 }
 
-#[derive(Debug, Deserialize)]
-struct ChatUsage {
-    #[serde(rename = "prompt_tokens")]
-    prompt: u32,
-    #[serde(rename = "completion_tokens")]
-    completion: u32,
-    #[serde(rename = "total_tokens")]
-    total: u32,
+/// Statuses worth retrying on the fallback model: provider rate limits, unknown or
+/// unavailable models, and server-side failures.
+fn should_fall_back(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::NOT_FOUND
+        || status.is_server_error()
 }
 
 fn default_base_url() -> &'static str {
@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use chrono::{Duration, TimeZone, Utc};
+use learn_core::model::{CardKind, CardPhase, CardSource, DeckSettings};
+use learn_core::time::fixed_now;
+use services::{AnkiImportError, AnkiImportService, Clock, DeckService, MediaService};
+use storage::repository::Storage;
+
+/// Basic, reversed, and cloze notes across two decks; see the card notes in the assertions.
+const SAMPLE_APKG: &[u8] = include_bytes!("fixtures/sample.apkg");
+
+async fn services(name: &str) -> (Storage, Arc<DeckService>, AnkiImportService) {
+    let storage = Storage::sqlite(&format!("sqlite:file:{name}?mode=memory&cache=shared"))
+        .await
+        .expect("connect sqlite");
+    let clock = Clock::fixed(fixed_now());
+    let decks = Arc::new(DeckService::new(
+        clock,
+        Arc::clone(&storage.decks),
+        Arc::clone(&storage.cards),
+        Arc::clone(&storage.review_logs),
+    ));
+    let media = Arc::new(MediaService::new(clock, Arc::clone(&storage.media)));
+    let import = AnkiImportService::new(
+        clock,
+        Arc::clone(&decks),
+        Arc::clone(&storage.cards),
+        media,
+    );
+    (storage, decks, import)
+}
+
+#[tokio::test]
+async fn import_apkg_maps_notes_scheduling_and_media() {
+    let (storage, decks, import) = services("memdb_anki_import").await;
+
+    let report = import.import_apkg(SAMPLE_APKG).await.expect("import");
+
+    assert_eq!(report.decks.len(), 2);
+    assert_eq!(report.imported, 7);
+    assert_eq!(report.scheduled, 1);
+    // A card with an empty answer and a note of an unknown type.
+    assert_eq!(report.skipped, 2);
+    assert_eq!(report.media_imported, 1);
+    // The `[sound:]` reference.
+    assert_eq!(report.media_skipped, 1);
+
+    let names: Vec<String> = decks
+        .list_decks(10, false)
+        .await
+        .expect("list decks")
+        .iter()
+        .map(|deck| deck.name().to_owned())
+        .collect();
+    assert!(names.contains(&"Spanish::Verbs".to_owned()));
+    assert!(names.contains(&"Geography".to_owned()));
+
+    let verbs = storage
+        .cards
+        .list_cards(report.decks[0], 20)
+        .await
+        .expect("list verbs");
+    assert_eq!(verbs.len(), 4);
+    let prompts: Vec<&str> = verbs.iter().map(|card| card.prompt().text()).collect();
+    assert!(prompts.contains(&"to live"), "reversed card: {prompts:?}");
+    assert!(verbs.iter().all(|card| card.source() == Some(CardSource::Anki)));
+
+    let comer = verbs
+        .iter()
+        .find(|card| card.prompt().text() == "comer")
+        .expect("review card");
+    assert_eq!(comer.answer().text(), "to eat");
+    assert!(comer.answer().media_id().is_some());
+    assert_eq!(comer.phase(), CardPhase::Reviewing);
+    assert_eq!(comer.review_count(), 5);
+    let due = Utc.timestamp_opt(1_699_920_000, 0).unwrap() + Duration::days(10);
+    assert_eq!(comer.next_review_at(), due);
+    assert_eq!(comer.last_review_at(), Some(due - Duration::days(12)));
+
+    let hablar = verbs
+        .iter()
+        .find(|card| card.prompt().text() == "hablar")
+        .expect("new card");
+    assert!(hablar.is_new());
+    assert_eq!(hablar.next_review_at(), fixed_now());
+
+    let geography = storage
+        .cards
+        .list_cards(report.decks[1], 20)
+        .await
+        .expect("list geography");
+    assert_eq!(geography.len(), 3);
+    let (clozes, basic): (Vec<_>, Vec<_>) = geography
+        .iter()
+        .partition(|card| matches!(card.kind(), CardKind::Cloze { .. }));
+    assert_eq!(basic.len(), 1);
+    assert_eq!(basic[0].prompt().text(), "Say hi", "sound reference stripped");
+    for card in &clozes {
+        // The learning card restarts as new.
+        assert!(card.is_new());
+        assert_eq!(card.extra().map(|extra| extra.text()), Some("In Europe"));
+    }
+    let answers: Vec<&str> = clozes.iter().map(|card| card.answer().text()).collect();
+    assert!(answers.contains(&"[Paris] is the capital of France"), "cloze answers: {answers:?}");
+    assert!(answers.contains(&"Paris is the capital of [France]"), "cloze answers: {answers:?}");
+}
+
+#[tokio::test]
+async fn import_apkg_rejects_taken_deck_names_before_writing() {
+    let (storage, decks, import) = services("memdb_anki_import_taken").await;
+    decks
+        .create_deck(" geography ".into(), None, DeckSettings::default_for_adhd())
+        .await
+        .expect("create deck");
+
+    let err = import.import_apkg(SAMPLE_APKG).await.unwrap_err();
+
+    assert!(matches!(err, AnkiImportError::DeckNameTaken(ref name) if name == "Geography"));
+    assert_eq!(storage.decks.list_decks(10, false).await.expect("list").len(), 1);
+}
+
+#[tokio::test]
+async fn import_apkg_rejects_archives_without_a_collection() {
+    let (_storage, _decks, import) = services("memdb_anki_import_empty").await;
+
+    let err = import.import_apkg(b"not a zip").await.unwrap_err();
+
+    assert!(matches!(err, AnkiImportError::Archive(_)));
+}
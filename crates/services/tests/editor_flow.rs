@@ -12,7 +12,12 @@ async fn editor_flow_create_edit_delete_undo() {
         .await
         .expect("connect sqlite");
     let clock = Clock::fixed(fixed_now());
-    let deck_service = DeckService::new(clock, Arc::clone(&storage.decks));
+    let deck_service = DeckService::new(
+        clock,
+        Arc::clone(&storage.decks),
+        Arc::clone(&storage.cards),
+        Arc::clone(&storage.review_logs),
+    );
     let card_service = CardService::new(clock, Arc::clone(&storage.cards));
 
     let deck_id = deck_service
@@ -27,9 +32,10 @@ async fn editor_flow_create_edit_delete_undo() {
     let original_prompt = ContentDraft::text_only("What is Rust?");
     let original_answer = ContentDraft::text_only("A systems language.");
     let card_id = card_service
-        .create_card(deck_id, original_prompt.clone(), original_answer.clone())
+        .create_card(deck_id, original_prompt.clone(), original_answer.clone(), None)
         .await
-        .expect("create card");
+        .expect("create card")
+        .id;
 
     card_service
         .update_card_content(
@@ -63,9 +69,10 @@ async fn editor_flow_create_edit_delete_undo() {
 
     // Undo deletion by re-creating the card content.
     let restored_id = card_service
-        .create_card(deck_id, original_prompt, original_answer)
+        .create_card(deck_id, original_prompt, original_answer, None)
         .await
-        .expect("undo delete");
+        .expect("undo delete")
+        .id;
     let cards = card_service
         .list_cards(deck_id, 10)
         .await
@@ -0,0 +1,1109 @@
+use std::sync::Arc;
+
+use chrono::Duration;
+use learn_core::model::{
+    AnswerButtons, Card, CardId, CardPhase, DeckSettings, Flag, LeechAction, NoteId, ReviewGrade,
+    TagName,
+};
+use services::test_support::{ServicesFixture, single_pass_settings};
+use services::{
+    Clock, CramFilter, NothingDue, ReviewLogPrune, SessionError, SessionLoopService, SessionService,
+    SessionStart,
+};
+
+#[tokio::test]
+async fn fixture_runs_create_review_summary_flow() {
+    let fixture = ServicesFixture::builder()
+        .with_deck("Fixture Deck", single_pass_settings())
+        .with_cards(2)
+        .build()
+        .await
+        .expect("build fixture");
+
+    let card_id = fixture
+        .card_service()
+        .create_card(
+            fixture.deck_id(),
+            learn_core::model::ContentDraft::text_only("Q3"),
+            learn_core::model::ContentDraft::text_only("A3"),
+            None,
+        )
+        .await
+        .expect("create card")
+        .id;
+
+    let loop_svc = fixture.session_loop();
+    let mut session = loop_svc
+        .start_session(fixture.deck_id())
+        .await
+        .expect("start session");
+    assert_eq!(session.total_cards(), 3);
+
+    while !session.is_complete() {
+        loop_svc
+            .answer_current(&mut session, ReviewGrade::Good)
+            .await
+            .expect("answer card");
+    }
+
+    let summary_id = session.summary_id().expect("summary persisted");
+    let summary = fixture
+        .session_summaries()
+        .get_summary(summary_id)
+        .await
+        .expect("load summary");
+    assert_eq!(summary.total_reviews(), 3);
+    assert_eq!(summary.good(), 3);
+    assert_eq!(summary.completed_at(), fixture.clock().now());
+
+    let cards = fixture
+        .card_service()
+        .list_cards(fixture.deck_id(), 10)
+        .await
+        .expect("list cards");
+    let reviewed = cards.iter().find(|card| card.id() == card_id).unwrap();
+    assert_eq!(reviewed.review_count(), 1);
+}
+
+#[tokio::test]
+async fn undo_last_answer_reverts_review_and_summary() {
+    let fixture = ServicesFixture::builder()
+        .with_deck("Fixture Deck", single_pass_settings())
+        .with_cards(1)
+        .build()
+        .await
+        .expect("build fixture");
+    let card_id = fixture.card_ids()[0];
+
+    let loop_svc = fixture.session_loop();
+    let mut session = loop_svc
+        .start_session(fixture.deck_id())
+        .await
+        .expect("start session");
+    loop_svc
+        .answer_current(&mut session, ReviewGrade::Good)
+        .await
+        .expect("answer card");
+    let summary_id = session.summary_id().expect("summary persisted");
+
+    let undone = loop_svc
+        .undo_last_answer(&mut session)
+        .await
+        .expect("undo answer");
+    assert_eq!(undone.card_id, card_id);
+    assert!(!session.is_complete());
+    assert_eq!(session.answered_count(), 0);
+    assert_eq!(session.summary_id(), None);
+    assert_eq!(session.current_card().map(|card| card.id()), Some(card_id));
+
+    let logs = fixture
+        .storage()
+        .review_logs
+        .logs_for_card(fixture.deck_id(), card_id)
+        .await
+        .expect("load logs");
+    assert!(logs.is_empty());
+    let stored = fixture
+        .card_service()
+        .list_cards(fixture.deck_id(), 10)
+        .await
+        .expect("list cards");
+    assert_eq!(stored[0].review_count(), 0);
+    assert!(
+        fixture
+            .session_summaries()
+            .get_summary(summary_id)
+            .await
+            .is_err()
+    );
+
+    let err = loop_svc.undo_last_answer(&mut session).await.unwrap_err();
+    assert!(matches!(err, SessionError::NothingToUndo));
+}
+
+#[tokio::test]
+async fn restudying_a_lapse_shows_the_card_again_without_another_review_log() {
+    let fixture = ServicesFixture::builder()
+        .with_cards(2)
+        .build()
+        .await
+        .expect("build fixture");
+    let loop_svc = fixture.session_loop();
+    let mut session = loop_svc
+        .start_session(fixture.deck_id())
+        .await
+        .expect("start session");
+    let err = loop_svc.restudy_last_lapse(&session).unwrap_err();
+    assert!(matches!(err, SessionError::NothingToRestudy));
+
+    let failed_id = session.current_card().map(Card::id).expect("current card");
+    loop_svc
+        .answer_current(&mut session, ReviewGrade::Again)
+        .await
+        .expect("answer card");
+    let review_logs = &fixture.storage().review_logs;
+    let deck_id = fixture.deck_id();
+    let logs = review_logs.logs_for_card(deck_id, failed_id).await.expect("load logs");
+    assert_eq!(logs.len(), 1);
+
+    let shown = loop_svc.restudy_last_lapse(&session).expect("restudy");
+    assert_eq!(shown.id(), failed_id);
+    loop_svc.finish_restudy(&mut session);
+    assert_eq!(session.answered_count(), 1);
+    let logs = review_logs.logs_for_card(deck_id, failed_id).await.expect("load logs");
+    assert_eq!(logs.len(), 1);
+
+    loop_svc
+        .answer_current(&mut session, ReviewGrade::Good)
+        .await
+        .expect("answer next card");
+    assert!(loop_svc.restudy_last_lapse(&session).is_err());
+    let logs = review_logs.logs_for_card(deck_id, failed_id).await.expect("load logs");
+    assert_eq!(logs.len(), 1);
+}
+
+#[tokio::test]
+async fn cram_session_records_flagged_summary_without_scheduling() {
+    let fixture = ServicesFixture::builder()
+        .with_cards(3)
+        .with_reviews([ReviewGrade::Good])
+        .build()
+        .await
+        .expect("build fixture");
+    let reviewed_id = fixture.card_ids()[0];
+    let loop_svc = fixture.session_loop();
+
+    let mut session = loop_svc
+        .start_cram_session(fixture.deck_id(), &CramFilter::default())
+        .await
+        .expect("start cram session");
+    assert!(session.is_cram());
+    assert_eq!(session.total_cards(), 3);
+    while !session.is_complete() {
+        loop_svc
+            .answer_current(&mut session, ReviewGrade::Again)
+            .await
+            .expect("answer card");
+    }
+
+    let summary_id = session.summary_id().expect("summary persisted");
+    let summary = fixture
+        .session_summaries()
+        .get_summary(summary_id)
+        .await
+        .expect("load summary");
+    assert!(summary.is_cram());
+    assert_eq!(summary.again(), 3);
+
+    let stored = fixture
+        .card_service()
+        .list_cards(fixture.deck_id(), 10)
+        .await
+        .expect("list cards");
+    for card in &stored {
+        let seeded = fixture.cards().iter().find(|c| c.id() == card.id()).unwrap();
+        assert_eq!(card, seeded);
+    }
+    let logs = fixture
+        .storage()
+        .review_logs
+        .logs_for_card(fixture.deck_id(), reviewed_id)
+        .await
+        .expect("load logs");
+    assert_eq!(logs.len(), 1);
+}
+
+#[tokio::test]
+async fn cram_filter_selects_by_tag_and_due_window() {
+    let fixture = ServicesFixture::builder()
+        .with_cards(3)
+        .with_reviews([ReviewGrade::Good, ReviewGrade::Good])
+        .build()
+        .await
+        .expect("build fixture");
+    let ids = fixture.card_ids();
+    let tag = TagName::new("exam").unwrap();
+    for id in [ids[1], ids[2]] {
+        fixture
+            .storage()
+            .cards
+            .set_tags_for_card(fixture.deck_id(), id, std::slice::from_ref(&tag))
+            .await
+            .expect("tag card");
+    }
+    let loop_svc = fixture.session_loop();
+
+    let tagged = loop_svc
+        .start_cram_session(
+            fixture.deck_id(),
+            &CramFilter {
+                tags: vec![tag.clone()],
+                due_within_days: None,
+            },
+        )
+        .await
+        .expect("start tagged cram");
+    assert_eq!(tagged.total_cards(), 2);
+
+    let due_soon = loop_svc
+        .start_cram_session(
+            fixture.deck_id(),
+            &CramFilter {
+                tags: vec![tag],
+                due_within_days: Some(365),
+            },
+        )
+        .await
+        .expect("start due cram");
+    assert_eq!(due_soon.total_cards(), 1);
+    assert_eq!(due_soon.current_card().map(|card| card.id()), Some(ids[1]));
+
+    let err = loop_svc
+        .start_cram_session(
+            fixture.deck_id(),
+            &CramFilter {
+                tags: Vec::new(),
+                due_within_days: Some(0),
+            },
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, SessionError::Empty));
+}
+
+#[tokio::test]
+async fn custom_session_studies_the_chosen_cards_in_order() {
+    let fixture = ServicesFixture::builder()
+        .with_deck("Fixture Deck", single_pass_settings())
+        .with_cards(5)
+        .build()
+        .await
+        .expect("build fixture");
+    let ids = fixture.card_ids();
+    let chosen = [ids[3], ids[0], ids[4]];
+    let loop_svc = fixture.session_loop();
+
+    let mut session = loop_svc
+        .start_custom_session(fixture.deck_id(), &chosen, false)
+        .await
+        .expect("start custom session");
+    assert!(!session.is_cram());
+    assert_eq!(session.total_cards(), 3);
+    let mut studied = Vec::new();
+    while let Some(card) = session.current_card() {
+        studied.push(card.id());
+        loop_svc
+            .answer_current(&mut session, ReviewGrade::Good)
+            .await
+            .expect("answer card");
+    }
+    assert_eq!(studied, chosen);
+
+    let stored = fixture
+        .card_service()
+        .list_cards(fixture.deck_id(), 10)
+        .await
+        .expect("list cards");
+    for card in &stored {
+        let expected = u32::from(chosen.contains(&card.id()));
+        assert_eq!(card.review_count(), expected);
+    }
+
+    let err = loop_svc
+        .start_custom_session(fixture.deck_id(), &[ids[1], CardId::new(9_999)], true)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, SessionError::CardsUnavailable));
+}
+
+#[tokio::test]
+async fn an_empty_due_plan_reports_nothing_due_with_the_next_due_time() {
+    let fixture = ServicesFixture::builder()
+        .with_deck("Fixture Deck", single_pass_settings())
+        .with_cards(2)
+        .build()
+        .await
+        .expect("build fixture");
+    let loop_svc = fixture.session_loop();
+    let mut session = loop_svc.start_session(fixture.deck_id()).await.expect("start session");
+    while !session.is_complete() {
+        loop_svc
+            .answer_current(&mut session, ReviewGrade::Good)
+            .await
+            .expect("answer card");
+    }
+
+    let start = loop_svc
+        .start_or_nothing_due(fixture.deck_id())
+        .await
+        .expect("an empty plan is not an error");
+    let SessionStart::NothingDue(nothing_due) = start else {
+        panic!("expected nothing due, got {start:?}");
+    };
+    let cards = fixture.card_service().list_cards(fixture.deck_id(), 10).await.unwrap();
+    let soonest = cards.iter().map(Card::next_review_at).min();
+    assert_eq!(nothing_due.next_due_at, soonest);
+    assert_eq!(nothing_due.checked_at, fixture.clock().now());
+    assert!(nothing_due.time_until_next().is_some_and(|wait| wait > Duration::zero()));
+
+    let empty = ServicesFixture::builder().build().await.expect("build fixture");
+    let start = empty.session_loop().start_or_nothing_due(empty.deck_id()).await.unwrap();
+    assert!(matches!(
+        start,
+        SessionStart::NothingDue(NothingDue { next_due_at: None, .. })
+    ));
+}
+
+#[tokio::test]
+async fn study_ahead_pulls_cards_due_within_the_window_and_schedules_from_now() {
+    let fixture = ServicesFixture::builder()
+        .with_deck("Fixture Deck", single_pass_settings())
+        .with_cards(2)
+        .build()
+        .await
+        .expect("build fixture");
+    let deck_id = fixture.deck_id();
+    let now = fixture.clock().now();
+    // Each card was last reviewed 4 days before it falls due.
+    let reviewing = |seeded: &Card, due_in_days: i64| {
+        Card::from_persisted(
+            seeded.id(),
+            deck_id,
+            seeded.kind().clone(),
+            seeded.prompt().clone(),
+            seeded.answer().clone(),
+            seeded.created_at(),
+            now + Duration::days(due_in_days),
+            Some(now + Duration::days(due_in_days - 4)),
+            CardPhase::Reviewing,
+            3,
+            4.0,
+            5.0,
+        )
+        .unwrap()
+    };
+    let on_time = reviewing(&fixture.cards()[0], 0);
+    let early = reviewing(&fixture.cards()[1], 3);
+    let store = &fixture.storage().cards;
+    store.upsert_card(&on_time).await.expect("seed due card");
+    store.upsert_card(&early).await.expect("seed card due in 3 days");
+    let loop_svc = fixture.session_loop();
+
+    for within_days in [0, 2] {
+        let session = loop_svc
+            .start_ahead_session(deck_id, within_days)
+            .await
+            .expect("start short ahead session");
+        assert_eq!(session.total_cards(), 1);
+        assert!(!session.is_pending(early.id()));
+    }
+    for within_days in [3, 5, u32::MAX] {
+        let session = loop_svc
+            .start_ahead_session(deck_id, within_days)
+            .await
+            .expect("start ahead session");
+        assert_eq!(session.total_cards(), 2);
+        assert!(session.is_pending(early.id()));
+    }
+
+    let far = reviewing(&fixture.cards()[1], i64::from(SessionLoopService::MAX_AHEAD_DAYS) + 1);
+    store.upsert_card(&far).await.expect("seed card beyond the cap");
+    let capped = loop_svc
+        .start_ahead_session(deck_id, u32::MAX)
+        .await
+        .expect("start capped ahead session");
+    assert!(!capped.is_pending(far.id()));
+    store.upsert_card(&early).await.expect("restore card due in 3 days");
+
+    let mut session = loop_svc
+        .start_ahead_session(deck_id, 3)
+        .await
+        .expect("start ahead session");
+    assert!(session.is_ahead());
+    while !session.is_complete() {
+        loop_svc
+            .answer_current(&mut session, ReviewGrade::Good)
+            .await
+            .expect("answer card");
+    }
+    let summary = fixture
+        .session_summaries()
+        .get_summary(session.summary_id().expect("summary persisted"))
+        .await
+        .expect("load summary");
+    assert!(summary.is_ahead());
+    assert!(!summary.is_cram());
+    assert_eq!(summary.good(), 2);
+
+    let graded = store.get_cards(deck_id, &[on_time.id(), early.id()]).await.unwrap();
+    let graded_early = graded.iter().find(|card| card.id() == early.id()).unwrap();
+    let graded_on_time = graded.iter().find(|card| card.id() == on_time.id()).unwrap();
+    assert_eq!(graded_early.last_review_at(), Some(now));
+    assert!(graded_early.next_review_at() > now);
+    let stability = |card: &Card| card.memory_state().expect("reviewed").stability;
+    assert!(stability(graded_early) < stability(graded_on_time));
+}
+
+#[tokio::test]
+async fn suspended_cards_stay_out_of_session_queues() {
+    let fixture = ServicesFixture::builder()
+        .with_cards(3)
+        .build()
+        .await
+        .expect("build fixture");
+    let ids = fixture.card_ids();
+    let cards = fixture.card_service();
+    cards
+        .set_suspended(fixture.deck_id(), ids[0], true)
+        .await
+        .expect("suspend card");
+
+    let loop_svc = fixture.session_loop();
+    let mut session = loop_svc
+        .start_session(fixture.deck_id())
+        .await
+        .expect("start session");
+    assert_eq!(session.total_cards(), 2);
+    while let Some(card_id) = session.current_card().map(|card| card.id()) {
+        assert_ne!(card_id, ids[0]);
+        loop_svc
+            .answer_current(&mut session, ReviewGrade::Good)
+            .await
+            .expect("answer card");
+    }
+
+    let cram = loop_svc
+        .start_cram_session(fixture.deck_id(), &CramFilter::default())
+        .await
+        .expect("start cram");
+    assert_eq!(cram.total_cards(), 2);
+
+    let stats = cards
+        .deck_practice_stats(fixture.deck_id())
+        .await
+        .expect("practice stats");
+    assert_eq!((stats.total, stats.new), (3, 0));
+
+    let listed = cards
+        .list_cards(fixture.deck_id(), 10)
+        .await
+        .expect("list cards");
+    assert_eq!(listed.len(), 3);
+    assert!(listed.iter().any(|card| card.id() == ids[0] && card.is_suspended()));
+
+    cards
+        .set_suspended(fixture.deck_id(), ids[0], false)
+        .await
+        .expect("resume card");
+    let resumed = loop_svc
+        .start_session(fixture.deck_id())
+        .await
+        .expect("start session after resume");
+    assert_eq!(resumed.current_card().map(|card| card.id()), Some(ids[0]));
+}
+
+#[tokio::test]
+async fn flagging_the_current_card_survives_answering_it() {
+    let fixture = ServicesFixture::builder()
+        .with_deck("Fixture Deck", single_pass_settings())
+        .with_cards(2)
+        .build()
+        .await
+        .expect("build fixture");
+    let loop_svc = fixture.session_loop();
+    let mut session = loop_svc
+        .start_session(fixture.deck_id())
+        .await
+        .expect("start session");
+    let card_id = session.current_card().map(Card::id).expect("current card");
+
+    loop_svc
+        .flag_current(&mut session, Some(Flag::Orange))
+        .await
+        .expect("flag card");
+    assert_eq!(session.current_card().and_then(Card::flag), Some(Flag::Orange));
+    loop_svc
+        .answer_current(&mut session, ReviewGrade::Good)
+        .await
+        .expect("answer card");
+
+    let cards = fixture
+        .card_service()
+        .list_cards(fixture.deck_id(), 10)
+        .await
+        .expect("list cards");
+    let flagged = cards.iter().find(|card| card.id() == card_id).expect("flagged card");
+    assert_eq!((flagged.flag(), flagged.review_count()), (Some(Flag::Orange), 1));
+    assert!(cards.iter().all(|card| card.id() == card_id || card.flag().is_none()));
+}
+
+#[tokio::test]
+async fn skipped_card_returns_at_the_end_until_the_skip_cap() {
+    let fixture = ServicesFixture::builder()
+        .with_deck("Skip Deck", single_pass_settings())
+        .with_cards(3)
+        .build()
+        .await
+        .expect("build fixture");
+    let loop_svc = fixture.session_loop();
+    let mut session = loop_svc
+        .start_session(fixture.deck_id())
+        .await
+        .expect("start session");
+    let queue = |session: &SessionService| -> Vec<CardId> {
+        session.pending_cards().iter().map(Card::id).collect()
+    };
+    let order = queue(&session);
+
+    loop_svc.skip_card(&mut session).await.expect("skip card");
+    assert_eq!(queue(&session), [order[1], order[2], order[0]]);
+    assert_eq!(session.answered_count(), 0);
+    let stored = fixture
+        .card_service()
+        .list_cards(fixture.deck_id(), 10)
+        .await
+        .expect("list cards");
+    assert!(stored.iter().all(|card| card.review_count() == 0));
+
+    for _ in 0..2 {
+        loop_svc
+            .answer_current(&mut session, ReviewGrade::Good)
+            .await
+            .expect("answer card");
+    }
+    assert_eq!(queue(&session), [order[0]]);
+    for _ in 1..SessionService::MAX_SKIPS {
+        loop_svc.skip_card(&mut session).await.expect("skip last card");
+    }
+    assert!(!session.can_skip());
+    let err = loop_svc.skip_card(&mut session).await.unwrap_err();
+    assert!(matches!(err, SessionError::SkipLimitReached));
+
+    let result = loop_svc
+        .answer_current(&mut session, ReviewGrade::Good)
+        .await
+        .expect("grade skipped card");
+    assert!(result.is_complete);
+}
+
+#[tokio::test]
+async fn buried_card_returns_on_the_next_day() {
+    let fixture = ServicesFixture::builder()
+        .with_cards(2)
+        .build()
+        .await
+        .expect("build fixture");
+    let ids = fixture.card_ids();
+    let loop_svc = fixture.session_loop();
+    let mut session = loop_svc
+        .start_session(fixture.deck_id())
+        .await
+        .expect("start session");
+    assert!(session.is_pending(ids[1]));
+
+    loop_svc
+        .bury_card(&mut session, ids[1])
+        .await
+        .expect("bury card");
+    assert!(!session.is_pending(ids[1]));
+    assert_eq!(session.total_cards(), 1);
+    let buried = fixture
+        .card_service()
+        .list_cards(fixture.deck_id(), 10)
+        .await
+        .expect("list cards");
+    assert!(buried.iter().any(|card| card.id() == ids[1] && !card.is_suspended()));
+
+    let same_day = loop_svc
+        .start_session(fixture.deck_id())
+        .await
+        .expect("start same-day session");
+    assert_eq!(same_day.total_cards(), 1);
+    assert!(!same_day.is_pending(ids[1]));
+
+    let mut clock = fixture.clock();
+    clock.advance(chrono::Duration::days(1));
+    let next_day = SessionLoopService::new(
+        clock,
+        Arc::clone(&fixture.storage().decks),
+        Arc::clone(&fixture.storage().cards),
+        Arc::clone(&fixture.storage().reviews),
+        Arc::clone(&fixture.storage().session_summaries),
+    )
+    .start_session(fixture.deck_id())
+    .await
+    .expect("start next-day session");
+    assert!(next_day.is_pending(ids[1]));
+}
+
+#[tokio::test]
+async fn queued_card_buries_its_note_sibling_until_the_next_day() {
+    let fixture = ServicesFixture::builder()
+        .with_cards(2)
+        .build()
+        .await
+        .expect("build fixture");
+    let ids = fixture.card_ids();
+    let note = Some(NoteId::new(1));
+    for card in fixture
+        .card_service()
+        .list_cards(fixture.deck_id(), 10)
+        .await
+        .expect("list cards")
+    {
+        fixture
+            .storage()
+            .cards
+            .upsert_card(&card.with_note_id(note))
+            .await
+            .expect("link sibling");
+    }
+
+    let loop_svc = fixture.session_loop();
+    let session = loop_svc
+        .start_session(fixture.deck_id())
+        .await
+        .expect("start session");
+    assert_eq!(session.total_cards(), 1);
+    let sibling = ids
+        .iter()
+        .copied()
+        .find(|id| !session.is_pending(*id))
+        .expect("one sibling held back");
+    let cards = fixture
+        .card_service()
+        .list_cards(fixture.deck_id(), 10)
+        .await
+        .expect("list cards");
+    let buried = cards.iter().find(|card| card.id() == sibling).expect("sibling");
+    assert!(buried.is_buried_at(fixture.clock().now()));
+
+    let same_day = loop_svc
+        .start_session(fixture.deck_id())
+        .await
+        .expect("start same-day session");
+    assert!(!same_day.is_pending(sibling));
+}
+
+#[tokio::test]
+async fn progress_counts_requeued_learning_cards_and_drops_buried_ones() {
+    let fixture = ServicesFixture::builder()
+        .with_cards(3)
+        .build()
+        .await
+        .expect("build fixture");
+    let ids = fixture.card_ids();
+    let loop_svc = fixture.session_loop();
+    let mut session = loop_svc
+        .start_session(fixture.deck_id())
+        .await
+        .expect("start session");
+    let counts = |session: &services::SessionService| {
+        let progress = loop_svc.progress(session);
+        (progress.answered, progress.remaining)
+    };
+    assert_eq!(counts(&session), (0, 3));
+    assert_eq!(loop_svc.progress(&session).daily_target, 35);
+
+    // Good on a new card moves it to the 10m step, so it stays queued for later.
+    loop_svc
+        .answer_current(&mut session, ReviewGrade::Good)
+        .await
+        .expect("answer card");
+    assert_eq!(counts(&session), (1, 3));
+
+    loop_svc
+        .bury_card(&mut session, ids[2])
+        .await
+        .expect("bury card");
+    assert_eq!(counts(&session), (1, 2));
+    assert_eq!(loop_svc.progress(&session).percent_answered(), 33);
+
+    while !session.is_complete() {
+        loop_svc
+            .answer_current(&mut session, ReviewGrade::Easy)
+            .await
+            .expect("answer card");
+    }
+    let done = loop_svc.progress(&session);
+    assert_eq!((done.answered, done.remaining), (3, 0));
+    assert_eq!(done.percent_answered(), 100);
+}
+
+#[tokio::test]
+async fn resume_continues_a_session_after_restart() {
+    let fixture = ServicesFixture::builder()
+        .with_deck("Fixture Deck", single_pass_settings())
+        .with_cards(3)
+        .build()
+        .await
+        .expect("build fixture");
+
+    let remaining = {
+        let loop_svc = fixture.session_loop();
+        let mut session = loop_svc
+            .start_session(fixture.deck_id())
+            .await
+            .expect("start session");
+        loop_svc
+            .answer_current(&mut session, ReviewGrade::Hard)
+            .await
+            .expect("answer card");
+        session.pending_cards().iter().map(|card| card.id()).collect::<Vec<_>>()
+    };
+    assert_eq!(remaining.len(), 2);
+
+    let loop_svc = fixture.session_loop();
+    assert!(loop_svc.has_saved_session(fixture.deck_id()).await.unwrap());
+    let mut session = loop_svc
+        .resume(fixture.deck_id())
+        .await
+        .expect("resume session")
+        .expect("saved session");
+    let pending: Vec<_> = session.pending_cards().iter().map(|card| card.id()).collect();
+    assert_eq!(pending, remaining);
+    assert_eq!(session.answered_count(), 1);
+    assert_eq!(session.total_cards(), 3);
+    assert!(!session.can_undo());
+
+    while !session.is_complete() {
+        loop_svc
+            .answer_current(&mut session, ReviewGrade::Good)
+            .await
+            .expect("answer card");
+    }
+    let summary = fixture
+        .session_summaries()
+        .get_summary(session.summary_id().expect("summary persisted"))
+        .await
+        .expect("load summary");
+    assert_eq!(summary.total_reviews(), 3);
+    assert_eq!(summary.hard(), 1);
+    assert_eq!(summary.good(), 2);
+
+    assert!(!loop_svc.has_saved_session(fixture.deck_id()).await.unwrap());
+    assert!(loop_svc.resume(fixture.deck_id()).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn resume_skips_cards_that_changed_while_away() {
+    let fixture = ServicesFixture::builder()
+        .with_deck("Fixture Deck", single_pass_settings())
+        .with_cards(3)
+        .build()
+        .await
+        .expect("build fixture");
+
+    let loop_svc = fixture.session_loop();
+    let session = loop_svc
+        .start_session(fixture.deck_id())
+        .await
+        .expect("start session");
+    let mut reviewed = session.pending_cards()[0].clone();
+    let suspended = session.pending_cards()[1].id();
+    let kept = session.pending_cards()[2].id();
+
+    fixture
+        .review_service()
+        .expect("review service")
+        .review_card_persisted_with_settings(
+            &mut reviewed,
+            ReviewGrade::Easy,
+            fixture.clock().now(),
+            fixture.deck().settings(),
+            fixture.storage().reviews.as_ref(),
+        )
+        .await
+        .expect("review elsewhere");
+    fixture
+        .card_service()
+        .set_suspended(fixture.deck_id(), suspended, true)
+        .await
+        .expect("suspend card");
+
+    let resumed = fixture
+        .session_loop()
+        .resume(fixture.deck_id())
+        .await
+        .expect("resume session")
+        .expect("saved session");
+    let pending: Vec<_> = resumed.pending_cards().iter().map(|card| card.id()).collect();
+    assert_eq!(pending, vec![kept]);
+    assert_eq!(resumed.answered_count(), 0);
+}
+
+#[tokio::test]
+async fn two_button_grades_schedule_like_their_four_button_equivalents() {
+    let build = |buttons: AnswerButtons| async move {
+        let settings = DeckSettings::default_for_adhd().with_answer_buttons(buttons);
+        ServicesFixture::builder()
+            .with_deck("Fixture Deck", settings)
+            .with_cards(2)
+            .build()
+            .await
+            .expect("build fixture")
+    };
+    let four = build(AnswerButtons::Four).await;
+    let two = build(AnswerButtons::Two).await;
+
+    let four_loop = four.session_loop();
+    let two_loop = two.session_loop();
+    let mut four_session = four_loop.start_session(four.deck_id()).await.expect("start");
+    let mut two_session = two_loop.start_session(two.deck_id()).await.expect("start");
+
+    let preview = two_loop.preview_current(&two_session).expect("preview");
+    let previewed: Vec<_> = preview.grades.iter().map(|grade| grade.grade).collect();
+    assert_eq!(previewed, vec![ReviewGrade::Again, ReviewGrade::Good]);
+    let err = two_loop.answer_button(&mut two_session, 2).await.unwrap_err();
+    assert!(matches!(err, SessionError::UnknownAnswerButton(2)));
+
+    for (button, grade) in [(1, ReviewGrade::Good), (0, ReviewGrade::Again)] {
+        let four_answer = four_loop
+            .answer_current(&mut four_session, grade)
+            .await
+            .expect("four-button answer");
+        let two_answer = two_loop
+            .answer_button(&mut two_session, button)
+            .await
+            .expect("two-button answer");
+        assert_eq!(two_answer.review.result.applied.log.grade, grade);
+        assert_eq!(two_answer.review.result, four_answer.review.result);
+    }
+}
+
+#[tokio::test]
+async fn lapsing_past_the_leech_threshold_tags_and_suspends_the_card() {
+    let settings = single_pass_settings().with_leech(2, LeechAction::Suspend);
+    let fixture = ServicesFixture::builder()
+        .with_deck("Fixture Deck", settings)
+        .with_cards(1)
+        .build()
+        .await
+        .expect("build fixture");
+    let deck_id = fixture.deck_id();
+    let seeded = &fixture.cards()[0];
+    let now = fixture.clock().now();
+    let reviewing = Card::from_persisted(
+        seeded.id(),
+        deck_id,
+        seeded.kind().clone(),
+        seeded.prompt().clone(),
+        seeded.answer().clone(),
+        seeded.created_at(),
+        now - Duration::hours(1),
+        Some(now - Duration::days(5)),
+        CardPhase::Reviewing,
+        3,
+        5.0,
+        5.0,
+    )
+    .unwrap()
+    .with_lapses(1);
+    let store = &fixture.storage().cards;
+    store.upsert_card(&reviewing).await.expect("seed reviewing card");
+    let leech_state = || async {
+        let card = store.get_cards(deck_id, &[seeded.id()]).await.unwrap().remove(0);
+        let tags = store.list_tags_for_card(deck_id, seeded.id()).await.unwrap();
+        let tagged = tags.iter().any(|tag| tag.name() == &TagName::leech());
+        (card.lapses(), tagged, card.is_suspended())
+    };
+
+    let loop_svc = fixture.session_loop();
+    let mut session = loop_svc.start_session(deck_id).await.expect("start session");
+    loop_svc
+        .answer_current(&mut session, ReviewGrade::Again)
+        .await
+        .expect("lapse card");
+    assert_eq!(leech_state().await, (2, true, true));
+
+    loop_svc.undo_last_answer(&mut session).await.expect("undo lapse");
+    assert_eq!(leech_state().await, (1, false, false));
+
+    loop_svc
+        .answer_current(&mut session, ReviewGrade::Again)
+        .await
+        .expect("lapse card again");
+    assert_eq!(leech_state().await, (2, true, true));
+    let err = loop_svc.start_session(deck_id).await.unwrap_err();
+    assert!(matches!(err, SessionError::Empty));
+}
+
+#[tokio::test]
+async fn repeated_agains_take_a_card_out_of_the_session_at_the_fail_limit() {
+    let settings = DeckSettings::default_for_adhd().with_same_session_fail_limit(3);
+    let fixture = ServicesFixture::builder()
+        .with_deck("Fixture Deck", settings)
+        .with_cards(1)
+        .build()
+        .await
+        .expect("build fixture");
+    let deck_id = fixture.deck_id();
+    let card_id = fixture.card_ids()[0];
+    let loop_svc = fixture.session_loop();
+    let mut session = loop_svc.start_session(deck_id).await.expect("start session");
+
+    for attempt in 1..=2 {
+        let answer = loop_svc
+            .answer_current(&mut session, ReviewGrade::Again)
+            .await
+            .expect("answer again");
+        assert!(!answer.failed_out, "attempt {attempt}");
+        assert!(session.is_pending(card_id));
+    }
+    let answer = loop_svc
+        .answer_current(&mut session, ReviewGrade::Again)
+        .await
+        .expect("answer again");
+    assert!(answer.failed_out);
+    assert!(answer.is_complete);
+    assert!(!session.is_pending(card_id));
+    assert_eq!(session.answered_count(), 3);
+    let tags = fixture
+        .storage()
+        .cards
+        .list_tags_for_card(deck_id, card_id)
+        .await
+        .expect("list tags");
+    assert!(tags.iter().any(|tag| tag.name() == &TagName::leech()));
+
+    // The count starts over in the next session.
+    let mut next = loop_svc
+        .start_session_all_cards(deck_id)
+        .await
+        .expect("start next session");
+    let answer = loop_svc
+        .answer_current(&mut next, ReviewGrade::Again)
+        .await
+        .expect("answer again");
+    assert!(!answer.failed_out);
+    assert!(next.is_pending(card_id));
+}
+
+#[tokio::test]
+async fn answer_times_are_measured_from_showing_the_card_and_capped() {
+    let fixture = ServicesFixture::builder()
+        .with_deck("Fixture Deck", single_pass_settings())
+        .with_cards(2)
+        .build()
+        .await
+        .expect("build fixture");
+    let deck_id = fixture.deck_id();
+    let ids = fixture.card_ids();
+    let loop_at = |elapsed: Duration| {
+        let mut clock = fixture.clock();
+        clock.advance(elapsed);
+        SessionLoopService::new(
+            clock,
+            Arc::clone(&fixture.storage().decks),
+            Arc::clone(&fixture.storage().cards),
+            Arc::clone(&fixture.storage().reviews),
+            Arc::clone(&fixture.storage().session_summaries),
+        )
+    };
+    let review_logs = &fixture.storage().review_logs;
+    let answer_ms = |card_id| async move {
+        let logs = review_logs.logs_for_card(deck_id, card_id).await;
+        logs.unwrap().last().and_then(|log| log.answer_ms)
+    };
+
+    let mut session = fixture.session_loop().start_session(deck_id).await.expect("start");
+    loop_at(Duration::seconds(12))
+        .answer_current(&mut session, ReviewGrade::Good)
+        .await
+        .expect("answer first card");
+    assert_eq!(answer_ms(ids[0]).await, Some(12_000));
+
+    loop_at(Duration::minutes(10))
+        .answer_current(&mut session, ReviewGrade::Good)
+        .await
+        .expect("answer second card");
+    assert_eq!(answer_ms(ids[1]).await, Some(60_000));
+
+    loop_at(Duration::minutes(20))
+        .undo_last_answer(&mut session)
+        .await
+        .expect("undo second answer");
+    loop_at(Duration::minutes(20) + Duration::seconds(30))
+        .with_max_answer_time(Duration::seconds(5))
+        .answer_current(&mut session, ReviewGrade::Good)
+        .await
+        .expect("answer second card again");
+    assert_eq!(answer_ms(ids[1]).await, Some(5_000));
+}
+
+#[tokio::test]
+async fn peek_plan_matches_the_cards_a_started_session_serves() {
+    let fixture = ServicesFixture::builder()
+        .with_deck("Fixture Deck", single_pass_settings())
+        .with_cards(8)
+        .with_reviews([ReviewGrade::Again, ReviewGrade::Good])
+        .build()
+        .await
+        .expect("build fixture");
+    // Late enough for both reviewed cards to be due again.
+    let later = Clock::fixed(fixture.clock().now() + Duration::days(30));
+    let storage = fixture.storage();
+    let loop_svc = SessionLoopService::new(
+        later,
+        Arc::clone(&storage.decks),
+        Arc::clone(&storage.cards),
+        Arc::clone(&storage.reviews),
+        Arc::clone(&storage.session_summaries),
+    )
+    .with_progress(Arc::clone(&storage.session_progress));
+
+    let preview = loop_svc.peek_plan(fixture.deck_id()).await.expect("peek plan");
+    // Two due reviews and five new cards under the daily caps, cut to a micro-session of five.
+    assert_eq!(preview.card_ids.len(), 5);
+    assert_eq!(preview.reviews + preview.learning, 2);
+    assert_eq!(preview.new, 3);
+    assert!(!loop_svc.has_saved_session(fixture.deck_id()).await.unwrap());
+    assert_eq!(loop_svc.peek_plan(fixture.deck_id()).await.unwrap(), preview);
+
+    let session = loop_svc.start_session(fixture.deck_id()).await.expect("start session");
+    let served: Vec<_> = session.pending_cards().iter().map(Card::id).collect();
+    assert_eq!(served, preview.card_ids);
+}
+
+#[tokio::test]
+async fn reviewing_a_pruned_mature_card_leaves_the_new_card_allowance_intact() {
+    let fixture = ServicesFixture::builder()
+        .with_deck("Fixture Deck", single_pass_settings())
+        .with_cards(8)
+        .with_reviews([ReviewGrade::Good])
+        .build()
+        .await
+        .expect("build fixture");
+    let later = Clock::fixed(fixture.clock().now() + Duration::days(30));
+    let storage = fixture.storage();
+    fixture
+        .review_service()
+        .unwrap()
+        .with_clock(later)
+        .review_card_persisted_by_id(
+            fixture.deck_id(),
+            fixture.card_ids()[0],
+            storage.cards.as_ref(),
+            storage.reviews.as_ref(),
+            ReviewGrade::Good,
+        )
+        .await
+        .expect("review mature card");
+    // Only today's review of the mature card survives.
+    storage
+        .review_logs
+        .prune(fixture.deck_id(), ReviewLogPrune::KeepLastPerCard(1))
+        .await
+        .expect("prune");
+
+    let loop_svc = SessionLoopService::new(
+        later,
+        Arc::clone(&storage.decks),
+        Arc::clone(&storage.cards),
+        Arc::clone(&storage.reviews),
+        Arc::clone(&storage.session_summaries),
+    )
+    .with_review_logs(Arc::clone(&storage.review_logs));
+    let preview = loop_svc.peek_plan(fixture.deck_id()).await.expect("peek plan");
+    assert_eq!(preview.new, 5);
+}
@@ -1,7 +1,8 @@
 use learn_core::model::{
-    Card, CardId, ContentDraft, Deck, DeckId, DeckSettings, ReviewGrade, SessionSummary,
+    Card, CardId, CardKind, ContentDraft, Deck, DeckId, DeckSettings, ReviewGrade, SessionSummary,
 };
 use learn_core::time::fixed_now;
+use services::test_support::single_pass_settings;
 use services::{Clock, SessionLoopService};
 use storage::repository::{
     CardRepository, DeckRepository, InMemoryRepository, SessionSummaryRepository, SessionSummaryRow,
@@ -39,6 +40,10 @@ impl SessionSummaryRepository for FlakySummaryRepo {
         self.inner.get_summary(id).await
     }
 
+    async fn delete_summary(&self, id: i64) -> Result<(), StorageError> {
+        self.inner.delete_summary(id).await
+    }
+
     async fn list_summaries(
         &self,
         deck_id: DeckId,
@@ -69,6 +74,16 @@ impl SessionSummaryRepository for FlakySummaryRepo {
     ) -> Result<Vec<SessionSummaryRow>, StorageError> {
         self.inner.list_latest_summary_rows(deck_ids).await
     }
+
+    async fn list_summaries_between(
+        &self,
+        completed_from: chrono::DateTime<chrono::Utc>,
+        completed_until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<SessionSummary>, StorageError> {
+        self.inner
+            .list_summaries_between(completed_from, completed_until)
+            .await
+    }
 }
 
 #[tokio::test]
@@ -81,7 +96,7 @@ async fn session_loop_persists_summary() {
         deck_id,
         "Smoke Deck",
         None,
-        DeckSettings::default_for_adhd(),
+        single_pass_settings(),
         now,
     )
     .unwrap();
@@ -94,7 +109,16 @@ async fn session_loop_persists_summary() {
         let answer = ContentDraft::text_only(format!("A{id}"))
             .validate(now, None, None)
             .unwrap();
-        let card = Card::new(CardId::new(id), deck_id, prompt, answer, now, now).unwrap();
+        let card = Card::new(
+            CardId::new(id),
+            deck_id,
+            CardKind::Basic,
+            prompt,
+            answer,
+            now,
+            now,
+        )
+        .unwrap();
         repo.upsert_card(&card).await.unwrap();
     }
 
@@ -131,7 +155,7 @@ async fn session_loop_retry_persists_summary_after_failure() {
         deck_id,
         "Retry Deck",
         None,
-        DeckSettings::default_for_adhd(),
+        single_pass_settings(),
         now,
     )
     .unwrap();
@@ -143,7 +167,16 @@ async fn session_loop_retry_persists_summary_after_failure() {
     let answer = ContentDraft::text_only("A1")
         .validate(now, None, None)
         .unwrap();
-    let card = Card::new(CardId::new(1), deck_id, prompt, answer, now, now).unwrap();
+    let card = Card::new(
+        CardId::new(1),
+        deck_id,
+        CardKind::Basic,
+        prompt,
+        answer,
+        now,
+        now,
+    )
+    .unwrap();
     repo.upsert_card(&card).await.unwrap();
 
     let loop_svc = SessionLoopService::new(
@@ -167,3 +200,48 @@ async fn session_loop_retry_persists_summary_after_failure() {
     let summary = summaries_repo.get_summary(summary_id).await.unwrap();
     assert_eq!(summary.total_reviews(), 1);
 }
+
+#[tokio::test]
+async fn session_loop_shrinks_new_card_intake_on_easy_days() {
+    let repo = InMemoryRepository::new();
+    let deck_id = DeckId::new(1);
+    let now = fixed_now();
+    let weekend = DeckSettings::default_for_adhd().easy_days_mask();
+    let settings = DeckSettings::new(
+        6, 30, 20, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, true, 0.5,
+        weekend, 0.85, true, 100,
+    )
+    .unwrap();
+    let deck = Deck::new(deck_id, "Easy Deck", None, settings, now).unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+
+    for id in 1..=10 {
+        let prompt = ContentDraft::text_only(format!("Q{id}"))
+            .validate(now, None, None)
+            .unwrap();
+        let answer = ContentDraft::text_only(format!("A{id}"))
+            .validate(now, None, None)
+            .unwrap();
+        let card = Card::new(CardId::new(id), deck_id, CardKind::Basic, prompt, answer, now, now)
+            .unwrap();
+        repo.upsert_card(&card).await.unwrap();
+    }
+
+    let loop_for = |clock: Clock| {
+        SessionLoopService::new(
+            clock,
+            Arc::new(repo.clone()),
+            Arc::new(repo.clone()),
+            Arc::new(repo.clone()),
+            Arc::new(repo.clone()),
+        )
+    };
+    // `fixed_now` is a Tuesday; four days later is a Saturday.
+    let saturday = now + chrono::Duration::days(4);
+
+    let weekday = loop_for(Clock::fixed(now)).start_session(deck_id).await.unwrap();
+    let easy = loop_for(Clock::fixed(saturday)).start_session(deck_id).await.unwrap();
+
+    assert_eq!(weekday.total_cards(), 6);
+    assert_eq!(easy.total_cards(), 3);
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "tracing")]
+
+use std::sync::{Arc, Mutex};
+
+use learn_core::model::{ContentDraft, ReviewGrade};
+use services::test_support::ServicesFixture;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+
+#[derive(Debug, Clone)]
+struct CapturedSpan {
+    id: Id,
+    name: &'static str,
+    fields: Vec<(String, String)>,
+}
+
+impl CapturedSpan {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+struct FieldVisitor<'a>(&'a mut Vec<(String, String)>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push((field.name().to_owned(), format!("{value:?}")));
+    }
+}
+
+/// Keeps every span it sees, with the fields recorded at creation and later.
+#[derive(Clone, Default)]
+struct CapturingLayer {
+    spans: Arc<Mutex<Vec<CapturedSpan>>>,
+}
+
+impl CapturingLayer {
+    fn spans(&self) -> Vec<CapturedSpan> {
+        self.spans.lock().unwrap().clone()
+    }
+}
+
+impl<S> Layer<S> for CapturingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+        let mut fields = Vec::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+        self.spans.lock().unwrap().push(CapturedSpan {
+            id: id.clone(),
+            name: attrs.metadata().name(),
+            fields,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        let mut spans = self.spans.lock().unwrap();
+        if let Some(span) = spans.iter_mut().rev().find(|span| span.id == *id) {
+            values.record(&mut FieldVisitor(&mut span.fields));
+        }
+    }
+}
+
+#[tokio::test]
+async fn card_creation_and_review_emit_spans_with_ids_but_no_content() {
+    let fixture = ServicesFixture::builder().build().await.expect("build fixture");
+    let layer = CapturingLayer::default();
+    let subscriber = tracing_subscriber::registry().with(layer.clone());
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let card_id = fixture
+        .card_service()
+        .create_card(
+            fixture.deck_id(),
+            ContentDraft::text_only("private prompt"),
+            ContentDraft::text_only("private answer"),
+            None,
+        )
+        .await
+        .expect("create card")
+        .id;
+    let loop_svc = fixture.session_loop();
+    let mut session = loop_svc.start_session(fixture.deck_id()).await.expect("start session");
+    loop_svc
+        .answer_current(&mut session, ReviewGrade::Good)
+        .await
+        .expect("answer card");
+
+    let spans = layer.spans();
+    let deck_id = fixture.deck_id().value().to_string();
+    let card_id = card_id.value().to_string();
+
+    let created = spans.iter().find(|span| span.name == "create_card").expect("create span");
+    assert_eq!(created.field("deck_id"), Some(deck_id.as_str()));
+    assert_eq!(created.field("card_id"), Some(card_id.as_str()));
+
+    let answered = spans.iter().find(|span| span.name == "answer_card").expect("review span");
+    assert_eq!(answered.field("deck_id"), Some(deck_id.as_str()));
+    assert_eq!(answered.field("card_id"), Some(card_id.as_str()));
+    assert_eq!(answered.field("grade"), Some("Good"));
+
+    assert!(
+        spans
+            .iter()
+            .flat_map(|span| &span.fields)
+            .all(|(_, value)| !value.contains("private")),
+        "spans must not carry card content"
+    );
+}
@@ -1,7 +1,7 @@
 use std::fmt;
 
 use chrono::{DateTime, Duration, Utc};
-use learn_core::model::{Card, CardId, ContentDraft, Deck, DeckId, DeckSettings, SessionSummary};
+use learn_core::model::{Card, CardId, CardKind, ContentDraft, Deck, DeckId, DeckSettings, SessionSummary};
 use storage::repository::{NewDeckRecord, Storage};
 
 #[derive(Debug, Clone)]
@@ -206,6 +206,7 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
         let card = Card::new(
             CardId::new(u64::from(i + 1)),
             deck_id,
+            CardKind::Basic,
             prompt,
             answer,
             now,
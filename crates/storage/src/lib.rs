@@ -1,2 +1,4 @@
+#[cfg(feature = "postgres")]
+pub mod postgres;
 pub mod repository;
 pub mod sqlite;
@@ -0,0 +1,186 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+
+use crate::repository::{
+    AiUsageCompletion, AiUsageRecord, AiUsageRepository, AiUsageStatus, NewAiUsageRecord,
+    StorageError,
+};
+
+use super::mapping::parse_ai_usage_status;
+use super::PostgresRepository;
+
+#[async_trait]
+impl AiUsageRepository for PostgresRepository {
+    async fn insert_started(&self, record: NewAiUsageRecord) -> Result<i64, StorageError> {
+        let id: i64 = sqlx::query_scalar(
+            r"
+            INSERT INTO ai_usage (provider, model, created_at, status)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            ",
+        )
+        .bind(record.provider)
+        .bind(record.model)
+        .bind(record.created_at)
+        .bind(status_to_str(AiUsageStatus::Started))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| StorageError::Connection(err.to_string()))?;
+
+        Ok(id)
+    }
+
+    async fn update_completion(
+        &self,
+        id: i64,
+        completion: AiUsageCompletion,
+    ) -> Result<(), StorageError> {
+        let result = sqlx::query(
+            r"
+            UPDATE ai_usage
+            SET status = $1,
+                prompt_tokens = $2,
+                completion_tokens = $3,
+                total_tokens = $4,
+                cost_micro_usd = $5
+            WHERE id = $6
+            ",
+        )
+        .bind(status_to_str(completion.status))
+        .bind(completion.prompt_tokens.map(i64::from))
+        .bind(completion.completion_tokens.map(i64::from))
+        .bind(completion.total_tokens.map(i64::from))
+        .bind(completion.cost_micro_usd.map(|val| {
+            i64::try_from(val).unwrap_or(i64::MAX)
+        }))
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|err| StorageError::Connection(err.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn count_since(&self, since: DateTime<Utc>) -> Result<u32, StorageError> {
+        let row = sqlx::query(
+            r"
+            SELECT COUNT(*) as count
+            FROM ai_usage
+            WHERE created_at >= $1
+            ",
+        )
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| StorageError::Connection(err.to_string()))?;
+
+        let count: i64 = row
+            .try_get("count")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        Ok(u32::try_from(count).unwrap_or(u32::MAX))
+    }
+
+    async fn last_request_at(&self) -> Result<Option<DateTime<Utc>>, StorageError> {
+        let row = sqlx::query(
+            r"
+            SELECT MAX(created_at) as last_request_at
+            FROM ai_usage
+            ",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| StorageError::Connection(err.to_string()))?;
+
+        let last: Option<DateTime<Utc>> = row
+            .try_get("last_request_at")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        Ok(last)
+    }
+
+    async fn sum_cost_since(&self, since: DateTime<Utc>) -> Result<u64, StorageError> {
+        let row = sqlx::query(
+            r"
+            SELECT CAST(COALESCE(SUM(cost_micro_usd), 0) AS BIGINT) as total_cost
+            FROM ai_usage
+            WHERE created_at >= $1
+            ",
+        )
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|err| StorageError::Connection(err.to_string()))?;
+
+        let total: i64 = row
+            .try_get("total_cost")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        Ok(u64::try_from(total).unwrap_or(u64::MAX))
+    }
+
+    async fn list_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<AiUsageRecord>, StorageError> {
+        let rows = sqlx::query(
+            r"
+            SELECT id, provider, model, created_at, status,
+                   prompt_tokens, completion_tokens, total_tokens, cost_micro_usd
+            FROM ai_usage
+            WHERE created_at >= $1 AND created_at < $2
+            ORDER BY created_at ASC, id ASC
+            ",
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| StorageError::Connection(err.to_string()))?;
+
+        rows.iter().map(map_usage_row).collect()
+    }
+}
+
+fn status_to_str(status: AiUsageStatus) -> &'static str {
+    match status {
+        AiUsageStatus::Started => "started",
+        AiUsageStatus::Succeeded => "succeeded",
+        AiUsageStatus::Failed => "failed",
+    }
+}
+
+fn map_usage_row(row: &sqlx::postgres::PgRow) -> Result<AiUsageRecord, StorageError> {
+    let ser = |err: sqlx::Error| StorageError::Serialization(err.to_string());
+    let tokens = |column: &str| -> Result<Option<u32>, StorageError> {
+        row.try_get::<Option<i64>, _>(column)
+            .map_err(ser)?
+            .map(|value| {
+                u32::try_from(value)
+                    .map_err(|_| StorageError::Serialization(format!("{column} out of range")))
+            })
+            .transpose()
+    };
+    let cost = row
+        .try_get::<Option<i64>, _>("cost_micro_usd")
+        .map_err(ser)?
+        .map(|value| {
+            u64::try_from(value)
+                .map_err(|_| StorageError::Serialization("cost_micro_usd sign overflow".into()))
+        })
+        .transpose()?;
+    Ok(AiUsageRecord {
+        id: row.try_get("id").map_err(ser)?,
+        provider: row.try_get("provider").map_err(ser)?,
+        model: row.try_get("model").map_err(ser)?,
+        created_at: row.try_get("created_at").map_err(ser)?,
+        status: parse_ai_usage_status(&row.try_get::<String, _>("status").map_err(ser)?)?,
+        prompt_tokens: tokens("prompt_tokens")?,
+        completion_tokens: tokens("completion_tokens")?,
+        total_tokens: tokens("total_tokens")?,
+        cost_micro_usd: cost,
+    })
+}
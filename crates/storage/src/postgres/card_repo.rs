@@ -0,0 +1,2068 @@
+use std::collections::HashMap;
+
+use learn_core::model::{Card, CardId, CardSource, DeckId, Flag, NoteId, Tag, TagName};
+use sqlx::Row;
+
+use super::{
+    PostgresRepository,
+    mapping::{
+        audio_to_columns, card_id_from_i64, card_kind_to_columns, deck_id_from_i64, map_card_row,
+        map_tag_row, media_id_to_i64, note_id_to_i64, parse_card_phase,
+    },
+};
+use crate::repository::{
+    CardMaturityCounts, CardOrder, CardPhaseCounts, CardRepository, DeckCardMatch,
+    DeckPracticeCounts, DeckPracticeCountsRow, NewCardRecord, StorageError, TagPracticeCounts,
+    TrashedCard, search_terms,
+};
+
+fn u32_from_i64(field: &'static str, value: i64) -> Result<u32, StorageError> {
+    u32::try_from(value)
+        .map_err(|_| StorageError::Serialization(format!("invalid {field}: {value}")))
+}
+
+fn ser(error: &sqlx::Error) -> StorageError {
+    StorageError::Serialization(error.to_string())
+}
+
+/// Follow a card just moved from deck `from` to `to` with its review logs, and relink its
+/// tags to same-named tags in `to`, creating those as needed.
+pub(super) async fn relink_moved_card(
+    conn: &mut sqlx::PgConnection,
+    card: i64,
+    from: i64,
+    to: i64,
+) -> Result<(), StorageError> {
+    sqlx::query(
+        r"
+        UPDATE review_logs
+        SET deck_id = $1
+        WHERE card_id = $2 AND deck_id = $3
+        ",
+    )
+    .bind(to)
+    .bind(card)
+    .bind(from)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+    // Recreate the card's tags by name in the destination deck, then relink.
+    sqlx::query(
+        r"
+        INSERT INTO tags (deck_id, name)
+        SELECT $1, tags.name
+        FROM card_tags
+        JOIN tags ON tags.id = card_tags.tag_id
+        WHERE card_tags.card_id = $2 AND tags.deck_id = $3
+        ON CONFLICT(deck_id, name) DO NOTHING
+        ",
+    )
+    .bind(to)
+    .bind(card)
+    .bind(from)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+    sqlx::query(
+        r"
+        INSERT INTO card_tags (card_id, tag_id)
+        SELECT $2, dest.id
+        FROM card_tags
+        JOIN tags src ON src.id = card_tags.tag_id
+        JOIN tags dest ON dest.deck_id = $1 AND dest.name = src.name
+        WHERE card_tags.card_id = $2 AND src.deck_id = $3
+        ON CONFLICT DO NOTHING
+        ",
+    )
+    .bind(to)
+    .bind(card)
+    .bind(from)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+    sqlx::query(
+        r"
+        DELETE FROM card_tags
+        WHERE card_id = $1
+          AND tag_id IN (SELECT id FROM tags WHERE deck_id = $2)
+        ",
+    )
+    .bind(card)
+    .bind(from)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Insert one card row, letting the database assign its ID.
+async fn insert_card_row<'e, E>(executor: E, card: NewCardRecord) -> Result<CardId, StorageError>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let deck_id = i64::try_from(card.deck_id.value())
+        .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+    let prompt_media_id = card
+        .prompt_media_id
+        .map(i64::try_from)
+        .transpose()
+        .map_err(|_| StorageError::Serialization("prompt_media_id overflow".into()))?;
+    let answer_media_id = card
+        .answer_media_id
+        .map(i64::try_from)
+        .transpose()
+        .map_err(|_| StorageError::Serialization("answer_media_id overflow".into()))?;
+    let (cloze_text, cloze_indices) = card_kind_to_columns(&card.kind);
+
+    let id: i64 = sqlx::query_scalar(
+        r"
+        INSERT INTO cards (
+            deck_id, prompt, prompt_media_id, answer, answer_media_id,
+            phase, created_at, next_review_at, last_review_at, review_count,
+            stability, difficulty, cloze_text, cloze_indices, note_id, extra, updated_at, source
+        )
+        VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16,
+            $7, $17
+        )
+        RETURNING id
+        ",
+    )
+    .bind(deck_id)
+    .bind(card.prompt_text)
+    .bind(prompt_media_id)
+    .bind(card.answer_text)
+    .bind(answer_media_id)
+    .bind(card.phase.as_str())
+    .bind(card.created_at)
+    .bind(card.next_review_at)
+    .bind(card.last_review_at)
+    .bind(i64::from(card.review_count))
+    .bind(card.stability)
+    .bind(card.difficulty)
+    .bind(cloze_text)
+    .bind(cloze_indices)
+    .bind(note_id_to_i64(card.note_id)?)
+    .bind(card.extra_text)
+    .bind(card.source.map(CardSource::as_str))
+    .fetch_one(executor)
+    .await
+    .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+    card_id_from_i64(id)
+}
+
+async fn upsert_card_row<'e, E>(executor: E, card: &Card) -> Result<(), StorageError>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let (cloze_text, cloze_indices) = card_kind_to_columns(card.kind());
+    let (prompt_audio_uri, prompt_audio_hash) = audio_to_columns(card.prompt().audio());
+    let (answer_audio_uri, answer_audio_hash) = audio_to_columns(card.answer().audio());
+    sqlx::query(
+        r"
+        INSERT INTO cards (
+            id, deck_id, prompt, prompt_media_id, answer, answer_media_id,
+            phase, created_at, next_review_at, last_review_at, review_count,
+            stability, difficulty, cloze_text, cloze_indices, note_id,
+            prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+            suspended, buried_until, learning_step, lapses, extra, flag, updated_at, source
+        )
+        VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
+            $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28
+        )
+        ON CONFLICT(id) DO UPDATE SET
+            -- keep created_at from the original insert; only update mutable fields
+            prompt = excluded.prompt,
+            prompt_media_id = excluded.prompt_media_id,
+            answer = excluded.answer,
+            answer_media_id = excluded.answer_media_id,
+            phase = excluded.phase,
+            next_review_at = excluded.next_review_at,
+            last_review_at = excluded.last_review_at,
+            review_count = excluded.review_count,
+            stability = excluded.stability,
+            difficulty = excluded.difficulty,
+            cloze_text = excluded.cloze_text,
+            cloze_indices = excluded.cloze_indices,
+            note_id = excluded.note_id,
+            prompt_audio_uri = excluded.prompt_audio_uri,
+            prompt_audio_hash = excluded.prompt_audio_hash,
+            answer_audio_uri = excluded.answer_audio_uri,
+            answer_audio_hash = excluded.answer_audio_hash,
+            suspended = excluded.suspended,
+            buried_until = excluded.buried_until,
+            learning_step = excluded.learning_step,
+            lapses = excluded.lapses,
+            extra = excluded.extra,
+            flag = excluded.flag,
+            updated_at = excluded.updated_at,
+            source = excluded.source
+        ",
+    )
+    .bind(
+        i64::try_from(card.id().value())
+            .map_err(|_| StorageError::Serialization("card_id overflow".into()))?,
+    )
+    .bind(
+        i64::try_from(card.deck_id().value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?,
+    )
+    .bind(card.prompt().text().to_owned())
+    .bind(media_id_to_i64(card.prompt().media_id())?)
+    .bind(card.answer().text().to_owned())
+    .bind(media_id_to_i64(card.answer().media_id())?)
+    .bind(card.phase().as_str())
+    .bind(card.created_at())
+    .bind(card.next_review_at())
+    .bind(card.last_review_at())
+    .bind(i64::from(card.review_count()))
+    .bind(card.memory_state().map(|m| m.stability))
+    .bind(card.memory_state().map(|m| m.difficulty))
+    .bind(cloze_text)
+    .bind(cloze_indices)
+    .bind(note_id_to_i64(card.note_id())?)
+    .bind(prompt_audio_uri)
+    .bind(prompt_audio_hash)
+    .bind(answer_audio_uri)
+    .bind(answer_audio_hash)
+    .bind(i64::from(i32::from(card.is_suspended())))
+    .bind(card.buried_until())
+    .bind(i64::from(card.learning_step()))
+    .bind(i64::from(card.lapses()))
+    .bind(card.extra().map(|extra| extra.text().to_owned()))
+    .bind(card.flag().map(Flag::as_str))
+    .bind(card.updated_at())
+    .bind(card.source().map(CardSource::as_str))
+    .execute(executor)
+    .await
+    .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl CardRepository for PostgresRepository {
+    async fn insert_new_card(&self, card: NewCardRecord) -> Result<CardId, StorageError> {
+        insert_card_row(&self.pool, card).await
+    }
+
+    async fn insert_new_cards(
+        &self,
+        cards: Vec<NewCardRecord>,
+    ) -> Result<Vec<CardId>, StorageError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let mut ids = Vec::with_capacity(cards.len());
+        for card in cards {
+            ids.push(insert_card_row(&mut *tx, card).await?);
+        }
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(ids)
+    }
+
+    async fn upsert_card(&self, card: &Card) -> Result<(), StorageError> {
+        upsert_card_row(&self.pool, card).await?;
+        super::sync_id_sequence(&self.pool, "cards")
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))
+    }
+
+    async fn upsert_cards(&self, cards: &[Card]) -> Result<(), StorageError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        for card in cards {
+            upsert_card_row(&mut *tx, card).await?;
+        }
+        super::sync_id_sequence(&mut *tx, "cards")
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))
+    }
+
+    async fn move_card(
+        &self,
+        card_id: CardId,
+        from_deck: DeckId,
+        to_deck: DeckId,
+    ) -> Result<(), StorageError> {
+        let card = i64::try_from(card_id.value())
+            .map_err(|_| StorageError::Serialization("card_id overflow".into()))?;
+        let from = i64::try_from(from_deck.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let to = i64::try_from(to_deck.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let deck_exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM decks WHERE id = $1")
+            .bind(to)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        if deck_exists == 0 {
+            return Err(StorageError::NotFound);
+        }
+        if from == to {
+            let in_deck: i64 =
+                sqlx::query_scalar(
+                    "SELECT COUNT(*) FROM cards \
+                     WHERE id = $1 AND deck_id = $2 AND deleted_at IS NULL",
+                )
+                .bind(card)
+                .bind(from)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| StorageError::Connection(e.to_string()))?;
+            return if in_deck == 0 {
+                Err(StorageError::NotFound)
+            } else {
+                Ok(())
+            };
+        }
+
+        let result = sqlx::query(
+            r"
+            UPDATE cards
+            SET deck_id = $1, note_id = NULL
+            WHERE id = $2 AND deck_id = $3 AND deleted_at IS NULL
+            ",
+        )
+        .bind(to)
+        .bind(card)
+        .bind(from)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound);
+        }
+
+        relink_moved_card(&mut tx, card, from, to).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn delete_card(&self, deck_id: DeckId, card_id: CardId) -> Result<(), StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let card = i64::try_from(card_id.value())
+            .map_err(|_| StorageError::Serialization("card_id overflow".into()))?;
+
+        let result = sqlx::query(
+            r"
+            DELETE FROM cards
+            WHERE id = $1 AND deck_id = $2
+            ",
+        )
+        .bind(card)
+        .bind(deck)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn trash_card(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        deleted_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let card = i64::try_from(card_id.value())
+            .map_err(|_| StorageError::Serialization("card_id overflow".into()))?;
+
+        let result = sqlx::query(
+            r"
+            UPDATE cards
+            SET deleted_at = $3
+            WHERE id = $1 AND deck_id = $2 AND deleted_at IS NULL
+            ",
+        )
+        .bind(card)
+        .bind(deck)
+        .bind(deleted_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn trash_cards(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+        deleted_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        for card_id in card_ids {
+            let card = i64::try_from(card_id.value())
+                .map_err(|_| StorageError::Serialization("card_id overflow".into()))?;
+            let result = sqlx::query(
+                r"
+                UPDATE cards
+                SET deleted_at = $3
+                WHERE id = $1 AND deck_id = $2 AND deleted_at IS NULL
+                ",
+            )
+            .bind(card)
+            .bind(deck)
+            .bind(deleted_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+            // Dropping the transaction rolls back the cards already trashed.
+            if result.rows_affected() == 0 {
+                return Err(StorageError::NotFound);
+            }
+        }
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))
+    }
+
+    async fn restore_card(&self, deck_id: DeckId, card_id: CardId) -> Result<(), StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let card = i64::try_from(card_id.value())
+            .map_err(|_| StorageError::Serialization("card_id overflow".into()))?;
+
+        let result = sqlx::query(
+            r"
+            UPDATE cards
+            SET deleted_at = NULL
+            WHERE id = $1 AND deck_id = $2 AND deleted_at IS NOT NULL
+            ",
+        )
+        .bind(card)
+        .bind(deck)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn list_trashed_cards(
+        &self,
+        deck_id: DeckId,
+        limit: u32,
+    ) -> Result<Vec<TrashedCard>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let rows = sqlx::query(
+            r"
+            SELECT
+                id, deck_id, prompt, prompt_media_id, answer, answer_media_id, phase, created_at,
+                next_review_at, last_review_at, review_count, stability, difficulty,
+                cloze_text, cloze_indices, note_id,
+                prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+                suspended, buried_until, learning_step, lapses, extra, flag, updated_at,
+                source, deleted_at
+            FROM cards
+            WHERE deck_id = $1 AND deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC, id DESC
+            LIMIT $2
+            ",
+        )
+        .bind(deck)
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(TrashedCard {
+                    card: map_card_row(row)?,
+                    deleted_at: row.try_get("deleted_at").map_err(|e| ser(&e))?,
+                })
+            })
+            .collect()
+    }
+
+    async fn purge_trashed_cards(
+        &self,
+        deleted_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<u64, StorageError> {
+        // Review logs and tag links go with the cards through ON DELETE CASCADE.
+        let result = sqlx::query("DELETE FROM cards WHERE deleted_at < $1")
+            .bind(deleted_before)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn get_cards(&self, deck_id: DeckId, ids: &[CardId]) -> Result<Vec<Card>, StorageError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut sql = String::from(
+            r"
+            SELECT
+                id, deck_id, prompt, prompt_media_id, answer, answer_media_id, phase, created_at,
+                next_review_at, last_review_at, review_count, stability, difficulty,
+                cloze_text, cloze_indices, note_id,
+                prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+                suspended, buried_until, learning_step, lapses, extra, flag, updated_at,
+                source
+            FROM cards
+            WHERE deck_id = $1 AND deleted_at IS NULL AND id IN (
+            ",
+        );
+
+        for i in 0..ids.len() {
+            if i > 0 {
+                sql.push_str(", ");
+            }
+            sql.push('$');
+            sql.push_str(&(i + 2).to_string());
+        }
+        sql.push_str(")\n");
+
+        let mut q = sqlx::query(&sql).bind(
+            i64::try_from(deck_id.value())
+                .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?,
+        );
+
+        for id in ids {
+            q = q.bind(
+                i64::try_from(id.value())
+                    .map_err(|_| StorageError::Serialization("card_id overflow".into()))?,
+            );
+        }
+
+        let rows = q
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut by_id: HashMap<u64, Card> = HashMap::with_capacity(rows.len());
+
+        for row in rows {
+            let card = map_card_row(&row)?;
+            by_id.insert(card.id().value(), card);
+        }
+
+        let mut out = Vec::with_capacity(ids.len());
+        for id in ids {
+            match by_id.remove(&id.value()) {
+                Some(card) => out.push(card),
+                None => return Err(StorageError::NotFound),
+            }
+        }
+
+        Ok(out)
+    }
+
+    async fn due_cards(
+        &self,
+        deck_id: DeckId,
+        now: chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let lim = i64::from(limit);
+
+        let rows = sqlx::query(
+            r"
+            SELECT
+                id, deck_id, prompt, prompt_media_id, answer, answer_media_id, phase, created_at,
+                next_review_at, last_review_at, review_count, stability, difficulty,
+                cloze_text, cloze_indices, note_id,
+                prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+                suspended, buried_until, learning_step, lapses, extra, flag, updated_at,
+                source
+            FROM cards
+            WHERE deck_id = $1
+              AND deleted_at IS NULL
+              AND suspended = 0
+              AND (buried_until IS NULL OR buried_until <= $2)
+              AND review_count > 0
+              AND next_review_at <= $2
+            ORDER BY next_review_at ASC, id ASC
+            LIMIT $3
+            ",
+        )
+        .bind(deck)
+        .bind(now)
+        .bind(lim)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut cards = Vec::with_capacity(rows.len());
+        for row in rows {
+            cards.push(map_card_row(&row)?);
+        }
+        Ok(cards)
+    }
+
+    async fn due_times(
+        &self,
+        deck_id: DeckId,
+        until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<chrono::DateTime<chrono::Utc>>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let rows = sqlx::query(
+            r"
+            SELECT next_review_at
+            FROM cards
+            WHERE deck_id = $1
+              AND deleted_at IS NULL
+              AND suspended = 0
+              AND review_count > 0
+              AND next_review_at <= $2
+            ORDER BY next_review_at ASC
+            ",
+        )
+        .bind(deck)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        rows.iter()
+            .map(|row| row.try_get("next_review_at").map_err(|e| ser(&e)))
+            .collect()
+    }
+
+    async fn new_cards(
+        &self,
+        deck_id: DeckId,
+        now: chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let lim = i64::from(limit);
+
+        let rows = sqlx::query(
+            r"
+            SELECT
+                id, deck_id, prompt, prompt_media_id, answer, answer_media_id, phase, created_at,
+                next_review_at, last_review_at, review_count, stability, difficulty,
+                cloze_text, cloze_indices, note_id,
+                prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+                suspended, buried_until, learning_step, lapses, extra, flag, updated_at,
+                source
+            FROM cards
+            WHERE deck_id = $1
+              AND deleted_at IS NULL
+              AND suspended = 0
+              AND (buried_until IS NULL OR buried_until <= $2)
+              AND review_count = 0
+            ORDER BY created_at ASC, id ASC
+            LIMIT $3
+            ",
+        )
+        .bind(deck)
+        .bind(now)
+        .bind(lim)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut cards = Vec::with_capacity(rows.len());
+        for row in rows {
+            cards.push(map_card_row(&row)?);
+        }
+        Ok(cards)
+    }
+
+    async fn list_cards(&self, deck_id: DeckId, limit: u32) -> Result<Vec<Card>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let lim = i64::from(limit);
+
+        let rows = sqlx::query(
+            r"
+            SELECT
+                id, deck_id, prompt, prompt_media_id, answer, answer_media_id, phase, created_at,
+                next_review_at, last_review_at, review_count, stability, difficulty,
+                cloze_text, cloze_indices, note_id,
+                prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+                suspended, buried_until, learning_step, lapses, extra, flag, updated_at,
+                source
+            FROM cards
+            WHERE deck_id = $1
+              AND deleted_at IS NULL
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            ",
+        )
+        .bind(deck)
+        .bind(lim)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut cards = Vec::with_capacity(rows.len());
+        for row in rows {
+            cards.push(map_card_row(&row)?);
+        }
+        Ok(cards)
+    }
+
+    async fn list_cards_page(
+        &self,
+        deck_id: DeckId,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let rows = sqlx::query(
+            r"
+            SELECT
+                id, deck_id, prompt, prompt_media_id, answer, answer_media_id, phase, created_at,
+                next_review_at, last_review_at, review_count, stability, difficulty,
+                cloze_text, cloze_indices, note_id,
+                prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+                suspended, buried_until, learning_step, lapses, extra, flag, updated_at,
+                source
+            FROM cards
+            WHERE deck_id = $1
+              AND deleted_at IS NULL
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2 OFFSET $3
+            ",
+        )
+        .bind(deck)
+        .bind(i64::from(limit))
+        .bind(i64::from(offset))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut cards = Vec::with_capacity(rows.len());
+        for row in rows {
+            cards.push(map_card_row(&row)?);
+        }
+        Ok(cards)
+    }
+
+    async fn list_cards_after(
+        &self,
+        deck_id: DeckId,
+        after: Option<CardId>,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let after = i64::try_from(after.map_or(0, |id| id.value()))
+            .map_err(|_| StorageError::Serialization("card_id overflow".into()))?;
+
+        let rows = sqlx::query(
+            r"
+            SELECT
+                id, deck_id, prompt, prompt_media_id, answer, answer_media_id, phase, created_at,
+                next_review_at, last_review_at, review_count, stability, difficulty,
+                cloze_text, cloze_indices, note_id,
+                prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+                suspended, buried_until, learning_step, lapses, extra, flag, updated_at,
+                source
+            FROM cards
+            WHERE deck_id = $1
+              AND deleted_at IS NULL
+              AND id > $2
+            ORDER BY id
+            LIMIT $3
+            ",
+        )
+        .bind(deck)
+        .bind(after)
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut cards = Vec::with_capacity(rows.len());
+        for row in rows {
+            cards.push(map_card_row(&row)?);
+        }
+        Ok(cards)
+    }
+
+    async fn list_cards_ordered(
+        &self,
+        deck_id: DeckId,
+        order: CardOrder,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        // New cards have no due date or difficulty worth sorting by; they go last.
+        let order_by = match order {
+            CardOrder::DueDate => "CASE WHEN review_count > 0 THEN next_review_at END ASC",
+            CardOrder::Difficulty => "CASE WHEN review_count > 0 THEN difficulty END DESC",
+        };
+        let sql = format!(
+            r"
+            SELECT
+                id, deck_id, prompt, prompt_media_id, answer, answer_media_id, phase, created_at,
+                next_review_at, last_review_at, review_count, stability, difficulty,
+                cloze_text, cloze_indices, note_id,
+                prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+                suspended, buried_until, learning_step, lapses, extra, flag, updated_at,
+                source
+            FROM cards
+            WHERE deck_id = $1
+              AND deleted_at IS NULL
+            ORDER BY review_count = 0, {order_by}, created_at ASC, id ASC
+            LIMIT $2
+            "
+        );
+
+        let rows = sqlx::query(&sql)
+            .bind(deck)
+            .bind(i64::from(limit))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut cards = Vec::with_capacity(rows.len());
+        for row in rows {
+            cards.push(map_card_row(&row)?);
+        }
+        Ok(cards)
+    }
+
+    async fn count_cards_created_between(
+        &self,
+        deck_id: DeckId,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let count: i64 = sqlx::query_scalar(
+            r"
+            SELECT COUNT(*)
+            FROM cards
+            WHERE deck_id = $1 AND created_at >= $2 AND created_at < $3 AND deleted_at IS NULL
+            ",
+        )
+        .bind(deck)
+        .bind(start)
+        .bind(end)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+        u32_from_i64("created_today", count)
+    }
+
+    async fn mistakes_count(&self, deck_id: DeckId) -> Result<u32, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let row = sqlx::query(
+            r"
+            SELECT COUNT(*) AS total
+            FROM cards
+            WHERE deck_id = $1 AND suspended = 0 AND phase = 'relearning' AND deleted_at IS NULL
+            ",
+        )
+        .bind(deck)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        u32_from_i64("total", row.try_get::<i64, _>("total").map_err(|e| ser(&e))?)
+    }
+
+    async fn count_cards_by_phase(&self, deck_id: DeckId) -> Result<CardPhaseCounts, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let rows = sqlx::query(
+            r"
+            SELECT phase, suspended, COUNT(*) AS total
+            FROM cards
+            WHERE deck_id = $1 AND deleted_at IS NULL
+            GROUP BY phase, suspended
+            ",
+        )
+        .bind(deck)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut counts = CardPhaseCounts::default();
+        for row in rows {
+            let phase = row.try_get::<String, _>("phase").map_err(|e| ser(&e))?;
+            let suspended = row.try_get::<i64, _>("suspended").map_err(|e| ser(&e))? != 0;
+            let total = row.try_get::<i64, _>("total").map_err(|e| ser(&e))?;
+            counts.add(parse_card_phase(&phase)?, suspended, u32_from_i64("total", total)?);
+        }
+        Ok(counts)
+    }
+
+    async fn count_cards_by_maturity(
+        &self,
+        deck_id: DeckId,
+        mature_days: u32,
+    ) -> Result<CardMaturityCounts, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let rows = sqlx::query(
+            r"
+            SELECT
+                CASE
+                    WHEN phase = 'new' THEN 'new'
+                    WHEN next_review_at - last_review_at >= make_interval(days => $2) THEN 'mature'
+                    ELSE 'young'
+                END AS maturity,
+                COUNT(*) AS total
+            FROM cards
+            WHERE deck_id = $1 AND deleted_at IS NULL
+            GROUP BY maturity
+            ",
+        )
+        .bind(deck)
+        .bind(i32::try_from(mature_days).unwrap_or(i32::MAX))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut counts = CardMaturityCounts::default();
+        for row in rows {
+            let maturity = row.try_get::<String, _>("maturity").map_err(|e| ser(&e))?;
+            let total = u32_from_i64("total", row.try_get("total").map_err(|e| ser(&e))?)?;
+            match maturity.as_str() {
+                "new" => counts.new = total,
+                "mature" => counts.mature = total,
+                _ => counts.young = total,
+            }
+        }
+        Ok(counts)
+    }
+
+    async fn reset_deck_learning(
+        &self,
+        deck_id: DeckId,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<u64, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let result = sqlx::query(
+            r"
+            UPDATE cards
+            SET
+                phase = 'new',
+                next_review_at = $2,
+                last_review_at = NULL,
+                review_count = 0,
+                stability = NULL,
+                difficulty = NULL,
+                learning_step = 0,
+                lapses = 0
+            WHERE deck_id = $1
+              AND deleted_at IS NULL
+            ",
+        )
+        .bind(deck)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn reset_card_learning(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        now: chrono::DateTime<chrono::Utc>,
+        clear_history: bool,
+    ) -> Result<(), StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let card = i64::try_from(card_id.value())
+            .map_err(|_| StorageError::Serialization("card_id overflow".into()))?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let result = sqlx::query(
+            r"
+            UPDATE cards
+            SET
+                phase = 'new',
+                next_review_at = $3,
+                last_review_at = NULL,
+                review_count = 0,
+                stability = NULL,
+                difficulty = NULL,
+                learning_step = 0,
+                lapses = 0
+            WHERE id = $1 AND deck_id = $2 AND deleted_at IS NULL
+            ",
+        )
+        .bind(card)
+        .bind(deck)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound);
+        }
+
+        if clear_history {
+            sqlx::query("DELETE FROM review_logs WHERE card_id = $1 AND deck_id = $2")
+                .bind(card)
+                .bind(deck)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| StorageError::Connection(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn reschedule_cards(
+        &self,
+        deck_id: DeckId,
+        due_dates: &[(CardId, chrono::DateTime<chrono::Utc>)],
+    ) -> Result<u64, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let mut updated = 0_u64;
+        for &(card_id, due_at) in due_dates {
+            let id = i64::try_from(card_id.value())
+                .map_err(|_| StorageError::Serialization("card_id overflow".into()))?;
+            let result = sqlx::query(
+                "UPDATE cards SET next_review_at = $1 WHERE id = $2 AND deck_id = $3",
+            )
+            .bind(due_at)
+            .bind(id)
+            .bind(deck)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+            if result.rows_affected() == 0 {
+                return Err(StorageError::NotFound);
+            }
+            updated += result.rows_affected();
+        }
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(updated)
+    }
+
+    async fn deck_practice_counts(
+        &self,
+        deck_id: DeckId,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<DeckPracticeCounts, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let row = sqlx::query(
+            r"
+            SELECT
+                COUNT(*) AS total,
+                COALESCE(
+                    SUM(CASE WHEN suspended = 0 AND review_count = 0 THEN 1 ELSE 0 END),
+                    0
+                ) AS new_count,
+                COALESCE(
+                    SUM(
+                        CASE
+                            WHEN suspended = 0 AND review_count > 0 AND next_review_at <= $2
+                            THEN 1
+                            ELSE 0
+                        END
+                    ),
+                    0
+                ) AS due_count,
+                COALESCE(
+                    SUM(
+                        CASE
+                            WHEN suspended = 0 AND review_count > 0 AND next_review_at <= $2
+                                AND phase IN ('learning', 'relearning')
+                            THEN 1
+                            ELSE 0
+                        END
+                    ),
+                    0
+                ) AS learning_count
+            FROM cards
+            WHERE deck_id = $1
+              AND deleted_at IS NULL
+            ",
+        )
+        .bind(deck)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let total = u32_from_i64("total", row.try_get::<i64, _>("total").map_err(|e| ser(&e))?)?;
+        let new = u32_from_i64(
+            "new_count",
+            row.try_get::<i64, _>("new_count").map_err(|e| ser(&e))?,
+        )?;
+        let due = u32_from_i64(
+            "due_count",
+            row.try_get::<i64, _>("due_count").map_err(|e| ser(&e))?,
+        )?;
+        let learning = u32_from_i64(
+            "learning_count",
+            row.try_get::<i64, _>("learning_count").map_err(|e| ser(&e))?,
+        )?;
+
+        Ok(DeckPracticeCounts {
+            total,
+            due,
+            new,
+            learning,
+        })
+    }
+
+    async fn list_deck_practice_counts(
+        &self,
+        deck_ids: &[DeckId],
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<DeckPracticeCountsRow>, StorageError> {
+        if deck_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut sql = String::from(
+            r"
+            SELECT
+                deck_id,
+                COUNT(*) AS total,
+                COALESCE(
+                    SUM(CASE WHEN suspended = 0 AND review_count = 0 THEN 1 ELSE 0 END),
+                    0
+                ) AS new_count,
+                COALESCE(
+                    SUM(
+                        CASE
+                            WHEN suspended = 0 AND review_count > 0 AND next_review_at <= $1
+                            THEN 1
+                            ELSE 0
+                        END
+                    ),
+                    0
+                ) AS due_count,
+                COALESCE(
+                    SUM(
+                        CASE
+                            WHEN suspended = 0 AND review_count > 0 AND next_review_at <= $1
+                                AND phase IN ('learning', 'relearning')
+                            THEN 1
+                            ELSE 0
+                        END
+                    ),
+                    0
+                ) AS learning_count
+            FROM cards
+            WHERE deleted_at IS NULL AND deck_id IN (
+            ",
+        );
+
+        for i in 0..deck_ids.len() {
+            if i > 0 {
+                sql.push_str(", ");
+            }
+            sql.push('$');
+            sql.push_str(&(i + 2).to_string());
+        }
+        sql.push_str(")\n GROUP BY deck_id\n");
+
+        let mut query = sqlx::query(&sql).bind(now);
+        for deck_id in deck_ids {
+            let deck = i64::try_from(deck_id.value())
+                .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+            query = query.bind(deck);
+        }
+
+        let rows = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut out = Vec::with_capacity(rows.len());
+        for row in rows {
+            let deck_id =
+                deck_id_from_i64(row.try_get::<i64, _>("deck_id").map_err(|e| ser(&e))?)?;
+
+            let total =
+                u32_from_i64("total", row.try_get::<i64, _>("total").map_err(|e| ser(&e))?)?;
+            let new = u32_from_i64(
+                "new_count",
+                row.try_get::<i64, _>("new_count").map_err(|e| ser(&e))?,
+            )?;
+            let due = u32_from_i64(
+                "due_count",
+                row.try_get::<i64, _>("due_count").map_err(|e| ser(&e))?,
+            )?;
+            let learning = u32_from_i64(
+                "learning_count",
+                row.try_get::<i64, _>("learning_count").map_err(|e| ser(&e))?,
+            )?;
+
+            out.push(DeckPracticeCountsRow::new(
+                deck_id,
+                DeckPracticeCounts {
+                    total,
+                    due,
+                    new,
+                    learning,
+                },
+            ));
+        }
+
+        Ok(out)
+    }
+
+    async fn list_tag_practice_counts(
+        &self,
+        deck_id: DeckId,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<TagPracticeCounts>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let rows = sqlx::query(
+            r"
+            SELECT
+                tags.name AS name,
+                COALESCE(COUNT(cards.id), 0) AS total,
+                COALESCE(
+                    SUM(
+                        CASE WHEN cards.suspended = 0 AND cards.review_count = 0 THEN 1 ELSE 0 END
+                    ),
+                    0
+                ) AS new_count,
+                COALESCE(
+                    SUM(
+                        CASE
+                            WHEN cards.suspended = 0
+                                AND cards.review_count > 0
+                                AND cards.next_review_at <= $2 THEN 1
+                            ELSE 0
+                        END
+                    ),
+                    0
+                ) AS due_count
+            FROM tags
+            LEFT JOIN card_tags ON card_tags.tag_id = tags.id
+            LEFT JOIN cards ON cards.id = card_tags.card_id AND cards.deleted_at IS NULL
+            WHERE tags.deck_id = $1
+            GROUP BY tags.id
+            ORDER BY tags.name ASC, tags.id ASC
+            ",
+        )
+        .bind(deck)
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut out = Vec::with_capacity(rows.len());
+        for row in rows {
+            let name_raw: String = row.try_get("name").map_err(|e| ser(&e))?;
+            let name = TagName::new(name_raw)
+                .map_err(|e| StorageError::Serialization(e.to_string()))?;
+            let total = u32_from_i64(
+                "total",
+                row.try_get::<i64, _>("total").map_err(|e| ser(&e))?,
+            )?;
+            let new = u32_from_i64(
+                "new_count",
+                row.try_get::<i64, _>("new_count").map_err(|e| ser(&e))?,
+            )?;
+            let due = u32_from_i64(
+                "due_count",
+                row.try_get::<i64, _>("due_count").map_err(|e| ser(&e))?,
+            )?;
+
+            out.push(TagPracticeCounts { name, total, due, new });
+        }
+
+        Ok(out)
+    }
+
+    async fn list_cards_by_tags(
+        &self,
+        deck_id: DeckId,
+        tag_names: &[TagName],
+    ) -> Result<Vec<Card>, StorageError> {
+        if tag_names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let mut sql = String::from(
+            r"
+            SELECT DISTINCT
+                cards.id, cards.deck_id, cards.prompt, cards.prompt_media_id, cards.answer,
+                cards.answer_media_id, cards.phase, cards.created_at, cards.next_review_at,
+                cards.last_review_at, cards.review_count, cards.stability, cards.difficulty,
+                cards.cloze_text, cards.cloze_indices, cards.note_id,
+                cards.prompt_audio_uri, cards.prompt_audio_hash, cards.answer_audio_uri,
+                cards.answer_audio_hash, cards.suspended, cards.buried_until, cards.learning_step,
+                cards.lapses, cards.extra, cards.flag, cards.updated_at, cards.source
+            FROM cards
+            JOIN card_tags ON card_tags.card_id = cards.id
+            JOIN tags ON tags.id = card_tags.tag_id
+            WHERE cards.deck_id = $1
+              AND cards.deleted_at IS NULL
+              AND tags.name IN (
+            ",
+        );
+
+        for i in 0..tag_names.len() {
+            if i > 0 {
+                sql.push_str(", ");
+            }
+            sql.push('$');
+            sql.push_str(&(i + 2).to_string());
+        }
+        sql.push_str(")\n");
+
+        let mut q = sqlx::query(&sql).bind(deck);
+        for name in tag_names {
+            q = q.bind(name.as_str());
+        }
+
+        let rows = q
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut cards = Vec::with_capacity(rows.len());
+        for row in rows {
+            cards.push(map_card_row(&row)?);
+        }
+        Ok(cards)
+    }
+
+    async fn list_cards_with_all_tags(
+        &self,
+        deck_id: DeckId,
+        tag_names: &[TagName],
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        let mut names: Vec<&str> = tag_names.iter().map(TagName::as_str).collect();
+        names.sort_unstable();
+        names.dedup();
+        if names.is_empty() {
+            return self.list_cards(deck_id, limit).await;
+        }
+
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let required = i64::try_from(names.len())
+            .map_err(|_| StorageError::Serialization("tag count overflow".into()))?;
+
+        let mut sql = String::from(
+            r"
+            SELECT
+                id, deck_id, prompt, prompt_media_id, answer, answer_media_id, phase, created_at,
+                next_review_at, last_review_at, review_count, stability, difficulty,
+                cloze_text, cloze_indices, note_id,
+                prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+                suspended, buried_until, learning_step, lapses, extra, flag, updated_at,
+                source
+            FROM cards
+            WHERE deck_id = $1
+              AND deleted_at IS NULL
+              AND id IN (
+                SELECT card_tags.card_id
+                FROM card_tags
+                JOIN tags ON tags.id = card_tags.tag_id
+                WHERE tags.deck_id = $1
+                  AND tags.name IN (
+            ",
+        );
+
+        for i in 0..names.len() {
+            if i > 0 {
+                sql.push_str(", ");
+            }
+            sql.push('$');
+            sql.push_str(&(i + 4).to_string());
+        }
+        sql.push_str(
+            r"
+                  )
+                GROUP BY card_tags.card_id
+                HAVING COUNT(DISTINCT tags.id) = $2
+              )
+            ORDER BY created_at DESC, id DESC
+            LIMIT $3
+            ",
+        );
+
+        let mut q = sqlx::query(&sql)
+            .bind(deck)
+            .bind(required)
+            .bind(i64::from(limit));
+        for name in names {
+            q = q.bind(name);
+        }
+
+        let rows = q
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut cards = Vec::with_capacity(rows.len());
+        for row in rows {
+            cards.push(map_card_row(&row)?);
+        }
+        Ok(cards)
+    }
+
+    async fn list_untagged_cards(
+        &self,
+        deck_id: DeckId,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let rows = sqlx::query(
+            r"
+            SELECT
+                id, deck_id, prompt, prompt_media_id, answer, answer_media_id, phase, created_at,
+                next_review_at, last_review_at, review_count, stability, difficulty,
+                cloze_text, cloze_indices, note_id,
+                prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+                suspended, buried_until, learning_step, lapses, extra, flag, updated_at,
+                source
+            FROM cards
+            WHERE deck_id = $1
+              AND deleted_at IS NULL
+              AND NOT EXISTS (SELECT 1 FROM card_tags WHERE card_tags.card_id = cards.id)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            ",
+        )
+        .bind(deck)
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut cards = Vec::with_capacity(rows.len());
+        for row in rows {
+            cards.push(map_card_row(&row)?);
+        }
+        Ok(cards)
+    }
+
+    async fn list_note_cards(
+        &self,
+        deck_id: DeckId,
+        note_id: NoteId,
+    ) -> Result<Vec<Card>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let rows = sqlx::query(
+            r"
+            SELECT
+                id, deck_id, prompt, prompt_media_id, answer, answer_media_id, phase, created_at,
+                next_review_at, last_review_at, review_count, stability, difficulty,
+                cloze_text, cloze_indices, note_id,
+                prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+                suspended, buried_until, learning_step, lapses, extra, flag, updated_at,
+                source
+            FROM cards
+            WHERE deck_id = $1 AND note_id = $2 AND deleted_at IS NULL
+            ORDER BY id ASC
+            ",
+        )
+        .bind(deck)
+        .bind(note_id_to_i64(Some(note_id))?)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut cards = Vec::with_capacity(rows.len());
+        for row in rows {
+            cards.push(map_card_row(&row)?);
+        }
+        Ok(cards)
+    }
+
+    async fn search_cards(
+        &self,
+        deck_id: DeckId,
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let rows = self.search_rows(Some(deck), query, limit).await?;
+
+        let mut cards = Vec::with_capacity(rows.len());
+        for row in rows {
+            cards.push(map_card_row(&row)?);
+        }
+        Ok(cards)
+    }
+
+    async fn search_all_decks(
+        &self,
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<DeckCardMatch>, StorageError> {
+        let rows = self.search_rows(None, query, limit).await?;
+
+        let mut matches = Vec::with_capacity(rows.len());
+        for row in rows {
+            matches.push(DeckCardMatch {
+                deck_name: row
+                    .try_get("deck_name")
+                    .map_err(|e| StorageError::Serialization(e.to_string()))?,
+                card: map_card_row(&row)?,
+            });
+        }
+        Ok(matches)
+    }
+
+    async fn prompt_exists(
+        &self,
+        deck_id: DeckId,
+        prompt_text: &str,
+        exclude: Option<CardId>,
+    ) -> Result<bool, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let exists = if let Some(card_id) = exclude {
+            let exclude_id = i64::try_from(card_id.value())
+                .map_err(|_| StorageError::Serialization("card_id overflow".into()))?;
+            sqlx::query_scalar::<_, bool>(
+                r"
+                SELECT EXISTS(
+                    SELECT 1
+                    FROM cards
+                    WHERE deck_id = $1
+                      AND deleted_at IS NULL
+                      AND LOWER(TRIM(prompt)) = LOWER(TRIM($2))
+                      AND id != $3
+                )
+                ",
+            )
+            .bind(deck)
+            .bind(prompt_text)
+            .bind(exclude_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?
+        } else {
+            sqlx::query_scalar::<_, bool>(
+                r"
+                SELECT EXISTS(
+                    SELECT 1
+                    FROM cards
+                    WHERE deck_id = $1
+                      AND deleted_at IS NULL
+                      AND LOWER(TRIM(prompt)) = LOWER(TRIM($2))
+                )
+                ",
+            )
+            .bind(deck)
+            .bind(prompt_text)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?
+        };
+
+        Ok(exists)
+    }
+
+    async fn list_tags_for_deck(&self, deck_id: DeckId) -> Result<Vec<Tag>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let rows = sqlx::query(
+            r"
+            SELECT id, deck_id, name
+            FROM tags
+            WHERE deck_id = $1
+            ORDER BY name ASC, id ASC
+            ",
+        )
+        .bind(deck)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut tags = Vec::with_capacity(rows.len());
+        for row in rows {
+            tags.push(map_tag_row(&row)?);
+        }
+        Ok(tags)
+    }
+
+    async fn list_tags_for_card(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+    ) -> Result<Vec<Tag>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let card = i64::try_from(card_id.value())
+            .map_err(|_| StorageError::Serialization("card_id overflow".into()))?;
+
+        let rows = sqlx::query(
+            r"
+            SELECT tags.id, tags.deck_id, tags.name
+            FROM tags
+            JOIN card_tags ON card_tags.tag_id = tags.id
+            JOIN cards ON cards.id = card_tags.card_id
+            WHERE cards.deck_id = $1
+              AND cards.id = $2
+            ORDER BY tags.name ASC, tags.id ASC
+            ",
+        )
+        .bind(deck)
+        .bind(card)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut tags = Vec::with_capacity(rows.len());
+        for row in rows {
+            tags.push(map_tag_row(&row)?);
+        }
+        Ok(tags)
+    }
+
+    async fn list_tags_for_cards(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+    ) -> Result<Vec<(CardId, Tag)>, StorageError> {
+        if card_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut sql = String::from(
+            r"
+            SELECT card_tags.card_id AS card_id, tags.id, tags.deck_id, tags.name
+            FROM card_tags
+            JOIN tags ON tags.id = card_tags.tag_id
+            JOIN cards ON cards.id = card_tags.card_id
+            WHERE cards.deck_id = $1
+              AND card_tags.card_id IN (
+            ",
+        );
+
+        for i in 0..card_ids.len() {
+            if i > 0 {
+                sql.push_str(", ");
+            }
+            sql.push('$');
+            sql.push_str(&(i + 2).to_string());
+        }
+        sql.push_str(")\n ORDER BY card_tags.card_id ASC, tags.name ASC, tags.id ASC\n");
+
+        let mut q = sqlx::query(&sql).bind(
+            i64::try_from(deck_id.value())
+                .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?,
+        );
+        for id in card_ids {
+            q = q.bind(
+                i64::try_from(id.value())
+                    .map_err(|_| StorageError::Serialization("card_id overflow".into()))?,
+            );
+        }
+
+        let rows = q
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut out = Vec::with_capacity(rows.len());
+        for row in rows {
+            let card_id =
+                card_id_from_i64(row.try_get::<i64, _>("card_id").map_err(|e| ser(&e))?)?;
+            out.push((card_id, map_tag_row(&row)?));
+        }
+        Ok(out)
+    }
+
+    async fn upsert_tag(&self, deck_id: DeckId, name: &TagName) -> Result<Tag, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        sqlx::query(
+            r"
+            INSERT INTO tags (deck_id, name)
+            VALUES ($1, $2)
+            ON CONFLICT(deck_id, name) DO NOTHING
+            ",
+        )
+        .bind(deck)
+        .bind(name.as_str())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let row = sqlx::query(
+            r"
+            SELECT id, deck_id, name
+            FROM tags
+            WHERE deck_id = $1 AND name = $2
+            ",
+        )
+        .bind(deck)
+        .bind(name.as_str())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        map_tag_row(&row)
+    }
+
+    async fn set_tags_for_card(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        tag_names: &[TagName],
+    ) -> Result<Vec<Tag>, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let card = i64::try_from(card_id.value())
+            .map_err(|_| StorageError::Serialization("card_id overflow".into()))?;
+
+        let mut tx = self.pool.begin().await.map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        sqlx::query(
+            r"
+            DELETE FROM card_tags
+            WHERE card_id = $1
+            ",
+        )
+        .bind(card)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        for name in tag_names {
+            sqlx::query(
+                r"
+                INSERT INTO tags (deck_id, name)
+                VALUES ($1, $2)
+                ON CONFLICT(deck_id, name) DO NOTHING
+                ",
+            )
+            .bind(deck)
+            .bind(name.as_str())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+            let tag_id: i64 = sqlx::query_scalar(
+                r"
+                SELECT id
+                FROM tags
+                WHERE deck_id = $1 AND name = $2
+                ",
+            )
+            .bind(deck)
+            .bind(name.as_str())
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+            sqlx::query(
+                r"
+                INSERT INTO card_tags (card_id, tag_id)
+                VALUES ($1, $2)
+                ON CONFLICT(card_id, tag_id) DO NOTHING
+                ",
+            )
+            .bind(card)
+            .bind(tag_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        }
+
+        let rows = sqlx::query(
+            r"
+            SELECT tags.id, tags.deck_id, tags.name
+            FROM tags
+            JOIN card_tags ON card_tags.tag_id = tags.id
+            WHERE card_tags.card_id = $1
+            ORDER BY tags.name ASC, tags.id ASC
+            ",
+        )
+        .bind(card)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut tags = Vec::with_capacity(rows.len());
+        for row in rows {
+            tags.push(map_tag_row(&row)?);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        Ok(tags)
+    }
+
+
+    async fn add_tags_to_cards(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+        tag_names: &[TagName],
+    ) -> Result<u64, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let mut tx = self.pool.begin().await.map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut tag_ids = Vec::with_capacity(tag_names.len());
+        for name in tag_names {
+            sqlx::query(
+                r"
+                INSERT INTO tags (deck_id, name)
+                VALUES ($1, $2)
+                ON CONFLICT(deck_id, name) DO NOTHING
+                ",
+            )
+            .bind(deck)
+            .bind(name.as_str())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+            let tag_id: i64 = sqlx::query_scalar(
+                r"
+                SELECT id
+                FROM tags
+                WHERE deck_id = $1 AND name = $2
+                ",
+            )
+            .bind(deck)
+            .bind(name.as_str())
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+            tag_ids.push(tag_id);
+        }
+
+        let mut affected = 0_u64;
+        for card_id in card_ids {
+            let card = i64::try_from(card_id.value())
+                .map_err(|_| StorageError::Serialization("card_id overflow".into()))?;
+            let mut inserted = 0_u64;
+            for tag_id in &tag_ids {
+                // Only cards of this deck get the tag; others insert nothing.
+                inserted += sqlx::query(
+                    r"
+                    INSERT INTO card_tags (card_id, tag_id)
+                    SELECT id, $2
+                    FROM cards
+                    WHERE id = $1 AND deck_id = $3
+                    ON CONFLICT(card_id, tag_id) DO NOTHING
+                    ",
+                )
+                .bind(card)
+                .bind(*tag_id)
+                .bind(deck)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| StorageError::Connection(e.to_string()))?
+                .rows_affected();
+            }
+            if inserted > 0 {
+                affected += 1;
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        Ok(affected)
+    }
+
+    async fn remove_tags_from_cards(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+        tag_names: &[TagName],
+    ) -> Result<u64, StorageError> {
+        let deck = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let mut tx = self.pool.begin().await.map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut affected = 0_u64;
+        for card_id in card_ids {
+            let card = i64::try_from(card_id.value())
+                .map_err(|_| StorageError::Serialization("card_id overflow".into()))?;
+            let mut removed = 0_u64;
+            for name in tag_names {
+                removed += sqlx::query(
+                    r"
+                    DELETE FROM card_tags
+                    WHERE card_id = $1
+                      AND tag_id IN (
+                        SELECT id FROM tags WHERE deck_id = $2 AND name = $3
+                      )
+                    ",
+                )
+                .bind(card)
+                .bind(deck)
+                .bind(name.as_str())
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| StorageError::Connection(e.to_string()))?
+                .rows_affected();
+            }
+            if removed > 0 {
+                affected += 1;
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        Ok(affected)
+    }
+}
+
+impl PostgresRepository {
+    /// Card rows matching every word of `query` as a prefix, in `deck` or in all decks when
+    /// `None`, best `ts_rank` first. Rows carry the deck's name as `deck_name`.
+    async fn search_rows(
+        &self,
+        deck: Option<i64>,
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<sqlx::postgres::PgRow>, StorageError> {
+        let terms = search_terms(query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let expr = terms
+            .iter()
+            .map(|term| format!("{term}:*"))
+            .collect::<Vec<_>>()
+            .join(" & ");
+
+        sqlx::query(
+            r"
+            SELECT
+                cards.id, cards.deck_id, cards.prompt, cards.prompt_media_id, cards.answer,
+                cards.answer_media_id, cards.phase, cards.created_at, cards.next_review_at,
+                cards.last_review_at, cards.review_count, cards.stability, cards.difficulty,
+                cards.cloze_text, cards.cloze_indices, cards.note_id,
+                cards.prompt_audio_uri, cards.prompt_audio_hash, cards.answer_audio_uri,
+                cards.answer_audio_hash, cards.suspended, cards.buried_until, cards.learning_step,
+                cards.lapses, cards.extra, cards.flag, cards.updated_at, cards.source,
+                decks.name AS deck_name
+            FROM cards
+            JOIN decks ON decks.id = cards.deck_id
+            WHERE ($1::BIGINT IS NULL OR cards.deck_id = $1)
+              AND cards.deleted_at IS NULL
+              AND to_tsvector('simple', cards.prompt || ' ' || cards.answer)
+                  @@ to_tsquery('simple', $2)
+            ORDER BY
+                ts_rank(
+                    to_tsvector('simple', cards.prompt || ' ' || cards.answer),
+                    to_tsquery('simple', $2)
+                ) DESC,
+                cards.created_at DESC,
+                cards.id DESC
+            LIMIT $3
+            ",
+        )
+        .bind(deck)
+        .bind(expr)
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))
+    }
+}
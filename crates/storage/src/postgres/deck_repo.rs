@@ -0,0 +1,762 @@
+use learn_core::model::{CardTemplate, Deck, DeckColor, DeckIcon, DeckId, NewCardTemplate};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+
+use super::mapping::{
+    deck_id_from_i64, format_fsrs_parameters, format_learning_steps, parse_fsrs_parameters,
+    parse_answer_buttons, parse_learning_steps, parse_deck_color, parse_deck_icon,
+    parse_leech_action, parse_new_card_order, parse_new_review_mix,
+};
+use super::PostgresRepository;
+use super::card_repo::relink_moved_card;
+use crate::repository::{
+    DeckMergeCounts, DeckRepository, MergeConflict, NewDeckRecord, StorageError,
+};
+
+fn ser<E: core::fmt::Display>(e: E) -> StorageError {
+    StorageError::Serialization(e.to_string())
+}
+
+/// Insert one deck row, letting the database assign its ID.
+async fn insert_deck_row<'e, E>(executor: E, deck: NewDeckRecord) -> Result<DeckId, StorageError>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let description = deck.description;
+    let created_at = deck.created_at;
+    let new_cards = i64::from(deck.new_cards_per_day);
+    let review_limit = i64::from(deck.review_limit_per_day);
+    let micro = i64::from(deck.micro_session_size);
+    let protect_overload = i64::from(i32::from(deck.protect_overload));
+    let preserve_stability_on_lapse = i64::from(i32::from(deck.preserve_stability_on_lapse));
+    let lapse_min_interval_secs = i64::from(deck.lapse_min_interval_secs);
+    let show_timer = i64::from(i32::from(deck.show_timer));
+    let soft_time_reminder = i64::from(i32::from(deck.soft_time_reminder));
+    let auto_advance_cards = i64::from(i32::from(deck.auto_advance_cards));
+    let soft_time_reminder_secs = i64::from(deck.soft_time_reminder_secs);
+    let auto_reveal_secs = i64::from(deck.auto_reveal_secs);
+    let min_interval_secs = i64::from(deck.min_interval_secs);
+    let max_interval_days = i64::from(deck.max_interval_days);
+    let easy_days_enabled = i64::from(i32::from(deck.easy_days_enabled));
+    let easy_day_load_factor = f64::from(deck.easy_day_load_factor);
+    let easy_days_mask = i64::from(deck.easy_days_mask);
+    let fsrs_target_retention = f64::from(deck.fsrs_target_retention);
+    let fsrs_optimize_enabled = i64::from(i32::from(deck.fsrs_optimize_enabled));
+    let fsrs_optimize_after = i64::from(deck.fsrs_optimize_after);
+    let new_review_mix = deck.new_review_mix.as_str();
+    let load_balance = i64::from(i32::from(deck.load_balance));
+    let learning_steps = format_learning_steps(&deck.learning_steps_secs);
+    let relearning_steps = format_learning_steps(&deck.relearning_steps_secs);
+    let fsrs_parameters = format_fsrs_parameters(&deck.fsrs_parameters);
+    let answer_buttons = deck.answer_buttons.as_str();
+    let leech_threshold = i64::from(deck.leech_threshold);
+    let leech_action = deck.leech_action.as_str();
+    let color = deck.color.map(DeckColor::as_str);
+    let icon = deck.icon.map(DeckIcon::as_str);
+    let hold_new = i64::from(i32::from(deck.hold_new_until_reviews_done));
+    let front_template = deck.front_template.as_deref();
+    let back_template = deck.back_template.as_deref();
+    let interval_fuzz = i64::from(i32::from(deck.interval_fuzz));
+    let bury_siblings = i64::from(i32::from(deck.bury_siblings));
+    let same_session_fail_limit = i64::from(deck.same_session_fail_limit);
+    let progressive_reveal = i64::from(i32::from(deck.progressive_reveal));
+    let typed_answer = i64::from(i32::from(deck.typed_answer));
+    let new_card_order = deck.new_card_order.as_str();
+    let new_card_prompt = deck.new_card_template.prompt();
+    let new_card_answer = deck.new_card_template.answer();
+    let new_per_session = i64::from(deck.new_per_session);
+
+    let id: i64 = sqlx::query_scalar(
+        r"
+        INSERT INTO decks (
+            name, description, created_at, new_cards_per_day, review_limit_per_day,
+            micro_session_size, protect_overload, preserve_stability_on_lapse,
+            lapse_min_interval_secs, show_timer, soft_time_reminder, auto_advance_cards,
+            soft_time_reminder_secs, auto_reveal_secs, min_interval_secs, max_interval_days,
+            easy_days_enabled, easy_day_load_factor, easy_days_mask,
+            fsrs_target_retention, fsrs_optimize_enabled, fsrs_optimize_after, new_review_mix,
+            load_balance, learning_steps, fsrs_parameters, answer_buttons, leech_threshold,
+            leech_action, color, icon, hold_new_until_reviews_done, front_template,
+            back_template, interval_fuzz, bury_siblings, relearning_steps,
+            same_session_fail_limit, progressive_reveal, typed_answer, updated_at,
+            new_card_order, new_card_prompt, new_card_answer, new_per_session
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39, $40, $3, $41, $42, $43, $44)
+        RETURNING id
+        ",
+    )
+    .bind(deck.name)
+    .bind(description)
+    .bind(created_at)
+    .bind(new_cards)
+    .bind(review_limit)
+    .bind(micro)
+    .bind(protect_overload)
+    .bind(preserve_stability_on_lapse)
+    .bind(lapse_min_interval_secs)
+    .bind(show_timer)
+    .bind(soft_time_reminder)
+    .bind(auto_advance_cards)
+    .bind(soft_time_reminder_secs)
+    .bind(auto_reveal_secs)
+    .bind(min_interval_secs)
+    .bind(max_interval_days)
+    .bind(easy_days_enabled)
+    .bind(easy_day_load_factor)
+    .bind(easy_days_mask)
+    .bind(fsrs_target_retention)
+    .bind(fsrs_optimize_enabled)
+    .bind(fsrs_optimize_after)
+    .bind(new_review_mix)
+    .bind(load_balance)
+    .bind(learning_steps)
+    .bind(fsrs_parameters)
+    .bind(answer_buttons)
+    .bind(leech_threshold)
+    .bind(leech_action)
+    .bind(color)
+    .bind(icon)
+    .bind(hold_new)
+    .bind(front_template)
+    .bind(back_template)
+    .bind(interval_fuzz)
+    .bind(bury_siblings)
+    .bind(relearning_steps)
+    .bind(same_session_fail_limit)
+    .bind(progressive_reveal)
+    .bind(typed_answer)
+    .bind(new_card_order)
+    .bind(new_card_prompt)
+    .bind(new_card_answer)
+    .bind(new_per_session)
+    .fetch_one(executor)
+    .await
+    .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+    deck_id_from_i64(id)
+}
+
+/// Insert or update one deck row, bumping `version` on update.
+///
+/// With `expected_version` set, an existing row is only updated while its stored version
+/// still matches. Returns the number of rows written.
+async fn upsert_deck_row<'e, E>(
+    executor: E,
+    deck: &Deck,
+    expected_version: Option<u64>,
+) -> Result<u64, StorageError>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let id = deck.id().value();
+    let name = deck.name().to_string();
+    let description = deck.description().map(ToString::to_string);
+    let created_at = deck.created_at();
+    let new_cards = i64::from(deck.settings().new_cards_per_day());
+    let review_limit = i64::from(deck.settings().review_limit_per_day());
+    let micro = i64::from(deck.settings().micro_session_size());
+    let protect_overload = i64::from(i32::from(deck.settings().protect_overload()));
+    let preserve_stability_on_lapse =
+        i64::from(i32::from(deck.settings().preserve_stability_on_lapse()));
+    let lapse_min_interval_secs = i64::from(deck.settings().lapse_min_interval_secs());
+    let show_timer = i64::from(i32::from(deck.settings().show_timer()));
+    let soft_time_reminder = i64::from(i32::from(deck.settings().soft_time_reminder()));
+    let auto_advance_cards = i64::from(i32::from(deck.settings().auto_advance_cards()));
+    let soft_time_reminder_secs = i64::from(deck.settings().soft_time_reminder_secs());
+    let auto_reveal_secs = i64::from(deck.settings().auto_reveal_secs());
+    let min_interval_secs = i64::from(deck.settings().min_interval_secs());
+    let max_interval_days = i64::from(deck.settings().max_interval_days());
+    let easy_days_enabled = i64::from(i32::from(deck.settings().easy_days_enabled()));
+    let easy_day_load_factor = f64::from(deck.settings().easy_day_load_factor());
+    let easy_days_mask = i64::from(deck.settings().easy_days_mask());
+    let fsrs_target_retention = f64::from(deck.settings().fsrs_target_retention());
+    let fsrs_optimize_enabled = i64::from(i32::from(deck.settings().fsrs_optimize_enabled()));
+    let fsrs_optimize_after = i64::from(deck.settings().fsrs_optimize_after());
+    let new_review_mix = deck.settings().new_review_mix().as_str();
+    let load_balance = i64::from(i32::from(deck.settings().load_balance()));
+    let learning_steps = format_learning_steps(deck.settings().learning_steps_secs());
+    let relearning_steps = format_learning_steps(deck.settings().relearning_steps_secs());
+    let fsrs_parameters = format_fsrs_parameters(deck.settings().fsrs_parameters());
+    let answer_buttons = deck.settings().answer_buttons().as_str();
+    let leech_threshold = i64::from(deck.settings().leech_threshold());
+    let leech_action = deck.settings().leech_action().as_str();
+    let color = deck.settings().color().map(DeckColor::as_str);
+    let icon = deck.settings().icon().map(DeckIcon::as_str);
+    let hold_new = i64::from(i32::from(deck.settings().hold_new_until_reviews_done()));
+    let front_template = deck.settings().front_template().map(CardTemplate::as_str);
+    let back_template = deck.settings().back_template().map(CardTemplate::as_str);
+    let interval_fuzz = i64::from(i32::from(deck.settings().interval_fuzz()));
+    let bury_siblings = i64::from(i32::from(deck.settings().bury_siblings()));
+    let same_session_fail_limit = i64::from(deck.settings().same_session_fail_limit());
+    let progressive_reveal = i64::from(i32::from(deck.settings().progressive_reveal()));
+    let typed_answer = i64::from(i32::from(deck.settings().typed_answer()));
+    let new_card_order = deck.settings().new_card_order().as_str();
+    let new_card_prompt = deck.settings().new_card_template().prompt();
+    let new_card_answer = deck.settings().new_card_template().answer();
+    let new_per_session = i64::from(deck.settings().new_per_session());
+    let archived = i64::from(i32::from(deck.is_archived()));
+    let favorite = i64::from(i32::from(deck.is_favorite()));
+    let version = i64::try_from(deck.version())
+        .map_err(|_| StorageError::Serialization("version overflow".into()))?;
+    let expected_version = expected_version
+        .map(i64::try_from)
+        .transpose()
+        .map_err(|_| StorageError::Serialization("version overflow".into()))?;
+
+    let res = sqlx::query(
+        r"
+        INSERT INTO decks (
+            id, name, description, created_at, new_cards_per_day, review_limit_per_day,
+            micro_session_size, protect_overload, preserve_stability_on_lapse,
+            lapse_min_interval_secs, show_timer, soft_time_reminder, auto_advance_cards,
+            soft_time_reminder_secs, auto_reveal_secs, min_interval_secs, max_interval_days,
+            easy_days_enabled, easy_day_load_factor, easy_days_mask,
+            fsrs_target_retention, fsrs_optimize_enabled, fsrs_optimize_after, new_review_mix,
+            load_balance, learning_steps, fsrs_parameters, answer_buttons, leech_threshold,
+            leech_action, color, icon, hold_new_until_reviews_done, front_template,
+            back_template, interval_fuzz, bury_siblings, relearning_steps, archived, favorite,
+            version, same_session_fail_limit, progressive_reveal, typed_answer, updated_at,
+            new_card_order, new_card_prompt, new_card_answer, new_per_session
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39, $40, $41, $42, $43, $44, $46, $47, $48, $49, $50)
+        ON CONFLICT(id) DO UPDATE SET
+            name = excluded.name,
+            description = excluded.description,
+            new_cards_per_day = excluded.new_cards_per_day,
+            review_limit_per_day = excluded.review_limit_per_day,
+            micro_session_size = excluded.micro_session_size,
+            protect_overload = excluded.protect_overload,
+            preserve_stability_on_lapse = excluded.preserve_stability_on_lapse,
+            lapse_min_interval_secs = excluded.lapse_min_interval_secs,
+            show_timer = excluded.show_timer,
+            soft_time_reminder = excluded.soft_time_reminder,
+            auto_advance_cards = excluded.auto_advance_cards,
+            soft_time_reminder_secs = excluded.soft_time_reminder_secs,
+            auto_reveal_secs = excluded.auto_reveal_secs,
+            min_interval_secs = excluded.min_interval_secs,
+            max_interval_days = excluded.max_interval_days,
+            easy_days_enabled = excluded.easy_days_enabled,
+            easy_day_load_factor = excluded.easy_day_load_factor,
+            easy_days_mask = excluded.easy_days_mask,
+            fsrs_target_retention = excluded.fsrs_target_retention,
+            fsrs_optimize_enabled = excluded.fsrs_optimize_enabled,
+            fsrs_optimize_after = excluded.fsrs_optimize_after,
+            new_review_mix = excluded.new_review_mix,
+            load_balance = excluded.load_balance,
+            learning_steps = excluded.learning_steps,
+            fsrs_parameters = excluded.fsrs_parameters,
+            answer_buttons = excluded.answer_buttons,
+            leech_threshold = excluded.leech_threshold,
+            leech_action = excluded.leech_action,
+            color = excluded.color,
+            icon = excluded.icon,
+            hold_new_until_reviews_done = excluded.hold_new_until_reviews_done,
+            front_template = excluded.front_template,
+            back_template = excluded.back_template,
+            interval_fuzz = excluded.interval_fuzz,
+            bury_siblings = excluded.bury_siblings,
+            relearning_steps = excluded.relearning_steps,
+            archived = excluded.archived,
+            favorite = excluded.favorite,
+            same_session_fail_limit = excluded.same_session_fail_limit,
+            progressive_reveal = excluded.progressive_reveal,
+            typed_answer = excluded.typed_answer,
+            updated_at = excluded.updated_at,
+            new_card_order = excluded.new_card_order,
+            new_card_prompt = excluded.new_card_prompt,
+            new_card_answer = excluded.new_card_answer,
+            new_per_session = excluded.new_per_session,
+            version = decks.version + 1
+        WHERE $45::BIGINT IS NULL OR decks.version = $45
+        ",
+    )
+    .bind(i64::try_from(id).map_err(|_| StorageError::Serialization("id overflow".into()))?)
+    .bind(name)
+    .bind(description)
+    .bind(created_at)
+    .bind(new_cards)
+    .bind(review_limit)
+    .bind(micro)
+    .bind(protect_overload)
+    .bind(preserve_stability_on_lapse)
+    .bind(lapse_min_interval_secs)
+    .bind(show_timer)
+    .bind(soft_time_reminder)
+    .bind(auto_advance_cards)
+    .bind(soft_time_reminder_secs)
+    .bind(auto_reveal_secs)
+    .bind(min_interval_secs)
+    .bind(max_interval_days)
+    .bind(easy_days_enabled)
+    .bind(easy_day_load_factor)
+    .bind(easy_days_mask)
+    .bind(fsrs_target_retention)
+    .bind(fsrs_optimize_enabled)
+    .bind(fsrs_optimize_after)
+    .bind(new_review_mix)
+    .bind(load_balance)
+    .bind(learning_steps)
+    .bind(fsrs_parameters)
+    .bind(answer_buttons)
+    .bind(leech_threshold)
+    .bind(leech_action)
+    .bind(color)
+    .bind(icon)
+    .bind(hold_new)
+    .bind(front_template)
+    .bind(back_template)
+    .bind(interval_fuzz)
+    .bind(bury_siblings)
+    .bind(relearning_steps)
+    .bind(archived)
+    .bind(favorite)
+    .bind(version)
+    .bind(same_session_fail_limit)
+    .bind(progressive_reveal)
+    .bind(typed_answer)
+    .bind(expected_version)
+    .bind(deck.updated_at())
+    .bind(new_card_order)
+    .bind(new_card_prompt)
+    .bind(new_card_answer)
+    .bind(new_per_session)
+    .execute(executor)
+    .await
+    .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+    Ok(res.rows_affected())
+}
+
+#[async_trait::async_trait]
+impl DeckRepository for PostgresRepository {
+    async fn insert_new_deck(&self, deck: NewDeckRecord) -> Result<DeckId, StorageError> {
+        insert_deck_row(&self.pool, deck).await
+    }
+
+    async fn upsert_deck(&self, deck: &Deck) -> Result<(), StorageError> {
+        upsert_deck_row(&self.pool, deck, None).await?;
+
+        super::sync_id_sequence(&self.pool, "decks")
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn update_deck_if_version(&self, deck: &Deck) -> Result<u64, StorageError> {
+        let id = i64::try_from(deck.id().value())
+            .map_err(|_| StorageError::Serialization("id overflow".into()))?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM decks WHERE id = $1")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        if exists == 0 {
+            return Err(StorageError::NotFound);
+        }
+        if upsert_deck_row(&mut *tx, deck, Some(deck.version())).await? == 0 {
+            return Err(StorageError::Conflict);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(deck.version() + 1)
+    }
+
+    async fn get_deck(&self, id: learn_core::model::DeckId) -> Result<Option<Deck>, StorageError> {
+        let row = sqlx::query(
+            r"
+            SELECT id, name, description, created_at, new_cards_per_day, review_limit_per_day,
+                   micro_session_size, protect_overload, preserve_stability_on_lapse,
+                   lapse_min_interval_secs, show_timer, soft_time_reminder, auto_advance_cards,
+                   soft_time_reminder_secs, auto_reveal_secs, min_interval_secs, max_interval_days,
+                   easy_days_enabled, easy_day_load_factor, easy_days_mask,
+                   fsrs_target_retention, fsrs_optimize_enabled, fsrs_optimize_after, new_review_mix,
+                   load_balance, learning_steps, fsrs_parameters, answer_buttons,
+                   leech_threshold, leech_action, color, icon, hold_new_until_reviews_done,
+                   front_template, back_template, interval_fuzz, bury_siblings,
+                   relearning_steps, archived, favorite, version, same_session_fail_limit,
+                   progressive_reveal, typed_answer, updated_at,
+                   new_card_order, new_card_prompt, new_card_answer, new_per_session
+            FROM decks WHERE id = $1
+            ",
+        )
+        .bind(
+            i64::try_from(id.value()).map_err(|_| StorageError::Serialization("id overflow".into()))?,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        match row {
+            Some(row) => deck_from_row(&row).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    async fn list_decks(
+        &self,
+        limit: u32,
+        include_archived: bool,
+    ) -> Result<Vec<Deck>, StorageError> {
+        let rows = sqlx::query(
+            r"
+            SELECT id, name, description, created_at, new_cards_per_day, review_limit_per_day,
+                   micro_session_size, protect_overload, preserve_stability_on_lapse,
+                   lapse_min_interval_secs, show_timer, soft_time_reminder, auto_advance_cards,
+                   soft_time_reminder_secs, auto_reveal_secs, min_interval_secs, max_interval_days,
+                   easy_days_enabled, easy_day_load_factor, easy_days_mask,
+                   fsrs_target_retention, fsrs_optimize_enabled, fsrs_optimize_after, new_review_mix,
+                   load_balance, learning_steps, fsrs_parameters, answer_buttons,
+                   leech_threshold, leech_action, color, icon, hold_new_until_reviews_done,
+                   front_template, back_template, interval_fuzz, bury_siblings,
+                   relearning_steps, archived, favorite, version, same_session_fail_limit,
+                   progressive_reveal, typed_answer, updated_at,
+                   new_card_order, new_card_prompt, new_card_answer, new_per_session
+            FROM decks
+            WHERE $2 OR archived = 0
+            ORDER BY favorite DESC, id ASC
+            LIMIT $1
+            ",
+        )
+        .bind(i64::from(limit))
+        .bind(include_archived)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut decks = Vec::with_capacity(rows.len());
+        for row in rows {
+            decks.push(deck_from_row(&row)?);
+        }
+        Ok(decks)
+    }
+
+    async fn duplicate_deck(
+        &self,
+        source: DeckId,
+        deck: NewDeckRecord,
+    ) -> Result<DeckId, StorageError> {
+        let source = i64::try_from(source.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let now = deck.created_at;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let source_exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM decks WHERE id = $1")
+            .bind(source)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        if source_exists == 0 {
+            return Err(StorageError::NotFound);
+        }
+
+        let deck_id = insert_deck_row(&mut *tx, deck).await?;
+        let target = i64::try_from(deck_id.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        sqlx::query(
+            r"
+            INSERT INTO tags (deck_id, name)
+            SELECT $1, name FROM tags WHERE deck_id = $2
+            ",
+        )
+        .bind(target)
+        .bind(source)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let card_ids: Vec<i64> =
+            sqlx::query_scalar(
+                "SELECT id FROM cards WHERE deck_id = $1 AND deleted_at IS NULL ORDER BY id ASC",
+            )
+            .bind(source)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        for card_id in card_ids {
+            let new_card_id: i64 = sqlx::query_scalar(
+                r"
+                INSERT INTO cards (
+                    deck_id, prompt, prompt_media_id, answer, answer_media_id,
+                    phase, created_at, next_review_at, last_review_at, review_count,
+                    stability, difficulty, cloze_text, cloze_indices, note_id,
+                    prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+                    extra, updated_at, source
+                )
+                SELECT $1, prompt, prompt_media_id, answer, answer_media_id,
+                       'new', $2, $2, NULL, 0,
+                       NULL, NULL, cloze_text, cloze_indices, NULL,
+                       prompt_audio_uri, prompt_audio_hash, answer_audio_uri, answer_audio_hash,
+                       extra, $2, source
+                FROM cards WHERE id = $3
+                RETURNING id
+                ",
+            )
+            .bind(target)
+            .bind(now)
+            .bind(card_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+            sqlx::query(
+                r"
+                INSERT INTO card_tags (card_id, tag_id)
+                SELECT $1, dest.id
+                FROM card_tags
+                JOIN tags src ON src.id = card_tags.tag_id
+                JOIN tags dest ON dest.deck_id = $2 AND dest.name = src.name
+                WHERE card_tags.card_id = $3
+                ",
+            )
+            .bind(new_card_id)
+            .bind(target)
+            .bind(card_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(deck_id)
+    }
+
+    async fn merge_decks(
+        &self,
+        source: DeckId,
+        dest: DeckId,
+        on_conflict: MergeConflict,
+    ) -> Result<DeckMergeCounts, StorageError> {
+        let from = i64::try_from(source.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+        let to = i64::try_from(dest.value())
+            .map_err(|_| StorageError::Serialization("deck_id overflow".into()))?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let found: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM decks WHERE id IN ($1, $2)")
+            .bind(from)
+            .bind(to)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let expected = if from == to { 1 } else { 2 };
+        if found != expected {
+            return Err(StorageError::NotFound);
+        }
+
+        // Conflicts are judged against the destination as it was before the merge.
+        let rows: Vec<(i64, bool)> = sqlx::query_as(
+            r"
+            SELECT id, EXISTS(
+                SELECT 1
+                FROM cards existing
+                WHERE existing.deck_id = $2
+                  AND existing.deleted_at IS NULL
+                  AND LOWER(TRIM(existing.prompt)) = LOWER(TRIM(cards.prompt))
+            )
+            FROM cards
+            WHERE deck_id = $1 AND deleted_at IS NULL
+            ORDER BY id ASC
+            ",
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut counts = DeckMergeCounts::default();
+        for (card, conflict) in rows {
+            if conflict && on_conflict == MergeConflict::Skip {
+                counts.skipped += 1;
+                continue;
+            }
+            sqlx::query("UPDATE cards SET deck_id = $1, note_id = NULL WHERE id = $2")
+                .bind(to)
+                .bind(card)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| StorageError::Connection(e.to_string()))?;
+            relink_moved_card(&mut tx, card, from, to).await?;
+            counts.moved += 1;
+        }
+
+        sqlx::query("UPDATE decks SET archived = 1, version = version + 1 WHERE id = $1")
+            .bind(from)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(counts)
+    }
+}
+
+fn deck_from_row(row: &PgRow) -> Result<Deck, StorageError> {
+    let fsrs_parameters =
+        parse_fsrs_parameters(&row.try_get::<String, _>("fsrs_parameters").map_err(ser)?)?;
+    let answer_buttons =
+        parse_answer_buttons(&row.try_get::<String, _>("answer_buttons").map_err(ser)?)?;
+    let leech_threshold = u32::try_from(row.try_get::<i64, _>("leech_threshold").map_err(ser)?)
+        .map_err(|_| StorageError::Serialization("leech_threshold overflow".into()))?;
+    let leech_action =
+        parse_leech_action(&row.try_get::<String, _>("leech_action").map_err(ser)?)?;
+    let color = row
+        .try_get::<Option<String>, _>("color")
+        .map_err(ser)?
+        .as_deref()
+        .map(parse_deck_color)
+        .transpose()?;
+    let icon = row
+        .try_get::<Option<String>, _>("icon")
+        .map_err(ser)?
+        .as_deref()
+        .map(parse_deck_icon)
+        .transpose()?;
+    let hold_new = row.try_get::<i64, _>("hold_new_until_reviews_done").map_err(ser)? != 0;
+    let front_template = row.try_get::<Option<String>, _>("front_template").map_err(ser)?;
+    let back_template = row.try_get::<Option<String>, _>("back_template").map_err(ser)?;
+    let interval_fuzz = row.try_get::<i64, _>("interval_fuzz").map_err(ser)? != 0;
+    let bury_siblings = row.try_get::<i64, _>("bury_siblings").map_err(ser)? != 0;
+    let same_session_fail_limit =
+        u32::try_from(row.try_get::<i64, _>("same_session_fail_limit").map_err(ser)?)
+            .map_err(|_| StorageError::Serialization("same_session_fail_limit overflow".into()))?;
+    let progressive_reveal = row.try_get::<i64, _>("progressive_reveal").map_err(ser)? != 0;
+    let typed_answer = row.try_get::<i64, _>("typed_answer").map_err(ser)? != 0;
+    let new_card_order =
+        parse_new_card_order(&row.try_get::<String, _>("new_card_order").map_err(ser)?)?;
+    let new_card_template = NewCardTemplate::new(
+        &row.try_get::<String, _>("new_card_prompt").map_err(ser)?,
+        &row.try_get::<String, _>("new_card_answer").map_err(ser)?,
+    );
+    let new_per_session = u32::try_from(row.try_get::<i64, _>("new_per_session").map_err(ser)?)
+        .map_err(|_| StorageError::Serialization("new_per_session overflow".into()))?;
+    let new_review_mix =
+        parse_new_review_mix(&row.try_get::<String, _>("new_review_mix").map_err(ser)?)?;
+    let load_balance = row.try_get::<i64, _>("load_balance").map_err(ser)? != 0;
+    let learning_steps =
+        parse_learning_steps(&row.try_get::<String, _>("learning_steps").map_err(ser)?)?;
+    let relearning_steps =
+        parse_learning_steps(&row.try_get::<String, _>("relearning_steps").map_err(ser)?)?;
+    let settings = learn_core::model::DeckSettings::new(
+        u32::try_from(row.try_get::<i64, _>("new_cards_per_day").map_err(ser)?)
+            .map_err(|_| StorageError::Serialization("new_cards_per_day overflow".into()))?,
+        u32::try_from(row.try_get::<i64, _>("review_limit_per_day").map_err(ser)?)
+            .map_err(|_| StorageError::Serialization("review_limit_per_day overflow".into()))?,
+        u32::try_from(row.try_get::<i64, _>("micro_session_size").map_err(ser)?)
+            .map_err(|_| StorageError::Serialization("micro_session_size overflow".into()))?,
+        row.try_get::<i64, _>("protect_overload").map_err(ser)? != 0,
+        row.try_get::<i64, _>("preserve_stability_on_lapse")
+            .map_err(ser)?
+            != 0,
+        u32::try_from(row.try_get::<i64, _>("lapse_min_interval_secs").map_err(ser)?)
+            .map_err(|_| StorageError::Serialization("lapse_min_interval_secs overflow".into()))?,
+        row.try_get::<i64, _>("show_timer").map_err(ser)? != 0,
+        row.try_get::<i64, _>("soft_time_reminder").map_err(ser)? != 0,
+        row.try_get::<i64, _>("auto_advance_cards").map_err(ser)? != 0,
+        u32::try_from(row.try_get::<i64, _>("soft_time_reminder_secs").map_err(ser)?)
+            .map_err(|_| StorageError::Serialization("soft_time_reminder_secs overflow".into()))?,
+        u32::try_from(row.try_get::<i64, _>("auto_reveal_secs").map_err(ser)?)
+            .map_err(|_| StorageError::Serialization("auto_reveal_secs overflow".into()))?,
+        u32::try_from(row.try_get::<i64, _>("min_interval_secs").map_err(ser)?)
+            .map_err(|_| StorageError::Serialization("min_interval_secs overflow".into()))?,
+        u32::try_from(row.try_get::<i64, _>("max_interval_days").map_err(ser)?)
+            .map_err(|_| StorageError::Serialization("max_interval_days overflow".into()))?,
+        row.try_get::<i64, _>("easy_days_enabled").map_err(ser)? != 0,
+        {
+            let factor = row.try_get::<f32, _>("easy_day_load_factor").map_err(ser)?;
+            if !factor.is_finite() || factor <= 0.0 || factor > 1.0 {
+                return Err(StorageError::Serialization(
+                    "easy_day_load_factor invalid".into(),
+                ));
+            }
+            factor
+        },
+        u8::try_from(row.try_get::<i64, _>("easy_days_mask").map_err(ser)?)
+            .map_err(|_| StorageError::Serialization("easy_days_mask overflow".into()))?,
+        {
+            let retention = row.try_get::<f32, _>("fsrs_target_retention").map_err(ser)?;
+            if !retention.is_finite() || retention <= 0.0 || retention > 1.0 {
+                return Err(StorageError::Serialization(
+                    "fsrs_target_retention invalid".into(),
+                ));
+            }
+            retention
+        },
+        row.try_get::<i64, _>("fsrs_optimize_enabled").map_err(ser)? != 0,
+        u32::try_from(row.try_get::<i64, _>("fsrs_optimize_after").map_err(ser)?)
+            .map_err(|_| StorageError::Serialization("fsrs_optimize_after overflow".into()))?,
+    )
+    .and_then(|settings| settings.with_fsrs_parameters(fsrs_parameters))
+    .and_then(|settings| settings.with_learning_steps(learning_steps))
+    .and_then(|settings| settings.with_relearning_steps(relearning_steps))
+    .and_then(|settings| {
+        settings
+            .with_new_review_mix(new_review_mix)
+            .with_load_balance(load_balance)
+            .with_answer_buttons(answer_buttons)
+            .with_leech(leech_threshold, leech_action)
+            .with_appearance(color, icon)
+            .with_hold_new_until_reviews_done(hold_new)
+            .with_interval_fuzz(interval_fuzz)
+            .with_bury_siblings(bury_siblings)
+            .with_same_session_fail_limit(same_session_fail_limit)
+            .with_progressive_reveal(progressive_reveal)
+            .with_typed_answer(typed_answer)
+            .with_new_card_order(new_card_order)
+            .with_new_card_template(new_card_template)
+            .with_new_per_session(new_per_session)
+            .with_templates(front_template.as_deref(), back_template.as_deref())
+    })
+    .map_err(|e| StorageError::Serialization(e.to_string()))?;
+    let archived = row.try_get::<i64, _>("archived").map_err(ser)? != 0;
+    let favorite = row.try_get::<i64, _>("favorite").map_err(ser)? != 0;
+    let version = u64::try_from(row.try_get::<i64, _>("version").map_err(ser)?)
+        .map_err(|_| StorageError::Serialization("version sign overflow".into()))?;
+    let created_at: chrono::DateTime<chrono::Utc> = row.try_get("created_at").map_err(ser)?;
+    let updated_at: Option<chrono::DateTime<chrono::Utc>> =
+        row.try_get("updated_at").map_err(ser)?;
+
+    Deck::new(
+        learn_core::model::DeckId::new(
+            u64::try_from(row.try_get::<i64, _>("id").map_err(ser)?)
+                .map_err(|_| StorageError::Serialization("id sign overflow".into()))?,
+        ),
+        row.try_get::<String, _>("name").map_err(ser)?,
+        row.try_get::<Option<String>, _>("description").map_err(ser)?,
+        settings,
+        created_at,
+    )
+    .map(|deck| {
+        deck.with_archived(archived)
+            .with_favorite(favorite)
+            .with_version(version)
+            .with_updated_at(updated_at.unwrap_or(created_at))
+    })
+    .map_err(|e| StorageError::Serialization(e.to_string()))
+}
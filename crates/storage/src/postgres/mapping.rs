@@ -0,0 +1,168 @@
+//! Row decoding for the Postgres backend.
+//!
+//! Column types match the `SQLite` schema, so the ID, phase, and grade conversions are shared
+//! with `sqlite::mapping`.
+
+use learn_core::model::{Card, Tag, TagName, content::Content};
+use sqlx::Row;
+
+pub(crate) use crate::sqlite::mapping::{
+    answer_ms_from_column, audio_from_columns, audio_to_columns, card_id_from_i64,
+    card_kind_from_columns, card_kind_to_columns, deck_id_from_i64, extra_from_column,
+    format_fsrs_parameters, format_learning_steps, grade_from_i64, grade_to_i64, media_id_from_i64,
+    media_id_to_i64, note_id_from_i64, note_id_to_i64, parse_accent_color, parse_ai_usage_status,
+    parse_answer_buttons, parse_app_language, parse_card_phase, parse_card_source,
+    parse_deck_color, parse_deck_icon, parse_flag, parse_fsrs_parameters, parse_learning_steps,
+    parse_leech_action, parse_new_card_order, parse_new_review_mix, parse_theme_preference,
+    tag_id_from_i64,
+};
+use crate::repository::StorageError;
+
+fn ser<E: core::fmt::Display>(e: E) -> StorageError {
+    StorageError::Serialization(e.to_string())
+}
+
+pub(crate) fn map_card_row(row: &sqlx::postgres::PgRow) -> Result<Card, StorageError> {
+    let created_at: chrono::DateTime<chrono::Utc> = row.try_get("created_at").map_err(ser)?;
+    let updated_at: Option<chrono::DateTime<chrono::Utc>> =
+        row.try_get("updated_at").map_err(ser)?;
+
+    let prompt = Content::from_persisted(
+        row.try_get::<String, _>("prompt").map_err(ser)?,
+        row.try_get::<Option<i64>, _>("prompt_media_id")
+            .map_err(ser)?
+            .map(media_id_from_i64)
+            .transpose()?,
+    )
+    .map_err(ser)?
+    .with_audio(audio_from_columns(
+        row.try_get("prompt_audio_uri").map_err(ser)?,
+        row.try_get("prompt_audio_hash").map_err(ser)?,
+    )?);
+
+    let answer = Content::from_persisted(
+        row.try_get::<String, _>("answer").map_err(ser)?,
+        row.try_get::<Option<i64>, _>("answer_media_id")
+            .map_err(ser)?
+            .map(media_id_from_i64)
+            .transpose()?,
+    )
+    .map_err(ser)?
+    .with_audio(audio_from_columns(
+        row.try_get("answer_audio_uri").map_err(ser)?,
+        row.try_get("answer_audio_hash").map_err(ser)?,
+    )?);
+
+    let phase_str: String = row.try_get("phase").map_err(ser)?;
+    let phase = parse_card_phase(phase_str.as_str())?;
+
+    let review_count_i64: i64 = row.try_get("review_count").map_err(ser)?;
+    let review_count: u32 = u32::try_from(review_count_i64).map_err(|_| {
+        StorageError::Serialization(format!("invalid review_count: {review_count_i64}"))
+    })?;
+
+    let stability: f64 = if review_count == 0 {
+        0.0
+    } else {
+        row.try_get::<Option<f64>, _>("stability")
+            .map_err(ser)?
+            .ok_or_else(|| StorageError::Serialization("missing stability".into()))?
+    };
+
+    let difficulty: f64 = if review_count == 0 {
+        0.0
+    } else {
+        row.try_get::<Option<f64>, _>("difficulty")
+            .map_err(ser)?
+            .ok_or_else(|| StorageError::Serialization("missing difficulty".into()))?
+    };
+
+    let kind = card_kind_from_columns(
+        row.try_get("cloze_text").map_err(ser)?,
+        row.try_get("cloze_indices").map_err(ser)?,
+    )?;
+
+    let note_id = row
+        .try_get::<Option<i64>, _>("note_id")
+        .map_err(ser)?
+        .map(note_id_from_i64)
+        .transpose()?;
+    let suspended = row.try_get::<i64, _>("suspended").map_err(ser)? != 0;
+    let buried_until = row.try_get("buried_until").map_err(ser)?;
+    let learning_step = u32::try_from(row.try_get::<i64, _>("learning_step").map_err(ser)?)
+        .map_err(|_| StorageError::Serialization("learning_step sign overflow".into()))?;
+    let lapses = u32::try_from(row.try_get::<i64, _>("lapses").map_err(ser)?)
+        .map_err(|_| StorageError::Serialization("lapses sign overflow".into()))?;
+    let extra = extra_from_column(row.try_get("extra").map_err(ser)?)?;
+    let flag = row
+        .try_get::<Option<String>, _>("flag")
+        .map_err(ser)?
+        .as_deref()
+        .map(parse_flag)
+        .transpose()?;
+    let source = row
+        .try_get::<Option<String>, _>("source")
+        .map_err(ser)?
+        .as_deref()
+        .map(parse_card_source)
+        .transpose()?;
+
+    Card::from_persisted(
+        card_id_from_i64(row.try_get::<i64, _>("id").map_err(ser)?)?,
+        deck_id_from_i64(row.try_get::<i64, _>("deck_id").map_err(ser)?)?,
+        kind,
+        prompt,
+        answer,
+        created_at,
+        row.try_get("next_review_at").map_err(ser)?,
+        row.try_get("last_review_at").map_err(ser)?,
+        phase,
+        review_count,
+        stability,
+        difficulty,
+    )
+    .map(|card| {
+        card.with_note_id(note_id)
+            .with_suspended(suspended)
+            .with_buried_until(buried_until)
+            .with_learning_step(learning_step)
+            .with_lapses(lapses)
+            .with_extra(extra)
+            .with_flag(flag)
+            .with_source(source)
+            .with_updated_at(updated_at.unwrap_or(created_at))
+    })
+    .map_err(ser)
+}
+
+pub(crate) fn map_tag_row(row: &sqlx::postgres::PgRow) -> Result<Tag, StorageError> {
+    let name: String = row.try_get("name").map_err(ser)?;
+    let name = TagName::new(name).map_err(ser)?;
+    Ok(Tag::new(
+        tag_id_from_i64(row.try_get::<i64, _>("id").map_err(ser)?)?,
+        deck_id_from_i64(row.try_get::<i64, _>("deck_id").map_err(ser)?)?,
+        name,
+    ))
+}
+
+pub(crate) fn map_review_log_row(
+    row: &sqlx::postgres::PgRow,
+) -> Result<crate::repository::ReviewLogRecord, StorageError> {
+    use crate::repository::ReviewLogRecord;
+
+    let reviewed_at = row.try_get("reviewed_at").map_err(ser)?;
+    Ok(ReviewLogRecord {
+        id: Some(row.try_get("id").map_err(ser)?),
+        deck_id: deck_id_from_i64(row.try_get::<i64, _>("deck_id").map_err(ser)?)?,
+        card_id: card_id_from_i64(row.try_get::<i64, _>("card_id").map_err(ser)?)?,
+        grade: grade_from_i64(row.try_get::<i64, _>("grade").map_err(ser)?)?,
+        reviewed_at,
+        elapsed_days: row.try_get("elapsed_days").map_err(ser)?,
+        scheduled_days: row.try_get("scheduled_days").map_err(ser)?,
+        stability: row.try_get("stability").map_err(ser)?,
+        difficulty: row.try_get("difficulty").map_err(ser)?,
+        next_review_at: row.try_get("next_review_at").map_err(ser)?,
+        answer_ms: answer_ms_from_column(row.try_get("answer_ms").map_err(ser)?)?,
+        first_review: row.try_get::<i64, _>("first_review").map_err(ser)? != 0,
+    })
+}
@@ -0,0 +1,1847 @@
+use chrono::Utc;
+use sqlx::PgPool;
+
+use super::PostgresInitError;
+
+/// Postgres-dialect counterpart of the `SQLite` migrations.
+///
+/// Version numbers are kept in step with `sqlite::migrate` so both backends describe the same
+/// schema. Integer columns use `BIGINT` and timestamps use `TIMESTAMPTZ`.
+#[allow(clippy::too_many_lines)]
+pub async fn run_migrations(pool: &PgPool) -> Result<(), PostgresInitError> {
+    async fn is_applied(pool: &PgPool, version: i64) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("SELECT 1 FROM schema_migrations WHERE version = $1")
+            .bind(version)
+            .fetch_optional(pool)
+            .await?;
+        Ok(row.is_some())
+    }
+
+    sqlx::query(
+        r"
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version BIGINT PRIMARY KEY,
+                applied_at TIMESTAMPTZ NOT NULL
+            );
+            ",
+    )
+    .execute(pool)
+    .await?;
+
+    // Version 1: full schema snapshot.
+    if !is_applied(pool, 1).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS decks (
+                    id BIGSERIAL PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    description TEXT,
+                    created_at TIMESTAMPTZ NOT NULL,
+                    new_cards_per_day BIGINT NOT NULL CHECK (new_cards_per_day >= 0),
+                    review_limit_per_day BIGINT NOT NULL CHECK (review_limit_per_day >= 0),
+                    micro_session_size BIGINT NOT NULL CHECK (micro_session_size >= 0),
+                    protect_overload BIGINT NOT NULL DEFAULT 1 CHECK (protect_overload IN (0, 1)),
+                    preserve_stability_on_lapse BIGINT NOT NULL DEFAULT 1
+                        CHECK (preserve_stability_on_lapse IN (0, 1)),
+                    lapse_min_interval_secs BIGINT NOT NULL DEFAULT 86400
+                        CHECK (lapse_min_interval_secs >= 1),
+                    show_timer BIGINT NOT NULL DEFAULT 0 CHECK (show_timer IN (0, 1)),
+                    soft_time_reminder BIGINT NOT NULL DEFAULT 0 CHECK (soft_time_reminder IN (0, 1)),
+                    auto_advance_cards BIGINT NOT NULL DEFAULT 0 CHECK (auto_advance_cards IN (0, 1)),
+                    soft_time_reminder_secs BIGINT NOT NULL DEFAULT 25
+                        CHECK (soft_time_reminder_secs BETWEEN 5 AND 600),
+                    auto_reveal_secs BIGINT NOT NULL DEFAULT 20
+                        CHECK (auto_reveal_secs BETWEEN 5 AND 600),
+                    min_interval_days BIGINT NOT NULL DEFAULT 1 CHECK (min_interval_days >= 1),
+                    max_interval_days BIGINT NOT NULL DEFAULT 365 CHECK (max_interval_days >= 1),
+                    easy_days_enabled BIGINT NOT NULL DEFAULT 1 CHECK (easy_days_enabled IN (0, 1)),
+                    easy_day_load_factor REAL NOT NULL DEFAULT 0.5
+                        CHECK (easy_day_load_factor > 0 AND easy_day_load_factor <= 1),
+                    easy_days_mask BIGINT NOT NULL DEFAULT 96 CHECK (easy_days_mask BETWEEN 0 AND 127),
+                    fsrs_target_retention REAL NOT NULL DEFAULT 0.85
+                        CHECK (fsrs_target_retention > 0 AND fsrs_target_retention <= 1),
+                    fsrs_optimize_enabled BIGINT NOT NULL DEFAULT 1 CHECK (fsrs_optimize_enabled IN (0, 1)),
+                    fsrs_optimize_after BIGINT NOT NULL DEFAULT 100 CHECK (fsrs_optimize_after >= 1)
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS cards (
+                    id BIGSERIAL PRIMARY KEY,
+                    deck_id BIGINT NOT NULL,
+                    prompt TEXT NOT NULL,
+                    prompt_media_id BIGINT,
+                    answer TEXT NOT NULL,
+                    answer_media_id BIGINT,
+                    phase TEXT NOT NULL,
+                    created_at TIMESTAMPTZ NOT NULL,
+                    next_review_at TIMESTAMPTZ NOT NULL,
+                    last_review_at TIMESTAMPTZ,
+                    review_count BIGINT NOT NULL CHECK (review_count >= 0),
+                    stability DOUBLE PRECISION,
+                    difficulty DOUBLE PRECISION,
+                    FOREIGN KEY (deck_id) REFERENCES decks(id) ON DELETE CASCADE
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS tags (
+                    id BIGSERIAL PRIMARY KEY,
+                    deck_id BIGINT NOT NULL,
+                    name TEXT NOT NULL,
+                    FOREIGN KEY (deck_id) REFERENCES decks(id) ON DELETE CASCADE,
+                    UNIQUE(deck_id, name)
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS card_tags (
+                    card_id BIGINT NOT NULL,
+                    tag_id BIGINT NOT NULL,
+                    PRIMARY KEY (card_id, tag_id),
+                    FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE,
+                    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS review_logs (
+                    id BIGSERIAL PRIMARY KEY,
+                    deck_id BIGINT NOT NULL,
+                    card_id BIGINT NOT NULL,
+                    grade BIGINT NOT NULL CHECK (grade BETWEEN 0 AND 3),
+                    reviewed_at TIMESTAMPTZ NOT NULL,
+                    elapsed_days DOUBLE PRECISION NOT NULL,
+                    scheduled_days DOUBLE PRECISION NOT NULL,
+                    stability DOUBLE PRECISION NOT NULL,
+                    difficulty DOUBLE PRECISION NOT NULL,
+                    next_review_at TIMESTAMPTZ NOT NULL,
+                    FOREIGN KEY (deck_id) REFERENCES decks(id) ON DELETE CASCADE,
+                    FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS session_summaries (
+                    id BIGSERIAL PRIMARY KEY,
+                    deck_id BIGINT NOT NULL,
+                    started_at TIMESTAMPTZ NOT NULL,
+                    completed_at TIMESTAMPTZ NOT NULL,
+                    total_reviews BIGINT NOT NULL CHECK (total_reviews >= 0),
+                    again BIGINT NOT NULL CHECK (again >= 0),
+                    hard BIGINT NOT NULL CHECK (hard >= 0),
+                    good BIGINT NOT NULL CHECK (good >= 0),
+                    easy BIGINT NOT NULL CHECK (easy >= 0),
+                    FOREIGN KEY (deck_id) REFERENCES decks(id) ON DELETE CASCADE
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS app_settings (
+                    id BIGINT PRIMARY KEY CHECK (id = 1),
+                    api_key TEXT,
+                    api_model TEXT,
+                    api_fallback_model TEXT,
+                    ai_system_prompt TEXT,
+                    ai_daily_request_cap BIGINT,
+                    ai_cooldown_secs BIGINT
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS ai_price_book (
+                    provider TEXT NOT NULL,
+                    model TEXT NOT NULL,
+                    input_micro_usd_per_million BIGINT NOT NULL,
+                    output_micro_usd_per_million BIGINT NOT NULL,
+                    deprecated BIGINT NOT NULL DEFAULT 0,
+                    PRIMARY KEY (provider, model)
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS ai_usage (
+                    id BIGSERIAL PRIMARY KEY,
+                    provider TEXT NOT NULL,
+                    model TEXT NOT NULL,
+                    created_at TIMESTAMPTZ NOT NULL,
+                    status TEXT NOT NULL,
+                    prompt_tokens BIGINT,
+                    completion_tokens BIGINT,
+                    total_tokens BIGINT,
+                    cost_micro_usd BIGINT
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE INDEX IF NOT EXISTS idx_cards_deck_next_review
+                    ON cards(deck_id, next_review_at);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE INDEX IF NOT EXISTS idx_cards_deck_reviewcount_created
+                    ON cards(deck_id, review_count, created_at, id);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE INDEX IF NOT EXISTS idx_tags_deck_name
+                    ON tags(deck_id, name);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE INDEX IF NOT EXISTS idx_card_tags_card
+                    ON card_tags(card_id, tag_id);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE INDEX IF NOT EXISTS idx_card_tags_tag
+                    ON card_tags(tag_id, card_id);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE INDEX IF NOT EXISTS idx_review_logs_deck_card_reviewed_at
+                    ON review_logs (deck_id, card_id, reviewed_at);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE INDEX IF NOT EXISTS idx_session_summaries_deck_completed
+                    ON session_summaries (deck_id, completed_at);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO ai_price_book (
+                    provider, model, input_micro_usd_per_million, output_micro_usd_per_million, deprecated
+                )
+                VALUES
+                    ('openai', 'gpt-4.1-mini', 3000000, 15000000, 0),
+                    ('openai', 'gpt-4.1', 10000000, 30000000, 0),
+                    ('openai', 'gpt-4o-mini', 150000, 600000, 0)
+                ON CONFLICT(provider, model) DO NOTHING;
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(1_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 2: add min_interval_secs for sub-day scheduling.
+    if !is_applied(pool, 2).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN min_interval_secs BIGINT NOT NULL DEFAULT 86400
+                    CHECK (min_interval_secs >= 1);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                UPDATE decks
+                SET min_interval_secs = min_interval_days * 86400;
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(2_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 3: full-text index over card text (the `SQLite` backend uses FTS5).
+    if !is_applied(pool, 3).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                CREATE INDEX IF NOT EXISTS idx_cards_fts
+                    ON cards USING GIN (to_tsvector('simple', prompt || ' ' || answer));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(3_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 4: cloze cards keep their source text and hidden indices.
+    if !is_applied(pool, 4).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE cards ADD COLUMN cloze_text TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE cards ADD COLUMN cloze_indices TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(4_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 5: note linkage for sibling cards (e.g. reverse direction).
+    if !is_applied(pool, 5).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE cards ADD COLUMN note_id BIGINT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                CREATE INDEX IF NOT EXISTS idx_cards_deck_note
+                    ON cards(deck_id, note_id);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(5_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 6: cram sessions record summaries without touching scheduling.
+    if !is_applied(pool, 6).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE session_summaries
+                ADD COLUMN is_cram BIGINT NOT NULL DEFAULT 0 CHECK (is_cram IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(6_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 7: per-deck ordering of new cards among due reviews.
+    if !is_applied(pool, 7).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN new_review_mix TEXT NOT NULL DEFAULT 'after_reviews'
+                    CHECK (new_review_mix IN ('front_load', 'interleaved', 'after_reviews'));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(7_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 8: optional audio clip per card side.
+    if !is_applied(pool, 8).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE cards
+                ADD COLUMN prompt_audio_uri TEXT,
+                ADD COLUMN prompt_audio_hash TEXT,
+                ADD COLUMN answer_audio_uri TEXT,
+                ADD COLUMN answer_audio_hash TEXT;
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(8_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 9: stored card images, deduplicated by checksum.
+    if !is_applied(pool, 9).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS media (
+                    id BIGSERIAL PRIMARY KEY,
+                    checksum TEXT NOT NULL UNIQUE,
+                    mime_type TEXT NOT NULL,
+                    width BIGINT NOT NULL CHECK (width > 0),
+                    height BIGINT NOT NULL CHECK (height > 0),
+                    bytes BYTEA NOT NULL,
+                    created_at TIMESTAMPTZ NOT NULL
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(9_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 10: raw blobs share the media table, so image metadata becomes optional.
+    if !is_applied(pool, 10).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE media
+                    ALTER COLUMN mime_type DROP NOT NULL,
+                    ALTER COLUMN width DROP NOT NULL,
+                    ALTER COLUMN height DROP NOT NULL;
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(10_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 11: archived decks stay in storage but drop out of lists and planning.
+    if !is_applied(pool, 11).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN archived BIGINT NOT NULL DEFAULT 0 CHECK (archived IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(11_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 12: appearance and audio preferences join the app settings row.
+    if !is_applied(pool, 12).await? {
+        let mut tx = pool.begin().await?;
+
+        // Appearance and audio preferences; NULL means "use the default".
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN theme TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN accent_color TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN language TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN analytics_enabled BIGINT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN autoplay_audio BIGINT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN replay_audio_after_answer BIGINT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN audio_delay_ms BIGINT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(12_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 13: suspended cards stay in their deck but drop out of planning and due counts.
+    if !is_applied(pool, 13).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE cards
+                ADD COLUMN suspended BIGINT NOT NULL DEFAULT 0 CHECK (suspended IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(13_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 14: buried cards sit out of planning until the stored time passes.
+    if !is_applied(pool, 14).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE cards ADD COLUMN buried_until TIMESTAMPTZ;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(14_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 15: a deck revision counter so concurrent settings saves can detect conflicts.
+    if !is_applied(pool, 15).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE decks ADD COLUMN version BIGINT NOT NULL DEFAULT 0;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(15_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 16: target retention is now limited to 0.70-0.99; clamp older decks into range.
+    if !is_applied(pool, 16).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                UPDATE decks
+                SET fsrs_target_retention = LEAST(GREATEST(fsrs_target_retention, 0.7), 0.99);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(16_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 17: opt-in load balancing of review due dates.
+    if !is_applied(pool, 17).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN load_balance BIGINT NOT NULL DEFAULT 0
+                    CHECK (load_balance IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(17_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 18: learning steps per deck and each card's position within them. Existing
+    // decks get no steps so their cards keep graduating straight to review; new decks get
+    // the `DeckSettings` default.
+    if !is_applied(pool, 18).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN learning_steps TEXT NOT NULL DEFAULT '';
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE cards
+                ADD COLUMN learning_step BIGINT NOT NULL DEFAULT 0
+                    CHECK (learning_step >= 0);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(18_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 19: FSRS weights learned from each deck's review history.
+    if !is_applied(pool, 19).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN fsrs_parameters TEXT NOT NULL DEFAULT '';
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(19_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 20: the deck the app reopens on launch; NULL means "pick the first deck".
+    if !is_applied(pool, 20).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN last_deck_id BIGINT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(20_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 21: optional extra info shown beneath the answer; NULL means none.
+    if !is_applied(pool, 21).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE cards ADD COLUMN extra TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(21_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 22: unfinished sessions, saved after every answer so they survive a restart.
+    if !is_applied(pool, 22).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS session_progress (
+                    deck_id BIGINT PRIMARY KEY,
+                    started_at TIMESTAMPTZ NOT NULL,
+                    is_cram BIGINT NOT NULL DEFAULT 0 CHECK (is_cram IN (0, 1)),
+                    FOREIGN KEY (deck_id) REFERENCES decks(id) ON DELETE CASCADE
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS session_progress_answers (
+                    deck_id BIGINT NOT NULL,
+                    position BIGINT NOT NULL,
+                    card_id BIGINT NOT NULL,
+                    grade BIGINT NOT NULL CHECK (grade BETWEEN 0 AND 3),
+                    reviewed_at TIMESTAMPTZ NOT NULL,
+                    elapsed_days DOUBLE PRECISION NOT NULL,
+                    scheduled_days DOUBLE PRECISION NOT NULL,
+                    stability DOUBLE PRECISION NOT NULL,
+                    difficulty DOUBLE PRECISION NOT NULL,
+                    next_review_at TIMESTAMPTZ NOT NULL,
+                    PRIMARY KEY (deck_id, position),
+                    FOREIGN KEY (deck_id) REFERENCES session_progress(deck_id) ON DELETE CASCADE
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS session_progress_pending (
+                    deck_id BIGINT NOT NULL,
+                    position BIGINT NOT NULL,
+                    card_id BIGINT NOT NULL,
+                    next_review_at TIMESTAMPTZ NOT NULL,
+                    PRIMARY KEY (deck_id, position),
+                    FOREIGN KEY (deck_id) REFERENCES session_progress(deck_id) ON DELETE CASCADE
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(22_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 23: how many grade buttons the deck's sessions offer.
+    if !is_applied(pool, 23).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN answer_buttons TEXT NOT NULL DEFAULT 'four'
+                    CHECK (answer_buttons IN ('two', 'four'));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(23_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 24: per-card lapse counts and the deck's leech handling.
+    if !is_applied(pool, 24).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE cards
+                ADD COLUMN lapses BIGINT NOT NULL DEFAULT 0
+                    CHECK (lapses >= 0);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN leech_threshold BIGINT NOT NULL DEFAULT 8
+                    CHECK (leech_threshold >= 0);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN leech_action TEXT NOT NULL DEFAULT 'tag'
+                    CHECK (leech_action IN ('tag', 'suspend'));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(24_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 25: how long each answer took, in milliseconds; NULL means it was not timed.
+    if !is_applied(pool, 25).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE review_logs
+                ADD COLUMN answer_ms BIGINT
+                    CHECK (answer_ms IS NULL OR answer_ms >= 0);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE session_progress_answers
+                ADD COLUMN answer_ms BIGINT
+                    CHECK (answer_ms IS NULL OR answer_ms >= 0);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(25_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 26: favorite decks are pinned to the top of deck lists.
+    if !is_applied(pool, 26).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN favorite BIGINT NOT NULL DEFAULT 0 CHECK (favorite IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(26_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 27: optional preset color and icon that tell decks apart in lists.
+    if !is_applied(pool, 27).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN color TEXT CHECK (color IN (
+                    'red', 'orange', 'yellow', 'green', 'teal', 'blue', 'purple', 'pink', 'gray'
+                ));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN icon TEXT CHECK (icon IN (
+                    'book', 'language', 'science', 'math', 'music', 'code', 'globe', 'star', 'heart'
+                ));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(27_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 28: study-ahead sessions are told apart from regular ones.
+    if !is_applied(pool, 28).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE session_summaries
+                ADD COLUMN is_ahead BIGINT NOT NULL DEFAULT 0 CHECK (is_ahead IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE session_progress
+                ADD COLUMN is_ahead BIGINT NOT NULL DEFAULT 0 CHECK (is_ahead IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(28_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 29: active decks need distinct names, ignoring case and outer spaces.
+    if !is_applied(pool, 29).await? {
+        let mut tx = pool.begin().await?;
+
+        // Older duplicates get their ID appended so the index can be built.
+        sqlx::query(
+            r"
+                UPDATE decks
+                SET name = name || ' (' || id || ')'
+                WHERE archived = 0
+                  AND EXISTS (
+                      SELECT 1 FROM decks AS earlier
+                      WHERE earlier.archived = 0
+                        AND earlier.id < decks.id
+                        AND lower(trim(earlier.name)) = lower(trim(decks.name))
+                  );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE UNIQUE INDEX IF NOT EXISTS idx_decks_active_name
+                ON decks ((lower(trim(name))))
+                WHERE archived = 0;
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(29_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 30: deleted cards go to a trash and keep their history until purged.
+    if !is_applied(pool, 30).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE cards ADD COLUMN deleted_at TIMESTAMPTZ;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(30_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 31: decks can hold new cards back until the day's reviews are done.
+    if !is_applied(pool, 31).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN hold_new_until_reviews_done BIGINT NOT NULL DEFAULT 0
+                    CHECK (hold_new_until_reviews_done IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(31_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 32: the hour a study day starts; NULL means midnight.
+    if !is_applied(pool, 32).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN day_cutoff_hour BIGINT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(32_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 33: an optional colored flag per card.
+    if !is_applied(pool, 33).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE cards
+                ADD COLUMN flag TEXT
+                    CHECK (flag IN ('red', 'orange', 'green', 'blue'));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(33_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 34: optional per-deck front and back card templates.
+    if !is_applied(pool, 34).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN front_template TEXT,
+                ADD COLUMN back_template TEXT;
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(34_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 35: decks can turn off fuzzing of young cards' intervals.
+    if !is_applied(pool, 35).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN interval_fuzz BIGINT NOT NULL DEFAULT 1
+                    CHECK (interval_fuzz IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(35_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 36: whether review sessions hide everything but the card; NULL means off.
+    if !is_applied(pool, 36).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN focus_mode BIGINT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(36_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 37: cover the `id` tiebreak that history lists sort by after `completed_at`.
+    if !is_applied(pool, 37).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DROP INDEX IF EXISTS idx_session_summaries_deck_completed;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                CREATE INDEX IF NOT EXISTS idx_session_summaries_deck_completed_id
+                    ON session_summaries (deck_id, completed_at DESC, id DESC);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(37_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 38: whether queueing a card buries its note siblings for the day.
+    if !is_applied(pool, 38).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN bury_siblings BIGINT NOT NULL DEFAULT 1
+                    CHECK (bury_siblings IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(38_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 39: delays a lapsed card waits on before it returns to review. Existing decks
+    // get no steps so their lapses keep going straight back to review; new decks get the
+    // `DeckSettings` default.
+    if !is_applied(pool, 39).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN relearning_steps TEXT NOT NULL DEFAULT '';
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(39_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 40: Agains in a row that take a card out of the session it fails in.
+    if !is_applied(pool, 40).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN same_session_fail_limit BIGINT NOT NULL DEFAULT 4
+                    CHECK (same_session_fail_limit >= 0);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(40_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 41: a per-1k-token price for a preferred model missing from the price book.
+    if !is_applied(pool, 41).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN ai_manual_price_micro_usd_per_1k BIGINT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(41_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 42: whether long answers reveal one paragraph per keypress before grading.
+    if !is_applied(pool, 42).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN progressive_reveal BIGINT NOT NULL DEFAULT 0
+                    CHECK (progressive_reveal IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(42_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 43: whether sessions ask for the answer to be typed and checked before grading.
+    if !is_applied(pool, 43).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN typed_answer BIGINT NOT NULL DEFAULT 0
+                    CHECK (typed_answer IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(43_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 44: user key bindings for the editor and session shortcuts.
+    if !is_applied(pool, 44).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN key_bindings TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(44_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 45: last-write timestamps on decks and cards; existing rows are stamped now.
+    if !is_applied(pool, 45).await? {
+        let mut tx = pool.begin().await?;
+        let now = Utc::now();
+
+        for table in ["decks", "cards"] {
+            sqlx::query(&format!("ALTER TABLE {table} ADD COLUMN updated_at TIMESTAMPTZ;"))
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(&format!(
+                "UPDATE {table} SET updated_at = $1 WHERE updated_at IS NULL;"
+            ))
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(45_i64)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 46: whether new cards come out oldest first or in a per-day shuffle.
+    if !is_applied(pool, 46).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN new_card_order TEXT NOT NULL DEFAULT 'sequential'
+                    CHECK (new_card_order IN ('sequential', 'random'));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(46_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 47: how each card was created; left NULL for cards that predate it.
+    if !is_applied(pool, 47).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE cards
+                ADD COLUMN source TEXT
+                    CHECK (source IN ('manual', 'csv', 'anki', 'ai'));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(47_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 48: starter text the editor pre-fills new cards with; empty leaves them blank.
+    if !is_applied(pool, 48).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN new_card_prompt TEXT NOT NULL DEFAULT '';
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN new_card_answer TEXT NOT NULL DEFAULT '';
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(48_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 49: per-session cap on new cards, under the daily limit; zero turns it off.
+    if !is_applied(pool, 49).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN new_per_session BIGINT NOT NULL DEFAULT 0;
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(49_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 50: whether a review was the card's first, i.e. introduced it as a new card.
+    // Existing logs stay unmarked, so they never count against today's new-card limit.
+    if !is_applied(pool, 50).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE review_logs
+                ADD COLUMN first_review BIGINT NOT NULL DEFAULT 0
+                    CHECK (first_review IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE session_progress_answers
+                ADD COLUMN first_review BIGINT NOT NULL DEFAULT 0
+                    CHECK (first_review IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES ($1, $2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(50_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::{PgPool, postgres::PgPoolOptions};
+use thiserror::Error;
+
+use crate::repository::{
+    AiPriceBookRepository, AiUsageRepository, AppSettingsRepository, CardRepository, DeckRepository,
+    MediaRepository, ReviewLogRepository, ReviewPersistence, SessionProgressRepository,
+    SessionSummaryRepository, Storage, StorageError,
+};
+
+mod ai_price_book_repo;
+mod ai_usage_repo;
+mod app_settings_repo;
+mod card_repo;
+mod deck_repo;
+mod mapping;
+mod media_repo;
+mod migrate;
+mod review_log_repo;
+mod session_progress_repo;
+mod session_summary_repo;
+
+/// Postgres implementation of the repository traits.
+///
+/// Mirrors `SqliteRepository` query-for-query; integer and flag columns are stored as
+/// `BIGINT` so row decoding matches the `SQLite` backend.
+#[derive(Clone)]
+pub struct PostgresRepository {
+    pool: PgPool,
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum PostgresInitError {
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+}
+
+impl PostgresRepository {
+    /// Connect to Postgres using the given `postgres://` URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PostgresInitError` if the connection cannot be established.
+    pub async fn connect(database_url: &str) -> Result<Self, PostgresInitError> {
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .acquire_timeout(Duration::from_secs(5))
+            .connect(database_url)
+            .await?;
+        Ok(Self { pool })
+    }
+
+    #[must_use]
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Apply every schema migration this database has not recorded yet.
+    ///
+    /// Safe to run on every start: applied versions are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PostgresInitError` if migration queries fail.
+    pub async fn migrate(&self) -> Result<(), PostgresInitError> {
+        migrate::run_migrations(&self.pool).await
+    }
+
+    /// Highest migration version recorded in `schema_migrations`, or 0 before any ran.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Connection` if the table cannot be read, e.g. before `migrate`.
+    pub async fn current_schema_version(&self) -> Result<i64, StorageError> {
+        sqlx::query_scalar("SELECT COALESCE(MAX(version), 0) FROM schema_migrations")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))
+    }
+}
+
+/// Move a table's `id` sequence past rows written with explicit IDs.
+///
+/// `SQLite` rowids track this implicitly; Postgres sequences only advance on default inserts,
+/// so upserts of new rows would otherwise collide with later `insert_new_*` calls.
+async fn sync_id_sequence<'e, E>(executor: E, table: &'static str) -> Result<(), sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let sql = format!(
+        "SELECT setval(pg_get_serial_sequence('{table}', 'id'), GREATEST((SELECT MAX(id) FROM {table}), 1))"
+    );
+    sqlx::query(&sql).execute(executor).await?;
+    Ok(())
+}
+
+impl Storage {
+    /// Build a `Storage` backed by Postgres.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PostgresInitError` if connection or migrations cannot be
+    /// completed.
+    pub async fn postgres(database_url: &str) -> Result<Self, PostgresInitError> {
+        let repo = PostgresRepository::connect(database_url).await?;
+        repo.migrate().await?;
+
+        let deck_repo: Arc<dyn DeckRepository> = Arc::new(repo.clone());
+        let card_repo: Arc<dyn CardRepository> = Arc::new(repo.clone());
+        let log_repo: Arc<dyn ReviewLogRepository> = Arc::new(repo.clone());
+        let review_repo: Arc<dyn ReviewPersistence> = Arc::new(repo.clone());
+        let summary_repo: Arc<dyn SessionSummaryRepository> = Arc::new(repo.clone());
+        let progress_repo: Arc<dyn SessionProgressRepository> = Arc::new(repo.clone());
+        let app_settings_repo: Arc<dyn AppSettingsRepository> = Arc::new(repo.clone());
+        let ai_price_book_repo: Arc<dyn AiPriceBookRepository> = Arc::new(repo.clone());
+        let ai_usage_repo: Arc<dyn AiUsageRepository> = Arc::new(repo.clone());
+        let media_repo: Arc<dyn MediaRepository> = Arc::new(repo);
+        Ok(Self {
+            decks: deck_repo,
+            cards: card_repo,
+            review_logs: log_repo,
+            reviews: review_repo,
+            session_summaries: summary_repo,
+            session_progress: progress_repo,
+            app_settings: app_settings_repo,
+            ai_price_book: ai_price_book_repo,
+            ai_usage: ai_usage_repo,
+            media: media_repo,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repository_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<PostgresRepository>();
+    }
+}
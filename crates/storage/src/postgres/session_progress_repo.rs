@@ -0,0 +1,198 @@
+use learn_core::model::DeckId;
+use sqlx::Row;
+
+use super::{
+    PostgresRepository,
+    mapping::{answer_ms_from_column, card_id_from_i64, grade_from_i64, grade_to_i64},
+};
+use crate::repository::{
+    PendingSessionCard, ReviewLogRecord, SessionProgressRecord, SessionProgressRepository,
+    StorageError,
+};
+
+fn id_i64(field: &'static str, v: u64) -> Result<i64, StorageError> {
+    i64::try_from(v).map_err(|_| StorageError::Serialization(format!("{field} overflow")))
+}
+
+fn ser<E: core::fmt::Display>(e: E) -> StorageError {
+    StorageError::Serialization(e.to_string())
+}
+
+fn conn<E: core::fmt::Display>(e: E) -> StorageError {
+    StorageError::Connection(e.to_string())
+}
+
+fn position_i64(position: usize) -> Result<i64, StorageError> {
+    i64::try_from(position).map_err(|_| StorageError::Serialization("position overflow".into()))
+}
+
+#[async_trait::async_trait]
+impl SessionProgressRepository for PostgresRepository {
+    async fn save_progress(&self, progress: &SessionProgressRecord) -> Result<(), StorageError> {
+        let deck_id = id_i64("deck_id", progress.deck_id.value())?;
+        let mut tx = self.pool.begin().await.map_err(conn)?;
+
+        // Child rows go with the parent through ON DELETE CASCADE.
+        sqlx::query("DELETE FROM session_progress WHERE deck_id = $1")
+            .bind(deck_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(conn)?;
+
+        sqlx::query(
+            r"
+                INSERT INTO session_progress (deck_id, started_at, is_cram, is_ahead)
+                VALUES ($1, $2, $3, $4)
+            ",
+        )
+        .bind(deck_id)
+        .bind(progress.started_at)
+        .bind(i64::from(progress.is_cram))
+        .bind(i64::from(progress.is_ahead))
+        .execute(&mut *tx)
+        .await
+        .map_err(conn)?;
+
+        for (position, answer) in progress.answered.iter().enumerate() {
+            sqlx::query(
+                r"
+                    INSERT INTO session_progress_answers (
+                        deck_id, position, card_id, grade, reviewed_at,
+                        elapsed_days, scheduled_days, stability, difficulty, next_review_at,
+                        answer_ms, first_review
+                    )
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                ",
+            )
+            .bind(deck_id)
+            .bind(position_i64(position)?)
+            .bind(id_i64("card_id", answer.card_id.value())?)
+            .bind(grade_to_i64(answer.grade))
+            .bind(answer.reviewed_at)
+            .bind(answer.elapsed_days)
+            .bind(answer.scheduled_days)
+            .bind(answer.stability)
+            .bind(answer.difficulty)
+            .bind(answer.next_review_at)
+            .bind(answer.answer_ms.map(i64::from))
+            .bind(i64::from(answer.first_review))
+            .execute(&mut *tx)
+            .await
+            .map_err(conn)?;
+        }
+
+        for (position, pending) in progress.pending.iter().enumerate() {
+            sqlx::query(
+                r"
+                    INSERT INTO session_progress_pending (
+                        deck_id, position, card_id, next_review_at
+                    )
+                    VALUES ($1, $2, $3, $4)
+                ",
+            )
+            .bind(deck_id)
+            .bind(position_i64(position)?)
+            .bind(id_i64("card_id", pending.card_id.value())?)
+            .bind(pending.next_review_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(conn)?;
+        }
+
+        tx.commit().await.map_err(conn)
+    }
+
+    async fn get_progress(
+        &self,
+        deck_id: DeckId,
+    ) -> Result<Option<SessionProgressRecord>, StorageError> {
+        let deck = id_i64("deck_id", deck_id.value())?;
+        let Some(row) = sqlx::query(
+            "SELECT started_at, is_cram, is_ahead FROM session_progress WHERE deck_id = $1",
+        )
+        .bind(deck)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(conn)?
+        else {
+            return Ok(None);
+        };
+        let started_at = row.try_get("started_at").map_err(ser)?;
+        let is_cram = row.try_get::<i64, _>("is_cram").map_err(ser)? != 0;
+        let is_ahead = row.try_get::<i64, _>("is_ahead").map_err(ser)? != 0;
+
+        let answer_rows = sqlx::query(
+            r"
+                SELECT
+                    card_id, grade, reviewed_at, elapsed_days, scheduled_days,
+                    stability, difficulty, next_review_at, answer_ms, first_review
+                FROM session_progress_answers
+                WHERE deck_id = $1
+                ORDER BY position ASC
+            ",
+        )
+        .bind(deck)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(conn)?;
+        let answered = answer_rows
+            .iter()
+            .map(|row| {
+                Ok(ReviewLogRecord {
+                    id: None,
+                    deck_id,
+                    card_id: card_id_from_i64(row.try_get::<i64, _>("card_id").map_err(ser)?)?,
+                    grade: grade_from_i64(row.try_get::<i64, _>("grade").map_err(ser)?)?,
+                    reviewed_at: row.try_get("reviewed_at").map_err(ser)?,
+                    elapsed_days: row.try_get("elapsed_days").map_err(ser)?,
+                    scheduled_days: row.try_get("scheduled_days").map_err(ser)?,
+                    stability: row.try_get("stability").map_err(ser)?,
+                    difficulty: row.try_get("difficulty").map_err(ser)?,
+                    next_review_at: row.try_get("next_review_at").map_err(ser)?,
+                    answer_ms: answer_ms_from_column(row.try_get("answer_ms").map_err(ser)?)?,
+                    first_review: row.try_get::<i64, _>("first_review").map_err(ser)? != 0,
+                })
+            })
+            .collect::<Result<Vec<_>, StorageError>>()?;
+
+        let pending_rows = sqlx::query(
+            r"
+                SELECT card_id, next_review_at
+                FROM session_progress_pending
+                WHERE deck_id = $1
+                ORDER BY position ASC
+            ",
+        )
+        .bind(deck)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(conn)?;
+        let pending = pending_rows
+            .iter()
+            .map(|row| {
+                Ok(PendingSessionCard {
+                    card_id: card_id_from_i64(row.try_get::<i64, _>("card_id").map_err(ser)?)?,
+                    next_review_at: row.try_get("next_review_at").map_err(ser)?,
+                })
+            })
+            .collect::<Result<Vec<_>, StorageError>>()?;
+
+        Ok(Some(SessionProgressRecord {
+            deck_id,
+            started_at,
+            is_cram,
+            is_ahead,
+            answered,
+            pending,
+        }))
+    }
+
+    async fn clear_progress(&self, deck_id: DeckId) -> Result<(), StorageError> {
+        sqlx::query("DELETE FROM session_progress WHERE deck_id = $1")
+            .bind(id_i64("deck_id", deck_id.value())?)
+            .execute(&self.pool)
+            .await
+            .map_err(conn)?;
+        Ok(())
+    }
+}
@@ -1,10 +1,12 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use learn_core::model::{
-    AppSettings, Card, CardError, CardId, CardPhase, Deck, DeckId, DeckSettings, MediaId,
-    ReviewGrade, ReviewLog, ReviewOutcome, SessionSummary, Tag, TagId, TagName, content::Content,
+    AnswerButtons, AppSettings, Card, CardError, CardId, CardKind, CardPhase, CardSource, Deck,
+    DeckColor, DeckIcon, DeckId, DeckSettings, Flag, ImageFormat, ImageMeta, LeechAction,
+    MediaHash, MediaId, NewCardOrder, NewCardTemplate, NewReviewMix, NoteId, ReviewGrade,
+    ReviewLog, ReviewOutcome, SessionSummary, Tag, TagId, TagName, content::Content,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
@@ -21,6 +23,10 @@ pub enum StorageError {
     #[error("connection error: {0}")]
     Connection(String),
 
+    /// Another connection held the database for longer than writes wait for it.
+    #[error("database busy")]
+    Busy,
+
     #[error("serialization error: {0}")]
     Serialization(String),
 }
@@ -163,6 +169,17 @@ pub trait AiUsageRepository: Send + Sync {
     ///
     /// Returns `StorageError` on persistence failures.
     async fn sum_cost_since(&self, since: DateTime<Utc>) -> Result<u64, StorageError>;
+
+    /// List usage records created in `[start, end)`, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on persistence failures.
+    async fn list_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<AiUsageRecord>, StorageError>;
 }
 
 /// Persisted shape for a card, including lifecycle phase.
@@ -173,6 +190,8 @@ pub trait AiUsageRepository: Send + Sync {
 pub struct CardRecord {
     pub id: CardId,
     pub deck_id: DeckId,
+    pub kind: CardKind,
+    pub note_id: Option<NoteId>,
     pub prompt_text: String,
     pub prompt_media_id: Option<u64>,
     pub answer_text: String,
@@ -184,12 +203,21 @@ pub struct CardRecord {
     pub review_count: u32,
     pub stability: Option<f64>,
     pub difficulty: Option<f64>,
+    pub suspended: bool,
+    pub buried_until: Option<DateTime<Utc>>,
+    pub learning_step: u32,
+    pub lapses: u32,
+    pub extra_text: Option<String>,
+    pub flag: Option<Flag>,
+    pub source: Option<CardSource>,
 }
 
 /// Persisted shape for inserting a brand-new card (no ID yet).
 #[derive(Debug, Clone)]
 pub struct NewCardRecord {
     pub deck_id: DeckId,
+    pub kind: CardKind,
+    pub note_id: Option<NoteId>,
     pub prompt_text: String,
     pub prompt_media_id: Option<u64>,
     pub answer_text: String,
@@ -201,6 +229,10 @@ pub struct NewCardRecord {
     pub review_count: u32,
     pub stability: Option<f64>,
     pub difficulty: Option<f64>,
+    /// Extra info shown beneath the answer; `None` for cards without it.
+    pub extra_text: Option<String>,
+    /// How the card was created.
+    pub source: Option<CardSource>,
 }
 
 /// Persisted shape for inserting a brand-new deck (no ID yet).
@@ -229,14 +261,46 @@ pub struct NewDeckRecord {
     pub fsrs_target_retention: f32,
     pub fsrs_optimize_enabled: bool,
     pub fsrs_optimize_after: u32,
+    pub new_review_mix: NewReviewMix,
+    pub load_balance: bool,
+    pub learning_steps_secs: Vec<u32>,
+    pub fsrs_parameters: Vec<f32>,
+    pub answer_buttons: AnswerButtons,
+    pub leech_threshold: u32,
+    pub leech_action: LeechAction,
+    pub color: Option<DeckColor>,
+    pub icon: Option<DeckIcon>,
+    pub hold_new_until_reviews_done: bool,
+    pub front_template: Option<String>,
+    pub back_template: Option<String>,
+    pub interval_fuzz: bool,
+    pub bury_siblings: bool,
+    pub relearning_steps_secs: Vec<u32>,
+    pub same_session_fail_limit: u32,
+    pub progressive_reveal: bool,
+    pub typed_answer: bool,
+    pub new_card_order: NewCardOrder,
+    pub new_card_template: NewCardTemplate,
+    pub new_per_session: u32,
 }
 
-/// Aggregate card counts for a deck at a given time.
+/// Scheduling-based orderings for `CardRepository::list_cards_ordered`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardOrder {
+    /// Reviewed cards by `next_review_at` ascending; new cards last, oldest first.
+    DueDate,
+    /// Reviewed cards by FSRS difficulty, hardest first; new cards last, oldest first.
+    Difficulty,
+}
+
+/// Aggregate card counts for a deck at a given time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct DeckPracticeCounts {
     pub total: u32,
     pub due: u32,
     pub new: u32,
+    /// Due cards still in (re)learning; a subset of `due`.
+    pub learning: u32,
 }
 
 /// Aggregate card counts for a specific deck at a given time.
@@ -262,6 +326,71 @@ pub struct TagPracticeCounts {
     pub new: u32,
 }
 
+/// A deck's cards split by phase; suspended cards count only as suspended.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CardPhaseCounts {
+    pub total: u32,
+    pub new: u32,
+    /// Cards in learning or relearning.
+    pub learning: u32,
+    pub review: u32,
+    pub suspended: u32,
+}
+
+impl CardPhaseCounts {
+    /// Count `count` more cards in `phase`, or as suspended when `suspended` is set.
+    pub fn add(&mut self, phase: CardPhase, suspended: bool, count: u32) {
+        let bucket = if suspended {
+            &mut self.suspended
+        } else {
+            match phase {
+                CardPhase::New => &mut self.new,
+                CardPhase::Learning | CardPhase::Relearning => &mut self.learning,
+                CardPhase::Reviewing => &mut self.review,
+            }
+        };
+        *bucket = bucket.saturating_add(count);
+        self.total = self.total.saturating_add(count);
+    }
+}
+
+/// A deck's cards split by how long their current interval is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CardMaturityCounts {
+    pub new: u32,
+    /// Studied cards whose interval is shorter than the maturity threshold.
+    pub young: u32,
+    /// Studied cards whose interval reaches the maturity threshold.
+    pub mature: u32,
+}
+
+impl CardMaturityCounts {
+    /// Share of all cards that are mature, as a whole percentage; `0` for an empty deck.
+    #[must_use]
+    pub fn mature_percent(&self) -> u32 {
+        let total = u64::from(self.new) + u64::from(self.young) + u64::from(self.mature);
+        if total == 0 {
+            return 0;
+        }
+        let percent = (u64::from(self.mature) * 100 + total / 2) / total;
+        u32::try_from(percent).unwrap_or(100)
+    }
+}
+
+/// A card in the trash, with when it was deleted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrashedCard {
+    pub card: Card,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// A card found by `CardRepository::search_all_decks`, with the name of its deck.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeckCardMatch {
+    pub deck_name: String,
+    pub card: Card,
+}
+
 impl NewDeckRecord {
     #[must_use]
     pub fn from_deck(deck: &Deck) -> Self {
@@ -288,6 +417,27 @@ impl NewDeckRecord {
             fsrs_target_retention: deck.settings().fsrs_target_retention(),
             fsrs_optimize_enabled: deck.settings().fsrs_optimize_enabled(),
             fsrs_optimize_after: deck.settings().fsrs_optimize_after(),
+            new_review_mix: deck.settings().new_review_mix(),
+            load_balance: deck.settings().load_balance(),
+            learning_steps_secs: deck.settings().learning_steps_secs().to_vec(),
+            fsrs_parameters: deck.settings().fsrs_parameters().to_vec(),
+            answer_buttons: deck.settings().answer_buttons(),
+            leech_threshold: deck.settings().leech_threshold(),
+            leech_action: deck.settings().leech_action(),
+            color: deck.settings().color(),
+            icon: deck.settings().icon(),
+            hold_new_until_reviews_done: deck.settings().hold_new_until_reviews_done(),
+            front_template: deck.settings().front_template().map(|t| t.as_str().to_owned()),
+            back_template: deck.settings().back_template().map(|t| t.as_str().to_owned()),
+            interval_fuzz: deck.settings().interval_fuzz(),
+            bury_siblings: deck.settings().bury_siblings(),
+            relearning_steps_secs: deck.settings().relearning_steps_secs().to_vec(),
+            same_session_fail_limit: deck.settings().same_session_fail_limit(),
+            progressive_reveal: deck.settings().progressive_reveal(),
+            typed_answer: deck.settings().typed_answer(),
+            new_card_order: deck.settings().new_card_order(),
+            new_card_template: deck.settings().new_card_template().clone(),
+            new_per_session: deck.settings().new_per_session(),
         }
     }
 }
@@ -298,6 +448,8 @@ impl CardRecord {
         Self {
             id: card.id(),
             deck_id: card.deck_id(),
+            kind: card.kind().clone(),
+            note_id: card.note_id(),
             prompt_text: card.prompt().text().to_owned(),
             prompt_media_id: card.prompt().media_id().map(|m| m.value()),
             answer_text: card.answer().text().to_owned(),
@@ -309,6 +461,13 @@ impl CardRecord {
             review_count: card.review_count(),
             stability: card.memory_state().map(|m| m.stability),
             difficulty: card.memory_state().map(|m| m.difficulty),
+            suspended: card.is_suspended(),
+            buried_until: card.buried_until(),
+            learning_step: card.learning_step(),
+            lapses: card.lapses(),
+            extra_text: card.extra().map(|extra| extra.text().to_owned()),
+            flag: card.flag(),
+            source: card.source(),
         }
     }
 
@@ -324,6 +483,11 @@ impl CardRecord {
         let answer =
             Content::from_persisted(self.answer_text, self.answer_media_id.map(MediaId::new))
                 .map_err(CardError::InvalidAnswer)?;
+        let extra = self
+            .extra_text
+            .map(|text| Content::from_persisted(text, None))
+            .transpose()
+            .map_err(CardError::InvalidExtra)?;
 
         // For brand-new cards (review_count == 0), stability/difficulty are semantically unset.
         // We allow `None` in storage and normalize to 0.0 for the persisted constructor.
@@ -341,6 +505,7 @@ impl CardRecord {
         Card::from_persisted(
             self.id,
             self.deck_id,
+            self.kind,
             prompt,
             answer,
             self.created_at,
@@ -351,6 +516,16 @@ impl CardRecord {
             stability,
             difficulty,
         )
+        .map(|card| {
+            card.with_note_id(self.note_id)
+                .with_suspended(self.suspended)
+                .with_buried_until(self.buried_until)
+                .with_learning_step(self.learning_step)
+                .with_lapses(self.lapses)
+                .with_extra(extra)
+                .with_flag(self.flag)
+                .with_source(self.source)
+        })
     }
 }
 
@@ -367,6 +542,10 @@ pub struct ReviewLogRecord {
     pub stability: f64,
     pub difficulty: f64,
     pub next_review_at: DateTime<Utc>,
+    /// Milliseconds from showing the card to grading it; `None` when it was not timed.
+    pub answer_ms: Option<u32>,
+    /// The review introduced the card, which had never been reviewed before.
+    pub first_review: bool,
 }
 
 impl ReviewLogRecord {
@@ -388,6 +567,8 @@ impl ReviewLogRecord {
             stability: outcome.stability,
             difficulty: outcome.difficulty,
             next_review_at: outcome.next_review,
+            answer_ms: log.answer_ms,
+            first_review: log.first_review,
         }
     }
 
@@ -398,6 +579,32 @@ impl ReviewLogRecord {
     }
 }
 
+/// Which review logs `ReviewLogRepository::prune` deletes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewLogPrune {
+    /// Keep each card's `n` newest reviews and delete the rest.
+    KeepLastPerCard(u32),
+    /// Delete reviews made before this time.
+    OlderThan(DateTime<Utc>),
+}
+
+/// What `DeckRepository::merge_decks` does with a source card whose prompt the
+/// destination deck already has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeConflict {
+    /// Leave the card behind in the archived source deck.
+    Skip,
+    /// Move it anyway, so the destination holds both cards.
+    KeepBoth,
+}
+
+/// Cards a deck merge moved and cards it left behind under `MergeConflict::Skip`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeckMergeCounts {
+    pub moved: u32,
+    pub skipped: u32,
+}
+
 /// Repository contract for decks and cards.
 #[async_trait]
 pub trait DeckRepository: Send + Sync {
@@ -415,6 +622,17 @@ pub trait DeckRepository: Send + Sync {
     /// Returns `StorageError` if the deck cannot be stored.
     async fn upsert_deck(&self, deck: &Deck) -> Result<(), StorageError>;
 
+    /// Update a deck only while its stored version still equals `deck.version()`.
+    ///
+    /// Returns the new stored version.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::NotFound` if the deck does not exist.
+    /// Returns `StorageError::Conflict` if the deck was written since it was loaded.
+    /// Returns `StorageError` on other storage failures.
+    async fn update_deck_if_version(&self, deck: &Deck) -> Result<u64, StorageError>;
+
     /// Fetch a deck by ID.
     ///
     /// Returns `Ok(None)` when the deck does not exist.
@@ -424,12 +642,52 @@ pub trait DeckRepository: Send + Sync {
     /// Returns `StorageError` on storage failures.
     async fn get_deck(&self, id: DeckId) -> Result<Option<Deck>, StorageError>;
 
-    /// List decks up to the given limit, ordered by ID.
+    /// List decks up to the given limit, favorites first and then by ID.
+    ///
+    /// Archived decks are skipped unless `include_archived` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on storage failures.
+    async fn list_decks(
+        &self,
+        limit: u32,
+        include_archived: bool,
+    ) -> Result<Vec<Deck>, StorageError>;
+
+    /// Insert `deck` and copy every card of `source` into it, in one transaction.
+    ///
+    /// Copies keep their content, kind, media and audio references, and tags (recreated by
+    /// name in the new deck). They start as fresh `New` cards created at `deck.created_at`;
+    /// review history and note links are not copied.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::NotFound` if `source` does not exist.
+    /// Returns `StorageError` on storage failures.
+    async fn duplicate_deck(
+        &self,
+        source: DeckId,
+        deck: NewDeckRecord,
+    ) -> Result<DeckId, StorageError>;
+
+    /// Move the cards of `source` into `dest` and archive `source`, in one transaction.
+    ///
+    /// Moved cards keep their scheduling, review history, and media and audio references;
+    /// as with `CardRepository::move_card`, tags are recreated by name in `dest` and note
+    /// links are dropped. A card whose prompt (trimmed, case-insensitive) `dest` already
+    /// has is moved or left in `source` as `on_conflict` says. Trashed cards stay behind.
     ///
     /// # Errors
     ///
+    /// Returns `StorageError::NotFound` if either deck does not exist.
     /// Returns `StorageError` on storage failures.
-    async fn list_decks(&self, limit: u32) -> Result<Vec<Deck>, StorageError>;
+    async fn merge_decks(
+        &self,
+        source: DeckId,
+        dest: DeckId,
+        on_conflict: MergeConflict,
+    ) -> Result<DeckMergeCounts, StorageError>;
 }
 
 #[async_trait]
@@ -441,6 +699,18 @@ pub trait CardRepository: Send + Sync {
     /// Returns `StorageError` if persistence fails.
     async fn insert_new_card(&self, card: NewCardRecord) -> Result<CardId, StorageError>;
 
+    /// Insert several brand-new cards atomically, returning IDs in input order.
+    ///
+    /// Either every card is stored or none are.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` if persistence fails.
+    async fn insert_new_cards(
+        &self,
+        cards: Vec<NewCardRecord>,
+    ) -> Result<Vec<CardId>, StorageError>;
+
     /// Persist or update a card with phase information.
     ///
     /// # Errors
@@ -448,6 +718,15 @@ pub trait CardRepository: Send + Sync {
     /// Returns `StorageError` if the card cannot be stored.
     async fn upsert_card(&self, card: &Card) -> Result<(), StorageError>;
 
+    /// Persist or update several cards atomically.
+    ///
+    /// Either every card is stored or none are.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` if any card cannot be stored.
+    async fn upsert_cards(&self, cards: &[Card]) -> Result<(), StorageError>;
+
     /// Delete a card by ID within a deck.
     ///
     /// # Errors
@@ -456,6 +735,82 @@ pub trait CardRepository: Send + Sync {
     /// Returns `StorageError` on storage failures.
     async fn delete_card(&self, deck_id: DeckId, card_id: CardId) -> Result<(), StorageError>;
 
+    /// Move a card to the trash at `deleted_at`.
+    ///
+    /// Trashed cards keep their review history and tags but are left out of every other
+    /// card query until restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::NotFound` if the card is missing or already in the trash.
+    /// Returns `StorageError` on storage failures.
+    async fn trash_card(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<(), StorageError>;
+
+    /// Move several cards to the trash at `deleted_at`, all or none.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::NotFound`, trashing nothing, if any card is missing from the deck
+    /// or already in the trash.
+    /// Returns `StorageError` on storage failures.
+    async fn trash_cards(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+        deleted_at: DateTime<Utc>,
+    ) -> Result<(), StorageError>;
+
+    /// Take a card out of the trash.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::NotFound` if the card is not in the deck's trash.
+    /// Returns `StorageError` on storage failures.
+    async fn restore_card(&self, deck_id: DeckId, card_id: CardId) -> Result<(), StorageError>;
+
+    /// List a deck's trashed cards, most recently deleted first.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on storage failures.
+    async fn list_trashed_cards(
+        &self,
+        deck_id: DeckId,
+        limit: u32,
+    ) -> Result<Vec<TrashedCard>, StorageError>;
+
+    /// Permanently delete every card trashed before `deleted_before`, along with its
+    /// history, returning how many were deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on storage failures.
+    async fn purge_trashed_cards(&self, deleted_before: DateTime<Utc>)
+    -> Result<u64, StorageError>;
+
+    /// Move a card, its review logs and its tags from one deck to another.
+    ///
+    /// Tags are recreated by name in the destination deck. The card leaves any note it
+    /// belonged to, since notes are scoped to a deck. Moving a card to its own deck is a
+    /// no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::NotFound` if `to_deck` does not exist or the card is not in
+    /// `from_deck`.
+    /// Returns `StorageError` on storage failures.
+    async fn move_card(
+        &self,
+        card_id: CardId,
+        from_deck: DeckId,
+        to_deck: DeckId,
+    ) -> Result<(), StorageError>;
+
     /// Fetch cards for a deck by IDs.
     ///
     /// # Errors
@@ -465,6 +820,8 @@ pub trait CardRepository: Send + Sync {
 
     /// Fetch due cards for a deck up to the given limit, ordered by next review time.
     ///
+    /// Cards buried past `now` are skipped.
+    ///
     /// # Errors
     ///
     /// Returns `StorageError` on connection or serialization failure.
@@ -475,12 +832,30 @@ pub trait CardRepository: Send + Sync {
         limit: u32,
     ) -> Result<Vec<Card>, StorageError>;
 
+    /// List next review times of reviewed cards due at or before `until`, ascending.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on connection or serialization failure.
+    async fn due_times(
+        &self,
+        deck_id: DeckId,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<DateTime<Utc>>, StorageError>;
+
     /// Fetch new (unreviewed) cards for a deck up to the given limit, ordered by creation time.
     ///
+    /// Cards buried past `now` are skipped.
+    ///
     /// # Errors
     ///
     /// Returns `StorageError` on connection or serialization failure.
-    async fn new_cards(&self, deck_id: DeckId, limit: u32) -> Result<Vec<Card>, StorageError>;
+    async fn new_cards(
+        &self,
+        deck_id: DeckId,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError>;
 
     /// List cards for a deck up to the given limit.
     ///
@@ -491,6 +866,49 @@ pub trait CardRepository: Send + Sync {
     /// Returns `StorageError` on connection or serialization failure.
     async fn list_cards(&self, deck_id: DeckId, limit: u32) -> Result<Vec<Card>, StorageError>;
 
+    /// List up to `limit` cards for a deck after skipping the first `offset`.
+    ///
+    /// Ordered like `list_cards`, so consecutive pages neither overlap nor skip cards.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on connection or serialization failure.
+    async fn list_cards_page(
+        &self,
+        deck_id: DeckId,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError>;
+
+    /// List up to `limit` cards for a deck with IDs above `after`, in ascending ID order.
+    ///
+    /// Pass the last ID of one page as `after` for the next, starting from `None`; pages
+    /// neither overlap nor skip cards even when cards are added or deleted in between.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on connection or serialization failure.
+    async fn list_cards_after(
+        &self,
+        deck_id: DeckId,
+        after: Option<CardId>,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError>;
+
+    /// List cards for a deck up to the given limit, in a scheduling-based `order`.
+    ///
+    /// Ties are broken by `created_at` ascending, then `id` ascending.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on connection or serialization failure.
+    async fn list_cards_ordered(
+        &self,
+        deck_id: DeckId,
+        order: CardOrder,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError>;
+
     /// Count cards created in a time range for a deck.
     ///
     /// The range is inclusive of `start` and exclusive of `end`.
@@ -512,6 +930,27 @@ pub trait CardRepository: Send + Sync {
     /// Returns `StorageError` on connection or serialization failure.
     async fn mistakes_count(&self, deck_id: DeckId) -> Result<u32, StorageError>;
 
+    /// Count a deck's cards by phase in one grouped query, without loading them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on connection or serialization failure.
+    async fn count_cards_by_phase(&self, deck_id: DeckId) -> Result<CardPhaseCounts, StorageError>;
+
+    /// Count a deck's cards as new, young or mature in one grouped query.
+    ///
+    /// A studied card is mature once the gap between its last review and its next one is
+    /// at least `mature_days` days.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on connection or serialization failure.
+    async fn count_cards_by_maturity(
+        &self,
+        deck_id: DeckId,
+        mature_days: u32,
+    ) -> Result<CardMaturityCounts, StorageError>;
+
     /// Reset learning state for all cards in a deck.
     ///
     /// Resets phase to `New`, clears review metadata, and sets `next_review_at` to `now`.
@@ -525,6 +964,37 @@ pub trait CardRepository: Send + Sync {
         now: DateTime<Utc>,
     ) -> Result<u64, StorageError>;
 
+    /// Reset one card's learning state the way `reset_deck_learning` does.
+    ///
+    /// With `clear_history`, the card's review logs are deleted in the same transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::NotFound` if the card is not in the deck.
+    /// Returns `StorageError` on connection or serialization failure.
+    async fn reset_card_learning(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        now: DateTime<Utc>,
+        clear_history: bool,
+    ) -> Result<(), StorageError>;
+
+    /// Move the due dates of cards in a deck, leaving the rest of their state alone.
+    ///
+    /// Runs in one transaction: either every card moves or none do. Returns the number of
+    /// cards updated.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::NotFound` if any card is not in the deck.
+    /// Returns `StorageError` on connection or serialization failure.
+    async fn reschedule_cards(
+        &self,
+        deck_id: DeckId,
+        due_dates: &[(CardId, DateTime<Utc>)],
+    ) -> Result<u64, StorageError>;
+
     /// Count total, new, and due cards for a deck at the given time.
     ///
     /// # Errors
@@ -569,6 +1039,67 @@ pub trait CardRepository: Send + Sync {
         tag_names: &[TagName],
     ) -> Result<Vec<Card>, StorageError>;
 
+    /// List cards for a deck that carry every one of the provided tags.
+    ///
+    /// An empty tag set matches all cards, as `list_cards` would. Ordered like `list_cards`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on connection or serialization failure.
+    async fn list_cards_with_all_tags(
+        &self,
+        deck_id: DeckId,
+        tag_names: &[TagName],
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError>;
+
+    /// List cards for a deck that have no tags. Ordered like `list_cards`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on connection or serialization failure.
+    async fn list_untagged_cards(&self, deck_id: DeckId, limit: u32)
+    -> Result<Vec<Card>, StorageError>;
+
+    /// List cards sharing a note, ordered by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on connection or serialization failure.
+    async fn list_note_cards(
+        &self,
+        deck_id: DeckId,
+        note_id: NoteId,
+    ) -> Result<Vec<Card>, StorageError>;
+
+    /// Search prompt and answer text, best matches first.
+    ///
+    /// The query is split into words; every word must match the start of a word in the card
+    /// (case-insensitive). A query without words returns no cards.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on connection or serialization failure.
+    async fn search_cards(
+        &self,
+        deck_id: DeckId,
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError>;
+
+    /// Search prompt and answer text in every deck, best matches first.
+    ///
+    /// Words match as in `search_cards`; each card comes with its deck's name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on connection or serialization failure.
+    async fn search_all_decks(
+        &self,
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<DeckCardMatch>, StorageError>;
+
     /// Returns true if a card with the given prompt exists in the deck.
     ///
     /// Comparison is normalized (trimmed, case-insensitive).
@@ -601,29 +1132,81 @@ pub trait CardRepository: Send + Sync {
         card_id: CardId,
     ) -> Result<Vec<Tag>, StorageError>;
 
-    /// Replace the tags for a card, creating new tags if needed.
+    /// List tags attached to each of the given cards, ordered by card ID then tag name.
+    ///
+    /// Cards without tags (or not in the deck) are omitted.
     ///
     /// # Errors
     ///
     /// Returns `StorageError` on storage failures.
-    async fn set_tags_for_card(
+    async fn list_tags_for_cards(
         &self,
         deck_id: DeckId,
-        card_id: CardId,
-        tag_names: &[TagName],
-    ) -> Result<Vec<Tag>, StorageError>;
-}
+        card_ids: &[CardId],
+    ) -> Result<Vec<(CardId, Tag)>, StorageError>;
 
-#[async_trait]
-pub trait ReviewLogRepository: Send + Sync {
-    /// Append a review log, returning the assigned ID.
+    /// Create a tag in a deck if it does not exist, returning the stored tag.
     ///
     /// # Errors
     ///
-    /// Returns `StorageError` if the log cannot be persisted or serialized.
-    async fn append_log(&self, log: ReviewLogRecord) -> Result<i64, StorageError>;
+    /// Returns `StorageError` on storage failures.
+    async fn upsert_tag(&self, deck_id: DeckId, name: &TagName) -> Result<Tag, StorageError>;
 
-    /// Fetch all logs for a card, ordered by review time.
+    /// Replace the tags for a card, creating new tags if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on storage failures.
+    async fn set_tags_for_card(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        tag_names: &[TagName],
+    ) -> Result<Vec<Tag>, StorageError>;
+
+
+    /// Attach every tag in `tag_names` to each card in `card_ids`, creating unknown tags,
+    /// in one transaction.
+    ///
+    /// Returns how many cards gained at least one tag; cards that already carried them all
+    /// are left as they were.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on storage failures; nothing is changed.
+    async fn add_tags_to_cards(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+        tag_names: &[TagName],
+    ) -> Result<u64, StorageError>;
+
+    /// Detach every tag in `tag_names` from each card in `card_ids` in one transaction.
+    ///
+    /// Returns how many cards lost at least one tag. Names the deck has no tag for are
+    /// ignored, and the tags themselves stay in the deck.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on storage failures; nothing is changed.
+    async fn remove_tags_from_cards(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+        tag_names: &[TagName],
+    ) -> Result<u64, StorageError>;
+}
+
+#[async_trait]
+pub trait ReviewLogRepository: Send + Sync {
+    /// Append a review log, returning the assigned ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` if the log cannot be persisted or serialized.
+    async fn append_log(&self, log: ReviewLogRecord) -> Result<i64, StorageError>;
+
+    /// Fetch all logs for a card, ordered by review time.
     ///
     /// # Errors
     ///
@@ -633,16 +1216,74 @@ pub trait ReviewLogRepository: Send + Sync {
         deck_id: DeckId,
         card_id: CardId,
     ) -> Result<Vec<ReviewLogRecord>, StorageError>;
+
+    /// Fetch every log in a deck, ordered by review time.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on storage failures or deserialization issues.
+    async fn logs_for_deck(&self, deck_id: DeckId) -> Result<Vec<ReviewLogRecord>, StorageError>;
+
+    /// Fetch up to `limit` logs in a deck with IDs above `after_id`, in ID order.
+    ///
+    /// Pass the last ID of a page as `after_id` to read the next one; `None` starts from
+    /// the oldest log. Lets callers walk long histories without loading them at once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on storage failures or deserialization issues.
+    async fn logs_for_deck_page(
+        &self,
+        deck_id: DeckId,
+        after_id: Option<i64>,
+        limit: u32,
+    ) -> Result<Vec<ReviewLogRecord>, StorageError>;
+
+    /// Count the deck's cards introduced at or after `since`, from logs marked as a card's
+    /// first review.
+    ///
+    /// Pruning older logs never makes a reviewed card look new again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on storage failures.
+    async fn count_introduced_since(
+        &self,
+        deck_id: DeckId,
+        since: DateTime<Utc>,
+    ) -> Result<u32, StorageError>;
+
+    /// Delete a deck's review logs matched by `prune`, returning how many were deleted.
+    ///
+    /// Cards keep their scheduling state; only the history behind it goes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on storage failures.
+    async fn prune(&self, deck_id: DeckId, prune: ReviewLogPrune) -> Result<u64, StorageError>;
 }
 
 #[async_trait]
 pub trait ReviewPersistence: Send + Sync {
     /// Persist a card update and the associated review log atomically.
     ///
+    /// The card's `updated_at` is stamped with the log's `reviewed_at`.
+    ///
     /// # Errors
     ///
+    /// Returns `StorageError::Busy` if another writer holds the database through every retry.
     /// Returns `StorageError` if persistence fails or if the log/card IDs mismatch.
     async fn apply_review(&self, card: &Card, log: ReviewLogRecord) -> Result<i64, StorageError>;
+
+    /// Undo `apply_review`: restore the card's scheduling state and delete the log atomically.
+    ///
+    /// Only scheduling fields are written; content edits made since are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::NotFound` if the card or the log for it is missing.
+    /// Returns `StorageError` on storage failures.
+    async fn revert_review(&self, card: &Card, log_id: i64) -> Result<(), StorageError>;
 }
 
 /// A session summary paired with its storage-assigned identifier.
@@ -678,6 +1319,13 @@ pub trait SessionSummaryRepository: Send + Sync {
     /// Returns `StorageError::NotFound` if missing.
     async fn get_summary(&self, id: i64) -> Result<SessionSummary, StorageError>;
 
+    /// Delete a session summary by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::NotFound` if missing.
+    async fn delete_summary(&self, id: i64) -> Result<(), StorageError>;
+
     /// List session summaries for a deck within an optional time range.
     ///
     /// Results are ordered by `completed_at` descending, limited by `limit`.
@@ -717,25 +1365,169 @@ pub trait SessionSummaryRepository: Send + Sync {
         &self,
         deck_ids: &[DeckId],
     ) -> Result<Vec<SessionSummaryRow>, StorageError>;
+
+    /// List session summaries across every deck completed in `[completed_from, completed_until)`.
+    ///
+    /// Results are ordered by `completed_at` ascending, then `id` ascending.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on persistence failures.
+    async fn list_summaries_between(
+        &self,
+        completed_from: DateTime<Utc>,
+        completed_until: DateTime<Utc>,
+    ) -> Result<Vec<SessionSummary>, StorageError>;
+}
+
+/// A card still waiting in a saved session, with the due time it had when the session was saved.
+///
+/// A stored card whose due time no longer matches was reviewed or rescheduled since.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingSessionCard {
+    pub card_id: CardId,
+    pub next_review_at: DateTime<Utc>,
+}
+
+/// An unfinished session saved so it can be picked up again after a restart.
+#[derive(Debug, Clone)]
+pub struct SessionProgressRecord {
+    pub deck_id: DeckId,
+    pub started_at: DateTime<Utc>,
+    pub is_cram: bool,
+    pub is_ahead: bool,
+    /// Answers given so far, oldest first; `id` is always `None`.
+    pub answered: Vec<ReviewLogRecord>,
+    /// Cards still to answer, in queue order.
+    pub pending: Vec<PendingSessionCard>,
+}
+
+/// Keeps at most one unfinished session per deck.
+#[async_trait]
+pub trait SessionProgressRepository: Send + Sync {
+    /// Save the session for its deck, replacing whatever was saved for that deck before.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on persistence failures.
+    async fn save_progress(&self, progress: &SessionProgressRecord) -> Result<(), StorageError>;
+
+    /// Fetch the session saved for a deck, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on persistence failures.
+    async fn get_progress(
+        &self,
+        deck_id: DeckId,
+    ) -> Result<Option<SessionProgressRecord>, StorageError>;
+
+    /// Forget the session saved for a deck; a no-op when there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on persistence failures.
+    async fn clear_progress(&self, deck_id: DeckId) -> Result<(), StorageError>;
+}
+
+/// Encoded image bytes ready to persist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewMediaRecord {
+    pub format: ImageFormat,
+    pub meta: ImageMeta,
+    pub checksum: MediaHash,
+    pub bytes: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A stored image referenced by card content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaRecord {
+    pub id: MediaId,
+    pub format: ImageFormat,
+    pub meta: ImageMeta,
+    pub checksum: MediaHash,
+    pub bytes: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Content-addressed store for card images and other media blobs.
+///
+/// Rows are keyed on `MediaHash`, so the same bytes used on many cards are stored once.
+#[async_trait]
+pub trait MediaRepository: Send + Sync {
+    /// Store an image, returning the existing ID when its checksum is already stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on persistence failures.
+    async fn insert_media(&self, media: NewMediaRecord) -> Result<MediaId, StorageError>;
+
+    /// Fetch a stored image by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on persistence failures.
+    async fn get_media(&self, id: MediaId) -> Result<Option<MediaRecord>, StorageError>;
+
+    /// Store raw bytes under their hash; storing an existing hash is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on persistence failures.
+    async fn put_blob(
+        &self,
+        hash: &MediaHash,
+        bytes: Vec<u8>,
+        created_at: DateTime<Utc>,
+    ) -> Result<(), StorageError>;
+
+    /// Fetch the bytes stored under a hash, whether written as a blob or an image.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on persistence failures.
+    async fn get_blob(&self, hash: &MediaHash) -> Result<Option<Vec<u8>>, StorageError>;
+
+    /// Whether any bytes are stored under a hash.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on persistence failures.
+    async fn exists(&self, hash: &MediaHash) -> Result<bool, StorageError>;
+
+    /// Delete media that no card references by image ID or audio hash.
+    ///
+    /// Meant to run after bulk deletes; returns the number of rows removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError` on persistence failures.
+    async fn gc_unreferenced(&self) -> Result<u64, StorageError>;
 }
 
 #[derive(Default)]
 struct InMemState {
     decks: HashMap<DeckId, Deck>,
     cards: HashMap<CardId, Card>,
+    trashed: HashMap<CardId, TrashedCard>,
     tags: HashMap<TagId, Tag>,
     card_tags: HashMap<CardId, Vec<TagId>>,
     logs: Vec<ReviewLogRecord>,
     summaries: HashMap<i64, SessionSummary>,
+    session_progress: HashMap<DeckId, SessionProgressRecord>,
     app_settings: Option<AppSettings>,
     ai_price_book: HashMap<(String, String), AiPriceBookEntry>,
     ai_usage: Vec<AiUsageRecord>,
+    media: HashMap<MediaId, MediaRecord>,
+    blobs: HashMap<String, Vec<u8>>,
     next_deck_id: u64,
     next_card_id: u64,
     next_tag_id: u64,
     next_log_id: i64,
     next_summary_id: i64,
     next_ai_usage_id: i64,
+    next_media_id: u64,
 }
 
 /// Simple in-memory repository implementation for testing and prototyping.
@@ -754,6 +1546,7 @@ impl InMemoryRepository {
             next_log_id: 1,
             next_summary_id: 1,
             next_ai_usage_id: 1,
+            next_media_id: 1,
             ..InMemState::default()
         };
         seed_price_book(&mut state);
@@ -906,12 +1699,210 @@ impl AiUsageRepository for InMemoryRepository {
             .fold(0_u64, u64::saturating_add);
         Ok(total)
     }
+
+    async fn list_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<AiUsageRecord>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let mut records: Vec<AiUsageRecord> = guard
+            .ai_usage
+            .iter()
+            .filter(|record| record.created_at >= start && record.created_at < end)
+            .cloned()
+            .collect();
+        records.sort_by_key(|record| (record.created_at, record.id));
+        Ok(records)
+    }
+}
+
+fn tally_practice_counts(counts: &mut DeckPracticeCounts, card: &Card, now: DateTime<Utc>) {
+    counts.total = counts.total.saturating_add(1);
+    if card.is_suspended() {
+        return;
+    }
+    if card.is_new() {
+        counts.new = counts.new.saturating_add(1);
+    } else if card.is_due(now) {
+        counts.due = counts.due.saturating_add(1);
+        if matches!(card.phase(), CardPhase::Learning | CardPhase::Relearning) {
+            counts.learning = counts.learning.saturating_add(1);
+        }
+    }
+}
+
+/// `card` back in the `New` phase with its review metadata cleared and due at `now`.
+fn reset_learning(card: &Card, now: DateTime<Utc>) -> Result<Card, StorageError> {
+    Card::from_persisted(
+        card.id(),
+        card.deck_id(),
+        card.kind().clone(),
+        card.prompt().clone(),
+        card.answer().clone(),
+        card.created_at(),
+        now,
+        None,
+        CardPhase::New,
+        0,
+        0.0,
+        0.0,
+    )
+    .map_err(|e| StorageError::Serialization(e.to_string()))
+    .map(|reset| {
+        reset
+            .with_note_id(card.note_id())
+            .with_suspended(card.is_suspended())
+            .with_buried_until(card.buried_until())
+            .with_extra(card.extra().cloned())
+            .with_flag(card.flag())
+            .with_source(card.source())
+    })
+}
+
+/// Move `card` into `to_deck` along with its review logs, relinking its tags to same-named
+/// tags there (created as needed) and dropping its note link.
+fn move_card_in_state(
+    state: &mut InMemState,
+    card: Card,
+    to_deck: DeckId,
+) -> Result<(), StorageError> {
+    let card_id = card.id();
+    let from_deck = card.deck_id();
+    let mut moved = card.with_note_id(None);
+    moved.move_to_deck(to_deck);
+    state.cards.insert(card_id, moved);
+
+    for log in &mut state.logs {
+        if log.card_id == card_id && log.deck_id == from_deck {
+            log.deck_id = to_deck;
+        }
+    }
+
+    let names: Vec<TagName> = state
+        .card_tags
+        .get(&card_id)
+        .into_iter()
+        .flatten()
+        .filter_map(|tag_id| state.tags.get(tag_id))
+        .map(|tag| tag.name().clone())
+        .collect();
+    let mut tag_ids = Vec::with_capacity(names.len());
+    for name in names {
+        let existing = state
+            .tags
+            .values()
+            .find(|tag| tag.deck_id() == to_deck && tag.name() == &name)
+            .map(Tag::id);
+        let tag_id = if let Some(id) = existing {
+            id
+        } else {
+            let id = state.next_tag_id;
+            state.next_tag_id = id
+                .checked_add(1)
+                .ok_or_else(|| StorageError::Serialization("tag_id overflow".into()))?;
+            let tag_id = TagId::new(id);
+            state.tags.insert(tag_id, Tag::new(tag_id, to_deck, name));
+            tag_id
+        };
+        tag_ids.push(tag_id);
+    }
+    if tag_ids.is_empty() {
+        state.card_tags.remove(&card_id);
+    } else {
+        state.card_tags.insert(card_id, tag_ids);
+    }
+    Ok(())
 }
 
 fn limit_usize(limit: u32) -> usize {
     usize::try_from(limit).unwrap_or(usize::MAX)
 }
 
+/// Sort cards in memory the way `CardRepository::list_cards_ordered` orders them.
+///
+/// For card sets the repository cannot order itself, such as tag-filtered lists.
+pub fn sort_cards_by_order(cards: &mut [Card], order: CardOrder) {
+    cards.sort_by(|a, b| {
+        let new_last = (a.review_count() == 0).cmp(&(b.review_count() == 0));
+        let key = match order {
+            CardOrder::DueDate => {
+                let due = |card: &Card| (card.review_count() > 0).then(|| card.next_review_at());
+                due(a).cmp(&due(b))
+            }
+            CardOrder::Difficulty => {
+                let difficulty = |card: &Card| card.memory_state().map(|m| m.difficulty);
+                difficulty(b)
+                    .partial_cmp(&difficulty(a))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            }
+        };
+        new_last
+            .then(key)
+            .then_with(|| a.created_at().cmp(&b.created_at()))
+            .then_with(|| a.id().value().cmp(&b.id().value()))
+    });
+}
+
+/// Cards matching every word of `query`, most word hits first, optionally within one deck.
+fn search_state(
+    state: &InMemState,
+    deck_id: Option<DeckId>,
+    query: &str,
+    limit: u32,
+) -> Vec<Card> {
+    let terms = search_terms(query);
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    let mut scored: Vec<(usize, Card)> = state
+        .cards
+        .values()
+        .filter(|card| deck_id.is_none_or(|deck_id| card.deck_id() == deck_id))
+        .filter_map(|card| {
+            let words: Vec<String> = search_terms(card.prompt().text())
+                .into_iter()
+                .chain(search_terms(card.answer().text()))
+                .collect();
+            let mut score = 0;
+            for term in &terms {
+                let hits = words.iter().filter(|word| word.starts_with(term.as_str())).count();
+                if hits == 0 {
+                    return None;
+                }
+                score += hits;
+            }
+            Some((score, card.clone()))
+        })
+        .collect();
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| b.created_at().cmp(&a.created_at()))
+            .then_with(|| b.id().value().cmp(&a.id().value()))
+    });
+    scored
+        .into_iter()
+        .take(limit_usize(limit))
+        .map(|(_, card)| card)
+        .collect()
+}
+
+/// Split free text into lowercase alphanumeric search words.
+///
+/// Matches the FTS5 `unicode61` tokenizer closely enough that each word can be quoted into a
+/// prefix query without escaping.
+pub(crate) fn search_terms(text: &str) -> Vec<String> {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
 fn normalize_prompt(text: &str) -> String {
     text.trim().to_lowercase()
 }
@@ -971,6 +1962,27 @@ impl DeckRepository for InMemoryRepository {
             deck.fsrs_optimize_enabled,
             deck.fsrs_optimize_after,
         )
+        .and_then(|settings| settings.with_fsrs_parameters(deck.fsrs_parameters))
+        .and_then(|settings| settings.with_learning_steps(deck.learning_steps_secs))
+        .and_then(|settings| settings.with_relearning_steps(deck.relearning_steps_secs))
+        .and_then(|settings| {
+            settings
+                .with_new_review_mix(deck.new_review_mix)
+                .with_load_balance(deck.load_balance)
+                .with_answer_buttons(deck.answer_buttons)
+                .with_leech(deck.leech_threshold, deck.leech_action)
+                .with_appearance(deck.color, deck.icon)
+                .with_hold_new_until_reviews_done(deck.hold_new_until_reviews_done)
+                .with_interval_fuzz(deck.interval_fuzz)
+                .with_bury_siblings(deck.bury_siblings)
+                .with_same_session_fail_limit(deck.same_session_fail_limit)
+                .with_progressive_reveal(deck.progressive_reveal)
+                .with_typed_answer(deck.typed_answer)
+                .with_new_card_order(deck.new_card_order)
+                .with_new_card_template(deck.new_card_template)
+                .with_new_per_session(deck.new_per_session)
+                .with_templates(deck.front_template.as_deref(), deck.back_template.as_deref())
+        })
         .map_err(|e| StorageError::Serialization(e.to_string()))?;
 
         let mut guard = self
@@ -1007,10 +2019,28 @@ impl DeckRepository for InMemoryRepository {
                 .checked_add(1)
                 .ok_or_else(|| StorageError::Serialization("deck_id overflow".into()))?;
         }
-        guard.decks.insert(deck.id(), deck.clone());
+        let stored = match guard.decks.get(&deck.id()) {
+            Some(existing) => deck.clone().with_version(existing.version() + 1),
+            None => deck.clone(),
+        };
+        guard.decks.insert(deck.id(), stored);
         Ok(())
     }
 
+    async fn update_deck_if_version(&self, deck: &Deck) -> Result<u64, StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let existing = guard.decks.get(&deck.id()).ok_or(StorageError::NotFound)?;
+        if existing.version() != deck.version() {
+            return Err(StorageError::Conflict);
+        }
+        let version = deck.version() + 1;
+        guard.decks.insert(deck.id(), deck.clone().with_version(version));
+        Ok(version)
+    }
+
     async fn get_deck(&self, id: DeckId) -> Result<Option<Deck>, StorageError> {
         let guard = self
             .state
@@ -1019,52 +2049,252 @@ impl DeckRepository for InMemoryRepository {
         Ok(guard.decks.get(&id).cloned())
     }
 
-    async fn list_decks(&self, limit: u32) -> Result<Vec<Deck>, StorageError> {
+    async fn list_decks(
+        &self,
+        limit: u32,
+        include_archived: bool,
+    ) -> Result<Vec<Deck>, StorageError> {
         let guard = self
             .state
             .lock()
             .map_err(|e| StorageError::Connection(e.to_string()))?;
-        let mut decks: Vec<Deck> = guard.decks.values().cloned().collect();
-        decks.sort_by_key(|deck| deck.id().value());
+        let mut decks: Vec<Deck> = guard
+            .decks
+            .values()
+            .filter(|deck| include_archived || !deck.is_archived())
+            .cloned()
+            .collect();
+        decks.sort_by_key(|deck| (!deck.is_favorite(), deck.id().value()));
         decks.truncate(limit_usize(limit));
         Ok(decks)
     }
-}
 
-#[async_trait]
-impl CardRepository for InMemoryRepository {
-    async fn insert_new_card(&self, card: NewCardRecord) -> Result<CardId, StorageError> {
+    async fn duplicate_deck(
+        &self,
+        source: DeckId,
+        deck: NewDeckRecord,
+    ) -> Result<DeckId, StorageError> {
+        {
+            let guard = self
+                .state
+                .lock()
+                .map_err(|e| StorageError::Connection(e.to_string()))?;
+            if !guard.decks.contains_key(&source) {
+                return Err(StorageError::NotFound);
+            }
+        }
+        let now = deck.created_at;
+        let deck_id = self.insert_new_deck(deck).await?;
+
         let mut guard = self
             .state
             .lock()
             .map_err(|e| StorageError::Connection(e.to_string()))?;
-        let id = guard.next_card_id;
-        guard.next_card_id = id
-            .checked_add(1)
-            .ok_or_else(|| StorageError::Serialization("card_id overflow".into()))?;
-        let record = CardRecord {
-            id: CardId::new(id),
-            deck_id: card.deck_id,
-            prompt_text: card.prompt_text,
-            prompt_media_id: card.prompt_media_id,
-            answer_text: card.answer_text,
-            answer_media_id: card.answer_media_id,
-            phase: card.phase,
-            created_at: card.created_at,
-            next_review_at: card.next_review_at,
-            last_review_at: card.last_review_at,
-            review_count: card.review_count,
-            stability: card.stability,
-            difficulty: card.difficulty,
-        };
-        let card = record
-            .into_card()
-            .map_err(|e| StorageError::Serialization(e.to_string()))?;
-        let id = card.id();
+        let mut sources: Vec<Card> = guard
+            .cards
+            .values()
+            .filter(|card| card.deck_id() == source)
+            .cloned()
+            .collect();
+        sources.sort_by_key(|card| card.id().value());
+
+        let mut tag_map: HashMap<TagId, TagId> = HashMap::new();
+        let source_tags: Vec<Tag> = guard
+            .tags
+            .values()
+            .filter(|tag| tag.deck_id() == source)
+            .cloned()
+            .collect();
+        for tag in source_tags {
+            let id = guard.next_tag_id;
+            guard.next_tag_id = id
+                .checked_add(1)
+                .ok_or_else(|| StorageError::Serialization("tag_id overflow".into()))?;
+            let tag_id = TagId::new(id);
+            guard
+                .tags
+                .insert(tag_id, Tag::new(tag_id, deck_id, tag.name().clone()));
+            tag_map.insert(tag.id(), tag_id);
+        }
+
+        for card in sources {
+            let id = guard.next_card_id;
+            guard.next_card_id = id
+                .checked_add(1)
+                .ok_or_else(|| StorageError::Serialization("card_id overflow".into()))?;
+            let copy = Card::new(
+                CardId::new(id),
+                deck_id,
+                card.kind().clone(),
+                card.prompt().clone(),
+                card.answer().clone(),
+                now,
+                now,
+            )
+            .map_err(|e| StorageError::Serialization(e.to_string()))?
+            .with_extra(card.extra().cloned());
+            let tag_ids: Vec<TagId> = guard
+                .card_tags
+                .get(&card.id())
+                .into_iter()
+                .flatten()
+                .filter_map(|tag_id| tag_map.get(tag_id).copied())
+                .collect();
+            if !tag_ids.is_empty() {
+                guard.card_tags.insert(copy.id(), tag_ids);
+            }
+            guard.cards.insert(copy.id(), copy);
+        }
+        Ok(deck_id)
+    }
+
+    async fn merge_decks(
+        &self,
+        source: DeckId,
+        dest: DeckId,
+        on_conflict: MergeConflict,
+    ) -> Result<DeckMergeCounts, StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        if !guard.decks.contains_key(&source) || !guard.decks.contains_key(&dest) {
+            return Err(StorageError::NotFound);
+        }
+
+        let dest_prompts: HashSet<String> = guard
+            .cards
+            .values()
+            .filter(|card| card.deck_id() == dest)
+            .map(|card| normalize_prompt(card.prompt().text()))
+            .collect();
+        let mut sources: Vec<Card> = guard
+            .cards
+            .values()
+            .filter(|card| card.deck_id() == source)
+            .cloned()
+            .collect();
+        sources.sort_by_key(|card| card.id().value());
+
+        let mut counts = DeckMergeCounts::default();
+        for card in sources {
+            if on_conflict == MergeConflict::Skip
+                && dest_prompts.contains(&normalize_prompt(card.prompt().text()))
+            {
+                counts.skipped += 1;
+                continue;
+            }
+            move_card_in_state(&mut guard, card, dest)?;
+            counts.moved += 1;
+        }
+
+        if let Some(deck) = guard.decks.remove(&source) {
+            let version = deck.version() + 1;
+            guard
+                .decks
+                .insert(source, deck.with_archived(true).with_version(version));
+        }
+        Ok(counts)
+    }
+}
+
+#[async_trait]
+impl CardRepository for InMemoryRepository {
+    async fn insert_new_card(&self, card: NewCardRecord) -> Result<CardId, StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let id = guard.next_card_id;
+        guard.next_card_id = id
+            .checked_add(1)
+            .ok_or_else(|| StorageError::Serialization("card_id overflow".into()))?;
+        let record = CardRecord {
+            id: CardId::new(id),
+            deck_id: card.deck_id,
+            kind: card.kind,
+            note_id: card.note_id,
+            prompt_text: card.prompt_text,
+            prompt_media_id: card.prompt_media_id,
+            answer_text: card.answer_text,
+            answer_media_id: card.answer_media_id,
+            phase: card.phase,
+            created_at: card.created_at,
+            next_review_at: card.next_review_at,
+            last_review_at: card.last_review_at,
+            review_count: card.review_count,
+            stability: card.stability,
+            difficulty: card.difficulty,
+            suspended: false,
+            buried_until: None,
+            learning_step: 0,
+            lapses: 0,
+            extra_text: card.extra_text,
+            flag: None,
+            source: card.source,
+        };
+        let card = record
+            .into_card()
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        let id = card.id();
         guard.cards.insert(id, card);
         Ok(id)
     }
 
+    async fn insert_new_cards(
+        &self,
+        cards: Vec<NewCardRecord>,
+    ) -> Result<Vec<CardId>, StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let mut next_id = guard.next_card_id;
+        let mut built = Vec::with_capacity(cards.len());
+        for card in cards {
+            let id = next_id;
+            next_id = id
+                .checked_add(1)
+                .ok_or_else(|| StorageError::Serialization("card_id overflow".into()))?;
+            let record = CardRecord {
+                id: CardId::new(id),
+                deck_id: card.deck_id,
+                kind: card.kind,
+                note_id: card.note_id,
+                prompt_text: card.prompt_text,
+                prompt_media_id: card.prompt_media_id,
+                answer_text: card.answer_text,
+                answer_media_id: card.answer_media_id,
+                phase: card.phase,
+                created_at: card.created_at,
+                next_review_at: card.next_review_at,
+                last_review_at: card.last_review_at,
+                review_count: card.review_count,
+                stability: card.stability,
+                difficulty: card.difficulty,
+                suspended: false,
+                buried_until: None,
+                learning_step: 0,
+                lapses: 0,
+                extra_text: card.extra_text,
+                flag: None,
+                source: card.source,
+            };
+            built.push(
+                record
+                    .into_card()
+                    .map_err(|e| StorageError::Serialization(e.to_string()))?,
+            );
+        }
+
+        guard.next_card_id = next_id;
+        let ids = built.iter().map(Card::id).collect();
+        for card in built {
+            guard.cards.insert(card.id(), card);
+        }
+        Ok(ids)
+    }
+
     async fn upsert_card(&self, card: &Card) -> Result<(), StorageError> {
         let mut guard = self
             .state
@@ -1077,10 +2307,59 @@ impl CardRepository for InMemoryRepository {
                 .checked_add(1)
                 .ok_or_else(|| StorageError::Serialization("card_id overflow".into()))?;
         }
-        guard.cards.insert(card.id(), card.clone());
+        if let Some(trashed) = guard.trashed.get_mut(&card.id()) {
+            trashed.card = card.clone();
+        } else {
+            guard.cards.insert(card.id(), card.clone());
+        }
+        Ok(())
+    }
+
+    async fn upsert_cards(&self, cards: &[Card]) -> Result<(), StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let mut next_id = guard.next_card_id;
+        for card in cards {
+            if card.id().value() >= next_id {
+                next_id = card
+                    .id()
+                    .value()
+                    .checked_add(1)
+                    .ok_or_else(|| StorageError::Serialization("card_id overflow".into()))?;
+            }
+        }
+        guard.next_card_id = next_id;
+        for card in cards {
+            guard.cards.insert(card.id(), card.clone());
+        }
         Ok(())
     }
 
+    async fn move_card(
+        &self,
+        card_id: CardId,
+        from_deck: DeckId,
+        to_deck: DeckId,
+    ) -> Result<(), StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        if !guard.decks.contains_key(&to_deck) {
+            return Err(StorageError::NotFound);
+        }
+        let Some(card) = guard.cards.get(&card_id).filter(|c| c.deck_id() == from_deck) else {
+            return Err(StorageError::NotFound);
+        };
+        if from_deck == to_deck {
+            return Ok(());
+        }
+        let card = card.clone();
+        move_card_in_state(&mut guard, card, to_deck)
+    }
+
     async fn delete_card(&self, deck_id: DeckId, card_id: CardId) -> Result<(), StorageError> {
         let mut guard = self
             .state
@@ -1099,6 +2378,116 @@ impl CardRepository for InMemoryRepository {
         }
     }
 
+    async fn trash_card(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<(), StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        match guard.cards.remove(&card_id) {
+            Some(card) if card.deck_id() == deck_id => {
+                guard.trashed.insert(card_id, TrashedCard { card, deleted_at });
+                Ok(())
+            }
+            Some(card) => {
+                guard.cards.insert(card_id, card);
+                Err(StorageError::NotFound)
+            }
+            None => Err(StorageError::NotFound),
+        }
+    }
+
+    async fn trash_cards(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+        deleted_at: DateTime<Utc>,
+    ) -> Result<(), StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let mut seen = HashSet::new();
+        for card_id in card_ids {
+            let in_deck = guard.cards.get(card_id).is_some_and(|card| card.deck_id() == deck_id);
+            if !in_deck || !seen.insert(*card_id) {
+                return Err(StorageError::NotFound);
+            }
+        }
+        for card_id in card_ids {
+            if let Some(card) = guard.cards.remove(card_id) {
+                guard.trashed.insert(*card_id, TrashedCard { card, deleted_at });
+            }
+        }
+        Ok(())
+    }
+
+    async fn restore_card(&self, deck_id: DeckId, card_id: CardId) -> Result<(), StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        match guard.trashed.remove(&card_id) {
+            Some(trashed) if trashed.card.deck_id() == deck_id => {
+                guard.cards.insert(card_id, trashed.card);
+                Ok(())
+            }
+            Some(trashed) => {
+                guard.trashed.insert(card_id, trashed);
+                Err(StorageError::NotFound)
+            }
+            None => Err(StorageError::NotFound),
+        }
+    }
+
+    async fn list_trashed_cards(
+        &self,
+        deck_id: DeckId,
+        limit: u32,
+    ) -> Result<Vec<TrashedCard>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let mut trashed: Vec<_> = guard
+            .trashed
+            .values()
+            .filter(|trashed| trashed.card.deck_id() == deck_id)
+            .cloned()
+            .collect();
+        trashed.sort_by_key(|trashed| {
+            (std::cmp::Reverse(trashed.deleted_at), std::cmp::Reverse(trashed.card.id()))
+        });
+        trashed.truncate(limit as usize);
+        Ok(trashed)
+    }
+
+    async fn purge_trashed_cards(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<u64, StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let purged: Vec<CardId> = guard
+            .trashed
+            .iter()
+            .filter(|(_, trashed)| trashed.deleted_at < deleted_before)
+            .map(|(id, _)| *id)
+            .collect();
+        for card_id in &purged {
+            guard.trashed.remove(card_id);
+            guard.card_tags.remove(card_id);
+        }
+        guard.logs.retain(|log| !purged.contains(&log.card_id));
+        Ok(purged.len() as u64)
+    }
+
     async fn get_cards(&self, deck_id: DeckId, ids: &[CardId]) -> Result<Vec<Card>, StorageError> {
         let guard = self
             .state
@@ -1128,7 +2517,13 @@ impl CardRepository for InMemoryRepository {
         let mut due: Vec<Card> = guard
             .cards
             .values()
-            .filter(|c| c.deck_id() == deck_id && c.review_count() > 0 && c.next_review_at() <= now)
+            .filter(|c| {
+                c.deck_id() == deck_id
+                    && !c.is_suspended()
+                    && !c.is_buried_at(now)
+                    && c.review_count() > 0
+                    && c.next_review_at() <= now
+            })
             .cloned()
             .collect();
         due.sort_by_key(|c| (c.next_review_at(), c.id().value()));
@@ -1136,7 +2531,37 @@ impl CardRepository for InMemoryRepository {
         Ok(due)
     }
 
-    async fn new_cards(&self, deck_id: DeckId, limit: u32) -> Result<Vec<Card>, StorageError> {
+    async fn due_times(
+        &self,
+        deck_id: DeckId,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<DateTime<Utc>>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut times: Vec<DateTime<Utc>> = guard
+            .cards
+            .values()
+            .filter(|c| {
+                c.deck_id() == deck_id
+                    && !c.is_suspended()
+                    && c.review_count() > 0
+                    && c.next_review_at() <= until
+            })
+            .map(Card::next_review_at)
+            .collect();
+        times.sort();
+        Ok(times)
+    }
+
+    async fn new_cards(
+        &self,
+        deck_id: DeckId,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
         let guard = self
             .state
             .lock()
@@ -1145,7 +2570,12 @@ impl CardRepository for InMemoryRepository {
         let mut new_cards: Vec<Card> = guard
             .cards
             .values()
-            .filter(|c| c.deck_id() == deck_id && c.review_count() == 0)
+            .filter(|c| {
+                c.deck_id() == deck_id
+                    && !c.is_suspended()
+                    && !c.is_buried_at(now)
+                    && c.review_count() == 0
+            })
             .cloned()
             .collect();
         new_cards.sort_by_key(|c| (c.created_at(), c.id().value()));
@@ -1175,6 +2605,65 @@ impl CardRepository for InMemoryRepository {
         Ok(cards)
     }
 
+    async fn list_cards_page(
+        &self,
+        deck_id: DeckId,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        let cards = self.list_cards(deck_id, u32::MAX).await?;
+        Ok(cards
+            .into_iter()
+            .skip(limit_usize(offset))
+            .take(limit_usize(limit))
+            .collect())
+    }
+
+    async fn list_cards_after(
+        &self,
+        deck_id: DeckId,
+        after: Option<CardId>,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let after = after.map_or(0, |id| id.value());
+
+        let mut cards: Vec<Card> = guard
+            .cards
+            .values()
+            .filter(|c| c.deck_id() == deck_id && c.id().value() > after)
+            .cloned()
+            .collect();
+        cards.sort_by_key(|c| c.id().value());
+        cards.truncate(limit_usize(limit));
+        Ok(cards)
+    }
+
+    async fn list_cards_ordered(
+        &self,
+        deck_id: DeckId,
+        order: CardOrder,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut cards: Vec<Card> = guard
+            .cards
+            .values()
+            .filter(|c| c.deck_id() == deck_id)
+            .cloned()
+            .collect();
+        sort_cards_by_order(&mut cards, order);
+        cards.truncate(limit_usize(limit));
+        Ok(cards)
+    }
+
     async fn count_cards_created_between(
         &self,
         deck_id: DeckId,
@@ -1205,11 +2694,54 @@ impl CardRepository for InMemoryRepository {
         let count = guard
             .cards
             .values()
-            .filter(|card| card.deck_id() == deck_id && card.phase() == CardPhase::Relearning)
+            .filter(|card| {
+                card.deck_id() == deck_id
+                    && !card.is_suspended()
+                    && card.phase() == CardPhase::Relearning
+            })
             .count();
         Ok(u32::try_from(count).unwrap_or(u32::MAX))
     }
 
+    async fn count_cards_by_phase(&self, deck_id: DeckId) -> Result<CardPhaseCounts, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let mut counts = CardPhaseCounts::default();
+        for card in guard.cards.values().filter(|card| card.deck_id() == deck_id) {
+            counts.add(card.phase(), card.is_suspended(), 1);
+        }
+        Ok(counts)
+    }
+
+    async fn count_cards_by_maturity(
+        &self,
+        deck_id: DeckId,
+        mature_days: u32,
+    ) -> Result<CardMaturityCounts, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let threshold = chrono::Duration::days(i64::from(mature_days));
+        let mut counts = CardMaturityCounts::default();
+        for card in guard.cards.values().filter(|card| card.deck_id() == deck_id) {
+            let bucket = if card.phase() == CardPhase::New {
+                &mut counts.new
+            } else if card
+                .last_review_at()
+                .is_some_and(|last| card.next_review_at() - last >= threshold)
+            {
+                &mut counts.mature
+            } else {
+                &mut counts.young
+            };
+            *bucket = bucket.saturating_add(1);
+        }
+        Ok(counts)
+    }
+
     async fn reset_deck_learning(
         &self,
         deck_id: DeckId,
@@ -1230,25 +2762,82 @@ impl CardRepository for InMemoryRepository {
             let Some(card) = guard.cards.get(&id) else {
                 continue;
             };
-            let prompt = card.prompt().clone();
-            let answer = card.answer().clone();
-            let created_at = card.created_at();
-            let reset = Card::from_persisted(
+            let reset = reset_learning(card, now)?;
+            guard.cards.insert(id, reset);
+            updated = updated.saturating_add(1);
+        }
+        Ok(updated)
+    }
+
+    async fn reset_card_learning(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        now: DateTime<Utc>,
+        clear_history: bool,
+    ) -> Result<(), StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let Some(card) = guard.cards.get(&card_id).filter(|c| c.deck_id() == deck_id) else {
+            return Err(StorageError::NotFound);
+        };
+        let reset = reset_learning(card, now)?;
+        guard.cards.insert(card_id, reset);
+        if clear_history {
+            guard
+                .logs
+                .retain(|log| log.card_id != card_id || log.deck_id != deck_id);
+        }
+        Ok(())
+    }
+
+    async fn reschedule_cards(
+        &self,
+        deck_id: DeckId,
+        due_dates: &[(CardId, DateTime<Utc>)],
+    ) -> Result<u64, StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let mut moved = Vec::with_capacity(due_dates.len());
+        for &(id, due_at) in due_dates {
+            let card = guard
+                .cards
+                .get(&id)
+                .filter(|card| card.deck_id() == deck_id)
+                .ok_or(StorageError::NotFound)?;
+            let state = card.memory_state();
+            let rescheduled = Card::from_persisted(
                 id,
                 deck_id,
-                prompt,
-                answer,
-                created_at,
-                now,
-                None,
-                CardPhase::New,
-                0,
-                0.0,
-                0.0,
+                card.kind().clone(),
+                card.prompt().clone(),
+                card.answer().clone(),
+                card.created_at(),
+                due_at,
+                card.last_review_at(),
+                card.phase(),
+                card.review_count(),
+                state.as_ref().map_or(0.0, |m| m.stability),
+                state.map_or(0.0, |m| m.difficulty),
             )
-            .map_err(|e| StorageError::Serialization(e.to_string()))?;
-            guard.cards.insert(id, reset);
-            updated = updated.saturating_add(1);
+            .map_err(|e| StorageError::Serialization(e.to_string()))?
+            .with_note_id(card.note_id())
+            .with_suspended(card.is_suspended())
+            .with_buried_until(card.buried_until())
+            .with_learning_step(card.learning_step())
+            .with_lapses(card.lapses())
+            .with_extra(card.extra().cloned())
+            .with_flag(card.flag())
+            .with_source(card.source());
+            moved.push(rescheduled);
+        }
+        let updated = u64::try_from(moved.len()).unwrap_or(u64::MAX);
+        for card in moved {
+            guard.cards.insert(card.id(), card);
         }
         Ok(updated)
     }
@@ -1263,20 +2852,12 @@ impl CardRepository for InMemoryRepository {
             .lock()
             .map_err(|e| StorageError::Connection(e.to_string()))?;
 
-        let mut total = 0u32;
-        let mut due = 0u32;
-        let mut new = 0u32;
-
+        let mut counts = DeckPracticeCounts::default();
         for card in guard.cards.values().filter(|c| c.deck_id() == deck_id) {
-            total = total.saturating_add(1);
-            if card.review_count() == 0 {
-                new = new.saturating_add(1);
-            } else if card.next_review_at() <= now {
-                due = due.saturating_add(1);
-            }
+            tally_practice_counts(&mut counts, card, now);
         }
 
-        Ok(DeckPracticeCounts { total, due, new })
+        Ok(counts)
     }
 
     async fn list_deck_practice_counts(
@@ -1291,24 +2872,12 @@ impl CardRepository for InMemoryRepository {
 
         let mut counts_by_deck = HashMap::new();
         for deck_id in deck_ids {
-            counts_by_deck.insert(
-                *deck_id,
-                DeckPracticeCounts {
-                    total: 0,
-                    due: 0,
-                    new: 0,
-                },
-            );
+            counts_by_deck.insert(*deck_id, DeckPracticeCounts::default());
         }
 
         for card in guard.cards.values() {
             if let Some(counts) = counts_by_deck.get_mut(&card.deck_id()) {
-                counts.total = counts.total.saturating_add(1);
-                if card.is_new() {
-                    counts.new = counts.new.saturating_add(1);
-                } else if card.is_due(now) {
-                    counts.due = counts.due.saturating_add(1);
-                }
+                tally_practice_counts(counts, card, now);
             }
         }
 
@@ -1343,26 +2912,14 @@ impl CardRepository for InMemoryRepository {
 
         let mut counts: HashMap<TagId, DeckPracticeCounts> = HashMap::new();
         for tag in &tags {
-            counts.insert(
-                tag.id(),
-                DeckPracticeCounts {
-                    total: 0,
-                    due: 0,
-                    new: 0,
-                },
-            );
+            counts.insert(tag.id(), DeckPracticeCounts::default());
         }
 
         for card in guard.cards.values().filter(|c| c.deck_id() == deck_id) {
             if let Some(tag_ids) = guard.card_tags.get(&card.id()) {
                 for tag_id in tag_ids {
                     if let Some(entry) = counts.get_mut(tag_id) {
-                        entry.total = entry.total.saturating_add(1);
-                        if card.review_count() == 0 {
-                            entry.new = entry.new.saturating_add(1);
-                        } else if card.next_review_at() <= now {
-                            entry.due = entry.due.saturating_add(1);
-                        }
+                        tally_practice_counts(entry, card, now);
                     }
                 }
             }
@@ -1370,11 +2927,7 @@ impl CardRepository for InMemoryRepository {
 
         let mut out = Vec::with_capacity(tags.len());
         for tag in tags {
-            let counts = counts.get(&tag.id()).copied().unwrap_or(DeckPracticeCounts {
-                total: 0,
-                due: 0,
-                new: 0,
-            });
+            let counts = counts.get(&tag.id()).copied().unwrap_or_default();
             out.push(TagPracticeCounts {
                 name: tag.name().clone(),
                 total: counts.total,
@@ -1435,6 +2988,133 @@ impl CardRepository for InMemoryRepository {
         Ok(cards)
     }
 
+    async fn list_cards_with_all_tags(
+        &self,
+        deck_id: DeckId,
+        tag_names: &[TagName],
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        if tag_names.is_empty() {
+            return self.list_cards(deck_id, limit).await;
+        }
+
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut required: Vec<TagId> = Vec::with_capacity(tag_names.len());
+        for name in tag_names {
+            let Some(tag) = guard
+                .tags
+                .values()
+                .find(|tag| tag.deck_id() == deck_id && tag.name() == name)
+            else {
+                return Ok(Vec::new());
+            };
+            required.push(tag.id());
+        }
+
+        let mut cards: Vec<Card> = guard
+            .cards
+            .values()
+            .filter(|card| card.deck_id() == deck_id)
+            .filter(|card| {
+                guard
+                    .card_tags
+                    .get(&card.id())
+                    .is_some_and(|ids| required.iter().all(|id| ids.contains(id)))
+            })
+            .cloned()
+            .collect();
+        cards.sort_by(|a, b| {
+            b.created_at()
+                .cmp(&a.created_at())
+                .then_with(|| b.id().value().cmp(&a.id().value()))
+        });
+        cards.truncate(limit_usize(limit));
+        Ok(cards)
+    }
+
+    async fn list_untagged_cards(
+        &self,
+        deck_id: DeckId,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut cards: Vec<Card> = guard
+            .cards
+            .values()
+            .filter(|card| card.deck_id() == deck_id)
+            .filter(|card| guard.card_tags.get(&card.id()).is_none_or(Vec::is_empty))
+            .cloned()
+            .collect();
+        cards.sort_by(|a, b| {
+            b.created_at()
+                .cmp(&a.created_at())
+                .then_with(|| b.id().value().cmp(&a.id().value()))
+        });
+        cards.truncate(limit_usize(limit));
+        Ok(cards)
+    }
+
+    async fn list_note_cards(
+        &self,
+        deck_id: DeckId,
+        note_id: NoteId,
+    ) -> Result<Vec<Card>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let mut cards: Vec<Card> = guard
+            .cards
+            .values()
+            .filter(|card| card.deck_id() == deck_id && card.note_id() == Some(note_id))
+            .cloned()
+            .collect();
+        cards.sort_by_key(|card| card.id().value());
+        Ok(cards)
+    }
+
+    async fn search_cards(
+        &self,
+        deck_id: DeckId,
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<Card>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(search_state(&guard, Some(deck_id), query, limit))
+    }
+
+    async fn search_all_decks(
+        &self,
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<DeckCardMatch>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        search_state(&guard, None, query, limit)
+            .into_iter()
+            .map(|card| {
+                let deck = guard.decks.get(&card.deck_id()).ok_or(StorageError::NotFound)?;
+                Ok(DeckCardMatch {
+                    deck_name: deck.name().to_owned(),
+                    card,
+                })
+            })
+            .collect()
+    }
+
     async fn prompt_exists(
         &self,
         deck_id: DeckId,
@@ -1503,68 +3183,229 @@ impl CardRepository for InMemoryRepository {
                 }
             }
         }
-        tags.sort_by(|a, b| {
-            a.name()
-                .as_str()
-                .cmp(b.name().as_str())
-                .then_with(|| a.id().value().cmp(&b.id().value()))
-        });
+        tags.sort_by(|a, b| {
+            a.name()
+                .as_str()
+                .cmp(b.name().as_str())
+                .then_with(|| a.id().value().cmp(&b.id().value()))
+        });
+        Ok(tags)
+    }
+
+    async fn list_tags_for_cards(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+    ) -> Result<Vec<(CardId, Tag)>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut out = Vec::new();
+        for card_id in card_ids {
+            let in_deck = guard
+                .cards
+                .get(card_id)
+                .is_some_and(|card| card.deck_id() == deck_id);
+            if !in_deck {
+                continue;
+            }
+            let Some(tag_ids) = guard.card_tags.get(card_id) else {
+                continue;
+            };
+            for tag_id in tag_ids {
+                if let Some(tag) = guard.tags.get(tag_id) {
+                    out.push((*card_id, tag.clone()));
+                }
+            }
+        }
+        out.sort_by(|(a_id, a), (b_id, b)| {
+            a_id.value()
+                .cmp(&b_id.value())
+                .then_with(|| a.name().as_str().cmp(b.name().as_str()))
+        });
+        out.dedup_by(|(a_id, a), (b_id, b)| a_id == b_id && a.id() == b.id());
+        Ok(out)
+    }
+
+    async fn upsert_tag(&self, deck_id: DeckId, name: &TagName) -> Result<Tag, StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        if let Some(tag) = guard
+            .tags
+            .values()
+            .find(|tag| tag.deck_id() == deck_id && tag.name() == name)
+        {
+            return Ok(tag.clone());
+        }
+
+        let id = guard.next_tag_id;
+        guard.next_tag_id = id
+            .checked_add(1)
+            .ok_or_else(|| StorageError::Serialization("tag_id overflow".into()))?;
+        let tag = Tag::new(TagId::new(id), deck_id, name.clone());
+        guard.tags.insert(tag.id(), tag.clone());
+        Ok(tag)
+    }
+
+    async fn set_tags_for_card(
+        &self,
+        deck_id: DeckId,
+        card_id: CardId,
+        tag_names: &[TagName],
+    ) -> Result<Vec<Tag>, StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let Some(card) = guard.cards.get(&card_id) else {
+            return Err(StorageError::NotFound);
+        };
+        if card.deck_id() != deck_id {
+            return Err(StorageError::NotFound);
+        }
+
+        let mut tag_ids = Vec::new();
+        for name in tag_names {
+            let existing = guard
+                .tags
+                .values()
+                .find(|tag| tag.deck_id() == deck_id && tag.name() == name)
+                .map(Tag::id);
+
+            let tag_id = if let Some(id) = existing {
+                id
+            } else {
+                let id = guard.next_tag_id;
+                guard.next_tag_id = id
+                    .checked_add(1)
+                    .ok_or_else(|| StorageError::Serialization("tag_id overflow".into()))?;
+                let tag_id = TagId::new(id);
+                let tag = Tag::new(tag_id, deck_id, name.clone());
+                guard.tags.insert(tag_id, tag);
+                tag_id
+            };
+
+            if !tag_ids.contains(&tag_id) {
+                tag_ids.push(tag_id);
+            }
+        }
+
+        guard.card_tags.insert(card_id, tag_ids);
+        let mut tags = Vec::new();
+        if let Some(tag_ids) = guard.card_tags.get(&card_id) {
+            for tag_id in tag_ids {
+                if let Some(tag) = guard.tags.get(tag_id) {
+                    tags.push(tag.clone());
+                }
+            }
+        }
         Ok(tags)
     }
 
-    async fn set_tags_for_card(
+
+    async fn add_tags_to_cards(
         &self,
         deck_id: DeckId,
-        card_id: CardId,
+        card_ids: &[CardId],
         tag_names: &[TagName],
-    ) -> Result<Vec<Tag>, StorageError> {
+    ) -> Result<u64, StorageError> {
         let mut guard = self
             .state
             .lock()
             .map_err(|e| StorageError::Connection(e.to_string()))?;
-        let Some(card) = guard.cards.get(&card_id) else {
-            return Err(StorageError::NotFound);
-        };
-        if card.deck_id() != deck_id {
-            return Err(StorageError::NotFound);
-        }
 
-        let mut tag_ids = Vec::new();
+        let mut tag_ids = Vec::with_capacity(tag_names.len());
+        let mut next_tag_id = guard.next_tag_id;
+        let mut created = Vec::new();
         for name in tag_names {
             let existing = guard
                 .tags
                 .values()
+                .chain(created.iter())
                 .find(|tag| tag.deck_id() == deck_id && tag.name() == name)
                 .map(Tag::id);
-
             let tag_id = if let Some(id) = existing {
                 id
             } else {
-                let id = guard.next_tag_id;
-                guard.next_tag_id = id
+                let tag_id = TagId::new(next_tag_id);
+                next_tag_id = next_tag_id
                     .checked_add(1)
                     .ok_or_else(|| StorageError::Serialization("tag_id overflow".into()))?;
-                let tag_id = TagId::new(id);
-                let tag = Tag::new(tag_id, deck_id, name.clone());
-                guard.tags.insert(tag_id, tag);
+                created.push(Tag::new(tag_id, deck_id, name.clone()));
                 tag_id
             };
-
             if !tag_ids.contains(&tag_id) {
                 tag_ids.push(tag_id);
             }
         }
 
-        guard.card_tags.insert(card_id, tag_ids);
-        let mut tags = Vec::new();
-        if let Some(tag_ids) = guard.card_tags.get(&card_id) {
-            for tag_id in tag_ids {
-                if let Some(tag) = guard.tags.get(tag_id) {
-                    tags.push(tag.clone());
+        let mut affected = 0_u64;
+        for card_id in card_ids {
+            if guard
+                .cards
+                .get(card_id)
+                .is_none_or(|card| card.deck_id() != deck_id)
+            {
+                continue;
+            }
+            let attached = guard.card_tags.entry(*card_id).or_default();
+            let before = attached.len();
+            for tag_id in &tag_ids {
+                if !attached.contains(tag_id) {
+                    attached.push(*tag_id);
                 }
             }
+            if attached.len() > before {
+                affected += 1;
+            }
         }
-        Ok(tags)
+
+        guard.next_tag_id = next_tag_id;
+        for tag in created {
+            guard.tags.insert(tag.id(), tag);
+        }
+        Ok(affected)
+    }
+
+    async fn remove_tags_from_cards(
+        &self,
+        deck_id: DeckId,
+        card_ids: &[CardId],
+        tag_names: &[TagName],
+    ) -> Result<u64, StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let tag_ids: Vec<TagId> = guard
+            .tags
+            .values()
+            .filter(|tag| tag.deck_id() == deck_id && tag_names.contains(tag.name()))
+            .map(Tag::id)
+            .collect();
+
+        let mut affected = 0_u64;
+        for card_id in card_ids {
+            if guard
+                .cards
+                .get(card_id)
+                .is_none_or(|card| card.deck_id() != deck_id)
+            {
+                continue;
+            }
+            if let Some(attached) = guard.card_tags.get_mut(card_id) {
+                let before = attached.len();
+                attached.retain(|tag_id| !tag_ids.contains(tag_id));
+                if attached.len() < before {
+                    affected += 1;
+                }
+            }
+        }
+        Ok(affected)
     }
 }
 
@@ -1603,6 +3444,109 @@ impl ReviewLogRepository for InMemoryRepository {
 
         Ok(logs)
     }
+
+    async fn logs_for_deck(&self, deck_id: DeckId) -> Result<Vec<ReviewLogRecord>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut logs: Vec<_> = guard
+            .logs
+            .iter()
+            .filter(|log| log.deck_id == deck_id)
+            .cloned()
+            .collect();
+
+        logs.sort_by_key(|l| l.reviewed_at);
+
+        Ok(logs)
+    }
+
+    async fn logs_for_deck_page(
+        &self,
+        deck_id: DeckId,
+        after_id: Option<i64>,
+        limit: u32,
+    ) -> Result<Vec<ReviewLogRecord>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut logs: Vec<_> = guard
+            .logs
+            .iter()
+            .filter(|log| log.deck_id == deck_id && log.id > after_id)
+            .cloned()
+            .collect();
+
+        logs.sort_by_key(|l| l.id);
+        logs.truncate(usize::try_from(limit).unwrap_or(usize::MAX));
+
+        Ok(logs)
+    }
+
+    async fn count_introduced_since(
+        &self,
+        deck_id: DeckId,
+        since: DateTime<Utc>,
+    ) -> Result<u32, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let introduced: HashSet<CardId> = guard
+            .logs
+            .iter()
+            .filter(|log| log.deck_id == deck_id && log.first_review && log.reviewed_at >= since)
+            .map(|log| log.card_id)
+            .collect();
+        let introduced = introduced.len();
+
+        Ok(u32::try_from(introduced).unwrap_or(u32::MAX))
+    }
+
+    async fn prune(&self, deck_id: DeckId, prune: ReviewLogPrune) -> Result<u64, StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let before = guard.logs.len();
+        match prune {
+            ReviewLogPrune::KeepLastPerCard(keep) => {
+                let mut newest_first: Vec<_> = guard
+                    .logs
+                    .iter()
+                    .filter(|log| log.deck_id == deck_id)
+                    .map(|log| (log.card_id, log.reviewed_at, log.id))
+                    .collect();
+                newest_first.sort_by_key(|log| std::cmp::Reverse((log.1, log.2)));
+                let mut kept: HashMap<CardId, u32> = HashMap::new();
+                let doomed: HashSet<Option<i64>> = newest_first
+                    .into_iter()
+                    .filter(|(card_id, _, _)| {
+                        let count = kept.entry(*card_id).or_insert(0);
+                        *count += 1;
+                        *count > keep
+                    })
+                    .map(|(_, _, id)| id)
+                    .collect();
+                guard
+                    .logs
+                    .retain(|log| log.deck_id != deck_id || !doomed.contains(&log.id));
+            }
+            ReviewLogPrune::OlderThan(cutoff) => {
+                guard
+                    .logs
+                    .retain(|log| log.deck_id != deck_id || log.reviewed_at >= cutoff);
+            }
+        }
+
+        Ok((before - guard.logs.len()) as u64)
+    }
 }
 
 #[async_trait]
@@ -1619,13 +3563,56 @@ impl ReviewPersistence for InMemoryRepository {
             .state
             .lock()
             .map_err(|e| StorageError::Connection(e.to_string()))?;
-        guard.cards.insert(card.id(), card.clone());
+        guard
+            .cards
+            .insert(card.id(), card.clone().with_updated_at(log.reviewed_at));
         let id = guard.next_log_id;
         guard.next_log_id = id.saturating_add(1);
         log.id = Some(id);
         guard.logs.push(log);
         Ok(id)
     }
+
+    async fn revert_review(&self, card: &Card, log_id: i64) -> Result<(), StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let Some(position) = guard.logs.iter().position(|log| {
+            log.id == Some(log_id) && log.card_id == card.id() && log.deck_id == card.deck_id()
+        }) else {
+            return Err(StorageError::NotFound);
+        };
+        let Some(stored) = guard.cards.get(&card.id()) else {
+            return Err(StorageError::NotFound);
+        };
+        let restored = Card::from_persisted(
+            stored.id(),
+            stored.deck_id(),
+            stored.kind().clone(),
+            stored.prompt().clone(),
+            stored.answer().clone(),
+            stored.created_at(),
+            card.next_review_at(),
+            card.last_review_at(),
+            card.phase(),
+            card.review_count(),
+            card.memory_state().map_or(0.0, |m| m.stability),
+            card.memory_state().map_or(0.0, |m| m.difficulty),
+        )
+        .map_err(|e| StorageError::Serialization(e.to_string()))?
+        .with_note_id(stored.note_id())
+        .with_suspended(stored.is_suspended())
+        .with_buried_until(stored.buried_until())
+        .with_learning_step(card.learning_step())
+        .with_lapses(card.lapses())
+        .with_extra(stored.extra().cloned())
+        .with_flag(stored.flag())
+        .with_source(stored.source());
+        guard.cards.insert(card.id(), restored);
+        guard.logs.remove(position);
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -1653,6 +3640,18 @@ impl SessionSummaryRepository for InMemoryRepository {
             .ok_or(StorageError::NotFound)
     }
 
+    async fn delete_summary(&self, id: i64) -> Result<(), StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        guard
+            .summaries
+            .remove(&id)
+            .map(|_| ())
+            .ok_or(StorageError::NotFound)
+    }
+
     async fn list_summaries(
         &self,
         deck_id: DeckId,
@@ -1729,7 +3728,163 @@ impl SessionSummaryRepository for InMemoryRepository {
                 .or_insert(row);
         }
 
-        Ok(latest_by_deck.into_values().collect())
+        // Match SQLite ordering: deck_id ASC
+        let mut rows: Vec<SessionSummaryRow> = latest_by_deck.into_values().collect();
+        rows.sort_by_key(|row| row.summary.deck_id());
+        Ok(rows)
+    }
+
+    async fn list_summaries_between(
+        &self,
+        completed_from: DateTime<Utc>,
+        completed_until: DateTime<Utc>,
+    ) -> Result<Vec<SessionSummary>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let mut rows: Vec<(i64, &SessionSummary)> = guard
+            .summaries
+            .iter()
+            .filter(|(_, summary)| {
+                summary.completed_at() >= completed_from && summary.completed_at() < completed_until
+            })
+            .map(|(id, summary)| (*id, summary))
+            .collect();
+        rows.sort_by_key(|(id, summary)| (summary.completed_at(), *id));
+
+        Ok(rows.into_iter().map(|(_, summary)| summary.clone()).collect())
+    }
+}
+
+#[async_trait]
+impl SessionProgressRepository for InMemoryRepository {
+    async fn save_progress(&self, progress: &SessionProgressRecord) -> Result<(), StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        guard
+            .session_progress
+            .insert(progress.deck_id, progress.clone());
+        Ok(())
+    }
+
+    async fn get_progress(
+        &self,
+        deck_id: DeckId,
+    ) -> Result<Option<SessionProgressRecord>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(guard.session_progress.get(&deck_id).cloned())
+    }
+
+    async fn clear_progress(&self, deck_id: DeckId) -> Result<(), StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        guard.session_progress.remove(&deck_id);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MediaRepository for InMemoryRepository {
+    async fn insert_media(&self, media: NewMediaRecord) -> Result<MediaId, StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        if let Some(existing) = guard.media.values().find(|m| m.checksum == media.checksum) {
+            return Ok(existing.id);
+        }
+        guard.blobs.remove(media.checksum.as_str());
+        let id = MediaId::new(guard.next_media_id);
+        guard.next_media_id += 1;
+        guard.media.insert(
+            id,
+            MediaRecord {
+                id,
+                format: media.format,
+                meta: media.meta,
+                checksum: media.checksum,
+                bytes: media.bytes,
+                created_at: media.created_at,
+            },
+        );
+        Ok(id)
+    }
+
+    async fn get_media(&self, id: MediaId) -> Result<Option<MediaRecord>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(guard.media.get(&id).cloned())
+    }
+
+    async fn put_blob(
+        &self,
+        hash: &MediaHash,
+        bytes: Vec<u8>,
+        _created_at: DateTime<Utc>,
+    ) -> Result<(), StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        if !guard.media.values().any(|m| &m.checksum == hash) {
+            guard.blobs.entry(hash.as_str().to_owned()).or_insert(bytes);
+        }
+        Ok(())
+    }
+
+    async fn get_blob(&self, hash: &MediaHash) -> Result<Option<Vec<u8>>, StorageError> {
+        let guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        let image = guard.media.values().find(|m| &m.checksum == hash);
+        Ok(image
+            .map(|m| m.bytes.clone())
+            .or_else(|| guard.blobs.get(hash.as_str()).cloned()))
+    }
+
+    async fn exists(&self, hash: &MediaHash) -> Result<bool, StorageError> {
+        Ok(self.get_blob(hash).await?.is_some())
+    }
+
+    async fn gc_unreferenced(&self) -> Result<u64, StorageError> {
+        let mut guard = self
+            .state
+            .lock()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        // Trashed cards keep their media so they can still be restored.
+        let sides = || {
+            guard
+                .cards
+                .values()
+                .chain(guard.trashed.values().map(|trashed| &trashed.card))
+                .flat_map(|card| [card.prompt(), card.answer()])
+        };
+        let media_ids: HashSet<MediaId> = sides().filter_map(Content::media_id).collect();
+        let audio_hashes: HashSet<String> = sides()
+            .filter_map(|content| content.audio().map(|a| a.checksum().as_str().to_owned()))
+            .collect();
+
+        let before = guard.media.len() + guard.blobs.len();
+        guard.media.retain(|id, m| {
+            media_ids.contains(id) || audio_hashes.contains(m.checksum.as_str())
+        });
+        guard.blobs.retain(|hash, _| audio_hashes.contains(hash));
+        let removed = before - guard.media.len() - guard.blobs.len();
+        Ok(removed as u64)
     }
 }
 
@@ -1741,9 +3896,11 @@ pub struct Storage {
     pub review_logs: Arc<dyn ReviewLogRepository>,
     pub reviews: Arc<dyn ReviewPersistence>,
     pub session_summaries: Arc<dyn SessionSummaryRepository>,
+    pub session_progress: Arc<dyn SessionProgressRepository>,
     pub app_settings: Arc<dyn AppSettingsRepository>,
     pub ai_price_book: Arc<dyn AiPriceBookRepository>,
     pub ai_usage: Arc<dyn AiUsageRepository>,
+    pub media: Arc<dyn MediaRepository>,
 }
 
 impl Storage {
@@ -1755,18 +3912,22 @@ impl Storage {
         let review_logs: Arc<dyn ReviewLogRepository> = Arc::new(repo.clone());
         let reviews: Arc<dyn ReviewPersistence> = Arc::new(repo.clone());
         let session_summaries: Arc<dyn SessionSummaryRepository> = Arc::new(repo.clone());
+        let session_progress: Arc<dyn SessionProgressRepository> = Arc::new(repo.clone());
         let app_settings: Arc<dyn AppSettingsRepository> = Arc::new(repo.clone());
         let ai_price_book: Arc<dyn AiPriceBookRepository> = Arc::new(repo.clone());
-        let ai_usage: Arc<dyn AiUsageRepository> = Arc::new(repo);
+        let ai_usage: Arc<dyn AiUsageRepository> = Arc::new(repo.clone());
+        let media: Arc<dyn MediaRepository> = Arc::new(repo);
         Self {
             decks,
             cards,
             review_logs,
             reviews,
             session_summaries,
+            session_progress,
             app_settings,
             ai_price_book,
             ai_usage,
+            media,
         }
     }
 }
@@ -1797,7 +3958,7 @@ mod tests {
             .validate(fixed_now(), None, None)
             .unwrap();
         let now = fixed_now();
-        Card::new(CardId::new(id), deck_id, prompt, answer, now, now).unwrap()
+        Card::new(CardId::new(id), deck_id, CardKind::Basic, prompt, answer, now, now).unwrap()
     }
 
     #[tokio::test]
@@ -1868,6 +4029,7 @@ mod tests {
                 total: 2,
                 due: 1,
                 new: 1,
+                learning: 1,
             })
         );
         assert_eq!(
@@ -1876,6 +4038,7 @@ mod tests {
                 total: 2,
                 due: 0,
                 new: 1,
+                learning: 0,
             })
         );
     }
@@ -1914,4 +4077,47 @@ mod tests {
         assert_eq!(by_deck.get(&deck2.id()), Some(&id3));
         assert_ne!(by_deck.get(&deck1.id()), Some(&id1));
     }
+
+    #[tokio::test]
+    async fn blobs_dedupe_by_hash_and_gc_drops_unreferenced() {
+        let repo = InMemoryRepository::new();
+        let deck = build_deck(1);
+        repo.upsert_deck(&deck).await.unwrap();
+
+        let hash = MediaHash::new("sha256:aa");
+        repo.put_blob(&hash, vec![1], fixed_now()).await.unwrap();
+        repo.put_blob(&hash, vec![2], fixed_now()).await.unwrap();
+        assert_eq!(repo.get_blob(&hash).await.unwrap(), Some(vec![1]));
+
+        let image_id = repo
+            .insert_media(NewMediaRecord {
+                format: ImageFormat::Png,
+                meta: ImageMeta::new(1, 1).unwrap(),
+                checksum: MediaHash::new("sha256:img"),
+                bytes: vec![3],
+                created_at: fixed_now(),
+            })
+            .await
+            .unwrap();
+        let prompt = ContentDraft::text_only("Q")
+            .with_media_id(Some(image_id))
+            .validate(fixed_now(), None, None)
+            .unwrap();
+        let card = build_card(1, deck.id());
+        let card = Card::new(
+            card.id(),
+            deck.id(),
+            CardKind::Basic,
+            prompt,
+            card.answer().clone(),
+            fixed_now(),
+            fixed_now(),
+        )
+        .unwrap();
+        repo.upsert_card(&card).await.unwrap();
+
+        assert_eq!(repo.gc_unreferenced().await.unwrap(), 1);
+        assert!(!repo.exists(&hash).await.unwrap());
+        assert!(repo.get_media(image_id).await.unwrap().is_some());
+    }
 }
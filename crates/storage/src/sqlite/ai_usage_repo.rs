@@ -3,9 +3,11 @@ use chrono::{DateTime, Utc};
 use sqlx::Row;
 
 use crate::repository::{
-    AiUsageCompletion, AiUsageRepository, AiUsageStatus, NewAiUsageRecord, StorageError,
+    AiUsageCompletion, AiUsageRecord, AiUsageRepository, AiUsageStatus, NewAiUsageRecord,
+    StorageError,
 };
 
+use super::mapping::parse_ai_usage_status;
 use super::SqliteRepository;
 
 #[async_trait]
@@ -117,6 +119,29 @@ impl AiUsageRepository for SqliteRepository {
             .map_err(|err| StorageError::Serialization(err.to_string()))?;
         Ok(u64::try_from(total).unwrap_or(u64::MAX))
     }
+
+    async fn list_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<AiUsageRecord>, StorageError> {
+        let rows = sqlx::query(
+            r"
+            SELECT id, provider, model, created_at, status,
+                   prompt_tokens, completion_tokens, total_tokens, cost_micro_usd
+            FROM ai_usage
+            WHERE created_at >= ?1 AND created_at < ?2
+            ORDER BY created_at ASC, id ASC
+            ",
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| StorageError::Connection(err.to_string()))?;
+
+        rows.iter().map(map_usage_row).collect()
+    }
 }
 
 fn status_to_str(status: AiUsageStatus) -> &'static str {
@@ -126,3 +151,35 @@ fn status_to_str(status: AiUsageStatus) -> &'static str {
         AiUsageStatus::Failed => "failed",
     }
 }
+
+fn map_usage_row(row: &sqlx::sqlite::SqliteRow) -> Result<AiUsageRecord, StorageError> {
+    let ser = |err: sqlx::Error| StorageError::Serialization(err.to_string());
+    let tokens = |column: &str| -> Result<Option<u32>, StorageError> {
+        row.try_get::<Option<i64>, _>(column)
+            .map_err(ser)?
+            .map(|value| {
+                u32::try_from(value)
+                    .map_err(|_| StorageError::Serialization(format!("{column} out of range")))
+            })
+            .transpose()
+    };
+    let cost = row
+        .try_get::<Option<i64>, _>("cost_micro_usd")
+        .map_err(ser)?
+        .map(|value| {
+            u64::try_from(value)
+                .map_err(|_| StorageError::Serialization("cost_micro_usd sign overflow".into()))
+        })
+        .transpose()?;
+    Ok(AiUsageRecord {
+        id: row.try_get("id").map_err(ser)?,
+        provider: row.try_get("provider").map_err(ser)?,
+        model: row.try_get("model").map_err(ser)?,
+        created_at: row.try_get("created_at").map_err(ser)?,
+        status: parse_ai_usage_status(&row.try_get::<String, _>("status").map_err(ser)?)?,
+        prompt_tokens: tokens("prompt_tokens")?,
+        completion_tokens: tokens("completion_tokens")?,
+        total_tokens: tokens("total_tokens")?,
+        cost_micro_usd: cost,
+    })
+}
@@ -0,0 +1,130 @@
+//! Read-only access to an Anki collection database (`collection.anki2`).
+//!
+//! Rows come back as stored; interpreting note types, templates, and scheduling is left
+//! to the importer in `services`.
+
+use std::path::Path;
+
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection, SqliteRow};
+use sqlx::{Connection, Row};
+
+use crate::repository::StorageError;
+
+/// One row of the collection's `notes` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnkiNoteRow {
+    pub id: i64,
+    /// Note type ID, a key into `AnkiCollection::models`.
+    pub model_id: i64,
+    /// Space-separated tags.
+    pub tags: String,
+    /// Field values in note-type order, separated by `\x1f`.
+    pub fields: String,
+}
+
+/// One row of the collection's `cards` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnkiCardRow {
+    pub id: i64,
+    pub note_id: i64,
+    pub deck_id: i64,
+    /// Template index for standard notes, cloze number minus one for cloze notes.
+    pub ord: i64,
+    /// 0 new, 1 learning, 2 review, 3 relearning.
+    pub card_type: i64,
+    /// Negative while suspended or buried; otherwise mirrors `card_type`.
+    pub queue: i64,
+    /// Review cards: days since the collection was created. Learning cards: a Unix time.
+    pub due: i64,
+    /// Days for review cards; negative seconds while learning.
+    pub interval: i64,
+    /// Ease in permille, e.g. 2500 for 250%.
+    pub factor: i64,
+    pub reps: i64,
+    pub lapses: i64,
+}
+
+/// Everything the importer needs from a collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnkiCollection {
+    /// Collection creation time, in Unix seconds; review due days count from here.
+    pub created_secs: i64,
+    /// JSON object of note types keyed by ID.
+    pub models: String,
+    /// JSON object of decks keyed by ID.
+    pub decks: String,
+    pub notes: Vec<AnkiNoteRow>,
+    pub cards: Vec<AnkiCardRow>,
+}
+
+fn read_err(err: sqlx::Error) -> StorageError {
+    StorageError::Serialization(format!("not a readable Anki collection: {err}"))
+}
+
+/// Open the collection at `path` read-only and load its notes, cards, note types, and decks.
+///
+/// # Errors
+///
+/// Returns `StorageError::Connection` if the file cannot be opened, and
+/// `StorageError::Serialization` if it lacks the tables of an Anki 2.1 collection.
+pub async fn read_anki_collection(path: &Path) -> Result<AnkiCollection, StorageError> {
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let mut conn = SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+    let col = sqlx::query("SELECT crt, models, decks FROM col LIMIT 1")
+        .fetch_one(&mut conn)
+        .await
+        .map_err(read_err)?;
+    let notes = sqlx::query("SELECT id, mid, tags, flds FROM notes ORDER BY id")
+        .fetch_all(&mut conn)
+        .await
+        .map_err(read_err)?;
+    let cards = sqlx::query(
+        r"
+        SELECT id, nid, did, ord, type, queue, due, ivl, factor, reps, lapses
+        FROM cards
+        ORDER BY id
+        ",
+    )
+    .fetch_all(&mut conn)
+    .await
+    .map_err(read_err)?;
+    conn.close()
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+    Ok(AnkiCollection {
+        created_secs: col.try_get("crt").map_err(read_err)?,
+        models: col.try_get("models").map_err(read_err)?,
+        decks: col.try_get("decks").map_err(read_err)?,
+        notes: notes.iter().map(note_from_row).collect::<Result<_, _>>()?,
+        cards: cards.iter().map(card_from_row).collect::<Result<_, _>>()?,
+    })
+}
+
+fn note_from_row(row: &SqliteRow) -> Result<AnkiNoteRow, StorageError> {
+    Ok(AnkiNoteRow {
+        id: row.try_get("id").map_err(read_err)?,
+        model_id: row.try_get("mid").map_err(read_err)?,
+        tags: row.try_get("tags").map_err(read_err)?,
+        fields: row.try_get("flds").map_err(read_err)?,
+    })
+}
+
+fn card_from_row(row: &SqliteRow) -> Result<AnkiCardRow, StorageError> {
+    Ok(AnkiCardRow {
+        id: row.try_get("id").map_err(read_err)?,
+        note_id: row.try_get("nid").map_err(read_err)?,
+        deck_id: row.try_get("did").map_err(read_err)?,
+        ord: row.try_get("ord").map_err(read_err)?,
+        card_type: row.try_get("type").map_err(read_err)?,
+        queue: row.try_get("queue").map_err(read_err)?,
+        due: row.try_get("due").map_err(read_err)?,
+        interval: row.try_get("ivl").map_err(read_err)?,
+        factor: row.try_get("factor").map_err(read_err)?,
+        reps: row.try_get("reps").map_err(read_err)?,
+        lapses: row.try_get("lapses").map_err(read_err)?,
+    })
+}
@@ -2,9 +2,10 @@ use async_trait::async_trait;
 use sqlx::Row;
 
 use crate::repository::{AppSettingsRepository, StorageError};
-use learn_core::model::{AppSettings, AppSettingsDraft};
+use learn_core::model::{AppSettings, AppSettingsDraft, DeckId, KeyBindings};
 
 use super::SqliteRepository;
+use super::mapping::{parse_accent_color, parse_app_language, parse_theme_preference};
 
 #[async_trait]
 impl AppSettingsRepository for SqliteRepository {
@@ -17,7 +18,19 @@ impl AppSettingsRepository for SqliteRepository {
                 api_fallback_model,
                 ai_system_prompt,
                 ai_daily_request_cap,
-                ai_cooldown_secs
+                ai_cooldown_secs,
+                ai_manual_price_micro_usd_per_1k,
+                theme,
+                accent_color,
+                language,
+                analytics_enabled,
+                autoplay_audio,
+                replay_audio_after_answer,
+                audio_delay_ms,
+                last_deck_id,
+                day_cutoff_hour,
+                focus_mode,
+                key_bindings
             FROM app_settings
             WHERE id = 1
             ",
@@ -48,6 +61,47 @@ impl AppSettingsRepository for SqliteRepository {
         let ai_cooldown_secs: Option<i64> = row
             .try_get("ai_cooldown_secs")
             .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        let ai_manual_price: Option<i64> = row
+            .try_get("ai_manual_price_micro_usd_per_1k")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        let theme: Option<String> = row
+            .try_get("theme")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        let accent_color: Option<String> = row
+            .try_get("accent_color")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        let language: Option<String> = row
+            .try_get("language")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        let analytics_enabled: Option<i64> = row
+            .try_get("analytics_enabled")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        let autoplay_audio: Option<i64> = row
+            .try_get("autoplay_audio")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        let replay_audio_after_answer: Option<i64> = row
+            .try_get("replay_audio_after_answer")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        let audio_delay_ms: Option<i64> = row
+            .try_get("audio_delay_ms")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        let last_deck_id: Option<i64> = row
+            .try_get("last_deck_id")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        let day_cutoff_hour: Option<i64> = row
+            .try_get("day_cutoff_hour")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        let focus_mode: Option<i64> = row
+            .try_get("focus_mode")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        let key_bindings: Option<String> = row
+            .try_get("key_bindings")
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
+        let key_bindings = key_bindings
+            .as_deref()
+            .map(KeyBindings::parse)
+            .transpose()
+            .map_err(|err| StorageError::Serialization(err.to_string()))?;
 
         AppSettings::from_persisted(AppSettingsDraft {
             api_key,
@@ -56,6 +110,21 @@ impl AppSettingsRepository for SqliteRepository {
             ai_system_prompt,
             ai_daily_request_cap: ai_daily_request_cap.and_then(|val| u32::try_from(val).ok()),
             ai_cooldown_secs: ai_cooldown_secs.and_then(|val| u32::try_from(val).ok()),
+            ai_manual_price_micro_usd_per_1k: ai_manual_price
+                .and_then(|val| u64::try_from(val).ok()),
+            theme: theme.as_deref().map(parse_theme_preference).transpose()?,
+            accent_color: accent_color.as_deref().map(parse_accent_color).transpose()?,
+            language: language.as_deref().map(parse_app_language).transpose()?,
+            analytics_enabled: analytics_enabled.map(|val| val != 0),
+            autoplay_audio: autoplay_audio.map(|val| val != 0),
+            replay_audio_after_answer: replay_audio_after_answer.map(|val| val != 0),
+            audio_delay_ms: audio_delay_ms.and_then(|val| u32::try_from(val).ok()),
+            last_deck_id: last_deck_id
+                .and_then(|val| u64::try_from(val).ok())
+                .map(DeckId::new),
+            day_cutoff_hour: day_cutoff_hour.and_then(|val| u32::try_from(val).ok()),
+            focus_mode: focus_mode.map(|val| val != 0),
+            key_bindings,
         })
         .map(Some)
         .map_err(|err| StorageError::Serialization(err.to_string()))
@@ -71,16 +140,43 @@ impl AppSettingsRepository for SqliteRepository {
                 api_fallback_model,
                 ai_system_prompt,
                 ai_daily_request_cap,
-                ai_cooldown_secs
+                ai_cooldown_secs,
+                ai_manual_price_micro_usd_per_1k,
+                theme,
+                accent_color,
+                language,
+                analytics_enabled,
+                autoplay_audio,
+                replay_audio_after_answer,
+                audio_delay_ms,
+                last_deck_id,
+                day_cutoff_hour,
+                focus_mode,
+                key_bindings
+            )
+            VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+                ?17, ?18, ?19
             )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
             ON CONFLICT(id) DO UPDATE SET
                 api_key = excluded.api_key,
                 api_model = excluded.api_model,
                 api_fallback_model = excluded.api_fallback_model,
                 ai_system_prompt = excluded.ai_system_prompt,
                 ai_daily_request_cap = excluded.ai_daily_request_cap,
-                ai_cooldown_secs = excluded.ai_cooldown_secs
+                ai_cooldown_secs = excluded.ai_cooldown_secs,
+                ai_manual_price_micro_usd_per_1k = excluded.ai_manual_price_micro_usd_per_1k,
+                theme = excluded.theme,
+                accent_color = excluded.accent_color,
+                language = excluded.language,
+                analytics_enabled = excluded.analytics_enabled,
+                autoplay_audio = excluded.autoplay_audio,
+                replay_audio_after_answer = excluded.replay_audio_after_answer,
+                audio_delay_ms = excluded.audio_delay_ms,
+                last_deck_id = excluded.last_deck_id,
+                day_cutoff_hour = excluded.day_cutoff_hour,
+                focus_mode = excluded.focus_mode,
+                key_bindings = excluded.key_bindings
             ",
         )
         .bind(1_i64)
@@ -90,6 +186,30 @@ impl AppSettingsRepository for SqliteRepository {
         .bind(settings.ai_system_prompt())
         .bind(i64::from(settings.ai_daily_request_cap()))
         .bind(i64::from(settings.ai_cooldown_secs()))
+        .bind(
+            settings
+                .ai_manual_price_micro_usd_per_1k()
+                .map(i64::try_from)
+                .transpose()
+                .map_err(|err| StorageError::Serialization(err.to_string()))?,
+        )
+        .bind(settings.theme().as_str())
+        .bind(settings.accent_color().as_str())
+        .bind(settings.language().as_str())
+        .bind(i64::from(settings.analytics_enabled()))
+        .bind(i64::from(settings.autoplay_audio()))
+        .bind(i64::from(settings.replay_audio_after_answer()))
+        .bind(i64::from(settings.audio_delay_ms()))
+        .bind(
+            settings
+                .last_deck_id()
+                .map(|id| i64::try_from(id.value()))
+                .transpose()
+                .map_err(|err| StorageError::Serialization(err.to_string()))?,
+        )
+        .bind(i64::from(settings.day_cutoff_hour()))
+        .bind(i64::from(settings.focus_mode()))
+        .bind(settings.key_bindings().to_string())
         .execute(&self.pool)
         .await
         .map_err(|err| StorageError::Connection(err.to_string()))?;
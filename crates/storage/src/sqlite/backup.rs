@@ -0,0 +1,182 @@
+//! Timestamped snapshots of a file-backed database with rotation.
+//!
+//! Copies go through `SQLite`'s online backup API, so a snapshot taken while another
+//! connection writes, or while pages still sit in the WAL, is always consistent.
+
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use libsqlite3_sys as ffi;
+use sqlx::Connection;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode};
+use thiserror::Error;
+
+const BACKUP_PREFIX: &str = "learn-";
+const BACKUP_EXTENSION: &str = ".sqlite3";
+/// Sorts the same as it orders in time, so the newest backup has the greatest name.
+const BACKUP_STAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum BackupError {
+    #[error("backup directory: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+    #[error("online backup failed: {0}")]
+    Backup(String),
+}
+
+/// Snapshot the database at `database_url` into `dir`, then delete all but the newest
+/// `keep` snapshots there. Returns the path of the new snapshot.
+///
+/// Snapshots are named `learn-<UTC time of now>.sqlite3`; other files in `dir` are left
+/// alone.
+///
+/// # Errors
+///
+/// Returns `BackupError::Io` if `dir` cannot be created or listed or a stale snapshot
+/// cannot be deleted, `BackupError::Sqlx` if either database cannot be opened, and
+/// `BackupError::Backup` if `SQLite` rejects the copy.
+pub async fn backup_with_rotation(
+    database_url: &str,
+    dir: &Path,
+    keep: usize,
+    now: DateTime<Utc>,
+) -> Result<PathBuf, BackupError> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(backup_file_name(now));
+    backup_database(database_url, &path).await?;
+
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        names.push(entry?.file_name().to_string_lossy().into_owned());
+    }
+    for name in stale_backups(names, keep) {
+        std::fs::remove_file(dir.join(name))?;
+    }
+    Ok(path)
+}
+
+/// Copy the database at `database_url` into a standalone file at `path`.
+///
+/// # Errors
+///
+/// Returns `BackupError::Sqlx` if either database cannot be opened, and
+/// `BackupError::Backup` if `SQLite` rejects the copy.
+pub async fn backup_database(database_url: &str, path: &Path) -> Result<(), BackupError> {
+    let source_options = SqliteConnectOptions::from_str(database_url)?.read_only(true);
+    let mut source = SqliteConnection::connect_with(&source_options).await?;
+    // A rollback journal keeps the snapshot to one file with no `-wal` beside it.
+    let dest_options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Delete);
+    let mut dest = SqliteConnection::connect_with(&dest_options).await?;
+
+    let copied = {
+        let mut source_handle = source.lock_handle().await?;
+        let mut dest_handle = dest.lock_handle().await?;
+        copy_database(source_handle.as_raw_handle(), dest_handle.as_raw_handle())
+    };
+    let _ = source.close().await;
+    dest.close().await?;
+    copied
+}
+
+/// Run a single-step online backup of `source`'s main database into `dest`.
+fn copy_database(
+    source: NonNull<ffi::sqlite3>,
+    dest: NonNull<ffi::sqlite3>,
+) -> Result<(), BackupError> {
+    // SAFETY: both handles come from open connections whose locks the caller holds for the
+    // whole call, and the backup object is finished before returning.
+    unsafe {
+        let backup = ffi::sqlite3_backup_init(
+            dest.as_ptr(),
+            c"main".as_ptr(),
+            source.as_ptr(),
+            c"main".as_ptr(),
+        );
+        if backup.is_null() {
+            return Err(BackupError::Backup(error_message(dest)));
+        }
+        // -1 copies every page in one step, under one read of the source.
+        let step = ffi::sqlite3_backup_step(backup, -1);
+        let finish = ffi::sqlite3_backup_finish(backup);
+        if step != ffi::SQLITE_DONE || finish != ffi::SQLITE_OK {
+            return Err(BackupError::Backup(error_message(dest)));
+        }
+    }
+    Ok(())
+}
+
+/// The last error `SQLite` recorded on `db`.
+///
+/// # Safety
+///
+/// `db` must be an open connection handle.
+unsafe fn error_message(db: NonNull<ffi::sqlite3>) -> String {
+    // SAFETY: `sqlite3_errmsg` returns a NUL-terminated string owned by the open handle.
+    unsafe { CStr::from_ptr(ffi::sqlite3_errmsg(db.as_ptr())) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn backup_file_name(now: DateTime<Utc>) -> String {
+    format!("{BACKUP_PREFIX}{}{BACKUP_EXTENSION}", now.format(BACKUP_STAMP_FORMAT))
+}
+
+fn is_backup_file_name(name: &str) -> bool {
+    name.strip_prefix(BACKUP_PREFIX)
+        .and_then(|rest| rest.strip_suffix(BACKUP_EXTENSION))
+        .is_some_and(|stamp| NaiveDateTime::parse_from_str(stamp, BACKUP_STAMP_FORMAT).is_ok())
+}
+
+/// Snapshot file names among `names` that fall outside the newest `keep`, oldest first.
+fn stale_backups(names: impl IntoIterator<Item = String>, keep: usize) -> Vec<String> {
+    let mut backups: Vec<String> =
+        names.into_iter().filter(|name| is_backup_file_name(name)).collect();
+    backups.sort();
+    let stale = backups.len().saturating_sub(keep);
+    backups.truncate(stale);
+    backups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    fn name_at(hour: u32) -> String {
+        backup_file_name(Utc.with_ymd_and_hms(2026, 3, 1, hour, 15, 0).unwrap())
+    }
+
+    #[test]
+    fn backup_names_sort_by_time() {
+        assert_eq!(name_at(9), "learn-20260301-091500.sqlite3");
+        assert!(name_at(9) < name_at(10));
+        assert!(is_backup_file_name(&name_at(9)));
+        assert!(!is_backup_file_name("learn-notes.sqlite3"));
+        assert!(!is_backup_file_name("dev.sqlite3"));
+    }
+
+    #[test]
+    fn rotation_keeps_the_newest_backups() {
+        let names = vec![
+            name_at(12),
+            "notes.txt".to_owned(),
+            name_at(8),
+            name_at(15),
+            name_at(10),
+        ];
+
+        assert_eq!(stale_backups(names.clone(), 2), vec![name_at(8), name_at(10)]);
+        assert_eq!(stale_backups(names.clone(), 4), Vec::<String>::new());
+        assert_eq!(stale_backups(names, 0).len(), 4);
+    }
+}
@@ -0,0 +1,97 @@
+//! Read-only health check of an `SQLite` database, for diagnosing reported breakage.
+//!
+//! Nothing here writes: the checks are `PRAGMA`s and counting queries, so they are safe
+//! to run against a database the app is having trouble with.
+
+use sqlx::Row;
+
+use super::SqliteRepository;
+use crate::repository::StorageError;
+
+/// What `check` found in one database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    /// Lines of `PRAGMA integrity_check`; a healthy file reports a single `ok`.
+    pub integrity: Vec<String>,
+    /// Rows `PRAGMA foreign_key_check` reports as pointing at a missing parent.
+    pub foreign_key_violations: u64,
+    /// Highest migration version recorded in `schema_migrations`.
+    pub schema_version: i64,
+    pub decks: u64,
+    pub cards: u64,
+    pub review_logs: u64,
+    /// Cards whose deck no longer exists.
+    pub orphaned_cards: u64,
+    /// Review logs whose card no longer exists.
+    pub orphaned_review_logs: u64,
+}
+
+impl HealthReport {
+    /// Whether the file passed the integrity check and holds no dangling rows.
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.integrity == ["ok"]
+            && self.foreign_key_violations == 0
+            && self.orphaned_cards == 0
+            && self.orphaned_review_logs == 0
+    }
+}
+
+/// Run every health check against `repo` without changing it.
+///
+/// Open `repo` with `SqliteRepository::connect_read_only` so that opening the file does not
+/// write either.
+///
+/// # Errors
+///
+/// Returns `StorageError::Connection` if a check cannot run, e.g. on a database that was
+/// never migrated.
+pub async fn check(repo: &SqliteRepository) -> Result<HealthReport, StorageError> {
+    let pool = repo.pool();
+    let conn = |e: sqlx::Error| StorageError::Connection(e.to_string());
+
+    let integrity: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_all(pool)
+        .await
+        .map_err(conn)?;
+    let foreign_key_violations = sqlx::query("PRAGMA foreign_key_check")
+        .fetch_all(pool)
+        .await
+        .map_err(conn)?
+        .len();
+    let schema_version = repo.current_schema_version().await?;
+
+    let row = sqlx::query(
+        r"
+        SELECT
+            (SELECT COUNT(*) FROM decks) AS decks,
+            (SELECT COUNT(*) FROM cards) AS cards,
+            (SELECT COUNT(*) FROM review_logs) AS review_logs,
+            (SELECT COUNT(*) FROM cards
+             WHERE deck_id NOT IN (SELECT id FROM decks)) AS orphaned_cards,
+            (SELECT COUNT(*) FROM review_logs
+             WHERE card_id NOT IN (SELECT id FROM cards)) AS orphaned_review_logs
+        ",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(conn)?;
+    let count = |column: &str| -> Result<u64, StorageError> {
+        let value: i64 = row
+            .try_get(column)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        u64::try_from(value)
+            .map_err(|_| StorageError::Serialization(format!("{column} count overflow")))
+    };
+
+    Ok(HealthReport {
+        integrity,
+        foreign_key_violations: u64::try_from(foreign_key_violations).unwrap_or(u64::MAX),
+        schema_version,
+        decks: count("decks")?,
+        cards: count("cards")?,
+        review_logs: count("review_logs")?,
+        orphaned_cards: count("orphaned_cards")?,
+        orphaned_review_logs: count("orphaned_review_logs")?,
+    })
+}
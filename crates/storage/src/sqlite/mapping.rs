@@ -1,9 +1,12 @@
 use learn_core::model::{
-    Card, CardId, CardPhase, DeckId, ReviewGrade, Tag, TagId, TagName, content::Content,
+    AccentColor, AnswerButtons, AppLanguage, Card, CardId, CardKind, CardPhase, CardSource,
+    DeckColor, DeckIcon, DeckId, DeckSettings, Flag, LeechAction, NewCardOrder, NewReviewMix,
+    NoteId, ReviewGrade, Tag, TagId, TagName, ThemePreference,
+    content::{AudioClip, Content, MediaHash, MediaUri},
 };
 use sqlx::Row;
 
-use crate::repository::StorageError;
+use crate::repository::{AiUsageStatus, StorageError};
 
 fn ser<E: core::fmt::Display>(e: E) -> StorageError {
     StorageError::Serialization(e.to_string())
@@ -25,6 +28,19 @@ pub(crate) fn media_id_from_i64(v: i64) -> Result<learn_core::model::MediaId, St
     Ok(learn_core::model::MediaId::new(i64_to_u64("media_id", v)?))
 }
 
+pub(crate) fn note_id_from_i64(v: i64) -> Result<NoteId, StorageError> {
+    Ok(NoteId::new(i64_to_u64("note_id", v)?))
+}
+
+pub(crate) fn note_id_to_i64(note_id: Option<NoteId>) -> Result<Option<i64>, StorageError> {
+    note_id
+        .map(|n| {
+            i64::try_from(n.value())
+                .map_err(|_| StorageError::Serialization("note_id overflow".into()))
+        })
+        .transpose()
+}
+
 pub(crate) fn tag_id_from_i64(v: i64) -> Result<TagId, StorageError> {
     Ok(TagId::new(i64_to_u64("tag_id", v)?))
 }
@@ -49,8 +65,224 @@ pub(crate) fn parse_card_phase(s: &str) -> Result<CardPhase, StorageError> {
     }
 }
 
+pub(crate) fn parse_new_review_mix(s: &str) -> Result<NewReviewMix, StorageError> {
+    match s {
+        "front_load" => Ok(NewReviewMix::FrontLoad),
+        "interleaved" => Ok(NewReviewMix::Interleaved),
+        "after_reviews" => Ok(NewReviewMix::AfterReviews),
+        _ => Err(StorageError::Serialization(format!("invalid new_review_mix: {s}"))),
+    }
+}
+
+pub(crate) fn parse_answer_buttons(s: &str) -> Result<AnswerButtons, StorageError> {
+    match s {
+        "two" => Ok(AnswerButtons::Two),
+        "four" => Ok(AnswerButtons::Four),
+        _ => Err(StorageError::Serialization(format!("invalid answer_buttons: {s}"))),
+    }
+}
+
+pub(crate) fn parse_flag(s: &str) -> Result<Flag, StorageError> {
+    Flag::ALL
+        .into_iter()
+        .find(|flag| flag.as_str() == s)
+        .ok_or_else(|| StorageError::Serialization(format!("invalid flag: {s}")))
+}
+
+pub(crate) fn parse_card_source(s: &str) -> Result<CardSource, StorageError> {
+    CardSource::ALL
+        .into_iter()
+        .find(|source| source.as_str() == s)
+        .ok_or_else(|| StorageError::Serialization(format!("invalid card source: {s}")))
+}
+
+pub(crate) fn parse_leech_action(s: &str) -> Result<LeechAction, StorageError> {
+    match s {
+        "tag" => Ok(LeechAction::Tag),
+        "suspend" => Ok(LeechAction::Suspend),
+        _ => Err(StorageError::Serialization(format!("invalid leech_action: {s}"))),
+    }
+}
+
+pub(crate) fn parse_new_card_order(s: &str) -> Result<NewCardOrder, StorageError> {
+    match s {
+        "sequential" => Ok(NewCardOrder::Sequential),
+        "random" => Ok(NewCardOrder::Random),
+        _ => Err(StorageError::Serialization(format!("invalid new_card_order: {s}"))),
+    }
+}
+
+pub(crate) fn parse_deck_color(s: &str) -> Result<DeckColor, StorageError> {
+    s.parse()
+        .map_err(|_| StorageError::Serialization(format!("invalid color: {s}")))
+}
+
+pub(crate) fn parse_deck_icon(s: &str) -> Result<DeckIcon, StorageError> {
+    s.parse()
+        .map_err(|_| StorageError::Serialization(format!("invalid icon: {s}")))
+}
+
+/// Learning steps are stored as comma-separated seconds; an empty string means no steps.
+pub(crate) fn parse_learning_steps(s: &str) -> Result<Vec<u32>, StorageError> {
+    if s.is_empty() {
+        return Ok(Vec::new());
+    }
+    s.split(',')
+        .map(|step| {
+            step.parse::<u32>()
+                .map_err(|_| StorageError::Serialization(format!("invalid learning_steps: {s}")))
+        })
+        .collect()
+}
+
+pub(crate) fn format_learning_steps(steps: &[u32]) -> String {
+    steps
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// FSRS weights are stored as comma-separated floats; an empty string means the defaults.
+pub(crate) fn parse_fsrs_parameters(s: &str) -> Result<Vec<f32>, StorageError> {
+    DeckSettings::parse_fsrs_parameters(s)
+        .map_err(|_| StorageError::Serialization(format!("invalid fsrs_parameters: {s}")))
+}
+
+pub(crate) fn format_fsrs_parameters(weights: &[f32]) -> String {
+    DeckSettings::format_fsrs_parameters(weights)
+}
+
+pub(crate) fn parse_theme_preference(s: &str) -> Result<ThemePreference, StorageError> {
+    match s {
+        "system" => Ok(ThemePreference::System),
+        "light" => Ok(ThemePreference::Light),
+        "dark" => Ok(ThemePreference::Dark),
+        _ => Err(StorageError::Serialization(format!("invalid theme: {s}"))),
+    }
+}
+
+pub(crate) fn parse_ai_usage_status(s: &str) -> Result<AiUsageStatus, StorageError> {
+    match s {
+        "started" => Ok(AiUsageStatus::Started),
+        "succeeded" => Ok(AiUsageStatus::Succeeded),
+        "failed" => Ok(AiUsageStatus::Failed),
+        _ => Err(StorageError::Serialization(format!("invalid ai usage status: {s}"))),
+    }
+}
+
+pub(crate) fn parse_accent_color(s: &str) -> Result<AccentColor, StorageError> {
+    match s {
+        "blue" => Ok(AccentColor::Blue),
+        "green" => Ok(AccentColor::Green),
+        "orange" => Ok(AccentColor::Orange),
+        "purple" => Ok(AccentColor::Purple),
+        _ => Err(StorageError::Serialization(format!("invalid accent_color: {s}"))),
+    }
+}
+
+pub(crate) fn parse_app_language(s: &str) -> Result<AppLanguage, StorageError> {
+    match s {
+        "en" => Ok(AppLanguage::English),
+        "de" => Ok(AppLanguage::German),
+        "es" => Ok(AppLanguage::Spanish),
+        "fr" => Ok(AppLanguage::French),
+        _ => Err(StorageError::Serialization(format!("invalid language: {s}"))),
+    }
+}
+
+/// Split a card kind into the nullable `cloze_text` / `cloze_indices` columns.
+///
+/// Indices are stored comma-separated, e.g. `"1,3"`; basic cards store `NULL` in both.
+pub(crate) fn card_kind_to_columns(kind: &CardKind) -> (Option<String>, Option<String>) {
+    match kind {
+        CardKind::Basic => (None, None),
+        CardKind::Cloze { text, indices } => {
+            let indices = indices
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            (Some(text.clone()), Some(indices))
+        }
+    }
+}
+
+pub(crate) fn card_kind_from_columns(
+    text: Option<String>,
+    indices: Option<String>,
+) -> Result<CardKind, StorageError> {
+    match (text, indices) {
+        (None, None) => Ok(CardKind::Basic),
+        (Some(text), Some(indices)) => {
+            let indices = indices
+                .split(',')
+                .map(|raw| {
+                    raw.trim().parse::<u32>().map_err(|_| {
+                        StorageError::Serialization(format!("invalid cloze index: {raw}"))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(CardKind::Cloze { text, indices })
+        }
+        _ => Err(StorageError::Serialization(
+            "cloze_text and cloze_indices must both be set".into(),
+        )),
+    }
+}
+
+/// Split an audio clip into the nullable `*_audio_uri` / `*_audio_hash` columns.
+///
+/// URLs are stored as-is and file paths as plain text, so a value containing `://` reads
+/// back as a URL.
+pub(crate) fn audio_to_columns(audio: Option<&AudioClip>) -> (Option<String>, Option<String>) {
+    match audio {
+        None => (None, None),
+        Some(clip) => {
+            let uri = match clip.uri() {
+                MediaUri::FilePath(path) => path.to_string_lossy().into_owned(),
+                MediaUri::Url(url) => url.as_str().to_owned(),
+            };
+            (Some(uri), Some(clip.checksum().as_str().to_owned()))
+        }
+    }
+}
+
+pub(crate) fn audio_from_columns(
+    uri: Option<String>,
+    hash: Option<String>,
+) -> Result<Option<AudioClip>, StorageError> {
+    match (uri, hash) {
+        (None, None) => Ok(None),
+        (Some(uri), Some(hash)) => {
+            let uri = if uri.contains("://") {
+                MediaUri::from_url(&uri)
+            } else {
+                MediaUri::from_file(uri)
+            }
+            .map_err(ser)?;
+            AudioClip::from_persisted(uri, MediaHash::new(hash))
+                .map(Some)
+                .map_err(ser)
+        }
+        _ => Err(StorageError::Serialization(
+            "audio uri and hash must both be set".into(),
+        )),
+    }
+}
+
+/// Read the nullable `extra` column; the extra info is stored as text only.
+pub(crate) fn extra_from_column(extra: Option<String>) -> Result<Option<Content>, StorageError> {
+    extra
+        .map(|text| Content::from_persisted(text, None))
+        .transpose()
+        .map_err(ser)
+}
+
 pub(crate) fn map_card_row(row: &sqlx::sqlite::SqliteRow) -> Result<Card, StorageError> {
     let created_at: chrono::DateTime<chrono::Utc> = row.try_get("created_at").map_err(ser)?;
+    let updated_at: Option<chrono::DateTime<chrono::Utc>> =
+        row.try_get("updated_at").map_err(ser)?;
 
     let prompt = Content::from_persisted(
         row.try_get::<String, _>("prompt").map_err(ser)?,
@@ -59,7 +291,11 @@ pub(crate) fn map_card_row(row: &sqlx::sqlite::SqliteRow) -> Result<Card, Storag
             .map(media_id_from_i64)
             .transpose()?,
     )
-    .map_err(ser)?;
+    .map_err(ser)?
+    .with_audio(audio_from_columns(
+        row.try_get("prompt_audio_uri").map_err(ser)?,
+        row.try_get("prompt_audio_hash").map_err(ser)?,
+    )?);
 
     let answer = Content::from_persisted(
         row.try_get::<String, _>("answer").map_err(ser)?,
@@ -68,7 +304,11 @@ pub(crate) fn map_card_row(row: &sqlx::sqlite::SqliteRow) -> Result<Card, Storag
             .map(media_id_from_i64)
             .transpose()?,
     )
-    .map_err(ser)?;
+    .map_err(ser)?
+    .with_audio(audio_from_columns(
+        row.try_get("answer_audio_uri").map_err(ser)?,
+        row.try_get("answer_audio_hash").map_err(ser)?,
+    )?);
 
     let phase_str: String = row.try_get("phase").map_err(ser)?;
     let phase = parse_card_phase(phase_str.as_str())?;
@@ -94,9 +334,40 @@ pub(crate) fn map_card_row(row: &sqlx::sqlite::SqliteRow) -> Result<Card, Storag
             .ok_or_else(|| StorageError::Serialization("missing difficulty".into()))?
     };
 
+    let kind = card_kind_from_columns(
+        row.try_get("cloze_text").map_err(ser)?,
+        row.try_get("cloze_indices").map_err(ser)?,
+    )?;
+
+    let note_id = row
+        .try_get::<Option<i64>, _>("note_id")
+        .map_err(ser)?
+        .map(note_id_from_i64)
+        .transpose()?;
+    let suspended = row.try_get::<i64, _>("suspended").map_err(ser)? != 0;
+    let buried_until = row.try_get("buried_until").map_err(ser)?;
+    let learning_step = u32::try_from(row.try_get::<i64, _>("learning_step").map_err(ser)?)
+        .map_err(|_| StorageError::Serialization("learning_step sign overflow".into()))?;
+    let lapses = u32::try_from(row.try_get::<i64, _>("lapses").map_err(ser)?)
+        .map_err(|_| StorageError::Serialization("lapses sign overflow".into()))?;
+    let extra = extra_from_column(row.try_get("extra").map_err(ser)?)?;
+    let flag = row
+        .try_get::<Option<String>, _>("flag")
+        .map_err(ser)?
+        .as_deref()
+        .map(parse_flag)
+        .transpose()?;
+    let source = row
+        .try_get::<Option<String>, _>("source")
+        .map_err(ser)?
+        .as_deref()
+        .map(parse_card_source)
+        .transpose()?;
+
     Card::from_persisted(
         card_id_from_i64(row.try_get::<i64, _>("id").map_err(ser)?)?,
         deck_id_from_i64(row.try_get::<i64, _>("deck_id").map_err(ser)?)?,
+        kind,
         prompt,
         answer,
         created_at,
@@ -107,6 +378,17 @@ pub(crate) fn map_card_row(row: &sqlx::sqlite::SqliteRow) -> Result<Card, Storag
         stability,
         difficulty,
     )
+    .map(|card| {
+        card.with_note_id(note_id)
+            .with_suspended(suspended)
+            .with_buried_until(buried_until)
+            .with_learning_step(learning_step)
+            .with_lapses(lapses)
+            .with_extra(extra)
+            .with_flag(flag)
+            .with_source(source)
+            .with_updated_at(updated_at.unwrap_or(created_at))
+    })
     .map_err(ser)
 }
 
@@ -147,6 +429,16 @@ pub(crate) fn grade_from_i64(value: i64) -> Result<ReviewGrade, StorageError> {
     }
 }
 
+/// Answer times are stored as non-negative milliseconds; NULL means the answer was not timed.
+pub(crate) fn answer_ms_from_column(value: Option<i64>) -> Result<Option<u32>, StorageError> {
+    value
+        .map(|ms| {
+            u32::try_from(ms)
+                .map_err(|_| StorageError::Serialization("answer_ms out of range".into()))
+        })
+        .transpose()
+}
+
 pub(crate) fn map_review_log_row(
     row: &sqlx::sqlite::SqliteRow,
 ) -> Result<crate::repository::ReviewLogRecord, StorageError> {
@@ -164,5 +456,7 @@ pub(crate) fn map_review_log_row(
         stability: row.try_get("stability").map_err(ser)?,
         difficulty: row.try_get("difficulty").map_err(ser)?,
         next_review_at: row.try_get("next_review_at").map_err(ser)?,
+        answer_ms: answer_ms_from_column(row.try_get("answer_ms").map_err(ser)?)?,
+        first_review: row.try_get::<i64, _>("first_review").map_err(ser)? != 0,
     })
 }
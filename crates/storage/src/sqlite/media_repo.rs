@@ -0,0 +1,140 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use learn_core::model::{ImageFormat, ImageMeta, MediaHash, MediaId};
+use sqlx::Row;
+
+use super::{
+    SqliteRepository,
+    mapping::{media_id_from_i64, media_id_to_i64},
+};
+use crate::repository::{MediaRecord, MediaRepository, NewMediaRecord, StorageError};
+
+fn ser<E: core::fmt::Display>(e: E) -> StorageError {
+    StorageError::Serialization(e.to_string())
+}
+
+fn map_media_row(row: &sqlx::sqlite::SqliteRow) -> Result<MediaRecord, StorageError> {
+    let mime_type: String = row.try_get("mime_type").map_err(ser)?;
+    let format = ImageFormat::from_mime_type(&mime_type)
+        .ok_or_else(|| StorageError::Serialization(format!("invalid mime_type: {mime_type}")))?;
+    let width = u32::try_from(row.try_get::<i64, _>("width").map_err(ser)?).map_err(ser)?;
+    let height = u32::try_from(row.try_get::<i64, _>("height").map_err(ser)?).map_err(ser)?;
+    Ok(MediaRecord {
+        id: media_id_from_i64(row.try_get::<i64, _>("id").map_err(ser)?)?,
+        format,
+        meta: ImageMeta::new(width, height).map_err(ser)?,
+        checksum: MediaHash::new(row.try_get::<String, _>("checksum").map_err(ser)?),
+        bytes: row.try_get("bytes").map_err(ser)?,
+        created_at: row.try_get("created_at").map_err(ser)?,
+    })
+}
+
+#[async_trait]
+impl MediaRepository for SqliteRepository {
+    async fn insert_media(&self, media: NewMediaRecord) -> Result<MediaId, StorageError> {
+        sqlx::query(
+            r"
+            INSERT INTO media (checksum, mime_type, width, height, bytes, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(checksum) DO UPDATE SET
+                mime_type = COALESCE(media.mime_type, excluded.mime_type),
+                width = COALESCE(media.width, excluded.width),
+                height = COALESCE(media.height, excluded.height)
+            ",
+        )
+        .bind(media.checksum.as_str())
+        .bind(media.format.mime_type())
+        .bind(i64::from(media.meta.width))
+        .bind(i64::from(media.meta.height))
+        .bind(media.bytes)
+        .bind(media.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        let row = sqlx::query("SELECT id FROM media WHERE checksum = ?1")
+            .bind(media.checksum.as_str())
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        media_id_from_i64(row.try_get::<i64, _>("id").map_err(ser)?)
+    }
+
+    async fn get_media(&self, id: MediaId) -> Result<Option<MediaRecord>, StorageError> {
+        let row = sqlx::query(
+            r"
+            SELECT id, checksum, mime_type, width, height, bytes, created_at
+            FROM media
+            WHERE id = ?1 AND mime_type IS NOT NULL
+            ",
+        )
+        .bind(media_id_to_i64(Some(id))?)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        row.as_ref().map(map_media_row).transpose()
+    }
+
+    async fn put_blob(
+        &self,
+        hash: &MediaHash,
+        bytes: Vec<u8>,
+        created_at: DateTime<Utc>,
+    ) -> Result<(), StorageError> {
+        sqlx::query(
+            r"
+            INSERT INTO media (checksum, bytes, created_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(checksum) DO NOTHING
+            ",
+        )
+        .bind(hash.as_str())
+        .bind(bytes)
+        .bind(created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn get_blob(&self, hash: &MediaHash) -> Result<Option<Vec<u8>>, StorageError> {
+        let row = sqlx::query("SELECT bytes FROM media WHERE checksum = ?1")
+            .bind(hash.as_str())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        row.map(|row| row.try_get("bytes").map_err(ser)).transpose()
+    }
+
+    async fn exists(&self, hash: &MediaHash) -> Result<bool, StorageError> {
+        let row = sqlx::query("SELECT 1 FROM media WHERE checksum = ?1")
+            .bind(hash.as_str())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(row.is_some())
+    }
+
+    async fn gc_unreferenced(&self) -> Result<u64, StorageError> {
+        let result = sqlx::query(
+            r"
+            DELETE FROM media
+            WHERE id NOT IN (
+                SELECT prompt_media_id FROM cards WHERE prompt_media_id IS NOT NULL
+                UNION
+                SELECT answer_media_id FROM cards WHERE answer_media_id IS NOT NULL
+            )
+            AND checksum NOT IN (
+                SELECT prompt_audio_hash FROM cards WHERE prompt_audio_hash IS NOT NULL
+                UNION
+                SELECT answer_audio_hash FROM cards WHERE answer_audio_hash IS NOT NULL
+            )
+            ",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+}
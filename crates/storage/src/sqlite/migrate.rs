@@ -3,10 +3,11 @@ use sqlx::SqlitePool;
 
 use super::SqliteInitError;
 
-/// Runs a single, consolidated migration for the current schema.
+/// Apply the numbered schema migrations in order, skipping versions already recorded.
 ///
-/// Creates the full schema (decks, cards, tags, review logs, session summaries, AI usage, and
-/// indexes). This is intended for development where the database can be reset.
+/// Version 1 creates the full schema (decks, cards, tags, review logs, session summaries, AI
+/// usage, and indexes); later versions alter it. Each version runs in its own transaction and
+/// records itself in `schema_migrations`, so existing databases move forward without a reset.
 #[allow(clippy::too_many_lines)]
 pub async fn run_migrations(pool: &SqlitePool) -> Result<(), SqliteInitError> {
     async fn is_applied(pool: &SqlitePool, version: i64) -> Result<bool, sqlx::Error> {
@@ -341,5 +342,1570 @@ pub async fn run_migrations(pool: &SqlitePool) -> Result<(), SqliteInitError> {
         tx.commit().await?;
     }
 
+    // Version 3: FTS5 index over card text, kept in sync by triggers. Left unapplied when the
+    // linked SQLite lacks FTS5 so a later build can pick it up; search falls back to LIKE.
+    if !is_applied(pool, 3).await? {
+        let mut tx = pool.begin().await?;
+
+        let created = sqlx::query(
+            r"
+                CREATE VIRTUAL TABLE IF NOT EXISTS cards_fts USING fts5(
+                    prompt,
+                    answer,
+                    content = 'cards',
+                    content_rowid = 'id',
+                    tokenize = 'unicode61'
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await;
+
+        match created {
+            Ok(_) => {
+                sqlx::query(
+                    r"
+                        CREATE TRIGGER IF NOT EXISTS cards_fts_insert AFTER INSERT ON cards BEGIN
+                            INSERT INTO cards_fts (rowid, prompt, answer)
+                            VALUES (new.id, new.prompt, new.answer);
+                        END;
+                    ",
+                )
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query(
+                    r"
+                        CREATE TRIGGER IF NOT EXISTS cards_fts_delete AFTER DELETE ON cards BEGIN
+                            INSERT INTO cards_fts (cards_fts, rowid, prompt, answer)
+                            VALUES ('delete', old.id, old.prompt, old.answer);
+                        END;
+                    ",
+                )
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query(
+                    r"
+                        CREATE TRIGGER IF NOT EXISTS cards_fts_update
+                        AFTER UPDATE OF prompt, answer ON cards BEGIN
+                            INSERT INTO cards_fts (cards_fts, rowid, prompt, answer)
+                            VALUES ('delete', old.id, old.prompt, old.answer);
+                            INSERT INTO cards_fts (rowid, prompt, answer)
+                            VALUES (new.id, new.prompt, new.answer);
+                        END;
+                    ",
+                )
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query("INSERT INTO cards_fts (cards_fts) VALUES ('rebuild');")
+                    .execute(&mut *tx)
+                    .await?;
+
+                sqlx::query(
+                    r"
+                        INSERT INTO schema_migrations (version, applied_at)
+                        VALUES (?1, ?2)
+                        ON CONFLICT(version) DO NOTHING
+                    ",
+                )
+                .bind(3_i64)
+                .bind(Utc::now())
+                .execute(&mut *tx)
+                .await?;
+
+                tx.commit().await?;
+            }
+            Err(err) if is_missing_fts5(&err) => tx.rollback().await?,
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    // Version 4: cloze cards keep their source text and hidden indices.
+    if !is_applied(pool, 4).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE cards ADD COLUMN cloze_text TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE cards ADD COLUMN cloze_indices TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(4_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 5: note linkage for sibling cards (e.g. reverse direction).
+    if !is_applied(pool, 5).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE cards ADD COLUMN note_id INTEGER;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                CREATE INDEX IF NOT EXISTS idx_cards_deck_note
+                    ON cards(deck_id, note_id);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(5_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 6: cram sessions record summaries without touching scheduling.
+    if !is_applied(pool, 6).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE session_summaries
+                ADD COLUMN is_cram INTEGER NOT NULL DEFAULT 0 CHECK (is_cram IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(6_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 7: per-deck ordering of new cards among due reviews.
+    if !is_applied(pool, 7).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN new_review_mix TEXT NOT NULL DEFAULT 'after_reviews'
+                    CHECK (new_review_mix IN ('front_load', 'interleaved', 'after_reviews'));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(7_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 8: optional audio clip per card side.
+    if !is_applied(pool, 8).await? {
+        let mut tx = pool.begin().await?;
+
+        for column in [
+            "prompt_audio_uri",
+            "prompt_audio_hash",
+            "answer_audio_uri",
+            "answer_audio_hash",
+        ] {
+            sqlx::query(&format!("ALTER TABLE cards ADD COLUMN {column} TEXT"))
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(8_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 9: stored card images, deduplicated by checksum.
+    if !is_applied(pool, 9).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS media (
+                    id INTEGER PRIMARY KEY,
+                    checksum TEXT NOT NULL UNIQUE,
+                    mime_type TEXT NOT NULL,
+                    width INTEGER NOT NULL CHECK (width > 0),
+                    height INTEGER NOT NULL CHECK (height > 0),
+                    bytes BLOB NOT NULL,
+                    created_at TEXT NOT NULL
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(9_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 10: raw blobs share the media table, so image metadata becomes optional.
+    if !is_applied(pool, 10).await? {
+        let mut tx = pool.begin().await?;
+
+        for stmt in [
+            r"
+                CREATE TABLE media_v10 (
+                    id INTEGER PRIMARY KEY,
+                    checksum TEXT NOT NULL UNIQUE,
+                    mime_type TEXT,
+                    width INTEGER CHECK (width > 0),
+                    height INTEGER CHECK (height > 0),
+                    bytes BLOB NOT NULL,
+                    created_at TEXT NOT NULL
+                );
+            ",
+            r"
+                INSERT INTO media_v10 (id, checksum, mime_type, width, height, bytes, created_at)
+                SELECT id, checksum, mime_type, width, height, bytes, created_at FROM media;
+            ",
+            "DROP TABLE media;",
+            "ALTER TABLE media_v10 RENAME TO media;",
+        ] {
+            sqlx::query(stmt).execute(&mut *tx).await?;
+        }
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(10_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 11: archived decks stay in storage but drop out of lists and planning.
+    if !is_applied(pool, 11).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN archived INTEGER NOT NULL DEFAULT 0 CHECK (archived IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(11_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 12: appearance and audio preferences join the app settings row.
+    if !is_applied(pool, 12).await? {
+        let mut tx = pool.begin().await?;
+
+        // Appearance and audio preferences; NULL means "use the default".
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN theme TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN accent_color TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN language TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN analytics_enabled INTEGER;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN autoplay_audio INTEGER;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN replay_audio_after_answer INTEGER;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN audio_delay_ms INTEGER;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(12_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 13: suspended cards stay in their deck but drop out of planning and due counts.
+    if !is_applied(pool, 13).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE cards
+                ADD COLUMN suspended INTEGER NOT NULL DEFAULT 0 CHECK (suspended IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(13_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 14: buried cards sit out of planning until the stored time passes.
+    if !is_applied(pool, 14).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE cards ADD COLUMN buried_until TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(14_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 15: a deck revision counter so concurrent settings saves can detect conflicts.
+    if !is_applied(pool, 15).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE decks ADD COLUMN version INTEGER NOT NULL DEFAULT 0;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(15_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 16: target retention is now limited to 0.70-0.99; clamp older decks into range.
+    if !is_applied(pool, 16).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                UPDATE decks
+                SET fsrs_target_retention = MIN(MAX(fsrs_target_retention, 0.7), 0.99);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(16_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 17: opt-in load balancing of review due dates.
+    if !is_applied(pool, 17).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN load_balance INTEGER NOT NULL DEFAULT 0
+                    CHECK (load_balance IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(17_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 18: learning steps per deck and each card's position within them. Existing
+    // decks get no steps so their cards keep graduating straight to review; new decks get
+    // the `DeckSettings` default.
+    if !is_applied(pool, 18).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN learning_steps TEXT NOT NULL DEFAULT '';
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE cards
+                ADD COLUMN learning_step INTEGER NOT NULL DEFAULT 0
+                    CHECK (learning_step >= 0);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(18_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 19: FSRS weights learned from each deck's review history.
+    if !is_applied(pool, 19).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN fsrs_parameters TEXT NOT NULL DEFAULT '';
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(19_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 20: the deck the app reopens on launch; NULL means "pick the first deck".
+    if !is_applied(pool, 20).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN last_deck_id INTEGER;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(20_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 21: optional extra info shown beneath the answer; NULL means none.
+    if !is_applied(pool, 21).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE cards ADD COLUMN extra TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(21_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 22: unfinished sessions, saved after every answer so they survive a restart.
+    if !is_applied(pool, 22).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS session_progress (
+                    deck_id INTEGER PRIMARY KEY,
+                    started_at TEXT NOT NULL,
+                    is_cram INTEGER NOT NULL DEFAULT 0 CHECK (is_cram IN (0, 1)),
+                    FOREIGN KEY (deck_id) REFERENCES decks(id) ON DELETE CASCADE
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS session_progress_answers (
+                    deck_id INTEGER NOT NULL,
+                    position INTEGER NOT NULL,
+                    card_id INTEGER NOT NULL,
+                    grade INTEGER NOT NULL CHECK (grade BETWEEN 0 AND 3),
+                    reviewed_at TEXT NOT NULL,
+                    elapsed_days REAL NOT NULL,
+                    scheduled_days REAL NOT NULL,
+                    stability REAL NOT NULL,
+                    difficulty REAL NOT NULL,
+                    next_review_at TEXT NOT NULL,
+                    PRIMARY KEY (deck_id, position),
+                    FOREIGN KEY (deck_id) REFERENCES session_progress(deck_id) ON DELETE CASCADE
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE TABLE IF NOT EXISTS session_progress_pending (
+                    deck_id INTEGER NOT NULL,
+                    position INTEGER NOT NULL,
+                    card_id INTEGER NOT NULL,
+                    next_review_at TEXT NOT NULL,
+                    PRIMARY KEY (deck_id, position),
+                    FOREIGN KEY (deck_id) REFERENCES session_progress(deck_id) ON DELETE CASCADE
+                );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(22_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 23: how many grade buttons the deck's sessions offer.
+    if !is_applied(pool, 23).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN answer_buttons TEXT NOT NULL DEFAULT 'four'
+                    CHECK (answer_buttons IN ('two', 'four'));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(23_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 24: per-card lapse counts and the deck's leech handling.
+    if !is_applied(pool, 24).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE cards
+                ADD COLUMN lapses INTEGER NOT NULL DEFAULT 0
+                    CHECK (lapses >= 0);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN leech_threshold INTEGER NOT NULL DEFAULT 8
+                    CHECK (leech_threshold >= 0);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN leech_action TEXT NOT NULL DEFAULT 'tag'
+                    CHECK (leech_action IN ('tag', 'suspend'));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(24_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 25: how long each answer took, in milliseconds; NULL means it was not timed.
+    if !is_applied(pool, 25).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE review_logs
+                ADD COLUMN answer_ms INTEGER
+                    CHECK (answer_ms IS NULL OR answer_ms >= 0);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE session_progress_answers
+                ADD COLUMN answer_ms INTEGER
+                    CHECK (answer_ms IS NULL OR answer_ms >= 0);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(25_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 26: favorite decks are pinned to the top of deck lists.
+    if !is_applied(pool, 26).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN favorite INTEGER NOT NULL DEFAULT 0 CHECK (favorite IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(26_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 27: optional preset color and icon that tell decks apart in lists.
+    if !is_applied(pool, 27).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN color TEXT CHECK (color IN (
+                    'red', 'orange', 'yellow', 'green', 'teal', 'blue', 'purple', 'pink', 'gray'
+                ));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN icon TEXT CHECK (icon IN (
+                    'book', 'language', 'science', 'math', 'music', 'code', 'globe', 'star', 'heart'
+                ));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(27_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 28: study-ahead sessions are told apart from regular ones.
+    if !is_applied(pool, 28).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE session_summaries
+                ADD COLUMN is_ahead INTEGER NOT NULL DEFAULT 0 CHECK (is_ahead IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE session_progress
+                ADD COLUMN is_ahead INTEGER NOT NULL DEFAULT 0 CHECK (is_ahead IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(28_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 29: active decks need distinct names, ignoring case and outer spaces.
+    if !is_applied(pool, 29).await? {
+        let mut tx = pool.begin().await?;
+
+        // Older duplicates get their ID appended so the index can be built.
+        sqlx::query(
+            r"
+                UPDATE decks
+                SET name = name || ' (' || id || ')'
+                WHERE archived = 0
+                  AND EXISTS (
+                      SELECT 1 FROM decks AS earlier
+                      WHERE earlier.archived = 0
+                        AND earlier.id < decks.id
+                        AND lower(trim(earlier.name)) = lower(trim(decks.name))
+                  );
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                CREATE UNIQUE INDEX IF NOT EXISTS idx_decks_active_name
+                ON decks ((lower(trim(name))))
+                WHERE archived = 0;
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(29_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 30: deleted cards go to a trash and keep their history until purged.
+    if !is_applied(pool, 30).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE cards ADD COLUMN deleted_at TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(30_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 31: decks can hold new cards back until the day's reviews are done.
+    if !is_applied(pool, 31).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN hold_new_until_reviews_done INTEGER NOT NULL DEFAULT 0
+                    CHECK (hold_new_until_reviews_done IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(31_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 32: the hour a study day starts; NULL means midnight.
+    if !is_applied(pool, 32).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN day_cutoff_hour INTEGER;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(32_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 33: an optional colored flag per card.
+    if !is_applied(pool, 33).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE cards
+                ADD COLUMN flag TEXT
+                    CHECK (flag IN ('red', 'orange', 'green', 'blue'));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(33_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 34: optional per-deck front and back card templates.
+    if !is_applied(pool, 34).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE decks ADD COLUMN front_template TEXT;")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("ALTER TABLE decks ADD COLUMN back_template TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(34_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 35: decks can turn off fuzzing of young cards' intervals.
+    if !is_applied(pool, 35).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN interval_fuzz INTEGER NOT NULL DEFAULT 1
+                    CHECK (interval_fuzz IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(35_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 36: whether review sessions hide everything but the card; NULL means off.
+    if !is_applied(pool, 36).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN focus_mode INTEGER;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(36_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 37: cover the `id` tiebreak that history lists sort by after `completed_at`.
+    if !is_applied(pool, 37).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DROP INDEX IF EXISTS idx_session_summaries_deck_completed;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                CREATE INDEX IF NOT EXISTS idx_session_summaries_deck_completed_id
+                    ON session_summaries (deck_id, completed_at DESC, id DESC);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(37_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 38: whether queueing a card buries its note siblings for the day.
+    if !is_applied(pool, 38).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN bury_siblings INTEGER NOT NULL DEFAULT 1
+                    CHECK (bury_siblings IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(38_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 39: delays a lapsed card waits on before it returns to review. Existing decks
+    // get no steps so their lapses keep going straight back to review; new decks get the
+    // `DeckSettings` default.
+    if !is_applied(pool, 39).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN relearning_steps TEXT NOT NULL DEFAULT '';
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(39_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 40: Agains in a row that take a card out of the session it fails in.
+    if !is_applied(pool, 40).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN same_session_fail_limit INTEGER NOT NULL DEFAULT 4
+                    CHECK (same_session_fail_limit >= 0);
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(40_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 41: a per-1k-token price for a preferred model missing from the price book.
+    if !is_applied(pool, 41).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN ai_manual_price_micro_usd_per_1k INTEGER;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(41_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 42: whether long answers reveal one paragraph per keypress before grading.
+    if !is_applied(pool, 42).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN progressive_reveal INTEGER NOT NULL DEFAULT 0
+                    CHECK (progressive_reveal IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(42_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 43: whether sessions ask for the answer to be typed and checked before grading.
+    if !is_applied(pool, 43).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN typed_answer INTEGER NOT NULL DEFAULT 0
+                    CHECK (typed_answer IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(43_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 44: user key bindings for the editor and session shortcuts.
+    if !is_applied(pool, 44).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("ALTER TABLE app_settings ADD COLUMN key_bindings TEXT;")
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(44_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 45: last-write timestamps on decks and cards; existing rows are stamped now.
+    if !is_applied(pool, 45).await? {
+        let mut tx = pool.begin().await?;
+        let now = Utc::now();
+
+        for table in ["decks", "cards"] {
+            sqlx::query(&format!("ALTER TABLE {table} ADD COLUMN updated_at TEXT;"))
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(&format!(
+                "UPDATE {table} SET updated_at = ?1 WHERE updated_at IS NULL;"
+            ))
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(45_i64)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 46: whether new cards come out oldest first or in a per-day shuffle.
+    if !is_applied(pool, 46).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN new_card_order TEXT NOT NULL DEFAULT 'sequential'
+                    CHECK (new_card_order IN ('sequential', 'random'));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(46_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 47: how each card was created; left NULL for cards that predate it.
+    if !is_applied(pool, 47).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE cards
+                ADD COLUMN source TEXT
+                    CHECK (source IN ('manual', 'csv', 'anki', 'ai'));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(47_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 48: starter text the editor pre-fills new cards with; empty leaves them blank.
+    if !is_applied(pool, 48).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN new_card_prompt TEXT NOT NULL DEFAULT '';
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN new_card_answer TEXT NOT NULL DEFAULT '';
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(48_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 49: per-session cap on new cards, under the daily limit; zero turns it off.
+    if !is_applied(pool, 49).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE decks
+                ADD COLUMN new_per_session INTEGER NOT NULL DEFAULT 0;
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(49_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
+    // Version 50: whether a review was the card's first, i.e. introduced it as a new card.
+    // Existing logs stay unmarked, so they never count against today's new-card limit.
+    if !is_applied(pool, 50).await? {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE review_logs
+                ADD COLUMN first_review INTEGER NOT NULL DEFAULT 0
+                    CHECK (first_review IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                ALTER TABLE session_progress_answers
+                ADD COLUMN first_review INTEGER NOT NULL DEFAULT 0
+                    CHECK (first_review IN (0, 1));
+            ",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r"
+                INSERT INTO schema_migrations (version, applied_at)
+                VALUES (?1, ?2)
+                ON CONFLICT(version) DO NOTHING
+            ",
+        )
+        .bind(50_i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+    }
+
     Ok(())
 }
+
+fn is_missing_fts5(err: &sqlx::Error) -> bool {
+    matches!(err, sqlx::Error::Database(db) if db.message().contains("no such module: fts5"))
+}
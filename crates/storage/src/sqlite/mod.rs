@@ -1,22 +1,31 @@
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
-use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection, SqlitePoolOptions};
+use sqlx::{Connection, SqlitePool};
 use thiserror::Error;
 
 use crate::repository::{
     AiPriceBookRepository, AiUsageRepository, AppSettingsRepository, CardRepository, DeckRepository,
-    ReviewLogRepository, ReviewPersistence, SessionSummaryRepository, Storage,
+    MediaRepository, ReviewLogRepository, ReviewPersistence, SessionProgressRepository,
+    SessionSummaryRepository, Storage, StorageError,
 };
 
 mod ai_price_book_repo;
 mod ai_usage_repo;
+pub mod anki;
 mod app_settings_repo;
+pub mod backup;
 mod card_repo;
 mod deck_repo;
-mod mapping;
+pub mod doctor;
+pub(crate) mod mapping;
+mod media_repo;
 mod migrate;
+mod retry;
 mod review_log_repo;
+mod session_progress_repo;
 mod session_summary_repo;
 
 #[derive(Clone)]
@@ -27,10 +36,55 @@ pub struct SqliteRepository {
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum SqliteInitError {
+    #[error(
+        "the database file is locked by another process; \
+         close other running instances of the app and try again"
+    )]
+    Locked(#[source] sqlx::Error),
+    #[error(
+        "the database file is corrupt or not an SQLite database ({integrity}); \
+         restore it from a backup, or move it aside to start with a fresh one"
+    )]
+    Corrupt {
+        /// Outcome of `PRAGMA integrity_check`, when it could be run.
+        integrity: String,
+        #[source]
+        source: sqlx::Error,
+    },
     #[error(transparent)]
-    Sqlx(#[from] sqlx::Error),
+    Sqlx(sqlx::Error),
 }
 
+impl From<sqlx::Error> for SqliteInitError {
+    fn from(err: sqlx::Error) -> Self {
+        match primary_code(&err) {
+            Some(SQLITE_BUSY | SQLITE_LOCKED) => Self::Locked(err),
+            Some(SQLITE_CORRUPT | SQLITE_NOTADB) => Self::Corrupt {
+                integrity: "integrity check not run".to_owned(),
+                source: err,
+            },
+            _ => Self::Sqlx(err),
+        }
+    }
+}
+
+/// `SQLite` result code of a failed statement, without the extended detail.
+fn primary_code(err: &sqlx::Error) -> Option<i32> {
+    // Primary result codes live in the low byte of the extended code sqlx reports.
+    match err {
+        sqlx::Error::Database(db) => db
+            .code()
+            .and_then(|code| code.parse::<i32>().ok())
+            .map(|code| code & 0xff),
+        _ => None,
+    }
+}
+
+const SQLITE_BUSY: i32 = 5;
+const SQLITE_LOCKED: i32 = 6;
+const SQLITE_CORRUPT: i32 = 11;
+const SQLITE_NOTADB: i32 = 26;
+
 impl SqliteRepository {
     /// Connect to `SQLite` using the given URL.
     ///
@@ -39,9 +93,28 @@ impl SqliteRepository {
     /// Returns `SqliteInitError` if the connection cannot be established or if
     /// enforcing foreign key constraints fails during setup.
     pub async fn connect(database_url: &str) -> Result<Self, SqliteInitError> {
-        let pool = SqlitePoolOptions::new()
+        // The pool retries failed connects until its acquire timeout, which hides the cause, so
+        // open one connection up front to surface locked or corrupt files directly.
+        let options =
+            SqliteConnectOptions::from_str(database_url)?.busy_timeout(Duration::from_secs(5));
+        let mut probe = SqliteConnection::connect_with(&options).await?;
+        sqlx::query("PRAGMA journal_mode = WAL;")
+            .execute(&mut probe)
+            .await?;
+        probe.close().await?;
+
+        let mut pool_options = SqlitePoolOptions::new()
             .max_connections(5)
-            .acquire_timeout(Duration::from_secs(5))
+            .acquire_timeout(Duration::from_secs(5));
+        if is_in_memory_url(database_url) {
+            // sqlx shares one in-memory database across the pool, but it vanishes once the last
+            // connection closes, so never let the pool retire its connections.
+            pool_options = pool_options
+                .min_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None);
+        }
+        let pool = pool_options
             .after_connect(|conn, _meta| {
                 Box::pin(async move {
                     sqlx::query("PRAGMA foreign_keys = ON;")
@@ -56,7 +129,27 @@ impl SqliteRepository {
                     Ok(())
                 })
             })
-            .connect(database_url)
+            .connect_with(options)
+            .await?;
+        Ok(Self { pool })
+    }
+
+    /// Open an existing `SQLite` database without ever writing to it.
+    ///
+    /// Unlike `connect`, this leaves the journal mode alone and refuses writes, so it suits
+    /// inspecting a file the app is having trouble with.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SqliteInitError` if the file is missing, locked, or cannot be opened.
+    pub async fn connect_read_only(database_url: &str) -> Result<Self, SqliteInitError> {
+        let options = SqliteConnectOptions::from_str(database_url)?
+            .read_only(true)
+            .busy_timeout(Duration::from_secs(5));
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .acquire_timeout(Duration::from_secs(5))
+            .connect_with(options)
             .await?;
         Ok(Self { pool })
     }
@@ -66,7 +159,9 @@ impl SqliteRepository {
         &self.pool
     }
 
-    /// Create tables if they do not exist.
+    /// Apply every schema migration this database has not recorded yet.
+    ///
+    /// Safe to run on every start: applied versions are skipped.
     ///
     /// # Errors
     ///
@@ -74,9 +169,63 @@ impl SqliteRepository {
     pub async fn migrate(&self) -> Result<(), SqliteInitError> {
         migrate::run_migrations(&self.pool).await
     }
+
+    /// Run `PRAGMA integrity_check` on a single fresh connection and return its report lines.
+    ///
+    /// A healthy file reports a single `ok` line.
+    ///
+    /// # Errors
+    ///
+    /// Returns `sqlx::Error` if the file cannot be opened or the check itself fails.
+    pub async fn integrity_check(database_url: &str) -> Result<Vec<String>, sqlx::Error> {
+        let options = SqliteConnectOptions::from_str(database_url)?.read_only(true);
+        let mut conn = SqliteConnection::connect_with(&options).await?;
+        let lines = sqlx::query_scalar("PRAGMA integrity_check")
+            .fetch_all(&mut conn)
+            .await;
+        let _ = conn.close().await;
+        lines
+    }
+
+    /// Highest migration version recorded in `schema_migrations`, or 0 before any ran.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Connection` if the table cannot be read, e.g. before `migrate`.
+    pub async fn current_schema_version(&self) -> Result<i64, StorageError> {
+        sqlx::query_scalar("SELECT COALESCE(MAX(version), 0) FROM schema_migrations")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))
+    }
+}
+
+/// Whether the URL names an `SQLite` database that lives only in memory.
+#[must_use]
+pub fn is_in_memory_url(database_url: &str) -> bool {
+    let Some(rest) = database_url
+        .strip_prefix("sqlite://")
+        .or_else(|| database_url.strip_prefix("sqlite:"))
+    else {
+        return false;
+    };
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    path == ":memory:" || query.split('&').any(|pair| pair == "mode=memory")
 }
 
 impl Storage {
+    /// Build a throwaway `SQLite`-backed `Storage` in memory, migrated and ready to use.
+    ///
+    /// Every call gets its own empty database, shared by all repositories of the returned
+    /// `Storage` and dropped along with it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SqliteInitError` if the connection or migrations fail.
+    pub async fn memory() -> Result<Self, SqliteInitError> {
+        Self::sqlite("sqlite::memory:").await
+    }
+
     /// Build a `Storage` backed by `SQLite`.
     ///
     /// # Errors
@@ -84,30 +233,51 @@ impl Storage {
     /// Returns `SqliteInitError` if connection or migrations cannot be
     /// completed.
     pub async fn sqlite(database_url: &str) -> Result<Self, SqliteInitError> {
-        let repo = SqliteRepository::connect(database_url).await?;
-        repo.migrate().await?;
+        let repo = match SqliteRepository::connect(database_url).await {
+            Ok(repo) => match repo.migrate().await {
+                Ok(()) => repo,
+                Err(err) => return Err(with_integrity_report(err, database_url).await),
+            },
+            Err(err) => return Err(with_integrity_report(err, database_url).await),
+        };
 
         let deck_repo: Arc<dyn DeckRepository> = Arc::new(repo.clone());
         let card_repo: Arc<dyn CardRepository> = Arc::new(repo.clone());
         let log_repo: Arc<dyn ReviewLogRepository> = Arc::new(repo.clone());
         let review_repo: Arc<dyn ReviewPersistence> = Arc::new(repo.clone());
         let summary_repo: Arc<dyn SessionSummaryRepository> = Arc::new(repo.clone());
+        let progress_repo: Arc<dyn SessionProgressRepository> = Arc::new(repo.clone());
         let app_settings_repo: Arc<dyn AppSettingsRepository> = Arc::new(repo.clone());
         let ai_price_book_repo: Arc<dyn AiPriceBookRepository> = Arc::new(repo.clone());
-        let ai_usage_repo: Arc<dyn AiUsageRepository> = Arc::new(repo);
+        let ai_usage_repo: Arc<dyn AiUsageRepository> = Arc::new(repo.clone());
+        let media_repo: Arc<dyn MediaRepository> = Arc::new(repo);
         Ok(Self {
             decks: deck_repo,
             cards: card_repo,
             review_logs: log_repo,
             reviews: review_repo,
             session_summaries: summary_repo,
+            session_progress: progress_repo,
             app_settings: app_settings_repo,
             ai_price_book: ai_price_book_repo,
             ai_usage: ai_usage_repo,
+            media: media_repo,
         })
     }
 }
 
+/// Fill in the integrity check result for corruption errors so the caller can report it.
+async fn with_integrity_report(err: SqliteInitError, database_url: &str) -> SqliteInitError {
+    let SqliteInitError::Corrupt { source, .. } = err else {
+        return err;
+    };
+    let integrity = match SqliteRepository::integrity_check(database_url).await {
+        Ok(lines) => format!("integrity check: {}", lines.join("; ")),
+        Err(check_err) => format!("integrity check could not run: {check_err}"),
+    };
+    SqliteInitError::Corrupt { integrity, source }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,4 +287,12 @@ mod tests {
         fn assert_send_sync<T: Send + Sync>() {}
         assert_send_sync::<SqliteRepository>();
     }
+
+    #[test]
+    fn in_memory_urls_are_recognised() {
+        assert!(is_in_memory_url("sqlite::memory:"));
+        assert!(is_in_memory_url("sqlite:file:memdb_x?mode=memory&cache=shared"));
+        assert!(!is_in_memory_url("sqlite://dev.sqlite3"));
+        assert!(!is_in_memory_url("sqlite:notes?mode=rwc"));
+    }
 }
@@ -0,0 +1,81 @@
+//! Retrying write transactions that lose a race for the database.
+//!
+//! WAL lets readers run beside a writer, but writers still take turns. A write that waits
+//! past `busy_timeout` (or hits a shared-cache table lock, which never waits) fails with
+//! `SQLITE_BUSY`/`SQLITE_LOCKED`; the transaction rolled back whole, so running it again
+//! from the start is safe.
+
+use std::future::Future;
+use std::time::Duration;
+
+use super::primary_code;
+use super::{SQLITE_BUSY, SQLITE_LOCKED};
+use crate::repository::StorageError;
+
+/// Times a write transaction is run before a busy database is reported.
+const BUSY_ATTEMPTS: u32 = 4;
+
+/// Pause before the first retry; each later retry waits one step longer.
+const BUSY_BACKOFF: Duration = Duration::from_millis(25);
+
+/// Map a failed statement of a write transaction, keeping busy apart so it can be retried.
+pub(crate) fn write_error(err: sqlx::Error) -> StorageError {
+    match primary_code(&err) {
+        Some(SQLITE_BUSY | SQLITE_LOCKED) => StorageError::Busy,
+        _ => StorageError::Connection(err.to_string()),
+    }
+}
+
+/// Run the transaction `write` builds, starting it over while the database is busy.
+///
+/// `write` must open, fill, and commit its own transaction so every attempt starts clean.
+pub(crate) async fn retry_on_busy<T, F, Fut>(mut write: F) -> Result<T, StorageError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, StorageError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match write().await {
+            Err(StorageError::Busy) if attempt < BUSY_ATTEMPTS => {
+                tokio::time::sleep(BUSY_BACKOFF * attempt).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn busy_writes_are_retried_until_the_attempts_run_out() {
+        let calls = AtomicU32::new(0);
+        let result = retry_on_busy(|| async {
+            if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(StorageError::Busy)
+            } else {
+                Ok(7)
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<(), _> = retry_on_busy(|| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(StorageError::Busy)
+        })
+        .await;
+        assert!(matches!(result, Err(StorageError::Busy)));
+        assert_eq!(calls.load(Ordering::SeqCst), BUSY_ATTEMPTS);
+
+        let result: Result<(), _> = retry_on_busy(|| async { Err(StorageError::NotFound) }).await;
+        assert!(matches!(result, Err(StorageError::NotFound)));
+    }
+}
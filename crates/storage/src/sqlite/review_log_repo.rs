@@ -1,10 +1,14 @@
+use chrono::{DateTime, Utc};
 use learn_core::model::{Card, CardId, DeckId};
 
 use super::{
     SqliteRepository,
     mapping::{grade_to_i64, map_review_log_row, media_id_to_i64},
+    retry::{retry_on_busy, write_error},
+};
+use crate::repository::{
+    ReviewLogPrune, ReviewLogRecord, ReviewLogRepository, ReviewPersistence, StorageError,
 };
-use crate::repository::{ReviewLogRecord, ReviewLogRepository, ReviewPersistence, StorageError};
 
 fn id_i64(field: &'static str, v: u64) -> Result<i64, StorageError> {
     i64::try_from(v).map_err(|_| StorageError::Serialization(format!("{field} overflow")))
@@ -20,9 +24,10 @@ impl ReviewLogRepository for SqliteRepository {
             r"
                 INSERT INTO review_logs (
                     deck_id, card_id, grade, reviewed_at,
-                    elapsed_days, scheduled_days, stability, difficulty, next_review_at
+                    elapsed_days, scheduled_days, stability, difficulty, next_review_at, answer_ms,
+                    first_review
                 )
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
             ",
         )
         .bind(deck_id)
@@ -34,6 +39,8 @@ impl ReviewLogRepository for SqliteRepository {
         .bind(log.stability)
         .bind(log.difficulty)
         .bind(log.next_review_at)
+        .bind(log.answer_ms.map(i64::from))
+        .bind(i64::from(log.first_review))
         .execute(&self.pool)
         .await
         .map_err(|e| StorageError::Connection(e.to_string()))?;
@@ -53,7 +60,8 @@ impl ReviewLogRepository for SqliteRepository {
             r"
                 SELECT
                     id, deck_id, card_id, grade, reviewed_at,
-                    elapsed_days, scheduled_days, stability, difficulty, next_review_at
+                    elapsed_days, scheduled_days, stability, difficulty, next_review_at, answer_ms,
+                    first_review
                 FROM review_logs
                 WHERE deck_id = ?1 AND card_id = ?2
                 ORDER BY reviewed_at ASC
@@ -71,6 +79,122 @@ impl ReviewLogRepository for SqliteRepository {
         }
         Ok(out)
     }
+
+    async fn logs_for_deck(&self, deck_id: DeckId) -> Result<Vec<ReviewLogRecord>, StorageError> {
+        let deck = id_i64("deck_id", deck_id.value())?;
+
+        let rows = sqlx::query(
+            r"
+                SELECT
+                    id, deck_id, card_id, grade, reviewed_at,
+                    elapsed_days, scheduled_days, stability, difficulty, next_review_at, answer_ms,
+                    first_review
+                FROM review_logs
+                WHERE deck_id = ?1
+                ORDER BY reviewed_at ASC, id ASC
+            ",
+        )
+        .bind(deck)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        rows.iter().map(map_review_log_row).collect()
+    }
+
+    async fn logs_for_deck_page(
+        &self,
+        deck_id: DeckId,
+        after_id: Option<i64>,
+        limit: u32,
+    ) -> Result<Vec<ReviewLogRecord>, StorageError> {
+        let deck = id_i64("deck_id", deck_id.value())?;
+
+        let rows = sqlx::query(
+            r"
+                SELECT
+                    id, deck_id, card_id, grade, reviewed_at,
+                    elapsed_days, scheduled_days, stability, difficulty, next_review_at, answer_ms,
+                    first_review
+                FROM review_logs
+                WHERE deck_id = ?1 AND id > ?2
+                ORDER BY id ASC
+                LIMIT ?3
+            ",
+        )
+        .bind(deck)
+        .bind(after_id.unwrap_or(0))
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        rows.iter().map(map_review_log_row).collect()
+    }
+
+    async fn count_introduced_since(
+        &self,
+        deck_id: DeckId,
+        since: DateTime<Utc>,
+    ) -> Result<u32, StorageError> {
+        let deck = id_i64("deck_id", deck_id.value())?;
+
+        let count: i64 = sqlx::query_scalar(
+            r"
+                SELECT COUNT(DISTINCT card_id)
+                FROM review_logs
+                WHERE deck_id = ?1 AND first_review = 1 AND reviewed_at >= ?2
+            ",
+        )
+        .bind(deck)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        Ok(u32::try_from(count).unwrap_or(u32::MAX))
+    }
+
+    async fn prune(&self, deck_id: DeckId, prune: ReviewLogPrune) -> Result<u64, StorageError> {
+        let deck = id_i64("deck_id", deck_id.value())?;
+
+        let query = match prune {
+            ReviewLogPrune::KeepLastPerCard(keep) => sqlx::query(
+                r"
+                    DELETE FROM review_logs
+                    WHERE id IN (
+                        SELECT id FROM (
+                            SELECT
+                                id,
+                                ROW_NUMBER() OVER (
+                                    PARTITION BY card_id
+                                    ORDER BY reviewed_at DESC, id DESC
+                                ) AS newest_rank
+                            FROM review_logs
+                            WHERE deck_id = ?1
+                        ) AS ranked
+                        WHERE newest_rank > ?2
+                    )
+                ",
+            )
+            .bind(deck)
+            .bind(i64::from(keep)),
+            ReviewLogPrune::OlderThan(cutoff) => sqlx::query(
+                r"
+                    DELETE FROM review_logs
+                    WHERE deck_id = ?1 AND reviewed_at < ?2
+                ",
+            )
+            .bind(deck)
+            .bind(cutoff),
+        };
+        let result = query
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
 }
 
 #[async_trait::async_trait]
@@ -83,77 +207,146 @@ impl ReviewPersistence for SqliteRepository {
         let card_id = id_i64("card_id", card.id().value())?;
         let deck_id = id_i64("deck_id", card.deck_id().value())?;
 
-        let mut tx = self
-            .pool
-            .begin()
+        let log = &log;
+        retry_on_busy(|| async move {
+            let mut tx = self.pool.begin().await.map_err(write_error)?;
+
+            sqlx::query(
+                r"
+                INSERT INTO cards (
+                    id, deck_id, prompt, prompt_media_id, answer, answer_media_id,
+                    phase, created_at, next_review_at, last_review_at, review_count,
+                    stability, difficulty, learning_step, lapses, updated_at
+                )
+                VALUES (
+                    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15,
+                    ?16
+                )
+                ON CONFLICT(id) DO UPDATE SET
+                    -- keep created_at from the original insert; only update mutable fields
+                    prompt = excluded.prompt,
+                    prompt_media_id = excluded.prompt_media_id,
+                    answer = excluded.answer,
+                    answer_media_id = excluded.answer_media_id,
+                    phase = excluded.phase,
+                    next_review_at = excluded.next_review_at,
+                    last_review_at = excluded.last_review_at,
+                    review_count = excluded.review_count,
+                    stability = excluded.stability,
+                    difficulty = excluded.difficulty,
+                    learning_step = excluded.learning_step,
+                    lapses = excluded.lapses,
+                    updated_at = excluded.updated_at
+                ",
+            )
+            .bind(card_id)
+            .bind(deck_id)
+            .bind(card.prompt().text().to_owned())
+            .bind(media_id_to_i64(card.prompt().media_id())?)
+            .bind(card.answer().text().to_owned())
+            .bind(media_id_to_i64(card.answer().media_id())?)
+            .bind(card.phase().as_str())
+            .bind(card.created_at())
+            .bind(card.next_review_at())
+            .bind(card.last_review_at())
+            .bind(i64::from(card.review_count()))
+            .bind(card.memory_state().map(|m| m.stability))
+            .bind(card.memory_state().map(|m| m.difficulty))
+            .bind(i64::from(card.learning_step()))
+            .bind(i64::from(card.lapses()))
+            .bind(log.reviewed_at)
+            .execute(&mut *tx)
             .await
-            .map_err(|e| StorageError::Connection(e.to_string()))?;
+            .map_err(write_error)?;
 
-        sqlx::query(
-            r"
-            INSERT INTO cards (
-                id, deck_id, prompt, prompt_media_id, answer, answer_media_id,
-                phase, created_at, next_review_at, last_review_at, review_count,
-                stability, difficulty
+            let res = sqlx::query(
+                r"
+                    INSERT INTO review_logs (
+                        deck_id, card_id, grade, reviewed_at, elapsed_days, scheduled_days,
+                        stability, difficulty, next_review_at, answer_ms, first_review
+                    )
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                ",
             )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
-            ON CONFLICT(id) DO UPDATE SET
-                -- keep created_at from the original insert; only update mutable fields
-                prompt = excluded.prompt,
-                prompt_media_id = excluded.prompt_media_id,
-                answer = excluded.answer,
-                answer_media_id = excluded.answer_media_id,
-                phase = excluded.phase,
-                next_review_at = excluded.next_review_at,
-                last_review_at = excluded.last_review_at,
-                review_count = excluded.review_count,
-                stability = excluded.stability,
-                difficulty = excluded.difficulty
-            ",
-        )
-        .bind(card_id)
-        .bind(deck_id)
-        .bind(card.prompt().text().to_owned())
-        .bind(media_id_to_i64(card.prompt().media_id())?)
-        .bind(card.answer().text().to_owned())
-        .bind(media_id_to_i64(card.answer().media_id())?)
-        .bind(card.phase().as_str())
-        .bind(card.created_at())
-        .bind(card.next_review_at())
-        .bind(card.last_review_at())
-        .bind(i64::from(card.review_count()))
-        .bind(card.memory_state().map(|m| m.stability))
-        .bind(card.memory_state().map(|m| m.difficulty))
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| StorageError::Connection(e.to_string()))?;
+            .bind(deck_id)
+            .bind(card_id)
+            .bind(grade_to_i64(log.grade))
+            .bind(log.reviewed_at)
+            .bind(log.elapsed_days)
+            .bind(log.scheduled_days)
+            .bind(log.stability)
+            .bind(log.difficulty)
+            .bind(log.next_review_at)
+            .bind(log.answer_ms.map(i64::from))
+            .bind(i64::from(log.first_review))
+            .execute(&mut *tx)
+            .await
+            .map_err(write_error)?;
 
-        let res = sqlx::query(
-            r"
-                INSERT INTO review_logs (
-                    deck_id, card_id, grade, reviewed_at,
-                    elapsed_days, scheduled_days, stability, difficulty, next_review_at
-                )
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
-            ",
-        )
-        .bind(deck_id)
-        .bind(card_id)
-        .bind(grade_to_i64(log.grade))
-        .bind(log.reviewed_at)
-        .bind(log.elapsed_days)
-        .bind(log.scheduled_days)
-        .bind(log.stability)
-        .bind(log.difficulty)
-        .bind(log.next_review_at)
-        .execute(&mut *tx)
+            tx.commit().await.map_err(write_error)?;
+
+            Ok(res.last_insert_rowid())
+        })
         .await
-        .map_err(|e| StorageError::Connection(e.to_string()))?;
+    }
+
+    async fn revert_review(&self, card: &Card, log_id: i64) -> Result<(), StorageError> {
+        let card_id = id_i64("card_id", card.id().value())?;
+        let deck_id = id_i64("deck_id", card.deck_id().value())?;
+
+        retry_on_busy(|| async move {
+            let mut tx = self.pool.begin().await.map_err(write_error)?;
+
+            let deleted = sqlx::query(
+                r"
+                DELETE FROM review_logs
+                WHERE id = ?1 AND card_id = ?2 AND deck_id = ?3
+                ",
+            )
+            .bind(log_id)
+            .bind(card_id)
+            .bind(deck_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(write_error)?;
+            if deleted.rows_affected() == 0 {
+                return Err(StorageError::NotFound);
+            }
 
-        tx.commit()
+            let updated = sqlx::query(
+                r"
+                UPDATE cards
+                SET phase = ?1,
+                    next_review_at = ?2,
+                    last_review_at = ?3,
+                    review_count = ?4,
+                    stability = ?5,
+                    difficulty = ?6,
+                    learning_step = ?7,
+                    lapses = ?8
+                WHERE id = ?9 AND deck_id = ?10
+                ",
+            )
+            .bind(card.phase().as_str())
+            .bind(card.next_review_at())
+            .bind(card.last_review_at())
+            .bind(i64::from(card.review_count()))
+            .bind(card.memory_state().map(|m| m.stability))
+            .bind(card.memory_state().map(|m| m.difficulty))
+            .bind(i64::from(card.learning_step()))
+            .bind(i64::from(card.lapses()))
+            .bind(card_id)
+            .bind(deck_id)
+            .execute(&mut *tx)
             .await
-            .map_err(|e| StorageError::Connection(e.to_string()))?;
+            .map_err(write_error)?;
+            if updated.rows_affected() == 0 {
+                return Err(StorageError::NotFound);
+            }
 
-        Ok(res.last_insert_rowid())
+            tx.commit().await.map_err(write_error)?;
+            Ok(())
+        })
+        .await
     }
 }
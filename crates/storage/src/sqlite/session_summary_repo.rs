@@ -29,6 +29,8 @@ fn map_summary_row(row: &sqlx::sqlite::SqliteRow) -> Result<SessionSummary, Stor
     let hard = u32_from_i64("hard", row.try_get::<i64, _>("hard").map_err(ser)?)?;
     let good = u32_from_i64("good", row.try_get::<i64, _>("good").map_err(ser)?)?;
     let easy = u32_from_i64("easy", row.try_get::<i64, _>("easy").map_err(ser)?)?;
+    let is_cram = row.try_get::<i64, _>("is_cram").map_err(ser)? != 0;
+    let is_ahead = row.try_get::<i64, _>("is_ahead").map_err(ser)? != 0;
 
     SessionSummary::from_persisted(
         deck_id,
@@ -40,6 +42,7 @@ fn map_summary_row(row: &sqlx::sqlite::SqliteRow) -> Result<SessionSummary, Stor
         good,
         easy,
     )
+    .map(|summary| summary.with_cram(is_cram).with_ahead(is_ahead))
     .map_err(ser)
 }
 
@@ -60,9 +63,9 @@ impl SessionSummaryRepository for SqliteRepository {
             r"
                 INSERT INTO session_summaries (
                     deck_id, started_at, completed_at, total_reviews,
-                    again, hard, good, easy
+                    again, hard, good, easy, is_cram, is_ahead
                 )
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
             ",
         )
         .bind(deck_id)
@@ -73,6 +76,8 @@ impl SessionSummaryRepository for SqliteRepository {
         .bind(i64::from(summary.hard()))
         .bind(i64::from(summary.good()))
         .bind(i64::from(summary.easy()))
+        .bind(i64::from(summary.is_cram()))
+        .bind(i64::from(summary.is_ahead()))
         .execute(&self.pool)
         .await
         .map_err(|e| StorageError::Connection(e.to_string()))?;
@@ -85,7 +90,7 @@ impl SessionSummaryRepository for SqliteRepository {
             r"
                 SELECT
                     deck_id, started_at, completed_at, total_reviews,
-                    again, hard, good, easy
+                    again, hard, good, easy, is_cram, is_ahead
                 FROM session_summaries
                 WHERE id = ?1
             ",
@@ -99,6 +104,18 @@ impl SessionSummaryRepository for SqliteRepository {
         map_summary_row(&row)
     }
 
+    async fn delete_summary(&self, id: i64) -> Result<(), StorageError> {
+        let result = sqlx::query("DELETE FROM session_summaries WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound);
+        }
+        Ok(())
+    }
+
     async fn list_summaries(
         &self,
         deck_id: DeckId,
@@ -123,7 +140,7 @@ impl SessionSummaryRepository for SqliteRepository {
             r"
                 SELECT
                     id, deck_id, started_at, completed_at, total_reviews,
-                    again, hard, good, easy
+                    again, hard, good, easy, is_cram, is_ahead
                 FROM session_summaries
                 WHERE deck_id = ?1
             ",
@@ -178,7 +195,7 @@ impl SessionSummaryRepository for SqliteRepository {
             r"
                 SELECT
                     id, deck_id, started_at, completed_at, total_reviews,
-                    again, hard, good, easy
+                    again, hard, good, easy, is_cram, is_ahead
                 FROM session_summaries
                 WHERE deck_id IN (
             ",
@@ -216,4 +233,28 @@ impl SessionSummaryRepository for SqliteRepository {
 
         Ok(out)
     }
+
+    async fn list_summaries_between(
+        &self,
+        completed_from: chrono::DateTime<chrono::Utc>,
+        completed_until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<SessionSummary>, StorageError> {
+        let rows = sqlx::query(
+            r"
+                SELECT
+                    deck_id, started_at, completed_at, total_reviews,
+                    again, hard, good, easy, is_cram, is_ahead
+                FROM session_summaries
+                WHERE completed_at >= ?1 AND completed_at < ?2
+                ORDER BY completed_at ASC, id ASC
+            ",
+        )
+        .bind(completed_from)
+        .bind(completed_until)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StorageError::Connection(e.to_string()))?;
+
+        rows.iter().map(map_summary_row).collect()
+    }
 }
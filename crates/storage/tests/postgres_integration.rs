@@ -0,0 +1,593 @@
+//! Postgres backend tests.
+//!
+//! Requires the `postgres` feature and a server URL in `LEARN_TEST_POSTGRES_URL`
+//! (e.g. `postgres://postgres@127.0.0.1:5432/postgres`); tests return early otherwise.
+//! Each test runs in its own schema so they can share one database.
+#![cfg(feature = "postgres")]
+
+use chrono::Duration;
+use learn_core::model::content::ContentDraft;
+use learn_core::model::{
+    AnswerButtons, AppSettings, AudioClip, Card, CardId, CardKind, CardPhase, CardSide,
+    CardSource, CardTemplate, DeckColor, DeckIcon, DeckId, DeckSettings, Flag, ImageFormat,
+    ImageMeta, LeechAction, MediaHash, MediaUri, NoteId, ReviewGrade, ReviewLog, SessionSummary,
+    TagName,
+    ThemePreference,
+};
+use learn_core::time::fixed_now;
+use storage::postgres::PostgresRepository;
+use storage::repository::{
+    AiPriceBookRepository, AiUsageCompletion, AiUsageRepository, AiUsageStatus,
+    AppSettingsRepository, CardOrder, CardRepository, DeckPracticeCounts, DeckRepository, MediaRepository,
+    NewAiUsageRecord, NewCardRecord, NewDeckRecord, NewMediaRecord, ReviewLogPrune, ReviewLogRecord,
+    ReviewLogRepository,
+    PendingSessionCard, ReviewPersistence, SessionProgressRecord, SessionProgressRepository,
+    SessionSummaryRepository,
+};
+
+async fn connect_fresh(schema: &str) -> Option<PostgresRepository> {
+    let base = std::env::var("LEARN_TEST_POSTGRES_URL").ok()?;
+
+    let admin = PostgresRepository::connect(&base).await.expect("connect");
+    sqlx::query(&format!("DROP SCHEMA IF EXISTS {schema} CASCADE"))
+        .execute(admin.pool())
+        .await
+        .expect("drop schema");
+    sqlx::query(&format!("CREATE SCHEMA {schema}"))
+        .execute(admin.pool())
+        .await
+        .expect("create schema");
+
+    let sep = if base.contains('?') { '&' } else { '?' };
+    let url = format!("{base}{sep}options=-c%20search_path%3D{schema}");
+    let repo = PostgresRepository::connect(&url).await.expect("connect");
+    repo.migrate().await.expect("migrate");
+    Some(repo)
+}
+
+fn build_deck(id: u64) -> learn_core::model::Deck {
+    learn_core::model::Deck::new(
+        DeckId::new(id),
+        format!("Test {id}"),
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap()
+}
+
+fn build_card(id: u64, deck_id: DeckId) -> Card {
+    build_text_card(id, deck_id, "Q", "A")
+}
+
+fn build_text_card(id: u64, deck_id: DeckId, prompt: &str, answer: &str) -> Card {
+    let prompt = ContentDraft::text_only(prompt)
+        .validate(fixed_now(), None, None)
+        .unwrap();
+    let answer = ContentDraft::text_only(answer)
+        .validate(fixed_now(), None, None)
+        .unwrap();
+    let now = fixed_now();
+    Card::new(CardId::new(id), deck_id, CardKind::Basic, prompt, answer, now, now).unwrap()
+}
+
+#[tokio::test]
+async fn postgres_roundtrip_persists_deck_settings_card_and_logs() {
+    let Some(repo) = connect_fresh("learn_test_roundtrip").await else {
+        return;
+    };
+
+    let deck = build_deck(1);
+    repo.upsert_deck(&deck).await.unwrap();
+    let fetched_deck = repo.get_deck(deck.id()).await.unwrap().unwrap();
+    assert_eq!(fetched_deck.settings(), deck.settings());
+    assert_eq!(fetched_deck.created_at(), deck.created_at());
+    repo.upsert_deck(&build_deck(2).with_archived(true)).await.unwrap();
+    assert!(repo.get_deck(DeckId::new(2)).await.unwrap().unwrap().is_archived());
+    assert_eq!(repo.list_decks(10, false).await.unwrap().len(), 1);
+    assert_eq!(repo.list_decks(10, true).await.unwrap().len(), 2);
+    repo.upsert_deck(&build_deck(3).with_favorite(true)).await.unwrap();
+    let listed: Vec<DeckId> = repo
+        .list_decks(10, false)
+        .await
+        .unwrap()
+        .iter()
+        .map(learn_core::model::Deck::id)
+        .collect();
+    assert_eq!(listed, vec![DeckId::new(3), deck.id()]);
+    let stale = fetched_deck.clone();
+    assert_eq!(repo.update_deck_if_version(&fetched_deck).await.unwrap(), 1);
+    assert!(matches!(
+        repo.update_deck_if_version(&stale).await,
+        Err(storage::repository::StorageError::Conflict)
+    ));
+    assert!(matches!(
+        repo.update_deck_if_version(&build_deck(9)).await,
+        Err(storage::repository::StorageError::NotFound)
+    ));
+    let weights: Vec<f32> = (1..=21_u8).map(|weight| f32::from(weight) / 7.0).collect();
+    let learned = deck
+        .settings()
+        .clone()
+        .with_fsrs_parameters(weights.clone())
+        .unwrap()
+        .with_answer_buttons(AnswerButtons::Two)
+        .with_leech(4, LeechAction::Suspend)
+        .with_appearance(Some(DeckColor::Teal), Some(DeckIcon::Science))
+        .with_hold_new_until_reviews_done(true)
+        .with_interval_fuzz(false)
+        .with_bury_siblings(false)
+        .with_same_session_fail_limit(2)
+        .with_progressive_reveal(true)
+        .with_typed_answer(true)
+        .with_templates(Some("<h2>{{front}}</h2>"), Some("{{back}}<hr>{{extra}}"))
+        .unwrap();
+    repo.upsert_deck(&deck.clone().with_settings(learned.clone())).await.unwrap();
+    let fetched_deck = repo.get_deck(deck.id()).await.unwrap().unwrap();
+    assert_eq!(fetched_deck.settings().fsrs_parameters(), weights.as_slice());
+    assert_eq!(fetched_deck.settings().answer_buttons(), AnswerButtons::Two);
+    assert_eq!(fetched_deck.settings().leech_threshold(), 4);
+    assert_eq!(fetched_deck.settings().leech_action(), LeechAction::Suspend);
+    assert_eq!(fetched_deck.settings().color(), Some(DeckColor::Teal));
+    assert_eq!(fetched_deck.settings().icon(), Some(DeckIcon::Science));
+    assert!(fetched_deck.settings().hold_new_until_reviews_done());
+    assert!(!fetched_deck.settings().interval_fuzz());
+    assert!(!fetched_deck.settings().bury_siblings());
+    assert_eq!(fetched_deck.settings().same_session_fail_limit(), 2);
+    assert!(fetched_deck.settings().progressive_reveal());
+    assert!(fetched_deck.settings().typed_answer());
+    assert_eq!(
+        fetched_deck.settings().front_template().map(CardTemplate::as_str),
+        Some("<h2>{{front}}</h2>")
+    );
+    assert_eq!(fetched_deck.settings().back_template(), learned.back_template());
+
+    let now = fixed_now();
+    let mut card = build_card(1, deck.id());
+    repo.upsert_card(&card).await.unwrap();
+    assert!(repo.prompt_exists(deck.id(), " q ", None).await.unwrap());
+    assert!(!repo.prompt_exists(deck.id(), "Q", Some(card.id())).await.unwrap());
+
+    let outcome =
+        learn_core::model::ReviewOutcome::new(now - Duration::hours(1), 1.0, 2.0, 0.5, 0.5);
+    card.apply_review_with_phase(ReviewGrade::Good, &outcome, now);
+    let log = ReviewLog::new(card.id(), ReviewGrade::Good, now);
+    let record = ReviewLogRecord::from_applied(deck.id(), &log, &outcome);
+    let log_id = repo.apply_review(&card, record).await.unwrap();
+    let deck_logs = repo.logs_for_deck(deck.id()).await.unwrap();
+    assert_eq!(deck_logs.len(), 1);
+    assert_eq!(deck_logs[0].id, Some(log_id));
+    let page = repo.logs_for_deck_page(deck.id(), None, 10).await.unwrap();
+    assert_eq!(page.iter().map(|log| log.id).collect::<Vec<_>>(), vec![Some(log_id)]);
+    assert!(repo.logs_for_deck_page(deck.id(), Some(log_id), 10).await.unwrap().is_empty());
+
+    let due = repo.due_cards(deck.id(), now, 10).await.unwrap();
+    assert_eq!(due.len(), 1);
+    repo.upsert_card(&card.clone().with_suspended(true)).await.unwrap();
+    assert!(repo.get_cards(deck.id(), &[card.id()]).await.unwrap()[0].is_suspended());
+    assert!(repo.due_cards(deck.id(), now, 10).await.unwrap().is_empty());
+    let until = now + Duration::hours(1);
+    repo.upsert_card(&card.clone().with_buried_until(Some(until))).await.unwrap();
+    assert!(repo.due_cards(deck.id(), now, 10).await.unwrap().is_empty());
+    assert_eq!(repo.due_cards(deck.id(), until, 10).await.unwrap().len(), 1);
+    repo.upsert_card(&card.clone().with_learning_step(1)).await.unwrap();
+    assert_eq!(repo.get_cards(deck.id(), &[card.id()]).await.unwrap()[0].learning_step(), 1);
+    repo.upsert_card(&card.clone().with_lapses(2)).await.unwrap();
+    assert_eq!(repo.get_cards(deck.id(), &[card.id()]).await.unwrap()[0].lapses(), 2);
+    repo.upsert_card(&card.clone().with_flag(Some(Flag::Blue))).await.unwrap();
+    assert_eq!(repo.get_cards(deck.id(), &[card.id()]).await.unwrap()[0].flag(), Some(Flag::Blue));
+    repo.upsert_card(&card).await.unwrap();
+    let later = now + Duration::days(2);
+    assert_eq!(repo.reschedule_cards(deck.id(), &[(card.id(), later)]).await.unwrap(), 1);
+    assert_eq!(repo.get_cards(deck.id(), &[card.id()]).await.unwrap()[0].next_review_at(), later);
+    repo.upsert_card(&card).await.unwrap();
+    let due_times = repo.due_times(deck.id(), now).await.unwrap();
+    assert_eq!(due_times, vec![now - Duration::hours(1)]);
+
+    let cloze = CardKind::Cloze {
+        text: "{{c1::Q}}".to_string(),
+        indices: vec![1],
+    };
+    let cloze_card = Card::new(
+        CardId::new(2),
+        deck.id(),
+        cloze.clone(),
+        card.prompt().clone(),
+        card.answer().clone(),
+        now,
+        now + Duration::days(1),
+    )
+    .unwrap();
+    repo.upsert_card(&cloze_card).await.unwrap();
+    let fetched = repo.get_cards(deck.id(), &[cloze_card.id()]).await.unwrap();
+    assert_eq!(fetched[0].kind(), &cloze);
+
+    let mut with_audio = cloze_card.clone();
+    let clip = AudioClip::from_persisted(
+        MediaUri::from_url("https://example.com/q.ogg").unwrap(),
+        MediaHash::new("hash-q"),
+    )
+    .unwrap();
+    with_audio.set_audio(CardSide::Prompt, Some(clip.clone()));
+    repo.upsert_card(&with_audio).await.unwrap();
+    let fetched = repo.get_cards(deck.id(), &[cloze_card.id()]).await.unwrap();
+    assert_eq!(fetched[0].prompt().audio(), Some(&clip));
+    assert!(fetched[0].answer().audio().is_none());
+
+    let media = NewMediaRecord {
+        format: ImageFormat::Gif,
+        meta: ImageMeta::new(2, 2).unwrap(),
+        checksum: MediaHash::new("sha256:bb"),
+        bytes: vec![9, 8, 7],
+        created_at: now,
+    };
+    let media_id = repo.insert_media(media.clone()).await.unwrap();
+    assert_eq!(repo.insert_media(media.clone()).await.unwrap(), media_id);
+    let stored = repo.get_media(media_id).await.unwrap().unwrap();
+    assert_eq!(stored.bytes, media.bytes);
+    assert_eq!(stored.format, ImageFormat::Gif);
+
+    repo.put_blob(&MediaHash::new("hash-q"), vec![5], now).await.unwrap();
+    repo.put_blob(&MediaHash::new("hash-q"), vec![6], now).await.unwrap();
+    assert_eq!(repo.get_blob(&MediaHash::new("hash-q")).await.unwrap(), Some(vec![5]));
+    assert_eq!(repo.get_blob(&media.checksum).await.unwrap(), Some(media.bytes.clone()));
+    assert_eq!(repo.gc_unreferenced().await.unwrap(), 1);
+    assert!(!repo.exists(&media.checksum).await.unwrap());
+    assert!(repo.exists(&MediaHash::new("hash-q")).await.unwrap());
+
+    let reverse = build_text_card(3, deck.id(), "A", "Q").with_note_id(Some(NoteId::new(3)));
+    repo.upsert_card(&reverse).await.unwrap();
+    let note_cards = repo.list_note_cards(deck.id(), NoteId::new(3)).await.unwrap();
+    assert_eq!(note_cards.len(), 1);
+    assert_eq!(note_cards[0].note_id(), Some(NoteId::new(3)));
+    assert_eq!(due[0].phase(), CardPhase::Learning);
+    assert_eq!(due[0].review_count(), 1);
+
+    let logs = repo.logs_for_card(deck.id(), card.id()).await.unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].id, Some(log_id));
+    assert_eq!(logs[0].next_review_at, outcome.next_review);
+
+    let later = ReviewLog::new(card.id(), ReviewGrade::Easy, now + Duration::hours(1));
+    repo.append_log(ReviewLogRecord::from_applied(deck.id(), &later, &outcome))
+        .await
+        .unwrap();
+    assert_eq!(repo.prune(deck.id(), ReviewLogPrune::KeepLastPerCard(1)).await.unwrap(), 1);
+    let kept = repo.logs_for_card(deck.id(), card.id()).await.unwrap();
+    assert_eq!(kept.len(), 1);
+    assert_eq!(kept[0].grade, ReviewGrade::Easy);
+    let cutoff = ReviewLogPrune::OlderThan(now + Duration::hours(2));
+    assert_eq!(repo.prune(deck.id(), cutoff).await.unwrap(), 1);
+    assert!(repo.logs_for_deck(deck.id()).await.unwrap().is_empty());
+
+    repo.set_tags_for_card(deck.id(), card.id(), &[TagName::new("Verbs").unwrap()])
+        .await
+        .unwrap();
+    let mut copy_record = NewDeckRecord::from_deck(&deck);
+    copy_record.name = "Copy".to_string();
+    let copy_id = repo.duplicate_deck(deck.id(), copy_record).await.unwrap();
+    let copies = repo.list_cards(copy_id, 10).await.unwrap();
+    assert_eq!(copies.len(), 3);
+    assert_eq!(repo.list_cards_page(copy_id, 1, 5).await.unwrap(), copies[1..]);
+    assert!(copies.iter().all(|copy| copy.phase() == CardPhase::New && copy.note_id().is_none()));
+    let copy_ids: Vec<CardId> = copies.iter().map(Card::id).collect();
+    let copy_tags = repo.list_tags_for_cards(copy_id, &copy_ids).await.unwrap();
+    assert_eq!(copy_tags.len(), 1);
+}
+
+#[tokio::test]
+async fn postgres_inserts_after_explicit_id_upserts() {
+    let Some(repo) = connect_fresh("learn_test_sequences").await else {
+        return;
+    };
+
+    repo.upsert_deck(&build_deck(5)).await.unwrap();
+    let deck_id = repo
+        .insert_new_deck(NewDeckRecord::from_deck(&build_deck(1)))
+        .await
+        .unwrap();
+    assert_eq!(deck_id, DeckId::new(6));
+
+    repo.upsert_card(&build_card(3, deck_id)).await.unwrap();
+    let card_id = repo
+        .insert_new_card(NewCardRecord {
+            deck_id,
+            kind: CardKind::Basic,
+            note_id: None,
+            prompt_text: "Q".to_string(),
+            prompt_media_id: None,
+            answer_text: "A".to_string(),
+            answer_media_id: None,
+            phase: CardPhase::New,
+            created_at: fixed_now(),
+            next_review_at: fixed_now(),
+            last_review_at: None,
+            review_count: 0,
+            stability: None,
+            difficulty: None,
+            extra_text: Some("note".to_string()),
+            source: Some(CardSource::Anki),
+        })
+        .await
+        .unwrap();
+    assert_eq!(card_id, CardId::new(4));
+    let stored = repo.get_cards(deck_id, &[CardId::new(3), card_id]).await.unwrap();
+    assert!(stored[0].extra().is_none());
+    assert_eq!(stored[1].extra().map(|extra| extra.text()), Some("note"));
+    assert_eq!((stored[0].source(), stored[1].source()), (None, Some(CardSource::Anki)));
+
+    let batch = NewCardRecord {
+        deck_id,
+        kind: CardKind::Basic,
+        note_id: None,
+        prompt_text: "Batch".to_string(),
+        prompt_media_id: None,
+        answer_text: "A".to_string(),
+        answer_media_id: None,
+        phase: CardPhase::New,
+        created_at: fixed_now(),
+        next_review_at: fixed_now(),
+        last_review_at: None,
+        review_count: 0,
+        stability: None,
+        difficulty: None,
+        extra_text: None,
+        source: None,
+    };
+    let ids = repo
+        .insert_new_cards(vec![batch.clone(), batch])
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![CardId::new(5), CardId::new(6)]);
+}
+
+#[tokio::test]
+async fn postgres_counts_practice_stats_and_tags() {
+    let Some(repo) = connect_fresh("learn_test_counts").await else {
+        return;
+    };
+
+    let deck = build_deck(1);
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let now = fixed_now();
+    let card1 = build_card(1, deck.id());
+    repo.upsert_card(&card1).await.unwrap();
+    let mut card2 = build_card(2, deck.id());
+    let outcome_due =
+        learn_core::model::ReviewOutcome::new(now - Duration::hours(1), 1.0, 2.0, 0.0, 1.0);
+    card2.apply_review_with_phase(ReviewGrade::Good, &outcome_due, now);
+    repo.upsert_card(&card2).await.unwrap();
+
+    let tag = TagName::new("Language").unwrap();
+    repo.set_tags_for_card(deck.id(), card1.id(), std::slice::from_ref(&tag))
+        .await
+        .unwrap();
+    repo.set_tags_for_card(deck.id(), card2.id(), std::slice::from_ref(&tag))
+        .await
+        .unwrap();
+
+    let counts = repo.deck_practice_counts(deck.id(), now).await.unwrap();
+    assert_eq!(
+        counts,
+        DeckPracticeCounts {
+            total: 2,
+            due: 1,
+            new: 1,
+            learning: 1,
+        }
+    );
+
+    let rows = repo
+        .list_deck_practice_counts(&[deck.id()], now)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].counts, counts);
+
+    let tag_counts = repo.list_tag_practice_counts(deck.id(), now).await.unwrap();
+    assert_eq!(tag_counts.len(), 1);
+    assert_eq!(tag_counts[0].total, 2);
+    assert_eq!(tag_counts[0].due, 1);
+
+    let tagged = repo.list_cards_by_tags(deck.id(), std::slice::from_ref(&tag)).await.unwrap();
+    assert_eq!(tagged.len(), 2);
+    let missing = TagName::new("Missing").unwrap();
+    let all_tags = repo
+        .list_cards_with_all_tags(deck.id(), &[tag.clone(), missing], 10)
+        .await
+        .unwrap();
+    assert!(all_tags.is_empty());
+    let all_tags = repo
+        .list_cards_with_all_tags(deck.id(), std::slice::from_ref(&tag), 10)
+        .await
+        .unwrap();
+    assert_eq!(all_tags.len(), 2);
+    assert!(repo.list_untagged_cards(deck.id(), 10).await.unwrap().is_empty());
+
+    for order in [CardOrder::DueDate, CardOrder::Difficulty] {
+        let ordered = repo.list_cards_ordered(deck.id(), order, 10).await.unwrap();
+        let ids: Vec<_> = ordered.iter().map(Card::id).collect();
+        assert_eq!(ids, vec![card2.id(), card1.id()]);
+    }
+
+    let existing = repo.upsert_tag(deck.id(), &tag).await.unwrap();
+    assert_eq!(existing.name(), &tag);
+    let by_card = repo
+        .list_tags_for_cards(deck.id(), &[card1.id(), card2.id()])
+        .await
+        .unwrap();
+    assert_eq!(by_card.len(), 2);
+    assert_eq!(by_card[0].0, card1.id());
+
+    let other = build_deck(2);
+    repo.upsert_deck(&other).await.unwrap();
+    repo.move_card(card2.id(), deck.id(), other.id()).await.unwrap();
+    let moved_tags = repo.list_tags_for_card(other.id(), card2.id()).await.unwrap();
+    assert_eq!(moved_tags.len(), 1);
+    assert_eq!(moved_tags[0].deck_id(), other.id());
+    assert_eq!(repo.deck_practice_counts(other.id(), now).await.unwrap().total, 1);
+    repo.reset_card_learning(other.id(), card2.id(), now, true).await.unwrap();
+    let reset = repo.get_cards(other.id(), &[card2.id()]).await.unwrap();
+    assert!(reset[0].is_new());
+    assert!(repo.logs_for_card(other.id(), card2.id()).await.unwrap().is_empty());
+
+    repo.trash_card(deck.id(), card1.id(), now).await.unwrap();
+    assert!(repo.list_cards(deck.id(), 10).await.unwrap().is_empty());
+    assert_eq!(repo.deck_practice_counts(deck.id(), now).await.unwrap().total, 0);
+    let trashed = repo.list_trashed_cards(deck.id(), 10).await.unwrap();
+    assert_eq!(trashed.len(), 1);
+    assert_eq!(trashed[0].card.id(), card1.id());
+    assert_eq!(trashed[0].deleted_at, now);
+    repo.restore_card(deck.id(), card1.id()).await.unwrap();
+    assert_eq!(repo.list_cards(deck.id(), 10).await.unwrap().len(), 1);
+
+    repo.trash_card(other.id(), card2.id(), now).await.unwrap();
+    assert_eq!(repo.purge_trashed_cards(now).await.unwrap(), 0);
+    assert_eq!(repo.purge_trashed_cards(now + Duration::seconds(1)).await.unwrap(), 1);
+    assert!(repo.list_trashed_cards(other.id(), 10).await.unwrap().is_empty());
+
+    repo.delete_card(deck.id(), card1.id()).await.unwrap();
+    let links: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM card_tags")
+        .fetch_one(repo.pool())
+        .await
+        .unwrap();
+    assert_eq!(links, 0);
+}
+
+#[tokio::test]
+async fn postgres_search_cards_matches_word_prefixes() {
+    let Some(repo) = connect_fresh("learn_test_search").await else {
+        return;
+    };
+
+    let deck = build_deck(1);
+    repo.upsert_deck(&deck).await.unwrap();
+    repo.upsert_card(&build_text_card(1, deck.id(), "Photosynthesis", "Light into sugar"))
+        .await
+        .unwrap();
+    repo.upsert_card(&build_text_card(2, deck.id(), "Mitochondria", "Powerhouse"))
+        .await
+        .unwrap();
+
+    let found = repo.search_cards(deck.id(), "PHOTO lig", 10).await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id(), CardId::new(1));
+    assert!(repo.search_cards(deck.id(), "hondria", 10).await.unwrap().is_empty());
+    assert!(repo.search_cards(deck.id(), "&|!", 10).await.unwrap().is_empty());
+
+    let mut physics = NewDeckRecord::from_deck(&deck);
+    physics.name = "Physics".to_string();
+    let physics_id = repo.insert_new_deck(physics).await.unwrap();
+    repo.upsert_card(&build_text_card(3, physics_id, "Photon", "Particle of light"))
+        .await
+        .unwrap();
+    let everywhere = repo.search_all_decks("photo", 10).await.unwrap();
+    let mut labeled: Vec<_> =
+        everywhere.iter().map(|hit| (hit.card.id(), hit.deck_name.as_str())).collect();
+    labeled.sort();
+    assert_eq!(labeled, vec![(CardId::new(1), "Test 1"), (CardId::new(3), "Physics")]);
+    assert_eq!(repo.search_cards(deck.id(), "photo", 10).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn postgres_persists_summaries_and_seeded_price_book() {
+    let Some(repo) = connect_fresh("learn_test_summaries").await else {
+        return;
+    };
+
+    let deck = build_deck(1);
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let now = fixed_now();
+    let logs = vec![ReviewLog::new(CardId::new(1), ReviewGrade::Good, now)];
+    let older = SessionSummary::from_logs(deck.id(), now, now, &logs).unwrap();
+    let newer = SessionSummary::from_logs(deck.id(), now, now + Duration::days(1), &logs)
+        .unwrap()
+        .with_cram(true)
+        .with_ahead(true);
+    repo.append_summary(&older).await.unwrap();
+    let newer_id = repo.append_summary(&newer).await.unwrap();
+
+    let listed = repo
+        .list_summaries(deck.id(), Some(now + Duration::hours(1)), None, 10)
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+
+    let latest = repo.list_latest_summary_rows(&[deck.id()]).await.unwrap();
+    assert_eq!(latest.len(), 1);
+    assert_eq!(latest[0].id, newer_id);
+    assert!(latest[0].summary.is_cram());
+    assert!(latest[0].summary.is_ahead());
+
+    let between = repo
+        .list_summaries_between(now, now + Duration::days(1))
+        .await
+        .unwrap();
+    assert_eq!(between, vec![older]);
+
+    let pending = vec![PendingSessionCard { card_id: CardId::new(4), next_review_at: now }];
+    let progress = SessionProgressRecord {
+        deck_id: deck.id(),
+        started_at: now,
+        is_cram: false,
+        is_ahead: true,
+        answered: vec![ReviewLogRecord::from_applied(
+            deck.id(),
+            &ReviewLog::new(CardId::new(2), ReviewGrade::Again, now),
+            &learn_core::model::ReviewOutcome::new(now, 0.4, 7.0, 0.0, 0.0),
+        )],
+        pending: pending.clone(),
+    };
+    repo.save_progress(&progress).await.unwrap();
+    repo.save_progress(&progress).await.unwrap();
+    let loaded = repo.get_progress(deck.id()).await.unwrap().expect("saved");
+    assert!(loaded.is_ahead);
+    assert_eq!(loaded.pending, pending);
+    assert_eq!(loaded.answered.len(), 1);
+    assert_eq!(loaded.answered[0].grade, ReviewGrade::Again);
+    repo.clear_progress(deck.id()).await.unwrap();
+    assert!(repo.get_progress(deck.id()).await.unwrap().is_none());
+
+    let entries = repo.list_entries().await.unwrap();
+    assert!(entries.iter().any(|entry| entry.model == "gpt-4.1-mini"));
+
+    let usage_id = repo
+        .insert_started(NewAiUsageRecord {
+            provider: "openai".into(),
+            model: "gpt-4.1-mini".into(),
+            created_at: now,
+        })
+        .await
+        .unwrap();
+    repo.update_completion(
+        usage_id,
+        AiUsageCompletion {
+            status: AiUsageStatus::Succeeded,
+            prompt_tokens: Some(100),
+            completion_tokens: Some(20),
+            total_tokens: Some(120),
+            cost_micro_usd: Some(72),
+        },
+    )
+    .await
+    .unwrap();
+    let usage = repo
+        .list_between(now, now + Duration::days(1))
+        .await
+        .unwrap();
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0].total_tokens, Some(120));
+
+    let mut draft = AppSettings::default().to_draft();
+    draft.theme = Some(ThemePreference::Light);
+    draft.replay_audio_after_answer = Some(true);
+    draft.last_deck_id = Some(DeckId::new(3));
+    draft.day_cutoff_hour = Some(5);
+    draft.focus_mode = Some(true);
+    draft.ai_manual_price_micro_usd_per_1k = Some(2_500);
+    let settings = draft.validate().unwrap();
+    repo.save_settings(&settings).await.unwrap();
+    assert_eq!(repo.get_settings().await.unwrap(), Some(settings));
+}
@@ -1,26 +1,37 @@
 use chrono::Duration;
 use learn_core::model::Card;
-use learn_core::model::content::ContentDraft;
+use learn_core::model::content::{Content, ContentDraft};
 use learn_core::model::{
-    CardId, CardPhase, DeckId, DeckSettings, MediaId, ReviewGrade, ReviewLog, SessionSummary,
-    TagName,
+    AccentColor, AnswerButtons, AppLanguage, AppSettings, AudioClip, BoundKey, CardId, CardKind,
+    CardPhase, CardSide, CardSource, DeckColor, DeckIcon, DeckId, DeckSettings, Flag, ImageFormat,
+    ImageMeta, KeyAction, KeyBindings, KeyCombo, LeechAction, MediaHash, MediaId, MediaUri,
+    NewCardOrder, NewCardTemplate, NewReviewMix, NoteId, ReviewGrade, ReviewLog,
+    SessionSummary, TagName, ThemePreference,
 };
 use learn_core::time::fixed_now;
+use sqlx::Connection;
 use storage::repository::{
-    CardRepository, DeckPracticeCounts, DeckRepository, ReviewLogRecord, ReviewLogRepository,
-    SessionSummaryRepository,
+    AiUsageCompletion, AiUsageRepository, AiUsageStatus, AppSettingsRepository, CardOrder, CardRepository, DeckCardMatch, DeckMergeCounts, DeckPracticeCounts, DeckRepository, MediaRepository, MergeConflict, NewCardRecord,
+    NewAiUsageRecord, NewMediaRecord, PendingSessionCard, ReviewLogPrune, ReviewLogRecord,
+    ReviewLogRepository, ReviewPersistence,
+    SessionProgressRecord, SessionProgressRepository, SessionSummaryRepository,
 };
-use storage::sqlite::SqliteRepository;
+use storage::sqlite::backup::backup_with_rotation;
+use storage::sqlite::{SqliteInitError, SqliteRepository};
 
 fn build_card(id: u64, deck_id: DeckId) -> Card {
-    let prompt = ContentDraft::text_only("Q")
+    build_text_card(id, deck_id, "Q", "A")
+}
+
+fn build_text_card(id: u64, deck_id: DeckId, prompt: &str, answer: &str) -> Card {
+    let prompt = ContentDraft::text_only(prompt)
         .validate(fixed_now(), None, None)
         .unwrap();
-    let answer = ContentDraft::text_only("A")
+    let answer = ContentDraft::text_only(answer)
         .validate(fixed_now(), None, None)
         .unwrap();
     let now = fixed_now();
-    Card::new(CardId::new(id), deck_id, prompt, answer, now, now).unwrap()
+    Card::new(CardId::new(id), deck_id, CardKind::Basic, prompt, answer, now, now).unwrap()
 }
 
 #[tokio::test]
@@ -57,267 +68,1200 @@ async fn sqlite_roundtrip_persists_phase_and_reviews() {
 }
 
 #[tokio::test]
-async fn sqlite_supports_due_new_and_logs() {
-    let repo = SqliteRepository::connect("sqlite:file:memdb_due_new?mode=memory&cache=shared")
+async fn sqlite_roundtrip_persists_new_review_mix() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_review_mix?mode=memory&cache=shared")
         .await
         .expect("connect");
     repo.migrate().await.expect("migrate");
 
-    let deck = learn_core::model::Deck::new(
-        DeckId::new(1),
-        "Test",
-        None,
-        DeckSettings::default_for_adhd(),
-        fixed_now(),
+    let settings = DeckSettings::new(
+        5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+        0.85, true, 100,
     )
-    .unwrap();
+    .unwrap()
+    .with_new_review_mix(NewReviewMix::Interleaved);
+    let deck = learn_core::model::Deck::new(DeckId::new(1), "Mix", None, settings, fixed_now())
+        .unwrap();
     repo.upsert_deck(&deck).await.unwrap();
 
-    let now = fixed_now();
-    let prompt = learn_core::model::content::Content::from_persisted(
-        "Q with media".to_string(),
-        Some(MediaId::new(42)),
+    let fetched = repo.get_deck(deck.id()).await.unwrap().expect("deck");
+    assert_eq!(fetched.settings().new_review_mix(), NewReviewMix::Interleaved);
+}
+
+#[tokio::test]
+async fn sqlite_roundtrip_persists_load_balance() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_load_balance?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let settings = DeckSettings::new(
+        5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+        0.85, true, 100,
     )
+    .unwrap()
+    .with_load_balance(true)
+    .with_hold_new_until_reviews_done(true)
+    .with_interval_fuzz(false)
+    .with_bury_siblings(false)
+    .with_same_session_fail_limit(2)
+    .with_progressive_reveal(true)
+    .with_typed_answer(true)
+    .with_new_card_order(NewCardOrder::Random)
+    .with_new_card_template(NewCardTemplate::new("Word: ", "Meaning: "))
+    .with_new_per_session(5)
+    .with_templates(Some("<h2>{{front}}</h2>"), Some("{{back}}<hr>{{extra}}"))
     .unwrap();
-    let answer =
-        learn_core::model::content::Content::from_persisted("A with media".to_string(), None)
-            .unwrap();
-    let mut card = Card::from_persisted(
-        CardId::new(1),
-        deck.id(),
-        prompt,
-        answer,
-        now,
-        now,
+    let deck = learn_core::model::Deck::new(DeckId::new(1), "Balanced", None, settings, fixed_now())
+        .unwrap();
+    let record = storage::repository::NewDeckRecord::from_deck(&deck);
+    let id = repo.insert_new_deck(record).await.unwrap();
+    let fetched = repo.get_deck(id).await.unwrap().expect("deck");
+    assert!(fetched.settings().load_balance());
+    assert!(fetched.settings().hold_new_until_reviews_done());
+    assert!(!fetched.settings().interval_fuzz());
+    assert!(!fetched.settings().bury_siblings());
+    assert_eq!(fetched.settings().same_session_fail_limit(), 2);
+    assert!(fetched.settings().progressive_reveal());
+    assert!(fetched.settings().typed_answer());
+    assert_eq!(fetched.settings().new_card_order(), NewCardOrder::Random);
+    assert_eq!(fetched.settings().new_card_template(), deck.settings().new_card_template());
+    assert_eq!(fetched.settings().new_per_session(), 5);
+    assert_eq!(fetched.settings().front_template(), deck.settings().front_template());
+    assert_eq!(fetched.settings().back_template(), deck.settings().back_template());
+
+    let disabled = learn_core::model::Deck::new(
+        id,
+        "Balanced",
         None,
-        CardPhase::New,
-        0,
-        0.0,
-        0.0,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
     )
     .unwrap();
-    repo.upsert_card(&card).await.unwrap();
+    repo.upsert_deck(&disabled).await.unwrap();
+    let fetched = repo.get_deck(id).await.unwrap().expect("deck");
+    assert!(!fetched.settings().load_balance());
+    assert!(!fetched.settings().hold_new_until_reviews_done());
+    assert!(fetched.settings().interval_fuzz());
+    assert!(fetched.settings().bury_siblings());
+    assert_eq!(fetched.settings().front_template(), None);
+}
 
-    let new_cards = repo.new_cards(deck.id(), 10).await.unwrap();
-    assert_eq!(new_cards.len(), 1);
-    assert_eq!(new_cards[0].prompt().media_id(), Some(MediaId::new(42)));
-    assert_eq!(new_cards[0].answer().media_id(), None);
+#[tokio::test]
+async fn sqlite_roundtrip_persists_learning_steps() {
+    let repo =
+        SqliteRepository::connect("sqlite:file:memdb_learning_steps?mode=memory&cache=shared")
+            .await
+            .expect("connect");
+    repo.migrate().await.expect("migrate");
 
-    let later = now + Duration::minutes(1);
-    let prompt2 =
-        learn_core::model::content::Content::from_persisted("Q2".to_string(), None).unwrap();
-    let answer2 =
-        learn_core::model::content::Content::from_persisted("A2".to_string(), None).unwrap();
-    let card2 = Card::from_persisted(
-        CardId::new(2),
-        deck.id(),
-        prompt2,
-        answer2,
-        later,
-        later,
-        None,
-        CardPhase::New,
-        0,
-        0.0,
-        0.0,
+    let settings = DeckSettings::new(
+        5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+        0.85, true, 100,
     )
+    .unwrap()
+    .with_learning_steps(vec![30, 300, 3_600])
+    .unwrap()
+    .with_relearning_steps(vec![120, 900])
     .unwrap();
-    repo.upsert_card(&card2).await.unwrap();
-
-    let new_cards = repo.new_cards(deck.id(), 10).await.unwrap();
-    assert_eq!(new_cards.len(), 2);
-    assert_eq!(new_cards[0].id(), CardId::new(1));
-    assert_eq!(new_cards[1].id(), CardId::new(2));
-
-    let reviewed_at = now;
-    // Force the card to be due immediately by scheduling the next review in the past.
-    let outcome =
-        learn_core::model::ReviewOutcome::new(now - Duration::hours(1), 1.0, 2.0, 0.5, 0.5);
-    card.apply_review_with_phase(ReviewGrade::Good, &outcome, reviewed_at);
-    repo.upsert_card(&card).await.unwrap();
-
-    let due_cards = repo.due_cards(deck.id(), now, 10).await.unwrap();
-    assert_eq!(due_cards.len(), 1);
+    let deck = learn_core::model::Deck::new(DeckId::new(1), "Steps", None, settings, fixed_now())
+        .unwrap();
+    let record = storage::repository::NewDeckRecord::from_deck(&deck);
+    let id = repo.insert_new_deck(record).await.unwrap();
+    let fetched = repo.get_deck(id).await.unwrap().expect("deck");
+    assert_eq!(fetched.settings().learning_steps_secs(), &[30, 300, 3_600]);
+    assert_eq!(fetched.settings().relearning_steps_secs(), &[120, 900]);
 
-    let card3 = Card::from_persisted(
-        CardId::new(3),
-        deck.id(),
-        learn_core::model::content::Content::from_persisted("Q3".to_string(), None).unwrap(),
-        learn_core::model::content::Content::from_persisted("A3".to_string(), None).unwrap(),
-        now,
-        now - Duration::hours(2),
-        Some(reviewed_at),
-        CardPhase::Reviewing,
-        1,
-        3.0,
-        4.0,
+    let no_steps = DeckSettings::new(
+        5, 30, 5, true, true, 86_400, false, false, false, 25, 20, 86_400, 365, false, 0.5, 0,
+        0.85, true, 100,
     )
     .unwrap();
-    repo.upsert_card(&card3).await.unwrap();
+    let cleared = learn_core::model::Deck::new(id, "Steps", None, no_steps, fixed_now()).unwrap();
+    repo.upsert_deck(&cleared).await.unwrap();
+    let fetched = repo.get_deck(id).await.unwrap().expect("deck");
+    assert!(fetched.settings().learning_steps_secs().is_empty());
+    assert_eq!(fetched.settings().relearning_steps_secs(), &[600]);
 
-    let due_cards = repo.due_cards(deck.id(), now, 10).await.unwrap();
-    assert_eq!(due_cards.len(), 2);
-    assert_eq!(due_cards[0].id(), CardId::new(3));
-    assert_eq!(due_cards[1].id(), CardId::new(1));
+    let weights: Vec<f32> = (1..=21_u8).map(|weight| f32::from(weight) / 7.0).collect();
+    let learned = cleared
+        .clone()
+        .with_settings(fetched.settings().clone().with_fsrs_parameters(weights.clone()).unwrap());
+    repo.upsert_deck(&learned).await.unwrap();
+    let fetched = repo.get_deck(id).await.unwrap().expect("deck");
+    assert_eq!(fetched.settings().fsrs_parameters(), weights.as_slice());
+    assert_eq!(fetched.settings().answer_buttons(), AnswerButtons::Four);
 
-    let log = learn_core::model::ReviewLog::new(card.id(), ReviewGrade::Good, reviewed_at);
-    let record = ReviewLogRecord::from_applied(deck.id(), &log, &outcome);
-    let id = repo.append_log(record).await.unwrap();
-    let logs = repo
-        .logs_for_card(deck.id(), card.id())
-        .await
-        .expect("logs");
-    assert_eq!(logs.len(), 1);
-    assert_eq!(logs[0].id, Some(id));
-    assert_eq!(logs[0].grade, ReviewGrade::Good);
-    assert_eq!(logs[0].next_review_at, outcome.next_review);
+    let two_buttons = learned.with_settings(
+        fetched.settings().clone().with_answer_buttons(AnswerButtons::Two),
+    );
+    repo.upsert_deck(&two_buttons).await.unwrap();
+    let fetched = repo.get_deck(id).await.unwrap().expect("deck");
+    assert_eq!(fetched.settings().answer_buttons(), AnswerButtons::Two);
+    assert_eq!(fetched.settings().fsrs_parameters(), weights.as_slice());
+    assert_eq!(fetched.settings().leech_threshold(), DeckSettings::DEFAULT_LEECH_THRESHOLD);
+
+    let leech = two_buttons.with_settings(
+        fetched.settings().clone().with_leech(4, LeechAction::Suspend),
+    );
+    repo.upsert_deck(&leech).await.unwrap();
+    let fetched = repo.get_deck(id).await.unwrap().expect("deck");
+    assert_eq!(fetched.settings().leech_threshold(), 4);
+    assert_eq!(fetched.settings().leech_action(), LeechAction::Suspend);
+
+    let card = build_card(1, id)
+        .with_learning_step(2)
+        .with_lapses(3)
+        .with_flag(Some(Flag::Orange));
+    repo.upsert_card(&card).await.unwrap();
+    let fetched = repo.get_cards(id, &[card.id()]).await.unwrap();
+    assert_eq!(fetched[0].learning_step(), 2);
+    assert_eq!(fetched[0].lapses(), 3);
+    assert_eq!(fetched[0].flag(), Some(Flag::Orange));
+    repo.reset_card_learning(id, card.id(), fixed_now(), false).await.unwrap();
+    let reset = repo.get_cards(id, &[card.id()]).await.unwrap();
+    assert_eq!((reset[0].lapses(), reset[0].flag()), (0, Some(Flag::Orange)));
 }
 
 #[tokio::test]
-async fn sqlite_persists_session_summary() {
-    let repo =
-        SqliteRepository::connect("sqlite:file:memdb_session_summary?mode=memory&cache=shared")
-            .await
-            .expect("connect");
+async fn sqlite_reschedule_cards_is_all_or_nothing() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_reschedule?mode=memory&cache=shared")
+        .await
+        .expect("connect");
     repo.migrate().await.expect("migrate");
 
     let deck = learn_core::model::Deck::new(
         DeckId::new(1),
-        "Test",
+        "Reschedule",
         None,
         DeckSettings::default_for_adhd(),
         fixed_now(),
     )
     .unwrap();
     repo.upsert_deck(&deck).await.unwrap();
+    let card = build_card(1, deck.id());
+    repo.upsert_card(&card).await.unwrap();
 
-    let now = fixed_now();
-    let logs = vec![
-        ReviewLog::new(CardId::new(1), ReviewGrade::Good, now),
-        ReviewLog::new(CardId::new(2), ReviewGrade::Again, now),
-        ReviewLog::new(CardId::new(3), ReviewGrade::Hard, now),
-    ];
+    let later = fixed_now() + Duration::days(3);
+    let err = repo
+        .reschedule_cards(deck.id(), &[(card.id(), later), (CardId::new(99), later)])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, storage::repository::StorageError::NotFound));
+    let fetched = repo.get_cards(deck.id(), &[card.id()]).await.unwrap();
+    assert_eq!(fetched[0].next_review_at(), card.next_review_at());
 
-    let summary = SessionSummary::from_logs(deck.id(), now, now, &logs).unwrap();
-    let id = repo.append_summary(&summary).await.unwrap();
+    let updated = repo.reschedule_cards(deck.id(), &[(card.id(), later)]).await.unwrap();
+    assert_eq!(updated, 1);
+    let fetched = repo.get_cards(deck.id(), &[card.id()]).await.unwrap();
+    assert_eq!(fetched[0].next_review_at(), later);
+    assert_eq!(fetched[0].phase(), card.phase());
+}
 
-    let stored = repo.get_summary(id).await.unwrap();
-    assert_eq!(stored.deck_id(), deck.id());
-    assert_eq!(stored.total_reviews(), 3);
-    assert_eq!(stored.good(), 1);
-    assert_eq!(stored.again(), 1);
-    assert_eq!(stored.hard(), 1);
+#[tokio::test(flavor = "multi_thread")]
+async fn sqlite_interleaved_grading_and_rescheduling_lose_no_update() {
+    let root = std::env::temp_dir().join(format!("learn_writers_{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    let path = root.join("writers.sqlite3");
+    std::fs::File::create(&path).unwrap();
+    let url = format!("sqlite://{}", path.display());
+    // The session and the background reschedule write through their own connections.
+    let grader = SqliteRepository::connect(&url).await.expect("connect grader");
+    grader.migrate().await.expect("migrate");
+    let rescheduler = SqliteRepository::connect(&url).await.expect("connect rescheduler");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Writers",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    grader.upsert_deck(&deck).await.unwrap();
+    let card = build_card(1, deck.id());
+    let other = build_card(2, deck.id());
+    grader.upsert_card(&card).await.unwrap();
+    grader.upsert_card(&other).await.unwrap();
+
+    // A third connection holds the write lock as both writers start. The grade's upsert
+    // reads before it writes, and that upgrade fails busy at once instead of waiting out
+    // `busy_timeout`, so only a retry can land it.
+    let hold = std::time::Duration::from_millis(300);
+    let mut holder = sqlx::SqliteConnection::connect(&url).await.unwrap();
+    sqlx::query("BEGIN IMMEDIATE").execute(&mut holder).await.unwrap();
+    let started = std::time::Instant::now();
+    let release = async {
+        tokio::time::sleep(hold).await;
+        sqlx::query("COMMIT").execute(&mut holder).await.unwrap();
+    };
+    let reviewed_at = fixed_now() + Duration::minutes(1);
+    let grading = async {
+        let mut card = card.clone();
+        let outcome = learn_core::model::ReviewOutcome::new(
+            reviewed_at + Duration::days(1),
+            1.0,
+            2.0,
+            0.0,
+            1.0,
+        );
+        card.apply_review_with_phase(ReviewGrade::Good, &outcome, reviewed_at);
+        let log = ReviewLog::new(card.id(), ReviewGrade::Good, reviewed_at);
+        let record = ReviewLogRecord::from_applied(deck.id(), &log, &outcome);
+        grader.apply_review(&card, record).await.expect("grade");
+        card
+    };
+    let due_at = fixed_now() + Duration::days(7);
+    let rescheduling = async {
+        rescheduler
+            .reschedule_cards(deck.id(), &[(card.id(), due_at), (other.id(), due_at)])
+            .await
+            .expect("reschedule")
+    };
+    let ((), graded, rescheduled) = tokio::join!(release, grading, rescheduling);
+    assert!(started.elapsed() >= hold);
+    assert_eq!(rescheduled, 2);
+
+    let logs = rescheduler.logs_for_card(deck.id(), card.id()).await.unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].reviewed_at, reviewed_at);
+    let fetched = rescheduler
+        .get_cards(deck.id(), &[card.id(), other.id()])
+        .await
+        .unwrap();
+    let fetched_card = fetched.iter().find(|c| c.id() == card.id()).unwrap();
+    let fetched_other = fetched.iter().find(|c| c.id() == other.id()).unwrap();
+    // Rescheduling only moves the due date, so the grade's state survives it whole and the
+    // due date is whichever writer committed last.
+    assert_eq!(fetched_card.review_count(), graded.review_count());
+    assert_eq!(fetched_card.last_review_at(), graded.last_review_at());
+    assert_eq!(fetched_card.memory_state(), graded.memory_state());
+    assert!([graded.next_review_at(), due_at].contains(&fetched_card.next_review_at()));
+    assert_eq!(fetched_other.next_review_at(), due_at);
+    std::fs::remove_dir_all(&root).ok();
 }
 
 #[tokio::test]
-async fn sqlite_counts_practice_stats() {
-    let repo = SqliteRepository::connect("sqlite:file:memdb_counts?mode=memory&cache=shared")
+async fn sqlite_upsert_cards_is_all_or_nothing() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_upsert_cards?mode=memory&cache=shared")
         .await
         .expect("connect");
     repo.migrate().await.expect("migrate");
 
     let deck = learn_core::model::Deck::new(
         DeckId::new(1),
-        "Test",
+        "Batch",
         None,
         DeckSettings::default_for_adhd(),
         fixed_now(),
     )
     .unwrap();
     repo.upsert_deck(&deck).await.unwrap();
+    repo.upsert_card(&build_card(1, deck.id())).await.unwrap();
 
-    let now = fixed_now();
-    let card1 = build_card(1, deck.id());
-    repo.upsert_card(&card1).await.unwrap();
-
-    let mut card2 = build_card(2, deck.id());
-    let outcome_due =
-        learn_core::model::ReviewOutcome::new(now - Duration::hours(1), 1.0, 2.0, 0.0, 1.0);
-    card2.apply_review_with_phase(ReviewGrade::Good, &outcome_due, now);
-    repo.upsert_card(&card2).await.unwrap();
+    // The second card points at a deck that does not exist, so the whole batch rolls back.
+    let edited = build_text_card(1, deck.id(), "Q2", "A2");
+    let orphan = build_card(2, DeckId::new(99));
+    repo.upsert_cards(&[edited.clone(), orphan]).await.unwrap_err();
+    let fetched = repo.get_cards(deck.id(), &[CardId::new(1)]).await.unwrap();
+    assert_eq!(fetched[0].prompt().text(), "Q");
 
-    let mut card3 = build_card(3, deck.id());
-    let outcome_future =
-        learn_core::model::ReviewOutcome::new(now + Duration::hours(4), 1.0, 2.0, 0.0, 1.0);
-    card3.apply_review_with_phase(ReviewGrade::Good, &outcome_future, now);
-    repo.upsert_card(&card3).await.unwrap();
+    let added = build_text_card(2, deck.id(), "Q3", "A3");
+    repo.upsert_cards(&[edited, added]).await.unwrap();
+    let fetched = repo.list_cards(deck.id(), 10).await.unwrap();
+    let prompts: Vec<&str> = fetched.iter().map(|card| card.prompt().text()).collect();
+    assert_eq!(prompts.len(), 2);
+    assert!(prompts.contains(&"Q2") && prompts.contains(&"Q3"));
+}
 
-    let tag_language = TagName::new("Language").unwrap();
-    let tag_grammar = TagName::new("Grammar").unwrap();
-    repo.set_tags_for_card(deck.id(), card1.id(), std::slice::from_ref(&tag_language))
-        .await
-        .unwrap();
-    repo.set_tags_for_card(deck.id(), card2.id(), &[tag_language.clone(), tag_grammar.clone()])
+#[tokio::test]
+async fn sqlite_archived_decks_round_trip_and_filter_listing() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_archived?mode=memory&cache=shared")
         .await
-        .unwrap();
-    repo.set_tags_for_card(deck.id(), card3.id(), std::slice::from_ref(&tag_grammar))
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let settings = DeckSettings::default_for_adhd();
+    let active =
+        learn_core::model::Deck::new(DeckId::new(1), "Active", None, settings.clone(), fixed_now())
+            .unwrap();
+    let archived =
+        learn_core::model::Deck::new(DeckId::new(2), "Archived", None, settings, fixed_now())
+            .unwrap()
+            .with_archived(true);
+    repo.upsert_deck(&active).await.unwrap();
+    repo.upsert_deck(&archived).await.unwrap();
+
+    let fetched = repo.get_deck(archived.id()).await.unwrap().expect("deck");
+    assert!(fetched.is_archived());
+    let listed = repo.list_decks(10, false).await.unwrap();
+    assert_eq!(listed, vec![active]);
+    assert_eq!(repo.list_decks(10, true).await.unwrap().len(), 2);
+
+    repo.upsert_deck(&fetched.with_archived(false)).await.unwrap();
+    assert_eq!(repo.list_decks(10, false).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn sqlite_favorite_decks_list_first() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_favorite?mode=memory&cache=shared")
         .await
-        .unwrap();
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
 
-    let counts = repo.deck_practice_counts(deck.id(), now).await.unwrap();
-    assert_eq!(
-        counts,
-        DeckPracticeCounts {
-            total: 3,
-            due: 1,
-            new: 1,
-        }
-    );
+    let settings = DeckSettings::default_for_adhd();
+    for (id, name) in [(1, "Alpha"), (2, "Beta"), (3, "Gamma")] {
+        let deck =
+            learn_core::model::Deck::new(DeckId::new(id), name, None, settings.clone(), fixed_now())
+                .unwrap();
+        repo.upsert_deck(&deck.with_favorite(id == 3)).await.unwrap();
+    }
 
-    let tag_counts = repo.list_tag_practice_counts(deck.id(), now).await.unwrap();
-    let language = tag_counts
-        .iter()
-        .find(|item| item.name == tag_language)
-        .unwrap();
-    assert_eq!(language.total, 2);
-    assert_eq!(language.new, 1);
-    assert_eq!(language.due, 1);
+    let listed = repo.list_decks(10, false).await.unwrap();
+    let names: Vec<&str> = listed.iter().map(learn_core::model::Deck::name).collect();
+    assert_eq!(names, vec!["Gamma", "Alpha", "Beta"]);
+    assert!(listed[0].is_favorite());
+    assert_eq!(repo.list_decks(1, false).await.unwrap()[0].id(), DeckId::new(3));
+}
 
-    let grammar = tag_counts
-        .iter()
-        .find(|item| item.name == tag_grammar)
-        .unwrap();
-    assert_eq!(grammar.total, 2);
-    assert_eq!(grammar.new, 0);
-    assert_eq!(grammar.due, 1);
+#[tokio::test]
+async fn sqlite_deck_color_and_icon_roundtrip_and_clear() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_deck_appearance?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let settings = DeckSettings::default_for_adhd()
+        .with_appearance(Some(DeckColor::Orange), Some(DeckIcon::Globe));
+    let deck =
+        learn_core::model::Deck::new(DeckId::new(1), "Travel", None, settings, fixed_now())
+            .unwrap();
+    let record = storage::repository::NewDeckRecord::from_deck(&deck);
+    let id = repo.insert_new_deck(record).await.unwrap();
+    let fetched = repo.get_deck(id).await.unwrap().expect("deck");
+    assert_eq!(fetched.settings().color(), Some(DeckColor::Orange));
+    assert_eq!(fetched.settings().icon(), Some(DeckIcon::Globe));
+
+    let plain = fetched.settings().clone().with_appearance(None, Some(DeckIcon::Book));
+    repo.upsert_deck(&fetched.with_settings(plain)).await.unwrap();
+    let fetched = repo.get_deck(id).await.unwrap().expect("deck");
+    assert_eq!(fetched.settings().color(), None);
+    assert_eq!(fetched.settings().icon(), Some(DeckIcon::Book));
 }
 
 #[tokio::test]
-async fn sqlite_lists_deck_practice_counts() {
-    let repo = SqliteRepository::connect("sqlite:file:memdb_counts_multi?mode=memory&cache=shared")
+async fn sqlite_versioned_deck_update_rejects_stale_snapshots() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_deck_version?mode=memory&cache=shared")
         .await
         .expect("connect");
     repo.migrate().await.expect("migrate");
 
-    let deck1 = learn_core::model::Deck::new(
+    let deck = learn_core::model::Deck::new(
         DeckId::new(1),
-        "Deck 1",
+        "Versioned",
         None,
         DeckSettings::default_for_adhd(),
         fixed_now(),
     )
     .unwrap();
-    let deck2 = learn_core::model::Deck::new(
-        DeckId::new(2),
-        "Deck 2",
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let first = repo.get_deck(deck.id()).await.unwrap().expect("deck");
+    let second = first.clone();
+    assert_eq!(first.version(), 0);
+
+    let renamed = learn_core::model::Deck::new(
+        first.id(),
+        "First window",
+        None,
+        first.settings().clone(),
+        first.created_at(),
+    )
+    .unwrap()
+    .with_version(first.version());
+    assert_eq!(repo.update_deck_if_version(&renamed).await.unwrap(), 1);
+    assert!(matches!(
+        repo.update_deck_if_version(&second).await,
+        Err(storage::repository::StorageError::Conflict)
+    ));
+
+    let stored = repo.get_deck(deck.id()).await.unwrap().expect("deck");
+    assert_eq!(stored.name(), "First window");
+    assert_eq!(stored.version(), 1);
+
+    repo.upsert_deck(&stored.clone().with_archived(true)).await.unwrap();
+    assert_eq!(repo.get_deck(deck.id()).await.unwrap().expect("deck").version(), 2);
+    assert!(matches!(
+        repo.update_deck_if_version(&stored).await,
+        Err(storage::repository::StorageError::Conflict)
+    ));
+
+    let missing = learn_core::model::Deck::new(
+        DeckId::new(9),
+        "Missing",
         None,
         DeckSettings::default_for_adhd(),
         fixed_now(),
     )
     .unwrap();
-    repo.upsert_deck(&deck1).await.unwrap();
-    repo.upsert_deck(&deck2).await.unwrap();
-
-    let now = fixed_now();
-    let card1 = build_card(1, deck1.id());
-    repo.upsert_card(&card1).await.unwrap();
-    let mut card2 = build_card(2, deck1.id());
-    let outcome_due =
-        learn_core::model::ReviewOutcome::new(now - Duration::hours(1), 1.0, 2.0, 0.0, 1.0);
-    card2.apply_review_with_phase(ReviewGrade::Good, &outcome_due, now);
-    repo.upsert_card(&card2).await.unwrap();
+    assert!(matches!(
+        repo.update_deck_if_version(&missing).await,
+        Err(storage::repository::StorageError::NotFound)
+    ));
+}
 
-    let card3 = build_card(3, deck2.id());
+#[tokio::test]
+async fn sqlite_active_deck_names_are_unique_ignoring_case() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_deck_names?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = |id: u64, name: &str| {
+        learn_core::model::Deck::new(
+            DeckId::new(id),
+            name,
+            None,
+            DeckSettings::default_for_adhd(),
+            fixed_now(),
+        )
+        .unwrap()
+    };
+    repo.upsert_deck(&deck(1, "Spanish")).await.unwrap();
+    assert!(repo.upsert_deck(&deck(2, "SPANISH")).await.is_err());
+    repo.upsert_deck(&deck(2, "Spanish").with_archived(true)).await.unwrap();
+    repo.upsert_deck(&deck(1, "spanish")).await.unwrap();
+    assert_eq!(repo.list_decks(10, true).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn sqlite_migration_clamps_out_of_range_target_retention() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_retention?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    for id in [1, 2] {
+        let deck = learn_core::model::Deck::new(
+            DeckId::new(id),
+            format!("Legacy {id}"),
+            None,
+            DeckSettings::default_for_adhd(),
+            fixed_now(),
+        )
+        .unwrap();
+        repo.upsert_deck(&deck).await.unwrap();
+    }
+    sqlx::query("UPDATE decks SET fsrs_target_retention = 0.5 WHERE id = 1")
+        .execute(repo.pool())
+        .await
+        .unwrap();
+    sqlx::query("UPDATE decks SET fsrs_target_retention = 1.0 WHERE id = 2")
+        .execute(repo.pool())
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM schema_migrations WHERE version = 16")
+        .execute(repo.pool())
+        .await
+        .unwrap();
+    repo.migrate().await.expect("re-migrate");
+
+    let low = repo.get_deck(DeckId::new(1)).await.unwrap().expect("deck");
+    let high = repo.get_deck(DeckId::new(2)).await.unwrap().expect("deck");
+    assert!((low.settings().fsrs_target_retention() - 0.7).abs() < f32::EPSILON);
+    assert!((high.settings().fsrs_target_retention() - 0.99).abs() < f32::EPSILON);
+}
+
+#[tokio::test]
+async fn sqlite_migration_gives_existing_decks_no_learning_steps() {
+    let repo =
+        SqliteRepository::connect("sqlite:file:memdb_steps_backfill?mode=memory&cache=shared")
+            .await
+            .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let default: String = sqlx::query_scalar(
+        "SELECT dflt_value FROM pragma_table_info('decks') WHERE name = 'learning_steps'",
+    )
+    .fetch_one(repo.pool())
+    .await
+    .unwrap();
+    assert_eq!(default, "''");
+}
+
+#[tokio::test]
+async fn sqlite_migration_gives_existing_decks_no_relearning_steps() {
+    let repo =
+        SqliteRepository::connect("sqlite:file:memdb_relearning_backfill?mode=memory&cache=shared")
+            .await
+            .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let default: String = sqlx::query_scalar(
+        "SELECT dflt_value FROM pragma_table_info('decks') WHERE name = 'relearning_steps'",
+    )
+    .fetch_one(repo.pool())
+    .await
+    .unwrap();
+    assert_eq!(default, "''");
+}
+
+#[tokio::test]
+async fn sqlite_lists_card_pages_in_list_order() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_card_pages?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Pages",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+    for id in 1..=5 {
+        repo.upsert_card(&build_card(id, deck.id())).await.unwrap();
+    }
+
+    let all = repo.list_cards(deck.id(), 10).await.unwrap();
+    let first = repo.list_cards_page(deck.id(), 0, 2).await.unwrap();
+    let second = repo.list_cards_page(deck.id(), 2, 2).await.unwrap();
+    let last = repo.list_cards_page(deck.id(), 4, 2).await.unwrap();
+
+    assert_eq!(first, all[0..2]);
+    assert_eq!(second, all[2..4]);
+    assert_eq!(last, all[4..]);
+    assert!(repo.list_cards_page(deck.id(), 5, 2).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn sqlite_lists_cards_after_an_id_in_id_order() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_card_keyset?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Keyset",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+    for id in 1..=5 {
+        repo.upsert_card(&build_card(id, deck.id())).await.unwrap();
+    }
+    let ids = |cards: Vec<Card>| cards.iter().map(|card| card.id().value()).collect::<Vec<_>>();
+
+    let first = repo.list_cards_after(deck.id(), None, 2).await.unwrap();
+    assert_eq!(ids(first), [1, 2]);
+    // Deleting a card already written does not shift the next page.
+    repo.delete_card(deck.id(), CardId::new(1)).await.unwrap();
+    let second = repo.list_cards_after(deck.id(), Some(CardId::new(2)), 2).await.unwrap();
+    assert_eq!(ids(second), [3, 4]);
+    let last = repo.list_cards_after(deck.id(), Some(CardId::new(4)), 2).await.unwrap();
+    assert_eq!(ids(last), [5]);
+}
+
+#[tokio::test]
+async fn sqlite_suspended_cards_round_trip_and_skip_planning() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_suspended?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Suspend",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+    let active = build_card(1, deck.id());
+    let suspended = build_card(2, deck.id()).with_suspended(true);
+    repo.upsert_card(&active).await.unwrap();
+    repo.upsert_card(&suspended).await.unwrap();
+
+    let fetched = repo.get_cards(deck.id(), &[suspended.id()]).await.unwrap();
+    assert!(fetched[0].is_suspended());
+    assert_eq!(repo.list_cards(deck.id(), 10).await.unwrap().len(), 2);
+    assert_eq!(repo.new_cards(deck.id(), fixed_now(), 10).await.unwrap(), vec![active]);
+    assert_eq!(
+        repo.deck_practice_counts(deck.id(), fixed_now()).await.unwrap(),
+        DeckPracticeCounts {
+            total: 2,
+            due: 0,
+            new: 1,
+            learning: 0,
+        }
+    );
+
+    repo.upsert_card(&fetched[0].clone().with_suspended(false))
+        .await
+        .unwrap();
+    assert_eq!(repo.new_cards(deck.id(), fixed_now(), 10).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn sqlite_buried_cards_skip_planning_until_the_marker_passes() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_buried?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Bury",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+    let until = fixed_now() + Duration::hours(8);
+    let buried = build_card(1, deck.id()).with_buried_until(Some(until));
+    repo.upsert_card(&buried).await.unwrap();
+
+    let fetched = repo.get_cards(deck.id(), &[buried.id()]).await.unwrap();
+    assert_eq!(fetched[0].buried_until(), Some(until));
+    assert!(repo.new_cards(deck.id(), fixed_now(), 10).await.unwrap().is_empty());
+    assert_eq!(repo.new_cards(deck.id(), until, 10).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn sqlite_migrations_apply_each_version_once() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_migrate_twice?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("first migrate");
+    let version = repo.current_schema_version().await.unwrap();
+    assert!(version >= 20);
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Kept",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+    let applied = || async {
+        sqlx::query_as::<_, (i64, String)>(
+            "SELECT version, applied_at FROM schema_migrations ORDER BY version",
+        )
+        .fetch_all(repo.pool())
+        .await
+        .unwrap()
+    };
+    let before = applied().await;
+    assert_eq!(before.len(), usize::try_from(version).unwrap());
+
+    repo.migrate().await.expect("second migrate");
+
+    assert_eq!(repo.current_schema_version().await.unwrap(), version);
+    assert_eq!(applied().await, before);
+    assert!(repo.get_deck(deck.id()).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn storage_memory_shares_one_database_across_repositories() {
+    let storage = storage::repository::Storage::memory().await.expect("memory storage");
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Demo",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    storage.decks.upsert_deck(&deck).await.unwrap();
+    storage.cards.upsert_card(&build_card(1, deck.id())).await.unwrap();
+
+    let fetched = storage.decks.get_deck(deck.id()).await.unwrap().expect("deck");
+    assert_eq!(fetched.name(), "Demo");
+    assert_eq!(storage.cards.list_cards(deck.id(), 10).await.unwrap().len(), 1);
+
+    let other = storage::repository::Storage::memory().await.expect("second memory storage");
+    assert!(other.decks.get_deck(deck.id()).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn sqlite_open_reports_a_corrupt_file_with_its_integrity_check() {
+    let path = std::env::temp_dir().join(format!("learn_corrupt_{}.sqlite3", std::process::id()));
+    std::fs::write(&path, vec![0x5a_u8; 8192]).unwrap();
+    let url = format!("sqlite://{}", path.display());
+
+    let err = storage::repository::Storage::sqlite(&url).await.err().expect("corrupt file must not open");
+    std::fs::remove_file(&path).ok();
+
+    let SqliteInitError::Corrupt { integrity, .. } = &err else {
+        panic!("expected a corruption error, got {err:?}");
+    };
+    assert!(integrity.starts_with("integrity check"));
+    assert!(err.to_string().contains("restore it from a backup"));
+}
+
+#[tokio::test]
+async fn sqlite_backup_snapshots_a_live_database_and_rotates() {
+    let root = std::env::temp_dir().join(format!("learn_backup_{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    let url = format!("sqlite://{}", root.join("live.sqlite3").display());
+    std::fs::File::create(root.join("live.sqlite3")).unwrap();
+    let storage = storage::repository::Storage::sqlite(&url).await.expect("open live file");
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Backed up",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    storage.decks.upsert_deck(&deck).await.unwrap();
+
+    let dir = root.join("backups");
+    let mut paths = Vec::new();
+    for minutes in 0..3 {
+        let now = fixed_now() + Duration::minutes(minutes);
+        paths.push(backup_with_rotation(&url, &dir, 2, now).await.expect("backup"));
+    }
+
+    assert!(!paths[0].exists());
+    assert!(paths[1].exists() && paths[2].exists());
+    let copy = storage::repository::Storage::sqlite(&format!("sqlite://{}", paths[2].display()))
+        .await
+        .expect("open backup");
+    let restored = copy.decks.get_deck(deck.id()).await.unwrap().expect("deck in backup");
+    assert_eq!(restored.name(), "Backed up");
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[tokio::test]
+async fn sqlite_app_settings_round_trip_appearance_and_audio() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_app_settings?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+    assert_eq!(repo.get_settings().await.unwrap(), None);
+
+    let mut draft = AppSettings::default().to_draft();
+    draft.theme = Some(ThemePreference::Dark);
+    draft.accent_color = Some(AccentColor::Purple);
+    draft.language = Some(AppLanguage::German);
+    draft.analytics_enabled = Some(true);
+    draft.autoplay_audio = Some(false);
+    draft.replay_audio_after_answer = Some(true);
+    draft.audio_delay_ms = Some(750);
+    draft.last_deck_id = Some(DeckId::new(7));
+    draft.day_cutoff_hour = Some(4);
+    draft.focus_mode = Some(true);
+    draft.ai_manual_price_micro_usd_per_1k = Some(2_500);
+    draft.key_bindings = Some(
+        KeyBindings::default()
+            .with_binding(KeyAction::NewCard, KeyCombo::command(BoundKey::Char('t'))),
+    );
+    let settings = draft.validate().unwrap();
+    repo.save_settings(&settings).await.unwrap();
+
+    let fetched = repo.get_settings().await.unwrap().expect("settings");
+    assert_eq!(fetched, settings);
+    assert_eq!(fetched.theme(), ThemePreference::Dark);
+    assert_eq!(fetched.audio_delay_ms(), 750);
+    assert_eq!(fetched.last_deck_id(), Some(DeckId::new(7)));
+    assert_eq!(fetched.day_cutoff_hour(), 4);
+    assert!(fetched.focus_mode());
+    assert_eq!(fetched.ai_manual_price_micro_usd_per_1k(), Some(2_500));
+    assert_eq!(
+        fetched.key_bindings().get(KeyAction::NewCard),
+        KeyCombo::command(BoundKey::Char('t'))
+    );
+}
+
+#[tokio::test]
+async fn sqlite_lists_ai_usage_records_in_range() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_ai_usage?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let now = fixed_now();
+    let mut ids = Vec::new();
+    for (offset, model) in [(0, "gpt-4.1-mini"), (1, "gpt-4.1"), (2, "gpt-4o-mini")] {
+        let id = repo
+            .insert_started(NewAiUsageRecord {
+                provider: "openai".into(),
+                model: model.into(),
+                created_at: now + Duration::hours(offset),
+            })
+            .await
+            .unwrap();
+        ids.push(id);
+    }
+    repo.update_completion(
+        ids[1],
+        AiUsageCompletion {
+            status: AiUsageStatus::Succeeded,
+            prompt_tokens: Some(120),
+            completion_tokens: Some(40),
+            total_tokens: Some(160),
+            cost_micro_usd: Some(90),
+        },
+    )
+    .await
+    .unwrap();
+
+    let records = repo
+        .list_between(now + Duration::hours(1), now + Duration::hours(3))
+        .await
+        .unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].model, "gpt-4.1");
+    assert_eq!(records[0].status, AiUsageStatus::Succeeded);
+    assert_eq!(records[0].prompt_tokens, Some(120));
+    assert_eq!(records[0].cost_micro_usd, Some(90));
+    assert_eq!(records[1].status, AiUsageStatus::Started);
+    assert_eq!(records[1].completion_tokens, None);
+}
+
+#[tokio::test]
+async fn sqlite_supports_due_new_and_logs() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_due_new?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let now = fixed_now();
+    let prompt = learn_core::model::content::Content::from_persisted(
+        "Q with media".to_string(),
+        Some(MediaId::new(42)),
+    )
+    .unwrap();
+    let answer =
+        learn_core::model::content::Content::from_persisted("A with media".to_string(), None)
+            .unwrap();
+    let mut card = Card::from_persisted(
+        CardId::new(1),
+        deck.id(),
+        CardKind::Basic,
+        prompt,
+        answer,
+        now,
+        now,
+        None,
+        CardPhase::New,
+        0,
+        0.0,
+        0.0,
+    )
+    .unwrap();
+    repo.upsert_card(&card).await.unwrap();
+
+    let new_cards = repo.new_cards(deck.id(), fixed_now(), 10).await.unwrap();
+    assert_eq!(new_cards.len(), 1);
+    assert_eq!(new_cards[0].prompt().media_id(), Some(MediaId::new(42)));
+    assert_eq!(new_cards[0].answer().media_id(), None);
+
+    let later = now + Duration::minutes(1);
+    let prompt2 =
+        learn_core::model::content::Content::from_persisted("Q2".to_string(), None).unwrap();
+    let answer2 =
+        learn_core::model::content::Content::from_persisted("A2".to_string(), None).unwrap();
+    let card2 = Card::from_persisted(
+        CardId::new(2),
+        deck.id(),
+        CardKind::Basic,
+        prompt2,
+        answer2,
+        later,
+        later,
+        None,
+        CardPhase::New,
+        0,
+        0.0,
+        0.0,
+    )
+    .unwrap();
+    repo.upsert_card(&card2).await.unwrap();
+
+    let new_cards = repo.new_cards(deck.id(), fixed_now(), 10).await.unwrap();
+    assert_eq!(new_cards.len(), 2);
+    assert_eq!(new_cards[0].id(), CardId::new(1));
+    assert_eq!(new_cards[1].id(), CardId::new(2));
+
+    let reviewed_at = now;
+    // Force the card to be due immediately by scheduling the next review in the past.
+    let outcome =
+        learn_core::model::ReviewOutcome::new(now - Duration::hours(1), 1.0, 2.0, 0.5, 0.5);
+    card.apply_review_with_phase(ReviewGrade::Good, &outcome, reviewed_at);
+    repo.upsert_card(&card).await.unwrap();
+
+    let due_cards = repo.due_cards(deck.id(), now, 10).await.unwrap();
+    assert_eq!(due_cards.len(), 1);
+
+    let card3 = Card::from_persisted(
+        CardId::new(3),
+        deck.id(),
+        CardKind::Basic,
+        learn_core::model::content::Content::from_persisted("Q3".to_string(), None).unwrap(),
+        learn_core::model::content::Content::from_persisted("A3".to_string(), None).unwrap(),
+        now,
+        now - Duration::hours(2),
+        Some(reviewed_at),
+        CardPhase::Reviewing,
+        1,
+        3.0,
+        4.0,
+    )
+    .unwrap();
+    repo.upsert_card(&card3).await.unwrap();
+
+    let due_cards = repo.due_cards(deck.id(), now, 10).await.unwrap();
+    assert_eq!(due_cards.len(), 2);
+    assert_eq!(due_cards[0].id(), CardId::new(3));
+    assert_eq!(due_cards[1].id(), CardId::new(1));
+    let due_times = repo.due_times(deck.id(), now).await.unwrap();
+    assert_eq!(due_times, vec![now - Duration::hours(2), now - Duration::hours(1)]);
+    let earlier = repo.due_times(deck.id(), now - Duration::hours(2)).await.unwrap();
+    assert_eq!(earlier.len(), 1);
+
+    let log = learn_core::model::ReviewLog::new(card.id(), ReviewGrade::Good, reviewed_at)
+        .with_answer_ms(Some(8_000));
+    let record = ReviewLogRecord::from_applied(deck.id(), &log, &outcome);
+    let id = repo.append_log(record).await.unwrap();
+    let logs = repo
+        .logs_for_card(deck.id(), card.id())
+        .await
+        .expect("logs");
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].id, Some(id));
+    assert_eq!(logs[0].grade, ReviewGrade::Good);
+    assert_eq!(logs[0].next_review_at, outcome.next_review);
+    assert_eq!(logs[0].answer_ms, Some(8_000));
+
+    let earlier = learn_core::model::ReviewLog::new(
+        card3.id(),
+        ReviewGrade::Again,
+        reviewed_at - Duration::days(1),
+    );
+    repo.append_log(ReviewLogRecord::from_applied(deck.id(), &earlier, &outcome))
+        .await
+        .unwrap();
+    let deck_logs = repo.logs_for_deck(deck.id()).await.expect("deck logs");
+    let order: Vec<CardId> = deck_logs.iter().map(|log| log.card_id).collect();
+    assert_eq!(order, vec![card3.id(), card.id()]);
+    assert_eq!(deck_logs[0].answer_ms, None);
+
+    // Pages follow insertion order, unlike `logs_for_deck`.
+    let first = repo.logs_for_deck_page(deck.id(), None, 1).await.unwrap();
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].card_id, card.id());
+    let second = repo.logs_for_deck_page(deck.id(), first[0].id, 1).await.unwrap();
+    assert_eq!(second[0].card_id, card3.id());
+    assert!(repo.logs_for_deck_page(deck.id(), second[0].id, 1).await.unwrap().is_empty());
+
+    for hours in [1, 2] {
+        let later = learn_core::model::ReviewLog::new(
+            card.id(),
+            ReviewGrade::Good,
+            reviewed_at + Duration::hours(hours),
+        );
+        repo.append_log(ReviewLogRecord::from_applied(deck.id(), &later, &outcome))
+            .await
+            .unwrap();
+    }
+    let pruned = repo.prune(deck.id(), ReviewLogPrune::KeepLastPerCard(2)).await.unwrap();
+    assert_eq!(pruned, 1);
+    let kept: Vec<_> = repo
+        .logs_for_card(deck.id(), card.id())
+        .await
+        .unwrap()
+        .iter()
+        .map(|log| log.reviewed_at)
+        .collect();
+    assert_eq!(kept, vec![reviewed_at + Duration::hours(1), reviewed_at + Duration::hours(2)]);
+    assert_eq!(repo.logs_for_card(deck.id(), card3.id()).await.unwrap().len(), 1);
+
+    let cutoff = ReviewLogPrune::OlderThan(reviewed_at + Duration::minutes(90));
+    assert_eq!(repo.prune(deck.id(), cutoff).await.unwrap(), 2);
+    let left = repo.logs_for_deck(deck.id()).await.unwrap();
+    assert_eq!(left.len(), 1);
+    assert_eq!(left[0].reviewed_at, reviewed_at + Duration::hours(2));
+}
+
+#[tokio::test]
+async fn sqlite_persists_session_summary() {
+    let repo =
+        SqliteRepository::connect("sqlite:file:memdb_session_summary?mode=memory&cache=shared")
+            .await
+            .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let now = fixed_now();
+    let logs = vec![
+        ReviewLog::new(CardId::new(1), ReviewGrade::Good, now),
+        ReviewLog::new(CardId::new(2), ReviewGrade::Again, now),
+        ReviewLog::new(CardId::new(3), ReviewGrade::Hard, now),
+    ];
+
+    let summary = SessionSummary::from_logs(deck.id(), now, now, &logs).unwrap();
+    let id = repo.append_summary(&summary).await.unwrap();
+
+    let stored = repo.get_summary(id).await.unwrap();
+    assert_eq!(stored.deck_id(), deck.id());
+    assert_eq!(stored.total_reviews(), 3);
+    assert_eq!(stored.good(), 1);
+    assert_eq!(stored.again(), 1);
+    assert_eq!(stored.hard(), 1);
+    assert!(!stored.is_cram());
+
+    assert!(!stored.is_ahead());
+
+    let cram_id = repo.append_summary(&summary.clone().with_cram(true)).await.unwrap();
+    assert!(repo.get_summary(cram_id).await.unwrap().is_cram());
+
+    let ahead_id = repo.append_summary(&summary.with_ahead(true)).await.unwrap();
+    let ahead = repo.get_summary(ahead_id).await.unwrap();
+    assert!(ahead.is_ahead());
+    assert!(!ahead.is_cram());
+}
+
+#[tokio::test]
+async fn sqlite_counts_practice_stats() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_counts?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let now = fixed_now();
+    let card1 = build_card(1, deck.id());
+    repo.upsert_card(&card1).await.unwrap();
+
+    let mut card2 = build_card(2, deck.id());
+    let outcome_due =
+        learn_core::model::ReviewOutcome::new(now - Duration::hours(1), 1.0, 2.0, 0.0, 1.0);
+    card2.apply_review_with_phase(ReviewGrade::Good, &outcome_due, now);
+    repo.upsert_card(&card2).await.unwrap();
+
+    let mut card3 = build_card(3, deck.id());
+    let outcome_future =
+        learn_core::model::ReviewOutcome::new(now + Duration::hours(4), 1.0, 2.0, 0.0, 1.0);
+    card3.apply_review_with_phase(ReviewGrade::Good, &outcome_future, now);
+    repo.upsert_card(&card3).await.unwrap();
+
+    let tag_language = TagName::new("Language").unwrap();
+    let tag_grammar = TagName::new("Grammar").unwrap();
+    repo.set_tags_for_card(deck.id(), card1.id(), std::slice::from_ref(&tag_language))
+        .await
+        .unwrap();
+    repo.set_tags_for_card(deck.id(), card2.id(), &[tag_language.clone(), tag_grammar.clone()])
+        .await
+        .unwrap();
+    repo.set_tags_for_card(deck.id(), card3.id(), std::slice::from_ref(&tag_grammar))
+        .await
+        .unwrap();
+
+    let counts = repo.deck_practice_counts(deck.id(), now).await.unwrap();
+    assert_eq!(
+        counts,
+        DeckPracticeCounts {
+            total: 3,
+            due: 1,
+            new: 1,
+            learning: 1,
+        }
+    );
+
+    let tag_counts = repo.list_tag_practice_counts(deck.id(), now).await.unwrap();
+    let language = tag_counts
+        .iter()
+        .find(|item| item.name == tag_language)
+        .unwrap();
+    assert_eq!(language.total, 2);
+    assert_eq!(language.new, 1);
+    assert_eq!(language.due, 1);
+
+    let grammar = tag_counts
+        .iter()
+        .find(|item| item.name == tag_grammar)
+        .unwrap();
+    assert_eq!(grammar.total, 2);
+    assert_eq!(grammar.new, 0);
+    assert_eq!(grammar.due, 1);
+}
+
+#[tokio::test]
+async fn sqlite_lists_deck_practice_counts() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_counts_multi?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck1 = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Deck 1",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    let deck2 = learn_core::model::Deck::new(
+        DeckId::new(2),
+        "Deck 2",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck1).await.unwrap();
+    repo.upsert_deck(&deck2).await.unwrap();
+
+    let now = fixed_now();
+    let card1 = build_card(1, deck1.id());
+    repo.upsert_card(&card1).await.unwrap();
+    let mut card2 = build_card(2, deck1.id());
+    let outcome_due =
+        learn_core::model::ReviewOutcome::new(now - Duration::hours(1), 1.0, 2.0, 0.0, 1.0);
+    card2.apply_review_with_phase(ReviewGrade::Good, &outcome_due, now);
+    repo.upsert_card(&card2).await.unwrap();
+
+    let card3 = build_card(3, deck2.id());
     repo.upsert_card(&card3).await.unwrap();
     let mut card4 = build_card(4, deck2.id());
     let outcome_future =
@@ -325,36 +1269,740 @@ async fn sqlite_lists_deck_practice_counts() {
     card4.apply_review_with_phase(ReviewGrade::Good, &outcome_future, now);
     repo.upsert_card(&card4).await.unwrap();
 
-    let rows = repo
-        .list_deck_practice_counts(&[deck1.id(), deck2.id()], now)
+    let rows = repo
+        .list_deck_practice_counts(&[deck1.id(), deck2.id()], now)
+        .await
+        .unwrap();
+    let mut by_deck = std::collections::HashMap::new();
+    for row in rows {
+        by_deck.insert(row.deck_id, row.counts);
+    }
+
+    assert_eq!(
+        by_deck.get(&deck1.id()),
+        Some(&DeckPracticeCounts {
+            total: 2,
+            due: 1,
+            new: 1,
+            learning: 1,
+        })
+    );
+    assert_eq!(
+        by_deck.get(&deck2.id()),
+        Some(&DeckPracticeCounts {
+            total: 2,
+            due: 0,
+            new: 1,
+            learning: 0,
+        })
+    );
+}
+
+#[tokio::test]
+async fn sqlite_lists_session_summaries_by_range() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_session_list?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let now = fixed_now();
+    let logs = vec![ReviewLog::new(CardId::new(1), ReviewGrade::Good, now)];
+
+    let summary1 = SessionSummary::from_logs(deck.id(), now, now, &logs).unwrap();
+    let summary2 =
+        SessionSummary::from_logs(deck.id(), now, now + Duration::days(1), &logs).unwrap();
+    let summary3 =
+        SessionSummary::from_logs(deck.id(), now, now + Duration::days(2), &logs).unwrap();
+
+    let id1 = repo.append_summary(&summary1).await.unwrap();
+    let id2 = repo.append_summary(&summary2).await.unwrap();
+    let id3 = repo.append_summary(&summary3).await.unwrap();
+
+    // Same completed_at as summary3 to validate the `id DESC` tie-breaker.
+    let summary4 =
+        SessionSummary::from_logs(deck.id(), now, now + Duration::days(2), &logs).unwrap();
+    let id4 = repo.append_summary(&summary4).await.unwrap();
+
+    let listed = repo
+        .list_summaries(
+            deck.id(),
+            Some(now + Duration::days(1)),
+            Some(now + Duration::days(2)),
+            10,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(listed.len(), 3);
+    assert!(listed[0].completed_at() >= listed[1].completed_at());
+    assert!(listed[1].completed_at() >= listed[2].completed_at());
+
+    assert_eq!(listed[0].completed_at(), now + Duration::days(2));
+    assert_eq!(listed[1].completed_at(), now + Duration::days(2));
+    assert_eq!(listed[2].completed_at(), now + Duration::days(1));
+
+    let rows = repo
+        .list_summary_rows(
+            deck.id(),
+            Some(now + Duration::days(1)),
+            Some(now + Duration::days(2)),
+            10,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(rows.len(), 3);
+
+    // Two summaries have the same completed_at (day 2); id DESC should put the later insert first.
+    assert_eq!(rows[0].summary.completed_at(), now + Duration::days(2));
+    assert_eq!(rows[1].summary.completed_at(), now + Duration::days(2));
+    assert_eq!(rows[2].summary.completed_at(), now + Duration::days(1));
+
+    assert_eq!(rows[0].id, id4);
+    assert_eq!(rows[1].id, id3);
+    assert_eq!(rows[2].id, id2);
+
+    // (Optional sanity) ensure the out-of-range summary1 is not included.
+    assert_ne!(rows[2].id, id1);
+}
+
+#[tokio::test]
+async fn sqlite_lists_latest_summary_rows() {
+    let repo =
+        SqliteRepository::connect("sqlite:file:memdb_latest_summary?mode=memory&cache=shared")
+            .await
+            .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck1 = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Deck 1",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    let deck2 = learn_core::model::Deck::new(
+        DeckId::new(2),
+        "Deck 2",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck1).await.unwrap();
+    repo.upsert_deck(&deck2).await.unwrap();
+
+    let now = fixed_now();
+    let logs = vec![ReviewLog::new(CardId::new(1), ReviewGrade::Good, now)];
+    let summary1 = SessionSummary::from_logs(deck1.id(), now, now, &logs).unwrap();
+    let summary2 =
+        SessionSummary::from_logs(deck1.id(), now, now + Duration::days(1), &logs).unwrap();
+    let earlier = now - Duration::days(1);
+    let summary3 = SessionSummary::from_logs(deck2.id(), earlier, earlier, &logs).unwrap();
+
+    let id1 = repo.append_summary(&summary1).await.unwrap();
+    let id2 = repo.append_summary(&summary2).await.unwrap();
+    let id3 = repo.append_summary(&summary3).await.unwrap();
+
+    let rows = repo
+        .list_latest_summary_rows(&[deck1.id(), deck2.id()])
+        .await
+        .unwrap();
+    let mut by_deck = std::collections::HashMap::new();
+    for row in rows {
+        by_deck.insert(row.summary.deck_id(), row.id);
+    }
+
+    assert_eq!(by_deck.get(&deck1.id()), Some(&id2));
+    assert_eq!(by_deck.get(&deck2.id()), Some(&id3));
+    assert_ne!(by_deck.get(&deck1.id()), Some(&id1));
+
+    let between = repo
+        .list_summaries_between(earlier, now + Duration::days(1))
+        .await
+        .unwrap();
+    assert_eq!(between, vec![summary3, summary1]);
+}
+
+#[tokio::test]
+async fn sqlite_tags_round_trip_and_cascade_on_card_delete() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_tags?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let verbs = TagName::new("Verbs").unwrap();
+    let created = repo.upsert_tag(deck.id(), &verbs).await.unwrap();
+    let again = repo.upsert_tag(deck.id(), &verbs).await.unwrap();
+    assert_eq!(created.id(), again.id());
+    assert_eq!(repo.list_tags_for_deck(deck.id()).await.unwrap().len(), 1);
+
+    let card1 = build_card(1, deck.id());
+    let card2 = build_card(2, deck.id());
+    repo.upsert_card(&card1).await.unwrap();
+    repo.upsert_card(&card2).await.unwrap();
+    let nouns = TagName::new("Nouns").unwrap();
+    repo.set_tags_for_card(deck.id(), card1.id(), &[verbs.clone(), nouns.clone()])
+        .await
+        .unwrap();
+
+    let rows = repo
+        .list_tags_for_cards(deck.id(), &[card1.id(), card2.id()])
+        .await
+        .unwrap();
+    let names: Vec<(CardId, &str)> = rows
+        .iter()
+        .map(|(card_id, tag)| (*card_id, tag.name().as_str()))
+        .collect();
+    assert_eq!(names, vec![(card1.id(), "Nouns"), (card1.id(), "Verbs")]);
+
+    repo.delete_card(deck.id(), card1.id()).await.unwrap();
+    let tagged = repo
+        .list_cards_by_tags(deck.id(), &[verbs, nouns])
+        .await
+        .unwrap();
+    assert!(tagged.is_empty());
+    let links: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM card_tags")
+        .fetch_one(repo.pool())
+        .await
+        .unwrap();
+    assert_eq!(links, 0);
+    // Tags themselves remain available for the deck.
+    assert_eq!(repo.list_tags_for_deck(deck.id()).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn sqlite_trashed_cards_keep_history_until_purged() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_trash?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let now = fixed_now();
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        now,
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+    let card = build_text_card(1, deck.id(), "Haus", "House");
+    repo.upsert_card(&card).await.unwrap();
+    repo.upsert_card(&build_text_card(2, deck.id(), "Baum", "Tree")).await.unwrap();
+    let tag = TagName::new("Nouns").unwrap();
+    repo.set_tags_for_card(deck.id(), card.id(), std::slice::from_ref(&tag))
+        .await
+        .unwrap();
+    let outcome = learn_core::model::ReviewOutcome::new(now, 1.0, 2.0, 0.0, 1.0);
+    let log = ReviewLog::new(card.id(), ReviewGrade::Good, now);
+    repo.append_log(ReviewLogRecord::from_applied(deck.id(), &log, &outcome))
+        .await
+        .unwrap();
+
+    repo.trash_card(deck.id(), card.id(), now).await.unwrap();
+    assert!(matches!(
+        repo.trash_card(deck.id(), card.id(), now).await,
+        Err(storage::repository::StorageError::NotFound)
+    ));
+    let listed = repo.list_cards(deck.id(), 10).await.unwrap();
+    assert_eq!(listed.iter().map(Card::id).collect::<Vec<_>>(), vec![CardId::new(2)]);
+    assert!(repo.get_cards(deck.id(), &[card.id()]).await.is_err());
+    assert_eq!(repo.deck_practice_counts(deck.id(), now).await.unwrap().total, 1);
+    assert!(repo.search_cards(deck.id(), "haus", 10).await.unwrap().is_empty());
+    assert!(repo.list_cards_by_tags(deck.id(), &[tag]).await.unwrap().is_empty());
+    assert!(!repo.prompt_exists(deck.id(), "Haus", None).await.unwrap());
+
+    let trashed = repo.list_trashed_cards(deck.id(), 10).await.unwrap();
+    assert_eq!(trashed.len(), 1);
+    assert_eq!(trashed[0].card.id(), card.id());
+    assert_eq!(trashed[0].deleted_at, now);
+    assert_eq!(repo.logs_for_card(deck.id(), card.id()).await.unwrap().len(), 1);
+
+    repo.restore_card(deck.id(), card.id()).await.unwrap();
+    assert!(matches!(
+        repo.restore_card(deck.id(), card.id()).await,
+        Err(storage::repository::StorageError::NotFound)
+    ));
+    assert_eq!(repo.list_cards(deck.id(), 10).await.unwrap().len(), 2);
+    assert_eq!(repo.list_tags_for_card(deck.id(), card.id()).await.unwrap().len(), 1);
+
+    repo.trash_card(deck.id(), card.id(), now).await.unwrap();
+    assert_eq!(repo.purge_trashed_cards(now).await.unwrap(), 0);
+    assert_eq!(repo.purge_trashed_cards(now + Duration::seconds(1)).await.unwrap(), 1);
+    assert!(repo.list_trashed_cards(deck.id(), 10).await.unwrap().is_empty());
+    assert!(repo.logs_for_card(deck.id(), card.id()).await.unwrap().is_empty());
+    assert_eq!(repo.list_cards(deck.id(), 10).await.unwrap().len(), 1);
+
+    let batch = [CardId::new(2), card.id()];
+    assert!(matches!(
+        repo.trash_cards(deck.id(), &batch, now).await,
+        Err(storage::repository::StorageError::NotFound)
+    ));
+    assert_eq!(repo.list_cards(deck.id(), 10).await.unwrap().len(), 1);
+    repo.trash_cards(deck.id(), &batch[..1], now).await.unwrap();
+    assert!(repo.list_cards(deck.id(), 10).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn sqlite_filters_cards_by_all_tags_and_untagged() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_tag_filters?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let card1 = build_card(1, deck.id());
+    let card2 = build_card(2, deck.id());
+    let card3 = build_card(3, deck.id());
+    for card in [&card1, &card2, &card3] {
+        repo.upsert_card(card).await.unwrap();
+    }
+    let verbs = TagName::new("Verbs").unwrap();
+    let nouns = TagName::new("Nouns").unwrap();
+    repo.set_tags_for_card(deck.id(), card1.id(), &[verbs.clone(), nouns.clone()])
+        .await
+        .unwrap();
+    repo.set_tags_for_card(deck.id(), card2.id(), std::slice::from_ref(&verbs))
+        .await
+        .unwrap();
+
+    let ids = |cards: Vec<Card>| cards.iter().map(Card::id).collect::<Vec<_>>();
+
+    let all = repo.list_cards_with_all_tags(deck.id(), &[], 10).await.unwrap();
+    assert_eq!(ids(all), vec![card3.id(), card2.id(), card1.id()]);
+
+    let both = repo
+        .list_cards_with_all_tags(deck.id(), &[verbs.clone(), nouns.clone(), verbs.clone()], 10)
+        .await
+        .unwrap();
+    assert_eq!(ids(both), vec![card1.id()]);
+
+    let with_verbs = repo
+        .list_cards_with_all_tags(deck.id(), std::slice::from_ref(&verbs), 1)
+        .await
+        .unwrap();
+    assert_eq!(ids(with_verbs), vec![card2.id()]);
+
+    let missing = repo
+        .list_cards_with_all_tags(deck.id(), &[TagName::new("Missing").unwrap()], 10)
+        .await
+        .unwrap();
+    assert!(missing.is_empty());
+
+    let untagged = repo.list_untagged_cards(deck.id(), 10).await.unwrap();
+    assert_eq!(ids(untagged), vec![card3.id()]);
+}
+
+#[tokio::test]
+async fn sqlite_lists_cards_by_due_date_and_difficulty_with_new_cards_last() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_card_order?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let new_card = build_card(1, deck.id());
+    repo.upsert_card(&new_card).await.unwrap();
+    for (id, due_days, difficulty) in [(2, 3, 4.0), (3, 1, 7.5), (4, 2, 2.0)] {
+        let mut card = build_card(id, deck.id());
+        let outcome = learn_core::model::ReviewOutcome::new(
+            fixed_now() + Duration::days(due_days),
+            1.0,
+            difficulty,
+            0.0,
+            1.0,
+        );
+        card.apply_review_with_phase(ReviewGrade::Good, &outcome, fixed_now());
+        repo.upsert_card(&card).await.unwrap();
+    }
+    repo.upsert_card(&build_card(5, deck.id())).await.unwrap();
+
+    let ids = |cards: Vec<Card>| cards.iter().map(|card| card.id().value()).collect::<Vec<_>>();
+
+    let by_due = repo
+        .list_cards_ordered(deck.id(), CardOrder::DueDate, 10)
+        .await
+        .unwrap();
+    assert_eq!(ids(by_due), vec![3, 4, 2, 1, 5]);
+
+    let by_difficulty = repo
+        .list_cards_ordered(deck.id(), CardOrder::Difficulty, 10)
+        .await
+        .unwrap();
+    assert_eq!(ids(by_difficulty), vec![3, 2, 4, 1, 5]);
+
+    let limited = repo
+        .list_cards_ordered(deck.id(), CardOrder::DueDate, 2)
+        .await
+        .unwrap();
+    assert_eq!(ids(limited), vec![3, 4]);
+}
+
+#[tokio::test]
+async fn sqlite_search_cards_ranks_prefix_matches_and_tracks_edits() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_search?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let cards = [
+        build_text_card(1, deck.id(), "Photosynthesis", "Plants turn light into sugar"),
+        build_text_card(2, deck.id(), "Photon", "A photon is a quantum of light, photo-emitted"),
+        build_text_card(3, deck.id(), "Mitochondria", "Powerhouse of the cell"),
+    ];
+    for card in &cards {
+        repo.upsert_card(card).await.unwrap();
+    }
+    let physics = learn_core::model::Deck::new(
+        DeckId::new(2),
+        "Physics",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&physics).await.unwrap();
+    let photoelectric =
+        build_text_card(4, physics.id(), "Photoelectric effect", "Light frees electrons");
+    repo.upsert_card(&photoelectric).await.unwrap();
+    let ids = |cards: Vec<Card>| cards.iter().map(Card::id).collect::<Vec<_>>();
+    let labeled = |matches: Vec<DeckCardMatch>| {
+        let mut labeled: Vec<_> =
+            matches.into_iter().map(|hit| (hit.card.id().value(), hit.deck_name)).collect();
+        labeled.sort();
+        labeled
+    };
+
+    let photo = repo.search_cards(deck.id(), "PHOTO", 10).await.unwrap();
+    assert_eq!(ids(photo)[0], CardId::new(2));
+    let both = repo.search_cards(deck.id(), "light pho", 10).await.unwrap();
+    assert_eq!(both.len(), 2);
+    let everywhere = repo.search_all_decks("light pho", 10).await.unwrap();
+    assert_eq!(
+        labeled(everywhere),
+        vec![(1, "Test".to_string()), (2, "Test".to_string()), (4, "Physics".to_string())]
+    );
+    assert!(repo.search_cards(deck.id(), "hondria", 10).await.unwrap().is_empty());
+    assert!(repo.search_cards(deck.id(), "  \"*  ", 10).await.unwrap().is_empty());
+
+    repo.upsert_card(&build_text_card(3, deck.id(), "Ribosome", "Builds proteins"))
+        .await
+        .unwrap();
+    assert!(repo.search_cards(deck.id(), "mito", 10).await.unwrap().is_empty());
+    let edited = repo.search_cards(deck.id(), "ribo", 10).await.unwrap();
+    assert_eq!(ids(edited), vec![CardId::new(3)]);
+
+    repo.delete_card(deck.id(), CardId::new(3)).await.unwrap();
+    assert!(repo.search_cards(deck.id(), "ribo", 10).await.unwrap().is_empty());
+
+    // Without the FTS table the LIKE fallback still finds substrings, prompt hits first.
+    for sql in [
+        "DROP TRIGGER cards_fts_insert",
+        "DROP TRIGGER cards_fts_delete",
+        "DROP TRIGGER cards_fts_update",
+        "DROP TABLE cards_fts",
+    ] {
+        sqlx::query(sql).execute(repo.pool()).await.unwrap();
+    }
+    let fallback = repo.search_cards(deck.id(), "photo", 10).await.unwrap();
+    assert_eq!(ids(fallback), vec![CardId::new(2), CardId::new(1)]);
+    let fallback = repo.search_cards(deck.id(), "light", 1).await.unwrap();
+    assert_eq!(fallback.len(), 1);
+    let fallback = repo.search_all_decks("electro", 10).await.unwrap();
+    assert_eq!(labeled(fallback), vec![(4, "Physics".to_string())]);
+}
+
+#[tokio::test]
+async fn sqlite_persists_cloze_kind() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_cloze?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let source = learn_core::model::content::Content::from_persisted(
+        "{{c1::H}}2{{c2::O}}".to_string(),
+        None,
+    )
+    .unwrap();
+    let expansions = learn_core::model::expand_cloze(&source).unwrap();
+    let now = fixed_now();
+    let mut ids = Vec::new();
+    for (offset, expansion) in expansions.into_iter().enumerate() {
+        let card = Card::new(
+            CardId::new(offset as u64 + 1),
+            deck.id(),
+            CardKind::Cloze {
+                text: source.text().to_string(),
+                indices: vec![expansion.index],
+            },
+            expansion.prompt,
+            expansion.answer,
+            now,
+            now,
+        )
+        .unwrap();
+        repo.upsert_card(&card).await.unwrap();
+        ids.push(card.id());
+    }
+    repo.upsert_card(&build_card(3, deck.id())).await.unwrap();
+    ids.push(CardId::new(3));
+
+    let fetched = repo.get_cards(deck.id(), &ids).await.unwrap();
+    assert_eq!(fetched[0].prompt().text(), "[...]2O");
+    assert_eq!(
+        fetched[1].kind(),
+        &CardKind::Cloze {
+            text: "{{c1::H}}2{{c2::O}}".to_string(),
+            indices: vec![2],
+        }
+    );
+    assert_eq!(fetched[2].kind(), &CardKind::Basic);
+}
+
+#[tokio::test]
+async fn sqlite_persists_card_audio() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_audio?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let prompt_clip = AudioClip::from_persisted(
+        MediaUri::from_file("/media/hola.mp3").unwrap(),
+        MediaHash::new("hash-prompt"),
+    )
+    .unwrap();
+    let answer_clip = AudioClip::from_persisted(
+        MediaUri::from_url("https://example.com/audio/hello.wav").unwrap(),
+        MediaHash::new("hash-answer"),
+    )
+    .unwrap();
+    let mut card = build_card(1, deck.id());
+    card.set_audio(CardSide::Prompt, Some(prompt_clip.clone()));
+    card.set_audio(CardSide::Answer, Some(answer_clip.clone()));
+    repo.upsert_card(&card).await.unwrap();
+
+    let listed = repo.list_cards(deck.id(), 10).await.unwrap();
+    assert_eq!(listed[0].prompt().audio(), Some(&prompt_clip));
+    assert_eq!(listed[0].answer().audio(), Some(&answer_clip));
+
+    card.set_audio(CardSide::Answer, None);
+    repo.upsert_card(&card).await.unwrap();
+    let fetched = repo.get_cards(deck.id(), &[card.id()]).await.unwrap();
+    assert_eq!(fetched[0].prompt().audio(), Some(&prompt_clip));
+    assert!(fetched[0].answer().audio().is_none());
+}
+
+#[tokio::test]
+async fn sqlite_media_round_trips_and_dedupes_by_checksum() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_media?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let record = NewMediaRecord {
+        format: ImageFormat::Png,
+        meta: ImageMeta::new(4, 3).unwrap(),
+        checksum: MediaHash::new("sha256:aa"),
+        bytes: vec![1, 2, 3],
+        created_at: fixed_now(),
+    };
+    let id = repo.insert_media(record.clone()).await.unwrap();
+    assert_eq!(repo.insert_media(record.clone()).await.unwrap(), id);
+
+    let stored = repo.get_media(id).await.unwrap().unwrap();
+    assert_eq!(stored.format, ImageFormat::Png);
+    assert_eq!(stored.meta, record.meta);
+    assert_eq!(stored.bytes, record.bytes);
+    assert_eq!(stored.checksum, record.checksum);
+    assert!(repo.get_media(MediaId::new(99)).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn sqlite_blobs_round_trip_by_hash_and_gc_keeps_referenced_media() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_blobs?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let clip_hash = MediaHash::new("sha256:clip");
+    let orphan_hash = MediaHash::new("sha256:orphan");
+    repo.put_blob(&clip_hash, vec![7, 7], fixed_now()).await.unwrap();
+    repo.put_blob(&clip_hash, vec![0], fixed_now()).await.unwrap();
+    repo.put_blob(&orphan_hash, vec![9], fixed_now()).await.unwrap();
+    assert!(repo.exists(&clip_hash).await.unwrap());
+    assert_eq!(repo.get_blob(&clip_hash).await.unwrap(), Some(vec![7, 7]));
+    assert!(!repo.exists(&MediaHash::new("sha256:none")).await.unwrap());
+
+    let image_hash = MediaHash::new("sha256:image");
+    let image_id = repo
+        .insert_media(NewMediaRecord {
+            format: ImageFormat::Png,
+            meta: ImageMeta::new(4, 3).unwrap(),
+            checksum: image_hash.clone(),
+            bytes: vec![1, 2, 3],
+            created_at: fixed_now(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(repo.get_blob(&image_hash).await.unwrap(), Some(vec![1, 2, 3]));
+    assert!(repo.get_media(image_id).await.unwrap().is_some());
+
+    let prompt = ContentDraft::text_only("Flag?")
+        .with_media_id(Some(image_id))
+        .validate(fixed_now(), None, None)
+        .unwrap();
+    let answer = ContentDraft::text_only("Japan")
+        .validate(fixed_now(), None, None)
+        .unwrap();
+    let mut card = Card::new(
+        CardId::new(1),
+        deck.id(),
+        CardKind::Basic,
+        prompt,
+        answer,
+        fixed_now(),
+        fixed_now(),
+    )
+    .unwrap();
+    let clip = AudioClip::from_persisted(
+        MediaUri::from_file("/media/clip.mp3").unwrap(),
+        clip_hash.clone(),
+    )
+    .unwrap();
+    card.set_audio(CardSide::Answer, Some(clip));
+    repo.upsert_card(&card).await.unwrap();
+
+    assert_eq!(repo.gc_unreferenced().await.unwrap(), 1);
+    assert!(!repo.exists(&orphan_hash).await.unwrap());
+    assert!(repo.exists(&clip_hash).await.unwrap());
+    assert!(repo.exists(&image_hash).await.unwrap());
+
+    repo.delete_card(deck.id(), card.id()).await.unwrap();
+    assert_eq!(repo.gc_unreferenced().await.unwrap(), 2);
+    assert!(!repo.exists(&image_hash).await.unwrap());
+}
+
+#[tokio::test]
+async fn sqlite_lists_note_cards_and_persists_note_id() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_note_cards?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let note = Some(NoteId::new(1));
+    repo.upsert_card(&build_text_card(1, deck.id(), "Hund", "Dog").with_note_id(note))
         .await
         .unwrap();
-    let mut by_deck = std::collections::HashMap::new();
-    for row in rows {
-        by_deck.insert(row.deck_id, row.counts);
-    }
+    repo.upsert_card(&build_text_card(2, deck.id(), "Dog", "Hund").with_note_id(note))
+        .await
+        .unwrap();
+    repo.upsert_card(&build_card(3, deck.id())).await.unwrap();
 
+    let cards = repo.list_note_cards(deck.id(), NoteId::new(1)).await.unwrap();
     assert_eq!(
-        by_deck.get(&deck1.id()),
-        Some(&DeckPracticeCounts {
-            total: 2,
-            due: 1,
-            new: 1,
-        })
-    );
-    assert_eq!(
-        by_deck.get(&deck2.id()),
-        Some(&DeckPracticeCounts {
-            total: 2,
-            due: 0,
-            new: 1,
-        })
+        cards.iter().map(Card::id).collect::<Vec<_>>(),
+        vec![CardId::new(1), CardId::new(2)]
     );
+    assert!(cards.iter().all(|card| card.note_id() == note));
+
+    let plain = repo.get_cards(deck.id(), &[CardId::new(3)]).await.unwrap();
+    assert_eq!(plain[0].note_id(), None);
 }
 
 #[tokio::test]
-async fn sqlite_lists_session_summaries_by_range() {
-    let repo = SqliteRepository::connect("sqlite:file:memdb_session_list?mode=memory&cache=shared")
+async fn sqlite_insert_new_cards_is_all_or_nothing() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_bulk_insert?mode=memory&cache=shared")
         .await
         .expect("connect");
     repo.migrate().await.expect("migrate");
@@ -369,116 +2017,490 @@ async fn sqlite_lists_session_summaries_by_range() {
     .unwrap();
     repo.upsert_deck(&deck).await.unwrap();
 
-    let now = fixed_now();
-    let logs = vec![ReviewLog::new(CardId::new(1), ReviewGrade::Good, now)];
+    let record = |deck_id: DeckId, prompt: &str| NewCardRecord {
+        deck_id,
+        kind: CardKind::Basic,
+        note_id: None,
+        prompt_text: prompt.to_string(),
+        prompt_media_id: None,
+        answer_text: "A".to_string(),
+        answer_media_id: None,
+        phase: CardPhase::New,
+        created_at: fixed_now(),
+        next_review_at: fixed_now(),
+        last_review_at: None,
+        review_count: 0,
+        stability: None,
+        difficulty: None,
+        extra_text: None,
+        source: None,
+    };
 
-    let summary1 = SessionSummary::from_logs(deck.id(), now, now, &logs).unwrap();
-    let summary2 =
-        SessionSummary::from_logs(deck.id(), now, now + Duration::days(1), &logs).unwrap();
-    let summary3 =
-        SessionSummary::from_logs(deck.id(), now, now + Duration::days(2), &logs).unwrap();
+    let ids = repo
+        .insert_new_cards(vec![record(deck.id(), "One"), record(deck.id(), "Two")])
+        .await
+        .unwrap();
+    assert_eq!(ids.len(), 2);
+    let fetched = repo.get_cards(deck.id(), &ids).await.unwrap();
+    assert_eq!(fetched[1].prompt().text(), "Two");
 
-    let id1 = repo.append_summary(&summary1).await.unwrap();
-    let id2 = repo.append_summary(&summary2).await.unwrap();
-    let id3 = repo.append_summary(&summary3).await.unwrap();
+    // The missing deck violates the foreign key, so the whole batch rolls back.
+    let result = repo
+        .insert_new_cards(vec![record(deck.id(), "Three"), record(DeckId::new(99), "Four")])
+        .await;
+    assert!(result.is_err());
+    assert_eq!(repo.list_cards(deck.id(), 10).await.unwrap().len(), 2);
+}
 
-    // Same completed_at as summary3 to validate the `id DESC` tie-breaker.
-    let summary4 =
-        SessionSummary::from_logs(deck.id(), now, now + Duration::days(2), &logs).unwrap();
-    let id4 = repo.append_summary(&summary4).await.unwrap();
+#[tokio::test]
+async fn sqlite_card_extra_round_trips_when_set_and_omitted() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_card_extra?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
 
-    let listed = repo
-        .list_summaries(
-            deck.id(),
-            Some(now + Duration::days(1)),
-            Some(now + Duration::days(2)),
-            10,
-        )
+    let ipa = Content::from_persisted("/ˈhʊnt/".to_string(), None).unwrap();
+    let with_extra = build_card(1, deck.id()).with_extra(Some(ipa.clone()));
+    repo.upsert_card(&with_extra).await.unwrap();
+    repo.upsert_card(&build_card(2, deck.id())).await.unwrap();
+    let inserted = repo
+        .insert_new_card(NewCardRecord {
+            deck_id: deck.id(),
+            kind: CardKind::Basic,
+            note_id: None,
+            prompt_text: "Katze".to_string(),
+            prompt_media_id: None,
+            answer_text: "Cat".to_string(),
+            answer_media_id: None,
+            phase: CardPhase::New,
+            created_at: fixed_now(),
+            next_review_at: fixed_now(),
+            last_review_at: None,
+            review_count: 0,
+            stability: None,
+            difficulty: None,
+            extra_text: Some("die Katze".to_string()),
+            source: Some(CardSource::Csv),
+        })
         .await
         .unwrap();
 
-    assert_eq!(listed.len(), 3);
-    assert!(listed[0].completed_at() >= listed[1].completed_at());
-    assert!(listed[1].completed_at() >= listed[2].completed_at());
+    let fetched = repo
+        .get_cards(deck.id(), &[CardId::new(1), CardId::new(2), inserted])
+        .await
+        .unwrap();
+    assert_eq!(fetched[0], with_extra);
+    assert!(fetched[1].extra().is_none());
+    assert_eq!(fetched[2].extra().map(Content::text), Some("die Katze"));
+    assert_eq!((fetched[1].source(), fetched[2].source()), (None, Some(CardSource::Csv)));
 
-    assert_eq!(listed[0].completed_at(), now + Duration::days(2));
-    assert_eq!(listed[1].completed_at(), now + Duration::days(2));
-    assert_eq!(listed[2].completed_at(), now + Duration::days(1));
+    repo.upsert_card(&with_extra.with_extra(None)).await.unwrap();
+    let cleared = repo.get_cards(deck.id(), &[CardId::new(1)]).await.unwrap();
+    assert!(cleared[0].extra().is_none());
+}
 
-    let rows = repo
-        .list_summary_rows(
-            deck.id(),
-            Some(now + Duration::days(1)),
-            Some(now + Duration::days(2)),
-            10,
+#[tokio::test]
+async fn sqlite_move_card_relocates_logs_and_tags() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_move_card?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let now = fixed_now();
+    for id in [1, 2] {
+        let deck = learn_core::model::Deck::new(
+            DeckId::new(id),
+            format!("Test {id}"),
+            None,
+            DeckSettings::default_for_adhd(),
+            now,
         )
+        .unwrap();
+        repo.upsert_deck(&deck).await.unwrap();
+    }
+    let (from, to) = (DeckId::new(1), DeckId::new(2));
+
+    let card = build_card(1, from).with_note_id(Some(NoteId::new(1)));
+    repo.upsert_card(&card).await.unwrap();
+    let verbs = TagName::new("Verbs").unwrap();
+    repo.upsert_tag(to, &verbs).await.unwrap();
+    repo.set_tags_for_card(from, card.id(), &[verbs.clone(), TagName::new("Nouns").unwrap()])
+        .await
+        .unwrap();
+    let outcome = learn_core::model::ReviewOutcome::new(now, 1.0, 2.0, 0.0, 1.0);
+    let log = ReviewLog::new(card.id(), ReviewGrade::Good, now);
+    repo.append_log(ReviewLogRecord::from_applied(from, &log, &outcome))
         .await
         .unwrap();
 
-    assert_eq!(rows.len(), 3);
+    assert!(matches!(
+        repo.move_card(card.id(), from, DeckId::new(9)).await,
+        Err(storage::repository::StorageError::NotFound)
+    ));
+    assert!(matches!(
+        repo.move_card(card.id(), to, from).await,
+        Err(storage::repository::StorageError::NotFound)
+    ));
+    repo.move_card(card.id(), from, from).await.unwrap();
+    assert_eq!(repo.list_tags_for_card(from, card.id()).await.unwrap().len(), 2);
 
-    // Two summaries have the same completed_at (day 2); id DESC should put the later insert first.
-    assert_eq!(rows[0].summary.completed_at(), now + Duration::days(2));
-    assert_eq!(rows[1].summary.completed_at(), now + Duration::days(2));
-    assert_eq!(rows[2].summary.completed_at(), now + Duration::days(1));
+    repo.move_card(card.id(), from, to).await.unwrap();
 
-    assert_eq!(rows[0].id, id4);
-    assert_eq!(rows[1].id, id3);
-    assert_eq!(rows[2].id, id2);
+    let moved = repo.get_cards(to, &[card.id()]).await.unwrap();
+    assert_eq!(moved[0].deck_id(), to);
+    assert_eq!(moved[0].note_id(), None);
+    assert_eq!(repo.logs_for_card(to, card.id()).await.unwrap().len(), 1);
+    assert!(repo.logs_for_card(from, card.id()).await.unwrap().is_empty());
+    let tags = repo.list_tags_for_card(to, card.id()).await.unwrap();
+    assert_eq!(tags.len(), 2);
+    assert!(tags.iter().all(|tag| tag.deck_id() == to));
+    assert!(repo.list_tags_for_card(from, card.id()).await.unwrap().is_empty());
+}
 
-    // (Optional sanity) ensure the out-of-range summary1 is not included.
-    assert_ne!(rows[2].id, id1);
+#[tokio::test]
+async fn sqlite_reset_card_learning_resets_one_card_and_optionally_its_logs() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_reset_card?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let now = fixed_now();
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        now,
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+    let outcome = learn_core::model::ReviewOutcome::new(now, 1.0, 2.0, 0.0, 1.0);
+    for id in [1, 2] {
+        let mut card = build_card(id, deck.id());
+        card.apply_review(&outcome, now);
+        repo.upsert_card(&card).await.unwrap();
+        let log = ReviewLog::new(card.id(), ReviewGrade::Good, now);
+        repo.append_log(ReviewLogRecord::from_applied(deck.id(), &log, &outcome))
+            .await
+            .unwrap();
+    }
+    let (reset_id, other_id) = (CardId::new(1), CardId::new(2));
+
+    assert!(matches!(
+        repo.reset_card_learning(DeckId::new(9), reset_id, now, true).await,
+        Err(storage::repository::StorageError::NotFound)
+    ));
+    repo.reset_card_learning(deck.id(), reset_id, now, false).await.unwrap();
+    assert_eq!(repo.logs_for_card(deck.id(), reset_id).await.unwrap().len(), 1);
+    repo.reset_card_learning(deck.id(), reset_id, now, true).await.unwrap();
+
+    let cards = repo.get_cards(deck.id(), &[reset_id, other_id]).await.unwrap();
+    assert_eq!(cards[0].phase(), CardPhase::New);
+    assert_eq!(cards[0].review_count(), 0);
+    assert_eq!(cards[1].review_count(), 1);
+    assert!(repo.logs_for_card(deck.id(), reset_id).await.unwrap().is_empty());
+    assert_eq!(repo.logs_for_card(deck.id(), other_id).await.unwrap().len(), 1);
 }
 
 #[tokio::test]
-async fn sqlite_lists_latest_summary_rows() {
+async fn sqlite_counts_cards_introduced_since_a_cutoff() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_introduced?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let now = fixed_now();
+    let yesterday = now - chrono::Duration::days(1);
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Test",
+        None,
+        DeckSettings::default_for_adhd(),
+        now,
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+    let outcome = learn_core::model::ReviewOutcome::new(now, 1.0, 2.0, 0.0, 1.0);
+    // Card 1 was introduced yesterday and reviewed again today; 2 and 3 are new today.
+    let reviews = [(1, yesterday, true), (1, now, false), (2, now, true), (3, now, true)];
+    for (id, reviewed_at, first_review) in reviews {
+        let card = build_card(id, deck.id());
+        repo.upsert_card(&card).await.unwrap();
+        let log = ReviewLog::new(card.id(), ReviewGrade::Good, reviewed_at)
+            .with_first_review(first_review);
+        repo.append_log(ReviewLogRecord::from_applied(deck.id(), &log, &outcome))
+            .await
+            .unwrap();
+    }
+
+    let since = now - chrono::Duration::hours(1);
+    assert_eq!(repo.count_introduced_since(deck.id(), since).await.unwrap(), 2);
+    assert_eq!(repo.count_introduced_since(deck.id(), yesterday).await.unwrap(), 3);
+    assert_eq!(repo.count_introduced_since(DeckId::new(9), since).await.unwrap(), 0);
+
+    // Keeping only the newest log leaves card 1 with today's review, which is not its first.
+    repo.prune(deck.id(), ReviewLogPrune::KeepLastPerCard(1)).await.unwrap();
+    assert_eq!(repo.count_introduced_since(deck.id(), since).await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn sqlite_duplicate_deck_copies_fresh_cards_and_tags() {
     let repo =
-        SqliteRepository::connect("sqlite:file:memdb_latest_summary?mode=memory&cache=shared")
+        SqliteRepository::connect("sqlite:file:memdb_duplicate_deck?mode=memory&cache=shared")
             .await
             .expect("connect");
     repo.migrate().await.expect("migrate");
 
-    let deck1 = learn_core::model::Deck::new(
+    let now = fixed_now();
+    let deck = learn_core::model::Deck::new(
         DeckId::new(1),
-        "Deck 1",
+        "Template",
         None,
         DeckSettings::default_for_adhd(),
-        fixed_now(),
+        now,
     )
     .unwrap();
-    let deck2 = learn_core::model::Deck::new(
-        DeckId::new(2),
-        "Deck 2",
+    repo.upsert_deck(&deck).await.unwrap();
+
+    let mut card = build_card(1, deck.id()).with_note_id(Some(NoteId::new(1)));
+    let outcome = learn_core::model::ReviewOutcome::new(now, 1.0, 2.0, 0.0, 1.0);
+    card.apply_review_with_phase(ReviewGrade::Good, &outcome, now);
+    let clip = AudioClip::from_persisted(
+        MediaUri::from_file("/media/clip.mp3").unwrap(),
+        MediaHash::new("hash-clip"),
+    )
+    .unwrap();
+    card.set_audio(CardSide::Prompt, Some(clip));
+    repo.upsert_card(&card).await.unwrap();
+    let verbs = TagName::new("Verbs").unwrap();
+    repo.set_tags_for_card(deck.id(), card.id(), std::slice::from_ref(&verbs))
+        .await
+        .unwrap();
+
+    let later = now + Duration::days(1);
+    let mut record = storage::repository::NewDeckRecord::from_deck(&deck);
+    record.name = "Copy".to_string();
+    record.created_at = later;
+
+    assert!(matches!(
+        repo.duplicate_deck(DeckId::new(9), record.clone()).await,
+        Err(storage::repository::StorageError::NotFound)
+    ));
+    let copy_id = repo.duplicate_deck(deck.id(), record).await.unwrap();
+
+    let copies = repo.list_cards(copy_id, 10).await.unwrap();
+    assert_eq!(copies.len(), 1);
+    let copy = &copies[0];
+    assert_ne!(copy.id(), card.id());
+    assert_eq!(copy.phase(), CardPhase::New);
+    assert_eq!(copy.review_count(), 0);
+    assert_eq!(copy.created_at(), later);
+    assert_eq!(copy.note_id(), None);
+    assert_eq!(copy.prompt().audio(), card.prompt().audio());
+    let tags = repo.list_tags_for_card(copy_id, copy.id()).await.unwrap();
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].name(), &verbs);
+    assert_eq!(tags[0].deck_id(), copy_id);
+    assert_eq!(repo.get_cards(deck.id(), &[card.id()]).await.unwrap()[0].review_count(), 1);
+}
+
+#[tokio::test]
+async fn sqlite_merge_decks_moves_cards_and_archives_the_source() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_merge_decks?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let now = fixed_now();
+    for id in [1, 2] {
+        let deck = learn_core::model::Deck::new(
+            DeckId::new(id),
+            format!("Merge {id}"),
+            None,
+            DeckSettings::default_for_adhd(),
+            now,
+        )
+        .unwrap();
+        repo.upsert_deck(&deck).await.unwrap();
+    }
+    let (source, dest) = (DeckId::new(1), DeckId::new(2));
+
+    let mut reviewed = build_text_card(1, source, "Hola", "Hello");
+    let outcome = learn_core::model::ReviewOutcome::new(now, 1.0, 2.0, 0.0, 1.0);
+    reviewed.apply_review_with_phase(ReviewGrade::Good, &outcome, now);
+    repo.upsert_card(&reviewed).await.unwrap();
+    repo.upsert_card(&build_text_card(2, source, "Gato", "Cat")).await.unwrap();
+    repo.upsert_card(&build_text_card(3, dest, " gato ", "Cat")).await.unwrap();
+    let verbs = TagName::new("Verbs").unwrap();
+    repo.set_tags_for_card(source, reviewed.id(), std::slice::from_ref(&verbs))
+        .await
+        .unwrap();
+    let log = ReviewLog::new(reviewed.id(), ReviewGrade::Good, now);
+    repo.append_log(ReviewLogRecord::from_applied(source, &log, &outcome))
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        repo.merge_decks(source, DeckId::new(9), MergeConflict::Skip).await,
+        Err(storage::repository::StorageError::NotFound)
+    ));
+    let counts = repo.merge_decks(source, dest, MergeConflict::Skip).await.unwrap();
+
+    assert_eq!(counts, DeckMergeCounts { moved: 1, skipped: 1 });
+    let moved = repo.get_cards(dest, &[reviewed.id()]).await.unwrap();
+    assert_eq!(moved[0].review_count(), 1);
+    assert_eq!(moved[0].next_review_at(), reviewed.next_review_at());
+    assert_eq!(repo.logs_for_card(dest, reviewed.id()).await.unwrap().len(), 1);
+    let tags = repo.list_tags_for_card(dest, reviewed.id()).await.unwrap();
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].deck_id(), dest);
+    assert_eq!(repo.list_cards(source, 10).await.unwrap().len(), 1);
+    assert!(repo.get_deck(source).await.unwrap().unwrap().is_archived());
+
+    let counts = repo.merge_decks(source, dest, MergeConflict::KeepBoth).await.unwrap();
+    assert_eq!(counts, DeckMergeCounts { moved: 1, skipped: 0 });
+    assert_eq!(repo.list_cards(dest, 10).await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn sqlite_session_progress_round_trips_and_replaces_per_deck() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_session_progress?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Progress",
         None,
         DeckSettings::default_for_adhd(),
         fixed_now(),
     )
     .unwrap();
-    repo.upsert_deck(&deck1).await.unwrap();
-    repo.upsert_deck(&deck2).await.unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+    assert!(repo.get_progress(deck.id()).await.unwrap().is_none());
 
     let now = fixed_now();
-    let logs = vec![ReviewLog::new(CardId::new(1), ReviewGrade::Good, now)];
-    let summary1 = SessionSummary::from_logs(deck1.id(), now, now, &logs).unwrap();
-    let summary2 =
-        SessionSummary::from_logs(deck1.id(), now, now + Duration::days(1), &logs).unwrap();
-    let earlier = now - Duration::days(1);
-    let summary3 = SessionSummary::from_logs(deck2.id(), earlier, earlier, &logs).unwrap();
+    let answer = ReviewLogRecord {
+        id: None,
+        deck_id: deck.id(),
+        card_id: CardId::new(2),
+        grade: ReviewGrade::Hard,
+        reviewed_at: now,
+        elapsed_days: 0.5,
+        scheduled_days: 1.0,
+        stability: 1.5,
+        difficulty: 6.0,
+        next_review_at: now + Duration::days(1),
+        answer_ms: Some(4_250),
+        first_review: true,
+    };
+    let pending = vec![
+        PendingSessionCard { card_id: CardId::new(3), next_review_at: now },
+        PendingSessionCard { card_id: CardId::new(1), next_review_at: now - Duration::hours(1) },
+    ];
+    let progress = SessionProgressRecord {
+        deck_id: deck.id(),
+        started_at: now,
+        is_cram: true,
+        is_ahead: false,
+        answered: vec![answer],
+        pending: pending.clone(),
+    };
+    repo.save_progress(&progress).await.unwrap();
 
-    let id1 = repo.append_summary(&summary1).await.unwrap();
-    let id2 = repo.append_summary(&summary2).await.unwrap();
-    let id3 = repo.append_summary(&summary3).await.unwrap();
+    let loaded = repo.get_progress(deck.id()).await.unwrap().expect("saved");
+    assert_eq!(loaded.started_at, now);
+    assert!(loaded.is_cram);
+    assert!(!loaded.is_ahead);
+    assert_eq!(loaded.pending, pending);
+    assert_eq!(loaded.answered.len(), 1);
+    assert_eq!(loaded.answered[0].card_id, CardId::new(2));
+    assert_eq!(loaded.answered[0].grade, ReviewGrade::Hard);
+    assert!((loaded.answered[0].stability - 1.5).abs() < f64::EPSILON);
+    assert_eq!(loaded.answered[0].next_review_at, now + Duration::days(1));
+    assert_eq!(loaded.answered[0].answer_ms, Some(4_250));
+    assert!(loaded.answered[0].first_review);
 
-    let rows = repo
-        .list_latest_summary_rows(&[deck1.id(), deck2.id()])
+    let shorter = SessionProgressRecord {
+        is_cram: false,
+        is_ahead: true,
+        answered: Vec::new(),
+        pending: pending[1..].to_vec(),
+        ..progress
+    };
+    repo.save_progress(&shorter).await.unwrap();
+    let loaded = repo.get_progress(deck.id()).await.unwrap().expect("saved");
+    assert!(!loaded.is_cram);
+    assert!(loaded.is_ahead);
+    assert!(loaded.answered.is_empty());
+    assert_eq!(loaded.pending, pending[1..].to_vec());
+
+    repo.clear_progress(deck.id()).await.unwrap();
+    assert!(repo.get_progress(deck.id()).await.unwrap().is_none());
+    repo.clear_progress(deck.id()).await.unwrap();
+}
+
+#[tokio::test]
+async fn doctor_gives_a_freshly_migrated_database_a_clean_bill() {
+    let repo = SqliteRepository::connect("sqlite:file:memdb_doctor?mode=memory&cache=shared")
+        .await
+        .expect("connect");
+    repo.migrate().await.expect("migrate");
+    let deck = learn_core::model::Deck::new(
+        DeckId::new(1),
+        "Checked",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    repo.upsert_deck(&deck).await.unwrap();
+    repo.upsert_card(&build_card(1, deck.id())).await.unwrap();
+
+    let report = storage::sqlite::doctor::check(&repo).await.expect("check");
+
+    assert!(report.is_healthy(), "{report:?}");
+    assert_eq!(report.integrity, vec!["ok".to_string()]);
+    assert_eq!(report.schema_version, repo.current_schema_version().await.unwrap());
+    assert!(report.schema_version > 0);
+    assert_eq!(report.cards, 1);
+    assert_eq!(report.review_logs, 0);
+    assert_eq!((report.orphaned_cards, report.orphaned_review_logs), (0, 0));
+}
+
+#[tokio::test]
+async fn doctor_checks_a_file_without_changing_its_journal_mode() {
+    // A backup is a rollback-journal file, so any switch to WAL on open would show.
+    let live_url = "sqlite:file:memdb_doctor_file?mode=memory&cache=shared";
+    let live = SqliteRepository::connect(live_url).await.expect("connect");
+    live.migrate().await.expect("migrate");
+    let path = std::env::temp_dir().join(format!("learn_doctor_{}.sqlite3", std::process::id()));
+    storage::sqlite::backup::backup_database(live_url, &path)
+        .await
+        .expect("backup");
+    let url = format!("sqlite://{}", path.display());
+
+    let repo = SqliteRepository::connect_read_only(&url).await.expect("open read-only");
+    let report = storage::sqlite::doctor::check(&repo).await.expect("check");
+    let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(repo.pool())
         .await
         .unwrap();
-    let mut by_deck = std::collections::HashMap::new();
-    for row in rows {
-        by_deck.insert(row.summary.deck_id(), row.id);
-    }
+    let write = sqlx::query("DELETE FROM decks").execute(repo.pool()).await;
+    repo.pool().close().await;
+    std::fs::remove_file(&path).ok();
 
-    assert_eq!(by_deck.get(&deck1.id()), Some(&id2));
-    assert_eq!(by_deck.get(&deck2.id()), Some(&id3));
-    assert_ne!(by_deck.get(&deck1.id()), Some(&id1));
+    assert!(report.is_healthy(), "{report:?}");
+    assert_eq!(journal_mode, "delete");
+    assert!(write.is_err(), "a read-only open must refuse writes");
 }
@@ -1,10 +1,116 @@
 use dioxus::prelude::*;
 use dioxus_router::Router;
+use learn_core::model::{AccentColor, AppLanguage, AppSettings, KeyBindings, ThemePreference};
 
+use crate::context::AppContext;
+use crate::i18n::Strings;
+use crate::platform::{SYSTEM_DARK_MODE_SCRIPT, resolve_theme};
 use crate::routes::Route;
 
+/// The theme and accent applied on the app root.
+///
+/// Provided as context so the settings screen can apply a saved change immediately.
+#[derive(Clone, Copy)]
+pub(crate) struct Appearance {
+    theme: Signal<ThemePreference>,
+    accent: Signal<AccentColor>,
+}
+
+impl Appearance {
+    pub(crate) fn apply(mut self, settings: &AppSettings) {
+        self.theme.set(settings.theme());
+        self.accent.set(settings.accent_color());
+    }
+}
+
+/// The user's keys for the remappable editor and session shortcuts.
+///
+/// Provided as context so key handlers and legends follow a remapping as soon as it is saved.
+#[derive(Clone, Copy)]
+pub(crate) struct Keymap(Signal<KeyBindings>);
+
+impl Keymap {
+    pub(crate) fn apply(mut self, settings: &AppSettings) {
+        self.0.set(*settings.key_bindings());
+    }
+
+    pub(crate) fn bindings(self) -> KeyBindings {
+        (self.0)()
+    }
+}
+
+/// The bindings from context, or the defaults when rendered outside the app root.
+pub(crate) fn use_key_bindings() -> KeyBindings {
+    try_use_context::<Keymap>().map(Keymap::bindings).unwrap_or_default()
+}
+
+/// The language UI strings are shown in.
+///
+/// Provided as context so a language picked in settings applies as soon as it is saved.
+#[derive(Clone, Copy)]
+pub(crate) struct Locale(Signal<AppLanguage>);
+
+impl Locale {
+    pub(crate) fn apply(mut self, settings: &AppSettings) {
+        self.0.set(settings.language());
+    }
+}
+
+/// Strings in the language from context, or English when rendered outside the app root.
+pub(crate) fn use_strings() -> Strings {
+    try_use_context::<Locale>()
+        .map(|locale| Strings::new((locale.0)()))
+        .unwrap_or_default()
+}
+
+/// Bumped whenever due counts may have changed, such as after a card is answered.
+///
+/// Provided as context so views that show counts can recompute without polling.
+#[derive(Clone, Copy)]
+pub(crate) struct DueCountsRefresh(Signal<u64>);
+
+impl DueCountsRefresh {
+    pub(crate) fn bump(mut self) {
+        *self.0.write() += 1;
+    }
+
+    /// Reads the counter, subscribing the caller to later bumps.
+    pub(crate) fn generation(self) -> u64 {
+        (self.0)()
+    }
+}
+
 #[component]
 pub fn App() -> Element {
+    let ctx = use_context::<AppContext>();
+    let appearance = use_context_provider(|| Appearance {
+        theme: Signal::new(ThemePreference::default()),
+        accent: Signal::new(AccentColor::default()),
+    });
+    let keymap = use_context_provider(|| Keymap(Signal::new(KeyBindings::default())));
+    let locale = use_context_provider(|| Locale(Signal::new(AppLanguage::default())));
+    use_context_provider(|| DueCountsRefresh(Signal::new(0)));
+    let app_settings = ctx.app_settings();
+    use_future(move || {
+        let app_settings = app_settings.clone();
+        async move {
+            if let Ok(settings) = app_settings.load().await {
+                appearance.apply(&settings);
+                keymap.apply(&settings);
+                locale.apply(&settings);
+            }
+        }
+    });
+    let mut system_dark = use_signal(|| false);
+    use_future(move || async move {
+        let mut watcher = document::eval(SYSTEM_DARK_MODE_SCRIPT);
+        while let Ok(dark) = watcher.recv::<bool>().await {
+            system_dark.set(dark);
+        }
+    });
+    let theme = resolve_theme((appearance.theme)(), system_dark());
+    let accent = (appearance.accent)().as_str();
+
     rsx! {
         document::Stylesheet { href: asset!("/assets/style.css") }
 
@@ -12,7 +118,7 @@ pub fn App() -> Element {
         document::Title { "Learn" }
 
         // A single root container for global layout CSS hooks.
-        div { class: "app-root",
+        div { class: "app-root", "data-theme": theme, "data-accent": accent,
             ErrorBoundary {
                 handle_error: |errors: ErrorContext| rsx! {
                     div { class: "fatal",
@@ -5,11 +5,11 @@ use std::sync::{
 
 use learn_core::model::DeckId;
 use services::{
-    AppSettingsService, CardService, DeckService, SessionLoopService, SessionSummaryService,
-    WritingToolsService,
+    AiUsageService, AppSettingsService, CardService, DeckService, MediaService, SessionLoopService,
+    SessionSummaryService, WritingToolsService,
 };
 
-use crate::platform::UiLinkOpener;
+use crate::platform::{UiAudioPlayer, UiLinkOpener};
 
 pub trait UiApp: Send + Sync {
     fn current_deck_id(&self) -> DeckId;
@@ -19,9 +19,12 @@ pub trait UiApp: Send + Sync {
     fn session_loop(&self) -> Arc<SessionLoopService>;
     fn card_service(&self) -> Arc<CardService>;
     fn deck_service(&self) -> Arc<DeckService>;
+    fn media_service(&self) -> Arc<MediaService>;
     fn app_settings(&self) -> Arc<AppSettingsService>;
     fn writing_tools(&self) -> Arc<WritingToolsService>;
+    fn ai_usage(&self) -> Arc<AiUsageService>;
     fn link_opener(&self) -> Arc<dyn UiLinkOpener>;
+    fn audio_player(&self) -> Arc<dyn UiAudioPlayer>;
 }
 
 #[derive(Clone)]
@@ -34,9 +37,12 @@ pub struct AppContext {
     session_loop: Arc<SessionLoopService>,
     card_service: Arc<CardService>,
     deck_service: Arc<DeckService>,
+    media_service: Arc<MediaService>,
     app_settings: Arc<AppSettingsService>,
     writing_tools: Arc<WritingToolsService>,
+    ai_usage: Arc<AiUsageService>,
     link_opener: Arc<dyn UiLinkOpener>,
+    audio_player: Arc<dyn UiAudioPlayer>,
 }
 
 impl AppContext {
@@ -49,9 +55,12 @@ impl AppContext {
         let session_loop = app.session_loop();
         let card_service = app.card_service();
         let deck_service = app.deck_service();
+        let media_service = app.media_service();
         let app_settings = app.app_settings();
         let writing_tools = app.writing_tools();
+        let ai_usage = app.ai_usage();
         let link_opener = app.link_opener();
+        let audio_player = app.audio_player();
 
         Self {
             current_deck_id,
@@ -61,9 +70,12 @@ impl AppContext {
             session_loop,
             card_service,
             deck_service,
+            media_service,
             app_settings,
             writing_tools,
+            ai_usage,
             link_opener,
+            audio_player,
         }
     }
 
@@ -104,6 +116,11 @@ impl AppContext {
         Arc::clone(&self.deck_service)
     }
 
+    #[must_use]
+    pub fn media_service(&self) -> Arc<MediaService> {
+        Arc::clone(&self.media_service)
+    }
+
     #[must_use]
     pub fn app_settings(&self) -> Arc<AppSettingsService> {
         Arc::clone(&self.app_settings)
@@ -114,11 +131,21 @@ impl AppContext {
         Arc::clone(&self.writing_tools)
     }
 
+    #[must_use]
+    pub fn ai_usage(&self) -> Arc<AiUsageService> {
+        Arc::clone(&self.ai_usage)
+    }
+
     #[must_use]
     pub fn link_opener(&self) -> Arc<dyn UiLinkOpener> {
         Arc::clone(&self.link_opener)
     }
 
+    #[must_use]
+    pub fn audio_player(&self) -> Arc<dyn UiAudioPlayer> {
+        Arc::clone(&self.audio_player)
+    }
+
 }
 
 // This context is provided by the application composition root (e.g. `crates/app`).
@@ -0,0 +1,217 @@
+//! UI strings in the user's language.
+//!
+//! Strings are looked up by key in a per-language table. A key a translation lacks falls
+//! back to English, and a key English lacks is shown as-is so it stands out in review.
+
+use learn_core::model::AppLanguage;
+
+type Table = &'static [(&'static str, &'static str)];
+
+const ENGLISH: Table = &[
+    ("settings.title", "General"),
+    ("settings.saving", "Saving..."),
+    ("settings.save_failed", "Save failed"),
+    ("settings.saved", "Saved"),
+    ("settings.unsaved", "Unsaved changes"),
+    ("settings.language", "Language"),
+    ("settings.theme", "Theme"),
+    ("settings.appearance", "Appearance"),
+    ("settings.accent_color", "Accent color"),
+    ("settings.protect_from_overload", "Protect from overload"),
+    ("settings.day_starts_at", "Next day starts at"),
+    ("settings.target_retention", "Target retention"),
+    ("settings.enable_analytics", "Enable analytics"),
+    ("settings.restore_defaults", "Restore defaults"),
+    ("settings.cancel", "Cancel"),
+    ("settings.save", "Save"),
+    ("session.title", "Practice Session"),
+    ("session.focus", "Focus"),
+    ("session.exit_focus", "Exit focus mode"),
+    ("session.quit", "Quit"),
+    ("session.show_answer", "Show Answer"),
+    ("session.check_answer", "Check Answer"),
+    ("session.continue", "Continue"),
+    ("session.remember", "How well did you remember?"),
+    ("session.grade.again", "Again"),
+    ("session.grade.hard", "Hard"),
+    ("session.grade.good", "Good"),
+    ("session.grade.easy", "Easy"),
+    ("session.review_again", "Review again now"),
+    ("session.bury", "Bury until tomorrow"),
+    ("session.skip", "Skip for now"),
+    ("session.flag", "Flag"),
+];
+
+const GERMAN: Table = &[
+    ("settings.title", "Allgemein"),
+    ("settings.saving", "Wird gespeichert..."),
+    ("settings.save_failed", "Speichern fehlgeschlagen"),
+    ("settings.saved", "Gespeichert"),
+    ("settings.unsaved", "Ungespeicherte Änderungen"),
+    ("settings.language", "Sprache"),
+    ("settings.theme", "Design"),
+    ("settings.appearance", "Darstellung"),
+    ("settings.accent_color", "Akzentfarbe"),
+    ("settings.protect_from_overload", "Vor Überlastung schützen"),
+    ("settings.day_starts_at", "Nächster Tag beginnt um"),
+    ("settings.target_retention", "Angestrebte Erinnerungsquote"),
+    ("settings.enable_analytics", "Analysen aktivieren"),
+    ("settings.restore_defaults", "Standardwerte wiederherstellen"),
+    ("settings.cancel", "Abbrechen"),
+    ("settings.save", "Speichern"),
+    ("session.title", "Übungssitzung"),
+    ("session.focus", "Fokus"),
+    ("session.exit_focus", "Fokusmodus beenden"),
+    ("session.quit", "Beenden"),
+    ("session.show_answer", "Antwort zeigen"),
+    ("session.check_answer", "Antwort prüfen"),
+    ("session.continue", "Weiter"),
+    ("session.remember", "Wie gut hast du dich erinnert?"),
+    ("session.grade.again", "Nochmal"),
+    ("session.grade.hard", "Schwer"),
+    ("session.grade.good", "Gut"),
+    ("session.grade.easy", "Einfach"),
+    ("session.review_again", "Jetzt noch einmal ansehen"),
+    ("session.bury", "Bis morgen zurückstellen"),
+    ("session.skip", "Vorerst überspringen"),
+    ("session.flag", "Markieren"),
+];
+
+const SPANISH: Table = &[
+    ("settings.title", "General"),
+    ("settings.saving", "Guardando..."),
+    ("settings.save_failed", "Error al guardar"),
+    ("settings.saved", "Guardado"),
+    ("settings.unsaved", "Cambios sin guardar"),
+    ("settings.language", "Idioma"),
+    ("settings.theme", "Tema"),
+    ("settings.appearance", "Apariencia"),
+    ("settings.accent_color", "Color de acento"),
+    ("settings.protect_from_overload", "Evitar la sobrecarga"),
+    ("settings.day_starts_at", "El día siguiente empieza a las"),
+    ("settings.target_retention", "Retención objetivo"),
+    ("settings.enable_analytics", "Activar analíticas"),
+    ("settings.restore_defaults", "Restablecer valores predeterminados"),
+    ("settings.cancel", "Cancelar"),
+    ("settings.save", "Guardar"),
+    ("session.title", "Sesión de práctica"),
+    ("session.focus", "Enfoque"),
+    ("session.exit_focus", "Salir del modo enfoque"),
+    ("session.quit", "Salir"),
+    ("session.show_answer", "Mostrar respuesta"),
+    ("session.check_answer", "Comprobar respuesta"),
+    ("session.continue", "Continuar"),
+    ("session.remember", "¿Qué tan bien lo recordaste?"),
+    ("session.grade.again", "Otra vez"),
+    ("session.grade.hard", "Difícil"),
+    ("session.grade.good", "Bien"),
+    ("session.grade.easy", "Fácil"),
+    ("session.review_again", "Repasar de nuevo ahora"),
+    ("session.bury", "Posponer hasta mañana"),
+    ("session.skip", "Saltar por ahora"),
+    ("session.flag", "Marcar"),
+];
+
+const FRENCH: Table = &[
+    ("settings.title", "Général"),
+    ("settings.saving", "Enregistrement..."),
+    ("settings.save_failed", "Échec de l'enregistrement"),
+    ("settings.saved", "Enregistré"),
+    ("settings.unsaved", "Modifications non enregistrées"),
+    ("settings.language", "Langue"),
+    ("settings.theme", "Thème"),
+    ("settings.appearance", "Apparence"),
+    ("settings.accent_color", "Couleur d'accent"),
+    ("settings.protect_from_overload", "Éviter la surcharge"),
+    ("settings.day_starts_at", "Le jour suivant commence à"),
+    ("settings.target_retention", "Rétention visée"),
+    ("settings.enable_analytics", "Activer les statistiques"),
+    ("settings.restore_defaults", "Rétablir les valeurs par défaut"),
+    ("settings.cancel", "Annuler"),
+    ("settings.save", "Enregistrer"),
+    ("session.title", "Session d'entraînement"),
+    ("session.focus", "Concentration"),
+    ("session.exit_focus", "Quitter le mode concentration"),
+    ("session.quit", "Quitter"),
+    ("session.show_answer", "Afficher la réponse"),
+    ("session.check_answer", "Vérifier la réponse"),
+    ("session.continue", "Continuer"),
+    ("session.remember", "Vous en êtes-vous bien souvenu ?"),
+    ("session.grade.again", "À revoir"),
+    ("session.grade.hard", "Difficile"),
+    ("session.grade.good", "Bien"),
+    ("session.grade.easy", "Facile"),
+    ("session.review_again", "Revoir maintenant"),
+    ("session.bury", "Reporter à demain"),
+    ("session.skip", "Passer pour l'instant"),
+    ("session.flag", "Marquer"),
+];
+
+/// Looks up UI strings in one language.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Strings {
+    language: AppLanguage,
+}
+
+impl Strings {
+    #[must_use]
+    pub fn new(language: AppLanguage) -> Self {
+        Self { language }
+    }
+
+    #[must_use]
+    pub fn language(self) -> AppLanguage {
+        self.language
+    }
+
+    /// The text for `key`, in English when this language has no translation for it.
+    #[must_use]
+    pub fn t(self, key: &'static str) -> &'static str {
+        translate(table(self.language), key)
+    }
+}
+
+fn table(language: AppLanguage) -> Table {
+    match language {
+        AppLanguage::English => ENGLISH,
+        AppLanguage::German => GERMAN,
+        AppLanguage::Spanish => SPANISH,
+        AppLanguage::French => FRENCH,
+    }
+}
+
+fn translate(table: Table, key: &'static str) -> &'static str {
+    let find = |table: Table| {
+        table
+            .iter()
+            .find(|(candidate, _)| *candidate == key)
+            .map(|(_, text)| *text)
+    };
+    find(table).or_else(|| find(ENGLISH)).unwrap_or(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn switching_language_translates_and_falls_back_to_english() {
+        let key = "session.show_answer";
+        assert_eq!(Strings::default().t(key), "Show Answer");
+        assert_eq!(Strings::new(AppLanguage::German).t(key), "Antwort zeigen");
+        assert_eq!(Strings::new(AppLanguage::French).t(key), "Afficher la réponse");
+
+        // A translation that lacks the key shows English; an unknown key shows itself.
+        assert_eq!(translate(&[("session.quit", "Beenden")], key), "Show Answer");
+        assert_eq!(Strings::new(AppLanguage::Spanish).t("no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn every_translation_uses_only_english_keys() {
+        for table in [GERMAN, SPANISH, FRENCH] {
+            for (key, _) in table {
+                assert!(ENGLISH.iter().any(|(english, _)| english == key), "{key}");
+            }
+        }
+    }
+}
@@ -1,10 +1,12 @@
 pub mod app;
 pub mod context;
+pub mod i18n;
 pub mod platform;
 pub mod routes;
+pub mod shortcuts;
 pub mod vm;
 pub mod views;
 
 pub use app::App;
 pub use context::{AppContext, UiApp, build_app_context};
-pub use platform::UiLinkOpener;
+pub use platform::{UiAudioPlayer, UiLinkOpener};
@@ -0,0 +1,21 @@
+use learn_core::model::ThemePreference;
+
+/// Reports whether the OS prefers a dark color scheme: once on start, then on every change.
+///
+/// The desktop webview tracks the OS setting, so `prefers-color-scheme` follows it live.
+pub(crate) const SYSTEM_DARK_MODE_SCRIPT: &str = r#"
+    const query = window.matchMedia("(prefers-color-scheme: dark)");
+    dioxus.send(query.matches);
+    query.addEventListener("change", (event) => dioxus.send(event.matches));
+"#;
+
+/// Resolve a theme preference to the `data-theme` value used by the stylesheet.
+#[must_use]
+pub(crate) fn resolve_theme(theme: ThemePreference, system_dark: bool) -> &'static str {
+    match theme {
+        ThemePreference::Light => "light",
+        ThemePreference::Dark => "dark",
+        ThemePreference::System if system_dark => "dark",
+        ThemePreference::System => "light",
+    }
+}
@@ -0,0 +1,101 @@
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+
+use learn_core::model::MediaUri;
+
+use super::UiAudioPlayer;
+
+/// Plays local audio files through the platform's command-line player.
+#[derive(Default)]
+pub struct DesktopAudioPlayer {
+    current: Mutex<Option<Child>>,
+}
+
+impl DesktopAudioPlayer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl UiAudioPlayer for DesktopAudioPlayer {
+    fn play(&self, source: &MediaUri) {
+        let Ok(mut current) = self.current.lock() else {
+            return;
+        };
+        if let Some(mut child) = current.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        // Remote clips are not fetched for playback.
+        let Some(path) = source.as_path() else {
+            return;
+        };
+        *current = spawn_player(path);
+    }
+
+    fn stop(&self) {
+        let Ok(mut current) = self.current.lock() else {
+            return;
+        };
+        if let Some(mut child) = current.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for DesktopAudioPlayer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn spawn_player(path: &Path) -> Option<Child> {
+    player_commands(path).into_iter().find_map(|mut command| {
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()
+    })
+}
+
+fn player_commands(path: &Path) -> Vec<Command> {
+    #[cfg(target_os = "macos")]
+    {
+        let mut afplay = Command::new("afplay");
+        afplay.arg(path);
+        vec![afplay]
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let script = format!(
+            "Add-Type -AssemblyName PresentationCore; \
+             $p = New-Object System.Windows.Media.MediaPlayer; $p.Open([uri]'{}'); $p.Play(); \
+             Start-Sleep -Milliseconds 500; \
+             while ($p.Position -lt $p.NaturalDuration.TimeSpan) {{ Start-Sleep -Milliseconds 200 }}",
+            path.display()
+        );
+        let mut powershell = Command::new("powershell");
+        powershell.args(["-NoProfile", "-Command"]).arg(script);
+        vec![powershell]
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let mut ffplay = Command::new("ffplay");
+        ffplay.args(["-nodisp", "-autoexit", "-loglevel", "quiet"]).arg(path);
+        let mut paplay = Command::new("paplay");
+        paplay.arg(path);
+        let mut aplay = Command::new("aplay");
+        aplay.arg("-q").arg(path);
+        vec![ffplay, paplay, aplay]
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = path;
+        Vec::new()
+    }
+}
@@ -0,0 +1,23 @@
+use std::process::Command;
+
+use super::{UiLinkOpener, spawn_detached, trimmed_url};
+
+/// Opens links through the desktop's URL handler.
+pub struct DesktopLinkOpener;
+
+impl UiLinkOpener for DesktopLinkOpener {
+    fn open_url(&self, url: &str) {
+        let Some(url) = trimmed_url(url) else {
+            return;
+        };
+        // `xdg-open` is missing on some minimal setups; fall back to the GIO launcher.
+        let launchers: [&[&str]; 2] = [&["xdg-open"], &["gio", "open"]];
+        for launcher in launchers {
+            let mut command = Command::new(launcher[0]);
+            command.args(&launcher[1..]).arg(url);
+            if spawn_detached(command) {
+                return;
+            }
+        }
+    }
+}
@@ -1,26 +1,17 @@
 use std::process::Command;
 
-use super::UiLinkOpener;
+use super::{UiLinkOpener, spawn_detached, trimmed_url};
 
+/// Opens links with `open`, leaving the app in the foreground.
 pub struct DesktopLinkOpener;
 
 impl UiLinkOpener for DesktopLinkOpener {
     fn open_url(&self, url: &str) {
-        let url = url.trim();
-        if url.is_empty() {
+        let Some(url) = trimmed_url(url) else {
             return;
-        }
-        #[cfg(target_os = "macos")]
-        {
-            let _ = Command::new("open").args(["-g", url]).spawn();
-        }
-        #[cfg(target_os = "windows")]
-        {
-            let _ = Command::new("cmd").args(["/C", "start", "", url]).spawn();
-        }
-        #[cfg(target_os = "linux")]
-        {
-            let _ = Command::new("xdg-open").arg(url).spawn();
-        }
+        };
+        let mut command = Command::new("open");
+        command.args(["-g", url]);
+        spawn_detached(command);
     }
 }
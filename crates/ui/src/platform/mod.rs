@@ -1,6 +1,16 @@
+use std::process::{Command, Stdio};
 use std::sync::Arc;
 
+use learn_core::model::MediaUri;
+
+mod appearance;
+mod audio;
+#[cfg(all(unix, not(target_os = "macos")))]
+mod linux;
+#[cfg(target_os = "macos")]
 mod macos;
+#[cfg(target_os = "windows")]
+mod windows;
 
 pub trait UiLinkOpener: Send + Sync {
     fn open_url(&self, url: &str);
@@ -8,4 +18,44 @@ pub trait UiLinkOpener: Send + Sync {
 
 pub type LinkOpenerRef = Arc<dyn UiLinkOpener>;
 
+/// Plays card audio; only one clip plays at a time.
+pub trait UiAudioPlayer: Send + Sync {
+    /// Start playing `source`, stopping whatever is currently playing.
+    fn play(&self, source: &MediaUri);
+    /// Stop the current clip, if any.
+    fn stop(&self);
+}
+
+pub(crate) use appearance::{SYSTEM_DARK_MODE_SCRIPT, resolve_theme};
+pub use audio::DesktopAudioPlayer;
+#[cfg(all(unix, not(target_os = "macos")))]
+pub use linux::DesktopLinkOpener;
+#[cfg(target_os = "macos")]
 pub use macos::DesktopLinkOpener;
+#[cfg(target_os = "windows")]
+pub use windows::DesktopLinkOpener;
+
+fn trimmed_url(url: &str) -> Option<&str> {
+    let url = url.trim();
+    (!url.is_empty()).then_some(url)
+}
+
+/// Spawn `command` without waiting on it; returns `false` if it could not be started.
+///
+/// The child is reaped on a background thread so finished openers don't linger.
+fn spawn_detached(mut command: Command) -> bool {
+    let spawned = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match spawned {
+        Ok(mut child) => {
+            std::thread::spawn(move || {
+                let _ = child.wait();
+            });
+            true
+        }
+        Err(_) => false,
+    }
+}
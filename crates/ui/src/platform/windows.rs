@@ -0,0 +1,23 @@
+use std::process::Command;
+
+use super::{UiLinkOpener, spawn_detached, trimmed_url};
+
+/// Opens links with the default handler via `ShellExecute`.
+pub struct DesktopLinkOpener;
+
+impl UiLinkOpener for DesktopLinkOpener {
+    fn open_url(&self, url: &str) {
+        let Some(url) = trimmed_url(url) else {
+            return;
+        };
+        // `url.dll` hands the URL to `ShellExecute` without going through `cmd`, which would
+        // treat `&` in query strings as a command separator.
+        let mut command = Command::new("rundll32");
+        command.args(["url.dll,FileProtocolHandler", url]);
+        if !spawn_detached(command) {
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", "", url]);
+            spawn_detached(command);
+        }
+    }
+}
@@ -3,8 +3,8 @@ use dioxus_router::{Link, Outlet, Routable, use_navigator, use_route};
 
 use crate::context::AppContext;
 use crate::views::{
-    EditorView, GeneralSettingsView, HistoryView, HomeView, PracticeView, SessionView, SettingsView,
-    SummaryView,
+    EditorView, GeneralSettingsView, HistoryView, HomeView, PracticeView, SearchView, SessionView,
+    SettingsView, SummaryView,
 };
 
 #[derive(Clone, Routable, PartialEq)]
@@ -20,9 +20,20 @@ pub enum Route {
         SessionAll { deck_id: u64 },
         #[route("/session/:deck_id/mistakes", SessionMistakesRoute)]
         SessionMistakes { deck_id: u64 },
+        #[route("/session/:deck_id/cram", SessionCramRoute)]
+        SessionCram { deck_id: u64 },
+        #[route("/session/:deck_id/ahead", SessionAheadRoute)]
+        SessionAhead { deck_id: u64 },
+        #[route("/session/:deck_id/resume", SessionResumeRoute)]
+        SessionResume { deck_id: u64 },
+        #[route("/session/:deck_id/cards/:card_ids", SessionCardsRoute)]
+        SessionCards { deck_id: u64, card_ids: String },
         #[route("/session/:deck_id/tag/:tag", SessionTagRoute)]
         SessionTag { deck_id: u64, tag: String },
-        #[route("/editor", EditorView)] Editor {},
+        #[route("/editor", EditorRoute)] Editor {},
+        #[route("/editor/:deck_id?:search", EditorDeckRoute)]
+        EditorDeck { deck_id: u64, search: String },
+        #[route("/search?:query", SearchView)] Search { query: String },
         #[route("/history", HistoryView)] History {},
         #[route("/history/:summary_id", SummaryView)] Summary { summary_id: i64 },
         #[route("/settings", SettingsRoute)] Settings {},
@@ -35,6 +46,7 @@ enum NavIcon {
     Home,
     Practice,
     Edit,
+    Search,
     History,
     Settings,
 }
@@ -52,7 +64,9 @@ fn Layout() -> Element {
             return;
         }
 
-        if ctx.take_open_editor_on_launch() && !matches!(route, Route::Editor { .. }) {
+        if ctx.take_open_editor_on_launch()
+            && !matches!(route, Route::Editor { .. } | Route::EditorDeck { .. })
+        {
             did_redirect.set(true);
             navigator.push(Route::Editor {});
         }
@@ -69,22 +83,109 @@ fn Layout() -> Element {
 
 #[component]
 fn SessionDeckRoute(deck_id: u64) -> Element {
-    rsx! { SessionView { deck_id, tag: None, mode: crate::vm::SessionStartMode::Due } }
+    rsx! {
+        SessionView {
+            deck_id,
+            tag: None,
+            mode: crate::vm::SessionStartMode::Due,
+            card_ids: Vec::new(),
+        }
+    }
 }
 
 #[component]
 fn SessionAllRoute(deck_id: u64) -> Element {
-    rsx! { SessionView { deck_id, tag: None, mode: crate::vm::SessionStartMode::All } }
+    rsx! {
+        SessionView {
+            deck_id,
+            tag: None,
+            mode: crate::vm::SessionStartMode::All,
+            card_ids: Vec::new(),
+        }
+    }
 }
 
 #[component]
 fn SessionMistakesRoute(deck_id: u64) -> Element {
-    rsx! { SessionView { deck_id, tag: None, mode: crate::vm::SessionStartMode::Mistakes } }
+    rsx! {
+        SessionView {
+            deck_id,
+            tag: None,
+            mode: crate::vm::SessionStartMode::Mistakes,
+            card_ids: Vec::new(),
+        }
+    }
+}
+
+#[component]
+fn SessionCramRoute(deck_id: u64) -> Element {
+    rsx! {
+        SessionView {
+            deck_id,
+            tag: None,
+            mode: crate::vm::SessionStartMode::Cram,
+            card_ids: Vec::new(),
+        }
+    }
+}
+
+#[component]
+fn SessionAheadRoute(deck_id: u64) -> Element {
+    rsx! {
+        SessionView {
+            deck_id,
+            tag: None,
+            mode: crate::vm::SessionStartMode::Ahead,
+            card_ids: Vec::new(),
+        }
+    }
+}
+
+#[component]
+fn SessionResumeRoute(deck_id: u64) -> Element {
+    rsx! {
+        SessionView {
+            deck_id,
+            tag: None,
+            mode: crate::vm::SessionStartMode::Resume,
+            card_ids: Vec::new(),
+        }
+    }
+}
+
+#[component]
+fn SessionCardsRoute(deck_id: u64, card_ids: String) -> Element {
+    let card_ids = crate::vm::parse_card_ids_param(&card_ids).unwrap_or_default();
+    rsx! {
+        SessionView {
+            deck_id,
+            tag: None,
+            mode: crate::vm::SessionStartMode::Selected,
+            card_ids,
+        }
+    }
 }
 
 #[component]
 fn SessionTagRoute(deck_id: u64, tag: String) -> Element {
-    rsx! { SessionView { deck_id, tag: Some(tag), mode: crate::vm::SessionStartMode::Due } }
+    rsx! {
+        SessionView {
+            deck_id,
+            tag: Some(tag),
+            mode: crate::vm::SessionStartMode::Due,
+            card_ids: Vec::new(),
+        }
+    }
+}
+
+#[component]
+fn EditorRoute() -> Element {
+    rsx! { EditorView { deck_id: None, search: None } }
+}
+
+#[component]
+fn EditorDeckRoute(deck_id: u64, search: String) -> Element {
+    rsx! { EditorView { deck_id: Some(deck_id), search: Some(search) } }
 }
 
 #[component]
@@ -120,6 +221,11 @@ fn Sidebar() -> Element {
                     NavItem { to: Route::Home {}, label: "Home", icon: NavIcon::Home }
                     NavItem { to: Route::Practice {}, label: "Practice", icon: NavIcon::Practice }
                     NavItem { to: Route::Editor {}, label: "Add / Edit", icon: NavIcon::Edit }
+                    NavItem {
+                        to: Route::Search { query: String::new() },
+                        label: "Search",
+                        icon: NavIcon::Search,
+                    }
                     NavItem { to: Route::History {}, label: "History", icon: NavIcon::History }
                     NavItem { to: Route::Settings {}, label: "Settings", icon: NavIcon::Settings }
                 }
@@ -169,6 +275,19 @@ fn NavItem(to: Route, label: &'static str, icon: NavIcon) -> Element {
                 path { d: "M14 6l4 4" }
             }
         },
+        NavIcon::Search => rsx! {
+            svg {
+                class: "sidebar__icon",
+                view_box: "0 0 24 24",
+                fill: "none",
+                stroke: "currentColor",
+                stroke_width: "1.6",
+                stroke_linecap: "round",
+                stroke_linejoin: "round",
+                circle { cx: "11", cy: "11", r: "7" }
+                path { d: "M20 20l-3.5-3.5" }
+            }
+        },
         NavIcon::History => rsx! {
             svg {
                 class: "sidebar__icon",
@@ -0,0 +1,429 @@
+//! Keyboard shortcuts handled by each view.
+//!
+//! Key handlers match events against these definitions and the "?" legend lists the same
+//! ones, so the legend stays in step with what the keys actually do. Shortcuts the user can
+//! remap are built from their `KeyBindings` rather than declared as constants.
+
+use dioxus::prelude::{Code, Key, KeyboardData, Modifiers, ModifiersInteraction};
+use learn_core::model::{BoundKey, KeyAction, KeyBindings, KeyScope};
+
+/// The key in a shortcut, apart from its modifiers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShortcutKey {
+    /// A printed character, matched case-insensitively.
+    Char(char),
+    Enter,
+    Escape,
+    Backspace,
+    Space,
+    Tab,
+    ArrowUp,
+    ArrowDown,
+}
+
+impl From<BoundKey> for ShortcutKey {
+    fn from(key: BoundKey) -> Self {
+        match key {
+            BoundKey::Char(value) => ShortcutKey::Char(value),
+            BoundKey::Enter => ShortcutKey::Enter,
+            BoundKey::Escape => ShortcutKey::Escape,
+            BoundKey::Backspace => ShortcutKey::Backspace,
+            BoundKey::Space => ShortcutKey::Space,
+            BoundKey::Tab => ShortcutKey::Tab,
+            BoundKey::ArrowUp => ShortcutKey::ArrowUp,
+            BoundKey::ArrowDown => ShortcutKey::ArrowDown,
+        }
+    }
+}
+
+/// The command modifier a shortcut needs, if any.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShortcutModifier {
+    /// No Command, Control, or Option key held.
+    None,
+    /// The Command key.
+    Meta,
+    /// Command or Control, for shortcuts the text fields also know under Control.
+    CommandOrControl,
+}
+
+/// One keyboard shortcut and what it does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shortcut {
+    pub key: ShortcutKey,
+    pub modifier: ShortcutModifier,
+    /// Whether Shift must be held. Shortcuts without it ignore Shift.
+    pub shift: bool,
+    pub description: &'static str,
+}
+
+impl Shortcut {
+    #[must_use]
+    pub const fn new(key: ShortcutKey, description: &'static str) -> Self {
+        Self {
+            key,
+            modifier: ShortcutModifier::None,
+            shift: false,
+            description,
+        }
+    }
+
+    #[must_use]
+    pub const fn meta(key: ShortcutKey, description: &'static str) -> Self {
+        Self {
+            modifier: ShortcutModifier::Meta,
+            ..Self::new(key, description)
+        }
+    }
+
+    #[must_use]
+    pub const fn command_or_control(key: ShortcutKey, description: &'static str) -> Self {
+        Self {
+            modifier: ShortcutModifier::CommandOrControl,
+            ..Self::new(key, description)
+        }
+    }
+
+    #[must_use]
+    pub const fn with_shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    /// The keys `bindings` assigns to `action`.
+    #[must_use]
+    pub fn bound(bindings: &KeyBindings, action: KeyAction, description: &'static str) -> Self {
+        let combo = bindings.get(action);
+        Self {
+            key: combo.key.into(),
+            modifier: if combo.command {
+                ShortcutModifier::Meta
+            } else {
+                ShortcutModifier::None
+            },
+            shift: combo.shift,
+            description,
+        }
+    }
+
+    /// Whether one key press can set off both shortcuts.
+    fn overlaps(&self, other: &Shortcut) -> bool {
+        self.key == other.key
+            && self.shift == other.shift
+            && (self.modifier == ShortcutModifier::None)
+                == (other.modifier == ShortcutModifier::None)
+    }
+
+    /// Whether `data` is this shortcut being pressed.
+    #[must_use]
+    pub fn matches(&self, data: &KeyboardData) -> bool {
+        self.matches_parts(&data.key(), data.code(), data.modifiers())
+    }
+
+    fn matches_parts(&self, key: &Key, code: Code, modifiers: Modifiers) -> bool {
+        let command = modifiers.intersects(Modifiers::META | Modifiers::CONTROL | Modifiers::ALT);
+        let modifier_ok = match self.modifier {
+            ShortcutModifier::None => !command,
+            ShortcutModifier::Meta => modifiers.contains(Modifiers::META),
+            ShortcutModifier::CommandOrControl => {
+                modifiers.intersects(Modifiers::META | Modifiers::CONTROL)
+            }
+        };
+        if !modifier_ok || (self.shift && !modifiers.contains(Modifiers::SHIFT)) {
+            return false;
+        }
+        match self.key {
+            ShortcutKey::Char(expected) => matches!(
+                key,
+                Key::Character(value) if value.chars().eq(expected.to_lowercase())
+                    || value.chars().eq(expected.to_uppercase())
+            ),
+            ShortcutKey::Enter => *key == Key::Enter,
+            ShortcutKey::Escape => *key == Key::Escape,
+            ShortcutKey::Backspace => *key == Key::Backspace,
+            ShortcutKey::Space => code == Code::Space,
+            ShortcutKey::Tab => *key == Key::Tab,
+            ShortcutKey::ArrowUp => *key == Key::ArrowUp,
+            ShortcutKey::ArrowDown => *key == Key::ArrowDown,
+        }
+    }
+
+    /// The keys to press as shown in the legend, e.g. `⇧ ⌘ 7`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        let mut parts = Vec::with_capacity(3);
+        if self.shift {
+            parts.push("⇧".to_string());
+        }
+        if self.modifier != ShortcutModifier::None {
+            parts.push("⌘".to_string());
+        }
+        parts.push(match self.key {
+            ShortcutKey::Char(value) => value.to_uppercase().collect(),
+            ShortcutKey::Enter => "Enter".to_string(),
+            ShortcutKey::Escape => "Esc".to_string(),
+            ShortcutKey::Backspace => "Backspace".to_string(),
+            ShortcutKey::Space => "Space".to_string(),
+            ShortcutKey::Tab => "Tab".to_string(),
+            ShortcutKey::ArrowUp => "↑".to_string(),
+            ShortcutKey::ArrowDown => "↓".to_string(),
+        });
+        parts.join(" ")
+    }
+}
+
+/// Opens the shortcuts legend in every view that has one.
+pub const SHOW_SHORTCUTS: Shortcut =
+    Shortcut::new(ShortcutKey::Char('?'), "Show keyboard shortcuts");
+
+/// A remapped shortcut bound to the keys of a fixed shortcut in the same view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedShortcutClash {
+    pub action: KeyAction,
+    pub fixed: Shortcut,
+}
+
+/// Remapped shortcuts whose keys a fixed shortcut of the same view already answers to.
+#[must_use]
+pub fn fixed_clashes(bindings: &KeyBindings) -> Vec<FixedShortcutClash> {
+    KeyAction::ALL
+        .into_iter()
+        .filter_map(|action| {
+            let fixed = match action.scope() {
+                KeyScope::Editor => editor::FIXED,
+                KeyScope::Session => session::FIXED,
+            };
+            let bound = Shortcut::bound(bindings, action, action.label());
+            fixed
+                .iter()
+                .find(|shortcut| shortcut.overlaps(&bound))
+                .map(|shortcut| FixedShortcutClash {
+                    action,
+                    fixed: *shortcut,
+                })
+        })
+        .collect()
+}
+
+/// Shortcuts in the card editor.
+pub mod editor {
+    use learn_core::model::{KeyAction, KeyBindings};
+
+    use super::{SHOW_SHORTCUTS, Shortcut, ShortcutKey};
+
+    #[must_use]
+    pub fn save(bindings: &KeyBindings) -> Shortcut {
+        Shortcut::bound(bindings, KeyAction::Save, "Save card")
+    }
+
+    #[must_use]
+    pub fn new_card(bindings: &KeyBindings) -> Shortcut {
+        Shortcut::bound(bindings, KeyAction::NewCard, "New card")
+    }
+
+    #[must_use]
+    pub fn delete_card(bindings: &KeyBindings) -> Shortcut {
+        Shortcut::bound(bindings, KeyAction::Delete, "Delete selected card")
+    }
+
+    pub const RENAME_DECK: Shortcut = Shortcut::meta(ShortcutKey::Char('r'), "Rename deck");
+    pub const CANCEL_NEW: Shortcut = Shortcut::new(ShortcutKey::Escape, "Cancel new card");
+    pub const UNDO: Shortcut = Shortcut::command_or_control(ShortcutKey::Char('z'), "Undo");
+    pub const REDO: Shortcut =
+        Shortcut::command_or_control(ShortcutKey::Char('z'), "Redo").with_shift();
+    pub const BOLD: Shortcut = Shortcut::meta(ShortcutKey::Char('b'), "Bold");
+    pub const ITALIC: Shortcut = Shortcut::meta(ShortcutKey::Char('i'), "Italic");
+    pub const LINK: Shortcut = Shortcut::meta(ShortcutKey::Char('k'), "Insert link");
+    pub const NUMBERED_LIST: Shortcut =
+        Shortcut::meta(ShortcutKey::Char('7'), "Numbered list").with_shift();
+    pub const BULLET_LIST: Shortcut =
+        Shortcut::meta(ShortcutKey::Char('8'), "Bulleted list").with_shift();
+    pub const NEXT_CARD: Shortcut = Shortcut::new(ShortcutKey::ArrowDown, "Next card in list");
+    pub const PREVIOUS_CARD: Shortcut =
+        Shortcut::new(ShortcutKey::ArrowUp, "Previous card in list");
+    pub const OPEN_CARD: Shortcut = Shortcut::new(ShortcutKey::Enter, "Open highlighted card");
+
+    /// Keys the card list moves its selection with.
+    pub const LIST_NAVIGATION: &[Shortcut] = &[NEXT_CARD, PREVIOUS_CARD, OPEN_CARD];
+
+    /// Editor shortcuts the user cannot remap.
+    pub const FIXED: &[Shortcut] = &[
+        RENAME_DECK,
+        CANCEL_NEW,
+        UNDO,
+        REDO,
+        BOLD,
+        ITALIC,
+        LINK,
+        NUMBERED_LIST,
+        BULLET_LIST,
+        NEXT_CARD,
+        PREVIOUS_CARD,
+        OPEN_CARD,
+        SHOW_SHORTCUTS,
+    ];
+
+    /// Every editor shortcut, remappable ones first.
+    #[must_use]
+    pub fn all(bindings: &KeyBindings) -> Vec<Shortcut> {
+        let mut shortcuts = vec![save(bindings), new_card(bindings), delete_card(bindings)];
+        shortcuts.extend_from_slice(FIXED);
+        shortcuts
+    }
+}
+
+/// Shortcuts in a review session.
+pub mod session {
+    use super::{SHOW_SHORTCUTS, Shortcut, ShortcutKey};
+    use learn_core::model::{KeyAction, KeyBindings, ReviewGrade};
+
+    #[must_use]
+    pub fn reveal(bindings: &KeyBindings) -> Shortcut {
+        Shortcut::bound(bindings, KeyAction::Reveal, "Reveal answer")
+    }
+
+    pub const LEAVE: Shortcut = Shortcut::new(ShortcutKey::Escape, "Leave session");
+    pub const MOVE_FOCUS: Shortcut = Shortcut::new(ShortcutKey::Tab, "Move between buttons");
+    pub const FLAG: Shortcut = Shortcut::new(ShortcutKey::Char('f'), "Cycle card flag");
+    pub const SKIP: Shortcut = Shortcut::new(ShortcutKey::Char('s'), "Skip card for now");
+
+    /// Session shortcuts the user cannot remap.
+    pub const FIXED: &[Shortcut] = &[FLAG, SKIP, LEAVE, MOVE_FOCUS, SHOW_SHORTCUTS];
+
+    /// Grade with the answer button at zero-based `position`, counting from the left.
+    ///
+    /// Returns `None` past the fourth button, which has no binding.
+    #[must_use]
+    pub fn grade(bindings: &KeyBindings, position: usize, grade: ReviewGrade) -> Option<Shortcut> {
+        let action = KeyAction::grade(position)?;
+        let description = match grade {
+            ReviewGrade::Again => "Grade Again",
+            ReviewGrade::Hard => "Grade Hard",
+            ReviewGrade::Good => "Grade Good",
+            ReviewGrade::Easy => "Grade Easy",
+        };
+        Some(Shortcut::bound(bindings, action, description))
+    }
+
+    /// Every session shortcut, with one grade key per button in `grades`.
+    #[must_use]
+    pub fn all(bindings: &KeyBindings, grades: &[ReviewGrade]) -> Vec<Shortcut> {
+        let mut shortcuts = vec![reveal(bindings)];
+        shortcuts.extend(
+            grades
+                .iter()
+                .enumerate()
+                .filter_map(|(index, value)| grade(bindings, index, *value)),
+        );
+        shortcuts.extend_from_slice(FIXED);
+        shortcuts
+    }
+}
+
+/// Shortcuts in the history view.
+pub mod history {
+    use super::{SHOW_SHORTCUTS, Shortcut, ShortcutKey};
+
+    pub const FOCUS_SEARCH: Shortcut = Shortcut::new(ShortcutKey::Char('/'), "Search history");
+    pub const TOGGLE_MISTAKES: Shortcut =
+        Shortcut::new(ShortcutKey::Char('m'), "Show only sessions with mistakes");
+    pub const CLOSE: Shortcut = Shortcut::new(ShortcutKey::Escape, "Close menu or dialog");
+
+    pub const ALL: &[Shortcut] = &[FOCUS_SEARCH, TOGGLE_MISTAKES, CLOSE, SHOW_SHORTCUTS];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use learn_core::model::{KeyCombo, ReviewGrade};
+
+    fn press(shortcut: &Shortcut, key: Key, modifiers: Modifiers) -> bool {
+        shortcut.matches_parts(&key, Code::Unidentified, modifiers)
+    }
+
+    #[test]
+    fn character_shortcuts_ignore_case_and_need_their_modifier() {
+        let new_card = editor::new_card(&KeyBindings::default());
+        assert!(press(&new_card, Key::Character("n".into()), Modifiers::META));
+        assert!(press(&new_card, Key::Character("N".into()), Modifiers::META | Modifiers::SHIFT));
+        assert!(!press(&new_card, Key::Character("n".into()), Modifiers::empty()));
+        assert!(!press(&new_card, Key::Character("n".into()), Modifiers::CONTROL));
+        assert!(press(&editor::UNDO, Key::Character("z".into()), Modifiers::CONTROL));
+    }
+
+    #[test]
+    fn plain_shortcuts_do_not_fire_with_command_held() {
+        assert!(press(&SHOW_SHORTCUTS, Key::Character("?".into()), Modifiers::SHIFT));
+        assert!(!press(&SHOW_SHORTCUTS, Key::Character("?".into()), Modifiers::META));
+        assert!(press(&editor::NEXT_CARD, Key::ArrowDown, Modifiers::empty()));
+        assert!(!press(&editor::OPEN_CARD, Key::Enter, Modifiers::META));
+    }
+
+    #[test]
+    fn shift_shortcuts_need_shift() {
+        let numbered = editor::NUMBERED_LIST;
+        assert!(press(&numbered, Key::Character("7".into()), Modifiers::META | Modifiers::SHIFT));
+        assert!(!press(&numbered, Key::Character("7".into()), Modifiers::META));
+    }
+
+    #[test]
+    fn labels_spell_out_modifiers_and_keys() {
+        assert_eq!(editor::save(&KeyBindings::default()).label(), "⌘ Enter");
+        assert_eq!(editor::BULLET_LIST.label(), "⇧ ⌘ 8");
+        assert_eq!(history::TOGGLE_MISTAKES.label(), "M");
+    }
+
+    #[test]
+    fn session_legend_has_one_grade_key_per_button() {
+        let shortcuts =
+            session::all(&KeyBindings::default(), &[ReviewGrade::Again, ReviewGrade::Good]);
+        let grades: Vec<_> = shortcuts
+            .iter()
+            .filter(|shortcut| shortcut.description.starts_with("Grade"))
+            .map(|shortcut| (shortcut.label(), shortcut.description))
+            .collect();
+        assert_eq!(
+            grades,
+            vec![("1".to_string(), "Grade Again"), ("2".to_string(), "Grade Good")]
+        );
+        assert!(shortcuts.contains(&SHOW_SHORTCUTS));
+    }
+
+    #[test]
+    fn remapped_shortcuts_follow_the_bindings() {
+        let bindings = KeyBindings::default()
+            .with_binding(KeyAction::Save, KeyCombo::command(BoundKey::Char('s')))
+            .with_binding(KeyAction::Grade1, KeyCombo::plain(BoundKey::Char('j')));
+        let save = editor::save(&bindings);
+        assert!(press(&save, Key::Character("s".into()), Modifiers::META));
+        assert!(!press(&save, Key::Enter, Modifiers::META));
+
+        let again = session::grade(&bindings, 0, ReviewGrade::Again).expect("first button");
+        assert_eq!(again.label(), "J");
+        assert!(press(&again, Key::Character("j".into()), Modifiers::empty()));
+        assert_eq!(session::grade(&bindings, 4, ReviewGrade::Easy), None);
+    }
+
+    #[test]
+    fn bindings_that_take_a_fixed_shortcut_are_reported() {
+        assert!(fixed_clashes(&KeyBindings::default()).is_empty());
+
+        let bindings = KeyBindings::default()
+            .with_binding(KeyAction::Reveal, KeyCombo::plain(BoundKey::Char('f')))
+            .with_binding(KeyAction::Delete, KeyCombo::command(BoundKey::Char('z')))
+            .with_binding(KeyAction::NewCard, KeyCombo::plain(BoundKey::Char('f')));
+        assert_eq!(
+            fixed_clashes(&bindings),
+            [
+                FixedShortcutClash {
+                    action: KeyAction::Delete,
+                    fixed: editor::UNDO,
+                },
+                FixedShortcutClash {
+                    action: KeyAction::Reveal,
+                    fixed: session::FLAG,
+                },
+            ]
+        );
+    }
+}
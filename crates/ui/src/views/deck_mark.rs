@@ -0,0 +1,23 @@
+use dioxus::prelude::*;
+use learn_core::model::{DeckColor, DeckIcon};
+
+use crate::vm::{deck_color_class, deck_icon_glyph};
+
+/// The colored dot or icon a deck is marked with; renders nothing for plain decks.
+#[component]
+pub(crate) fn DeckMark(color: Option<DeckColor>, icon: Option<DeckIcon>) -> Element {
+    let color_class = deck_color_class(color);
+    match (color, icon) {
+        (None, None) => rsx! {},
+        (_, Some(icon)) => rsx! {
+            span {
+                class: "deck-mark deck-mark--icon {color_class}",
+                aria_hidden: "true",
+                "{deck_icon_glyph(icon)}"
+            }
+        },
+        (Some(_), None) => rsx! {
+            span { class: "deck-mark deck-mark--dot {color_class}", aria_hidden: "true" }
+        },
+    }
+}
@@ -4,6 +4,7 @@ mod format;
 mod intent;
 mod keyboard;
 mod link;
+mod media;
 mod menus;
 mod save;
 mod tags;
@@ -39,6 +40,7 @@ struct EditorActionHandlers {
     add_tag: Callback<String>,
     remove_tag: Callback<String>,
     set_tag_filter: Callback<Option<String>>,
+    load_more_cards: Callback<()>,
     apply_format: Callback<(MarkdownField, MarkdownAction)>,
     apply_block_dir: Callback<(MarkdownField, String)>,
     indent: Callback<(MarkdownField, bool)>,
@@ -47,6 +49,9 @@ struct EditorActionHandlers {
     update_link_url: Callback<String>,
     apply_link: Callback<MarkdownField>,
     remove_link: Callback<MarkdownField>,
+    attach_image: Callback<(MarkdownField, dioxus::html::FileData)>,
+    paste_image: Callback<(MarkdownField, Vec<u8>)>,
+    remove_image: Callback<MarkdownField>,
     confirm_discard: Callback<()>,
     cancel_discard: Callback<()>,
     open_delete_modal: Callback<()>,
@@ -63,9 +68,17 @@ struct EditorActionHandlers {
     open_reset_deck_modal: Callback<()>,
     close_reset_deck_modal: Callback<()>,
     confirm_reset_deck: Callback<()>,
+    set_deck_archived: Callback<bool>,
+    set_deck_favorite: Callback<(learn_core::model::DeckId, bool)>,
+    toggle_archived_decks: Callback<()>,
     close_duplicate_modal: Callback<()>,
     confirm_duplicate: Callback<()>,
     delete: Callback<()>,
+    set_bulk_selection: Callback<Vec<learn_core::model::CardId>>,
+    bulk_delete: Callback<()>,
+    bulk_suspend: Callback<bool>,
+    bulk_tag: Callback<(String, bool)>,
+    reset_card: Callback<()>,
     cancel_new: Callback<()>,
 }
 
@@ -95,6 +108,9 @@ pub fn use_editor_dispatcher(state: &EditorState, services: &EditorServices) ->
     let update_link_url_action = link::build_update_link_url_action(&state);
     let apply_link_action = link::build_apply_link_action(&state);
     let remove_link_action = link::build_remove_link_action(&state);
+    let attach_image_action = media::build_attach_image_action(&state, &services);
+    let paste_image_action = media::build_paste_image_action(&state, &services);
+    let remove_image_action = media::build_remove_image_action(&state);
     let (confirm_discard_action, cancel_discard_action) = menus::build_discard_actions(
         &state,
         select_card_action,
@@ -105,6 +121,10 @@ pub fn use_editor_dispatcher(state: &EditorState, services: &EditorServices) ->
     let open_reset_deck_modal_action = decks::build_open_reset_deck_modal_action(&state);
     let close_reset_deck_modal_action = decks::build_close_reset_deck_modal_action(&state);
     let confirm_reset_deck_action = decks::build_confirm_reset_deck_action(&state, &services);
+    let set_deck_archived_action =
+        decks::build_set_deck_archived_action(&state, &services, request_select_deck_action);
+    let set_deck_favorite_action = decks::build_set_deck_favorite_action(&state, &services);
+    let toggle_archived_decks_action = decks::build_toggle_archived_decks_action(&state);
     let toggle_save_menu_action = menus::build_toggle_save_menu_action(&state);
     let close_save_menu_action = menus::build_close_save_menu_action(&state);
     let toggle_writing_tools_action = menus::build_toggle_writing_tools_action(&state);
@@ -119,9 +139,16 @@ pub fn use_editor_dispatcher(state: &EditorState, services: &EditorServices) ->
     let close_duplicate_modal_action = menus::build_close_duplicate_modal_action(&state);
     let confirm_duplicate_action = menus::build_confirm_duplicate_action(&state, save_action);
     let delete_action = cards::build_delete_action(&state, &services);
+    let set_bulk_selection_action = cards::build_set_bulk_selection_action(&state);
+    let bulk_delete_action = cards::build_bulk_delete_action(&state, &services);
+    let bulk_suspend_action = cards::build_bulk_suspend_action(&state, &services);
+    let bulk_tag_action = cards::build_bulk_tag_action(&state, &services);
+    let reset_card_action = cards::build_reset_card_action(&state, &services);
+    let load_more_cards_action = cards::build_load_more_cards_action(&state, &services);
     let cancel_new_action = cards::build_cancel_new_action(&state);
 
     cards::use_cards_resource_effect(&state, select_card_action);
+    decks::use_remember_deck_effect(&state, &services);
 
     let handlers = EditorActionHandlers {
         save: save_action,
@@ -135,6 +162,7 @@ pub fn use_editor_dispatcher(state: &EditorState, services: &EditorServices) ->
         add_tag: add_tag_action,
         remove_tag: remove_tag_action,
         set_tag_filter: set_tag_filter_action,
+        load_more_cards: load_more_cards_action,
         apply_format: apply_format_action,
         apply_block_dir: apply_block_dir_action,
         indent: indent_action,
@@ -143,12 +171,18 @@ pub fn use_editor_dispatcher(state: &EditorState, services: &EditorServices) ->
         update_link_url: update_link_url_action,
         apply_link: apply_link_action,
         remove_link: remove_link_action,
+        attach_image: attach_image_action,
+        paste_image: paste_image_action,
+        remove_image: remove_image_action,
         confirm_discard: confirm_discard_action,
         cancel_discard: cancel_discard_action,
         open_delete_modal: open_delete_modal_action,
         open_reset_deck_modal: open_reset_deck_modal_action,
         close_reset_deck_modal: close_reset_deck_modal_action,
         confirm_reset_deck: confirm_reset_deck_action,
+        set_deck_archived: set_deck_archived_action,
+        set_deck_favorite: set_deck_favorite_action,
+        toggle_archived_decks: toggle_archived_decks_action,
         toggle_save_menu: toggle_save_menu_action,
         close_save_menu: close_save_menu_action,
         toggle_writing_tools: toggle_writing_tools_action,
@@ -162,6 +196,11 @@ pub fn use_editor_dispatcher(state: &EditorState, services: &EditorServices) ->
         close_duplicate_modal: close_duplicate_modal_action,
         confirm_duplicate: confirm_duplicate_action,
         delete: delete_action,
+        set_bulk_selection: set_bulk_selection_action,
+        bulk_delete: bulk_delete_action,
+        bulk_suspend: bulk_suspend_action,
+        bulk_tag: bulk_tag_action,
+        reset_card: reset_card_action,
         cancel_new: cancel_new_action,
     };
 
@@ -188,11 +227,12 @@ fn dispatch_intent(intent: EditorIntent, handlers: &EditorActionHandlers) {
         EditorIntent::CommitRename => handlers.commit_rename.call(()),
         EditorIntent::BeginRename(label) => handlers.begin_rename.call(label),
         EditorIntent::RequestSelectDeck(deck_id) => handlers.request_select_deck.call(deck_id),
-        EditorIntent::RequestSelectCard(card) => handlers.request_select_card.call(card),
+        EditorIntent::RequestSelectCard(card) => handlers.request_select_card.call(*card),
         EditorIntent::RequestNewCard => handlers.request_new_card.call(()),
         EditorIntent::AddTag(tag) => handlers.add_tag.call(tag),
         EditorIntent::RemoveTag(tag) => handlers.remove_tag.call(tag),
         EditorIntent::SetTagFilter(tag) => handlers.set_tag_filter.call(tag),
+        EditorIntent::LoadMoreCards => handlers.load_more_cards.call(()),
         EditorIntent::ApplyFormat(field, action) => {
             handlers.apply_format.call((field, action));
         }
@@ -214,12 +254,26 @@ fn dispatch_intent(intent: EditorIntent, handlers: &EditorActionHandlers) {
         EditorIntent::RemoveLink(field) => {
             handlers.remove_link.call(field);
         }
+        EditorIntent::AttachImage(field, file) => {
+            handlers.attach_image.call((field, file));
+        }
+        EditorIntent::PasteImage(field, bytes) => {
+            handlers.paste_image.call((field, bytes));
+        }
+        EditorIntent::RemoveImage(field) => {
+            handlers.remove_image.call(field);
+        }
         EditorIntent::ConfirmDiscard => handlers.confirm_discard.call(()),
         EditorIntent::CancelDiscard => handlers.cancel_discard.call(()),
         EditorIntent::OpenDeleteModal => handlers.open_delete_modal.call(()),
         EditorIntent::OpenResetDeckModal => handlers.open_reset_deck_modal.call(()),
         EditorIntent::CloseResetDeckModal => handlers.close_reset_deck_modal.call(()),
         EditorIntent::ConfirmResetDeck => handlers.confirm_reset_deck.call(()),
+        EditorIntent::SetDeckArchived(archived) => handlers.set_deck_archived.call(archived),
+        EditorIntent::SetDeckFavorite(deck_id, favorite) => {
+            handlers.set_deck_favorite.call((deck_id, favorite));
+        }
+        EditorIntent::ToggleArchivedDecks => handlers.toggle_archived_decks.call(()),
         EditorIntent::ToggleSaveMenu => handlers.toggle_save_menu.call(()),
         EditorIntent::CloseSaveMenu => handlers.close_save_menu.call(()),
         EditorIntent::ToggleWritingTools(field) => handlers.toggle_writing_tools.call(field),
@@ -247,6 +301,11 @@ fn dispatch_intent(intent: EditorIntent, handlers: &EditorActionHandlers) {
         EditorIntent::CloseDuplicateModal => handlers.close_duplicate_modal.call(()),
         EditorIntent::ConfirmDuplicate => handlers.confirm_duplicate.call(()),
         EditorIntent::Delete => handlers.delete.call(()),
+        EditorIntent::SetBulkSelection(ids) => handlers.set_bulk_selection.call(ids),
+        EditorIntent::BulkDelete => handlers.bulk_delete.call(()),
+        EditorIntent::BulkSuspend(suspended) => handlers.bulk_suspend.call(suspended),
+        EditorIntent::BulkTag(tag, add) => handlers.bulk_tag.call((tag, add)),
+        EditorIntent::ResetCard => handlers.reset_card.call(()),
         EditorIntent::CancelNew => handlers.cancel_new.call(()),
     }
 }
@@ -2,13 +2,16 @@ use std::rc::Rc;
 use std::time::Duration;
 
 use dioxus::prelude::*;
+use learn_core::model::{CardId, NewCardTemplate, TagName};
+use services::{CardListSort, ResetCardOptions};
 
 use crate::vm::CardListItemVm;
 use crate::views::{ViewError, ViewState, view_state_from_resource};
 
 use super::super::state::{
-    DeleteState, EditorServices, EditorState, PendingAction, SaveMenuState, SaveState,
-    WritingToolsMenuState, WritingToolsResultStatus,
+    BulkActionState, CardListQuery, DeleteState, EditorServices, EditorState, PendingAction,
+    ResetCardState, SaveMenuState, SaveState, WritingToolsMenuState, WritingToolsResultStatus,
+    load_card_list_page,
 };
 
 pub(super) fn build_select_card_action(state: &EditorState) -> Callback<CardListItemVm> {
@@ -41,10 +44,20 @@ pub(super) fn build_select_card_action(state: &EditorState) -> Callback<CardList
         let mut writing_tools_result_status = state.writing_tools_result_status;
         let mut writing_tools_result_target = state.writing_tools_result_target;
         let mut writing_tools_request = state.writing_tools_request;
+        let mut prompt_media_id = state.prompt_media_id;
+        let mut answer_media_id = state.answer_media_id;
+        let mut media_error = state.media_error;
+        let mut extra_text = state.extra_text;
+        let mut bulk_selection = state.bulk_selection;
 
         selected_card_id.set(Some(item.id));
+        bulk_selection.set(Vec::new());
         last_selected_card.set(Some(item.clone()));
         is_create_mode.set(false);
+        prompt_media_id.set(item.prompt_media_id);
+        answer_media_id.set(item.answer_media_id);
+        media_error.set(None);
+        extra_text.set(item.extra_text.clone());
         let prompt_html = item.prompt_html;
         let answer_html = item.answer_html;
         set_editor_fields.borrow_mut()(prompt_html, answer_html);
@@ -83,7 +96,7 @@ pub(super) fn build_request_select_card_action(
         let mut show_unsaved_modal = state.show_unsaved_modal;
         let mut show_deck_menu = state.show_deck_menu;
         if has_unsaved_changes() {
-            pending_action.set(Some(PendingAction::SelectCard(item)));
+            pending_action.set(Some(PendingAction::SelectCard(Box::new(item))));
             show_unsaved_modal.set(true);
             show_deck_menu.set(false);
             return;
@@ -92,6 +105,18 @@ pub(super) fn build_request_select_card_action(
     })
 }
 
+/// Fill the fields of the card being created with the deck's starter text.
+pub(super) fn apply_new_card_template(state: &EditorState, template: NewCardTemplate) {
+    if !template.is_empty() {
+        state.set_editor_fields.borrow_mut()(
+            template.prompt().to_string(),
+            template.answer().to_string(),
+        );
+    }
+    let mut new_card_template = state.new_card_template;
+    new_card_template.set(template);
+}
+
 pub(super) fn build_new_card_action(state: &EditorState) -> Callback<()> {
     let state = state.clone();
     let clear_editor_fields = Rc::clone(&state.clear_editor_fields);
@@ -127,6 +152,11 @@ pub(super) fn build_new_card_action(state: &EditorState) -> Callback<()> {
         selected_card_id.set(None);
         is_create_mode.set(true);
         clear_editor_fields.borrow_mut()();
+        let template = match view_state_from_resource(&state.new_card_template_resource) {
+            ViewState::Ready(template) => template,
+            _ => NewCardTemplate::default(),
+        };
+        apply_new_card_template(&state, template);
         card_tags.set(Vec::new());
         tag_input.set(String::new());
         save_state.set(SaveState::Idle);
@@ -255,6 +285,194 @@ pub(super) fn build_delete_action(
     })
 }
 
+pub(super) fn build_set_bulk_selection_action(state: &EditorState) -> Callback<Vec<CardId>> {
+    let state = state.clone();
+    use_callback(move |ids: Vec<CardId>| {
+        let mut bulk_selection = state.bulk_selection;
+        let mut bulk_state = state.bulk_state;
+        bulk_selection.set(ids);
+        bulk_state.set(BulkActionState::Idle);
+    })
+}
+
+/// Move every multi-selected card to the trash in one batch.
+pub(super) fn build_bulk_delete_action(
+    state: &EditorState,
+    services: &EditorServices,
+) -> Callback<()> {
+    let state = state.clone();
+    let clear_editor_fields = Rc::clone(&state.clear_editor_fields);
+    let card_service = services.card_service.clone();
+    use_callback(move |()| {
+        let clear_editor_fields = Rc::clone(&clear_editor_fields);
+        let card_service = card_service.clone();
+        let mut bulk_selection = state.bulk_selection;
+        let mut bulk_state = state.bulk_state;
+        let mut cards_resource = state.cards_resource;
+        let mut selected_card_id = state.selected_card_id;
+        let mut last_selected_card = state.last_selected_card;
+        let mut card_tags = state.card_tags;
+        let mut last_selected_tags = state.last_selected_tags;
+        let deck_id = *state.selected_deck.read();
+        let ids = bulk_selection();
+        if ids.is_empty() || bulk_state() == BulkActionState::Working {
+            return;
+        }
+
+        spawn(async move {
+            bulk_state.set(BulkActionState::Working);
+            match card_service.delete_cards(deck_id, &ids).await {
+                Ok(_) => {
+                    bulk_state.set(BulkActionState::Idle);
+                    bulk_selection.set(Vec::new());
+                    if selected_card_id().is_some_and(|id| ids.contains(&id)) {
+                        selected_card_id.set(None);
+                        last_selected_card.set(None);
+                        clear_editor_fields.borrow_mut()();
+                        card_tags.set(Vec::new());
+                        last_selected_tags.set(Vec::new());
+                    }
+                    cards_resource.restart();
+                }
+                Err(_) => bulk_state.set(BulkActionState::Error(ViewError::Unknown)),
+            }
+        });
+    })
+}
+
+/// Suspend (`true`) or resume every multi-selected card in one batch.
+pub(super) fn build_bulk_suspend_action(
+    state: &EditorState,
+    services: &EditorServices,
+) -> Callback<bool> {
+    let state = state.clone();
+    let card_service = services.card_service.clone();
+    use_callback(move |suspended: bool| {
+        let card_service = card_service.clone();
+        let mut bulk_selection = state.bulk_selection;
+        let mut bulk_state = state.bulk_state;
+        let mut cards_resource = state.cards_resource;
+        let deck_id = *state.selected_deck.read();
+        let ids = bulk_selection();
+        if ids.is_empty() || bulk_state() == BulkActionState::Working {
+            return;
+        }
+
+        spawn(async move {
+            bulk_state.set(BulkActionState::Working);
+            match card_service.set_suspended_many(deck_id, &ids, suspended).await {
+                Ok(_) => {
+                    bulk_state.set(BulkActionState::Idle);
+                    bulk_selection.set(Vec::new());
+                    cards_resource.restart();
+                }
+                Err(_) => bulk_state.set(BulkActionState::Error(ViewError::Unknown)),
+            }
+        });
+    })
+}
+
+/// Add (`true`) or remove one tag on every multi-selected card in one batch.
+///
+/// The selection stays so the bar can report how many cards changed.
+pub(super) fn build_bulk_tag_action(
+    state: &EditorState,
+    services: &EditorServices,
+) -> Callback<(String, bool)> {
+    let state = state.clone();
+    let card_service = services.card_service.clone();
+    use_callback(move |(tag, add): (String, bool)| {
+        let card_service = card_service.clone();
+        let bulk_selection = state.bulk_selection;
+        let mut bulk_state = state.bulk_state;
+        let mut cards_resource = state.cards_resource;
+        let mut deck_tags_resource = state.deck_tags_resource;
+        let mut card_tags_resource = state.card_tags_resource;
+        let selected_card_id = state.selected_card_id;
+        let deck_id = *state.selected_deck.read();
+        let ids = bulk_selection();
+        if ids.is_empty() || bulk_state() == BulkActionState::Working {
+            return;
+        }
+        let Ok(tag) = TagName::new(tag.trim().to_string()) else {
+            bulk_state.set(BulkActionState::Error(ViewError::Unknown));
+            return;
+        };
+
+        spawn(async move {
+            bulk_state.set(BulkActionState::Working);
+            let tags = std::slice::from_ref(&tag);
+            let result = if add {
+                card_service.add_tags_many(deck_id, &ids, tags).await
+            } else {
+                card_service.remove_tags_many(deck_id, &ids, tags).await
+            };
+            match result {
+                Ok(count) => {
+                    bulk_state.set(BulkActionState::Tagged { count, added: add });
+                    cards_resource.restart();
+                    deck_tags_resource.restart();
+                    if selected_card_id().is_some_and(|id| ids.contains(&id)) {
+                        card_tags_resource.restart();
+                    }
+                }
+                Err(_) => bulk_state.set(BulkActionState::Error(ViewError::Unknown)),
+            }
+        });
+    })
+}
+
+/// Return the selected card to the `New` phase, keeping its review history.
+pub(super) fn build_reset_card_action(
+    state: &EditorState,
+    services: &EditorServices,
+) -> Callback<()> {
+    let state = state.clone();
+    let card_service = services.card_service.clone();
+    use_callback(move |()| {
+        let card_service = card_service.clone();
+        let mut reset_card_state = state.reset_card_state;
+        let mut cards_resource = state.cards_resource;
+        let sort_mode = state.sort_mode;
+        let selected_card_id = state.selected_card_id;
+        let deck_id = *state.selected_deck.read();
+        let Some(card_id) = selected_card_id() else {
+            return;
+        };
+        if reset_card_state() == ResetCardState::Resetting {
+            return;
+        }
+
+        spawn(async move {
+            reset_card_state.set(ResetCardState::Resetting);
+            let result = card_service
+                .reset_card_learning(deck_id, card_id, ResetCardOptions::default())
+                .await;
+            match result {
+                Ok(_) => {
+                    reset_card_state.set(ResetCardState::Success);
+                    // Only these orders depend on scheduling state.
+                    if matches!(
+                        *sort_mode.peek(),
+                        CardListSort::DueDate | CardListSort::Difficulty
+                    ) {
+                        cards_resource.restart();
+                    }
+                    spawn(async move {
+                        tokio::time::sleep(Duration::from_secs(2)).await;
+                        if reset_card_state() == ResetCardState::Success {
+                            reset_card_state.set(ResetCardState::Idle);
+                        }
+                    });
+                }
+                Err(_) => {
+                    reset_card_state.set(ResetCardState::Error(ViewError::Unknown));
+                }
+            }
+        });
+    })
+}
+
 pub(super) fn build_cancel_new_action(state: &EditorState) -> Callback<()> {
     let state = state.clone();
     let clear_editor_fields = Rc::clone(&state.clear_editor_fields);
@@ -281,6 +499,9 @@ pub(super) fn build_cancel_new_action(state: &EditorState) -> Callback<()> {
         let mut writing_tools_result_status = state.writing_tools_result_status;
         let mut writing_tools_result_target = state.writing_tools_result_target;
         let mut writing_tools_request = state.writing_tools_request;
+        let mut prompt_media_id = state.prompt_media_id;
+        let mut answer_media_id = state.answer_media_id;
+        let mut extra_text = state.extra_text;
 
         if !is_create_mode() {
             return;
@@ -289,6 +510,9 @@ pub(super) fn build_cancel_new_action(state: &EditorState) -> Callback<()> {
         if let Some(card) = last_selected_card() {
             selected_card_id.set(Some(card.id));
             set_editor_fields.borrow_mut()(card.prompt_html.clone(), card.answer_html.clone());
+            prompt_media_id.set(card.prompt_media_id);
+            answer_media_id.set(card.answer_media_id);
+            extra_text.set(card.extra_text.clone());
             card_tags.set(last_selected_tags());
             is_create_mode.set(false);
         } else {
@@ -314,6 +538,48 @@ pub(super) fn build_cancel_new_action(state: &EditorState) -> Callback<()> {
     })
 }
 
+pub(super) fn build_load_more_cards_action(
+    state: &EditorState,
+    services: &EditorServices,
+) -> Callback<()> {
+    let state = state.clone();
+    let card_service = services.card_service.clone();
+    let deck_service = services.deck_service.clone();
+    use_callback(move |()| {
+        let card_service = card_service.clone();
+        let deck_service = deck_service.clone();
+        let mut more_cards = state.more_cards;
+        let mut has_more_cards = state.has_more_cards;
+        let mut is_loading_more_cards = state.is_loading_more_cards;
+        if !has_more_cards() || is_loading_more_cards() {
+            return;
+        }
+        let ViewState::Ready(items) = state.cards_state() else {
+            return;
+        };
+        let offset = u32::try_from(items.len()).unwrap_or(u32::MAX);
+        let query = CardListQuery::current(&state);
+        let state = state.clone();
+
+        is_loading_more_cards.set(true);
+        spawn(async move {
+            let page = load_card_list_page(&card_service, &deck_service, &query, offset).await;
+            is_loading_more_cards.set(false);
+            // The list was reloaded for another deck, sort, filter, or search meanwhile.
+            if CardListQuery::current(&state) != query {
+                return;
+            }
+            match page {
+                Ok(page) => {
+                    more_cards.write().extend(page.items);
+                    has_more_cards.set(page.has_more);
+                }
+                Err(_) => has_more_cards.set(false),
+            }
+        });
+    })
+}
+
 pub(super) fn use_cards_resource_effect(
     state: &EditorState,
     select_card_action: Callback<CardListItemVm>,
@@ -334,7 +600,7 @@ pub(super) fn use_cards_resource_effect(
         let mut writing_tools_result_status = state.writing_tools_result_status;
         let mut writing_tools_result_target = state.writing_tools_result_target;
         let mut writing_tools_request = state.writing_tools_request;
-        let cards_state = view_state_from_resource(&state.cards_resource);
+        let cards_state = state.cards_state();
         if let ViewState::Ready(items) = &cards_state {
             if should_enter_create_mode(items.len(), is_create_mode()) {
                 selected_card_id.set(None);
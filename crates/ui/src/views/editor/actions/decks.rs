@@ -3,12 +3,13 @@ use std::time::Duration;
 
 use dioxus::prelude::*;
 use learn_core::model::DeckSettings;
+use services::DeckServiceError;
 
 use crate::views::ViewError;
 
 use super::super::state::{
-    DeleteState, EditorServices, EditorState, PendingAction, ResetDeckState, SaveMenuState,
-    SaveState, WritingToolsMenuState, WritingToolsResultStatus,
+    BulkActionState, DeleteState, EditorServices, EditorState, PendingAction, ResetDeckState,
+    SaveMenuState, SaveState, WritingToolsMenuState, WritingToolsResultStatus,
 };
 
 pub(super) fn build_create_deck_action(
@@ -134,21 +135,29 @@ pub(super) fn build_rename_actions(
             rename_deck_state.set(SaveState::Saving);
             rename_deck_error.set(None);
 
-            if deck_service.rename_deck(deck_id, name).await.is_ok() {
-                rename_deck_state.set(SaveState::Success);
-                is_renaming_deck.set(false);
-                decks_resource.restart();
-            } else {
-                rename_deck_state.set(SaveState::Error(ViewError::Unknown));
-                let message = "Rename failed. Please try again.".to_string();
-                rename_deck_error.set(Some(message.clone()));
-                let mut rename_deck_error = rename_deck_error;
-                spawn(async move {
-                    tokio::time::sleep(Duration::from_secs(2)).await;
-                    if rename_deck_error.read().as_ref() == Some(&message) {
-                        rename_deck_error.set(None);
-                    }
-                });
+            match deck_service.rename_deck(deck_id, name).await {
+                Ok(()) => {
+                    rename_deck_state.set(SaveState::Success);
+                    is_renaming_deck.set(false);
+                    decks_resource.restart();
+                }
+                Err(DeckServiceError::DuplicateName) => {
+                    // Stays until the name is edited, since retrying cannot help.
+                    rename_deck_state.set(SaveState::Error(ViewError::Unknown));
+                    rename_deck_error.set(Some("Another deck already has this name.".to_string()));
+                }
+                Err(_) => {
+                    rename_deck_state.set(SaveState::Error(ViewError::Unknown));
+                    let message = "Rename failed. Please try again.".to_string();
+                    rename_deck_error.set(Some(message.clone()));
+                    let mut rename_deck_error = rename_deck_error;
+                    spawn(async move {
+                        tokio::time::sleep(Duration::from_secs(2)).await;
+                        if rename_deck_error.read().as_ref() == Some(&message) {
+                            rename_deck_error.set(None);
+                        }
+                    });
+                }
             }
         });
     });
@@ -211,11 +220,15 @@ pub(super) fn build_apply_select_deck_action(state: &EditorState) -> Callback<le
         let mut writing_tools_result_status = state.writing_tools_result_status;
         let mut writing_tools_result_target = state.writing_tools_result_target;
         let mut writing_tools_request = state.writing_tools_request;
+        let mut bulk_selection = state.bulk_selection;
+        let mut bulk_state = state.bulk_state;
 
         selected_deck.set(deck_id);
         show_new_deck.set(false);
         new_deck_state.set(SaveState::Idle);
         selected_card_id.set(None);
+        bulk_selection.set(Vec::new());
+        bulk_state.set(BulkActionState::Idle);
         last_selected_card.set(None);
         is_create_mode.set(false);
         clear_editor_fields.borrow_mut()();
@@ -244,6 +257,20 @@ pub(super) fn build_apply_select_deck_action(state: &EditorState) -> Callback<le
     })
 }
 
+/// Store the selected deck so the next launch reopens it.
+pub(super) fn use_remember_deck_effect(state: &EditorState, services: &EditorServices) {
+    let selected_deck = state.selected_deck;
+    let app_settings = services.app_settings.clone();
+    use_effect(move || {
+        let deck_id = *selected_deck.read();
+        let app_settings = app_settings.clone();
+        spawn(async move {
+            // Best effort: failing to remember the deck only affects the next launch.
+            let _ = app_settings.remember_last_deck(deck_id).await;
+        });
+    });
+}
+
 pub(super) fn build_request_select_deck_action(
     state: &EditorState,
     apply_select_deck_action: Callback<learn_core::model::DeckId>,
@@ -338,6 +365,76 @@ pub(super) fn build_confirm_reset_deck_action(
     })
 }
 
+pub(super) fn build_set_deck_archived_action(
+    state: &EditorState,
+    services: &EditorServices,
+    request_select_deck_action: Callback<learn_core::model::DeckId>,
+) -> Callback<bool> {
+    let state = state.clone();
+    let deck_service = services.deck_service.clone();
+    use_callback(move |archived: bool| {
+        let deck_service = deck_service.clone();
+        let mut show_deck_actions = state.show_deck_actions;
+        let mut rename_deck_error = state.rename_deck_error;
+        let mut decks_resource = state.decks_resource;
+        let show_archived_decks = state.show_archived_decks;
+        let deck_id = *state.selected_deck.read();
+        show_deck_actions.set(false);
+
+        spawn(async move {
+            if deck_service.set_archived(deck_id, archived).await.is_err() {
+                let message = if archived {
+                    "Archive failed. Please try again."
+                } else {
+                    "Restore failed. Please try again."
+                };
+                rename_deck_error.set(Some(message.to_string()));
+                return;
+            }
+            decks_resource.restart();
+
+            // The archived deck drops out of the list, so move the editor to an active one.
+            if archived
+                && !show_archived_decks()
+                && let Ok(decks) = deck_service.list_decks(1, false).await
+                && let Some(next) = decks.first()
+            {
+                request_select_deck_action.call(next.id());
+            }
+        });
+    })
+}
+
+pub(super) fn build_set_deck_favorite_action(
+    state: &EditorState,
+    services: &EditorServices,
+) -> Callback<(learn_core::model::DeckId, bool)> {
+    let state = state.clone();
+    let deck_service = services.deck_service.clone();
+    use_callback(move |(deck_id, favorite): (learn_core::model::DeckId, bool)| {
+        let deck_service = deck_service.clone();
+        let mut rename_deck_error = state.rename_deck_error;
+        let mut decks_resource = state.decks_resource;
+
+        spawn(async move {
+            if deck_service.set_favorite(deck_id, favorite).await.is_err() {
+                rename_deck_error.set(Some("Starring failed. Please try again.".to_string()));
+                return;
+            }
+            // Reload so the deck moves in or out of the pinned group.
+            decks_resource.restart();
+        });
+    })
+}
+
+pub(super) fn build_toggle_archived_decks_action(state: &EditorState) -> Callback<()> {
+    let state = state.clone();
+    use_callback(move |()| {
+        let mut show_archived_decks = state.show_archived_decks;
+        show_archived_decks.set(!show_archived_decks());
+    })
+}
+
 fn is_valid_deck_name(name: &str) -> bool {
     !name.trim().is_empty()
 }
@@ -1,10 +1,11 @@
 use dioxus::document::eval;
 use dioxus::prelude::*;
 
-use crate::vm::{MarkdownAction, MarkdownField};
+use crate::vm::{MarkdownAction, MarkdownEdit, MarkdownField, starter_table_html};
 
 use super::super::scripts::{
-    exec_command_script, read_editable_html, set_block_dir_script, wrap_selection_script,
+    exec_command_script, read_editable_html, read_selection_range, set_block_dir_script,
+    wrap_selection_script,
 };
 use super::super::state::{EditorState, SaveState};
 
@@ -44,6 +45,7 @@ pub(super) fn build_format_actions(state: &EditorState) -> FormatCallbacks {
         let mut prompt_text = state_for_format.prompt_text;
         let mut answer_text = state_for_format.answer_text;
         let mut save_state = state_for_format.save_state;
+        let mut edit_history = state_for_format.edit_history;
         spawn(async move {
             let element_id = match field {
                 MarkdownField::Front => "prompt",
@@ -53,6 +55,13 @@ pub(super) fn build_format_actions(state: &EditorState) -> FormatCallbacks {
                 r#"document.getElementById("{element_id}")?.focus();"#
             ))
             .await;
+            let before = MarkdownEdit {
+                text: match field {
+                    MarkdownField::Front => prompt_text.peek().clone(),
+                    MarkdownField::Back => answer_text.peek().clone(),
+                },
+                selection: read_selection_range(element_id).await.unwrap_or_default(),
+            };
             let script = match action {
                 MarkdownAction::Bold => exec_command_script(element_id, "bold", None),
                 MarkdownAction::Italic => exec_command_script(element_id, "italic", None),
@@ -65,9 +74,14 @@ pub(super) fn build_format_actions(state: &EditorState) -> FormatCallbacks {
                 }
                 MarkdownAction::Code => wrap_selection_script(element_id, "code", None),
                 MarkdownAction::CodeBlock => wrap_selection_script(element_id, "pre", Some("code")),
+                MarkdownAction::Table => {
+                    exec_command_script(element_id, "insertHTML", Some(&starter_table_html()))
+                }
             };
             let _ = eval(&script).await;
             if let Some(updated) = read_editable_html(element_id).await {
+                let after = read_selection_range(element_id).await.unwrap_or_default();
+                edit_history.write().field_mut(field).record(before, after);
                 match field {
                     MarkdownField::Front => prompt_text.set(updated),
                     MarkdownField::Back => answer_text.set(updated),
@@ -1,4 +1,5 @@
-use learn_core::model::DeckId;
+use dioxus::html::FileData;
+use learn_core::model::{CardId, DeckId};
 
 use crate::vm::{CardListItemVm, MarkdownAction, MarkdownField};
 
@@ -12,11 +13,12 @@ pub enum EditorIntent {
     CommitRename,
     BeginRename(String),
     RequestSelectDeck(DeckId),
-    RequestSelectCard(CardListItemVm),
+    RequestSelectCard(Box<CardListItemVm>),
     RequestNewCard,
     AddTag(String),
     RemoveTag(String),
     SetTagFilter(Option<String>),
+    LoadMoreCards,
     ApplyFormat(MarkdownField, MarkdownAction),
     ApplyBlockDir(MarkdownField, String),
     OpenLinkEditor(MarkdownField),
@@ -24,6 +26,9 @@ pub enum EditorIntent {
     UpdateLinkEditorUrl(String),
     ApplyLink(MarkdownField),
     RemoveLink(MarkdownField),
+    AttachImage(MarkdownField, FileData),
+    PasteImage(MarkdownField, Vec<u8>),
+    RemoveImage(MarkdownField),
     ConfirmDiscard,
     CancelDiscard,
     OpenDeleteModal,
@@ -41,8 +46,16 @@ pub enum EditorIntent {
     OpenResetDeckModal,
     CloseResetDeckModal,
     ConfirmResetDeck,
+    SetDeckArchived(bool),
+    SetDeckFavorite(DeckId, bool),
+    ToggleArchivedDecks,
     CloseDuplicateModal,
     ConfirmDuplicate,
     Delete,
+    SetBulkSelection(Vec<CardId>),
+    BulkDelete,
+    BulkSuspend(bool),
+    BulkTag(String, bool),
+    ResetCard,
     CancelNew,
 }
@@ -1,11 +1,15 @@
-use dioxus::document::eval;
 use dioxus::prelude::*;
+use learn_core::model::KeyBindings;
 
-use crate::vm::{DeckOptionVm, MarkdownAction, MarkdownField, filter_card_list_items};
-use crate::views::{ViewState, view_state_from_resource};
+use crate::app::Keymap;
+use crate::shortcuts::{SHOW_SHORTCUTS, editor};
+use crate::vm::{
+    DeckOptionVm, MarkdownAction, MarkdownEdit, MarkdownField, filter_card_list_items,
+};
+use crate::views::{ViewState, open_shortcuts_legend, view_state_from_resource};
 
-use super::super::state::{DeleteState, EditorState, SaveRequest};
-use super::super::scripts::exec_command_script;
+use super::super::state::{DeleteState, EditorState, SaveRequest, SaveState};
+use super::super::scripts::{read_focused_element_id, read_selection_range, restore_field};
 use super::intent::EditorIntent;
 
 fn current_deck_label(
@@ -22,35 +26,53 @@ fn current_deck_label(
 }
 
 fn handle_undo_redo(state: &EditorState, evt: &KeyboardEvent) -> bool {
-    let modifiers = evt.data.modifiers();
-    if !modifiers.contains(Modifiers::CONTROL) && !modifiers.contains(Modifiers::META) {
+    let redo = editor::REDO.matches(&evt.data);
+    if !redo && !editor::UNDO.matches(&evt.data) {
         return false;
     }
 
-    let wants_undo = matches!(
-        evt.data.key(),
-        Key::Character(value) if value.eq_ignore_ascii_case("z")
-    );
-    let wants_redo = matches!(
-        evt.data.key(),
-        Key::Character(value) if value.eq_ignore_ascii_case("y")
-    );
-    if !wants_undo && !wants_redo {
-        return false;
-    }
-
-    let active_field = (state.last_focus_field)();
-    let element_id = if active_field == MarkdownField::Front {
-        "prompt"
-    } else {
-        "answer"
-    };
-    let redo = wants_redo || (wants_undo && modifiers.contains(Modifiers::SHIFT));
-    let command = if redo { "redo" } else { "undo" };
     evt.prevent_default();
+    let last_focus_field = (state.last_focus_field)();
+    let mut edit_history = state.edit_history;
+    let mut save_state = state.save_state;
+    let prompt_text = state.prompt_text;
+    let answer_text = state.answer_text;
+    let extra_text = state.extra_text;
     spawn(async move {
-        let script = exec_command_script(element_id, command, None);
-        let _ = eval(&script).await;
+        // `None` is the extra field, which has no markdown toolbar.
+        let field = match read_focused_element_id().await.as_str() {
+            "extra" => None,
+            "answer" => Some(MarkdownField::Back),
+            "prompt" => Some(MarkdownField::Front),
+            _ => Some(last_focus_field),
+        };
+        let (element_id, mut text) = match field {
+            Some(MarkdownField::Front) => ("prompt", prompt_text),
+            Some(MarkdownField::Back) => ("answer", answer_text),
+            None => ("extra", extra_text),
+        };
+        let current = MarkdownEdit {
+            text: text.peek().clone(),
+            selection: read_selection_range(element_id).await.unwrap_or_default(),
+        };
+        let restored = {
+            let mut histories = edit_history.write();
+            let history = match field {
+                Some(field) => histories.field_mut(field),
+                None => &mut histories.extra,
+            };
+            if redo {
+                history.redo(current)
+            } else {
+                history.undo(current)
+            }
+        };
+        let Some(restored) = restored else {
+            return;
+        };
+        restore_field(element_id, &restored.text, restored.selection).await;
+        text.set(restored.text);
+        save_state.set(SaveState::Idle);
     });
     true
 }
@@ -59,24 +81,21 @@ fn handle_primary_meta_actions(
     state: &EditorState,
     evt: &KeyboardEvent,
     dispatch: &Callback<EditorIntent>,
+    bindings: &KeyBindings,
 ) -> bool {
-    if !evt.data.modifiers().contains(Modifiers::META) {
-        return false;
-    }
-
-    if evt.data.key() == Key::Enter {
+    if editor::save(bindings).matches(&evt.data) {
         evt.prevent_default();
         dispatch.call(EditorIntent::Save(SaveRequest::new(false)));
         return true;
     }
 
-    if matches!(evt.data.key(), Key::Character(value) if value.eq_ignore_ascii_case("n")) {
+    if editor::new_card(bindings).matches(&evt.data) {
         evt.prevent_default();
         dispatch.call(EditorIntent::RequestNewCard);
         return true;
     }
 
-    if evt.data.key() == Key::Backspace
+    if editor::delete_card(bindings).matches(&evt.data)
         && (state.selected_card_id)().is_some()
         && !(state.is_create_mode)()
         && (state.delete_state)() != DeleteState::Deleting
@@ -94,10 +113,7 @@ fn handle_rename_shortcut(
     evt: &KeyboardEvent,
     dispatch: &Callback<EditorIntent>,
 ) -> bool {
-    if !evt.data.modifiers().contains(Modifiers::META) {
-        return false;
-    }
-    if !matches!(evt.data.key(), Key::Character(value) if value.eq_ignore_ascii_case("r")) {
+    if !editor::RENAME_DECK.matches(&evt.data) {
         return false;
     }
     if !matches!(
@@ -119,10 +135,6 @@ fn handle_format_shortcuts(
     evt: &KeyboardEvent,
     dispatch: &Callback<EditorIntent>,
 ) -> bool {
-    if !evt.data.modifiers().contains(Modifiers::META) {
-        return false;
-    }
-
     let can_edit = (state.is_create_mode)() || (state.selected_card_id)().is_some();
     if !can_edit {
         return false;
@@ -134,35 +146,26 @@ fn handle_format_shortcuts(
     }
 
     let field = active_field;
-    let shift = evt.data.modifiers().contains(Modifiers::SHIFT);
-    match evt.data.key() {
-        Key::Character(value) if value.eq_ignore_ascii_case("b") => {
-            evt.prevent_default();
-            dispatch.call(EditorIntent::ApplyFormat(field, MarkdownAction::Bold));
-            true
-        }
-        Key::Character(value) if value.eq_ignore_ascii_case("i") => {
-            evt.prevent_default();
-            dispatch.call(EditorIntent::ApplyFormat(field, MarkdownAction::Italic));
-            true
-        }
-        Key::Character(value) if value.eq_ignore_ascii_case("k") => {
-            evt.prevent_default();
-            dispatch.call(EditorIntent::OpenLinkEditor(field));
-            true
-        }
-        Key::Character(value) if value == "7" && shift => {
-            evt.prevent_default();
-            dispatch.call(EditorIntent::ApplyFormat(field, MarkdownAction::NumberedList));
-            true
-        }
-        Key::Character(value) if value == "8" && shift => {
-            evt.prevent_default();
-            dispatch.call(EditorIntent::ApplyFormat(field, MarkdownAction::BulletList));
-            true
-        }
-        _ => false,
-    }
+    let intent = if editor::LINK.matches(&evt.data) {
+        EditorIntent::OpenLinkEditor(field)
+    } else {
+        let formats = [
+            (editor::BOLD, MarkdownAction::Bold),
+            (editor::ITALIC, MarkdownAction::Italic),
+            (editor::NUMBERED_LIST, MarkdownAction::NumberedList),
+            (editor::BULLET_LIST, MarkdownAction::BulletList),
+        ];
+        let Some((_, action)) = formats
+            .into_iter()
+            .find(|(shortcut, _)| shortcut.matches(&evt.data))
+        else {
+            return false;
+        };
+        EditorIntent::ApplyFormat(field, action)
+    };
+    evt.prevent_default();
+    dispatch.call(intent);
+    true
 }
 
 pub(super) fn build_on_key_action(
@@ -170,16 +173,31 @@ pub(super) fn build_on_key_action(
     dispatch: Callback<EditorIntent>,
 ) -> Callback<KeyboardEvent> {
     let state = state.clone();
+    let keymap = try_use_context::<Keymap>();
     use_callback(move |evt: KeyboardEvent| {
         if evt.data.key() == Key::Tab {
             return;
         }
 
+        let mut show_shortcuts = state.show_shortcuts;
+        if show_shortcuts() {
+            if evt.data.key() == Key::Escape {
+                evt.prevent_default();
+                show_shortcuts.set(false);
+            }
+            return;
+        }
+        if SHOW_SHORTCUTS.matches(&evt.data) {
+            open_shortcuts_legend(show_shortcuts);
+            return;
+        }
+
         if handle_undo_redo(&state, &evt) {
             return;
         }
 
-        if handle_primary_meta_actions(&state, &evt, &dispatch) {
+        let bindings = keymap.map(Keymap::bindings).unwrap_or_default();
+        if handle_primary_meta_actions(&state, &evt, &dispatch, &bindings) {
             return;
         }
 
@@ -191,7 +209,7 @@ pub(super) fn build_on_key_action(
             return;
         }
 
-        if evt.data.key() == Key::Escape && (state.is_create_mode)() {
+        if editor::CANCEL_NEW.matches(&evt.data) && (state.is_create_mode)() {
             evt.prevent_default();
             dispatch.call(EditorIntent::CancelNew);
         }
@@ -203,9 +221,12 @@ pub(super) fn build_list_on_key_action(
     dispatch: Callback<EditorIntent>,
 ) -> Callback<KeyboardEvent> {
     let state = state.clone();
-    let cards_state = view_state_from_resource(&state.cards_resource);
+    let cards_state = state.cards_state();
     use_callback(move |evt: KeyboardEvent| {
-        if !evt.data.modifiers().is_empty() {
+        if !editor::LIST_NAVIGATION
+            .iter()
+            .any(|shortcut| shortcut.matches(&evt.data))
+        {
             return;
         }
 
@@ -227,7 +248,7 @@ pub(super) fn build_list_on_key_action(
 
         if let Some(item) = filtered.get(next_index).cloned() {
             evt.prevent_default();
-            dispatch.call(EditorIntent::RequestSelectCard(item));
+            dispatch.call(EditorIntent::RequestSelectCard(Box::new(item)));
         }
     })
 }
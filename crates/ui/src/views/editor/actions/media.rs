@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use dioxus::html::FileData;
+use dioxus::prelude::*;
+use learn_core::model::MediaValidationError;
+use learn_core::model::content::MAX_IMAGE_SIZE_BYTES;
+use services::{MediaService, MediaServiceError};
+
+use crate::vm::MarkdownField;
+use crate::views::ViewError;
+
+use super::super::state::{EditorServices, EditorState, SaveState};
+
+fn media_error_message(err: &MediaServiceError) -> String {
+    match err {
+        MediaServiceError::Invalid(err) => err.to_string(),
+        _ => ViewError::Unknown.message().to_string(),
+    }
+}
+
+/// Validate and store an image, then attach it to `field`.
+async fn store_field_image(
+    state: EditorState,
+    media_service: Arc<MediaService>,
+    field: MarkdownField,
+    bytes: Vec<u8>,
+) {
+    let mut media_error = state.media_error;
+    let mut save_state = state.save_state;
+    let mut target = match field {
+        MarkdownField::Front => state.prompt_media_id,
+        MarkdownField::Back => state.answer_media_id,
+    };
+    match media_service.store_image(bytes).await {
+        Ok(media_id) => {
+            target.set(Some(media_id));
+            save_state.set(SaveState::Idle);
+        }
+        Err(err) => media_error.set(Some(media_error_message(&err))),
+    }
+}
+
+pub(super) fn build_attach_image_action(
+    state: &EditorState,
+    services: &EditorServices,
+) -> Callback<(MarkdownField, FileData)> {
+    let state = state.clone();
+    let media_service = services.media_service.clone();
+    use_callback(move |(field, file): (MarkdownField, FileData)| {
+        let mut media_error = state.media_error;
+        media_error.set(None);
+        if file.size() > MAX_IMAGE_SIZE_BYTES {
+            media_error.set(Some(MediaValidationError::ImageTooLarge.to_string()));
+            return;
+        }
+
+        let state = state.clone();
+        let media_service = media_service.clone();
+        spawn(async move {
+            let Ok(bytes) = file.read_bytes().await else {
+                media_error.set(Some(ViewError::Unknown.message().to_string()));
+                return;
+            };
+            store_field_image(state, media_service, field, bytes.to_vec()).await;
+        });
+    })
+}
+
+pub(super) fn build_paste_image_action(
+    state: &EditorState,
+    services: &EditorServices,
+) -> Callback<(MarkdownField, Vec<u8>)> {
+    let state = state.clone();
+    let media_service = services.media_service.clone();
+    use_callback(move |(field, bytes): (MarkdownField, Vec<u8>)| {
+        let mut media_error = state.media_error;
+        media_error.set(None);
+        spawn(store_field_image(state.clone(), media_service.clone(), field, bytes));
+    })
+}
+
+pub(super) fn build_remove_image_action(state: &EditorState) -> Callback<MarkdownField> {
+    let state = state.clone();
+    use_callback(move |field: MarkdownField| {
+        let mut target = match field {
+            MarkdownField::Front => state.prompt_media_id,
+            MarkdownField::Back => state.answer_media_id,
+        };
+        let mut media_error = state.media_error;
+        target.set(None);
+        media_error.set(None);
+    })
+}
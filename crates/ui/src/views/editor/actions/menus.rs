@@ -29,7 +29,7 @@ pub(super) fn build_discard_actions(
         if let Some(action) = pending_action() {
             match action {
                 PendingAction::SelectCard(item) => {
-                    select_card_action.call(item);
+                    select_card_action.call(*item);
                 }
                 PendingAction::SelectDeck(deck_id) => {
                     apply_select_deck_action.call(deck_id);
@@ -315,6 +315,7 @@ pub(super) fn build_replace_writing_tools_action(
         let mut prompt_render_html = state.prompt_render_html;
         let mut answer_render_html = state.answer_render_html;
         let mut save_state = state.save_state;
+        let mut ai_assisted = state.ai_assisted;
         let element_id = match field {
             MarkdownField::Front => "prompt",
             MarkdownField::Back => "answer",
@@ -333,6 +334,7 @@ pub(super) fn build_replace_writing_tools_action(
                         answer_render_html.set(updated);
                     }
                 }
+                ai_assisted.set(true);
                 save_state.set(SaveState::Idle);
             }
         });
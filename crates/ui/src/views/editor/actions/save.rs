@@ -1,27 +1,45 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use dioxus::prelude::*;
 use dioxus_router::Navigator;
-use learn_core::model::{CardId, ContentDraft, DeckId, TagName};
+use learn_core::model::{Card, CardId, CardSource, ContentDraft, DeckId, MediaId, TagName};
+use services::CreateCardOptions;
 
 use crate::routes::Route;
-use crate::vm::{build_card_list_item, sanitize_html, strip_html_tags};
+use crate::vm::{
+    CharCounterVm, build_card_list_item, map_card_list_items, sanitize_html, strip_html_tags,
+};
 use crate::views::ViewError;
 
 use super::super::state::{
-    DeleteState, DuplicateCheckState, EditorServices, EditorState, SaveMenuState, SaveRequest,
-    SaveState,
+    CardListQuery, DeleteState, DuplicateCheckState, EditorServices, EditorState, SaveMenuState,
+    SaveRequest, SaveState,
 };
 use super::super::utils::tag_names_from_strings;
+use super::cards::apply_new_card_template;
 
 struct SavePayload {
     deck_id: DeckId,
     editing_id: Option<CardId>,
     prompt_html: String,
     answer_html: String,
+    prompt_media_id: Option<MediaId>,
+    answer_media_id: Option<MediaId>,
+    extra_text: String,
     tag_names: Vec<TagName>,
     practice: bool,
     skip_duplicate_check: bool,
+    generate_reverse: bool,
+    source: CardSource,
+}
+
+enum SavedCard {
+    /// A single new card, as stored.
+    Created(Box<Card>),
+    /// A new card and its reverse.
+    CreatedNote,
+    Updated(CardId),
 }
 
 fn is_blank_content(prompt_html: &str, answer_html: &str) -> bool {
@@ -30,6 +48,13 @@ fn is_blank_content(prompt_html: &str, answer_html: &str) -> bool {
     prompt_plain.trim().is_empty() || answer_plain.trim().is_empty()
 }
 
+/// Whether any field is past the length the card service would reject.
+fn is_too_long(prompt_html: &str, answer_html: &str, extra_text: &str) -> bool {
+    [prompt_html, answer_html, extra_text]
+        .iter()
+        .any(|text| CharCounterVm::new(text).over_limit())
+}
+
 fn build_save_payload(state: &EditorState, request: SaveRequest) -> Option<SavePayload> {
     let save_state = state.save_state;
     let duplicate_check_state = state.duplicate_check_state;
@@ -46,7 +71,10 @@ fn build_save_payload(state: &EditorState, request: SaveRequest) -> Option<SaveP
     let raw_answer = state.answer_text.read().to_string();
     let prompt_html = sanitize_html(&raw_prompt);
     let answer_html = sanitize_html(&raw_answer);
-    if is_blank_content(&prompt_html, &answer_html) {
+    let extra_text = state.extra_text.read().trim().to_owned();
+    if is_blank_content(&prompt_html, &answer_html)
+        || is_too_long(&prompt_html, &answer_html, &extra_text)
+    {
         let mut show_validation = state.show_validation;
         show_validation.set(true);
         return None;
@@ -68,9 +96,14 @@ fn build_save_payload(state: &EditorState, request: SaveRequest) -> Option<SaveP
         editing_id,
         prompt_html,
         answer_html,
+        prompt_media_id: (state.prompt_media_id)(),
+        answer_media_id: (state.answer_media_id)(),
+        extra_text,
         tag_names: tag_names_from_strings(&state.card_tags.read()),
         practice: request.practice,
         skip_duplicate_check: request.skip_duplicate_check,
+        generate_reverse: (state.generate_reverse)(),
+        source: if (state.ai_assisted)() { CardSource::Ai } else { CardSource::Manual },
     })
 }
 
@@ -103,39 +136,114 @@ async fn check_duplicate(
     }
 }
 
+fn extra_draft(payload: &SavePayload) -> Option<ContentDraft> {
+    (!payload.extra_text.is_empty()).then(|| ContentDraft::text_only(payload.extra_text.clone()))
+}
+
 async fn persist_card(
     card_service: &services::CardService,
     payload: &SavePayload,
-) -> Result<Option<CardId>, ViewError> {
+) -> Result<SavedCard, ViewError> {
+    let prompt = ContentDraft::new(payload.prompt_html.clone(), None)
+        .with_media_id(payload.prompt_media_id);
+    let answer = ContentDraft::new(payload.answer_html.clone(), None)
+        .with_media_id(payload.answer_media_id);
     let result = match payload.editing_id {
-        None => card_service
-            .create_card_with_tags(
+        None if !payload.generate_reverse => card_service
+            .create_card_returning(
                 payload.deck_id,
-                ContentDraft::new(payload.prompt_html.clone(), None),
-                ContentDraft::new(payload.answer_html.clone(), None),
+                prompt,
+                answer,
+                extra_draft(payload),
                 &payload.tag_names,
+                payload.source,
             )
             .await
-            .map(Some),
+            .map(|card| SavedCard::Created(Box::new(card))),
+        None => match card_service
+            .create_card_with_options(
+                payload.deck_id,
+                prompt,
+                answer,
+                &payload.tag_names,
+                CreateCardOptions {
+                    generate_reverse: payload.generate_reverse,
+                    source: payload.source,
+                },
+            )
+            .await
+        {
+            Ok(_) if payload.extra_text.is_empty() => Ok(SavedCard::CreatedNote),
+            Ok(created) => card_service
+                .update_card_extra(payload.deck_id, created.id, extra_draft(payload))
+                .await
+                .map(|()| SavedCard::CreatedNote),
+            Err(err) => Err(err),
+        },
+        Some(card_id) if payload.generate_reverse => {
+            match card_service
+                .update_card_and_reverse(payload.deck_id, card_id, prompt, answer)
+                .await
+            {
+                Ok(()) => card_service
+                    .set_tags_for_card(payload.deck_id, card_id, &payload.tag_names)
+                    .await
+                    .map(|_| SavedCard::Updated(card_id)),
+                Err(err) => Err(err),
+            }
+        }
         Some(card_id) => card_service
             .update_card_content_with_tags(
                 payload.deck_id,
                 card_id,
-                ContentDraft::new(payload.prompt_html.clone(), None),
-                ContentDraft::new(payload.answer_html.clone(), None),
+                prompt,
+                answer,
                 &payload.tag_names,
             )
             .await
-            .map(|()| Some(card_id)),
+            .map(|()| SavedCard::Updated(card_id)),
     };
-    result.map_err(|_| ViewError::Unknown)
+    let saved = result.map_err(|_| ViewError::Unknown)?;
+    if let SavedCard::Updated(card_id) = saved {
+        card_service
+            .update_card_extra(payload.deck_id, card_id, extra_draft(payload))
+            .await
+            .map_err(|_| ViewError::Unknown)?;
+    }
+    Ok(saved)
+}
+
+/// Put a newly created card at the top of the loaded list instead of reloading it.
+///
+/// Only the plain newest-first list is updated in place; returns `false` when the list has
+/// to be reloaded to show the card in the right spot.
+fn prepend_created_card(state: &EditorState, payload: &SavePayload, saved: &SavedCard) -> bool {
+    let SavedCard::Created(card) = saved else {
+        return false;
+    };
+    if !CardListQuery::current(state).is_newest_first() {
+        return false;
+    }
+    let tags = HashMap::from([(card.id(), payload.tag_names.clone())]);
+    // A card that was just created has no reviews, so it has no recall chance to compute.
+    let item =
+        map_card_list_items(std::slice::from_ref(card.as_ref()), &tags, None, card.created_at())
+            .remove(0);
+    let mut cards_resource = state.cards_resource;
+    match cards_resource.write().as_mut() {
+        Some(Ok(items)) => {
+            items.insert(0, item);
+            true
+        }
+        _ => false,
+    }
 }
 
 fn apply_save_success(
     state: &EditorState,
     navigator: Navigator,
     payload: &SavePayload,
-    card_id: Option<CardId>,
+    saved: SavedCard,
 ) {
     let mut save_state = state.save_state;
     let mut delete_state = state.delete_state;
@@ -159,7 +267,9 @@ fn apply_save_success(
     show_validation.set(false);
     save_menu_state.set(SaveMenuState::Closed);
     tag_input.set(String::new());
-    cards_resource.restart();
+    if !prepend_created_card(state, payload, &saved) {
+        cards_resource.restart();
+    }
     deck_tags_resource.restart();
     daily_limit_resource.restart();
     card_tags_resource.restart();
@@ -171,21 +281,23 @@ fn apply_save_success(
             });
         }
         (true, false) => {
+            let template = (state.new_card_template)();
             state.clear_editor_fields.borrow_mut()();
+            apply_new_card_template(state, template);
             card_tags.set(Vec::new());
             focus_prompt.set(true);
         }
         (false, _) => {
-            if let Some(card_id) = card_id {
+            if let SavedCard::Updated(card_id) = saved {
                 selected_card_id.set(Some(card_id));
                 state
                     .set_editor_fields
                     .borrow_mut()(payload.prompt_html.clone(), payload.answer_html.clone());
-                last_selected_card.set(Some(build_card_list_item(
-                    card_id,
-                    &payload.prompt_html,
-                    &payload.answer_html,
-                )));
+                last_selected_card.set(Some(
+                    build_card_list_item(card_id, &payload.prompt_html, &payload.answer_html)
+                        .with_media(payload.prompt_media_id, payload.answer_media_id)
+                        .with_extra(&payload.extra_text),
+                ));
                 last_selected_tags.set(card_tags.read().clone());
                 focus_prompt.set(true);
             }
@@ -224,7 +336,7 @@ async fn run_save(
     let mut save_state = state.save_state;
     save_state.set(SaveState::Saving);
     match persist_card(&card_service, &payload).await {
-        Ok(card_id) => apply_save_success(&state, navigator, &payload, card_id),
+        Ok(saved) => apply_save_success(&state, navigator, &payload, saved),
         Err(err) => save_state.set(SaveState::Error(err)),
     }
 }
@@ -251,7 +363,9 @@ pub(super) fn build_save_action(
 
 #[cfg(test)]
 mod tests {
-    use super::is_blank_content;
+    use learn_core::model::MAX_TEXT_CHARS;
+
+    use super::{is_blank_content, is_too_long};
 
     #[test]
     fn blank_content_detects_empty_html() {
@@ -265,4 +379,14 @@ mod tests {
         assert!(!is_blank_content("<p>Prompt</p>", "<p>Answer</p>"));
         assert!(!is_blank_content("Prompt", "<div>Answer</div>"));
     }
+
+    #[test]
+    fn too_long_checks_every_field_at_the_limit() {
+        let at_limit = "a".repeat(MAX_TEXT_CHARS);
+        let over = format!("{at_limit}a");
+        assert!(!is_too_long(&at_limit, &at_limit, &at_limit));
+        assert!(is_too_long(&over, "Answer", ""));
+        assert!(is_too_long("Prompt", &over, ""));
+        assert!(is_too_long("Prompt", "Answer", &over));
+    }
 }
@@ -1,12 +1,15 @@
+use dioxus::html::FileData;
 use dioxus::prelude::*;
-use learn_core::model::CardId;
+use learn_core::model::{CardId, MediaId};
 
-use crate::vm::{MarkdownAction, MarkdownField};
+use crate::vm::{CardHistoryRowVm, CharCounterVm, MarkdownAction, MarkdownField};
+use crate::views::{MediaImage, ViewState};
 
-use super::EditorFormatToolbar;
+use super::{EditorFormatToolbar, EditorPreviewPane};
 use super::super::state::{
-    DeleteState, DuplicateCheckState, LinkEditorState, SaveMenuState, SaveRequest, SaveState,
-    WritingToolsCommand, WritingToolsMenuState, WritingToolsResultStatus, WritingToolsTone,
+    DeleteState, DuplicateCheckState, ImagePasteChoice, LinkEditorState, PasteChoice, PendingPaste,
+    ResetCardState, SaveMenuState, SaveRequest, SaveState, WritingToolsCommand,
+    WritingToolsMenuState, WritingToolsResultStatus, WritingToolsTone,
 };
 
 #[component]
@@ -19,14 +22,21 @@ pub fn EditorDetailPane(
     can_submit: bool,
     prompt_invalid: bool,
     answer_invalid: bool,
+    prompt_counter: CharCounterVm,
+    answer_counter: CharCounterVm,
+    extra_counter: CharCounterVm,
     prompt_toolbar_disabled: bool,
     answer_toolbar_disabled: bool,
     tag_input_value: String,
     tag_suggestions: Vec<String>,
     card_tags: Vec<String>,
+    generate_reverse: bool,
     daily_limit_warning: Option<String>,
+    recall_label: Option<String>,
+    source_label: Option<String>,
     save_state: SaveState,
     delete_state: DeleteState,
+    reset_card_state: ResetCardState,
     duplicate_check_state: DuplicateCheckState,
     save_menu_state: SaveMenuState,
     writing_tools_menu_state: WritingToolsMenuState,
@@ -37,9 +47,20 @@ pub fn EditorDetailPane(
     writing_tools_result_title: String,
     writing_tools_result_html: String,
     link_editor_state: Option<LinkEditorState>,
+    pending_paste: Option<PendingPaste>,
+    image_paste_field: Option<MarkdownField>,
+    prompt_media_id: Option<MediaId>,
+    answer_media_id: Option<MediaId>,
+    media_error: Option<String>,
+    extra_text: String,
+    show_preview: bool,
+    preview_prompt_html: String,
+    preview_answer_html: String,
+    card_history: ViewState<Vec<CardHistoryRowVm>>,
     on_focus_field: Callback<MarkdownField>,
     on_prompt_input: Callback<()>,
     on_answer_input: Callback<()>,
+    on_extra_input: Callback<String>,
     on_format: Callback<(MarkdownField, MarkdownAction)>,
     on_block_dir: Callback<(MarkdownField, String)>,
     on_indent: Callback<(MarkdownField, bool)>,
@@ -49,14 +70,21 @@ pub fn EditorDetailPane(
     on_update_link_url: Callback<String>,
     on_apply_link: Callback<MarkdownField>,
     on_remove_link: Callback<MarkdownField>,
+    on_resolve_paste: Callback<PasteChoice>,
+    on_resolve_image_paste: Callback<ImagePasteChoice>,
+    on_attach_image: Callback<(MarkdownField, FileData)>,
+    on_remove_image: Callback<MarkdownField>,
     on_tag_input_change: Callback<String>,
     on_tag_add: Callback<String>,
     on_tag_remove: Callback<String>,
+    on_toggle_reverse: Callback<bool>,
     on_cancel: Callback<()>,
     on_open_delete: Callback<()>,
+    on_reset_card: Callback<()>,
     on_save: Callback<SaveRequest>,
     on_toggle_save_menu: Callback<()>,
     on_close_save_menu: Callback<()>,
+    on_toggle_preview: Callback<()>,
     on_toggle_writing_tools: Callback<MarkdownField>,
     on_update_writing_tools_prompt: Callback<String>,
     on_select_writing_tools_tone: Callback<WritingToolsTone>,
@@ -69,6 +97,10 @@ pub fn EditorDetailPane(
     let tag_input_for_keydown = tag_input_value.clone();
     let tag_input_for_blur = tag_input_value.clone();
     let card_tags_for_backspace = card_tags.clone();
+    let prompt_paste = pending_paste
+        .clone()
+        .filter(|pending| pending.field == MarkdownField::Front);
+    let answer_paste = pending_paste.filter(|pending| pending.field == MarkdownField::Back);
 
     rsx! {
         section { class: "editor-detail",
@@ -85,199 +117,373 @@ pub fn EditorDetailPane(
                         span { class: "editor-detail-dirty", "• Unsaved" }
                     }
                 }
-            }
-
-            div { class: "editor-body",
-                if !can_edit {
-                    p { class: "editor-empty-hint", "Select a card or click + New Card." }
-                }
-                div { class: "editor-group editor-group--editor",
-                    div { class: "editor-field-header",
-                        label { class: "editor-label", r#for: "prompt", "Front" }
+                if let Some(recall) = recall_label {
+                    span {
+                        class: "editor-detail-recall",
+                        title: "Predicted chance of recalling this card now",
+                        "Recall {recall}"
                     }
-                    EditorFormatToolbar {
-                        field: MarkdownField::Front,
-                        disabled: prompt_toolbar_disabled,
-                        writing_menu_state: writing_tools_menu_state,
-                        writing_prompt: writing_tools_prompt.clone(),
-                        writing_tone: writing_tools_tone,
-                        writing_result_status: writing_tools_result_status,
-                        writing_result_target: writing_tools_result_target,
-                        writing_result_title: writing_tools_result_title.clone(),
-                        writing_result_html: writing_tools_result_html.clone(),
-                        link_editor_state: link_editor_state.clone(),
-                        on_format,
-                        on_block_dir,
-                        on_open_link_editor,
-                        on_close_link_editor,
-                        on_update_link_url,
-                        on_apply_link,
-                        on_remove_link,
-                        on_toggle_writing_menu: on_toggle_writing_tools,
-                        on_writing_prompt_change: on_update_writing_tools_prompt,
-                        on_select_writing_tone: on_select_writing_tools_tone,
-                        on_select_writing_command: on_select_writing_tools_command,
-                        on_writing_result_replace: on_replace_writing_tools,
-                        on_writing_result_copy: on_copy_writing_tools,
-                    }
-                    div {
-                        id: "prompt",
-                        class: if prompt_invalid {
-                            "editor-input editor-input--multi editor-input--error"
-                        } else {
-                            "editor-input editor-input--multi"
-                        },
-                        contenteditable: "{can_edit}",
-                        dir: "auto",
-                        aria_label: "Front",
-                        role: "textbox",
-                        aria_multiline: "true",
-                        aria_placeholder: "Enter the prompt for the front of the card...",
-                        spellcheck: "true",
-                        tabindex: "0",
-                        onfocus: move |_| on_focus_field.call(MarkdownField::Front),
-                        onclick: move |evt| {
-                            on_link_open_click.call((MarkdownField::Front, evt));
-                        },
-                        onkeydown: move |evt| {
-                            if evt.data.key() == Key::Tab {
-                                evt.prevent_default();
-                                let outdent = evt.data.modifiers().contains(Modifiers::SHIFT);
-                                on_indent.call((MarkdownField::Front, outdent));
-                            }
-                        },
-                        oninput: move |_| on_prompt_input.call(()),
-                    }
-                    if prompt_invalid {
-                        p { class: "editor-error", "Front is required." }
+                }
+                if let Some(source) = source_label {
+                    span { class: "editor-detail-source", "{source}" }
+                }
+                if can_edit {
+                    button {
+                        class: "btn editor-preview-toggle",
+                        r#type: "button",
+                        aria_pressed: "{show_preview}",
+                        title: "Show how the card will look in a session",
+                        onclick: move |_| on_toggle_preview.call(()),
+                        "Preview"
                     }
                 }
+            }
 
-                div { class: "editor-group editor-group--editor",
-                    div { class: "editor-field-header",
-                        label { class: "editor-label", r#for: "answer", "Back" }
+            div {
+                class: if show_preview {
+                    "editor-workspace editor-workspace--preview"
+                } else {
+                    "editor-workspace"
+                },
+                div { class: "editor-body",
+                    if !can_edit {
+                        p { class: "editor-empty-hint", "Select a card or click + New Card." }
                     }
-                    EditorFormatToolbar {
-                        field: MarkdownField::Back,
-                        disabled: answer_toolbar_disabled,
-                        writing_menu_state: writing_tools_menu_state,
-                        writing_prompt: writing_tools_prompt,
-                        writing_tone: writing_tools_tone,
-                        writing_result_status: writing_tools_result_status,
-                        writing_result_target: writing_tools_result_target,
-                        writing_result_title: writing_tools_result_title,
-                        writing_result_html: writing_tools_result_html,
-                        link_editor_state,
-                        on_format,
-                        on_block_dir,
-                        on_open_link_editor,
-                        on_close_link_editor,
-                        on_update_link_url,
-                        on_apply_link,
-                        on_remove_link,
-                        on_toggle_writing_menu: on_toggle_writing_tools,
-                        on_writing_prompt_change: on_update_writing_tools_prompt,
-                        on_select_writing_tone: on_select_writing_tools_tone,
-                        on_select_writing_command: on_select_writing_tools_command,
-                        on_writing_result_replace: on_replace_writing_tools,
-                        on_writing_result_copy: on_copy_writing_tools,
-                    }
-                    div {
-                        id: "answer",
-                        class: if answer_invalid {
-                            "editor-input editor-input--multi editor-input--error"
-                        } else {
-                            "editor-input editor-input--multi"
-                        },
-                        contenteditable: "{can_edit}",
-                        dir: "auto",
-                        aria_label: "Back",
-                        role: "textbox",
-                        aria_multiline: "true",
-                        aria_placeholder: "Enter the answer for the back of the card...",
-                        spellcheck: "true",
-                        tabindex: "0",
-                        onfocus: move |_| on_focus_field.call(MarkdownField::Back),
-                        onclick: move |evt| {
-                            on_link_open_click.call((MarkdownField::Back, evt));
-                        },
-                        onkeydown: move |evt| {
-                            if evt.data.key() == Key::Tab {
-                                evt.prevent_default();
-                                let outdent = evt.data.modifiers().contains(Modifiers::SHIFT);
-                                on_indent.call((MarkdownField::Back, outdent));
+                    div { class: "editor-group editor-group--editor",
+                        div { class: "editor-field-header",
+                            label { class: "editor-label", r#for: "prompt", "Front" }
+                            EditorCharCounter { counter: prompt_counter }
+                        }
+                        EditorFormatToolbar {
+                            field: MarkdownField::Front,
+                            disabled: prompt_toolbar_disabled,
+                            writing_menu_state: writing_tools_menu_state,
+                            writing_prompt: writing_tools_prompt.clone(),
+                            writing_tone: writing_tools_tone,
+                            writing_result_status: writing_tools_result_status,
+                            writing_result_target: writing_tools_result_target,
+                            writing_result_title: writing_tools_result_title.clone(),
+                            writing_result_html: writing_tools_result_html.clone(),
+                            link_editor_state: link_editor_state.clone(),
+                            on_format,
+                            on_block_dir,
+                            on_open_link_editor,
+                            on_close_link_editor,
+                            on_update_link_url,
+                            on_apply_link,
+                            on_remove_link,
+                            on_attach_image,
+                            on_toggle_writing_menu: on_toggle_writing_tools,
+                            on_writing_prompt_change: on_update_writing_tools_prompt,
+                            on_select_writing_tone: on_select_writing_tools_tone,
+                            on_select_writing_command: on_select_writing_tools_command,
+                            on_writing_result_replace: on_replace_writing_tools,
+                            on_writing_result_copy: on_copy_writing_tools,
+                        }
+                        div {
+                            id: "prompt",
+                            class: if prompt_invalid {
+                                "editor-input editor-input--multi editor-input--error"
+                            } else {
+                                "editor-input editor-input--multi"
+                            },
+                            contenteditable: "{can_edit}",
+                            dir: "auto",
+                            aria_label: "Front",
+                            role: "textbox",
+                            aria_multiline: "true",
+                            aria_placeholder: "Enter the prompt for the front of the card...",
+                            spellcheck: "true",
+                            tabindex: "0",
+                            onfocus: move |_| on_focus_field.call(MarkdownField::Front),
+                            onclick: move |evt| {
+                                on_link_open_click.call((MarkdownField::Front, evt));
+                            },
+                            onkeydown: move |evt| {
+                                if evt.data.key() == Key::Tab {
+                                    evt.prevent_default();
+                                    let outdent = evt.data.modifiers().contains(Modifiers::SHIFT);
+                                    on_indent.call((MarkdownField::Front, outdent));
+                                }
+                            },
+                            oninput: move |_| on_prompt_input.call(()),
+                        }
+                        if let Some(pending) = prompt_paste {
+                            EditorPasteOffer { pending, on_resolve: on_resolve_paste }
+                        }
+                        if image_paste_field == Some(MarkdownField::Front) {
+                            EditorImagePasteOffer { on_resolve: on_resolve_image_paste }
+                        }
+                        if let Some(media_id) = prompt_media_id {
+                            div { class: "editor-image",
+                                MediaImage { media_id, class: "editor-image-preview" }
+                                if can_edit {
+                                    button {
+                                        class: "editor-image-remove",
+                                        r#type: "button",
+                                        aria_label: "Remove image",
+                                        onclick: move |_| {
+                                            on_remove_image.call(MarkdownField::Front);
+                                        },
+                                        "Remove image"
+                                    }
+                                }
                             }
-                        },
-                        oninput: move |_| on_answer_input.call(()),
-                    }
-                    if answer_invalid {
-                        p { class: "editor-error", "Back is required." }
+                        }
+                        if prompt_invalid && prompt_counter.over_limit() {
+                            p { class: "editor-error",
+                                "Front can be at most {prompt_counter.max} characters."
+                            }
+                        } else if prompt_invalid {
+                            p { class: "editor-error", "Front is required." }
+                        }
                     }
-                }
 
-                div { class: "editor-group",
-                    label { class: "editor-label", "Tags" }
-                    div { class: "editor-tag-input",
-                        for tag in card_tags {
-                            span { class: "editor-tag-chip",
-                                "{tag}"
+                    div { class: "editor-group editor-group--editor",
+                        div { class: "editor-field-header",
+                            label { class: "editor-label", r#for: "answer", "Back" }
+                            EditorCharCounter { counter: answer_counter }
+                        }
+                        EditorFormatToolbar {
+                            field: MarkdownField::Back,
+                            disabled: answer_toolbar_disabled,
+                            writing_menu_state: writing_tools_menu_state,
+                            writing_prompt: writing_tools_prompt,
+                            writing_tone: writing_tools_tone,
+                            writing_result_status: writing_tools_result_status,
+                            writing_result_target: writing_tools_result_target,
+                            writing_result_title: writing_tools_result_title,
+                            writing_result_html: writing_tools_result_html,
+                            link_editor_state,
+                            on_format,
+                            on_block_dir,
+                            on_open_link_editor,
+                            on_close_link_editor,
+                            on_update_link_url,
+                            on_apply_link,
+                            on_remove_link,
+                            on_attach_image,
+                            on_toggle_writing_menu: on_toggle_writing_tools,
+                            on_writing_prompt_change: on_update_writing_tools_prompt,
+                            on_select_writing_tone: on_select_writing_tools_tone,
+                            on_select_writing_command: on_select_writing_tools_command,
+                            on_writing_result_replace: on_replace_writing_tools,
+                            on_writing_result_copy: on_copy_writing_tools,
+                        }
+                        div {
+                            id: "answer",
+                            class: if answer_invalid {
+                                "editor-input editor-input--multi editor-input--error"
+                            } else {
+                                "editor-input editor-input--multi"
+                            },
+                            contenteditable: "{can_edit}",
+                            dir: "auto",
+                            aria_label: "Back",
+                            role: "textbox",
+                            aria_multiline: "true",
+                            aria_placeholder: "Enter the answer for the back of the card...",
+                            spellcheck: "true",
+                            tabindex: "0",
+                            onfocus: move |_| on_focus_field.call(MarkdownField::Back),
+                            onclick: move |evt| {
+                                on_link_open_click.call((MarkdownField::Back, evt));
+                            },
+                            onkeydown: move |evt| {
+                                if evt.data.key() == Key::Tab {
+                                    evt.prevent_default();
+                                    let outdent = evt.data.modifiers().contains(Modifiers::SHIFT);
+                                    on_indent.call((MarkdownField::Back, outdent));
+                                }
+                            },
+                            oninput: move |_| on_answer_input.call(()),
+                        }
+                        if let Some(pending) = answer_paste {
+                            EditorPasteOffer { pending, on_resolve: on_resolve_paste }
+                        }
+                        if image_paste_field == Some(MarkdownField::Back) {
+                            EditorImagePasteOffer { on_resolve: on_resolve_image_paste }
+                        }
+                        if let Some(media_id) = answer_media_id {
+                            div { class: "editor-image",
+                                MediaImage { media_id, class: "editor-image-preview" }
                                 if can_edit {
                                     button {
-                                        class: "editor-tag-remove",
+                                        class: "editor-image-remove",
                                         r#type: "button",
-                                        aria_label: "Remove tag",
-                                        onclick: move |_| on_tag_remove.call(tag.clone()),
-                                        "×"
+                                        aria_label: "Remove image",
+                                        onclick: move |_| on_remove_image.call(MarkdownField::Back),
+                                        "Remove image"
                                     }
                                 }
                             }
                         }
-                        input {
-                            class: "editor-tag-field",
-                            r#type: "text",
-                            placeholder: "Add tag",
-                            value: "{tag_input_value}",
+                        if answer_invalid && answer_counter.over_limit() {
+                            p { class: "editor-error",
+                                "Back can be at most {answer_counter.max} characters."
+                            }
+                        } else if answer_invalid {
+                            p { class: "editor-error", "Back is required." }
+                        }
+                    }
+
+                    if let Some(err) = media_error {
+                        p { class: "editor-error", role: "alert", "{err}" }
+                    }
+
+                    div { class: "editor-group",
+                        div { class: "editor-field-header",
+                            label { class: "editor-label", r#for: "extra", "Extra" }
+                            EditorCharCounter { counter: extra_counter }
+                        }
+                        textarea {
+                            id: "extra",
+                            class: if extra_counter.over_limit() {
+                                "editor-input editor-extra editor-input--error"
+                            } else {
+                                "editor-input editor-extra"
+                            },
+                            rows: "2",
+                            dir: "auto",
+                            placeholder: "Usage notes, pronunciation… shown after the answer",
+                            value: "{extra_text}",
                             disabled: !can_edit,
-                            oninput: move |evt| on_tag_input_change.call(evt.value()),
-                            onkeydown: move |evt| match evt.data.key() {
-                                Key::Enter => {
-                                    evt.prevent_default();
-                                    on_tag_add.call(tag_input_for_keydown.clone());
-                                }
-                                Key::Character(value) if value == "," => {
-                                    evt.prevent_default();
-                                    on_tag_add.call(tag_input_for_keydown.clone());
-                                }
-                                Key::Backspace => {
-                                    if tag_input_for_keydown.trim().is_empty()
-                                        && let Some(last_tag) = card_tags_for_backspace.last()
-                                    {
-                                        on_tag_remove.call(last_tag.clone());
+                            oninput: move |evt| on_extra_input.call(evt.value()),
+                        }
+                        if extra_counter.over_limit() {
+                            p { class: "editor-error",
+                                "Extra can be at most {extra_counter.max} characters."
+                            }
+                        }
+                    }
+
+                    div { class: "editor-group",
+                        label { class: "editor-label", "Tags" }
+                        div { class: "editor-tag-input",
+                            for tag in card_tags {
+                                span { class: "editor-tag-chip",
+                                    "{tag}"
+                                    if can_edit {
+                                        button {
+                                            class: "editor-tag-remove",
+                                            r#type: "button",
+                                            aria_label: "Remove tag",
+                                            onclick: move |_| on_tag_remove.call(tag.clone()),
+                                            "×"
+                                        }
                                     }
                                 }
-                                _ => {}
-                            },
-                            onblur: move |_| {
-                                let value = tag_input_for_blur.trim().to_string();
-                                if !value.is_empty() {
-                                    on_tag_add.call(value);
+                            }
+                            input {
+                                class: "editor-tag-field",
+                                r#type: "text",
+                                placeholder: "Add tag",
+                                value: "{tag_input_value}",
+                                disabled: !can_edit,
+                                oninput: move |evt| on_tag_input_change.call(evt.value()),
+                                onkeydown: move |evt| match evt.data.key() {
+                                    Key::Enter => {
+                                        evt.prevent_default();
+                                        on_tag_add.call(tag_input_for_keydown.clone());
+                                    }
+                                    Key::Character(value) if value == "," => {
+                                        evt.prevent_default();
+                                        on_tag_add.call(tag_input_for_keydown.clone());
+                                    }
+                                    Key::Backspace => {
+                                        if tag_input_for_keydown.trim().is_empty()
+                                            && let Some(last_tag) = card_tags_for_backspace.last()
+                                        {
+                                            on_tag_remove.call(last_tag.clone());
+                                        }
+                                    }
+                                    _ => {}
+                                },
+                                onblur: move |_| {
+                                    let value = tag_input_for_blur.trim().to_string();
+                                    if !value.is_empty() {
+                                        on_tag_add.call(value);
+                                    }
+                                },
+                            }
+                        }
+                        if can_edit && !tag_suggestions.is_empty() {
+                            div { class: "editor-tag-suggestions",
+                                for suggestion in tag_suggestions {
+                                    button {
+                                        class: "editor-tag-suggestion",
+                                        r#type: "button",
+                                        onclick: move |_| on_tag_add.call(suggestion.clone()),
+                                        "{suggestion}"
+                                    }
                                 }
-                            },
+                            }
                         }
                     }
-                    if can_edit && !tag_suggestions.is_empty() {
-                        div { class: "editor-tag-suggestions",
-                            for suggestion in tag_suggestions {
-                                button {
-                                    class: "editor-tag-suggestion",
-                                    r#type: "button",
-                                    onclick: move |_| on_tag_add.call(suggestion.clone()),
-                                    "{suggestion}"
-                                }
+
+                    if can_edit {
+                        label { class: "editor-reverse-toggle",
+                            input {
+                                r#type: "checkbox",
+                                checked: generate_reverse,
+                                onchange: move |evt| on_toggle_reverse.call(evt.checked()),
+                            }
+                            if is_create_mode {
+                                "Also create reverse card"
+                            } else {
+                                "Also update reverse card"
                             }
                         }
                     }
                 }
+                if show_preview {
+                    EditorPreviewPane {
+                        prompt_html: preview_prompt_html,
+                        answer_html: preview_answer_html,
+                        prompt_media_id,
+                        answer_media_id,
+                    }
+                }
+            }
+
+            if can_show_delete {
+                details { class: "editor-history",
+                    summary { class: "editor-history-summary", "Review history" }
+                    match card_history {
+                        ViewState::Idle | ViewState::Loading => rsx! {
+                            p { class: "editor-history-empty", "Loading history..." }
+                        },
+                        ViewState::Error(err) => rsx! {
+                            p { class: "editor-error", "{err.message()}" }
+                        },
+                        ViewState::Ready(rows) if rows.is_empty() => rsx! {
+                            p { class: "editor-history-empty", "No reviews yet." }
+                        },
+                        ViewState::Ready(rows) => rsx! {
+                            ol { class: "editor-history-list",
+                                for row in rows {
+                                    li { class: "editor-history-row",
+                                        span {
+                                            class: "history-metric history-metric--{row.grade_modifier}",
+                                            "{row.grade_label}"
+                                        }
+                                        span {
+                                            class: "editor-history-time",
+                                            title: "{row.reviewed_at_str}",
+                                            "{row.reviewed_ago}"
+                                        }
+                                        span { class: "editor-history-stat",
+                                            "Interval {row.interval_label}"
+                                        }
+                                        span { class: "editor-history-stat",
+                                            "Stability {row.stability_label}"
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                    }
+                }
             }
 
             footer { class: "editor-footer",
@@ -285,6 +491,12 @@ pub fn EditorDetailPane(
                     if let Some(message) = daily_limit_warning {
                         span { class: "editor-warning", "{message}" }
                     }
+                    match reset_card_state {
+                        ResetCardState::Resetting => rsx! { span { "Resetting progress..." } },
+                        ResetCardState::Success => rsx! { span { "Progress reset." } },
+                        ResetCardState::Error(err) => rsx! { span { "{err.message()}" } },
+                        ResetCardState::Idle => rsx! {},
+                    }
                     match delete_state {
                         DeleteState::Idle => match duplicate_check_state {
                             DuplicateCheckState::Checking => rsx! { span { "Checking..." } },
@@ -316,6 +528,15 @@ pub fn EditorDetailPane(
                         "Cancel"
                     }
                     if can_show_delete {
+                        button {
+                            class: "btn editor-reset-card",
+                            r#type: "button",
+                            title: "Forget this card's scheduling and study it as new",
+                            disabled: reset_card_state == ResetCardState::Resetting
+                                || save_state == SaveState::Saving,
+                            onclick: move |_| on_reset_card.call(()),
+                            "Reset progress"
+                        }
                         button {
                             class: "btn editor-delete",
                             r#type: "button",
@@ -380,3 +601,77 @@ pub fn EditorDetailPane(
         }
     }
 }
+
+#[component]
+fn EditorCharCounter(counter: CharCounterVm) -> Element {
+    rsx! {
+        span {
+            class: if counter.near_limit() {
+                "editor-char-count editor-char-count--near"
+            } else {
+                "editor-char-count"
+            },
+            aria_live: "polite",
+            "{counter.label()}"
+        }
+    }
+}
+
+#[component]
+fn EditorImagePasteOffer(on_resolve: Callback<ImagePasteChoice>) -> Element {
+    rsx! {
+        div { class: "editor-paste-offer", role: "group", aria_label: "Paste options",
+            p { class: "editor-paste-offer-title", "This paste has an image and text." }
+            div { class: "editor-paste-actions",
+                button {
+                    class: "btn btn-primary",
+                    r#type: "button",
+                    onclick: move |_| on_resolve.call(ImagePasteChoice::Image),
+                    "Attach Image"
+                }
+                button {
+                    class: "btn",
+                    r#type: "button",
+                    onclick: move |_| on_resolve.call(ImagePasteChoice::Text),
+                    "Paste Text"
+                }
+                button {
+                    class: "editor-paste-cancel",
+                    r#type: "button",
+                    onclick: move |_| on_resolve.call(ImagePasteChoice::Cancel),
+                    "Cancel"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn EditorPasteOffer(pending: PendingPaste, on_resolve: Callback<PasteChoice>) -> Element {
+    rsx! {
+        div { class: "editor-paste-offer", role: "group", aria_label: "Paste options",
+            p { class: "editor-paste-offer-title", "This paste has lists or tables." }
+            pre { class: "editor-paste-preview", "{pending.offer.markdown}" }
+            div { class: "editor-paste-actions",
+                button {
+                    class: "btn btn-primary",
+                    r#type: "button",
+                    onclick: move |_| on_resolve.call(PasteChoice::Markdown),
+                    "Paste as Markdown"
+                }
+                button {
+                    class: "btn",
+                    r#type: "button",
+                    onclick: move |_| on_resolve.call(PasteChoice::PlainText),
+                    "Paste as Plain Text"
+                }
+                button {
+                    class: "editor-paste-cancel",
+                    r#type: "button",
+                    onclick: move |_| on_resolve.call(PasteChoice::Cancel),
+                    "Cancel"
+                }
+            }
+        }
+    }
+}
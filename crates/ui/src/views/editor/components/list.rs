@@ -2,90 +2,12 @@ use dioxus::prelude::*;
 use learn_core::model::CardId;
 use services::CardListSort;
 
-use crate::vm::{CardListItemVm, filter_card_list_items};
-use crate::views::ViewState;
+use crate::vm::{CardListItemVm, filter_card_list_items, flag_label, highlight_spans};
+use crate::views::{MediaImage, ViewState};
 
+use super::super::state::BulkActionState;
 use super::super::utils::{sort_from_value, sort_value};
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct HighlightSpan {
-    text: String,
-    is_match: bool,
-}
-
-fn highlight_spans(text: &str, query: &str) -> Vec<HighlightSpan> {
-    let needle = query.trim();
-    if needle.is_empty() || text.is_empty() {
-        return vec![HighlightSpan {
-            text: text.to_string(),
-            is_match: false,
-        }];
-    }
-
-    let mut lowered = Vec::new();
-    let mut map_start = Vec::new();
-    let mut map_end = Vec::new();
-
-    for (idx, ch) in text.char_indices() {
-        let end = idx + ch.len_utf8();
-        for lower in ch.to_lowercase() {
-            lowered.push(lower);
-            map_start.push(idx);
-            map_end.push(end);
-        }
-    }
-
-    let needle_chars: Vec<char> = needle.to_lowercase().chars().collect();
-    if needle_chars.is_empty() {
-        return vec![HighlightSpan {
-            text: text.to_string(),
-            is_match: false,
-        }];
-    }
-
-    let mut spans = Vec::new();
-    let mut cursor = 0usize;
-    let mut idx = 0usize;
-    while idx + needle_chars.len() <= lowered.len() {
-        if lowered[idx..idx + needle_chars.len()] == needle_chars[..] {
-            let start = map_start[idx];
-            let end = map_end[idx + needle_chars.len() - 1];
-            if start > cursor {
-                spans.push(HighlightSpan {
-                    text: text[cursor..start].to_string(),
-                    is_match: false,
-                });
-            }
-            if start < end {
-                spans.push(HighlightSpan {
-                    text: text[start..end].to_string(),
-                    is_match: true,
-                });
-            }
-            cursor = end;
-            idx += needle_chars.len();
-        } else {
-            idx += 1;
-        }
-    }
-
-    if cursor < text.len() {
-        spans.push(HighlightSpan {
-            text: text[cursor..].to_string(),
-            is_match: false,
-        });
-    }
-
-    if spans.is_empty() {
-        spans.push(HighlightSpan {
-            text: text.to_string(),
-            is_match: false,
-        });
-    }
-
-    spans
-}
-
 fn render_highlighted(text: &str, query: &str) -> Vec<Element> {
     highlight_spans(text, query)
         .into_iter()
@@ -106,12 +28,67 @@ fn render_highlighted(text: &str, query: &str) -> Vec<Element> {
         .collect()
 }
 
+/// Distance from the bottom of the list, in pixels, at which the next page starts loading.
+const LOAD_MORE_THRESHOLD_PX: f64 = 240.0;
+
+/// What the bulk bar says after adding or removing a tag on the selection.
+fn bulk_tag_report(count: u32, added: bool) -> String {
+    match (count, added) {
+        (0, true) => "Every selected card already has that tag".to_string(),
+        (0, false) => "No selected card has that tag".to_string(),
+        (1, true) => "Tagged 1 card".to_string(),
+        (1, false) => "Untagged 1 card".to_string(),
+        (count, true) => format!("Tagged {count} cards"),
+        (count, false) => format!("Untagged {count} cards"),
+    }
+}
+
+/// Bulk selection after a cmd/ctrl-click on `clicked`, which toggles it.
+///
+/// The first such click also picks the open card, so it selects two cards at once.
+fn toggle_bulk_selection(
+    selection: &[CardId],
+    open_card: Option<CardId>,
+    clicked: CardId,
+) -> Vec<CardId> {
+    let mut next = selection.to_vec();
+    if next.is_empty()
+        && let Some(open) = open_card
+        && open != clicked
+    {
+        next.push(open);
+    }
+    if let Some(pos) = next.iter().position(|id| *id == clicked) {
+        next.remove(pos);
+    } else {
+        next.push(clicked);
+    }
+    next
+}
+
+/// Bulk selection after a shift-click on `clicked`: every visible card from `anchor` to it.
+///
+/// Without an anchor in view, only `clicked` is picked.
+fn range_bulk_selection(
+    visible: &[CardId],
+    anchor: Option<CardId>,
+    clicked: CardId,
+) -> Vec<CardId> {
+    let end = visible.iter().position(|id| *id == clicked);
+    let start = anchor.and_then(|anchor| visible.iter().position(|id| *id == anchor));
+    match (start, end) {
+        (Some(start), Some(end)) => visible[start.min(end)..=start.max(end)].to_vec(),
+        _ => vec![clicked],
+    }
+}
+
 #[component]
 pub fn EditorListPane(
     cards_state: ViewState<Vec<CardListItemVm>>,
     selected_card_id: Option<CardId>,
     search_value: String,
     match_count: Option<usize>,
+    card_counts_label: Option<String>,
     sort_mode: CardListSort,
     selected_tag: Option<String>,
     deck_tags: Vec<String>,
@@ -124,7 +101,42 @@ pub fn EditorListPane(
     on_select_card: Callback<CardListItemVm>,
     on_new_card: Callback<()>,
     on_list_key: Callback<KeyboardEvent>,
+    has_more_cards: bool,
+    is_loading_more_cards: bool,
+    on_load_more_cards: Callback<()>,
+    bulk_selection: Vec<CardId>,
+    bulk_state: BulkActionState,
+    on_bulk_select: Callback<Vec<CardId>>,
+    on_bulk_delete: Callback<()>,
+    on_bulk_suspend: Callback<bool>,
+    on_bulk_tag: Callback<(String, bool)>,
+    on_bulk_study: Callback<()>,
 ) -> Element {
+    // Where a shift-click range starts: the last card clicked without shift.
+    let mut bulk_anchor = use_signal(|| None::<CardId>);
+    // The tag being typed in the bulk bar; `None` while the tag field is closed.
+    let mut bulk_tag_draft = use_signal(|| None::<String>);
+    let bulk_busy = bulk_state == BulkActionState::Working;
+    let bulk_count = bulk_selection.len();
+    let on_item_click = {
+        let selection = bulk_selection.clone();
+        use_callback(
+            move |(item, modifiers, visible): (CardListItemVm, Modifiers, Vec<CardId>)| {
+                if modifiers.contains(Modifiers::SHIFT) {
+                    let anchor = bulk_anchor().or(selected_card_id);
+                    on_bulk_select.call(range_bulk_selection(&visible, anchor, item.id));
+                    return;
+                }
+                bulk_anchor.set(Some(item.id));
+                if modifiers.contains(Modifiers::META) || modifiers.contains(Modifiers::CONTROL) {
+                    on_bulk_select
+                        .call(toggle_bulk_selection(&selection, selected_card_id, item.id));
+                } else {
+                    on_select_card.call(item);
+                }
+            },
+        )
+    };
     let has_search = !search_value.trim().is_empty();
     let query = search_value.trim();
     let selected_tag_value = selected_tag.clone().unwrap_or_default();
@@ -143,6 +155,8 @@ pub fn EditorListPane(
                             span { class: "editor-list-count",
                                 if count == 1 { "1 result" } else { "{count} results" }
                             }
+                        } else if let Some(label) = card_counts_label {
+                            span { class: "editor-list-count", "{label}" }
                         }
                     }
                     div { class: "editor-list-search",
@@ -200,6 +214,8 @@ pub fn EditorListPane(
                             option { value: "recent", "Recent" }
                             option { value: "created", "Created" }
                             option { value: "alpha", "A–Z" }
+                            option { value: "due", "Due date" }
+                            option { value: "difficulty", "Hardest" }
                         }
                     }
                     div { class: "editor-list-controls",
@@ -233,8 +249,128 @@ pub fn EditorListPane(
                     }
                 }
             }
+            if bulk_count > 0 {
+                div { class: "editor-bulk-bar", role: "toolbar", aria_label: "Selected cards",
+                    span { class: "editor-bulk-count", "{bulk_count} selected" }
+                    button {
+                        class: "btn editor-bulk-action",
+                        r#type: "button",
+                        disabled: bulk_busy,
+                        onclick: move |_| on_bulk_study.call(()),
+                        "Study selected"
+                    }
+                    button {
+                        class: "btn editor-bulk-action",
+                        r#type: "button",
+                        disabled: bulk_busy,
+                        onclick: move |_| on_bulk_suspend.call(true),
+                        "Suspend"
+                    }
+                    button {
+                        class: "btn editor-bulk-action",
+                        r#type: "button",
+                        disabled: bulk_busy,
+                        onclick: move |_| on_bulk_suspend.call(false),
+                        "Resume"
+                    }
+                    button {
+                        class: "btn editor-bulk-action",
+                        r#type: "button",
+                        disabled: bulk_busy,
+                        aria_expanded: "{bulk_tag_draft().is_some()}",
+                        onclick: move |_| {
+                            let next = match bulk_tag_draft() {
+                                Some(_) => None,
+                                None => Some(String::new()),
+                            };
+                            bulk_tag_draft.set(next);
+                        },
+                        "Tag\u{2026}"
+                    }
+                    button {
+                        class: "btn editor-bulk-action editor-bulk-action--danger",
+                        r#type: "button",
+                        disabled: bulk_busy,
+                        onclick: move |_| on_bulk_delete.call(()),
+                        "Delete"
+                    }
+                    button {
+                        class: "btn editor-bulk-clear",
+                        r#type: "button",
+                        disabled: bulk_busy,
+                        onclick: move |_| on_bulk_select.call(Vec::new()),
+                        "Clear"
+                    }
+                    if let Some(draft) = bulk_tag_draft() {
+                        div { class: "editor-bulk-tag",
+                            input {
+                                class: "editor-input editor-bulk-tag-input",
+                                r#type: "text",
+                                placeholder: "Tag name",
+                                aria_label: "Tag for selected cards",
+                                list: "editor-bulk-tag-options",
+                                autofocus: true,
+                                value: "{draft}",
+                                oninput: move |evt| bulk_tag_draft.set(Some(evt.value())),
+                                onkeydown: move |evt| {
+                                    if evt.key() == Key::Escape {
+                                        bulk_tag_draft.set(None);
+                                    } else if evt.key() == Key::Enter {
+                                        let tag = bulk_tag_draft().unwrap_or_default();
+                                        if !tag.trim().is_empty() {
+                                            on_bulk_tag.call((tag, true));
+                                        }
+                                    }
+                                },
+                            }
+                            datalist { id: "editor-bulk-tag-options",
+                                for tag in deck_tags.clone() {
+                                    option { value: "{tag}" }
+                                }
+                            }
+                            button {
+                                class: "btn editor-bulk-action",
+                                r#type: "button",
+                                disabled: bulk_busy || draft.trim().is_empty(),
+                                onclick: {
+                                    let tag = draft.clone();
+                                    move |_| on_bulk_tag.call((tag.clone(), true))
+                                },
+                                "Add"
+                            }
+                            button {
+                                class: "btn editor-bulk-action",
+                                r#type: "button",
+                                disabled: bulk_busy || draft.trim().is_empty(),
+                                onclick: {
+                                    let tag = draft.clone();
+                                    move |_| on_bulk_tag.call((tag.clone(), false))
+                                },
+                                "Remove"
+                            }
+                        }
+                    }
+                    if let BulkActionState::Tagged { count, added } = bulk_state {
+                        span { class: "editor-bulk-status", role: "status",
+                            "{bulk_tag_report(count, added)}"
+                        }
+                    }
+                    if let BulkActionState::Error(err) = bulk_state {
+                        span { class: "editor-bulk-error", "{err.message()}" }
+                    }
+                }
+            }
             div { class: "editor-list-surface",
-                div { class: "editor-list-body",
+                div {
+                    class: "editor-list-body",
+                    onscroll: move |evt| {
+                        let remaining = f64::from(evt.data.scroll_height())
+                            - f64::from(evt.data.client_height())
+                            - evt.data.scroll_top();
+                        if has_more_cards && remaining < LOAD_MORE_THRESHOLD_PX {
+                            on_load_more_cards.call(());
+                        }
+                    },
                 match cards_state {
                     ViewState::Idle => rsx! {
                         p { class: "editor-list-empty", "Idle" }
@@ -263,18 +399,39 @@ pub fn EditorListPane(
                                     p { class: "editor-list-empty", "No matches." }
                                 }
                             } else {
+                                let visible_ids: Vec<CardId> =
+                                    filtered_items.iter().map(|item| item.id).collect();
                                 rsx! {
                                     ul { class: "editor-list-items",
                                         for item in filtered_items {
                                             li {
-                                                class: if Some(item.id) == selected_card_id {
+                                                class: if bulk_selection.contains(&item.id) {
+                                                    "editor-list-item editor-list-item--picked"
+                                                } else if Some(item.id) == selected_card_id {
                                                     "editor-list-item editor-list-item--active"
                                                 } else {
                                                     "editor-list-item"
                                                 },
                                                 key: "{item.id.value()}",
-                                                onclick: move |_| on_select_card.call(item.clone()),
+                                                onclick: {
+                                                    let visible_ids = visible_ids.clone();
+                                                    let item = item.clone();
+                                                    move |evt: MouseEvent| {
+                                                        on_item_click.call((
+                                                            item.clone(),
+                                                            evt.modifiers(),
+                                                            visible_ids.clone(),
+                                                        ));
+                                                    }
+                                                },
                                                 div { class: "editor-list-front",
+                                                    if let Some(flag) = item.flag {
+                                                        span {
+                                                            class: "card-flag card-flag--{flag.as_str()}",
+                                                            title: flag_label(flag),
+                                                            aria_label: flag_label(flag),
+                                                        }
+                                                    }
                                                     for node in render_highlighted(
                                                         &item.prompt_preview,
                                                         query,
@@ -290,9 +447,49 @@ pub fn EditorListPane(
                                                         {node}
                                                     }
                                                 }
+                                                span {
+                                                    class: if item.overdue {
+                                                        "editor-list-due editor-list-due--overdue"
+                                                    } else {
+                                                        "editor-list-due"
+                                                    },
+                                                    "{item.due_label}"
+                                                }
+                                                if item.suspended {
+                                                    span {
+                                                        class: "editor-list-suspended",
+                                                        title: "Left out of study sessions",
+                                                        "Suspended"
+                                                    }
+                                                }
+                                                if item.is_leech() {
+                                                    span {
+                                                        class: "editor-list-leech",
+                                                        title: "Lapsed often; try rewriting it",
+                                                        "Leech"
+                                                    }
+                                                }
+                                                if !item.tags.is_empty() {
+                                                    div { class: "editor-list-tags",
+                                                        for tag in item.tags.clone() {
+                                                            span { class: "editor-list-tag", "{tag}" }
+                                                        }
+                                                    }
+                                                }
+                                                if let Some(media_id) =
+                                                    item.prompt_media_id.or(item.answer_media_id)
+                                                {
+                                                    MediaImage {
+                                                        media_id,
+                                                        class: "editor-list-thumb",
+                                                    }
+                                                }
                                             }
                                         }
                                     }
+                                    if is_loading_more_cards {
+                                        p { class: "editor-list-empty", "Loading more cards..." }
+                                    }
                                 }
                             }
                         }
@@ -306,23 +503,34 @@ pub fn EditorListPane(
 
 #[cfg(test)]
 mod tests {
-    use super::highlight_spans;
+    use learn_core::model::CardId;
+
+    use super::{bulk_tag_report, range_bulk_selection, toggle_bulk_selection};
+
+    #[test]
+    fn bulk_tag_report_counts_changed_cards() {
+        assert_eq!(bulk_tag_report(3, true), "Tagged 3 cards");
+        assert_eq!(bulk_tag_report(1, false), "Untagged 1 card");
+        assert_eq!(bulk_tag_report(0, true), "Every selected card already has that tag");
+        assert_eq!(bulk_tag_report(0, false), "No selected card has that tag");
+    }
 
     #[test]
-    fn highlight_spans_marks_match_segments() {
-        let spans = highlight_spans("Rust", "st");
-        assert_eq!(spans.len(), 2);
-        assert_eq!(spans[0].text, "Ru");
-        assert!(!spans[0].is_match);
-        assert_eq!(spans[1].text, "st");
-        assert!(spans[1].is_match);
+    fn toggle_bulk_selection_seeds_with_the_open_card_and_toggles() {
+        let ids: Vec<CardId> = (1..=3).map(CardId::new).collect();
+        let picked = toggle_bulk_selection(&[], Some(ids[0]), ids[2]);
+        assert_eq!(picked, vec![ids[0], ids[2]]);
+        let picked = toggle_bulk_selection(&picked, Some(ids[0]), ids[0]);
+        assert_eq!(picked, vec![ids[2]]);
+        assert_eq!(toggle_bulk_selection(&[], Some(ids[1]), ids[1]), vec![ids[1]]);
     }
 
     #[test]
-    fn highlight_spans_handles_no_match() {
-        let spans = highlight_spans("Rust", "zz");
-        assert_eq!(spans.len(), 1);
-        assert_eq!(spans[0].text, "Rust");
-        assert!(!spans[0].is_match);
+    fn range_bulk_selection_spans_visible_cards_in_either_direction() {
+        let ids: Vec<CardId> = (1..=4).map(CardId::new).collect();
+        assert_eq!(range_bulk_selection(&ids, Some(ids[3]), ids[1]), ids[1..].to_vec());
+        assert_eq!(range_bulk_selection(&ids, Some(ids[0]), ids[2]), ids[..3].to_vec());
+        assert_eq!(range_bulk_selection(&ids, None, ids[2]), vec![ids[2]]);
+        assert_eq!(range_bulk_selection(&ids, Some(CardId::new(9)), ids[1]), vec![ids[1]]);
     }
 }
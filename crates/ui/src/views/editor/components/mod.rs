@@ -1,9 +1,13 @@
 mod detail;
 mod list;
 mod modals;
+mod preview;
 mod toolbar;
+mod trash;
 
 pub use detail::EditorDetailPane;
 pub use list::EditorListPane;
 pub use modals::EditorOverlays;
+pub use preview::EditorPreviewPane;
 pub use toolbar::EditorFormatToolbar;
+pub use trash::RecentlyDeletedModal;
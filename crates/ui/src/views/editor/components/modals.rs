@@ -43,7 +43,7 @@ pub fn EditorOverlays(
                     onclick: move |evt| evt.stop_propagation(),
                     h3 { class: "editor-modal-title", "Delete card?" }
                     p { class: "editor-modal-body",
-                        "This will remove the card and its review history."
+                        "The card moves to Recently deleted, where you can restore it."
                     }
                     div { class: "editor-modal-actions",
                         button {
@@ -0,0 +1,61 @@
+use dioxus::prelude::*;
+use learn_core::model::MediaId;
+
+use crate::context::AppContext;
+use crate::vm::{CardMedia, render_preview_side};
+
+/// Live render of the card being edited, as a session would show it once saved.
+///
+/// `prompt_html` and `answer_html` are the debounced field contents, not the live ones.
+#[component]
+pub fn EditorPreviewPane(
+    prompt_html: String,
+    answer_html: String,
+    prompt_media_id: Option<MediaId>,
+    answer_media_id: Option<MediaId>,
+) -> Element {
+    let ctx = use_context::<AppContext>();
+    let media_service = ctx.media_service();
+    let media_sources = use_resource(use_reactive!(|prompt_media_id, answer_media_id| {
+        let media_service = media_service.clone();
+        async move {
+            let mut sources = Vec::new();
+            for media_id in [prompt_media_id, answer_media_id].into_iter().flatten() {
+                if let Ok(url) = media_service.image_data_url(media_id).await {
+                    sources.push((media_id, CardMedia::from_data_url(url)));
+                }
+            }
+            sources
+        }
+    }));
+    let media = |media_id: Option<MediaId>| {
+        let sources = media_sources.read();
+        media_id.and_then(|media_id| {
+            sources
+                .as_ref()?
+                .iter()
+                .find(|(id, _)| *id == media_id)
+                .map(|(_, media)| media.clone())
+        })
+    };
+    let front = render_preview_side(&prompt_html, prompt_media_id, media(prompt_media_id).as_ref());
+    let back = render_preview_side(&answer_html, answer_media_id, media(answer_media_id).as_ref());
+
+    rsx! {
+        aside { class: "editor-preview", aria_label: "Card preview",
+            for (label, html) in [("Front", front), ("Back", back)] {
+                div { class: "editor-preview-side",
+                    span { class: "editor-label", "{label}" }
+                    if let Some(html) = html {
+                        div {
+                            class: "session-text editor-preview-text",
+                            dangerous_inner_html: "{html}",
+                        }
+                    } else {
+                        p { class: "editor-preview-empty", "Nothing to show yet." }
+                    }
+                }
+            }
+        }
+    }
+}
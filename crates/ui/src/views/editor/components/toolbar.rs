@@ -1,3 +1,4 @@
+use dioxus::html::FileData;
 use dioxus::prelude::*;
 
 use crate::vm::{MarkdownAction, MarkdownField};
@@ -25,6 +26,7 @@ pub fn EditorFormatToolbar(
     on_update_link_url: Callback<String>,
     on_apply_link: Callback<MarkdownField>,
     on_remove_link: Callback<MarkdownField>,
+    on_attach_image: Callback<(MarkdownField, FileData)>,
     on_toggle_writing_menu: Callback<MarkdownField>,
     on_writing_prompt_change: Callback<String>,
     on_select_writing_tone: Callback<WritingToolsTone>,
@@ -140,12 +142,25 @@ pub fn EditorFormatToolbar(
             }
             div { class: "editor-md-toolbar-separator" }
             div { class: "editor-md-toolbar-group",
-                button {
-                    class: "editor-md-toolbar-btn",
-                    r#type: "button",
-                    disabled: disabled,
+                label {
+                    class: if disabled {
+                        "editor-md-toolbar-btn editor-md-toolbar-btn--disabled"
+                    } else {
+                        "editor-md-toolbar-btn"
+                    },
                     "data-tooltip": "Add image",
                     aria_label: "Add image",
+                    input {
+                        class: "editor-md-toolbar-file",
+                        r#type: "file",
+                        accept: "image/png,image/jpeg,image/gif,image/webp",
+                        disabled: disabled,
+                        onchange: move |evt| {
+                            if let Some(file) = evt.files().into_iter().next() {
+                                on_attach_image.call((field, file));
+                            }
+                        },
+                    }
                     svg {
                         class: "editor-md-toolbar-icon",
                         view_box: "0 0 24 24",
@@ -248,6 +263,23 @@ pub fn EditorFormatToolbar(
                         path { d: "M14 10l2 2-2 2" }
                     }
                 }
+                button {
+                    class: "editor-md-toolbar-btn",
+                    r#type: "button",
+                    disabled: disabled,
+                    "data-tooltip": "Table",
+                    aria_label: "Insert table",
+                    onclick: move |_| {
+                        on_format.call((field, MarkdownAction::Table));
+                    },
+                    svg {
+                        class: "editor-md-toolbar-icon",
+                        view_box: "0 0 24 24",
+                        rect { x: "4.5", y: "5.5", width: "15", height: "13", rx: "2" }
+                        line { x1: "4.5", y1: "10", x2: "19.5", y2: "10" }
+                        line { x1: "12", y1: "5.5", x2: "12", y2: "18.5" }
+                    }
+                }
             }
             div { class: "editor-md-toolbar-separator" }
             div { class: "editor-md-toolbar-group",
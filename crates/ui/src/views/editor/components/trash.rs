@@ -0,0 +1,120 @@
+use dioxus::prelude::*;
+use learn_core::model::DeckId;
+use services::CardService;
+
+use crate::context::AppContext;
+use crate::views::{ViewError, ViewState, view_state_from_resource};
+use crate::vm::map_trashed_cards;
+
+/// Most trashed cards listed at once.
+const TRASH_LIMIT: u32 = 100;
+
+/// Modal listing the deck's recently deleted cards, each with a restore button.
+///
+/// Opening it first purges cards past `CardService::TRASH_RETENTION`.
+#[component]
+pub fn RecentlyDeletedModal(
+    deck_id: DeckId,
+    on_close: Callback<()>,
+    on_restored: Callback<()>,
+) -> Element {
+    let ctx = use_context::<AppContext>();
+    let card_service = ctx.card_service();
+    let mut restore_failed = use_signal(|| false);
+
+    let resource = {
+        let card_service = card_service.clone();
+        use_resource(move || {
+            let card_service = card_service.clone();
+            async move {
+                card_service
+                    .purge_trash(CardService::TRASH_RETENTION)
+                    .await
+                    .map_err(|_| ViewError::Unknown)?;
+                let trashed = card_service
+                    .list_trash(deck_id, TRASH_LIMIT)
+                    .await
+                    .map_err(|_| ViewError::Unknown)?;
+                Ok::<_, ViewError>(map_trashed_cards(&trashed, card_service.now()))
+            }
+        })
+    };
+    let state = view_state_from_resource(&resource);
+    let retention_days = CardService::TRASH_RETENTION.num_days();
+
+    rsx! {
+        div {
+            class: "editor-modal-overlay",
+            onclick: move |_| on_close.call(()),
+            div {
+                class: "editor-modal trash-modal",
+                role: "dialog",
+                aria_label: "Recently deleted",
+                onclick: move |evt| evt.stop_propagation(),
+                h3 { class: "editor-modal-title", "Recently deleted" }
+                p { class: "editor-modal-body",
+                    "Deleted cards stay here for {retention_days} days with their review history."
+                }
+                if restore_failed() {
+                    p { class: "editor-modal-error", "Restore failed. Please try again." }
+                }
+                match state {
+                    ViewState::Idle | ViewState::Loading => rsx! {
+                        p { class: "trash-empty", "Loading..." }
+                    },
+                    ViewState::Error(err) => rsx! {
+                        p { class: "editor-modal-error", "{err.message()}" }
+                    },
+                    ViewState::Ready(cards) => rsx! {
+                        if cards.is_empty() {
+                            p { class: "trash-empty", "No recently deleted cards." }
+                        } else {
+                            ul { class: "trash-list",
+                                for trashed in cards {
+                                    li { key: "{trashed.item.id.value()}", class: "trash-row",
+                                        div { class: "trash-row__text",
+                                            span { class: "trash-row__prompt",
+                                                "{trashed.item.prompt_preview}"
+                                            }
+                                            span { class: "trash-row__deleted", "{trashed.deleted_label}" }
+                                        }
+                                        button {
+                                            class: "btn editor-modal-cancel",
+                                            r#type: "button",
+                                            onclick: {
+                                                let card_service = card_service.clone();
+                                                let card_id = trashed.item.id;
+                                                move |_| {
+                                                    let card_service = card_service.clone();
+                                                    spawn(async move {
+                                                        let result =
+                                                            card_service.restore_card(deck_id, card_id).await;
+                                                        restore_failed.set(result.is_err());
+                                                        let mut resource = resource;
+                                                        resource.restart();
+                                                        if result.is_ok() {
+                                                            on_restored.call(());
+                                                        }
+                                                    });
+                                                }
+                                            },
+                                            "Restore"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                }
+                div { class: "editor-modal-actions",
+                    button {
+                        class: "btn editor-modal-cancel",
+                        r#type: "button",
+                        onclick: move |_| on_close.call(()),
+                        "Close"
+                    }
+                }
+            }
+        }
+    }
+}
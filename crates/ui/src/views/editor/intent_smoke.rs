@@ -1,10 +1,10 @@
 use dioxus::prelude::{ReadableExt, WritableExt};
-use learn_core::model::DeckSettings;
+use learn_core::model::{DeckSettings, MediaId, NewCardTemplate};
 
-use crate::vm::build_card_list_item;
+use crate::vm::{MarkdownField, build_card_list_item};
 
 use super::actions::EditorIntent;
-use super::state::SaveRequest;
+use super::state::{BulkActionState, SaveRequest};
 use super::test_harness::{set_fields, setup_editor_harness};
 
 #[tokio::test(flavor = "current_thread")]
@@ -29,7 +29,7 @@ async fn editor_intents_smoke_create_edit_delete_undo() {
         created.prompt().text(),
         created.answer().text(),
     );
-    dispatch.call(EditorIntent::RequestSelectCard(list_item));
+    dispatch.call(EditorIntent::RequestSelectCard(Box::new(list_item)));
     harness.drive();
     set_fields(
         &state,
@@ -48,6 +48,9 @@ async fn editor_intents_smoke_create_edit_delete_undo() {
     harness.drive();
     let cards = card_service.list_cards(deck_id, 10).await.expect("list deleted");
     assert!(cards.is_empty());
+    let trash = card_service.list_trash(deck_id, 10).await.expect("list trash");
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].card.id(), created.id());
 
     // Undo deletion by recreating the card content.
     dispatch.call(EditorIntent::RequestNewCard);
@@ -62,6 +65,86 @@ async fn editor_intents_smoke_create_edit_delete_undo() {
     assert_eq!(cards[0].answer().text(), "A systems language.");
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn editor_intents_smoke_new_card_starts_from_deck_template() {
+    let (mut harness, deck_service, card_service, _deck_id) =
+        setup_editor_harness("Default").await;
+    let dispatch = harness.dispatch();
+    let state = harness.state();
+
+    let settings = DeckSettings::default_for_adhd()
+        .with_new_card_template(NewCardTemplate::new("Word: ", "Meaning: "));
+    let vocab_id = deck_service
+        .create_deck("Vocab".to_string(), None, settings)
+        .await
+        .expect("create template deck");
+    dispatch.call(EditorIntent::RequestSelectDeck(vocab_id));
+    harness.drive();
+    harness.drive();
+
+    dispatch.call(EditorIntent::RequestNewCard);
+    harness.drive();
+    assert_eq!(state.prompt_text.read().as_str(), "Word: ");
+    assert_eq!(state.answer_text.read().as_str(), "Meaning: ");
+    assert!(!(state.has_unsaved_changes)());
+
+    // The untouched template is not a card worth saving.
+    dispatch.call(EditorIntent::Save(SaveRequest::new(false)));
+    harness.drive();
+    let cards = card_service.list_cards(vocab_id, 10).await.expect("list unchanged");
+    assert!(cards.is_empty());
+
+    set_fields(&state, "Word: chat", "Meaning: cat");
+    assert!((state.has_unsaved_changes)());
+    dispatch.call(EditorIntent::Save(SaveRequest::new(false)));
+    harness.drive();
+    let cards = card_service.list_cards(vocab_id, 10).await.expect("list saved");
+    assert_eq!(cards.len(), 1);
+    assert_eq!(state.prompt_text.read().as_str(), "Word: ");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn editor_intents_smoke_bulk_suspend_and_delete() {
+    let (mut harness, _deck_service, card_service, deck_id) =
+        setup_editor_harness("Default").await;
+    let dispatch = harness.dispatch();
+    let state = harness.state();
+
+    for (prompt, answer) in [("One", "1"), ("Two", "2"), ("Three", "3")] {
+        dispatch.call(EditorIntent::RequestNewCard);
+        harness.drive();
+        set_fields(&state, prompt, answer);
+        dispatch.call(EditorIntent::Save(SaveRequest::new(false)));
+        harness.drive();
+    }
+    let cards = card_service.list_cards(deck_id, 10).await.expect("list cards");
+    assert_eq!(cards.len(), 3);
+    let picked = vec![cards[0].id(), cards[1].id()];
+
+    dispatch.call(EditorIntent::SetBulkSelection(picked.clone()));
+    dispatch.call(EditorIntent::BulkSuspend(true));
+    harness.drive();
+    let cards = card_service.list_cards(deck_id, 10).await.expect("list suspended");
+    assert_eq!(cards.iter().filter(|card| card.is_suspended()).count(), 2);
+    assert!(state.bulk_selection.read().is_empty());
+
+    dispatch.call(EditorIntent::SetBulkSelection(picked.clone()));
+    dispatch.call(EditorIntent::BulkTag(" verbs ".to_string(), true));
+    harness.drive();
+    let tags = card_service.list_tags_for_cards(deck_id, &picked).await.expect("list tags");
+    assert_eq!(tags.len(), 2);
+    assert_eq!(*state.bulk_state.read(), BulkActionState::Tagged { count: 2, added: true });
+    assert_eq!(state.bulk_selection.read().len(), 2);
+
+    dispatch.call(EditorIntent::SetBulkSelection(picked));
+    dispatch.call(EditorIntent::BulkDelete);
+    harness.drive();
+    let cards = card_service.list_cards(deck_id, 10).await.expect("list after bulk delete");
+    assert_eq!(cards.len(), 1);
+    assert!(!cards[0].is_suspended());
+    assert_eq!(card_service.list_trash(deck_id, 10).await.expect("list trash").len(), 2);
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn editor_intents_smoke_duplicate_prompt_confirm() {
     let (mut harness, _deck_service, card_service, deck_id) =
@@ -94,6 +177,46 @@ async fn editor_intents_smoke_duplicate_prompt_confirm() {
     assert_eq!(cards.len(), 2);
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn editor_intents_smoke_save_and_remove_image() {
+    let (mut harness, _deck_service, card_service, deck_id) =
+        setup_editor_harness("Default").await;
+    let dispatch = harness.dispatch();
+    let state = harness.state();
+
+    dispatch.call(EditorIntent::RequestNewCard);
+    harness.drive();
+    set_fields(&state, "Which flag?", "Japan.");
+    let mut prompt_media_id = state.prompt_media_id;
+    prompt_media_id.set(Some(MediaId::new(7)));
+    dispatch.call(EditorIntent::Save(SaveRequest::new(false)));
+    harness.drive();
+
+    let cards = card_service.list_cards(deck_id, 10).await.expect("list cards");
+    assert_eq!(cards[0].prompt().media_id(), Some(MediaId::new(7)));
+    assert_eq!(cards[0].answer().media_id(), None);
+
+    let list_item = build_card_list_item(
+        cards[0].id(),
+        cards[0].prompt().text(),
+        cards[0].answer().text(),
+    )
+    .with_media(cards[0].prompt().media_id(), None);
+    dispatch.call(EditorIntent::RequestSelectCard(Box::new(list_item)));
+    harness.drive();
+    assert_eq!((state.prompt_media_id)(), Some(MediaId::new(7)));
+    assert!(!(state.has_unsaved_changes)());
+
+    dispatch.call(EditorIntent::RemoveImage(MarkdownField::Front));
+    harness.drive();
+    assert!((state.has_unsaved_changes)());
+    dispatch.call(EditorIntent::Save(SaveRequest::new(false)));
+    harness.drive();
+
+    let cards = card_service.list_cards(deck_id, 10).await.expect("list edited");
+    assert_eq!(cards[0].prompt().media_id(), None);
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn editor_intents_smoke_rename_and_switch_deck() {
     let (mut harness, deck_service, _card_service, deck_id) =
@@ -121,7 +244,7 @@ async fn editor_intents_smoke_rename_and_switch_deck() {
     dispatch.call(EditorIntent::CommitRename);
     harness.drive();
 
-    let decks = deck_service.list_decks(10).await.expect("list decks");
+    let decks = deck_service.list_decks(10, false).await.expect("list decks");
     let renamed = decks
         .iter()
         .find(|deck| deck.id() == second_id)
@@ -132,3 +255,61 @@ async fn editor_intents_smoke_rename_and_switch_deck() {
     harness.drive();
     assert_eq!(*state.selected_deck.read(), deck_id);
 }
+
+#[tokio::test(flavor = "current_thread")]
+async fn editor_intents_smoke_archive_deck_moves_to_active_deck() {
+    let (mut harness, deck_service, _card_service, deck_id) =
+        setup_editor_harness("Default").await;
+    let dispatch = harness.dispatch();
+    let state = harness.state();
+
+    let second_id = deck_service
+        .create_deck(
+            "Second".to_string(),
+            None,
+            DeckSettings::default_for_adhd(),
+        )
+        .await
+        .expect("create second deck");
+    dispatch.call(EditorIntent::RequestSelectDeck(second_id));
+    harness.drive();
+
+    dispatch.call(EditorIntent::SetDeckArchived(true));
+    harness.drive();
+
+    let second = deck_service
+        .get_deck(second_id)
+        .await
+        .expect("get deck")
+        .expect("archived deck");
+    assert!(second.is_archived());
+    assert_eq!(*state.selected_deck.read(), deck_id);
+
+    dispatch.call(EditorIntent::ToggleArchivedDecks);
+    harness.drive();
+    assert!((state.show_archived_decks)());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn editor_intents_smoke_star_deck_pins_it_first() {
+    let (mut harness, deck_service, _card_service, deck_id) =
+        setup_editor_harness("Default").await;
+    let dispatch = harness.dispatch();
+
+    let second_id = deck_service
+        .create_deck(
+            "Second".to_string(),
+            None,
+            DeckSettings::default_for_adhd(),
+        )
+        .await
+        .expect("create second deck");
+
+    dispatch.call(EditorIntent::SetDeckFavorite(second_id, true));
+    harness.drive();
+
+    let decks = deck_service.list_decks(10, false).await.expect("list decks");
+    let ids: Vec<_> = decks.iter().map(learn_core::model::Deck::id).collect();
+    assert_eq!(ids, vec![second_id, deck_id]);
+    assert!(decks[0].is_favorite());
+}
@@ -1,6 +1,10 @@
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
 use dioxus::document::eval;
 use serde::Deserialize;
 
+use crate::vm::SelectionRange;
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct SelectionSnapshot {
     pub html: String,
@@ -29,6 +33,14 @@ pub async fn read_selection_snapshot(element_id: &str) -> Option<SelectionSnapsh
     eval(&script).join::<SelectionSnapshot>().await.ok()
 }
 
+/// The ID of the focused element, or an empty string.
+pub async fn read_focused_element_id() -> String {
+    eval("return document.activeElement?.id || \"\";")
+        .join::<String>()
+        .await
+        .unwrap_or_default()
+}
+
 pub async fn read_editable_html(element_id: &str) -> Option<String> {
     let script = read_editable_html_script(element_id);
     eval(&script).join::<String>().await.ok()
@@ -54,6 +66,37 @@ pub async fn attach_rich_paste_handler(element_id: &str) {
     let _ = eval(&script).await;
 }
 
+/// A paste with lists, tables, or an image, held back by the rich paste handler so the
+/// editor can decide how it is inserted.
+#[derive(Clone, Debug, Deserialize)]
+pub struct StructuredPaste {
+    pub element_id: String,
+    pub html: String,
+    pub text: String,
+    /// The clipboard image, base64-encoded, when the paste carries one.
+    #[serde(default)]
+    pub image: Option<String>,
+}
+
+impl StructuredPaste {
+    /// The decoded clipboard image; `None` when there is none or it fails to decode.
+    #[must_use]
+    pub fn image_bytes(&self) -> Option<Vec<u8>> {
+        STANDARD.decode(self.image.as_deref()?).ok()
+    }
+}
+
+/// Registers the sink the rich paste handler reports structured and image pastes to; the
+/// paste's selection is kept for `insert_at_stored_selection`.
+pub const STRUCTURED_PASTE_WATCH_SCRIPT: &str = r#"
+    window.__learnStructuredPasteSink = (paste) => dioxus.send(paste);
+"#;
+
+pub async fn insert_at_stored_selection(element_id: &str, html: Option<&str>, text: &str) {
+    let script = insert_at_stored_selection_script(element_id, html, text);
+    let _ = eval(&script).await;
+}
+
 pub async fn read_selected_link_href(element_id: &str) -> Option<String> {
     let script = read_selected_link_href_script(element_id);
     eval(&script).join::<String>().await.ok()
@@ -85,6 +128,20 @@ pub async fn remove_link(element_id: &str) {
     let _ = eval(&script).await;
 }
 
+/// The selection in a field as character offsets into its text, for the undo history.
+pub async fn read_selection_range(element_id: &str) -> Option<SelectionRange> {
+    let script = read_selection_range_script(element_id);
+    let [start, end] = eval(&script).join::<[usize; 2]>().await.ok()?;
+    Some(SelectionRange::new(start, end))
+}
+
+/// Put back a field's content and selection from the undo history. Rich-text fields take
+/// HTML; the extra textarea takes its plain value.
+pub async fn restore_field(element_id: &str, content: &str, selection: SelectionRange) {
+    let script = restore_field_script(element_id, content, selection);
+    let _ = eval(&script).await;
+}
+
 pub fn set_block_dir_script(element_id: &str, dir: &str) -> String {
     let dir_literal = js_string_literal(dir);
     let align_literal = if dir == "rtl" {
@@ -264,6 +321,76 @@ fn set_editable_html_script(element_id: &str, html: &str) -> String {
     )
 }
 
+fn read_selection_range_script(element_id: &str) -> String {
+    format!(
+        r#"
+        const el = document.getElementById("{element_id}");
+        if (!el) {{ return null; }}
+        if (el.tagName === "TEXTAREA") {{
+            return [el.selectionStart, el.selectionEnd];
+        }}
+        const length = (el.textContent || "").length;
+        const sel = window.getSelection();
+        if (!sel || sel.rangeCount === 0) {{ return [length, length]; }}
+        const range = sel.getRangeAt(0);
+        if (!el.contains(range.startContainer) || !el.contains(range.endContainer)) {{
+            return [length, length];
+        }}
+        const offset = (node, nodeOffset) => {{
+            const prefix = document.createRange();
+            prefix.selectNodeContents(el);
+            prefix.setEnd(node, nodeOffset);
+            return prefix.toString().length;
+        }};
+        return [
+            offset(range.startContainer, range.startOffset),
+            offset(range.endContainer, range.endOffset),
+        ];
+        "#
+    )
+}
+
+fn restore_field_script(element_id: &str, content: &str, selection: SelectionRange) -> String {
+    let content_literal = js_string_literal(content);
+    let set_html = set_editable_html_script(element_id, content);
+    let SelectionRange { start, end } = selection;
+    format!(
+        r#"
+        const field = document.getElementById("{element_id}");
+        if (!field) {{ return; }}
+        if (field.tagName === "TEXTAREA") {{
+            field.value = {content_literal};
+            field.focus();
+            field.setSelectionRange({start}, {end});
+            return;
+        }}
+        {set_html}
+        field.focus();
+        const locate = (target) => {{
+            const walker = document.createTreeWalker(field, NodeFilter.SHOW_TEXT);
+            let seen = 0;
+            let last = null;
+            let node;
+            while ((node = walker.nextNode())) {{
+                const length = node.textContent.length;
+                if (seen + length >= target) {{ return [node, target - seen]; }}
+                seen += length;
+                last = node;
+            }}
+            return last ? [last, last.textContent.length] : [field, field.childNodes.length];
+        }};
+        const range = document.createRange();
+        range.setStart(...locate({start}));
+        range.setEnd(...locate({end}));
+        const sel = window.getSelection();
+        if (sel) {{
+            sel.removeAllRanges();
+            sel.addRange(range);
+        }}
+        "#
+    )
+}
+
 fn replace_selection_or_all_script(element_id: &str, html: &str) -> String {
     let html_literal = js_string_literal(html);
     format!(
@@ -485,7 +612,10 @@ fn attach_rich_paste_handler_script(element_id: &str) -> String {
                 const start = lower.indexOf("<");
                 if (start === -1) return false;
                 if (lower.slice(start + 1).indexOf(">") === -1) return false;
-                const tags = ["<!doctype", "<html", "<body", "<p", "<div", "<span", "<br", "<a ", "<img"];
+                const tags = [
+                    "<!doctype", "<html", "<body", "<p", "<div", "<span", "<br", "<a ", "<img",
+                    "<ul", "<ol", "<li", "<table"
+                ];
                 return tags.some(tag => lower.includes(tag));
             }};
             window.__learnLooksLikeMarkdown = function (input) {{
@@ -638,9 +768,44 @@ fn attach_rich_paste_handler_script(element_id: &str) -> String {
                     }}
                     const html = event.clipboardData.getData("text/html") || "";
                     const text = event.clipboardData.getData("text/plain") || "";
+                    const sink = window.__learnStructuredPasteSink;
+                    const storeSelection = function () {{
+                        const sel = window.getSelection();
+                        if (sel && sel.rangeCount > 0) {{
+                            if (!window.__learnStoredSelection) {{
+                                window.__learnStoredSelection = {{}};
+                            }}
+                            window.__learnStoredSelection[el.id] = sel.getRangeAt(0).cloneRange();
+                        }}
+                    }};
+                    const imageItem = Array.from(event.clipboardData.items || []).find(
+                        item => item.kind === "file" && item.type.startsWith("image/")
+                    );
+                    const imageFile = imageItem ? imageItem.getAsFile() : null;
+                    if (typeof sink === "function" && imageFile) {{
+                        event.preventDefault();
+                        storeSelection();
+                        const reader = new FileReader();
+                        reader.onload = function () {{
+                            const url = String(reader.result || "");
+                            const image = url.slice(url.indexOf(",") + 1);
+                            sink({{ element_id: el.id, html: html, text: text, image: image }});
+                        }};
+                        reader.readAsDataURL(imageFile);
+                        return;
+                    }}
                     if (!html && !text) {{
                         return;
                     }}
+                    const markup = html && html.trim()
+                        ? html
+                        : (window.__learnLooksLikeHtml(text) ? text : "");
+                    if (typeof sink === "function" && /<(ul|ol|table)[^a-z]/i.test(markup)) {{
+                        event.preventDefault();
+                        storeSelection();
+                        sink({{ element_id: el.id, html: markup, text: text }});
+                        return;
+                    }}
                     let insertHtml = "";
                     if (html && html.trim()) {{
                         insertHtml = window.__learnSanitizeHtml(html);
@@ -664,6 +829,30 @@ fn attach_rich_paste_handler_script(element_id: &str) -> String {
     )
 }
 
+fn insert_at_stored_selection_script(element_id: &str, html: Option<&str>, text: &str) -> String {
+    let (command, value) = match html {
+        Some(html) => ("insertHTML", js_string_literal(html)),
+        None => ("insertText", js_string_literal(text)),
+    };
+    format!(
+        r#"
+        if (!window.__learnStoredSelection) {{
+            window.__learnStoredSelection = {{}};
+        }}
+        const el = document.getElementById("{element_id}");
+        if (!el) {{ return; }}
+        el.focus();
+        const sel = window.getSelection();
+        const stored = window.__learnStoredSelection[el.id];
+        if (stored && sel && el.contains(stored.commonAncestorContainer)) {{
+            sel.removeAllRanges();
+            sel.addRange(stored);
+        }}
+        document.execCommand("{command}", false, {value});
+        "#
+    )
+}
+
 fn save_selection_range_script(element_id: &str) -> String {
     format!(
         r#"
@@ -1,20 +1,31 @@
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::rc::Rc;
 use std::sync::Arc;
 
 use dioxus::prelude::*;
-use learn_core::model::{CardId, DeckId};
-use services::{CardListFilter, CardListSort, CardService, DeckService};
+use learn_core::model::{Card, CardId, DeckId, MediaId, NewCardTemplate};
+use services::{
+    AppSettingsService, CardListFilter, CardListSort, CardPhaseCounts, CardService, DeckService,
+    MediaService,
+};
 
 use crate::vm::{
-    CardListItemVm, DailyLimitVm, MarkdownField, map_card_list_items, map_deck_options,
-    strip_html_tags,
+    CardHistoryRowVm, CardListItemVm, DailyLimitVm, FieldEditHistories, MarkdownField, PasteOffer,
+    PreviewDebounce, map_card_history, map_card_list_items, map_deck_options, strip_html_tags,
 };
 use crate::views::{ViewError, ViewState, view_state_from_resource};
 
-use super::utils::{tag_filter_key, tag_names_from_strings, tags_equal};
+use super::utils::{differs_from_starter, tag_filter_key, tag_names_from_strings, tags_equal};
 
 type CardTagsResource = Resource<Result<(Option<CardId>, Vec<String>), ViewError>>;
+type CardHistoryResource = Resource<Result<(Option<CardId>, Vec<CardHistoryRowVm>), ViewError>>;
+
+/// Reviews shown in the editor's history panel for the selected card.
+pub const CARD_HISTORY_LIMIT: u32 = 50;
+
+/// Cards fetched per request; scrolling near the end of the list fetches the next page.
+pub const CARD_PAGE_SIZE: u32 = 100;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SaveState {
@@ -32,6 +43,16 @@ pub enum DeleteState {
     Error(ViewError),
 }
 
+/// Progress of a bulk action on the multi-selected cards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BulkActionState {
+    Idle,
+    Working,
+    /// A bulk tag change finished; `count` cards gained (or lost) the tag.
+    Tagged { count: u32, added: bool },
+    Error(ViewError),
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ResetDeckState {
     Idle,
@@ -40,6 +61,14 @@ pub enum ResetDeckState {
     Error(ViewError),
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResetCardState {
+    Idle,
+    Resetting,
+    Success,
+    Error(ViewError),
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SaveMenuState {
     Closed,
@@ -101,9 +130,39 @@ pub struct LinkEditorState {
     pub url: String,
 }
 
+/// A structured paste waiting for the user to pick how it is inserted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingPaste {
+    pub field: MarkdownField,
+    pub offer: PasteOffer,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PasteChoice {
+    Markdown,
+    PlainText,
+    Cancel,
+}
+
+/// A paste carrying both an image and text, waiting for the user to pick one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingImagePaste {
+    pub field: MarkdownField,
+    pub image: Vec<u8>,
+    pub html: String,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImagePasteChoice {
+    Image,
+    Text,
+    Cancel,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum PendingAction {
-    SelectCard(CardListItemVm),
+    SelectCard(Box<CardListItemVm>),
     SelectDeck(DeckId),
     NewCard,
 }
@@ -137,6 +196,8 @@ pub type SetFieldsAction = Rc<RefCell<dyn FnMut(String, String)>>;
 pub struct EditorServices {
     pub deck_service: Arc<DeckService>,
     pub card_service: Arc<CardService>,
+    pub media_service: Arc<MediaService>,
+    pub app_settings: Arc<AppSettingsService>,
 }
 
 #[derive(Clone)]
@@ -147,6 +208,7 @@ pub struct EditorState {
     pub show_delete_modal: Signal<bool>,
     pub show_validation: Signal<bool>,
     pub focus_prompt: Signal<bool>,
+    pub generate_reverse: Signal<bool>,
     pub show_unsaved_modal: Signal<bool>,
     pub pending_action: Signal<Option<PendingAction>>,
     pub save_menu_state: Signal<SaveMenuState>,
@@ -163,18 +225,27 @@ pub struct EditorState {
     pub writing_tools_selection_html: Signal<String>,
     pub writing_tools_selection_text: Signal<String>,
     pub link_editor_state: Signal<Option<LinkEditorState>>,
+    pub pending_paste: Signal<Option<PendingPaste>>,
+    pub pending_image_paste: Signal<Option<PendingImagePaste>>,
     pub show_new_deck: Signal<bool>,
     pub new_deck_name: Signal<String>,
     pub new_deck_state: Signal<SaveState>,
     pub show_deck_menu: Signal<bool>,
     pub show_deck_actions: Signal<bool>,
+    pub show_archived_decks: Signal<bool>,
     pub is_renaming_deck: Signal<bool>,
     pub rename_deck_name: Signal<String>,
     pub rename_deck_state: Signal<SaveState>,
     pub rename_deck_error: Signal<Option<String>>,
     pub selected_card_id: Signal<Option<CardId>>,
+    /// Cards picked with shift- or cmd/ctrl-click for a bulk action.
+    pub bulk_selection: Signal<Vec<CardId>>,
+    pub bulk_state: Signal<BulkActionState>,
     pub last_selected_card: Signal<Option<CardListItemVm>>,
     pub is_create_mode: Signal<bool>,
+    /// Starter text the card being created was filled with; leaving it as-is is not an
+    /// unsaved change.
+    pub new_card_template: Signal<NewCardTemplate>,
     pub search_query: Signal<String>,
     pub sort_mode: Signal<CardListSort>,
     pub selected_tag_filters: Signal<Vec<String>>,
@@ -186,16 +257,47 @@ pub struct EditorState {
     pub show_duplicate_modal: Signal<bool>,
     pub pending_duplicate_practice: Signal<bool>,
     pub show_reset_deck_modal: Signal<bool>,
+    /// Whether the keyboard shortcuts legend is open.
+    pub show_shortcuts: Signal<bool>,
+    /// Whether the "Recently deleted" list is open.
+    pub show_trash: Signal<bool>,
+    /// Whether the live card preview is open beside (or, on narrow windows, instead of) the
+    /// fields.
+    pub show_preview: Signal<bool>,
+    /// Front and back as the preview last rendered them; trails the fields by the debounce.
+    pub preview_fields: Signal<(String, String)>,
+    pub preview_debounce: Signal<PreviewDebounce>,
     pub reset_deck_state: Signal<ResetDeckState>,
+    pub reset_card_state: Signal<ResetCardState>,
     pub prompt_text: Signal<String>,
     pub answer_text: Signal<String>,
+    /// Plain-text extra info shown beneath the answer in sessions.
+    pub extra_text: Signal<String>,
+    /// Undo and redo steps per field; reset whenever the fields are cleared or loaded.
+    pub edit_history: Signal<FieldEditHistories>,
+    /// Whether an AI writing tools result was put into the fields since they were cleared
+    /// or loaded, so a new card is saved as written with AI.
+    pub ai_assisted: Signal<bool>,
     pub prompt_render_html: Signal<String>,
     pub answer_render_html: Signal<String>,
+    pub prompt_media_id: Signal<Option<MediaId>>,
+    pub answer_media_id: Signal<Option<MediaId>>,
+    pub media_error: Signal<Option<String>>,
     pub decks_resource: Resource<Result<Vec<crate::vm::DeckOptionVm>, ViewError>>,
     pub cards_resource: Resource<Result<Vec<CardListItemVm>, ViewError>>,
+    /// Pages loaded after the first one that `cards_resource` holds.
+    pub more_cards: Signal<Vec<CardListItemVm>>,
+    pub has_more_cards: Signal<bool>,
+    pub is_loading_more_cards: Signal<bool>,
+    /// Phase breakdown of the selected deck, refreshed with the card list.
+    pub card_counts: Signal<Option<CardPhaseCounts>>,
     pub deck_tags_resource: Resource<Result<Vec<String>, ViewError>>,
     pub daily_limit_resource: Resource<Result<DailyLimitVm, ViewError>>,
+    /// Starter text the selected deck gives its new cards.
+    pub new_card_template_resource: Resource<Result<NewCardTemplate, ViewError>>,
     pub card_tags_resource: CardTagsResource,
+    /// Newest-first review history of the selected card.
+    pub card_history_resource: CardHistoryResource,
     pub clear_editor_fields: VoidAction,
     pub set_editor_fields: SetFieldsAction,
     pub reset_duplicate_state: VoidAction,
@@ -210,6 +312,7 @@ pub fn use_editor_state(deck_id: DeckId, services: &EditorServices) -> EditorSta
     let show_delete_modal = use_signal(|| false);
     let show_validation = use_signal(|| false);
     let focus_prompt = use_signal(|| false);
+    let generate_reverse = use_signal(|| false);
     let show_unsaved_modal = use_signal(|| false);
     let pending_action = use_signal(|| None::<PendingAction>);
     let save_menu_state = use_signal(|| SaveMenuState::Closed);
@@ -226,16 +329,21 @@ pub fn use_editor_state(deck_id: DeckId, services: &EditorServices) -> EditorSta
     let writing_tools_selection_html = use_signal(String::new);
     let writing_tools_selection_text = use_signal(String::new);
     let link_editor_state = use_signal(|| None::<LinkEditorState>);
+    let pending_paste = use_signal(|| None::<PendingPaste>);
+    let pending_image_paste = use_signal(|| None::<PendingImagePaste>);
     let show_new_deck = use_signal(|| false);
     let new_deck_name = use_signal(String::new);
     let new_deck_state = use_signal(|| SaveState::Idle);
     let show_deck_menu = use_signal(|| false);
     let show_deck_actions = use_signal(|| false);
+    let show_archived_decks = use_signal(|| false);
     let is_renaming_deck = use_signal(|| false);
     let rename_deck_name = use_signal(String::new);
     let rename_deck_state = use_signal(|| SaveState::Idle);
     let rename_deck_error = use_signal(|| None::<String>);
     let selected_card_id = use_signal(|| None::<CardId>);
+    let bulk_selection = use_signal(Vec::new);
+    let bulk_state = use_signal(|| BulkActionState::Idle);
     let last_selected_card = use_signal(|| None::<CardListItemVm>);
     let is_create_mode = use_signal(|| false);
     let search_query = use_signal(String::new);
@@ -249,43 +357,66 @@ pub fn use_editor_state(deck_id: DeckId, services: &EditorServices) -> EditorSta
     let show_duplicate_modal = use_signal(|| false);
     let pending_duplicate_practice = use_signal(|| false);
     let show_reset_deck_modal = use_signal(|| false);
+    let show_shortcuts = use_signal(|| false);
+    let show_trash = use_signal(|| false);
+    let show_preview = use_signal(|| false);
+    let preview_fields = use_signal(|| (String::new(), String::new()));
+    let preview_debounce = use_signal(PreviewDebounce::default);
     let reset_deck_state = use_signal(|| ResetDeckState::Idle);
+    let reset_card_state = use_signal(|| ResetCardState::Idle);
 
     let deck_service_for_resource = services.deck_service.clone();
     let decks_resource = use_resource(move || {
         let deck_service = deck_service_for_resource.clone();
+        let include_archived = show_archived_decks();
         async move {
             let decks = deck_service
-                .list_decks(64)
+                .list_decks(64, include_archived)
+                .await
+                .map_err(|_| ViewError::Unknown)?;
+            let counts = deck_service
+                .deck_counts(&decks)
                 .await
                 .map_err(|_| ViewError::Unknown)?;
-            Ok::<_, ViewError>(map_deck_options(&decks))
+            Ok::<_, ViewError>(map_deck_options(&decks, &counts))
         }
     });
 
+    let more_cards = use_signal(Vec::new);
+    let has_more_cards = use_signal(|| false);
+    let is_loading_more_cards = use_signal(|| false);
+    let card_counts = use_signal(|| None::<CardPhaseCounts>);
     let card_service_for_list = services.card_service.clone();
+    let deck_service_for_list = services.deck_service.clone();
     let cards_resource = use_resource(move || {
         let card_service = card_service_for_list.clone();
-        let deck_id = *selected_deck.read();
-        let sort = sort_mode();
-        let filter = CardListFilter::All;
-        let tag_filters = selected_tag_filters();
-        let tag_names = tag_names_from_strings(&tag_filters);
+        let deck_service = deck_service_for_list.clone();
+        let query = CardListQuery {
+            deck_id: *selected_deck.read(),
+            sort: sort_mode(),
+            tag_filters: selected_tag_filters(),
+            search: search_query.read().trim().to_string(),
+        };
+        let mut more_cards = more_cards;
+        let mut has_more_cards = has_more_cards;
+        let mut card_counts = card_counts;
         async move {
-            let cards = card_service
-                .list_cards_filtered(deck_id, 100, sort, filter, &tag_names)
-                .await
-                .map_err(|_| ViewError::Unknown)?;
-            Ok::<_, ViewError>(map_card_list_items(&cards))
+            let page = load_card_list_page(&card_service, &deck_service, &query, 0).await?;
+            more_cards.set(Vec::new());
+            has_more_cards.set(page.has_more);
+            card_counts.set(card_service.count_cards(query.deck_id).await.ok());
+            Ok::<_, ViewError>(page.items)
         }
     });
 
-    let mut last_cards_query = use_signal(|| (deck_id, CardListSort::Recent, String::new()));
+    let mut last_cards_query =
+        use_signal(|| (deck_id, CardListSort::Recent, String::new(), String::new()));
     use_effect(move || {
         let current = (
             *selected_deck.read(),
             sort_mode(),
             tag_filter_key(&selected_tag_filters()),
+            search_query.read().trim().to_string(),
         );
         if last_cards_query() != current {
             last_cards_query.set(current);
@@ -334,6 +465,20 @@ pub fn use_editor_state(deck_id: DeckId, services: &EditorServices) -> EditorSta
         }
     });
 
+    let deck_service_for_template = services.deck_service.clone();
+    let new_card_template_resource = use_resource(move || {
+        let deck_service = deck_service_for_template.clone();
+        let deck_id = *selected_deck.read();
+        async move {
+            let deck = deck_service
+                .get_deck(deck_id)
+                .await
+                .map_err(|_| ViewError::Unknown)?
+                .ok_or(ViewError::Unknown)?;
+            Ok::<NewCardTemplate, ViewError>(deck.settings().new_card_template().clone())
+        }
+    });
+
     let mut last_daily_limit_deck = use_signal(|| deck_id);
     use_effect(move || {
         let current = *selected_deck.read();
@@ -341,6 +486,8 @@ pub fn use_editor_state(deck_id: DeckId, services: &EditorServices) -> EditorSta
             last_daily_limit_deck.set(current);
             let mut daily_limit_resource = daily_limit_resource;
             daily_limit_resource.restart();
+            let mut new_card_template_resource = new_card_template_resource;
+            new_card_template_resource.restart();
         }
     });
 
@@ -365,6 +512,25 @@ pub fn use_editor_state(deck_id: DeckId, services: &EditorServices) -> EditorSta
         }
     });
 
+    let deck_service_for_history = services.deck_service.clone();
+    let card_history_resource = use_resource(move || {
+        let deck_service = deck_service_for_history.clone();
+        let deck_id = *selected_deck.read();
+        let card_id = selected_card_id();
+        async move {
+            let rows = if let Some(card_id) = card_id {
+                let entries = deck_service
+                    .card_history(deck_id, card_id, CARD_HISTORY_LIMIT)
+                    .await
+                    .map_err(|_| ViewError::Unknown)?;
+                map_card_history(&entries, deck_service.now())
+            } else {
+                Vec::new()
+            };
+            Ok::<_, ViewError>((card_id, rows))
+        }
+    });
+
     let mut last_deck_for_tags = use_signal(|| deck_id);
     use_effect(move || {
         let current = *selected_deck.read();
@@ -382,6 +548,8 @@ pub fn use_editor_state(deck_id: DeckId, services: &EditorServices) -> EditorSta
             last_card_tags_key.set(current);
             let mut card_tags_resource = card_tags_resource;
             card_tags_resource.restart();
+            let mut card_history_resource = card_history_resource;
+            card_history_resource.restart();
         }
     });
 
@@ -415,25 +583,48 @@ pub fn use_editor_state(deck_id: DeckId, services: &EditorServices) -> EditorSta
 
     let prompt_text = use_signal(String::new);
     let answer_text = use_signal(String::new);
+    let extra_text = use_signal(String::new);
+    let edit_history = use_signal(FieldEditHistories::default);
+    let ai_assisted = use_signal(|| false);
     let prompt_render_html = use_signal(String::new);
     let answer_render_html = use_signal(String::new);
+    let prompt_media_id = use_signal(|| None::<MediaId>);
+    let answer_media_id = use_signal(|| None::<MediaId>);
+    let media_error = use_signal(|| None::<String>);
+    let new_card_template = use_signal(NewCardTemplate::default);
 
     let clear_editor_fields = {
         let mut prompt_text = prompt_text;
         let mut answer_text = answer_text;
+        let mut extra_text = extra_text;
+        let mut edit_history = edit_history;
+        let mut ai_assisted = ai_assisted;
         let mut prompt_render_html = prompt_render_html;
         let mut answer_render_html = answer_render_html;
+        let mut prompt_media_id = prompt_media_id;
+        let mut answer_media_id = answer_media_id;
+        let mut media_error = media_error;
+        let mut new_card_template = new_card_template;
         Rc::new(RefCell::new(move || {
             prompt_text.set(String::new());
             answer_text.set(String::new());
+            extra_text.set(String::new());
+            edit_history.set(FieldEditHistories::default());
+            ai_assisted.set(false);
             prompt_render_html.set(String::new());
             answer_render_html.set(String::new());
+            prompt_media_id.set(None);
+            answer_media_id.set(None);
+            media_error.set(None);
+            new_card_template.set(NewCardTemplate::default());
         }))
     };
 
     let set_editor_fields = {
         let mut prompt_text = prompt_text;
         let mut answer_text = answer_text;
+        let mut edit_history = edit_history;
+        let mut ai_assisted = ai_assisted;
         let mut prompt_render_html = prompt_render_html;
         let mut answer_render_html = answer_render_html;
         Rc::new(RefCell::new(move |prompt_html: String, answer_html: String| {
@@ -441,6 +632,8 @@ pub fn use_editor_state(deck_id: DeckId, services: &EditorServices) -> EditorSta
             let answer_clone = answer_html.clone();
             prompt_text.set(prompt_clone);
             answer_text.set(answer_clone);
+            edit_history.set(FieldEditHistories::default());
+            ai_assisted.set(false);
             prompt_render_html.set(prompt_html);
             answer_render_html.set(answer_html);
         }))
@@ -465,20 +658,29 @@ pub fn use_editor_state(deck_id: DeckId, services: &EditorServices) -> EditorSta
             let answer_html = answer_text.read().to_string();
             let prompt_plain = strip_html_tags(&prompt_html);
             let answer_plain = strip_html_tags(&answer_html);
+            let extra = extra_text.read().trim().to_owned();
             let tags = card_tags.read().clone();
+            let media = (prompt_media_id(), answer_media_id());
             if is_create_mode() {
-                return !prompt_plain.trim().is_empty()
-                    || !answer_plain.trim().is_empty()
-                    || !tags.is_empty();
+                let starter = new_card_template.read();
+                return differs_from_starter(&prompt_html, starter.prompt())
+                    || differs_from_starter(&answer_html, starter.answer())
+                    || !extra.is_empty()
+                    || !tags.is_empty()
+                    || media != (None, None);
             }
             if let Some(original) = last_selected_card() {
                 prompt_html.trim() != original.prompt_html.trim()
                     || answer_html.trim() != original.answer_html.trim()
+                    || extra != original.extra_text.trim()
                     || !tags_equal(&tags, &last_selected_tags())
+                    || media != (original.prompt_media_id, original.answer_media_id)
             } else {
                 !prompt_plain.trim().is_empty()
                     || !answer_plain.trim().is_empty()
+                    || !extra.is_empty()
                     || !tags.is_empty()
+                    || media != (None, None)
             }
     });
 
@@ -489,6 +691,7 @@ pub fn use_editor_state(deck_id: DeckId, services: &EditorServices) -> EditorSta
         show_delete_modal,
         show_validation,
         focus_prompt,
+        generate_reverse,
         show_unsaved_modal,
         pending_action,
         save_menu_state,
@@ -505,18 +708,24 @@ pub fn use_editor_state(deck_id: DeckId, services: &EditorServices) -> EditorSta
         writing_tools_selection_html,
         writing_tools_selection_text,
         link_editor_state,
+        pending_paste,
+        pending_image_paste,
         show_new_deck,
         new_deck_name,
         new_deck_state,
         show_deck_menu,
         show_deck_actions,
+        show_archived_decks,
         is_renaming_deck,
         rename_deck_name,
         rename_deck_state,
         rename_deck_error,
         selected_card_id,
+        bulk_selection,
+        bulk_state,
         last_selected_card,
         is_create_mode,
+        new_card_template,
         search_query,
         sort_mode,
         selected_tag_filters,
@@ -528,19 +737,161 @@ pub fn use_editor_state(deck_id: DeckId, services: &EditorServices) -> EditorSta
         show_duplicate_modal,
         pending_duplicate_practice,
         show_reset_deck_modal,
+        show_shortcuts,
+        show_trash,
+        show_preview,
+        preview_fields,
+        preview_debounce,
         reset_deck_state,
+        reset_card_state,
         prompt_text,
         answer_text,
+        extra_text,
+        edit_history,
+        ai_assisted,
         prompt_render_html,
         answer_render_html,
+        prompt_media_id,
+        answer_media_id,
+        media_error,
         decks_resource,
         cards_resource,
+        more_cards,
+        has_more_cards,
+        is_loading_more_cards,
+        card_counts,
         deck_tags_resource,
         daily_limit_resource,
+        new_card_template_resource,
         card_tags_resource,
+        card_history_resource,
         clear_editor_fields,
         set_editor_fields,
         reset_duplicate_state,
         has_unsaved_changes,
     }
 }
+
+impl EditorState {
+    /// The card list loaded so far: the first page plus any pages fetched while scrolling.
+    pub fn cards_state(&self) -> ViewState<Vec<CardListItemVm>> {
+        match view_state_from_resource(&self.cards_resource) {
+            ViewState::Ready(mut items) => {
+                let loaded: HashSet<CardId> = items.iter().map(|item| item.id).collect();
+                items.extend(
+                    self.more_cards
+                        .read()
+                        .iter()
+                        .filter(|item| !loaded.contains(&item.id))
+                        .cloned(),
+                );
+                ViewState::Ready(items)
+            }
+            other => other,
+        }
+    }
+
+    /// History rows for the selected card, treating rows loaded for another card as loading.
+    pub fn card_history_state(&self) -> ViewState<Vec<CardHistoryRowVm>> {
+        match view_state_from_resource(&self.card_history_resource) {
+            ViewState::Ready((card_id, rows)) if card_id == *self.selected_card_id.read() => {
+                ViewState::Ready(rows)
+            }
+            ViewState::Ready(_) | ViewState::Loading => ViewState::Loading,
+            ViewState::Idle => ViewState::Idle,
+            ViewState::Error(err) => ViewState::Error(err),
+        }
+    }
+}
+
+/// What the editor card list shows: a deck, its sort and tag filters, and any search text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CardListQuery {
+    pub deck_id: DeckId,
+    pub sort: CardListSort,
+    pub tag_filters: Vec<String>,
+    pub search: String,
+}
+
+impl CardListQuery {
+    pub fn current(state: &EditorState) -> Self {
+        Self {
+            deck_id: *state.selected_deck.peek(),
+            sort: *state.sort_mode.peek(),
+            tag_filters: state.selected_tag_filters.peek().clone(),
+            search: state.search_query.peek().trim().to_string(),
+        }
+    }
+
+    /// Whether the list is the plain newest-first view, where a new card belongs at the top.
+    pub fn is_newest_first(&self) -> bool {
+        self.sort == CardListSort::Recent && self.tag_filters.is_empty() && self.search.is_empty()
+    }
+}
+
+pub struct CardListPage {
+    pub items: Vec<CardListItemVm>,
+    pub has_more: bool,
+}
+
+/// Load the `CARD_PAGE_SIZE` list items after the first `offset`.
+///
+/// The default newest-first list pages through storage directly. Searches, other sorts, and
+/// tag filters re-run their query up to the end of the page and keep its tail. Recall chances
+/// come from the deck's own scheduler.
+pub async fn load_card_list_page(
+    card_service: &CardService,
+    deck_service: &DeckService,
+    query: &CardListQuery,
+    offset: u32,
+) -> Result<CardListPage, ViewError> {
+    let deck_id = query.deck_id;
+    let tag_names = tag_names_from_strings(&query.tag_filters);
+    let end = offset.saturating_add(CARD_PAGE_SIZE);
+    let tail = |cards: Vec<Card>| {
+        let has_more = cards.len() >= end as usize;
+        (cards.into_iter().skip(offset as usize).collect::<Vec<_>>(), has_more)
+    };
+    let (mut cards, has_more) = if !query.search.is_empty() {
+        tail(
+            card_service
+                .search_cards(deck_id, &query.search, end)
+                .await
+                .map_err(|_| ViewError::Unknown)?,
+        )
+    } else if query.sort == CardListSort::Recent && tag_names.is_empty() {
+        let page = card_service
+            .list_cards_page(deck_id, offset, CARD_PAGE_SIZE)
+            .await
+            .map_err(|_| ViewError::Unknown)?;
+        let has_more = page.has_more(offset);
+        (page.cards, has_more)
+    } else {
+        tail(
+            card_service
+                .list_cards_filtered(deck_id, end, query.sort, CardListFilter::All, &tag_names)
+                .await
+                .map_err(|_| ViewError::Unknown)?,
+        )
+    };
+
+    let card_ids: Vec<_> = cards.iter().map(Card::id).collect();
+    let tags = card_service
+        .list_tags_for_cards(deck_id, &card_ids)
+        .await
+        .map_err(|_| ViewError::Unknown)?;
+    if !query.search.is_empty() && !tag_names.is_empty() {
+        cards.retain(|card| {
+            tags.get(&card.id())
+                .is_some_and(|names| names.iter().any(|name| tag_names.contains(name)))
+        });
+    }
+    let scheduler = deck_service
+        .scheduler(deck_id)
+        .await
+        .map_err(|_| ViewError::Unknown)?;
+    Ok(CardListPage {
+        items: map_card_list_items(&cards, &tags, Some(&scheduler), card_service.now()),
+        has_more,
+    })
+}
@@ -7,7 +7,7 @@ use dioxus::prelude::*;
 use dioxus_router::{Routable, Router};
 use learn_core::model::DeckSettings;
 use learn_core::time::fixed_now;
-use services::{CardService, Clock, DeckService};
+use services::{AppSettingsService, CardService, Clock, DeckService, MediaService};
 use storage::repository::Storage;
 
 use super::actions::{EditorIntent, use_editor_dispatcher};
@@ -124,7 +124,12 @@ pub async fn setup_editor_harness(
 ) {
     let storage = Storage::in_memory();
     let clock = Clock::fixed(fixed_now());
-    let deck_service = Arc::new(DeckService::new(clock, Arc::clone(&storage.decks)));
+    let deck_service = Arc::new(DeckService::new(
+        clock,
+        Arc::clone(&storage.decks),
+        Arc::clone(&storage.cards),
+        Arc::clone(&storage.review_logs),
+    ));
     let card_service = Arc::new(CardService::new(clock, Arc::clone(&storage.cards)));
 
     let deck_id = deck_service
@@ -139,6 +144,8 @@ pub async fn setup_editor_harness(
     let services = EditorServices {
         deck_service: Arc::clone(&deck_service),
         card_service: Arc::clone(&card_service),
+        media_service: Arc::new(MediaService::new(clock, Arc::clone(&storage.media))),
+        app_settings: Arc::new(AppSettingsService::new(Arc::clone(&storage.app_settings))),
     };
     let handles = HarnessHandles::default();
     let mut dom = VirtualDom::new_with_props(
@@ -1,10 +1,14 @@
 use learn_core::model::TagName;
 use services::CardListSort;
 
+use crate::vm::strip_html_tags;
+
 pub fn sort_value(sort: CardListSort) -> &'static str {
     match sort {
         CardListSort::Created => "created",
         CardListSort::Alpha => "alpha",
+        CardListSort::DueDate => "due",
+        CardListSort::Difficulty => "difficulty",
         _ => "recent",
     }
 }
@@ -13,6 +17,8 @@ pub fn sort_from_value(value: &str) -> CardListSort {
     match value {
         "created" => CardListSort::Created,
         "alpha" => CardListSort::Alpha,
+        "due" => CardListSort::DueDate,
+        "difficulty" => CardListSort::Difficulty,
         _ => CardListSort::Recent,
     }
 }
@@ -27,6 +33,15 @@ pub fn tag_names_from_strings(tags: &[String]) -> Vec<TagName> {
     tags.iter().filter_map(|tag| TagName::new(tag.clone()).ok()).collect()
 }
 
+/// Whether a field of a card being created holds more than the deck's starter text for it.
+pub fn differs_from_starter(html: &str, starter: &str) -> bool {
+    if starter.is_empty() {
+        !strip_html_tags(html).trim().is_empty()
+    } else {
+        html.trim() != starter.trim()
+    }
+}
+
 pub fn tags_equal(left: &[String], right: &[String]) -> bool {
     let mut left_sorted = left.to_vec();
     let mut right_sorted = right.to_vec();
@@ -55,7 +70,13 @@ mod tests {
 
     #[test]
     fn sort_value_round_trip() {
-        for sort in [CardListSort::Recent, CardListSort::Created, CardListSort::Alpha] {
+        for sort in [
+            CardListSort::Recent,
+            CardListSort::Created,
+            CardListSort::Alpha,
+            CardListSort::DueDate,
+            CardListSort::Difficulty,
+        ] {
             let value = sort_value(sort);
             assert_eq!(sort_from_value(value), sort);
         }
@@ -1,42 +1,65 @@
 use dioxus::document::eval;
+use dioxus::html::FileData;
 use dioxus::prelude::*;
 use dioxus_router::use_navigator;
+use learn_core::model::DeckId;
+use services::{WritingToolsError, WritingToolsErrorKind};
 
+use crate::app::use_key_bindings;
 use crate::context::AppContext;
 use crate::routes::Route;
 use crate::vm::{
-    MarkdownField, looks_like_html, markdown_to_html, sanitize_html,
+    MarkdownField, PREVIEW_DEBOUNCE, PasteKind, card_ids_param, looks_like_html, markdown_to_html,
+    sanitize_html,
 };
-use crate::views::{ViewState, view_state_from_resource};
+use crate::shortcuts;
+use crate::views::{DeckMark, ShortcutsOverlay, ViewState, view_state_from_resource};
 
 use super::actions::{EditorIntent, use_editor_dispatcher};
-use super::components::{EditorDetailPane, EditorListPane, EditorOverlays};
+use super::components::{
+    EditorDetailPane, EditorListPane, EditorOverlays, RecentlyDeletedModal,
+};
 use super::scripts::{
-    attach_rich_paste_handler, read_editable_html, read_link_href_at_point,
-    read_selected_link_href, set_editable_html,
+    STRUCTURED_PASTE_WATCH_SCRIPT, StructuredPaste, attach_rich_paste_handler,
+    insert_at_stored_selection, read_editable_html, read_link_href_at_point,
+    read_selected_link_href, read_selection_range, set_editable_html,
 };
 use super::state::{
-    DeleteState, EditorServices, SaveMenuState, SaveState, WritingToolsMenuState,
-    WritingToolsResultStatus, use_editor_state,
+    DeleteState, EditorServices, ImagePasteChoice, PasteChoice, PendingImagePaste, PendingPaste,
+    SaveMenuState, SaveState, WritingToolsMenuState, WritingToolsResultStatus, use_editor_state,
 };
 use crate::vm::build_editor_vm;
 
+/// The card editor, on `deck_id` if given or else the current deck.
+///
+/// `search` pre-fills the card list search, e.g. when opened from an all-decks search.
 #[component]
-pub fn EditorView() -> Element {
+pub fn EditorView(deck_id: Option<u64>, search: Option<String>) -> Element {
     let ctx = use_context::<AppContext>();
     let navigator = use_navigator();
     let services = EditorServices {
         deck_service: ctx.deck_service(),
         card_service: ctx.card_service(),
+        media_service: ctx.media_service(),
+        app_settings: ctx.app_settings(),
     };
-    let state = use_editor_state(ctx.current_deck_id(), &services);
+    let deck_id = deck_id.map_or_else(|| ctx.current_deck_id(), DeckId::new);
+    let state = use_editor_state(deck_id, &services);
+    let key_bindings = use_key_bindings();
+    let mut search_query = state.search_query;
+    use_hook(move || {
+        if let Some(search) = search {
+            search_query.set(search);
+        }
+    });
     let dispatcher = use_editor_dispatcher(&state, &services);
     let dispatch = dispatcher.dispatch;
 
     let decks_state = view_state_from_resource(&state.decks_resource);
-    let cards_state = view_state_from_resource(&state.cards_resource);
+    let cards_state = state.cards_state();
     let deck_tags_state = view_state_from_resource(&state.deck_tags_resource);
     let daily_limit_state = view_state_from_resource(&state.daily_limit_resource);
+    let card_history_state = state.card_history_state();
 
     let vm = build_editor_vm(
         &state,
@@ -57,6 +80,7 @@ pub fn EditorView() -> Element {
     let show_duplicate_modal = state.show_duplicate_modal;
     let show_unsaved_modal = state.show_unsaved_modal;
     let save_menu_state = state.save_menu_state;
+    let generate_reverse = state.generate_reverse;
     let writing_tools_menu_state = state.writing_tools_menu_state;
     let writing_tools_prompt = state.writing_tools_prompt;
     let writing_tools_tone = state.writing_tools_tone;
@@ -67,6 +91,13 @@ pub fn EditorView() -> Element {
     let writing_tools_result_body = state.writing_tools_result_body;
     let writing_tools_result_html = state.writing_tools_result_html;
     let link_editor_state = state.link_editor_state;
+    let pending_paste = state.pending_paste;
+    let pending_image_paste = state.pending_image_paste;
+    let prompt_media_id = state.prompt_media_id;
+    let answer_media_id = state.answer_media_id;
+    let media_error = state.media_error;
+    let has_more_cards = state.has_more_cards;
+    let is_loading_more_cards = state.is_loading_more_cards;
 
     let writing_tools_service = ctx.writing_tools();
     use_effect(move || {
@@ -89,7 +120,7 @@ pub fn EditorView() -> Element {
                 }
                 Err(err) => {
                     writing_tools_result_status.set(WritingToolsResultStatus::Error);
-                    writing_tools_result_body.set(err.to_string());
+                    writing_tools_result_body.set(writing_tools_error_message(&err));
                 }
             }
         });
@@ -104,7 +135,11 @@ pub fn EditorView() -> Element {
         }
     });
     let show_reset_deck_modal = state.show_reset_deck_modal;
+    let mut show_shortcuts = state.show_shortcuts;
+    let mut show_trash = state.show_trash;
     let reset_deck_state = state.reset_deck_state;
+    let reset_card_state = state.reset_card_state;
+    let show_archived_decks = state.show_archived_decks;
     let mut show_new_deck = state.show_new_deck;
     let mut new_deck_state = state.new_deck_state;
     let mut new_deck_name = state.new_deck_name;
@@ -116,6 +151,27 @@ pub fn EditorView() -> Element {
     let last_focus_field = state.last_focus_field;
     let prompt_text = state.prompt_text;
     let answer_text = state.answer_text;
+    let extra_text = state.extra_text;
+    let edit_history = state.edit_history;
+    let mut show_preview = state.show_preview;
+    let preview_fields = state.preview_fields;
+
+    let preview_debounce = state.preview_debounce;
+    use_effect(move || {
+        let fields = (prompt_text(), answer_text());
+        if !show_preview() {
+            return;
+        }
+        let mut preview_debounce = preview_debounce;
+        let mut preview_fields = preview_fields;
+        let ticket = preview_debounce.write().schedule();
+        spawn(async move {
+            tokio::time::sleep(PREVIEW_DEBOUNCE).await;
+            if preview_debounce.peek().is_latest(ticket) {
+                preview_fields.set(fields);
+            }
+        });
+    });
 
     let mut focus_prompt = state.focus_prompt;
     use_effect(move || {
@@ -149,6 +205,76 @@ pub fn EditorView() -> Element {
         });
     });
 
+    use_future(move || async move {
+        let mut pending_image_paste = pending_image_paste;
+        let mut watcher = eval(STRUCTURED_PASTE_WATCH_SCRIPT);
+        while let Ok(paste) = watcher.recv::<StructuredPaste>().await {
+            let field = match paste.element_id.as_str() {
+                "prompt" => MarkdownField::Front,
+                "answer" => MarkdownField::Back,
+                _ => continue,
+            };
+            let image = paste.image_bytes();
+            match (PasteKind::classify(image.is_some(), &paste.html, &paste.text), image) {
+                (PasteKind::Image, Some(image)) => {
+                    dispatch.call(EditorIntent::PasteImage(field, image));
+                }
+                (PasteKind::ImageOrText, Some(image)) => {
+                    pending_image_paste.set(Some(PendingImagePaste {
+                        field,
+                        image,
+                        html: paste.html,
+                        text: paste.text,
+                    }));
+                }
+                (kind, _) => paste_text(pending_paste, field, kind, &paste.text).await,
+            }
+        }
+    });
+    use_drop(|| {
+        let _ = eval("window.__learnStructuredPasteSink = null;");
+    });
+
+    let on_resolve_paste = use_callback(move |choice: PasteChoice| {
+        let mut pending_paste = pending_paste;
+        let Some(pending) = pending_paste() else {
+            return;
+        };
+        pending_paste.set(None);
+        let element_id = match pending.field {
+            MarkdownField::Front => "prompt",
+            MarkdownField::Back => "answer",
+        };
+        let html = match choice {
+            PasteChoice::Markdown => Some(pending.offer.markdown_html()),
+            PasteChoice::PlainText => None,
+            PasteChoice::Cancel => return,
+        };
+        spawn(async move {
+            insert_at_stored_selection(element_id, html.as_deref(), &pending.offer.plain).await;
+        });
+    });
+
+    let on_resolve_image_paste = use_callback(move |choice: ImagePasteChoice| {
+        let mut pending_image_paste = pending_image_paste;
+        let Some(pending) = pending_image_paste() else {
+            return;
+        };
+        pending_image_paste.set(None);
+        match choice {
+            ImagePasteChoice::Image => {
+                dispatch.call(EditorIntent::PasteImage(pending.field, pending.image));
+            }
+            ImagePasteChoice::Text => {
+                let kind = PasteKind::classify(false, &pending.html, &pending.text);
+                spawn(async move {
+                    paste_text(pending_paste, pending.field, kind, &pending.text).await;
+                });
+            }
+            ImagePasteChoice::Cancel => {}
+        }
+    });
+
     let deck_overlay_close = {
         let mut show_deck_menu = show_deck_menu;
         let mut show_deck_actions = show_deck_actions;
@@ -189,6 +315,12 @@ pub fn EditorView() -> Element {
         })
     };
 
+    let load_more_cards = {
+        use_callback(move |()| {
+            dispatch.call(EditorIntent::LoadMoreCards);
+        })
+    };
+
     let on_focus_field = {
         let mut last_focus_field = last_focus_field;
         use_callback(move |field: MarkdownField| {
@@ -199,8 +331,14 @@ pub fn EditorView() -> Element {
     let on_prompt_input = use_callback(move |()| {
         let mut prompt_text = prompt_text;
         let mut save_state = save_state;
+        let mut edit_history = edit_history;
         spawn(async move {
             if let Some(updated) = read_editable_html("prompt").await {
+                let before = prompt_text.peek().clone();
+                if before != updated {
+                    let after = read_selection_range("prompt").await.unwrap_or_default();
+                    edit_history.write().front.record_typing(before, after);
+                }
                 prompt_text.set(updated);
                 save_state.set(SaveState::Idle);
             }
@@ -210,14 +348,33 @@ pub fn EditorView() -> Element {
     let on_answer_input = use_callback(move |()| {
         let mut answer_text = answer_text;
         let mut save_state = save_state;
+        let mut edit_history = edit_history;
         spawn(async move {
             if let Some(updated) = read_editable_html("answer").await {
+                let before = answer_text.peek().clone();
+                if before != updated {
+                    let after = read_selection_range("answer").await.unwrap_or_default();
+                    edit_history.write().back.record_typing(before, after);
+                }
                 answer_text.set(updated);
                 save_state.set(SaveState::Idle);
             }
         });
     });
 
+    let on_extra_input = use_callback(move |value: String| {
+        let mut extra_text = extra_text;
+        let mut save_state = save_state;
+        let mut edit_history = edit_history;
+        let before = extra_text.peek().clone();
+        extra_text.set(value);
+        save_state.set(SaveState::Idle);
+        spawn(async move {
+            let after = read_selection_range("extra").await.unwrap_or_default();
+            edit_history.write().extra.record_typing(before, after);
+        });
+    });
+
     let on_indent = {
         use_callback(move |(field, outdent): (MarkdownField, bool)| {
             dispatch.call(EditorIntent::Indent(field, outdent));
@@ -230,6 +387,18 @@ pub fn EditorView() -> Element {
         })
     };
 
+    let on_attach_image = {
+        use_callback(move |(field, file): (MarkdownField, FileData)| {
+            dispatch.call(EditorIntent::AttachImage(field, file));
+        })
+    };
+
+    let on_remove_image = {
+        use_callback(move |field: MarkdownField| {
+            dispatch.call(EditorIntent::RemoveImage(field));
+        })
+    };
+
     let on_copy_writing_tools = {
         use_callback(move |field: MarkdownField| {
             dispatch.call(EditorIntent::WritingToolsCopy(field));
@@ -243,6 +412,13 @@ pub fn EditorView() -> Element {
         })
     };
 
+    let on_toggle_reverse = {
+        let mut generate_reverse = generate_reverse;
+        use_callback(move |checked: bool| {
+            generate_reverse.set(checked);
+        })
+    };
+
     let on_delete_close = {
         use_callback(move |()| {
             dispatch.call(EditorIntent::CloseDeleteModal);
@@ -293,7 +469,7 @@ pub fn EditorView() -> Element {
 
     let on_request_select_card = {
         use_callback(move |card| {
-            dispatch.call(EditorIntent::RequestSelectCard(card));
+            dispatch.call(EditorIntent::RequestSelectCard(Box::new(card)));
         })
     };
 
@@ -363,6 +539,12 @@ pub fn EditorView() -> Element {
         })
     };
 
+    let on_reset_card = {
+        use_callback(move |()| {
+            dispatch.call(EditorIntent::ResetCard);
+        })
+    };
+
     let on_save = {
         use_callback(move |request| {
             dispatch.call(EditorIntent::Save(request));
@@ -466,6 +648,8 @@ pub fn EditorView() -> Element {
         })
     };
 
+    let image_paste_field = pending_image_paste.read().as_ref().map(|pending| pending.field);
+
     rsx! {
         div { class: "page page--editor", tabindex: "0", onkeydown: dispatcher.on_key,
             EditorOverlays {
@@ -495,6 +679,22 @@ pub fn EditorView() -> Element {
                 on_unsaved_cancel: on_unsaved_cancel,
                 on_unsaved_confirm: on_unsaved_confirm,
             }
+            if show_shortcuts() {
+                ShortcutsOverlay {
+                    shortcuts: shortcuts::editor::all(&key_bindings),
+                    on_close: move |()| show_shortcuts.set(false),
+                }
+            }
+            if show_trash() {
+                RecentlyDeletedModal {
+                    deck_id: *state.selected_deck.read(),
+                    on_close: move |()| show_trash.set(false),
+                    on_restored: move |()| {
+                        let mut cards_resource = state.cards_resource;
+                        cards_resource.restart();
+                    },
+                }
+            }
 
             section { class: "editor-shell",
                 header { class: "editor-toolbar",
@@ -513,6 +713,10 @@ pub fn EditorView() -> Element {
                             ViewState::Ready(options) => {
                                 let deck_label_for_double = deck_label.clone();
                                 let deck_label_for_context = deck_label.clone();
+                                let selected_deck_id = *state.selected_deck.read();
+                                let selected_archived = options
+                                    .iter()
+                                    .any(|opt| opt.id == selected_deck_id && opt.archived);
                                 rsx! {
                                     div { class: "editor-deck-trigger",
                                         if is_renaming_deck() {
@@ -601,15 +805,39 @@ pub fn EditorView() -> Element {
                                     if show_deck_menu() {
                                         div { class: "editor-deck-popover",
                                             for opt in options {
-                                                button {
-                                                    class: if opt.id == *state.selected_deck.read() {
-                                                        "editor-deck-item editor-deck-item--active"
-                                                    } else {
-                                                        "editor-deck-item"
-                                                    },
-                                                    r#type: "button",
-                                                    onclick: move |_| on_request_select_deck.call(opt.id),
-                                                    "{opt.label}"
+                                                div { key: "{opt.id.value()}", class: "editor-deck-row",
+                                                    button {
+                                                        class: if opt.id == *state.selected_deck.read() {
+                                                            "editor-deck-item editor-deck-item--active"
+                                                        } else {
+                                                            "editor-deck-item"
+                                                        },
+                                                        r#type: "button",
+                                                        onclick: move |_| on_request_select_deck.call(opt.id),
+                                                        DeckMark { color: opt.color, icon: opt.icon }
+                                                        "{opt.label}"
+                                                        if opt.archived {
+                                                            span { class: "editor-deck-item-badge", "Archived" }
+                                                        } else if let Some(counts) = opt.counts_label() {
+                                                            span { class: "editor-deck-item-counts", "{counts}" }
+                                                        }
+                                                    }
+                                                    button {
+                                                        class: if opt.favorite {
+                                                            "editor-deck-star editor-deck-star--on"
+                                                        } else {
+                                                            "editor-deck-star"
+                                                        },
+                                                        r#type: "button",
+                                                        aria_label: "Star deck",
+                                                        aria_pressed: "{opt.favorite}",
+                                                        onclick: move |_| {
+                                                            let star = !opt.favorite;
+                                                            dispatch
+                                                                .call(EditorIntent::SetDeckFavorite(opt.id, star));
+                                                        },
+                                                        if opt.favorite { "★" } else { "☆" }
+                                                    }
                                                 }
                                             }
                                             button {
@@ -627,6 +855,16 @@ pub fn EditorView() -> Element {
                                                 },
                                                 "+ New deck..."
                                             }
+                                            button {
+                                                class: "editor-deck-item editor-deck-item--toggle",
+                                                r#type: "button",
+                                                onclick: move |_| dispatch.call(EditorIntent::ToggleArchivedDecks),
+                                                if show_archived_decks() {
+                                                    "Hide archived decks"
+                                                } else {
+                                                    "Show archived decks"
+                                                }
+                                            }
                                         }
                                     }
                                     if show_deck_actions() {
@@ -650,6 +888,24 @@ pub fn EditorView() -> Element {
                                                 },
                                                 "Reset deck learning..."
                                             }
+                                            button {
+                                                class: "editor-deck-action",
+                                                r#type: "button",
+                                                onclick: move |_| {
+                                                    show_deck_actions.set(false);
+                                                    show_trash.set(true);
+                                                },
+                                                "Recently deleted..."
+                                            }
+                                            button {
+                                                class: "editor-deck-action",
+                                                r#type: "button",
+                                                onclick: move |_| {
+                                                    dispatch
+                                                        .call(EditorIntent::SetDeckArchived(!selected_archived));
+                                                },
+                                                if selected_archived { "Restore deck" } else { "Archive deck" }
+                                            }
                                         }
                                     }
                                 }
@@ -714,6 +970,7 @@ pub fn EditorView() -> Element {
                         selected_card_id: vm.selected_card_id,
                         search_value: vm.search_value.clone(),
                         match_count: vm.match_count,
+                        card_counts_label: vm.card_counts_label.clone(),
                         sort_mode: sort_mode(),
                         selected_tag: vm.selected_tag.clone(),
                         deck_tags: vm.deck_tags.clone(),
@@ -726,6 +983,26 @@ pub fn EditorView() -> Element {
                         on_select_card: on_request_select_card,
                         on_new_card: on_request_new_card,
                         on_list_key: dispatcher.list_on_key,
+                        has_more_cards: has_more_cards(),
+                        is_loading_more_cards: is_loading_more_cards(),
+                        on_load_more_cards: load_more_cards,
+                        bulk_selection: state.bulk_selection.read().clone(),
+                        bulk_state: *state.bulk_state.read(),
+                        on_bulk_select: move |ids| {
+                            dispatch.call(EditorIntent::SetBulkSelection(ids));
+                        },
+                        on_bulk_delete: move |()| dispatch.call(EditorIntent::BulkDelete),
+                        on_bulk_suspend: move |suspended| {
+                            dispatch.call(EditorIntent::BulkSuspend(suspended));
+                        },
+                        on_bulk_tag: move |(tag, add)| {
+                            dispatch.call(EditorIntent::BulkTag(tag, add));
+                        },
+                        on_bulk_study: move |()| {
+                            let card_ids = card_ids_param(&state.bulk_selection.read());
+                            let deck_id = state.selected_deck.read().value();
+                            navigator.push(Route::SessionCards { deck_id, card_ids });
+                        },
                     }
                     EditorDetailPane {
                         can_edit: vm.can_edit,
@@ -736,14 +1013,21 @@ pub fn EditorView() -> Element {
                         can_submit: vm.can_submit,
                         prompt_invalid: vm.prompt_invalid,
                         answer_invalid: vm.answer_invalid,
+                        prompt_counter: vm.prompt_counter,
+                        answer_counter: vm.answer_counter,
+                        extra_counter: vm.extra_counter,
                         prompt_toolbar_disabled: vm.prompt_toolbar_disabled,
                         answer_toolbar_disabled: vm.answer_toolbar_disabled,
                         tag_input_value: vm.tag_input_value.clone(),
                         tag_suggestions: vm.tag_suggestions.clone(),
                         card_tags: vm.card_tags.clone(),
+                        generate_reverse: generate_reverse(),
                         daily_limit_warning: vm.daily_limit_warning.clone(),
+                        recall_label: vm.recall_label.clone(),
+                        source_label: vm.source_label.clone(),
                         save_state: save_state(),
                         delete_state: delete_state(),
+                        reset_card_state: reset_card_state(),
                         duplicate_check_state: duplicate_check_state(),
                         save_menu_state: save_menu_state(),
                         writing_tools_menu_state: writing_tools_menu_state(),
@@ -754,9 +1038,20 @@ pub fn EditorView() -> Element {
                         writing_tools_result_title: writing_tools_result_title(),
                         writing_tools_result_html: writing_tools_result_html(),
                         link_editor_state: link_editor_state(),
+                        pending_paste: pending_paste(),
+                        image_paste_field,
+                        prompt_media_id: prompt_media_id(),
+                        answer_media_id: answer_media_id(),
+                        media_error: media_error(),
+                        extra_text: extra_text(),
+                        show_preview: show_preview(),
+                        preview_prompt_html: preview_fields.read().0.clone(),
+                        preview_answer_html: preview_fields.read().1.clone(),
+                        card_history: card_history_state,
                         on_focus_field,
                         on_prompt_input,
                         on_answer_input,
+                        on_extra_input,
                         on_format: on_format,
                         on_block_dir: on_block_dir,
                         on_indent,
@@ -766,6 +1061,10 @@ pub fn EditorView() -> Element {
                         on_update_link_url: on_update_link_url,
                         on_apply_link: on_apply_link,
                         on_remove_link: on_remove_link,
+                        on_resolve_paste,
+                        on_resolve_image_paste,
+                        on_attach_image,
+                        on_remove_image,
                         on_toggle_writing_tools: on_toggle_writing_tools,
                         on_update_writing_tools_prompt: on_update_writing_tools_prompt,
                         on_select_writing_tools_tone: on_select_writing_tools_tone,
@@ -775,11 +1074,14 @@ pub fn EditorView() -> Element {
                         on_tag_input_change,
                         on_tag_add: on_tag_add,
                         on_tag_remove: on_tag_remove,
+                        on_toggle_reverse,
                         on_cancel: on_cancel_new,
                         on_open_delete: on_open_delete,
+                        on_reset_card,
                         on_save: on_save,
                         on_toggle_save_menu: on_toggle_save_menu,
                         on_close_save_menu: on_close_save_menu,
+                        on_toggle_preview: move |()| show_preview.set(!show_preview()),
                     }
                 }
             }
@@ -787,6 +1089,27 @@ pub fn EditorView() -> Element {
     }
 }
 
+/// Insert a paste without an image. Lists and tables wait for the user to pick markdown or
+/// plain text; anything else goes in at the paste's stored selection.
+async fn paste_text(
+    mut pending_paste: Signal<Option<PendingPaste>>,
+    field: MarkdownField,
+    kind: PasteKind,
+    text: &str,
+) {
+    let element_id = match field {
+        MarkdownField::Front => "prompt",
+        MarkdownField::Back => "answer",
+    };
+    match kind {
+        PasteKind::Structured(offer) => pending_paste.set(Some(PendingPaste { field, offer })),
+        PasteKind::Html(html) => insert_at_stored_selection(element_id, Some(&html), text).await,
+        PasteKind::Image | PasteKind::ImageOrText | PasteKind::Text => {
+            insert_at_stored_selection(element_id, None, text).await;
+        }
+    }
+}
+
 fn render_writing_tools_html(body: &str) -> String {
     if body.trim().is_empty() {
         return String::new();
@@ -796,3 +1119,22 @@ fn render_writing_tools_html(body: &str) -> String {
     }
     markdown_to_html(body)
 }
+
+/// User-facing text for a failed writing-tools request, phrased by failure kind.
+fn writing_tools_error_message(err: &WritingToolsError) -> String {
+    match err.kind() {
+        WritingToolsErrorKind::Offline => {
+            "You appear to be offline. Check your connection and try again.".to_string()
+        }
+        WritingToolsErrorKind::RateLimited => {
+            "Too many requests. Wait a moment and try again.".to_string()
+        }
+        WritingToolsErrorKind::Auth => {
+            "The API key is missing or was rejected. Check it in Settings.".to_string()
+        }
+        WritingToolsErrorKind::Retryable => {
+            "The AI service is not responding. Try again in a moment.".to_string()
+        }
+        _ => err.to_string(),
+    }
+}
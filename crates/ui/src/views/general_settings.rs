@@ -1,8 +1,14 @@
 use dioxus::prelude::*;
 
-use learn_core::model::{AppSettings, AppSettingsDraft};
+use learn_core::model::{
+    self as model, AppLanguage, AppSettings, KeyAction, KeyBindings, KeyCombo, ThemePreference,
+};
+use learn_core::time::StudyDay;
+use services::{AiCostEstimate, AiUsageRange};
 
+use crate::app::{Appearance, Keymap, Locale, use_strings};
 use crate::context::AppContext;
+use crate::shortcuts;
 use crate::views::{ViewError, ViewState, view_state_from_resource};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -20,6 +26,22 @@ impl ThemeChoice {
             ThemeChoice::Dark => "Dark",
         }
     }
+
+    fn to_model(self) -> ThemePreference {
+        match self {
+            ThemeChoice::System => ThemePreference::System,
+            ThemeChoice::Light => ThemePreference::Light,
+            ThemeChoice::Dark => ThemePreference::Dark,
+        }
+    }
+
+    fn from_model(theme: ThemePreference) -> Self {
+        match theme {
+            ThemePreference::System => ThemeChoice::System,
+            ThemePreference::Light => ThemeChoice::Light,
+            ThemePreference::Dark => ThemeChoice::Dark,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -48,6 +70,24 @@ impl LanguageOption {
             _ => LanguageOption::English,
         }
     }
+
+    fn to_model(self) -> AppLanguage {
+        match self {
+            LanguageOption::English => AppLanguage::English,
+            LanguageOption::German => AppLanguage::German,
+            LanguageOption::Spanish => AppLanguage::Spanish,
+            LanguageOption::French => AppLanguage::French,
+        }
+    }
+
+    fn from_model(language: AppLanguage) -> Self {
+        match language {
+            AppLanguage::English => LanguageOption::English,
+            AppLanguage::German => LanguageOption::German,
+            AppLanguage::Spanish => LanguageOption::Spanish,
+            AppLanguage::French => LanguageOption::French,
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -97,6 +137,24 @@ impl AccentColor {
             _ => AccentColor::Blue,
         }
     }
+
+    fn to_model(self) -> model::AccentColor {
+        match self {
+            AccentColor::Blue => model::AccentColor::Blue,
+            AccentColor::Green => model::AccentColor::Green,
+            AccentColor::Orange => model::AccentColor::Orange,
+            AccentColor::Purple => model::AccentColor::Purple,
+        }
+    }
+
+    fn from_model(color: model::AccentColor) -> Self {
+        match color {
+            model::AccentColor::Blue => AccentColor::Blue,
+            model::AccentColor::Green => AccentColor::Green,
+            model::AccentColor::Orange => AccentColor::Orange,
+            model::AccentColor::Purple => AccentColor::Purple,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -106,6 +164,7 @@ struct GeneralSettingsForm {
     appearance_enabled: bool,
     accent_color: AccentColor,
     protect_overload: bool,
+    day_cutoff_hour: u32,
     target_retention: String,
     analytics_enabled: bool,
     email: String,
@@ -115,6 +174,10 @@ struct GeneralSettingsForm {
     ai_system_prompt: String,
     ai_daily_request_cap: String,
     ai_cooldown_secs: String,
+    /// USD per 1,000 tokens for a preferred model the price book does not list.
+    ai_manual_price: String,
+    /// Typed key combination per `KeyAction::ALL` entry, e.g. "Cmd+Enter".
+    key_bindings: Vec<String>,
 }
 
 impl Default for GeneralSettingsForm {
@@ -125,6 +188,7 @@ impl Default for GeneralSettingsForm {
             appearance_enabled: true,
             accent_color: AccentColor::Blue,
             protect_overload: true,
+            day_cutoff_hour: 0,
             target_retention: "0.85".to_string(),
             analytics_enabled: false,
             email: "john.smil@gmail.com".to_string(),
@@ -134,6 +198,8 @@ impl Default for GeneralSettingsForm {
             ai_system_prompt: String::new(),
             ai_daily_request_cap: "100".to_string(),
             ai_cooldown_secs: "5".to_string(),
+            ai_manual_price: String::new(),
+            key_bindings: key_binding_fields(&KeyBindings::default()),
         }
     }
 }
@@ -147,12 +213,64 @@ enum SaveState {
 }
 
 fn apply_app_settings(form: &mut GeneralSettingsForm, settings: &AppSettings) {
+    form.language = LanguageOption::from_model(settings.language());
+    form.theme = ThemeChoice::from_model(settings.theme());
+    form.accent_color = AccentColor::from_model(settings.accent_color());
+    form.analytics_enabled = settings.analytics_enabled();
+    form.day_cutoff_hour = settings.day_cutoff_hour();
     form.ai_api_key = settings.api_key().unwrap_or_default().to_string();
     form.ai_model = settings.api_model().unwrap_or_default().to_string();
     form.ai_fallback_model = settings.api_fallback_model().unwrap_or_default().to_string();
     form.ai_system_prompt = settings.ai_system_prompt().unwrap_or_default().to_string();
     form.ai_daily_request_cap = settings.ai_daily_request_cap().to_string();
     form.ai_cooldown_secs = settings.ai_cooldown_secs().to_string();
+    form.ai_manual_price = settings
+        .ai_manual_price_micro_usd_per_1k()
+        .map(format_usd_from_micro)
+        .unwrap_or_default();
+    form.key_bindings = key_binding_fields(settings.key_bindings());
+}
+
+const SHORTCUT_INPUT_CLASS: &str = "editor-input settings-input settings-input--short";
+const SHORTCUT_INPUT_ERROR_CLASS: &str =
+    "editor-input settings-input settings-input--short editor-input--error";
+
+fn key_binding_fields(bindings: &KeyBindings) -> Vec<String> {
+    KeyAction::ALL
+        .iter()
+        .map(|action| bindings.get(*action).to_string())
+        .collect()
+}
+
+/// Parse the shortcut fields, or explain what is wrong with each field that has a problem.
+///
+/// A combination clashes when another action in the same view, or a shortcut that cannot be
+/// remapped, already answers to it.
+fn check_key_bindings(fields: &[String]) -> Result<KeyBindings, Vec<(KeyAction, String)>> {
+    let mut bindings = KeyBindings::default();
+    let mut problems = Vec::new();
+    for (action, field) in KeyAction::ALL.iter().zip(fields) {
+        match KeyCombo::parse(field) {
+            Ok(combo) => bindings = bindings.with_binding(*action, combo),
+            Err(_) => problems.push((*action, "Not a key combination".to_string())),
+        }
+    }
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    for conflict in bindings.conflicts() {
+        let message = format!("Also bound to {}", conflict.first.label());
+        problems.push((conflict.second, message));
+    }
+    for clash in shortcuts::fixed_clashes(&bindings) {
+        problems.push((clash.action, format!("Already used by {}", clash.fixed.description)));
+    }
+    if problems.is_empty() {
+        Ok(bindings)
+    } else {
+        Err(problems)
+    }
 }
 
 fn to_optional(value: &str) -> Option<String> {
@@ -172,16 +290,66 @@ fn parse_optional_u32(value: &str) -> Result<Option<u32>, ()> {
     trimmed.parse::<u32>().map(Some).map_err(|_| ())
 }
 
+/// Parse a dollar amount such as "0.002" into micro-USD.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn parse_optional_usd_micro(value: &str) -> Result<Option<u64>, ()> {
+    let trimmed = value.trim().trim_start_matches('$');
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    let usd = trimmed.parse::<f64>().map_err(|_| ())?;
+    if !usd.is_finite() || usd < 0.0 {
+        return Err(());
+    }
+    Ok(Some((usd * 1_000_000.0).round() as u64))
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_usd_from_micro(micro_usd: u64) -> String {
+    (micro_usd as f64 / 1_000_000.0).to_string()
+}
+
+fn format_spend(estimate: &AiCostEstimate) -> String {
+    if estimate.total_micro_usd > 0 && estimate.total_micro_usd < 10_000 {
+        "<$0.01".to_string()
+    } else {
+        format!("${:.2}", estimate.total_usd())
+    }
+}
+
+fn unpriced_models_label(today: &AiCostEstimate, month: &AiCostEstimate) -> Option<String> {
+    let mut models: Vec<&str> = month
+        .unpriced
+        .iter()
+        .chain(today.unpriced.iter())
+        .map(|usage| usage.model.as_str())
+        .collect();
+    models.sort_unstable();
+    models.dedup();
+    if models.is_empty() {
+        None
+    } else {
+        Some(format!("Not included (no pricing): {}", models.join(", ")))
+    }
+}
 
 #[component]
 pub fn GeneralSettingsView() -> Element {
     let ctx = use_context::<AppContext>();
     let app_settings = ctx.app_settings();
+    let appearance = try_use_context::<Appearance>();
+    let keymap = try_use_context::<Keymap>();
+    let locale = try_use_context::<Locale>();
+    let strings = use_strings();
     let app_settings_for_resource = app_settings.clone();
+    let ai_usage = ctx.ai_usage();
+    let ai_usage_for_pricing = ai_usage.clone();
     let mut form = use_signal(GeneralSettingsForm::default);
     let mut initial = use_signal(GeneralSettingsForm::default);
     let mut save_state = use_signal(|| SaveState::Idle);
     let mut settings_loaded = use_signal(|| false);
+    // Last persisted settings; fields this form does not edit are saved back unchanged.
+    let mut persisted = use_signal(AppSettings::default);
     let mut show_model_menu = use_signal(|| false);
 
     let settings_resource = use_resource(move || {
@@ -192,6 +360,28 @@ pub fn GeneralSettingsView() -> Element {
         }
     });
 
+    let spend_resource = use_resource(move || {
+        let ai_usage = ai_usage.clone();
+        async move {
+            let today = ai_usage.estimated_cost(AiUsageRange::Today).await.ok()?;
+            let month = ai_usage.estimated_cost(AiUsageRange::ThisMonth).await.ok()?;
+            Some((today, month))
+        }
+    });
+    let spend = spend_resource.read().clone().flatten();
+
+    let model_priced_resource = use_resource(move || {
+        let ai_usage = ai_usage_for_pricing.clone();
+        let model = form.read().ai_model.trim().to_string();
+        async move {
+            if model.is_empty() {
+                return true;
+            }
+            ai_usage.is_model_priced(&model).await.unwrap_or(true)
+        }
+    });
+    let model_unpriced = model_priced_resource.read().is_some_and(|priced| !priced);
+
     let settings_state = view_state_from_resource(&settings_resource);
     if let ViewState::Ready(settings) = settings_state
         && !settings_loaded()
@@ -200,18 +390,34 @@ pub fn GeneralSettingsView() -> Element {
         apply_app_settings(&mut next, &settings);
         form.set(next.clone());
         initial.set(next);
+        persisted.set(settings);
         settings_loaded.set(true);
     }
 
     let form_value = form();
     let initial_value = initial();
     let is_dirty = form_value != initial_value;
+    let key_binding_problems = check_key_bindings(&form_value.key_bindings)
+        .err()
+        .unwrap_or_default();
+    let can_save = is_dirty && key_binding_problems.is_empty();
+    let key_binding_rows: Vec<(usize, KeyAction, Option<String>)> = KeyAction::ALL
+        .into_iter()
+        .enumerate()
+        .map(|(index, action)| {
+            let problem = key_binding_problems
+                .iter()
+                .find(|(problem_action, _)| *problem_action == action)
+                .map(|(_, problem)| problem.clone());
+            (index, action, problem)
+        })
+        .collect();
 
     let status_label = match save_state() {
-        SaveState::Saving => Some("Saving..."),
-        SaveState::Error(_) => Some("Save failed"),
-        SaveState::Saved if !is_dirty => Some("Saved"),
-        _ if is_dirty => Some("Unsaved changes"),
+        SaveState::Saving => Some(strings.t("settings.saving")),
+        SaveState::Error(_) => Some(strings.t("settings.save_failed")),
+        SaveState::Saved if !is_dirty => Some(strings.t("settings.saved")),
+        _ if is_dirty => Some(strings.t("settings.unsaved")),
         _ => None,
     };
 
@@ -226,7 +432,7 @@ pub fn GeneralSettingsView() -> Element {
                 }
                 header { class: "settings-topbar",
                     div { class: "settings-title-group",
-                        h2 { class: "settings-title", "General" }
+                        h2 { class: "settings-title", {strings.t("settings.title")} }
                         if let Some(label) = status_label {
                             p { class: "settings-status", "{label}" }
                         }
@@ -251,7 +457,7 @@ pub fn GeneralSettingsView() -> Element {
                                         path { d: "M12 3a15 15 0 0 0 0 18" }
                                     }
                                 }
-                                span { "Language" }
+                                span { {strings.t("settings.language")} }
                             }
                             div { class: "settings-row__field",
                                 div { class: "settings-select-wrap",
@@ -295,7 +501,7 @@ pub fn GeneralSettingsView() -> Element {
                                         path { d: "M17.7 6.3l1.4-1.4" }
                                     }
                                 }
-                                span { "Theme" }
+                                span { {strings.t("settings.theme")} }
                             }
                             div { class: "settings-row__field settings-row__field--wide",
                                 div { class: "settings-segment",
@@ -330,7 +536,7 @@ pub fn GeneralSettingsView() -> Element {
                                         path { d: "M12 7v5l3 2" }
                                     }
                                 }
-                                span { "Appearance" }
+                                span { {strings.t("settings.appearance")} }
                             }
                             div { class: "settings-row__field settings-row__field--toggle",
                                 button {
@@ -362,7 +568,7 @@ pub fn GeneralSettingsView() -> Element {
                                         path { d: "M3 12h18" }
                                     }
                                 }
-                                span { "Accent color" }
+                                span { {strings.t("settings.accent_color")} }
                             }
                             div { class: "settings-row__field",
                                 div { class: "settings-select-wrap",
@@ -399,7 +605,7 @@ pub fn GeneralSettingsView() -> Element {
                                     }
                                 }
                                 div { class: "settings-row__text",
-                                    span { "Protect from overload" }
+                                    span { {strings.t("settings.protect_from_overload")} }
                                     span { class: "settings-row__sub",
                                         "Delays extra reviews instead of overwhelming you."
                                     }
@@ -419,6 +625,47 @@ pub fn GeneralSettingsView() -> Element {
                                 }
                             }
                         }
+
+                        div { class: "settings-row",
+                            div { class: "settings-row__label settings-row__label--stacked",
+                                span { class: "settings-row__icon",
+                                    svg {
+                                        view_box: "0 0 24 24",
+                                        fill: "none",
+                                        stroke: "currentColor",
+                                        stroke_width: "1.6",
+                                        stroke_linecap: "round",
+                                        stroke_linejoin: "round",
+                                        circle { cx: "12", cy: "12", r: "9" }
+                                        path { d: "M12 7v5l3 2" }
+                                    }
+                                }
+                                div { class: "settings-row__text",
+                                    span { {strings.t("settings.day_starts_at")} }
+                                    span { class: "settings-row__sub",
+                                        "Reviews before this hour count toward the previous day. Applies after a restart."
+                                    }
+                                }
+                            }
+                            div { class: "settings-row__field",
+                                div { class: "settings-select-wrap",
+                                    select {
+                                        class: "settings-select",
+                                        value: "{form_value.day_cutoff_hour}",
+                                        onchange: move |evt| {
+                                            let mut next = form();
+                                            next.day_cutoff_hour = evt.value().parse().unwrap_or(0);
+                                            form.set(next);
+                                            save_state.set(SaveState::Idle);
+                                        },
+                                        for hour in 0..=StudyDay::MAX_CUTOFF_HOUR {
+                                            option { value: "{hour}", "{hour:02}:00" }
+                                        }
+                                    }
+                                    span { class: "settings-select-caret" }
+                                }
+                            }
+                        }
                     }
                 }
 
@@ -544,6 +791,50 @@ pub fn GeneralSettingsView() -> Element {
                                         }
                                     }
                                 }
+                                if model_unpriced {
+                                    p { class: "settings-inline-note", "Pricing unknown" }
+                                }
+                            }
+                        }
+                        if model_unpriced || !form_value.ai_manual_price.trim().is_empty() {
+                            div { class: "settings-row",
+                                div { class: "settings-row__label",
+                                    span { class: "settings-row__icon",
+                                        svg {
+                                            view_box: "0 0 24 24",
+                                            fill: "none",
+                                            stroke: "currentColor",
+                                            stroke_width: "1.6",
+                                            stroke_linecap: "round",
+                                            stroke_linejoin: "round",
+                                            circle { cx: "12", cy: "12", r: "9" }
+                                            path { d: "M12 7v10" }
+                                            path { d: "M15 9.5c-.5-.9-1.6-1.5-3-1.5-1.7 0-3 .9-3 2s1.3 1.6 3 2 3 .9 3 2-1.3 2-3 2c-1.4 0-2.5-.6-3-1.5" }
+                                        }
+                                    }
+                                    span { class: "settings-row__label-text",
+                                        span { "Price per 1k tokens" }
+                                        span {
+                                            class: "settings-help",
+                                            title: "USD per 1k tokens for a model with no built-in price.",
+                                            "?"
+                                        }
+                                    }
+                                }
+                                div { class: "settings-row__field",
+                                    input {
+                                        class: "editor-input settings-input settings-input--short",
+                                        r#type: "text",
+                                        value: "{form_value.ai_manual_price}",
+                                        placeholder: "0.002",
+                                        oninput: move |evt| {
+                                            let mut next = form();
+                                            next.ai_manual_price = evt.value();
+                                            form.set(next);
+                                            save_state.set(SaveState::Idle);
+                                        },
+                                    }
+                                }
                             }
                         }
                         div { class: "settings-row",
@@ -654,6 +945,82 @@ pub fn GeneralSettingsView() -> Element {
                                 }
                             }
                         }
+                        if let Some((today, month)) = spend {
+                            div { class: "settings-row",
+                                div { class: "settings-row__label settings-row__label--stacked",
+                                    span { class: "settings-row__icon",
+                                        svg {
+                                            view_box: "0 0 24 24",
+                                            fill: "none",
+                                            stroke: "currentColor",
+                                            stroke_width: "1.6",
+                                            stroke_linecap: "round",
+                                            stroke_linejoin: "round",
+                                            circle { cx: "12", cy: "12", r: "9" }
+                                            path { d: "M14.5 9.5c-.4-.9-1.4-1.5-2.5-1.5-1.5 0-2.5.8-2.5 2s1 1.7 2.5 2 2.5.8 2.5 2-1 2-2.5 2c-1.1 0-2.1-.6-2.5-1.5" }
+                                            path { d: "M12 6v2" }
+                                            path { d: "M12 16v2" }
+                                        }
+                                    }
+                                    div { class: "settings-row__text",
+                                        span { "Estimated spend" }
+                                        if let Some(label) = unpriced_models_label(&today, &month) {
+                                            span { class: "settings-row__sub", "{label}" }
+                                        }
+                                    }
+                                }
+                                div { class: "settings-row__field settings-spend",
+                                    span { class: "settings-spend__item",
+                                        span { class: "settings-spend__label", "Today" }
+                                        span { class: "settings-spend__value", "{format_spend(&today)}" }
+                                    }
+                                    span { class: "settings-spend__item",
+                                        span { class: "settings-spend__label", "This month" }
+                                        span { class: "settings-spend__value", "{format_spend(&month)}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                section { class: "settings-section",
+                    h3 { class: "settings-section-title", "Keyboard Shortcuts" }
+                    div { class: "settings-card",
+                        for (index, action, problem) in key_binding_rows {
+                            div { class: "settings-row",
+                                div { class: "settings-row__label settings-row__label--stacked",
+                                    div { class: "settings-row__text",
+                                        span { "{action.label()}" }
+                                        if let Some(problem) = &problem {
+                                            span {
+                                                class: "settings-row__sub settings-row__sub--error",
+                                                "{problem}"
+                                            }
+                                        }
+                                    }
+                                }
+                                div { class: "settings-row__field",
+                                    input {
+                                        class: if problem.is_some() {
+                                            SHORTCUT_INPUT_ERROR_CLASS
+                                        } else {
+                                            SHORTCUT_INPUT_CLASS
+                                        },
+                                        r#type: "text",
+                                        "aria-label": "{action.label()} shortcut",
+                                        value: "{form_value.key_bindings[index]}",
+                                        placeholder: "Cmd+Enter",
+                                        oninput: move |evt| {
+                                            let mut next = form();
+                                            next.key_bindings[index] = evt.value();
+                                            form.set(next);
+                                            save_state.set(SaveState::Idle);
+                                        },
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
 
@@ -674,7 +1041,7 @@ pub fn GeneralSettingsView() -> Element {
                                         path { d: "M12 6v6l4 2" }
                                     }
                                 }
-                                span { "Target retention" }
+                                span { {strings.t("settings.target_retention")} }
                             }
                             div { class: "settings-row__field",
                                 input {
@@ -708,7 +1075,7 @@ pub fn GeneralSettingsView() -> Element {
                                         path { d: "M7 15l3-3 4 4 3-6" }
                                     }
                                 }
-                                span { "Enable analytics" }
+                                span { {strings.t("settings.enable_analytics")} }
                             }
                             div { class: "settings-row__field settings-row__field--toggle",
                                 button {
@@ -779,7 +1146,7 @@ pub fn GeneralSettingsView() -> Element {
                             form.set(GeneralSettingsForm::default());
                             save_state.set(SaveState::Idle);
                         },
-                        "Restore defaults"
+                        {strings.t("settings.restore_defaults")}
                     }
                     div { class: "settings-footer-actions",
                         button {
@@ -790,17 +1157,18 @@ pub fn GeneralSettingsView() -> Element {
                                 form.set(initial());
                                 save_state.set(SaveState::Idle);
                             },
-                            "Cancel"
+                            {strings.t("settings.cancel")}
                         }
                         button {
                             class: "button button-primary",
                             r#type: "button",
-                            disabled: !is_dirty || save_state() == SaveState::Saving,
+                            disabled: !can_save || save_state() == SaveState::Saving,
                             onclick: move |_| {
                                 let snapshot = form();
                                 let mut initial = initial;
                                 let mut form = form;
                                 let mut save_state = save_state;
+                                let mut persisted = persisted;
                                 let app_settings = app_settings.clone();
                                 spawn(async move {
                                     save_state.set(SaveState::Saving);
@@ -816,24 +1184,50 @@ pub fn GeneralSettingsView() -> Element {
                                         save_state.set(SaveState::Error(ViewError::Unknown));
                                         return;
                                     };
-                                    let draft = AppSettingsDraft {
-                                        api_key: to_optional(&snapshot.ai_api_key),
-                                        api_model: to_optional(&snapshot.ai_model),
-                                        api_fallback_model: to_optional(
-                                            &snapshot.ai_fallback_model,
-                                        ),
-                                        ai_system_prompt: to_optional(
-                                            &snapshot.ai_system_prompt,
-                                        ),
-                                        ai_daily_request_cap,
-                                        ai_cooldown_secs,
+                                    let Ok(ai_manual_price) =
+                                        parse_optional_usd_micro(&snapshot.ai_manual_price)
+                                    else {
+                                        save_state.set(SaveState::Error(ViewError::Unknown));
+                                        return;
                                     };
+                                    let Ok(key_bindings) =
+                                        check_key_bindings(&snapshot.key_bindings)
+                                    else {
+                                        save_state.set(SaveState::Error(ViewError::Unknown));
+                                        return;
+                                    };
+                                    let mut draft = persisted.peek().to_draft();
+                                    draft.api_key = to_optional(&snapshot.ai_api_key);
+                                    draft.api_model = to_optional(&snapshot.ai_model);
+                                    draft.api_fallback_model =
+                                        to_optional(&snapshot.ai_fallback_model);
+                                    draft.ai_system_prompt =
+                                        to_optional(&snapshot.ai_system_prompt);
+                                    draft.ai_daily_request_cap = ai_daily_request_cap;
+                                    draft.ai_cooldown_secs = ai_cooldown_secs;
+                                    draft.ai_manual_price_micro_usd_per_1k = ai_manual_price;
+                                    draft.theme = Some(snapshot.theme.to_model());
+                                    draft.accent_color = Some(snapshot.accent_color.to_model());
+                                    draft.language = Some(snapshot.language.to_model());
+                                    draft.analytics_enabled = Some(snapshot.analytics_enabled);
+                                    draft.day_cutoff_hour = Some(snapshot.day_cutoff_hour);
+                                    draft.key_bindings = Some(key_bindings);
                                     match app_settings.save(draft).await {
                                         Ok(settings) => {
                                             let mut next = snapshot;
                                             apply_app_settings(&mut next, &settings);
                                             form.set(next.clone());
                                             initial.set(next);
+                                            if let Some(appearance) = appearance {
+                                                appearance.apply(&settings);
+                                            }
+                                            if let Some(keymap) = keymap {
+                                                keymap.apply(&settings);
+                                            }
+                                            if let Some(locale) = locale {
+                                                locale.apply(&settings);
+                                            }
+                                            persisted.set(settings);
                                             save_state.set(SaveState::Saved);
                                         }
                                         Err(_) => {
@@ -842,7 +1236,7 @@ pub fn GeneralSettingsView() -> Element {
                                     }
                                 });
                             },
-                            "Save"
+                            {strings.t("settings.save")}
                         }
                     }
                 }
@@ -1,10 +1,12 @@
+use dioxus::document::eval;
 use dioxus::prelude::*;
 use dioxus_router::use_navigator;
 use learn_core::model::DeckId;
 
 use crate::context::AppContext;
 use crate::routes::Route;
-use crate::views::{ViewError, ViewState, view_state_from_resource};
+use crate::shortcuts::{self, history};
+use crate::views::{ShortcutsOverlay, ViewError, ViewState, view_state_from_resource};
 use crate::vm::{SessionSummaryCardVm, map_session_summary_cards};
 
 #[derive(Clone, Debug, PartialEq)]
@@ -31,6 +33,8 @@ pub fn HistoryView() -> Element {
     let mut reset_target = use_signal(|| None::<u64>);
     let mut reset_state = use_signal(|| ResetState::Idle);
     let mut show_mistakes_only = use_signal(|| false);
+    let mut search_focused = use_signal(|| false);
+    let mut show_shortcuts = use_signal(|| false);
 
     let resource = use_resource(move || {
         let summaries = summaries.clone();
@@ -39,7 +43,7 @@ pub fn HistoryView() -> Element {
                 .list_recent_summaries(deck_id, 7, 10)
                 .await
                 .map_err(|_| ViewError::Unknown)?;
-            let cards = map_session_summary_cards(&items);
+            let cards = map_session_summary_cards(&items, summaries.now());
             Ok(HistoryData { cards })
         }
     });
@@ -81,8 +85,40 @@ pub fn HistoryView() -> Element {
         .map_or_else(|| "Due: --".to_string(), |due| format!("Due: {due}"));
     let query = search().trim().to_lowercase();
 
+    let on_key = move |evt: KeyboardEvent| {
+        if history::CLOSE.matches(&evt.data) {
+            // Close the topmost layer first: the legend, then the reset dialog, then a menu.
+            if show_shortcuts() {
+                show_shortcuts.set(false);
+            } else if reset_target().is_some() {
+                reset_target.set(None);
+                reset_state.set(ResetState::Idle);
+            } else if open_menu().is_some() {
+                open_menu.set(None);
+            } else {
+                return;
+            }
+            evt.prevent_default();
+            return;
+        }
+        // Letters typed into the search box are part of the query.
+        if search_focused() || show_shortcuts() {
+            return;
+        }
+        if shortcuts::SHOW_SHORTCUTS.matches(&evt.data) {
+            evt.prevent_default();
+            show_shortcuts.set(true);
+        } else if history::FOCUS_SEARCH.matches(&evt.data) {
+            evt.prevent_default();
+            let _ = eval("document.getElementById('history-search-input')?.focus();");
+        } else if history::TOGGLE_MISTAKES.matches(&evt.data) {
+            evt.prevent_default();
+            show_mistakes_only.set(!show_mistakes_only());
+        }
+    };
+
     rsx! {
-        div { class: "page history-page",
+        div { class: "page history-page", tabindex: "0", onkeydown: on_key,
             header { class: "view-header",
                 h2 { class: "view-title", "History" }
                 p { class: "view-subtitle", "Recent sessions from the last week." }
@@ -106,6 +142,7 @@ pub fn HistoryView() -> Element {
                             query.is_empty()
                                 || matches_deck
                                 || card.completed_at_str.to_lowercase().contains(&query)
+                                || card.completed_ago.contains(&query)
                         })
                         .filter(|card| !show_mistakes_only() || card.again > 0)
                         .cloned()
@@ -144,10 +181,13 @@ pub fn HistoryView() -> Element {
                                 }
                                 input {
                                     class: "practice-search-input",
+                                    id: "history-search-input",
                                     r#type: "text",
                                     placeholder: "Search history...",
                                     value: "{search()}",
                                     oninput: move |evt| search.set(evt.value()),
+                                    onfocus: move |_| search_focused.set(true),
+                                    onblur: move |_| search_focused.set(false),
                                 }
                                 if !search().is_empty() {
                                     button {
@@ -267,6 +307,12 @@ pub fn HistoryView() -> Element {
                     }
                 },
             }
+            if show_shortcuts() {
+                ShortcutsOverlay {
+                    shortcuts: history::ALL.to_vec(),
+                    on_close: move |()| show_shortcuts.set(false),
+                }
+            }
         }
     }
 }
@@ -292,6 +338,14 @@ fn SummaryCard(
                     h3 { class: "history-item__title", "{deck_label}" }
                     div { class: "history-item__meta",
                         span { class: "history-item__count", "{card.cards_label}" }
+                        if card.is_cram {
+                            span { class: "history-item__dot", "•" }
+                            span { class: "history-item__cram", "Cram" }
+                        }
+                        if card.is_ahead {
+                            span { class: "history-item__dot", "•" }
+                            span { class: "history-item__cram", "Ahead" }
+                        }
                         span { class: "history-item__dot", "•" }
                         if card.total == 0 {
                             span { class: "history-item__breakdown", "No reviews" }
@@ -305,7 +359,11 @@ fn SummaryCard(
                 }
             }
             div { class: "history-item__actions",
-                span { class: "history-item__date", "{card.completed_at_str}" }
+                span {
+                    class: "history-item__date",
+                    title: "{card.completed_at_str}",
+                    "{card.completed_ago}"
+                }
                 span { class: "history-item__due", "{due_label}" }
                 if has_mistakes {
                     button {
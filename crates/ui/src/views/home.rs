@@ -1,26 +1,54 @@
 use chrono::{DateTime, Utc};
+use dioxus::document::eval;
 use dioxus::prelude::*;
 use dioxus_router::Link;
+use services::DeckDueCounts;
 
+use crate::app::DueCountsRefresh;
 use crate::context::AppContext;
 use crate::routes::Route;
 use crate::views::{ViewError, ViewState, view_state_from_resource};
-use crate::vm::format_relative_datetime;
-use learn_core::model::DeckId;
+use crate::vm::{
+    card_counts_label, deck_color_class, deck_icon_glyph, format_relative_datetime,
+};
+use learn_core::model::{Deck, DeckColor, DeckIcon, DeckId};
 use std::collections::HashMap;
+use std::time::Duration;
+
+/// How often the due badge recomputes while home stays open, so it follows the day along.
+const DUE_BADGE_REFRESH: Duration = Duration::from_secs(5 * 60);
+
+/// Sends a message each time the window regains focus or becomes visible again.
+const WINDOW_FOCUS_SCRIPT: &str = r#"
+    const notify = () => dioxus.send(true);
+    window.addEventListener("focus", notify);
+    document.addEventListener("visibilitychange", () => {
+        if (document.visibilityState === "visible") notify();
+    });
+"#;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct HomePracticeNow {
     deck_id: DeckId,
     deck_name: String,
-    due: u32,
-    new: u32,
+    /// Cards the next session would serve, from `SessionLoopService::peek_plan`.
+    reviews: usize,
+    new: usize,
+    learning: usize,
+    /// Whether an unfinished session from before the app closed can be picked up.
+    has_saved_session: bool,
+    /// The deck's size and phase breakdown, such as "120 cards · 30 new · 5 suspended".
+    cards_label: String,
+    /// Share of the deck's cards past the mature interval, as a whole percentage.
+    mature_pct: u32,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct HomeRecentSession {
     deck_id: DeckId,
     deck_name: String,
+    color: Option<DeckColor>,
+    icon: Option<DeckIcon>,
     completed_at: DateTime<Utc>,
     meta_label: String,
     again_pct: u32,
@@ -33,6 +61,8 @@ struct HomeRecentSession {
 struct HomeUpcomingDeck {
     deck_id: DeckId,
     deck_name: String,
+    color: Option<DeckColor>,
+    icon: Option<DeckIcon>,
     due: u32,
     new: u32,
 }
@@ -51,11 +81,39 @@ pub fn HomeView() -> Element {
     let deck_id = ctx.current_deck_id();
     let summaries = ctx.session_summaries();
     let deck_service = ctx.deck_service();
+    let session_loop = ctx.session_loop();
     let card_service = ctx.card_service();
+    let due_counts_refresh = try_use_context::<DueCountsRefresh>();
+
+    // Recompute the badge on a coarse timer and whenever the window comes back into view.
+    let mut badge_tick = use_signal(|| 0_u64);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(DUE_BADGE_REFRESH).await;
+            badge_tick += 1;
+        }
+    });
+    use_future(move || async move {
+        let mut watcher = eval(WINDOW_FOCUS_SCRIPT);
+        while watcher.recv::<bool>().await.is_ok() {
+            badge_tick += 1;
+        }
+    });
+    let due_now = {
+        let deck_service = deck_service.clone();
+        use_resource(move || {
+            let deck_service = deck_service.clone();
+            badge_tick();
+            due_counts_refresh.map(DueCountsRefresh::generation);
+            async move { deck_service.due_now_counts().await.ok() }
+        })
+    };
+    let due_badge = due_now.value().read().flatten().map(due_badge_label);
 
     let resource = use_resource(move || {
         let summaries = summaries.clone();
         let deck_service = deck_service.clone();
+        let session_loop = session_loop.clone();
         let card_service = card_service.clone();
 
         async move {
@@ -66,19 +124,19 @@ pub fn HomeView() -> Element {
                 .map_err(|_| ViewError::Unknown)?
                 .ok_or(ViewError::Unknown)?;
             let decks = deck_service
-                .list_decks(8)
+                .list_decks(8, false)
                 .await
                 .map_err(|_| ViewError::Unknown)?;
 
             let deck_ids: Vec<DeckId> = decks.iter().map(learn_core::model::Deck::id).collect();
-            let stats_rows = card_service
-                .list_deck_practice_stats(&deck_ids)
+            let mut counted = decks.clone();
+            if !deck_ids.contains(&deck_id) {
+                counted.push(current_deck.clone());
+            }
+            let counts_by_deck = deck_service
+                .deck_counts(&counted)
                 .await
                 .map_err(|_| ViewError::Unknown)?;
-            let stats_by_deck: HashMap<DeckId, services::DeckPracticeStats> = stats_rows
-                .into_iter()
-                .map(|row| (row.deck_id, row.stats))
-                .collect();
             let summary_rows = summaries
                 .list_latest_summaries_by_deck(&deck_ids)
                 .await
@@ -87,10 +145,8 @@ pub fn HomeView() -> Element {
                 .into_iter()
                 .map(|row| (row.deck_id, row))
                 .collect();
-            let deck_name_by_id: HashMap<DeckId, String> = decks
-                .iter()
-                .map(|deck| (deck.id(), deck.name().to_string()))
-                .collect();
+            let deck_by_id: HashMap<DeckId, &Deck> =
+                decks.iter().map(|deck| (deck.id(), deck)).collect();
 
             let mut recent_sessions = Vec::new();
             for item in summary_by_deck.values() {
@@ -103,13 +159,13 @@ pub fn HomeView() -> Element {
                     }
                 };
                 let date_label = format_relative_datetime(&item.completed_at, &now);
-                let deck_name = deck_name_by_id
-                    .get(&item.deck_id)
-                    .cloned()
-                    .unwrap_or_else(|| "Deck".to_string());
+                let deck = deck_by_id.get(&item.deck_id);
+                let deck_name = deck.map_or_else(|| "Deck".to_string(), |deck| deck.name().into());
                 recent_sessions.push(HomeRecentSession {
                     deck_id: item.deck_id,
                     deck_name,
+                    color: deck.and_then(|deck| deck.settings().color()),
+                    icon: deck.and_then(|deck| deck.settings().icon()),
                     completed_at: item.completed_at,
                     meta_label: format!("{date_label} \u{00b7} {total} Cards"),
                     again_pct: pct(item.again),
@@ -123,38 +179,48 @@ pub fn HomeView() -> Element {
 
             let mut upcoming_decks = Vec::new();
             for deck in &decks {
-                let stats = stats_by_deck.get(&deck.id()).copied().unwrap_or(
-                    services::DeckPracticeStats {
-                        total: 0,
-                        due: 0,
-                        new: 0,
-                    },
-                );
-                if stats.due > 0 || stats.new > 0 {
+                let counts = counts_by_deck.get(&deck.id()).copied().unwrap_or_default();
+                let due = counts.reviews();
+                if due > 0 || counts.new > 0 {
                     upcoming_decks.push(HomeUpcomingDeck {
                         deck_id: deck.id(),
                         deck_name: deck.name().to_string(),
-                        due: stats.due,
-                        new: stats.new,
+                        color: deck.settings().color(),
+                        icon: deck.settings().icon(),
+                        due,
+                        new: counts.new,
                     });
                 }
             }
             upcoming_decks.sort_by(|a, b| b.due.cmp(&a.due).then_with(|| b.new.cmp(&a.new)));
             upcoming_decks.truncate(3);
 
-            let current_stats = stats_by_deck.get(&deck_id).copied().unwrap_or(
-                services::DeckPracticeStats {
-                    total: 0,
-                    due: 0,
-                    new: 0,
-                },
-            );
+            let plan = session_loop
+                .peek_plan(deck_id)
+                .await
+                .map_err(|_| ViewError::Unknown)?;
+            let has_saved_session = session_loop
+                .has_saved_session(deck_id)
+                .await
+                .map_err(|_| ViewError::Unknown)?;
+            let card_counts = card_service
+                .count_cards(deck_id)
+                .await
+                .map_err(|_| ViewError::Unknown)?;
+            let maturity = deck_service
+                .maturity(deck_id)
+                .await
+                .map_err(|_| ViewError::Unknown)?;
             Ok::<_, ViewError>(HomeData {
                 practice_now: HomePracticeNow {
                     deck_id,
                     deck_name: current_deck.name().to_string(),
-                    due: current_stats.due,
-                    new: current_stats.new,
+                    reviews: plan.reviews,
+                    new: plan.new,
+                    learning: plan.learning,
+                    has_saved_session,
+                    cards_label: card_counts_label(&card_counts),
+                    mature_pct: maturity.mature_percent(),
                 },
                 recent_sessions,
                 upcoming_decks,
@@ -177,6 +243,12 @@ pub fn HomeView() -> Element {
                     section { class: "home-hero",
                         h1 { class: "home-hero__title", "Welcome back!" }
                         p { class: "home-hero__subtitle", "Ready to start practicing?" }
+                        if let Some((label, clear)) = due_badge.as_ref() {
+                            span {
+                                class: if *clear { "home-due-badge home-due-badge--clear" } else { "home-due-badge" },
+                                "{label}"
+                            }
+                        }
                     }
 
                     section { class: "home-top-cards",
@@ -197,16 +269,32 @@ pub fn HomeView() -> Element {
                             }
                             h3 { class: "home-card__title", "Practice Now" }
                             p { class: "home-card__meta",
-                                "{data.practice_now.due} Due"
+                                "{data.practice_now.reviews} Reviews"
                                 span { class: "home-card__dot", "\u{00b7}" }
                                 "{data.practice_now.new} New"
                                 span { class: "home-card__dot", "\u{00b7}" }
+                                "{data.practice_now.learning} Learning"
+                                span { class: "home-card__dot", "\u{00b7}" }
                                 "{data.practice_now.deck_name}"
                             }
-                            Link {
-                                class: "btn btn-primary home-card__action",
-                                to: Route::Session { deck_id: data.practice_now.deck_id.value() },
-                                "Start"
+                            p { class: "home-card__meta",
+                                "{data.practice_now.cards_label}"
+                                span { class: "home-card__dot", "\u{00b7}" }
+                                "{data.practice_now.mature_pct}% mature"
+                            }
+                            div { class: "home-card__actions",
+                                if data.practice_now.has_saved_session {
+                                    Link {
+                                        class: "btn btn-primary home-card__action",
+                                        to: Route::SessionResume { deck_id: data.practice_now.deck_id.value() },
+                                        "Resume previous session"
+                                    }
+                                }
+                                Link {
+                                    class: if data.practice_now.has_saved_session { "btn btn-secondary home-card__action" } else { "btn btn-primary home-card__action" },
+                                    to: Route::Session { deck_id: data.practice_now.deck_id.value() },
+                                    "Start"
+                                }
                             }
                         }
                         div { class: "home-card",
@@ -240,8 +328,8 @@ pub fn HomeView() -> Element {
                                 for item in data.recent_sessions {
                                     div { class: "home-session-row",
                                         div { class: "home-session-row__left",
-                                            div { class: "home-session-avatar",
-                                                "{deck_initial(&item.deck_name)}"
+                                            div { class: "home-session-avatar {deck_color_class(item.color)}",
+                                                "{deck_avatar(&item.deck_name, item.icon)}"
                                             }
                                             div { class: "home-session-text",
                                                 h5 { class: "home-session-title", "{item.deck_name}" }
@@ -283,8 +371,13 @@ pub fn HomeView() -> Element {
                                 } else {
                                     for deck in data.upcoming_decks {
                                         div { class: "home-upcoming-row",
-                                            div { class: "home-session-avatar home-session-avatar--muted",
-                                                "{deck_initial(&deck.deck_name)}"
+                                            div {
+                                                class: if deck.color.is_some() {
+                                                    "home-session-avatar {deck_color_class(deck.color)}"
+                                                } else {
+                                                    "home-session-avatar home-session-avatar--muted"
+                                                },
+                                                "{deck_avatar(&deck.deck_name, deck.icon)}"
                                             }
                                             div { class: "home-session-text",
                                                 h5 { class: "home-session-title", "{deck.deck_name}" }
@@ -328,6 +421,19 @@ pub fn HomeView() -> Element {
     }
 }
 
-fn deck_initial(name: &str) -> String {
+/// Badge text for the collection-wide counts, and whether nothing is waiting.
+fn due_badge_label(counts: DeckDueCounts) -> (String, bool) {
+    let reviews = counts.reviews();
+    if reviews == 0 && counts.new == 0 {
+        return ("All caught up".to_string(), true);
+    }
+    (format!("{reviews} due now \u{00b7} {} new", counts.new), false)
+}
+
+/// The deck's icon, or the first letter of its name when it has none.
+fn deck_avatar(name: &str, icon: Option<DeckIcon>) -> String {
+    if let Some(icon) = icon {
+        return deck_icon_glyph(icon).to_string();
+    }
     name.chars().next().map_or_else(|| "?".to_string(), |ch| ch.to_string())
 }
@@ -0,0 +1,30 @@
+use dioxus::prelude::*;
+use learn_core::model::MediaId;
+
+use crate::context::AppContext;
+
+/// Render a stored card image; nothing is shown while it loads, and a placeholder if it is
+/// no longer stored.
+#[component]
+pub(crate) fn MediaImage(media_id: MediaId, class: &'static str) -> Element {
+    let ctx = use_context::<AppContext>();
+    let media_service = ctx.media_service();
+
+    let resource = use_resource(use_reactive!(|media_id| {
+        let media_service = media_service.clone();
+        async move { media_service.image_data_url(media_id).await.ok() }
+    }));
+    let loaded = resource.read().clone().flatten();
+
+    rsx! {
+        match loaded {
+            Some(Some(url)) => rsx! {
+                img { class, src: "{url}", alt: "" }
+            },
+            Some(None) => rsx! {
+                span { class: "card-media-missing", "Media unavailable" }
+            },
+            None => rsx! {},
+        }
+    }
+}
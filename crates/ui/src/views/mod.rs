@@ -1,18 +1,26 @@
+mod deck_mark;
 pub(crate) mod editor;
 mod history;
 mod home;
+mod media_image;
 mod practice;
+mod search;
 mod session;
+mod shortcuts_overlay;
 mod general_settings;
 mod settings;
 mod summary;
 mod state;
 
+pub(crate) use deck_mark::DeckMark;
 pub use editor::EditorView;
 pub use history::HistoryView;
 pub use home::HomeView;
+pub(crate) use media_image::MediaImage;
 pub use practice::PracticeView;
+pub use search::SearchView;
 pub use session::SessionView;
+pub(crate) use shortcuts_overlay::{ShortcutsOverlay, open_shortcuts_legend};
 pub use general_settings::GeneralSettingsView;
 pub use settings::SettingsView;
 pub use summary::SummaryView;
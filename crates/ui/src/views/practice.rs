@@ -1,6 +1,7 @@
 use dioxus::prelude::*;
 use dioxus_router::use_navigator;
 use learn_core::model::DeckId;
+use services::SessionLoopService;
 
 use crate::context::AppContext;
 use crate::routes::Route;
@@ -27,6 +28,7 @@ pub fn PracticeView(deck_id: Option<u64>) -> Element {
     let deck_service = ctx.deck_service();
     let card_service = ctx.card_service();
     let deck_scope_id = deck_id.map(DeckId::new);
+    let ahead_label = format!("Study Ahead (Next {} Days)", SessionLoopService::MAX_AHEAD_DAYS);
     let mut search = use_signal(String::new);
     let mut open_menu = use_signal(|| None::<u64>);
     let mut reset_target = use_signal(|| None::<u64>);
@@ -49,7 +51,7 @@ pub fn PracticeView(deck_id: Option<u64>) -> Element {
                 }
             } else {
                 deck_service
-                    .list_decks(64)
+                    .list_decks(64, false)
                     .await
                     .map_err(|_| ViewError::Unknown)?
             };
@@ -227,6 +229,24 @@ pub fn PracticeView(deck_id: Option<u64>) -> Element {
                                                 },
                                                 "Re-practice Mistakes"
                                             }
+                                            button {
+                                                class: "practice-action-item",
+                                                r#type: "button",
+                                                onclick: move |_| {
+                                                    open_menu.set(None);
+                                                    let _ = nav.push(Route::SessionCram { deck_id });
+                                                },
+                                                "Cram (Keep Schedule)"
+                                            }
+                                            button {
+                                                class: "practice-action-item",
+                                                r#type: "button",
+                                                onclick: move |_| {
+                                                    open_menu.set(None);
+                                                    let _ = nav.push(Route::SessionAhead { deck_id });
+                                                },
+                                                "{ahead_label}"
+                                            }
                                             button {
                                                 class: "practice-action-item",
                                                 r#type: "button",
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use dioxus::prelude::*;
+use dioxus_router::{Link, use_navigator};
+
+use crate::context::AppContext;
+use crate::routes::Route;
+use crate::views::{ViewError, ViewState, view_state_from_resource};
+use crate::vm::{DeckSearchGroupVm, map_deck_search_groups};
+
+/// Most cards listed for one search, across all decks.
+const SEARCH_LIMIT: u32 = 50;
+
+#[component]
+pub fn SearchView(query: String) -> Element {
+    let ctx = use_context::<AppContext>();
+    let navigator = use_navigator();
+    let card_service = ctx.card_service();
+    let deck_service = ctx.deck_service();
+    let mut search = use_signal(|| query.clone());
+
+    let resource = use_resource(move || {
+        let card_service = card_service.clone();
+        let deck_service = deck_service.clone();
+        let query = search.read().trim().to_string();
+        async move {
+            let groups = card_service
+                .search_all_decks(&query, SEARCH_LIMIT)
+                .await
+                .map_err(|_| ViewError::Unknown)?;
+            let mut schedulers = HashMap::new();
+            for group in &groups {
+                let scheduler = deck_service
+                    .scheduler(group.deck_id)
+                    .await
+                    .map_err(|_| ViewError::Unknown)?;
+                schedulers.insert(group.deck_id, scheduler);
+            }
+            Ok::<_, ViewError>(map_deck_search_groups(&groups, &schedulers, card_service.now()))
+        }
+    });
+
+    let mut set_search = move |value: String| {
+        search.set(value.clone());
+        // Keep the query in the route so coming back from the editor shows the same results.
+        navigator.replace(Route::Search { query: value });
+    };
+    let state = view_state_from_resource(&resource);
+    let query = search().trim().to_string();
+
+    rsx! {
+        div { class: "page search-page",
+            header { class: "view-header",
+                h2 { class: "view-title", "Search" }
+                p { class: "view-subtitle", "Find a card in any deck." }
+            }
+            div { class: "view-divider" }
+            div { class: "practice-search",
+                span { class: "practice-search-icon", aria_hidden: "true",
+                    svg {
+                        view_box: "0 0 24 24",
+                        stroke: "currentColor",
+                        stroke_width: "1.8",
+                        fill: "none",
+                        stroke_linecap: "round",
+                        stroke_linejoin: "round",
+                        circle { cx: "11", cy: "11", r: "7" }
+                        path { d: "M20 20l-3.5-3.5" }
+                    }
+                }
+                input {
+                    class: "practice-search-input",
+                    r#type: "text",
+                    placeholder: "Search all decks...",
+                    aria_label: "Search all decks",
+                    value: "{search()}",
+                    oninput: move |evt| set_search(evt.value()),
+                }
+                if !search().is_empty() {
+                    button {
+                        class: "practice-search-clear",
+                        r#type: "button",
+                        aria_label: "Clear search",
+                        onclick: move |_| set_search(String::new()),
+                        span { class: "practice-search-clear-icon", "×" }
+                    }
+                }
+            }
+
+            if query.is_empty() {
+                p { class: "search-empty", "Type a word to search every deck." }
+            } else {
+                match state {
+                    ViewState::Idle => rsx! {
+                        p { "Idle" }
+                    },
+                    ViewState::Loading => rsx! {
+                        p { "Loading..." }
+                    },
+                    ViewState::Error(err) => rsx! {
+                        p { "{err.message()}" }
+                        button {
+                            class: "btn btn-secondary",
+                            r#type: "button",
+                            onclick: move |_| {
+                                let mut resource = resource;
+                                resource.restart();
+                            },
+                            "Retry"
+                        }
+                    },
+                    ViewState::Ready(groups) => rsx! {
+                        if groups.is_empty() {
+                            p { class: "search-empty", "No cards match that search." }
+                        } else {
+                            for group in groups {
+                                SearchGroup {
+                                    key: "{group.deck_id.value()}",
+                                    group,
+                                    query: query.clone(),
+                                }
+                            }
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SearchGroup(group: DeckSearchGroupVm, query: String) -> Element {
+    let count = group.items.len();
+    let count_label = if count == 1 { "1 card".to_string() } else { format!("{count} cards") };
+    let to = Route::EditorDeck {
+        deck_id: group.deck_id.value(),
+        search: query,
+    };
+
+    rsx! {
+        section { class: "search-group", aria_label: "{group.deck_name}",
+            header { class: "search-group__header",
+                h3 { class: "search-group__title", "{group.deck_name}" }
+                span { class: "search-group__count", "{count_label}" }
+                Link { class: "search-group__open", to: to.clone(), "Open in editor" }
+            }
+            ul { class: "search-group__list",
+                for item in group.items {
+                    li { key: "{item.id.value()}", class: "search-result",
+                        Link { class: "search-result__link", to: to.clone(),
+                            span { class: "search-result__prompt", "{item.prompt_preview}" }
+                            span { class: "search-result__answer", "{item.answer_preview}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
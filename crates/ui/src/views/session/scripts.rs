@@ -14,8 +14,22 @@ pub(super) fn session_timer_script(
                         key: null,
                         seconds: 0,
                         autoFired: false,
+                        autoCanceled: false,
                         id: null,
+                        refresh: null,
                     }});
+                    if (!state.listening) {{
+                        // Any interaction cancels the auto-reveal countdown for this card.
+                        const cancel = () => {{
+                            if (state.autoFired || state.autoCanceled) return;
+                            state.autoCanceled = true;
+                            if (state.refresh) state.refresh();
+                        }};
+                        document.addEventListener("keydown", cancel, true);
+                        document.addEventListener("pointerdown", cancel, true);
+                        document.addEventListener("wheel", cancel, {{ capture: true, passive: true }});
+                        state.listening = true;
+                    }}
                     if (!root) {{
                         if (state.id) {{
                             clearInterval(state.id);
@@ -24,6 +38,8 @@ pub(super) fn session_timer_script(
                         state.key = null;
                         state.seconds = 0;
                         state.autoFired = false;
+                        state.autoCanceled = false;
+                        state.refresh = null;
                         return;
                     }}
                     const key = {timer_key:?};
@@ -35,10 +51,13 @@ pub(super) fn session_timer_script(
                     const autoSecs = {auto_secs};
                     const label = document.getElementById("session-timer-label");
                     const reminder = document.getElementById("session-soft-reminder");
+                    const countdown = document.getElementById("session-auto-reveal");
+                    const countdownBar = document.getElementById("session-auto-reveal-bar");
                     if (state.key !== key) {{
                         state.key = key;
                         state.seconds = 0;
                         state.autoFired = false;
+                        state.autoCanceled = false;
                     }}
                     const updateUi = () => {{
                         if (label) {{
@@ -55,7 +74,18 @@ pub(super) fn session_timer_script(
                             const show = softReminder && active && state.seconds >= softSecs;
                             reminder.hidden = !show;
                         }}
+                        if (countdown) {{
+                            const pending = autoAdvance && active && !state.autoFired
+                                && !state.autoCanceled;
+                            countdown.hidden = !pending;
+                            const percent = autoSecs > 0
+                                ? Math.min(100, Math.round(state.seconds * 100 / autoSecs))
+                                : 100;
+                            countdown.setAttribute("aria-valuenow", String(percent));
+                            if (countdownBar) countdownBar.style.width = percent + "%";
+                        }}
                     }};
+                    state.refresh = updateUi;
                     updateUi();
                     if (!active) {{
                         if (state.id) {{
@@ -72,8 +102,9 @@ pub(super) fn session_timer_script(
                                 return;
                             }}
                             state.seconds += 1;
-                            updateUi();
-                            if (autoAdvance && !state.autoFired && state.seconds >= autoSecs) {{
+                            if (state.refresh) state.refresh();
+                            if (autoAdvance && !state.autoFired && !state.autoCanceled
+                                && state.seconds >= autoSecs) {{
                                 state.autoFired = true;
                                 const btn = document.getElementById("session-reveal");
                                 if (btn) btn.click();
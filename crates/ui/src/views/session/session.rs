@@ -1,20 +1,29 @@
+use dioxus::core::Task;
 use dioxus::document::eval;
 use dioxus::prelude::*;
 use dioxus_router::use_navigator;
-use keyboard_types::{Code, Key, Modifiers};
+use keyboard_types::{Key, Modifiers};
 use services::SessionReviewPreview;
 
-use learn_core::model::{DeckId, ReviewGrade, TagName};
+use learn_core::model::{
+    AnswerButtons, AppSettings, CardId, CardSide, CardTemplate, DeckId, KeyBindings, MediaId,
+    MediaUri, ReviewGrade, TagName,
+};
 
+use crate::app::{DueCountsRefresh, use_key_bindings, use_strings};
 use crate::context::AppContext;
 use crate::routes::Route;
-use crate::views::{ViewError, ViewState, view_state_from_resource};
+use crate::shortcuts::{self, Shortcut};
+use crate::views::{ShortcutsOverlay, ViewError, ViewState, view_state_from_resource};
 use crate::vm::{
-    SessionIntent, SessionOutcome, SessionPhase, SessionStartMode, SessionVm, sanitize_html,
-    start_session,
+    AnswerDiffSpan, CardMedia, SessionIntent, SessionOutcome, SessionPhase, SessionStartMode,
+    SessionVm, StartedSession, TemplateFields, flag_label, format_interval, nothing_due_label,
+    render_card_content, render_card_template, render_card_text, start_session,
 };
 use super::scripts::session_timer_script;
 
+use std::time::Duration;
+
 #[cfg(test)]
 use std::cell::RefCell;
 #[cfg(test)]
@@ -24,6 +33,9 @@ use std::rc::Rc;
 enum LastAction {
     StartSession,
     Answer(ReviewGrade),
+    Bury,
+    Skip,
+    CycleFlag,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -41,6 +53,41 @@ struct TimerSettings {
     auto_reveal_secs: u32,
 }
 
+impl TimerSettings {
+    fn enabled(self) -> bool {
+        self.show_timer || self.soft_time_reminder || self.auto_advance_cards
+    }
+}
+
+/// Audio playback preferences for a session, taken from app settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct AudioSettings {
+    autoplay_audio: bool,
+    replay_audio_after_answer: bool,
+    audio_delay_ms: u64,
+}
+
+impl AudioSettings {
+    fn from_app_settings(settings: &AppSettings) -> Self {
+        Self {
+            autoplay_audio: settings.autoplay_audio(),
+            replay_audio_after_answer: settings.replay_audio_after_answer(),
+            audio_delay_ms: u64::from(settings.audio_delay_ms()),
+        }
+    }
+
+    /// Pick the clip to play when the session reaches `phase` on the current card.
+    fn source_for(self, vm: &SessionVm, phase: SessionPhase) -> Option<MediaUri> {
+        match phase {
+            SessionPhase::Prompt if self.autoplay_audio => vm.prompt_audio(),
+            SessionPhase::Answer if self.replay_audio_after_answer => {
+                vm.answer_audio().or_else(|| vm.prompt_audio())
+            }
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 struct PracticeCounts {
     total: u32,
@@ -63,6 +110,50 @@ impl PracticeCounts {
     }
 }
 
+/// Keyboard bindings for reviewing without the mouse.
+///
+/// Grade keys follow the deck's grade buttons from left to right, so a two-button deck
+/// answers with the first two grade bindings, 1 and 2 unless remapped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SessionKeyMap {
+    reveal: Shortcut,
+    grades: Vec<(Shortcut, ReviewGrade)>,
+    legend: Vec<Shortcut>,
+}
+
+impl SessionKeyMap {
+    fn for_buttons(bindings: &KeyBindings, buttons: AnswerButtons) -> Self {
+        Self {
+            reveal: shortcuts::session::reveal(bindings),
+            grades: buttons
+                .grades()
+                .iter()
+                .enumerate()
+                .filter_map(|(index, grade)| {
+                    shortcuts::session::grade(bindings, index, *grade)
+                        .map(|shortcut| (shortcut, *grade))
+                })
+                .collect(),
+            legend: shortcuts::session::all(bindings, buttons.grades()),
+        }
+    }
+
+    fn is_reveal(&self, data: &KeyboardData) -> bool {
+        self.reveal.matches(data)
+    }
+
+    fn grade_for(&self, data: &KeyboardData) -> Option<ReviewGrade> {
+        self.grades
+            .iter()
+            .find(|(shortcut, _)| shortcut.matches(data))
+            .map(|(_, grade)| *grade)
+    }
+
+    fn legend(&self) -> Vec<Shortcut> {
+        self.legend.clone()
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct CompletionFlags {
     can_practice_again: bool,
@@ -121,7 +212,7 @@ fn focus_cycle_ids_for_phase(
         };
     }
     match phase {
-        Some(SessionPhase::Prompt) => &["session-quit", "session-reveal"],
+        Some(SessionPhase::Prompt) => &["session-quit", "session-typed-answer", "session-reveal"],
         Some(SessionPhase::Answer) => &[
             "session-quit",
             "session-grade-again",
@@ -144,34 +235,27 @@ fn format_next_review_label(
     grade: ReviewGrade,
 ) -> Option<String> {
     let item = preview.grades.iter().find(|item| item.grade == grade)?;
-    let delta = item
-        .next_review
-        .signed_duration_since(preview.reviewed_at)
-        .num_seconds()
-        .max(0);
-    let label = if delta < 60 {
-        "now".to_string()
-    } else if delta < 3600 {
-        let minutes = (delta + 59) / 60;
-        format!("{minutes}m")
-    } else if delta < 86_400 {
-        let hours = (delta + 3599) / 3600;
-        format!("{hours}h")
-    } else {
-        let days = (delta + 86_399) / 86_400;
-        format!("{days}d")
-    };
-    Some(format!("<= {label}"))
+    Some(format!("<= {}", format_interval(preview.reviewed_at, item.next_review)))
 }
 
 #[component]
-pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) -> Element {
+pub fn SessionView(
+    deck_id: u64,
+    tag: Option<String>,
+    mode: SessionStartMode,
+    card_ids: Vec<CardId>,
+) -> Element {
     let ctx = use_context::<AppContext>();
     let navigator = use_navigator();
+    let strings = use_strings();
     let deck_id = DeckId::new(deck_id);
     let session_loop = ctx.session_loop();
     let card_service = ctx.card_service();
     let deck_service = ctx.deck_service();
+    let media_service = ctx.media_service();
+    let audio_player = ctx.audio_player();
+    let app_settings = ctx.app_settings();
+    let due_counts_refresh = try_use_context::<DueCountsRefresh>();
     let parsed_tag = tag.as_deref().map(|value| TagName::new(value.to_string()));
     let (tag_name, invalid_tag) = match parsed_tag {
         Some(Ok(tag)) => (Some(tag), false),
@@ -256,6 +340,8 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
                             soft_time_reminder_secs: settings.soft_time_reminder_secs(),
                             auto_reveal_secs: settings.auto_reveal_secs(),
                         },
+                        settings.answer_buttons(),
+                        (settings.front_template().cloned(), settings.back_template().cloned()),
                     )
                 }))
             }
@@ -266,6 +352,7 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
     let resource = use_resource(move || {
         let session_loop = session_loop_for_resource.clone();
         let tag_name = tag_name.clone();
+        let card_ids = card_ids.clone();
         let mut error = error;
         let mut vm = vm;
         let mut last_action = last_action;
@@ -276,10 +363,16 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
             if invalid_tag {
                 return Err(ViewError::Unknown);
             }
-            let started = start_session(&session_loop, deck_id, tag_name, mode).await?;
-            vm.set(Some(started));
+            let started = start_session(&session_loop, deck_id, tag_name, mode, &card_ids).await?;
+            let nothing_due = match started {
+                StartedSession::Ready(started) => {
+                    vm.set(Some(*started));
+                    None
+                }
+                StartedSession::NothingDue(nothing_due) => Some(nothing_due),
+            };
             error.set(None);
-            Ok::<_, ViewError>(())
+            Ok::<_, ViewError>(nothing_due)
         }
     });
 
@@ -311,7 +404,15 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
         last_focus_phase.set(phase);
         last_focus_completed.set(completed);
         last_focus_can_practice.set(completion_flags.can_practice_again);
-        let target = focus_target_for_phase(completed, completion_flags, phase);
+        let types_answer = vm.read().as_ref().is_some_and(SessionVm::asks_typed_answer);
+        let restudying = vm.read().as_ref().is_some_and(SessionVm::is_restudying);
+        let target = if restudying && !completed {
+            "session-restudy-done"
+        } else if types_answer && !completed && phase == Some(SessionPhase::Prompt) {
+            "session-typed-answer"
+        } else {
+            focus_target_for_phase(completed, completion_flags, phase)
+        };
         let js = format!(
             "document.getElementById({target:?})?.focus();",
         );
@@ -337,11 +438,39 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
                         review_preview.set(None);
                     }
                 }
-                SessionIntent::Grade(grade) => {
+                SessionIntent::Restudy => {
+                    review_preview.set(None);
+                    if let Some(vm) = vm.write().as_mut() {
+                        error.set(vm.restudy_last_lapse(&session_loop).err());
+                    }
+                }
+                SessionIntent::EndRestudy => {
+                    if let Some(vm) = vm.write().as_mut() {
+                        vm.end_restudy(&session_loop);
+                    }
+                }
+                SessionIntent::CycleFlag => {
+                    let session_loop = session_loop.clone();
+                    spawn(async move {
+                        last_action.set(Some(LastAction::CycleFlag));
+                        let Some(mut vm_value) = vm.write().take() else {
+                            error.set(Some(ViewError::Unknown));
+                            return;
+                        };
+                        let result = vm_value.cycle_flag(&session_loop).await;
+                        vm.set(Some(vm_value));
+                        error.set(result.err());
+                    });
+                }
+                SessionIntent::Grade(_) | SessionIntent::Bury | SessionIntent::Skip => {
                     review_preview.set(None);
                     let session_loop = session_loop.clone();
                     spawn(async move {
-                        last_action.set(Some(LastAction::Answer(grade)));
+                        last_action.set(Some(match intent {
+                            SessionIntent::Grade(grade) => LastAction::Answer(grade),
+                            SessionIntent::Skip => LastAction::Skip,
+                            _ => LastAction::Bury,
+                        }));
                         let mut local_vm = {
                             let mut guard = vm.write();
                             guard.take()
@@ -352,7 +481,13 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
                             return;
                         };
 
-                        let result = vm_value.answer_current(&session_loop, grade).await;
+                        let result = match intent {
+                            SessionIntent::Grade(grade) => {
+                                vm_value.answer_current(&session_loop, grade).await
+                            }
+                            SessionIntent::Skip => vm_value.skip_current(&session_loop).await,
+                            _ => vm_value.bury_current(&session_loop).await,
+                        };
 
                         // Always put the session back so the UI remains usable even after errors.
                         {
@@ -363,6 +498,9 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
                         match result {
                             Ok(outcome) => {
                                 error.set(None);
+                                if let Some(refresh) = due_counts_refresh {
+                                    refresh.bump();
+                                }
                                 match outcome {
                                     SessionOutcome::Continue => {}
                                     SessionOutcome::Completed { summary_id } => {
@@ -405,6 +543,15 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
             Some(LastAction::Answer(grade)) => {
                 dispatch_intent.call(SessionIntent::Grade(grade));
             }
+            Some(LastAction::Bury) => {
+                dispatch_intent.call(SessionIntent::Bury);
+            }
+            Some(LastAction::Skip) => {
+                dispatch_intent.call(SessionIntent::Skip);
+            }
+            Some(LastAction::CycleFlag) => {
+                dispatch_intent.call(SessionIntent::CycleFlag);
+            }
         }
     });
     let on_restart = {
@@ -418,8 +565,64 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
         })
     };
 
+    let answer_buttons = deck_info_resource
+        .value()
+        .read()
+        .as_ref()
+        .and_then(|value| value.as_ref().ok())
+        .and_then(|info| info.as_ref().map(|(_, _, buttons, _)| *buttons))
+        .unwrap_or_default();
+    let key_map = SessionKeyMap::for_buttons(&use_key_bindings(), answer_buttons);
+    // What the learner is typing for the current card when the deck asks for typed answers.
+    let mut typed_draft = use_signal(String::new);
+    let submit_typed_answer = use_callback(move |()| {
+        let mut vm = vm;
+        let typed = typed_draft();
+        typed_draft.set(String::new());
+        if let Some(vm) = vm.write().as_mut() {
+            vm.set_typed_answer(typed);
+        }
+        dispatch_intent.call(SessionIntent::Reveal);
+    });
+    let legend = key_map.legend();
+    let mut show_shortcuts = use_signal(|| false);
+    // Focus mode leaves only the card and its controls; the preference is saved in app settings.
+    let mut focus_mode = use_signal(|| false);
+    {
+        let app_settings = app_settings.clone();
+        use_future(move || {
+            let app_settings = app_settings.clone();
+            async move {
+                if let Ok(settings) = app_settings.load().await {
+                    focus_mode.set(settings.focus_mode());
+                }
+            }
+        });
+    }
+    let set_focus_mode = {
+        let app_settings = app_settings.clone();
+        use_callback(move |enabled: bool| {
+            focus_mode.set(enabled);
+            let app_settings = app_settings.clone();
+            spawn(async move {
+                let _ = app_settings.set_focus_mode(enabled).await;
+            });
+        })
+    };
     let on_key = {
         use_callback(move |evt: KeyboardEvent| {
+            if show_shortcuts() {
+                if shortcuts::session::LEAVE.matches(&evt.data) {
+                    evt.prevent_default();
+                    show_shortcuts.set(false);
+                }
+                return;
+            }
+            if shortcuts::SHOW_SHORTCUTS.matches(&evt.data) {
+                evt.prevent_default();
+                show_shortcuts.set(true);
+                return;
+            }
             if evt.data.key() == Key::Tab {
                 evt.prevent_default();
                 let shift = evt.data.modifiers().contains(Modifiers::SHIFT);
@@ -434,7 +637,7 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
                 let delta = if shift { -1 } else { 1 };
                 let js = format!(
                     r"(function() {{
-                        const ids = [{ids_js}];
+                        const ids = [{ids_js}].filter((id) => document.getElementById(id));
                         if (!ids.length) return;
                         const active = document.activeElement && document.activeElement.id;
                         let idx = ids.indexOf(active);
@@ -448,9 +651,13 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
                 let _ = eval(&js);
                 return;
             }
-            if evt.data.key() == Key::Escape {
+            if shortcuts::session::LEAVE.matches(&evt.data) {
                 evt.prevent_default();
-                navigator.push(Route::Practice {});
+                if focus_mode() {
+                    set_focus_mode.call(false);
+                } else {
+                    navigator.push(Route::Practice {});
+                }
                 return;
             }
 
@@ -458,68 +665,144 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
             if !has_card {
                 return;
             }
-
-            if evt.data.code() == Code::Space {
-                if vm.read().as_ref().map(SessionVm::phase) == Some(SessionPhase::Prompt) {
+            // While restudying a failed card only the reveal key works; it moves on.
+            if vm.read().as_ref().is_some_and(SessionVm::is_restudying) {
+                if key_map.is_reveal(&evt.data) {
                     evt.prevent_default();
-                    dispatch_intent.call(SessionIntent::Reveal);
+                    dispatch_intent.call(SessionIntent::EndRestudy);
                 }
                 return;
             }
 
-            if vm.read().as_ref().map(SessionVm::phase) != Some(SessionPhase::Answer) {
+            if shortcuts::session::FLAG.matches(&evt.data) {
+                evt.prevent_default();
+                dispatch_intent.call(SessionIntent::CycleFlag);
+                return;
+            }
+            if shortcuts::session::SKIP.matches(&evt.data) {
+                evt.prevent_default();
+                if vm.read().as_ref().is_some_and(SessionVm::can_skip) {
+                    dispatch_intent.call(SessionIntent::Skip);
+                }
                 return;
             }
 
-            if let Key::Character(value) = evt.data.key() {
-                match value.as_str() {
-                    "1" => {
-                        evt.prevent_default();
-                        dispatch_intent.call(SessionIntent::Grade(ReviewGrade::Again));
-                    }
-                    "2" => {
-                        evt.prevent_default();
-                        dispatch_intent.call(SessionIntent::Grade(ReviewGrade::Hard));
-                    }
-                    "3" => {
-                        evt.prevent_default();
-                        dispatch_intent.call(SessionIntent::Grade(ReviewGrade::Good));
-                    }
-                    "4" => {
-                        evt.prevent_default();
-                        dispatch_intent.call(SessionIntent::Grade(ReviewGrade::Easy));
-                    }
-                    _ => {}
+            let phase = vm.read().as_ref().map(SessionVm::phase);
+            let fully_revealed = vm.read().as_ref().is_some_and(SessionVm::answer_fully_revealed);
+            if key_map.is_reveal(&evt.data) {
+                if phase == Some(SessionPhase::Prompt) || !fully_revealed {
+                    evt.prevent_default();
+                    dispatch_intent.call(SessionIntent::Reveal);
                 }
+                return;
+            }
+
+            // Grades go through the same intent as the grade buttons, and only once the whole
+            // answer shows.
+            if !fully_revealed {
+                return;
+            }
+            if let Some(grade) = key_map.grade_for(&evt.data) {
+                evt.prevent_default();
+                dispatch_intent.call(SessionIntent::Grade(grade));
             }
         })
     };
 
     let vm_guard = vm.read();
-    let card_prompt_html = use_memo(move || {
+    let card_media_ids = use_memo(move || {
         let vm_guard = vm.read();
-        vm_guard
+        let vm_ref = vm_guard.as_ref();
+        (
+            vm_ref.and_then(SessionVm::prompt_media_id),
+            vm_ref.and_then(SessionVm::answer_media_id),
+        )
+    });
+    let card_media_sources = use_resource(move || {
+        let media_service = media_service.clone();
+        let (prompt_id, answer_id) = card_media_ids();
+        async move {
+            let mut sources = Vec::new();
+            for media_id in [prompt_id, answer_id].into_iter().flatten() {
+                if let Ok(url) = media_service.image_data_url(media_id).await {
+                    sources.push((media_id, CardMedia::from_data_url(url)));
+                }
+            }
+            sources
+        }
+    });
+    // Sources are keyed by media id so a card never shows the previous card's image.
+    let media_src = move |media_id: Option<MediaId>| {
+        let sources = card_media_sources.read();
+        media_id.and_then(|media_id| {
+            sources
+                .as_ref()?
+                .iter()
+                .find(|(id, _)| *id == media_id)
+                .map(|(_, media)| media.clone())
+        })
+    };
+    let card_templates = move || -> (Option<CardTemplate>, Option<CardTemplate>) {
+        deck_info_resource
+            .value()
+            .read()
             .as_ref()
-            .and_then(SessionVm::prompt_text)
-            .map(sanitize_html)
+            .and_then(|value| value.as_ref().ok())
+            .and_then(|info| info.as_ref().map(|(_, _, _, templates)| templates.clone()))
+            .unwrap_or_default()
+    };
+    let card_prompt_html = use_memo(move || {
+        let vm_guard = vm.read();
+        let vm = vm_guard.as_ref()?;
+        let prompt = vm.prompt_content()?;
+        let front_html = render_card_content(prompt, media_src(prompt.media_id()).as_ref());
+        let fields = TemplateFields {
+            front_html: &front_html,
+            back_html: "",
+            extra: vm.extra_text(),
+        };
+        Some(render_card_template(card_templates().0.as_ref(), CardSide::Prompt, &fields))
     });
     let card_answer_html = use_memo(move || {
         let vm_guard = vm.read();
-        vm_guard
-            .as_ref()
-            .and_then(SessionVm::answer_text)
-            .map(sanitize_html)
+        let vm = vm_guard.as_ref()?;
+        let (prompt, answer) = (vm.prompt_content()?, vm.answer_content()?);
+        let front_html = render_card_content(prompt, media_src(prompt.media_id()).as_ref());
+        // Mid-reveal, only the steps shown so far render; the image waits for the last one.
+        let back_html = vm.partial_answer_text().map_or_else(
+            || render_card_content(answer, media_src(answer.media_id()).as_ref()),
+            render_card_text,
+        );
+        let fields = TemplateFields {
+            front_html: &front_html,
+            back_html: &back_html,
+            extra: vm.extra_text(),
+        };
+        Some(render_card_template(card_templates().1.as_ref(), CardSide::Answer, &fields))
     });
     let card_prompt_html_read = card_prompt_html.read();
     let card_answer_html_read = card_answer_html.read();
     let card_prompt_html = card_prompt_html_read.as_deref();
     let card_answer_html = card_answer_html_read.as_deref();
     let phase = vm_guard.as_ref().map(SessionVm::phase);
+    let reveal_progress = vm_guard.as_ref().and_then(SessionVm::reveal_progress);
+    let asks_typed_answer = vm_guard.as_ref().is_some_and(SessionVm::asks_typed_answer);
+    let typed_answer_check = vm_guard.as_ref().and_then(SessionVm::typed_answer_check);
+    let current_flag = vm_guard.as_ref().and_then(SessionVm::current_flag);
     let completion_state = *completion.read();
     let (current_index, total_cards) = vm_guard.as_ref().map_or((0, 0), |vm| {
         (vm.current_index(), vm.total_cards())
     });
     let progress_label = format!("{current_index} / {total_cards} Cards");
+    let session_progress = vm_guard
+        .as_ref()
+        .map(|vm| vm.progress(&session_loop))
+        .filter(|progress| !progress.is_complete);
+    let failed_out_notice = vm_guard.as_ref().and_then(SessionVm::failed_out_notice);
+    let interval_explanation = vm_guard.as_ref().and_then(SessionVm::last_interval_explanation);
+    let restudying = vm_guard.as_ref().is_some_and(SessionVm::is_restudying);
+    let can_restudy = vm_guard.as_ref().is_some_and(SessionVm::can_restudy);
+    let can_skip = vm_guard.as_ref().is_some_and(SessionVm::can_skip);
     let streak_label = vm_guard.as_ref().map_or_else(
         || "Streak: 0 🔥".to_string(),
         |vm| format!("Streak: {} 🔥", vm.streak()),
@@ -546,21 +829,19 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
         .and_then(|value| value.as_ref().ok())
         .and_then(Clone::clone);
     let preview = review_preview.read();
-    let again_next = preview
-        .as_ref()
-        .and_then(|value| format_next_review_label(value, ReviewGrade::Again));
-    let hard_next = preview
-        .as_ref()
-        .and_then(|value| format_next_review_label(value, ReviewGrade::Hard));
-    let good_next = preview
-        .as_ref()
-        .and_then(|value| format_next_review_label(value, ReviewGrade::Good));
-    let easy_next = preview
-        .as_ref()
-        .and_then(|value| format_next_review_label(value, ReviewGrade::Easy));
+    let grade_buttons = answer_buttons
+        .grades()
+        .iter()
+        .map(|grade| {
+            let next_label = preview
+                .as_ref()
+                .and_then(|value| format_next_review_label(value, *grade));
+            (*grade, next_label)
+        })
+        .collect::<Vec<_>>();
     let (deck_label, timer_settings) = deck_info.map_or(
         (None, TimerSettings::default()),
-        |(label, settings)| (Some(label), settings),
+        |(label, settings, _, _)| (Some(label), settings),
     );
     let context_label = match (deck_label.as_deref(), tag.as_deref()) {
         (Some(deck), Some(tag)) => format!("{deck} · Tag: {tag}"),
@@ -568,11 +849,9 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
         (None, Some(tag)) => format!("Tag: {tag}"),
         (None, None) => String::new(),
     };
-    let timer_enabled = timer_settings.show_timer
-        || timer_settings.soft_time_reminder
-        || timer_settings.auto_advance_cards;
-    let timer_active = timer_enabled
-        && matches!(state, ViewState::Ready(()))
+    // The timer only runs while the prompt is showing; revealing the answer stops it.
+    let timer_active = timer_settings.enabled()
+        && matches!(state, ViewState::Ready(None))
         && completion_state.is_none()
         && phase == Some(SessionPhase::Prompt);
     let timer_label = format_timer(0);
@@ -582,53 +861,147 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
     let timer_settings_for_js = timer_settings;
     use_effect(move || {
         let vm_guard = vm_for_timer.read();
-            let phase = vm_guard.as_ref().map(SessionVm::phase);
-            let current_index = vm_guard.as_ref().map_or(0, |vm| vm.current_index());
-            let completion_state = completion_for_timer.read().is_some();
-            let timer_enabled = timer_settings_for_js.show_timer
-                || timer_settings_for_js.soft_time_reminder
-                || timer_settings_for_js.auto_advance_cards;
-            let timer_active = timer_enabled
-                && matches!(state_for_timer, ViewState::Ready(()))
-                && !completion_state
-                && phase == Some(SessionPhase::Prompt);
-            let timer_key = format!("{}:{current_index}", completion_state as u8);
-            let js = session_timer_script(
-                &timer_key,
-                timer_active,
-                timer_settings_for_js.show_timer,
-                timer_settings_for_js.soft_time_reminder,
-                timer_settings_for_js.auto_advance_cards,
-                timer_settings_for_js.soft_time_reminder_secs,
-                timer_settings_for_js.auto_reveal_secs,
-            );
-            let _ = eval(&js);
+        let phase = vm_guard.as_ref().map(SessionVm::phase);
+        let current_index = vm_guard.as_ref().map_or(0, SessionVm::current_index);
+        let card_id = vm_guard
+            .as_ref()
+            .and_then(SessionVm::current_card_id)
+            .map_or(0, |id| id.value());
+        let completion_state = completion_for_timer.read().is_some();
+        let timer_active = timer_settings_for_js.enabled()
+            && matches!(state_for_timer, ViewState::Ready(None))
+            && !completion_state
+            && phase == Some(SessionPhase::Prompt);
+        // Keyed per card so the elapsed time and reminder reset even when a restart
+        // lands on the same position again.
+        let timer_key = format!("{}:{current_index}:{card_id}", completion_state as u8);
+        let js = session_timer_script(
+            &timer_key,
+            timer_active,
+            timer_settings_for_js.show_timer,
+            timer_settings_for_js.soft_time_reminder,
+            timer_settings_for_js.auto_advance_cards,
+            timer_settings_for_js.soft_time_reminder_secs,
+            timer_settings_for_js.auto_reveal_secs,
+        );
+        let _ = eval(&js);
     });
     let show_timer = timer_settings.show_timer && timer_active;
+    let focused = focus_mode();
+
+    // Play card audio as each card appears and again on reveal; anything still pending
+    // or playing is cut off as soon as the card or phase changes.
+    let mut audio_key = use_signal(|| None::<(u64, SessionPhase)>);
+    let mut audio_task = use_signal(|| None::<Task>);
+    let audio_settings_resource = use_resource(move || {
+        let app_settings = app_settings.clone();
+        async move {
+            let settings = app_settings.load().await.unwrap_or_default();
+            AudioSettings::from_app_settings(&settings)
+        }
+    });
+    let audio_player_for_effect = audio_player.clone();
+    use_effect(move || {
+        // Wait for the preferences so the first card is not skipped.
+        let Some(audio_settings) = *audio_settings_resource.read() else {
+            return;
+        };
+        let vm_guard = vm.read();
+        let current = vm_guard.as_ref().and_then(|vm| {
+            let card_id = vm.current_card_id()?;
+            Some((card_id.value(), vm.phase()))
+        });
+        if *audio_key.peek() == current {
+            return;
+        }
+        audio_key.set(current);
+        if let Some(task) = audio_task.write().take() {
+            task.cancel();
+        }
+        audio_player_for_effect.stop();
+
+        let source = vm_guard
+            .as_ref()
+            .zip(current)
+            .and_then(|(vm, (_, phase))| audio_settings.source_for(vm, phase));
+        let Some(source) = source else {
+            return;
+        };
+        let player = audio_player_for_effect.clone();
+        let delay = Duration::from_millis(audio_settings.audio_delay_ms);
+        audio_task.set(Some(spawn(async move {
+            tokio::time::sleep(delay).await;
+            player.play(&source);
+        })));
+    });
+    use_drop(move || audio_player.stop());
 
     rsx! {
-        div { class: "page session-page", id: "session-root", tabindex: "0", onkeydown: on_key,
+        div {
+            class: if focused { "page session-page session-page--focus" } else { "page session-page" },
+            id: "session-root",
+            tabindex: "0",
+            onkeydown: on_key,
             div { class: "session-overlay",
                 div {
                     class: "session-modal",
                     role: "dialog",
                     aria_modal: "true",
-                    aria_labelledby: "session-modal-title",
-                    header { class: "session-modal__header",
-                        div { class: "session-modal__heading",
-                            h2 { class: "session-modal__title", id: "session-modal-title", "Practice Session" }
-                            if !context_label.is_empty() {
-                                p { class: "session-modal__context", "{context_label}" }
-                            }
-                        }
+                    aria_label: "Practice Session",
+                    if focused {
                         button {
-                            class: "session-modal__quit",
-                            id: "session-quit",
+                            class: "session-focus-exit",
+                            id: "session-focus-exit",
                             r#type: "button",
-                            onclick: move |_| {
-                                let _ = navigator.push(Route::Practice {});
-                            },
-                            "Quit"
+                            title: "Show the session details again (Esc)",
+                            onclick: move |_| set_focus_mode.call(false),
+                            {strings.t("session.exit_focus")}
+                        }
+                    } else {
+                        header { class: "session-modal__header",
+                            button {
+                                class: "session-modal__focus",
+                                id: "session-focus",
+                                r#type: "button",
+                                title: "Hide everything but the card",
+                                onclick: move |_| set_focus_mode.call(true),
+                                {strings.t("session.focus")}
+                            }
+                            div { class: "session-modal__heading",
+                                h2 { class: "session-modal__title", {strings.t("session.title")} }
+                                if !context_label.is_empty() {
+                                    p { class: "session-modal__context", "{context_label}" }
+                                }
+                            }
+                            button {
+                                class: "session-modal__quit",
+                                id: "session-quit",
+                                r#type: "button",
+                                onclick: move |_| {
+                                    let _ = navigator.push(Route::Practice {});
+                                },
+                                {strings.t("session.quit")}
+                            }
+                        }
+                    }
+                    if let Some(progress) = session_progress.filter(|_| !focused) {
+                        div { class: "session-progress",
+                            div {
+                                class: "session-progress__track",
+                                id: "session-progress",
+                                role: "progressbar",
+                                aria_label: "Session progress",
+                                aria_valuemin: "0",
+                                aria_valuemax: "100",
+                                aria_valuenow: "{progress.percent_answered()}",
+                                div {
+                                    class: "session-progress__bar",
+                                    style: "width: {progress.percent_answered()}%",
+                                }
+                            }
+                            p { class: "session-progress__label",
+                                "{progress.answered} done · {progress.remaining} left · daily limit {progress.daily_target}"
+                            }
                         }
                     }
                     div { class: "session-modal__body",
@@ -660,7 +1033,25 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
                                     }
                                 }
                             },
-                            ViewState::Ready(()) => rsx! {
+                            ViewState::Ready(Some(nothing_due)) => rsx! {
+                                if nothing_due.next_due_at.is_some() {
+                                    div { class: "session-complete",
+                                        h3 { class: "session-complete__title", "All done for today!" }
+                                        p { class: "session-complete__subtitle", "{nothing_due_label(&nothing_due)}" }
+                                    }
+                                } else {
+                                    p { "{empty_session_message}" }
+                                }
+                                button {
+                                    class: "btn btn-secondary",
+                                    r#type: "button",
+                                    onclick: move |_| {
+                                        let _ = navigator.push(empty_session_cta.1.clone());
+                                    },
+                                    "{empty_session_cta.0}"
+                                }
+                            },
+                            ViewState::Ready(None) => rsx! {
                                 if let Some(err) = *error.read() {
                                     p { "{err.message()}" }
                                     button {
@@ -676,57 +1067,170 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
                                         p { class: "session-complete__subtitle", "Nice work. You finished this practice." }
                                     }
                                 } else if let Some(prompt_html) = card_prompt_html {
+                                    if let Some(notice) = failed_out_notice {
+                                        p { class: "session-notice", role: "status", "{notice}" }
+                                    }
+                                    if let Some(lines) = interval_explanation {
+                                        details { class: "session-explain",
+                                            summary { class: "session-explain__summary",
+                                                "Why did the last card get this interval?"
+                                            }
+                                            ul { class: "session-explain__list",
+                                                for line in lines {
+                                                    li { "{line}" }
+                                                }
+                                            }
+                                        }
+                                    }
+                                    if restudying {
+                                        p { class: "session-notice", role: "status",
+                                            "Take another look at the card you just missed."
+                                        }
+                                    }
                                     div { class: "session-question",
                                         div { class: "session-text", dangerous_inner_html: "{prompt_html}" }
                                     }
                                     match phase {
+                                        Some(SessionPhase::Prompt) if asks_typed_answer => rsx! {
+                                            input {
+                                                class: "session-typed-input",
+                                                id: "session-typed-answer",
+                                                r#type: "text",
+                                                autocomplete: "off",
+                                                spellcheck: "false",
+                                                aria_label: "Your answer",
+                                                placeholder: "Type the answer",
+                                                value: "{typed_draft}",
+                                                oninput: move |evt| typed_draft.set(evt.value()),
+                                                // Keys typed here are text, not shortcuts; Tab
+                                                // still moves focus.
+                                                onkeydown: move |evt| {
+                                                    if evt.data.key() == Key::Tab {
+                                                        return;
+                                                    }
+                                                    evt.stop_propagation();
+                                                    if evt.data.key() == Key::Enter {
+                                                        evt.prevent_default();
+                                                        submit_typed_answer.call(());
+                                                    }
+                                                },
+                                            }
+                                            button {
+                                                class: "session-reveal-btn",
+                                                id: "session-reveal",
+                                                onclick: move |_| submit_typed_answer.call(()),
+                                                {strings.t("session.check_answer")}
+                                            }
+                                        },
                                         Some(SessionPhase::Prompt) => rsx! {
                                             button {
                                                 class: "session-reveal-btn",
                                                 id: "session-reveal",
                                                 onclick: move |_| dispatch_intent.call(SessionIntent::Reveal),
-                                                "Show Answer"
+                                                {strings.t("session.show_answer")}
                                             }
                                         },
                                         Some(SessionPhase::Answer) => rsx! {
+                                            if let Some(check) = typed_answer_check {
+                                                TypedAnswerResult {
+                                                    is_match: check.is_match,
+                                                    diff: check.diff,
+                                                }
+                                            }
                                             if let Some(answer_html) = card_answer_html {
                                                 div { class: "session-answer",
                                                     div { class: "session-text", dangerous_inner_html: "{answer_html}" }
                                                 }
                                             }
-                                            p { class: "session-remember", "How well did you remember?" }
-                                            div { class: "session-grades",
-                                                GradeButton {
-                                                    label: "Again",
-                                                    grade: ReviewGrade::Again,
-                                                    on_intent: dispatch_intent,
-                                                    next_label: again_next,
+                                            if restudying {
+                                                button {
+                                                    class: "session-reveal-btn",
+                                                    id: "session-restudy-done",
+                                                    onclick: move |_| dispatch_intent.call(SessionIntent::EndRestudy),
+                                                    {strings.t("session.continue")}
                                                 }
-                                                GradeButton {
-                                                    label: "Hard",
-                                                    grade: ReviewGrade::Hard,
-                                                    on_intent: dispatch_intent,
-                                                    next_label: hard_next,
+                                            } else if let Some((shown, total)) = reveal_progress {
+                                                button {
+                                                    class: "session-reveal-btn",
+                                                    id: "session-reveal",
+                                                    onclick: move |_| dispatch_intent.call(SessionIntent::Reveal),
+                                                    "Show More ({shown}/{total})"
                                                 }
-                                                GradeButton {
-                                                    label: "Good",
-                                                    grade: ReviewGrade::Good,
-                                                    on_intent: dispatch_intent,
-                                                    next_label: good_next,
+                                            } else {
+                                                p { class: "session-remember",
+                                                    {strings.t("session.remember")}
                                                 }
-                                                GradeButton {
-                                                    label: "Easy",
-                                                    grade: ReviewGrade::Easy,
-                                                    on_intent: dispatch_intent,
-                                                    next_label: easy_next,
+                                                div { class: "session-grades",
+                                                    for (grade, next_label) in grade_buttons.iter().cloned() {
+                                                        GradeButton {
+                                                            key: "{grade:?}",
+                                                            grade,
+                                                            on_intent: dispatch_intent,
+                                                            next_label,
+                                                        }
+                                                    }
                                                 }
                                             }
                                         },
                                         None => rsx! {},
                                     }
+                                    if can_restudy {
+                                        button {
+                                            class: "session-bury-btn",
+                                            id: "session-restudy",
+                                            r#type: "button",
+                                            title: "Show the card you just missed once more",
+                                            onclick: move |_| dispatch_intent.call(SessionIntent::Restudy),
+                                            {strings.t("session.review_again")}
+                                        }
+                                    }
+                                    if !focused && !restudying {
+                                        button {
+                                            class: "session-bury-btn",
+                                            r#type: "button",
+                                            title: "Skip this card until tomorrow",
+                                            onclick: move |_| dispatch_intent.call(SessionIntent::Bury),
+                                            {strings.t("session.bury")}
+                                        }
+                                        button {
+                                            class: "session-bury-btn",
+                                            r#type: "button",
+                                            disabled: !can_skip,
+                                            title: "Show this card again later in this session (S)",
+                                            onclick: move |_| dispatch_intent.call(SessionIntent::Skip),
+                                            {strings.t("session.skip")}
+                                        }
+                                        button {
+                                            class: "session-bury-btn session-flag-btn",
+                                            r#type: "button",
+                                            title: "Cycle the card's flag (F)",
+                                            onclick: move |_| dispatch_intent.call(SessionIntent::CycleFlag),
+                                            if let Some(flag) = current_flag {
+                                                span { class: "card-flag card-flag--{flag.as_str()}" }
+                                                "{flag_label(flag)}"
+                                            } else {
+                                                {strings.t("session.flag")}
+                                            }
+                                        }
+                                    }
                                 } else {
                                     p { "No cards available." }
                                 }
+                                if timer_settings.auto_advance_cards {
+                                    div {
+                                        class: "session-auto-reveal",
+                                        id: "session-auto-reveal",
+                                        role: "progressbar",
+                                        aria_label: "Answer reveals automatically",
+                                        aria_valuemin: "0",
+                                        aria_valuemax: "100",
+                                        hidden: "true",
+                                        div {
+                                            class: "session-auto-reveal__bar",
+                                            id: "session-auto-reveal-bar",
+                                        }
+                                    }
+                                }
                                 if timer_settings.soft_time_reminder {
                                     p {
                                         class: "session-soft-reminder",
@@ -750,7 +1254,7 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
                                 completion_note,
                             }
                         }
-                    } else {
+                    } else if !focused {
                         footer { class: "session-modal__footer",
                             span { class: "session-footer__item", "{progress_label}" }
                             span { class: "session-footer__item", "{streak_label}" }
@@ -767,33 +1271,76 @@ pub fn SessionView(deck_id: u64, tag: Option<String>, mode: SessionStartMode) ->
                     }
                 }
             }
+            if show_shortcuts() {
+                ShortcutsOverlay {
+                    shortcuts: legend,
+                    on_close: move |()| show_shortcuts.set(false),
+                }
+            }
         }
     }
 }
 
 #[component]
 fn GradeButton(
-    label: &'static str,
     grade: ReviewGrade,
     on_intent: EventHandler<SessionIntent>,
     next_label: Option<String>,
 ) -> Element {
-    let variant = match grade {
-        ReviewGrade::Again => ("session-grade session-grade--again", "session-grade-again"),
-        ReviewGrade::Hard => ("session-grade session-grade--hard", "session-grade-hard"),
-        ReviewGrade::Good => ("session-grade session-grade--good", "session-grade-good"),
-        ReviewGrade::Easy => ("session-grade session-grade--easy", "session-grade-easy"),
+    let (key, variant) = match grade {
+        ReviewGrade::Again => (
+            "session.grade.again",
+            ("session-grade session-grade--again", "session-grade-again"),
+        ),
+        ReviewGrade::Hard => (
+            "session.grade.hard",
+            ("session-grade session-grade--hard", "session-grade-hard"),
+        ),
+        ReviewGrade::Good => (
+            "session.grade.good",
+            ("session-grade session-grade--good", "session-grade-good"),
+        ),
+        ReviewGrade::Easy => (
+            "session.grade.easy",
+            ("session-grade session-grade--easy", "session-grade-easy"),
+        ),
     };
+    let label = use_strings().t(key);
     rsx! {
         div { class: "session-grade-item",
+            if let Some(next_label) = next_label {
+                p { class: "session-grade-next", "{next_label}" }
+            }
             button {
                 class: "{variant.0}",
                 id: "{variant.1}",
                 onclick: move |_| on_intent.call(SessionIntent::Grade(grade)),
                 "{label}"
             }
-            if let Some(next_label) = next_label {
-                p { class: "session-grade-next", "{next_label}" }
+        }
+    }
+}
+
+/// The typed answer beside the card's answer: kept, mistyped, and left-out characters.
+#[component]
+fn TypedAnswerResult(is_match: bool, diff: Vec<AnswerDiffSpan>) -> Element {
+    rsx! {
+        div { class: "session-typed-result", role: "status",
+            if is_match {
+                p { class: "session-typed-result__verdict session-typed-result__verdict--match",
+                    "Correct"
+                }
+            } else {
+                p { class: "session-typed-result__verdict", "Not quite" }
+                p { class: "session-typed-result__diff",
+                    for (index, part) in diff.into_iter().enumerate() {
+                        span {
+                            key: "{index}",
+                            class: "typed-diff typed-diff--{part.kind.as_str()}",
+                            "{part.text}"
+                        }
+                    }
+                }
             }
         }
     }
@@ -1,5 +1,8 @@
 use dioxus::prelude::*;
 
+use crate::vm::highlight_spans;
+
+use super::search::{SettingsRowText, SettingsSearch};
 use super::state::SettingsSection;
 
 #[component]
@@ -36,6 +39,7 @@ pub(super) fn SettingsNavIcon(section: SettingsSection) -> Element {
         SettingsSection::Audio => "M5 9h4l5-4v14l-5-4H5z",
         SettingsSection::Timers => "M12 6v6l4 2",
         SettingsSection::EasyDays => "M12 4v16M4 12h16",
+        SettingsSection::Appearance => "M12 4a8 8 0 1 0 8 8h-5a3 3 0 0 1-3-3z",
         SettingsSection::Advanced => "M12 2l3 6 6 1-4 4 1 6-6-3-6 3 1-6-4-4 6-1z",
     };
     let view_box = "0 0 24 24";
@@ -52,15 +56,35 @@ pub(super) fn SettingsNavIcon(section: SettingsSection) -> Element {
     }
 }
 
+fn render_highlighted(text: &str, query: &str) -> Element {
+    rsx! {
+        for (idx, span) in highlight_spans(text, query).into_iter().enumerate() {
+            if span.is_match {
+                mark { key: "{idx}", class: "settings-search-highlight", "{span.text}" }
+            } else {
+                span { key: "{idx}", "{span.text}" }
+            }
+        }
+    }
+}
+
+/// An accordion section; while a search is active it opens itself when any of its rows
+/// match and disappears when none do.
 #[component]
 pub(super) fn SettingsAccordionSection(
-    label: &'static str,
     section: SettingsSection,
     expanded: bool,
     on_toggle: Signal<Option<SettingsSection>>,
     help_title: Option<&'static str>,
     children: Element,
 ) -> Element {
+    let search = use_context::<Signal<SettingsSearch>>();
+    let search = search();
+    if !search.matches_section(section) {
+        return rsx! {};
+    }
+    let expanded = expanded || search.is_active();
+
     rsx! {
         div { class: "settings-accordion-section", id: "{section.anchor_id()}",
             button {
@@ -77,7 +101,7 @@ pub(super) fn SettingsAccordionSection(
                         on_toggle.set(Some(section));
                     }
                 },
-                span { "{label}" }
+                span { {render_highlighted(section.label(), search.query())} }
                 span { class: "settings-accordion-trailing",
                     if let Some(help) = help_title {
                         span { class: "settings-accordion-help", title: "{help}", "?" }
@@ -93,3 +117,27 @@ pub(super) fn SettingsAccordionSection(
         }
     }
 }
+
+/// A settings row with its label and help tooltip, hidden when the search skips it.
+#[component]
+pub(super) fn SettingsRow(
+    row: SettingsRowText,
+    input_id: Option<&'static str>,
+    children: Element,
+) -> Element {
+    let search = use_context::<Signal<SettingsSearch>>();
+    let search = search();
+    if !search.matches_row(&row) {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "settings-row",
+            div { class: "settings-row__label",
+                label { r#for: input_id, {render_highlighted(row.label, search.query())} }
+                span { class: "settings-row__help", title: "{row.help}", "?" }
+            }
+            {children}
+        }
+    }
+}
@@ -1,10 +1,13 @@
-pub(super) fn parse_positive_u32(value: &str) -> Option<u32> {
-    let value = value.trim();
-    let parsed = value.parse::<u32>().ok()?;
-    if parsed == 0 {
-        return None;
-    }
-    Some(parsed)
+use learn_core::model::{AnswerButtons, LeechAction, NewCardOrder, NewReviewMix};
+
+/// Parse a whole-number field; range rules live in `DeckSettings::new`.
+pub(super) fn parse_u32(value: &str) -> Option<u32> {
+    value.trim().parse::<u32>().ok()
+}
+
+/// Parse the audio delay field; blank or non-numeric input is invalid.
+pub(super) fn parse_audio_delay_ms(value: &str) -> Option<u32> {
+    value.trim().parse::<u32>().ok()
 }
 
 pub(super) fn parse_lapse_interval_secs(value: &str) -> Option<u32> {
@@ -22,9 +25,6 @@ pub(super) fn parse_lapse_interval_secs(value: &str) -> Option<u32> {
     }
     let (number_part, suffix) = normalized.split_at(split_at);
     let amount = number_part.trim().parse::<u32>().ok()?;
-    if amount == 0 {
-        return None;
-    }
     let unit = suffix.trim();
     match unit {
         "" | "d" | "day" | "days" => amount.checked_mul(86_400),
@@ -47,28 +47,79 @@ pub(super) fn format_lapse_interval(secs: u32) -> String {
     }
 }
 
-pub(super) fn parse_timer_secs(value: &str) -> Option<u32> {
-    let value = value.trim();
-    let parsed = value.parse::<u32>().ok()?;
-    if !(5..=600).contains(&parsed) {
-        return None;
-    }
-    Some(parsed)
+/// Parse space- or comma-separated learning step delays such as "1m 10m"; blank means none.
+pub(super) fn parse_learning_steps(value: &str) -> Option<Vec<u32>> {
+    value
+        .split(|ch: char| ch == ',' || ch.is_whitespace())
+        .filter(|step| !step.is_empty())
+        .map(parse_lapse_interval_secs)
+        .collect()
 }
 
-pub(super) fn parse_retention(value: &str) -> Option<f32> {
-    let trimmed = value.trim();
-    let parsed = trimmed.parse::<f32>().ok()?;
-    if !parsed.is_finite() || parsed <= 0.0 || parsed > 1.0 {
-        return None;
-    }
-    Some(parsed)
+pub(super) fn format_learning_steps(steps: &[u32]) -> String {
+    steps
+        .iter()
+        .map(|secs| format_lapse_interval(*secs))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// FSRS default weights, shown as the placeholder while a deck schedules with the defaults.
+const DEFAULT_FSRS_PARAMETERS: [f32; 21] = [
+    0.2120, 1.2931, 2.3065, 8.2956, 6.4133, 0.8334, 3.0194, 0.0010, 1.8722, 0.1666, 0.7960,
+    1.4835, 0.0614, 0.2629, 1.6483, 0.6014, 1.8729, 0.5425, 0.0912, 0.0658, 0.1542,
+];
+
+pub(super) fn default_fsrs_parameters_placeholder() -> String {
+    DEFAULT_FSRS_PARAMETERS
+        .iter()
+        .map(|weight| format!("{weight:.4}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parse a decimal field such as a retention or load factor; ranges are checked in core.
+pub(super) fn parse_decimal(value: &str) -> Option<f32> {
+    value.trim().parse::<f32>().ok().filter(|parsed| parsed.is_finite())
 }
 
 pub(super) fn format_retention(value: f32) -> String {
     format!("{value:.2}")
 }
 
+pub(super) fn parse_new_card_order(value: &str) -> Option<NewCardOrder> {
+    match value.trim() {
+        "sequential" => Some(NewCardOrder::Sequential),
+        "random" => Some(NewCardOrder::Random),
+        _ => None,
+    }
+}
+
+pub(super) fn parse_new_review_mix(value: &str) -> Option<NewReviewMix> {
+    match value.trim() {
+        "front_load" => Some(NewReviewMix::FrontLoad),
+        "interleaved" => Some(NewReviewMix::Interleaved),
+        "after_reviews" => Some(NewReviewMix::AfterReviews),
+        _ => None,
+    }
+}
+
+pub(super) fn parse_answer_buttons(value: &str) -> Option<AnswerButtons> {
+    match value.trim() {
+        "two" => Some(AnswerButtons::Two),
+        "four" => Some(AnswerButtons::Four),
+        _ => None,
+    }
+}
+
+pub(super) fn parse_leech_action(value: &str) -> Option<LeechAction> {
+    match value.trim() {
+        "tag" => Some(LeechAction::Tag),
+        "suspend" => Some(LeechAction::Suspend),
+        _ => None,
+    }
+}
+
 pub(super) fn normalize_description(value: &str) -> Option<String> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
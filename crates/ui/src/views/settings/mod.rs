@@ -1,5 +1,6 @@
 mod components;
 mod helpers;
+mod search;
 mod sections;
 mod state;
 mod view;
@@ -0,0 +1,380 @@
+use super::state::SettingsSection;
+
+/// Label and help text of one settings row, which the search matches against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct SettingsRowText {
+    pub(super) section: SettingsSection,
+    pub(super) label: &'static str,
+    pub(super) help: &'static str,
+}
+
+pub(super) const NEW_CARDS: SettingsRowText = SettingsRowText {
+    section: SettingsSection::DailyLimits,
+    label: "New cards per day",
+    help: "Maximum number of new cards introduced today. Keeping this low improves focus and reduces anxiety.",
+};
+pub(super) const NEW_PER_SESSION: SettingsRowText = SettingsRowText {
+    section: SettingsSection::DailyLimits,
+    label: "New cards per session",
+    help: "Most new cards one session introduces, taken from the daily limit. 0 means no extra cap.",
+};
+pub(super) const REVIEW_LIMIT: SettingsRowText = SettingsRowText {
+    section: SettingsSection::DailyLimits,
+    label: "Maximum reviews per day",
+    help: "Upper limit of review cards shown per day. Extra reviews are postponed to the next day.",
+};
+pub(super) const NEW_REVIEW_MIX: SettingsRowText = SettingsRowText {
+    section: SettingsSection::DailyLimits,
+    label: "New card placement",
+    help: "Where new cards appear in a session: after all due reviews, spread evenly between them, or before them.",
+};
+pub(super) const NEW_CARD_ORDER: SettingsRowText = SettingsRowText {
+    section: SettingsSection::DailyLimits,
+    label: "New card order",
+    help: "Which new cards come first: the oldest ones, or a random pick that changes each day.",
+};
+pub(super) const LEARNING_STEPS: SettingsRowText = SettingsRowText {
+    section: SettingsSection::DailyLimits,
+    label: "Learning steps",
+    help: "New cards come back after each delay before they move to regular reviews. Leave empty to skip.",
+};
+pub(super) const PROTECT_OVERLOAD: SettingsRowText = SettingsRowText {
+    section: SettingsSection::DailyLimits,
+    label: "Protect from overload",
+    help: "When enabled, the system delays additional reviews instead of overwhelming you.",
+};
+pub(super) const HOLD_NEW: SettingsRowText = SettingsRowText {
+    section: SettingsSection::DailyLimits,
+    label: "Reviews before new cards",
+    help: "New cards wait until today's due reviews are done.",
+};
+pub(super) const BURY_SIBLINGS: SettingsRowText = SettingsRowText {
+    section: SettingsSection::DailyLimits,
+    label: "One card per note a day",
+    help: "When a card is queued, its reverse and cloze siblings wait until tomorrow.",
+};
+pub(super) const PRESERVE_STABILITY: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Lapses,
+    label: "Preserve stability on lapse",
+    help: "Forgetting a card does not reset all previous progress.",
+};
+pub(super) const LAPSE_MIN_INTERVAL: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Lapses,
+    label: "Minimum interval after lapse",
+    help: "Shortest interval a lapsed card gets once it is back in regular reviews.",
+};
+pub(super) const RELEARNING_STEPS: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Lapses,
+    label: "Relearning steps",
+    help: "A forgotten review card comes back after each delay before it returns to regular reviews. Leave empty to skip.",
+};
+pub(super) const LEECH_THRESHOLD: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Lapses,
+    label: "Leech threshold",
+    help: "After this many lapses a card is tagged \"leech\" so you can rewrite it.",
+};
+pub(super) const LEECH_ACTION: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Lapses,
+    label: "Leech action",
+    help: "Suspending takes a leech out of sessions until you resume it.",
+};
+pub(super) const SAME_SESSION_FAIL_LIMIT: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Lapses,
+    label: "Session fail limit",
+    help: "After this many Agains in a row a card leaves the session and is tagged \"leech\".",
+};
+pub(super) const FSRS_RETENTION: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Fsrs,
+    label: "Target retention",
+    help: "Desired probability of remembering a card. 0.85 balances speed and long-term retention.",
+};
+pub(super) const FSRS_OPTIMIZE: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Fsrs,
+    label: "Enable FSRS optimization",
+    help: "Allows FSRS to adapt scheduling based on your review history.",
+};
+pub(super) const FSRS_OPTIMIZE_AFTER: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Fsrs,
+    label: "Start optimization after",
+    help: "Minimum number of reviews required before FSRS begins self-optimizing.",
+};
+pub(super) const AUDIO_AUTOPLAY: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Audio,
+    label: "Auto-play audio",
+    help: "Automatically plays audio when the card appears.",
+};
+pub(super) const AUDIO_REPLAY: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Audio,
+    label: "Replay audio after answer",
+    help: "Plays audio again after revealing the answer.",
+};
+pub(super) const AUDIO_DELAY: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Audio,
+    label: "Audio delay",
+    help: "Short delay before playback to improve focus.",
+};
+pub(super) const ANSWER_BUTTONS: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Timers,
+    label: "Grade buttons",
+    help: "Two buttons (Again / Good) means fewer choices after each card; four adds Hard and Easy.",
+};
+pub(super) const SHOW_TIMER: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Timers,
+    label: "Show timer",
+    help: "Timers can increase anxiety. Disabled by default.",
+};
+pub(super) const SOFT_REMINDER: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Timers,
+    label: "Soft time reminder",
+    help: "Gentle reminder instead of hard time limits.",
+};
+pub(super) const SOFT_REMINDER_SECS: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Timers,
+    label: "Soft reminder after",
+    help: "How many seconds before the gentle reminder appears.",
+};
+pub(super) const AUTO_ADVANCE: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Timers,
+    label: "Auto-advance cards",
+    help: "Keeps the user fully in control of pacing.",
+};
+pub(super) const AUTO_REVEAL: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Timers,
+    label: "Auto reveal after",
+    help: "Seconds before the answer is revealed automatically.",
+};
+pub(super) const PROGRESSIVE_REVEAL: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Timers,
+    label: "Reveal step by step",
+    help: "Long answers show one paragraph or line per press; grading unlocks after the last.",
+};
+pub(super) const TYPED_ANSWER: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Timers,
+    label: "Type the answer",
+    help: "Type your answer before revealing; differences from the card are highlighted.",
+};
+pub(super) const EASY_DAYS_ENABLED: SettingsRowText = SettingsRowText {
+    section: SettingsSection::EasyDays,
+    label: "Enable easy days",
+    help: "Reduces daily workload on selected days.",
+};
+pub(super) const EASY_DAY_FACTOR: SettingsRowText = SettingsRowText {
+    section: SettingsSection::EasyDays,
+    label: "Easy day load factor",
+    help: "Percentage of normal review volume on easy days.",
+};
+pub(super) const EASY_DAYS: SettingsRowText = SettingsRowText {
+    section: SettingsSection::EasyDays,
+    label: "Easy days",
+    help: "Days with intentionally reduced cognitive load.",
+};
+pub(super) const DECK_COLOR: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Appearance,
+    label: "Color",
+    help: "Shown as a dot next to the deck's name.",
+};
+pub(super) const DECK_ICON: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Appearance,
+    label: "Icon",
+    help: "Shown next to the deck's name, in its color if it has one.",
+};
+pub(super) const MAX_INTERVAL: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Advanced,
+    label: "Maximum interval",
+    help: "Upper bound for review intervals.",
+};
+pub(super) const MIN_INTERVAL: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Advanced,
+    label: "Minimum interval",
+    help: "Prevents overly frequent reviews. Use 10m, 2h, or 1d.",
+};
+pub(super) const LOAD_BALANCE: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Advanced,
+    label: "Balance review load",
+    help: "Moves due dates a day or two toward quieter days without letting recall slip.",
+};
+pub(super) const INTERVAL_FUZZ: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Advanced,
+    label: "Spread new card reviews",
+    help: "Varies a young card's interval a little so cards learned together don't all come due on one day.",
+};
+pub(super) const FSRS_PARAMS: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Advanced,
+    label: "FSRS parameters",
+    help: "The 21 scheduler weights. Leave empty to use the FSRS defaults.",
+};
+pub(super) const FRONT_TEMPLATE: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Advanced,
+    label: "Front template",
+    help: "Layout of the question. Leave empty to show the front as written.",
+};
+pub(super) const BACK_TEMPLATE: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Advanced,
+    label: "Back template",
+    help: "Layout of the revealed answer. Leave empty to show the back, then the extra note.",
+};
+pub(super) const NEW_CARD_PROMPT: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Advanced,
+    label: "New card front",
+    help: "Starter text for the front of each new card in this deck. Leave empty to start blank.",
+};
+pub(super) const NEW_CARD_ANSWER: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Advanced,
+    label: "New card back",
+    help: "Starter text for the back of each new card in this deck. Leave empty to start blank.",
+};
+pub(super) const PRUNE_HISTORY: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Advanced,
+    label: "Review history",
+    help: "Delete older reviews, keeping the newest ones for each card.",
+};
+pub(super) const RESET_FSRS: SettingsRowText = SettingsRowText {
+    section: SettingsSection::Advanced,
+    label: "Reset FSRS data",
+    help: "Resets all scheduling data for this deck.",
+};
+
+/// Every row, in the order the settings page shows them.
+const ROWS: [SettingsRowText; 44] = [
+    NEW_CARDS,
+    NEW_PER_SESSION,
+    REVIEW_LIMIT,
+    NEW_REVIEW_MIX,
+    NEW_CARD_ORDER,
+    LEARNING_STEPS,
+    PROTECT_OVERLOAD,
+    HOLD_NEW,
+    BURY_SIBLINGS,
+    PRESERVE_STABILITY,
+    LAPSE_MIN_INTERVAL,
+    RELEARNING_STEPS,
+    LEECH_THRESHOLD,
+    LEECH_ACTION,
+    SAME_SESSION_FAIL_LIMIT,
+    FSRS_RETENTION,
+    FSRS_OPTIMIZE,
+    FSRS_OPTIMIZE_AFTER,
+    AUDIO_AUTOPLAY,
+    AUDIO_REPLAY,
+    AUDIO_DELAY,
+    ANSWER_BUTTONS,
+    SHOW_TIMER,
+    SOFT_REMINDER,
+    SOFT_REMINDER_SECS,
+    AUTO_ADVANCE,
+    AUTO_REVEAL,
+    PROGRESSIVE_REVEAL,
+    TYPED_ANSWER,
+    EASY_DAYS_ENABLED,
+    EASY_DAY_FACTOR,
+    EASY_DAYS,
+    DECK_COLOR,
+    DECK_ICON,
+    MAX_INTERVAL,
+    MIN_INTERVAL,
+    LOAD_BALANCE,
+    INTERVAL_FUZZ,
+    FSRS_PARAMS,
+    FRONT_TEMPLATE,
+    BACK_TEMPLATE,
+    NEW_CARD_PROMPT,
+    NEW_CARD_ANSWER,
+    PRUNE_HISTORY,
+    RESET_FSRS,
+];
+
+/// The settings page search query.
+///
+/// A row matches when its label, its help text, or its section's label contains the
+/// trimmed query, ignoring case. A blank query matches everything.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(super) struct SettingsSearch {
+    query: String,
+}
+
+impl SettingsSearch {
+    pub(super) fn new(query: String) -> Self {
+        Self { query }
+    }
+
+    /// The query as typed, for the search input.
+    pub(super) fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub(super) fn is_active(&self) -> bool {
+        !self.query.trim().is_empty()
+    }
+
+    pub(super) fn matches_row(&self, row: &SettingsRowText) -> bool {
+        let needle = self.query.trim().to_lowercase();
+        needle.is_empty()
+            || [row.label, row.help, row.section.label()]
+                .iter()
+                .any(|text| text.to_lowercase().contains(&needle))
+    }
+
+    /// Whether any row of `section` matches, so the section stays visible.
+    pub(super) fn matches_section(&self, section: SettingsSection) -> bool {
+        ROWS.iter()
+            .any(|row| row.section == section && self.matches_row(row))
+    }
+
+    pub(super) fn has_matches(&self) -> bool {
+        SettingsSection::ALL
+            .into_iter()
+            .any(|section| self.matches_section(section))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(query: &str) -> SettingsSearch {
+        SettingsSearch::new(query.to_string())
+    }
+
+    #[test]
+    fn blank_query_matches_every_row() {
+        let search = search("   ");
+        assert!(!search.is_active());
+        assert!(ROWS.iter().all(|row| search.matches_row(row)));
+    }
+
+    #[test]
+    fn query_matches_labels_ignoring_case() {
+        let search = search("LEECH");
+        assert!(search.matches_row(&LEECH_THRESHOLD));
+        assert!(search.matches_row(&LEECH_ACTION));
+        assert!(!search.matches_row(&NEW_CARDS));
+        assert!(search.matches_section(SettingsSection::Lapses));
+        assert!(!search.matches_section(SettingsSection::DailyLimits));
+    }
+
+    #[test]
+    fn query_matches_help_text() {
+        let search = search(" anxiety ");
+        assert!(search.matches_row(&NEW_CARDS));
+        assert!(search.matches_row(&SHOW_TIMER));
+        assert!(!search.matches_row(&REVIEW_LIMIT));
+        assert!(search.matches_section(SettingsSection::Timers));
+        assert!(!search.matches_section(SettingsSection::Audio));
+    }
+
+    #[test]
+    fn section_label_match_keeps_the_whole_section() {
+        let search = search("appearance");
+        assert!(search.matches_row(&DECK_COLOR));
+        assert!(search.matches_row(&DECK_ICON));
+        assert!(!search.matches_row(&FSRS_PARAMS));
+    }
+
+    #[test]
+    fn unmatched_query_hides_every_section() {
+        let unmatched = search("zzz");
+        assert!(unmatched.is_active());
+        assert!(!unmatched.has_matches());
+        assert!(search("retention").has_matches());
+    }
+}
@@ -1,7 +1,13 @@
 use dioxus::prelude::*;
+use learn_core::model::{CardSide, CardTemplate, DeckColor, DeckIcon};
 
-use super::components::SettingsAccordionSection;
-use super::state::{DeckSettingsErrors, DeckSettingsForm, SaveState, SettingsSection};
+use super::components::{SettingsAccordionSection, SettingsRow};
+use super::helpers::default_fsrs_parameters_placeholder;
+use super::search::{self, SettingsSearch};
+use super::state::{
+    DeckSettingsErrors, DeckSettingsForm, PruneControls, PruneState, SaveState, SettingsSection,
+};
+use crate::vm::{deck_color_class, deck_color_label, deck_icon_glyph, deck_icon_label};
 
 const EASY_DAY_OPTIONS: [(&str, u8, &str); 7] = [
     ("Mon", 1_u8 << 0, "Monday"),
@@ -13,6 +19,9 @@ const EASY_DAY_OPTIONS: [(&str, u8, &str); 7] = [
     ("Sun", 1_u8 << 6, "Sunday"),
 ];
 
+/// Shown under the template fields; kept out of `rsx!`, where `{{` escapes a brace.
+const TEMPLATE_HINT: &str = "Place {{front}}, {{back}}, and {{extra}} in the layout.";
+
 pub(super) fn daily_limits_section(
     form: Signal<DeckSettingsForm>,
     errors: Signal<DeckSettingsErrors>,
@@ -21,7 +30,6 @@ pub(super) fn daily_limits_section(
 ) -> Element {
     rsx! {
         SettingsAccordionSection {
-            label: "Daily Limits",
             section: SettingsSection::DailyLimits,
             expanded: expanded_section() == Some(SettingsSection::DailyLimits),
             on_toggle: expanded_section,
@@ -39,8 +47,14 @@ fn daily_limits_card(
     rsx! {
         div { class: "settings-card",
             {daily_limits_new_cards_row(form, errors, save_state)}
+            {daily_limits_new_per_session_row(form, errors, save_state)}
             {daily_limits_review_limit_row(form, errors, save_state)}
+            {daily_limits_mix_row(form, errors, save_state)}
+            {daily_limits_new_order_row(form, errors, save_state)}
+            {daily_limits_learning_steps_row(form, errors, save_state)}
             {daily_limits_protect_row(form, save_state)}
+            {daily_limits_hold_new_row(form, save_state)}
+            {daily_limits_bury_siblings_row(form, save_state)}
         }
     }
 }
@@ -54,15 +68,7 @@ fn daily_limits_new_cards_row(
     let errors_value = errors();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { r#for: "new-cards", "New cards per day" }
-                span {
-                    class: "settings-row__help",
-                    title: "Maximum number of new cards introduced today. Keeping this low improves focus and reduces anxiety.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::NEW_CARDS, input_id: "new-cards",
             div { class: "settings-row__field",
                 input {
                     id: "new-cards",
@@ -93,7 +99,7 @@ fn daily_limits_new_cards_row(
     }
 }
 
-fn daily_limits_review_limit_row(
+fn daily_limits_new_per_session_row(
     mut form: Signal<DeckSettingsForm>,
     mut errors: Signal<DeckSettingsErrors>,
     mut save_state: Signal<SaveState>,
@@ -102,15 +108,47 @@ fn daily_limits_review_limit_row(
     let errors_value = errors();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { r#for: "review-limit", "Maximum reviews per day" }
-                span {
-                    class: "settings-row__help",
-                    title: "Upper limit of review cards shown per day. Extra reviews are postponed to the next day.",
-                    "?"
+        SettingsRow { row: search::NEW_PER_SESSION, input_id: "new-per-session",
+            div { class: "settings-row__field",
+                input {
+                    id: "new-per-session",
+                    class: if errors_value.new_per_session.is_some() {
+                        "editor-input settings-input editor-input--error"
+                    } else {
+                        "editor-input settings-input"
+                    },
+                    r#type: "number",
+                    min: "0",
+                    inputmode: "numeric",
+                    value: "{form_value.new_per_session}",
+                    oninput: move |evt| {
+                        let mut next = form();
+                        next.new_per_session = evt.value();
+                        form.set(next);
+                        let mut next_errors = errors();
+                        next_errors.new_per_session = None;
+                        errors.set(next_errors);
+                        save_state.set(SaveState::Idle);
+                    },
+                }
+                if let Some(message) = errors_value.new_per_session {
+                    p { class: "editor-error", "{message}" }
                 }
             }
+        }
+    }
+}
+
+fn daily_limits_review_limit_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut errors: Signal<DeckSettingsErrors>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+    let errors_value = errors();
+
+    rsx! {
+        SettingsRow { row: search::REVIEW_LIMIT, input_id: "review-limit",
             div { class: "settings-row__field",
                 input {
                     id: "review-limit",
@@ -141,22 +179,130 @@ fn daily_limits_review_limit_row(
     }
 }
 
-fn daily_limits_protect_row(
+fn daily_limits_mix_row(
     mut form: Signal<DeckSettingsForm>,
+    mut errors: Signal<DeckSettingsErrors>,
     mut save_state: Signal<SaveState>,
 ) -> Element {
     let form_value = form();
+    let errors_value = errors();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { "Protect from overload" }
-                span {
-                    class: "settings-row__help",
-                    title: "When enabled, the system delays additional reviews instead of overwhelming you.",
-                    "?"
+        SettingsRow { row: search::NEW_REVIEW_MIX, input_id: "new-review-mix",
+            div { class: "settings-row__field",
+                div { class: "settings-select-wrap",
+                    select {
+                        id: "new-review-mix",
+                        class: "settings-select",
+                        value: "{form_value.new_review_mix}",
+                        onchange: move |evt| {
+                            let mut next = form();
+                            next.new_review_mix = evt.value();
+                            form.set(next);
+                            let mut next_errors = errors();
+                            next_errors.new_review_mix = None;
+                            errors.set(next_errors);
+                            save_state.set(SaveState::Idle);
+                        },
+                        option { value: "after_reviews", "After reviews" }
+                        option { value: "interleaved", "Interleaved" }
+                        option { value: "front_load", "New cards first" }
+                    }
+                    span { class: "settings-select-caret" }
+                }
+                if let Some(message) = errors_value.new_review_mix {
+                    p { class: "editor-error", "{message}" }
+                }
+            }
+        }
+    }
+}
+
+fn daily_limits_new_order_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut errors: Signal<DeckSettingsErrors>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+    let errors_value = errors();
+
+    rsx! {
+        SettingsRow { row: search::NEW_CARD_ORDER, input_id: "new-card-order",
+            div { class: "settings-row__field",
+                div { class: "settings-select-wrap",
+                    select {
+                        id: "new-card-order",
+                        class: "settings-select",
+                        value: "{form_value.new_card_order}",
+                        onchange: move |evt| {
+                            let mut next = form();
+                            next.new_card_order = evt.value();
+                            form.set(next);
+                            let mut next_errors = errors();
+                            next_errors.new_card_order = None;
+                            errors.set(next_errors);
+                            save_state.set(SaveState::Idle);
+                        },
+                        option { value: "sequential", "Oldest first" }
+                        option { value: "random", "Random, new each day" }
+                    }
+                    span { class: "settings-select-caret" }
+                }
+                if let Some(message) = errors_value.new_card_order {
+                    p { class: "editor-error", "{message}" }
                 }
             }
+        }
+    }
+}
+
+fn daily_limits_learning_steps_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut errors: Signal<DeckSettingsErrors>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+    let errors_value = errors();
+
+    rsx! {
+        SettingsRow { row: search::LEARNING_STEPS, input_id: "learning-steps",
+            div { class: "settings-row__field settings-row__field--wide",
+                input {
+                    id: "learning-steps",
+                    class: if errors_value.learning_steps.is_some() {
+                        "editor-input settings-input editor-input--error"
+                    } else {
+                        "editor-input settings-input"
+                    },
+                    r#type: "text",
+                    value: "{form_value.learning_steps}",
+                    oninput: move |evt| {
+                        let mut next = form();
+                        next.learning_steps = evt.value();
+                        form.set(next);
+                        let mut next_errors = errors();
+                        next_errors.learning_steps = None;
+                        errors.set(next_errors);
+                        save_state.set(SaveState::Idle);
+                    },
+                }
+                p { class: "settings-field-hint", "Use 1m 10m." }
+                if let Some(message) = errors_value.learning_steps {
+                    p { class: "editor-error", "{message}" }
+                }
+            }
+        }
+    }
+}
+
+fn daily_limits_protect_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+
+    rsx! {
+        SettingsRow { row: search::PROTECT_OVERLOAD,
             div { class: "settings-row__field settings-row__field--toggle",
                 button {
                     class: "settings-toggle",
@@ -175,6 +321,58 @@ fn daily_limits_protect_row(
     }
 }
 
+fn daily_limits_hold_new_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+
+    rsx! {
+        SettingsRow { row: search::HOLD_NEW,
+            div { class: "settings-row__field settings-row__field--toggle",
+                button {
+                    class: "settings-toggle",
+                    r#type: "button",
+                    role: "switch",
+                    aria_checked: "{form_value.hold_new_until_reviews_done}",
+                    onclick: move |_| {
+                        let mut next = form();
+                        next.hold_new_until_reviews_done = !next.hold_new_until_reviews_done;
+                        form.set(next);
+                        save_state.set(SaveState::Idle);
+                    },
+                }
+            }
+        }
+    }
+}
+
+fn daily_limits_bury_siblings_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+
+    rsx! {
+        SettingsRow { row: search::BURY_SIBLINGS,
+            div { class: "settings-row__field settings-row__field--toggle",
+                button {
+                    class: "settings-toggle",
+                    r#type: "button",
+                    role: "switch",
+                    aria_checked: "{form_value.bury_siblings}",
+                    onclick: move |_| {
+                        let mut next = form();
+                        next.bury_siblings = !next.bury_siblings;
+                        form.set(next);
+                        save_state.set(SaveState::Idle);
+                    },
+                }
+            }
+        }
+    }
+}
+
 pub(super) fn lapses_section(
     mut form: Signal<DeckSettingsForm>,
     mut errors: Signal<DeckSettingsErrors>,
@@ -186,21 +384,12 @@ pub(super) fn lapses_section(
 
     rsx! {
         SettingsAccordionSection {
-            label: "Lapses",
             section: SettingsSection::Lapses,
             expanded: expanded_section() == Some(SettingsSection::Lapses),
             on_toggle: expanded_section,
             help_title: Some("Lapse settings for failed review cards."),
             div { class: "settings-card",
-                div { class: "settings-row",
-                    div { class: "settings-row__label",
-                        label { "Preserve stability on lapse" }
-                        span {
-                            class: "settings-row__help",
-                            title: "Forgetting a card does not reset all previous progress.",
-                            "?"
-                        }
-                    }
+                SettingsRow { row: search::PRESERVE_STABILITY,
                     div { class: "settings-row__field settings-row__field--toggle",
                         button {
                             class: "settings-toggle",
@@ -217,15 +406,7 @@ pub(super) fn lapses_section(
                         }
                     }
                 }
-                div { class: "settings-row",
-                    div { class: "settings-row__label",
-                        label { r#for: "lapse-min-interval", "Minimum interval after lapse" }
-                        span {
-                            class: "settings-row__help",
-                            title: "Small delay after pressing \"Again\" to avoid immediate pressure.",
-                            "?"
-                        }
-                    }
+                SettingsRow { row: search::LAPSE_MIN_INTERVAL, input_id: "lapse-min-interval",
                     div { class: "settings-row__field settings-row__field--wide",
                         input {
                             id: "lapse-min-interval",
@@ -252,6 +433,169 @@ pub(super) fn lapses_section(
                         }
                     }
                 }
+                {relearning_steps_row(form, errors, save_state)}
+                {leech_threshold_row(form, errors, save_state)}
+                {leech_action_row(form, errors, save_state)}
+                {same_session_fail_limit_row(form, errors, save_state)}
+            }
+        }
+    }
+}
+
+fn relearning_steps_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut errors: Signal<DeckSettingsErrors>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+    let errors_value = errors();
+
+    rsx! {
+        SettingsRow { row: search::RELEARNING_STEPS, input_id: "relearning-steps",
+            div { class: "settings-row__field settings-row__field--wide",
+                input {
+                    id: "relearning-steps",
+                    class: if errors_value.relearning_steps.is_some() {
+                        "editor-input settings-input editor-input--error"
+                    } else {
+                        "editor-input settings-input"
+                    },
+                    r#type: "text",
+                    value: "{form_value.relearning_steps}",
+                    oninput: move |evt| {
+                        let mut next = form();
+                        next.relearning_steps = evt.value();
+                        form.set(next);
+                        let mut next_errors = errors();
+                        next_errors.relearning_steps = None;
+                        errors.set(next_errors);
+                        save_state.set(SaveState::Idle);
+                    },
+                }
+                p { class: "settings-field-hint", "Use 10m." }
+                if let Some(message) = errors_value.relearning_steps {
+                    p { class: "editor-error", "{message}" }
+                }
+            }
+        }
+    }
+}
+
+fn leech_threshold_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut errors: Signal<DeckSettingsErrors>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+    let errors_value = errors();
+
+    rsx! {
+        SettingsRow { row: search::LEECH_THRESHOLD, input_id: "leech-threshold",
+            div { class: "settings-row__field",
+                input {
+                    id: "leech-threshold",
+                    class: if errors_value.leech_threshold.is_some() {
+                        "editor-input settings-input editor-input--error"
+                    } else {
+                        "editor-input settings-input"
+                    },
+                    r#type: "number",
+                    min: "0",
+                    inputmode: "numeric",
+                    value: "{form_value.leech_threshold}",
+                    oninput: move |evt| {
+                        let mut next = form();
+                        next.leech_threshold = evt.value();
+                        form.set(next);
+                        let mut next_errors = errors();
+                        next_errors.leech_threshold = None;
+                        errors.set(next_errors);
+                        save_state.set(SaveState::Idle);
+                    },
+                }
+                p { class: "settings-field-hint", "Lapses; 0 turns it off." }
+                if let Some(message) = errors_value.leech_threshold {
+                    p { class: "editor-error", "{message}" }
+                }
+            }
+        }
+    }
+}
+
+fn same_session_fail_limit_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut errors: Signal<DeckSettingsErrors>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+    let errors_value = errors();
+
+    rsx! {
+        SettingsRow { row: search::SAME_SESSION_FAIL_LIMIT, input_id: "same-session-fail-limit",
+            div { class: "settings-row__field",
+                input {
+                    id: "same-session-fail-limit",
+                    class: if errors_value.same_session_fail_limit.is_some() {
+                        "editor-input settings-input editor-input--error"
+                    } else {
+                        "editor-input settings-input"
+                    },
+                    r#type: "number",
+                    min: "0",
+                    inputmode: "numeric",
+                    value: "{form_value.same_session_fail_limit}",
+                    oninput: move |evt| {
+                        let mut next = form();
+                        next.same_session_fail_limit = evt.value();
+                        form.set(next);
+                        let mut next_errors = errors();
+                        next_errors.same_session_fail_limit = None;
+                        errors.set(next_errors);
+                        save_state.set(SaveState::Idle);
+                    },
+                }
+                p { class: "settings-field-hint", "Agains in a row; 0 turns it off." }
+                if let Some(message) = errors_value.same_session_fail_limit {
+                    p { class: "editor-error", "{message}" }
+                }
+            }
+        }
+    }
+}
+
+fn leech_action_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut errors: Signal<DeckSettingsErrors>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+    let errors_value = errors();
+
+    rsx! {
+        SettingsRow { row: search::LEECH_ACTION, input_id: "leech-action",
+            div { class: "settings-row__field",
+                div { class: "settings-select-wrap",
+                    select {
+                        id: "leech-action",
+                        class: "settings-select",
+                        value: "{form_value.leech_action}",
+                        onchange: move |evt| {
+                            let mut next = form();
+                            next.leech_action = evt.value();
+                            form.set(next);
+                            let mut next_errors = errors();
+                            next_errors.leech_action = None;
+                            errors.set(next_errors);
+                            save_state.set(SaveState::Idle);
+                        },
+                        option { value: "tag", "Tag only" }
+                        option { value: "suspend", "Tag and suspend" }
+                    }
+                    span { class: "settings-select-caret" }
+                }
+                if let Some(message) = errors_value.leech_action {
+                    p { class: "editor-error", "{message}" }
+                }
             }
         }
     }
@@ -265,7 +609,6 @@ pub(super) fn fsrs_section(
 ) -> Element {
     rsx! {
         SettingsAccordionSection {
-            label: "FSRS",
             section: SettingsSection::Fsrs,
             expanded: expanded_section() == Some(SettingsSection::Fsrs),
             on_toggle: expanded_section,
@@ -298,15 +641,7 @@ fn fsrs_retention_row(
     let errors_value = errors();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { r#for: "fsrs-retention", "Target retention" }
-                span {
-                    class: "settings-row__help",
-                    title: "Desired probability of remembering a card. 0.85 balances speed and long-term retention.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::FSRS_RETENTION, input_id: "fsrs-retention",
             div { class: "settings-row__field settings-row__field--wide",
                 input {
                     id: "fsrs-retention",
@@ -342,15 +677,7 @@ fn fsrs_optimize_toggle_row(
     let form_value = form();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { "Enable FSRS optimization" }
-                span {
-                    class: "settings-row__help",
-                    title: "Allows FSRS to adapt scheduling based on your review history.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::FSRS_OPTIMIZE,
             div { class: "settings-row__field settings-row__field--toggle",
                 button {
                     class: "settings-toggle",
@@ -378,15 +705,7 @@ fn fsrs_optimize_after_row(
     let errors_value = errors();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { r#for: "fsrs-optimize-after", "Start optimization after" }
-                span {
-                    class: "settings-row__help",
-                    title: "Minimum number of reviews required before FSRS begins self-optimizing.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::FSRS_OPTIMIZE_AFTER, input_id: "fsrs-optimize-after",
             div { class: "settings-row__field settings-row__field--wide",
                 input {
                     id: "fsrs-optimize-after",
@@ -425,7 +744,6 @@ pub(super) fn audio_section(
 ) -> Element {
     rsx! {
         SettingsAccordionSection {
-            label: "Audio",
             section: SettingsSection::Audio,
             expanded: expanded_section() == Some(SettingsSection::Audio),
             on_toggle: expanded_section,
@@ -451,15 +769,7 @@ fn audio_card(
 
 fn audio_autoplay_row(mut autoplay_audio: Signal<bool>) -> Element {
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { "Auto-play audio" }
-                span {
-                    class: "settings-row__help",
-                    title: "Automatically plays audio when the card appears.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::AUDIO_AUTOPLAY,
             div { class: "settings-row__field settings-row__field--toggle",
                 button {
                     class: "settings-toggle",
@@ -475,15 +785,7 @@ fn audio_autoplay_row(mut autoplay_audio: Signal<bool>) -> Element {
 
 fn audio_replay_row(mut replay_audio_after_answer: Signal<bool>) -> Element {
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { "Replay audio after answer" }
-                span {
-                    class: "settings-row__help",
-                    title: "Plays audio again after revealing the answer.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::AUDIO_REPLAY,
             div { class: "settings-row__field settings-row__field--toggle",
                 button {
                     class: "settings-toggle",
@@ -501,15 +803,7 @@ fn audio_replay_row(mut replay_audio_after_answer: Signal<bool>) -> Element {
 
 fn audio_delay_row(mut audio_delay_ms: Signal<String>) -> Element {
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { r#for: "audio-delay", "Audio delay" }
-                span {
-                    class: "settings-row__help",
-                    title: "Short delay before playback to improve focus.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::AUDIO_DELAY, input_id: "audio-delay",
             div { class: "settings-row__field settings-row__field--wide",
                 div { class: "settings-inline-input",
                     input {
@@ -536,7 +830,6 @@ pub(super) fn timers_section(
 ) -> Element {
     rsx! {
         SettingsAccordionSection {
-            label: "Timers",
             section: SettingsSection::Timers,
             expanded: expanded_section() == Some(SettingsSection::Timers),
             on_toggle: expanded_section,
@@ -546,18 +839,111 @@ pub(super) fn timers_section(
     }
 }
 
-fn timers_card(
-    form: Signal<DeckSettingsForm>,
-    errors: Signal<DeckSettingsErrors>,
-    save_state: Signal<SaveState>,
+fn timers_card(
+    form: Signal<DeckSettingsForm>,
+    errors: Signal<DeckSettingsErrors>,
+    save_state: Signal<SaveState>,
+) -> Element {
+    rsx! {
+        div { class: "settings-card",
+            {timer_show_row(form, save_state)}
+            {timer_soft_reminder_row(form, save_state)}
+            {timer_soft_reminder_secs_row(form, errors, save_state)}
+            {timer_auto_advance_row(form, save_state)}
+            {timer_auto_reveal_row(form, errors, save_state)}
+            {progressive_reveal_row(form, save_state)}
+            {typed_answer_row(form, save_state)}
+            {answer_buttons_row(form, errors, save_state)}
+        }
+    }
+}
+
+fn progressive_reveal_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+
+    rsx! {
+        SettingsRow { row: search::PROGRESSIVE_REVEAL,
+            div { class: "settings-row__field settings-row__field--toggle",
+                button {
+                    class: "settings-toggle",
+                    r#type: "button",
+                    role: "switch",
+                    aria_checked: "{form_value.progressive_reveal}",
+                    onclick: move |_| {
+                        let mut next = form();
+                        next.progressive_reveal = !next.progressive_reveal;
+                        form.set(next);
+                        save_state.set(SaveState::Idle);
+                    },
+                }
+            }
+        }
+    }
+}
+
+fn typed_answer_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+
+    rsx! {
+        SettingsRow { row: search::TYPED_ANSWER,
+            div { class: "settings-row__field settings-row__field--toggle",
+                button {
+                    class: "settings-toggle",
+                    r#type: "button",
+                    role: "switch",
+                    aria_checked: "{form_value.typed_answer}",
+                    onclick: move |_| {
+                        let mut next = form();
+                        next.typed_answer = !next.typed_answer;
+                        form.set(next);
+                        save_state.set(SaveState::Idle);
+                    },
+                }
+            }
+        }
+    }
+}
+
+fn answer_buttons_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut errors: Signal<DeckSettingsErrors>,
+    mut save_state: Signal<SaveState>,
 ) -> Element {
+    let form_value = form();
+    let errors_value = errors();
+
     rsx! {
-        div { class: "settings-card",
-            {timer_show_row(form, save_state)}
-            {timer_soft_reminder_row(form, save_state)}
-            {timer_soft_reminder_secs_row(form, errors, save_state)}
-            {timer_auto_advance_row(form, save_state)}
-            {timer_auto_reveal_row(form, errors, save_state)}
+        SettingsRow { row: search::ANSWER_BUTTONS, input_id: "answer-buttons",
+            div { class: "settings-row__field",
+                div { class: "settings-select-wrap",
+                    select {
+                        id: "answer-buttons",
+                        class: "settings-select",
+                        value: "{form_value.answer_buttons}",
+                        onchange: move |evt| {
+                            let mut next = form();
+                            next.answer_buttons = evt.value();
+                            form.set(next);
+                            let mut next_errors = errors();
+                            next_errors.answer_buttons = None;
+                            errors.set(next_errors);
+                            save_state.set(SaveState::Idle);
+                        },
+                        option { value: "four", "Four (Again, Hard, Good, Easy)" }
+                        option { value: "two", "Two (Again, Good)" }
+                    }
+                    span { class: "settings-select-caret" }
+                }
+                if let Some(message) = errors_value.answer_buttons {
+                    p { class: "editor-error", "{message}" }
+                }
+            }
         }
     }
 }
@@ -569,15 +955,7 @@ fn timer_show_row(
     let form_value = form();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { "Show timer" }
-                span {
-                    class: "settings-row__help",
-                    title: "Timers can increase anxiety. Disabled by default.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::SHOW_TIMER,
             div { class: "settings-row__field settings-row__field--toggle",
                 button {
                     class: "settings-toggle",
@@ -603,15 +981,7 @@ fn timer_soft_reminder_row(
     let form_value = form();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { "Soft time reminder" }
-                span {
-                    class: "settings-row__help",
-                    title: "Gentle reminder instead of hard time limits.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::SOFT_REMINDER,
             div { class: "settings-row__field settings-row__field--toggle",
                 button {
                     class: "settings-toggle",
@@ -639,15 +1009,7 @@ fn timer_soft_reminder_secs_row(
     let errors_value = errors();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { r#for: "soft-reminder-secs", "Soft reminder after" }
-                span {
-                    class: "settings-row__help",
-                    title: "How many seconds before the gentle reminder appears.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::SOFT_REMINDER_SECS, input_id: "soft-reminder-secs",
             div { class: "settings-row__field settings-row__field--wide",
                 div { class: "settings-inline-input",
                     input {
@@ -690,15 +1052,7 @@ fn timer_auto_advance_row(
     let form_value = form();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { "Auto-advance cards" }
-                span {
-                    class: "settings-row__help",
-                    title: "Keeps the user fully in control of pacing.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::AUTO_ADVANCE,
             div { class: "settings-row__field settings-row__field--toggle",
                 button {
                     class: "settings-toggle",
@@ -726,15 +1080,7 @@ fn timer_auto_reveal_row(
     let errors_value = errors();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { r#for: "auto-reveal-secs", "Auto reveal after" }
-                span {
-                    class: "settings-row__help",
-                    title: "Seconds before the answer is revealed automatically.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::AUTO_REVEAL, input_id: "auto-reveal-secs",
             div { class: "settings-row__field settings-row__field--wide",
                 div { class: "settings-inline-input",
                     input {
@@ -778,7 +1124,6 @@ pub(super) fn easy_days_section(
 ) -> Element {
     rsx! {
         SettingsAccordionSection {
-            label: "Easy Days",
             section: SettingsSection::EasyDays,
             expanded: expanded_section() == Some(SettingsSection::EasyDays),
             on_toggle: expanded_section,
@@ -809,15 +1154,7 @@ fn easy_days_toggle_row(
     let form_value = form();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { "Enable easy days" }
-                span {
-                    class: "settings-row__help",
-                    title: "Reduces daily workload on selected days.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::EASY_DAYS_ENABLED,
             div { class: "settings-row__field settings-row__field--toggle",
                 button {
                     class: "settings-toggle",
@@ -845,15 +1182,7 @@ fn easy_days_factor_row(
     let errors_value = errors();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { r#for: "easy-day-factor", "Easy day load factor" }
-                span {
-                    class: "settings-row__help",
-                    title: "Percentage of normal review volume on easy days.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::EASY_DAY_FACTOR, input_id: "easy-day-factor",
             div { class: "settings-row__field settings-row__field--wide",
                 input {
                     id: "easy-day-factor",
@@ -891,15 +1220,7 @@ fn easy_days_picker_row(
     let errors_value = errors();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { r#for: "easy-days", "Easy days" }
-                span {
-                    class: "settings-row__help",
-                    title: "Days with intentionally reduced cognitive load.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::EASY_DAYS, input_id: "easy-days",
             div { class: "settings-row__field settings-row__field--wide",
                 div { class: "settings-day-picker",
                     for (label, bit, title) in EASY_DAY_OPTIONS {
@@ -937,22 +1258,128 @@ fn easy_days_picker_row(
     }
 }
 
+pub(super) fn appearance_section(
+    form: Signal<DeckSettingsForm>,
+    save_state: Signal<SaveState>,
+    expanded_section: Signal<Option<SettingsSection>>,
+) -> Element {
+    rsx! {
+        SettingsAccordionSection {
+            section: SettingsSection::Appearance,
+            expanded: expanded_section() == Some(SettingsSection::Appearance),
+            on_toggle: expanded_section,
+            help_title: Some("How this deck is marked in deck lists."),
+            div { class: "settings-card",
+                {appearance_color_row(form, save_state)}
+                {appearance_icon_row(form, save_state)}
+            }
+        }
+    }
+}
+
+fn appearance_color_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let selected = form().color;
+
+    rsx! {
+        SettingsRow { row: search::DECK_COLOR, input_id: "deck-color",
+            div { class: "settings-row__field settings-row__field--wide",
+                div { id: "deck-color", class: "settings-swatches", role: "radiogroup",
+                    button {
+                        class: if selected.is_none() {
+                            "settings-swatch settings-swatch--none settings-swatch--active"
+                        } else {
+                            "settings-swatch settings-swatch--none"
+                        },
+                        r#type: "button",
+                        role: "radio",
+                        title: "No color",
+                        aria_checked: "{selected.is_none()}",
+                        onclick: move |_| {
+                            let mut next = form();
+                            next.color = None;
+                            form.set(next);
+                            save_state.set(SaveState::Idle);
+                        },
+                    }
+                    for color in DeckColor::ALL {
+                        button {
+                            class: if selected == Some(color) {
+                                "settings-swatch settings-swatch--active {deck_color_class(selected)}"
+                            } else {
+                                "settings-swatch {deck_color_class(Some(color))}"
+                            },
+                            r#type: "button",
+                            role: "radio",
+                            title: "{deck_color_label(color)}",
+                            aria_checked: "{selected == Some(color)}",
+                            onclick: move |_| {
+                                let mut next = form();
+                                next.color = Some(color);
+                                form.set(next);
+                                save_state.set(SaveState::Idle);
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn appearance_icon_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let selected = form().icon.map_or("", DeckIcon::as_str);
+
+    rsx! {
+        SettingsRow { row: search::DECK_ICON, input_id: "deck-icon",
+            div { class: "settings-row__field",
+                div { class: "settings-select-wrap",
+                    select {
+                        id: "deck-icon",
+                        class: "settings-select",
+                        value: "{selected}",
+                        onchange: move |evt| {
+                            let mut next = form();
+                            next.icon = evt.value().parse().ok();
+                            form.set(next);
+                            save_state.set(SaveState::Idle);
+                        },
+                        option { value: "", "None" }
+                        for icon in DeckIcon::ALL {
+                            option { value: "{icon.as_str()}",
+                                "{deck_icon_glyph(icon)} {deck_icon_label(icon)}"
+                            }
+                        }
+                    }
+                    span { class: "settings-select-caret" }
+                }
+            }
+        }
+    }
+}
+
 pub(super) fn advanced_section(
     form: Signal<DeckSettingsForm>,
     errors: Signal<DeckSettingsErrors>,
     save_state: Signal<SaveState>,
     reset_state: Signal<super::state::ResetState>,
     show_reset_modal: Signal<bool>,
+    prune: PruneControls,
     expanded_section: Signal<Option<SettingsSection>>,
 ) -> Element {
     rsx! {
         SettingsAccordionSection {
-            label: "Advanced",
             section: SettingsSection::Advanced,
             expanded: expanded_section() == Some(SettingsSection::Advanced),
             on_toggle: expanded_section,
             help_title: Some("Power-user settings."),
             {advanced_card(form, errors, save_state)}
+            {advanced_prune_card(prune)}
             {advanced_reset_button(reset_state, show_reset_modal)}
         }
     }
@@ -967,7 +1394,13 @@ fn advanced_card(
         div { class: "settings-card",
             {advanced_max_interval_row(form, errors, save_state)}
             {advanced_min_interval_row(form, errors, save_state)}
-            {advanced_fsrs_params_row(form)}
+            {advanced_load_balance_row(form, save_state)}
+            {advanced_interval_fuzz_row(form, save_state)}
+            {advanced_fsrs_params_row(form, errors, save_state)}
+            {advanced_template_row(form, errors, save_state, CardSide::Prompt)}
+            {advanced_template_row(form, errors, save_state, CardSide::Answer)}
+            {advanced_new_card_text_row(form, save_state, CardSide::Prompt)}
+            {advanced_new_card_text_row(form, save_state, CardSide::Answer)}
         }
     }
 }
@@ -981,15 +1414,7 @@ fn advanced_max_interval_row(
     let errors_value = errors();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { r#for: "max-interval", "Maximum interval" }
-                span {
-                    class: "settings-row__help",
-                    title: "Upper bound for review intervals.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::MAX_INTERVAL, input_id: "max-interval",
             div { class: "settings-row__field settings-row__field--wide",
                 div { class: "settings-inline-input",
                     input {
@@ -1032,15 +1457,7 @@ fn advanced_min_interval_row(
     let errors_value = errors();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { r#for: "min-interval", "Minimum interval" }
-                span {
-                    class: "settings-row__help",
-                    title: "Prevents overly frequent reviews. Use 10m, 2h, or 1d.",
-                    "?"
-                }
-            }
+        SettingsRow { row: search::MIN_INTERVAL, input_id: "min-interval",
             div { class: "settings-row__field settings-row__field--wide",
                 div { class: "settings-inline-input",
                     input {
@@ -1074,26 +1491,253 @@ fn advanced_min_interval_row(
     }
 }
 
-fn advanced_fsrs_params_row(form: Signal<DeckSettingsForm>) -> Element {
+fn advanced_load_balance_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+
+    rsx! {
+        SettingsRow { row: search::LOAD_BALANCE,
+            div { class: "settings-row__field settings-row__field--toggle",
+                button {
+                    class: "settings-toggle",
+                    r#type: "button",
+                    role: "switch",
+                    aria_checked: "{form_value.load_balance}",
+                    onclick: move |_| {
+                        let mut next = form();
+                        next.load_balance = !next.load_balance;
+                        form.set(next);
+                        save_state.set(SaveState::Idle);
+                    },
+                }
+            }
+        }
+    }
+}
+
+fn advanced_interval_fuzz_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
     let form_value = form();
 
     rsx! {
-        div { class: "settings-row",
-            div { class: "settings-row__label",
-                label { r#for: "fsrs-params-readonly", "FSRS parameters" }
-                span {
-                    class: "settings-row__help",
-                    title: "Displays current learned parameters.",
-                    "?"
+        SettingsRow { row: search::INTERVAL_FUZZ,
+            div { class: "settings-row__field settings-row__field--toggle",
+                button {
+                    class: "settings-toggle",
+                    r#type: "button",
+                    role: "switch",
+                    aria_checked: "{form_value.interval_fuzz}",
+                    onclick: move |_| {
+                        let mut next = form();
+                        next.interval_fuzz = !next.interval_fuzz;
+                        form.set(next);
+                        save_state.set(SaveState::Idle);
+                    },
                 }
             }
+        }
+    }
+}
+
+fn advanced_fsrs_params_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut errors: Signal<DeckSettingsErrors>,
+    mut save_state: Signal<SaveState>,
+) -> Element {
+    let form_value = form();
+    let errors_value = errors();
+    let placeholder = default_fsrs_parameters_placeholder();
+
+    rsx! {
+        SettingsRow { row: search::FSRS_PARAMS, input_id: "fsrs-params",
             div { class: "settings-row__field settings-row__field--wide",
                 textarea {
-                    id: "fsrs-params-readonly",
-                    class: "editor-input settings-input settings-fsrs-textarea",
+                    id: "fsrs-params",
+                    class: if errors_value.fsrs_parameters.is_some() {
+                        "editor-input settings-input settings-fsrs-textarea editor-input--error"
+                    } else {
+                        "editor-input settings-input settings-fsrs-textarea"
+                    },
                     rows: "3",
+                    placeholder: "{placeholder}",
                     value: "{form_value.fsrs_parameters}",
-                    disabled: true,
+                    oninput: move |evt| {
+                        let mut next = form();
+                        next.fsrs_parameters = evt.value();
+                        form.set(next);
+                        let mut next_errors = errors();
+                        next_errors.fsrs_parameters = None;
+                        errors.set(next_errors);
+                        save_state.set(SaveState::Idle);
+                    },
+                }
+                p { class: "settings-field-hint", "Separate weights with commas or spaces." }
+                if let Some(message) = errors_value.fsrs_parameters {
+                    p { class: "editor-error", "{message}" }
+                }
+            }
+        }
+    }
+}
+
+fn advanced_template_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut errors: Signal<DeckSettingsErrors>,
+    mut save_state: Signal<SaveState>,
+    side: CardSide,
+) -> Element {
+    let form_value = form();
+    let errors_value = errors();
+    let (id, row, value, error, placeholder) = match side {
+        CardSide::Prompt => (
+            "front-template",
+            search::FRONT_TEMPLATE,
+            form_value.front_template,
+            errors_value.front_template,
+            CardTemplate::DEFAULT_FRONT,
+        ),
+        CardSide::Answer => (
+            "back-template",
+            search::BACK_TEMPLATE,
+            form_value.back_template,
+            errors_value.back_template,
+            CardTemplate::DEFAULT_BACK,
+        ),
+    };
+
+    rsx! {
+        SettingsRow { row, input_id: id,
+            div { class: "settings-row__field settings-row__field--wide",
+                textarea {
+                    id: "{id}",
+                    class: if error.is_some() {
+                        "editor-input settings-input settings-fsrs-textarea editor-input--error"
+                    } else {
+                        "editor-input settings-input settings-fsrs-textarea"
+                    },
+                    rows: "2",
+                    placeholder: "{placeholder}",
+                    value: "{value}",
+                    oninput: move |evt| {
+                        let mut next = form();
+                        let mut next_errors = errors();
+                        match side {
+                            CardSide::Prompt => {
+                                next.front_template = evt.value();
+                                next_errors.front_template = None;
+                            }
+                            CardSide::Answer => {
+                                next.back_template = evt.value();
+                                next_errors.back_template = None;
+                            }
+                        }
+                        form.set(next);
+                        errors.set(next_errors);
+                        save_state.set(SaveState::Idle);
+                    },
+                }
+                p { class: "settings-field-hint", "{TEMPLATE_HINT}" }
+                if let Some(message) = error {
+                    p { class: "editor-error", "{message}" }
+                }
+            }
+        }
+    }
+}
+
+fn advanced_new_card_text_row(
+    mut form: Signal<DeckSettingsForm>,
+    mut save_state: Signal<SaveState>,
+    side: CardSide,
+) -> Element {
+    let form_value = form();
+    let (id, row, value) = match side {
+        CardSide::Prompt => {
+            ("new-card-prompt", search::NEW_CARD_PROMPT, form_value.new_card_prompt)
+        }
+        CardSide::Answer => {
+            ("new-card-answer", search::NEW_CARD_ANSWER, form_value.new_card_answer)
+        }
+    };
+
+    rsx! {
+        SettingsRow { row, input_id: id,
+            div { class: "settings-row__field settings-row__field--wide",
+                textarea {
+                    id: "{id}",
+                    class: "editor-input settings-input settings-fsrs-textarea",
+                    rows: "2",
+                    value: "{value}",
+                    oninput: move |evt| {
+                        let mut next = form();
+                        match side {
+                            CardSide::Prompt => next.new_card_prompt = evt.value(),
+                            CardSide::Answer => next.new_card_answer = evt.value(),
+                        }
+                        form.set(next);
+                        save_state.set(SaveState::Idle);
+                    },
+                }
+            }
+        }
+    }
+}
+
+fn advanced_prune_card(prune: PruneControls) -> Element {
+    if !consume_context::<Signal<SettingsSearch>>()().matches_row(&search::PRUNE_HISTORY) {
+        return rsx! {};
+    }
+    let PruneControls {
+        mut keep,
+        mut state,
+    } = prune;
+    let keep_value = keep();
+    let invalid = prune.keep_count().is_none();
+    let pruned = match state() {
+        PruneState::Pruned(1) => Some("Removed 1 review.".to_string()),
+        PruneState::Pruned(count) => Some(format!("Removed {count} reviews.")),
+        _ => None,
+    };
+
+    rsx! {
+        div { class: "settings-card",
+            SettingsRow { row: search::PRUNE_HISTORY, input_id: "prune-keep",
+                div { class: "settings-row__field settings-row__field--wide",
+                    div { class: "settings-inline-input",
+                        input {
+                            id: "prune-keep",
+                            class: if invalid {
+                                "editor-input settings-input editor-input--error"
+                            } else {
+                                "editor-input settings-input"
+                            },
+                            r#type: "number",
+                            min: "1",
+                            inputmode: "numeric",
+                            value: "{keep_value}",
+                            oninput: move |evt| {
+                                keep.set(evt.value());
+                                state.set(PruneState::Idle);
+                            },
+                        }
+                        span { class: "settings-inline-suffix", "newest per card" }
+                    }
+                    button {
+                        class: "btn settings-danger",
+                        r#type: "button",
+                        disabled: invalid,
+                        onclick: move |_| state.set(PruneState::Confirming),
+                        "Prune history"
+                    }
+                    if invalid {
+                        p { class: "editor-error", "Keep at least 1 review per card." }
+                    } else if let Some(message) = pruned {
+                        p { class: "settings-field-hint", "{message}" }
+                    }
                 }
             }
         }
@@ -1104,6 +1748,10 @@ fn advanced_reset_button(
     mut reset_state: Signal<super::state::ResetState>,
     mut show_reset_modal: Signal<bool>,
 ) -> Element {
+    if !consume_context::<Signal<SettingsSearch>>()().matches_row(&search::RESET_FSRS) {
+        return rsx! {};
+    }
+
     rsx! {
         button {
             class: "btn settings-danger",
@@ -1112,8 +1760,8 @@ fn advanced_reset_button(
                 reset_state.set(super::state::ResetState::Idle);
                 show_reset_modal.set(true);
             },
-            title: "Resets all scheduling data for this deck.",
-            "Reset FSRS data"
+            title: search::RESET_FSRS.help,
+            "{search::RESET_FSRS.label}"
         }
     }
 }
@@ -1,10 +1,16 @@
-use learn_core::model::{Deck, DeckId, DeckSettings};
+use dioxus::prelude::Signal;
+use learn_core::model::{
+    AnswerButtons, CardSide, CardTemplate, Deck, DeckColor, DeckIcon, DeckId, DeckSettings,
+    LeechAction, NewCardOrder, NewCardTemplate, NewReviewMix, TemplateError,
+};
 
 use crate::views::ViewError;
 
 use super::helpers::{
-    format_lapse_interval, format_retention, normalize_description, parse_lapse_interval_secs,
-    parse_positive_u32, parse_retention, parse_timer_secs,
+    format_lapse_interval, format_learning_steps, format_retention, normalize_description,
+    parse_answer_buttons, parse_decimal, parse_lapse_interval_secs, parse_learning_steps,
+    parse_leech_action, parse_new_card_order, parse_new_review_mix,
+    parse_u32,
 };
 
 #[derive(Clone, Debug, PartialEq)]
@@ -21,9 +27,17 @@ pub(super) struct DeckSettingsSnapshot {
     pub(super) new_cards_per_day: u32,
     pub(super) review_limit_per_day: u32,
     pub(super) micro_session_size: u32,
+    pub(super) new_per_session: u32,
     pub(super) protect_overload: bool,
+    pub(super) hold_new_until_reviews_done: bool,
+    pub(super) bury_siblings: bool,
+    pub(super) progressive_reveal: bool,
+    pub(super) typed_answer: bool,
     pub(super) preserve_stability_on_lapse: bool,
     pub(super) lapse_min_interval_secs: u32,
+    pub(super) leech_threshold: u32,
+    pub(super) leech_action: LeechAction,
+    pub(super) same_session_fail_limit: u32,
     pub(super) show_timer: bool,
     pub(super) soft_time_reminder: bool,
     pub(super) auto_advance_cards: bool,
@@ -37,49 +51,64 @@ pub(super) struct DeckSettingsSnapshot {
     pub(super) fsrs_target_retention: f32,
     pub(super) fsrs_optimize_enabled: bool,
     pub(super) fsrs_optimize_after: u32,
+    pub(super) new_review_mix: NewReviewMix,
+    pub(super) new_card_order: NewCardOrder,
+    pub(super) load_balance: bool,
+    pub(super) interval_fuzz: bool,
+    pub(super) learning_steps_secs: Vec<u32>,
+    pub(super) relearning_steps_secs: Vec<u32>,
+    pub(super) fsrs_parameters: Vec<f32>,
+    pub(super) answer_buttons: AnswerButtons,
+    pub(super) color: Option<DeckColor>,
+    pub(super) icon: Option<DeckIcon>,
+    pub(super) front_template: Option<String>,
+    pub(super) back_template: Option<String>,
+    pub(super) new_card_template: NewCardTemplate,
 }
 
 impl DeckSettingsSnapshot {
     pub(super) fn from_deck(deck: &Deck) -> Self {
-        let settings = deck.settings();
-        Self {
-            deck_id: deck.id(),
-            name: deck.name().to_string(),
-            description: deck.description().map(str::to_owned),
-            new_cards_per_day: settings.new_cards_per_day(),
-            review_limit_per_day: settings.review_limit_per_day(),
-            micro_session_size: settings.micro_session_size(),
-            protect_overload: settings.protect_overload(),
-            preserve_stability_on_lapse: settings.preserve_stability_on_lapse(),
-            lapse_min_interval_secs: settings.lapse_min_interval_secs(),
-            show_timer: settings.show_timer(),
-            soft_time_reminder: settings.soft_time_reminder(),
-            auto_advance_cards: settings.auto_advance_cards(),
-            soft_time_reminder_secs: settings.soft_time_reminder_secs(),
-            auto_reveal_secs: settings.auto_reveal_secs(),
-            min_interval_secs: settings.min_interval_secs(),
-            max_interval_days: settings.max_interval_days(),
-            easy_days_enabled: settings.easy_days_enabled(),
-            easy_day_load_factor: settings.easy_day_load_factor(),
-            easy_days_mask: settings.easy_days_mask(),
-            fsrs_target_retention: settings.fsrs_target_retention(),
-            fsrs_optimize_enabled: settings.fsrs_optimize_enabled(),
-            fsrs_optimize_after: settings.fsrs_optimize_after(),
-        }
+        Self::from_settings(
+            deck.id(),
+            deck.name().to_string(),
+            deck.description().map(str::to_owned),
+            deck.settings(),
+        )
     }
 
     pub(super) fn from_validated(deck_id: DeckId, validated: &ValidatedSettings) -> Self {
-        let settings = &validated.settings;
+        Self::from_settings(
+            deck_id,
+            validated.name.clone(),
+            validated.description.clone(),
+            &validated.settings,
+        )
+    }
+
+    fn from_settings(
+        deck_id: DeckId,
+        name: String,
+        description: Option<String>,
+        settings: &DeckSettings,
+    ) -> Self {
         Self {
             deck_id,
-            name: validated.name.clone(),
-            description: validated.description.clone(),
+            name,
+            description,
             new_cards_per_day: settings.new_cards_per_day(),
             review_limit_per_day: settings.review_limit_per_day(),
             micro_session_size: settings.micro_session_size(),
+            new_per_session: settings.new_per_session(),
             protect_overload: settings.protect_overload(),
+            hold_new_until_reviews_done: settings.hold_new_until_reviews_done(),
+            bury_siblings: settings.bury_siblings(),
+            progressive_reveal: settings.progressive_reveal(),
+            typed_answer: settings.typed_answer(),
             preserve_stability_on_lapse: settings.preserve_stability_on_lapse(),
             lapse_min_interval_secs: settings.lapse_min_interval_secs(),
+            leech_threshold: settings.leech_threshold(),
+            leech_action: settings.leech_action(),
+            same_session_fail_limit: settings.same_session_fail_limit(),
             show_timer: settings.show_timer(),
             soft_time_reminder: settings.soft_time_reminder(),
             auto_advance_cards: settings.auto_advance_cards(),
@@ -93,6 +122,19 @@ impl DeckSettingsSnapshot {
             fsrs_target_retention: settings.fsrs_target_retention(),
             fsrs_optimize_enabled: settings.fsrs_optimize_enabled(),
             fsrs_optimize_after: settings.fsrs_optimize_after(),
+            new_review_mix: settings.new_review_mix(),
+            new_card_order: settings.new_card_order(),
+            answer_buttons: settings.answer_buttons(),
+            load_balance: settings.load_balance(),
+            interval_fuzz: settings.interval_fuzz(),
+            learning_steps_secs: settings.learning_steps_secs().to_vec(),
+            relearning_steps_secs: settings.relearning_steps_secs().to_vec(),
+            fsrs_parameters: settings.fsrs_parameters().to_vec(),
+            color: settings.color(),
+            icon: settings.icon(),
+            front_template: settings.front_template().map(|t| t.as_str().to_owned()),
+            back_template: settings.back_template().map(|t| t.as_str().to_owned()),
+            new_card_template: settings.new_card_template().clone(),
         }
     }
 }
@@ -105,9 +147,22 @@ pub(super) struct DeckSettingsForm {
     pub(super) new_cards_per_day: String,
     pub(super) review_limit_per_day: String,
     pub(super) micro_session_size: String,
+    pub(super) new_per_session: String,
+    pub(super) new_review_mix: String,
+    pub(super) new_card_order: String,
+    pub(super) answer_buttons: String,
+    pub(super) learning_steps: String,
     pub(super) protect_overload: bool,
+    pub(super) hold_new_until_reviews_done: bool,
+    pub(super) bury_siblings: bool,
+    pub(super) progressive_reveal: bool,
+    pub(super) typed_answer: bool,
     pub(super) preserve_stability_on_lapse: bool,
+    pub(super) relearning_steps: String,
     pub(super) lapse_min_interval: String,
+    pub(super) leech_threshold: String,
+    pub(super) leech_action: String,
+    pub(super) same_session_fail_limit: String,
     pub(super) show_timer: bool,
     pub(super) soft_time_reminder: bool,
     pub(super) auto_advance_cards: bool,
@@ -121,7 +176,15 @@ pub(super) struct DeckSettingsForm {
     pub(super) fsrs_optimize_after: String,
     pub(super) max_interval_days: String,
     pub(super) min_interval: String,
+    pub(super) load_balance: bool,
+    pub(super) interval_fuzz: bool,
     pub(super) fsrs_parameters: String,
+    pub(super) color: Option<DeckColor>,
+    pub(super) icon: Option<DeckIcon>,
+    pub(super) front_template: String,
+    pub(super) back_template: String,
+    pub(super) new_card_prompt: String,
+    pub(super) new_card_answer: String,
 }
 
 impl DeckSettingsForm {
@@ -132,9 +195,22 @@ impl DeckSettingsForm {
             new_cards_per_day: snapshot.new_cards_per_day.to_string(),
             review_limit_per_day: snapshot.review_limit_per_day.to_string(),
             micro_session_size: snapshot.micro_session_size.to_string(),
+            new_per_session: snapshot.new_per_session.to_string(),
+            new_review_mix: snapshot.new_review_mix.as_str().to_string(),
+            new_card_order: snapshot.new_card_order.as_str().to_string(),
+            answer_buttons: snapshot.answer_buttons.as_str().to_string(),
+            learning_steps: format_learning_steps(&snapshot.learning_steps_secs),
             protect_overload: snapshot.protect_overload,
+            hold_new_until_reviews_done: snapshot.hold_new_until_reviews_done,
+            bury_siblings: snapshot.bury_siblings,
+            progressive_reveal: snapshot.progressive_reveal,
+            typed_answer: snapshot.typed_answer,
             preserve_stability_on_lapse: snapshot.preserve_stability_on_lapse,
+            relearning_steps: format_learning_steps(&snapshot.relearning_steps_secs),
             lapse_min_interval: format_lapse_interval(snapshot.lapse_min_interval_secs),
+            leech_threshold: snapshot.leech_threshold.to_string(),
+            leech_action: snapshot.leech_action.as_str().to_string(),
+            same_session_fail_limit: snapshot.same_session_fail_limit.to_string(),
             show_timer: snapshot.show_timer,
             soft_time_reminder: snapshot.soft_time_reminder,
             auto_advance_cards: snapshot.auto_advance_cards,
@@ -148,60 +224,68 @@ impl DeckSettingsForm {
             fsrs_optimize_after: snapshot.fsrs_optimize_after.to_string(),
             max_interval_days: snapshot.max_interval_days.to_string(),
             min_interval: format_lapse_interval(snapshot.min_interval_secs),
-            fsrs_parameters: "0.2120, 1.2931, 2.3065, 8.2956, 6.4133, 0.8334, 3.0194, 0.0010, 1.8722, 0.1666, 0.7960, 1.4835, 0.0614, 0.2629, 1.6483, 0.6014, 1.8729, 0.5425, 0.0912, 0.0658, 0.1542".to_string(),
+            load_balance: snapshot.load_balance,
+            interval_fuzz: snapshot.interval_fuzz,
+            fsrs_parameters: DeckSettings::format_fsrs_parameters(&snapshot.fsrs_parameters),
+            color: snapshot.color,
+            icon: snapshot.icon,
+            front_template: snapshot.front_template.clone().unwrap_or_default(),
+            back_template: snapshot.back_template.clone().unwrap_or_default(),
+            new_card_prompt: snapshot.new_card_template.prompt().to_string(),
+            new_card_answer: snapshot.new_card_template.answer().to_string(),
         }
     }
 
     pub(super) fn to_snapshot(&self, deck_id: DeckId) -> Option<DeckSettingsSnapshot> {
-        let name = self.name.trim();
-        if name.is_empty() {
-            return None;
-        }
-
-        let new_cards_per_day = parse_positive_u32(&self.new_cards_per_day)?;
-        let review_limit_per_day = parse_positive_u32(&self.review_limit_per_day)?;
-        let micro_session_size = parse_positive_u32(&self.micro_session_size)?;
-
-        let lapse_min_interval_secs = parse_lapse_interval_secs(&self.lapse_min_interval)?;
-        let fsrs_target_retention = parse_retention(&self.fsrs_target_retention)?;
-        let fsrs_optimize_after = parse_positive_u32(&self.fsrs_optimize_after)?;
-        let soft_time_reminder_secs = parse_timer_secs(&self.soft_time_reminder_secs)?;
-        let auto_reveal_secs = parse_timer_secs(&self.auto_reveal_secs)?;
-        let min_interval_secs = parse_lapse_interval_secs(&self.min_interval)?;
-        let max_interval_days = parse_positive_u32(&self.max_interval_days)?;
-        let easy_day_load_factor = parse_retention(&self.easy_day_load_factor)?;
-        if self.easy_days_enabled && self.easy_days_mask == 0 {
-            return None;
-        }
-        let max_interval_secs = max_interval_days.checked_mul(86_400)?;
-        if min_interval_secs > max_interval_secs {
-            return None;
-        }
+        let validated = validate_form(self).ok()?;
+        Some(DeckSettingsSnapshot::from_validated(deck_id, &validated))
+    }
 
-        Some(DeckSettingsSnapshot {
-            deck_id,
-            name: name.to_string(),
-            description: normalize_description(&self.description),
-            new_cards_per_day,
-            review_limit_per_day,
-            micro_session_size,
-            protect_overload: self.protect_overload,
-            preserve_stability_on_lapse: self.preserve_stability_on_lapse,
-            lapse_min_interval_secs,
-            show_timer: self.show_timer,
-            soft_time_reminder: self.soft_time_reminder,
-            auto_advance_cards: self.auto_advance_cards,
-            soft_time_reminder_secs,
-            auto_reveal_secs,
-            min_interval_secs,
-            max_interval_days,
-            easy_days_enabled: self.easy_days_enabled,
-            easy_day_load_factor,
-            easy_days_mask: self.easy_days_mask,
-            fsrs_target_retention,
-            fsrs_optimize_enabled: self.fsrs_optimize_enabled,
-            fsrs_optimize_after,
-        })
+    /// Fill every scheduling field from `settings`, keeping the name, description, and
+    /// appearance already in the form.
+    pub(super) fn load_settings(&mut self, settings: &DeckSettings) {
+        self.new_cards_per_day = settings.new_cards_per_day().to_string();
+        self.review_limit_per_day = settings.review_limit_per_day().to_string();
+        self.micro_session_size = settings.micro_session_size().to_string();
+        self.new_per_session = settings.new_per_session().to_string();
+        self.new_review_mix = settings.new_review_mix().as_str().to_string();
+        self.new_card_order = settings.new_card_order().as_str().to_string();
+        self.answer_buttons = settings.answer_buttons().as_str().to_string();
+        self.protect_overload = settings.protect_overload();
+        self.hold_new_until_reviews_done = settings.hold_new_until_reviews_done();
+        self.bury_siblings = settings.bury_siblings();
+        self.progressive_reveal = settings.progressive_reveal();
+        self.typed_answer = settings.typed_answer();
+        self.preserve_stability_on_lapse = settings.preserve_stability_on_lapse();
+        self.relearning_steps = format_learning_steps(settings.relearning_steps_secs());
+        self.lapse_min_interval = format_lapse_interval(settings.lapse_min_interval_secs());
+        self.leech_threshold = settings.leech_threshold().to_string();
+        self.leech_action = settings.leech_action().as_str().to_string();
+        self.same_session_fail_limit = settings.same_session_fail_limit().to_string();
+        self.show_timer = settings.show_timer();
+        self.soft_time_reminder = settings.soft_time_reminder();
+        self.auto_advance_cards = settings.auto_advance_cards();
+        self.soft_time_reminder_secs = settings.soft_time_reminder_secs().to_string();
+        self.auto_reveal_secs = settings.auto_reveal_secs().to_string();
+        self.min_interval = format_lapse_interval(settings.min_interval_secs());
+        self.max_interval_days = settings.max_interval_days().to_string();
+        self.easy_days_enabled = settings.easy_days_enabled();
+        self.easy_day_load_factor = format_retention(settings.easy_day_load_factor());
+        self.easy_days_mask = settings.easy_days_mask();
+        self.fsrs_target_retention = format_retention(settings.fsrs_target_retention());
+        self.fsrs_optimize_enabled = settings.fsrs_optimize_enabled();
+        self.fsrs_optimize_after = settings.fsrs_optimize_after().to_string();
+        self.load_balance = settings.load_balance();
+        self.interval_fuzz = settings.interval_fuzz();
+        self.learning_steps = format_learning_steps(settings.learning_steps_secs());
+        self.fsrs_parameters = DeckSettings::format_fsrs_parameters(settings.fsrs_parameters());
+        let template_text = |template: Option<&CardTemplate>| {
+            template.map(CardTemplate::as_str).unwrap_or_default().to_string()
+        };
+        self.front_template = template_text(settings.front_template());
+        self.back_template = template_text(settings.back_template());
+        self.new_card_prompt = settings.new_card_template().prompt().to_string();
+        self.new_card_answer = settings.new_card_template().answer().to_string();
     }
 }
 
@@ -211,7 +295,16 @@ pub(super) struct DeckSettingsErrors {
     pub(super) new_cards_per_day: Option<&'static str>,
     pub(super) review_limit_per_day: Option<&'static str>,
     pub(super) micro_session_size: Option<&'static str>,
+    pub(super) new_per_session: Option<&'static str>,
+    pub(super) new_review_mix: Option<&'static str>,
+    pub(super) new_card_order: Option<&'static str>,
+    pub(super) answer_buttons: Option<&'static str>,
+    pub(super) learning_steps: Option<&'static str>,
+    pub(super) relearning_steps: Option<&'static str>,
     pub(super) lapse_min_interval: Option<&'static str>,
+    pub(super) leech_threshold: Option<&'static str>,
+    pub(super) leech_action: Option<&'static str>,
+    pub(super) same_session_fail_limit: Option<&'static str>,
     pub(super) soft_time_reminder_secs: Option<&'static str>,
     pub(super) auto_reveal_secs: Option<&'static str>,
     pub(super) min_interval: Option<&'static str>,
@@ -220,6 +313,9 @@ pub(super) struct DeckSettingsErrors {
     pub(super) easy_days_mask: Option<&'static str>,
     pub(super) fsrs_target_retention: Option<&'static str>,
     pub(super) fsrs_optimize_after: Option<&'static str>,
+    pub(super) fsrs_parameters: Option<&'static str>,
+    pub(super) front_template: Option<&'static str>,
+    pub(super) back_template: Option<&'static str>,
 }
 
 impl DeckSettingsErrors {
@@ -228,7 +324,16 @@ impl DeckSettingsErrors {
             || self.new_cards_per_day.is_some()
             || self.review_limit_per_day.is_some()
             || self.micro_session_size.is_some()
+            || self.new_per_session.is_some()
+            || self.new_review_mix.is_some()
+            || self.new_card_order.is_some()
+            || self.answer_buttons.is_some()
+            || self.learning_steps.is_some()
+            || self.relearning_steps.is_some()
             || self.lapse_min_interval.is_some()
+            || self.leech_threshold.is_some()
+            || self.leech_action.is_some()
+            || self.same_session_fail_limit.is_some()
             || self.soft_time_reminder_secs.is_some()
             || self.auto_reveal_secs.is_some()
             || self.min_interval.is_some()
@@ -237,6 +342,9 @@ impl DeckSettingsErrors {
             || self.easy_days_mask.is_some()
             || self.fsrs_target_retention.is_some()
             || self.fsrs_optimize_after.is_some()
+            || self.fsrs_parameters.is_some()
+            || self.front_template.is_some()
+            || self.back_template.is_some()
     }
 }
 
@@ -254,10 +362,35 @@ pub(super) enum SettingsSection {
     Audio,
     Timers,
     EasyDays,
+    Appearance,
     Advanced,
 }
 
 impl SettingsSection {
+    pub(super) const ALL: [SettingsSection; 8] = [
+        SettingsSection::DailyLimits,
+        SettingsSection::Lapses,
+        SettingsSection::Fsrs,
+        SettingsSection::Audio,
+        SettingsSection::Timers,
+        SettingsSection::EasyDays,
+        SettingsSection::Appearance,
+        SettingsSection::Advanced,
+    ];
+
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            SettingsSection::DailyLimits => "Daily Limits",
+            SettingsSection::Lapses => "Lapses",
+            SettingsSection::Fsrs => "FSRS",
+            SettingsSection::Audio => "Audio",
+            SettingsSection::Timers => "Timers",
+            SettingsSection::EasyDays => "Easy Days",
+            SettingsSection::Appearance => "Appearance",
+            SettingsSection::Advanced => "Advanced",
+        }
+    }
+
     pub(super) fn anchor_id(self) -> &'static str {
         match self {
             SettingsSection::DailyLimits => "settings-daily-limits",
@@ -266,6 +399,7 @@ impl SettingsSection {
             SettingsSection::Audio => "settings-audio",
             SettingsSection::Timers => "settings-timers",
             SettingsSection::EasyDays => "settings-easy-days",
+            SettingsSection::Appearance => "settings-appearance",
             SettingsSection::Advanced => "settings-advanced",
         }
     }
@@ -276,6 +410,8 @@ pub(super) enum SaveState {
     Idle,
     Saving,
     Saved,
+    /// The deck was saved elsewhere after this form loaded it.
+    Conflict,
     Error(ViewError),
 }
 
@@ -286,6 +422,31 @@ pub(super) enum ResetState {
     Error(ViewError),
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum PruneState {
+    Idle,
+    Confirming,
+    Pruning,
+    /// How many reviews the last prune deleted.
+    Pruned(u64),
+    Error(ViewError),
+}
+
+/// The review history prune action in the Advanced section and its confirmation.
+#[derive(Clone, Copy, PartialEq)]
+pub(super) struct PruneControls {
+    /// Reviews to keep per card, as typed.
+    pub(super) keep: Signal<String>,
+    pub(super) state: Signal<PruneState>,
+}
+
+impl PruneControls {
+    /// The typed keep count, if it is a whole number of at least 1.
+    pub(super) fn keep_count(&self) -> Option<u32> {
+        parse_u32(&(self.keep)()).filter(|count| *count > 0)
+    }
+}
+
 #[allow(clippy::too_many_lines)]
 pub(super) fn validate_form(
     form: &DeckSettingsForm,
@@ -318,6 +479,30 @@ pub(super) fn validate_form(
         form.fsrs_optimize_enabled,
         parsed.fsrs_optimize_after,
     )
+    .and_then(|settings| settings.with_fsrs_parameters(parsed.fsrs_parameters))
+    .and_then(|settings| settings.with_learning_steps(parsed.learning_steps_secs))
+    .and_then(|settings| settings.with_relearning_steps(parsed.relearning_steps_secs))
+    .and_then(|settings| {
+        settings
+            .with_new_review_mix(parsed.new_review_mix)
+            .with_load_balance(form.load_balance)
+            .with_answer_buttons(parsed.answer_buttons)
+            .with_leech(parsed.leech_threshold, parsed.leech_action)
+            .with_appearance(form.color, form.icon)
+            .with_hold_new_until_reviews_done(form.hold_new_until_reviews_done)
+            .with_bury_siblings(form.bury_siblings)
+            .with_same_session_fail_limit(parsed.same_session_fail_limit)
+            .with_new_per_session(parsed.new_per_session)
+            .with_progressive_reveal(form.progressive_reveal)
+            .with_typed_answer(form.typed_answer)
+            .with_new_card_order(parsed.new_card_order)
+            .with_interval_fuzz(form.interval_fuzz)
+            .with_new_card_template(NewCardTemplate::new(
+                &form.new_card_prompt,
+                &form.new_card_answer,
+            ))
+            .with_templates(Some(&form.front_template), Some(&form.back_template))
+    })
     .map_err(|err| map_deck_settings_error(&err))?;
 
     Ok(ValidatedSettings {
@@ -332,7 +517,11 @@ struct ParsedSettings {
     new_cards_per_day: u32,
     review_limit_per_day: u32,
     micro_session_size: u32,
+    new_per_session: u32,
     lapse_min_interval_secs: u32,
+    leech_threshold: u32,
+    leech_action: LeechAction,
+    same_session_fail_limit: u32,
     soft_time_reminder_secs: u32,
     auto_reveal_secs: u32,
     min_interval_secs: u32,
@@ -341,6 +530,12 @@ struct ParsedSettings {
     easy_days_mask: u8,
     fsrs_target_retention: f32,
     fsrs_optimize_after: u32,
+    new_review_mix: NewReviewMix,
+    new_card_order: NewCardOrder,
+    answer_buttons: AnswerButtons,
+    learning_steps_secs: Vec<u32>,
+    relearning_steps_secs: Vec<u32>,
+    fsrs_parameters: Vec<f32>,
 }
 
 fn parse_settings_form(
@@ -352,32 +547,72 @@ fn parse_settings_form(
     let new_cards_per_day = parse_u32_field(
         &form.new_cards_per_day,
         &mut errors.new_cards_per_day,
-        "Enter a positive number.",
+        "Enter a whole number.",
     );
     let review_limit_per_day = parse_u32_field(
         &form.review_limit_per_day,
         &mut errors.review_limit_per_day,
-        "Enter a positive number.",
+        "Enter a whole number.",
     );
     let micro_session_size = parse_u32_field(
         &form.micro_session_size,
         &mut errors.micro_session_size,
-        "Enter a positive number.",
+        "Enter a whole number.",
+    );
+    let new_per_session = parse_u32_field(
+        &form.new_per_session,
+        &mut errors.new_per_session,
+        "Enter a whole number; 0 leaves only the daily limit.",
     );
+    let new_review_mix = parse_new_review_mix(&form.new_review_mix).unwrap_or_else(|| {
+        errors.new_review_mix = Some("Pick how new cards mix with reviews.");
+        NewReviewMix::default()
+    });
+    let new_card_order = parse_new_card_order(&form.new_card_order).unwrap_or_else(|| {
+        errors.new_card_order = Some("Pick the order new cards are shown in.");
+        NewCardOrder::default()
+    });
+    let answer_buttons = parse_answer_buttons(&form.answer_buttons).unwrap_or_else(|| {
+        errors.answer_buttons = Some("Pick how many grade buttons to show.");
+        AnswerButtons::default()
+    });
+    let learning_steps_secs = parse_learning_steps(&form.learning_steps).unwrap_or_else(|| {
+        errors.learning_steps = Some("Use durations like 1m 10m.");
+        Vec::new()
+    });
+    let relearning_steps_secs =
+        parse_learning_steps(&form.relearning_steps).unwrap_or_else(|| {
+            errors.relearning_steps = Some("Use durations like 10m.");
+            Vec::new()
+        });
     let lapse_min_interval_secs = parse_duration_field(
         &form.lapse_min_interval,
         &mut errors.lapse_min_interval,
         "Use a duration like 10m or 1d.",
     );
-    let soft_time_reminder_secs = parse_timer_field(
+    let leech_threshold = parse_u32_field(
+        &form.leech_threshold,
+        &mut errors.leech_threshold,
+        "Enter a whole number; 0 turns leech detection off.",
+    );
+    let leech_action = parse_leech_action(&form.leech_action).unwrap_or_else(|| {
+        errors.leech_action = Some("Pick what happens to leech cards.");
+        LeechAction::default()
+    });
+    let same_session_fail_limit = parse_u32_field(
+        &form.same_session_fail_limit,
+        &mut errors.same_session_fail_limit,
+        "Enter a whole number; 0 lets failed cards keep coming back.",
+    );
+    let soft_time_reminder_secs = parse_u32_field(
         &form.soft_time_reminder_secs,
         &mut errors.soft_time_reminder_secs,
-        "Enter 5-600 seconds.",
+        "Enter a number of seconds.",
     );
-    let auto_reveal_secs = parse_timer_field(
+    let auto_reveal_secs = parse_u32_field(
         &form.auto_reveal_secs,
         &mut errors.auto_reveal_secs,
-        "Enter 5-600 seconds.",
+        "Enter a number of seconds.",
     );
     let min_interval_secs = parse_duration_field(
         &form.min_interval,
@@ -387,39 +622,42 @@ fn parse_settings_form(
     let max_interval_days = parse_u32_field(
         &form.max_interval_days,
         &mut errors.max_interval_days,
-        "Enter a positive number.",
+        "Enter a whole number.",
     );
-    let max_interval_secs = max_interval_days.saturating_mul(86_400);
-    if min_interval_secs > 0 && max_interval_days > 0 && min_interval_secs > max_interval_secs {
-        errors.min_interval = Some("Must be <= maximum interval.");
-        errors.max_interval_days = Some("Must be >= minimum interval.");
-    }
-    let easy_day_load_factor = parse_retention_field(
+    let easy_day_load_factor = parse_decimal_field(
         &form.easy_day_load_factor,
         &mut errors.easy_day_load_factor,
-        "Enter a value between 0 and 1.",
+        "Enter a decimal like 0.5.",
     );
     let easy_days_mask = form.easy_days_mask;
-    if form.easy_days_enabled && easy_days_mask == 0 {
-        errors.easy_days_mask = Some("Pick at least one day.");
-    }
-    let fsrs_target_retention = parse_retention_field(
+    let fsrs_target_retention = parse_decimal_field(
         &form.fsrs_target_retention,
         &mut errors.fsrs_target_retention,
-        "Enter a value between 0 and 1.",
+        "Enter a decimal like 0.85.",
     );
     let fsrs_optimize_after = parse_u32_field(
         &form.fsrs_optimize_after,
         &mut errors.fsrs_optimize_after,
-        "Enter a positive number.",
+        "Enter a whole number.",
     );
+    let fsrs_parameters =
+        DeckSettings::parse_fsrs_parameters(&form.fsrs_parameters).unwrap_or_else(|_| {
+            errors.fsrs_parameters = Some("Enter 21 numbers separated by commas or spaces.");
+            Vec::new()
+        });
+    check_template(CardSide::Prompt, &form.front_template, &mut errors.front_template);
+    check_template(CardSide::Answer, &form.back_template, &mut errors.back_template);
 
     ParsedSettings {
         name,
         new_cards_per_day,
         review_limit_per_day,
         micro_session_size,
+        new_per_session,
         lapse_min_interval_secs,
+        leech_threshold,
+        leech_action,
+        same_session_fail_limit,
         soft_time_reminder_secs,
         auto_reveal_secs,
         min_interval_secs,
@@ -428,6 +666,12 @@ fn parse_settings_form(
         easy_days_mask,
         fsrs_target_retention,
         fsrs_optimize_after,
+        new_review_mix,
+        new_card_order,
+        answer_buttons,
+        learning_steps_secs,
+        relearning_steps_secs,
+        fsrs_parameters,
     }
 }
 
@@ -444,7 +688,7 @@ fn parse_u32_field(
     error_slot: &mut Option<&'static str>,
     message: &'static str,
 ) -> u32 {
-    parse_positive_u32(value).unwrap_or_else(|| {
+    parse_u32(value).unwrap_or_else(|| {
         *error_slot = Some(message);
         0
     })
@@ -461,44 +705,49 @@ fn parse_duration_field(
     })
 }
 
-fn parse_timer_field(
-    value: &str,
-    error_slot: &mut Option<&'static str>,
-    message: &'static str,
-) -> u32 {
-    parse_timer_secs(value).unwrap_or_else(|| {
-        *error_slot = Some(message);
-        0
-    })
-}
-
-fn parse_retention_field(
+fn parse_decimal_field(
     value: &str,
     error_slot: &mut Option<&'static str>,
     message: &'static str,
 ) -> f32 {
-    parse_retention(value).unwrap_or_else(|| {
+    parse_decimal(value).unwrap_or_else(|| {
         *error_slot = Some(message);
         0.0
     })
 }
 
+fn check_template(side: CardSide, value: &str, error_slot: &mut Option<&'static str>) {
+    if value.trim().is_empty() {
+        return;
+    }
+    if let Err(err) = CardTemplate::parse(side, value) {
+        *error_slot = Some(match err {
+            TemplateError::UnknownField(_) => "Use only {{front}}, {{back}}, and {{extra}}.",
+            TemplateError::Unclosed(_) => "Close every {{ with }}.",
+            TemplateError::FieldInTag(_) => "Put fields between tags, not inside them.",
+            TemplateError::AnswerOnFront => "The front cannot show {{back}}.",
+            _ => "Check the template.",
+        });
+    }
+}
+
+/// Point a `DeckSettings::new` rejection at the form field it belongs to.
 fn map_deck_settings_error(
     error: &learn_core::model::DeckError,
 ) -> Box<DeckSettingsErrors> {
     let mut errors = DeckSettingsErrors::default();
     match *error {
         learn_core::model::DeckError::InvalidMicroSessionSize => {
-            errors.micro_session_size = Some("Enter a positive number.");
+            errors.micro_session_size = Some("Must be at least 1.");
         }
         learn_core::model::DeckError::InvalidNewCardsPerDay => {
-            errors.new_cards_per_day = Some("Enter a positive number.");
+            errors.new_cards_per_day = Some("Must be at least 1.");
         }
         learn_core::model::DeckError::InvalidReviewLimitPerDay => {
-            errors.review_limit_per_day = Some("Enter a positive number.");
+            errors.review_limit_per_day = Some("Must be at least 1.");
         }
         learn_core::model::DeckError::InvalidLapseMinInterval => {
-            errors.lapse_min_interval = Some("Use a duration like 10m or 1d.");
+            errors.lapse_min_interval = Some("Must be longer than 0.");
         }
         learn_core::model::DeckError::InvalidSoftReminderSeconds => {
             errors.soft_time_reminder_secs = Some("Enter 5-600 seconds.");
@@ -517,16 +766,25 @@ fn map_deck_settings_error(
             errors.max_interval_days = Some("Must be >= minimum interval.");
         }
         learn_core::model::DeckError::InvalidEasyDayLoadFactor => {
-            errors.easy_day_load_factor = Some("Enter a value between 0 and 1.");
+            errors.easy_day_load_factor = Some("Enter a value above 0, up to 1.");
         }
         learn_core::model::DeckError::InvalidEasyDaysMask => {
             errors.easy_days_mask = Some("Pick at least one day.");
         }
         learn_core::model::DeckError::InvalidFsrsTargetRetention => {
-            errors.fsrs_target_retention = Some("Enter a value between 0 and 1.");
+            errors.fsrs_target_retention = Some("Enter a value between 0.70 and 0.99.");
+        }
+        learn_core::model::DeckError::InvalidLearningSteps => {
+            errors.learning_steps = Some("Enter up to 10 steps between 1s and 1d.");
+        }
+        learn_core::model::DeckError::InvalidRelearningSteps => {
+            errors.relearning_steps = Some("Enter up to 10 steps between 1s and 1d.");
         }
         learn_core::model::DeckError::InvalidFsrsOptimizeAfter => {
-            errors.fsrs_optimize_after = Some("Enter a positive number.");
+            errors.fsrs_optimize_after = Some("Must be at least 1.");
+        }
+        learn_core::model::DeckError::InvalidFsrsParameters => {
+            errors.fsrs_parameters = Some("Enter 21 numbers separated by commas or spaces.");
         }
         learn_core::model::DeckError::EmptyName => {
             errors.name = Some("Deck name is required.");
@@ -1,20 +1,23 @@
 use dioxus::document::eval;
 use dioxus::prelude::*;
 use dioxus_router::use_navigator;
-use learn_core::model::{DeckId, DeckSettings};
+use learn_core::model::{AppSettings, DeckId, DeckSettings};
+
+use services::{DeckServiceError, ReviewLogPrune};
 
 use crate::context::AppContext;
 use crate::views::{ViewError, ViewState, view_state_from_resource};
 
 use super::components::SettingsNavItem;
-use super::helpers::{format_lapse_interval, format_retention};
+use super::helpers::parse_audio_delay_ms;
+use super::search::SettingsSearch;
 use super::sections::{
-    advanced_section, audio_section, daily_limits_section, easy_days_section, fsrs_section,
-    lapses_section, timers_section,
+    advanced_section, appearance_section, audio_section, daily_limits_section, easy_days_section,
+    fsrs_section, lapses_section, timers_section,
 };
 use super::state::{
-    DeckSettingsData, DeckSettingsErrors, DeckSettingsForm, DeckSettingsSnapshot, ResetState,
-    SaveState, SettingsSection, validate_form,
+    DeckSettingsData, DeckSettingsErrors, DeckSettingsForm, DeckSettingsSnapshot, PruneControls,
+    PruneState, ResetState, SaveState, SettingsSection, validate_form,
 };
 
 #[component]
@@ -25,19 +28,48 @@ pub fn SettingsView(deck_id: Option<u64>) -> Element {
     let deck_service = ctx.deck_service();
     let deck_service_for_resource = deck_service.clone();
     let card_service = ctx.card_service();
+    let app_settings = ctx.app_settings();
 
     let mut form = use_signal(DeckSettingsForm::default);
     let mut errors = use_signal(DeckSettingsErrors::default);
     let mut save_state = use_signal(|| SaveState::Idle);
     let mut initial_snapshot = use_signal(|| None::<DeckSettingsSnapshot>);
+    let mut deck_version = use_signal(|| 0_u64);
     let mut show_reset_modal = use_signal(|| false);
     let mut reset_state = use_signal(|| ResetState::Idle);
+    let prune = PruneControls {
+        keep: use_signal(|| "20".to_string()),
+        state: use_signal(|| PruneState::Idle),
+    };
+    let mut prune_state = prune.state;
     let active_section = use_signal(|| SettingsSection::DailyLimits);
-    let mut search = use_signal(String::new);
-    let expanded_section = use_signal(|| Some(SettingsSection::DailyLimits));
-    let autoplay_audio = use_signal(|| true);
-    let replay_audio_after_answer = use_signal(|| false);
-    let audio_delay_ms = use_signal(|| "300".to_string());
+    let mut search = use_context_provider(|| Signal::new(SettingsSearch::default()));
+    let mut expanded_section = use_signal(|| Some(SettingsSection::DailyLimits));
+    let mut autoplay_audio = use_signal(|| true);
+    let mut replay_audio_after_answer = use_signal(|| false);
+    let mut audio_delay_ms = use_signal(|| "300".to_string());
+    // Audio preferences are app-wide, so they round-trip through app settings rather
+    // than the deck.
+    let mut audio_initial = use_signal(|| None::<AppSettings>);
+    let mut preset_notice = use_signal(|| None::<String>);
+    let audio_resource = {
+        let app_settings = app_settings.clone();
+        use_resource(move || {
+            let app_settings = app_settings.clone();
+            async move { app_settings.load().await.ok() }
+        })
+    };
+    use_effect(move || {
+        let loaded = audio_resource.value().read().clone().flatten();
+        if let Some(settings) = loaded
+            && audio_initial.peek().is_none()
+        {
+            autoplay_audio.set(settings.autoplay_audio());
+            replay_audio_after_answer.set(settings.replay_audio_after_answer());
+            audio_delay_ms.set(settings.audio_delay_ms().to_string());
+            audio_initial.set(Some(settings));
+        }
+    });
 
     let resource = use_resource(move || {
         let deck_service = deck_service_for_resource.clone();
@@ -65,6 +97,7 @@ pub fn SettingsView(deck_id: Option<u64>) -> Element {
                 .is_none_or(|snapshot| snapshot.deck_id != deck.id());
             if should_reset {
                 let snapshot = DeckSettingsSnapshot::from_deck(&deck);
+                deck_version.set(deck.version());
                 initial_snapshot.set(Some(snapshot.clone()));
                 form.set(DeckSettingsForm::from_snapshot(&snapshot));
                 errors.set(DeckSettingsErrors::default());
@@ -78,14 +111,22 @@ pub fn SettingsView(deck_id: Option<u64>) -> Element {
     let form_snapshot = current_snapshot
         .as_ref()
         .and_then(|snapshot| form_value.to_snapshot(snapshot.deck_id));
-    let has_valid_form = form_snapshot.is_some();
-    let is_dirty = current_snapshot
-        .as_ref()
-        .is_some_and(|snapshot| form_snapshot.as_ref() != Some(snapshot));
+    let audio_delay_value = parse_audio_delay_ms(&audio_delay_ms());
+    let audio_dirty = audio_initial().is_some_and(|settings| {
+        settings.autoplay_audio() != autoplay_audio()
+            || settings.replay_audio_after_answer() != replay_audio_after_answer()
+            || Some(settings.audio_delay_ms()) != audio_delay_value
+    });
+    let has_valid_form = form_snapshot.is_some() && audio_delay_value.is_some();
+    let is_dirty = audio_dirty
+        || current_snapshot
+            .as_ref()
+            .is_some_and(|snapshot| form_snapshot.as_ref() != Some(snapshot));
 
     let status_label = match save_state() {
         SaveState::Saving => Some("Saving..."),
         SaveState::Saved => Some("Saved"),
+        SaveState::Conflict => Some("Changed in another window"),
         SaveState::Error(_) => Some("Couldn't save"),
         SaveState::Idle if is_dirty => Some("Unsaved changes"),
         SaveState::Idle => None,
@@ -99,31 +140,69 @@ pub fn SettingsView(deck_id: Option<u64>) -> Element {
         let initial_snapshot = initial_snapshot;
         use_callback(move |()| {
             let form_value = form();
+            let audio_draft = audio_initial()
+                .filter(|_| audio_dirty)
+                .map(|settings| {
+                    let mut draft = settings.to_draft();
+                    draft.autoplay_audio = Some(autoplay_audio());
+                    draft.replay_audio_after_answer = Some(replay_audio_after_answer());
+                    draft.audio_delay_ms = audio_delay_value;
+                    draft
+                });
             match validate_form(&form_value) {
                 Ok(validated) => {
                     errors.set(DeckSettingsErrors::default());
                     let deck_service = deck_service.clone();
+                    let app_settings = app_settings.clone();
                         let mut save_state = save_state;
                         let mut initial_snapshot = initial_snapshot;
                         let mut form = form;
+                        let mut deck_version = deck_version;
+                    // Cards already due past a lowered maximum are pulled in once it is saved.
+                    let max_days = validated.settings.max_interval_days();
+                    let lowered_max = initial_snapshot().is_some_and(|snapshot| {
+                        max_days > 0
+                            && (snapshot.max_interval_days == 0
+                                || max_days < snapshot.max_interval_days)
+                    });
                     spawn(async move {
                         save_state.set(SaveState::Saving);
                         match deck_service
                             .update_deck(
                                 deck_id,
+                                deck_version(),
                                 validated.name.clone(),
                                 validated.description.clone(),
                                 validated.settings.clone(),
                             )
                             .await
                         {
-                            Ok(()) => {
+                            Ok(version) => {
+                                deck_version.set(version);
                                 let snapshot =
                                     DeckSettingsSnapshot::from_validated(deck_id, &validated);
                                 initial_snapshot.set(Some(snapshot.clone()));
                                 form.set(DeckSettingsForm::from_snapshot(&snapshot));
+                                if lowered_max
+                                    && deck_service.clamp_intervals(deck_id).await.is_err()
+                                {
+                                    save_state.set(SaveState::Error(ViewError::Unknown));
+                                    return;
+                                }
+                                if let Some(draft) = audio_draft {
+                                    match app_settings.save(draft).await {
+                                        Ok(settings) => audio_initial.set(Some(settings)),
+                                        Err(_) => {
+                                            save_state.set(SaveState::Error(ViewError::Unknown));
+                                            return;
+                                        }
+                                    }
+                                }
                                 save_state.set(SaveState::Saved);
                             }
+                            Err(DeckServiceError::Conflict) => {
+                                save_state.set(SaveState::Conflict);
+                            }
                             Err(_) => {
                                 save_state.set(SaveState::Error(ViewError::Unknown));
                             }
@@ -142,33 +221,59 @@ pub fn SettingsView(deck_id: Option<u64>) -> Element {
         let mut errors = errors;
         let mut save_state = save_state;
         use_callback(move |()| {
-            let defaults = DeckSettings::default_for_adhd();
             let mut next = form();
-            next.new_cards_per_day = defaults.new_cards_per_day().to_string();
-        next.review_limit_per_day = defaults.review_limit_per_day().to_string();
-        next.micro_session_size = defaults.micro_session_size().to_string();
-        next.protect_overload = defaults.protect_overload();
-        next.preserve_stability_on_lapse = defaults.preserve_stability_on_lapse();
-        next.lapse_min_interval = format_lapse_interval(defaults.lapse_min_interval_secs());
-        next.show_timer = defaults.show_timer();
-        next.soft_time_reminder = defaults.soft_time_reminder();
-        next.auto_advance_cards = defaults.auto_advance_cards();
-        next.soft_time_reminder_secs = defaults.soft_time_reminder_secs().to_string();
-        next.auto_reveal_secs = defaults.auto_reveal_secs().to_string();
-        next.min_interval = format_lapse_interval(defaults.min_interval_secs());
-        next.max_interval_days = defaults.max_interval_days().to_string();
-        next.easy_days_enabled = defaults.easy_days_enabled();
-        next.easy_day_load_factor = format_retention(defaults.easy_day_load_factor());
-        next.easy_days_mask = defaults.easy_days_mask();
-        next.fsrs_target_retention = format_retention(defaults.fsrs_target_retention());
-        next.fsrs_optimize_enabled = defaults.fsrs_optimize_enabled();
-        next.fsrs_optimize_after = defaults.fsrs_optimize_after().to_string();
-        form.set(next);
-        errors.set(DeckSettingsErrors::default());
-        save_state.set(SaveState::Idle);
-    })
+            next.load_settings(&DeckSettings::default_for_adhd());
+            form.set(next);
+            let audio_defaults = AppSettings::default();
+            autoplay_audio.set(audio_defaults.autoplay_audio());
+            replay_audio_after_answer.set(audio_defaults.replay_audio_after_answer());
+            audio_delay_ms.set(audio_defaults.audio_delay_ms().to_string());
+            errors.set(DeckSettingsErrors::default());
+            save_state.set(SaveState::Idle);
+        })
     };
 
+    // Presets travel through the clipboard; a pasted one fills the form for review before Save.
+    let on_copy_preset = use_callback(move |()| match validate_form(&form()) {
+        Ok(validated) => {
+            let preset = validated.settings.to_preset_json();
+            spawn(async move {
+                let copy = eval(
+                    "const text = await dioxus.recv(); await navigator.clipboard.writeText(text);",
+                );
+                let copied = copy.send(preset).is_ok() && copy.await.is_ok();
+                preset_notice.set(Some(
+                    if copied { "Settings copied" } else { "Couldn't copy settings" }.to_string(),
+                ));
+            });
+        }
+        Err(next_errors) => {
+            errors.set(*next_errors);
+            preset_notice.set(Some("Fix the highlighted settings before copying".to_string()));
+        }
+    });
+
+    let on_paste_preset = use_callback(move |()| {
+        spawn(async move {
+            let pasted = eval("return await navigator.clipboard.readText();")
+                .join::<String>()
+                .await;
+            let notice = match pasted.map(|text| DeckSettings::from_preset_json(&text)) {
+                Ok(Ok(settings)) => {
+                    let mut next = form();
+                    next.load_settings(&settings);
+                    form.set(next);
+                    errors.set(DeckSettingsErrors::default());
+                    save_state.set(SaveState::Idle);
+                    "Settings pasted. Review them, then Save.".to_string()
+                }
+                Ok(Err(err)) => format!("The clipboard doesn't hold deck settings: {err}"),
+                Err(_) => "Couldn't read the clipboard".to_string(),
+            };
+            preset_notice.set(Some(notice));
+        });
+    });
+
     let deck_title = form_value.name.trim().to_string();
     let deck_title = if deck_title.is_empty() {
         current_snapshot
@@ -249,6 +354,12 @@ pub fn SettingsView(deck_id: Option<u64>) -> Element {
                             active: active_section(),
                             on_select: on_nav_select,
                         }
+                        SettingsNavItem {
+                            label: "Appearance",
+                            section: SettingsSection::Appearance,
+                            active: active_section(),
+                            on_select: on_nav_select,
+                        }
                         SettingsNavItem {
                             label: "Advanced",
                             section: SettingsSection::Advanced,
@@ -306,8 +417,16 @@ pub fn SettingsView(deck_id: Option<u64>) -> Element {
                                 class: "settings-search-input",
                                 r#type: "search",
                                 placeholder: "Search",
-                                value: "{search()}",
-                                oninput: move |evt| search.set(evt.value()),
+                                value: "{search.read().query()}",
+                                oninput: move |evt| {
+                                    let next = SettingsSearch::new(evt.value());
+                                    // Searching opens every matching section; clearing it
+                                    // goes back to the default layout.
+                                    if search.read().is_active() && !next.is_active() {
+                                        expanded_section.set(Some(SettingsSection::DailyLimits));
+                                    }
+                                    search.set(next);
+                                },
                             }
                         }
                     }
@@ -321,6 +440,11 @@ pub fn SettingsView(deck_id: Option<u64>) -> Element {
                         },
                         ViewState::Ready(_) => {
                             rsx! {
+                                if !search.read().has_matches() {
+                                    p { class: "settings-search-empty",
+                                        "No settings match \"{search.read().query().trim()}\"."
+                                    }
+                                }
                                 section { class: "settings-accordion",
                                     {daily_limits_section(form, errors, save_state, expanded_section)}
                                     {lapses_section(form, errors, save_state, expanded_section)}
@@ -328,14 +452,48 @@ pub fn SettingsView(deck_id: Option<u64>) -> Element {
                                     {audio_section(autoplay_audio, replay_audio_after_answer, audio_delay_ms, expanded_section)}
                                     {timers_section(form, errors, save_state, expanded_section)}
                                     {easy_days_section(form, errors, save_state, expanded_section)}
-                                    {advanced_section(form, errors, save_state, reset_state, show_reset_modal, expanded_section)}
+                                    {appearance_section(form, save_state, expanded_section)}
+                                    {advanced_section(form, errors, save_state, reset_state, show_reset_modal, prune, expanded_section)}
                                 }
 
                                 footer { class: "settings-footer",
                                     if let Some(label) = status_label {
                                         span { class: "settings-footer-status", "{label}" }
                                     }
+                                    if save_state() == SaveState::Conflict {
+                                        div { class: "settings-conflict", role: "alert",
+                                            span {
+                                                "Saved from another window. Reload to get those changes; edits here will be lost."
+                                            }
+                                            button {
+                                                class: "btn btn-secondary",
+                                                r#type: "button",
+                                                onclick: move |_| {
+                                                    initial_snapshot.set(None);
+                                                    save_state.set(SaveState::Idle);
+                                                    let mut resource = resource;
+                                                    resource.restart();
+                                                },
+                                                "Reload"
+                                            }
+                                        }
+                                    }
+                                    if let Some(notice) = preset_notice() {
+                                        span { class: "settings-footer-status", role: "status", "{notice}" }
+                                    }
                                     div { class: "settings-footer-actions",
+                                        button {
+                                            class: "btn btn-secondary",
+                                            r#type: "button",
+                                            onclick: move |_| on_copy_preset.call(()),
+                                            "Copy Settings"
+                                        }
+                                        button {
+                                            class: "btn btn-secondary",
+                                            r#type: "button",
+                                            onclick: move |_| on_paste_preset.call(()),
+                                            "Paste Settings"
+                                        }
                                         button {
                                             class: "btn btn-secondary",
                                             r#type: "button",
@@ -422,6 +580,57 @@ pub fn SettingsView(deck_id: Option<u64>) -> Element {
                     }
                 }
             }
+            if matches!(
+                prune_state(),
+                PruneState::Confirming | PruneState::Pruning | PruneState::Error(_)
+            ) {
+                div {
+                    class: "editor-modal-overlay",
+                    onclick: move |_| prune_state.set(PruneState::Idle),
+                    div {
+                        class: "editor-modal",
+                        onclick: move |evt| evt.stop_propagation(),
+                        h3 { class: "editor-modal-title", "Prune review history?" }
+                        p { class: "editor-modal-body",
+                            "This deletes all but the newest {prune.keep.read().trim()} reviews of each card in this deck. Scheduling is unchanged, but FSRS optimization learns only from the reviews that remain, so pruning deeply makes future optimization less accurate."
+                        }
+                        if let PruneState::Error(err) = prune_state() {
+                            p { class: "editor-modal-error", "{err.message()}" }
+                        }
+                        div { class: "editor-modal-actions",
+                            button {
+                                class: "btn editor-modal-cancel",
+                                r#type: "button",
+                                onclick: move |_| prune_state.set(PruneState::Idle),
+                                "Cancel"
+                            }
+                            button {
+                                class: "btn editor-modal-confirm",
+                                r#type: "button",
+                                disabled: prune_state() == PruneState::Pruning,
+                                onclick: move |_| {
+                                    let Some(keep) = prune.keep_count() else {
+                                        return;
+                                    };
+                                    let mut prune_state = prune_state;
+                                    let deck_service = deck_service.clone();
+                                    spawn(async move {
+                                        prune_state.set(PruneState::Pruning);
+                                        let prune = ReviewLogPrune::KeepLastPerCard(keep);
+                                        match deck_service.prune_history(deck_id, prune).await {
+                                            Ok(count) => prune_state.set(PruneState::Pruned(count)),
+                                            Err(_) => {
+                                                prune_state.set(PruneState::Error(ViewError::Unknown));
+                                            }
+                                        }
+                                    });
+                                },
+                                "Prune"
+                            }
+                        }
+                    }
+                }
+            }
         }
     }
 }
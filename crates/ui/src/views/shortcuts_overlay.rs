@@ -0,0 +1,56 @@
+use dioxus::document::eval;
+use dioxus::prelude::*;
+
+use crate::shortcuts::Shortcut;
+
+const FOCUS_IS_EDITABLE_SCRIPT: &str = r"
+    const el = document.activeElement;
+    const tag = el ? el.tagName : '';
+    return Boolean(el && (el.isContentEditable || tag === 'INPUT' || tag === 'TEXTAREA'));
+";
+
+/// Show the shortcuts legend, unless "?" was typed into a text field.
+pub(crate) fn open_shortcuts_legend(mut show: Signal<bool>) {
+    spawn(async move {
+        let typing = eval(FOCUS_IS_EDITABLE_SCRIPT).join::<bool>().await.unwrap_or(false);
+        if !typing {
+            show.set(true);
+        }
+    });
+}
+
+/// Modal listing a view's keyboard shortcuts.
+///
+/// The view's own key handler closes it on Escape, since focus stays on the page.
+#[component]
+pub(crate) fn ShortcutsOverlay(shortcuts: Vec<Shortcut>, on_close: Callback<()>) -> Element {
+    rsx! {
+        div {
+            class: "editor-modal-overlay",
+            onclick: move |_| on_close.call(()),
+            div {
+                class: "editor-modal shortcuts-modal",
+                role: "dialog",
+                aria_label: "Keyboard shortcuts",
+                onclick: move |evt| evt.stop_propagation(),
+                h3 { class: "editor-modal-title", "Keyboard shortcuts" }
+                dl { class: "shortcuts-list",
+                    for shortcut in shortcuts {
+                        div { key: "{shortcut.label()}", class: "shortcuts-row",
+                            dt { kbd { class: "shortcuts-keys", "{shortcut.label()}" } }
+                            dd { class: "shortcuts-description", "{shortcut.description}" }
+                        }
+                    }
+                }
+                div { class: "editor-modal-actions",
+                    button {
+                        class: "btn editor-modal-cancel",
+                        r#type: "button",
+                        onclick: move |_| on_close.call(()),
+                        "Close"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -27,7 +27,7 @@ pub fn SummaryView(summary_id: i64) -> Element {
                 .map_err(|_| ViewError::Unknown)?;
 
             Ok::<_, ViewError>(SummaryData {
-                summary: map_session_summary_detail(&summary),
+                summary: map_session_summary_detail(&summary, summaries.now()),
             })
         }
     });
@@ -78,22 +78,36 @@ fn SummaryDetails(summary: SessionSummaryDetailVm) -> Element {
             dd { "{summary.started_at_str}" }
 
             dt { "Completed" }
-            dd { "{summary.completed_at_str}" }
+            dd { title: "{summary.completed_at_str}", "{summary.completed_ago}" }
 
             dt { "Total" }
             dd { "{summary.total}" }
 
-            dt { "Again" }
-            dd { "{summary.again}" }
-
-            dt { "Hard" }
-            dd { "{summary.hard}" }
-
-            dt { "Good" }
-            dd { "{summary.good}" }
-
-            dt { "Easy" }
-            dd { "{summary.easy}" }
+            dt { "Grades" }
+            dd {
+                div { class: "summary-grades",
+                    if summary.total > 0 {
+                        div { class: "summary-grade-bar", aria_hidden: "true",
+                            for entry in summary.grades.iter().filter(|entry| entry.count > 0) {
+                                span {
+                                    key: "{entry.label}",
+                                    class: "summary-grade-seg summary-grade-seg--{entry.modifier}",
+                                    style: "flex-grow: {entry.count};",
+                                }
+                            }
+                        }
+                    }
+                    ul { class: "summary-grade-list",
+                        for entry in summary.grades.iter() {
+                            li {
+                                key: "{entry.label}",
+                                class: "history-metric history-metric--{entry.modifier}",
+                                "{entry.label} {entry.count} ({entry.pct}%)"
+                            }
+                        }
+                    }
+                }
+            }
         }
     }
 }
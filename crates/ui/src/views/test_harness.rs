@@ -4,17 +4,17 @@ use dioxus::core::NoOpMutations;
 use dioxus::prelude::*;
 use dioxus_router::{Routable, Router};
 use learn_core::model::DeckId;
-use learn_core::model::DeckSettings;
+use learn_core::model::{DeckSettings, MediaUri};
 use learn_core::time::fixed_now;
 use services::{
-    AppSettingsService, CardService, Clock, DeckService, SessionLoopService, SessionSummaryService,
-    WritingToolsService,
+    AppSettingsService, CardService, Clock, DeckService, MediaService, SessionLoopService,
+    SessionSummaryService, WritingToolsService,
 };
 use storage::repository::{SessionSummaryRepository, Storage};
 
 use crate::context::{UiApp, build_app_context};
-use crate::platform::UiLinkOpener;
-use crate::views::{HistoryView, HomeView, PracticeView, SummaryView, SessionView};
+use crate::platform::{UiAudioPlayer, UiLinkOpener};
+use crate::views::{HistoryView, HomeView, PracticeView, SearchView, SummaryView, SessionView};
 use crate::views::session::SessionTestHandles;
 
 #[derive(Clone)]
@@ -24,9 +24,12 @@ struct TestApp {
     session_loop: Arc<SessionLoopService>,
     card_service: Arc<CardService>,
     deck_service: Arc<DeckService>,
+    media_service: Arc<MediaService>,
     writing_tools: Arc<WritingToolsService>,
+    ai_usage: Arc<services::AiUsageService>,
     app_settings: Arc<AppSettingsService>,
     link_opener: Arc<dyn UiLinkOpener>,
+    audio_player: Arc<dyn UiAudioPlayer>,
 }
 
 struct NoopLinkOpener;
@@ -35,6 +38,14 @@ impl UiLinkOpener for NoopLinkOpener {
     fn open_url(&self, _url: &str) {}
 }
 
+struct NoopAudioPlayer;
+
+impl UiAudioPlayer for NoopAudioPlayer {
+    fn play(&self, _source: &MediaUri) {}
+
+    fn stop(&self) {}
+}
+
 impl UiApp for TestApp {
     fn current_deck_id(&self) -> DeckId {
         self.deck_id
@@ -60,6 +71,10 @@ impl UiApp for TestApp {
         Arc::clone(&self.deck_service)
     }
 
+    fn media_service(&self) -> Arc<MediaService> {
+        Arc::clone(&self.media_service)
+    }
+
     fn app_settings(&self) -> Arc<AppSettingsService> {
         Arc::clone(&self.app_settings)
     }
@@ -68,10 +83,18 @@ impl UiApp for TestApp {
         Arc::clone(&self.writing_tools)
     }
 
+    fn ai_usage(&self) -> Arc<services::AiUsageService> {
+        Arc::clone(&self.ai_usage)
+    }
+
     fn link_opener(&self) -> Arc<dyn UiLinkOpener> {
         Arc::clone(&self.link_opener)
     }
 
+    fn audio_player(&self) -> Arc<dyn UiAudioPlayer> {
+        Arc::clone(&self.audio_player)
+    }
+
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -79,6 +102,7 @@ pub enum ViewKind {
     Home,
     Practice,
     History,
+    Search(&'static str),
     Summary(i64),
     Session(u64),
 }
@@ -123,8 +147,16 @@ fn Root() -> Element {
         ViewKind::Home => rsx! { HomeView {} },
         ViewKind::Practice => rsx! { PracticeView {} },
         ViewKind::History => rsx! { HistoryView {} },
+        ViewKind::Search(query) => rsx! { SearchView { query } },
         ViewKind::Summary(summary_id) => rsx! { SummaryView { summary_id } },
-        ViewKind::Session(deck_id) => rsx! { SessionView { deck_id, tag: None, mode: crate::vm::SessionStartMode::Due } },
+        ViewKind::Session(deck_id) => rsx! {
+            SessionView {
+                deck_id,
+                tag: None,
+                mode: crate::vm::SessionStartMode::Due,
+                card_ids: Vec::new(),
+            }
+        },
     }
 }
 
@@ -176,7 +208,12 @@ pub async fn setup_view_harness_with_summary_repo(
     summaries: Arc<dyn SessionSummaryRepository>,
 ) -> ViewHarness {
     let clock = Clock::fixed(fixed_now());
-    let deck_service = Arc::new(DeckService::new(clock, Arc::clone(&storage.decks)));
+    let deck_service = Arc::new(DeckService::new(
+        clock,
+        Arc::clone(&storage.decks),
+        Arc::clone(&storage.cards),
+        Arc::clone(&storage.review_logs),
+    ));
     let card_service = Arc::new(CardService::new(clock, Arc::clone(&storage.cards)));
     let card_service_for_harness = Arc::clone(&card_service);
     let session_summaries = Arc::new(SessionSummaryService::new(clock, Arc::clone(&summaries)));
@@ -186,7 +223,8 @@ pub async fn setup_view_harness_with_summary_repo(
         Arc::clone(&storage.cards),
         Arc::clone(&storage.reviews),
         Arc::clone(&summaries),
-    ));
+    )
+    .with_progress(Arc::clone(&storage.session_progress)));
     let ai_usage = Arc::new(services::AiUsageService::new(
         clock,
         Arc::clone(&storage.app_settings),
@@ -218,13 +256,16 @@ pub async fn setup_view_harness_with_summary_repo(
         session_loop,
         card_service,
         deck_service,
+        media_service: Arc::new(MediaService::new(clock, Arc::clone(&storage.media))),
         writing_tools: Arc::new(WritingToolsService::new(
             Arc::clone(&storage.app_settings),
             None,
             Arc::clone(&ai_usage),
         )),
+        ai_usage,
         app_settings: Arc::new(AppSettingsService::new(Arc::clone(&storage.app_settings))),
         link_opener: Arc::new(NoopLinkOpener),
+        audio_player: Arc::new(NoopAudioPlayer),
     });
 
     let dom = VirtualDom::new_with_props(
@@ -252,7 +293,12 @@ pub async fn setup_view_harness_with_session_loop(
     session_loop: Arc<SessionLoopService>,
 ) -> ViewHarness {
     let clock = Clock::fixed(fixed_now());
-    let deck_service = Arc::new(DeckService::new(clock, Arc::clone(&storage.decks)));
+    let deck_service = Arc::new(DeckService::new(
+        clock,
+        Arc::clone(&storage.decks),
+        Arc::clone(&storage.cards),
+        Arc::clone(&storage.review_logs),
+    ));
     let card_service = Arc::new(CardService::new(clock, Arc::clone(&storage.cards)));
     let card_service_for_harness = Arc::clone(&card_service);
     let session_summaries = Arc::new(SessionSummaryService::new(
@@ -290,13 +336,16 @@ pub async fn setup_view_harness_with_session_loop(
         session_loop,
         card_service,
         deck_service,
+        media_service: Arc::new(MediaService::new(clock, Arc::clone(&storage.media))),
         writing_tools: Arc::new(WritingToolsService::new(
             Arc::clone(&storage.app_settings),
             None,
             Arc::clone(&ai_usage),
         )),
+        ai_usage,
         app_settings: Arc::new(AppSettingsService::new(Arc::clone(&storage.app_settings))),
         link_opener: Arc::new(NoopLinkOpener),
+        audio_player: Arc::new(NoopAudioPlayer),
     });
 
     let dom = VirtualDom::new_with_props(
@@ -2,13 +2,15 @@ use chrono::Duration;
 use dioxus::prelude::ReadableExt;
 use learn_core::model::content::ContentDraft;
 use learn_core::model::{
-    CardId, Deck, DeckId, DeckSettings, ReviewGrade, ReviewLog, SessionSummary, TagName,
+    AnswerButtons, AppSettings, Card, CardId, Deck, DeckId, DeckSettings, Flag, ReviewGrade,
+    ReviewLog, SessionSummary, TagName,
 };
 use learn_core::time::fixed_now;
+use services::test_support::single_pass_settings;
 use services::{Clock, SessionLoopService};
 use storage::repository::{
-    DeckRepository, InMemoryRepository, NewDeckRecord, SessionSummaryRepository, Storage,
-    StorageError,
+    DeckMergeCounts, DeckRepository, InMemoryRepository, MergeConflict, NewDeckRecord,
+    PendingSessionCard, SessionProgressRecord, SessionSummaryRepository, Storage, StorageError,
 };
 
 use super::test_harness::{
@@ -49,6 +51,7 @@ async fn home_view_smoke_renders_recent_count() {
             deck_id,
             ContentDraft::text_only("What is Rust?"),
             ContentDraft::text_only("A systems language."),
+            None,
         )
         .await
         .expect("create card");
@@ -79,6 +82,7 @@ async fn home_view_smoke_renders_recent_count() {
     assert!(html.contains("Default"), "missing deck name in {html}");
     assert!(html.contains("0 Due"), "missing due label in {html}");
     assert!(html.contains("1 New"), "missing new label in {html}");
+    assert!(html.contains("0 due now \u{00b7} 1 new"), "missing due badge in {html}");
     assert!(
         html.contains("Today \u{00b7} 10:13 PM \u{00b7} 1 Cards"),
         "missing today label in {html}"
@@ -87,6 +91,41 @@ async fn home_view_smoke_renders_recent_count() {
         html.contains("Yesterday \u{00b7} 10:13 PM \u{00b7} 1 Cards"),
         "missing yesterday label in {html}"
     );
+    assert!(!html.contains("Resume previous session"), "unexpected resume link in {html}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn home_view_smoke_offers_to_resume_a_saved_session() {
+    let mut harness = setup_view_harness(ViewKind::Home, "Default").await;
+    let deck_id = harness.deck_id;
+    let card_id = harness
+        .card_service
+        .create_card(
+            deck_id,
+            ContentDraft::text_only("What is Rust?"),
+            ContentDraft::text_only("A systems language."),
+            None,
+        )
+        .await
+        .expect("create card")
+        .id;
+    harness
+        .storage
+        .session_progress
+        .save_progress(&SessionProgressRecord {
+            deck_id,
+            started_at: fixed_now(),
+            is_cram: false,
+            is_ahead: false,
+            answered: Vec::new(),
+            pending: vec![PendingSessionCard { card_id, next_review_at: fixed_now() }],
+        })
+        .await
+        .expect("save progress");
+
+    harness.rebuild();
+    let html = harness.render();
+    assert!(html.contains("Resume previous session"), "missing resume link in {html}");
 }
 
 #[tokio::test(flavor = "current_thread")]
@@ -134,6 +173,45 @@ async fn history_view_smoke_renders_summary_card() {
     assert!(html.contains("% Good"), "missing grade breakdown in {html}");
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn search_view_smoke_labels_matches_by_deck() {
+    let mut harness = setup_view_harness(ViewKind::Search("photo"), "Biology").await;
+    let card_service = harness.card_service.clone();
+    let physics = Deck::new(
+        DeckId::new(1),
+        "Physics",
+        None,
+        DeckSettings::default_for_adhd(),
+        fixed_now(),
+    )
+    .unwrap();
+    let physics_id = harness
+        .storage
+        .decks
+        .insert_new_deck(NewDeckRecord::from_deck(&physics))
+        .await
+        .expect("insert deck");
+    for (deck_id, front) in [(harness.deck_id, "Photosynthesis"), (physics_id, "Photon")] {
+        card_service
+            .create_card(
+                deck_id,
+                ContentDraft::text_only(front),
+                ContentDraft::text_only("A"),
+                None,
+            )
+            .await
+            .expect("create card");
+    }
+
+    harness.rebuild();
+    let html = harness.render();
+    assert!(html.contains("Biology"), "missing first deck label in {html}");
+    assert!(html.contains("Physics"), "missing second deck label in {html}");
+    assert!(html.contains("Photosynthesis"), "missing first match in {html}");
+    assert!(html.contains("Photon"), "missing second match in {html}");
+    assert!(html.contains("/editor/"), "missing editor link in {html}");
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn summary_view_smoke_renders_details() {
     let repo = InMemoryRepository::new();
@@ -169,6 +247,10 @@ impl SessionSummaryRepository for FailingSummaryRepo {
         Err(StorageError::Connection("fail".to_string()))
     }
 
+    async fn delete_summary(&self, _id: i64) -> Result<(), StorageError> {
+        Err(StorageError::Connection("fail".to_string()))
+    }
+
     async fn list_summaries(
         &self,
         _deck_id: DeckId,
@@ -195,6 +277,14 @@ impl SessionSummaryRepository for FailingSummaryRepo {
     ) -> Result<Vec<storage::repository::SessionSummaryRow>, StorageError> {
         Err(StorageError::Connection("fail".to_string()))
     }
+
+    async fn list_summaries_between(
+        &self,
+        _completed_from: chrono::DateTime<chrono::Utc>,
+        _completed_until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<SessionSummary>, StorageError> {
+        Err(StorageError::Connection("fail".to_string()))
+    }
 }
 
 struct FailingDeckRepo;
@@ -209,11 +299,36 @@ impl DeckRepository for FailingDeckRepo {
         Err(StorageError::Connection("fail".to_string()))
     }
 
+    async fn update_deck_if_version(&self, _deck: &Deck) -> Result<u64, StorageError> {
+        Err(StorageError::Connection("fail".to_string()))
+    }
+
     async fn get_deck(&self, _id: DeckId) -> Result<Option<Deck>, StorageError> {
         Err(StorageError::Connection("fail".to_string()))
     }
 
-    async fn list_decks(&self, _limit: u32) -> Result<Vec<Deck>, StorageError> {
+    async fn list_decks(
+        &self,
+        _limit: u32,
+        _include_archived: bool,
+    ) -> Result<Vec<Deck>, StorageError> {
+        Err(StorageError::Connection("fail".to_string()))
+    }
+
+    async fn duplicate_deck(
+        &self,
+        _source: DeckId,
+        _deck: NewDeckRecord,
+    ) -> Result<DeckId, StorageError> {
+        Err(StorageError::Connection("fail".to_string()))
+    }
+
+    async fn merge_decks(
+        &self,
+        _source: DeckId,
+        _dest: DeckId,
+        _on_conflict: MergeConflict,
+    ) -> Result<DeckMergeCounts, StorageError> {
         Err(StorageError::Connection("fail".to_string()))
     }
 }
@@ -244,6 +359,7 @@ async fn session_view_smoke_reveal_and_grade() {
             deck_id,
             ContentDraft::text_only("What is Rust?"),
             ContentDraft::text_only("A systems language."),
+            None,
         )
         .await
         .expect("create card");
@@ -252,6 +368,7 @@ async fn session_view_smoke_reveal_and_grade() {
             deck_id,
             ContentDraft::text_only("What is Dioxus?"),
             ContentDraft::text_only("A Rust UI framework."),
+            None,
         )
         .await
         .expect("create card");
@@ -289,6 +406,152 @@ async fn session_view_smoke_reveal_and_grade() {
         vm_signal.read().as_ref().and_then(SessionVm::prompt_text),
         Some("What is Dioxus?")
     );
+
+    // The first card is queued again for its next learning step.
+    let html = harness.render();
+    assert!(html.contains("1 done · 2 left"), "missing progress label in {html}");
+    assert!(html.contains("aria-valuenow=\"33\""), "missing progress bar in {html}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn session_view_smoke_previews_learning_step_intervals() {
+    let mut harness = setup_view_harness(ViewKind::Session(0), "Default").await;
+    let deck_id = harness.deck_id;
+    harness
+        .card_service
+        .clone()
+        .create_card(
+            deck_id,
+            ContentDraft::text_only("What is Rust?"),
+            ContentDraft::text_only("A systems language."),
+            None,
+        )
+        .await
+        .expect("create card");
+
+    harness.rebuild();
+    harness.drive_async().await;
+    let handles = harness.session_handles.as_ref().expect("session handles");
+    handles.dispatch().call(SessionIntent::Reveal);
+    harness.drive_async().await;
+
+    // The default deck steps through 1m and 10m before graduating.
+    let html = harness.render();
+    assert!(html.contains("&#60;= 1m"), "missing Again preview in {html}");
+    assert!(html.contains("&#60;= 10m"), "missing Good preview in {html}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn session_view_smoke_cycles_the_current_card_flag() {
+    let mut harness = setup_view_harness(ViewKind::Session(0), "Default").await;
+    let deck_id = harness.deck_id;
+    let card_id = harness
+        .card_service
+        .clone()
+        .create_card(
+            deck_id,
+            ContentDraft::text_only("What is Rust?"),
+            ContentDraft::text_only("A systems language."),
+            None,
+        )
+        .await
+        .expect("create card")
+        .id;
+
+    harness.rebuild();
+    harness.drive_async().await;
+    let dispatch = harness.session_handles.as_ref().expect("session handles").dispatch();
+    dispatch.call(SessionIntent::CycleFlag);
+    harness.drive_async().await;
+    dispatch.call(SessionIntent::CycleFlag);
+    harness.drive_async().await;
+
+    let html = harness.render();
+    assert!(html.contains("card-flag--orange"), "missing flag dot in {html}");
+    let stored = harness.card_service.list_cards(deck_id, 10).await.expect("list cards");
+    assert_eq!(
+        stored.iter().find(|card| card.id() == card_id).and_then(Card::flag),
+        Some(Flag::Orange)
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn session_view_smoke_opens_in_saved_focus_mode() {
+    let mut harness = setup_view_harness(ViewKind::Session(0), "Default").await;
+    let deck_id = harness.deck_id;
+    harness
+        .card_service
+        .clone()
+        .create_card(
+            deck_id,
+            ContentDraft::text_only("What is Rust?"),
+            ContentDraft::text_only("A systems language."),
+            None,
+        )
+        .await
+        .expect("create card");
+    let mut draft = AppSettings::default().to_draft();
+    draft.focus_mode = Some(true);
+    harness
+        .storage
+        .app_settings
+        .save_settings(&draft.validate().expect("valid settings"))
+        .await
+        .expect("save settings");
+
+    harness.rebuild();
+    harness.drive_async().await;
+
+    let html = harness.render();
+    assert!(html.contains("session-page--focus"), "missing focus mode in {html}");
+    assert!(html.contains("Exit focus mode"), "missing exit button in {html}");
+    assert!(!html.contains("Default"), "deck name shown in {html}");
+    assert!(!html.contains("Bury until tomorrow"), "chrome shown in {html}");
+
+    let handles = harness.session_handles.as_ref().expect("session handles");
+    handles.dispatch().call(SessionIntent::Reveal);
+    harness.drive_async().await;
+    let html = harness.render();
+    assert!(html.contains("session-grade-good"), "missing grade buttons in {html}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn session_view_smoke_two_button_deck_hides_hard_and_easy() {
+    let mut harness = setup_view_harness(ViewKind::Session(0), "Default").await;
+    let deck_id = harness.deck_id;
+    let card_service = harness.card_service.clone();
+
+    let deck = harness.storage.decks.get_deck(deck_id).await.unwrap().expect("deck");
+    let settings = deck.settings().clone().with_answer_buttons(AnswerButtons::Two);
+    harness
+        .storage
+        .decks
+        .upsert_deck(&deck.with_settings(settings))
+        .await
+        .expect("update deck");
+
+    card_service
+        .create_card(
+            deck_id,
+            ContentDraft::text_only("What is Rust?"),
+            ContentDraft::text_only("A systems language."),
+            None,
+        )
+        .await
+        .expect("create card");
+
+    harness.rebuild();
+    harness.drive_async().await;
+
+    let handles = harness.session_handles.as_ref().expect("session handles");
+    handles.dispatch().call(SessionIntent::Reveal);
+    harness.drive_async().await;
+
+    let html = harness.render();
+    assert!(html.contains("session-grade-again"), "missing Again in {html}");
+    assert!(html.contains("session-grade-good"), "missing Good in {html}");
+    assert!(!html.contains("session-grade-hard"), "unexpected Hard in {html}");
+    assert!(!html.contains("session-grade-easy"), "unexpected Easy in {html}");
 }
 
 #[tokio::test(flavor = "current_thread")]
@@ -297,11 +560,21 @@ async fn session_view_smoke_completion_footer() {
     let deck_id = harness.deck_id;
     let card_service = harness.card_service.clone();
 
+    // Without learning steps a Good answer graduates the card, so the session ends after it.
+    let deck = harness.storage.decks.get_deck(deck_id).await.unwrap().expect("deck");
+    harness
+        .storage
+        .decks
+        .upsert_deck(&deck.with_settings(single_pass_settings()))
+        .await
+        .expect("update deck");
+
     card_service
         .create_card(
             deck_id,
             ContentDraft::text_only("What is Rust?"),
             ContentDraft::text_only("A systems language."),
+            None,
         )
         .await
         .expect("create card");
@@ -332,6 +605,48 @@ async fn session_view_smoke_completion_footer() {
     );
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn session_view_smoke_nothing_due_shows_the_next_due_time() {
+    let mut harness = setup_view_harness(ViewKind::Session(0), "Default").await;
+    let deck_id = harness.deck_id;
+    let card_service = harness.card_service.clone();
+
+    let deck = harness.storage.decks.get_deck(deck_id).await.unwrap().expect("deck");
+    harness
+        .storage
+        .decks
+        .upsert_deck(&deck.with_settings(single_pass_settings()))
+        .await
+        .expect("update deck");
+
+    card_service
+        .create_card(
+            deck_id,
+            ContentDraft::text_only("What is Rust?"),
+            ContentDraft::text_only("A systems language."),
+            None,
+        )
+        .await
+        .expect("create card");
+
+    harness.rebuild();
+    harness.drive_async().await;
+
+    let dispatch = harness.session_handles.as_ref().expect("session handles").dispatch();
+    dispatch.call(SessionIntent::Reveal);
+    harness.drive_async().await;
+    dispatch.call(SessionIntent::Grade(ReviewGrade::Good));
+    harness.drive_async().await;
+
+    harness.rebuild();
+    harness.drive_async().await;
+
+    let html = harness.render();
+    assert!(html.contains("All done for today!"), "missing all-done state in {html}");
+    assert!(html.contains("The next card is due in"), "missing next due time in {html}");
+    assert!(html.contains("Back to Practice"), "missing practice CTA in {html}");
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn session_view_smoke_empty_state() {
     let mut harness = setup_view_harness(ViewKind::Session(0), "Default").await;
@@ -0,0 +1,391 @@
+use learn_core::model::{CardSide, CardTemplate, Content, TemplateField, TemplatePart};
+
+use super::markdown_vm::{
+    looks_like_html, looks_like_markdown, markdown_to_html, sanitize_html, strip_html_tags,
+};
+
+/// Image formats a rendered card may inline, matching what the media service stores.
+const INLINE_IMAGE_TYPES: [&str; 4] = ["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// Shown in place of a card image that is no longer stored.
+const MEDIA_UNAVAILABLE_HTML: &str = r#"<p class="card-media-missing">Media unavailable</p>"#;
+
+/// What loading a card side's image turned up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CardMedia {
+    /// A displayable source, such as the data URL from `MediaService::image_data_url`.
+    Src(String),
+    /// The image is referenced but no longer stored.
+    Missing,
+}
+
+impl CardMedia {
+    /// Interpret the result of `MediaService::image_data_url`.
+    #[must_use]
+    pub fn from_data_url(url: Option<String>) -> Self {
+        url.map_or(Self::Missing, Self::Src)
+    }
+}
+
+/// Render one side of a card to sanitized HTML for `dangerous_inner_html`.
+///
+/// `media` is the content's image once loaded. A source is appended below the text only
+/// when the content references an image and the source is an inline image of a supported
+/// type; a missing image appends a "Media unavailable" placeholder instead.
+#[must_use]
+pub fn render_card_content(content: &Content, media: Option<&CardMedia>) -> String {
+    let mut html = render_card_text(content.text());
+    if content.media_id().is_none() {
+        return html;
+    }
+    match media {
+        Some(CardMedia::Src(src)) if is_inline_image_src(src) => {
+            html.push_str(&format!(r#"<img class="card-image" src="{src}" alt="">"#));
+        }
+        Some(CardMedia::Missing) => html.push_str(MEDIA_UNAVAILABLE_HTML),
+        Some(CardMedia::Src(_)) | None => {}
+    }
+    html
+}
+
+/// Render card text to sanitized HTML.
+///
+/// Stored HTML is sanitized as-is, markdown is converted first, and plain text is left
+/// as text.
+#[must_use]
+pub fn render_card_text(text: &str) -> String {
+    if !looks_like_html(text) && looks_like_markdown(text) {
+        markdown_to_html(text)
+    } else {
+        sanitize_html(text)
+    }
+}
+
+/// Rendered card content handed to `render_card_template`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TemplateFields<'a> {
+    /// Sanitized HTML of the prompt side, as from `render_card_content`.
+    pub front_html: &'a str,
+    /// Sanitized HTML of the answer side, as from `render_card_content`.
+    pub back_html: &'a str,
+    /// The card's extra note, as plain text.
+    pub extra: Option<&'a str>,
+}
+
+/// Lay out one side of a card with a deck template, falling back to the default layout.
+///
+/// The template's own markup goes through `sanitize_html`, so it may only use the tags card
+/// text may use. Field HTML is inserted after that pass and is expected to be sanitized
+/// already; a missing extra renders as nothing.
+#[must_use]
+pub fn render_card_template(
+    template: Option<&CardTemplate>,
+    side: CardSide,
+    fields: &TemplateFields<'_>,
+) -> String {
+    let default;
+    let template = match template {
+        Some(template) => template,
+        None => {
+            default = CardTemplate::default_for(side);
+            &default
+        }
+    };
+    let layout: String = template
+        .parts()
+        .iter()
+        .map(|part| match part {
+            TemplatePart::Text(text) => text.clone(),
+            TemplatePart::Field(field) => field_token(*field),
+        })
+        .collect();
+    let extra_html = fields
+        .extra
+        .filter(|extra| !extra.trim().is_empty())
+        .map(|extra| format!(r#"<p class="card-extra">{}</p>"#, ammonia::clean_text(extra)))
+        .unwrap_or_default();
+
+    let sanitized = sanitize_html(&layout);
+    let mut html = String::with_capacity(sanitized.len());
+    let mut rest = sanitized.as_str();
+    while let Some(start) = rest.find("{{") {
+        html.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let matched = TemplateField::ALL.into_iter().find_map(|field| {
+            let token = field_token(field);
+            rest.starts_with(&token).then_some((field, token.len()))
+        });
+        let Some((field, len)) = matched else {
+            html.push_str("{{");
+            rest = &rest[2..];
+            continue;
+        };
+        html.push_str(match field {
+            TemplateField::Front => fields.front_html,
+            TemplateField::Back => fields.back_html,
+            TemplateField::Extra => &extra_html,
+        });
+        rest = &rest[len..];
+    }
+    html.push_str(rest);
+    html
+}
+
+/// Closing tags after which stored HTML may pause during a progressive reveal.
+const REVEAL_BREAK_TAGS: [&str; 6] = ["<br>", "<br/>", "<br />", "</p>", "</li>", "</div>"];
+
+/// Answer text revealed at each step of a progressive reveal, the last step being all of it.
+///
+/// Paragraphs separated by a blank line are one step each; text without a blank line
+/// reveals line by line, keeping fenced code and table rows together. Stored HTML breaks
+/// after line breaks, paragraphs, list items, and divs. Every step adds visible text.
+#[must_use]
+pub fn reveal_step_prefixes(text: &str) -> Vec<&str> {
+    let text = text.trim();
+    let candidates = if looks_like_html(text) {
+        html_reveal_breaks(text)
+    } else {
+        text_reveal_breaks(text)
+    };
+    let mut prefixes = Vec::new();
+    for end in candidates {
+        let (shown, rest) = text.split_at(end);
+        let adds_text = prefixes
+            .last()
+            .is_none_or(|previous: &&str| has_visible_text(&shown[previous.len()..]));
+        if adds_text && has_visible_text(shown) && has_visible_text(rest) {
+            prefixes.push(shown);
+        }
+    }
+    prefixes.push(text);
+    prefixes
+}
+
+fn html_reveal_breaks(html: &str) -> Vec<usize> {
+    let lower = html.to_ascii_lowercase();
+    let mut ends: Vec<usize> = REVEAL_BREAK_TAGS
+        .iter()
+        .flat_map(|tag| lower.match_indices(tag).map(|(at, tag)| at + tag.len()))
+        .collect();
+    ends.sort_unstable();
+    ends.dedup();
+    ends
+}
+
+fn text_reveal_breaks(text: &str) -> Vec<usize> {
+    let by_paragraph = text.lines().any(|line| line.trim().is_empty());
+    let mut ends = Vec::new();
+    let mut offset = 0;
+    let mut in_fence = false;
+    let mut lines = text.split_inclusive('\n').peekable();
+    while let Some(line) = lines.next() {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim();
+        if content.starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if in_fence {
+            continue;
+        }
+        let next = lines.peek().map_or("", |next| next.trim());
+        if by_paragraph {
+            if content.is_empty() {
+                ends.push(start);
+            }
+        } else if !(content.starts_with('|') && next.starts_with('|')) {
+            ends.push(start + line.trim_end().len());
+        }
+    }
+    ends
+}
+
+fn has_visible_text(fragment: &str) -> bool {
+    !strip_html_tags(fragment).trim().is_empty()
+}
+
+fn field_token(field: TemplateField) -> String {
+    format!("{{{{{}}}}}", field.as_str())
+}
+
+/// A `data:` URL with a supported image type and a base64 payload.
+fn is_inline_image_src(src: &str) -> bool {
+    let Some((mime, payload)) = src
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"))
+    else {
+        return false;
+    };
+    INLINE_IMAGE_TYPES.contains(&mime)
+        && !payload.is_empty()
+        && payload
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'+' | b'/' | b'='))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use learn_core::model::{ContentDraft, MediaId, TemplateError};
+    use learn_core::time::fixed_now;
+
+    fn content(text: &str, media_id: Option<MediaId>) -> Content {
+        ContentDraft::text_only(text)
+            .with_media_id(media_id)
+            .validate(fixed_now(), None, None)
+            .unwrap()
+    }
+
+    #[test]
+    fn renders_markdown_with_tables_and_code() {
+        let html = render_card_text(
+            "**Bold** and `code`\n\n| A | B |\n| - | - |\n| 1 | 2 |\n\n```rust\nlet x = 1;\n```\n",
+        );
+
+        assert!(html.contains("<strong>Bold</strong>"));
+        assert!(html.contains("<code>code</code>"));
+        assert!(html.contains("<table>"));
+        assert!(html.contains("<td>1</td>"));
+        assert!(html.contains(r#"<code class="language-rust">"#));
+    }
+
+    #[test]
+    fn reveal_steps_split_paragraphs_then_lines() {
+        assert_eq!(reveal_step_prefixes("  Paris  "), ["Paris"]);
+        assert_eq!(reveal_step_prefixes(""), [""]);
+
+        assert_eq!(
+            reveal_step_prefixes("First idea.\nStill first.\n\nSecond idea.\n\n\nThird."),
+            [
+                "First idea.\nStill first.",
+                "First idea.\nStill first.\n\nSecond idea.",
+                "First idea.\nStill first.\n\nSecond idea.\n\n\nThird.",
+            ]
+        );
+        assert_eq!(reveal_step_prefixes("- one\n- two\n- three"), [
+            "- one",
+            "- one\n- two",
+            "- one\n- two\n- three",
+        ]);
+    }
+
+    #[test]
+    fn reveal_steps_keep_code_and_tables_whole_and_break_stored_html() {
+        let code = "Use this:\n```\nlet a = 1;\nlet b = 2;\n```";
+        assert_eq!(reveal_step_prefixes(code), ["Use this:", code]);
+        let table = "| A | B |\n| - | - |\n| 1 | 2 |\nDone";
+        assert_eq!(reveal_step_prefixes(table), ["| A | B |\n| - | - |\n| 1 | 2 |", table]);
+
+        let html = "<ul><li>one</li><li>two</li></ul>";
+        assert_eq!(reveal_step_prefixes(html), ["<ul><li>one</li>", html]);
+        assert_eq!(
+            reveal_step_prefixes("<p>a</p><p></p><p>b</p>"),
+            ["<p>a</p>", "<p>a</p><p></p><p>b</p>"]
+        );
+    }
+
+    #[test]
+    fn keeps_stored_html_and_plain_text() {
+        assert_eq!(render_card_text("<p>Hello <b>there</b></p>"), "<p>Hello <b>there</b></p>");
+        assert_eq!(render_card_text("Plain answer"), "Plain answer");
+    }
+
+    #[test]
+    fn appends_the_resolved_image_source() {
+        let src = "data:image/png;base64,iVBORw0KGgo=";
+        let media = CardMedia::Src(src.to_string());
+        let with_image = content("Front", Some(MediaId::new(7)));
+
+        let html = render_card_content(&with_image, Some(&media));
+        assert_eq!(html, format!(r#"Front<img class="card-image" src="{src}" alt="">"#));
+
+        assert_eq!(render_card_content(&with_image, None), "Front");
+        assert_eq!(render_card_content(&content("Front", None), Some(&media)), "Front");
+    }
+
+    #[test]
+    fn shows_a_placeholder_for_a_missing_image() {
+        let with_image = content("Front", Some(MediaId::new(7)));
+        let missing = CardMedia::from_data_url(None);
+
+        let html = render_card_content(&with_image, Some(&missing));
+        assert_eq!(html, format!("Front{MEDIA_UNAVAILABLE_HTML}"));
+        assert_eq!(render_card_content(&content("Front", None), Some(&missing)), "Front");
+    }
+
+    #[test]
+    fn rejects_image_sources_that_are_not_inline_images() {
+        let with_image = content("Front", Some(MediaId::new(7)));
+        for src in [
+            "javascript:alert(1)",
+            "https://example.com/cat.png",
+            "data:text/html;base64,PHNjcmlwdD4=",
+            r#"data:image/png;base64,AAAA" onerror="alert(1)"#,
+        ] {
+            let media = CardMedia::Src(src.to_string());
+            assert_eq!(render_card_content(&with_image, Some(&media)), "Front", "{src}");
+        }
+    }
+
+    #[test]
+    fn renders_default_layouts_when_the_deck_sets_none() {
+        let fields = TemplateFields {
+            front_html: "<p>Question</p>",
+            back_html: "<p>Answer</p>",
+            extra: Some("Note <b>&</b>"),
+        };
+
+        assert_eq!(render_card_template(None, CardSide::Prompt, &fields), "<p>Question</p>");
+        assert_eq!(
+            render_card_template(None, CardSide::Answer, &fields),
+            r#"<p>Answer</p><p class="card-extra">Note&#32;&lt;b&gt;&amp;&lt;&#47;b&gt;</p>"#
+        );
+        let no_extra = TemplateFields { extra: Some("  "), ..fields };
+        assert_eq!(render_card_template(None, CardSide::Answer, &no_extra), "<p>Answer</p>");
+    }
+
+    #[test]
+    fn substitutes_fields_into_sanitized_template_markup() {
+        let template = CardTemplate::parse(
+            CardSide::Answer,
+            r#"<div onclick="x()"><em>{{front}}</em></div><script>{{extra}}</script>{{back}}!"#,
+        )
+        .unwrap();
+        let fields = TemplateFields {
+            // Already-rendered fields are inserted verbatim, so braces in them stay put.
+            front_html: "{{back}}",
+            back_html: "<code>b</code>",
+            extra: Some("extra"),
+        };
+
+        assert_eq!(
+            render_card_template(Some(&template), CardSide::Answer, &fields),
+            "<div><em>{{back}}</em></div><code>b</code>!"
+        );
+    }
+
+    #[test]
+    fn templates_must_reference_known_fields() {
+        assert_eq!(
+            CardTemplate::parse(CardSide::Answer, "{{back}} {{hint}}"),
+            Err(TemplateError::UnknownField("hint".into()))
+        );
+    }
+
+    #[test]
+    fn strips_scripts_and_event_handlers() {
+        let html = render_card_text(
+            r#"<p onclick="alert(1)">Hi</p><script>alert(2)</script><img src=x onerror=alert(3)><a href="javascript:alert(4)">link</a>"#,
+        );
+
+        assert!(!html.contains("script"));
+        assert!(!html.contains("onclick"));
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains("<p>Hi</p>"));
+
+        let markdown = render_card_text("**Bold**<script>alert(1)</script>");
+        assert!(markdown.contains("<strong>Bold</strong>"));
+        assert!(!markdown.contains("script"));
+    }
+}
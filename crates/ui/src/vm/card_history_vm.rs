@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use services::CardHistoryEntry;
+
+use crate::vm::session_summary_vm::grade_label;
+use crate::vm::time_fmt::{format_datetime, relative_from};
+
+/// One row of a card's review history in the editor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CardHistoryRowVm {
+    pub grade_label: &'static str,
+    /// Suffix for the `history-metric--*` color classes.
+    pub grade_modifier: &'static str,
+    pub reviewed_at_str: String,
+    pub reviewed_ago: String,
+    pub interval_label: String,
+    pub stability_label: String,
+}
+
+impl CardHistoryRowVm {
+    #[must_use]
+    pub fn from_entry(entry: &CardHistoryEntry, now: DateTime<Utc>) -> Self {
+        let (grade_label, grade_modifier) = grade_label(entry.grade);
+        Self {
+            grade_label,
+            grade_modifier,
+            reviewed_at_str: format_datetime(&entry.reviewed_at),
+            reviewed_ago: relative_from(now, entry.reviewed_at),
+            interval_label: format_days(entry.interval_days),
+            stability_label: format_days(entry.stability),
+        }
+    }
+}
+
+#[must_use]
+pub fn map_card_history(
+    entries: &[CardHistoryEntry],
+    now: DateTime<Utc>,
+) -> Vec<CardHistoryRowVm> {
+    entries
+        .iter()
+        .map(|entry| CardHistoryRowVm::from_entry(entry, now))
+        .collect()
+}
+
+/// Short span label: minutes or hours under a day (learning steps), days otherwise.
+fn format_days(days: f64) -> String {
+    let minutes = days * 24.0 * 60.0;
+    if minutes < 60.0 {
+        format!("{:.0} min", minutes.max(0.0))
+    } else if days < 1.0 {
+        format!("{:.0} h", minutes / 60.0)
+    } else if days < 10.0 {
+        format!("{days:.1} d")
+    } else {
+        format!("{days:.0} d")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use learn_core::model::ReviewGrade;
+
+    use super::*;
+
+    #[test]
+    fn history_rows_format_times_and_spans() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+        let entries = [
+            CardHistoryEntry {
+                grade: ReviewGrade::Good,
+                reviewed_at: now - Duration::hours(2),
+                interval_days: 12.4,
+                stability: 3.3,
+            },
+            CardHistoryEntry {
+                grade: ReviewGrade::Again,
+                reviewed_at: now - Duration::days(30),
+                interval_days: 10.0 / (24.0 * 60.0),
+                stability: 0.25,
+            },
+        ];
+
+        let rows = map_card_history(&entries, now);
+        assert_eq!(
+            rows,
+            vec![
+                CardHistoryRowVm {
+                    grade_label: "Good",
+                    grade_modifier: "good",
+                    reviewed_at_str: format_datetime(&(now - Duration::hours(2))),
+                    reviewed_ago: "2 hours ago".to_string(),
+                    interval_label: "12 d".to_string(),
+                    stability_label: "3.3 d".to_string(),
+                },
+                CardHistoryRowVm {
+                    grade_label: "Again",
+                    grade_modifier: "again",
+                    reviewed_at_str: "Feb 9 \u{00b7} 12:00 PM".to_string(),
+                    reviewed_ago: "Feb 9 \u{00b7} 12:00 PM".to_string(),
+                    interval_label: "10 min".to_string(),
+                    stability_label: "6 h".to_string(),
+                },
+            ]
+        );
+    }
+}
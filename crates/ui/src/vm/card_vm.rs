@@ -1,6 +1,14 @@
-use learn_core::model::CardId;
+use std::collections::HashMap;
 
-use super::markdown_vm::{sanitize_html, strip_html_tags};
+use chrono::{DateTime, Utc};
+use learn_core::model::{CardId, CardSource, DeckId, Flag, MediaId, TagName};
+use learn_core::scheduler::Scheduler;
+use services::{DeckSearchResults, ReviewService};
+use storage::repository::TrashedCard;
+
+use super::card_content_vm::render_card_text;
+use super::markdown_vm::strip_html_tags;
+use super::time_fmt::{format_due, is_overdue, relative_from};
 
 /// UI-ready summary of a card for list rendering.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -12,6 +20,25 @@ pub struct CardListItemVm {
     pub answer_text: String,
     pub prompt_preview: String,
     pub answer_preview: String,
+    pub tags: Vec<String>,
+    pub prompt_media_id: Option<MediaId>,
+    pub answer_media_id: Option<MediaId>,
+    pub suspended: bool,
+    pub flag: Option<Flag>,
+    /// Plain-text extra info shown beneath the answer; empty when the card has none.
+    pub extra_text: String,
+    /// Predicted chance of recall in percent when the list was loaded; `None` for new cards.
+    pub recall_percent: Option<u8>,
+    /// When the card is next due; `None` for new cards.
+    pub next_due: Option<DateTime<Utc>>,
+    /// `next_due` relative to when the list was loaded, e.g. "due in 3d" or "new".
+    pub due_label: String,
+    /// Whether the card was a day or more past due when the list was loaded.
+    pub overdue: bool,
+    /// When the card was added; used to sort recently added cards first.
+    pub created_at: Option<DateTime<Utc>>,
+    /// How the card was created; `None` for cards from before this was recorded.
+    pub source: Option<CardSource>,
 }
 
 impl CardListItemVm {
@@ -33,24 +60,225 @@ impl CardListItemVm {
             answer_text,
             prompt_preview,
             answer_preview,
+            tags: Vec::new(),
+            prompt_media_id: None,
+            answer_media_id: None,
+            suspended: false,
+            flag: None,
+            extra_text: String::new(),
+            recall_percent: None,
+            next_due: None,
+            due_label: "new".to_string(),
+            overdue: false,
+            created_at: None,
+            source: None,
         }
     }
+
+    /// Attach tag names for display in the list.
+    #[must_use]
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Attach the images stored on each side of the card.
+    #[must_use]
+    pub fn with_media(
+        mut self,
+        prompt_media_id: Option<MediaId>,
+        answer_media_id: Option<MediaId>,
+    ) -> Self {
+        self.prompt_media_id = prompt_media_id;
+        self.answer_media_id = answer_media_id;
+        self
+    }
+
+    /// Attach the card's extra info for editing.
+    #[must_use]
+    pub fn with_extra(mut self, extra_text: &str) -> Self {
+        extra_text.clone_into(&mut self.extra_text);
+        self
+    }
+
+    /// Attach the card's predicted chance of recall, from 0 to 1.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn with_recall(mut self, recall: Option<f64>) -> Self {
+        self.recall_percent = recall.map(|recall| (recall.clamp(0.0, 1.0) * 100.0).round() as u8);
+        self
+    }
+
+    /// Attach when the card is next due, labeled relative to `now`; `None` marks a new card.
+    #[must_use]
+    pub fn with_next_due(mut self, next_due: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Self {
+        self.next_due = next_due;
+        self.due_label = format_due(now, next_due);
+        self.overdue = next_due.is_some_and(|due| is_overdue(now, due));
+        self
+    }
+
+    /// Attach when the card was added.
+    #[must_use]
+    pub fn with_created_at(mut self, created_at: DateTime<Utc>) -> Self {
+        self.created_at = Some(created_at);
+        self
+    }
+
+    /// Attach how the card was created.
+    #[must_use]
+    pub fn with_source(mut self, source: Option<CardSource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Short note on where the card came from; `None` for cards typed by hand or of unknown
+    /// origin, which need no note.
+    #[must_use]
+    pub fn source_label(&self) -> Option<&'static str> {
+        match self.source? {
+            CardSource::Manual => None,
+            CardSource::Csv => Some("Imported from CSV"),
+            CardSource::Anki => Some("Imported from Anki"),
+            CardSource::Ai => Some("Written with AI"),
+        }
+    }
+
+    /// Recall chance as a percentage, or a dash for cards never reviewed.
+    #[must_use]
+    pub fn recall_label(&self) -> String {
+        self.recall_percent
+            .map_or_else(|| "—".to_string(), |percent| format!("{percent}%"))
+    }
+
+    /// Mark the card as suspended so the list can flag it.
+    #[must_use]
+    pub fn with_suspended(mut self, suspended: bool) -> Self {
+        self.suspended = suspended;
+        self
+    }
+
+    /// Attach the card's flag so the list can show its dot.
+    #[must_use]
+    pub fn with_flag(mut self, flag: Option<Flag>) -> Self {
+        self.flag = flag;
+        self
+    }
+
+    /// Whether the card carries the leech tag, given after too many lapses.
+    #[must_use]
+    pub fn is_leech(&self) -> bool {
+        self.tags.iter().any(|tag| tag == TagName::LEECH)
+    }
 }
 
-/// Map domain cards into list-friendly view models.
+/// Name of a flag for tooltips and screen readers.
 #[must_use]
-pub fn map_card_list_items(cards: &[learn_core::model::Card]) -> Vec<CardListItemVm> {
+pub fn flag_label(flag: Flag) -> &'static str {
+    match flag {
+        Flag::Red => "Red flag",
+        Flag::Orange => "Orange flag",
+        Flag::Green => "Green flag",
+        Flag::Blue => "Blue flag",
+    }
+}
+
+/// Map domain cards into list-friendly view models, attaching each card's tag names and images.
+///
+/// Recall chances are computed as of `now` with the deck's `scheduler`; without one, no
+/// card shows a recall chance.
+#[must_use]
+pub fn map_card_list_items(
+    cards: &[learn_core::model::Card],
+    tags: &HashMap<CardId, Vec<TagName>>,
+    scheduler: Option<&Scheduler>,
+    now: DateTime<Utc>,
+) -> Vec<CardListItemVm> {
     cards
         .iter()
-        .map(|card| build_card_list_item(card.id(), card.prompt().text(), card.answer().text()))
+        .map(|card| {
+            let names = tags
+                .get(&card.id())
+                .map(|names| names.iter().map(|name| name.as_str().to_owned()).collect())
+                .unwrap_or_default();
+            let recall =
+                scheduler.and_then(|scheduler| ReviewService::retrievability(scheduler, card, now));
+            build_card_list_item(card.id(), card.prompt().text(), card.answer().text())
+                .with_tags(names)
+                .with_media(card.prompt().media_id(), card.answer().media_id())
+                .with_suspended(card.is_suspended())
+                .with_flag(card.flag())
+                .with_recall(recall)
+                .with_next_due((!card.is_new()).then_some(card.next_review_at()), now)
+                .with_extra(card.extra().map_or("", |extra| extra.text()))
+                .with_created_at(card.created_at())
+                .with_source(card.source())
+        })
+        .collect()
+}
+
+/// One deck's matches in an all-decks search, labeled with the deck's name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeckSearchGroupVm {
+    pub deck_id: DeckId,
+    pub deck_name: String,
+    pub items: Vec<CardListItemVm>,
+}
+
+/// Map `CardService::search_all_decks` results into labeled groups of list items.
+///
+/// Each group's recall chances use that deck's scheduler from `schedulers`.
+#[must_use]
+pub fn map_deck_search_groups(
+    groups: &[DeckSearchResults],
+    schedulers: &HashMap<DeckId, Scheduler>,
+    now: DateTime<Utc>,
+) -> Vec<DeckSearchGroupVm> {
+    groups
+        .iter()
+        .map(|group| DeckSearchGroupVm {
+            deck_id: group.deck_id,
+            deck_name: group.deck_name.clone(),
+            items: map_card_list_items(
+                &group.cards,
+                &HashMap::new(),
+                schedulers.get(&group.deck_id),
+                now,
+            ),
+        })
+        .collect()
+}
+
+/// A card in the editor's "Recently deleted" list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrashedCardVm {
+    pub item: CardListItemVm,
+    pub deleted_label: String,
+}
+
+/// Map `CardService::list_trash` results, labeling how long ago each card was deleted.
+#[must_use]
+pub fn map_trashed_cards(trashed: &[TrashedCard], now: DateTime<Utc>) -> Vec<TrashedCardVm> {
+    trashed
+        .iter()
+        .map(|trashed| TrashedCardVm {
+            item: build_card_list_item(
+                trashed.card.id(),
+                trashed.card.prompt().text(),
+                trashed.card.answer().text(),
+            ),
+            deleted_label: format!("Deleted {}", relative_from(now, trashed.deleted_at)),
+        })
         .collect()
 }
 
 /// Build a list item view model from raw prompt/answer text.
+///
+/// Both sides go through `render_card_text`, so the editor shows cards as a session does.
 #[must_use]
 pub fn build_card_list_item(id: CardId, prompt_html: &str, answer_html: &str) -> CardListItemVm {
-    let prompt_html = sanitize_html(prompt_html);
-    let answer_html = sanitize_html(answer_html);
+    let prompt_html = render_card_text(prompt_html);
+    let answer_html = render_card_text(answer_html);
     let prompt_text = strip_html_tags(&prompt_html);
     let answer_text = strip_html_tags(&answer_html);
     let prompt_preview = truncate_preview(&prompt_text, 56);
@@ -67,18 +295,24 @@ pub fn build_card_list_item(id: CardId, prompt_html: &str, answer_html: &str) ->
 }
 
 /// Filter list items by a search query (case-insensitive).
+///
+/// Every whitespace-separated word must appear in the prompt or answer, so items returned by
+/// `CardService::search_cards` for the same query are kept.
 #[must_use]
 pub fn filter_card_list_items(items: &[CardListItemVm], query: &str) -> Vec<CardListItemVm> {
-    let needle = query.trim().to_lowercase();
-    if needle.is_empty() {
+    let needles: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if needles.is_empty() {
         return items.to_vec();
     }
 
     items
         .iter()
         .filter(|item| {
-            item.prompt_text.to_lowercase().contains(&needle)
-                || item.answer_text.to_lowercase().contains(&needle)
+            let prompt = item.prompt_text.to_lowercase();
+            let answer = item.answer_text.to_lowercase();
+            needles
+                .iter()
+                .all(|needle| prompt.contains(needle) || answer.contains(needle))
         })
         .cloned()
         .collect()
@@ -112,3 +346,57 @@ fn truncate_preview(text: &str, max_chars: usize) -> String {
 
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use learn_core::model::content::ContentDraft;
+    use learn_core::model::{Card, CardKind, CardPhase};
+
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap()
+    }
+
+    fn card(id: u64, next_review_at: DateTime<Utc>, review_count: u32) -> Card {
+        let prompt = ContentDraft::text_only("Q").validate(now(), None, None).unwrap();
+        let answer = ContentDraft::text_only("A").validate(now(), None, None).unwrap();
+        let phase = if review_count == 0 { CardPhase::New } else { CardPhase::Reviewing };
+        Card::from_persisted(
+            CardId::new(id),
+            DeckId::new(1),
+            CardKind::Basic,
+            prompt,
+            answer,
+            now() - Duration::days(30),
+            next_review_at,
+            (review_count > 0).then_some(now() - Duration::days(10)),
+            phase,
+            review_count,
+            5.0,
+            5.0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn map_card_list_items_labels_when_each_card_is_due() {
+        let cards = [
+            card(1, now(), 0),
+            card(2, now() + Duration::hours(3), 2),
+            card(3, now() - Duration::days(4), 2),
+        ];
+
+        let items = map_card_list_items(&cards, &HashMap::new(), None, now());
+
+        assert_eq!(items[0].next_due, None);
+        assert_eq!(items[0].due_label, "new");
+        assert!(!items[0].overdue);
+        assert_eq!(items[1].next_due, Some(now() + Duration::hours(3)));
+        assert_eq!(items[1].due_label, "due in 3h");
+        assert!(!items[1].overdue);
+        assert_eq!(items[2].due_label, "overdue 4d");
+        assert!(items[2].overdue);
+    }
+}
@@ -1,29 +1,163 @@
-use learn_core::model::{Deck, DeckId};
+use std::collections::HashMap;
+
+use learn_core::model::{Deck, DeckColor, DeckIcon, DeckId};
+use services::{CardPhaseCounts, DeckDueCounts};
 
 /// UI-ready representation of a deck for selection controls.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DeckOptionVm {
     pub id: DeckId,
     pub label: String,
+    pub archived: bool,
+    pub favorite: bool,
+    pub color: Option<DeckColor>,
+    pub icon: Option<DeckIcon>,
+    pub counts: DeckDueCounts,
 }
 
 impl DeckOptionVm {
     #[must_use]
     pub fn new(id: DeckId, label: String) -> Self {
-        Self { id, label }
+        Self {
+            id,
+            label,
+            archived: false,
+            favorite: false,
+            color: None,
+            icon: None,
+            counts: DeckDueCounts::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_archived(mut self, archived: bool) -> Self {
+        self.archived = archived;
+        self
+    }
+
+    #[must_use]
+    pub fn with_favorite(mut self, favorite: bool) -> Self {
+        self.favorite = favorite;
+        self
+    }
+
+    #[must_use]
+    pub fn with_appearance(mut self, color: Option<DeckColor>, icon: Option<DeckIcon>) -> Self {
+        self.color = color;
+        self.icon = icon;
+        self
+    }
+
+    #[must_use]
+    pub fn with_counts(mut self, counts: DeckDueCounts) -> Self {
+        self.counts = counts;
+        self
+    }
+
+    /// Short "due · new" summary for the picker, or `None` when nothing is waiting.
+    #[must_use]
+    pub fn counts_label(&self) -> Option<String> {
+        let DeckDueCounts { due, learning, new } = self.counts;
+        let reviews = due + learning;
+        if reviews == 0 && new == 0 {
+            return None;
+        }
+        Some(format!("{reviews} due · {new} new"))
     }
 }
 
 /// Convert domain decks into selection-friendly view models.
+///
+/// Favorites come first; otherwise decks keep their order in `decks`. Decks missing from
+/// `counts` show no due or new cards.
 #[must_use]
-pub fn map_deck_options(decks: &[Deck]) -> Vec<DeckOptionVm> {
-    decks
+pub fn map_deck_options(
+    decks: &[Deck],
+    counts: &HashMap<DeckId, DeckDueCounts>,
+) -> Vec<DeckOptionVm> {
+    let mut options: Vec<DeckOptionVm> = decks
         .iter()
         .map(|deck| {
             let label = format_deck_label(deck.name(), deck.description());
             DeckOptionVm::new(deck.id(), label)
+                .with_archived(deck.is_archived())
+                .with_favorite(deck.is_favorite())
+                .with_appearance(deck.settings().color(), deck.settings().icon())
+                .with_counts(counts.get(&deck.id()).copied().unwrap_or_default())
         })
-        .collect()
+        .collect();
+    options.sort_by_key(|option| !option.favorite);
+    options
+}
+
+/// Summary like "120 cards · 30 new · 5 suspended"; phases with no cards are left out.
+#[must_use]
+pub fn card_counts_label(counts: &CardPhaseCounts) -> String {
+    let noun = if counts.total == 1 { "card" } else { "cards" };
+    let mut parts = vec![format!("{} {noun}", counts.total)];
+    for (count, label) in [
+        (counts.new, "new"),
+        (counts.learning, "learning"),
+        (counts.review, "review"),
+        (counts.suspended, "suspended"),
+    ] {
+        if count > 0 {
+            parts.push(format!("{count} {label}"));
+        }
+    }
+    parts.join(" · ")
+}
+
+/// Class that sets `--deck-color` for a deck's mark, or an empty string for plain decks.
+#[must_use]
+pub fn deck_color_class(color: Option<DeckColor>) -> String {
+    color.map_or_else(String::new, |color| format!("deck-color--{}", color.as_str()))
+}
+
+#[must_use]
+pub fn deck_color_label(color: DeckColor) -> &'static str {
+    match color {
+        DeckColor::Red => "Red",
+        DeckColor::Orange => "Orange",
+        DeckColor::Yellow => "Yellow",
+        DeckColor::Green => "Green",
+        DeckColor::Teal => "Teal",
+        DeckColor::Blue => "Blue",
+        DeckColor::Purple => "Purple",
+        DeckColor::Pink => "Pink",
+        DeckColor::Gray => "Gray",
+    }
+}
+
+#[must_use]
+pub fn deck_icon_label(icon: DeckIcon) -> &'static str {
+    match icon {
+        DeckIcon::Book => "Book",
+        DeckIcon::Language => "Language",
+        DeckIcon::Science => "Science",
+        DeckIcon::Math => "Math",
+        DeckIcon::Music => "Music",
+        DeckIcon::Code => "Code",
+        DeckIcon::Globe => "Globe",
+        DeckIcon::Star => "Star",
+        DeckIcon::Heart => "Heart",
+    }
+}
+
+/// The emoji shown for a deck icon.
+#[must_use]
+pub fn deck_icon_glyph(icon: DeckIcon) -> &'static str {
+    match icon {
+        DeckIcon::Book => "📖",
+        DeckIcon::Language => "🗣️",
+        DeckIcon::Science => "🔬",
+        DeckIcon::Math => "➗",
+        DeckIcon::Music => "🎵",
+        DeckIcon::Code => "💻",
+        DeckIcon::Globe => "🌍",
+        DeckIcon::Star => "⭐",
+        DeckIcon::Heart => "❤️",
+    }
 }
 
 fn format_deck_label(name: &str, description: Option<&str>) -> String {
@@ -32,3 +166,18 @@ fn format_deck_label(name: &str, description: Option<&str>) -> String {
         None => name.to_owned(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn card_counts_label_skips_empty_phases() {
+        let counts = CardPhaseCounts { total: 120, new: 30, learning: 0, review: 85, suspended: 5 };
+        assert_eq!(card_counts_label(&counts), "120 cards · 30 new · 85 review · 5 suspended");
+
+        let single = CardPhaseCounts { total: 1, new: 1, ..CardPhaseCounts::default() };
+        assert_eq!(card_counts_label(&single), "1 card · 1 new");
+        assert_eq!(card_counts_label(&CardPhaseCounts::default()), "0 cards");
+    }
+}
@@ -0,0 +1,181 @@
+use super::markdown_vm::{
+    MarkdownAction, MarkdownEdit, MarkdownField, SelectionRange, apply_markdown_action,
+};
+
+/// Undo steps kept per field; the oldest are dropped first.
+pub const EDIT_HISTORY_LIMIT: usize = 100;
+
+/// Undo and redo stacks for one editor field.
+///
+/// Each step is the field's content and selection from just before a change. Consecutive
+/// typing collapses into one step; an action, undo, or redo ends the run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EditHistory {
+    undo: Vec<MarkdownEdit>,
+    redo: Vec<MarkdownEdit>,
+    typing: bool,
+    selection: SelectionRange,
+}
+
+impl EditHistory {
+    /// Apply `action` to `current`, recording `current` as the step to undo back to.
+    pub fn apply(&mut self, current: &MarkdownEdit, action: MarkdownAction) -> MarkdownEdit {
+        let edited = apply_markdown_action(&current.text, current.selection, action);
+        self.record(current.clone(), edited.selection);
+        edited
+    }
+
+    /// Record the state from before a change made outside `apply`, such as a toolbar command
+    /// run in the rich-text editor. `after` is the selection the change left behind.
+    pub fn record(&mut self, before: MarkdownEdit, after: SelectionRange) {
+        self.push(before);
+        self.typing = false;
+        self.selection = after;
+    }
+
+    /// Record typed text. The field's last known selection stands in for the selection
+    /// before the keystroke, and the step is merged into an ongoing typing run.
+    pub fn record_typing(&mut self, before_text: String, after: SelectionRange) {
+        if !self.typing {
+            let before = MarkdownEdit {
+                text: before_text,
+                selection: self.selection,
+            };
+            self.typing = self.push(before);
+        }
+        self.selection = after;
+    }
+
+    /// Step back, returning the state to restore. `current` becomes the redo step.
+    pub fn undo(&mut self, current: MarkdownEdit) -> Option<MarkdownEdit> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        Some(self.restore(previous))
+    }
+
+    /// Reapply the last undone step, returning the state to restore.
+    pub fn redo(&mut self, current: MarkdownEdit) -> Option<MarkdownEdit> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(self.restore(next))
+    }
+
+    #[must_use]
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    #[must_use]
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    fn restore(&mut self, edit: MarkdownEdit) -> MarkdownEdit {
+        self.typing = false;
+        self.selection = edit.selection;
+        edit
+    }
+
+    /// Push an undo step, skipping one whose text matches the newest step, as when a
+    /// toolbar command's input event arrives after the command was recorded.
+    fn push(&mut self, before: MarkdownEdit) -> bool {
+        if self.undo.last().is_some_and(|last| last.text == before.text) {
+            return false;
+        }
+        if self.undo.len() == EDIT_HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(before);
+        self.redo.clear();
+        true
+    }
+}
+
+/// One `EditHistory` per editor field, reset whenever another card is loaded.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FieldEditHistories {
+    pub front: EditHistory,
+    pub back: EditHistory,
+    pub extra: EditHistory,
+}
+
+impl FieldEditHistories {
+    pub fn field_mut(&mut self, field: MarkdownField) -> &mut EditHistory {
+        match field {
+            MarkdownField::Front => &mut self.front,
+            MarkdownField::Back => &mut self.back,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(text: &str, start: usize, end: usize) -> MarkdownEdit {
+        MarkdownEdit {
+            text: text.to_string(),
+            selection: SelectionRange::new(start, end),
+        }
+    }
+
+    #[test]
+    fn apply_undo_redo_restores_text_and_selection() {
+        let mut history = EditHistory::default();
+        let original = edit("make this bold", 5, 9);
+
+        let bolded = history.apply(&original, MarkdownAction::Bold);
+        assert_eq!(bolded.text, "make **this** bold");
+
+        let undone = history.undo(bolded.clone()).expect("undo step");
+        assert_eq!(undone, original);
+        assert!(history.can_redo());
+
+        let redone = history.redo(undone).expect("redo step");
+        assert_eq!(redone, bolded);
+        assert!(!history.can_redo());
+        assert_eq!(history.undo(redone), Some(original));
+    }
+
+    #[test]
+    fn typing_run_is_one_step_and_actions_break_it() {
+        let mut history = EditHistory::default();
+        history.record_typing(String::new(), SelectionRange::caret(1));
+        history.record_typing("h".to_string(), SelectionRange::caret(2));
+        history.record_typing("hi".to_string(), SelectionRange::caret(3));
+        let typed = edit("hi!", 0, 3);
+        let bolded = history.apply(&typed, MarkdownAction::Bold);
+        history.record_typing(bolded.text.clone(), SelectionRange::caret(8));
+
+        let after_typing = edit("**hi!** x", 8, 8);
+        assert_eq!(history.undo(after_typing), Some(bolded.clone()));
+        assert_eq!(history.undo(bolded), Some(typed.clone()));
+        assert_eq!(history.undo(typed), Some(edit("", 0, 0)));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn new_change_clears_redo_and_repeated_snapshots_are_skipped() {
+        let mut history = EditHistory::default();
+        let original = edit("one", 0, 3);
+        let italic = history.apply(&original, MarkdownAction::Italic);
+        history.record_typing(original.text.clone(), italic.selection);
+        let undone = history.undo(italic).expect("undo step");
+        assert!(!history.can_undo(), "the echoed input event adds no step");
+
+        history.record(undone, SelectionRange::caret(2));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn histories_are_kept_per_field() {
+        let mut histories = FieldEditHistories::default();
+        histories
+            .field_mut(MarkdownField::Front)
+            .apply(&edit("front", 0, 5), MarkdownAction::Code);
+
+        assert!(histories.front.can_undo());
+        assert!(!histories.field_mut(MarkdownField::Back).can_undo());
+        assert!(!histories.extra.can_undo());
+    }
+}
@@ -1,7 +1,9 @@
 use dioxus::prelude::ReadableExt;
-use learn_core::model::{CardId, DeckId};
+use learn_core::model::{CardId, DeckId, MAX_TEXT_CHARS};
 
-use super::{CardListItemVm, DeckOptionVm, filter_card_list_items, strip_html_tags};
+use super::{
+    CardListItemVm, DeckOptionVm, card_counts_label, filter_card_list_items, strip_html_tags,
+};
 use crate::views::ViewState;
 use crate::views::editor::state::{DeleteState, DuplicateCheckState, EditorState, SaveState};
 use crate::views::editor::utils::build_tag_suggestions;
@@ -12,6 +14,40 @@ pub struct DailyLimitVm {
     pub created_today: u32,
 }
 
+/// Live character count for one editor field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CharCounterVm {
+    pub count: usize,
+    pub max: usize,
+}
+
+impl CharCounterVm {
+    /// Count `text` against `MAX_TEXT_CHARS`, the limit saving enforces.
+    #[must_use]
+    pub fn new(text: &str) -> Self {
+        Self {
+            count: text.chars().count(),
+            max: MAX_TEXT_CHARS,
+        }
+    }
+
+    #[must_use]
+    pub fn label(&self) -> String {
+        format!("{} / {}", self.count, self.max)
+    }
+
+    /// Within the last tenth of the limit, or past it.
+    #[must_use]
+    pub fn near_limit(&self) -> bool {
+        self.count.saturating_mul(10) >= self.max.saturating_mul(9)
+    }
+
+    #[must_use]
+    pub fn over_limit(&self) -> bool {
+        self.count > self.max
+    }
+}
+
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Clone, Debug)]
 pub struct EditorVm {
@@ -24,8 +60,13 @@ pub struct EditorVm {
     pub has_unsaved_changes: bool,
     pub prompt_invalid: bool,
     pub answer_invalid: bool,
+    pub prompt_counter: CharCounterVm,
+    pub answer_counter: CharCounterVm,
+    pub extra_counter: CharCounterVm,
     pub search_value: String,
     pub match_count: Option<usize>,
+    /// Phase breakdown of the deck, such as "120 cards · 30 new · 5 suspended".
+    pub card_counts_label: Option<String>,
     pub deck_tags: Vec<String>,
     pub deck_tags_loading: bool,
     pub deck_tags_error: bool,
@@ -36,6 +77,10 @@ pub struct EditorVm {
     pub prompt_toolbar_disabled: bool,
     pub answer_toolbar_disabled: bool,
     pub daily_limit_warning: Option<String>,
+    /// Recall chance of the selected card, or `None` while no saved card is open.
+    pub recall_label: Option<String>,
+    /// Where the selected card came from, when it was imported or written with AI.
+    pub source_label: Option<String>,
 }
 
 #[must_use]
@@ -59,10 +104,15 @@ pub fn build_editor_vm(
         && has_unsaved_changes;
     let can_cancel = is_create_mode && (state.last_selected_card)().is_some();
 
+    let prompt_counter = CharCounterVm::new(&state.prompt_text.read());
+    let answer_counter = CharCounterVm::new(&state.answer_text.read());
+    let extra_counter = CharCounterVm::new(state.extra_text.read().trim());
     let prompt_plain = strip_html_tags(&state.prompt_text.read());
     let answer_plain = strip_html_tags(&state.answer_text.read());
-    let prompt_invalid = (state.show_validation)() && prompt_plain.trim().is_empty();
-    let answer_invalid = (state.show_validation)() && answer_plain.trim().is_empty();
+    let prompt_invalid = (state.show_validation)()
+        && (prompt_plain.trim().is_empty() || prompt_counter.over_limit());
+    let answer_invalid = (state.show_validation)()
+        && (answer_plain.trim().is_empty() || answer_counter.over_limit());
 
     let search_value = state.search_query.read().to_string();
     let match_count = match cards_state {
@@ -71,6 +121,7 @@ pub fn build_editor_vm(
         }
         _ => None,
     };
+    let card_counts_label = state.card_counts.read().as_ref().map(card_counts_label);
 
     let deck_tags = match deck_tags_state {
         ViewState::Ready(tags) => tags.clone(),
@@ -98,6 +149,17 @@ pub fn build_editor_vm(
         _ => None,
     };
 
+    let selected_item = match cards_state {
+        ViewState::Ready(items) if !is_create_mode => {
+            selected_card_id.and_then(|card_id| items.iter().find(|item| item.id == card_id))
+        }
+        _ => None,
+    };
+    let recall_label = selected_item.map(CardListItemVm::recall_label);
+    let source_label = selected_item
+        .and_then(CardListItemVm::source_label)
+        .map(str::to_owned);
+
     EditorVm {
         deck_label,
         is_create_mode,
@@ -108,8 +170,12 @@ pub fn build_editor_vm(
         has_unsaved_changes,
         prompt_invalid,
         answer_invalid,
+        prompt_counter,
+        answer_counter,
+        extra_counter,
         search_value,
         match_count,
+        card_counts_label,
         deck_tags,
         deck_tags_loading,
         deck_tags_error,
@@ -120,6 +186,8 @@ pub fn build_editor_vm(
         prompt_toolbar_disabled: !can_edit,
         answer_toolbar_disabled: !can_edit,
         daily_limit_warning,
+        recall_label,
+        source_label,
     }
 }
 
@@ -144,7 +212,7 @@ fn match_count_for_query(items: &[CardListItemVm], query: &str) -> usize {
 mod tests {
     use learn_core::model::{CardId, DeckId};
 
-    use super::{deck_label_from_state, match_count_for_query};
+    use super::{CharCounterVm, deck_label_from_state, match_count_for_query};
     use crate::vm::CardListItemVm;
     use crate::views::ViewState;
 
@@ -175,6 +243,17 @@ mod tests {
         assert_eq!(deck_label_from_state(&state, deck_id), "Default");
     }
 
+    #[test]
+    fn char_counter_warns_in_the_last_tenth_of_the_limit() {
+        let counter = |count: usize| CharCounterVm { count, max: 8_000 };
+
+        assert!(!counter(7_199).near_limit());
+        assert!(counter(7_200).near_limit());
+        assert!(!counter(8_000).over_limit());
+        assert!(counter(8_001).over_limit());
+        assert_eq!(CharCounterVm::new("héllo").label(), "5 / 8000");
+    }
+
     #[test]
     fn match_count_for_query_filters_items() {
         let items = vec![
@@ -184,5 +263,7 @@ mod tests {
         assert_eq!(match_count_for_query(&items, "rust"), 1);
         assert_eq!(match_count_for_query(&items, "ui"), 1);
         assert_eq!(match_count_for_query(&items, ""), 2);
+        assert_eq!(match_count_for_query(&items, "lang RUST"), 1);
+        assert_eq!(match_count_for_query(&items, "rust ui"), 0);
     }
 }
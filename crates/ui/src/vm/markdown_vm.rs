@@ -13,9 +13,57 @@ pub enum MarkdownAction {
     NumberedList,
     Code,
     CodeBlock,
+    Table,
 }
 
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+
+/// Markdown inserted by the toolbar's table action: a header row and one body row.
+pub const STARTER_TABLE_MARKDOWN: &str =
+    "| Column 1 | Column 2 |\n| -------- | -------- |\n|          |          |\n";
+
+/// Stands in for pipes inside table cells while html2md runs; it does not escape them.
+const CELL_PIPE_PLACEHOLDER: char = '\u{E000}';
+
+/// Delimits code-block tokens; `<pre>` blocks are converted to fences outside html2md,
+/// which drops language hints and escapes markdown inside code.
+const CODE_BLOCK_PLACEHOLDER: char = '\u{E001}';
+
+/// Byte offsets into a field's markdown source; `start == end` is a caret.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SelectionRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl SelectionRange {
+    #[must_use]
+    pub fn new(start: usize, end: usize) -> Self {
+        Self {
+            start: start.min(end),
+            end: start.max(end),
+        }
+    }
+
+    #[must_use]
+    pub fn caret(at: usize) -> Self {
+        Self { start: at, end: at }
+    }
+
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Markdown source and selection after applying an action.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarkdownEdit {
+    pub text: String,
+    pub selection: SelectionRange,
+}
+
 #[must_use]
 pub fn markdown_to_html(input: &str) -> String {
     let mut options = pulldown_cmark::Options::empty();
@@ -26,34 +74,416 @@ pub fn markdown_to_html(input: &str) -> String {
     let parser = pulldown_cmark::Parser::new_ext(input, options);
     let mut html = String::new();
     pulldown_cmark::html::push_html(&mut html, parser);
-    sanitize_html(&html)
+    sanitize_html(&alignment_styles_to_attrs(&html))
+}
+
+/// HTML for `STARTER_TABLE_MARKDOWN`, for inserting into the rich-text editor.
+#[must_use]
+pub fn starter_table_html() -> String {
+    markdown_to_html(STARTER_TABLE_MARKDOWN)
 }
 
 #[must_use]
 pub fn html_to_markdown(input: &str) -> String {
-    let markdown = html2md::parse_html(input);
+    let (html, code_blocks) = extract_code_blocks(input);
+    let html = alignment_styles_to_attrs(&mark_cell_pipes(&html));
+    let mut markdown = html2md::parse_html(&html).replace(CELL_PIPE_PLACEHOLDER, "\\|");
+    for (index, block) in code_blocks.iter().enumerate() {
+        markdown = markdown.replace(&code_block_token(index), block);
+    }
     normalize_markdown(&markdown)
 }
 
+/// Structured clipboard HTML (lists or tables) that the editor offers to paste as markdown
+/// or as plain text instead of inserting as-is.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PasteOffer {
+    /// Clipboard HTML after sanitizing; scripts, styles, and unknown tags are gone.
+    pub html: String,
+    /// `html` converted to markdown, shown as the preview.
+    pub markdown: String,
+    /// What "Paste as plain text" inserts.
+    pub plain: String,
+}
+
+impl PasteOffer {
+    /// Build an offer from the clipboard's `text/html` and `text/plain` flavors.
+    ///
+    /// Raw markup in the plain flavor counts as HTML. Returns `None` when the content has
+    /// no list or table, so an ordinary paste can go ahead.
+    #[must_use]
+    pub fn from_clipboard(html: &str, text: &str) -> Option<Self> {
+        let source = if !html.trim().is_empty() {
+            html
+        } else if looks_like_html(text) {
+            text
+        } else {
+            return None;
+        };
+        let html = sanitize_html(source);
+        if !has_paste_structure(&html) {
+            return None;
+        }
+        let markdown = drop_empty_link_targets(&html_to_markdown(&html));
+        let plain = if text.trim().is_empty() || looks_like_html(text) {
+            strip_html_tags(&html)
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            text.trim().to_string()
+        };
+        Some(Self {
+            html,
+            markdown,
+            plain,
+        })
+    }
+
+    /// HTML for "Paste as Markdown": the preview rendered back, so the editor receives the
+    /// same clean structure the markdown describes.
+    #[must_use]
+    pub fn markdown_html(&self) -> String {
+        markdown_to_html(&self.markdown)
+    }
+}
+
+/// How the editor handles a paste, decided from the clipboard flavors it carries.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PasteKind {
+    /// Only an image: it is stored and attached to the field.
+    Image,
+    /// An image alongside text; the user picks which one to paste.
+    ImageOrText,
+    /// Lists or tables, offered as markdown or plain text.
+    Structured(PasteOffer),
+    /// Other markup, sanitized and ready to insert.
+    Html(String),
+    /// Plain text.
+    Text,
+}
+
+impl PasteKind {
+    /// Classify a paste from whether it holds image data and from its `text/html` and
+    /// `text/plain` flavors.
+    ///
+    /// HTML that only wraps the image, as browsers write when copying one, is not text.
+    #[must_use]
+    pub fn classify(has_image: bool, html: &str, text: &str) -> Self {
+        if has_image {
+            let html_text = strip_html_tags(&sanitize_html(html));
+            return if text.trim().is_empty() && html_text.trim().is_empty() {
+                Self::Image
+            } else {
+                Self::ImageOrText
+            };
+        }
+        if let Some(offer) = PasteOffer::from_clipboard(html, text) {
+            return Self::Structured(offer);
+        }
+        if !html.trim().is_empty() {
+            Self::Html(sanitize_html(html))
+        } else if looks_like_html(text) {
+            Self::Html(sanitize_html(text))
+        } else {
+            Self::Text
+        }
+    }
+}
+
+fn has_paste_structure(sanitized: &str) -> bool {
+    ["<ul", "<ol", "<table"]
+        .iter()
+        .any(|tag| sanitized.contains(tag))
+}
+
+/// The sanitizer drops unsafe hrefs but keeps the anchor, which html2md renders as
+/// `[text]()`; keep just the text.
+fn drop_empty_link_targets(markdown: &str) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut rest = markdown;
+    while let Some(end) = rest.find("]()") {
+        let head = &rest[..end];
+        if let Some(open) = head.rfind('[') {
+            out.push_str(&head[..open]);
+            out.push_str(&head[open + 1..]);
+        } else {
+            out.push_str(head);
+            out.push_str("]()");
+        }
+        rest = &rest[end + 3..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Apply a toolbar action to markdown source.
+///
+/// Inline actions wrap the selection; line actions prefix every selected line. The
+/// returned selection covers the edited content so actions can be chained.
+#[must_use]
+pub fn apply_markdown_action(
+    text: &str,
+    selection: SelectionRange,
+    action: MarkdownAction,
+) -> MarkdownEdit {
+    let start = floor_char_boundary(text, selection.start);
+    let end = floor_char_boundary(text, selection.end.max(start));
+    match action {
+        MarkdownAction::Bold => wrap_inline(text, start, end, "**", "**"),
+        MarkdownAction::Italic => wrap_inline(text, start, end, "_", "_"),
+        MarkdownAction::Code => {
+            let selected = &text[start..end];
+            let fence = "`".repeat(longest_backtick_run(selected) + 1);
+            let pad = if selected.starts_with('`') || selected.ends_with('`') {
+                " "
+            } else {
+                ""
+            };
+            wrap_inline(text, start, end, &format!("{fence}{pad}"), &format!("{pad}{fence}"))
+        }
+        MarkdownAction::CodeBlock => wrap_code_block(text, start, end),
+        MarkdownAction::Quote => prefix_lines(text, start, end, |_| "> ".to_string()),
+        MarkdownAction::BulletList => prefix_lines(text, start, end, |_| "- ".to_string()),
+        MarkdownAction::NumberedList => {
+            prefix_lines(text, start, end, |index| format!("{}. ", index + 1))
+        }
+        MarkdownAction::Table => {
+            let before = if start == 0 || text[..start].ends_with('\n') { "" } else { "\n" };
+            let after = if text[end..].is_empty() || text[end..].starts_with('\n') {
+                ""
+            } else {
+                "\n"
+            };
+            let table = STARTER_TABLE_MARKDOWN.trim_end_matches('\n');
+            let header_start = start + before.len() + 2;
+            let edited = format!("{}{before}{table}{after}{}", &text[..start], &text[end..]);
+            MarkdownEdit {
+                text: edited,
+                selection: SelectionRange::new(header_start, header_start + "Column 1".len()),
+            }
+        }
+    }
+}
+
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn wrap_inline(text: &str, start: usize, end: usize, open: &str, close: &str) -> MarkdownEdit {
+    let edited = format!("{}{open}{}{close}{}", &text[..start], &text[start..end], &text[end..]);
+    MarkdownEdit {
+        text: edited,
+        selection: SelectionRange::new(start + open.len(), end + open.len()),
+    }
+}
+
+fn wrap_code_block(text: &str, start: usize, end: usize) -> MarkdownEdit {
+    let selected = &text[start..end];
+    let fence = "`".repeat((longest_backtick_run(selected) + 1).max(3));
+    let before = if start == 0 || text[..start].ends_with('\n') { "" } else { "\n" };
+    let content_newline = if selected.ends_with('\n') { "" } else { "\n" };
+    let after = if text[end..].is_empty() || text[end..].starts_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    let content_start = start + before.len() + fence.len() + 1;
+    let edited = format!(
+        "{}{before}{fence}\n{selected}{content_newline}{fence}{after}{}",
+        &text[..start],
+        &text[end..]
+    );
+    MarkdownEdit {
+        text: edited,
+        selection: SelectionRange::new(
+            content_start,
+            content_start + selected.trim_end_matches('\n').len(),
+        ),
+    }
+}
+
+fn prefix_lines(
+    text: &str,
+    start: usize,
+    end: usize,
+    prefix: impl Fn(usize) -> String,
+) -> MarkdownEdit {
+    let line_start = text[..start].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = if end > start && text[..end].ends_with('\n') {
+        end - 1
+    } else {
+        text[end..].find('\n').map_or(text.len(), |index| end + index)
+    };
+    let prefixed: Vec<String> = text[line_start..line_end]
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| format!("{}{line}", prefix(index)))
+        .collect();
+    let block = prefixed.join("\n");
+    let edited = format!("{}{block}{}", &text[..line_start], &text[line_end..]);
+    MarkdownEdit {
+        selection: SelectionRange::new(line_start, line_start + block.len()),
+        text: edited,
+    }
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|ch| ch != '`').map(str::len).max().unwrap_or(0)
+}
+
+fn code_block_token(index: usize) -> String {
+    format!("{CODE_BLOCK_PLACEHOLDER}{index}{CODE_BLOCK_PLACEHOLDER}")
+}
+
+/// Replace each `<pre>` block with a placeholder paragraph and return the blocks as
+/// fenced markdown, keeping `language-*` hints and the code text verbatim.
+fn extract_code_blocks(html: &str) -> (String, Vec<String>) {
+    let lower = html.to_ascii_lowercase();
+    let mut out = String::with_capacity(html.len());
+    let mut blocks = Vec::new();
+    let mut pos = 0;
+    while let Some(found) = lower[pos..].find("<pre") {
+        let start = pos + found;
+        let is_pre_tag = matches!(
+            lower.as_bytes().get(start + 4),
+            Some(b'>' | b' ' | b'\t' | b'\n')
+        );
+        let Some(close) = lower[start..].find("</pre>").filter(|_| is_pre_tag) else {
+            out.push_str(&html[pos..start + 4]);
+            pos = start + 4;
+            continue;
+        };
+        out.push_str(&html[pos..start]);
+        out.push_str(&format!("<p>{}</p>", code_block_token(blocks.len())));
+        blocks.push(code_block_markdown(&html[start..start + close]));
+        pos = start + close + "</pre>".len();
+    }
+    out.push_str(&html[pos..]);
+    (out, blocks)
+}
+
+fn code_block_markdown(block: &str) -> String {
+    // Opening `<pre ...>` plus an optional `<code ...>` directly inside it.
+    let mut head_end = block.find('>').map_or(block.len(), |index| index + 1);
+    if block[head_end..].trim_start().to_ascii_lowercase().starts_with("<code") {
+        let rest = &block[head_end..];
+        let code_start = head_end + (rest.len() - rest.trim_start().len());
+        head_end = block[code_start..]
+            .find('>')
+            .map_or(block.len(), |index| code_start + index + 1);
+    }
+    let head = &block[..head_end];
+    let language = head
+        .find("language-")
+        .map(|index| {
+            head[index + "language-".len()..]
+                .chars()
+                .take_while(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '+' | '#'))
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+    let text = strip_html_tags(&block[head_end..]);
+    let code = text.strip_suffix('\n').unwrap_or(&text);
+    let fence = "`".repeat((longest_backtick_run(code) + 1).max(3));
+    format!("{fence}{language}\n{code}\n{fence}")
+}
+
+/// pulldown-cmark marks column alignment with inline styles; html2md and the sanitizer
+/// only understand the `align` attribute.
+fn alignment_styles_to_attrs(html: &str) -> String {
+    html.replace(r#" style="text-align: left""#, r#" align="left""#)
+        .replace(r#" style="text-align: center""#, r#" align="center""#)
+        .replace(r#" style="text-align: right""#, r#" align="right""#)
+}
+
+fn mark_cell_pipes(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut cell_depth = 0usize;
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        let (text, tail) = rest.split_at(start);
+        push_cell_text(&mut out, text, cell_depth > 0);
+        let end = tail.find('>').map_or(tail.len(), |index| index + 1);
+        let (tag, remaining) = tail.split_at(end);
+        let name = tag
+            .trim_start_matches('<')
+            .split(|ch: char| ch.is_whitespace() || ch == '>' || ch == '/')
+            .find(|part| !part.is_empty())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if name == "td" || name == "th" {
+            if tag.starts_with("</") {
+                cell_depth = cell_depth.saturating_sub(1);
+            } else {
+                cell_depth += 1;
+            }
+        }
+        out.push_str(tag);
+        rest = remaining;
+    }
+    push_cell_text(&mut out, rest, cell_depth > 0);
+    out
+}
+
+fn push_cell_text(out: &mut String, text: &str, in_cell: bool) {
+    if in_cell {
+        out.extend(
+            text.chars()
+                .map(|ch| if ch == '|' { CELL_PIPE_PLACEHOLDER } else { ch }),
+        );
+    } else {
+        out.push_str(text);
+    }
+}
+
 #[must_use]
 pub fn sanitize_html(html: &str) -> String {
     let tags: HashSet<&str> = [
         "p", "div", "span", "br", "em", "strong", "b", "i", "code", "pre", "blockquote", "ul",
-        "ol", "li", "a",
+        "ol", "li", "a", "table", "thead", "tbody", "tr", "th", "td",
     ]
     .into_iter()
     .collect();
 
     let mut attributes: HashMap<&str, HashSet<&str>> = HashMap::new();
     attributes.insert("a", ["href", "data-href"].into_iter().collect());
+    attributes.insert("th", ["align"].into_iter().collect());
+    attributes.insert("td", ["align"].into_iter().collect());
+    attributes.insert("code", ["class"].into_iter().collect());
 
     ammonia::Builder::new()
         .tags(tags)
         .tag_attributes(attributes)
+        .attribute_filter(|element, attribute, value| match (element, attribute) {
+            ("code", "class") => language_class(value).map(Cow::Owned),
+            _ => Some(value.into()),
+        })
         .clean(html)
         .to_string()
 }
 
+/// Keep only a `language-*` class on code elements, as emitted for fenced blocks.
+fn language_class(value: &str) -> Option<String> {
+    value
+        .split_whitespace()
+        .find(|class| {
+            class.strip_prefix("language-").is_some_and(|language| {
+                !language.is_empty()
+                    && language
+                        .chars()
+                        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '+' | '#'))
+            })
+        })
+        .map(str::to_string)
+}
+
 #[must_use]
 pub fn looks_like_markdown(input: &str) -> bool {
     let trimmed = input.trim_start();
@@ -127,11 +557,11 @@ pub fn strip_html_tags(input: &str) -> String {
     }
 
     out.replace("&nbsp;", " ")
-        .replace("&amp;", "&")
         .replace("&lt;", "<")
         .replace("&gt;", ">")
         .replace("&quot;", "\"")
         .replace("&#39;", "'")
+        .replace("&amp;", "&")
 }
 
 #[must_use]
@@ -159,6 +589,10 @@ pub fn looks_like_html(input: &str) -> bool {
         "<br",
         "<a ",
         "<img",
+        "<ul",
+        "<ol",
+        "<li",
+        "<table",
     ];
     tags.iter().any(|tag| lower.contains(tag))
 }
@@ -167,9 +601,24 @@ pub fn looks_like_html(input: &str) -> bool {
 pub fn normalize_markdown(input: &str) -> String {
     let normalized = input.replace("\r\n", "\n").replace('\r', "\n");
     let mut lines = Vec::new();
+    let mut in_code = Vec::new();
     let mut blank_streak = 0usize;
+    let mut fence: Option<(char, usize)> = None;
 
     for line in normalized.split('\n') {
+        // Fenced code is kept byte-for-byte, including indentation and blank lines.
+        if let Some((fence_char, fence_len)) = fence {
+            if fence_run(line).is_some_and(|(ch, len)| ch == fence_char && len >= fence_len)
+                && line.trim().chars().all(|ch| ch == fence_char)
+            {
+                fence = None;
+            }
+            lines.push(line.to_string());
+            in_code.push(true);
+            blank_streak = 0;
+            continue;
+        }
+
         let trimmed = line.trim_end_matches([' ', '\t']).to_string();
         if trimmed.is_empty() {
             blank_streak += 1;
@@ -179,22 +628,176 @@ pub fn normalize_markdown(input: &str) -> String {
         } else {
             blank_streak = 0;
         }
+        fence = fence_run(&trimmed);
+        in_code.push(fence.is_some());
         lines.push(trimmed);
     }
 
-    let mut output = lines.join("\n");
+    let mut output = align_tables(&lines, &in_code).join("\n");
     if !output.is_empty() && !output.ends_with('\n') {
         output.push('\n');
     }
     output
 }
 
+/// Fence character and run length when `line` is a code fence (up to three spaces of
+/// indentation, then at least three backticks or tildes).
+fn fence_run(line: &str) -> Option<(char, usize)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let fence_char = trimmed.chars().next().filter(|ch| matches!(ch, '`' | '~'))?;
+    let len = trimmed.chars().take_while(|ch| *ch == fence_char).count();
+    (len >= 3).then_some((fence_char, len))
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ColumnAlign {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+/// Rewrite pipe tables so every row has the same cells and each column is padded to
+/// its widest cell. Lines flagged in `in_code` are left alone.
+fn align_tables(lines: &[String], in_code: &[bool]) -> Vec<String> {
+    let mut output = Vec::with_capacity(lines.len());
+    let mut index = 0;
+    while index < lines.len() {
+        let line = &lines[index];
+        let is_table_start = !in_code[index]
+            && !in_code.get(index + 1).copied().unwrap_or(true)
+            && line.contains('|')
+            && lines
+                .get(index + 1)
+                .and_then(|next| parse_separator(next))
+                .is_some();
+        if !is_table_start {
+            output.push(line.clone());
+            index += 1;
+            continue;
+        }
+
+        let mut end = index + 2;
+        while end < lines.len()
+            && !in_code[end]
+            && lines[end].contains('|')
+            && !lines[end].trim().is_empty()
+        {
+            end += 1;
+        }
+        let header = split_table_row(line);
+        let aligns = parse_separator(&lines[index + 1]).unwrap_or_default();
+        let body: Vec<Vec<String>> = lines[index + 2..end]
+            .iter()
+            .map(|row| split_table_row(row))
+            .collect();
+        output.extend(format_table(&header, &aligns, &body));
+        index = end;
+    }
+    output
+}
+
+fn parse_separator(line: &str) -> Option<Vec<ColumnAlign>> {
+    let trimmed = line.trim();
+    if !trimmed.contains('-') || !trimmed.contains('|') {
+        return None;
+    }
+    split_table_row(trimmed)
+        .iter()
+        .map(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|ch| ch == '-') {
+                return None;
+            }
+            Some(match (cell.starts_with(':'), cell.ends_with(':')) {
+                (true, true) => ColumnAlign::Center,
+                (true, false) => ColumnAlign::Left,
+                (false, true) => ColumnAlign::Right,
+                (false, false) => ColumnAlign::None,
+            })
+        })
+        .collect()
+}
+
+/// Split a row on unescaped pipes; escaped pipes stay escaped in the cell text.
+fn split_table_row(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let trimmed = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let trimmed = if trimmed.ends_with('|') && !trimmed.ends_with("\\|") {
+        &trimmed[..trimmed.len() - 1]
+    } else {
+        trimmed
+    };
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut escaped = false;
+    for ch in trimmed.chars() {
+        if ch == '|' && !escaped {
+            cells.push(current.trim().to_string());
+            current.clear();
+            continue;
+        }
+        escaped = ch == '\\' && !escaped;
+        current.push(ch);
+    }
+    cells.push(current.trim().to_string());
+    cells
+}
+
+fn format_table(header: &[String], aligns: &[ColumnAlign], body: &[Vec<String>]) -> Vec<String> {
+    let columns = body
+        .iter()
+        .map(Vec::len)
+        .chain([header.len(), aligns.len()])
+        .max()
+        .unwrap_or(0);
+    let cell = |row: &[String], column: usize| row.get(column).cloned().unwrap_or_default();
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            body.iter()
+                .chain(std::iter::once(&header.to_vec()))
+                .map(|row| cell(row, column).chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(3)
+        })
+        .collect();
+
+    let format_row = |row: &[String]| {
+        let cells: Vec<String> = (0..columns)
+            .map(|column| format!("{:<width$}", cell(row, column), width = widths[column]))
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let separator: Vec<String> = (0..columns)
+        .map(|column| {
+            let width = widths[column];
+            match aligns.get(column).copied().unwrap_or(ColumnAlign::None) {
+                ColumnAlign::None => "-".repeat(width),
+                ColumnAlign::Left => format!(":{}", "-".repeat(width - 1)),
+                ColumnAlign::Center => format!(":{}:", "-".repeat(width - 2)),
+                ColumnAlign::Right => format!("{}:", "-".repeat(width - 1)),
+            }
+        })
+        .collect();
+
+    let mut rows = Vec::with_capacity(body.len() + 2);
+    rows.push(format_row(header));
+    rows.push(format!("| {} |", separator.join(" | ")));
+    rows.extend(body.iter().map(|row| format_row(row)));
+    rows
+}
+
 
 #[cfg(test)]
 mod tests {
     use super::{
-        html_to_markdown, looks_like_html, looks_like_markdown, markdown_to_html,
-        normalize_markdown, strip_html_tags,
+        MarkdownAction, PasteKind, PasteOffer, STARTER_TABLE_MARKDOWN, SelectionRange,
+        apply_markdown_action, html_to_markdown, looks_like_html, looks_like_markdown,
+        markdown_to_html, normalize_markdown, sanitize_html, starter_table_html, strip_html_tags,
     };
 
     #[test]
@@ -247,4 +850,203 @@ mod tests {
         let text = strip_html_tags("<p>Hello<br>World</p>");
         assert_eq!(text.trim(), "Hello\nWorld");
     }
+
+    #[test]
+    fn tables_round_trip_with_alignment_empty_cells_and_escaped_pipes() {
+        let markdown = "| Left | Center | Right |\n|:---|:---:|---:|\n| a \\| b | | c |\n";
+        let html = markdown_to_html(markdown);
+        assert!(html.contains(r#"<th align="center">Center</th>"#));
+        assert!(html.contains(r#"<td align="left">a | b</td>"#));
+
+        let round_trip = html_to_markdown(&html);
+        assert_eq!(
+            round_trip,
+            "| Left   | Center | Right |\n\
+             | :----- | :----: | ----: |\n\
+             | a \\| b |        | c     |\n"
+        );
+        assert_eq!(html_to_markdown(&markdown_to_html(&round_trip)), round_trip);
+    }
+
+    #[test]
+    fn normalize_markdown_aligns_ragged_tables_outside_code_fences() {
+        let input = "Intro\n|a|bb\n|-|-\n|ccc\n| d | e | f |\n\n```\n|x|y|\n|-|-|\n```\n";
+        let output = normalize_markdown(input);
+        assert_eq!(
+            output,
+            "Intro\n\
+             | a   | bb  |     |\n\
+             | --- | --- | --- |\n\
+             | ccc |     |     |\n\
+             | d   | e   | f   |\n\
+             \n\
+             ```\n|x|y|\n|-|-|\n```\n"
+        );
+    }
+
+    #[test]
+    fn starter_table_is_normalized_and_renders() {
+        assert_eq!(normalize_markdown(STARTER_TABLE_MARKDOWN), STARTER_TABLE_MARKDOWN);
+        let html = starter_table_html();
+        assert!(html.contains("<th>Column 1</th>"));
+        assert_eq!(html.matches("<td></td>").count(), 2);
+    }
+
+    #[test]
+    fn fenced_code_round_trips_with_language_and_indentation() {
+        let markdown = "Intro\n\n```rust\nfn main() {\n    let x = a * b_c;\n\n\n    x\n}\n```\n";
+        let html = markdown_to_html(markdown);
+        assert!(html.contains(r#"<code class="language-rust">"#));
+        assert_eq!(html_to_markdown(&html), markdown);
+    }
+
+    #[test]
+    fn fenced_code_with_nested_backticks_round_trips() {
+        let markdown = "````markdown\n```\ninner `tick`\n```\n````\n";
+        assert_eq!(normalize_markdown(markdown), markdown);
+        assert_eq!(html_to_markdown(&markdown_to_html(markdown)), markdown);
+    }
+
+    #[test]
+    fn normalize_markdown_leaves_fenced_content_alone() {
+        let input = "Text  \n~~~\n  *a*  \n\n\n__b__\t\n| x |y|\n|-|-|\n~~~\nAfter\n";
+        let output = normalize_markdown(input);
+        assert_eq!(
+            output,
+            "Text\n~~~\n  *a*  \n\n\n__b__\t\n| x |y|\n|-|-|\n~~~\nAfter\n"
+        );
+    }
+
+    #[test]
+    fn sanitize_html_keeps_only_language_classes_on_code() {
+        let html = sanitize_html(
+            r#"<pre><code class="x language-js">a</code></pre><p class="y">b</p>"#,
+        );
+        assert!(html.contains(r#"<code class="language-js">"#));
+        assert!(!html.contains("class=\"x"));
+        assert!(!html.contains("<p class"));
+    }
+
+    #[test]
+    fn code_block_action_wraps_selection_in_longer_fence_when_needed() {
+        let text = "See:\nlet x = `y`;\ndone";
+        let edit =
+            apply_markdown_action(text, SelectionRange::new(5, 17), MarkdownAction::CodeBlock);
+        assert_eq!(edit.text, "See:\n```\nlet x = `y`;\n```\ndone");
+        assert_eq!(&edit.text[edit.selection.start..edit.selection.end], "let x = `y`;");
+
+        let text = "Run ```sh``` first";
+        let edit =
+            apply_markdown_action(text, SelectionRange::new(4, 12), MarkdownAction::CodeBlock);
+        assert_eq!(edit.text, "Run \n````\n```sh```\n````\n first");
+
+        let edit = apply_markdown_action("", SelectionRange::caret(0), MarkdownAction::CodeBlock);
+        assert_eq!(edit.text, "```\n\n```");
+        assert_eq!(edit.selection, SelectionRange::caret(4));
+    }
+
+    #[test]
+    fn inline_and_line_actions_edit_markdown_source() {
+        let edit = apply_markdown_action("a b c", SelectionRange::new(2, 3), MarkdownAction::Bold);
+        assert_eq!(edit.text, "a **b** c");
+        assert_eq!(edit.selection, SelectionRange::new(4, 5));
+
+        let edit = apply_markdown_action("x `y`", SelectionRange::new(2, 5), MarkdownAction::Code);
+        assert_eq!(edit.text, "x `` `y` ``");
+
+        let edit = apply_markdown_action(
+            "one\ntwo\nthree",
+            SelectionRange::new(1, 5),
+            MarkdownAction::NumberedList,
+        );
+        assert_eq!(edit.text, "1. one\n2. two\nthree");
+        assert_eq!(edit.selection, SelectionRange::new(0, 13));
+
+        let edit = apply_markdown_action("Intro", SelectionRange::caret(5), MarkdownAction::Table);
+        assert!(edit.text.starts_with("Intro\n| Column 1 |"));
+        assert_eq!(&edit.text[edit.selection.start..edit.selection.end], "Column 1");
+    }
+
+    #[test]
+    fn paste_offer_converts_messy_list_html_and_drops_scripts() {
+        let html = "<html><head><meta charset=\"utf-8\"><style>li { color: red; }</style></head>\
+            <body><!--StartFragment--><ul style=\"margin:0\">\
+            <li><span style=\"font-weight:700\">First</span> item</li>\
+            <li onclick=\"alert(1)\">Second <a href=\"javascript:alert(1)\">link</a></li>\
+            </ul><script>alert('x')</script><ol><li>Step one</li><li>Step two</li></ol>\
+            <!--EndFragment--></body></html>";
+
+        let offer = PasteOffer::from_clipboard(html, "First item\nSecond link").unwrap();
+
+        assert!(!offer.html.contains("script"));
+        assert!(!offer.html.contains("style"));
+        assert!(!offer.html.contains("onclick"));
+        assert!(!offer.html.contains("javascript:"));
+        assert!(!offer.markdown.contains("alert"));
+        assert!(!offer.markdown.contains("color"));
+        assert!(offer.markdown.contains("First item"));
+        assert!(offer.markdown.contains("* Second link\n"));
+        assert!(offer.markdown.contains("1. Step one"));
+        assert!(offer.markdown.contains("2. Step two"));
+        assert_eq!(offer.plain, "First item\nSecond link");
+        assert!(offer.markdown_html().contains("<ul>"));
+    }
+
+    #[test]
+    fn paste_offer_converts_web_tables() {
+        let html = "<meta charset=\"utf-8\"><table class=\"wikitable\"><tbody>\
+            <tr><th style=\"width:40%\">Word</th><th>Meaning</th></tr>\
+            <tr><td><b>Hund</b></td><td>dog</td></tr>\
+            <tr><td>Katze</td><td>cat | feline</td></tr></tbody></table>";
+
+        let offer = PasteOffer::from_clipboard(html, "").unwrap();
+
+        assert!(offer.markdown.contains("| Word"));
+        assert!(offer.markdown.contains("**Hund**"));
+        assert!(offer.markdown.contains("cat \\| feline"));
+        assert!(!offer.html.contains("wikitable"));
+        assert!(offer.plain.contains("Katze"));
+        assert!(offer.markdown_html().contains("<table>"));
+    }
+
+    #[test]
+    fn paste_offer_reads_markup_from_plain_text_and_skips_unstructured_content() {
+        let offer = PasteOffer::from_clipboard("", "<ul><li>One</li><li>Two</li></ul>").unwrap();
+        assert_eq!(offer.markdown, "* One\n* Two\n");
+        assert_eq!(offer.plain, "One\nTwo");
+
+        assert_eq!(PasteOffer::from_clipboard("<p>Just <b>bold</b></p>", "Just bold"), None);
+        assert_eq!(PasteOffer::from_clipboard("", "- a markdown list"), None);
+        assert_eq!(PasteOffer::from_clipboard("  ", "plain"), None);
+    }
+
+    #[test]
+    fn paste_kind_separates_images_from_text() {
+        assert_eq!(PasteKind::classify(true, "", ""), PasteKind::Image);
+        let copied_image = "<meta charset=\"utf-8\"><img src=\"https://example.com/cat.png\">";
+        assert_eq!(PasteKind::classify(true, copied_image, " "), PasteKind::Image);
+        assert_eq!(PasteKind::classify(true, "", "A caption"), PasteKind::ImageOrText);
+        assert_eq!(
+            PasteKind::classify(true, "<p>Cat <img src=\"cat.png\"></p>", ""),
+            PasteKind::ImageOrText
+        );
+    }
+
+    #[test]
+    fn paste_kind_without_an_image_picks_structured_html_or_text() {
+        assert!(matches!(
+            PasteKind::classify(false, "<ul><li>One</li></ul>", "One"),
+            PasteKind::Structured(_)
+        ));
+        assert_eq!(
+            PasteKind::classify(false, "<p>Just <b>bold</b><script>x()</script></p>", "Just bold"),
+            PasteKind::Html(sanitize_html("<p>Just <b>bold</b></p>"))
+        );
+        assert!(matches!(
+            PasteKind::classify(false, "", "<p>markup</p>"),
+            PasteKind::Html(html) if html.contains("markup")
+        ));
+        assert_eq!(PasteKind::classify(false, "", "plain words"), PasteKind::Text);
+        assert_eq!(PasteKind::classify(false, "", ""), PasteKind::Text);
+    }
 }
@@ -1,27 +1,54 @@
 mod deck_vm;
 mod card_vm;
+mod card_history_vm;
 mod session_summary_vm;
 mod session_vm;
 mod markdown_vm;
+mod edit_history_vm;
+mod card_content_vm;
 mod time_fmt;
 mod editor_vm;
 mod practice_vm;
+mod preview_vm;
+mod text_highlight;
+mod typed_answer_vm;
 
-pub use deck_vm::{DeckOptionVm, map_deck_options};
+pub use deck_vm::{
+    DeckOptionVm, card_counts_label, deck_color_class, deck_color_label, deck_icon_glyph,
+    deck_icon_label, map_deck_options,
+};
 pub use card_vm::{
-    CardListItemVm, build_card_list_item, filter_card_list_items, map_card_list_items,
+    CardListItemVm, DeckSearchGroupVm, TrashedCardVm, build_card_list_item,
+    filter_card_list_items, flag_label, map_card_list_items, map_deck_search_groups,
+    map_trashed_cards,
 };
+pub use card_history_vm::{CardHistoryRowVm, map_card_history};
 pub use session_summary_vm::{
     SessionSummaryCardVm, SessionSummaryDetailVm, map_session_summary_cards,
     map_session_summary_detail,
 };
 pub use session_vm::{
-    SessionIntent, SessionOutcome, SessionPhase, SessionStartMode, SessionVm, start_session,
+    SessionIntent, SessionOutcome, SessionPhase, SessionStartMode, SessionVm, StartedSession,
+    card_ids_param, nothing_due_label, parse_card_ids_param, start_session,
 };
 pub use markdown_vm::{
-    MarkdownAction, MarkdownField, html_to_markdown, looks_like_html, looks_like_markdown,
-    markdown_to_html, normalize_markdown, sanitize_html, strip_html_tags,
+    MarkdownAction, MarkdownEdit, MarkdownField, PasteKind, PasteOffer, SelectionRange,
+    apply_markdown_action, html_to_markdown, looks_like_html, looks_like_markdown, markdown_to_html,
+    normalize_markdown, sanitize_html, starter_table_html, strip_html_tags,
+};
+pub use edit_history_vm::{EDIT_HISTORY_LIMIT, EditHistory, FieldEditHistories};
+pub use card_content_vm::{
+    CardMedia, TemplateFields, render_card_content, render_card_template, render_card_text,
+    reveal_step_prefixes,
 };
-pub use editor_vm::{DailyLimitVm, EditorVm, build_editor_vm};
+pub use editor_vm::{CharCounterVm, DailyLimitVm, EditorVm, build_editor_vm};
 pub use practice_vm::{PracticeDeckCardVm, PracticeTagPillVm, map_practice_deck_card};
-pub use time_fmt::{format_datetime, format_relative_datetime};
+pub use preview_vm::{PREVIEW_DEBOUNCE, PreviewDebounce, render_preview_side};
+pub use text_highlight::{HighlightSpan, highlight_spans};
+pub use typed_answer_vm::{
+    AnswerDiffKind, AnswerDiffSpan, TypedAnswerCheck, check_typed_answer, normalize_typed_answer,
+};
+pub use time_fmt::{
+    format_datetime, format_due, format_interval, format_relative_datetime, is_overdue,
+    relative_from,
+};
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use learn_core::model::{Content, MediaId};
+
+use super::card_content_vm::{CardMedia, render_card_content};
+use super::markdown_vm::{sanitize_html, strip_html_tags};
+
+/// How long the editor preview waits after the last edit before rendering again.
+pub const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Collapses a burst of edits into a single preview render.
+///
+/// Each edit takes a ticket and schedules a render after `PREVIEW_DEBOUNCE`; once the delay
+/// is over, the render only goes ahead if no later edit has taken a ticket since.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PreviewDebounce {
+    latest: u64,
+}
+
+impl PreviewDebounce {
+    /// Take a ticket for an edit, superseding every earlier one.
+    pub fn schedule(&mut self) -> u64 {
+        self.latest = self.latest.wrapping_add(1);
+        self.latest
+    }
+
+    /// Whether the render scheduled with `ticket` is still wanted.
+    #[must_use]
+    pub fn is_latest(self, ticket: u64) -> bool {
+        ticket == self.latest
+    }
+}
+
+/// Render one side of the card being edited the way a session shows it once saved.
+///
+/// `raw_html` is the field as typed and is sanitized like a save would; `media` is the
+/// image of `media_id`, if loaded. Returns `None` while the side has no text.
+#[must_use]
+pub fn render_preview_side(
+    raw_html: &str,
+    media_id: Option<MediaId>,
+    media: Option<&CardMedia>,
+) -> Option<String> {
+    let html = sanitize_html(raw_html);
+    if strip_html_tags(&html).trim().is_empty() {
+        return None;
+    }
+    let content = Content::from_persisted(html, media_id).ok()?;
+    Some(render_card_content(&content, media))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_last_edit_of_a_burst_renders() {
+        let mut debounce = PreviewDebounce::default();
+        let first = debounce.schedule();
+        let second = debounce.schedule();
+        assert!(!debounce.is_latest(first));
+        assert!(debounce.is_latest(second));
+
+        // A pause lets the last render through; the next edit starts a new burst.
+        let third = debounce.schedule();
+        assert!(!debounce.is_latest(second));
+        assert!(debounce.is_latest(third));
+    }
+
+    #[test]
+    fn preview_renders_markdown_and_inlines_loaded_images() {
+        let table = "| a | b |\n|---|---|\n| 1 | 2 |";
+        let html = render_preview_side(table, None, None).expect("preview");
+        assert!(html.contains("<table>"), "{html}");
+
+        let src = "data:image/png;base64,AAAA";
+        let media = CardMedia::Src(src.to_string());
+        let html = render_preview_side("Cat", Some(MediaId::new(1)), Some(&media)).unwrap();
+        assert!(html.contains(src), "{html}");
+        let html = render_preview_side("Cat", Some(MediaId::new(1)), None).unwrap();
+        assert!(!html.contains("<img"), "{html}");
+
+        assert_eq!(render_preview_side("<p> </p>", None, None), None);
+    }
+}
@@ -1,17 +1,23 @@
-use services::SessionSummaryListItem;
+use chrono::{DateTime, Utc};
+use learn_core::model::ReviewGrade;
+use services::{SessionSummaryId, SessionSummaryListItem};
 
-use crate::vm::time_fmt::format_datetime;
+use crate::vm::time_fmt::{format_datetime, relative_from};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SessionSummaryCardVm {
-    pub id: i64,
+    /// Stable key for the history list row.
+    pub id: SessionSummaryId,
     pub completed_at_str: String,
+    pub completed_ago: String,
 
     pub total: u32,
     pub again: u32,
     pub hard: u32,
     pub good: u32,
     pub easy: u32,
+    pub is_cram: bool,
+    pub is_ahead: bool,
 
     pub cards_label: String,
     pub again_pct: u32,
@@ -20,18 +26,22 @@ pub struct SessionSummaryCardVm {
     pub easy_pct: u32,
 }
 
-impl From<&SessionSummaryListItem> for SessionSummaryCardVm {
-    fn from(item: &SessionSummaryListItem) -> Self {
+impl SessionSummaryCardVm {
+    #[must_use]
+    pub fn from_item(item: &SessionSummaryListItem, now: DateTime<Utc>) -> Self {
         let total = item.total;
         let pct = |count: u32| if total == 0 { 0 } else { (count.saturating_mul(100)) / total };
         Self {
             id: item.id,
             completed_at_str: format_datetime(&item.completed_at),
+            completed_ago: relative_from(now, item.completed_at),
             total: item.total,
             again: item.again,
             hard: item.hard,
             good: item.good,
             easy: item.easy,
+            is_cram: item.is_cram,
+            is_ahead: item.is_ahead,
             cards_label: format!("{total} Cards"),
             again_pct: pct(item.again),
             hard_pct: pct(item.hard),
@@ -42,32 +52,127 @@ impl From<&SessionSummaryListItem> for SessionSummaryCardVm {
 }
 
 #[must_use]
-pub fn map_session_summary_cards(items: &[SessionSummaryListItem]) -> Vec<SessionSummaryCardVm> {
-    items.iter().map(SessionSummaryCardVm::from).collect()
+pub fn map_session_summary_cards(
+    items: &[SessionSummaryListItem],
+    now: DateTime<Utc>,
+) -> Vec<SessionSummaryCardVm> {
+    items
+        .iter()
+        .map(|item| SessionSummaryCardVm::from_item(item, now))
+        .collect()
+}
+
+/// How often one grade was pressed in a session.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GradeCountVm {
+    pub grade: ReviewGrade,
+    pub label: &'static str,
+    /// Suffix for the `history-metric--*` color classes.
+    pub modifier: &'static str,
+    pub count: u32,
+    pub pct: u32,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SessionSummaryDetailVm {
     pub started_at_str: String,
     pub completed_at_str: String,
+    pub completed_ago: String,
     pub total: u32,
-    pub again: u32,
-    pub hard: u32,
-    pub good: u32,
-    pub easy: u32,
+    /// One entry per grade, from Again to Easy.
+    pub grades: Vec<GradeCountVm>,
 }
 
 #[must_use]
 pub fn map_session_summary_detail(
     summary: &learn_core::model::SessionSummary,
+    now: DateTime<Utc>,
 ) -> SessionSummaryDetailVm {
+    let total = summary.total_reviews();
     SessionSummaryDetailVm {
         started_at_str: format_datetime(&summary.started_at()),
         completed_at_str: format_datetime(&summary.completed_at()),
-        total: summary.total_reviews(),
-        again: summary.again(),
-        hard: summary.hard(),
-        good: summary.good(),
-        easy: summary.easy(),
+        completed_ago: relative_from(now, summary.completed_at()),
+        total,
+        grades: vec![
+            grade_count(ReviewGrade::Again, summary.again(), total),
+            grade_count(ReviewGrade::Hard, summary.hard(), total),
+            grade_count(ReviewGrade::Good, summary.good(), total),
+            grade_count(ReviewGrade::Easy, summary.easy(), total),
+        ],
+    }
+}
+
+fn grade_count(grade: ReviewGrade, count: u32, total: u32) -> GradeCountVm {
+    let (label, modifier) = grade_label(grade);
+    let pct = count.saturating_mul(100).checked_div(total).unwrap_or(0);
+    GradeCountVm {
+        grade,
+        label,
+        modifier,
+        count,
+        pct,
+    }
+}
+
+/// Display label and `history-metric--*` modifier for a grade.
+pub(super) fn grade_label(grade: ReviewGrade) -> (&'static str, &'static str) {
+    match grade {
+        ReviewGrade::Again => ("Again", "again"),
+        ReviewGrade::Hard => ("Hard", "hard"),
+        ReviewGrade::Good => ("Good", "good"),
+        ReviewGrade::Easy => ("Easy", "easy"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use learn_core::model::{CardId, DeckId, ReviewLog, SessionSummary};
+
+    use super::*;
+
+    #[test]
+    fn detail_vm_breaks_down_grades_in_order() {
+        let started_at = Utc.with_ymd_and_hms(2024, 3, 10, 9, 0, 0).unwrap();
+        let completed_at = started_at + Duration::minutes(12);
+        let grades = [
+            ReviewGrade::Good,
+            ReviewGrade::Again,
+            ReviewGrade::Good,
+            ReviewGrade::Easy,
+            ReviewGrade::Again,
+            ReviewGrade::Good,
+            ReviewGrade::Hard,
+            ReviewGrade::Good,
+            ReviewGrade::Again,
+            ReviewGrade::Good,
+        ];
+        let logs: Vec<ReviewLog> = grades
+            .iter()
+            .zip(1_u64..)
+            .map(|(grade, id)| ReviewLog::new(CardId::new(id), *grade, completed_at))
+            .collect();
+        let summary =
+            SessionSummary::from_logs(DeckId::new(1), started_at, completed_at, &logs).unwrap();
+
+        let vm = map_session_summary_detail(&summary, completed_at + Duration::minutes(5));
+
+        assert_eq!(vm.total, 10);
+        assert_eq!(vm.completed_ago, "5 min ago");
+        let breakdown: Vec<_> = vm
+            .grades
+            .iter()
+            .map(|entry| (entry.grade, entry.label, entry.count, entry.pct))
+            .collect();
+        assert_eq!(
+            breakdown,
+            [
+                (ReviewGrade::Again, "Again", 3, 30),
+                (ReviewGrade::Hard, "Hard", 1, 10),
+                (ReviewGrade::Good, "Good", 5, 50),
+                (ReviewGrade::Easy, "Easy", 1, 10),
+            ]
+        );
     }
 }
@@ -1,12 +1,32 @@
-use learn_core::model::{DeckId, ReviewGrade, TagName};
-use services::{SessionLoopService, SessionReviewPreview, SessionService};
+use learn_core::model::{
+    Card, CardId, Content, DeckId, Flag, MediaId, MediaUri, ReviewGrade, TagName,
+};
+use learn_core::scheduler::{IntervalAdjustmentKind, ReviewExplanation};
+use services::{
+    CramFilter, NothingDue, SessionLoopService, SessionProgress, SessionReviewPreview,
+    SessionService, SessionStart,
+};
 
 use crate::views::ViewError;
 
+use super::card_content_vm::reveal_step_prefixes;
+use super::time_fmt::format_interval;
+use super::typed_answer_vm::{TypedAnswerCheck, check_typed_answer};
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SessionIntent {
     Reveal,
     Grade(ReviewGrade),
+    /// Set the current card aside until tomorrow without grading it.
+    Bury,
+    /// Move the current card to the end of this session without grading it.
+    Skip,
+    /// Move the current card's flag on to the next color, or clear it after the last.
+    CycleFlag,
+    /// Show the card just failed again, answer included, before the next card.
+    Restudy,
+    /// Leave restudy and go on to the next card.
+    EndRestudy,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -14,6 +34,14 @@ pub enum SessionStartMode {
     Due,
     All,
     Mistakes,
+    /// Grade every card for self-check without changing scheduling.
+    Cram,
+    /// Review cards due in the next few days before they fall due.
+    Ahead,
+    /// Pick up the session left unfinished when the app was closed.
+    Resume,
+    /// Study the cards picked in the editor, due or not, grading them as usual.
+    Selected,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -31,6 +59,13 @@ pub enum SessionOutcome {
 pub struct SessionVm {
     session: SessionService,
     phase: SessionPhase,
+    /// Reveal steps of the answer shown so far, counting from zero, under progressive reveal.
+    reveal_step: usize,
+    /// What the learner typed for the current card, when the deck asks for typed answers.
+    typed_answer: Option<String>,
+    /// The failed card shown again for restudy; it stands in for the current card until
+    /// the learner moves on.
+    restudy: Option<Card>,
 }
 
 impl SessionVm {
@@ -39,26 +74,188 @@ impl SessionVm {
         Self {
             session,
             phase: SessionPhase::Prompt,
+            reveal_step: 0,
+            typed_answer: None,
+            restudy: None,
         }
     }
 
+    /// The answer shows throughout restudy.
     #[must_use]
     pub fn phase(&self) -> SessionPhase {
-        self.phase
+        if self.restudy.is_some() {
+            SessionPhase::Answer
+        } else {
+            self.phase
+        }
+    }
+
+    /// Whether the failed card is being shown again.
+    #[must_use]
+    pub fn is_restudying(&self) -> bool {
+        self.restudy.is_some()
     }
 
+    /// Whether the last answer was an Again whose card can be shown again right now.
+    #[must_use]
+    pub fn can_restudy(&self) -> bool {
+        self.restudy.is_none()
+            && self.phase == SessionPhase::Prompt
+            && self.session.last_lapse().is_some()
+    }
+
+    /// Show the card just failed again, without grading it a second time.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ViewError::Unknown` when the last answer was not an Again.
+    pub fn restudy_last_lapse(
+        &mut self,
+        session_loop: &SessionLoopService,
+    ) -> Result<(), ViewError> {
+        let card = session_loop
+            .restudy_last_lapse(&self.session)
+            .map_err(|_| ViewError::Unknown)?;
+        self.restudy = Some(card);
+        Ok(())
+    }
+
+    /// Leave restudy and show the next card's prompt.
+    pub fn end_restudy(&mut self, session_loop: &SessionLoopService) {
+        if self.restudy.take().is_some() {
+            session_loop.finish_restudy(&mut self.session);
+        }
+    }
+
+    /// The card whose content is showing: the restudied card, else the current one.
+    fn shown_card(&self) -> Option<&Card> {
+        self.restudy.as_ref().or_else(|| self.session.current_card())
+    }
+
+    /// Show the answer, or its next step when the deck reveals answers progressively.
     pub fn reveal(&mut self) {
-        self.phase = SessionPhase::Answer;
+        if self.phase == SessionPhase::Prompt {
+            self.phase = SessionPhase::Answer;
+            self.reveal_step = 0;
+        } else if !self.answer_fully_revealed() {
+            self.reveal_step += 1;
+        }
+    }
+
+    /// Whether the whole answer is showing, which is when grading unlocks.
+    #[must_use]
+    pub fn answer_fully_revealed(&self) -> bool {
+        self.restudy.is_some()
+            || (self.phase == SessionPhase::Answer
+                && self.reveal_step + 1 >= self.reveal_steps().len())
+    }
+
+    /// Steps shown and total steps while a progressive reveal is under way.
+    #[must_use]
+    pub fn reveal_progress(&self) -> Option<(usize, usize)> {
+        let total = self.reveal_steps().len();
+        (self.phase() == SessionPhase::Answer && !self.answer_fully_revealed())
+            .then_some((self.reveal_step + 1, total))
+    }
+
+    /// The part of the answer text revealed so far; `None` once all of it shows.
+    #[must_use]
+    pub fn partial_answer_text(&self) -> Option<&str> {
+        if self.phase() == SessionPhase::Prompt || self.answer_fully_revealed() {
+            return None;
+        }
+        self.reveal_steps().get(self.reveal_step).copied()
+    }
+
+    /// Whether the deck asks for the answer to be typed before it is revealed.
+    #[must_use]
+    pub fn asks_typed_answer(&self) -> bool {
+        self.session.deck_settings().typed_answer()
+    }
+
+    /// Keep what the learner typed so it can be checked once the answer shows.
+    pub fn set_typed_answer(&mut self, typed: String) {
+        self.typed_answer = Some(typed);
+    }
+
+    /// The typed answer checked against the card's answer, once the answer is revealed.
+    #[must_use]
+    pub fn typed_answer_check(&self) -> Option<TypedAnswerCheck> {
+        if self.phase == SessionPhase::Prompt {
+            return None;
+        }
+        let typed = self.typed_answer.as_deref()?;
+        Some(check_typed_answer(typed, self.answer_text()?))
+    }
+
+    fn reveal_steps(&self) -> Vec<&str> {
+        let Some(answer) = self.answer_text() else {
+            return Vec::new();
+        };
+        if self.session.deck_settings().progressive_reveal() {
+            reveal_step_prefixes(answer)
+        } else {
+            vec![answer]
+        }
     }
 
     #[must_use]
     pub fn prompt_text(&self) -> Option<&str> {
-        self.session.current_card().map(|card| card.prompt().text())
+        self.shown_card().map(|card| card.prompt().text())
     }
 
     #[must_use]
     pub fn answer_text(&self) -> Option<&str> {
-        self.session.current_card().map(|card| card.answer().text())
+        self.shown_card().map(|card| card.answer().text())
+    }
+
+    #[must_use]
+    pub fn prompt_content(&self) -> Option<&Content> {
+        self.shown_card().map(|card| card.prompt())
+    }
+
+    #[must_use]
+    pub fn answer_content(&self) -> Option<&Content> {
+        self.shown_card().map(|card| card.answer())
+    }
+
+    /// Extra info for the current card, shown beneath the answer once it is revealed.
+    #[must_use]
+    pub fn extra_text(&self) -> Option<&str> {
+        self.shown_card()
+            .and_then(|card| card.extra())
+            .map(|extra| extra.text())
+    }
+
+    #[must_use]
+    pub fn prompt_media_id(&self) -> Option<MediaId> {
+        self.shown_card()
+            .and_then(|card| card.prompt().media_id())
+    }
+
+    #[must_use]
+    pub fn answer_media_id(&self) -> Option<MediaId> {
+        self.shown_card()
+            .and_then(|card| card.answer().media_id())
+    }
+
+    #[must_use]
+    pub fn prompt_audio(&self) -> Option<MediaUri> {
+        self.shown_card()
+            .and_then(|card| card.prompt().audio())
+            .map(|clip| clip.uri().clone())
+    }
+
+    #[must_use]
+    pub fn answer_audio(&self) -> Option<MediaUri> {
+        self.shown_card()
+            .and_then(|card| card.answer().audio())
+            .map(|clip| clip.uri().clone())
+    }
+
+    #[must_use]
+    pub fn current_card_id(&self) -> Option<CardId> {
+        self.session.current_card().map(|card| card.id())
     }
 
     #[must_use]
@@ -95,6 +292,26 @@ impl SessionVm {
             .count()
     }
 
+    /// Lines explaining how the last graded card got its interval, for the
+    /// "why this interval?" disclosure; `None` before the first grade of this sitting.
+    #[must_use]
+    pub fn last_interval_explanation(&self) -> Option<Vec<String>> {
+        let review = self.session.results().last()?;
+        review.result.explanation.as_ref().map(explanation_lines)
+    }
+
+    /// Note for the learner when their last answer took its card out of the session
+    /// after too many Agains in a row.
+    #[must_use]
+    pub fn failed_out_notice(&self) -> Option<String> {
+        self.session.failed_out_last().then(|| {
+            let limit = self.session.deck_settings().same_session_fail_limit();
+            format!(
+                "That card missed {limit} times in a row, so it's out for now and tagged \"leech\"."
+            )
+        })
+    }
+
     /// # Errors
     ///
     /// Returns `ViewError::Unknown` for service failures.
@@ -114,10 +331,82 @@ impl SessionVm {
             });
         }
 
-        self.phase = SessionPhase::Prompt;
+        self.show_next_prompt();
+        Ok(SessionOutcome::Continue)
+    }
+
+    /// # Errors
+    ///
+    /// Returns `ViewError::Unknown` for service failures.
+    pub async fn bury_current(
+        &mut self,
+        session_loop: &SessionLoopService,
+    ) -> Result<SessionOutcome, ViewError> {
+        let card_id = self.current_card_id().ok_or(ViewError::Unknown)?;
+        session_loop
+            .bury_card(&mut self.session, card_id)
+            .await
+            .map_err(|_| ViewError::Unknown)?;
+
+        if self.session.is_complete() {
+            return Ok(SessionOutcome::Completed {
+                summary_id: self.session.summary_id(),
+            });
+        }
+
+        self.show_next_prompt();
+        Ok(SessionOutcome::Continue)
+    }
+
+    /// Whether the current card may still be skipped rather than graded.
+    #[must_use]
+    pub fn can_skip(&self) -> bool {
+        self.restudy.is_none() && self.session.can_skip()
+    }
+
+    /// # Errors
+    ///
+    /// Returns `ViewError::Unknown` for service failures, including a card that has been
+    /// skipped too often.
+    pub async fn skip_current(
+        &mut self,
+        session_loop: &SessionLoopService,
+    ) -> Result<SessionOutcome, ViewError> {
+        session_loop
+            .skip_card(&mut self.session)
+            .await
+            .map_err(|_| ViewError::Unknown)?;
+        self.show_next_prompt();
         Ok(SessionOutcome::Continue)
     }
 
+    fn show_next_prompt(&mut self) {
+        self.phase = SessionPhase::Prompt;
+        self.reveal_step = 0;
+        self.typed_answer = None;
+    }
+
+    #[must_use]
+    pub fn current_flag(&self) -> Option<Flag> {
+        self.session.current_card().and_then(|card| card.flag())
+    }
+
+    /// # Errors
+    ///
+    /// Returns `ViewError::Unknown` for service failures.
+    pub async fn cycle_flag(&mut self, session_loop: &SessionLoopService) -> Result<(), ViewError> {
+        let flag = Flag::cycle(self.current_flag());
+        session_loop
+            .flag_current(&mut self.session, flag)
+            .await
+            .map_err(|_| ViewError::Unknown)
+    }
+
+    #[must_use]
+    pub fn progress(&self, session_loop: &SessionLoopService) -> SessionProgress {
+        session_loop.progress(&self.session)
+    }
+
     #[must_use]
     pub fn preview_current(
         &self,
@@ -127,31 +416,139 @@ impl SessionVm {
     }
 }
 
+/// What `start_session` opened.
+pub enum StartedSession {
+    Ready(Box<SessionVm>),
+    /// A due session found nothing to practice; show when the next card is due instead.
+    NothingDue(NothingDue),
+}
+
+/// Encouraging line for an empty due session, with how long until the next card.
+#[must_use]
+pub fn nothing_due_label(nothing_due: &NothingDue) -> String {
+    nothing_due.next_due_at.map_or_else(
+        || "No cards are scheduled yet.".to_string(),
+        |at| match format_interval(nothing_due.checked_at, at).as_str() {
+            "now" => "The next card is due in a moment.".to_string(),
+            wait => format!("The next card is due in {wait}."),
+        },
+    )
+}
+
+fn explanation_lines(explanation: &ReviewExplanation) -> Vec<String> {
+    let mut lines = Vec::new();
+    match (&explanation.previous, explanation.retrievability) {
+        (Some(previous), Some(recall)) => {
+            lines.push(format!(
+                "Stability {:.1} days, difficulty {:.1}",
+                previous.stability, previous.difficulty
+            ));
+            lines.push(format!(
+                "{:.0}% recall chance after {:.1} days",
+                recall * 100.0,
+                explanation.elapsed_days
+            ));
+        }
+        _ => lines.push("No memory state yet, so FSRS started fresh".to_string()),
+    }
+    lines.push(format!("FSRS proposed {:.1} days", explanation.fsrs_days));
+    for adjustment in &explanation.adjustments {
+        let reason = match adjustment.kind {
+            IntervalAdjustmentKind::LearningStep => "Learning step",
+            IntervalAdjustmentKind::LapseMinimum => "Minimum after a lapse",
+            IntervalAdjustmentKind::Clamp => "Deck interval limits",
+            IntervalAdjustmentKind::Fuzz => "Fuzz",
+            IntervalAdjustmentKind::LoadBalance => "Load balancing",
+        };
+        lines.push(format!(
+            "{reason}: {:.1} \u{2192} {:.1} days",
+            adjustment.from_days, adjustment.to_days
+        ));
+    }
+    lines.push(format!("Scheduled in {:.1} days", explanation.scheduled_days));
+    lines
+}
+
+/// Card ids joined for a session route, e.g. `12,7,30`.
+#[must_use]
+pub fn card_ids_param(card_ids: &[CardId]) -> String {
+    card_ids
+        .iter()
+        .map(|id| id.value().to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Card ids read back from a session route; `None` if any part is not an id.
+#[must_use]
+pub fn parse_card_ids_param(param: &str) -> Option<Vec<CardId>> {
+    param
+        .split(',')
+        .map(|part| part.trim().parse::<u64>().ok().map(CardId::new))
+        .collect()
+}
+
+/// `card_ids` are the cards to study in `SessionStartMode::Selected` and ignored otherwise.
+///
 /// # Errors
 ///
-/// Returns `ViewError::EmptySession` when no cards are available.
+/// Returns `ViewError::EmptySession` when no cards are available, including when
+/// there is no saved session left to resume. A due session with nothing due is not an
+/// error; it comes back as `StartedSession::NothingDue`.
 /// Returns `ViewError::Unknown` for other failures.
 pub async fn start_session(
     session_loop: &SessionLoopService,
     deck_id: DeckId,
     tag: Option<TagName>,
     mode: SessionStartMode,
-) -> Result<SessionVm, ViewError> {
-    let session_result = if let Some(tag) = tag {
-        session_loop.start_session_with_tags(deck_id, &[tag]).await
-    } else {
-        match mode {
-            SessionStartMode::Due => session_loop.start_session(deck_id).await,
-            SessionStartMode::All => session_loop.start_session_all_cards(deck_id).await,
-            SessionStartMode::Mistakes => session_loop.start_session_mistakes(deck_id).await,
+    card_ids: &[CardId],
+) -> Result<StartedSession, ViewError> {
+    let begun = |session| SessionStart::Started(Box::new(session));
+    let started = match (mode, tag) {
+        (SessionStartMode::Due, None) => session_loop.start_or_nothing_due(deck_id).await,
+        (SessionStartMode::Cram, tag) => {
+            let filter = CramFilter {
+                tags: tag.into_iter().collect(),
+                due_within_days: None,
+            };
+            session_loop
+                .start_cram_session(deck_id, &filter)
+                .await
+                .map(begun)
         }
+        (SessionStartMode::Ahead, _) => session_loop
+            .start_ahead_session(deck_id, SessionLoopService::MAX_AHEAD_DAYS)
+            .await
+            .map(begun),
+        (SessionStartMode::Resume, _) => session_loop
+            .resume(deck_id)
+            .await
+            .and_then(|session| session.ok_or(services::SessionError::Empty))
+            .map(begun),
+        (SessionStartMode::Selected, _) => session_loop
+            .start_custom_session(deck_id, card_ids, false)
+            .await
+            .map(begun),
+        (_, Some(tag)) => session_loop
+            .start_session_with_tags(deck_id, &[tag])
+            .await
+            .map(begun),
+        (SessionStartMode::All, None) => session_loop
+            .start_session_all_cards(deck_id)
+            .await
+            .map(begun),
+        (SessionStartMode::Mistakes, None) => session_loop
+            .start_session_mistakes(deck_id)
+            .await
+            .map(begun),
     };
 
-    let session = match session_result {
-        Ok(session) => session,
-        Err(services::SessionError::Empty) => return Err(ViewError::EmptySession),
-        Err(_) => return Err(ViewError::Unknown),
-    };
-
-    Ok(SessionVm::new(session))
+    match started {
+        Ok(SessionStart::Started(session)) => {
+            Ok(StartedSession::Ready(Box::new(SessionVm::new(*session))))
+        }
+        Ok(SessionStart::NothingDue(nothing_due)) => Ok(StartedSession::NothingDue(nothing_due)),
+        Err(services::SessionError::Empty) => Err(ViewError::EmptySession),
+        Err(_) => Err(ViewError::Unknown),
+    }
 }
@@ -0,0 +1,190 @@
+/// A run of text that either matches the search query or sits between matches.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HighlightSpan {
+    pub text: String,
+    pub is_match: bool,
+}
+
+/// Split `text` into runs around case-insensitive matches of `query`.
+///
+/// Matching compares lowercased characters but cuts spans at the original char
+/// boundaries, so a match never splits a multi-byte character, even when one source
+/// char lowercases to several (`İ` to `i` plus a combining dot). Text is not normalized,
+/// so a precomposed `é` does not match `e` followed by a combining accent. A blank query
+/// or text yields the whole text as one unmatched span.
+#[must_use]
+pub fn highlight_spans(text: &str, query: &str) -> Vec<HighlightSpan> {
+    let needle = query.trim();
+    if needle.is_empty() || text.is_empty() {
+        return vec![HighlightSpan {
+            text: text.to_string(),
+            is_match: false,
+        }];
+    }
+
+    let mut lowered = Vec::new();
+    let mut map_start = Vec::new();
+    let mut map_end = Vec::new();
+
+    for (idx, ch) in text.char_indices() {
+        let end = idx + ch.len_utf8();
+        for lower in ch.to_lowercase() {
+            lowered.push(lower);
+            map_start.push(idx);
+            map_end.push(end);
+        }
+    }
+
+    let needle_chars: Vec<char> = needle.to_lowercase().chars().collect();
+    if needle_chars.is_empty() {
+        return vec![HighlightSpan {
+            text: text.to_string(),
+            is_match: false,
+        }];
+    }
+
+    let mut spans = Vec::new();
+    let mut cursor = 0usize;
+    let mut idx = 0usize;
+    while idx + needle_chars.len() <= lowered.len() {
+        if lowered[idx..idx + needle_chars.len()] == needle_chars[..] {
+            let start = map_start[idx];
+            let end = map_end[idx + needle_chars.len() - 1];
+            if start > cursor {
+                spans.push(HighlightSpan {
+                    text: text[cursor..start].to_string(),
+                    is_match: false,
+                });
+            }
+            if start < end {
+                spans.push(HighlightSpan {
+                    text: text[start..end].to_string(),
+                    is_match: true,
+                });
+            }
+            cursor = end;
+            idx += needle_chars.len();
+        } else {
+            idx += 1;
+        }
+    }
+
+    if cursor < text.len() {
+        spans.push(HighlightSpan {
+            text: text[cursor..].to_string(),
+            is_match: false,
+        });
+    }
+
+    if spans.is_empty() {
+        spans.push(HighlightSpan {
+            text: text.to_string(),
+            is_match: false,
+        });
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HighlightSpan, highlight_spans};
+
+    fn spans(text: &str, query: &str) -> Vec<(String, bool)> {
+        highlight_spans(text, query)
+            .into_iter()
+            .map(|HighlightSpan { text, is_match }| (text, is_match))
+            .collect()
+    }
+
+    fn span(text: &str, is_match: bool) -> (String, bool) {
+        (text.to_string(), is_match)
+    }
+
+    #[test]
+    fn highlight_spans_marks_match_segments() {
+        let spans = highlight_spans("Rust", "st");
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].text, "Ru");
+        assert!(!spans[0].is_match);
+        assert_eq!(spans[1].text, "st");
+        assert!(spans[1].is_match);
+    }
+
+    #[test]
+    fn highlight_spans_handles_no_match() {
+        let spans = highlight_spans("Rust", "zz");
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].text, "Rust");
+        assert!(!spans[0].is_match);
+    }
+
+    #[test]
+    fn highlight_spans_keeps_multi_byte_characters_whole() {
+        assert_eq!(
+            spans("日本語テキスト", "本語"),
+            vec![
+                span("日", false),
+                span("本語", true),
+                span("テキスト", false)
+            ]
+        );
+        assert_eq!(
+            spans("ÉCOLE", "é"),
+            vec![span("É", true), span("COLE", false)]
+        );
+    }
+
+    #[test]
+    fn highlight_spans_maps_lowercase_expansions_back_to_the_source_char() {
+        // `İ` lowercases to `i` plus a combining dot, two chars for one source char.
+        let expected = vec![span("İ", true), span("stanbul", false)];
+        assert_eq!(spans("İstanbul", "i"), expected);
+        assert_eq!(spans("İstanbul", "İ"), expected);
+        assert_eq!(spans("İstanbul", "\u{307}"), expected);
+        assert_eq!(
+            spans("xİi", "\u{307}I"),
+            vec![span("x", false), span("İi", true)]
+        );
+    }
+
+    #[test]
+    fn highlight_spans_matches_combining_sequences_as_written() {
+        let decomposed = "cafe\u{301} noir";
+        assert_eq!(
+            spans(decomposed, "e\u{301}"),
+            vec![
+                span("caf", false),
+                span("e\u{301}", true),
+                span(" noir", false)
+            ]
+        );
+        // The combining accent stays with the rest of the text, not inside the match.
+        assert_eq!(
+            spans(decomposed, "cafe"),
+            vec![span("cafe", true), span("\u{301} noir", false)]
+        );
+        // No normalization: a precomposed `é` does not match `e` plus an accent.
+        assert_eq!(
+            spans(decomposed, "caf\u{e9}"),
+            vec![span(decomposed, false)]
+        );
+    }
+
+    #[test]
+    fn highlight_spans_always_reassemble_the_text() {
+        let cases = [
+            ("Straße STRASSE", "ss"),
+            ("ǅemal ǆ", "ǆ"),
+            ("ΣΊΣΥΦΟΣ", "σ"),
+            ("a\u{308}a\u{308}", "a"),
+        ];
+        for (text, query) in cases {
+            let joined: String = spans(text, query)
+                .into_iter()
+                .map(|(part, _)| part)
+                .collect();
+            assert_eq!(joined, text, "query {query:?}");
+        }
+    }
+}
@@ -20,3 +20,140 @@ pub fn format_relative_datetime(value: &DateTime<Utc>, now: &DateTime<Utc>) -> S
 
     format_datetime(value)
 }
+
+/// Human-friendly age of `then` relative to `now`, e.g. "just now", "5 min ago", or
+/// "2 days ago"; anything older than a week falls back to `format_datetime`.
+///
+/// Timestamps slightly in the future (clock skew) read as "just now".
+#[must_use]
+pub fn relative_from(now: DateTime<Utc>, then: DateTime<Utc>) -> String {
+    let elapsed = now.signed_duration_since(then);
+    if elapsed < Duration::minutes(1) {
+        return "just now".to_string();
+    }
+    if elapsed < Duration::hours(1) {
+        return format!("{} min ago", elapsed.num_minutes());
+    }
+    if elapsed < Duration::days(1) {
+        return plural_ago(elapsed.num_hours(), "hour");
+    }
+    if elapsed <= Duration::weeks(1) {
+        return plural_ago(elapsed.num_days(), "day");
+    }
+    format_datetime(&then)
+}
+
+/// Compact length of a review interval, e.g. "now", "10m", "3h", or "4d".
+///
+/// Partial units round up, so a 90-second learning step reads as "2m" rather than "1m".
+#[must_use]
+pub fn format_interval(from: DateTime<Utc>, to: DateTime<Utc>) -> String {
+    let secs = to.signed_duration_since(from).num_seconds().max(0);
+    if secs < 60 {
+        "now".to_string()
+    } else if secs < 3600 {
+        format!("{}m", (secs + 59) / 60)
+    } else if secs < 86_400 {
+        format!("{}h", (secs + 3599) / 3600)
+    } else {
+        format!("{}d", (secs + 86_399) / 86_400)
+    }
+}
+
+/// When a card is next due relative to `now`: "new" for a card never studied, "due in 3d"
+/// ahead of time, "due now" once due, and "overdue 2d" once `is_overdue`.
+#[must_use]
+pub fn format_due(now: DateTime<Utc>, next_due: Option<DateTime<Utc>>) -> String {
+    let Some(due) = next_due else {
+        return "new".to_string();
+    };
+    if is_overdue(now, due) {
+        return format!("overdue {}", format_interval(due, now));
+    }
+    match format_interval(now, due).as_str() {
+        "now" => "due now".to_string(),
+        interval => format!("due in {interval}"),
+    }
+}
+
+/// Whether a card due at `due` has waited a day or more past it.
+#[must_use]
+pub fn is_overdue(now: DateTime<Utc>, due: DateTime<Utc>) -> bool {
+    now.signed_duration_since(due) >= Duration::days(1)
+}
+
+fn plural_ago(count: i64, unit: &str) -> String {
+    if count == 1 {
+        format!("1 {unit} ago")
+    } else {
+        format!("{count} {unit}s ago")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap()
+    }
+
+    fn ago(duration: Duration) -> String {
+        relative_from(now(), now() - duration)
+    }
+
+    #[test]
+    fn format_interval_rounds_up_within_each_unit() {
+        let interval = |duration: Duration| format_interval(now(), now() + duration);
+        assert_eq!(interval(Duration::seconds(-5)), "now");
+        assert_eq!(interval(Duration::seconds(59)), "now");
+        assert_eq!(interval(Duration::minutes(1)), "1m");
+        assert_eq!(interval(Duration::seconds(90)), "2m");
+        assert_eq!(interval(Duration::minutes(10)), "10m");
+        assert_eq!(interval(Duration::hours(1)), "1h");
+        assert_eq!(interval(Duration::hours(23) + Duration::minutes(1)), "24h");
+        assert_eq!(interval(Duration::days(1)), "1d");
+        assert_eq!(interval(Duration::days(4) + Duration::hours(2)), "5d");
+    }
+
+    #[test]
+    fn format_due_labels_new_upcoming_due_and_overdue_cards() {
+        let due = |duration: Duration| format_due(now(), Some(now() + duration));
+        assert_eq!(format_due(now(), None), "new");
+        assert_eq!(due(Duration::days(3)), "due in 3d");
+        assert_eq!(due(Duration::minutes(10)), "due in 10m");
+        assert_eq!(due(Duration::seconds(30)), "due now");
+        assert_eq!(due(-Duration::hours(23)), "due now");
+        assert_eq!(due(-Duration::days(1)), "overdue 1d");
+        assert_eq!(due(-Duration::days(2) - Duration::hours(3)), "overdue 3d");
+        assert!(!is_overdue(now(), now() - Duration::hours(23)));
+        assert!(is_overdue(now(), now() - Duration::days(1)));
+    }
+
+    #[test]
+    fn relative_from_switches_units_at_boundaries() {
+        assert_eq!(ago(Duration::zero()), "just now");
+        assert_eq!(ago(Duration::seconds(59)), "just now");
+        assert_eq!(ago(Duration::seconds(60)), "1 min ago");
+        assert_eq!(ago(Duration::minutes(59) + Duration::seconds(59)), "59 min ago");
+        assert_eq!(ago(Duration::minutes(60)), "1 hour ago");
+        assert_eq!(ago(Duration::hours(23)), "23 hours ago");
+        assert_eq!(ago(Duration::hours(24)), "1 day ago");
+        assert_eq!(ago(Duration::days(2) + Duration::hours(5)), "2 days ago");
+        assert_eq!(ago(Duration::weeks(1)), "7 days ago");
+    }
+
+    #[test]
+    fn relative_from_uses_absolute_dates_beyond_a_week() {
+        let then = now() - Duration::weeks(1) - Duration::seconds(1);
+        assert_eq!(relative_from(now(), then), format_datetime(&then));
+        assert_eq!(relative_from(now(), then), "Mar 3 \u{00b7} 11:59 AM");
+    }
+
+    #[test]
+    fn relative_from_treats_future_timestamps_as_just_now() {
+        assert_eq!(relative_from(now(), now() + Duration::minutes(3)), "just now");
+    }
+}
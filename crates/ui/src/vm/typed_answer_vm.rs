@@ -0,0 +1,147 @@
+use super::markdown_vm::strip_html_tags;
+
+/// How a run of characters in a typed answer compares with the card's answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnswerDiffKind {
+    /// Typed as the answer has it.
+    Match,
+    /// In the answer but not typed.
+    Missing,
+    /// Typed but not in the answer.
+    Extra,
+}
+
+impl AnswerDiffKind {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AnswerDiffKind::Match => "match",
+            AnswerDiffKind::Missing => "missing",
+            AnswerDiffKind::Extra => "extra",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnswerDiffSpan {
+    pub kind: AnswerDiffKind,
+    pub text: String,
+}
+
+/// A typed answer checked against the card's answer, both normalized.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypedAnswerCheck {
+    pub is_match: bool,
+    /// The typed and expected text merged in reading order; empty when both are empty.
+    pub diff: Vec<AnswerDiffSpan>,
+}
+
+/// Trim, lowercase, and collapse runs of whitespace to one space.
+#[must_use]
+pub fn normalize_typed_answer(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Compare `typed` with the card's `answer`, which may be stored as HTML.
+///
+/// Differences are found character by character along the longest common subsequence,
+/// so a swapped or missing letter marks just that letter.
+#[must_use]
+pub fn check_typed_answer(typed: &str, answer: &str) -> TypedAnswerCheck {
+    let typed = normalize_typed_answer(typed);
+    let expected = normalize_typed_answer(&strip_html_tags(answer));
+    TypedAnswerCheck {
+        is_match: typed == expected,
+        diff: diff_chars(&typed, &expected),
+    }
+}
+
+fn diff_chars(typed: &str, expected: &str) -> Vec<AnswerDiffSpan> {
+    let typed: Vec<char> = typed.chars().collect();
+    let expected: Vec<char> = expected.chars().collect();
+    // common[i][j]: longest common subsequence of `typed[i..]` and `expected[j..]`.
+    let mut common = vec![vec![0_usize; expected.len() + 1]; typed.len() + 1];
+    for i in (0..typed.len()).rev() {
+        for j in (0..expected.len()).rev() {
+            common[i][j] = if typed[i] == expected[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut spans: Vec<AnswerDiffSpan> = Vec::new();
+    let mut push = |kind, ch| match spans.last_mut() {
+        Some(span) if span.kind == kind => span.text.push(ch),
+        _ => spans.push(AnswerDiffSpan {
+            kind,
+            text: ch.to_string(),
+        }),
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < typed.len() || j < expected.len() {
+        if i < typed.len() && j < expected.len() && typed[i] == expected[j] {
+            push(AnswerDiffKind::Match, typed[i]);
+            i += 1;
+            j += 1;
+        } else if j == expected.len()
+            || (i < typed.len() && common[i + 1][j] >= common[i][j + 1])
+        {
+            push(AnswerDiffKind::Extra, typed[i]);
+            i += 1;
+        } else {
+            push(AnswerDiffKind::Missing, expected[j]);
+            j += 1;
+        }
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(kind: AnswerDiffKind, text: &str) -> AnswerDiffSpan {
+        AnswerDiffSpan {
+            kind,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn exact_answer_matches() {
+        let check = check_typed_answer("Paris", "Paris");
+        assert!(check.is_match);
+        assert_eq!(check.diff, [span(AnswerDiffKind::Match, "paris")]);
+    }
+
+    #[test]
+    fn case_and_whitespace_do_not_count_as_differences() {
+        assert!(check_typed_answer("  new   YORK\n", "New York").is_match);
+        assert!(check_typed_answer("new york", "<p>New <b>York</b></p>").is_match);
+        assert!(!check_typed_answer("newyork", "New York").is_match);
+    }
+
+    #[test]
+    fn mismatch_marks_extra_and_missing_letters() {
+        let check = check_typed_answer("recieve", "receive");
+        assert!(!check.is_match);
+        assert_eq!(
+            check.diff,
+            [
+                span(AnswerDiffKind::Match, "rec"),
+                span(AnswerDiffKind::Extra, "i"),
+                span(AnswerDiffKind::Match, "e"),
+                span(AnswerDiffKind::Missing, "i"),
+                span(AnswerDiffKind::Match, "ve"),
+            ]
+        );
+
+        let blank = check_typed_answer("", "cat");
+        assert_eq!(blank.diff, [span(AnswerDiffKind::Missing, "cat")]);
+    }
+}